@@ -0,0 +1,122 @@
+//! DAV request parsing and multistatus generation, measured in-process so the
+//! numbers only cover the adapters.
+
+use std::collections::HashMap;
+
+use chrono::{Duration, TimeZone, Utc};
+use criterion::{Criterion, Throughput};
+use oxicloud::application::adapters::caldav_adapter::CalDavAdapter;
+use oxicloud::application::adapters::webdav_adapter::WebDavAdapter;
+use oxicloud::application::dtos::calendar_dto::CalendarEventDto;
+use oxicloud::application::dtos::file_dto::FileDto;
+use oxicloud::application::dtos::folder_dto::FolderDto;
+
+const LARGE_FOLDER_FILES: usize = 10_000;
+const LARGE_FOLDER_SUBFOLDERS: usize = 500;
+const CALENDAR_EVENTS: usize = 5_000;
+
+const PROPFIND_ALLPROP: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<D:propfind xmlns:D="DAV:"><D:allprop/></D:propfind>"#;
+
+const CALENDAR_QUERY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<C:calendar-query xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:prop><D:getetag/><C:calendar-data/></D:prop>
+  <C:filter>
+    <C:comp-filter name="VCALENDAR">
+      <C:comp-filter name="VEVENT">
+        <C:time-range start="2026-01-01T00:00:00Z" end="2027-01-01T00:00:00Z"/>
+      </C:comp-filter>
+    </C:comp-filter>
+  </C:filter>
+</C:calendar-query>"#;
+
+pub fn benches(c: &mut Criterion) {
+    propfind_large_folder(c);
+    calendar_query(c);
+}
+
+fn propfind_large_folder(c: &mut Criterion) {
+    let folder = FolderDto {
+        id: "large".to_string(),
+        name: "Large".to_string(),
+        path: "Large".to_string(),
+        ..FolderDto::default()
+    };
+    let files: Vec<FileDto> = (0..LARGE_FOLDER_FILES)
+        .map(|i| FileDto {
+            id: format!("file-{}", i),
+            name: format!("document-{:05}.pdf", i),
+            path: format!("Large/document-{:05}.pdf", i),
+            size: 1024 * i as u64,
+            mime_type: "application/pdf".to_string(),
+            folder_id: Some("large".to_string()),
+            created_at: 1_700_000_000 + i as u64,
+            modified_at: 1_700_000_000 + i as u64,
+            ..FileDto::default()
+        })
+        .collect();
+    let subfolders: Vec<FolderDto> = (0..LARGE_FOLDER_SUBFOLDERS)
+        .map(|i| FolderDto {
+            id: format!("folder-{}", i),
+            name: format!("Folder {}", i),
+            path: format!("Large/Folder {}", i),
+            parent_id: Some("large".to_string()),
+            ..FolderDto::default()
+        })
+        .collect();
+    let attributes = HashMap::new();
+    let dead_properties = HashMap::new();
+
+    let mut group = c.benchmark_group("propfind");
+    group.throughput(Throughput::Elements((LARGE_FOLDER_FILES + LARGE_FOLDER_SUBFOLDERS) as u64));
+    group.bench_function("large_folder_depth_1", |b| b.iter(|| {
+        let request = WebDavAdapter::parse_propfind(PROPFIND_ALLPROP.as_bytes()).unwrap();
+        let mut body = Vec::new();
+        WebDavAdapter::generate_propfind_response(
+            &mut body,
+            Some(&folder),
+            &files,
+            &subfolders,
+            &request,
+            "1",
+            "/webdav/Large/",
+            &attributes,
+            &dead_properties,
+        )
+        .unwrap();
+        body
+    }));
+    group.finish();
+}
+
+fn calendar_query(c: &mut Criterion) {
+    let first = Utc.with_ymd_and_hms(2026, 1, 1, 9, 0, 0).unwrap();
+    let events: Vec<CalendarEventDto> = (0..CALENDAR_EVENTS)
+        .map(|i| {
+            let start = first + Duration::hours(i as i64 * 2);
+            CalendarEventDto {
+                id: format!("event-{}", i),
+                calendar_id: "work".to_string(),
+                summary: format!("Meeting {}", i),
+                description: Some("Weekly sync with the team".to_string()),
+                location: Some("Room 4".to_string()),
+                start_time: start,
+                end_time: start + Duration::hours(1),
+                ical_uid: format!("{}@oxicloud.bench", i),
+                created_at: first,
+                updated_at: first,
+                ..CalendarEventDto::default()
+            }
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("caldav");
+    group.throughput(Throughput::Elements(CALENDAR_EVENTS as u64));
+    group.bench_function("calendar_query_5000_events", |b| b.iter(|| {
+        let request = CalDavAdapter::parse_report(CALENDAR_QUERY.as_bytes()).unwrap();
+        let mut body = Vec::new();
+        CalDavAdapter::generate_calendar_events_response(&mut body, &events, &request, "/caldav/work/").unwrap();
+        body
+    }));
+    group.finish();
+}
@@ -0,0 +1,37 @@
+//! Benchmarks for the request paths that dominate real workloads: PROPFIND on
+//! large folders, calendar-query over thousands of events, concurrent uploads
+//! and search.
+//!
+//! ```text
+//! cargo bench --bench hot_paths                  # every case
+//! cargo bench --bench hot_paths -- propfind      # cases whose name contains "propfind"
+//! ```
+//!
+//! The upload and search cases start the real server through the end-to-end
+//! harness, so like the end-to-end tests they need Docker or
+//! `OXICLOUD_TEST_DATABASE_URL` (see `tests/e2e`); the server only starts
+//! when one of them is selected.
+//!
+//! Criterion keeps its reports in `target/criterion` and compares every run
+//! with the previous one. To compare against a release instead, save its
+//! numbers under a name and point later runs at it:
+//!
+//! ```text
+//! cargo bench --bench hot_paths -- --save-baseline release
+//! cargo bench --bench hot_paths -- --baseline release
+//! ```
+
+use criterion::{criterion_group, criterion_main};
+
+#[allow(dead_code)]
+#[path = "../../tests/e2e/clients.rs"]
+mod clients;
+#[allow(dead_code)]
+#[path = "../../tests/e2e/harness.rs"]
+mod harness;
+
+mod dav;
+mod server;
+
+criterion_group!(hot_paths, dav::benches, server::benches);
+criterion_main!(hot_paths);
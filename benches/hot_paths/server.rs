@@ -0,0 +1,142 @@
+//! Cases that go through the whole stack: HTTP, services, repositories, the
+//! database and the storage directory. They reuse the end-to-end harness, so
+//! they need Docker or `OXICLOUD_TEST_DATABASE_URL` like the end-to-end tests.
+
+use std::cell::{Cell, OnceCell};
+use std::time::{Duration, Instant};
+
+use criterion::{Criterion, Throughput};
+use futures::stream::{self, StreamExt};
+use reqwest::{Method, StatusCode};
+use serde_json::{json, Value};
+use tokio::runtime::Runtime;
+
+use crate::clients::ApiClient;
+use crate::harness::TestServer;
+
+const CONCURRENT_UPLOADS: usize = 16;
+const UPLOAD_SIZE: usize = 256 * 1024;
+
+const SEARCH_FOLDERS: usize = 20;
+const SEARCH_FILES_PER_FOLDER: usize = 50;
+
+/// Server shared by the cases, with the client of its only user
+struct BenchServer {
+    _server: TestServer,
+    api: ApiClient,
+}
+
+/// Starts the server on first use, so that filtering the server cases out
+/// needs neither Docker nor a database
+struct LazyServer<'a> {
+    runtime: &'a Runtime,
+    server: OnceCell<BenchServer>,
+}
+
+impl LazyServer<'_> {
+    fn api(&self) -> &ApiClient {
+        let server = self.server.get_or_init(|| self.runtime.block_on(async {
+            let server = TestServer::start().await;
+            let user = server.create_user("bench").await;
+            let api = server.api(&user);
+            BenchServer { _server: server, api }
+        }));
+        &server.api
+    }
+}
+
+pub fn benches(c: &mut Criterion) {
+    let runtime = Runtime::new().expect("cannot start the tokio runtime");
+    let server = LazyServer { runtime: &runtime, server: OnceCell::new() };
+
+    concurrent_uploads(c, &server);
+    search(c, &server);
+}
+
+fn concurrent_uploads(c: &mut Criterion, server: &LazyServer) {
+    let content: Vec<u8> = (0..UPLOAD_SIZE).map(|i| (i % 251) as u8).collect();
+    let round = Cell::new(0);
+
+    let mut group = c.benchmark_group("uploads");
+    group.throughput(Throughput::Elements(CONCURRENT_UPLOADS as u64));
+    group.bench_function("16_concurrent_256k", |b| {
+        let api = server.api();
+        let (content, round) = (&content, &round);
+        b.to_async(server.runtime).iter_custom(move |iterations| async move {
+            let mut elapsed = Duration::ZERO;
+            for _ in 0..iterations {
+                round.set(round.get() + 1);
+                let round = round.get();
+                let start = Instant::now();
+                let statuses: Vec<StatusCode> = stream::iter(0..CONCURRENT_UPLOADS)
+                    .map(|i| async move {
+                        let name = format!("upload-{}-{}.bin", round, i);
+                        api.upload_file(None, &name, content, "application/octet-stream").await.status()
+                    })
+                    .buffer_unordered(CONCURRENT_UPLOADS)
+                    .collect()
+                    .await;
+                elapsed += start.elapsed();
+                assert!(statuses.iter().all(|status| *status == StatusCode::CREATED), "uploads failed: {:?}", statuses);
+            }
+            elapsed
+        });
+    });
+    group.finish();
+}
+
+fn search(c: &mut Criterion, server: &LazyServer) {
+    let seeded = OnceCell::new();
+
+    let mut group = c.benchmark_group("search");
+    group.throughput(Throughput::Elements((SEARCH_FOLDERS * SEARCH_FILES_PER_FOLDER) as u64));
+    group.bench_function("name_in_1000_files", |b| {
+        let api = server.api();
+        seeded.get_or_init(|| server.runtime.block_on(seed_search_corpus(api)));
+        b.to_async(server.runtime).iter_custom(move |iterations| async move {
+            let mut elapsed = Duration::ZERO;
+            for _ in 0..iterations {
+                // Results are cached by query; measure the uncached path
+                let response = api.delete("/api/search/cache").await;
+                assert!(response.status().is_success(), "clearing the search cache returned {}", response.status());
+
+                let start = Instant::now();
+                let response = api.get("/api/search?query=invoice&limit=200").await;
+                elapsed += start.elapsed();
+                assert_eq!(response.status(), StatusCode::OK);
+            }
+            elapsed
+        });
+    });
+    group.finish();
+}
+
+/// Creates folders holding files whose names are half "invoice" and half "notes"
+async fn seed_search_corpus(api: &ApiClient) {
+    for folder_index in 0..SEARCH_FOLDERS {
+        let response = api
+            .post_json("/api/folders", &json!({ "name": format!("Archive {}", folder_index), "parent_id": null }))
+            .await;
+        assert!(response.status().is_success(), "creating a folder returned {}", response.status());
+        let folder: Value = response.json().await.unwrap();
+        let folder_id = folder["id"].as_str().unwrap().to_string();
+
+        let statuses: Vec<StatusCode> = stream::iter(0..SEARCH_FILES_PER_FOLDER)
+            .map(|i| {
+                let folder_id = folder_id.clone();
+                async move {
+                    let kind = if i % 2 == 0 { "invoice" } else { "notes" };
+                    let name = format!("{}-{}-{}.txt", kind, folder_index, i);
+                    api.upload_file(Some(&folder_id), &name, name.as_bytes(), "text/plain").await.status()
+                }
+            })
+            .buffer_unordered(8)
+            .collect()
+            .await;
+        assert!(statuses.iter().all(|status| *status == StatusCode::CREATED), "seeding failed: {:?}", statuses);
+    }
+    // Give the listing caches a moment to settle before timing
+    let response = api.request(Method::GET, "/api/folders").send().await.unwrap();
+    assert!(response.status().is_success());
+    tokio::time::sleep(Duration::from_millis(100)).await;
+}
@@ -0,0 +1,23 @@
+-- Image labels produced by an external inference service
+
+-- Per-user privacy setting: tagging is opt-in
+CREATE TABLE IF NOT EXISTS auth.user_image_tagging (
+    user_id VARCHAR(36) PRIMARY KEY REFERENCES auth.users(id) ON DELETE CASCADE,
+    enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Labels detected in each image
+CREATE TABLE IF NOT EXISTS auth.image_labels (
+    id SERIAL PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    file_id TEXT NOT NULL,
+    label TEXT NOT NULL,
+    confidence REAL NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(user_id, file_id, label)
+);
+
+-- Create indexes for label search and cleanup
+CREATE INDEX IF NOT EXISTS idx_image_labels_user_label ON auth.image_labels(user_id, label);
+CREATE INDEX IF NOT EXISTS idx_image_labels_file_id ON auth.image_labels(file_id);
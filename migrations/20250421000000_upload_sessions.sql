@@ -0,0 +1,19 @@
+-- Chunked upload sessions, persisted so partial uploads survive a restart
+
+CREATE TABLE IF NOT EXISTS auth.upload_sessions (
+    id VARCHAR(36) PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    filename TEXT NOT NULL,
+    folder_id TEXT,
+    content_type TEXT NOT NULL,
+    total_size BIGINT NOT NULL,
+    upload_offset BIGINT NOT NULL DEFAULT 0,
+    temp_path TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+-- Create indexes for per-user listing and expiry sweeps
+CREATE INDEX IF NOT EXISTS idx_upload_sessions_user_id ON auth.upload_sessions(user_id);
+CREATE INDEX IF NOT EXISTS idx_upload_sessions_expires_at ON auth.upload_sessions(expires_at);
@@ -0,0 +1,17 @@
+-- WebDAV locks (RFC 4918), persisted so they survive a restart
+
+CREATE TABLE IF NOT EXISTS auth.webdav_locks (
+    token VARCHAR(64) PRIMARY KEY,
+    path TEXT NOT NULL,
+    owner_id VARCHAR(36) NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    owner_info TEXT,
+    exclusive BOOLEAN NOT NULL DEFAULT TRUE,
+    infinite_depth BOOLEAN NOT NULL DEFAULT FALSE,
+    timeout_secs BIGINT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+-- Create indexes for path lookups and expiry sweeps
+CREATE INDEX IF NOT EXISTS idx_webdav_locks_path ON auth.webdav_locks(path text_pattern_ops);
+CREATE INDEX IF NOT EXISTS idx_webdav_locks_expires_at ON auth.webdav_locks(expires_at);
@@ -0,0 +1,109 @@
+-- Change journal for calendars and address books.
+-- Every insert, update or delete of an event or contact bumps the revision
+-- of its collection and leaves a row in the journal, so clients can compare
+-- the collection ctag (CalendarServer getctag) and skip a full sync when
+-- nothing changed.
+
+ALTER TABLE caldav.calendars ADD COLUMN IF NOT EXISTS sync_revision BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE carddav.address_books ADD COLUMN IF NOT EXISTS sync_revision BIGINT NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS caldav.calendar_changes (
+    calendar_id UUID NOT NULL REFERENCES caldav.calendars(id) ON DELETE CASCADE,
+    revision BIGINT NOT NULL,
+    object_id UUID NOT NULL,
+    object_uid VARCHAR(255) NOT NULL,
+    operation VARCHAR(10) NOT NULL CHECK (operation IN ('created', 'modified', 'deleted')),
+    changed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY(calendar_id, revision)
+);
+
+CREATE TABLE IF NOT EXISTS carddav.address_book_changes (
+    address_book_id UUID NOT NULL REFERENCES carddav.address_books(id) ON DELETE CASCADE,
+    revision BIGINT NOT NULL,
+    object_id UUID NOT NULL,
+    object_uid VARCHAR(255) NOT NULL,
+    operation VARCHAR(10) NOT NULL CHECK (operation IN ('created', 'modified', 'deleted')),
+    changed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY(address_book_id, revision)
+);
+
+-- Bumps the calendar revision and journals the change. When the calendar
+-- itself is being deleted (cascade) there is nothing left to update.
+CREATE OR REPLACE FUNCTION caldav.journal_calendar_change(
+    p_calendar_id UUID, p_object_id UUID, p_object_uid VARCHAR, p_operation VARCHAR
+) RETURNS VOID AS $$
+DECLARE
+    new_revision BIGINT;
+BEGIN
+    UPDATE caldav.calendars
+    SET sync_revision = sync_revision + 1
+    WHERE id = p_calendar_id
+    RETURNING sync_revision INTO new_revision;
+
+    IF new_revision IS NOT NULL THEN
+        INSERT INTO caldav.calendar_changes (calendar_id, revision, object_id, object_uid, operation)
+        VALUES (p_calendar_id, new_revision, p_object_id, p_object_uid, p_operation);
+    END IF;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION caldav.record_event_change() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        PERFORM caldav.journal_calendar_change(NEW.calendar_id, NEW.id, NEW.ical_uid, 'created');
+    ELSIF TG_OP = 'DELETE' THEN
+        PERFORM caldav.journal_calendar_change(OLD.calendar_id, OLD.id, OLD.ical_uid, 'deleted');
+    ELSIF OLD.calendar_id <> NEW.calendar_id OR OLD.ical_uid <> NEW.ical_uid THEN
+        -- Moved to another calendar or renamed: gone from one place, new in the other
+        PERFORM caldav.journal_calendar_change(OLD.calendar_id, OLD.id, OLD.ical_uid, 'deleted');
+        PERFORM caldav.journal_calendar_change(NEW.calendar_id, NEW.id, NEW.ical_uid, 'created');
+    ELSE
+        PERFORM caldav.journal_calendar_change(NEW.calendar_id, NEW.id, NEW.ical_uid, 'modified');
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS calendar_events_journal ON caldav.calendar_events;
+CREATE TRIGGER calendar_events_journal
+AFTER INSERT OR UPDATE OR DELETE ON caldav.calendar_events
+FOR EACH ROW EXECUTE FUNCTION caldav.record_event_change();
+
+CREATE OR REPLACE FUNCTION carddav.journal_address_book_change(
+    p_address_book_id UUID, p_object_id UUID, p_object_uid VARCHAR, p_operation VARCHAR
+) RETURNS VOID AS $$
+DECLARE
+    new_revision BIGINT;
+BEGIN
+    UPDATE carddav.address_books
+    SET sync_revision = sync_revision + 1
+    WHERE id = p_address_book_id
+    RETURNING sync_revision INTO new_revision;
+
+    IF new_revision IS NOT NULL THEN
+        INSERT INTO carddav.address_book_changes (address_book_id, revision, object_id, object_uid, operation)
+        VALUES (p_address_book_id, new_revision, p_object_id, p_object_uid, p_operation);
+    END IF;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION carddav.record_contact_change() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        PERFORM carddav.journal_address_book_change(NEW.address_book_id, NEW.id, NEW.uid, 'created');
+    ELSIF TG_OP = 'DELETE' THEN
+        PERFORM carddav.journal_address_book_change(OLD.address_book_id, OLD.id, OLD.uid, 'deleted');
+    ELSIF OLD.address_book_id <> NEW.address_book_id OR OLD.uid <> NEW.uid THEN
+        PERFORM carddav.journal_address_book_change(OLD.address_book_id, OLD.id, OLD.uid, 'deleted');
+        PERFORM carddav.journal_address_book_change(NEW.address_book_id, NEW.id, NEW.uid, 'created');
+    ELSE
+        PERFORM carddav.journal_address_book_change(NEW.address_book_id, NEW.id, NEW.uid, 'modified');
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS contacts_journal ON carddav.contacts;
+CREATE TRIGGER contacts_journal
+AFTER INSERT OR UPDATE OR DELETE ON carddav.contacts
+FOR EACH ROW EXECUTE FUNCTION carddav.record_contact_change();
@@ -0,0 +1,11 @@
+-- WebDAV dead properties (RFC 4918 §4.2): custom properties set by clients
+-- through PROPPATCH and returned unchanged by PROPFIND
+
+CREATE TABLE IF NOT EXISTS auth.dead_properties (
+    resource_id VARCHAR(255) NOT NULL,
+    namespace TEXT NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    value TEXT,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY(resource_id, namespace, name)
+);
@@ -0,0 +1,23 @@
+-- Sync conflicts detected for each user (conflicted copies, writes rejected
+-- by locks, lock contention), listed on the conflict dashboard
+
+CREATE TABLE IF NOT EXISTS auth.sync_conflicts (
+    id VARCHAR(36) PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    kind VARCHAR(32) NOT NULL,
+    path TEXT NOT NULL,
+    conflict_path TEXT,
+    detail TEXT,
+    occurrences INTEGER NOT NULL DEFAULT 1,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_seen_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    resolved_at TIMESTAMP WITH TIME ZONE,
+    resolution VARCHAR(16)
+);
+
+-- Repeated occurrences of an open conflict are folded into a single row
+CREATE UNIQUE INDEX IF NOT EXISTS idx_sync_conflicts_open
+    ON auth.sync_conflicts(user_id, kind, path, (COALESCE(conflict_path, '')))
+    WHERE resolved_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_sync_conflicts_user ON auth.sync_conflicts(user_id, last_seen_at DESC);
@@ -0,0 +1,20 @@
+-- Feature flags to roll out risky subsystems gradually. The global rule
+-- applies to a percentage of users; group and user overrides take precedence
+
+CREATE TABLE IF NOT EXISTS auth.feature_flags (
+    key VARCHAR(100) PRIMARY KEY,
+    description TEXT,
+    enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    rollout_percentage SMALLINT NOT NULL DEFAULT 100 CHECK (rollout_percentage BETWEEN 0 AND 100),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS auth.feature_flag_overrides (
+    flag_key VARCHAR(100) NOT NULL REFERENCES auth.feature_flags(key) ON DELETE CASCADE,
+    scope VARCHAR(10) NOT NULL CHECK (scope IN ('group', 'user')),
+    subject VARCHAR(255) NOT NULL,
+    enabled BOOLEAN NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY(flag_key, scope, subject)
+);
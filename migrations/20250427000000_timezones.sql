@@ -0,0 +1,7 @@
+-- Timezone support for CalDAV. The calendar keeps its calendar-timezone
+-- property (an iCalendar object with a single VTIMEZONE, RFC 4791 5.2.2)
+-- and each event the TZID of its DTSTART; start/end stay stored in UTC
+
+ALTER TABLE caldav.calendars ADD COLUMN IF NOT EXISTS timezone TEXT;
+
+ALTER TABLE caldav.calendar_events ADD COLUMN IF NOT EXISTS timezone VARCHAR(255);
@@ -0,0 +1,4 @@
+-- Alarms (VALARM) of calendar events. They are also kept in ical_data;
+-- the column lets the server list reminders without parsing every event
+
+ALTER TABLE caldav.calendar_events ADD COLUMN IF NOT EXISTS alarms JSONB NOT NULL DEFAULT '[]'::jsonb;
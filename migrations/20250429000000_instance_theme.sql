@@ -0,0 +1,13 @@
+-- Instance branding set by administrators: name, logo, colors and the
+-- message shown on the login page. There is a single row for the instance
+
+CREATE TABLE IF NOT EXISTS auth.instance_theme (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    instance_name VARCHAR(100) NOT NULL,
+    primary_color VARCHAR(7) NOT NULL,
+    accent_color VARCHAR(7) NOT NULL,
+    login_message TEXT,
+    logo BYTEA,
+    logo_content_type VARCHAR(100),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,29 @@
+-- Announcements broadcast by admins to all users or to some groups, shown in
+-- the notification center and as a banner while their window is active
+
+CREATE TABLE IF NOT EXISTS auth.announcements (
+    id VARCHAR(36) PRIMARY KEY,
+    title VARCHAR(200) NOT NULL,
+    message TEXT NOT NULL,
+    severity VARCHAR(10) NOT NULL DEFAULT 'info' CHECK (severity IN ('info', 'warning', 'critical')),
+    starts_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    ends_at TIMESTAMP WITH TIME ZONE,
+    -- Empty means every user
+    target_groups TEXT[] NOT NULL DEFAULT '{}',
+    created_by VARCHAR(36) REFERENCES auth.users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (ends_at IS NULL OR ends_at > starts_at)
+);
+
+CREATE INDEX IF NOT EXISTS idx_announcements_window ON auth.announcements(starts_at, ends_at);
+
+-- Who has read each announcement, for the acknowledgment rate
+CREATE TABLE IF NOT EXISTS auth.announcement_reads (
+    announcement_id VARCHAR(36) NOT NULL REFERENCES auth.announcements(id) ON DELETE CASCADE,
+    user_id VARCHAR(36) NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    read_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY(announcement_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_announcement_reads_user ON auth.announcement_reads(user_id);
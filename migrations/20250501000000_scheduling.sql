@@ -0,0 +1,34 @@
+-- Scheduling (iTIP, RFC 5546 and RFC 6638): organizers and attendees of
+-- calendar events, and the scheduling inbox where invitations and replies
+-- between users of this server are delivered
+
+ALTER TABLE caldav.calendar_events ADD COLUMN IF NOT EXISTS organizer_email TEXT;
+ALTER TABLE caldav.calendar_events ADD COLUMN IF NOT EXISTS organizer_name TEXT;
+
+-- Attendees are also kept in ical_data; the table lets the server find the
+-- events a user is invited to and their participation status
+CREATE TABLE IF NOT EXISTS caldav.calendar_event_attendees (
+    event_id UUID NOT NULL REFERENCES caldav.calendar_events(id) ON DELETE CASCADE,
+    email TEXT NOT NULL,
+    name TEXT,
+    role VARCHAR(20) NOT NULL DEFAULT 'REQ-PARTICIPANT',
+    status VARCHAR(20) NOT NULL DEFAULT 'NEEDS-ACTION',
+    rsvp BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Order of the ATTENDEE properties in the event
+    position INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY(event_id, email)
+);
+
+CREATE INDEX IF NOT EXISTS idx_calendar_event_attendees_email ON caldav.calendar_event_attendees(email);
+
+CREATE TABLE IF NOT EXISTS caldav.scheduling_messages (
+    id VARCHAR(36) PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    method VARCHAR(16) NOT NULL CHECK (method IN ('REQUEST', 'REPLY', 'CANCEL')),
+    ical_uid VARCHAR(255) NOT NULL,
+    sender TEXT NOT NULL,
+    ical_data TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_scheduling_messages_user ON caldav.scheduling_messages(user_id, created_at);
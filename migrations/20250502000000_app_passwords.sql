@@ -0,0 +1,15 @@
+-- App passwords: per-client credentials for HTTP Basic authentication on
+-- the WebDAV, CalDAV and CardDAV endpoints, revocable one by one without
+-- changing the account password
+
+CREATE TABLE IF NOT EXISTS auth.app_passwords (
+    id VARCHAR(36) PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    label VARCHAR(100) NOT NULL,
+    -- Argon2 hash; the password itself is only shown when it is created
+    password_hash TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_used_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_app_passwords_user_id ON auth.app_passwords(user_id);
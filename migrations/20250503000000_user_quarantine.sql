@@ -0,0 +1,13 @@
+-- Users deleted by an administrator: the account is disabled and its data
+-- kept until purge_after, when it is removed for good. Until then the
+-- deletion can be undone by reactivating the user.
+
+CREATE TABLE IF NOT EXISTS auth.user_quarantine (
+    user_id VARCHAR(36) PRIMARY KEY REFERENCES auth.users(id) ON DELETE CASCADE,
+    quarantined_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    purge_after TIMESTAMPTZ NOT NULL,
+    -- Username of the administrator who deleted the user
+    quarantined_by VARCHAR(32) NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_quarantine_purge_after ON auth.user_quarantine(purge_after);
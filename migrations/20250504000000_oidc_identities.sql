@@ -0,0 +1,16 @@
+-- Accounts of an external OpenID Connect provider linked to local users.
+-- A user is identified by the issuer and the subject (`sub` claim), which
+-- never change, and not by the username or email, which can.
+
+CREATE TABLE IF NOT EXISTS auth.oidc_identities (
+    issuer TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    user_id VARCHAR(36) NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    -- Email reported by the provider when the account was linked
+    email TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_login_at TIMESTAMPTZ,
+    PRIMARY KEY (issuer, subject)
+);
+
+CREATE INDEX IF NOT EXISTS idx_oidc_identities_user_id ON auth.oidc_identities(user_id);
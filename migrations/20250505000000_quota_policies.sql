@@ -0,0 +1,17 @@
+-- Default storage quotas per group. A user's effective quota is the one set
+-- for them by hand if any, otherwise the policy with the highest priority
+-- among their groups, otherwise the quota stored when the account was created
+
+CREATE TABLE IF NOT EXISTS auth.quota_policies (
+    group_name VARCHAR(255) PRIMARY KEY,
+    -- Negative means unlimited
+    quota_bytes BIGINT NOT NULL,
+    priority INTEGER NOT NULL DEFAULT 0,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS auth.user_quota_overrides (
+    user_id VARCHAR(36) PRIMARY KEY REFERENCES auth.users(id) ON DELETE CASCADE,
+    quota_bytes BIGINT NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
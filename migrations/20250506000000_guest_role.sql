@@ -0,0 +1,3 @@
+-- Guest accounts: read-only access to their own files and to what is
+-- shared with them
+ALTER TYPE auth.userrole ADD VALUE IF NOT EXISTS 'guest';
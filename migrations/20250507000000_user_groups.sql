@@ -0,0 +1,40 @@
+-- User groups (teams) and their members. A share granted to a group covers
+-- every member, including the ones added after the share was made
+
+CREATE TABLE IF NOT EXISTS auth.user_groups (
+    id VARCHAR(36) PRIMARY KEY,
+    name VARCHAR(255) NOT NULL UNIQUE,
+    description TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS auth.user_group_members (
+    group_id VARCHAR(36) NOT NULL REFERENCES auth.user_groups(id) ON DELETE CASCADE,
+    user_id VARCHAR(36) NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    added_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (group_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_group_members_user ON auth.user_group_members(user_id);
+
+-- Calendars and address books shared with a whole group
+CREATE TABLE IF NOT EXISTS caldav.calendar_group_shares (
+    calendar_id UUID NOT NULL REFERENCES caldav.calendars(id) ON DELETE CASCADE,
+    group_id VARCHAR(36) NOT NULL REFERENCES auth.user_groups(id) ON DELETE CASCADE,
+    access_level VARCHAR(50) NOT NULL, -- 'read', 'write', 'owner'
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (calendar_id, group_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_calendar_group_shares_group ON caldav.calendar_group_shares(group_id);
+
+CREATE TABLE IF NOT EXISTS carddav.address_book_group_shares (
+    address_book_id UUID NOT NULL REFERENCES carddav.address_books(id) ON DELETE CASCADE,
+    group_id VARCHAR(36) NOT NULL REFERENCES auth.user_groups(id) ON DELETE CASCADE,
+    can_write BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (address_book_id, group_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_address_book_group_shares_group ON carddav.address_book_group_shares(group_id);
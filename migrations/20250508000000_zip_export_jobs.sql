@@ -0,0 +1,25 @@
+-- Background ZIP exports of large selections. The archive is built into a
+-- temporary file and checkpointed, so an export survives a restart
+
+CREATE TABLE IF NOT EXISTS auth.zip_export_jobs (
+    id VARCHAR(36) PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    file_name TEXT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending', -- 'pending', 'running', 'completed', 'failed'
+    file_ids JSONB NOT NULL,
+    folder_ids JSONB NOT NULL,
+    entries JSONB,                -- planned ZIP entries, once the selection has been walked
+    next_entry INTEGER NOT NULL DEFAULT 0,
+    checkpoint JSONB,             -- ZIP writer state after entry next_entry - 1
+    archive_size BIGINT NOT NULL DEFAULT 0,
+    temp_path TEXT NOT NULL,
+    error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    completed_at TIMESTAMP WITH TIME ZONE,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+-- Create indexes for per-user listing and expiry sweeps
+CREATE INDEX IF NOT EXISTS idx_zip_export_jobs_user_id ON auth.zip_export_jobs(user_id);
+CREATE INDEX IF NOT EXISTS idx_zip_export_jobs_expires_at ON auth.zip_export_jobs(expires_at);
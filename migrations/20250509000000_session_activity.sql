@@ -0,0 +1,5 @@
+-- Last activity of each login session, shown in the session list. A session
+-- keeps its id across token refreshes, so this is its last refresh or request
+
+ALTER TABLE auth.sessions
+    ADD COLUMN IF NOT EXISTS last_active_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP;
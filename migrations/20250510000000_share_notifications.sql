@@ -0,0 +1,17 @@
+-- Notices telling users that a file, folder, calendar or address book was
+-- shared with them or no longer is, kept until they dismiss them
+
+CREATE TABLE IF NOT EXISTS auth.share_notifications (
+    id VARCHAR(36) PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    change VARCHAR(16) NOT NULL,
+    item_kind VARCHAR(16) NOT NULL,
+    item_id VARCHAR(255) NOT NULL,
+    item_name TEXT NOT NULL,
+    actor_id VARCHAR(36),
+    actor_name TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    read_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_share_notifications_user ON auth.share_notifications(user_id, created_at DESC);
@@ -0,0 +1,10 @@
+-- Email notifications each user wants to receive; users without a row get
+-- every email
+
+CREATE TABLE IF NOT EXISTS auth.notification_preferences (
+    user_id VARCHAR(36) PRIMARY KEY REFERENCES auth.users(id) ON DELETE CASCADE,
+    share_received BOOLEAN NOT NULL DEFAULT TRUE,
+    share_downloaded BOOLEAN NOT NULL DEFAULT TRUE,
+    quota_warning BOOLEAN NOT NULL DEFAULT TRUE,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
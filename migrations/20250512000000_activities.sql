@@ -0,0 +1,20 @@
+-- Activity feed: who uploaded, renamed, deleted, shared or restored which
+-- file or folder, and when. Entries outlive their actor so folder feeds
+-- keep the history of deleted users
+
+CREATE TABLE IF NOT EXISTS auth.activities (
+    id VARCHAR(36) PRIMARY KEY,
+    actor_id VARCHAR(36) NOT NULL,
+    actor_name TEXT NOT NULL,
+    action VARCHAR(16) NOT NULL,
+    target_kind VARCHAR(16) NOT NULL,
+    target_id VARCHAR(255) NOT NULL,
+    target_name TEXT NOT NULL,
+    folder_id VARCHAR(255),
+    details TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_activities_actor ON auth.activities(actor_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_activities_folder ON auth.activities(folder_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_activities_target ON auth.activities(target_id, created_at DESC);
@@ -0,0 +1,36 @@
+-- Append-only audit log of security-relevant events: logins, failed logins,
+-- role changes and admin actions. Rows are never updated or deleted, and
+-- the trigger below rejects any attempt to do so
+
+CREATE TABLE IF NOT EXISTS auth.audit_log (
+    id VARCHAR(36) PRIMARY KEY,
+    occurred_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    action VARCHAR(32) NOT NULL,
+    -- No foreign key: entries must outlive the users they mention
+    actor_id VARCHAR(36),
+    actor_name TEXT,
+    target TEXT,
+    ip_address VARCHAR(64),
+    success BOOLEAN NOT NULL,
+    details TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_occurred ON auth.audit_log(occurred_at DESC);
+CREATE INDEX IF NOT EXISTS idx_audit_log_actor ON auth.audit_log(actor_id, occurred_at DESC);
+CREATE INDEX IF NOT EXISTS idx_audit_log_action ON auth.audit_log(action, occurred_at DESC);
+
+CREATE OR REPLACE FUNCTION auth.reject_audit_log_changes() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'auth.audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS audit_log_append_only ON auth.audit_log;
+CREATE TRIGGER audit_log_append_only
+BEFORE UPDATE OR DELETE ON auth.audit_log
+FOR EACH ROW EXECUTE FUNCTION auth.reject_audit_log_changes();
+
+DROP TRIGGER IF EXISTS audit_log_no_truncate ON auth.audit_log;
+CREATE TRIGGER audit_log_no_truncate
+BEFORE TRUNCATE ON auth.audit_log
+FOR EACH STATEMENT EXECUTE FUNCTION auth.reject_audit_log_changes();
@@ -0,0 +1,25 @@
+-- User-defined tags and their assignments to files and folders. Items live
+-- in storage, not in the database, so assignments keep the item ID and kind
+-- without a foreign key
+
+CREATE TABLE IF NOT EXISTS auth.tags (
+    id VARCHAR(36) PRIMARY KEY,
+    owner_id VARCHAR(36) NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    name VARCHAR(64) NOT NULL,
+    color VARCHAR(7) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- A user cannot have two tags with the same name, whatever the case
+CREATE UNIQUE INDEX IF NOT EXISTS idx_tags_owner_name ON auth.tags(owner_id, LOWER(name));
+
+CREATE TABLE IF NOT EXISTS auth.tag_assignments (
+    tag_id VARCHAR(36) NOT NULL REFERENCES auth.tags(id) ON DELETE CASCADE,
+    item_id VARCHAR(255) NOT NULL,
+    item_type VARCHAR(10) NOT NULL CHECK (item_type IN ('file', 'folder')),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (tag_id, item_id, item_type)
+);
+
+CREATE INDEX IF NOT EXISTS idx_tag_assignments_item ON auth.tag_assignments(item_id, item_type);
@@ -0,0 +1,16 @@
+-- Comments on files and folders. Comments outlive their author so the
+-- discussion stays readable; mentions keep the IDs of the mentioned users
+
+CREATE TABLE IF NOT EXISTS auth.comments (
+    id VARCHAR(36) PRIMARY KEY,
+    item_id VARCHAR(255) NOT NULL,
+    item_kind VARCHAR(16) NOT NULL,
+    author_id VARCHAR(36) NOT NULL,
+    author_name TEXT NOT NULL,
+    message TEXT NOT NULL,
+    mentions TEXT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    edited_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_comments_item ON auth.comments(item_id, item_kind, created_at);
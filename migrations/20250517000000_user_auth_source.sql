@@ -0,0 +1,8 @@
+-- Where an account authenticates. Only accounts created from the external
+-- directory (LDAP) follow it: a directory login never takes over a local
+-- account with the same username unless an administrator links it.
+-- Existing accounts are local; accounts created by earlier directory logins
+-- have to be linked again by an administrator.
+ALTER TABLE auth.users
+    ADD COLUMN IF NOT EXISTS auth_source TEXT NOT NULL DEFAULT 'local'
+    CHECK (auth_source IN ('local', 'directory'));
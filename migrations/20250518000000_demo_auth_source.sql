@@ -0,0 +1,8 @@
+-- Accounts created by the demo mode. A demo reset deletes the account with
+-- the configured username only when it carries this source, so a real
+-- account that happens to share the name is never removed. Demo accounts
+-- created before this have to be deleted once by an administrator.
+ALTER TABLE auth.users DROP CONSTRAINT IF EXISTS users_auth_source_check;
+ALTER TABLE auth.users
+    ADD CONSTRAINT users_auth_source_check
+    CHECK (auth_source IN ('local', 'directory', 'demo'));
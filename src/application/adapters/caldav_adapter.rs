@@ -5,6 +5,7 @@
  * It handles parsing CalDAV request XML and generating CalDAV response XML according to RFC 4791.
  */
 
+use std::collections::HashMap;
 use std::io::{Read, Write, BufReader};
 use chrono::{DateTime, Utc};
 use quick_xml::{Reader, Writer, events::{Event, BytesStart, BytesEnd, BytesText}};
@@ -12,13 +13,21 @@ use uuid::Uuid;
 
 use crate::application::adapters::webdav_adapter::{WebDavAdapter, QualifiedName, PropFindType, PropFindRequest, Result, WebDavError};
 use crate::application::dtos::calendar_dto::{CalendarDto, CalendarEventDto};
+use crate::application::dtos::dav_principal_dto::{
+    PrincipalDto, PrincipalKind, PrincipalSearchDto, PrincipalSearchProperty, PrincipalSearchResultDto, PropertyMatchDto,
+};
+use crate::application::dtos::scheduling_dto::{ScheduleRecipientDto, SchedulingMessageDto};
+use crate::domain::services::calendar_filter_service::{Collation, CompFilter, ParamFilter, PropFilter, TextMatch, TimeRange};
+use crate::domain::services::icalendar_service::parse_date_time;
+use crate::domain::services::path_codec_service::encode_segment;
 
 /// CalDAV report type
 #[derive(Debug, PartialEq)]
 pub enum CalDavReportType {
     /// Calendar-query report
     CalendarQuery {
-        time_range: Option<(DateTime<Utc>, DateTime<Utc>)>,
+        /// The `CALDAV:filter`, starting at the VCALENDAR comp-filter
+        filter: Option<CompFilter>,
         props: Vec<QualifiedName>,
     },
     /// Calendar-multiget report
@@ -29,182 +38,290 @@ pub enum CalDavReportType {
     /// Sync-collection report
     SyncCollection {
         sync_token: String,
+        /// `DAV:limit/nresults`, the most members the client wants back
+        limit: Option<usize>,
         props: Vec<QualifiedName>,
     }
 }
 
+/// Principal REPORTs of WebDAV ACL (RFC 3744, section 9)
+#[derive(Debug, PartialEq)]
+pub enum PrincipalReportType {
+    /// principal-search-property-set: which properties can be searched
+    SearchPropertySet,
+    /// principal-property-search
+    PropertySearch {
+        search: PrincipalSearchDto,
+        props: Vec<QualifiedName>,
+    },
+}
+
+/// URLs of the collections of the current user, reported to clients that
+/// discover their account (RFC 5397, RFC 4791 section 6.2.1, RFC 6352
+/// section 7.1.1 and RFC 6638 section 2)
+#[derive(Debug, Clone)]
+pub struct DiscoveryLinks {
+    pub principal_href: String,
+    pub principal_collection_href: String,
+    pub calendar_home_href: String,
+    pub addressbook_home_href: String,
+    pub inbox_href: String,
+    pub outbox_href: String,
+}
+
+impl CalDavReportType {
+    /// Time range a calendar-query asks events to overlap, if any
+    pub fn time_range(&self) -> Option<TimeRange> {
+        match self {
+            CalDavReportType::CalendarQuery { filter: Some(filter), .. } => filter.event_time_range(),
+            _ => None,
+        }
+    }
+}
+
+/// Filter element being built while parsing a calendar-query
+enum FilterNode {
+    Comp(CompFilter),
+    Prop(PropFilter),
+    Param(ParamFilter),
+}
+
+/// Root element of a REPORT body
+#[derive(Clone, Copy, PartialEq)]
+enum ReportKind {
+    CalendarQuery,
+    CalendarMultiget,
+    SyncCollection,
+}
+
 /// CalDAV adapter for converting between XML and domain objects
 pub struct CalDavAdapter;
 
 impl CalDavAdapter {
     /// Parse a REPORT XML request for CalDAV
+    /// 
+    /// Calendar-query filters are parsed in full (RFC 4791, section 9.7):
+    /// nested comp-filter, prop-filter and param-filter elements with their
+    /// is-not-defined, time-range and text-match conditions.
     pub fn parse_report<R: Read>(reader: R) -> Result<CalDavReportType> {
         let mut xml_reader = Reader::from_reader(BufReader::new(reader));
         xml_reader.config_mut().trim_text(true);
         
         let mut buffer = Vec::new();
-        let mut in_calendar_query = false;
-        let mut in_calendar_multiget = false;
-        let mut in_sync_collection = false;
-        let mut in_prop = false;
-        let mut in_filter = false;
-        let mut in_time_range = false;
-        let mut start_time: Option<DateTime<Utc>> = None;
-        let mut end_time: Option<DateTime<Utc>> = None;
+        let mut kind = None;
+        // Local names of the open elements
+        let mut path: Vec<String> = Vec::new();
+        // Depth of the <prop> element whose children are the requested properties
+        let mut prop_depth = None;
         let mut props = Vec::new();
         let mut hrefs = Vec::new();
         let mut sync_token = String::new();
+        let mut limit = None;
+        // Namespace prefixes declared so far (xmlns:D="DAV:"); "" is the default namespace
+        let mut namespaces: HashMap<String, String> = HashMap::new();
+        let mut filters: Vec<FilterNode> = Vec::new();
+        let mut root_filter = None;
+        let mut text_match: Option<TextMatch> = None;
         
         loop {
-            match xml_reader.read_event_into(&mut buffer) {
-                Ok(Event::Start(ref e)) => {
-                    let name = e.name();
-                    let name_str = std::str::from_utf8(name.as_ref()).unwrap_or("");
-                    
-                    match name_str {
-                        s if s == "calendar-query" || s.ends_with(":calendar-query") => in_calendar_query = true,
-                        s if s == "calendar-multiget" || s.ends_with(":calendar-multiget") => in_calendar_multiget = true,
-                        s if s == "sync-collection" || s.ends_with(":sync-collection") => in_sync_collection = true,
-                        s if s == "prop" || s.ends_with(":prop") => in_prop = true,
-                        s if s == "filter" || s.ends_with(":filter") => in_filter = true,
-                        s if s == "time-range" || s.ends_with(":time-range") => {
-                            in_time_range = true;
-                            
-                            // Parse time-range attributes
-                            for attr in e.attributes() {
-                                if let Ok(attr) = attr {
-                                    let attr_name = std::str::from_utf8(attr.key.as_ref()).unwrap_or("");
-                                    let attr_value = attr.unescape_value().unwrap_or_default();
-                                    
-                                    if attr_name == "start" {
-                                        // Parse ISO date format with Z for UTC
-                                        start_time = DateTime::parse_from_rfc3339(&attr_value)
-                                            .ok()
-                                            .map(|dt| dt.with_timezone(&Utc));
-                                    } else if attr_name == "end" {
-                                        end_time = DateTime::parse_from_rfc3339(&attr_value)
-                                            .ok()
-                                            .map(|dt| dt.with_timezone(&Utc));
-                                    }
-                                }
-                            }
-                        },
-                        s if s == "sync-token" || s.ends_with(":sync-token") => {
-                            // We'll capture the text in the Text event
-                        },
-                        s if s == "href" || s.ends_with(":href") => {
-                            // We'll capture the text in the Text event
+            let event = xml_reader.read_event_into(&mut buffer).map_err(WebDavError::XmlError)?;
+            let (element, is_empty) = match &event {
+                Event::Start(e) => (Some(e), false),
+                Event::Empty(e) => (Some(e), true),
+                _ => (None, false),
+            };
+            
+            if let Some(e) = element {
+                let name = e.name();
+                let name_str = std::str::from_utf8(name.as_ref()).unwrap_or("");
+                let local_name = WebDavAdapter::extract_local_name(name_str);
+                
+                Self::declare_namespaces(e, &mut namespaces);
+                
+                if prop_depth.is_some_and(|depth| path.len() == depth + 1) {
+                    // Add property to request, resolving its prefix to the declared namespace URI
+                    let prefix = name_str.rfind(':').map_or("", |idx| &name_str[..idx]);
+                    let namespace = namespaces.get(prefix).cloned()
+                        .unwrap_or_else(|| WebDavAdapter::extract_namespace(name_str));
+                    props.push(QualifiedName::new(namespace, local_name.clone()));
+                } else if path.is_empty() {
+                    kind = match local_name.as_str() {
+                        "calendar-query" => Some(ReportKind::CalendarQuery),
+                        "calendar-multiget" => Some(ReportKind::CalendarMultiget),
+                        "sync-collection" => Some(ReportKind::SyncCollection),
+                        _ => None,
+                    };
+                } else if prop_depth.is_none() {
+                    match local_name.as_str() {
+                        "prop" if path.len() == 1 => prop_depth = Some(path.len()),
+                        "comp-filter" => filters.push(FilterNode::Comp(CompFilter::new(&Self::name_attribute(e)))),
+                        "prop-filter" => filters.push(FilterNode::Prop(PropFilter::new(&Self::name_attribute(e)))),
+                        "param-filter" => filters.push(FilterNode::Param(ParamFilter::new(&Self::name_attribute(e)))),
+                        "is-not-defined" => match filters.last_mut() {
+                            Some(FilterNode::Comp(filter)) => filter.is_not_defined = true,
+                            Some(FilterNode::Prop(filter)) => filter.is_not_defined = true,
+                            Some(FilterNode::Param(filter)) => filter.is_not_defined = true,
+                            None => {}
                         },
-                        _ if in_prop => {
-                            // Add property to request
-                            let namespace = WebDavAdapter::extract_namespace(name_str);
-                            let prop_name = WebDavAdapter::extract_local_name(name_str);
-                            
-                            props.push(QualifiedName::new(namespace, prop_name));
+                        "time-range" => {
+                            let range = Self::parse_time_range(e)?;
+                            match filters.last_mut() {
+                                Some(FilterNode::Comp(filter)) => filter.time_range = Some(range),
+                                Some(FilterNode::Prop(filter)) => filter.time_range = Some(range),
+                                _ => {}
+                            }
                         },
-                        _ => { /* Ignore other elements */ }
-                    }
-                },
-                Ok(Event::Text(e)) => {
-                    let text = e.unescape().unwrap_or_default();
-                    
-                    // Check if we're in sync-token element
-                    if in_sync_collection && !in_prop && !in_filter {
-                        sync_token = text.to_string();
+                        "text-match" => text_match = Some(Self::parse_text_match(e)?),
+                        _ => {}
                     }
-                    
-                    // Check if we're in href element
-                    if (in_calendar_multiget || in_sync_collection) && !in_prop && !in_filter {
-                        hrefs.push(text.to_string());
+                }
+                
+                if is_empty {
+                    if prop_depth == Some(path.len()) {
+                        prop_depth = None;
                     }
-                },
-                Ok(Event::End(ref e)) => {
-                    let name = e.name();
-                    let name_str = std::str::from_utf8(name.as_ref()).unwrap_or("");
-                    
-                    match name_str {
-                        s if s == "calendar-query" || s.ends_with(":calendar-query") => in_calendar_query = false,
-                        s if s == "calendar-multiget" || s.ends_with(":calendar-multiget") => in_calendar_multiget = false,
-                        s if s == "sync-collection" || s.ends_with(":sync-collection") => in_sync_collection = false,
-                        s if s == "prop" || s.ends_with(":prop") => in_prop = false,
-                        s if s == "filter" || s.ends_with(":filter") => in_filter = false,
-                        s if s == "time-range" || s.ends_with(":time-range") => in_time_range = false,
-                        _ => ()
+                    Self::close_element(&local_name, &mut filters, &mut root_filter, &mut text_match);
+                } else {
+                    path.push(local_name);
+                }
+            }
+            
+            match event {
+                Event::Text(e) => {
+                    let text = e.unescape().unwrap_or_default();
+                    match path.last().map(String::as_str) {
+                        Some("text-match") => {
+                            if let Some(text_match) = text_match.as_mut() {
+                                text_match.text.push_str(&text);
+                            }
+                        },
+                        Some("sync-token") if prop_depth.is_none() => sync_token = text.to_string(),
+                        Some("nresults") if prop_depth.is_none() => limit = text.trim().parse().ok(),
+                        Some("href") if prop_depth.is_none() => hrefs.push(text.to_string()),
+                        _ => {}
                     }
                 },
-                Ok(Event::Empty(ref e)) => {
-                    let name = e.name();
-                    let name_str = std::str::from_utf8(name.as_ref()).unwrap_or("");
-                    
-                    if in_prop {
-                        // Add empty property element to request
-                        let namespace = WebDavAdapter::extract_namespace(name_str);
-                        let prop_name = WebDavAdapter::extract_local_name(name_str);
-                        
-                        props.push(QualifiedName::new(namespace, prop_name));
-                    } else if name_str == "time-range" || name_str.ends_with(":time-range") {
-                        // Parse time-range attributes
-                        for attr in e.attributes() {
-                            if let Ok(attr) = attr {
-                                let attr_name = std::str::from_utf8(attr.key.as_ref()).unwrap_or("");
-                                let attr_value = attr.unescape_value().unwrap_or_default();
-                                
-                                if attr_name == "start" {
-                                    // Parse ISO date format with Z for UTC
-                                    start_time = DateTime::parse_from_rfc3339(&attr_value)
-                                        .ok()
-                                        .map(|dt| dt.with_timezone(&Utc));
-                                } else if attr_name == "end" {
-                                    end_time = DateTime::parse_from_rfc3339(&attr_value)
-                                        .ok()
-                                        .map(|dt| dt.with_timezone(&Utc));
-                                }
-                            }
+                Event::End(_) => {
+                    if let Some(local_name) = path.pop() {
+                        if prop_depth == Some(path.len()) {
+                            prop_depth = None;
                         }
+                        Self::close_element(&local_name, &mut filters, &mut root_filter, &mut text_match);
                     }
                 },
-                Ok(Event::Eof) => break,
-                Err(e) => return Err(WebDavError::XmlError(e)),
+                Event::Eof => break,
                 _ => (),
             }
             
             buffer.clear();
         }
         
-        // Create the appropriate report type based on what we parsed
-        let report_type = if in_calendar_query {
-            // If both start and end time are present, create a time range
-            let time_range = if let (Some(start), Some(end)) = (start_time, end_time) {
-                Some((start, end))
-            } else {
-                None
-            };
-            
-            CalDavReportType::CalendarQuery {
-                time_range,
-                props,
-            }
-        } else if in_calendar_multiget {
-            CalDavReportType::CalendarMultiget {
+        // Create the appropriate report type based on the root element
+        let report_type = match kind {
+            Some(ReportKind::CalendarMultiget) => CalDavReportType::CalendarMultiget {
                 hrefs,
                 props,
-            }
-        } else if in_sync_collection {
-            CalDavReportType::SyncCollection {
+            },
+            Some(ReportKind::SyncCollection) => CalDavReportType::SyncCollection {
                 sync_token,
+                limit,
                 props,
-            }
-        } else {
-            // Default to empty calendar query
-            CalDavReportType::CalendarQuery {
-                time_range: None,
+            },
+            // Default to a calendar query
+            _ => CalDavReportType::CalendarQuery {
+                filter: root_filter,
                 props,
-            }
+            },
         };
         
         Ok(report_type)
     }
     
+    /// Records the `xmlns` declarations of an element
+    pub(crate) fn declare_namespaces(element: &BytesStart, namespaces: &mut HashMap<String, String>) {
+        for attribute in element.attributes().flatten() {
+            let key = std::str::from_utf8(attribute.key.as_ref()).unwrap_or("");
+            let prefix = match key.strip_prefix("xmlns") {
+                Some("") => "",
+                Some(rest) => match rest.strip_prefix(':') {
+                    Some(prefix) => prefix,
+                    None => continue,
+                },
+                None => continue,
+            };
+            if let Ok(value) = attribute.unescape_value() {
+                namespaces.insert(prefix.to_string(), value.to_string());
+            }
+        }
+    }
+    
+    /// Finishes the filter element that was just closed, attaching it to its parent
+    fn close_element(
+        local_name: &str,
+        filters: &mut Vec<FilterNode>,
+        root_filter: &mut Option<CompFilter>,
+        text_match: &mut Option<TextMatch>,
+    ) {
+        match local_name {
+            "comp-filter" | "prop-filter" | "param-filter" => {
+                let Some(node) = filters.pop() else { return };
+                match (filters.last_mut(), node) {
+                    (Some(FilterNode::Comp(parent)), FilterNode::Comp(filter)) => parent.comp_filters.push(filter),
+                    (Some(FilterNode::Comp(parent)), FilterNode::Prop(filter)) => parent.prop_filters.push(filter),
+                    (Some(FilterNode::Prop(parent)), FilterNode::Param(filter)) => parent.param_filters.push(filter),
+                    (None, FilterNode::Comp(filter)) => *root_filter = Some(filter),
+                    // Misplaced filters are ignored
+                    _ => {}
+                }
+            },
+            "text-match" => match (filters.last_mut(), text_match.take()) {
+                (Some(FilterNode::Prop(filter)), Some(text_match)) => filter.text_match = Some(text_match),
+                (Some(FilterNode::Param(filter)), Some(text_match)) => filter.text_match = Some(text_match),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+    
+    /// Returns the value of the `name` attribute of a filter element
+    fn name_attribute(element: &BytesStart) -> String {
+        Self::attribute(element, "name").unwrap_or_default()
+    }
+    
+    fn attribute(element: &BytesStart, name: &str) -> Option<String> {
+        element.attributes().flatten()
+            .find(|attr| attr.key.local_name().as_ref() == name.as_bytes())
+            .and_then(|attr| attr.unescape_value().ok())
+            .map(|value| value.into_owned())
+    }
+    
+    /// Parses the start and end of a time-range element
+    /// 
+    /// RFC 4791 uses the iCalendar UTC form (`20240101T000000Z`); RFC 3339
+    /// values are accepted too.
+    fn parse_time_range(element: &BytesStart) -> Result<TimeRange> {
+        let parse = |name: &str| -> Result<Option<DateTime<Utc>>> {
+            let Some(value) = Self::attribute(element, name) else { return Ok(None) };
+            parse_date_time(&value, false)
+                .map(|(instant, _)| instant)
+                .or_else(|| DateTime::parse_from_rfc3339(&value).ok().map(|dt| dt.with_timezone(&Utc)))
+                .map(Some)
+                .ok_or_else(|| WebDavError::ParseError(format!("Invalid time-range {}: {}", name, value)))
+        };
+        Ok(TimeRange::new(parse("start")?, parse("end")?))
+    }
+    
+    /// Parses the attributes of a text-match element; the text comes later
+    fn parse_text_match(element: &BytesStart) -> Result<TextMatch> {
+        let collation = match Self::attribute(element, "collation") {
+            Some(name) => Collation::parse(&name)
+                .ok_or_else(|| WebDavError::ParseError(format!("Unsupported collation: {}", name)))?,
+            None => Collation::default(),
+        };
+        let negate = Self::attribute(element, "negate-condition").is_some_and(|value| value == "yes");
+        Ok(TextMatch { text: String::new(), collation, negate })
+    }
+    
     /// Generate a PROPFIND response for calendars
     pub fn generate_calendars_propfind_response<W: Write>(
         writer: W,
@@ -327,7 +444,12 @@ impl CalDavAdapter {
         xml_writer.write_event(Event::End(BytesEnd::new("C:supported-calendar-component-set")))?;
         
         // Calendar timezone (empty for UTC)
-        xml_writer.write_event(Event::Empty(BytesStart::new("C:calendar-timezone")))?;
+        Self::write_calendar_timezone(xml_writer, calendar)?;
+        
+        // Collection tag, changes whenever any event in the calendar changes
+        xml_writer.write_event(Event::Start(BytesStart::new("CS:getctag")))?;
+        xml_writer.write_event(Event::Text(BytesText::new(&calendar.ctag)))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("CS:getctag")))?;
         
         // Calendar color
         if let Some(color) = &calendar.color {
@@ -389,6 +511,7 @@ impl CalDavAdapter {
         xml_writer.write_event(Event::Empty(BytesStart::new("C:supported-calendar-component-set")))?;
         xml_writer.write_event(Event::Empty(BytesStart::new("C:calendar-timezone")))?;
         xml_writer.write_event(Event::Empty(BytesStart::new("CS:calendar-color")))?;
+        xml_writer.write_event(Event::Empty(BytesStart::new("CS:getctag")))?;
         xml_writer.write_event(Event::Empty(BytesStart::new("C:calendar-access")))?;
         xml_writer.write_event(Event::Empty(BytesStart::new("D:current-user-privilege-set")))?;
         xml_writer.write_event(Event::Empty(BytesStart::new("C:calendar-description")))?;
@@ -454,7 +577,7 @@ impl CalDavAdapter {
                     xml_writer.write_event(Event::End(BytesEnd::new("C:supported-calendar-component-set")))?;
                 },
                 ("urn:ietf:params:xml:ns:caldav", "calendar-timezone") => {
-                    xml_writer.write_event(Event::Empty(BytesStart::new("C:calendar-timezone")))?;
+                    Self::write_calendar_timezone(xml_writer, calendar)?;
                 },
                 ("urn:ietf:params:xml:ns:caldav", "calendar-access") => {
                     xml_writer.write_event(Event::Empty(BytesStart::new("C:calendar-access")))?;
@@ -479,6 +602,11 @@ impl CalDavAdapter {
                         xml_writer.write_event(Event::Empty(BytesStart::new("CS:calendar-color")))?;
                     }
                 },
+                ("http://calendarserver.org/ns/", "getctag") => {
+                    xml_writer.write_event(Event::Start(BytesStart::new("CS:getctag")))?;
+                    xml_writer.write_event(Event::Text(BytesText::new(&calendar.ctag)))?;
+                    xml_writer.write_event(Event::End(BytesEnd::new("CS:getctag")))?;
+                },
                 
                 // Custom properties from the calendar
                 _ => {
@@ -544,7 +672,7 @@ impl CalDavAdapter {
         // Add responses for events
         for event in events {
             // Create the event href based on its UID
-            let href = format!("{}{}.ics", base_href, event.ical_uid);
+            let href = format!("{}{}.ics", base_href, encode_segment(&event.ical_uid));
             
             // Write event response
             Self::write_event_response(&mut xml_writer, event, &props, &href)?;
@@ -556,6 +684,601 @@ impl CalDavAdapter {
         Ok(())
     }
     
+    /// Generate a sync-collection response (RFC 6578, section 3.2)
+    ///
+    /// Changed events are returned with the requested properties and deleted
+    /// ones with a 404 status. When the result was truncated the collection
+    /// itself is reported with 507 so the client syncs again from the new token.
+    pub fn generate_sync_collection_response<W: Write>(
+        writer: W,
+        events: &[CalendarEventDto],
+        deleted_uids: &[String],
+        truncated: bool,
+        sync_token: &str,
+        props: &[QualifiedName],
+        collection_href: &str,
+    ) -> Result<()> {
+        let mut xml_writer = Writer::new(writer);
+        
+        xml_writer.write_event(Event::Start(BytesStart::new("D:multistatus").with_attributes([
+            ("xmlns:D", "DAV:"),
+            ("xmlns:C", "urn:ietf:params:xml:ns:caldav"),
+            ("xmlns:CS", "http://calendarserver.org/ns/"),
+        ])))?;
+        
+        for event in events {
+            let href = format!("{}{}.ics", collection_href, encode_segment(&event.ical_uid));
+            Self::write_event_response(&mut xml_writer, event, props, &href)?;
+        }
+        
+        for uid in deleted_uids {
+            Self::write_status_response(&mut xml_writer, &format!("{}{}.ics", collection_href, encode_segment(uid)), "HTTP/1.1 404 Not Found")?;
+        }
+        
+        if truncated {
+            Self::write_status_response(&mut xml_writer, collection_href, "HTTP/1.1 507 Insufficient Storage")?;
+        }
+        
+        xml_writer.write_event(Event::Start(BytesStart::new("D:sync-token")))?;
+        xml_writer.write_event(Event::Text(BytesText::new(sync_token)))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:sync-token")))?;
+        
+        xml_writer.write_event(Event::End(BytesEnd::new("D:multistatus")))?;
+        
+        Ok(())
+    }
+    
+    /// Generate the error body for an unknown or expired sync-token
+    pub fn generate_invalid_sync_token_error<W: Write>(writer: W) -> Result<()> {
+        let mut xml_writer = Writer::new(writer);
+        xml_writer.write_event(Event::Start(BytesStart::new("D:error").with_attributes([("xmlns:D", "DAV:")])))?;
+        xml_writer.write_event(Event::Empty(BytesStart::new("D:valid-sync-token")))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:error")))?;
+        Ok(())
+    }
+    
+    /// Generate a PROPFIND response for the scheduling inbox (RFC 6638, 2.2)
+    ///
+    /// Lists the inbox collection and, at depth 1, one `{id}.ics` resource
+    /// per pending iTIP message.
+    pub fn generate_schedule_inbox_response<W: Write>(
+        writer: W,
+        messages: &[SchedulingMessageDto],
+        depth: &str,
+        inbox_href: &str,
+    ) -> Result<()> {
+        let mut xml_writer = Writer::new(writer);
+        
+        xml_writer.write_event(Event::Start(BytesStart::new("D:multistatus").with_attributes([
+            ("xmlns:D", "DAV:"),
+            ("xmlns:C", "urn:ietf:params:xml:ns:caldav"),
+        ])))?;
+        
+        xml_writer.write_event(Event::Start(BytesStart::new("D:response")))?;
+        xml_writer.write_event(Event::Start(BytesStart::new("D:href")))?;
+        xml_writer.write_event(Event::Text(BytesText::new(inbox_href)))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:href")))?;
+        xml_writer.write_event(Event::Start(BytesStart::new("D:propstat")))?;
+        xml_writer.write_event(Event::Start(BytesStart::new("D:prop")))?;
+        xml_writer.write_event(Event::Start(BytesStart::new("D:resourcetype")))?;
+        xml_writer.write_event(Event::Empty(BytesStart::new("D:collection")))?;
+        xml_writer.write_event(Event::Empty(BytesStart::new("C:schedule-inbox")))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:resourcetype")))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:prop")))?;
+        Self::write_propstat_status(&mut xml_writer, "HTTP/1.1 200 OK")?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:response")))?;
+        
+        if depth != "0" {
+            for message in messages {
+                let href = format!("{}{}.ics", inbox_href, message.id);
+                xml_writer.write_event(Event::Start(BytesStart::new("D:response")))?;
+                xml_writer.write_event(Event::Start(BytesStart::new("D:href")))?;
+                xml_writer.write_event(Event::Text(BytesText::new(&href)))?;
+                xml_writer.write_event(Event::End(BytesEnd::new("D:href")))?;
+                xml_writer.write_event(Event::Start(BytesStart::new("D:propstat")))?;
+                xml_writer.write_event(Event::Start(BytesStart::new("D:prop")))?;
+                xml_writer.write_event(Event::Empty(BytesStart::new("D:resourcetype")))?;
+                xml_writer.write_event(Event::Start(BytesStart::new("D:getcontenttype")))?;
+                xml_writer.write_event(Event::Text(BytesText::new("text/calendar; charset=utf-8")))?;
+                xml_writer.write_event(Event::End(BytesEnd::new("D:getcontenttype")))?;
+                xml_writer.write_event(Event::Start(BytesStart::new("D:getetag")))?;
+                xml_writer.write_event(Event::Text(BytesText::new(&format!("\"{}\"", message.id))))?;
+                xml_writer.write_event(Event::End(BytesEnd::new("D:getetag")))?;
+                xml_writer.write_event(Event::Start(BytesStart::new("D:getlastmodified")))?;
+                xml_writer.write_event(Event::Text(BytesText::new(&message.created_at.to_rfc2822())))?;
+                xml_writer.write_event(Event::End(BytesEnd::new("D:getlastmodified")))?;
+                xml_writer.write_event(Event::End(BytesEnd::new("D:prop")))?;
+                Self::write_propstat_status(&mut xml_writer, "HTTP/1.1 200 OK")?;
+                xml_writer.write_event(Event::End(BytesEnd::new("D:response")))?;
+            }
+        }
+        
+        xml_writer.write_event(Event::End(BytesEnd::new("D:multistatus")))?;
+        
+        Ok(())
+    }
+    
+    /// Generate the schedule-response of a POST to the scheduling outbox
+    /// (RFC 6638, 3.2.9), with the delivery status of every recipient
+    pub fn generate_schedule_response<W: Write>(writer: W, results: &[ScheduleRecipientDto]) -> Result<()> {
+        let mut xml_writer = Writer::new(writer);
+        
+        xml_writer.write_event(Event::Start(BytesStart::new("C:schedule-response").with_attributes([
+            ("xmlns:D", "DAV:"),
+            ("xmlns:C", "urn:ietf:params:xml:ns:caldav"),
+        ])))?;
+        
+        for result in results {
+            xml_writer.write_event(Event::Start(BytesStart::new("C:response")))?;
+            xml_writer.write_event(Event::Start(BytesStart::new("C:recipient")))?;
+            xml_writer.write_event(Event::Start(BytesStart::new("D:href")))?;
+            xml_writer.write_event(Event::Text(BytesText::new(&result.recipient)))?;
+            xml_writer.write_event(Event::End(BytesEnd::new("D:href")))?;
+            xml_writer.write_event(Event::End(BytesEnd::new("C:recipient")))?;
+            xml_writer.write_event(Event::Start(BytesStart::new("C:request-status")))?;
+            xml_writer.write_event(Event::Text(BytesText::new(&result.request_status)))?;
+            xml_writer.write_event(Event::End(BytesEnd::new("C:request-status")))?;
+            xml_writer.write_event(Event::End(BytesEnd::new("C:response")))?;
+        }
+        
+        xml_writer.write_event(Event::End(BytesEnd::new("C:schedule-response")))?;
+        
+        Ok(())
+    }
+    
+    /// Parse a principal REPORT body (RFC 3744, sections 9.4 and 9.5)
+    pub fn parse_principal_report<R: Read>(reader: R) -> Result<PrincipalReportType> {
+        let mut xml_reader = Reader::from_reader(BufReader::new(reader));
+        xml_reader.config_mut().trim_text(true);
+        
+        let mut buffer = Vec::new();
+        let mut root = None;
+        let mut match_all = false;
+        // Local names of the open elements
+        let mut path: Vec<String> = Vec::new();
+        let mut namespaces: HashMap<String, String> = HashMap::new();
+        let mut props = Vec::new();
+        let mut matches = Vec::new();
+        // Property and match text of the property-search being read
+        let mut property = None;
+        let mut text = String::new();
+        
+        loop {
+            let event = xml_reader.read_event_into(&mut buffer).map_err(WebDavError::XmlError)?;
+            let (element, is_empty) = match &event {
+                Event::Start(e) => (Some(e), false),
+                Event::Empty(e) => (Some(e), true),
+                _ => (None, false),
+            };
+            
+            if let Some(e) = element {
+                let name = e.name();
+                let name_str = std::str::from_utf8(name.as_ref()).unwrap_or("");
+                let local_name = WebDavAdapter::extract_local_name(name_str);
+                
+                Self::declare_namespaces(e, &mut namespaces);
+                
+                let parents: Vec<&str> = path.iter().map(String::as_str).collect();
+                match parents.as_slice() {
+                    [] => {
+                        match_all = Self::attribute(e, "test").is_some_and(|test| test.eq_ignore_ascii_case("allof"));
+                        root = Some(local_name.clone());
+                    },
+                    [_, "prop"] => props.push(Self::qualified_name(name_str, &local_name, &namespaces)),
+                    [_, "property-search", "prop"] => {
+                        let searched = Self::qualified_name(name_str, &local_name, &namespaces);
+                        property = Some(match (searched.namespace.as_str(), searched.name.as_str()) {
+                            ("DAV:", "displayname") => PrincipalSearchProperty::DisplayName,
+                            ("urn:ietf:params:xml:ns:caldav", "calendar-user-address-set")
+                            | ("http://calendarserver.org/ns/", "email-address-set") => PrincipalSearchProperty::CalendarUserAddress,
+                            _ => return Err(WebDavError::ParseError(format!("Property {} is not searchable", searched.name))),
+                        });
+                    },
+                    _ => {}
+                }
+                
+                if !is_empty {
+                    path.push(local_name);
+                }
+            }
+            
+            match event {
+                Event::Text(e) => {
+                    if path.len() == 3 && path[1] == "property-search" && path[2] == "match" {
+                        text.push_str(&e.unescape().unwrap_or_default());
+                    }
+                },
+                Event::End(_) => {
+                    if let Some(local_name) = path.pop() {
+                        if local_name == "property-search" && path.len() == 1 {
+                            let property = property.take().ok_or_else(|| {
+                                WebDavError::ParseError("property-search without a property".to_string())
+                            })?;
+                            matches.push(PropertyMatchDto { property, text: std::mem::take(&mut text) });
+                        }
+                    }
+                },
+                Event::Eof => break,
+                _ => (),
+            }
+            
+            buffer.clear();
+        }
+        
+        match root.as_deref() {
+            Some("principal-search-property-set") => Ok(PrincipalReportType::SearchPropertySet),
+            Some("principal-property-search") => Ok(PrincipalReportType::PropertySearch {
+                search: PrincipalSearchDto { matches, match_all },
+                props,
+            }),
+            _ => Err(WebDavError::ParseError("Unsupported principal REPORT".to_string())),
+        }
+    }
+    
+    /// Resolves the prefix of an element name to its declared namespace URI
+    fn qualified_name(name_str: &str, local_name: &str, namespaces: &HashMap<String, String>) -> QualifiedName {
+        let prefix = name_str.rfind(':').map_or("", |idx| &name_str[..idx]);
+        let namespace = namespaces.get(prefix).cloned()
+            .unwrap_or_else(|| WebDavAdapter::extract_namespace(name_str));
+        QualifiedName::new(namespace, local_name.to_string())
+    }
+    
+    /// Generate the principal-search-property-set response (RFC 3744, section 9.5)
+    pub fn generate_principal_search_property_set<W: Write>(writer: W) -> Result<()> {
+        let mut xml_writer = Writer::new(writer);
+        
+        xml_writer.write_event(Event::Start(BytesStart::new("D:principal-search-property-set").with_attributes([
+            ("xmlns:D", "DAV:"),
+            ("xmlns:C", "urn:ietf:params:xml:ns:caldav"),
+        ])))?;
+        
+        for (property, description) in [("D:displayname", "Display name"), ("C:calendar-user-address-set", "E-mail address")] {
+            xml_writer.write_event(Event::Start(BytesStart::new("D:principal-search-property")))?;
+            xml_writer.write_event(Event::Start(BytesStart::new("D:prop")))?;
+            xml_writer.write_event(Event::Empty(BytesStart::new(property)))?;
+            xml_writer.write_event(Event::End(BytesEnd::new("D:prop")))?;
+            xml_writer.write_event(Event::Start(BytesStart::new("D:description").with_attributes([("xml:lang", "en")])))?;
+            xml_writer.write_event(Event::Text(BytesText::new(description)))?;
+            xml_writer.write_event(Event::End(BytesEnd::new("D:description")))?;
+            xml_writer.write_event(Event::End(BytesEnd::new("D:principal-search-property")))?;
+        }
+        
+        xml_writer.write_event(Event::End(BytesEnd::new("D:principal-search-property-set")))?;
+        
+        Ok(())
+    }
+    
+    /// Generate a principal-property-search response (RFC 3744, section 9.4)
+    ///
+    /// Principal URLs live below `principals_href` (`users/{name}/` and
+    /// `groups/{name}/`). Properties the requester may not see are reported
+    /// with 404, like unknown ones, and a truncated result adds a 507 entry
+    /// for the principal collection.
+    pub fn generate_principal_search_response<W: Write>(
+        writer: W,
+        result: &PrincipalSearchResultDto,
+        props: &[QualifiedName],
+        principals_href: &str,
+    ) -> Result<()> {
+        let mut xml_writer = Writer::new(writer);
+        
+        xml_writer.write_event(Event::Start(BytesStart::new("D:multistatus").with_attributes([
+            ("xmlns:D", "DAV:"),
+            ("xmlns:C", "urn:ietf:params:xml:ns:caldav"),
+            ("xmlns:CS", "http://calendarserver.org/ns/"),
+        ])))?;
+        
+        let default_props = [
+            QualifiedName::new("DAV:", "displayname"),
+            QualifiedName::new("DAV:", "principal-URL"),
+            QualifiedName::new("DAV:", "resourcetype"),
+        ];
+        let props = if props.is_empty() { &default_props[..] } else { props };
+        
+        for principal in &result.principals {
+            Self::write_principal_response(&mut xml_writer, principal, props, principals_href, None)?;
+        }
+        
+        if result.truncated {
+            Self::write_status_response(&mut xml_writer, principals_href, "HTTP/1.1 507 Insufficient Storage")?;
+        }
+        
+        xml_writer.write_event(Event::End(BytesEnd::new("D:multistatus")))?;
+        
+        Ok(())
+    }
+    
+    /// Generate the PROPFIND response of the principal of the current user
+    ///
+    /// Besides its own properties the principal reports where the calendar
+    /// and address book homes and the scheduling collections are, which is
+    /// how clients configure an account from the principal URL alone.
+    pub fn generate_principal_propfind_response<W: Write>(
+        writer: W,
+        principal: &PrincipalDto,
+        request: &PropFindRequest,
+        links: &DiscoveryLinks,
+    ) -> Result<()> {
+        let mut xml_writer = Writer::new(writer);
+        
+        xml_writer.write_event(Event::Start(BytesStart::new("D:multistatus").with_attributes([
+            ("xmlns:D", "DAV:"),
+            ("xmlns:C", "urn:ietf:params:xml:ns:caldav"),
+            ("xmlns:CS", "http://calendarserver.org/ns/"),
+        ])))?;
+        
+        let props = match &request.prop_find_type {
+            PropFindType::Prop(props) => props.clone(),
+            _ => vec![
+                QualifiedName::new("DAV:", "displayname"),
+                QualifiedName::new("DAV:", "resourcetype"),
+                QualifiedName::new("DAV:", "principal-URL"),
+                QualifiedName::new("DAV:", "current-user-principal"),
+                QualifiedName::new("urn:ietf:params:xml:ns:caldav", "calendar-home-set"),
+                QualifiedName::new("urn:ietf:params:xml:ns:carddav", "addressbook-home-set"),
+                QualifiedName::new("urn:ietf:params:xml:ns:caldav", "calendar-user-address-set"),
+                QualifiedName::new("urn:ietf:params:xml:ns:caldav", "schedule-inbox-URL"),
+                QualifiedName::new("urn:ietf:params:xml:ns:caldav", "schedule-outbox-URL"),
+            ],
+        };
+        Self::write_principal_response(&mut xml_writer, principal, &props, &links.principal_collection_href, Some(links))?;
+        
+        xml_writer.write_event(Event::End(BytesEnd::new("D:multistatus")))?;
+        
+        Ok(())
+    }
+    
+    /// Generate the PROPFIND response of a DAV context path, the collection
+    /// `/.well-known/caldav` and `/.well-known/carddav` redirect to (RFC 6764)
+    ///
+    /// It only knows the discovery properties; clients follow
+    /// `current-user-principal` from there.
+    pub fn generate_discovery_response<W: Write>(
+        writer: W,
+        href: &str,
+        request: &PropFindRequest,
+        links: &DiscoveryLinks,
+    ) -> Result<()> {
+        let mut xml_writer = Writer::new(writer);
+        
+        xml_writer.write_event(Event::Start(BytesStart::new("D:multistatus").with_attributes([
+            ("xmlns:D", "DAV:"),
+            ("xmlns:C", "urn:ietf:params:xml:ns:caldav"),
+            ("xmlns:CS", "http://calendarserver.org/ns/"),
+        ])))?;
+        xml_writer.write_event(Event::Start(BytesStart::new("D:response")))?;
+        xml_writer.write_event(Event::Start(BytesStart::new("D:href")))?;
+        xml_writer.write_event(Event::Text(BytesText::new(href)))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:href")))?;
+        
+        let props = match &request.prop_find_type {
+            PropFindType::Prop(props) => props.clone(),
+            _ => vec![
+                QualifiedName::new("DAV:", "resourcetype"),
+                QualifiedName::new("DAV:", "current-user-principal"),
+                QualifiedName::new("DAV:", "principal-collection-set"),
+            ],
+        };
+        
+        xml_writer.write_event(Event::Start(BytesStart::new("D:propstat")))?;
+        xml_writer.write_event(Event::Start(BytesStart::new("D:prop")))?;
+        let mut missing = Vec::new();
+        for prop in &props {
+            if prop.namespace == "DAV:" && prop.name == "resourcetype" {
+                xml_writer.write_event(Event::Start(BytesStart::new("D:resourcetype")))?;
+                xml_writer.write_event(Event::Empty(BytesStart::new("D:collection")))?;
+                xml_writer.write_event(Event::End(BytesEnd::new("D:resourcetype")))?;
+            } else if !Self::write_discovery_prop(&mut xml_writer, prop, links)? {
+                missing.push(prop);
+            }
+        }
+        xml_writer.write_event(Event::End(BytesEnd::new("D:prop")))?;
+        Self::write_propstat_status(&mut xml_writer, "HTTP/1.1 200 OK")?;
+        Self::write_missing_propstat(&mut xml_writer, &missing)?;
+        
+        xml_writer.write_event(Event::End(BytesEnd::new("D:response")))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:multistatus")))?;
+        
+        Ok(())
+    }
+    
+    /// URL of a principal below the principal collection
+    fn principal_href(principals_href: &str, kind: PrincipalKind, name: &str) -> String {
+        match kind {
+            PrincipalKind::User => format!("{}users/{}/", principals_href, name),
+            PrincipalKind::Group => format!("{}groups/{}/", principals_href, name),
+        }
+    }
+    
+    /// Write a principal with its properties, found ones under 200 and the rest under 404
+    fn write_principal_response<W: Write>(
+        xml_writer: &mut Writer<W>,
+        principal: &PrincipalDto,
+        props: &[QualifiedName],
+        principals_href: &str,
+        links: Option<&DiscoveryLinks>,
+    ) -> Result<()> {
+        let href = Self::principal_href(principals_href, principal.kind, &principal.name);
+        
+        xml_writer.write_event(Event::Start(BytesStart::new("D:response")))?;
+        
+        xml_writer.write_event(Event::Start(BytesStart::new("D:href")))?;
+        xml_writer.write_event(Event::Text(BytesText::new(&href)))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:href")))?;
+        
+        xml_writer.write_event(Event::Start(BytesStart::new("D:propstat")))?;
+        xml_writer.write_event(Event::Start(BytesStart::new("D:prop")))?;
+        
+        let write_hrefs = |xml_writer: &mut Writer<W>, element: &str, hrefs: &[String]| -> Result<()> {
+            xml_writer.write_event(Event::Start(BytesStart::new(element)))?;
+            for href in hrefs {
+                xml_writer.write_event(Event::Start(BytesStart::new("D:href")))?;
+                xml_writer.write_event(Event::Text(BytesText::new(href)))?;
+                xml_writer.write_event(Event::End(BytesEnd::new("D:href")))?;
+            }
+            xml_writer.write_event(Event::End(BytesEnd::new(element)))?;
+            Ok(())
+        };
+        
+        let mut missing = Vec::new();
+        for prop in props {
+            if let Some(links) = links {
+                if Self::write_discovery_prop(xml_writer, prop, links)? {
+                    continue;
+                }
+            }
+            match (prop.namespace.as_str(), prop.name.as_str()) {
+                ("DAV:", "displayname") => {
+                    xml_writer.write_event(Event::Start(BytesStart::new("D:displayname")))?;
+                    xml_writer.write_event(Event::Text(BytesText::new(&principal.display_name)))?;
+                    xml_writer.write_event(Event::End(BytesEnd::new("D:displayname")))?;
+                },
+                ("DAV:", "resourcetype") => {
+                    xml_writer.write_event(Event::Start(BytesStart::new("D:resourcetype")))?;
+                    xml_writer.write_event(Event::Empty(BytesStart::new("D:principal")))?;
+                    xml_writer.write_event(Event::End(BytesEnd::new("D:resourcetype")))?;
+                },
+                ("DAV:", "principal-URL") => {
+                    write_hrefs(xml_writer, "D:principal-URL", std::slice::from_ref(&href))?;
+                },
+                ("DAV:", "group-membership") => {
+                    let hrefs: Vec<String> = principal.group_membership.iter()
+                        .map(|group| Self::principal_href(principals_href, PrincipalKind::Group, group))
+                        .collect();
+                    write_hrefs(xml_writer, "D:group-membership", &hrefs)?;
+                },
+                ("DAV:", "group-member-set") if principal.group_member_set.is_some() => {
+                    let hrefs: Vec<String> = principal.group_member_set.iter().flatten()
+                        .map(|member| Self::principal_href(principals_href, PrincipalKind::User, member))
+                        .collect();
+                    write_hrefs(xml_writer, "D:group-member-set", &hrefs)?;
+                },
+                ("urn:ietf:params:xml:ns:caldav", "calendar-user-address-set") => {
+                    // The principal URL always identifies the user; the address only when visible
+                    let mut hrefs = vec![href.clone()];
+                    hrefs.extend(principal.email.iter().map(|email| format!("mailto:{}", email)));
+                    write_hrefs(xml_writer, "C:calendar-user-address-set", &hrefs)?;
+                },
+                ("urn:ietf:params:xml:ns:caldav", "calendar-user-type") => {
+                    let user_type = match principal.kind {
+                        PrincipalKind::User => "INDIVIDUAL",
+                        PrincipalKind::Group => "GROUP",
+                    };
+                    xml_writer.write_event(Event::Start(BytesStart::new("C:calendar-user-type")))?;
+                    xml_writer.write_event(Event::Text(BytesText::new(user_type)))?;
+                    xml_writer.write_event(Event::End(BytesEnd::new("C:calendar-user-type")))?;
+                },
+                ("http://calendarserver.org/ns/", "email-address-set") if principal.email.is_some() => {
+                    let email = principal.email.as_deref().unwrap_or_default();
+                    xml_writer.write_event(Event::Start(BytesStart::new("CS:email-address-set")))?;
+                    xml_writer.write_event(Event::Start(BytesStart::new("CS:email-address")))?;
+                    xml_writer.write_event(Event::Text(BytesText::new(email)))?;
+                    xml_writer.write_event(Event::End(BytesEnd::new("CS:email-address")))?;
+                    xml_writer.write_event(Event::End(BytesEnd::new("CS:email-address-set")))?;
+                },
+                _ => missing.push(prop),
+            }
+        }
+        
+        xml_writer.write_event(Event::End(BytesEnd::new("D:prop")))?;
+        xml_writer.write_event(Event::Start(BytesStart::new("D:status")))?;
+        xml_writer.write_event(Event::Text(BytesText::new("HTTP/1.1 200 OK")))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:status")))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:propstat")))?;
+        
+        Self::write_missing_propstat(xml_writer, &missing)?;
+        
+        xml_writer.write_event(Event::End(BytesEnd::new("D:response")))?;
+        
+        Ok(())
+    }
+    
+    /// Write the properties that point a client at the collections of the
+    /// current user; returns whether `prop` is one of them
+    fn write_discovery_prop<W: Write>(
+        xml_writer: &mut Writer<W>,
+        prop: &QualifiedName,
+        links: &DiscoveryLinks,
+    ) -> Result<bool> {
+        let (element, href) = match (prop.namespace.as_str(), prop.name.as_str()) {
+            ("DAV:", "current-user-principal") => (BytesStart::new("D:current-user-principal"), &links.principal_href),
+            ("DAV:", "principal-collection-set") => (BytesStart::new("D:principal-collection-set"), &links.principal_collection_href),
+            ("urn:ietf:params:xml:ns:caldav", "calendar-home-set") => (BytesStart::new("C:calendar-home-set"), &links.calendar_home_href),
+            ("urn:ietf:params:xml:ns:caldav", "schedule-inbox-URL") => (BytesStart::new("C:schedule-inbox-URL"), &links.inbox_href),
+            ("urn:ietf:params:xml:ns:caldav", "schedule-outbox-URL") => (BytesStart::new("C:schedule-outbox-URL"), &links.outbox_href),
+            // The CardDAV namespace is declared on the element, since CalDAV multistatus responses do not
+            ("urn:ietf:params:xml:ns:carddav", "addressbook-home-set") => (
+                BytesStart::new("addressbook-home-set").with_attributes([("xmlns", "urn:ietf:params:xml:ns:carddav")]),
+                &links.addressbook_home_href,
+            ),
+            _ => return Ok(false),
+        };
+        
+        let end = element.to_end().into_owned();
+        xml_writer.write_event(Event::Start(element))?;
+        xml_writer.write_event(Event::Start(BytesStart::new("D:href")))?;
+        xml_writer.write_event(Event::Text(BytesText::new(href)))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:href")))?;
+        xml_writer.write_event(Event::End(end))?;
+        Ok(true)
+    }
+    
+    /// Write the requested properties a resource does not have, under 404
+    fn write_missing_propstat<W: Write>(xml_writer: &mut Writer<W>, missing: &[&QualifiedName]) -> Result<()> {
+        if missing.is_empty() {
+            return Ok(());
+        }
+        
+        xml_writer.write_event(Event::Start(BytesStart::new("D:propstat")))?;
+        xml_writer.write_event(Event::Start(BytesStart::new("D:prop")))?;
+        for prop in missing {
+            let prefix = match prop.namespace.as_str() {
+                "DAV:" => "D",
+                "urn:ietf:params:xml:ns:caldav" => "C",
+                "http://calendarserver.org/ns/" => "CS",
+                _ => "",
+            };
+            if prefix.is_empty() {
+                xml_writer.write_event(Event::Empty(BytesStart::new(prop.name.as_str())
+                    .with_attributes([("xmlns", prop.namespace.as_str())])))?;
+            } else {
+                xml_writer.write_event(Event::Empty(BytesStart::new(format!("{}:{}", prefix, prop.name))))?;
+            }
+        }
+        xml_writer.write_event(Event::End(BytesEnd::new("D:prop")))?;
+        Self::write_propstat_status(xml_writer, "HTTP/1.1 404 Not Found")
+    }
+    
+    /// Write a response carrying only a status, without properties
+    fn write_status_response<W: Write>(
+        xml_writer: &mut Writer<W>,
+        href: &str,
+        status: &str,
+    ) -> Result<()> {
+        xml_writer.write_event(Event::Start(BytesStart::new("D:response")))?;
+        
+        xml_writer.write_event(Event::Start(BytesStart::new("D:href")))?;
+        xml_writer.write_event(Event::Text(BytesText::new(href)))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:href")))?;
+        
+        xml_writer.write_event(Event::Start(BytesStart::new("D:status")))?;
+        xml_writer.write_event(Event::Text(BytesText::new(status)))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:status")))?;
+        
+        xml_writer.write_event(Event::End(BytesEnd::new("D:response")))?;
+        
+        Ok(())
+    }
+    
+    /// Close a D:propstat with its status
+    fn write_propstat_status<W: Write>(xml_writer: &mut Writer<W>, status: &str) -> Result<()> {
+        xml_writer.write_event(Event::Start(BytesStart::new("D:status")))?;
+        xml_writer.write_event(Event::Text(BytesText::new(status)))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:status")))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:propstat")))?;
+        Ok(())
+    }
+    
     /// Write event properties as a response
     fn write_event_response<W: Write>(
         xml_writer: &mut Writer<W>,
@@ -631,9 +1354,48 @@ impl CalDavAdapter {
         
         // Calendar data (iCalendar format)
         xml_writer.write_event(Event::Start(BytesStart::new("C:calendar-data")))?;
-        // In a full implementation, we would generate a complete iCalendar component here
-        // For now, we'll just provide a basic example
-        let ical_data = format!(
+        let ical_data = Self::event_calendar_data(event);
+        xml_writer.write_event(Event::Text(BytesText::new(&ical_data)))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("C:calendar-data")))?;
+        
+        Ok(())
+    }
+    
+    /// Writes the calendar-timezone property (empty for UTC)
+    fn write_calendar_timezone<W: Write>(
+        xml_writer: &mut Writer<W>,
+        calendar: &CalendarDto,
+    ) -> Result<()> {
+        match &calendar.timezone {
+            Some(timezone) => {
+                xml_writer.write_event(Event::Start(BytesStart::new("C:calendar-timezone")))?;
+                xml_writer.write_event(Event::Text(BytesText::new(timezone)))?;
+                xml_writer.write_event(Event::End(BytesEnd::new("C:calendar-timezone")))?;
+            }
+            None => {
+                xml_writer.write_event(Event::Empty(BytesStart::new("C:calendar-timezone")))?;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Returns the iCalendar object of an event for calendar-data
+    /// 
+    /// The stored object is returned as the client sent it, so DTSTART/DTEND
+    /// keep their TZID and the VTIMEZONE components travel with them. Events
+    /// without stored data are rebuilt from their UTC fields.
+    fn event_calendar_data(event: &CalendarEventDto) -> String {
+        if event.ical_data.contains("BEGIN:VCALENDAR") {
+            return event.ical_data.clone();
+        }
+        if event.ical_data.contains("BEGIN:VEVENT") {
+            let vevent = event.ical_data.trim_end_matches(['\r', '\n']);
+            return format!(
+                "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//OxiCloud//NONSGML Calendar//EN\r\n{}\r\nEND:VCALENDAR\r\n",
+                vevent
+            );
+        }
+        format!(
             "BEGIN:VCALENDAR\r\n\
             VERSION:2.0\r\n\
             PRODID:-//OxiCloud//NONSGML Calendar//EN\r\n\
@@ -652,11 +1414,7 @@ impl CalDavAdapter {
             event.end_time.format("%Y%m%dT%H%M%SZ"),
             event.rrule.as_ref().map_or("".to_string(), |r| format!("RRULE:{}\r\n", r)),
             event.updated_at.format("%Y%m%dT%H%M%SZ"),
-        );
-        xml_writer.write_event(Event::Text(BytesText::new(&ical_data)))?;
-        xml_writer.write_event(Event::End(BytesEnd::new("C:calendar-data")))?;
-        
-        Ok(())
+        )
     }
     
     /// Write requested event properties
@@ -690,28 +1448,7 @@ impl CalDavAdapter {
                 // CalDAV namespace properties
                 ("urn:ietf:params:xml:ns:caldav", "calendar-data") => {
                     xml_writer.write_event(Event::Start(BytesStart::new("C:calendar-data")))?;
-                    // In a full implementation, we would generate a complete iCalendar component here
-                    // For now, we'll just provide a basic example
-                    let ical_data = format!(
-                        "BEGIN:VCALENDAR\r\n\
-                        VERSION:2.0\r\n\
-                        PRODID:-//OxiCloud//NONSGML Calendar//EN\r\n\
-                        BEGIN:VEVENT\r\n\
-                        UID:{}\r\n\
-                        SUMMARY:{}\r\n\
-                        DTSTART:{}\r\n\
-                        DTEND:{}\r\n\
-                        {}\
-                        DTSTAMP:{}\r\n\
-                        END:VEVENT\r\n\
-                        END:VCALENDAR\r\n",
-                        event.ical_uid,
-                        event.summary.replace("\n", "\\n"),
-                        event.start_time.format("%Y%m%dT%H%M%SZ"),
-                        event.end_time.format("%Y%m%dT%H%M%SZ"),
-                        event.rrule.as_ref().map_or("".to_string(), |r| format!("RRULE:{}\r\n", r)),
-                        event.updated_at.format("%Y%m%dT%H%M%SZ"),
-                    );
+                    let ical_data = Self::event_calendar_data(event);
                     xml_writer.write_event(Event::Text(BytesText::new(&ical_data)))?;
                     xml_writer.write_event(Event::End(BytesEnd::new("C:calendar-data")))?;
                 },
@@ -810,4 +1547,260 @@ impl CalDavAdapter {
         
         Ok((displayname, description, color))
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_propfind_emits_calendar_timezone() {
+        let timezone = "BEGIN:VCALENDAR\r\nBEGIN:VTIMEZONE\r\nTZID:Europe/Madrid\r\nEND:VTIMEZONE\r\nEND:VCALENDAR\r\n";
+        let calendar = CalendarDto {
+            id: "cal".to_string(),
+            name: "Work".to_string(),
+            timezone: Some(timezone.to_string()),
+            ..Default::default()
+        };
+        let request = PropFindRequest {
+            prop_find_type: PropFindType::Prop(vec![QualifiedName {
+                namespace: "urn:ietf:params:xml:ns:caldav".to_string(),
+                name: "calendar-timezone".to_string(),
+            }]),
+        };
+
+        let mut out = Vec::new();
+        CalDavAdapter::generate_calendars_propfind_response(&mut out, &[calendar], &request, "/caldav/").unwrap();
+        let xml = String::from_utf8(out).unwrap();
+        assert!(xml.contains("<C:calendar-timezone>BEGIN:VCALENDAR\r\nBEGIN:VTIMEZONE\r\nTZID:Europe/Madrid"));
+    }
+
+    #[test]
+    fn test_principal_propfind_reports_homes() {
+        let principal = PrincipalDto {
+            kind: PrincipalKind::User,
+            name: "alice".to_string(),
+            display_name: "alice".to_string(),
+            email: Some("alice@example.com".to_string()),
+            group_membership: Vec::new(),
+            group_member_set: Some(Vec::new()),
+        };
+        let links = DiscoveryLinks {
+            principal_href: "/caldav/principals/users/alice/".to_string(),
+            principal_collection_href: "/caldav/principals/".to_string(),
+            calendar_home_href: "/caldav".to_string(),
+            addressbook_home_href: "/carddav/addressbooks/".to_string(),
+            inbox_href: "/caldav/inbox/".to_string(),
+            outbox_href: "/caldav/outbox/".to_string(),
+        };
+        let request = PropFindRequest {
+            prop_find_type: PropFindType::Prop(vec![
+                QualifiedName::new("DAV:", "current-user-principal"),
+                QualifiedName::new("urn:ietf:params:xml:ns:caldav", "calendar-home-set"),
+                QualifiedName::new("urn:ietf:params:xml:ns:carddav", "addressbook-home-set"),
+                QualifiedName::new("DAV:", "quota-used-bytes"),
+            ]),
+        };
+
+        let mut out = Vec::new();
+        CalDavAdapter::generate_principal_propfind_response(&mut out, &principal, &request, &links).unwrap();
+        let xml = String::from_utf8(out).unwrap();
+        assert!(xml.contains("<D:href>/caldav/principals/users/alice/</D:href>"));
+        assert!(xml.contains("<C:calendar-home-set><D:href>/caldav</D:href></C:calendar-home-set>"));
+        assert!(xml.contains("<addressbook-home-set xmlns=\"urn:ietf:params:xml:ns:carddav\"><D:href>/carddav/addressbooks/</D:href></addressbook-home-set>"));
+        assert!(xml.contains("<D:quota-used-bytes/></D:prop><D:status>HTTP/1.1 404 Not Found"));
+    }
+
+    #[test]
+    fn test_calendar_data_keeps_stored_timezones() {
+        let event = CalendarEventDto {
+            ical_uid: "uid-1".to_string(),
+            ical_data: "BEGIN:VEVENT\r\nUID:uid-1\r\nDTSTART;TZID=Europe/Madrid:20240110T090000\r\nEND:VEVENT\r\n".to_string(),
+            ..Default::default()
+        };
+        let data = CalDavAdapter::event_calendar_data(&event);
+        assert!(data.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(data.contains("DTSTART;TZID=Europe/Madrid:20240110T090000\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n"));
+    }
+
+    #[test]
+    fn test_propfind_reports_ctag() {
+        let calendar = CalendarDto {
+            id: "cal".to_string(),
+            name: "Work".to_string(),
+            ctag: "abc-7".to_string(),
+            ..Default::default()
+        };
+        let request = PropFindRequest {
+            prop_find_type: PropFindType::Prop(vec![QualifiedName {
+                namespace: "http://calendarserver.org/ns/".to_string(),
+                name: "getctag".to_string(),
+            }]),
+        };
+
+        let mut out = Vec::new();
+        CalDavAdapter::generate_calendars_propfind_response(&mut out, &[calendar], &request, "/caldav/").unwrap();
+        let xml = String::from_utf8(out).unwrap();
+        assert!(xml.contains("<CS:getctag>abc-7</CS:getctag>"));
+    }
+
+    #[test]
+    fn test_parse_calendar_query_filters() {
+        let body = r#"<?xml version="1.0" encoding="utf-8" ?>
+<C:calendar-query xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:prop>
+    <D:getetag/>
+    <C:calendar-data>
+      <C:comp name="VCALENDAR"><C:comp name="VEVENT"/></C:comp>
+    </C:calendar-data>
+  </D:prop>
+  <C:filter>
+    <C:comp-filter name="VCALENDAR">
+      <C:comp-filter name="VEVENT">
+        <C:time-range start="20240101T000000Z" end="20240201T000000Z"/>
+        <C:prop-filter name="SUMMARY">
+          <C:text-match collation="i;octet" negate-condition="yes">Cancelled</C:text-match>
+        </C:prop-filter>
+        <C:prop-filter name="ATTENDEE">
+          <C:param-filter name="PARTSTAT">
+            <C:text-match>NEEDS-ACTION</C:text-match>
+          </C:param-filter>
+        </C:prop-filter>
+        <C:comp-filter name="VALARM"><C:is-not-defined/></C:comp-filter>
+      </C:comp-filter>
+    </C:comp-filter>
+  </C:filter>
+</C:calendar-query>"#;
+
+        let report = CalDavAdapter::parse_report(body.as_bytes()).unwrap();
+        let CalDavReportType::CalendarQuery { filter: Some(filter), props } = &report else {
+            panic!("unexpected report {:?}", report);
+        };
+        assert_eq!(props.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), ["getetag", "calendar-data"]);
+        assert_eq!(filter.name, "VCALENDAR");
+
+        let event = &filter.comp_filters[0];
+        assert_eq!(event.name, "VEVENT");
+        let range = report.time_range().unwrap();
+        assert_eq!(range.start.unwrap().to_rfc3339(), "2024-01-01T00:00:00+00:00");
+        assert_eq!(event.prop_filters.len(), 2);
+        let summary = event.prop_filters[0].text_match.as_ref().unwrap();
+        assert_eq!((summary.text.as_str(), summary.collation, summary.negate), ("Cancelled", Collation::Octet, true));
+        assert_eq!(event.prop_filters[1].param_filters[0].text_match.as_ref().unwrap().text, "NEEDS-ACTION");
+        assert!(event.comp_filters[0].is_not_defined);
+    }
+
+    #[test]
+    fn test_parse_multiget_hrefs() {
+        let body = r#"<C:calendar-multiget xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:prop><D:getetag/></D:prop>
+  <D:href>/caldav/work/a.ics</D:href>
+  <D:href>/caldav/work/b.ics</D:href>
+</C:calendar-multiget>"#;
+
+        let report = CalDavAdapter::parse_report(body.as_bytes()).unwrap();
+        assert_eq!(report, CalDavReportType::CalendarMultiget {
+            hrefs: vec!["/caldav/work/a.ics".to_string(), "/caldav/work/b.ics".to_string()],
+            props: vec![QualifiedName::new("DAV:".to_string(), "getetag".to_string())],
+        });
+    }
+
+    #[test]
+    fn test_parse_report_rejects_unknown_collations() {
+        let body = r#"<C:calendar-query xmlns:C="urn:ietf:params:xml:ns:caldav"><C:filter><C:comp-filter name="VCALENDAR"><C:comp-filter name="VEVENT"><C:prop-filter name="SUMMARY"><C:text-match collation="x-unknown">a</C:text-match></C:prop-filter></C:comp-filter></C:comp-filter></C:filter></C:calendar-query>"#;
+        assert!(CalDavAdapter::parse_report(body.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_sync_collection_report_round_trip() {
+        let body = r#"<?xml version="1.0" encoding="utf-8" ?>
+<D:sync-collection xmlns:D="DAV:">
+  <D:sync-token>http://oxicloud.org/ns/sync/abc/3</D:sync-token>
+  <D:sync-level>1</D:sync-level>
+  <D:limit><D:nresults>10</D:nresults></D:limit>
+  <D:prop><D:getetag/></D:prop>
+</D:sync-collection>"#;
+
+        let report = CalDavAdapter::parse_report(body.as_bytes()).unwrap();
+        let CalDavReportType::SyncCollection { sync_token, limit, props } = &report else {
+            panic!("unexpected report {:?}", report);
+        };
+        assert_eq!(sync_token, "http://oxicloud.org/ns/sync/abc/3");
+        assert_eq!(*limit, Some(10));
+
+        let event = CalendarEventDto {
+            id: "ev1".to_string(),
+            ical_uid: "uid-1".to_string(),
+            ..Default::default()
+        };
+        let mut out = Vec::new();
+        CalDavAdapter::generate_sync_collection_response(
+            &mut out, &[event], &["gone".to_string()], true, "http://oxicloud.org/ns/sync/abc/9", props, "/api/caldav/abc/",
+        ).unwrap();
+        let xml = String::from_utf8(out).unwrap();
+        assert!(xml.contains("<D:href>/api/caldav/abc/uid-1.ics</D:href><D:propstat><D:prop><D:getetag>&quot;ev1&quot;</D:getetag>"));
+        assert!(xml.contains("<D:href>/api/caldav/abc/gone.ics</D:href><D:status>HTTP/1.1 404 Not Found</D:status>"));
+        assert!(xml.contains("<D:href>/api/caldav/abc/</D:href><D:status>HTTP/1.1 507 Insufficient Storage</D:status>"));
+        assert!(xml.ends_with("<D:sync-token>http://oxicloud.org/ns/sync/abc/9</D:sync-token></D:multistatus>"));
+    }
+
+    #[test]
+    fn test_principal_property_search_round_trip() {
+        let body = r#"<?xml version="1.0"?>
+<D:principal-property-search xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav" test="allof">
+  <D:property-search><D:prop><D:displayname/></D:prop><D:match>ali</D:match></D:property-search>
+  <D:property-search><D:prop><C:calendar-user-address-set/></D:prop><D:match>alice@example.com</D:match></D:property-search>
+  <D:prop><D:displayname/><D:group-member-set/><C:calendar-user-address-set/></D:prop>
+</D:principal-property-search>"#;
+
+        let PrincipalReportType::PropertySearch { search, props } = CalDavAdapter::parse_principal_report(body.as_bytes()).unwrap() else {
+            panic!("expected principal-property-search");
+        };
+        assert!(search.match_all);
+        assert_eq!(search.matches, vec![
+            PropertyMatchDto { property: PrincipalSearchProperty::DisplayName, text: "ali".to_string() },
+            PropertyMatchDto { property: PrincipalSearchProperty::CalendarUserAddress, text: "alice@example.com".to_string() },
+        ]);
+        assert_eq!(props.len(), 3);
+
+        let result = PrincipalSearchResultDto {
+            principals: vec![PrincipalDto {
+                kind: PrincipalKind::Group,
+                name: "admin".to_string(),
+                display_name: "Administrators".to_string(),
+                email: None,
+                group_membership: Vec::new(),
+                group_member_set: None,
+            }],
+            truncated: true,
+        };
+        let mut output = Vec::new();
+        CalDavAdapter::generate_principal_search_response(&mut output, &result, &props, "/api/caldav/principals/").unwrap();
+        let xml = String::from_utf8(output).unwrap();
+        assert!(xml.contains("<D:href>/api/caldav/principals/groups/admin/</D:href>"));
+        // Hidden member sets are reported like unknown properties
+        assert!(xml.contains("<D:prop><D:group-member-set/></D:prop><D:status>HTTP/1.1 404 Not Found</D:status>"));
+        assert!(xml.contains("<C:calendar-user-address-set><D:href>/api/caldav/principals/groups/admin/</D:href></C:calendar-user-address-set>"));
+        assert!(xml.contains("<D:href>/api/caldav/principals/</D:href><D:status>HTTP/1.1 507 Insufficient Storage</D:status>"));
+
+        let unsupported = r#"<D:principal-property-search xmlns:D="DAV:"><D:property-search><D:prop><D:getetag/></D:prop><D:match>x</D:match></D:property-search></D:principal-property-search>"#;
+        assert!(CalDavAdapter::parse_principal_report(unsupported.as_bytes()).is_err());
+        assert_eq!(
+            CalDavAdapter::parse_principal_report(r#"<D:principal-search-property-set xmlns:D="DAV:"/>"#.as_bytes()).unwrap(),
+            PrincipalReportType::SearchPropertySet
+        );
+    }
+
+    #[test]
+    fn test_schedule_response_lists_recipients() {
+        let results = vec![
+            ScheduleRecipientDto { recipient: "mailto:john@example.com".to_string(), request_status: "1.2;Delivered".to_string() },
+            ScheduleRecipientDto { recipient: "mailto:eve@elsewhere.org".to_string(), request_status: "3.7;Invalid calendar user".to_string() },
+        ];
+        let mut out = Vec::new();
+        CalDavAdapter::generate_schedule_response(&mut out, &results).unwrap();
+        let xml = String::from_utf8(out).unwrap();
+        assert!(xml.starts_with("<C:schedule-response"));
+        assert!(xml.contains("<C:response><C:recipient><D:href>mailto:john@example.com</D:href></C:recipient><C:request-status>1.2;Delivered</C:request-status></C:response>"));
+        assert!(xml.contains("<C:request-status>3.7;Invalid calendar user</C:request-status>"));
+    }
+}
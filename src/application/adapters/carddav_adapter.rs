@@ -0,0 +1,788 @@
+/**
+ * CardDAV Adapter Module
+ *
+ * This module provides conversion between CardDAV protocol XML structures and OxiCloud domain objects.
+ * It handles parsing CardDAV request XML and generating CardDAV response XML according to RFC 6352.
+ */
+
+use std::collections::HashMap;
+use std::io::{Read, Write, BufReader};
+use quick_xml::{Reader, Writer, events::{Event, BytesStart, BytesEnd, BytesText}};
+
+use crate::application::adapters::caldav_adapter::CalDavAdapter;
+use crate::application::adapters::webdav_adapter::{WebDavAdapter, QualifiedName, PropFindType, PropFindRequest, Result, WebDavError};
+use crate::application::dtos::address_book_dto::AddressBookDto;
+use crate::application::dtos::contact_dto::VCardObjectDto;
+use crate::domain::services::calendar_filter_service::Collation;
+use crate::domain::services::path_codec_service::encode_segment;
+use crate::domain::services::vcard_filter_service::{
+    AddressbookFilter, CardParamFilter, CardPropFilter, CardTextMatch, FilterTest, MatchType,
+};
+
+const DAV_NS: &str = "DAV:";
+const CARDDAV_NS: &str = "urn:ietf:params:xml:ns:carddav";
+const CALENDARSERVER_NS: &str = "http://calendarserver.org/ns/";
+
+/// Content type of the vCard resources
+pub const VCARD_CONTENT_TYPE: &str = "text/vcard; charset=utf-8";
+
+/// CardDAV report type
+#[derive(Debug, PartialEq)]
+pub enum CardDavReportType {
+    /// Addressbook-query report (RFC 6352, section 8.6)
+    AddressbookQuery {
+        filter: AddressbookFilter,
+        /// `CARDDAV:limit/nresults`, the most vCards the client wants back
+        limit: Option<usize>,
+        props: Vec<QualifiedName>,
+    },
+    /// Addressbook-multiget report (RFC 6352, section 8.7)
+    AddressbookMultiget {
+        hrefs: Vec<String>,
+        props: Vec<QualifiedName>,
+    },
+    /// Sync-collection report (RFC 6578, section 3.2)
+    SyncCollection {
+        sync_token: String,
+        /// `DAV:limit/nresults`, the most changes the client wants back
+        limit: Option<usize>,
+        props: Vec<QualifiedName>,
+    },
+}
+
+/// Resource whose properties are written in a multistatus response
+enum Resource<'a> {
+    /// The address book home of the current user, with the URL of their
+    /// principal
+    Home(&'a str),
+    AddressBook(&'a AddressBookDto),
+    VCard(&'a VCardObjectDto),
+}
+
+impl Resource<'_> {
+    /// Properties returned for `allprop`
+    fn default_props(&self) -> Vec<QualifiedName> {
+        let names: &[(&str, &str)] = match self {
+            Resource::Home(_) => &[(DAV_NS, "resourcetype"), (DAV_NS, "displayname")],
+            Resource::AddressBook(_) => &[
+                (DAV_NS, "resourcetype"),
+                (DAV_NS, "displayname"),
+                (DAV_NS, "getetag"),
+                (CALENDARSERVER_NS, "getctag"),
+                (DAV_NS, "sync-token"),
+                (CARDDAV_NS, "addressbook-description"),
+                (CARDDAV_NS, "supported-address-data"),
+                (DAV_NS, "supported-report-set"),
+            ],
+            Resource::VCard(_) => &[
+                (DAV_NS, "resourcetype"),
+                (DAV_NS, "getetag"),
+                (DAV_NS, "getcontenttype"),
+                (DAV_NS, "getcontentlength"),
+                (DAV_NS, "getlastmodified"),
+            ],
+        };
+        names.iter().map(|(namespace, name)| QualifiedName::new(*namespace, *name)).collect()
+    }
+
+    /// Whether the resource has a value for a property
+    fn supports(&self, prop: &QualifiedName) -> bool {
+        match (prop.namespace.as_str(), prop.name.as_str()) {
+            (DAV_NS, "resourcetype") => true,
+            (DAV_NS, "displayname") => !matches!(self, Resource::VCard(_)),
+            (DAV_NS, "getetag") => !matches!(self, Resource::Home(_)),
+            (DAV_NS, "current-user-principal") => matches!(self, Resource::Home(_)),
+            (CALENDARSERVER_NS, "getctag")
+            | (DAV_NS, "sync-token")
+            | (CARDDAV_NS, "addressbook-description")
+            | (CARDDAV_NS, "supported-address-data")
+            | (DAV_NS, "supported-report-set") => matches!(self, Resource::AddressBook(_)),
+            (DAV_NS, "getcontenttype")
+            | (DAV_NS, "getcontentlength")
+            | (DAV_NS, "getlastmodified")
+            | (CARDDAV_NS, "address-data") => matches!(self, Resource::VCard(_)),
+            _ => false,
+        }
+    }
+}
+
+/// Root element of a REPORT body
+#[derive(Clone, Copy, PartialEq)]
+enum ReportKind {
+    AddressbookQuery,
+    AddressbookMultiget,
+    SyncCollection,
+}
+
+/// CardDAV adapter for converting between XML and domain objects
+pub struct CardDavAdapter;
+
+impl CardDavAdapter {
+    /// Parse a PROPFIND XML request, resolving property namespaces
+    ///
+    /// An empty body asks for all properties (RFC 4918, section 9.1).
+    pub fn parse_propfind<R: Read>(reader: R) -> Result<PropFindRequest> {
+        let mut xml_reader = Reader::from_reader(BufReader::new(reader));
+        xml_reader.config_mut().trim_text(true);
+
+        let mut buffer = Vec::new();
+        let mut path: Vec<String> = Vec::new();
+        let mut namespaces: HashMap<String, String> = HashMap::new();
+        let mut props = Vec::new();
+        let mut prop_find_type = None;
+
+        loop {
+            let event = xml_reader.read_event_into(&mut buffer).map_err(WebDavError::XmlError)?;
+            let (element, is_empty) = match &event {
+                Event::Start(e) => (Some(e), false),
+                Event::Empty(e) => (Some(e), true),
+                _ => (None, false),
+            };
+
+            if let Some(e) = element {
+                CalDavAdapter::declare_namespaces(e, &mut namespaces);
+                let (namespace, local_name) = Self::qualified_name(e, &namespaces);
+
+                match (path.len(), local_name.as_str()) {
+                    (1, "allprop") => prop_find_type = Some(PropFindType::AllProp),
+                    (1, "propname") => prop_find_type = Some(PropFindType::PropName),
+                    (2, _) if path[1] == "prop" => props.push(QualifiedName::new(namespace, local_name.clone())),
+                    _ => {}
+                }
+
+                if !is_empty {
+                    path.push(local_name);
+                }
+            }
+
+            match event {
+                Event::End(_) => {
+                    path.pop();
+                },
+                Event::Eof => break,
+                _ => (),
+            }
+
+            buffer.clear();
+        }
+
+        Ok(PropFindRequest {
+            prop_find_type: prop_find_type.unwrap_or(if props.is_empty() {
+                PropFindType::AllProp
+            } else {
+                PropFindType::Prop(props)
+            }),
+        })
+    }
+
+    /// Parse a REPORT XML request for CardDAV
+    ///
+    /// Addressbook-query filters are parsed in full (RFC 6352, section 10.5):
+    /// prop-filter and param-filter elements with their test, is-not-defined
+    /// and text-match conditions.
+    pub fn parse_report<R: Read>(reader: R) -> Result<CardDavReportType> {
+        let mut xml_reader = Reader::from_reader(BufReader::new(reader));
+        xml_reader.config_mut().trim_text(true);
+
+        let mut buffer = Vec::new();
+        let mut kind = None;
+        // Local names of the open elements
+        let mut path: Vec<String> = Vec::new();
+        let mut namespaces: HashMap<String, String> = HashMap::new();
+        let mut props = Vec::new();
+        let mut hrefs = Vec::new();
+        let mut sync_token = String::new();
+        let mut limit = None;
+        let mut filter = AddressbookFilter::default();
+        let mut prop_filter: Option<CardPropFilter> = None;
+        let mut param_filter: Option<CardParamFilter> = None;
+        let mut text_match: Option<CardTextMatch> = None;
+
+        loop {
+            let event = xml_reader.read_event_into(&mut buffer).map_err(WebDavError::XmlError)?;
+            let (element, is_empty) = match &event {
+                Event::Start(e) => (Some(e), false),
+                Event::Empty(e) => (Some(e), true),
+                _ => (None, false),
+            };
+
+            if let Some(e) = element {
+                CalDavAdapter::declare_namespaces(e, &mut namespaces);
+                let (namespace, local_name) = Self::qualified_name(e, &namespaces);
+                let in_prop = path.len() == 2 && path[1] == "prop";
+
+                if in_prop {
+                    props.push(QualifiedName::new(namespace, local_name.clone()));
+                } else if path.is_empty() {
+                    kind = match local_name.as_str() {
+                        "addressbook-query" => Some(ReportKind::AddressbookQuery),
+                        "addressbook-multiget" => Some(ReportKind::AddressbookMultiget),
+                        "sync-collection" => Some(ReportKind::SyncCollection),
+                        other => return Err(WebDavError::ParseError(format!("Unsupported report: {}", other))),
+                    };
+                } else if !path.iter().any(|name| name == "prop") {
+                    match local_name.as_str() {
+                        "filter" => filter.test = Self::test_attribute(e)?,
+                        "prop-filter" => {
+                            let mut new_filter = CardPropFilter::new(&Self::attribute(e, "name").unwrap_or_default());
+                            new_filter.test = Self::test_attribute(e)?;
+                            prop_filter = Some(new_filter);
+                        },
+                        "param-filter" => param_filter = Some(CardParamFilter::new(&Self::attribute(e, "name").unwrap_or_default())),
+                        "is-not-defined" => match (param_filter.as_mut(), prop_filter.as_mut()) {
+                            (Some(param), _) => param.is_not_defined = true,
+                            (None, Some(prop)) => prop.is_not_defined = true,
+                            _ => {}
+                        },
+                        "text-match" => text_match = Some(Self::parse_text_match(e)?),
+                        _ => {}
+                    }
+                }
+
+                if is_empty {
+                    Self::close_filter_element(&local_name, &mut filter, &mut prop_filter, &mut param_filter, &mut text_match);
+                } else {
+                    path.push(local_name);
+                }
+            }
+
+            match event {
+                Event::Text(e) => {
+                    let text = e.unescape().unwrap_or_default();
+                    let in_prop = path.iter().any(|name| name == "prop");
+                    match path.last().map(String::as_str) {
+                        Some("text-match") => {
+                            if let Some(text_match) = text_match.as_mut() {
+                                text_match.text.push_str(&text);
+                            }
+                        },
+                        Some("nresults") if !in_prop => limit = text.trim().parse().ok(),
+                        Some("href") if !in_prop => hrefs.push(text.to_string()),
+                        Some("sync-token") if !in_prop => sync_token = text.to_string(),
+                        _ => {}
+                    }
+                },
+                Event::End(_) => {
+                    if let Some(local_name) = path.pop() {
+                        Self::close_filter_element(&local_name, &mut filter, &mut prop_filter, &mut param_filter, &mut text_match);
+                    }
+                },
+                Event::Eof => break,
+                _ => (),
+            }
+
+            buffer.clear();
+        }
+
+        match kind {
+            Some(ReportKind::AddressbookQuery) => Ok(CardDavReportType::AddressbookQuery { filter, limit, props }),
+            Some(ReportKind::AddressbookMultiget) => Ok(CardDavReportType::AddressbookMultiget { hrefs, props }),
+            Some(ReportKind::SyncCollection) => Ok(CardDavReportType::SyncCollection { sync_token, limit, props }),
+            None => Err(WebDavError::ParseError("Empty REPORT body".to_string())),
+        }
+    }
+
+    /// Namespace URI and local name of an element
+    fn qualified_name(element: &BytesStart, namespaces: &HashMap<String, String>) -> (String, String) {
+        let name = element.name();
+        let name_str = std::str::from_utf8(name.as_ref()).unwrap_or("");
+        let prefix = name_str.rfind(':').map_or("", |idx| &name_str[..idx]);
+        let namespace = namespaces.get(prefix).cloned()
+            .unwrap_or_else(|| WebDavAdapter::extract_namespace(name_str));
+        (namespace, WebDavAdapter::extract_local_name(name_str))
+    }
+
+    /// Attaches a filter element that was just closed to its parent
+    fn close_filter_element(
+        local_name: &str,
+        filter: &mut AddressbookFilter,
+        prop_filter: &mut Option<CardPropFilter>,
+        param_filter: &mut Option<CardParamFilter>,
+        text_match: &mut Option<CardTextMatch>,
+    ) {
+        match local_name {
+            "text-match" => match (param_filter.as_mut(), prop_filter.as_mut(), text_match.take()) {
+                (Some(param), _, Some(text_match)) => param.text_match = Some(text_match),
+                (None, Some(prop), Some(text_match)) => prop.text_matches.push(text_match),
+                _ => {}
+            },
+            "param-filter" => {
+                if let (Some(prop), Some(param)) = (prop_filter.as_mut(), param_filter.take()) {
+                    prop.param_filters.push(param);
+                }
+            },
+            "prop-filter" => {
+                if let Some(prop) = prop_filter.take() {
+                    filter.prop_filters.push(prop);
+                }
+            },
+            _ => {}
+        }
+    }
+
+    fn attribute(element: &BytesStart, name: &str) -> Option<String> {
+        element.attributes().flatten()
+            .find(|attr| attr.key.local_name().as_ref() == name.as_bytes())
+            .and_then(|attr| attr.unescape_value().ok())
+            .map(|value| value.into_owned())
+    }
+
+    /// Parses the `test` attribute of a filter or prop-filter
+    fn test_attribute(element: &BytesStart) -> Result<FilterTest> {
+        match Self::attribute(element, "test") {
+            Some(value) => FilterTest::parse(&value)
+                .ok_or_else(|| WebDavError::ParseError(format!("Invalid filter test: {}", value))),
+            None => Ok(FilterTest::default()),
+        }
+    }
+
+    /// Parses the attributes of a text-match element; the text comes later
+    fn parse_text_match(element: &BytesStart) -> Result<CardTextMatch> {
+        let mut text_match = CardTextMatch::new("");
+        if let Some(name) = Self::attribute(element, "collation") {
+            text_match.collation = Collation::parse(&name)
+                .ok_or_else(|| WebDavError::ParseError(format!("Unsupported collation: {}", name)))?;
+        }
+        if let Some(value) = Self::attribute(element, "match-type") {
+            text_match.match_type = MatchType::parse(&value)
+                .ok_or_else(|| WebDavError::ParseError(format!("Unsupported match-type: {}", value)))?;
+        }
+        text_match.negate = Self::attribute(element, "negate-condition").is_some_and(|value| value == "yes");
+        Ok(text_match)
+    }
+
+    /// Generate a PROPFIND response for the address book home of a user
+    ///
+    /// Lists the home collection, which points clients at the principal of
+    /// the user, and at depth 1 every address book the user can see.
+    pub fn generate_home_response<W: Write>(
+        writer: W,
+        address_books: &[AddressBookDto],
+        request: &PropFindRequest,
+        depth: &str,
+        home_href: &str,
+        principal_href: &str,
+    ) -> Result<()> {
+        let mut xml_writer = Writer::new(writer);
+        Self::start_multistatus(&mut xml_writer)?;
+
+        Self::write_response(&mut xml_writer, home_href, &Resource::Home(principal_href), &request.prop_find_type)?;
+        if depth != "0" {
+            for address_book in address_books {
+                let href = format!("{}{}/", home_href, address_book.id);
+                Self::write_response(&mut xml_writer, &href, &Resource::AddressBook(address_book), &request.prop_find_type)?;
+            }
+        }
+
+        xml_writer.write_event(Event::End(BytesEnd::new("D:multistatus")))?;
+        Ok(())
+    }
+
+    /// Generate a PROPFIND response for an address book collection
+    ///
+    /// At depth 1 every vCard of the collection is listed as `{uid}.vcf`.
+    pub fn generate_address_book_response<W: Write>(
+        writer: W,
+        address_book: &AddressBookDto,
+        vcards: &[VCardObjectDto],
+        request: &PropFindRequest,
+        depth: &str,
+        collection_href: &str,
+    ) -> Result<()> {
+        let mut xml_writer = Writer::new(writer);
+        Self::start_multistatus(&mut xml_writer)?;
+
+        Self::write_response(&mut xml_writer, collection_href, &Resource::AddressBook(address_book), &request.prop_find_type)?;
+        if depth != "0" {
+            for vcard in vcards {
+                let href = Self::vcard_href(collection_href, vcard);
+                Self::write_response(&mut xml_writer, &href, &Resource::VCard(vcard), &request.prop_find_type)?;
+            }
+        }
+
+        xml_writer.write_event(Event::End(BytesEnd::new("D:multistatus")))?;
+        Ok(())
+    }
+
+    /// Generate the response of an addressbook-query or addressbook-multiget
+    ///
+    /// Hrefs of a multiget that do not exist are reported with 404. When a
+    /// query was cut at the client's limit, the collection itself is reported
+    /// with 507 (RFC 6352, section 8.6.1).
+    pub fn generate_report_response<W: Write>(
+        writer: W,
+        vcards: &[VCardObjectDto],
+        missing_hrefs: &[String],
+        props: &[QualifiedName],
+        truncated: bool,
+        collection_href: &str,
+    ) -> Result<()> {
+        let mut xml_writer = Writer::new(writer);
+        Self::start_multistatus(&mut xml_writer)?;
+
+        // Without a prop element clients get the ETag and the vCard itself
+        let props = if props.is_empty() {
+            vec![QualifiedName::new(DAV_NS, "getetag"), QualifiedName::new(CARDDAV_NS, "address-data")]
+        } else {
+            props.to_vec()
+        };
+        let prop_find_type = PropFindType::Prop(props);
+
+        for vcard in vcards {
+            let href = Self::vcard_href(collection_href, vcard);
+            Self::write_response(&mut xml_writer, &href, &Resource::VCard(vcard), &prop_find_type)?;
+        }
+        for href in missing_hrefs {
+            Self::write_status_response(&mut xml_writer, href, "HTTP/1.1 404 Not Found")?;
+        }
+        if truncated {
+            Self::write_status_response(&mut xml_writer, collection_href, "HTTP/1.1 507 Insufficient Storage")?;
+        }
+
+        xml_writer.write_event(Event::End(BytesEnd::new("D:multistatus")))?;
+        Ok(())
+    }
+
+    /// Generate a sync-collection response (RFC 6578, section 3.2)
+    ///
+    /// Changed vCards are returned with the requested properties and deleted
+    /// ones with a 404 status. When the result was truncated the collection
+    /// itself is reported with 507 so the client syncs again from the new token.
+    pub fn generate_sync_collection_response<W: Write>(
+        writer: W,
+        vcards: &[VCardObjectDto],
+        deleted_uids: &[String],
+        truncated: bool,
+        sync_token: &str,
+        props: &[QualifiedName],
+        collection_href: &str,
+    ) -> Result<()> {
+        let mut xml_writer = Writer::new(writer);
+        Self::start_multistatus(&mut xml_writer)?;
+
+        // Clients usually ask for the ETag only and fetch the vCards with a multiget
+        let props = if props.is_empty() {
+            vec![QualifiedName::new(DAV_NS, "getetag")]
+        } else {
+            props.to_vec()
+        };
+        let prop_find_type = PropFindType::Prop(props);
+
+        for vcard in vcards {
+            let href = Self::vcard_href(collection_href, vcard);
+            Self::write_response(&mut xml_writer, &href, &Resource::VCard(vcard), &prop_find_type)?;
+        }
+        for uid in deleted_uids {
+            Self::write_status_response(&mut xml_writer, &format!("{}{}.vcf", collection_href, encode_segment(uid)), "HTTP/1.1 404 Not Found")?;
+        }
+        if truncated {
+            Self::write_status_response(&mut xml_writer, collection_href, "HTTP/1.1 507 Insufficient Storage")?;
+        }
+        Self::write_text_element(&mut xml_writer, "D:sync-token", sync_token)?;
+
+        xml_writer.write_event(Event::End(BytesEnd::new("D:multistatus")))?;
+        Ok(())
+    }
+
+    /// Href of a vCard resource inside its collection
+    pub fn vcard_href(collection_href: &str, vcard: &VCardObjectDto) -> String {
+        format!("{}{}.vcf", collection_href, encode_segment(&vcard.uid))
+    }
+
+    /// Quoted ETag of a vCard resource
+    pub fn vcard_etag(vcard: &VCardObjectDto) -> String {
+        format!("\"{}\"", vcard.etag)
+    }
+
+    fn start_multistatus<W: Write>(xml_writer: &mut Writer<W>) -> Result<()> {
+        xml_writer.write_event(Event::Start(BytesStart::new("D:multistatus").with_attributes([
+            ("xmlns:D", DAV_NS),
+            ("xmlns:CARD", CARDDAV_NS),
+            ("xmlns:CS", CALENDARSERVER_NS),
+        ])))?;
+        Ok(())
+    }
+
+    /// Write one response, with the found properties in a 200 propstat and
+    /// the unknown ones in a 404 propstat
+    fn write_response<W: Write>(
+        xml_writer: &mut Writer<W>,
+        href: &str,
+        resource: &Resource,
+        prop_find_type: &PropFindType,
+    ) -> Result<()> {
+        xml_writer.write_event(Event::Start(BytesStart::new("D:response")))?;
+        Self::write_text_element(xml_writer, "D:href", href)?;
+
+        let requested = match prop_find_type {
+            PropFindType::Prop(props) => props.clone(),
+            _ => resource.default_props(),
+        };
+        let (found, missing): (Vec<QualifiedName>, Vec<QualifiedName>) =
+            requested.into_iter().partition(|prop| resource.supports(prop));
+
+        if !found.is_empty() || missing.is_empty() {
+            xml_writer.write_event(Event::Start(BytesStart::new("D:propstat")))?;
+            xml_writer.write_event(Event::Start(BytesStart::new("D:prop")))?;
+            for prop in &found {
+                if *prop_find_type == PropFindType::PropName {
+                    Self::write_empty_prop(xml_writer, prop)?;
+                } else {
+                    Self::write_prop(xml_writer, resource, prop)?;
+                }
+            }
+            xml_writer.write_event(Event::End(BytesEnd::new("D:prop")))?;
+            Self::write_propstat_status(xml_writer, "HTTP/1.1 200 OK")?;
+        }
+
+        if !missing.is_empty() {
+            xml_writer.write_event(Event::Start(BytesStart::new("D:propstat")))?;
+            xml_writer.write_event(Event::Start(BytesStart::new("D:prop")))?;
+            for prop in &missing {
+                Self::write_empty_prop(xml_writer, prop)?;
+            }
+            xml_writer.write_event(Event::End(BytesEnd::new("D:prop")))?;
+            Self::write_propstat_status(xml_writer, "HTTP/1.1 404 Not Found")?;
+        }
+
+        xml_writer.write_event(Event::End(BytesEnd::new("D:response")))?;
+        Ok(())
+    }
+
+    /// Write the value of a property the resource supports
+    fn write_prop<W: Write>(xml_writer: &mut Writer<W>, resource: &Resource, prop: &QualifiedName) -> Result<()> {
+        match (resource, prop.namespace.as_str(), prop.name.as_str()) {
+            (Resource::VCard(_), DAV_NS, "resourcetype") => {
+                xml_writer.write_event(Event::Empty(BytesStart::new("D:resourcetype")))?;
+            },
+            (_, DAV_NS, "resourcetype") => {
+                xml_writer.write_event(Event::Start(BytesStart::new("D:resourcetype")))?;
+                xml_writer.write_event(Event::Empty(BytesStart::new("D:collection")))?;
+                if matches!(resource, Resource::AddressBook(_)) {
+                    xml_writer.write_event(Event::Empty(BytesStart::new("CARD:addressbook")))?;
+                }
+                xml_writer.write_event(Event::End(BytesEnd::new("D:resourcetype")))?;
+            },
+            (Resource::Home(_), DAV_NS, "displayname") => {
+                Self::write_text_element(xml_writer, "D:displayname", "Address books")?;
+            },
+            (Resource::Home(principal_href), DAV_NS, "current-user-principal") => {
+                xml_writer.write_event(Event::Start(BytesStart::new("D:current-user-principal")))?;
+                Self::write_text_element(xml_writer, "D:href", principal_href)?;
+                xml_writer.write_event(Event::End(BytesEnd::new("D:current-user-principal")))?;
+            },
+            (Resource::AddressBook(address_book), DAV_NS, "displayname") => {
+                Self::write_text_element(xml_writer, "D:displayname", &address_book.name)?;
+            },
+            (Resource::AddressBook(address_book), DAV_NS, "getetag") => {
+                Self::write_text_element(xml_writer, "D:getetag", &format!("\"{}\"", address_book.ctag))?;
+            },
+            (Resource::AddressBook(address_book), CALENDARSERVER_NS, "getctag") => {
+                Self::write_text_element(xml_writer, "CS:getctag", &address_book.ctag)?;
+            },
+            (Resource::AddressBook(address_book), DAV_NS, "sync-token") => {
+                Self::write_text_element(xml_writer, "D:sync-token", &address_book.sync_token)?;
+            },
+            (Resource::AddressBook(address_book), CARDDAV_NS, "addressbook-description") => {
+                Self::write_text_element(xml_writer, "CARD:addressbook-description", address_book.description.as_deref().unwrap_or(""))?;
+            },
+            (Resource::AddressBook(_), CARDDAV_NS, "supported-address-data") => {
+                xml_writer.write_event(Event::Start(BytesStart::new("CARD:supported-address-data")))?;
+                for version in ["3.0", "4.0"] {
+                    xml_writer.write_event(Event::Empty(BytesStart::new("CARD:address-data-type").with_attributes([
+                        ("content-type", "text/vcard"),
+                        ("version", version),
+                    ])))?;
+                }
+                xml_writer.write_event(Event::End(BytesEnd::new("CARD:supported-address-data")))?;
+            },
+            (Resource::AddressBook(_), DAV_NS, "supported-report-set") => {
+                xml_writer.write_event(Event::Start(BytesStart::new("D:supported-report-set")))?;
+                for report in ["CARD:addressbook-query", "CARD:addressbook-multiget", "D:sync-collection"] {
+                    xml_writer.write_event(Event::Start(BytesStart::new("D:supported-report")))?;
+                    xml_writer.write_event(Event::Start(BytesStart::new("D:report")))?;
+                    xml_writer.write_event(Event::Empty(BytesStart::new(report)))?;
+                    xml_writer.write_event(Event::End(BytesEnd::new("D:report")))?;
+                    xml_writer.write_event(Event::End(BytesEnd::new("D:supported-report")))?;
+                }
+                xml_writer.write_event(Event::End(BytesEnd::new("D:supported-report-set")))?;
+            },
+            (Resource::VCard(vcard), DAV_NS, "getetag") => {
+                Self::write_text_element(xml_writer, "D:getetag", &Self::vcard_etag(vcard))?;
+            },
+            (Resource::VCard(_), DAV_NS, "getcontenttype") => {
+                Self::write_text_element(xml_writer, "D:getcontenttype", VCARD_CONTENT_TYPE)?;
+            },
+            (Resource::VCard(vcard), DAV_NS, "getcontentlength") => {
+                Self::write_text_element(xml_writer, "D:getcontentlength", &vcard.vcard.len().to_string())?;
+            },
+            (Resource::VCard(vcard), DAV_NS, "getlastmodified") => {
+                Self::write_text_element(xml_writer, "D:getlastmodified", &vcard.updated_at.to_rfc2822())?;
+            },
+            (Resource::VCard(vcard), CARDDAV_NS, "address-data") => {
+                Self::write_text_element(xml_writer, "CARD:address-data", &vcard.vcard)?;
+            },
+            _ => Self::write_empty_prop(xml_writer, prop)?,
+        }
+        Ok(())
+    }
+
+    /// Write a property as an empty element, declaring its namespace when it
+    /// is not one of the multistatus prefixes
+    fn write_empty_prop<W: Write>(xml_writer: &mut Writer<W>, prop: &QualifiedName) -> Result<()> {
+        let prefix = match prop.namespace.as_str() {
+            DAV_NS => Some("D"),
+            CARDDAV_NS => Some("CARD"),
+            CALENDARSERVER_NS => Some("CS"),
+            _ => None,
+        };
+        match prefix {
+            Some(prefix) => {
+                xml_writer.write_event(Event::Empty(BytesStart::new(format!("{}:{}", prefix, prop.name))))?;
+            },
+            None => {
+                xml_writer.write_event(Event::Empty(BytesStart::new(format!("X:{}", prop.name))
+                    .with_attributes([("xmlns:X", prop.namespace.as_str())])))?;
+            },
+        }
+        Ok(())
+    }
+
+    fn write_text_element<W: Write>(xml_writer: &mut Writer<W>, name: &str, text: &str) -> Result<()> {
+        xml_writer.write_event(Event::Start(BytesStart::new(name)))?;
+        xml_writer.write_event(Event::Text(BytesText::new(text)))?;
+        xml_writer.write_event(Event::End(BytesEnd::new(name)))?;
+        Ok(())
+    }
+
+    fn write_status_response<W: Write>(xml_writer: &mut Writer<W>, href: &str, status: &str) -> Result<()> {
+        xml_writer.write_event(Event::Start(BytesStart::new("D:response")))?;
+        Self::write_text_element(xml_writer, "D:href", href)?;
+        Self::write_text_element(xml_writer, "D:status", status)?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:response")))?;
+        Ok(())
+    }
+
+    /// Close a D:propstat with its status
+    fn write_propstat_status<W: Write>(xml_writer: &mut Writer<W>, status: &str) -> Result<()> {
+        Self::write_text_element(xml_writer, "D:status", status)?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:propstat")))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vcard() -> VCardObjectDto {
+        VCardObjectDto {
+            id: "1".to_string(),
+            address_book_id: "book".to_string(),
+            uid: "ana".to_string(),
+            etag: "e1".to_string(),
+            vcard: "BEGIN:VCARD\r\nVERSION:3.0\r\nUID:ana\r\nFN:Ana\r\nEND:VCARD\r\n".to_string(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_parse_propfind_resolves_namespaces() {
+        let body = r#"<propfind xmlns="DAV:" xmlns:card="urn:ietf:params:xml:ns:carddav"><prop><getetag/><card:address-data/></prop></propfind>"#;
+        let request = CardDavAdapter::parse_propfind(body.as_bytes()).unwrap();
+        assert_eq!(request.prop_find_type, PropFindType::Prop(vec![
+            QualifiedName::new(DAV_NS, "getetag"),
+            QualifiedName::new(CARDDAV_NS, "address-data"),
+        ]));
+        assert_eq!(CardDavAdapter::parse_propfind("".as_bytes()).unwrap().prop_find_type, PropFindType::AllProp);
+    }
+
+    #[test]
+    fn test_parse_addressbook_query() {
+        let body = r#"<C:addressbook-query xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:carddav">
+            <D:prop><D:getetag/></D:prop>
+            <C:filter test="allof">
+                <C:prop-filter name="EMAIL">
+                    <C:text-match collation="i;unicode-casemap" match-type="ends-with">@example.com</C:text-match>
+                    <C:param-filter name="TYPE"><C:text-match match-type="equals">work</C:text-match></C:param-filter>
+                </C:prop-filter>
+                <C:prop-filter name="NICKNAME"><C:is-not-defined/></C:prop-filter>
+            </C:filter>
+            <C:limit><C:nresults>10</C:nresults></C:limit>
+        </C:addressbook-query>"#;
+        let CardDavReportType::AddressbookQuery { filter, limit, props } = CardDavAdapter::parse_report(body.as_bytes()).unwrap() else {
+            panic!("expected an addressbook-query");
+        };
+        assert_eq!(limit, Some(10));
+        assert_eq!(props, vec![QualifiedName::new(DAV_NS, "getetag")]);
+        assert_eq!(filter.test, FilterTest::AllOf);
+        assert_eq!(filter.prop_filters.len(), 2);
+        let email = &filter.prop_filters[0];
+        assert_eq!(email.text_matches[0].text, "@example.com");
+        assert_eq!(email.text_matches[0].match_type, MatchType::EndsWith);
+        assert_eq!(email.param_filters[0].text_match.as_ref().unwrap().match_type, MatchType::Equals);
+        assert!(filter.prop_filters[1].is_not_defined);
+    }
+
+    #[test]
+    fn test_parse_addressbook_multiget() {
+        let body = r#"<C:addressbook-multiget xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:carddav">
+            <D:prop><D:getetag/><C:address-data/></D:prop>
+            <D:href>/api/carddav/addressbooks/book/ana.vcf</D:href>
+        </C:addressbook-multiget>"#;
+        let report = CardDavAdapter::parse_report(body.as_bytes()).unwrap();
+        assert_eq!(report, CardDavReportType::AddressbookMultiget {
+            hrefs: vec!["/api/carddav/addressbooks/book/ana.vcf".to_string()],
+            props: vec![QualifiedName::new(DAV_NS, "getetag"), QualifiedName::new(CARDDAV_NS, "address-data")],
+        });
+    }
+
+    #[test]
+    fn test_report_response_splits_found_and_missing() {
+        let mut out = Vec::new();
+        CardDavAdapter::generate_report_response(
+            &mut out,
+            &[vcard()],
+            &["/api/carddav/addressbooks/book/gone.vcf".to_string()],
+            &[QualifiedName::new(CARDDAV_NS, "address-data"), QualifiedName::new("urn:example", "color")],
+            false,
+            "/api/carddav/addressbooks/book/",
+        ).unwrap();
+        let xml = String::from_utf8(out).unwrap();
+        assert!(xml.contains("<D:href>/api/carddav/addressbooks/book/ana.vcf</D:href>"));
+        assert!(xml.contains("<CARD:address-data>BEGIN:VCARD\r\nVERSION:3.0"));
+        assert!(xml.contains("<X:color xmlns:X=\"urn:example\"/></D:prop><D:status>HTTP/1.1 404 Not Found</D:status>"));
+        assert!(xml.contains("<D:href>/api/carddav/addressbooks/book/gone.vcf</D:href><D:status>HTTP/1.1 404 Not Found</D:status>"));
+    }
+
+    #[test]
+    fn test_sync_collection_round_trip() {
+        let body = r#"<D:sync-collection xmlns:D="DAV:">
+            <D:sync-token>http://oxicloud.org/ns/sync/book/3</D:sync-token>
+            <D:sync-level>1</D:sync-level>
+            <D:prop><D:getetag/></D:prop>
+        </D:sync-collection>"#;
+        let report = CardDavAdapter::parse_report(body.as_bytes()).unwrap();
+        assert_eq!(report, CardDavReportType::SyncCollection {
+            sync_token: "http://oxicloud.org/ns/sync/book/3".to_string(),
+            limit: None,
+            props: vec![QualifiedName::new(DAV_NS, "getetag")],
+        });
+
+        let mut out = Vec::new();
+        CardDavAdapter::generate_sync_collection_response(
+            &mut out,
+            &[vcard()],
+            &["gone".to_string()],
+            true,
+            "http://oxicloud.org/ns/sync/book/9",
+            &[QualifiedName::new(DAV_NS, "getetag")],
+            "/api/carddav/addressbooks/book/",
+        ).unwrap();
+        let xml = String::from_utf8(out).unwrap();
+        assert!(xml.contains("<D:href>/api/carddav/addressbooks/book/ana.vcf</D:href><D:propstat><D:prop><D:getetag>\"e1\"</D:getetag>"));
+        assert!(xml.contains("<D:href>/api/carddav/addressbooks/book/gone.vcf</D:href><D:status>HTTP/1.1 404 Not Found</D:status>"));
+        assert!(xml.contains("<D:href>/api/carddav/addressbooks/book/</D:href><D:status>HTTP/1.1 507 Insufficient Storage</D:status>"));
+        assert!(xml.ends_with("<D:sync-token>http://oxicloud.org/ns/sync/book/9</D:sync-token></D:multistatus>"));
+    }
+}
@@ -1,4 +1,6 @@
 //! Adapters module for translating between external protocols and internal models
 
 pub mod webdav_adapter;
+pub mod webdav_ordering;
 pub mod caldav_adapter;
+pub mod carddav_adapter;
@@ -5,11 +5,22 @@
  * It handles parsing WebDAV request XML and generating WebDAV response XML according to RFC 4918.
  */
 
+use std::collections::HashMap;
 use std::io::{Read, Write, BufReader};
 use quick_xml::{Reader, Writer, events::{Event, BytesStart, BytesEnd, BytesText}};
 use chrono::Utc;
 use crate::application::dtos::file_dto::FileDto;
 use crate::application::dtos::folder_dto::FolderDto;
+use crate::application::ports::file_attribute_ports::FileAttributes;
+use crate::domain::entities::dead_property::DeadProperty;
+use crate::domain::services::extended_attribute_service::{ExtendedAttribute, APACHE_PROPS_NAMESPACE};
+use crate::domain::services::path_codec_service::encode_segment;
+
+/// CalendarServer namespace, for the `getctag` of collections
+pub const CALENDARSERVER_NS: &str = "http://calendarserver.org/ns/";
+
+/// ownCloud namespace, for the `tags` of files and folders read by Nextcloud clients
+pub const OWNCLOUD_NS: &str = "http://owncloud.org/ns";
 
 /// Result type for WebDAV operations
 pub type Result<T> = std::result::Result<T, WebDavError>;
@@ -204,6 +215,13 @@ impl WebDavAdapter {
     }
     
     /// Generate a PROPFIND response for files and folders
+    ///
+    /// `base_href` must already be URL-encoded; member names are encoded here.
+    /// Members are written folders first, each group in the order given, so
+    /// an ordered listing keeps its order in the multistatus. `ctag` is the
+    /// `CS:getctag` of `folder`, when its members were loaded to compute it.
+    /// `tags` holds the names of the user's tags on each item, by item ID.
+    #[allow(clippy::too_many_arguments)]
     pub fn generate_propfind_response<W: Write>(
         writer: W,
         folder: Option<&FolderDto>,
@@ -212,6 +230,10 @@ impl WebDavAdapter {
         request: &PropFindRequest,
         _depth: &str,
         base_href: &str,
+        attributes: &HashMap<String, FileAttributes>,
+        dead_properties: &HashMap<String, Vec<DeadProperty>>,
+        tags: &HashMap<String, Vec<String>>,
+        ctag: Option<&str>,
     ) -> Result<()> {
         let mut xml_writer = Writer::new(writer);
         
@@ -222,19 +244,43 @@ impl WebDavAdapter {
         
         // Add response for current folder if provided
         if let Some(folder) = folder {
-            Self::write_folder_response(&mut xml_writer, folder, request, &format!("{}", base_href))?;
+            Self::write_folder_response(
+                &mut xml_writer,
+                folder,
+                request,
+                &format!("{}", base_href),
+                dead_properties.get(&folder.id).map(Vec::as_slice).unwrap_or_default(),
+                tags.get(&folder.id).map(Vec::as_slice).unwrap_or_default(),
+                ctag,
+            )?;
         }
         
-        // If depth allows, add responses for files and subfolders
+        // If depth allows, add responses for subfolders and files
         if _depth != "0" {
-            // Add responses for files
-            for file in files {
-                Self::write_file_response(&mut xml_writer, file, request, &format!("{}{}", base_href, file.name))?;
-            }
-            
             // Add responses for subfolders
             for subfolder in subfolders {
-                Self::write_folder_response(&mut xml_writer, subfolder, request, &format!("{}{}/", base_href, subfolder.name))?;
+                Self::write_folder_response(
+                    &mut xml_writer,
+                    subfolder,
+                    request,
+                    &format!("{}{}/", base_href, encode_segment(&subfolder.name)),
+                    dead_properties.get(&subfolder.id).map(Vec::as_slice).unwrap_or_default(),
+                    tags.get(&subfolder.id).map(Vec::as_slice).unwrap_or_default(),
+                    None,
+                )?;
+            }
+            
+            // Add responses for files
+            for file in files {
+                Self::write_file_response(
+                    &mut xml_writer,
+                    file,
+                    request,
+                    &format!("{}{}", base_href, encode_segment(&file.name)),
+                    attributes.get(&file.id),
+                    dead_properties.get(&file.id).map(Vec::as_slice).unwrap_or_default(),
+                    tags.get(&file.id).map(Vec::as_slice).unwrap_or_default(),
+                )?;
             }
         }
         
@@ -251,6 +297,9 @@ impl WebDavAdapter {
         request: &PropFindRequest,
         _depth: &str,
         href: &str,
+        attributes: Option<&FileAttributes>,
+        dead_properties: &[DeadProperty],
+        tags: &[String],
     ) -> Result<()> {
         let mut xml_writer = Writer::new(writer);
         
@@ -260,7 +309,7 @@ impl WebDavAdapter {
         ])))?;
         
         // Add response for file
-        Self::write_file_response(&mut xml_writer, file, request, href)?;
+        Self::write_file_response(&mut xml_writer, file, request, href, attributes, dead_properties, tags)?;
         
         // End multistatus
         xml_writer.write_event(Event::End(BytesEnd::new("D:multistatus")))?;
@@ -274,6 +323,9 @@ impl WebDavAdapter {
         folder: &FolderDto,
         request: &PropFindRequest,
         href: &str,
+        dead_properties: &[DeadProperty],
+        tags: &[String],
+        ctag: Option<&str>,
     ) -> Result<()> {
         // Start response element
         xml_writer.write_event(Event::Start(BytesStart::new("D:response")))?;
@@ -294,14 +346,18 @@ impl WebDavAdapter {
             PropFindType::AllProp => {
                 // Write all standard properties for a folder
                 Self::write_folder_standard_props(xml_writer, folder)?;
+                Self::write_tags(xml_writer, tags, false)?;
+                Self::write_dead_properties(xml_writer, dead_properties, false)?;
             },
             PropFindType::PropName => {
                 // Write only property names (empty elements)
                 Self::write_folder_prop_names(xml_writer)?;
+                Self::write_tags(xml_writer, tags, true)?;
+                Self::write_dead_properties(xml_writer, dead_properties, true)?;
             },
             PropFindType::Prop(props) => {
                 // Write requested properties
-                Self::write_folder_requested_props(xml_writer, folder, props)?;
+                Self::write_folder_requested_props(xml_writer, folder, props, dead_properties, tags, ctag)?;
             }
         }
         
@@ -328,6 +384,9 @@ impl WebDavAdapter {
         file: &FileDto,
         request: &PropFindRequest,
         href: &str,
+        attributes: Option<&FileAttributes>,
+        dead_properties: &[DeadProperty],
+        tags: &[String],
     ) -> Result<()> {
         // Start response element
         xml_writer.write_event(Event::Start(BytesStart::new("D:response")))?;
@@ -348,14 +407,20 @@ impl WebDavAdapter {
             PropFindType::AllProp => {
                 // Write all standard properties for a file
                 Self::write_file_standard_props(xml_writer, file)?;
+                Self::write_file_attributes(xml_writer, attributes, false)?;
+                Self::write_tags(xml_writer, tags, false)?;
+                Self::write_dead_properties(xml_writer, dead_properties, false)?;
             },
             PropFindType::PropName => {
                 // Write only property names (empty elements)
                 Self::write_file_prop_names(xml_writer)?;
+                Self::write_file_attributes(xml_writer, attributes, true)?;
+                Self::write_tags(xml_writer, tags, true)?;
+                Self::write_dead_properties(xml_writer, dead_properties, true)?;
             },
             PropFindType::Prop(props) => {
                 // Write requested properties
-                Self::write_file_requested_props(xml_writer, file, props)?;
+                Self::write_file_requested_props(xml_writer, file, props, attributes, dead_properties, tags)?;
             }
         }
         
@@ -413,7 +478,7 @@ impl WebDavAdapter {
         
         // Other standard properties
         xml_writer.write_event(Event::Start(BytesStart::new("D:getetag")))?;
-        xml_writer.write_event(Event::Text(BytesText::new(&format!("\"{}\"", folder.id))))?;
+        xml_writer.write_event(Event::Text(BytesText::new(&Self::collection_etag(folder))))?;
         xml_writer.write_event(Event::End(BytesEnd::new("D:getetag")))?;
         
         // Content length (0 for directories)
@@ -517,6 +582,9 @@ impl WebDavAdapter {
         xml_writer: &mut Writer<W>,
         folder: &FolderDto,
         props: &[QualifiedName],
+        dead_properties: &[DeadProperty],
+        tags: &[String],
+        ctag: Option<&str>,
     ) -> Result<()> {
         for prop in props {
             if prop.namespace == "DAV:" {
@@ -553,7 +621,7 @@ impl WebDavAdapter {
                     },
                     "getetag" => {
                         xml_writer.write_event(Event::Start(BytesStart::new("D:getetag")))?;
-                        xml_writer.write_event(Event::Text(BytesText::new(&format!("\"{}\"", folder.id))))?;
+                        xml_writer.write_event(Event::Text(BytesText::new(&Self::collection_etag(folder))))?;
                         xml_writer.write_event(Event::End(BytesEnd::new("D:getetag")))?;
                     },
                     "getcontentlength" => {
@@ -571,6 +639,16 @@ impl WebDavAdapter {
                         xml_writer.write_event(Event::Empty(BytesStart::new(&format!("D:{}", prop.name))))?;
                     }
                 }
+            } else if let (CALENDARSERVER_NS, "getctag", Some(ctag)) = (prop.namespace.as_str(), prop.name.as_str(), ctag) {
+                let element = BytesStart::new("CS:getctag").with_attributes([("xmlns:CS", CALENDARSERVER_NS)]);
+                xml_writer.write_event(Event::Start(element))?;
+                xml_writer.write_event(Event::Text(BytesText::new(ctag)))?;
+                xml_writer.write_event(Event::End(BytesEnd::new("CS:getctag")))?;
+            } else if prop.namespace == OWNCLOUD_NS && prop.name == "tags" {
+                Self::write_tags_property(xml_writer, tags)?;
+            } else if let Some(property) = dead_properties.iter().find(|p| p.is(&prop.namespace, &prop.name)) {
+                // Custom property stored through PROPPATCH
+                Self::write_dead_property(xml_writer, property, false)?;
             } else {
                 // Non-DAV namespace, not supported
                 xml_writer.write_event(Event::Empty(BytesStart::new(&format!("{}:{}", prop.namespace, prop.name))))?;
@@ -585,6 +663,9 @@ impl WebDavAdapter {
         xml_writer: &mut Writer<W>,
         file: &FileDto,
         props: &[QualifiedName],
+        attributes: Option<&FileAttributes>,
+        dead_properties: &[DeadProperty],
+        tags: &[String],
     ) -> Result<()> {
         for prop in props {
             if prop.namespace == "DAV:" {
@@ -637,6 +718,16 @@ impl WebDavAdapter {
                         xml_writer.write_event(Event::Empty(BytesStart::new(&format!("D:{}", prop.name))))?;
                     }
                 }
+            } else if let Some((attribute, value)) = ExtendedAttribute::from_property(&prop.name)
+                .and_then(|attr| attributes?.get(attr.property_name()).map(|value| (attr, value)))
+            {
+                // Stored extended attribute
+                Self::write_attribute(xml_writer, attribute, Some(value))?;
+            } else if prop.namespace == OWNCLOUD_NS && prop.name == "tags" {
+                Self::write_tags_property(xml_writer, tags)?;
+            } else if let Some(property) = dead_properties.iter().find(|p| p.is(&prop.namespace, &prop.name)) {
+                // Custom property stored through PROPPATCH
+                Self::write_dead_property(xml_writer, property, false)?;
             } else {
                 // Non-DAV namespace, not supported
                 xml_writer.write_event(Event::Empty(BytesStart::new(&format!("{}:{}", prop.namespace, prop.name))))?;
@@ -646,6 +737,126 @@ impl WebDavAdapter {
         Ok(())
     }
     
+    /// Write the extended attributes stored for a file (values, or names only)
+    fn write_file_attributes<W: Write>(
+        xml_writer: &mut Writer<W>,
+        attributes: Option<&FileAttributes>,
+        names_only: bool,
+    ) -> Result<()> {
+        let Some(attributes) = attributes else { return Ok(()) };
+        
+        for attribute in ExtendedAttribute::ALL {
+            if let Some(value) = attributes.get(attribute.property_name()) {
+                Self::write_attribute(xml_writer, attribute, (!names_only).then_some(value.as_str()))?;
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Write a single extended attribute with its own namespace declaration
+    fn write_attribute<W: Write>(
+        xml_writer: &mut Writer<W>,
+        attribute: ExtendedAttribute,
+        value: Option<&str>,
+    ) -> Result<()> {
+        let prefix = if attribute.namespace() == APACHE_PROPS_NAMESPACE { "A" } else { "O" };
+        let tag = format!("{}:{}", prefix, attribute.property_name());
+        let start = BytesStart::new(tag.as_str())
+            .with_attributes([(format!("xmlns:{}", prefix).as_str(), attribute.namespace())]);
+        
+        match value {
+            Some(value) => {
+                xml_writer.write_event(Event::Start(start))?;
+                xml_writer.write_event(Event::Text(BytesText::new(value)))?;
+                xml_writer.write_event(Event::End(BytesEnd::new(tag.as_str())))?;
+            }
+            None => {
+                xml_writer.write_event(Event::Empty(start))?;
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Write the user's tags on an item, if it has any (values, or the name only)
+    fn write_tags<W: Write>(
+        xml_writer: &mut Writer<W>,
+        tags: &[String],
+        names_only: bool,
+    ) -> Result<()> {
+        if tags.is_empty() {
+            return Ok(());
+        }
+        if names_only {
+            xml_writer.write_event(Event::Empty(BytesStart::new("oc:tags").with_attributes([("xmlns:oc", OWNCLOUD_NS)])))?;
+            return Ok(());
+        }
+        Self::write_tags_property(xml_writer, tags)
+    }
+    
+    /// Write `oc:tags` with one `oc:tag` per tag name; empty when there are none
+    fn write_tags_property<W: Write>(
+        xml_writer: &mut Writer<W>,
+        tags: &[String],
+    ) -> Result<()> {
+        let start = BytesStart::new("oc:tags").with_attributes([("xmlns:oc", OWNCLOUD_NS)]);
+        if tags.is_empty() {
+            xml_writer.write_event(Event::Empty(start))?;
+            return Ok(());
+        }
+        
+        xml_writer.write_event(Event::Start(start))?;
+        for tag in tags {
+            xml_writer.write_event(Event::Start(BytesStart::new("oc:tag")))?;
+            xml_writer.write_event(Event::Text(BytesText::new(tag)))?;
+            xml_writer.write_event(Event::End(BytesEnd::new("oc:tag")))?;
+        }
+        xml_writer.write_event(Event::End(BytesEnd::new("oc:tags")))?;
+        
+        Ok(())
+    }
+    
+    /// Write the custom properties stored for a resource (values, or names only)
+    fn write_dead_properties<W: Write>(
+        xml_writer: &mut Writer<W>,
+        dead_properties: &[DeadProperty],
+        names_only: bool,
+    ) -> Result<()> {
+        for property in dead_properties {
+            Self::write_dead_property(xml_writer, property, names_only)?;
+        }
+        
+        Ok(())
+    }
+    
+    /// Write a single custom property with its own namespace declaration
+    fn write_dead_property<W: Write>(
+        xml_writer: &mut Writer<W>,
+        property: &DeadProperty,
+        names_only: bool,
+    ) -> Result<()> {
+        let (tag, xmlns) = if property.namespace.is_empty() {
+            (property.name.clone(), "xmlns")
+        } else {
+            (format!("X:{}", property.name), "xmlns:X")
+        };
+        let start = BytesStart::new(tag.as_str()).with_attributes([(xmlns, property.namespace.as_str())]);
+        
+        match property.value.as_deref().filter(|_| !names_only) {
+            Some(value) => {
+                xml_writer.write_event(Event::Start(start))?;
+                xml_writer.write_event(Event::Text(BytesText::new(value)))?;
+                xml_writer.write_event(Event::End(BytesEnd::new(tag.as_str())))?;
+            }
+            None => {
+                xml_writer.write_event(Event::Empty(start))?;
+            }
+        }
+        
+        Ok(())
+    }
+    
     /// Parse a PROPPATCH XML request
     pub fn parse_proppatch<R: Read>(reader: R) -> Result<(Vec<PropValue>, Vec<QualifiedName>)> {
         let mut xml_reader = Reader::from_reader(BufReader::new(reader));
@@ -846,6 +1057,41 @@ impl WebDavAdapter {
         Ok(())
     }
     
+    /// Generate a multistatus with the outcome of an operation on a single
+    /// resource, such as a COPY or MOVE that finished in the background
+    pub fn generate_status_response<W: Write>(
+        writer: W,
+        href: &str,
+        status: &str,
+        description: Option<&str>,
+    ) -> Result<()> {
+        let mut xml_writer = Writer::new(writer);
+
+        xml_writer.write_event(Event::Start(BytesStart::new("D:multistatus").with_attributes([
+            ("xmlns:D", "DAV:"),
+        ])))?;
+        xml_writer.write_event(Event::Start(BytesStart::new("D:response")))?;
+
+        xml_writer.write_event(Event::Start(BytesStart::new("D:href")))?;
+        xml_writer.write_event(Event::Text(BytesText::new(href)))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:href")))?;
+
+        xml_writer.write_event(Event::Start(BytesStart::new("D:status")))?;
+        xml_writer.write_event(Event::Text(BytesText::new(status)))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:status")))?;
+
+        if let Some(description) = description {
+            xml_writer.write_event(Event::Start(BytesStart::new("D:responsedescription")))?;
+            xml_writer.write_event(Event::Text(BytesText::new(description)))?;
+            xml_writer.write_event(Event::End(BytesEnd::new("D:responsedescription")))?;
+        }
+
+        xml_writer.write_event(Event::End(BytesEnd::new("D:response")))?;
+        xml_writer.write_event(Event::End(BytesEnd::new("D:multistatus")))?;
+
+        Ok(())
+    }
+
     /// Parse a LOCK XML request
     pub fn parse_lockinfo<R: Read>(reader: R) -> Result<(LockScope, LockType, Option<String>)> {
         let mut xml_reader = Reader::from_reader(BufReader::new(reader));
@@ -918,6 +1164,62 @@ impl WebDavAdapter {
         Ok((scope, type_, owner))
     }
     
+    /// Parse a Timeout header (`Second-3600`, `Infinite`); the first supported value wins
+    pub fn parse_timeout(header: &str) -> Option<u64> {
+        header.split(',').map(str::trim).find_map(|value| {
+            if value.eq_ignore_ascii_case("infinite") {
+                Some(u64::MAX)
+            } else {
+                value.get(..7)
+                    .filter(|prefix| prefix.eq_ignore_ascii_case("second-"))
+                    .and_then(|_| value[7..].parse().ok())
+            }
+        })
+    }
+    
+    /// Extract the lock tokens submitted in an If header.
+    ///
+    /// Tokens are the `<...>` entries inside condition lists, both untagged
+    /// (`(<token>)`) and tagged (`<href> (<token>)`); negated conditions and
+    /// entity tags are skipped.
+    pub fn parse_if_tokens(header: &str) -> Vec<String> {
+        let bytes = header.as_bytes();
+        let mut tokens = Vec::new();
+        let mut in_list = false;
+        let mut negated = false;
+        let mut i = 0;
+        
+        while i < bytes.len() {
+            match bytes[i] {
+                b'(' => {
+                    in_list = true;
+                    negated = false;
+                },
+                b')' => in_list = false,
+                b'[' => {
+                    // Entity tag condition, may contain any character
+                    i += header[i..].find(']').unwrap_or(header.len() - i);
+                },
+                b'<' => {
+                    let end = header[i..].find('>').map(|end| i + end).unwrap_or(header.len());
+                    if in_list && !negated {
+                        tokens.push(header[i + 1..end].trim().to_string());
+                    }
+                    negated = false;
+                    i = end;
+                },
+                b'N' | b'n' if in_list && header[i..].get(..3).is_some_and(|word| word.eq_ignore_ascii_case("not")) => {
+                    negated = true;
+                    i += 2;
+                },
+                _ => (),
+            }
+            i += 1;
+        }
+        
+        tokens
+    }
+    
     /// Generate a LOCK response (lockdiscovery)
     pub fn generate_lock_response<W: Write>(
         writer: W,
@@ -1006,6 +1308,12 @@ impl WebDavAdapter {
         "DAV:".to_string()
     }
     
+    /// Weak ETag of a collection: it changes whenever the folder's modification
+    /// time does, but two equal tags do not promise byte-identical listings
+    pub fn collection_etag(folder: &FolderDto) -> String {
+        format!("W/\"{}-{:x}\"", folder.id, folder.modified_at)
+    }
+    
     /// Helper method to extract local name from tag name
     pub fn extract_local_name(name: &str) -> String {
         if let Some(idx) = name.rfind(':') {
@@ -1015,4 +1323,110 @@ impl WebDavAdapter {
         }
         name.to_string()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timeout() {
+        assert_eq!(WebDavAdapter::parse_timeout("Second-3600"), Some(3600));
+        assert_eq!(WebDavAdapter::parse_timeout("Infinite, Second-4100000000"), Some(u64::MAX));
+        assert_eq!(WebDavAdapter::parse_timeout("Extend, second-60"), Some(60));
+        assert_eq!(WebDavAdapter::parse_timeout("Second-soon"), None);
+    }
+
+    #[test]
+    fn test_parse_if_tokens() {
+        assert_eq!(
+            WebDavAdapter::parse_if_tokens("(<opaquelocktoken:a>)"),
+            vec!["opaquelocktoken:a"]
+        );
+        assert_eq!(
+            WebDavAdapter::parse_if_tokens(
+                "<http://host/webdav/doc.txt> (<opaquelocktoken:a> [\"etag<1>\"]) (Not <opaquelocktoken:b>) (<urn:uuid:c>)"
+            ),
+            vec!["opaquelocktoken:a", "urn:uuid:c"]
+        );
+        assert!(WebDavAdapter::parse_if_tokens("([\"etag\"])").is_empty());
+    }
+
+    #[test]
+    fn test_propfind_returns_collection_ctag() {
+        let folder = FolderDto { id: "d1".to_string(), name: "Fotos".to_string(), ..Default::default() };
+        let request = PropFindRequest {
+            prop_find_type: PropFindType::Prop(vec![QualifiedName::new(CALENDARSERVER_NS, "getctag")]),
+        };
+        let mut out = Vec::new();
+        WebDavAdapter::generate_propfind_response(
+            &mut out, Some(&folder), &[], &[], &request, "0", "/webdav/Fotos/", &HashMap::new(), &HashMap::new(), &HashMap::new(), Some("abc"),
+        ).unwrap();
+        let xml = String::from_utf8(out).unwrap();
+        assert!(xml.contains(r#"<CS:getctag xmlns:CS="http://calendarserver.org/ns/">abc</CS:getctag>"#));
+    }
+
+    #[test]
+    fn test_propfind_returns_dead_properties() {
+        let file = FileDto { id: "f1".to_string(), name: "a.txt".to_string(), ..Default::default() };
+        let dead_properties = vec![
+            DeadProperty::new("http://example.com/ns", "color", Some("red".to_string())),
+            DeadProperty::new("http://example.com/ns", "flag", None),
+        ];
+        let render = |prop_find_type| {
+            let mut out = Vec::new();
+            let request = PropFindRequest { prop_find_type };
+            WebDavAdapter::generate_propfind_response_for_file(&mut out, &file, &request, "0", "/webdav/a.txt", None, &dead_properties, &[])
+                .unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        let xml = render(PropFindType::Prop(vec![QualifiedName::new("http://example.com/ns", "color")]));
+        assert!(xml.contains(r#"<X:color xmlns:X="http://example.com/ns">red</X:color>"#));
+
+        let xml = render(PropFindType::AllProp);
+        assert!(xml.contains(r#"<X:flag xmlns:X="http://example.com/ns"/>"#));
+
+        let xml = render(PropFindType::PropName);
+        assert!(xml.contains(r#"<X:color xmlns:X="http://example.com/ns"/>"#));
+    }
+
+    #[test]
+    fn test_propfind_returns_tags() {
+        let folder = FolderDto { id: "d1".to_string(), name: "Docs".to_string(), ..Default::default() };
+        let files = vec![
+            FileDto { id: "f1".to_string(), name: "a.txt".to_string(), ..Default::default() },
+            FileDto { id: "f2".to_string(), name: "b.txt".to_string(), ..Default::default() },
+        ];
+        let tags = HashMap::from([("f1".to_string(), vec!["Facturas".to_string(), "R&D".to_string()])]);
+        let render = |prop_find_type| {
+            let mut out = Vec::new();
+            let request = PropFindRequest { prop_find_type };
+            WebDavAdapter::generate_propfind_response(
+                &mut out, Some(&folder), &files, &[], &request, "1", "/webdav/Docs/", &HashMap::new(), &HashMap::new(), &tags, None,
+            ).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        // Requested: every item answers, untagged ones with an empty list
+        let xml = render(PropFindType::Prop(vec![QualifiedName::new(OWNCLOUD_NS, "tags")]));
+        assert!(xml.contains(r#"<oc:tags xmlns:oc="http://owncloud.org/ns"><oc:tag>Facturas</oc:tag><oc:tag>R&amp;D</oc:tag></oc:tags>"#));
+        assert_eq!(xml.matches(r#"<oc:tags xmlns:oc="http://owncloud.org/ns"/>"#).count(), 2);
+
+        // allprop and propname only list it for tagged items
+        let xml = render(PropFindType::AllProp);
+        assert_eq!(xml.matches("<oc:tags").count(), 1);
+        let xml = render(PropFindType::PropName);
+        assert!(xml.contains(r#"<oc:tags xmlns:oc="http://owncloud.org/ns"/>"#));
+        assert!(!xml.contains("Facturas"));
+    }
+
+    #[test]
+    fn test_status_response_escapes_description() {
+        let mut out = Vec::new();
+        WebDavAdapter::generate_status_response(&mut out, "/webdav/a%20b/", "HTTP/1.1 412 Precondition Failed", Some("<b> exists")).unwrap();
+        let xml = String::from_utf8(out).unwrap();
+        assert!(xml.contains("<D:status>HTTP/1.1 412 Precondition Failed</D:status>"));
+        assert!(xml.contains("<D:responsedescription>&lt;b&gt; exists</D:responsedescription>"));
+    }
+}
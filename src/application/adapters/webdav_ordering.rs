@@ -0,0 +1,212 @@
+//! Server-side ordering of WebDAV collection listings
+//!
+//! RFC 4918 leaves the order of the members of a multistatus unspecified and
+//! the ordered collections extension (RFC 3648) only covers orderings the
+//! client maintains by hand. Clients that page through large folders need a
+//! stable order instead: a `PROPFIND` can ask for one with the ordering hint
+//! and get folders first, each group sorted by the requested key and ties
+//! broken by name and id, so a page never repeats or skips a member.
+
+use std::cmp::Ordering;
+
+use crate::application::dtos::file_dto::FileDto;
+use crate::application::dtos::folder_dto::FolderDto;
+use crate::domain::services::content_digest_service::sha256_hex;
+
+/// Key a listing can be ordered by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderKey {
+    Name,
+    Modified,
+    /// Folders have no size and keep their name order
+    Size,
+}
+
+impl OrderKey {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Modified => "modified",
+            Self::Size => "size",
+        }
+    }
+}
+
+/// Ordering requested for the members of a collection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListingOrder {
+    pub key: OrderKey,
+    pub descending: bool,
+}
+
+impl ListingOrder {
+    /// Parses `<key> [asc|desc]`. Keys accept the DAV property they sort by
+    /// (`displayname`, `getlastmodified`, `getcontentlength`) as an alias
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.split_whitespace();
+        let key = match parts.next()?.to_lowercase().as_str() {
+            "name" | "displayname" => OrderKey::Name,
+            "modified" | "getlastmodified" => OrderKey::Modified,
+            "size" | "getcontentlength" => OrderKey::Size,
+            _ => return None,
+        };
+        let descending = match parts.next().map(str::to_lowercase).as_deref() {
+            None | Some("asc") => false,
+            Some("desc") => true,
+            Some(_) => return None,
+        };
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(Self { key, descending })
+    }
+
+    /// Canonical form, echoed back so the client knows the hint was applied
+    pub fn header_value(&self) -> String {
+        format!("{} {}", self.key.as_str(), if self.descending { "desc" } else { "asc" })
+    }
+}
+
+/// Slice of an ordered listing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ListingWindow {
+    pub offset: usize,
+    /// No limit returns every member from `offset` on
+    pub limit: Option<usize>,
+}
+
+fn by_name(a_name: &str, a_id: &str, b_name: &str, b_id: &str) -> Ordering {
+    a_name.to_lowercase()
+        .cmp(&b_name.to_lowercase())
+        .then_with(|| a_name.cmp(b_name))
+        .then_with(|| a_id.cmp(b_id))
+}
+
+fn directed(ordering: Ordering, descending: bool) -> Ordering {
+    if descending { ordering.reverse() } else { ordering }
+}
+
+/// Sorts the members of a collection and keeps the requested window.
+/// Folders come first; the window spans both groups as a single list.
+/// Returns how many members the collection has in total
+pub fn order_listing(
+    files: &mut Vec<FileDto>,
+    subfolders: &mut Vec<FolderDto>,
+    order: ListingOrder,
+    window: ListingWindow,
+) -> usize {
+    subfolders.sort_by(|a, b| {
+        let primary = match order.key {
+            OrderKey::Modified => a.modified_at.cmp(&b.modified_at),
+            OrderKey::Name | OrderKey::Size => Ordering::Equal,
+        };
+        directed(primary.then_with(|| by_name(&a.name, &a.id, &b.name, &b.id)), order.descending)
+    });
+    files.sort_by(|a, b| {
+        let primary = match order.key {
+            OrderKey::Name => Ordering::Equal,
+            OrderKey::Modified => a.modified_at.cmp(&b.modified_at),
+            OrderKey::Size => a.size.cmp(&b.size),
+        };
+        directed(primary.then_with(|| by_name(&a.name, &a.id, &b.name, &b.id)), order.descending)
+    });
+
+    let folder_count = subfolders.len();
+    let total = folder_count + files.len();
+    let start = window.offset.min(total);
+    let end = window.limit.map_or(total, |limit| start.saturating_add(limit).min(total));
+
+    // Folders occupy [0, folder_count) of the combined list, files the rest
+    files.truncate(end.saturating_sub(folder_count));
+    files.drain(..start.saturating_sub(folder_count).min(files.len()));
+    subfolders.truncate(end.min(folder_count));
+    subfolders.drain(..start.min(subfolders.len()));
+
+    total
+}
+
+/// Collection tag (CalendarServer `getctag`) of a folder: it changes whenever
+/// a member is added, removed, renamed or modified, so clients can poll it
+/// with a `Depth: 0` request before fetching the listing
+pub fn collection_ctag(files: &[FileDto], subfolders: &[FolderDto]) -> String {
+    let mut members: Vec<String> = subfolders
+        .iter()
+        .map(|folder| format!("d:{}:{}:{}", folder.id, folder.name, folder.modified_at))
+        .chain(files.iter().map(|file| format!("f:{}:{}:{}:{}", file.id, file.name, file.modified_at, file.size)))
+        .collect();
+    // The tag must not depend on the order the storage lists members in
+    members.sort();
+    sha256_hex(members.join("\n").as_bytes())[..32].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(id: &str, name: &str, size: u64, modified_at: u64) -> FileDto {
+        FileDto { id: id.to_string(), name: name.to_string(), size, modified_at, ..Default::default() }
+    }
+
+    fn folder(id: &str, name: &str, modified_at: u64) -> FolderDto {
+        FolderDto { id: id.to_string(), name: name.to_string(), modified_at, ..Default::default() }
+    }
+
+    fn names(files: &[FileDto], subfolders: &[FolderDto]) -> Vec<String> {
+        subfolders.iter().map(|f| f.name.clone()).chain(files.iter().map(|f| f.name.clone())).collect()
+    }
+
+    #[test]
+    fn test_parse_order() {
+        assert_eq!(ListingOrder::parse("name"), Some(ListingOrder { key: OrderKey::Name, descending: false }));
+        assert_eq!(ListingOrder::parse("getlastmodified DESC"), Some(ListingOrder { key: OrderKey::Modified, descending: true }));
+        assert_eq!(ListingOrder::parse(" size  asc "), Some(ListingOrder { key: OrderKey::Size, descending: false }));
+        assert_eq!(ListingOrder::parse("owner"), None);
+        assert_eq!(ListingOrder::parse("name sideways"), None);
+        assert_eq!(ListingOrder::parse("name asc extra"), None);
+        assert_eq!(ListingOrder::parse("modified desc").unwrap().header_value(), "modified desc");
+    }
+
+    #[test]
+    fn test_order_listing_puts_folders_first_and_breaks_ties() {
+        let mut files = vec![file("3", "b.txt", 10, 5), file("1", "A.txt", 30, 5), file("2", "a.txt", 20, 7)];
+        let mut subfolders = vec![folder("d2", "zeta", 1), folder("d1", "Alpha", 9)];
+
+        let order = ListingOrder { key: OrderKey::Modified, descending: false };
+        let total = order_listing(&mut files, &mut subfolders, order, ListingWindow::default());
+        assert_eq!(total, 5);
+        assert_eq!(names(&files, &subfolders), ["zeta", "Alpha", "A.txt", "b.txt", "a.txt"]);
+
+        let order = ListingOrder { key: OrderKey::Size, descending: true };
+        order_listing(&mut files, &mut subfolders, order, ListingWindow::default());
+        assert_eq!(names(&files, &subfolders), ["zeta", "Alpha", "A.txt", "a.txt", "b.txt"]);
+    }
+
+    #[test]
+    fn test_order_listing_windows_span_folders_and_files() {
+        let page = |offset, limit| {
+            let mut files = vec![file("1", "c.txt", 0, 0), file("2", "b.txt", 0, 0), file("3", "a.txt", 0, 0)];
+            let mut subfolders = vec![folder("d2", "y", 0), folder("d1", "x", 0)];
+            let order = ListingOrder { key: OrderKey::Name, descending: false };
+            order_listing(&mut files, &mut subfolders, order, ListingWindow { offset, limit });
+            names(&files, &subfolders)
+        };
+
+        assert_eq!(page(0, Some(2)), ["x", "y"]);
+        assert_eq!(page(1, Some(2)), ["y", "a.txt"]);
+        assert_eq!(page(2, Some(2)), ["a.txt", "b.txt"]);
+        assert_eq!(page(4, Some(2)), ["c.txt"]);
+        assert!(page(9, Some(2)).is_empty());
+        assert_eq!(page(3, None), ["b.txt", "c.txt"]);
+    }
+
+    #[test]
+    fn test_collection_ctag_ignores_listing_order() {
+        let files = vec![file("1", "a.txt", 1, 1), file("2", "b.txt", 2, 2)];
+        let reversed: Vec<FileDto> = files.iter().rev().cloned().collect();
+        assert_eq!(collection_ctag(&files, &[]), collection_ctag(&reversed, &[]));
+
+        let mut changed = files.clone();
+        changed[0].modified_at = 3;
+        assert_ne!(collection_ctag(&files, &[]), collection_ctag(&changed, &[]));
+    }
+}
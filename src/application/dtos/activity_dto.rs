@@ -0,0 +1,73 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::entities::activity::{Activity, ActivityAction, ActivityTargetKind};
+
+/// File or folder an action was done to, as it was at the time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityTargetDto {
+    pub kind: ActivityTargetKind,
+    pub id: String,
+    pub name: String,
+
+    /// Folder holding the item; `None` at the root
+    pub folder_id: Option<String>,
+}
+
+/// Entry of an activity feed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityDto {
+    pub id: String,
+    pub actor_id: String,
+    pub actor_name: String,
+    pub action: ActivityAction,
+    pub target: ActivityTargetDto,
+
+    /// Extra data of the action, such as the previous name of a renamed item
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Activity> for ActivityDto {
+    fn from(activity: Activity) -> Self {
+        Self {
+            id: activity.id,
+            actor_id: activity.actor_id,
+            actor_name: activity.actor_name,
+            action: activity.action,
+            target: ActivityTargetDto {
+                kind: activity.target_kind,
+                id: activity.target_id,
+                name: activity.target_name,
+                folder_id: activity.folder_id,
+            },
+            details: activity.details,
+            created_at: activity.created_at,
+        }
+    }
+}
+
+/// Query parameters of the activity feeds
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ActivityQueryDto {
+    /// Page to return, starting at 0
+    #[serde(default)]
+    pub page: usize,
+
+    /// Entries per page; defaults to 50
+    pub page_size: Option<usize>,
+
+    /// Only this kind of action
+    pub action: Option<ActivityAction>,
+
+    /// Only actions on files or only on folders
+    pub kind: Option<ActivityTargetKind>,
+
+    /// Only actions at or after this time
+    pub since: Option<DateTime<Utc>>,
+
+    /// Only actions before this time
+    pub until: Option<DateTime<Utc>>,
+}
@@ -1,6 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use crate::domain::entities::contact::AddressBook;
+use crate::domain::services::sync_token_service::SyncToken;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AddressBookDto {
@@ -12,6 +13,12 @@ pub struct AddressBookDto {
     pub is_public: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Collection tag (CalendarServer getctag); changes whenever any contact changes
+    #[serde(default)]
+    pub ctag: String,
+    /// Current sync-token (RFC 6578) for incremental sync-collection requests
+    #[serde(default)]
+    pub sync_token: String,
 }
 
 impl Default for AddressBookDto {
@@ -25,12 +32,16 @@ impl Default for AddressBookDto {
             is_public: false,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            ctag: String::new(),
+            sync_token: String::new(),
         }
     }
 }
 
 impl From<AddressBook> for AddressBookDto {
     fn from(book: AddressBook) -> Self {
+        let ctag = book.ctag();
+        let sync_token = SyncToken::new(book.id, book.sync_revision).to_uri();
         Self {
             id: book.id.to_string(),
             name: book.name,
@@ -40,6 +51,8 @@ impl From<AddressBook> for AddressBookDto {
             is_public: book.is_public,
             created_at: book.created_at,
             updated_at: book.updated_at,
+            ctag,
+            sync_token,
         }
     }
 }
@@ -73,4 +86,18 @@ pub struct ShareAddressBookDto {
 pub struct UnshareAddressBookDto {
     pub address_book_id: String,
     pub user_id: String,
-}
\ No newline at end of file
+}
+
+/// Shares an address book with every member of a user group
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareAddressBookWithGroupDto {
+    pub address_book_id: String,
+    pub group_id: String,
+    pub can_write: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnshareAddressBookFromGroupDto {
+    pub address_book_id: String,
+    pub group_id: String,
+}
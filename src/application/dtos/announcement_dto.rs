@@ -0,0 +1,125 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::entities::announcement::{Announcement, AnnouncementAcknowledgment, AnnouncementSeverity};
+
+/// How many of the targeted users have read an announcement
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct AcknowledgmentDto {
+    /// Targeted users that marked the announcement as read
+    pub read_count: i64,
+
+    /// Active users the announcement is shown to
+    pub target_count: i64,
+
+    /// `read_count / target_count`, between 0 and 1
+    pub rate: f64,
+}
+
+impl From<AnnouncementAcknowledgment> for AcknowledgmentDto {
+    fn from(acknowledgment: AnnouncementAcknowledgment) -> Self {
+        Self {
+            read_count: acknowledgment.read_count,
+            target_count: acknowledgment.target_count,
+            rate: acknowledgment.rate(),
+        }
+    }
+}
+
+/// An announcement as shown in the admin API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnnouncementDto {
+    pub id: String,
+    pub title: String,
+    pub message: String,
+    pub severity: AnnouncementSeverity,
+
+    /// Start of the window in which users see the announcement
+    pub starts_at: DateTime<Utc>,
+
+    /// End of the window; `None` shows it until it is deleted
+    pub ends_at: Option<DateTime<Utc>>,
+
+    /// Groups the announcement is shown to; empty means everyone
+    pub target_groups: Vec<String>,
+
+    /// Whether the announcement is currently shown
+    pub active: bool,
+
+    /// Admin who created the announcement
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+
+    /// Read tracking of the targeted users
+    pub acknowledgment: AcknowledgmentDto,
+}
+
+impl AnnouncementDto {
+    pub fn new(announcement: Announcement, acknowledgment: AnnouncementAcknowledgment, now: DateTime<Utc>) -> Self {
+        Self {
+            active: announcement.is_active_at(now),
+            id: announcement.id,
+            title: announcement.title,
+            message: announcement.message,
+            severity: announcement.severity,
+            starts_at: announcement.starts_at,
+            ends_at: announcement.ends_at,
+            target_groups: announcement.target_groups,
+            created_by: announcement.created_by,
+            created_at: announcement.created_at,
+            updated_at: announcement.updated_at,
+            acknowledgment: acknowledgment.into(),
+        }
+    }
+}
+
+/// An active announcement as shown to a user in the notification center
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserAnnouncementDto {
+    pub id: String,
+    pub title: String,
+    pub message: String,
+    pub severity: AnnouncementSeverity,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: Option<DateTime<Utc>>,
+
+    /// Whether the current user already marked it as read
+    pub read: bool,
+}
+
+impl UserAnnouncementDto {
+    pub fn new(announcement: Announcement, read: bool) -> Self {
+        Self {
+            id: announcement.id,
+            title: announcement.title,
+            message: announcement.message,
+            severity: announcement.severity,
+            starts_at: announcement.starts_at,
+            ends_at: announcement.ends_at,
+            read,
+        }
+    }
+}
+
+/// Creates or replaces an announcement
+#[derive(Debug, Clone, Deserialize)]
+pub struct SaveAnnouncementDto {
+    pub title: String,
+
+    pub message: String,
+
+    #[serde(default)]
+    pub severity: AnnouncementSeverity,
+
+    /// Defaults to now
+    #[serde(default)]
+    pub starts_at: Option<DateTime<Utc>>,
+
+    #[serde(default)]
+    pub ends_at: Option<DateTime<Utc>>,
+
+    /// Groups (user roles) to show the announcement to; empty means everyone
+    #[serde(default)]
+    pub target_groups: Vec<String>,
+}
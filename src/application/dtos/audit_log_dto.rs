@@ -0,0 +1,64 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::entities::audit_event::{AuditAction, AuditEvent};
+
+/// Entry of the audit log as admins see it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEventDto {
+    pub id: String,
+    pub occurred_at: DateTime<Utc>,
+    pub action: AuditAction,
+    pub actor_id: Option<String>,
+
+    /// Username, including the one tried in a failed login
+    pub actor_name: Option<String>,
+
+    /// User whose role changed or admin path that was called
+    pub target: Option<String>,
+
+    pub ip_address: Option<String>,
+    pub success: bool,
+
+    /// Cause of a failure or detail of the change
+    pub details: Option<String>,
+}
+
+impl From<AuditEvent> for AuditEventDto {
+    fn from(event: AuditEvent) -> Self {
+        Self {
+            id: event.id,
+            occurred_at: event.occurred_at,
+            action: event.action,
+            actor_id: event.actor_id,
+            actor_name: event.actor_name,
+            target: event.target,
+            ip_address: event.ip_address,
+            success: event.success,
+            details: event.details,
+        }
+    }
+}
+
+/// Query parameters of the admin audit log API
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AuditLogQueryDto {
+    /// Page to return, starting at 0
+    #[serde(default)]
+    pub page: usize,
+
+    /// Entries per page; defaults to 100
+    pub page_size: Option<usize>,
+
+    /// Only entries of this user, by ID or username
+    pub user: Option<String>,
+
+    /// Only this kind of event
+    pub action: Option<AuditAction>,
+
+    /// Only entries at or after this time
+    pub since: Option<DateTime<Utc>>,
+
+    /// Only entries before this time
+    pub until: Option<DateTime<Utc>>,
+}
@@ -4,6 +4,9 @@ use uuid::Uuid;
 use std::collections::HashMap;
 use crate::domain::entities::calendar::Calendar;
 use crate::domain::entities::calendar_event::CalendarEvent;
+use crate::domain::entities::event_alarm::{AlarmAction, AlarmRelated, AlarmTrigger, EventAlarm};
+use crate::domain::entities::event_attendee::{EventAttendee, EventOrganizer};
+use crate::common::errors::DomainError;
 
 /// DTO for calendar data transfer
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -17,6 +20,12 @@ pub struct CalendarDto {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub custom_properties: HashMap<String, String>,
+    /// Collection tag (CalendarServer getctag); changes whenever any event changes
+    #[serde(default)]
+    pub ctag: String,
+    /// calendar-timezone property: a VCALENDAR with the calendar's VTIMEZONE
+    #[serde(default)]
+    pub timezone: Option<String>,
 }
 
 impl Default for CalendarDto {
@@ -31,6 +40,8 @@ impl Default for CalendarDto {
             created_at: Utc::now(),
             updated_at: Utc::now(),
             custom_properties: HashMap::new(),
+            ctag: String::new(),
+            timezone: None,
         }
     }
 }
@@ -47,6 +58,8 @@ impl From<Calendar> for CalendarDto {
             created_at: *calendar.created_at(),
             updated_at: *calendar.updated_at(),
             custom_properties: calendar.custom_properties().clone(),
+            ctag: calendar.ctag(),
+            timezone: calendar.timezone().map(|s| s.to_string()),
         }
     }
 }
@@ -58,6 +71,8 @@ pub struct CreateCalendarDto {
     pub description: Option<String>,
     pub color: Option<String>,
     pub is_public: Option<bool>,
+    /// calendar-timezone value (VCALENDAR with a VTIMEZONE)
+    pub timezone: Option<String>,
 }
 
 /// DTO for calendar update
@@ -67,6 +82,8 @@ pub struct UpdateCalendarDto {
     pub description: Option<String>,
     pub color: Option<String>,
     pub is_public: Option<bool>,
+    /// calendar-timezone value (VCALENDAR with a VTIMEZONE)
+    pub timezone: Option<String>,
 }
 
 /// DTO for calendar sharing
@@ -90,6 +107,21 @@ pub struct CalendarEventDto {
     pub all_day: bool,
     pub rrule: Option<String>,
     pub ical_uid: String,
+    /// Complete iCalendar object, used by CalDAV and to evaluate query filters
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub ical_data: String,
+    /// TZID of the start time; times above are always UTC
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// Reminders of the event (VALARM components)
+    #[serde(default)]
+    pub alarms: Vec<EventAlarmDto>,
+    /// Organizer of a meeting (ORGANIZER property)
+    #[serde(default)]
+    pub organizer: Option<EventOrganizerDto>,
+    /// Attendees of a meeting and their participation status (ATTENDEE properties)
+    #[serde(default)]
+    pub attendees: Vec<EventAttendeeDto>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -107,6 +139,11 @@ impl Default for CalendarEventDto {
             all_day: false,
             rrule: None,
             ical_uid: String::new(),
+            ical_data: String::new(),
+            timezone: None,
+            alarms: Vec::new(),
+            organizer: None,
+            attendees: Vec::new(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
         }
@@ -126,12 +163,146 @@ impl From<CalendarEvent> for CalendarEventDto {
             all_day: event.all_day(),
             rrule: event.rrule().map(|s| s.to_string()),
             ical_uid: event.ical_uid().to_string(),
+            ical_data: event.ical_data().to_string(),
+            timezone: event.timezone().map(|s| s.to_string()),
+            alarms: event.alarms().iter().cloned().map(EventAlarmDto::from).collect(),
+            organizer: event.organizer().cloned().map(EventOrganizerDto::from),
+            attendees: event.attendees().iter().cloned().map(EventAttendeeDto::from).collect(),
             created_at: *event.created_at(),
             updated_at: *event.updated_at(),
         }
     }
 }
 
+/// DTO for the organizer of a meeting
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EventOrganizerDto {
+    pub email: String,
+    #[serde(default)]
+    pub common_name: Option<String>,
+}
+
+impl From<EventOrganizer> for EventOrganizerDto {
+    fn from(organizer: EventOrganizer) -> Self {
+        Self {
+            email: organizer.email,
+            common_name: organizer.common_name,
+        }
+    }
+}
+
+/// DTO for an attendee of a meeting
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EventAttendeeDto {
+    pub email: String,
+    #[serde(default)]
+    pub common_name: Option<String>,
+    /// CHAIR, REQ-PARTICIPANT, OPT-PARTICIPANT or NON-PARTICIPANT
+    pub role: String,
+    /// NEEDS-ACTION, ACCEPTED, DECLINED, TENTATIVE or DELEGATED
+    pub partstat: String,
+    /// Whether the organizer expects a reply
+    #[serde(default)]
+    pub rsvp: bool,
+}
+
+impl From<EventAttendee> for EventAttendeeDto {
+    fn from(attendee: EventAttendee) -> Self {
+        Self {
+            email: attendee.email,
+            common_name: attendee.common_name,
+            role: attendee.role.as_str().to_string(),
+            partstat: attendee.partstat.as_str().to_string(),
+            rsvp: attendee.rsvp,
+        }
+    }
+}
+
+/// DTO for an event reminder (VALARM)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EventAlarmDto {
+    /// DISPLAY, AUDIO or EMAIL
+    pub action: String,
+    /// Seconds from the start (or end, see `related`); negative before it
+    #[serde(default)]
+    pub trigger_offset_seconds: Option<i64>,
+    /// START or END, for relative triggers
+    #[serde(default)]
+    pub related: Option<String>,
+    /// Absolute trigger time; used instead of the offset when present
+    #[serde(default)]
+    pub trigger_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub repeat: u32,
+    #[serde(default)]
+    pub repeat_interval_seconds: Option<i64>,
+    #[serde(default)]
+    pub uid: Option<String>,
+}
+
+impl From<EventAlarm> for EventAlarmDto {
+    fn from(alarm: EventAlarm) -> Self {
+        let (trigger_offset_seconds, related, trigger_at) = match alarm.trigger {
+            AlarmTrigger::Relative { offset_seconds, related } => {
+                let related = match related {
+                    AlarmRelated::Start => "START",
+                    AlarmRelated::End => "END",
+                };
+                (Some(offset_seconds), Some(related.to_string()), None)
+            }
+            AlarmTrigger::Absolute { at } => (None, None, Some(at)),
+        };
+        Self {
+            action: alarm.action.as_str().to_string(),
+            trigger_offset_seconds,
+            related,
+            trigger_at,
+            description: alarm.description,
+            summary: alarm.summary,
+            repeat: alarm.repeat,
+            repeat_interval_seconds: alarm.repeat_interval_seconds,
+            uid: alarm.uid,
+        }
+    }
+}
+
+impl TryFrom<EventAlarmDto> for EventAlarm {
+    type Error = DomainError;
+
+    fn try_from(dto: EventAlarmDto) -> Result<Self, Self::Error> {
+        let action = AlarmAction::parse(&dto.action)
+            .ok_or_else(|| DomainError::validation_error(format!("Unsupported alarm action: {}", dto.action)))?;
+        let trigger = match (dto.trigger_at, dto.trigger_offset_seconds) {
+            (Some(at), _) => AlarmTrigger::Absolute { at },
+            (None, Some(offset_seconds)) => {
+                let related = match dto.related.as_deref().map(str::to_ascii_uppercase).as_deref() {
+                    None | Some("START") => AlarmRelated::Start,
+                    Some("END") => AlarmRelated::End,
+                    Some(other) => return Err(DomainError::validation_error(format!("Invalid alarm related value: {}", other))),
+                };
+                AlarmTrigger::Relative { offset_seconds, related }
+            }
+            (None, None) => return Err(DomainError::validation_error("Alarm needs trigger_at or trigger_offset_seconds")),
+        };
+        if dto.repeat > 0 && dto.repeat_interval_seconds.is_none_or(|interval| interval <= 0) {
+            return Err(DomainError::validation_error("Repeating alarms need a positive repeat_interval_seconds"));
+        }
+        Ok(Self {
+            action,
+            trigger,
+            description: dto.description,
+            summary: dto.summary,
+            repeat: dto.repeat,
+            repeat_interval_seconds: dto.repeat_interval_seconds.filter(|_| dto.repeat > 0),
+            uid: dto.uid,
+        })
+    }
+}
+
 /// DTO for calendar event creation using iCalendar data
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateEventICalDto {
@@ -139,6 +310,82 @@ pub struct CreateEventICalDto {
     pub ical_data: String,
 }
 
+/// What to do with imported events whose UID already exists in the calendar
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateUidPolicy {
+    /// Keep the stored event and report the imported one as a duplicate
+    #[default]
+    Skip,
+    /// Overwrite the stored event with the imported one
+    Replace,
+}
+
+/// An event of an imported .ics file that was not stored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RejectedEventDto {
+    /// Position of the VEVENT in the file, starting at 0
+    pub index: usize,
+    pub uid: Option<String>,
+    pub reason: String,
+}
+
+/// Outcome of importing an .ics file into a calendar
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CalendarImportResultDto {
+    /// Events created
+    pub imported: usize,
+    /// Stored events overwritten with `DuplicateUidPolicy::Replace`
+    pub replaced: usize,
+    /// Events skipped because their UID was already used
+    pub duplicates: Vec<RejectedEventDto>,
+    /// Events that could not be read
+    pub errors: Vec<RejectedEventDto>,
+}
+
+/// Format identifier of calendar JSON exports; bumped on incompatible changes
+pub const CALENDAR_JSON_EXPORT_SCHEMA: &str = "oxicloud.calendar-export/v1";
+
+/// A calendar exported as JSON (see doc/JSON-EXPORT.md).
+///
+/// A raw export lists every stored event once, with its recurrence rule; an
+/// expanded one lists the occurrences inside a time range instead.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarJsonExportDto {
+    pub schema: String,
+    pub exported_at: DateTime<Utc>,
+    pub calendar: CalendarDto,
+    /// Every event of the calendar (raw exports)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub events: Option<Vec<CalendarEventDto>>,
+    /// Occurrences overlapping `range_start..range_end`, by start time (expanded exports)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub occurrences: Option<Vec<EventOccurrenceDto>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range_start: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range_end: Option<DateTime<Utc>>,
+}
+
+/// One occurrence of an event in an expanded export
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventOccurrenceDto {
+    pub event_id: String,
+    pub ical_uid: String,
+    /// Original start of the occurrence (RECURRENCE-ID); `None` for events that do not repeat
+    pub recurrence_id: Option<DateTime<Utc>>,
+    pub summary: String,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub all_day: bool,
+    /// TZID of the start time; times above are always UTC
+    pub timezone: Option<String>,
+    /// Whether the occurrence was edited on its own and differs from the series
+    pub overridden: bool,
+}
+
 /// DTO for calendar event creation with structured data
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateEventDto {
@@ -150,6 +397,9 @@ pub struct CreateEventDto {
     pub end_time: DateTime<Utc>,
     pub all_day: Option<bool>,
     pub rrule: Option<String>,
+    /// Reminders of the new event
+    #[serde(default)]
+    pub alarms: Option<Vec<EventAlarmDto>>,
     pub user_id: String, // Added for authorization
 }
 
@@ -163,6 +413,9 @@ pub struct UpdateEventDto {
     pub end_time: Option<DateTime<Utc>>,
     pub all_day: Option<bool>,
     pub rrule: Option<String>,
+    /// Reminders; when omitted on update the current ones are kept
+    #[serde(default)]
+    pub alarms: Option<Vec<EventAlarmDto>>,
     pub user_id: String, // Added for authorization
 }
 
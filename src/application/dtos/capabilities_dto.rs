@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+
+/// Instance capabilities returned to clients so they can adapt their behavior
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapabilitiesDto {
+    /// Server version
+    pub version: String,
+
+    /// Enabled features
+    pub features: FeatureCapabilitiesDto,
+
+    /// Upload limits and chunking parameters
+    pub upload: UploadCapabilitiesDto,
+
+    /// DAV protocol support
+    pub dav: DavCapabilitiesDto,
+
+    /// REST API versions
+    pub api: ApiCapabilitiesDto,
+}
+
+/// Feature switches as seen by clients
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureCapabilitiesDto {
+    pub auth: bool,
+    pub file_sharing: bool,
+    pub trash: bool,
+    pub search: bool,
+    pub favorites: bool,
+    pub recent_items: bool,
+    pub user_storage_quotas: bool,
+    pub external_storage: bool,
+    pub image_placeholders: bool,
+    pub similar_photos: bool,
+    pub image_tagging: bool,
+    pub image_previews: bool,
+    pub resumable_uploads: bool,
+    pub versioning: bool,
+    pub e2ee: bool,
+    pub federation: bool,
+}
+
+/// Upload related limits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadCapabilitiesDto {
+    /// Maximum accepted request body for uploads in bytes
+    pub max_upload_size_bytes: u64,
+
+    /// Preferred chunk size for large transfers in bytes
+    pub chunk_size_bytes: u64,
+
+    /// Size above which files are processed in parallel chunks
+    pub parallel_threshold_bytes: u64,
+}
+
+/// DAV protocol support
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DavCapabilitiesDto {
+    /// Whether the WebDAV endpoint is mounted
+    pub webdav: bool,
+
+    /// WebDAV compliance classes advertised in the DAV header
+    pub webdav_classes: Vec<String>,
+
+    /// Base path of the WebDAV endpoint
+    pub webdav_path: String,
+
+    /// Whether the CalDAV endpoint is mounted
+    pub caldav: bool,
+
+    /// Whether the CardDAV endpoint is mounted
+    pub carddav: bool,
+}
+
+/// REST API versions served by the instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiCapabilitiesDto {
+    /// Version used when the client does not ask for one
+    pub current_version: String,
+
+    /// Versions that can be requested with the `/api/v{N}` prefix
+    pub supported_versions: Vec<String>,
+
+    /// When the unversioned `/api/...` routes stop working, if scheduled
+    pub legacy_sunset: Option<chrono::DateTime<chrono::Utc>>,
+}
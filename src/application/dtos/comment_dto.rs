@@ -0,0 +1,66 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::entities::comment::{Comment, CommentTargetKind};
+
+/// Comment on a file or folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommentDto {
+    pub id: String,
+    pub item_kind: CommentTargetKind,
+    pub item_id: String,
+    pub author_id: String,
+
+    /// Name of the author when the comment was written
+    pub author_name: String,
+
+    pub message: String,
+
+    /// IDs of the users mentioned with `@username`
+    pub mentions: Vec<String>,
+
+    pub created_at: DateTime<Utc>,
+
+    /// Last edit; absent if the comment was never edited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edited_at: Option<DateTime<Utc>>,
+}
+
+impl From<Comment> for CommentDto {
+    fn from(comment: Comment) -> Self {
+        Self {
+            id: comment.id,
+            item_kind: comment.item_kind,
+            item_id: comment.item_id,
+            author_id: comment.author_id,
+            author_name: comment.author_name,
+            message: comment.message,
+            mentions: comment.mentions,
+            created_at: comment.created_at,
+            edited_at: comment.edited_at,
+        }
+    }
+}
+
+/// Request to write a comment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateCommentDto {
+    pub message: String,
+}
+
+/// Request to change the text of a comment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateCommentDto {
+    pub message: String,
+}
+
+/// Query parameters of the comment list
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CommentQueryDto {
+    /// Page to return, starting at 0
+    #[serde(default)]
+    pub page: usize,
+
+    /// Comments per page; defaults to 50
+    pub page_size: Option<usize>,
+}
@@ -1,6 +1,8 @@
 use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use crate::application::dtos::address_book_dto::AddressBookDto;
 use crate::domain::entities::contact::{Contact, Email, Phone, Address, ContactGroup};
+use crate::domain::services::vcard_service::VCard;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmailDto {
@@ -79,6 +81,12 @@ pub struct ContactDto {
     pub photo_url: Option<String>,
     pub birthday: Option<NaiveDate>,
     pub anniversary: Option<NaiveDate>,
+    /// CATEGORIES of the vCard
+    #[serde(default)]
+    pub categories: Vec<String>,
+    /// URL properties of the vCard
+    #[serde(default)]
+    pub urls: Vec<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub etag: String,
@@ -103,6 +111,8 @@ impl Default for ContactDto {
             photo_url: None,
             birthday: None,
             anniversary: None,
+            categories: Vec::new(),
+            urls: Vec::new(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
             etag: uuid::Uuid::new_v4().to_string(),
@@ -112,6 +122,9 @@ impl Default for ContactDto {
 
 impl From<Contact> for ContactDto {
     fn from(contact: Contact) -> Self {
+        let card = VCard::parse(&contact.vcard).unwrap_or_default();
+        let categories = card.properties_named("CATEGORIES").flat_map(|property| property.list()).collect();
+        let urls = card.properties_named("URL").map(|property| property.text()).collect();
         Self {
             id: contact.id.to_string(),
             address_book_id: contact.address_book_id.to_string(),
@@ -129,6 +142,8 @@ impl From<Contact> for ContactDto {
             photo_url: contact.photo_url,
             birthday: contact.birthday,
             anniversary: contact.anniversary,
+            categories,
+            urls,
             created_at: contact.created_at,
             updated_at: contact.updated_at,
             etag: contact.etag,
@@ -220,4 +235,98 @@ pub struct UpdateContactGroupDto {
 pub struct GroupMembershipDto {
     pub group_id: String,
     pub contact_id: String,
-}
\ No newline at end of file
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContactPhotosRequestDto {
+    pub contact_ids: Vec<String>,
+    /// When false only hashes and sizes are returned, so clients can skip unchanged photos
+    #[serde(default)]
+    pub include_data: bool,
+    #[serde(default)]
+    pub user_id: String, // User requesting the photos
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContactPhotoDto {
+    pub contact_id: String,
+    /// SHA-256 of the photo, `None` when the contact has no photo
+    pub hash: Option<String>,
+    pub media_type: Option<String>,
+    pub size: Option<usize>,
+    /// Base64 image data for embedded photos
+    pub data: Option<String>,
+    /// Link for photos stored outside the vCard
+    pub uri: Option<String>,
+}
+
+/// A contact as a CardDAV resource: its vCard and the ETag clients use
+/// for conditional requests
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VCardObjectDto {
+    pub id: String,
+    pub address_book_id: String,
+    /// Resource name of the contact, `{uid}.vcf`
+    pub uid: String,
+    pub etag: String,
+    pub vcard: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Contact> for VCardObjectDto {
+    fn from(contact: Contact) -> Self {
+        Self {
+            id: contact.id.to_string(),
+            address_book_id: contact.address_book_id.to_string(),
+            uid: contact.uid,
+            etag: contact.etag,
+            vcard: contact.vcard,
+            updated_at: contact.updated_at,
+        }
+    }
+}
+
+/// File format of a contact import
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContactImportFormat {
+    /// One or more vCards, as exported by most address books
+    #[default]
+    Vcf,
+    /// One contact per row; the first row holds the column headers
+    Csv,
+}
+
+/// A record of an imported file that was not stored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RejectedContactDto {
+    /// Position of the vCard or CSV row in the file, starting at 0
+    pub index: usize,
+    pub uid: Option<String>,
+    pub reason: String,
+}
+
+/// Outcome of importing a contacts file into an address book
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ContactImportResultDto {
+    /// Records found in the file
+    pub total: usize,
+    /// Contacts created
+    pub imported: usize,
+    /// Records skipped because their UID was already used
+    pub duplicates: Vec<RejectedContactDto>,
+    /// Records that could not be read or stored
+    pub errors: Vec<RejectedContactDto>,
+}
+
+/// Format identifier of address book JSON exports; bumped on incompatible changes
+pub const ADDRESS_BOOK_JSON_EXPORT_SCHEMA: &str = "oxicloud.address-book-export/v1";
+
+/// An address book exported as JSON (see doc/JSON-EXPORT.md)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressBookJsonExportDto {
+    pub schema: String,
+    pub exported_at: DateTime<Utc>,
+    pub address_book: AddressBookDto,
+    /// Contacts sorted by full name
+    pub contacts: Vec<ContactDto>,
+}
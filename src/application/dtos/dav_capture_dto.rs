@@ -0,0 +1,40 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Request to start capturing the DAV traffic of a user
+#[derive(Debug, Clone, Deserialize)]
+pub struct StartDavCaptureDto {
+    /// User whose requests are captured
+    pub username: String,
+
+    /// Length of the capture window (server maximum when omitted)
+    #[serde(default)]
+    pub minutes: Option<u32>,
+}
+
+/// State of a DAV capture
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DavCaptureSessionDto {
+    /// Captured user
+    pub username: String,
+
+    /// Administrator who started the capture
+    pub started_by: String,
+
+    pub started_at: DateTime<Utc>,
+
+    /// End of the capture window
+    pub expires_at: DateTime<Utc>,
+
+    /// Set when an administrator stopped the capture early
+    pub stopped_at: Option<DateTime<Utc>>,
+
+    /// Request/response pairs recorded so far
+    pub exchanges: u64,
+
+    /// Bytes written to the capture log
+    pub captured_bytes: u64,
+
+    /// Whether new requests are still being recorded
+    pub active: bool,
+}
@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+/// Body of a multiget request
+#[derive(Debug, Clone, Deserialize)]
+pub struct MultigetRequestDto {
+    /// WebDAV, CalDAV or CardDAV hrefs, as full URLs or absolute paths
+    pub hrefs: Vec<String>,
+}
+
+/// One resolved href, mirroring a DAV multistatus `<response>`
+#[derive(Debug, Clone, Serialize)]
+pub struct MultigetItemDto {
+    /// Href exactly as it was requested
+    pub href: String,
+
+    /// HTTP status of this href (200, 400, 403, 404, 413 or 500)
+    pub status: u16,
+
+    /// ETag the DAV endpoint would return for the resource
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+
+    /// Resource body, encoded as described by `encoding`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+
+    /// "utf-8" for text bodies, "base64" for binary ones
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+
+    /// Why the href could not be returned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl MultigetItemDto {
+    /// Entry for an href that could not be returned
+    pub fn error(href: impl Into<String>, status: u16, error: impl Into<String>) -> Self {
+        Self {
+            href: href.into(),
+            status,
+            etag: None,
+            content_type: None,
+            body: None,
+            encoding: None,
+            error: Some(error.into()),
+        }
+    }
+}
+
+/// Multiget result, one entry per requested href in request order
+#[derive(Debug, Clone, Serialize)]
+pub struct MultigetResponseDto {
+    pub responses: Vec<MultigetItemDto>,
+}
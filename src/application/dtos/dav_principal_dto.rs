@@ -0,0 +1,61 @@
+/// Principal properties that can be searched (RFC 3744, section 9.5)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrincipalSearchProperty {
+    /// DAV:displayname
+    DisplayName,
+    /// CALDAV:calendar-user-address-set, matched against the e-mail address
+    CalendarUserAddress,
+}
+
+/// A single `property-search` of a principal-property-search REPORT
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyMatchDto {
+    pub property: PrincipalSearchProperty,
+    pub text: String,
+}
+
+/// Criteria of a principal-property-search REPORT
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrincipalSearchDto {
+    pub matches: Vec<PropertyMatchDto>,
+
+    /// `test="allof"`: a principal must satisfy every match instead of any
+    pub match_all: bool,
+}
+
+/// Kind of principal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PrincipalKind {
+    User,
+    Group,
+}
+
+/// A user or group principal as seen by the requesting user
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrincipalDto {
+    pub kind: PrincipalKind,
+
+    /// Username, or group name for groups
+    pub name: String,
+
+    pub display_name: String,
+
+    /// E-mail address, only when the requester is allowed to see it
+    pub email: Option<String>,
+
+    /// Names of the groups the principal belongs to
+    pub group_membership: Vec<String>,
+
+    /// Usernames of the members of a group; `None` when the requester may
+    /// not list them. Always empty for users.
+    pub group_member_set: Option<Vec<String>>,
+}
+
+/// Result of a principal-property-search REPORT
+#[derive(Debug, Clone)]
+pub struct PrincipalSearchResultDto {
+    pub principals: Vec<PrincipalDto>,
+
+    /// More principals matched than are returned; the client should narrow the search
+    pub truncated: bool,
+}
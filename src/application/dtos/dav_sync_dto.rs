@@ -0,0 +1,44 @@
+use crate::application::dtos::calendar_dto::CalendarEventDto;
+use crate::application::dtos::contact_dto::VCardObjectDto;
+
+/// Result of a sync-collection request (RFC 6578)
+#[derive(Debug, Clone)]
+pub enum SyncOutcome<T> {
+    /// Changes since the client's token
+    Changes(T),
+    /// The token is unknown or the changes since it are no longer kept;
+    /// the client has to start over with an empty token
+    InvalidToken,
+}
+
+/// Changes of a calendar since a sync-token
+#[derive(Debug, Clone)]
+pub struct CalendarSyncDto {
+    /// Token to send in the next sync-collection request
+    pub sync_token: String,
+
+    /// Events created or modified since the previous token
+    pub events: Vec<CalendarEventDto>,
+
+    /// iCalendar UIDs of the events deleted since the previous token
+    pub deleted_uids: Vec<String>,
+
+    /// More changes are pending; the client should sync again with `sync_token`
+    pub truncated: bool,
+}
+
+/// Changes of an address book since a sync-token
+#[derive(Debug, Clone)]
+pub struct AddressBookSyncDto {
+    /// Token to send in the next sync-collection request
+    pub sync_token: String,
+
+    /// vCards created or modified since the previous token
+    pub vcards: Vec<VCardObjectDto>,
+
+    /// Resource names (UIDs) of the vCards deleted since the previous token
+    pub deleted_uids: Vec<String>,
+
+    /// More changes are pending; the client should sync again with `sync_token`
+    pub truncated: bool,
+}
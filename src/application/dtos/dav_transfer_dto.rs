@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Progress of a WebDAV COPY or MOVE that runs in the background
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DavTransferState {
+    Running,
+    Completed,
+    Failed,
+}
+
+/// WebDAV COPY or MOVE answered with `202 Accepted` that keeps running in
+/// the background
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DavTransferDto {
+    pub id: String,
+
+    /// `copy` or `move`
+    pub operation: String,
+
+    /// Source and destination, relative to the WebDAV root
+    pub source: String,
+
+    pub destination: String,
+
+    /// Bytes under the source folder when the transfer started
+    pub size: u64,
+
+    pub state: DavTransferState,
+
+    pub created_at: DateTime<Utc>,
+
+    pub finished_at: Option<DateTime<Utc>>,
+
+    /// Status the request would have had if it had run in the foreground;
+    /// set once the transfer has finished
+    pub status: Option<u16>,
+
+    /// Cause of a failed transfer
+    pub error: Option<String>,
+}
@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+
+/// Problem found in one calendar object or contact
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationIssueDto {
+    /// Event or contact ID
+    pub item_id: String,
+
+    /// UID the item is stored with
+    pub uid: String,
+
+    /// "missing_uid", "duplicate_uid", "broken_rrule", "malformed_icalendar" or "malformed_vcard"
+    pub kind: String,
+
+    /// What is wrong with the item
+    pub message: String,
+
+    /// Automatic fix the repair job would apply ("set_uid", "remove_rrule", "complete_vcard")
+    pub fix: Option<String>,
+
+    /// Human-readable description of the fix
+    pub suggestion: Option<String>,
+}
+
+/// Result of scanning a calendar or an address book
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationReportDto {
+    /// Calendar or address book ID
+    pub collection_id: String,
+
+    /// "calendar" or "address_book"
+    pub collection_type: String,
+
+    /// Items examined
+    pub scanned: u64,
+
+    /// Issues that the repair job can fix
+    pub fixable: u64,
+
+    pub issues: Vec<ValidationIssueDto>,
+}
+
+/// Status of a repair job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepairJobDto {
+    /// Job ID
+    pub id: String,
+
+    /// Calendar or address book being repaired
+    pub collection_id: String,
+
+    /// "running", "completed" or "failed"
+    pub status: String,
+
+    /// Items rewritten with their fixes applied
+    pub repaired: u64,
+
+    /// Items whose fixes could not be stored
+    pub failed: u64,
+
+    /// Issues left for the user to resolve by hand
+    pub unfixable: u64,
+
+    /// Error that stopped the job
+    pub error: Option<String>,
+
+    /// Start timestamp (seconds since epoch)
+    pub started_at: u64,
+
+    /// End timestamp (seconds since epoch)
+    pub finished_at: Option<u64>,
+}
@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Public details of the demo account, shown on the login page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DemoInfoDto {
+    pub username: String,
+    pub password: String,
+
+    /// Minutes between automatic resets; 0 when resets only run on demand
+    pub reset_interval_minutes: u64,
+
+    pub last_reset_at: Option<DateTime<Utc>>,
+
+    /// When the data will be reset next, if resets are scheduled
+    pub next_reset_at: Option<DateTime<Utc>>,
+}
+
+/// What one reset of the demo account recreated
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DemoResetReportDto {
+    /// ID of the recreated demo user
+    pub user_id: String,
+
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+
+    /// Sample folders and files copied from the `demo` skeleton
+    pub folders_created: usize,
+    pub files_created: usize,
+
+    pub calendars_created: usize,
+    pub events_created: usize,
+
+    pub address_books_created: usize,
+    pub contacts_created: usize,
+}
@@ -0,0 +1,41 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// One step of a destructive operation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlannedActionDto {
+    /// What is done, such as `delete_folder` or `remove_share`
+    pub action: String,
+
+    /// What it is done to: an ID, a username or a path
+    pub target: String,
+
+    /// Bytes freed or removed by the step, when known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<u64>,
+}
+
+/// What a destructive admin operation would do, returned instead of running
+/// it when the request carries `?dry_run=true`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExecutionPlanDto {
+    /// Operation the plan belongs to, such as `storage_gc` or `user_purge`
+    pub operation: String,
+
+    /// Nothing was changed; the actions are what a real run would do
+    pub dry_run: bool,
+
+    /// Number of steps of each action, including those left out of `actions`
+    pub counts: BTreeMap<String, u64>,
+
+    /// Bytes the steps would free or remove, for those where it is known
+    pub affected_bytes: u64,
+
+    /// The steps in the order they would run
+    pub actions: Vec<PlannedActionDto>,
+
+    /// Some steps were left out of `actions` to keep the response small;
+    /// `counts` still includes them
+    pub truncated: bool,
+}
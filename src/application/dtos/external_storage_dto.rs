@@ -0,0 +1,178 @@
+use serde::{Deserialize, Serialize};
+
+use crate::application::ports::external_storage_ports::ExternalEntry;
+
+/// DTO describing an external storage mount
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalMountDto {
+    /// Mount name used in API paths
+    pub name: String,
+
+    /// Backend type (e.g. "sftp")
+    pub backend: String,
+
+    /// Whether writes are rejected
+    pub read_only: bool,
+
+    /// Last known status ("unknown", "online", "unreachable" or "error")
+    pub status: String,
+
+    /// Error reported by the last failed check
+    pub last_error: Option<String>,
+
+    /// Timestamp of the last change detection run
+    pub last_checked_at: Option<u64>,
+}
+
+/// DTO for an entry on an external mount
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalEntryDto {
+    /// Mount the entry belongs to
+    pub mount: String,
+
+    /// Entry name
+    pub name: String,
+
+    /// Path relative to the mount root
+    pub path: String,
+
+    /// Whether the entry is a directory
+    pub is_dir: bool,
+
+    /// Size in bytes
+    pub size: u64,
+
+    /// MIME type (directories use "inode/directory")
+    pub mime_type: String,
+
+    /// Last modification timestamp
+    pub modified_at: u64,
+}
+
+impl ExternalEntryDto {
+    /// Builds the DTO for an entry of the given mount
+    pub fn from_entry(mount: &str, entry: ExternalEntry) -> Self {
+        let mime_type = if entry.is_dir {
+            "inode/directory".to_string()
+        } else {
+            mime_guess::from_path(&entry.name)
+                .first_or_octet_stream()
+                .to_string()
+        };
+
+        Self {
+            mount: mount.to_string(),
+            name: entry.name,
+            path: entry.path,
+            is_dir: entry.is_dir,
+            size: entry.size,
+            mime_type,
+            modified_at: entry.modified_at,
+        }
+    }
+}
+
+/// Query parameters addressing a path on an external mount
+#[derive(Debug, Deserialize)]
+pub struct ExternalPathQuery {
+    /// Path relative to the mount root (defaults to the root)
+    #[serde(default)]
+    pub path: String,
+}
+
+/// DTO describing a change detected on an external mount
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalChangeDto {
+    /// Mount where the change was detected
+    pub mount: String,
+
+    /// Path relative to the mount root
+    pub path: String,
+
+    /// Kind of change ("created", "modified" or "deleted")
+    pub change: String,
+
+    /// Whether the entry is a directory
+    pub is_dir: bool,
+
+    /// When the change was detected
+    pub detected_at: u64,
+}
+
+/// Query parameters for listing detected changes
+#[derive(Debug, Deserialize)]
+pub struct ExternalChangesQuery {
+    /// Only return changes detected after this timestamp
+    pub since: Option<u64>,
+}
+
+/// Request body for moving an entry within or between mounts
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExternalMoveRequestDto {
+    /// Mount holding the entry
+    pub from_mount: String,
+
+    /// Entry path relative to the source mount root
+    pub from_path: String,
+
+    /// Destination mount (defaults to the source mount)
+    #[serde(default)]
+    pub to_mount: Option<String>,
+
+    /// Destination path relative to the destination mount root
+    pub to_path: String,
+}
+
+/// Result of a move: either done in place or handed over to a transfer job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalMoveDto {
+    /// "metadata" when the backend renamed the entry, "transfer" when a job copies it
+    pub strategy: String,
+
+    /// Moved entry (metadata moves only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry: Option<ExternalEntryDto>,
+
+    /// Background transfer job (moves between mounts only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub job: Option<ExternalTransferJobDto>,
+}
+
+/// DTO describing a background transfer between two mounts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalTransferJobDto {
+    /// Job identifier
+    pub id: String,
+
+    /// Source mount and path
+    pub from_mount: String,
+    pub from_path: String,
+
+    /// Destination mount and path
+    pub to_mount: String,
+    pub to_path: String,
+
+    /// "running", "completed", "rolled_back" or "failed"
+    pub status: String,
+
+    /// Files found on the source
+    pub total_files: u64,
+
+    /// Files copied and verified so far
+    pub transferred_files: u64,
+
+    /// Bytes copied and verified so far
+    pub transferred_bytes: u64,
+
+    /// Error that stopped the job
+    pub error: Option<String>,
+
+    /// When the job started
+    pub started_at: u64,
+
+    /// When the job finished
+    pub finished_at: Option<u64>,
+
+    /// Id of the user who started the job
+    pub started_by: String,
+}
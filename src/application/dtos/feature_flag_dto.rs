@@ -0,0 +1,58 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::entities::feature_flag::{FeatureFlag, FlagOverride};
+
+/// A feature flag as shown in the admin API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureFlagDto {
+    /// Flag identifier, e.g. `webdav.range-requests`
+    pub key: String,
+
+    /// What the flag controls
+    pub description: Option<String>,
+
+    /// Global rule for users without an override
+    pub enabled: bool,
+
+    /// Share of users (0-100) that get the flag when it is enabled
+    pub rollout_percentage: u8,
+
+    /// Group and user exceptions to the global rule
+    pub overrides: Vec<FlagOverride>,
+
+    /// Last time the flag or one of its overrides changed
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<FeatureFlag> for FeatureFlagDto {
+    fn from(flag: FeatureFlag) -> Self {
+        Self {
+            key: flag.key,
+            description: flag.description,
+            enabled: flag.enabled,
+            rollout_percentage: flag.rollout_percentage,
+            overrides: flag.overrides,
+            updated_at: flag.updated_at,
+        }
+    }
+}
+
+/// Creates a flag or changes its global rule; omitted fields keep their value
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UpdateFeatureFlagDto {
+    #[serde(default)]
+    pub description: Option<String>,
+
+    #[serde(default)]
+    pub enabled: Option<bool>,
+
+    #[serde(default)]
+    pub rollout_percentage: Option<u8>,
+}
+
+/// Enables or disables a flag for one group or user
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetFlagOverrideDto {
+    pub enabled: bool,
+}
@@ -27,6 +27,10 @@ pub struct FileDto {
     
     /// Last modification timestamp
     pub modified_at: u64,
+
+    /// Blurhash placeholder for images, shown before the thumbnail loads
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<String>,
 }
 
 impl From<File> for FileDto {
@@ -40,6 +44,7 @@ impl From<File> for FileDto {
             folder_id: file.folder_id().map(String::from),
             created_at: file.created_at(),
             modified_at: file.modified_at(),
+            placeholder: None,
         }
     }
 }
@@ -75,6 +80,7 @@ impl FileDto {
             folder_id: None,
             created_at: 0,
             modified_at: 0,
+            placeholder: None,
         }
     }
 }
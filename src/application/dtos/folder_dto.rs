@@ -25,6 +25,17 @@ pub struct MoveFolderDto {
     pub parent_id: Option<String>,
 }
 
+/// DTO for folder copy requests
+#[derive(Debug, Deserialize)]
+pub struct CopyFolderDto {
+    /// Parent folder of the copy (None for root level)
+    pub parent_id: Option<String>,
+
+    /// Name of the copy; defaults to the name of the source folder
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
 /// DTO for folder responses
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FolderDto {
@@ -101,4 +112,76 @@ impl Default for FolderDto {
     fn default() -> Self {
         Self::empty()
     }
-}
\ No newline at end of file
+}
+/// Format of a folder contents export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FolderExportFormat {
+    Csv,
+    Json,
+}
+
+impl FolderExportFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Json => "application/json",
+        }
+    }
+}
+
+/// One file or subfolder in the manifest of a folder export
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FolderManifestEntryDto {
+    /// Path relative to the exported folder, e.g. `Photos/2024/beach.jpg`
+    pub path: String,
+
+    pub name: String,
+
+    /// `file` or `folder`
+    pub item_type: String,
+
+    pub id: String,
+
+    /// Size in bytes; 0 for folders
+    pub size: u64,
+
+    /// MIME type of files
+    pub mime_type: Option<String>,
+
+    /// Last modification as a Unix timestamp
+    pub modified_at: u64,
+
+    /// SHA-256 of the file content in hex; none for folders
+    pub checksum: Option<String>,
+
+    /// Number of shared links pointing to the item
+    pub share_count: usize,
+}
+
+/// Manifest of the contents of a folder, recursively
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FolderManifestDto {
+    pub folder_id: String,
+    pub folder_name: String,
+
+    /// Unix timestamp of the export
+    pub generated_at: u64,
+
+    /// Folders first, then files, each level in name order
+    pub entries: Vec<FolderManifestEntryDto>,
+}
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+
+/// Images returned per page when the client does not ask for a size
+pub const DEFAULT_GALLERY_PAGE_SIZE: usize = 60;
+
+/// Images whose previews are warmed ahead of the page by default
+pub const DEFAULT_GALLERY_PREFETCH: usize = 60;
+
+/// Largest page and prefetch window a client can ask for
+pub const MAX_GALLERY_WINDOW: usize = 500;
+
+/// Query for a page of a photo folder, as the gallery scrolls
+#[derive(Debug, Clone, Deserialize)]
+pub struct GalleryPageQuery {
+    /// Folder being browsed (None means root)
+    pub folder_id: Option<String>,
+
+    /// Index of the first image in the viewport
+    #[serde(default)]
+    pub offset: usize,
+
+    /// Images in the viewport
+    pub limit: Option<usize>,
+
+    /// Images after the viewport whose previews are rendered in the background
+    pub prefetch: Option<usize>,
+
+    /// Thumbnail width in pixels
+    pub w: Option<u32>,
+
+    /// Thumbnail height in pixels
+    pub h: Option<u32>,
+
+    /// `contain`, `cover` (default) or `fill`
+    pub fit: Option<String>,
+
+    /// `fast` or `high` (defaults to the server setting)
+    pub quality: Option<String>,
+}
+
+/// Image of a gallery page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GalleryItemDto {
+    /// File ID
+    pub id: String,
+
+    /// File name
+    pub name: String,
+
+    /// MIME type
+    pub mime_type: String,
+
+    /// Size in bytes
+    pub size: u64,
+
+    /// Last modification timestamp
+    pub modified_at: u64,
+
+    /// Preview URL with the requested size; fetching it hits the warm cache
+    pub thumbnail_url: String,
+}
+
+/// Page of a photo folder plus the state of its prefetch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GalleryPageDto {
+    /// Folder browsed (None means root)
+    pub folder_id: Option<String>,
+
+    /// Index of the first returned image
+    pub offset: usize,
+
+    /// Images in the folder that can be previewed
+    pub total: usize,
+
+    /// Images of the page, in folder order
+    pub items: Vec<GalleryItemDto>,
+
+    /// Offset of the next page, if there are more images
+    pub next_offset: Option<usize>,
+
+    /// Images after the page queued for preview rendering
+    pub prefetched: usize,
+}
@@ -0,0 +1,16 @@
+use serde::Serialize;
+
+use crate::domain::services::hidden_file_service::HiddenFileRules;
+
+/// Hidden/system file rules as seen by a user
+#[derive(Debug, Clone, Serialize)]
+pub struct HiddenFileSettingsDto {
+    /// Rules configured for the instance
+    pub instance: HiddenFileRules,
+
+    /// Rules chosen by the user, if any
+    pub user: Option<HiddenFileRules>,
+
+    /// Rules actually applied: the strictest of both
+    pub effective: HiddenFileRules,
+}
@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+
+use crate::application::dtos::file_dto::FileDto;
+
+/// Label detected in an image
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageLabelDto {
+    /// Normalized label (lowercase)
+    pub label: String,
+
+    /// Confidence reported by the inference service (0.0 - 1.0)
+    pub confidence: f32,
+}
+
+/// Per-user image tagging privacy setting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageTaggingSettingsDto {
+    /// Whether the user's images are sent to the inference service
+    pub enabled: bool,
+
+    /// Labels removed when the feature was disabled
+    #[serde(default)]
+    pub labels_deleted: u64,
+}
+
+/// Request to change the image tagging setting
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateImageTaggingDto {
+    pub enabled: bool,
+}
+
+/// File matching a label search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabeledFileDto {
+    /// Matching file
+    #[serde(flatten)]
+    pub file: FileDto,
+
+    /// Matching label
+    pub label: String,
+
+    /// Confidence of the matching label
+    pub confidence: f32,
+}
+
+/// Query parameters for label search
+#[derive(Debug, Clone, Deserialize)]
+pub struct LabelSearchQuery {
+    /// Label to look for (case-insensitive, `*` matches any suffix)
+    pub label: String,
+}
+
+/// Status of a tagging backfill job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaggingJobDto {
+    /// Job ID
+    pub id: String,
+
+    /// "running", "completed" or "failed"
+    pub status: String,
+
+    /// Images sent to the inference service
+    pub processed: u64,
+
+    /// Images that received at least one label
+    pub labeled: u64,
+
+    /// Images the inference service could not process
+    pub failed: u64,
+
+    /// Error that stopped the job
+    pub error: Option<String>,
+
+    /// Start timestamp (seconds since epoch)
+    pub started_at: u64,
+
+    /// End timestamp (seconds since epoch)
+    pub finished_at: Option<u64>,
+}
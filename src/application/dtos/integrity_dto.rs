@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+/// What one integrity check found and, when repairing, fixed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IntegrityReportDto {
+    /// Unix timestamps of the check
+    pub started_at: u64,
+    pub finished_at: u64,
+
+    /// Dangling references were removed or repaired; otherwise they were only counted
+    pub repaired: bool,
+
+    /// Shares looked at
+    pub shares_checked: u64,
+
+    /// Shares of files or folders that no longer exist
+    pub orphaned_shares: u64,
+
+    /// Shares created by users that no longer exist
+    pub ownerless_shares: u64,
+
+    /// Shares limited to user groups that were deleted. The deleted groups
+    /// are dropped; a share left without anyone allowed is removed instead
+    pub shares_with_deleted_groups: u64,
+
+    /// Favorites of files or folders that no longer exist
+    pub dangling_favorites: u64,
+
+    /// Group memberships of users that no longer exist
+    pub orphaned_memberships: u64,
+
+    /// References that could not be checked or repaired; a later check retries them
+    pub errors: Vec<String>,
+}
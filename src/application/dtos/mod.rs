@@ -1,14 +1,48 @@
 pub mod address_book_dto;
+pub mod announcement_dto;
 pub mod calendar_dto;
+pub mod capabilities_dto;
 pub mod contact_dto;
+pub mod dav_capture_dto;
+pub mod dav_multiget_dto;
+pub mod dav_principal_dto;
+pub mod dav_sync_dto;
+pub mod dav_validation_dto;
+pub mod external_storage_dto;
 pub mod favorites_dto;
+pub mod feature_flag_dto;
 pub mod file_dto;
 pub mod folder_dto;
+pub mod gallery_dto;
+pub mod hidden_file_dto;
 pub mod i18n_dto;
+pub mod image_tagging_dto;
 pub mod pagination;
+pub mod photo_dto;
+pub mod quota_dto;
 pub mod recent_dto;
 pub mod search_dto;
 pub mod share_dto;
+pub mod sync_conflict_dto;
+pub mod theme_dto;
 pub mod trash_dto;
+pub mod undo_dto;
+pub mod presence_dto;
+pub mod notification_dto;
+pub mod upload_session_dto;
+pub mod zip_export_dto;
+pub mod dav_transfer_dto;
 pub mod user_dto;
-
+pub mod user_group_dto;
+pub mod storage_gc_dto;
+pub mod integrity_dto;
+pub mod scheduling_dto;
+pub mod demo_dto;
+pub mod template_dto;
+pub mod notification_preferences_dto;
+pub mod execution_plan_dto;
+pub mod activity_dto;
+pub mod audit_log_dto;
+pub mod webhook_dto;
+pub mod tag_dto;
+pub mod comment_dto;
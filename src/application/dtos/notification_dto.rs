@@ -0,0 +1,140 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::entities::comment::CommentTargetKind;
+use crate::domain::entities::share_notification::{ShareChange, ShareNotification, SharedItemKind};
+
+/// User-initiated job whose failure is reported in the notification center
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobKind {
+    ZipDownload,
+    ZipExport,
+    FolderExport,
+    CalendarImport,
+    CalendarExport,
+    ContactImport,
+    ContactExport,
+    #[serde(rename = "webdav_copy")]
+    WebDavCopy,
+    #[serde(rename = "webdav_move")]
+    WebDavMove,
+}
+
+/// Request that runs a failed job again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryActionDto {
+    pub method: String,
+
+    /// Path and query of the original request
+    pub href: String,
+
+    /// Whether the original request carried a file that must be sent again
+    pub requires_upload: bool,
+}
+
+/// Failure of a job, as reported by the code that ran it
+#[derive(Debug, Clone)]
+pub struct JobFailure {
+    /// ID returned to the client in the `X-Job-Id` header
+    pub job_id: String,
+    pub kind: JobKind,
+    /// Cause of the failure, meant to be shown to the user
+    pub error: String,
+    pub retry: RetryActionDto,
+}
+
+/// Sharing change reported to the users it affects
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareEventDto {
+    pub change: ShareChange,
+
+    pub item_kind: SharedItemKind,
+
+    pub item_id: String,
+
+    /// Name of the item when it was shared or unshared
+    pub item_name: String,
+
+    /// User who changed the share, if known
+    pub actor_id: Option<String>,
+
+    pub actor_name: Option<String>,
+}
+
+/// Mention of a user in a comment on a file or folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MentionEventDto {
+    pub comment_id: String,
+
+    pub item_kind: CommentTargetKind,
+
+    pub item_id: String,
+
+    /// Name of the commented item when the mention was made
+    pub item_name: String,
+
+    /// Author of the comment
+    pub actor_id: String,
+
+    pub actor_name: String,
+
+    /// Beginning of the comment
+    pub excerpt: String,
+}
+
+/// What a notification is about; serialized as a `type` field next to the
+/// fields of each variant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NotificationContentDto {
+    /// A job started by the user failed
+    JobFailure {
+        /// Job that failed; matches the `X-Job-Id` header of its response
+        job_id: String,
+
+        kind: JobKind,
+
+        error: String,
+
+        retry: RetryActionDto,
+    },
+
+    /// Something was shared with the user, or no longer is
+    Share(ShareEventDto),
+
+    /// Someone mentioned the user in a comment
+    Mention(MentionEventDto),
+}
+
+/// Entry of the notification center of a user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationDto {
+    pub id: String,
+
+    #[serde(flatten)]
+    pub content: NotificationContentDto,
+
+    pub created_at: DateTime<Utc>,
+
+    /// Set once the user has seen the notification
+    pub read: bool,
+}
+
+impl From<ShareNotification> for NotificationDto {
+    fn from(notification: ShareNotification) -> Self {
+        Self {
+            read: notification.is_read(),
+            id: notification.id,
+            content: NotificationContentDto::Share(ShareEventDto {
+                change: notification.change,
+                item_kind: notification.item_kind,
+                item_id: notification.item_id,
+                item_name: notification.item_name,
+                actor_id: notification.actor_id,
+                actor_name: notification.actor_name,
+            }),
+            created_at: notification.created_at,
+        }
+    }
+}
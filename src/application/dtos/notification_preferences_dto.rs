@@ -0,0 +1,38 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::entities::notification_preferences::NotificationPreferences;
+
+/// Emails a user receives about events that affect them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailPreferencesDto {
+    /// Another user shared a file, folder, calendar or address book with them
+    pub share_received: bool,
+
+    /// Someone downloaded a file through one of their shared links
+    pub share_downloaded: bool,
+
+    /// Their storage is almost full
+    pub quota_warning: bool,
+
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<NotificationPreferences> for EmailPreferencesDto {
+    fn from(preferences: NotificationPreferences) -> Self {
+        Self {
+            share_received: preferences.share_received,
+            share_downloaded: preferences.share_downloaded,
+            quota_warning: preferences.quota_warning,
+            updated_at: preferences.updated_at,
+        }
+    }
+}
+
+/// Changes to the email preferences; missing fields are left as they are
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UpdateEmailPreferencesDto {
+    pub share_received: Option<bool>,
+    pub share_downloaded: Option<bool>,
+    pub quota_warning: Option<bool>,
+}
@@ -105,7 +105,7 @@ impl<T> PaginatedResponseDto<T> {
             page_size,
             total_items,
             total_pages,
-            has_next: page < total_pages - 1,
+            has_next: page + 1 < total_pages,
             has_prev: page > 0,
         };
         
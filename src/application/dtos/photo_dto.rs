@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+/// Default maximum perceptual distance for two photos to be considered similar
+pub const DEFAULT_SIMILARITY_DISTANCE: u32 = 6;
+
+/// Largest accepted distance; beyond it unrelated photos start to match
+pub const MAX_SIMILARITY_DISTANCE: u32 = 20;
+
+/// Photo belonging to a group of similar photos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimilarPhotoDto {
+    /// File ID
+    pub id: String,
+
+    /// File name
+    pub name: String,
+
+    /// Path to the file (relative)
+    pub path: String,
+
+    /// Size in bytes
+    pub size: u64,
+
+    /// Last modification timestamp
+    pub modified_at: u64,
+
+    /// Dominant color as `#rrggbb`
+    pub dominant_color: String,
+
+    /// Perceptual distance to the first photo of the group (0 = identical)
+    pub distance: u32,
+}
+
+/// Set of visually similar photos, candidates for bulk cleanup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimilarPhotoGroupDto {
+    /// Largest distance between the first photo and any other member
+    pub max_distance: u32,
+
+    /// Bytes that would be freed by keeping only the largest photo
+    pub reclaimable_bytes: u64,
+
+    /// Photos of the group, largest first
+    pub photos: Vec<SimilarPhotoDto>,
+}
+
+/// Query parameters for the similar photos endpoint
+#[derive(Debug, Clone, Deserialize)]
+pub struct SimilarPhotosQuery {
+    /// Maximum perceptual distance (0 finds only near-identical copies)
+    pub threshold: Option<u32>,
+}
@@ -0,0 +1,66 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// What a user is doing with an open file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PresenceMode {
+    #[default]
+    Viewing,
+    Editing,
+}
+
+/// Where the file is open
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PresenceSource {
+    /// The web UI, which sends periodic heartbeats
+    #[default]
+    Web,
+    /// An office editor session opened through WOPI
+    Wopi,
+}
+
+/// Heartbeat telling that the current user still has a file open
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PresenceHeartbeatDto {
+    #[serde(default)]
+    pub mode: PresenceMode,
+    #[serde(default)]
+    pub source: PresenceSource,
+}
+
+/// A user who currently has a file open
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PresenceDto {
+    pub file_id: String,
+    pub user_id: String,
+    pub username: String,
+    pub mode: PresenceMode,
+    pub source: PresenceSource,
+
+    /// When the user opened the file
+    pub since: DateTime<Utc>,
+
+    /// Last heartbeat; the presence expires if no other arrives in time
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Change in who has a file open
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PresenceEventKind {
+    /// A user opened the file
+    Joined,
+    /// A user switched between viewing and editing
+    Updated,
+    /// A user closed the file or stopped sending heartbeats
+    Left,
+}
+
+/// Event pushed to the clients watching a file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresenceEventDto {
+    pub event: PresenceEventKind,
+    pub presence: PresenceDto,
+}
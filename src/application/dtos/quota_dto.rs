@@ -0,0 +1,133 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::entities::quota_policy::{EffectiveQuota, QuotaPolicy, QuotaSource};
+use crate::domain::entities::user::User;
+
+/// A user's storage quota as shown in the admin API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageQuotaDto {
+    pub user_id: String,
+
+    /// Allowed bytes; negative means unlimited
+    pub quota_bytes: i64,
+
+    /// Bytes currently counted against the quota
+    pub used_bytes: i64,
+
+    /// Bytes left before uploads are rejected, `None` when unlimited
+    pub available_bytes: Option<i64>,
+
+    /// Whether the quota was set for the user, comes from a group policy or
+    /// is the one assigned when the account was created
+    pub source: QuotaSource,
+
+    /// Group whose policy applies, when `source` is `group`
+    pub policy_group: Option<String>,
+}
+
+impl StorageQuotaDto {
+    pub fn new(user: &User, quota: &EffectiveQuota) -> Self {
+        let quota_bytes = quota.quota_bytes;
+        Self {
+            user_id: user.id().to_string(),
+            quota_bytes,
+            used_bytes: user.storage_used_bytes(),
+            available_bytes: (quota_bytes >= 0).then(|| (quota_bytes - user.storage_used_bytes()).max(0)),
+            source: quota.source,
+            policy_group: quota.group.clone(),
+        }
+    }
+}
+
+/// Body of `PUT /api/admin/quotas/{user_id}`
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetStorageQuotaDto {
+    /// New quota in bytes; negative removes the limit
+    pub quota_bytes: i64,
+}
+
+/// Default quota of a group as shown in the admin API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuotaPolicyDto {
+    pub group: String,
+
+    /// Allowed bytes; negative means unlimited
+    pub quota_bytes: i64,
+
+    /// When a user is in several groups, the policy with the highest priority wins
+    pub priority: i32,
+
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<QuotaPolicy> for QuotaPolicyDto {
+    fn from(policy: QuotaPolicy) -> Self {
+        Self {
+            group: policy.group,
+            quota_bytes: policy.quota_bytes,
+            priority: policy.priority,
+            updated_at: policy.updated_at,
+        }
+    }
+}
+
+/// Body of `PUT /api/admin/quotas/policies/{group}`
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetQuotaPolicyDto {
+    /// Default quota in bytes for the group; negative removes the limit
+    pub quota_bytes: i64,
+
+    #[serde(default)]
+    pub priority: i32,
+}
+
+/// Quota allocated to and space used by the members of a group
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupQuotaReportDto {
+    pub group: String,
+
+    /// Policy of the group, if it has one
+    pub policy: Option<QuotaPolicyDto>,
+
+    pub users: usize,
+
+    /// Sum of the quotas of the members that have a limit
+    pub allocated_bytes: i64,
+
+    pub used_bytes: i64,
+
+    pub unlimited_users: usize,
+
+    /// Members using more than their quota, e.g. after it was lowered
+    pub over_quota_users: usize,
+}
+
+/// Effective quota and usage of one user
+#[derive(Debug, Clone, Serialize)]
+pub struct UserQuotaReportDto {
+    pub user_id: String,
+    pub username: String,
+    pub group: String,
+    pub quota_bytes: i64,
+    pub source: QuotaSource,
+    pub policy_group: Option<String>,
+    pub used_bytes: i64,
+
+    /// Percentage of the quota in use, `None` when unlimited
+    pub usage_percent: Option<f64>,
+}
+
+/// Response of `GET /api/admin/quotas/report`: quota allocation against
+/// actual usage, per group and per user
+#[derive(Debug, Clone, Serialize)]
+pub struct QuotaReportDto {
+    pub allocated_bytes: i64,
+    pub used_bytes: i64,
+    pub unlimited_users: usize,
+    pub over_quota_users: usize,
+    pub groups: Vec<GroupQuotaReportDto>,
+
+    /// Users sorted by the share of their quota in use, fullest first
+    pub users: Vec<UserQuotaReportDto>,
+}
@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::entities::scheduling_message::SchedulingMessage;
+
+/// An iTIP message waiting in the scheduling inbox of a user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchedulingMessageDto {
+    pub id: String,
+    /// REQUEST, REPLY or CANCEL
+    pub method: String,
+    /// UID of the event the message is about
+    pub ical_uid: String,
+    /// Email of the organizer or attendee that sent it
+    pub sender: String,
+    /// VCALENDAR with the METHOD property
+    pub ical_data: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<SchedulingMessage> for SchedulingMessageDto {
+    fn from(message: SchedulingMessage) -> Self {
+        Self {
+            id: message.id,
+            method: message.method.as_str().to_string(),
+            ical_uid: message.ical_uid,
+            sender: message.sender,
+            ical_data: message.ical_data,
+            created_at: message.created_at,
+        }
+    }
+}
+
+/// Delivery result of an iTIP message for one recipient (RFC 6638, 3.2.10)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScheduleRecipientDto {
+    /// Calendar user address of the recipient (`mailto:...`)
+    pub recipient: String,
+    /// iTIP REQUEST-STATUS, e.g. `1.2;Delivered` or `3.7;Invalid calendar user`
+    pub request_status: String,
+}
+
+/// Request to change the participation status of the current user in an event
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateParticipationDto {
+    /// ACCEPTED, DECLINED, TENTATIVE, DELEGATED or NEEDS-ACTION
+    pub partstat: String,
+}
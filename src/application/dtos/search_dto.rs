@@ -13,6 +13,10 @@ pub struct SearchCriteriaDto {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name_contains: Option<String>,
     
+    /// Optional text to search in the content of indexed documents
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    
     /// Optional list of file extensions to include (e.g., "pdf", "jpg")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub file_types: Option<Vec<String>>,
@@ -45,6 +49,10 @@ pub struct SearchCriteriaDto {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub folder_id: Option<String>,
     
+    /// Optional tag IDs; only items carrying all of them are returned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    
     /// Whether to search recursively within subfolders (default: true)
     #[serde(default = "default_recursive")]
     pub recursive: bool,
@@ -72,6 +80,7 @@ impl Default for SearchCriteriaDto {
     fn default() -> Self {
         Self {
             name_contains: None,
+            content: None,
             file_types: None,
             created_after: None,
             created_before: None,
@@ -80,6 +89,7 @@ impl Default for SearchCriteriaDto {
             min_size: None,
             max_size: None,
             folder_id: None,
+            tags: None,
             recursive: default_recursive(),
             limit: default_limit(),
             offset: 0,
@@ -112,6 +122,23 @@ pub struct SearchResultsDto {
     
     /// Whether there are more results available
     pub has_more: bool,
+    
+    /// Content matches for the returned files, when searching by content
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub content_matches: Vec<ContentMatchDto>,
+}
+
+/// A file whose content matches a content search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentMatchDto {
+    pub file_id: String,
+    
+    /// Relevance of the match; higher is better
+    pub score: f32,
+    
+    /// Excerpt of the content around the matches, as HTML with the matched
+    /// words in `<b>`
+    pub snippet: String,
 }
 
 impl SearchResultsDto {
@@ -124,6 +151,7 @@ impl SearchResultsDto {
             limit: 0,
             offset: 0,
             has_more: false,
+            content_matches: Vec::new(),
         }
     }
     
@@ -147,6 +175,67 @@ impl SearchResultsDto {
             limit,
             offset,
             has_more,
+            content_matches: Vec::new(),
+        }
+    }
+}
+/// Kind of result a unified search can return
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchResultType {
+    /// Files and folders, matched by name
+    Files,
+    /// Contacts of the address books the user owns or that are shared with them
+    Contacts,
+    /// Events of the calendars the user owns or that are shared with them
+    CalendarEvents,
+}
+
+impl SearchResultType {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "files" => Some(Self::Files),
+            "contacts" => Some(Self::Contacts),
+            "calendar_events" => Some(Self::CalendarEvents),
+            _ => None,
         }
     }
-}
\ No newline at end of file
+}
+
+/// Query for a search across files, contacts and calendar events
+#[derive(Debug, Clone)]
+pub struct UnifiedSearchQueryDto {
+    /// Text to look for
+    pub query: String,
+    
+    /// Kinds of results wanted; all of them if empty
+    pub types: Vec<SearchResultType>,
+    
+    /// Maximum number of results in each group
+    pub limit: usize,
+}
+
+impl UnifiedSearchQueryDto {
+    /// Whether results of this kind were asked for
+    pub fn includes(&self, result_type: SearchResultType) -> bool {
+        self.types.is_empty() || self.types.contains(&result_type)
+    }
+}
+
+/// Results of one kind in a unified search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "items", rename_all = "snake_case")]
+pub enum SearchResultGroupDto {
+    Files(Vec<crate::application::dtos::file_dto::FileDto>),
+    Folders(Vec<crate::application::dtos::folder_dto::FolderDto>),
+    Contacts(Vec<crate::application::dtos::contact_dto::ContactDto>),
+    CalendarEvents(Vec<crate::application::dtos::calendar_dto::CalendarEventDto>),
+}
+
+/// Results of a search across files, contacts and calendar events, grouped by
+/// kind. Only the kinds that were searched have a group, even if it is empty
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnifiedSearchResultsDto {
+    pub query: String,
+    pub groups: Vec<SearchResultGroupDto>,
+}
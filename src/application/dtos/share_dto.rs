@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::domain::entities::share::{Share, SharePermissions};
+use crate::domain::entities::share_access_log::ShareAccessEntry;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ShareDto {
@@ -15,6 +16,96 @@ pub struct ShareDto {
     pub created_at: u64,
     pub created_by: String,
     pub access_count: u64,
+    /// Downloads of the shared content through the public link
+    #[serde(default)]
+    pub download_count: u64,
+    /// What link previews reveal: `hidden`, `details` or `thumbnail`
+    pub link_preview: String,
+    /// Visitors must verify their email with a one-time code
+    pub email_verification: bool,
+    /// Addresses allowed to verify; empty means any. Never sent to visitors.
+    pub allowed_emails: Vec<String>,
+    /// User groups whose members may verify. Never sent to visitors.
+    #[serde(default)]
+    pub allowed_groups: Vec<String>,
+}
+
+/// Metadata of a public share visible without authentication, used for link
+/// previews. Password-protected shares only reveal that a password is needed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublicShareMetadataDto {
+    /// Public URL of the shared link
+    pub url: String,
+    pub item_type: String,
+    pub name: Option<String>,
+    pub size: Option<u64>,
+    pub mime_type: Option<String>,
+    pub preview_available: bool,
+    pub requires_password: bool,
+    /// Visitors must verify their email before opening the share
+    pub requires_email: bool,
+    /// File drop link: visitors can upload but not see the folder content
+    pub upload_only: bool,
+    /// Thumbnail for link previews, only when the share allows it
+    pub thumbnail: Option<ShareThumbnailDto>,
+}
+
+/// A file or folder inside a shared folder, as visitors see it. Paths and
+/// owners are left out so the link does not reveal where the folder lives.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedItemDto {
+    pub id: String,
+    pub name: String,
+    /// `file` or `folder`
+    pub item_type: String,
+    pub size: Option<u64>,
+    pub mime_type: Option<String>,
+    pub modified_at: u64,
+}
+
+/// Content of a folder of a shared folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedFolderListingDto {
+    pub folder_id: String,
+    pub name: String,
+    /// Parent folder, when it is still inside the share
+    pub parent_id: Option<String>,
+    pub folders: Vec<SharedItemDto>,
+    pub files: Vec<SharedItemDto>,
+}
+
+/// Image thumbnail of a shared file used by link previews
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareThumbnailDto {
+    pub url: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Image format of a share link QR code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QrCodeFormat {
+    Png,
+    Svg,
+}
+
+impl QrCodeFormat {
+    /// Parses the `format` query value
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "png" => Some(Self::Png),
+            "svg" => Some(Self::Svg),
+            _ => None,
+        }
+    }
+}
+
+/// Rendered QR code of a shared link, with the share it points to
+#[derive(Debug, Clone)]
+pub struct ShareQrCodeDto {
+    pub content: Vec<u8>,
+    pub mime_type: &'static str,
+    pub share: ShareDto,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +113,13 @@ pub struct SharePermissionsDto {
     pub read: bool,
     pub write: bool,
     pub reshare: bool,
+    /// Delete, or move out of the share, the item or anything inside it
+    #[serde(default)]
+    pub delete: bool,
+    /// Visitors may upload into the shared folder; without `read` the link
+    /// is a file drop
+    #[serde(default)]
+    pub upload: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,6 +129,19 @@ pub struct CreateShareDto {
     pub password: Option<String>,
     pub expires_at: Option<u64>,
     pub permissions: Option<SharePermissionsDto>,
+    /// Link preview setting (`hidden`, `details` or `thumbnail`)
+    #[serde(default)]
+    pub link_preview: Option<String>,
+    /// Require visitors to verify their email with a one-time code
+    #[serde(default)]
+    pub email_verification: Option<bool>,
+    /// Addresses allowed to verify; empty or missing means any
+    #[serde(default)]
+    pub allowed_emails: Option<Vec<String>>,
+    /// IDs of user groups whose members may open the share after verifying
+    /// their account address; turns email verification on
+    #[serde(default)]
+    pub allowed_groups: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,6 +149,59 @@ pub struct UpdateShareDto {
     pub password: Option<String>,
     pub expires_at: Option<u64>,
     pub permissions: Option<SharePermissionsDto>,
+    #[serde(default)]
+    pub link_preview: Option<String>,
+    #[serde(default)]
+    pub email_verification: Option<bool>,
+    #[serde(default)]
+    pub allowed_emails: Option<Vec<String>>,
+    #[serde(default)]
+    pub allowed_groups: Option<Vec<String>>,
+}
+
+/// Request for a one-time code sent to a visitor's email
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestShareCodeDto {
+    pub email: String,
+}
+
+/// One-time code entered by a visitor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyShareCodeDto {
+    pub email: String,
+    pub code: String,
+}
+
+/// Session granted to a visitor who verified their email; it only opens the
+/// share it was issued for. Send it back in the `X-Share-Session` header.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareEmailSessionDto {
+    pub session_token: String,
+    pub email: String,
+    /// Unix timestamp (seconds) when the session stops working
+    pub expires_at: u64,
+}
+
+/// Entry of a shared link's access log, visible to its owner
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareAccessLogEntryDto {
+    /// `accessed`, `code_sent`, `email_rejected`, `code_rejected`, `verified`,
+    /// `downloaded`, `password_rejected` or `uploaded`
+    pub event: String,
+    pub email: Option<String>,
+    pub client_ip: Option<String>,
+    pub timestamp: u64,
+}
+
+impl From<ShareAccessEntry> for ShareAccessLogEntryDto {
+    fn from(entry: ShareAccessEntry) -> Self {
+        Self {
+            event: entry.event.as_str().to_string(),
+            email: entry.email,
+            client_ip: entry.client_ip,
+            timestamp: entry.timestamp,
+        }
+    }
 }
 
 /// Extension methods to convert between DTOs and domain entities
@@ -57,6 +221,11 @@ impl ShareDto {
             created_at: share.created_at,
             created_by: share.created_by.clone(),
             access_count: share.access_count,
+            download_count: share.download_count,
+            link_preview: share.link_preview.to_string(),
+            email_verification: share.email_verification,
+            allowed_emails: share.allowed_emails.clone(),
+            allowed_groups: share.allowed_groups.clone(),
         }
     }
 }
@@ -64,13 +233,17 @@ impl ShareDto {
 impl SharePermissionsDto {
     pub fn from_entity(permissions: &SharePermissions) -> Self {
         Self {
-            read: permissions.read,
-            write: permissions.write,
-            reshare: permissions.reshare,
+            read: permissions.read(),
+            write: permissions.write(),
+            reshare: permissions.reshare(),
+            delete: permissions.delete(),
+            upload: permissions.upload(),
         }
     }
     
     pub fn to_entity(&self) -> SharePermissions {
         SharePermissions::new(self.read, self.write, self.reshare)
+            .with_delete(self.delete)
+            .with_upload(self.upload)
     }
 }
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+/// What one garbage collection pass removed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StorageGcReportDto {
+    /// Unix timestamps of the pass
+    pub started_at: u64,
+    pub finished_at: Option<u64>,
+
+    /// Files and folders in the trash directory without a trash entry
+    pub orphaned_trash_blobs: u64,
+
+    /// Temporary upload files without an upload session
+    pub stale_upload_fragments: u64,
+
+    /// Leftovers of interrupted atomic writes (`.tmpXXXXXX`)
+    pub stale_temp_files: u64,
+
+    /// Stored image placeholders (gallery thumbnails) of deleted files
+    pub orphaned_placeholders: u64,
+
+    /// Stored image fingerprints of deleted files
+    pub orphaned_fingerprints: u64,
+
+    /// Bytes freed on disk
+    pub reclaimed_bytes: u64,
+
+    /// Items that could not be removed; they are retried on the next pass
+    pub errors: Vec<String>,
+}
+
+/// Garbage collection figures for the admin stats
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StorageGcStatsDto {
+    /// A pass is in progress
+    pub running: bool,
+
+    /// Completed passes since the server started
+    pub runs: u64,
+
+    /// Bytes freed by all passes since the server started
+    pub total_reclaimed_bytes: u64,
+
+    /// Files and entries younger than this are never removed
+    pub grace_period_secs: u64,
+
+    pub last_run: Option<StorageGcReportDto>,
+}
@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::entities::sync_conflict::{ConflictKind, ConflictResolution, SyncConflict};
+
+/// A sync conflict as shown in the conflict dashboard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncConflictDto {
+    /// Conflict identifier
+    pub id: String,
+
+    /// What kind of conflict was detected
+    pub kind: ConflictKind,
+
+    /// Affected resource (the original file for conflicted copies)
+    pub path: String,
+
+    /// Conflicted copy created next to the original
+    pub conflict_path: Option<String>,
+
+    /// Human readable detail, e.g. who holds the lock
+    pub detail: Option<String>,
+
+    /// How many times the same conflict was seen while open
+    pub occurrences: i32,
+
+    /// When the conflict was first detected
+    pub created_at: DateTime<Utc>,
+
+    /// When the conflict was last detected
+    pub last_seen_at: DateTime<Utc>,
+
+    /// When the user resolved the conflict
+    pub resolved_at: Option<DateTime<Utc>>,
+
+    /// How the user resolved the conflict
+    pub resolution: Option<ConflictResolution>,
+
+    /// Actions the user can take on this conflict
+    pub available_resolutions: Vec<ConflictResolution>,
+}
+
+impl From<SyncConflict> for SyncConflictDto {
+    fn from(conflict: SyncConflict) -> Self {
+        let available_resolutions = if conflict.is_resolved() {
+            Vec::new()
+        } else {
+            conflict.kind.resolutions().to_vec()
+        };
+
+        Self {
+            id: conflict.id,
+            kind: conflict.kind,
+            path: conflict.path,
+            conflict_path: conflict.conflict_path,
+            detail: conflict.detail,
+            occurrences: conflict.occurrences,
+            created_at: conflict.created_at,
+            last_seen_at: conflict.last_seen_at,
+            resolved_at: conflict.resolved_at,
+            resolution: conflict.resolution,
+            available_resolutions,
+        }
+    }
+}
+
+/// Request to resolve a conflict
+#[derive(Debug, Clone, Deserialize)]
+pub struct ResolveConflictDto {
+    pub resolution: ConflictResolution,
+}
@@ -0,0 +1,74 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::entities::tag::{Tag, TaggedItem, TaggedItemType};
+
+/// A tag a user defined to label their files and folders
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagDto {
+    pub id: String,
+    pub name: String,
+
+    /// Color as `#rrggbb`
+    pub color: String,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Tag> for TagDto {
+    fn from(tag: Tag) -> Self {
+        Self {
+            id: tag.id,
+            name: tag.name,
+            color: tag.color,
+            created_at: tag.created_at,
+            updated_at: tag.updated_at,
+        }
+    }
+}
+
+/// Request to create a tag
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateTagDto {
+    pub name: String,
+
+    /// Color as `#rrggbb`; grey if absent
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+/// Request to rename a tag or change its color; absent fields are kept
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateTagDto {
+    #[serde(default)]
+    pub name: Option<String>,
+
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+/// A file or folder a tag is assigned to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TagAssignmentDto {
+    pub item_id: String,
+    pub item_type: TaggedItemType,
+}
+
+impl From<TaggedItem> for TagAssignmentDto {
+    fn from(item: TaggedItem) -> Self {
+        Self {
+            item_id: item.item_id,
+            item_type: item.item_type,
+        }
+    }
+}
+
+impl From<TagAssignmentDto> for TaggedItem {
+    fn from(assignment: TagAssignmentDto) -> Self {
+        Self {
+            item_id: assignment.item_id,
+            item_type: assignment.item_type,
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+/// Who manages a template
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateScope {
+    /// Managed by an administrator and offered to every user
+    Global,
+    /// Kept by the user in the templates folder of their home folder
+    User,
+}
+
+/// Template offered in the "New document" menu
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateDto {
+    /// Identifier to pass to `new-from-template`
+    pub id: String,
+    /// File name of the template, including its extension
+    pub name: String,
+    pub scope: TemplateScope,
+    pub mime_type: String,
+    pub size: u64,
+}
+
+/// Request to create a document from a template
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewFromTemplateDto {
+    pub template_id: String,
+    /// Folder to create the document in; the user's home folder if omitted
+    #[serde(default)]
+    pub folder_id: Option<String>,
+    /// Name of the new document; the template's name if omitted. The
+    /// template's extension is added when missing.
+    #[serde(default)]
+    pub name: Option<String>,
+}
@@ -0,0 +1,73 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::entities::theme::InstanceTheme;
+
+/// Public path of the theme endpoints
+pub const THEME_PATH: &str = "/api/theme";
+
+/// Instance branding as served to the web UI and the login page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeDto {
+    /// Name shown in the sidebar, the login page and the page title
+    pub instance_name: String,
+
+    /// Main brand color, `#rrggbb`
+    pub primary_color: String,
+
+    /// Color for hovered buttons and links, `#rrggbb`
+    pub accent_color: String,
+
+    /// Message shown below the login form
+    pub login_message: Option<String>,
+
+    /// URL of the custom logo; `None` keeps the built-in one
+    pub logo_url: Option<String>,
+
+    /// URL of the stylesheet that applies the colors
+    pub stylesheet_url: String,
+
+    /// Last time an administrator changed the theme
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&InstanceTheme> for ThemeDto {
+    fn from(theme: &InstanceTheme) -> Self {
+        // The version query makes browsers fetch the new files after a change
+        let version = theme.version();
+        Self {
+            instance_name: theme.instance_name.clone(),
+            primary_color: theme.primary_color.clone(),
+            accent_color: theme.accent_color.clone(),
+            login_message: theme.login_message.clone(),
+            logo_url: theme.logo.as_ref().map(|_| format!("{}/logo?v={}", THEME_PATH, version)),
+            stylesheet_url: format!("{}/theme.css?v={}", THEME_PATH, version),
+            updated_at: theme.updated_at,
+        }
+    }
+}
+
+/// Changes the instance branding; omitted fields keep their value
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UpdateThemeDto {
+    #[serde(default)]
+    pub instance_name: Option<String>,
+
+    #[serde(default)]
+    pub primary_color: Option<String>,
+
+    #[serde(default)]
+    pub accent_color: Option<String>,
+
+    /// An empty message removes it
+    #[serde(default)]
+    pub login_message: Option<String>,
+}
+
+/// The uploaded logo, ready to be served
+#[derive(Debug, Clone)]
+pub struct ThemeLogoDto {
+    pub content_type: String,
+    pub data: Vec<u8>,
+    pub version: i64,
+}
@@ -0,0 +1,24 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Token that reverses a destructive operation while it is still valid
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UndoTokenDto {
+    pub token: String,
+
+    /// After this instant the operation can no longer be undone
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Result of undoing an operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UndoResultDto {
+    /// What was reversed: `restore`, `move` or `rename`
+    pub action: String,
+
+    /// The item that was brought back, moved back or renamed back
+    pub item_id: String,
+
+    /// `file` or `folder`
+    pub item_type: String,
+}
@@ -0,0 +1,62 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Request to start a chunked upload
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateUploadSessionDto {
+    /// Name of the file being uploaded
+    pub name: String,
+
+    /// Destination folder (home folder when omitted)
+    #[serde(default)]
+    pub folder_id: Option<String>,
+
+    /// MIME type of the final file
+    #[serde(default = "default_content_type")]
+    pub content_type: String,
+
+    /// Total size of the file in bytes
+    pub total_size: u64,
+}
+
+fn default_content_type() -> String {
+    "application/octet-stream".to_string()
+}
+
+/// State of a chunked upload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadSessionDto {
+    /// Session identifier
+    pub id: String,
+
+    /// Name of the file being uploaded
+    pub name: String,
+
+    /// Destination folder
+    pub folder_id: Option<String>,
+
+    /// MIME type of the final file
+    pub content_type: String,
+
+    /// Total size of the file in bytes
+    pub total_size: u64,
+
+    /// Bytes received so far; the next chunk must start here
+    pub offset: u64,
+
+    /// When the session expires if no more chunks arrive
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Outcome of the startup revalidation of persisted sessions
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UploadRecoveryReport {
+    /// Sessions that can be resumed
+    pub resumed: usize,
+
+    /// Sessions whose offset was corrected to match the temporary file
+    pub adjusted: usize,
+
+    /// Sessions dropped because their temporary file is gone
+    pub dropped: usize,
+}
@@ -1,8 +1,9 @@
 use serde::{Serialize, Deserialize};
 use chrono::{DateTime, Utc};
 use crate::domain::entities::user::User;
+use crate::domain::entities::app_password::AppPassword;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserDto {
     pub id: String,
     pub username: String,
@@ -14,6 +15,8 @@ pub struct UserDto {
     pub updated_at: DateTime<Utc>,
     pub last_login_at: Option<DateTime<Utc>>,
     pub active: bool,
+    /// Dónde se autentica la cuenta: `local` o `directory`
+    pub auth_source: String,
 }
 
 impl From<User> for UserDto {
@@ -29,6 +32,7 @@ impl From<User> for UserDto {
             updated_at: user.updated_at(),
             last_login_at: user.last_login_at(),
             active: user.is_active(),
+            auth_source: user.auth_source().as_str().to_string(),
         }
     }
 }
@@ -65,4 +69,103 @@ pub struct ChangePasswordDto {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RefreshTokenDto {
     pub refresh_token: String,
-}
\ No newline at end of file
+}
+
+/// Petición de cambio de rol de un usuario (`admin`, `user` o `guest`)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChangeRoleDto {
+    pub role: String,
+}
+
+/// Petición de vínculo de una cuenta local con la del directorio externo
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DirectoryLinkDto {
+    /// `true` para que la cuenta pase a autenticarse en el directorio,
+    /// `false` para devolverla a su contraseña local
+    pub linked: bool,
+}
+/// Resultado de aplicar el esqueleto de carpetas a la carpeta personal de un usuario
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SkeletonApplyResultDto {
+    pub home_folder_id: String,
+    /// Esqueletos aplicados: los de los grupos del usuario que tienen uno,
+    /// o `default` si ninguno lo tiene
+    pub skeletons: Vec<String>,
+    pub folders_created: usize,
+    pub files_created: usize,
+    pub skipped: usize,
+}
+
+/// Petición de una contraseña de aplicación nueva
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CreateAppPasswordDto {
+    pub label: String,
+}
+
+/// Contraseña de aplicación, sin la contraseña
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AppPasswordDto {
+    pub id: String,
+    pub label: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl From<AppPassword> for AppPasswordDto {
+    fn from(app_password: AppPassword) -> Self {
+        Self {
+            id: app_password.id,
+            label: app_password.label,
+            created_at: app_password.created_at,
+            last_used_at: app_password.last_used_at,
+        }
+    }
+}
+
+/// Contraseña de aplicación recién creada; es la única vez que se devuelve
+/// la contraseña en claro
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AppPasswordCreatedDto {
+    #[serde(flatten)]
+    pub app_password: AppPasswordDto,
+    pub password: String,
+}
+
+/// Datos del cliente que abre o refresca una sesión, tomados de la petición
+#[derive(Debug, Clone, Default)]
+pub struct SessionClient {
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+/// Sesión activa de un usuario, tal como la ve en su lista de dispositivos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionDto {
+    pub id: String,
+    /// Navegador y sistema deducidos del User-Agent, p. ej. "Firefox en Linux"
+    pub device: String,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_active_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// Si es la sesión desde la que se hace la petición
+    pub current: bool,
+}
+
+/// Resultado de revocar varias sesiones a la vez
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevokedSessionsDto {
+    pub revoked: u64,
+}
+
+/// Usuario borrado cuyos datos se conservan hasta `purge_after`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantinedUserDto {
+    pub user: UserDto,
+    pub quarantined_at: DateTime<Utc>,
+    /// A partir de este momento los datos se eliminan definitivamente
+    pub purge_after: DateTime<Utc>,
+    /// Administrador que borró al usuario
+    pub quarantined_by: String,
+}
@@ -0,0 +1,59 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::entities::user_group::UserGroup;
+
+/// A group of users that can be granted shares as a whole
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserGroupDto {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+
+    pub member_count: usize,
+
+    /// IDs of the members; only listed to admins and to members of the group
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub members: Option<Vec<String>>,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl UserGroupDto {
+    pub fn new(group: UserGroup, members: &[String], show_members: bool) -> Self {
+        Self {
+            id: group.id,
+            name: group.name,
+            description: group.description,
+            member_count: members.len(),
+            members: show_members.then(|| members.to_vec()),
+            created_at: group.created_at,
+            updated_at: group.updated_at,
+        }
+    }
+}
+
+/// Request to create a group
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateUserGroupDto {
+    pub name: String,
+
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Initial members, by user ID
+    #[serde(default)]
+    pub members: Vec<String>,
+}
+
+/// Request to rename a group or change its description; absent fields are kept
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateUserGroupDto {
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// An empty description removes it
+    #[serde(default)]
+    pub description: Option<String>,
+}
@@ -0,0 +1,107 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::entities::webhook::{DeliveryStatus, Webhook, WebhookDelivery, WebhookEvent};
+
+/// Registered webhook as admins see it; the secret is only returned once,
+/// when the webhook is created
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDto {
+    pub id: String,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub description: Option<String>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Webhook> for WebhookDto {
+    fn from(webhook: Webhook) -> Self {
+        Self {
+            id: webhook.id,
+            url: webhook.url,
+            events: webhook.events,
+            description: webhook.description,
+            active: webhook.active,
+            created_at: webhook.created_at,
+            updated_at: webhook.updated_at,
+        }
+    }
+}
+
+/// Newly created webhook together with the secret its deliveries are signed
+/// with. This is the only time the secret is returned
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WebhookCreatedDto {
+    #[serde(flatten)]
+    pub webhook: WebhookDto,
+    pub secret: String,
+}
+
+/// Body of a request to register a webhook
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateWebhookDto {
+    /// `http` or `https` URL the events are POSTed to
+    pub url: String,
+
+    /// Events to deliver; at least one
+    pub events: Vec<WebhookEvent>,
+
+    pub description: Option<String>,
+
+    /// Secret to sign deliveries with; a random one is generated if missing
+    pub secret: Option<String>,
+}
+
+/// Body of a request to change a webhook; missing fields keep their value
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UpdateWebhookDto {
+    pub url: Option<String>,
+    pub events: Option<Vec<WebhookEvent>>,
+    pub description: Option<String>,
+    pub active: Option<bool>,
+}
+
+/// One delivery of an event to a webhook, with the outcome of its last attempt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDeliveryDto {
+    pub id: String,
+    pub webhook_id: String,
+    pub event: WebhookEvent,
+    pub status: DeliveryStatus,
+    pub attempts: u32,
+
+    /// When the next attempt is due, while the delivery is pending
+    pub next_attempt_at: Option<DateTime<Utc>>,
+
+    /// HTTP status of the last response
+    pub response_status: Option<u16>,
+
+    /// Why the last attempt failed
+    pub error: Option<String>,
+
+    /// JSON body that was sent
+    pub payload: serde_json::Value,
+
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+impl From<WebhookDelivery> for WebhookDeliveryDto {
+    fn from(delivery: WebhookDelivery) -> Self {
+        Self {
+            id: delivery.id,
+            webhook_id: delivery.webhook_id,
+            event: delivery.event,
+            status: delivery.status,
+            attempts: delivery.attempts,
+            next_attempt_at: delivery.next_attempt_at,
+            response_status: delivery.response_status,
+            error: delivery.error,
+            payload: serde_json::from_str(&delivery.payload).unwrap_or(serde_json::Value::String(delivery.payload)),
+            created_at: delivery.created_at,
+            delivered_at: delivery.delivered_at,
+        }
+    }
+}
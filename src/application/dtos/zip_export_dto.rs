@@ -0,0 +1,90 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Request to export a selection of files and folders as a ZIP in the background
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateZipExportDto {
+    #[serde(default)]
+    pub file_ids: Vec<String>,
+
+    /// Folders are exported with all their contents
+    #[serde(default)]
+    pub folder_ids: Vec<String>,
+
+    /// Name of the archive, without the `.zip` extension
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// Query of a signed download link
+#[derive(Debug, Clone, Deserialize)]
+pub struct ZipExportDownloadQuery {
+    pub token: String,
+}
+
+/// Stage of an export job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ZipExportStatus {
+    /// Waiting to be planned or picked up by the worker
+    Pending,
+    /// Adding entries to the archive
+    Running,
+    /// The archive can be downloaded
+    Completed,
+    /// Stopped by an error; it can be resumed from its last checkpoint
+    Failed,
+}
+
+impl ZipExportStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Running => "running",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(Self::Pending),
+            "running" => Some(Self::Running),
+            "completed" => Some(Self::Completed),
+            "failed" => Some(Self::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// State of an export job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZipExportJobDto {
+    pub id: String,
+
+    /// Name the archive is downloaded with
+    pub file_name: String,
+
+    pub status: ZipExportStatus,
+
+    /// Entries of the archive, known once the selection has been walked
+    pub total_entries: Option<u64>,
+
+    /// Entries already in the archive at the last checkpoint
+    pub completed_entries: u64,
+
+    /// Size of the archive at the last checkpoint, or its final size
+    pub archive_size: u64,
+
+    /// Why the job stopped, when it failed
+    pub error: Option<String>,
+
+    /// Signed link to the archive, without authentication, once completed
+    pub download_url: Option<String>,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+
+    /// When the archive is removed and its link stops working
+    pub expires_at: DateTime<Utc>,
+}
@@ -0,0 +1,39 @@
+use async_trait::async_trait;
+
+use crate::application::dtos::activity_dto::{ActivityDto, ActivityQueryDto, ActivityTargetDto};
+use crate::application::dtos::pagination::PaginatedResponseDto;
+use crate::common::errors::DomainError;
+use crate::domain::entities::activity::{ActivityAction, ActivityTargetKind};
+
+/// Primary port for the activity feeds: who uploaded, renamed, deleted,
+/// shared or restored which file or folder, and when
+#[async_trait]
+pub trait ActivityUseCase: Send + Sync + 'static {
+    /// Current name and folder of a file or folder, to describe it in an
+    /// entry
+    async fn describe_target(&self, kind: ActivityTargetKind, id: &str) -> Option<ActivityTargetDto>;
+
+    /// Records an action. Errors are logged: a lost entry must not fail the
+    /// action itself
+    async fn record(
+        &self,
+        actor_id: &str,
+        actor_name: &str,
+        action: ActivityAction,
+        target: ActivityTargetDto,
+        details: Option<String>,
+    );
+
+    /// What a user did, newest first
+    async fn list_user_activity(&self, user_id: &str, query: ActivityQueryDto) -> Result<PaginatedResponseDto<ActivityDto>, DomainError>;
+
+    /// What anyone did in a folder or to the folder itself, newest first.
+    /// Only the owner of the folder and admins can read it
+    async fn list_folder_activity(
+        &self,
+        username: &str,
+        is_admin: bool,
+        folder_id: &str,
+        query: ActivityQueryDto,
+    ) -> Result<PaginatedResponseDto<ActivityDto>, DomainError>;
+}
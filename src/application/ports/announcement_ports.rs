@@ -0,0 +1,35 @@
+use async_trait::async_trait;
+
+use crate::application::dtos::announcement_dto::{AcknowledgmentDto, AnnouncementDto, SaveAnnouncementDto, UserAnnouncementDto};
+use crate::common::errors::DomainError;
+
+/// Primary port for admin announcements and their read tracking.
+///
+/// `groups` are the groups the user belongs to; the user's role until real
+/// groups exist.
+#[async_trait]
+pub trait AnnouncementUseCase: Send + Sync + 'static {
+    /// Active announcements for the user, most severe first, for the notification center
+    async fn list_for_user(&self, user_id: &str, groups: &[String]) -> Result<Vec<UserAnnouncementDto>, DomainError>;
+
+    /// The most severe active announcement the user has not read yet
+    async fn banner(&self, user_id: &str, groups: &[String]) -> Result<Option<UserAnnouncementDto>, DomainError>;
+
+    /// Marks an active announcement as read by the user
+    async fn mark_read(&self, user_id: &str, groups: &[String], announcement_id: &str) -> Result<(), DomainError>;
+
+    /// Lists every announcement with its acknowledgment rate
+    async fn list_announcements(&self) -> Result<Vec<AnnouncementDto>, DomainError>;
+
+    /// Creates an announcement
+    async fn create_announcement(&self, created_by: &str, request: SaveAnnouncementDto) -> Result<AnnouncementDto, DomainError>;
+
+    /// Replaces the content, window and targets of an announcement; reads are kept
+    async fn update_announcement(&self, announcement_id: &str, request: SaveAnnouncementDto) -> Result<AnnouncementDto, DomainError>;
+
+    /// Deletes an announcement and its reads
+    async fn delete_announcement(&self, announcement_id: &str) -> Result<(), DomainError>;
+
+    /// Read counts of an announcement
+    async fn acknowledgment(&self, announcement_id: &str) -> Result<AcknowledgmentDto, DomainError>;
+}
@@ -0,0 +1,21 @@
+use async_trait::async_trait;
+
+use crate::application::dtos::audit_log_dto::{AuditEventDto, AuditLogQueryDto};
+use crate::application::dtos::pagination::PaginatedResponseDto;
+use crate::common::errors::DomainError;
+use crate::domain::entities::audit_event::AuditEvent;
+
+/// Secondary port services write security-relevant events into
+#[async_trait]
+pub trait AuditLogPort: Send + Sync + 'static {
+    /// Appends an event. Errors are logged: a lost entry must not fail a
+    /// login or an admin action
+    async fn record(&self, event: AuditEvent);
+}
+
+/// Primary port for admins to read the audit log
+#[async_trait]
+pub trait AuditLogUseCase: Send + Sync + 'static {
+    /// Entries matching the query, newest first
+    async fn query(&self, query: AuditLogQueryDto) -> Result<PaginatedResponseDto<AuditEventDto>, DomainError>;
+}
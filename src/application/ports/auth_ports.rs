@@ -1,8 +1,40 @@
 use async_trait::async_trait;
-use crate::domain::entities::user::User;
+use chrono::{DateTime, Utc};
+use crate::domain::entities::user::{Permission, User, UserRole};
 use crate::domain::entities::session::Session;
+use crate::domain::entities::app_password::AppPassword;
+use crate::domain::entities::user_quarantine::UserQuarantine;
+use crate::domain::entities::oidc_identity::OidcIdentity;
 use crate::common::errors::DomainError;
 
+/// Authenticated user on whose behalf a service acts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Caller {
+    pub user_id: String,
+    pub role: UserRole,
+}
+
+impl Caller {
+    pub fn new(user_id: impl Into<String>, role: UserRole) -> Self {
+        Self { user_id: user_id.into(), role }
+    }
+
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.role.has_permission(permission)
+    }
+
+    /// Fails with `AccessDenied` when the caller's role lacks the permission
+    pub fn require(&self, permission: Permission) -> Result<(), DomainError> {
+        if self.has_permission(permission) {
+            return Ok(());
+        }
+        Err(DomainError::access_denied(
+            "Permission",
+            format!("Role '{}' lacks the {:?} permission", self.role, permission),
+        ))
+    }
+}
+
 #[async_trait]
 pub trait UserStoragePort: Send + Sync + 'static {
     /// Crea un nuevo usuario 
@@ -29,6 +61,9 @@ pub trait UserStoragePort: Send + Sync + 'static {
     /// Lista usuarios por rol (por ejemplo, "admin" o "user")
     async fn list_users_by_role(&self, role: &str) -> Result<Vec<User>, DomainError>;
     
+    /// Busca usuarios activos por nombre de usuario o correo electrónico
+    async fn search_users(&self, query: &str, limit: i64) -> Result<Vec<User>, DomainError>;
+    
     /// Elimina un usuario por su ID
     async fn delete_user(&self, user_id: &str) -> Result<(), DomainError>;
     
@@ -44,9 +79,73 @@ pub trait SessionStoragePort: Send + Sync + 'static {
     /// Obtiene una sesión por token de actualización
     async fn get_session_by_refresh_token(&self, refresh_token: &str) -> Result<Session, DomainError>;
     
+    /// Obtiene una sesión por ID
+    async fn get_session_by_id(&self, session_id: &str) -> Result<Session, DomainError>;
+    
+    /// Obtiene todas las sesiones de un usuario, las más recientes primero
+    async fn get_sessions_by_user_id(&self, user_id: &str) -> Result<Vec<Session>, DomainError>;
+    
+    /// Sustituye el token de actualización de una sesión vigente sin cambiar su ID
+    async fn rotate_refresh_token(
+        &self,
+        session_id: &str,
+        refresh_token: &str,
+        expires_at: DateTime<Utc>,
+        ip_address: Option<&str>,
+        user_agent: Option<&str>,
+    ) -> Result<(), DomainError>;
+    
+    /// Anota actividad en una sesión
+    async fn touch_session(&self, session_id: &str) -> Result<(), DomainError>;
+    
     /// Revoca una sesión específica
     async fn revoke_session(&self, session_id: &str) -> Result<(), DomainError>;
     
     /// Revoca todas las sesiones de un usuario
     async fn revoke_all_user_sessions(&self, user_id: &str) -> Result<u64, DomainError>;
-}
\ No newline at end of file
+    
+    /// Revoca todas las sesiones de un usuario salvo `keep_session_id`
+    async fn revoke_other_user_sessions(&self, user_id: &str, keep_session_id: &str) -> Result<u64, DomainError>;
+}
+
+#[async_trait]
+pub trait AppPasswordStoragePort: Send + Sync + 'static {
+    /// Guarda una contraseña de aplicación nueva
+    async fn create_app_password(&self, app_password: AppPassword) -> Result<AppPassword, DomainError>;
+    
+    /// Lista las contraseñas de aplicación de un usuario, las más recientes primero
+    async fn list_app_passwords(&self, user_id: &str) -> Result<Vec<AppPassword>, DomainError>;
+    
+    /// Elimina una contraseña de aplicación de un usuario; devuelve si existía
+    async fn delete_app_password(&self, user_id: &str, id: &str) -> Result<bool, DomainError>;
+    
+    /// Anota que una contraseña de aplicación se acaba de usar
+    async fn touch_app_password(&self, id: &str) -> Result<(), DomainError>;
+}
+
+#[async_trait]
+pub trait UserQuarantineStoragePort: Send + Sync + 'static {
+    /// Pone a un usuario en cuarentena, o actualiza la que ya tenía
+    async fn save_quarantine(&self, quarantine: UserQuarantine) -> Result<UserQuarantine, DomainError>;
+    
+    /// Obtiene la cuarentena de un usuario, si la tiene
+    async fn get_quarantine(&self, user_id: &str) -> Result<Option<UserQuarantine>, DomainError>;
+    
+    /// Lista los usuarios en cuarentena, los que se purgan antes primero
+    async fn list_quarantines(&self) -> Result<Vec<UserQuarantine>, DomainError>;
+    
+    /// Saca a un usuario de la cuarentena; devuelve si estaba en ella
+    async fn delete_quarantine(&self, user_id: &str) -> Result<bool, DomainError>;
+}
+
+#[async_trait]
+pub trait OidcIdentityStoragePort: Send + Sync + 'static {
+    /// Busca la cuenta vinculada a un `sub` de un emisor
+    async fn find_identity(&self, issuer: &str, subject: &str) -> Result<Option<OidcIdentity>, DomainError>;
+    
+    /// Vincula una cuenta del proveedor a un usuario
+    async fn link_identity(&self, identity: OidcIdentity) -> Result<OidcIdentity, DomainError>;
+    
+    /// Anota que el usuario acaba de entrar con esta cuenta
+    async fn touch_identity(&self, issuer: &str, subject: &str) -> Result<(), DomainError>;
+}
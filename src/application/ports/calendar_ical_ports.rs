@@ -0,0 +1,54 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::application::dtos::calendar_dto::{CalendarImportResultDto, CalendarJsonExportDto, DuplicateUidPolicy};
+use crate::common::errors::DomainError;
+
+/// A calendar exported as a single iCalendar file
+#[derive(Debug, Clone)]
+pub struct CalendarExport {
+    /// Name for the downloaded file, without extension
+    pub name: String,
+    pub ical_data: String,
+}
+
+/// How events appear in a JSON export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventExpansion {
+    /// Every stored event once, recurring ones with their rule
+    Raw,
+    /// One entry per occurrence overlapping `[start, end)`
+    Expanded { start: DateTime<Utc>, end: DateTime<Utc> },
+}
+
+/// Primary port for moving whole calendars in and out as .ics files
+#[async_trait]
+pub trait CalendarICalUseCase: Send + Sync + 'static {
+    /// Stores every VEVENT of an iCalendar file in a calendar. Events sharing a
+    /// UID become one recurring event with its overrides; events whose UID is
+    /// already in the calendar follow `on_duplicate`. Nothing is created if
+    /// the file is not a VCALENDAR.
+    async fn import_calendar(
+        &self,
+        user_id: &str,
+        is_admin: bool,
+        calendar_id: &str,
+        ical_data: &str,
+        on_duplicate: DuplicateUidPolicy,
+    ) -> Result<CalendarImportResultDto, DomainError>;
+
+    /// Returns every event of a calendar as one VCALENDAR
+    async fn export_calendar(&self, user_id: &str, is_admin: bool, calendar_id: &str) -> Result<CalendarExport, DomainError>;
+
+    /// Returns a calendar and its events as structured data, for scripts
+    /// that should not have to parse iCalendar. `include_ical` keeps the
+    /// iCalendar source of each event in raw exports
+    async fn export_calendar_json(
+        &self,
+        user_id: &str,
+        is_admin: bool,
+        calendar_id: &str,
+        expansion: EventExpansion,
+        include_ical: bool,
+    ) -> Result<CalendarJsonExportDto, DomainError>;
+}
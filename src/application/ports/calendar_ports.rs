@@ -5,6 +5,7 @@ use crate::application::dtos::calendar_dto::{
     CreateEventDto, UpdateEventDto, CreateEventICalDto
 };
 use crate::common::errors::DomainError;
+use crate::domain::services::calendar_filter_service::CompFilter;
 
 /// Port for external calendar storage mechanisms
 #[async_trait]
@@ -23,6 +24,9 @@ pub trait CalendarStoragePort: Send + Sync + 'static {
     async fn share_calendar(&self, calendar_id: &str, user_id: &str, access_level: &str) -> Result<(), DomainError>;
     async fn remove_calendar_sharing(&self, calendar_id: &str, user_id: &str) -> Result<(), DomainError>;
     async fn get_calendar_shares(&self, calendar_id: &str) -> Result<Vec<(String, String)>, DomainError>;
+    async fn share_calendar_with_group(&self, calendar_id: &str, group_id: &str, access_level: &str) -> Result<(), DomainError>;
+    async fn remove_calendar_group_sharing(&self, calendar_id: &str, group_id: &str) -> Result<(), DomainError>;
+    async fn get_calendar_group_shares(&self, calendar_id: &str) -> Result<Vec<(String, String)>, DomainError>;
     
     // Calendar properties
     async fn set_calendar_property(&self, calendar_id: &str, property_name: &str, property_value: &str) -> Result<(), DomainError>;
@@ -61,6 +65,9 @@ pub trait CalendarUseCase: Send + Sync + 'static {
     async fn share_calendar(&self, calendar_id: &str, user_id: &str, access_level: &str) -> Result<(), DomainError>;
     async fn remove_calendar_sharing(&self, calendar_id: &str, user_id: &str) -> Result<(), DomainError>;
     async fn get_calendar_shares(&self, calendar_id: &str) -> Result<Vec<(String, String)>, DomainError>;
+    async fn share_calendar_with_group(&self, calendar_id: &str, group_id: &str, access_level: &str) -> Result<(), DomainError>;
+    async fn remove_calendar_group_sharing(&self, calendar_id: &str, group_id: &str) -> Result<(), DomainError>;
+    async fn get_calendar_group_shares(&self, calendar_id: &str) -> Result<Vec<(String, String)>, DomainError>;
     
     // Event operations
     async fn create_event(&self, event: CreateEventDto) -> Result<CalendarEventDto, DomainError>;
@@ -75,4 +82,6 @@ pub trait CalendarUseCase: Send + Sync + 'static {
         start: DateTime<Utc>, 
         end: DateTime<Utc>
     ) -> Result<Vec<CalendarEventDto>, DomainError>;
+    /// Events whose iCalendar data passes a CalDAV calendar-query filter
+    async fn query_events(&self, calendar_id: &str, filter: &CompFilter) -> Result<Vec<CalendarEventDto>, DomainError>;
 }
\ No newline at end of file
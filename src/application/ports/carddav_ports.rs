@@ -2,12 +2,16 @@ use async_trait::async_trait;
 use crate::common::errors::DomainError;
 use crate::application::dtos::address_book_dto::{
     AddressBookDto, CreateAddressBookDto, UpdateAddressBookDto,
-    ShareAddressBookDto, UnshareAddressBookDto
+    ShareAddressBookDto, UnshareAddressBookDto,
+    ShareAddressBookWithGroupDto, UnshareAddressBookFromGroupDto
 };
 use crate::application::dtos::contact_dto::{
     ContactDto, CreateContactDto, UpdateContactDto, CreateContactVCardDto,
-    ContactGroupDto, CreateContactGroupDto, UpdateContactGroupDto, GroupMembershipDto
+    ContactGroupDto, CreateContactGroupDto, UpdateContactGroupDto, GroupMembershipDto,
+    ContactPhotosRequestDto, ContactPhotoDto, VCardObjectDto
 };
+use crate::application::ports::contact_photo_ports::ContactPhoto;
+use crate::domain::services::vcard_filter_service::AddressbookFilter;
 
 pub type CardDavRepositoryError = DomainError;
 
@@ -25,6 +29,9 @@ pub trait AddressBookUseCase: Send + Sync + 'static {
     async fn share_address_book(&self, dto: ShareAddressBookDto, user_id: &str) -> Result<(), DomainError>;
     async fn unshare_address_book(&self, dto: UnshareAddressBookDto, user_id: &str) -> Result<(), DomainError>;
     async fn get_address_book_shares(&self, address_book_id: &str, user_id: &str) -> Result<Vec<(String, bool)>, DomainError>;
+    async fn share_address_book_with_group(&self, dto: ShareAddressBookWithGroupDto, user_id: &str) -> Result<(), DomainError>;
+    async fn unshare_address_book_from_group(&self, dto: UnshareAddressBookFromGroupDto, user_id: &str) -> Result<(), DomainError>;
+    async fn get_address_book_group_shares(&self, address_book_id: &str, user_id: &str) -> Result<Vec<(String, bool)>, DomainError>;
 }
 
 #[async_trait]
@@ -52,6 +59,68 @@ pub trait ContactUseCase: Send + Sync + 'static {
     async fn list_groups_for_contact(&self, contact_id: &str, user_id: &str) -> Result<Vec<ContactGroupDto>, DomainError>;
     
     // vCard operations
-    async fn get_contact_vcard(&self, contact_id: &str, user_id: &str) -> Result<String, DomainError>;
-    async fn get_contacts_as_vcards(&self, address_book_id: &str, user_id: &str) -> Result<Vec<(String, String)>, DomainError>;
-}
\ No newline at end of file
+    // `max_photo_bytes` strips embedded photos larger than that for clients that cannot handle them
+    async fn get_contact_vcard(&self, contact_id: &str, user_id: &str, max_photo_bytes: Option<usize>) -> Result<String, DomainError>;
+    async fn get_contacts_as_vcards(&self, address_book_id: &str, user_id: &str, max_photo_bytes: Option<usize>) -> Result<Vec<(String, String)>, DomainError>;
+
+    // Photo operations
+    async fn get_contact_photos(&self, request: ContactPhotosRequestDto) -> Result<Vec<ContactPhotoDto>, DomainError>;
+    /// Binary photo of a contact, from the photo storage or its vCard
+    async fn get_contact_photo(&self, contact_id: &str, user_id: &str) -> Result<ContactPhoto, DomainError>;
+    /// Replaces the photo of a contact and embeds it in the vCard
+    async fn set_contact_photo(&self, contact_id: &str, user_id: &str, content: Vec<u8>) -> Result<ContactDto, DomainError>;
+}
+
+/// Primary port for the CardDAV protocol: address book collections and their
+/// vCard resources, addressed by resource name (`{uid}.vcf`)
+#[async_trait]
+pub trait CardDavUseCase: Send + Sync + 'static {
+    /// Address books the user owns or that are shared with them
+    async fn list_address_books(&self, user_id: &str) -> Result<Vec<AddressBookDto>, DomainError>;
+
+    async fn get_address_book(&self, user_id: &str, is_admin: bool, address_book_id: &str) -> Result<AddressBookDto, DomainError>;
+
+    async fn list_vcards(&self, user_id: &str, is_admin: bool, address_book_id: &str) -> Result<Vec<VCardObjectDto>, DomainError>;
+
+    /// vCards matching an addressbook-query filter
+    async fn query_vcards(
+        &self,
+        user_id: &str,
+        is_admin: bool,
+        address_book_id: &str,
+        filter: &AddressbookFilter,
+    ) -> Result<Vec<VCardObjectDto>, DomainError>;
+
+    /// vCards by resource name, `None` for the ones that do not exist
+    async fn get_vcards(
+        &self,
+        user_id: &str,
+        is_admin: bool,
+        address_book_id: &str,
+        uids: &[String],
+    ) -> Result<Vec<(String, Option<VCardObjectDto>)>, DomainError>;
+
+    async fn get_vcard(&self, user_id: &str, is_admin: bool, address_book_id: &str, uid: &str) -> Result<VCardObjectDto, DomainError>;
+
+    /// Creates or replaces a vCard. `if_match` and `if_none_match` are the raw
+    /// values of those headers. Returns the stored vCard and whether it is new.
+    async fn put_vcard(
+        &self,
+        user_id: &str,
+        is_admin: bool,
+        address_book_id: &str,
+        uid: &str,
+        vcard: &str,
+        if_match: Option<&str>,
+        if_none_match: Option<&str>,
+    ) -> Result<(VCardObjectDto, bool), DomainError>;
+
+    async fn delete_vcard(
+        &self,
+        user_id: &str,
+        is_admin: bool,
+        address_book_id: &str,
+        uid: &str,
+        if_match: Option<&str>,
+    ) -> Result<(), DomainError>;
+}
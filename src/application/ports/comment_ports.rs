@@ -0,0 +1,43 @@
+use async_trait::async_trait;
+
+use crate::application::dtos::comment_dto::{CommentDto, CommentQueryDto, CreateCommentDto, UpdateCommentDto};
+use crate::application::dtos::pagination::PaginatedResponseDto;
+use crate::application::ports::share_ports::ShareRecipient;
+use crate::common::errors::DomainError;
+use crate::domain::entities::comment::CommentTargetKind;
+
+/// Primary port for comments on files and folders.
+///
+/// A `@username` in a comment notifies the mentioned user if they can read
+/// the item. Reading comments needs read access to the item and writing them
+/// needs write access; administrators reach every item. Only the author can
+/// edit a comment; the author or an administrator can delete it.
+#[async_trait]
+pub trait CommentUseCase: Send + Sync + 'static {
+    /// Comments on a file or folder, oldest first
+    async fn list_comments(
+        &self,
+        caller: ShareRecipient<'_>,
+        is_admin: bool,
+        item_kind: CommentTargetKind,
+        item_id: &str,
+        query: CommentQueryDto,
+    ) -> Result<PaginatedResponseDto<CommentDto>, DomainError>;
+
+    /// Writes a comment and notifies the users it mentions
+    async fn create_comment(
+        &self,
+        author: ShareRecipient<'_>,
+        is_admin: bool,
+        item_kind: CommentTargetKind,
+        item_id: &str,
+        request: CreateCommentDto,
+    ) -> Result<CommentDto, DomainError>;
+
+    /// Changes the text of a comment of the user. Only users mentioned for
+    /// the first time are notified
+    async fn update_comment(&self, user_id: &str, comment_id: &str, request: UpdateCommentDto) -> Result<CommentDto, DomainError>;
+
+    /// Deletes a comment of the user, or any comment if `is_admin`
+    async fn delete_comment(&self, user_id: &str, is_admin: bool, comment_id: &str) -> Result<(), DomainError>;
+}
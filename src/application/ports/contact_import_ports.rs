@@ -0,0 +1,42 @@
+use async_trait::async_trait;
+
+use crate::application::dtos::contact_dto::{AddressBookJsonExportDto, ContactImportFormat, ContactImportResultDto};
+use crate::common::errors::DomainError;
+
+/// An address book exported as vCards
+#[derive(Debug, Clone)]
+pub struct AddressBookExport {
+    /// Name for the downloaded file, without extension
+    pub name: String,
+    /// Every contact as one vCard, each ending with CRLF
+    pub vcards: Vec<String>,
+}
+
+/// Primary port for bulk contact imports from .vcf and .csv files and
+/// whole address book exports
+#[async_trait]
+pub trait ContactImportUseCase: Send + Sync + 'static {
+    /// Stores every contact of a file in an address book, in batches.
+    ///
+    /// `columns` maps CSV columns by position to contact fields (`first_name`,
+    /// `email`...; empty to ignore a column); without it the fields are
+    /// guessed from the header row. Records that cannot be read or stored, or
+    /// whose UID is already in the address book, are reported one by one
+    /// without stopping the import.
+    async fn import_contacts(
+        &self,
+        user_id: &str,
+        is_admin: bool,
+        address_book_id: &str,
+        data: &str,
+        format: ContactImportFormat,
+        columns: Option<&[String]>,
+    ) -> Result<ContactImportResultDto, DomainError>;
+
+    /// Returns every contact of an address book, ready to be concatenated into one .vcf
+    async fn export_address_book(&self, user_id: &str, is_admin: bool, address_book_id: &str) -> Result<AddressBookExport, DomainError>;
+
+    /// Returns an address book and its contacts as structured data, for
+    /// scripts that should not have to parse vCard
+    async fn export_address_book_json(&self, user_id: &str, is_admin: bool, address_book_id: &str) -> Result<AddressBookJsonExportDto, DomainError>;
+}
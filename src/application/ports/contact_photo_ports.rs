@@ -0,0 +1,24 @@
+use async_trait::async_trait;
+
+use crate::common::errors::DomainError;
+
+/// Binary contact photo with its media type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContactPhoto {
+    pub content: Vec<u8>,
+    pub media_type: String,
+}
+
+/// Secondary port that keeps contact photos in the storage backend, so they
+/// can be served without decoding the vCard
+#[async_trait]
+pub trait ContactPhotoStoragePort: Send + Sync + 'static {
+    /// Stores (or replaces) the photo of a contact
+    async fn store_photo(&self, contact_id: &str, photo: &ContactPhoto) -> Result<(), DomainError>;
+
+    /// Returns the stored photo of a contact, if any
+    async fn get_photo(&self, contact_id: &str) -> Result<Option<ContactPhoto>, DomainError>;
+
+    /// Forgets the photo of a contact; missing photos are not an error
+    async fn delete_photo(&self, contact_id: &str) -> Result<(), DomainError>;
+}
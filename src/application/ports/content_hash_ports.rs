@@ -0,0 +1,39 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::application::dtos::file_dto::FileDto;
+use crate::common::errors::DomainError;
+
+/// SHA-256 of a file's content, with the size and modification time of the
+/// version it was computed for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentHash {
+    /// Lowercase hexadecimal SHA-256
+    pub sha256: String,
+    pub size: u64,
+    pub modified_at: u64,
+}
+
+impl ContentHash {
+    /// Whether the hash still describes the current content of the file.
+    ///
+    /// Writes that bypass the hash store change the size or modification
+    /// time, so a stale hash is never served.
+    pub fn matches(&self, file: &FileDto) -> bool {
+        self.size == file.size && self.modified_at == file.modified_at
+    }
+}
+
+/// Secondary port that keeps the content hash of stored files so downloads
+/// can expose it without reading the file twice
+#[async_trait]
+pub trait ContentHashPort: Send + Sync + 'static {
+    /// Returns the stored hash of a file, stale or not
+    async fn get_hash(&self, file_id: &str) -> Result<Option<ContentHash>, DomainError>;
+
+    /// Stores the hash of a file, replacing the previous one
+    async fn store_hash(&self, file_id: &str, hash: ContentHash) -> Result<(), DomainError>;
+
+    /// Forgets the hash of a deleted file
+    async fn remove_hash(&self, file_id: &str) -> Result<(), DomainError>;
+}
@@ -0,0 +1,24 @@
+use std::path::PathBuf;
+use async_trait::async_trait;
+
+use crate::common::errors::DomainError;
+
+/// Credentials used to authenticate against an external system
+#[derive(Debug, Clone, Default)]
+pub struct StoredCredential {
+    /// Login name on the remote system
+    pub username: Option<String>,
+
+    /// Password or shared secret
+    pub password: Option<String>,
+
+    /// Path to a private key file (key based authentication)
+    pub private_key_path: Option<PathBuf>,
+}
+
+/// Secondary port for looking up secrets by key
+#[async_trait]
+pub trait CredentialVaultPort: Send + Sync + 'static {
+    /// Retrieves the credential stored under the given key
+    async fn get_credential(&self, key: &str) -> Result<StoredCredential, DomainError>;
+}
@@ -0,0 +1,63 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::application::dtos::dav_capture_dto::DavCaptureSessionDto;
+use crate::common::errors::DomainError;
+
+/// Body captured from a request or response, cut at the configured limit
+#[derive(Debug, Clone, Default)]
+pub struct CapturedBody {
+    /// First bytes of the body
+    pub content: Vec<u8>,
+    /// Full length of the body, when it was seen entirely
+    pub total_len: Option<u64>,
+}
+
+/// One DAV request and the response sent for it, before redaction
+#[derive(Debug, Clone)]
+pub struct CapturedExchange {
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    pub method: String,
+    pub uri: String,
+    pub request_headers: Vec<(String, String)>,
+    pub request_body: CapturedBody,
+    pub status: u16,
+    pub response_headers: Vec<(String, String)>,
+    /// `None` when the response was streamed and not captured
+    pub response_body: Option<CapturedBody>,
+}
+
+/// Time-limited, per-user capture of raw DAV traffic used to diagnose
+/// client-specific sync failures
+#[async_trait]
+pub trait DavCaptureUseCase: Send + Sync + 'static {
+    /// Starts (or restarts) capturing the requests of a user, discarding any previous capture
+    async fn start_capture(
+        &self,
+        username: &str,
+        minutes: Option<u32>,
+        started_by: &str,
+    ) -> Result<DavCaptureSessionDto, DomainError>;
+
+    /// Stops a capture, keeping what was recorded for download
+    async fn stop_capture(&self, username: &str) -> Result<DavCaptureSessionDto, DomainError>;
+
+    /// Lists the known captures, active or finished
+    async fn list_captures(&self) -> Result<Vec<DavCaptureSessionDto>, DomainError>;
+
+    /// Deletes a capture and its recorded data
+    async fn discard_capture(&self, username: &str) -> Result<(), DomainError>;
+
+    /// Builds the diagnostics bundle (ZIP) of a capture
+    async fn export_bundle(&self, username: &str) -> Result<Vec<u8>, DomainError>;
+
+    /// Whether requests of this user must be recorded right now
+    fn is_capturing(&self, username: &str) -> bool;
+
+    /// Maximum number of body bytes kept per request or response
+    fn max_body_bytes(&self) -> usize;
+
+    /// Records an exchange; credentials are redacted before anything is written
+    async fn record_exchange(&self, username: &str, exchange: CapturedExchange) -> Result<(), DomainError>;
+}
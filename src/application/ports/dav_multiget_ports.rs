@@ -0,0 +1,14 @@
+use async_trait::async_trait;
+
+use crate::application::dtos::dav_multiget_dto::MultigetResponseDto;
+use crate::application::ports::share_ports::ShareRecipient;
+use crate::common::errors::DomainError;
+
+/// Primary port for fetching several DAV resources in one request
+#[async_trait]
+pub trait DavMultigetUseCase: Send + Sync + 'static {
+    /// Resolves files, events and contacts by href and returns their bodies and
+    /// ETags; per-href failures, such as files the caller may not read, are
+    /// reported in the entry, not as an error
+    async fn multiget(&self, caller: ShareRecipient<'_>, is_admin: bool, hrefs: &[String]) -> Result<MultigetResponseDto, DomainError>;
+}
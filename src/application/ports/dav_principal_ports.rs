@@ -0,0 +1,17 @@
+use async_trait::async_trait;
+
+use crate::application::dtos::dav_principal_dto::{PrincipalSearchDto, PrincipalSearchResultDto};
+use crate::common::errors::DomainError;
+
+/// Primary port for looking up user and group principals over DAV (RFC 3744)
+#[async_trait]
+pub trait DavPrincipalUseCase: Send + Sync + 'static {
+    /// Runs a principal-property-search on behalf of `user_id`, applying the
+    /// privacy rules for users with role `role`.
+    async fn search_principals(
+        &self,
+        user_id: &str,
+        role: &str,
+        search: &PrincipalSearchDto,
+    ) -> Result<PrincipalSearchResultDto, DomainError>;
+}
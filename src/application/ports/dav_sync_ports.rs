@@ -0,0 +1,30 @@
+use async_trait::async_trait;
+
+use crate::application::dtos::dav_sync_dto::{AddressBookSyncDto, CalendarSyncDto, SyncOutcome};
+use crate::common::errors::DomainError;
+
+/// Primary port for incremental synchronization of DAV collections
+#[async_trait]
+pub trait DavSyncUseCase: Send + Sync + 'static {
+    /// Returns the changes of a calendar since `sync_token`, or every event when
+    /// the token is empty. At most `limit` objects are returned per request.
+    async fn sync_calendar(
+        &self,
+        user_id: &str,
+        is_admin: bool,
+        calendar_id: &str,
+        sync_token: &str,
+        limit: Option<usize>,
+    ) -> Result<SyncOutcome<CalendarSyncDto>, DomainError>;
+
+    /// Returns the changes of an address book since `sync_token`, or every
+    /// vCard when the token is empty. At most `limit` objects are returned.
+    async fn sync_address_book(
+        &self,
+        user_id: &str,
+        is_admin: bool,
+        address_book_id: &str,
+        sync_token: &str,
+        limit: Option<usize>,
+    ) -> Result<SyncOutcome<AddressBookSyncDto>, DomainError>;
+}
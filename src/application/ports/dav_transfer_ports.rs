@@ -0,0 +1,30 @@
+use async_trait::async_trait;
+
+use crate::application::dtos::dav_transfer_dto::DavTransferDto;
+use crate::common::errors::DomainError;
+use crate::domain::services::ownership_service::TransferOperation;
+
+/// Primary port for WebDAV COPY and MOVE requests on large folders, which
+/// are answered right away and finish in the background
+#[async_trait]
+pub trait DavTransferUseCase: Send + Sync + 'static {
+    /// Bytes above which a transfer runs in the background
+    fn async_threshold(&self) -> u64;
+
+    /// Registers a transfer that is about to start in the background
+    async fn start(
+        &self,
+        user_id: &str,
+        operation: TransferOperation,
+        source: &str,
+        destination: &str,
+        size: u64,
+    ) -> DavTransferDto;
+
+    /// Records how a transfer ended, with the status the request would have
+    /// had in the foreground. Failures reach the notification center
+    async fn finish(&self, transfer_id: &str, status: u16, error: Option<String>);
+
+    /// A transfer started by `user_id`
+    async fn get_transfer(&self, user_id: &str, transfer_id: &str) -> Result<DavTransferDto, DomainError>;
+}
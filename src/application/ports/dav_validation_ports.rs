@@ -0,0 +1,31 @@
+use async_trait::async_trait;
+
+use crate::application::dtos::dav_validation_dto::{RepairJobDto, ValidationReportDto};
+use crate::common::errors::DomainError;
+
+/// Who is asking for a scan; administrators may scan any collection
+#[derive(Debug, Clone)]
+pub struct ValidationRequester {
+    pub user_id: String,
+    pub is_admin: bool,
+}
+
+/// Primary port for validating and repairing calendar and contact data
+#[async_trait]
+pub trait DavValidationUseCase: Send + Sync + 'static {
+    /// Scans a calendar for broken recurrence rules, missing or duplicate UIDs
+    /// and unparseable objects
+    async fn validate_calendar(&self, requester: &ValidationRequester, calendar_id: &str) -> Result<ValidationReportDto, DomainError>;
+
+    /// Scans an address book for malformed vCards and missing or duplicate UIDs
+    async fn validate_address_book(&self, requester: &ValidationRequester, address_book_id: &str) -> Result<ValidationReportDto, DomainError>;
+
+    /// Starts a job applying every automatic fix of a calendar
+    async fn start_calendar_repair(&self, requester: &ValidationRequester, calendar_id: &str) -> Result<RepairJobDto, DomainError>;
+
+    /// Starts a job applying every automatic fix of an address book
+    async fn start_address_book_repair(&self, requester: &ValidationRequester, address_book_id: &str) -> Result<RepairJobDto, DomainError>;
+
+    /// Returns the status of one of the requester's repair jobs
+    async fn get_repair_job(&self, requester: &ValidationRequester, job_id: &str) -> Result<RepairJobDto, DomainError>;
+}
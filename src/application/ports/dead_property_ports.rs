@@ -0,0 +1,34 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use crate::common::errors::DomainError;
+use crate::domain::entities::dead_property::DeadProperty;
+
+/// Primary port for WebDAV dead properties: custom properties set through
+/// PROPPATCH and returned by PROPFIND
+#[async_trait]
+pub trait DeadPropertyUseCase: Send + Sync + 'static {
+    /// Returns the stored properties for the given resources (file or folder
+    /// IDs), keyed by resource ID.
+    ///
+    /// Resources without properties are omitted.
+    async fn get_properties(&self, resource_ids: &[String]) -> Result<HashMap<String, Vec<DeadProperty>>, DomainError>;
+
+    /// Sets and removes properties of a resource atomically.
+    ///
+    /// Fails without storing anything if any property is invalid, e.g. a
+    /// property in the `DAV:` namespace, which is reserved for live properties.
+    async fn patch_properties(
+        &self,
+        resource_id: &str,
+        set: Vec<DeadProperty>,
+        remove: Vec<(String, String)>,
+    ) -> Result<(), DomainError>;
+
+    /// Copies every property of a resource onto another (used by COPY)
+    async fn copy_properties(&self, from_resource_id: &str, to_resource_id: &str) -> Result<(), DomainError>;
+
+    /// Forgets the properties of a deleted resource
+    async fn remove_properties(&self, resource_id: &str) -> Result<(), DomainError>;
+}
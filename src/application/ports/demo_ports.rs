@@ -0,0 +1,18 @@
+use async_trait::async_trait;
+
+use crate::application::dtos::demo_dto::{DemoInfoDto, DemoResetReportDto};
+use crate::common::errors::DomainError;
+
+/// Primary port for the demo mode.
+///
+/// A reset deletes the demo account with its files, calendars and contacts
+/// and provisions it again with the sample data. Only the demo account is
+/// ever touched, and it is never an administrator.
+#[async_trait]
+pub trait DemoUseCase: Send + Sync + 'static {
+    /// Resets the demo account now; fails with `Locked` when a reset is already running
+    async fn reset(&self) -> Result<DemoResetReportDto, DomainError>;
+
+    /// Credentials and reset schedule of the demo account
+    async fn info(&self) -> DemoInfoDto;
+}
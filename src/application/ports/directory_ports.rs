@@ -0,0 +1,25 @@
+use async_trait::async_trait;
+
+use crate::common::errors::DomainError;
+
+/// Account of an external directory (LDAP, Active Directory) whose password
+/// has just been verified
+#[derive(Debug, Clone)]
+pub struct DirectoryUser {
+    /// Distinguished name of the entry
+    pub dn: String,
+    /// Login name as stored in the directory
+    pub username: String,
+    pub email: Option<String>,
+    /// Groups the account belongs to, as distinguished names or plain names
+    pub groups: Vec<String>,
+}
+
+/// Secondary port for a directory that verifies passwords by binding as the user
+#[async_trait]
+pub trait DirectoryAuthPort: Send + Sync + 'static {
+    /// Verifies a password against the directory. Returns `None` when the
+    /// directory has no such account and an access denied error when the
+    /// password is wrong.
+    async fn authenticate(&self, username: &str, password: &str) -> Result<Option<DirectoryUser>, DomainError>;
+}
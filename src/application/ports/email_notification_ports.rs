@@ -0,0 +1,56 @@
+use async_trait::async_trait;
+
+use crate::application::dtos::notification_preferences_dto::{EmailPreferencesDto, UpdateEmailPreferencesDto};
+use crate::common::errors::DomainError;
+use crate::domain::entities::notification_preferences::EmailEventKind;
+use crate::domain::entities::share_notification::SharedItemKind;
+
+/// Event a user may be told about by email
+#[derive(Debug, Clone)]
+pub enum EmailEvent {
+    /// Another user shared something with the recipient
+    ShareReceived {
+        item_kind: SharedItemKind,
+        item_name: String,
+        actor_name: Option<String>,
+    },
+    /// A file was downloaded through a link created by the recipient
+    ShareDownloaded {
+        share_id: String,
+        item_name: String,
+        download_count: u64,
+    },
+    /// The recipient's usage crossed the warning threshold of their quota
+    QuotaWarning {
+        used_bytes: u64,
+        quota_bytes: u64,
+    },
+}
+
+impl EmailEvent {
+    pub fn kind(&self) -> EmailEventKind {
+        match self {
+            Self::ShareReceived { .. } => EmailEventKind::ShareReceived,
+            Self::ShareDownloaded { .. } => EmailEventKind::ShareDownloaded,
+            Self::QuotaWarning { .. } => EmailEventKind::QuotaWarning,
+        }
+    }
+}
+
+/// Secondary port used by other services to email users about events
+pub trait EmailNotificationPort: Send + Sync + 'static {
+    /// Queues an email for `user_id` and returns without waiting for it.
+    /// Whether it is sent depends on the user's preferences; delivery
+    /// failures are logged, never returned to the caller
+    fn notify(&self, user_id: &str, event: EmailEvent);
+}
+
+/// Primary port for the email preferences of the current user
+#[async_trait]
+pub trait NotificationPreferencesUseCase: Send + Sync + 'static {
+    /// Preferences of a user; every email is on until they change them
+    async fn get_preferences(&self, user_id: &str) -> Result<EmailPreferencesDto, DomainError>;
+
+    /// Turns emails on or off
+    async fn update_preferences(&self, user_id: &str, update: UpdateEmailPreferencesDto) -> Result<EmailPreferencesDto, DomainError>;
+}
@@ -0,0 +1,131 @@
+use async_trait::async_trait;
+
+use crate::common::errors::DomainError;
+
+/// Something that happened in a service that other parts of the server may
+/// react to, such as the search cache, the real-time channel, the audit log or
+/// webhooks
+#[derive(Debug, Clone, PartialEq)]
+pub enum DomainEvent {
+    /// A file was uploaded or created through WebDAV
+    FileUploaded {
+        file_id: String,
+        name: String,
+        folder_id: Option<String>,
+        /// Storage path, which names the owner in its home folder
+        path: String,
+        size: u64,
+    },
+    /// The content of a file was replaced or written, or the file was moved
+    FileUpdated {
+        file_id: String,
+        name: String,
+        folder_id: Option<String>,
+        path: String,
+        size: u64,
+    },
+    /// A file was deleted, whether moved to the trash or removed for good
+    FileDeleted {
+        file_id: String,
+        name: String,
+        folder_id: Option<String>,
+        /// Storage path the file had, which names the owner in its home folder
+        path: String,
+    },
+    /// A folder was renamed
+    FolderRenamed {
+        folder_id: String,
+        name: String,
+        previous_name: String,
+        parent_id: Option<String>,
+        /// Storage path after the rename, which names the owner in its home folder
+        path: String,
+    },
+    /// A folder was deleted, whether moved to the trash or removed for good
+    FolderDeleted {
+        folder_id: String,
+        name: String,
+        parent_id: Option<String>,
+        /// Storage path the folder had, which names the owner in its home folder
+        path: String,
+    },
+    /// A file or folder was restored from the trash
+    ItemRestored {
+        item_id: String,
+        /// `file` or `folder`
+        item_type: String,
+        name: String,
+        /// Storage path it was restored to
+        path: String,
+    },
+    /// A shared link to a file or folder was created
+    ShareCreated {
+        share_id: String,
+        item_id: String,
+        /// `file` or `folder`
+        item_type: String,
+        /// User who created the link
+        created_by: String,
+    },
+    /// A user account was created, by registration, by an admin or on the
+    /// first login through a directory
+    UserCreated {
+        user_id: String,
+        username: String,
+        email: String,
+        role: String,
+    },
+    /// A contact was deleted from an address book
+    ContactDeleted {
+        contact_id: String,
+        address_book_id: String,
+        uid: String,
+        /// Owner of the address book
+        owner_id: String,
+        /// User who deleted it, who may not be the owner
+        deleted_by: String,
+    },
+    /// An event of a calendar was created or changed
+    CalendarEventUpdated {
+        event_id: String,
+        calendar_id: String,
+        ical_uid: String,
+        /// Owner of the calendar
+        owner_id: String,
+    },
+}
+
+impl DomainEvent {
+    /// Name of the event in logs
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::FileUploaded { .. } => "file_uploaded",
+            Self::FileUpdated { .. } => "file_updated",
+            Self::FileDeleted { .. } => "file_deleted",
+            Self::FolderRenamed { .. } => "folder_renamed",
+            Self::FolderDeleted { .. } => "folder_deleted",
+            Self::ItemRestored { .. } => "item_restored",
+            Self::ShareCreated { .. } => "share_created",
+            Self::UserCreated { .. } => "user_created",
+            Self::ContactDeleted { .. } => "contact_deleted",
+            Self::CalendarEventUpdated { .. } => "calendar_event_updated",
+        }
+    }
+}
+
+/// Secondary port services publish domain events into
+pub trait EventBusPort: Send + Sync + 'static {
+    /// Queues an event for the subscribers and returns without waiting for them
+    fn publish(&self, event: DomainEvent);
+}
+
+/// Reacts to the domain events published on the bus
+#[async_trait]
+pub trait DomainEventSubscriber: Send + Sync + 'static {
+    /// Name of the subscriber in logs
+    fn name(&self) -> &'static str;
+
+    /// Handles one event. Errors are logged and never reach the publisher
+    /// or the other subscribers
+    async fn handle(&self, event: &DomainEvent) -> Result<(), DomainError>;
+}
@@ -0,0 +1,139 @@
+use std::pin::Pin;
+use std::sync::Arc;
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::Stream;
+
+use crate::application::dtos::external_storage_dto::{
+    ExternalChangeDto, ExternalEntryDto, ExternalMountDto, ExternalMoveDto, ExternalMoveRequestDto,
+    ExternalTransferJobDto,
+};
+use crate::common::errors::{DomainError, Result};
+
+/// Byte stream used to move file content to and from external storage
+pub type ExternalByteStream = Pin<Box<dyn Stream<Item = std::result::Result<Bytes, std::io::Error>> + Send>>;
+
+/// File or directory found on an external storage backend
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalEntry {
+    /// Entry name (last path component)
+    pub name: String,
+
+    /// Path relative to the mount root
+    pub path: String,
+
+    /// Whether the entry is a directory
+    pub is_dir: bool,
+
+    /// Size in bytes (0 for directories)
+    pub size: u64,
+
+    /// Last modification as a Unix timestamp
+    pub modified_at: u64,
+}
+
+/// Signed-in user on whose behalf a mount is used
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalMountUser {
+    /// User id, checked against the groups allowed on the mount
+    pub id: String,
+
+    /// Username, checked against the users allowed on the mount
+    pub username: String,
+
+    /// Administrators can use every mount
+    pub is_admin: bool,
+}
+
+/// Secondary port implemented by every external storage backend.
+///
+/// Paths are always relative to the mount root, use `/` as separator and
+/// have already been normalized by the application layer.
+#[async_trait]
+pub trait ExternalStoragePort: Send + Sync + 'static {
+    /// Short backend identifier (e.g. "sftp")
+    fn backend(&self) -> &'static str;
+
+    /// Whether the backend rejects every write operation
+    fn is_read_only(&self) -> bool {
+        false
+    }
+
+    /// Whether the backend holds the data of every user, like snapshots of
+    /// the whole storage. Such mounts are only used through `for_user`.
+    fn is_per_user(&self) -> bool {
+        false
+    }
+
+    /// Backend limited to the user's own data, for per-user backends
+    fn for_user(&self, _username: &str) -> std::result::Result<Arc<dyn ExternalStoragePort>, DomainError> {
+        Err(DomainError::operation_not_supported(
+            "ExternalMount",
+            format!("The {} backend is the same for every user", self.backend()),
+        ))
+    }
+
+    /// Lists the entries of a directory
+    async fn list(&self, path: &str) -> std::result::Result<Vec<ExternalEntry>, DomainError>;
+
+    /// Returns metadata for a single entry
+    async fn stat(&self, path: &str) -> std::result::Result<ExternalEntry, DomainError>;
+
+    /// Opens a file for streaming reads
+    async fn read_stream(&self, path: &str) -> std::result::Result<ExternalByteStream, DomainError>;
+
+    /// Writes a file from a stream, returning the number of bytes written
+    async fn write_stream(&self, path: &str, content: ExternalByteStream) -> std::result::Result<u64, DomainError>;
+
+    /// Creates a directory
+    async fn create_dir(&self, path: &str) -> std::result::Result<(), DomainError>;
+
+    /// Deletes a file or an empty directory
+    async fn delete(&self, path: &str) -> std::result::Result<(), DomainError>;
+
+    /// Renames or moves an entry within the backend without copying its content
+    async fn rename(&self, _from: &str, _to: &str) -> std::result::Result<(), DomainError> {
+        Err(DomainError::operation_not_supported(
+            "ExternalMount",
+            format!("The {} backend cannot rename entries", self.backend()),
+        ))
+    }
+}
+
+/// Primary port for browsing and transferring files on external mounts.
+///
+/// Every call is made on behalf of a user; mounts the user may not use are
+/// reported as not found so their names do not leak.
+#[async_trait]
+pub trait ExternalStorageUseCase: Send + Sync + 'static {
+    /// Lists the mounts the user may use
+    async fn list_mounts(&self, user: &ExternalMountUser) -> Vec<ExternalMountDto>;
+
+    /// Lists a directory inside a mount
+    async fn list_directory(&self, user: &ExternalMountUser, mount: &str, path: &str) -> Result<Vec<ExternalEntryDto>>;
+
+    /// Opens a file inside a mount for download
+    async fn download_file(&self, user: &ExternalMountUser, mount: &str, path: &str) -> Result<(ExternalEntryDto, ExternalByteStream)>;
+
+    /// Uploads a file into a mount
+    async fn upload_file(&self, user: &ExternalMountUser, mount: &str, path: &str, content: ExternalByteStream) -> Result<ExternalEntryDto>;
+
+    /// Creates a directory inside a mount
+    async fn create_directory(&self, user: &ExternalMountUser, mount: &str, path: &str) -> Result<ExternalEntryDto>;
+
+    /// Deletes a file or empty directory inside a mount
+    async fn delete_entry(&self, user: &ExternalMountUser, mount: &str, path: &str) -> Result<()>;
+
+    /// Lists changes detected by the periodic scans of a mount
+    async fn list_changes(&self, user: &ExternalMountUser, mount: &str, since: Option<u64>) -> Result<Vec<ExternalChangeDto>>;
+
+    /// Moves an entry. Within a mount this is a metadata-only rename; between
+    /// mounts a background transfer job is started and returned instead
+    async fn move_entry(&self, user: &ExternalMountUser, request: ExternalMoveRequestDto) -> Result<ExternalMoveDto>;
+
+    /// Lists the transfer jobs started by the user (every job for administrators)
+    async fn list_transfers(&self, user: &ExternalMountUser) -> Vec<ExternalTransferJobDto>;
+
+    /// Returns a transfer job by id if the user started it
+    async fn get_transfer(&self, user: &ExternalMountUser, id: &str) -> Result<ExternalTransferJobDto>;
+}
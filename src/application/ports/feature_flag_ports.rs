@@ -0,0 +1,54 @@
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+
+use crate::application::dtos::feature_flag_dto::{FeatureFlagDto, UpdateFeatureFlagDto};
+use crate::common::errors::DomainError;
+use crate::domain::entities::feature_flag::FlagScope;
+
+/// Who a flag is evaluated for
+#[derive(Debug, Clone, Default)]
+pub struct FlagSubject {
+    pub user_id: String,
+    /// Groups the user belongs to; the user's role until real groups exist
+    pub groups: Vec<String>,
+}
+
+impl FlagSubject {
+    pub fn new(user_id: impl Into<String>, groups: Vec<String>) -> Self {
+        Self { user_id: user_id.into(), groups }
+    }
+}
+
+/// Primary port for feature flags, backed by a cache so checks are cheap
+/// enough to run on every request
+#[async_trait]
+pub trait FeatureFlagUseCase: Send + Sync + 'static {
+    /// Whether a flag is on for the subject. Unknown flags are off, and so
+    /// are all flags when the store cannot be read and nothing is cached
+    async fn is_enabled(&self, key: &str, subject: &FlagSubject) -> bool;
+
+    /// Evaluates every flag for the subject
+    async fn evaluate_all(&self, subject: &FlagSubject) -> BTreeMap<String, bool>;
+
+    /// Lists all flags with their overrides
+    async fn list_flags(&self) -> Result<Vec<FeatureFlagDto>, DomainError>;
+
+    /// Creates a flag or changes its global rule
+    async fn upsert_flag(&self, key: &str, update: UpdateFeatureFlagDto) -> Result<FeatureFlagDto, DomainError>;
+
+    /// Deletes a flag and its overrides
+    async fn delete_flag(&self, key: &str) -> Result<(), DomainError>;
+
+    /// Enables or disables a flag for a group or user
+    async fn set_override(
+        &self,
+        key: &str,
+        scope: FlagScope,
+        subject: &str,
+        enabled: bool,
+    ) -> Result<FeatureFlagDto, DomainError>;
+
+    /// Removes a group or user override
+    async fn remove_override(&self, key: &str, scope: FlagScope, subject: &str) -> Result<FeatureFlagDto, DomainError>;
+}
@@ -0,0 +1,36 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use crate::common::errors::DomainError;
+use crate::domain::services::extended_attribute_service::ExtendedAttribute;
+
+/// Atributos guardados de un fichero, indexados por nombre de propiedad
+pub type FileAttributes = HashMap<String, String>;
+
+/// Secondary port that stores the extended attributes (POSIX mode bits,
+/// Finder metadata) sent by WebDAV clients, so they survive round trips
+#[async_trait]
+pub trait FileAttributePort: Send + Sync + 'static {
+    /// Returns the stored attributes for the given files, keyed by file ID.
+    ///
+    /// Files without attributes are omitted.
+    async fn get_attributes(&self, file_ids: &[String]) -> Result<HashMap<String, FileAttributes>, DomainError>;
+
+    /// Validates and stores an attribute, returning its canonical value
+    async fn set_attribute(
+        &self,
+        file_id: &str,
+        attribute: ExtendedAttribute,
+        value: &str,
+    ) -> Result<String, DomainError>;
+
+    /// Removes a single attribute
+    async fn remove_attribute(&self, file_id: &str, attribute: ExtendedAttribute) -> Result<(), DomainError>;
+
+    /// Copies every attribute of a file onto another (used by COPY)
+    async fn copy_attributes(&self, from_file_id: &str, to_file_id: &str) -> Result<(), DomainError>;
+
+    /// Forgets the attributes of a deleted file
+    async fn remove_attributes(&self, file_id: &str) -> Result<(), DomainError>;
+}
@@ -33,6 +33,9 @@ pub trait FileRetrievalUseCase: Send + Sync + 'static {
     
     /// Obtiene contenido de archivo como stream (para archivos grandes)
     async fn get_file_stream(&self, id: &str) -> Result<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>, DomainError>;
+    
+    /// Obtiene un rango del contenido de archivo como stream (peticiones HTTP Range)
+    async fn get_file_range(&self, id: &str, start: u64, length: u64) -> Result<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>, DomainError>;
 }
 
 /// Puerto primario para operaciones de gestión de archivos
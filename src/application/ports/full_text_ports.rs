@@ -0,0 +1,37 @@
+use async_trait::async_trait;
+
+use crate::common::errors::DomainError;
+
+/// Secondary port that turns document content into plain text for indexing
+#[async_trait]
+pub trait TextExtractionPort: Send + Sync + 'static {
+    /// Whether text can be extracted from files with this name
+    fn supports(&self, file_name: &str) -> bool;
+
+    /// Plain text of a document, or `None` if its format is not supported
+    async fn extract_text(&self, file_name: &str, content: Vec<u8>) -> Result<Option<String>, DomainError>;
+}
+
+/// File whose content matches a full-text query
+#[derive(Debug, Clone, PartialEq)]
+pub struct FullTextHit {
+    pub file_id: String,
+    /// Relevance; higher is better
+    pub score: f32,
+    /// Excerpt of the content around the matches, as HTML with the matched
+    /// words in `<b>`
+    pub snippet: String,
+}
+
+/// Secondary port for the index of file contents
+#[async_trait]
+pub trait FullTextIndexPort: Send + Sync + 'static {
+    /// Adds a file to the index, replacing what was indexed for it before
+    async fn index_document(&self, file_id: &str, name: &str, text: String) -> Result<(), DomainError>;
+
+    /// Removes a file from the index; nothing happens if it was not indexed
+    async fn remove_document(&self, file_id: &str) -> Result<(), DomainError>;
+
+    /// Files whose name or content match the query, best first
+    async fn search(&self, query: &str, limit: usize) -> Result<Vec<FullTextHit>, DomainError>;
+}
@@ -0,0 +1,26 @@
+use async_trait::async_trait;
+
+use crate::application::dtos::gallery_dto::GalleryPageDto;
+use crate::application::ports::image_preview_ports::ImagePreviewRequest;
+use crate::common::errors::DomainError;
+
+/// Viewport of a photo folder
+#[derive(Debug, Clone)]
+pub struct GalleryWindow {
+    pub folder_id: Option<String>,
+    /// Index of the first image shown
+    pub offset: usize,
+    /// Images shown
+    pub limit: usize,
+    /// Images after the viewport to warm ahead
+    pub prefetch: usize,
+}
+
+/// Primary port for smooth scrolling through large photo folders
+#[async_trait]
+pub trait GalleryUseCase: Send + Sync + 'static {
+    /// Returns the images of the viewport with their thumbnail URLs and
+    /// starts rendering the previews of the images that follow, so they are
+    /// cached by the time the user scrolls to them
+    async fn gallery_page(&self, window: GalleryWindow, thumbnail: ImagePreviewRequest) -> Result<GalleryPageDto, DomainError>;
+}
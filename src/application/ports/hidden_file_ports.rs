@@ -0,0 +1,26 @@
+use async_trait::async_trait;
+
+use crate::common::errors::DomainError;
+use crate::domain::services::hidden_file_service::HiddenFileRules;
+
+/// Port that provides the hidden/system file rules of the instance and the
+/// per-user overrides, so services and DAV apply them the same way
+#[async_trait]
+pub trait HiddenFileRulesPort: Send + Sync + 'static {
+    /// Rules configured for the whole instance
+    fn instance_rules(&self) -> HiddenFileRules;
+
+    /// Rules chosen by a user, if any
+    fn user_rules(&self, user_id: &str) -> Option<HiddenFileRules>;
+
+    /// Effective rules for a user: the strictest of the instance and user rules
+    fn rules_for_user(&self, user_id: &str) -> HiddenFileRules {
+        match self.user_rules(user_id) {
+            Some(user) => self.instance_rules().strictest(user),
+            None => self.instance_rules(),
+        }
+    }
+
+    /// Stores (or clears with `None`) the rules of a user
+    async fn set_user_rules(&self, user_id: &str, rules: Option<HiddenFileRules>) -> Result<(), DomainError>;
+}
@@ -0,0 +1,53 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::application::dtos::photo_dto::SimilarPhotoGroupDto;
+use crate::common::errors::DomainError;
+
+/// Perceptual fingerprint of an image
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageFingerprint {
+    /// 64-bit difference hash; similar images have a small Hamming distance
+    pub perceptual_hash: u64,
+
+    /// Dominant RGB color
+    pub dominant_color: [u8; 3],
+}
+
+impl ImageFingerprint {
+    /// Number of differing bits between two perceptual hashes (0 = identical)
+    pub fn distance(&self, other: &ImageFingerprint) -> u32 {
+        (self.perceptual_hash ^ other.perceptual_hash).count_ones()
+    }
+}
+
+/// Secondary port that computes and stores image fingerprints at upload time
+#[async_trait]
+pub trait ImageFingerprintPort: Send + Sync + 'static {
+    /// Computes the fingerprint of an uploaded file and stores it.
+    ///
+    /// Returns `None` when the file is not a supported image.
+    async fn index_image(
+        &self,
+        file_id: &str,
+        mime_type: &str,
+        content: &[u8],
+    ) -> Result<Option<ImageFingerprint>, DomainError>;
+
+    /// Returns every stored fingerprint with its file ID
+    async fn list_fingerprints(&self) -> Result<Vec<(String, ImageFingerprint)>, DomainError>;
+
+    /// Forgets the fingerprint of a deleted file
+    async fn remove_fingerprint(&self, file_id: &str) -> Result<(), DomainError>;
+}
+
+/// Primary port for finding visually similar photos
+#[async_trait]
+pub trait DuplicatePhotoUseCase: Send + Sync + 'static {
+    /// Groups the user's photos whose perceptual distance is at most `max_distance`
+    async fn find_similar_photos(
+        &self,
+        username: &str,
+        max_distance: u32,
+    ) -> Result<Vec<SimilarPhotoGroupDto>, DomainError>;
+}
@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::common::errors::DomainError;
+
+/// Source formats previews can be rendered from
+pub const PREVIEW_MIME_TYPES: &[&str] = &["image/png", "image/jpeg", "image/jpg", "image/pjpeg"];
+
+/// How the image is fitted into the requested box
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImageFit {
+    /// Scales the whole image to fit inside the box, keeping its aspect ratio
+    Contain,
+    /// Scales the image to cover the box and crops the overflow around the center
+    Cover,
+    /// Stretches the image to the exact box
+    Fill,
+}
+
+impl ImageFit {
+    /// Parses the `fit` query value
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "contain" | "inside" => Some(Self::Contain),
+            "cover" | "crop" => Some(Self::Cover),
+            "fill" | "stretch" => Some(Self::Fill),
+            _ => None,
+        }
+    }
+
+    /// Canonical `fit` query value
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Contain => "contain",
+            Self::Cover => "cover",
+            Self::Fill => "fill",
+        }
+    }
+}
+
+/// Trade-off between rendering cost and preview fidelity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PreviewQuality {
+    /// Nearest-neighbour sampling and fast compression
+    Fast,
+    /// Filtered (bilinear) resampling
+    High,
+}
+
+impl PreviewQuality {
+    /// Parses a quality name (`fast` or `high`)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "fast" | "low" => Some(Self::Fast),
+            "high" => Some(Self::High),
+            _ => None,
+        }
+    }
+
+    /// Canonical quality name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Fast => "fast",
+            Self::High => "high",
+        }
+    }
+}
+
+/// Requested preview size; a missing side follows the aspect ratio
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImagePreviewRequest {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub fit: ImageFit,
+    /// Overrides the configured quality
+    pub quality: Option<PreviewQuality>,
+}
+
+/// Rendered preview
+#[derive(Debug, Clone)]
+pub struct ImagePreview {
+    pub content: Arc<Vec<u8>>,
+    pub mime_type: &'static str,
+    /// Validator derived from the file version and the rendering parameters
+    pub etag: String,
+}
+
+/// Primary port for on-demand resized image previews
+#[async_trait]
+pub trait ImagePreviewUseCase: Send + Sync + 'static {
+    /// Renders (or returns from cache) a resized preview of an image file
+    async fn render_preview(&self, file_id: &str, request: ImagePreviewRequest) -> Result<ImagePreview, DomainError>;
+}
@@ -0,0 +1,42 @@
+use async_trait::async_trait;
+
+use crate::application::dtos::file_dto::FileDto;
+use crate::application::dtos::image_tagging_dto::{
+    ImageLabelDto, ImageTaggingSettingsDto, LabeledFileDto, TaggingJobDto,
+};
+use crate::common::errors::DomainError;
+
+/// Secondary port for an image inference service (faces, objects, scenes...)
+#[async_trait]
+pub trait ImageLabelingPort: Send + Sync + 'static {
+    /// Returns the labels detected in an image
+    async fn label_image(&self, mime_type: &str, content: Vec<u8>) -> Result<Vec<ImageLabelDto>, DomainError>;
+}
+
+/// Primary port for opt-in image tagging
+#[async_trait]
+pub trait ImageTaggingUseCase: Send + Sync + 'static {
+    /// Returns the user's tagging setting
+    async fn get_settings(&self, user_id: &str) -> Result<ImageTaggingSettingsDto, DomainError>;
+
+    /// Enables or disables tagging; disabling deletes the user's labels
+    async fn set_enabled(&self, user_id: &str, enabled: bool) -> Result<ImageTaggingSettingsDto, DomainError>;
+
+    /// Tags a freshly stored file if its owner opted in
+    async fn tag_stored_file(&self, file: &FileDto, content: &[u8]) -> Result<(), DomainError>;
+
+    /// Forgets the labels of a deleted file
+    async fn remove_file_labels(&self, file_id: &str) -> Result<(), DomainError>;
+
+    /// Lists the labels of one of the user's files
+    async fn get_file_labels(&self, user_id: &str, file_id: &str) -> Result<Vec<ImageLabelDto>, DomainError>;
+
+    /// Finds the user's files carrying a label
+    async fn search_by_label(&self, user_id: &str, label: &str) -> Result<Vec<LabeledFileDto>, DomainError>;
+
+    /// Starts tagging every image already in the user's home folder
+    async fn start_backfill(&self, user_id: &str, username: &str) -> Result<TaggingJobDto, DomainError>;
+
+    /// Returns the status of one of the user's backfill jobs
+    async fn get_backfill_job(&self, user_id: &str, job_id: &str) -> Result<TaggingJobDto, DomainError>;
+}
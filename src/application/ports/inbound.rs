@@ -1,11 +1,13 @@
+use std::path::Path;
 use std::sync::Arc;
 use async_trait::async_trait;
 use bytes::Bytes;
 use futures::Stream;
 
 use crate::application::dtos::file_dto::FileDto;
-use crate::application::dtos::folder_dto::{CreateFolderDto, FolderDto, MoveFolderDto, RenameFolderDto};
-use crate::application::dtos::search_dto::{SearchCriteriaDto, SearchResultsDto};
+use crate::application::dtos::folder_dto::{CopyFolderDto, CreateFolderDto, FolderDto, MoveFolderDto, RenameFolderDto};
+use crate::application::dtos::search_dto::{SearchCriteriaDto, SearchResultsDto, UnifiedSearchQueryDto, UnifiedSearchResultsDto};
+use crate::application::ports::share_ports::ShareRecipient;
 use crate::common::errors::DomainError;
 
 /// Puerto primario para operaciones de archivos
@@ -20,6 +22,22 @@ pub trait FileUseCase: Send + Sync + 'static {
         content: Vec<u8>,
     ) -> Result<FileDto, DomainError>;
     
+    /// Sube un nuevo archivo con el contenido de un fichero local.
+    ///
+    /// La implementación por defecto lo carga en memoria; los servicios con
+    /// almacenamiento en disco lo copian sin leerlo entero
+    async fn upload_file_from_path(
+        &self,
+        name: String,
+        folder_id: Option<String>,
+        content_type: String,
+        source: &Path,
+    ) -> Result<FileDto, DomainError> {
+        let content = tokio::fs::read(source).await
+            .map_err(|e| DomainError::internal_error("File", format!("Failed to read {}: {}", source.display(), e)))?;
+        self.upload_file(name, folder_id, content_type, content).await
+    }
+    
     /// Obtiene un archivo por su ID
     async fn get_file(&self, id: &str) -> Result<FileDto, DomainError>;
     
@@ -44,6 +62,12 @@ pub trait FileUseCase: Send + Sync + 'static {
     /// Obtiene contenido de archivo como stream (para archivos grandes)
     async fn get_file_stream(&self, id: &str) -> Result<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>, DomainError>;
     
+    /// Obtiene un rango del contenido de archivo como stream (peticiones HTTP Range)
+    async fn get_file_range(&self, id: &str, start: u64, length: u64) -> Result<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>, DomainError>;
+    
+    /// Sobrescribe parte del contenido a partir de `offset` sin reenviar el archivo entero
+    async fn write_file_range(&self, id: &str, offset: u64, content: &[u8]) -> Result<FileDto, DomainError>;
+    
     /// Mueve un archivo a otra carpeta
     async fn move_file(&self, file_id: &str, folder_id: Option<String>) -> Result<FileDto, DomainError>;
 }
@@ -76,6 +100,9 @@ pub trait FolderUseCase: Send + Sync + 'static {
     /// Mueve una carpeta a otro padre
     async fn move_folder(&self, id: &str, dto: MoveFolderDto) -> Result<FolderDto, DomainError>;
     
+    /// Copia una carpeta con todo su contenido (subcarpetas, archivos y metadatos)
+    async fn copy_folder(&self, id: &str, dto: CopyFolderDto) -> Result<FolderDto, DomainError>;
+    
     /// Elimina una carpeta
     async fn delete_folder(&self, id: &str) -> Result<(), DomainError>;
 }
@@ -91,10 +118,26 @@ pub trait SearchUseCase: Send + Sync + 'static {
     /**
      * Realiza una búsqueda basada en los criterios especificados
      * 
+     * Solo se devuelven los elementos que el usuario puede leer: los suyos,
+     * los que no están en ninguna carpeta personal y los compartidos con él.
+     * 
      * @param criteria Criterios de búsqueda que incluyen texto, fechas, tamaños, etc.
+     * @param caller Usuario que busca
+     * @param is_admin Si el usuario es administrador; entonces no se filtra nada
      * @return Resultados de la búsqueda que contienen archivos y carpetas coincidentes
      */
-    async fn search(&self, criteria: SearchCriteriaDto) -> Result<SearchResultsDto, DomainError>;
+    async fn search(&self, criteria: SearchCriteriaDto, caller: ShareRecipient<'_>, is_admin: bool) -> Result<SearchResultsDto, DomainError>;
+    
+    /**
+     * Busca a la vez en archivos, contactos y eventos de calendario
+     * 
+     * @param query Texto a buscar, tipos de resultado y límite por grupo
+     * @param caller Usuario que busca; se consultan sus libretas y calendarios
+     * y solo los archivos que puede leer
+     * @param is_admin Si el usuario es administrador
+     * @return Resultados agrupados por tipo
+     */
+    async fn search_all(&self, query: UnifiedSearchQueryDto, caller: ShareRecipient<'_>, is_admin: bool) -> Result<UnifiedSearchResultsDto, DomainError>;
     
     /**
      * Limpia la caché de resultados de búsqueda
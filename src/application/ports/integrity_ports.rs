@@ -0,0 +1,45 @@
+use async_trait::async_trait;
+
+use crate::application::dtos::execution_plan_dto::ExecutionPlanDto;
+use crate::application::dtos::integrity_dto::IntegrityReportDto;
+use crate::common::errors::DomainError;
+
+/// Primary port for the integrity check of stored references.
+///
+/// A check looks for shares of deleted files, folders, users or groups,
+/// favorites of deleted items and group memberships of deleted users.
+/// Items in the trash still count as existing, since they can be restored.
+#[async_trait]
+pub trait IntegrityUseCase: Send + Sync + 'static {
+    /// Runs a check now, removing or repairing what it finds when `repair`
+    /// is set; fails with `Locked` when one is already running
+    async fn check(&self, repair: bool) -> Result<IntegrityReportDto, DomainError>;
+
+    /// Runs a check and lists what a repair would remove or change, without
+    /// touching anything
+    async fn dry_run_repair(&self) -> Result<ExecutionPlanDto, DomainError>;
+}
+
+/// A favorite as stored, before it is resolved to its item
+#[derive(Debug, Clone)]
+pub struct FavoriteReference {
+    pub id: i32,
+    pub item_id: String,
+    pub item_type: String,
+}
+
+/// Secondary port for the references kept in the database
+#[async_trait]
+pub trait IntegrityStoragePort: Send + Sync + 'static {
+    /// Favorites of every user
+    async fn list_favorites(&self) -> Result<Vec<FavoriteReference>, DomainError>;
+
+    /// Removes favorites by ID, returning how many were removed
+    async fn delete_favorites(&self, ids: &[i32]) -> Result<u64, DomainError>;
+
+    /// Group memberships whose user no longer exists
+    async fn count_orphaned_memberships(&self) -> Result<u64, DomainError>;
+
+    /// Removes the group memberships whose user no longer exists
+    async fn delete_orphaned_memberships(&self) -> Result<u64, DomainError>;
+}
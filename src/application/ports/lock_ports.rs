@@ -0,0 +1,55 @@
+use async_trait::async_trait;
+
+use crate::common::errors::DomainError;
+use crate::domain::entities::lock::ResourceLock;
+
+/// Parameters of a new WebDAV lock
+#[derive(Debug, Clone)]
+pub struct LockRequest {
+    pub path: String,
+    pub owner_id: String,
+    /// Free-form `owner` element sent by the client
+    pub owner_info: Option<String>,
+    pub exclusive: bool,
+    pub infinite_depth: bool,
+    /// Requested timeout in seconds; `None` uses the server default
+    pub timeout_secs: Option<u64>,
+}
+
+/// Primary port for WebDAV locking (RFC 4918 class 2)
+#[async_trait]
+pub trait LockUseCase: Send + Sync + 'static {
+    /// Creates a lock, failing with `Locked` if it conflicts with an existing one
+    async fn lock(&self, request: LockRequest) -> Result<ResourceLock, DomainError>;
+
+    /// Extends a lock on `path` identified by one of the submitted tokens
+    async fn refresh_lock(
+        &self,
+        path: &str,
+        user_id: &str,
+        tokens: &[String],
+        timeout_secs: Option<u64>,
+    ) -> Result<ResourceLock, DomainError>;
+
+    /// Removes a lock; the token must protect `path` and belong to the user
+    async fn unlock(&self, path: &str, user_id: &str, token: &str) -> Result<(), DomainError>;
+
+    /// Checks that the user may modify `path` with the submitted tokens.
+    ///
+    /// `changes_membership` is set when the request creates, deletes or moves
+    /// the resource, which also needs the tokens of locks on the parent
+    /// collection and on anything inside `path`.
+    async fn check_write(
+        &self,
+        path: &str,
+        user_id: &str,
+        tokens: &[String],
+        changes_membership: bool,
+    ) -> Result<(), DomainError>;
+
+    /// Drops the locks of a resource that was deleted or moved away
+    async fn release_locks(&self, path: &str) -> Result<(), DomainError>;
+
+    /// Removes expired locks and returns how many were removed
+    async fn expire_locks(&self) -> Result<u64, DomainError>;
+}
@@ -0,0 +1,18 @@
+use async_trait::async_trait;
+
+use crate::common::errors::DomainError;
+
+/// Plain-text email to a single recipient
+#[derive(Debug, Clone)]
+pub struct OutgoingMail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Secondary port for delivering email
+#[async_trait]
+pub trait MailSenderPort: Send + Sync + 'static {
+    /// Hands a message over to the mail server; fails if it is not accepted
+    async fn send(&self, mail: OutgoingMail) -> Result<(), DomainError>;
+}
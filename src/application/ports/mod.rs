@@ -1,11 +1,63 @@
+pub mod announcement_ports;
 pub mod auth_ports;
 pub mod calendar_ports;
+pub mod calendar_ical_ports;
 pub mod carddav_ports;
+pub mod credential_ports;
+pub mod dav_capture_ports;
+pub mod dav_multiget_ports;
+pub mod dav_principal_ports;
+pub mod dav_sync_ports;
+pub mod dav_validation_ports;
+pub mod dead_property_ports;
+pub mod external_storage_ports;
 pub mod favorites_ports;
+pub mod feature_flag_ports;
+pub mod file_attribute_ports;
 pub mod file_ports;
+pub mod gallery_ports;
+pub mod hidden_file_ports;
+pub mod image_fingerprint_ports;
+pub mod image_preview_ports;
+pub mod image_tagging_ports;
 pub mod inbound;
+pub mod lock_ports;
 pub mod outbound;
+pub mod placeholder_ports;
+pub mod quota_ports;
 pub mod recent_ports;
 pub mod share_ports;
+pub mod skeleton_ports;
 pub mod storage_ports;
-pub mod trash_ports;
\ No newline at end of file
+pub mod sync_conflict_ports;
+pub mod theme_ports;
+pub mod trash_ports;
+pub mod upload_session_ports;
+pub mod storage_gc_ports;
+pub mod integrity_ports;
+pub mod scheduling_ports;
+pub mod demo_ports;
+pub mod contact_photo_ports;
+pub mod contact_import_ports;
+pub mod content_hash_ports;
+pub mod ownership_ports;
+pub mod undo_ports;
+pub mod presence_ports;
+pub mod user_quarantine_ports;
+pub mod user_group_ports;
+pub mod zip_export_ports;
+pub mod notification_ports;
+pub mod dav_transfer_ports;
+pub mod oidc_ports;
+pub mod template_ports;
+pub mod mail_ports;
+pub mod directory_ports;
+pub mod email_notification_ports;
+pub mod realtime_ports;
+pub mod event_bus_ports;
+pub mod activity_ports;
+pub mod audit_ports;
+pub mod webhook_ports;
+pub mod full_text_ports;
+pub mod tag_ports;
+pub mod comment_ports;
@@ -0,0 +1,29 @@
+use async_trait::async_trait;
+
+use crate::application::dtos::notification_dto::{JobFailure, MentionEventDto, NotificationDto, ShareEventDto};
+use crate::common::errors::DomainError;
+
+/// Primary port for the notification center where failed user jobs (ZIP
+/// downloads, imports, exports), sharing changes and mentions in comments
+/// stay visible until the user dismisses them
+#[async_trait]
+pub trait NotificationUseCase: Send + Sync + 'static {
+    /// Records the failure of a job started by `user_id`
+    async fn report_job_failure(&self, user_id: Option<&str>, failure: JobFailure) -> NotificationDto;
+
+    /// Tells each recipient that something was shared with them or no longer
+    /// is. Errors are logged: a lost notice must not undo the share
+    async fn notify_share(&self, recipients: &[String], event: ShareEventDto);
+
+    /// Tells each recipient that they were mentioned in a comment
+    async fn notify_mention(&self, recipients: &[String], event: MentionEventDto);
+
+    /// Notifications of a user, newest first
+    async fn list_notifications(&self, user_id: Option<&str>) -> Vec<NotificationDto>;
+
+    /// Marks a notification as seen
+    async fn mark_read(&self, user_id: Option<&str>, notification_id: &str) -> Result<NotificationDto, DomainError>;
+
+    /// Removes a notification from the center
+    async fn dismiss(&self, user_id: Option<&str>, notification_id: &str) -> Result<(), DomainError>;
+}
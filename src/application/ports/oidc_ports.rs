@@ -0,0 +1,30 @@
+use async_trait::async_trait;
+
+use crate::common::errors::DomainError;
+
+/// Identity of a user as asserted by a verified ID token
+#[derive(Debug, Clone)]
+pub struct OidcClaims {
+    /// Issuer (`iss`) the token was verified against
+    pub issuer: String,
+    /// Stable identifier of the account at the provider (`sub`)
+    pub subject: String,
+    pub email: Option<String>,
+    /// Whether the provider vouches for the email (`email_verified`)
+    pub email_verified: bool,
+    /// Value of the configured username claim, if present
+    pub username: Option<String>,
+    pub name: Option<String>,
+    /// `nonce` echoed back in the token
+    pub nonce: Option<String>,
+}
+
+/// Secondary port for an OpenID Connect provider (authorization-code flow)
+#[async_trait]
+pub trait OidcProviderPort: Send + Sync + 'static {
+    /// Builds the URL the browser is sent to in order to log in at the provider
+    async fn authorization_url(&self, state: &str, nonce: &str, code_challenge: &str) -> Result<String, DomainError>;
+
+    /// Exchanges an authorization code for a verified ID token
+    async fn exchange_code(&self, code: &str, code_verifier: &str) -> Result<OidcClaims, DomainError>;
+}
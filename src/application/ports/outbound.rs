@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use async_trait::async_trait;
 use bytes::Bytes;
 use futures::Stream;
@@ -36,6 +36,38 @@ pub trait FileStoragePort: Send + Sync + 'static {
         content: Vec<u8>,
     ) -> Result<File, DomainError>;
     
+    /// Copia un archivo a una carpeta con un nombre nuevo.
+    ///
+    /// La implementación por defecto carga el contenido en memoria; los
+    /// almacenamientos que pueden copiar sin leerlo entero deberían sobrescribirla.
+    async fn copy_file(
+        &self,
+        file_id: &str,
+        name: String,
+        target_folder_id: Option<String>,
+    ) -> Result<File, DomainError> {
+        let source = self.get_file(file_id).await?;
+        let content = self.get_file_content(file_id).await?;
+        self.save_file(name, target_folder_id, source.mime_type().to_string(), content).await
+    }
+    
+    /// Guarda un nuevo archivo con el contenido de un fichero local, como el
+    /// temporal de una subida por partes.
+    ///
+    /// La implementación por defecto carga el contenido en memoria; los
+    /// almacenamientos que pueden copiarlo sin leerlo entero deberían sobrescribirla.
+    async fn save_file_from_path(
+        &self,
+        name: String,
+        folder_id: Option<String>,
+        content_type: String,
+        source: &Path,
+    ) -> Result<File, DomainError> {
+        let content = tokio::fs::read(source).await
+            .map_err(|e| DomainError::internal_error("FileStorage", format!("Failed to read {}: {}", source.display(), e)))?;
+        self.save_file(name, folder_id, content_type, content).await
+    }
+    
     /// Obtiene un archivo por su ID
     async fn get_file(&self, id: &str) -> Result<File, DomainError>;
     
@@ -51,6 +83,9 @@ pub trait FileStoragePort: Send + Sync + 'static {
     /// Obtiene contenido de archivo como stream
     async fn get_file_stream(&self, id: &str) -> Result<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>, DomainError>;
     
+    /// Obtiene `length` bytes del archivo a partir de `start` como stream
+    async fn get_file_range(&self, id: &str, start: u64, length: u64) -> Result<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>, DomainError>;
+    
     /// Mueve un archivo a otra carpeta
     async fn move_file(&self, file_id: &str, target_folder_id: Option<String>) -> Result<File, DomainError>;
     
@@ -62,6 +97,24 @@ pub trait FileStoragePort: Send + Sync + 'static {
     
     /// Actualiza el contenido de un archivo existente
     async fn update_file_content(&self, file_id: &str, content: Vec<u8>) -> Result<(), DomainError>;
+    
+    /// Escribe `content` a partir de `offset`, extendiendo el archivo si hace falta.
+    ///
+    /// La implementación por defecto reescribe el archivo completo; los
+    /// almacenamientos que permiten escribir en el sitio deberían sobrescribirla.
+    async fn write_file_range(&self, file_id: &str, offset: u64, content: &[u8]) -> Result<(), DomainError> {
+        let mut current = self.get_file_content(file_id).await?;
+        let offset = usize::try_from(offset)
+            .ok()
+            .filter(|offset| *offset <= current.len())
+            .ok_or_else(|| DomainError::validation_error(format!("Offset {} is past the end of file {}", offset, file_id)))?;
+        let end = offset + content.len();
+        if end > current.len() {
+            current.resize(end, 0);
+        }
+        current[offset..end].copy_from_slice(content);
+        self.update_file_content(file_id, current).await
+    }
 }
 
 /// Puerto secundario para persistencia de carpetas
@@ -0,0 +1,68 @@
+use async_trait::async_trait;
+
+use crate::common::errors::DomainError;
+use crate::domain::entities::share::ShareItemType;
+use crate::domain::services::ownership_service::TransferOperation;
+
+/// Item that is about to be moved or copied
+#[derive(Debug, Clone)]
+pub enum TransferItem {
+    File { id: String, size: u64 },
+    /// A non-recursive folder copy (WebDAV `Depth: 0`) only takes the folder itself
+    Folder { id: String, recursive: bool },
+}
+
+/// Account that owns a home folder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemOwner {
+    pub user_id: String,
+    pub username: String,
+}
+
+/// Outcome of checking a MOVE or COPY against the ownership rules
+#[derive(Debug, Clone)]
+pub struct TransferPlan {
+    /// What the caller must actually do; under the `copy` policy a move into
+    /// another user's folder becomes a copy
+    pub operation: TransferOperation,
+    /// Owner of the item before the transfer
+    pub source_owner: Option<ItemOwner>,
+    /// Owner of the destination folder, who owns the item afterwards
+    pub destination_owner: Option<ItemOwner>,
+    /// Account charged for the bytes the transfer adds
+    pub charged_user_id: Option<String>,
+    /// Bytes of the item, including everything under a folder
+    pub size: u64,
+    /// The item and, for folders, everything under it
+    pub items: Vec<(String, ShareItemType)>,
+}
+
+impl TransferPlan {
+    /// Whether a move hands the item over to another user
+    pub fn transfers_ownership(&self) -> bool {
+        self.operation == TransferOperation::Move
+            && matches!((&self.source_owner, &self.destination_owner), (Some(from), Some(to)) if from != to)
+    }
+}
+
+/// Primary port that applies the ownership rules to moves and copies between
+/// folders of different users
+#[async_trait]
+pub trait ItemOwnershipUseCase: Send + Sync + 'static {
+    /// Checks a transfer from `source_path` to `destination_path` against the
+    /// cross-user policy and the quota of the account that pays for it.
+    ///
+    /// `actor_id` is charged for copies into folders that belong to nobody.
+    async fn plan_transfer(
+        &self,
+        actor_id: &str,
+        operation: TransferOperation,
+        item: TransferItem,
+        source_path: &str,
+        destination_path: &str,
+    ) -> Result<TransferPlan, DomainError>;
+
+    /// Records the usage change of a completed transfer and, when ownership
+    /// changed hands, revokes the previous owner's shares and favorites
+    async fn complete_transfer(&self, plan: &TransferPlan);
+}
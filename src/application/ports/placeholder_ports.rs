@@ -0,0 +1,29 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use crate::common::errors::DomainError;
+
+/// Secondary port that computes and stores tiny image placeholders
+/// (blurhash strings) shown by gallery views before thumbnails load
+#[async_trait]
+pub trait ImagePlaceholderPort: Send + Sync + 'static {
+    /// Computes the placeholder for an uploaded file and stores it.
+    ///
+    /// Returns `None` when the file is not a supported image.
+    async fn generate_placeholder(
+        &self,
+        file_id: &str,
+        mime_type: &str,
+        content: &[u8],
+    ) -> Result<Option<String>, DomainError>;
+
+    /// Returns the stored placeholders for the given files, keyed by file ID
+    async fn get_placeholders(&self, file_ids: &[String]) -> Result<HashMap<String, String>, DomainError>;
+
+    /// Forgets the placeholder of a deleted file
+    async fn remove_placeholder(&self, file_id: &str) -> Result<(), DomainError>;
+
+    /// IDs of every file with a stored placeholder
+    async fn list_placeholder_ids(&self) -> Result<Vec<String>, DomainError>;
+}
@@ -0,0 +1,39 @@
+use async_trait::async_trait;
+use tokio::sync::broadcast;
+
+use crate::application::dtos::presence_dto::{PresenceDto, PresenceEventDto, PresenceHeartbeatDto};
+use crate::application::ports::share_ports::ShareRecipient;
+use crate::common::errors::DomainError;
+
+/// Primary port that tracks who has each file open, so the UI can warn
+/// before two people edit the same file.
+///
+/// Presence is kept alive by heartbeats from the web UI or from a WOPI
+/// editor session and expires when they stop. Only users who can read a
+/// file may announce or see who has it open.
+#[async_trait]
+pub trait PresenceUseCase: Send + Sync + 'static {
+    /// Records that the caller has a file open; returns everyone who has it open
+    async fn heartbeat(
+        &self,
+        caller: ShareRecipient<'_>,
+        is_admin: bool,
+        file_id: &str,
+        heartbeat: PresenceHeartbeatDto,
+    ) -> Result<Vec<PresenceDto>, DomainError>;
+
+    /// Records that a user closed a file
+    async fn leave(&self, file_id: &str, user_id: &str);
+
+    /// Users who have a file open
+    async fn list(&self, caller: ShareRecipient<'_>, is_admin: bool, file_id: &str) -> Result<Vec<PresenceDto>, DomainError>;
+
+    /// Receives every presence change as it happens, once the caller is
+    /// known to be able to read all of `file_ids`
+    async fn watch(
+        &self,
+        caller: ShareRecipient<'_>,
+        is_admin: bool,
+        file_ids: &[String],
+    ) -> Result<broadcast::Receiver<PresenceEventDto>, DomainError>;
+}
@@ -0,0 +1,48 @@
+use async_trait::async_trait;
+
+use crate::application::dtos::quota_dto::{QuotaPolicyDto, QuotaReportDto, SetQuotaPolicyDto, StorageQuotaDto};
+use crate::application::ports::auth_ports::Caller;
+use crate::common::errors::DomainError;
+
+/// Primary port for per-user storage quotas.
+///
+/// Checks run against the usage stored with the user, which grows as writes
+/// are recorded and is recalculated by the storage usage service. The limit
+/// is the user's effective quota: one set for them, else the policy of their
+/// group, else the quota assigned when the account was created. Reading and
+/// changing quotas and policies takes the caller, since only administrators
+/// may do it (users may read their own quota).
+#[async_trait]
+pub trait StorageQuotaUseCase: Send + Sync + 'static {
+    /// Current quota and usage of a user
+    async fn get_quota(&self, caller: &Caller, user_id: &str) -> Result<StorageQuotaDto, DomainError>;
+
+    /// Sets a user's quota, taking precedence over group policies; a negative
+    /// value removes the limit
+    async fn set_quota(&self, caller: &Caller, user_id: &str, quota_bytes: i64) -> Result<StorageQuotaDto, DomainError>;
+
+    /// Fails with `QuotaExceeded` when `additional_bytes` more would not fit
+    async fn check_quota(&self, user_id: &str, additional_bytes: u64) -> Result<(), DomainError>;
+
+    /// Same as `check_quota` for callers that only know the username; returns
+    /// the user's ID so the write can be recorded afterwards
+    async fn check_quota_for_username(&self, username: &str, additional_bytes: u64) -> Result<String, DomainError>;
+
+    /// Adds `delta_bytes` (negative when space is freed) to the stored usage
+    async fn record_usage(&self, user_id: &str, delta_bytes: i64) -> Result<(), DomainError>;
+
+    /// Removes the quota set for a user so that group policies apply again
+    async fn clear_quota_override(&self, caller: &Caller, user_id: &str) -> Result<StorageQuotaDto, DomainError>;
+
+    /// Lists the default quota of each group
+    async fn list_policies(&self, caller: &Caller) -> Result<Vec<QuotaPolicyDto>, DomainError>;
+
+    /// Creates or changes the default quota of a group
+    async fn set_policy(&self, caller: &Caller, group: &str, dto: SetQuotaPolicyDto) -> Result<QuotaPolicyDto, DomainError>;
+
+    /// Deletes the default quota of a group
+    async fn delete_policy(&self, caller: &Caller, group: &str) -> Result<(), DomainError>;
+
+    /// Quota allocated against actual usage, per group and per user
+    async fn allocation_report(&self, caller: &Caller) -> Result<QuotaReportDto, DomainError>;
+}
@@ -0,0 +1,88 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::domain::entities::share_notification::SharedItemKind;
+
+/// Change pushed to the WebSocket clients of a user; serialized as a `type`
+/// field next to the fields of each variant
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RealtimeEvent {
+    FileCreated {
+        file_id: String,
+        name: String,
+        folder_id: Option<String>,
+    },
+    FileUpdated {
+        file_id: String,
+        name: String,
+        folder_id: Option<String>,
+    },
+    FileDeleted {
+        file_id: String,
+        folder_id: Option<String>,
+    },
+    /// Another user shared a file, folder, calendar or address book
+    ShareReceived {
+        item_kind: SharedItemKind,
+        item_id: String,
+        item_name: String,
+        actor_name: Option<String>,
+    },
+    /// A contact was deleted from an address book of the user
+    ContactDeleted {
+        address_book_id: String,
+        contact_id: String,
+    },
+    /// An event was created or changed in a calendar of the user
+    CalendarEventChanged {
+        calendar_id: String,
+        event_id: String,
+        ical_uid: String,
+    },
+    /// The connection was too slow and missed events; the client should
+    /// reload what it shows
+    Resync {
+        missed: u64,
+    },
+}
+
+/// Who receives an event. Files are only known by the username in the path
+/// of their home folder, everything else by user ID
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RealtimeAudience {
+    User(String),
+    Username(String),
+}
+
+impl RealtimeAudience {
+    pub fn includes(&self, user_id: &str, username: &str) -> bool {
+        match self {
+            Self::User(id) => id == user_id,
+            Self::Username(name) => name == username,
+        }
+    }
+}
+
+/// Event as it travels through the bus
+#[derive(Debug, Clone, Serialize)]
+pub struct RealtimeMessage {
+    #[serde(skip)]
+    pub audience: RealtimeAudience,
+
+    #[serde(flatten)]
+    pub event: RealtimeEvent,
+
+    pub at: DateTime<Utc>,
+}
+
+/// In-process bus that services publish changes into and the `/ws`
+/// endpoint forwards to the connected clients of each user
+pub trait RealtimeEventPort: Send + Sync + 'static {
+    /// Publishes an event without waiting; it is dropped if nobody listens
+    fn publish(&self, audience: RealtimeAudience, event: RealtimeEvent);
+
+    /// Receives every event published from now on, for every user
+    fn subscribe(&self) -> broadcast::Receiver<RealtimeMessage>;
+}
@@ -0,0 +1,44 @@
+use async_trait::async_trait;
+
+use crate::application::dtos::calendar_dto::CalendarEventDto;
+use crate::application::dtos::scheduling_dto::{ScheduleRecipientDto, SchedulingMessageDto};
+use crate::common::errors::DomainError;
+
+/// Primary port for iTIP scheduling between users of this server.
+///
+/// Messages are only delivered to users with an account here; recipients
+/// elsewhere get an "invalid calendar user" status in the delivery results.
+#[async_trait]
+pub trait SchedulingUseCase: Send + Sync + 'static {
+    /// Sends an iTIP message posted to the scheduling outbox of the user
+    async fn send_message(&self, user_id: &str, ical_data: &str) -> Result<Vec<ScheduleRecipientDto>, DomainError>;
+
+    /// Sends the invitations of an event organized by the user to its attendees
+    async fn send_invitations(
+        &self,
+        user_id: &str,
+        is_admin: bool,
+        calendar_id: &str,
+        ical_uid: &str,
+    ) -> Result<Vec<ScheduleRecipientDto>, DomainError>;
+
+    /// Sets the participation status of the user in an event and replies to the
+    /// organizer. An invitation still in the inbox is added to the calendar first
+    async fn respond(
+        &self,
+        user_id: &str,
+        is_admin: bool,
+        calendar_id: &str,
+        ical_uid: &str,
+        partstat: &str,
+    ) -> Result<CalendarEventDto, DomainError>;
+
+    /// Lists the scheduling inbox of the user, oldest first
+    async fn list_inbox(&self, user_id: &str) -> Result<Vec<SchedulingMessageDto>, DomainError>;
+
+    /// Gets a message of the scheduling inbox of the user
+    async fn get_inbox_message(&self, user_id: &str, message_id: &str) -> Result<SchedulingMessageDto, DomainError>;
+
+    /// Removes a message from the scheduling inbox of the user
+    async fn delete_inbox_message(&self, user_id: &str, message_id: &str) -> Result<(), DomainError>;
+}
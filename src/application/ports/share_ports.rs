@@ -1,14 +1,39 @@
 use async_trait::async_trait;
+use bytes::Bytes;
+use futures::Stream;
 
 use crate::{
-    application::dtos::{
-        pagination::PaginatedResponseDto,
-        share_dto::{CreateShareDto, ShareDto, UpdateShareDto}
+    application::{
+        dtos::{
+            pagination::PaginatedResponseDto,
+            share_dto::{
+                CreateShareDto, PublicShareMetadataDto, QrCodeFormat, ShareAccessLogEntryDto, ShareDto,
+                ShareEmailSessionDto, ShareQrCodeDto, SharedFolderListingDto, SharedItemDto, UpdateShareDto,
+            }
+        },
+        ports::image_preview_ports::ImagePreview,
     },
     common::errors::DomainError,
-    domain::entities::share::ShareItemType,
+    domain::entities::{share::{ShareItemType, SharePermissions}, share_access_log::ShareAccessEntry},
 };
 
+/// What a visitor presents to open the content of a shared link
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ShareCredentials<'a> {
+    /// Password of password-protected links
+    pub password: Option<&'a str>,
+    /// Session of links that require email verification
+    pub email_session: Option<&'a str>,
+    pub client_ip: Option<&'a str>,
+}
+
+/// A shared file ready to be streamed to a visitor
+pub struct SharedFileContent {
+    pub name: String,
+    pub mime_type: String,
+    pub size: u64,
+    pub content: Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>,
+}
 
 #[async_trait]
 pub trait ShareUseCase: Send + Sync + 'static {
@@ -22,9 +47,28 @@ pub trait ShareUseCase: Send + Sync + 'static {
     /// Get a shared link by its ID
     async fn get_shared_link(&self, id: &str) -> Result<ShareDto, DomainError>;
 
+    /// Render the public URL of a shared link as a QR code, `scale` pixels per
+    /// module. Only the user who created the link may get it. Expired links
+    /// are refused; the password is never encoded.
+    async fn get_shared_link_qr_code(
+        &self,
+        id: &str,
+        user_id: &str,
+        format: QrCodeFormat,
+        scale: usize,
+    ) -> Result<ShareQrCodeDto, DomainError>;
+
     /// Get a shared link by its token (for access by non-users)
     async fn get_shared_link_by_token(&self, token: &str) -> Result<ShareDto, DomainError>;
 
+    /// Get the public metadata of a shared link for link previews. Unknown,
+    /// expired and orphaned tokens all fail with the same not found error.
+    async fn get_public_share_metadata(&self, token: &str) -> Result<PublicShareMetadataDto, DomainError>;
+
+    /// Render the link preview thumbnail of a shared file. Fails with the same
+    /// not found error when the share does not allow thumbnails.
+    async fn get_public_share_thumbnail(&self, token: &str) -> Result<ImagePreview, DomainError>;
+
     /// Get all shared links for a specific item
     async fn get_shared_links_for_item(
         &self,
@@ -59,6 +103,105 @@ pub trait ShareUseCase: Send + Sync + 'static {
     
     /// Register an access to a shared link
     async fn register_shared_link_access(&self, token: &str) -> Result<(), DomainError>;
+
+    /// Open a shared link as a visitor: registers and logs the access. Links
+    /// that require email verification need a session from
+    /// `verify_share_email_code`, otherwise they fail with an access denied
+    /// error mentioning the email verification.
+    async fn access_shared_link(
+        &self,
+        token: &str,
+        email_session: Option<&str>,
+        client_ip: Option<&str>,
+    ) -> Result<ShareDto, DomainError>;
+
+    /// Send a one-time code to a visitor's email. Succeeds without sending
+    /// anything when the address is not allowed, so the allow list can't be probed.
+    async fn request_share_email_code(
+        &self,
+        token: &str,
+        email: &str,
+        client_ip: Option<&str>,
+    ) -> Result<(), DomainError>;
+
+    /// Check a one-time code and open a time-limited session for the share
+    async fn verify_share_email_code(
+        &self,
+        token: &str,
+        email: &str,
+        code: &str,
+        client_ip: Option<&str>,
+    ) -> Result<ShareEmailSessionDto, DomainError>;
+
+    /// Get the access log of a shared link, newest first. Only the user who
+    /// created the link may read it.
+    async fn get_share_access_log(&self, id: &str, user_id: &str) -> Result<Vec<ShareAccessLogEntryDto>, DomainError>;
+
+    /// Download the content of a shared link as a visitor and count the
+    /// download. Folder shares need the ID of a file inside the folder.
+    /// Password-protected links fail with an access denied error mentioning
+    /// the password until the right one is given.
+    async fn download_shared_file(
+        &self,
+        token: &str,
+        file_id: Option<&str>,
+        credentials: ShareCredentials<'_>,
+    ) -> Result<SharedFileContent, DomainError>;
+
+    /// List a folder of a shared folder as a visitor: the shared folder
+    /// itself, or one of its subfolders when `folder_id` is given
+    async fn list_shared_folder(
+        &self,
+        token: &str,
+        folder_id: Option<&str>,
+        credentials: ShareCredentials<'_>,
+    ) -> Result<SharedFolderListingDto, DomainError>;
+
+    /// Upload a file into a shared folder as a visitor. Needs a link with
+    /// the upload permission; the file never replaces an existing one and
+    /// gets a numbered name instead, so file drop visitors learn nothing
+    /// about the folder content.
+    async fn upload_to_shared_folder(
+        &self,
+        token: &str,
+        file_name: &str,
+        content_type: &str,
+        content: Vec<u8>,
+        credentials: ShareCredentials<'_>,
+    ) -> Result<SharedItemDto, DomainError>;
+}
+
+/// A signed-in user acting on files that may belong to someone else
+#[derive(Debug, Clone, Copy)]
+pub struct ShareRecipient<'a> {
+    pub user_id: &'a str,
+    pub username: &'a str,
+    pub email: &'a str,
+}
+
+/// Item a request acts on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShareTarget {
+    File(String),
+    Folder(String),
+    /// Path below the storage root, as WebDAV names it. It may not exist
+    /// yet, as when a file is uploaded
+    Path(String),
+}
+
+/// Permissions of share recipients on the files of other users
+#[async_trait]
+pub trait ShareAccessUseCase: Send + Sync + 'static {
+    /// Check that `recipient` may do everything in `required` on `target`.
+    /// Owners, and items outside every home folder, always pass; anyone
+    /// else needs shares addressed to them, by email or group, on the item
+    /// or a folder above it that together grant `required`.
+    async fn ensure_access(
+        &self,
+        recipient: ShareRecipient<'_>,
+        target: &ShareTarget,
+        required: SharePermissions,
+    ) -> Result<(), DomainError>;
 }
 
 #[async_trait]
@@ -82,4 +225,37 @@ pub trait ShareStoragePort: Send + Sync + 'static {
     
     async fn find_shares_by_user(&self, user_id: &str, offset: usize, limit: usize) 
         -> Result<(Vec<crate::domain::entities::share::Share>, usize), DomainError>;
+
+    /// Every share of every user, for the integrity check
+    async fn find_all_shares(&self) -> Result<Vec<crate::domain::entities::share::Share>, DomainError>;
+
+    /// Shares of any of `items` addressed to a recipient, by their
+    /// (normalized) email or through one of their groups
+    async fn find_shares_for_recipient(
+        &self,
+        items: &[(String, ShareItemType)],
+        email: &str,
+        group_ids: &[String],
+    ) -> Result<Vec<crate::domain::entities::share::Share>, DomainError> {
+        let mut shares = Vec::new();
+        for (item_id, item_type) in items {
+            for share in self.find_shares_by_item(item_id, item_type).await? {
+                if share.allowed_emails.iter().any(|allowed| allowed == email)
+                    || share.allowed_groups.iter().any(|group| group_ids.contains(group))
+                {
+                    shares.push(share);
+                }
+            }
+        }
+        Ok(shares)
+    }
+}
+
+/// Secondary port for the access log of shared links
+#[async_trait]
+pub trait ShareAccessLogPort: Send + Sync + 'static {
+    async fn record(&self, entry: ShareAccessEntry) -> Result<(), DomainError>;
+
+    /// Latest entries of a share, newest first
+    async fn list_for_share(&self, share_id: &str, limit: usize) -> Result<Vec<ShareAccessEntry>, DomainError>;
 }
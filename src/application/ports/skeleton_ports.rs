@@ -0,0 +1,26 @@
+use async_trait::async_trait;
+
+use crate::common::errors::DomainError;
+
+/// Entry of a home folder skeleton (folder or starter file)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkeletonEntry {
+    /// Path relative to the user's home folder, using `/` as separator
+    pub relative_path: String,
+
+    /// Whether the entry is a folder
+    pub is_dir: bool,
+
+    /// File content (None for folders)
+    pub content: Option<Vec<u8>>,
+}
+
+/// Secondary port providing the skeleton copied into new users' homes
+#[async_trait]
+pub trait SkeletonSourcePort: Send + Sync + 'static {
+    /// Loads a skeleton by name: a group id, or `default` for users whose
+    /// groups have none. `None` if there is no skeleton with that name.
+    ///
+    /// Entries are ordered so that every folder comes before its children.
+    async fn load_skeleton(&self, name: &str) -> Result<Option<Vec<SkeletonEntry>>, DomainError>;
+}
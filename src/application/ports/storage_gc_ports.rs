@@ -0,0 +1,23 @@
+use async_trait::async_trait;
+
+use crate::application::dtos::execution_plan_dto::ExecutionPlanDto;
+use crate::application::dtos::storage_gc_dto::{StorageGcReportDto, StorageGcStatsDto};
+use crate::common::errors::DomainError;
+
+/// Primary port for the garbage collection of orphaned storage.
+///
+/// A pass removes data nothing refers to anymore: trash blobs without a trash
+/// entry, abandoned upload fragments, temporary files of interrupted writes
+/// and image placeholders or fingerprints of deleted files. Files changed
+/// within the grace period are always kept.
+#[async_trait]
+pub trait StorageGcUseCase: Send + Sync + 'static {
+    /// Runs a pass now; fails with `Locked` when one is already running
+    async fn run(&self) -> Result<StorageGcReportDto, DomainError>;
+
+    /// What a pass would remove right now, without removing anything
+    async fn dry_run(&self) -> Result<ExecutionPlanDto, DomainError>;
+
+    /// Figures of the passes run so far
+    async fn stats(&self) -> StorageGcStatsDto;
+}
@@ -22,6 +22,9 @@ pub trait FileReadPort: Send + Sync + 'static {
     
     /// Obtiene contenido de archivo como stream
     async fn get_file_stream(&self, id: &str) -> Result<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>, DomainError>;
+    
+    /// Obtiene `length` bytes del archivo a partir de `start` como stream
+    async fn get_file_range(&self, id: &str, start: u64, length: u64) -> Result<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>, DomainError>;
 }
 
 /// Puerto secundario para escritura de archivos
@@ -0,0 +1,30 @@
+use async_trait::async_trait;
+
+use crate::application::dtos::sync_conflict_dto::SyncConflictDto;
+use crate::common::errors::DomainError;
+use crate::domain::entities::sync_conflict::{ConflictKind, ConflictResolution};
+
+/// Primary port for the cross-device sync conflict dashboard
+#[async_trait]
+pub trait SyncConflictUseCase: Send + Sync + 'static {
+    /// Records a detected conflict; repeats of an open conflict are counted, not duplicated
+    async fn record_conflict(
+        &self,
+        user_id: &str,
+        kind: ConflictKind,
+        path: &str,
+        conflict_path: Option<&str>,
+        detail: Option<String>,
+    ) -> Result<(), DomainError>;
+
+    /// Lists the conflicts of a user, most recent first
+    async fn list_conflicts(&self, user_id: &str, include_resolved: bool) -> Result<Vec<SyncConflictDto>, DomainError>;
+
+    /// Applies a resolution and marks the conflict as resolved
+    async fn resolve_conflict(
+        &self,
+        user_id: &str,
+        conflict_id: &str,
+        resolution: ConflictResolution,
+    ) -> Result<SyncConflictDto, DomainError>;
+}
@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use crate::application::dtos::tag_dto::{CreateTagDto, TagAssignmentDto, TagDto, UpdateTagDto};
+use crate::common::errors::DomainError;
+use crate::domain::entities::tag::TaggedItemType;
+
+/// Primary port for user-defined tags and their assignments to files and
+/// folders.
+///
+/// Tags are private: every user manages, assigns and sees only their own.
+#[async_trait]
+pub trait TagUseCase: Send + Sync + 'static {
+    /// The user's tags, by name
+    async fn list_tags(&self, user_id: &str) -> Result<Vec<TagDto>, DomainError>;
+
+    /// Creates a tag; names must be unique per user, ignoring case
+    async fn create_tag(&self, user_id: &str, request: CreateTagDto) -> Result<TagDto, DomainError>;
+
+    /// Renames a tag or changes its color
+    async fn update_tag(&self, user_id: &str, tag_id: &str, request: UpdateTagDto) -> Result<TagDto, DomainError>;
+
+    /// Deletes a tag and removes it from every item
+    async fn delete_tag(&self, user_id: &str, tag_id: &str) -> Result<(), DomainError>;
+
+    /// Files and folders carrying a tag, most recently tagged first
+    async fn list_tagged_items(&self, user_id: &str, tag_id: &str) -> Result<Vec<TagAssignmentDto>, DomainError>;
+
+    /// Assigns a tag to a file or folder; assigning it twice is not an error
+    async fn assign_tag(&self, user_id: &str, tag_id: &str, item: TagAssignmentDto) -> Result<(), DomainError>;
+
+    /// Removes a tag from a file or folder
+    async fn unassign_tag(&self, user_id: &str, tag_id: &str, item: TagAssignmentDto) -> Result<(), DomainError>;
+
+    /// The user's tags on a file or folder, by name
+    async fn get_item_tags(&self, user_id: &str, item_type: TaggedItemType, item_id: &str) -> Result<Vec<TagDto>, DomainError>;
+
+    /// The user's tags on several items at once, keyed by item ID. Items
+    /// without tags are left out
+    async fn get_tags_for_items(&self, user_id: &str, item_ids: &[String]) -> Result<HashMap<String, Vec<TagDto>>, DomainError>;
+}
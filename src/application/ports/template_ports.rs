@@ -0,0 +1,60 @@
+use async_trait::async_trait;
+
+use crate::application::dtos::file_dto::FileDto;
+use crate::application::dtos::template_dto::{NewFromTemplateDto, TemplateDto};
+use crate::common::errors::DomainError;
+use crate::domain::services::i18n_service::Locale;
+
+/// Template file kept in the administrator's template store
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredTemplate {
+    pub name: String,
+    pub size: u64,
+}
+
+/// Secondary port for the templates managed by administrators
+#[async_trait]
+pub trait TemplateStorePort: Send + Sync + 'static {
+    /// Lists the stored templates, sorted by name
+    async fn list_templates(&self) -> Result<Vec<StoredTemplate>, DomainError>;
+
+    /// Reads the content of a template
+    async fn read_template(&self, name: &str) -> Result<Vec<u8>, DomainError>;
+
+    /// Adds or replaces a template
+    async fn save_template(&self, name: &str, content: &[u8]) -> Result<StoredTemplate, DomainError>;
+
+    /// Removes a template; returns false if it did not exist
+    async fn delete_template(&self, name: &str) -> Result<bool, DomainError>;
+}
+
+/// User the placeholders of a new document are filled in for
+#[derive(Debug, Clone)]
+pub struct TemplateUser {
+    pub username: String,
+    pub email: String,
+}
+
+/// Primary port for the template gallery of the "New document" menu.
+///
+/// Templates come from the administrator's store and from the user's own
+/// templates folder. Creating a document copies the template and replaces
+/// the placeholders `{{date}}`, `{{time}}`, `{{username}}`, `{{email}}` and
+/// `{{title}}` in text files and in the XML parts of office documents.
+#[async_trait]
+pub trait TemplateUseCase: Send + Sync + 'static {
+    /// Lists the templates available to a user, global ones first
+    async fn list_templates(&self, username: &str) -> Result<Vec<TemplateDto>, DomainError>;
+
+    /// Creates a document from a template under a free name
+    async fn create_from_template(&self, user: &TemplateUser, request: NewFromTemplateDto, locale: Locale) -> Result<FileDto, DomainError>;
+
+    /// Lists the templates managed by administrators
+    async fn list_global_templates(&self) -> Result<Vec<TemplateDto>, DomainError>;
+
+    /// Adds or replaces a template offered to every user
+    async fn save_global_template(&self, name: &str, content: &[u8]) -> Result<TemplateDto, DomainError>;
+
+    /// Removes a template offered to every user
+    async fn delete_global_template(&self, name: &str) -> Result<(), DomainError>;
+}
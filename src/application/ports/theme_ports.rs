@@ -0,0 +1,30 @@
+use async_trait::async_trait;
+
+use crate::application::dtos::theme_dto::{ThemeDto, ThemeLogoDto, UpdateThemeDto};
+use crate::common::errors::DomainError;
+
+/// Primary port for instance branding. Reads are cached, since the theme is
+/// fetched on every page load and changes rarely
+#[async_trait]
+pub trait ThemeUseCase: Send + Sync + 'static {
+    /// Current theme; the built-in OxiCloud branding until an administrator changes it
+    async fn get_theme(&self) -> ThemeDto;
+
+    /// Stylesheet overriding the colors of the web UI, and the theme version
+    async fn stylesheet(&self) -> (String, i64);
+
+    /// Custom logo, if one was uploaded
+    async fn get_logo(&self) -> Option<ThemeLogoDto>;
+
+    /// Changes the name, colors or login message
+    async fn update_theme(&self, update: UpdateThemeDto) -> Result<ThemeDto, DomainError>;
+
+    /// Replaces the logo with an image of one of the supported formats
+    async fn set_logo(&self, content_type: &str, data: Vec<u8>) -> Result<ThemeDto, DomainError>;
+
+    /// Goes back to the built-in logo
+    async fn remove_logo(&self) -> Result<ThemeDto, DomainError>;
+
+    /// Restores the built-in branding, logo included
+    async fn reset_theme(&self) -> Result<ThemeDto, DomainError>;
+}
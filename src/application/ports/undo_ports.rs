@@ -0,0 +1,42 @@
+use async_trait::async_trait;
+
+use crate::application::dtos::undo_dto::{UndoResultDto, UndoTokenDto};
+use crate::common::errors::DomainError;
+
+/// How to reverse a completed destructive operation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UndoAction {
+    /// Restores an item that was moved to the trash of `trash_user_id`
+    RestoreFromTrash {
+        item_id: String,
+        item_type: String,
+        trash_user_id: String,
+    },
+    /// Moves a file back to the folder it was in (`None` is the root)
+    MoveFile {
+        file_id: String,
+        folder_id: Option<String>,
+    },
+    /// Moves a folder back under its previous parent (`None` is the root)
+    MoveFolder {
+        folder_id: String,
+        parent_id: Option<String>,
+    },
+    /// Gives a folder back its previous name
+    RenameFolder {
+        folder_id: String,
+        name: String,
+    },
+}
+
+/// Primary port for undoing deletes, moves and renames for a few minutes
+/// after they happen
+#[async_trait]
+pub trait UndoUseCase: Send + Sync + 'static {
+    /// Registers how to reverse an operation that just succeeded and returns
+    /// the token that triggers it. Only `user_id` can use the token.
+    async fn register(&self, user_id: Option<&str>, action: UndoAction) -> UndoTokenDto;
+
+    /// Reverses the operation of a token; each token works once
+    async fn undo(&self, user_id: Option<&str>, token: &str) -> Result<UndoResultDto, DomainError>;
+}
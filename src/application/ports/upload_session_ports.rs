@@ -0,0 +1,63 @@
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::Stream;
+
+use crate::application::dtos::file_dto::FileDto;
+use crate::application::dtos::upload_session_dto::{CreateUploadSessionDto, UploadRecoveryReport, UploadSessionDto};
+use crate::common::errors::DomainError;
+
+/// Request body streamed into an upload session
+pub type ChunkStream = Pin<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>>;
+
+/// Primary port for chunked uploads that can be resumed after a restart
+#[async_trait]
+pub trait UploadSessionUseCase: Send + Sync + 'static {
+    /// Starts a new upload session
+    async fn create_session(&self, user_id: &str, dto: CreateUploadSessionDto) -> Result<UploadSessionDto, DomainError>;
+
+    /// Returns the current state of a session
+    async fn get_session(&self, user_id: &str, session_id: &str) -> Result<UploadSessionDto, DomainError>;
+
+    /// Appends a chunk that must start at the current offset
+    async fn append_chunk(
+        &self,
+        user_id: &str,
+        session_id: &str,
+        offset: u64,
+        data: Bytes,
+    ) -> Result<UploadSessionDto, DomainError>;
+
+    /// Streams a chunk that must start at the current offset. Bytes received
+    /// before the stream fails are kept, so the client can resume after them
+    async fn append_stream(
+        &self,
+        user_id: &str,
+        session_id: &str,
+        offset: u64,
+        data: ChunkStream,
+    ) -> Result<UploadSessionDto, DomainError>;
+
+    /// Turns a fully received session into a file
+    async fn complete_session(&self, user_id: &str, session_id: &str) -> Result<FileDto, DomainError>;
+
+    /// Abandons a session and removes its temporary data
+    async fn cancel_session(&self, user_id: &str, session_id: &str) -> Result<(), DomainError>;
+
+    /// Checks persisted sessions against their temporary files after a restart
+    async fn recover_sessions(&self) -> Result<UploadRecoveryReport, DomainError>;
+
+    /// Removes sessions that received no data before their expiry
+    async fn expire_sessions(&self) -> Result<usize, DomainError>;
+
+    /// Directory holding the temporary files of the sessions
+    fn temp_dir(&self) -> &Path;
+
+    /// Temporary files that still belong to a session
+    async fn list_temp_files(&self) -> Result<Vec<PathBuf>, DomainError>;
+
+    /// Largest file size a session may declare
+    fn max_upload_size(&self) -> u64;
+}
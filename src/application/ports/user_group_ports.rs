@@ -0,0 +1,37 @@
+use async_trait::async_trait;
+
+use crate::application::dtos::user_group_dto::{CreateUserGroupDto, UpdateUserGroupDto, UserGroupDto};
+use crate::application::ports::auth_ports::Caller;
+use crate::common::errors::DomainError;
+
+/// Primary port for user groups and their membership.
+///
+/// Any signed-in user can list the groups, to pick one as the grantee of a
+/// share; creating, changing and deleting groups and their members needs
+/// the `ManageUsers` permission.
+#[async_trait]
+pub trait UserGroupUseCase: Send + Sync + 'static {
+    /// Lists every group; members are only included for admins
+    async fn list_groups(&self, caller: &Caller) -> Result<Vec<UserGroupDto>, DomainError>;
+
+    /// Groups the caller is a member of, with their members
+    async fn my_groups(&self, caller: &Caller) -> Result<Vec<UserGroupDto>, DomainError>;
+
+    /// A group with its members; admins and members only
+    async fn get_group(&self, caller: &Caller, group_id: &str) -> Result<UserGroupDto, DomainError>;
+
+    /// Creates a group, optionally with its first members
+    async fn create_group(&self, caller: &Caller, request: CreateUserGroupDto) -> Result<UserGroupDto, DomainError>;
+
+    /// Renames a group or changes its description
+    async fn update_group(&self, caller: &Caller, group_id: &str, request: UpdateUserGroupDto) -> Result<UserGroupDto, DomainError>;
+
+    /// Deletes a group; whatever was shared with it stops being shared
+    async fn delete_group(&self, caller: &Caller, group_id: &str) -> Result<(), DomainError>;
+
+    /// Adds a user to a group
+    async fn add_member(&self, caller: &Caller, group_id: &str, user_id: &str) -> Result<UserGroupDto, DomainError>;
+
+    /// Removes a user from a group
+    async fn remove_member(&self, caller: &Caller, group_id: &str, user_id: &str) -> Result<UserGroupDto, DomainError>;
+}
@@ -0,0 +1,30 @@
+use async_trait::async_trait;
+
+use crate::application::dtos::execution_plan_dto::ExecutionPlanDto;
+use crate::application::dtos::user_dto::{QuarantinedUserDto, UserDto};
+use crate::common::errors::DomainError;
+
+/// Primary port for deleting users in two steps.
+///
+/// Deleting a user disables the account: logins, app passwords and the
+/// user's shared links stop working, but the data is kept for a configurable
+/// period. Within that period an administrator can reactivate the user or
+/// finalize the deletion; once it ends the data is purged automatically.
+#[async_trait]
+pub trait UserQuarantineUseCase: Send + Sync + 'static {
+    /// Disables a user and keeps their data until the retention period ends
+    async fn quarantine_user(&self, admin_id: &str, admin_username: &str, user_id: &str) -> Result<QuarantinedUserDto, DomainError>;
+
+    /// Users whose deletion can still be undone, the first to be purged first
+    async fn list_quarantined(&self) -> Result<Vec<QuarantinedUserDto>, DomainError>;
+
+    /// Undoes the deletion of a quarantined user
+    async fn reactivate_user(&self, user_id: &str) -> Result<UserDto, DomainError>;
+
+    /// Removes a quarantined user and their data right away. With `dry_run`
+    /// nothing is removed and the plan lists what would be
+    async fn purge_user(&self, user_id: &str, dry_run: bool) -> Result<ExecutionPlanDto, DomainError>;
+
+    /// Purges the users whose retention period has ended; returns how many
+    async fn purge_expired(&self) -> Result<usize, DomainError>;
+}
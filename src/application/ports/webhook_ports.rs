@@ -0,0 +1,38 @@
+use async_trait::async_trait;
+
+use crate::application::dtos::pagination::{PaginatedResponseDto, PaginationRequestDto};
+use crate::application::dtos::webhook_dto::{
+    CreateWebhookDto, UpdateWebhookDto, WebhookCreatedDto, WebhookDeliveryDto, WebhookDto,
+};
+use crate::common::errors::DomainError;
+
+/// Primary port for admins to manage webhooks and read their deliveries
+#[async_trait]
+pub trait WebhookUseCase: Send + Sync + 'static {
+    /// Registers a webhook and returns it with its signing secret
+    async fn create_webhook(&self, dto: CreateWebhookDto) -> Result<WebhookCreatedDto, DomainError>;
+
+    async fn list_webhooks(&self) -> Result<Vec<WebhookDto>, DomainError>;
+
+    async fn get_webhook(&self, id: &str) -> Result<WebhookDto, DomainError>;
+
+    async fn update_webhook(&self, id: &str, dto: UpdateWebhookDto) -> Result<WebhookDto, DomainError>;
+
+    /// Removes a webhook together with its delivery log
+    async fn delete_webhook(&self, id: &str) -> Result<(), DomainError>;
+
+    /// Deliveries of a webhook, newest first
+    async fn list_deliveries(
+        &self,
+        webhook_id: &str,
+        pagination: PaginationRequestDto,
+    ) -> Result<PaginatedResponseDto<WebhookDeliveryDto>, DomainError>;
+}
+
+/// Secondary port that POSTs a delivery to a webhook URL
+#[async_trait]
+pub trait WebhookSenderPort: Send + Sync + 'static {
+    /// Sends a JSON body with the given headers and returns the HTTP status
+    /// of the response. Errors mean no response was received at all
+    async fn send(&self, url: &str, headers: &[(&str, String)], body: &str) -> Result<u16, DomainError>;
+}
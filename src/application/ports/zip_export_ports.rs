@@ -0,0 +1,36 @@
+use async_trait::async_trait;
+
+use crate::application::dtos::zip_export_dto::{CreateZipExportDto, ZipExportJobDto};
+use crate::application::ports::upload_session_ports::ChunkStream;
+use crate::common::errors::DomainError;
+
+/// Finished archive opened through its signed download link
+pub struct ZipExportDownload {
+    pub file_name: String,
+    pub size: u64,
+    pub content: ChunkStream,
+}
+
+/// Primary port for ZIP exports of selections too large to stream in one
+/// request: the archive is built in the background, checkpointed so it
+/// survives restarts, and downloaded through a signed, expiring link
+#[async_trait]
+pub trait ZipExportUseCase: Send + Sync + 'static {
+    /// Queues the export of a selection
+    async fn create_job(&self, user_id: &str, dto: CreateZipExportDto) -> Result<ZipExportJobDto, DomainError>;
+
+    /// Returns the progress of a job, with its download link once completed
+    async fn get_job(&self, user_id: &str, job_id: &str) -> Result<ZipExportJobDto, DomainError>;
+
+    /// Jobs of a user, newest first
+    async fn list_jobs(&self, user_id: &str) -> Result<Vec<ZipExportJobDto>, DomainError>;
+
+    /// Queues a failed job again; it continues from its last checkpoint
+    async fn resume_job(&self, user_id: &str, job_id: &str) -> Result<ZipExportJobDto, DomainError>;
+
+    /// Stops a job and removes its archive
+    async fn cancel_job(&self, user_id: &str, job_id: &str) -> Result<(), DomainError>;
+
+    /// Opens a completed archive if `token` is a valid link for it
+    async fn open_download(&self, job_id: &str, token: &str) -> Result<ZipExportDownload, DomainError>;
+}
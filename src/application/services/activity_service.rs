@@ -0,0 +1,141 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::application::dtos::activity_dto::{ActivityDto, ActivityQueryDto, ActivityTargetDto};
+use crate::application::dtos::pagination::{PaginatedResponseDto, PaginationRequestDto};
+use crate::application::ports::activity_ports::ActivityUseCase;
+use crate::application::ports::inbound::{FileUseCase, FolderUseCase};
+use crate::common::errors::DomainError;
+use crate::domain::entities::activity::{Activity, ActivityAction, ActivityTargetKind};
+use crate::domain::repositories::activity_repository::{ActivityFilter, ActivityRepository};
+use crate::domain::services::ownership_service::owner_of_path;
+
+/// Entradas por página si el cliente no pide otro tamaño
+const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// Actividad de los usuarios sobre sus archivos y carpetas.
+///
+/// Cada subida, modificación, renombrado, borrado, compartición o
+/// recuperación queda guardada con quién la hizo, sobre qué y cuándo; las
+/// entradas llegan del bus de eventos de dominio, sea cual sea el protocolo. Cada usuario ve lo que ha
+/// hecho él, y el dueño de una carpeta ve además lo que cualquiera ha hecho
+/// dentro de ella.
+pub struct ActivityService {
+    repository: Arc<dyn ActivityRepository>,
+    files: Arc<dyn FileUseCase>,
+    folders: Arc<dyn FolderUseCase>,
+}
+
+impl ActivityService {
+    pub fn new(
+        repository: Arc<dyn ActivityRepository>,
+        files: Arc<dyn FileUseCase>,
+        folders: Arc<dyn FolderUseCase>,
+    ) -> Self {
+        Self {
+            repository,
+            files,
+            folders,
+        }
+    }
+
+    /// Página de la actividad que cumple el filtro base y los de la consulta
+    async fn list(&self, mut filter: ActivityFilter, query: ActivityQueryDto) -> Result<PaginatedResponseDto<ActivityDto>, DomainError> {
+        if let (Some(since), Some(until)) = (query.since, query.until) {
+            if since >= until {
+                return Err(DomainError::validation_error("since must be earlier than until"));
+            }
+        }
+        filter.action = query.action;
+        filter.target_kind = query.kind;
+        filter.since = query.since;
+        filter.until = query.until;
+
+        let pagination = PaginationRequestDto {
+            page: query.page,
+            page_size: query.page_size.unwrap_or(DEFAULT_PAGE_SIZE),
+        }.validate_and_adjust();
+
+        let (activities, total) = self.repository
+            .list(&filter, pagination.limit() as i64, pagination.offset() as i64)
+            .await?;
+
+        Ok(PaginatedResponseDto::new(
+            activities.into_iter().map(ActivityDto::from).collect(),
+            pagination.page,
+            pagination.page_size,
+            total.max(0) as usize,
+        ))
+    }
+}
+
+#[async_trait]
+impl ActivityUseCase for ActivityService {
+    async fn describe_target(&self, kind: ActivityTargetKind, id: &str) -> Option<ActivityTargetDto> {
+        match kind {
+            ActivityTargetKind::File => self.files.get_file(id).await.ok().map(|file| ActivityTargetDto {
+                kind,
+                id: file.id,
+                name: file.name,
+                folder_id: file.folder_id,
+            }),
+            ActivityTargetKind::Folder => self.folders.get_folder(id).await.ok().map(|folder| ActivityTargetDto {
+                kind,
+                id: folder.id,
+                name: folder.name,
+                folder_id: folder.parent_id,
+            }),
+        }
+    }
+
+    async fn record(
+        &self,
+        actor_id: &str,
+        actor_name: &str,
+        action: ActivityAction,
+        target: ActivityTargetDto,
+        details: Option<String>,
+    ) {
+        let activity = Activity::new(
+            actor_id.to_string(),
+            actor_name.to_string(),
+            action,
+            target.kind,
+            target.id,
+            target.name,
+            target.folder_id,
+            details,
+        );
+        if let Err(e) = self.repository.record(&activity).await {
+            tracing::warn!("No se pudo registrar la actividad {} de {}: {}", action.as_str(), actor_name, e);
+        }
+    }
+
+    async fn list_user_activity(&self, user_id: &str, query: ActivityQueryDto) -> Result<PaginatedResponseDto<ActivityDto>, DomainError> {
+        let filter = ActivityFilter {
+            actor_id: Some(user_id.to_string()),
+            ..Default::default()
+        };
+        self.list(filter, query).await
+    }
+
+    async fn list_folder_activity(
+        &self,
+        username: &str,
+        is_admin: bool,
+        folder_id: &str,
+        query: ActivityQueryDto,
+    ) -> Result<PaginatedResponseDto<ActivityDto>, DomainError> {
+        let folder = self.folders.get_folder(folder_id).await?;
+        if !is_admin && owner_of_path(&folder.path) != Some(username) {
+            return Err(DomainError::access_denied("Folder", "Only the owner can see the activity of this folder"));
+        }
+
+        let filter = ActivityFilter {
+            folder_id: Some(folder.id),
+            ..Default::default()
+        };
+        self.list(filter, query).await
+    }
+}
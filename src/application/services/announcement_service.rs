@@ -0,0 +1,341 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use crate::application::dtos::announcement_dto::{AcknowledgmentDto, AnnouncementDto, SaveAnnouncementDto, UserAnnouncementDto};
+use crate::application::ports::announcement_ports::AnnouncementUseCase;
+use crate::common::errors::DomainError;
+use crate::domain::entities::announcement::{Announcement, MAX_MESSAGE_LENGTH, MAX_TITLE_LENGTH};
+use crate::domain::repositories::announcement_repository::AnnouncementRepository;
+
+/// Servicio de anuncios de administración.
+///
+/// Los administradores publican mensajes con una gravedad, una ventana de
+/// tiempo y unos grupos destinatarios; los usuarios los ven en el centro de
+/// notificaciones y en el banner, y al marcarlos como leídos alimentan la
+/// tasa de lectura que consultan los administradores.
+pub struct AnnouncementService {
+    repository: Arc<dyn AnnouncementRepository>,
+}
+
+impl AnnouncementService {
+    pub fn new(repository: Arc<dyn AnnouncementRepository>) -> Self {
+        Self { repository }
+    }
+
+    /// Comprueba y normaliza los datos de un anuncio
+    fn validate(request: SaveAnnouncementDto) -> Result<SaveAnnouncementDto, DomainError> {
+        let title = request.title.trim().to_string();
+        if title.is_empty() || title.chars().count() > MAX_TITLE_LENGTH {
+            return Err(DomainError::validation_error(format!(
+                "The title must have between 1 and {} characters", MAX_TITLE_LENGTH
+            )));
+        }
+
+        let message = request.message.trim().to_string();
+        if message.is_empty() || message.chars().count() > MAX_MESSAGE_LENGTH {
+            return Err(DomainError::validation_error(format!(
+                "The message must have between 1 and {} characters", MAX_MESSAGE_LENGTH
+            )));
+        }
+
+        let mut target_groups: Vec<String> = Vec::new();
+        for group in request.target_groups {
+            let group = group.trim().to_string();
+            if group.is_empty() {
+                return Err(DomainError::validation_error("Target group names cannot be empty"));
+            }
+            if !target_groups.contains(&group) {
+                target_groups.push(group);
+            }
+        }
+
+        Ok(SaveAnnouncementDto { title, message, target_groups, ..request })
+    }
+
+    /// Anuncios activos dirigidos al usuario, los más graves y recientes primero
+    async fn visible_announcements(&self, groups: &[String]) -> Result<Vec<Announcement>, DomainError> {
+        let now = Utc::now();
+        let mut announcements: Vec<Announcement> = self.repository.list_active(now).await?
+            .into_iter()
+            .filter(|announcement| announcement.is_active_at(now) && announcement.targets(groups))
+            .collect();
+        announcements.sort_by(|a, b| b.severity.cmp(&a.severity).then_with(|| b.starts_at.cmp(&a.starts_at)));
+        Ok(announcements)
+    }
+
+    async fn find(&self, announcement_id: &str) -> Result<Announcement, DomainError> {
+        self.repository.get_announcement(announcement_id).await?
+            .ok_or_else(|| DomainError::not_found("Announcement", announcement_id))
+    }
+
+    async fn to_dto(&self, announcement: Announcement) -> Result<AnnouncementDto, DomainError> {
+        let acknowledgment = self.repository.acknowledgment(&announcement).await?;
+        Ok(AnnouncementDto::new(announcement, acknowledgment, Utc::now()))
+    }
+}
+
+#[async_trait]
+impl AnnouncementUseCase for AnnouncementService {
+    async fn list_for_user(&self, user_id: &str, groups: &[String]) -> Result<Vec<UserAnnouncementDto>, DomainError> {
+        let announcements = self.visible_announcements(groups).await?;
+        let ids: Vec<String> = announcements.iter().map(|announcement| announcement.id.clone()).collect();
+        let read: HashSet<String> = self.repository.read_announcements(user_id, &ids).await?.into_iter().collect();
+
+        Ok(announcements.into_iter()
+            .map(|announcement| {
+                let is_read = read.contains(&announcement.id);
+                UserAnnouncementDto::new(announcement, is_read)
+            })
+            .collect())
+    }
+
+    async fn banner(&self, user_id: &str, groups: &[String]) -> Result<Option<UserAnnouncementDto>, DomainError> {
+        Ok(self.list_for_user(user_id, groups).await?
+            .into_iter()
+            .find(|announcement| !announcement.read))
+    }
+
+    async fn mark_read(&self, user_id: &str, groups: &[String], announcement_id: &str) -> Result<(), DomainError> {
+        // Solo cuentan las lecturas de quien puede ver el anuncio
+        let announcement = self.find(announcement_id).await?;
+        if !announcement.is_active_at(Utc::now()) || !announcement.targets(groups) {
+            return Err(DomainError::not_found("Announcement", announcement_id));
+        }
+        self.repository.mark_read(announcement_id, user_id, Utc::now()).await
+    }
+
+    async fn list_announcements(&self) -> Result<Vec<AnnouncementDto>, DomainError> {
+        let mut result = Vec::new();
+        for announcement in self.repository.list_announcements().await? {
+            result.push(self.to_dto(announcement).await?);
+        }
+        Ok(result)
+    }
+
+    async fn create_announcement(&self, created_by: &str, request: SaveAnnouncementDto) -> Result<AnnouncementDto, DomainError> {
+        let request = Self::validate(request)?;
+        let starts_at = request.starts_at.unwrap_or_else(Utc::now);
+        if request.ends_at.is_some_and(|ends_at| ends_at <= starts_at) {
+            return Err(DomainError::validation_error("ends_at must be later than starts_at"));
+        }
+
+        let announcement = Announcement::new(
+            request.title,
+            request.message,
+            request.severity,
+            starts_at,
+            request.ends_at,
+            request.target_groups,
+            Some(created_by.to_string()),
+        );
+        self.repository.save_announcement(&announcement).await?;
+        tracing::info!("Announcement {} created by {}", announcement.id, created_by);
+        self.to_dto(announcement).await
+    }
+
+    async fn update_announcement(&self, announcement_id: &str, request: SaveAnnouncementDto) -> Result<AnnouncementDto, DomainError> {
+        let request = Self::validate(request)?;
+        let stored = self.find(announcement_id).await?;
+        let starts_at = request.starts_at.unwrap_or(stored.starts_at);
+        if request.ends_at.is_some_and(|ends_at| ends_at <= starts_at) {
+            return Err(DomainError::validation_error("ends_at must be later than starts_at"));
+        }
+
+        let announcement = Announcement {
+            title: request.title,
+            message: request.message,
+            severity: request.severity,
+            starts_at,
+            ends_at: request.ends_at,
+            target_groups: request.target_groups,
+            updated_at: Utc::now(),
+            ..stored
+        };
+        self.repository.save_announcement(&announcement).await?;
+        self.to_dto(announcement).await
+    }
+
+    async fn delete_announcement(&self, announcement_id: &str) -> Result<(), DomainError> {
+        if !self.repository.delete_announcement(announcement_id).await? {
+            return Err(DomainError::not_found("Announcement", announcement_id));
+        }
+        Ok(())
+    }
+
+    async fn acknowledgment(&self, announcement_id: &str) -> Result<AcknowledgmentDto, DomainError> {
+        let announcement = self.find(announcement_id).await?;
+        Ok(self.repository.acknowledgment(&announcement).await?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use chrono::{DateTime, Duration};
+
+    use crate::common::errors::ErrorKind;
+    use crate::domain::entities::announcement::{AnnouncementAcknowledgment, AnnouncementSeverity};
+    use crate::domain::repositories::announcement_repository::AnnouncementRepositoryResult;
+
+    /// Anuncios en memoria; cada usuario de `users` pertenece a un grupo
+    struct InMemoryAnnouncementRepository {
+        announcements: Mutex<Vec<Announcement>>,
+        reads: Mutex<HashMap<(String, String), DateTime<Utc>>>,
+        users: Vec<(String, String)>,
+    }
+
+    impl InMemoryAnnouncementRepository {
+        fn new(users: &[(&str, &str)]) -> Self {
+            Self {
+                announcements: Mutex::new(Vec::new()),
+                reads: Mutex::new(HashMap::new()),
+                users: users.iter().map(|(id, group)| (id.to_string(), group.to_string())).collect(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl AnnouncementRepository for InMemoryAnnouncementRepository {
+        async fn list_announcements(&self) -> AnnouncementRepositoryResult<Vec<Announcement>> {
+            Ok(self.announcements.lock().unwrap().clone())
+        }
+
+        async fn list_active(&self, now: DateTime<Utc>) -> AnnouncementRepositoryResult<Vec<Announcement>> {
+            Ok(self.announcements.lock().unwrap().iter().filter(|a| a.is_active_at(now)).cloned().collect())
+        }
+
+        async fn get_announcement(&self, id: &str) -> AnnouncementRepositoryResult<Option<Announcement>> {
+            Ok(self.announcements.lock().unwrap().iter().find(|a| a.id == id).cloned())
+        }
+
+        async fn save_announcement(&self, announcement: &Announcement) -> AnnouncementRepositoryResult<()> {
+            let mut announcements = self.announcements.lock().unwrap();
+            announcements.retain(|a| a.id != announcement.id);
+            announcements.push(announcement.clone());
+            Ok(())
+        }
+
+        async fn delete_announcement(&self, id: &str) -> AnnouncementRepositoryResult<bool> {
+            let mut announcements = self.announcements.lock().unwrap();
+            let before = announcements.len();
+            announcements.retain(|a| a.id != id);
+            self.reads.lock().unwrap().retain(|(announcement_id, _), _| announcement_id != id);
+            Ok(announcements.len() < before)
+        }
+
+        async fn mark_read(&self, id: &str, user_id: &str, read_at: DateTime<Utc>) -> AnnouncementRepositoryResult<()> {
+            self.reads.lock().unwrap().entry((id.to_string(), user_id.to_string())).or_insert(read_at);
+            Ok(())
+        }
+
+        async fn read_announcements(&self, user_id: &str, ids: &[String]) -> AnnouncementRepositoryResult<Vec<String>> {
+            Ok(self.reads.lock().unwrap().keys()
+                .filter(|(id, user)| user == user_id && ids.contains(id))
+                .map(|(id, _)| id.clone())
+                .collect())
+        }
+
+        async fn acknowledgment(&self, announcement: &Announcement) -> AnnouncementRepositoryResult<AnnouncementAcknowledgment> {
+            let reads = self.reads.lock().unwrap();
+            let targeted: Vec<&String> = self.users.iter()
+                .filter(|(_, group)| announcement.targets(std::slice::from_ref(group)))
+                .map(|(id, _)| id)
+                .collect();
+            Ok(AnnouncementAcknowledgment {
+                read_count: targeted.iter().filter(|id| reads.contains_key(&(announcement.id.clone(), id.to_string()))).count() as i64,
+                target_count: targeted.len() as i64,
+            })
+        }
+    }
+
+    fn request(title: &str, severity: AnnouncementSeverity, groups: &[&str]) -> SaveAnnouncementDto {
+        SaveAnnouncementDto {
+            title: title.to_string(),
+            message: format!("{} details", title),
+            severity,
+            starts_at: None,
+            ends_at: None,
+            target_groups: groups.iter().map(|g| g.to_string()).collect(),
+        }
+    }
+
+    fn groups(group: &str) -> Vec<String> {
+        vec![group.to_string()]
+    }
+
+    #[tokio::test]
+    async fn test_banner_shows_most_severe_unread_announcement() {
+        let repository = Arc::new(InMemoryAnnouncementRepository::new(&[("alice", "user"), ("root", "admin")]));
+        let service = AnnouncementService::new(repository);
+
+        service.create_announcement("root", request("Welcome", AnnouncementSeverity::Info, &[])).await.unwrap();
+        let outage = service.create_announcement("root", request("Outage", AnnouncementSeverity::Critical, &[])).await.unwrap();
+        service.create_announcement("root", request("Admins only", AnnouncementSeverity::Critical, &["admin"])).await.unwrap();
+
+        let feed = service.list_for_user("alice", &groups("user")).await.unwrap();
+        let titles: Vec<_> = feed.iter().map(|a| a.title.as_str()).collect();
+        assert_eq!(titles, ["Outage", "Welcome"]);
+
+        let banner = service.banner("alice", &groups("user")).await.unwrap().unwrap();
+        assert_eq!(banner.id, outage.id);
+
+        service.mark_read("alice", &groups("user"), &outage.id).await.unwrap();
+        let banner = service.banner("alice", &groups("user")).await.unwrap().unwrap();
+        assert_eq!(banner.title, "Welcome");
+        assert!(service.list_for_user("alice", &groups("user")).await.unwrap()[0].read);
+    }
+
+    #[tokio::test]
+    async fn test_acknowledgment_rate_counts_targeted_users() {
+        let repository = Arc::new(InMemoryAnnouncementRepository::new(&[("alice", "user"), ("bob", "user"), ("root", "admin")]));
+        let service = AnnouncementService::new(repository);
+
+        let notice = service.create_announcement("root", request("Notice", AnnouncementSeverity::Warning, &["user"])).await.unwrap();
+        assert_eq!(notice.acknowledgment.target_count, 2);
+
+        service.mark_read("alice", &groups("user"), &notice.id).await.unwrap();
+        service.mark_read("alice", &groups("user"), &notice.id).await.unwrap();
+        let err = service.mark_read("root", &groups("admin"), &notice.id).await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::NotFound);
+
+        let acknowledgment = service.acknowledgment(&notice.id).await.unwrap();
+        assert_eq!((acknowledgment.read_count, acknowledgment.target_count), (1, 2));
+        assert_eq!(acknowledgment.rate, 0.5);
+    }
+
+    #[tokio::test]
+    async fn test_window_and_validation() {
+        let repository = Arc::new(InMemoryAnnouncementRepository::new(&[("alice", "user")]));
+        let service = AnnouncementService::new(repository);
+
+        let mut scheduled = request("Upgrade", AnnouncementSeverity::Info, &[]);
+        scheduled.starts_at = Some(Utc::now() + Duration::days(1));
+        let scheduled = service.create_announcement("root", scheduled).await.unwrap();
+        assert!(!scheduled.active);
+        assert!(service.list_for_user("alice", &groups("user")).await.unwrap().is_empty());
+        assert!(service.mark_read("alice", &groups("user"), &scheduled.id).await.is_err());
+
+        let mut backwards = request("Backwards", AnnouncementSeverity::Info, &[]);
+        backwards.ends_at = Some(Utc::now() - Duration::hours(1));
+        let err = service.create_announcement("root", backwards).await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidInput);
+
+        let err = service.create_announcement("root", request("  ", AnnouncementSeverity::Info, &[])).await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidInput);
+
+        // Updating keeps the author and creation date
+        let updated = service.update_announcement(&scheduled.id, request("Upgrade moved", AnnouncementSeverity::Warning, &[" user ", "user"])).await.unwrap();
+        assert_eq!(updated.created_by.as_deref(), Some("root"));
+        assert_eq!(updated.starts_at, scheduled.starts_at);
+        assert_eq!(updated.target_groups, ["user"]);
+
+        service.delete_announcement(&scheduled.id).await.unwrap();
+        assert_eq!(service.delete_announcement(&scheduled.id).await.unwrap_err().kind, ErrorKind::NotFound);
+    }
+}
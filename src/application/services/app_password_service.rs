@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use crate::application::dtos::user_dto::{AppPasswordCreatedDto, AppPasswordDto, CreateAppPasswordDto};
+use crate::application::ports::auth_ports::AppPasswordStoragePort;
+use crate::common::errors::DomainError;
+use crate::domain::entities::app_password::AppPassword;
+
+/// Servicio de contraseñas de aplicación: credenciales por dispositivo, con
+/// nombre y fecha de último uso, que se revocan una a una sin tocar la
+/// contraseña de la cuenta.
+pub struct AppPasswordService {
+    storage: Arc<dyn AppPasswordStoragePort>,
+}
+
+impl AppPasswordService {
+    pub fn new(storage: Arc<dyn AppPasswordStoragePort>) -> Self {
+        Self { storage }
+    }
+
+    /// Genera una contraseña de aplicación; la respuesta es la única vez que
+    /// se ve la contraseña
+    pub async fn create(&self, user_id: &str, dto: CreateAppPasswordDto) -> Result<AppPasswordCreatedDto, DomainError> {
+        let (app_password, password) = AppPassword::generate(user_id, &dto.label)?;
+        let app_password = self.storage.create_app_password(app_password).await?;
+
+        tracing::info!("Contraseña de aplicación {} creada para el usuario {}", app_password.id, user_id);
+        Ok(AppPasswordCreatedDto {
+            app_password: AppPasswordDto::from(app_password),
+            password,
+        })
+    }
+
+    /// Lista las contraseñas de aplicación de un usuario
+    pub async fn list(&self, user_id: &str) -> Result<Vec<AppPasswordDto>, DomainError> {
+        let app_passwords = self.storage.list_app_passwords(user_id).await?;
+        Ok(app_passwords.into_iter().map(AppPasswordDto::from).collect())
+    }
+
+    /// Revoca una contraseña de aplicación de un usuario
+    pub async fn revoke(&self, user_id: &str, id: &str) -> Result<(), DomainError> {
+        if !self.storage.delete_app_password(user_id, id).await? {
+            return Err(DomainError::not_found("AppPassword", id));
+        }
+        tracing::info!("Contraseña de aplicación {} revocada para el usuario {}", id, user_id);
+        Ok(())
+    }
+
+    /// Comprueba si `password` es una de las contraseñas de aplicación del
+    /// usuario y, si lo es, anota su uso
+    pub async fn verify(&self, user_id: &str, password: &str) -> Result<bool, DomainError> {
+        let app_passwords = self.storage.list_app_passwords(user_id).await?;
+        let Some(app_password) = app_passwords.iter().find(|app_password| app_password.verify(password)) else {
+            return Ok(false);
+        };
+        if let Err(e) = self.storage.touch_app_password(&app_password.id).await {
+            tracing::warn!("No se pudo anotar el uso de la contraseña de aplicación {}: {}", app_password.id, e);
+        }
+        Ok(true)
+    }
+}
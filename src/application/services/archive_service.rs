@@ -0,0 +1,112 @@
+use std::collections::HashSet;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use tracing::{debug, info};
+
+use crate::application::dtos::folder_dto::FolderDto;
+use crate::application::ports::inbound::{FileUseCase, FolderUseCase};
+use crate::common::errors::DomainError;
+use crate::infrastructure::services::zip_stream_writer::ZipStreamWriter;
+
+/// Bytes del ZIP que se acumulan antes de entregarlos al cliente
+const FLUSH_THRESHOLD: usize = 64 * 1024;
+
+/// Flujo de bytes de un archivo comprimido
+pub type ArchiveStream = Pin<Box<dyn Stream<Item = Result<Bytes, io::Error>> + Send>>;
+
+/// Archivo comprimido de una carpeta, listo para enviarse
+pub struct FolderArchive {
+    /// Nombre sugerido para la descarga
+    pub file_name: String,
+    /// Contenido del ZIP, generado a medida que se consume
+    pub stream: ArchiveStream,
+}
+
+/// Servicio que genera archivos ZIP de carpetas en streaming.
+///
+/// Recorre el árbol de la carpeta a medida que el cliente consume la
+/// respuesta: nunca hay en memoria más que un bloque de un archivo y la parte
+/// del ZIP pendiente de enviar, sea cual sea el tamaño de la carpeta.
+pub struct ArchiveService {
+    folder_service: Arc<dyn FolderUseCase>,
+    file_service: Arc<dyn FileUseCase>,
+}
+
+impl ArchiveService {
+    pub fn new(folder_service: Arc<dyn FolderUseCase>, file_service: Arc<dyn FileUseCase>) -> Self {
+        Self {
+            folder_service,
+            file_service,
+        }
+    }
+
+    /// Prepara el ZIP de una carpeta y de todas sus subcarpetas.
+    ///
+    /// La carpeta se comprueba antes de empezar, de modo que un ID inexistente
+    /// se informa como error; los fallos posteriores interrumpen el flujo.
+    pub async fn folder_archive(&self, folder_id: &str) -> Result<FolderArchive, DomainError> {
+        let folder = self.folder_service.get_folder(folder_id).await?;
+        info!("Generando ZIP de la carpeta: {} (ID: {})", folder.name, folder.id);
+
+        let file_name = format!("{}.zip", folder.name);
+        let stream = Self::stream_folder(self.folder_service.clone(), self.file_service.clone(), folder);
+        Ok(FolderArchive { file_name, stream })
+    }
+
+    fn stream_folder(
+        folder_service: Arc<dyn FolderUseCase>,
+        file_service: Arc<dyn FileUseCase>,
+        root: FolderDto,
+    ) -> ArchiveStream {
+        Box::pin(async_stream::try_stream! {
+            let mut zip = ZipStreamWriter::new();
+            // Carpetas pendientes con su ruta dentro del ZIP
+            let mut pending = vec![(root.name.clone(), root)];
+            // Evita ciclos si el árbol de carpetas estuviera corrupto
+            let mut processed = HashSet::new();
+
+            while let Some((path, folder)) = pending.pop() {
+                if !processed.insert(folder.id.clone()) {
+                    continue;
+                }
+                zip.add_directory(&format!("{}/", path), folder.modified_at)?;
+
+                let files = file_service.list_files(Some(&folder.id)).await.map_err(to_io_error)?;
+                for file in files {
+                    let file_path = format!("{}/{}", path, file.name);
+                    debug!("Añadiendo al ZIP: {}", file_path);
+
+                    let mut content = Box::into_pin(file_service.get_file_stream(&file.id).await.map_err(to_io_error)?);
+                    zip.start_file(&file_path, file.modified_at, file.size)?;
+                    while let Some(chunk) = content.next().await {
+                        zip.write(&chunk?)?;
+                        if zip.buffered_len() >= FLUSH_THRESHOLD {
+                            yield zip.take_output();
+                        }
+                    }
+                    zip.finish_file()?;
+                }
+
+                let subfolders = folder_service.list_folders(Some(&folder.id)).await.map_err(to_io_error)?;
+                for subfolder in subfolders.into_iter().rev() {
+                    pending.push((format!("{}/{}", path, subfolder.name), subfolder));
+                }
+
+                if zip.buffered_len() >= FLUSH_THRESHOLD {
+                    yield zip.take_output();
+                }
+            }
+
+            zip.finish()?;
+            yield zip.take_output();
+        })
+    }
+}
+
+fn to_io_error(err: DomainError) -> io::Error {
+    io::Error::other(err.to_string())
+}
@@ -0,0 +1,139 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::application::dtos::audit_log_dto::{AuditEventDto, AuditLogQueryDto};
+use crate::application::dtos::pagination::{PaginatedResponseDto, PaginationRequestDto};
+use crate::application::ports::audit_ports::{AuditLogPort, AuditLogUseCase};
+use crate::common::errors::DomainError;
+use crate::domain::entities::audit_event::AuditEvent;
+use crate::domain::repositories::audit_log_repository::{AuditLogFilter, AuditLogRepository};
+
+/// Entradas por página si el administrador no pide otro tamaño
+const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Registro de auditoría para los administradores.
+///
+/// A diferencia de la actividad de los usuarios, recoge los sucesos de
+/// seguridad: accesos, accesos fallidos, cambios de rol y lo que hacen los
+/// administradores. Se guarda en base de datos sin posibilidad de modificarlo
+/// y cada entrada se escribe también en el log con el destino `audit`.
+pub struct AuditLogService {
+    repository: Arc<dyn AuditLogRepository>,
+}
+
+impl AuditLogService {
+    pub fn new(repository: Arc<dyn AuditLogRepository>) -> Self {
+        Self { repository }
+    }
+}
+
+#[async_trait]
+impl AuditLogPort for AuditLogService {
+    async fn record(&self, event: AuditEvent) {
+        tracing::info!(
+            target: "audit",
+            "{} de {} sobre {} desde {}: {}",
+            event.action.as_str(),
+            event.actor_name.as_deref().or(event.actor_id.as_deref()).unwrap_or("-"),
+            event.target.as_deref().unwrap_or("-"),
+            event.ip_address.as_deref().unwrap_or("-"),
+            if event.success { "correcto" } else { "fallido" },
+        );
+        if let Err(e) = self.repository.append(&event).await {
+            tracing::error!("No se pudo guardar la entrada de auditoría {}: {}", event.action.as_str(), e);
+        }
+    }
+}
+
+#[async_trait]
+impl AuditLogUseCase for AuditLogService {
+    async fn query(&self, query: AuditLogQueryDto) -> Result<PaginatedResponseDto<AuditEventDto>, DomainError> {
+        if let (Some(since), Some(until)) = (query.since, query.until) {
+            if since >= until {
+                return Err(DomainError::validation_error("since must be earlier than until"));
+            }
+        }
+        let filter = AuditLogFilter {
+            user: query.user.filter(|user| !user.trim().is_empty()),
+            action: query.action,
+            since: query.since,
+            until: query.until,
+        };
+
+        let pagination = PaginationRequestDto {
+            page: query.page,
+            page_size: query.page_size.unwrap_or(DEFAULT_PAGE_SIZE),
+        }.validate_and_adjust();
+
+        let (events, total) = self.repository
+            .query(&filter, pagination.limit() as i64, pagination.offset() as i64)
+            .await?;
+
+        Ok(PaginatedResponseDto::new(
+            events.into_iter().map(AuditEventDto::from).collect(),
+            pagination.page,
+            pagination.page_size,
+            total.max(0) as usize,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+    use std::sync::Mutex;
+    use crate::domain::entities::audit_event::AuditAction;
+    use crate::domain::repositories::audit_log_repository::AuditLogRepositoryResult;
+
+    #[derive(Default)]
+    struct InMemoryAuditLog {
+        events: Mutex<Vec<AuditEvent>>,
+    }
+
+    #[async_trait]
+    impl AuditLogRepository for InMemoryAuditLog {
+        async fn append(&self, event: &AuditEvent) -> AuditLogRepositoryResult<()> {
+            self.events.lock().unwrap().push(event.clone());
+            Ok(())
+        }
+
+        async fn query(&self, filter: &AuditLogFilter, limit: i64, offset: i64) -> AuditLogRepositoryResult<(Vec<AuditEvent>, i64)> {
+            let matching: Vec<AuditEvent> = self.events.lock().unwrap().iter().rev()
+                .filter(|event| filter.action.is_none_or(|action| event.action == action))
+                .filter(|event| filter.user.as_ref().is_none_or(|user| {
+                    event.actor_id.as_ref() == Some(user) || event.actor_name.as_ref() == Some(user)
+                }))
+                .cloned()
+                .collect();
+            let total = matching.len() as i64;
+            Ok((matching.into_iter().skip(offset as usize).take(limit as usize).collect(), total))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_logins_are_found_by_the_username_tried() {
+        let service = AuditLogService::new(Arc::new(InMemoryAuditLog::default()));
+        service.record(AuditEvent::new(AuditAction::Login, Some("id-ana".to_string()), Some("ana".to_string()), None, None, true, None)).await;
+        service.record(AuditEvent::new(AuditAction::LoginFailed, None, Some("ana".to_string()), None, None, false, None)).await;
+        service.record(AuditEvent::new(AuditAction::LoginFailed, None, Some("luis".to_string()), None, None, false, None)).await;
+
+        let page = service.query(AuditLogQueryDto {
+            user: Some("ana".to_string()),
+            action: Some(AuditAction::LoginFailed),
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(page.pagination.total_items, 1);
+        assert_eq!(page.items[0].actor_name.as_deref(), Some("ana"));
+        assert!(!page.items[0].success);
+
+        let now = Utc::now();
+        let empty_range = service.query(AuditLogQueryDto {
+            since: Some(now),
+            until: Some(now - Duration::hours(1)),
+            ..Default::default()
+        }).await;
+        assert!(empty_range.is_err());
+    }
+}
@@ -1,18 +1,64 @@
-use std::sync::Arc;
-use crate::domain::entities::user::{User, UserRole};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use rand_core::{OsRng, RngCore};
+use crate::domain::entities::user::{AuthSource, Permission, User, UserRole};
 use crate::domain::entities::session::Session;
 use crate::domain::services::auth_service::AuthService;
-use crate::application::ports::auth_ports::{UserStoragePort, SessionStoragePort};
-use crate::application::dtos::user_dto::{UserDto, RegisterDto, LoginDto, AuthResponseDto, ChangePasswordDto, RefreshTokenDto};
+use crate::domain::services::content_digest_service::sha256_hex;
+use crate::application::ports::auth_ports::{Caller, UserStoragePort, SessionStoragePort};
+use crate::application::ports::audit_ports::AuditLogPort;
+use crate::application::ports::event_bus_ports::{DomainEvent, EventBusPort};
+use crate::domain::entities::audit_event::{AuditAction, AuditEvent};
+use crate::application::dtos::user_dto::{
+    UserDto, RegisterDto, LoginDto, AuthResponseDto, ChangePasswordDto, RefreshTokenDto, SkeletonApplyResultDto,
+    AppPasswordDto, AppPasswordCreatedDto, CreateAppPasswordDto, SessionClient, SessionDto,
+};
 use crate::application::dtos::folder_dto::CreateFolderDto;
 use crate::application::ports::inbound::FolderUseCase;
+use crate::application::services::user_skeleton_service::UserSkeletonService;
+use crate::application::services::app_password_service::AppPasswordService;
+use crate::application::services::directory_auth_service::{DirectoryAccount, DirectoryAuthService};
+use crate::application::services::login_throttle::LoginThrottle;
 use crate::common::errors::{DomainError, ErrorKind};
 
+/// Tiempo durante el que se recuerdan unas credenciales Basic ya comprobadas,
+/// para no calcular un hash Argon2 en cada petición de un cliente DAV
+const BASIC_CREDENTIALS_TTL: Duration = Duration::from_secs(300);
+
+/// Versión de las credenciales de un usuario: cambia con su contraseña, su
+/// rol o su estado, y deja sin valor las credenciales Basic recordadas
+fn credential_version(user: &User) -> String {
+    sha256_hex(format!("{}\0{}\0{}", user.password_hash(), user.role(), user.is_active()).as_bytes())
+}
+
+/// Precisión con la que se anota la última actividad de una sesión; evita
+/// escribir en la base de datos en cada petición
+const SESSION_ACTIVITY_RESOLUTION_SECS: i64 = 300;
+
 pub struct AuthApplicationService {
     user_storage: Arc<dyn UserStoragePort>,
     session_storage: Arc<dyn SessionStoragePort>,
     auth_service: Arc<AuthService>,
     folder_service: Option<Arc<dyn FolderUseCase>>,
+    skeleton_service: Option<Arc<UserSkeletonService>>,
+    app_password_service: Option<Arc<AppPasswordService>>,
+    /// Directorio externo (LDAP) que comprueba las contraseñas de sus cuentas
+    directory: Option<Arc<DirectoryAuthService>>,
+    /// Si la autenticación Basic acepta la contraseña de la cuenta además de
+    /// las contraseñas de aplicación
+    basic_account_password: bool,
+    /// Credenciales Basic válidas recientes, por hash de usuario y contraseña:
+    /// el usuario, la versión de sus credenciales y cuándo se comprobaron
+    basic_credentials: Mutex<HashMap<String, (String, String, Instant)>>,
+    /// Bloqueo de las cuentas con demasiados intentos fallidos
+    login_throttle: LoginThrottle,
+    /// Registro de auditoría de los accesos y los cambios de rol
+    audit_log: Option<Arc<dyn AuditLogPort>>,
+    /// Bus en el que se publican las cuentas nuevas
+    event_bus: Option<Arc<dyn EventBusPort>>,
 }
 
 impl AuthApplicationService {
@@ -26,6 +72,14 @@ impl AuthApplicationService {
             session_storage,
             auth_service,
             folder_service: None,
+            skeleton_service: None,
+            app_password_service: None,
+            directory: None,
+            basic_account_password: true,
+            basic_credentials: Mutex::new(HashMap::new()),
+            login_throttle: LoginThrottle::default(),
+            audit_log: None,
+            event_bus: None,
         }
     }
     
@@ -35,6 +89,178 @@ impl AuthApplicationService {
         self
     }
     
+    /// Configura el servicio de esqueletos aplicado a las carpetas personales nuevas
+    pub fn with_skeleton_service(mut self, skeleton_service: Arc<UserSkeletonService>) -> Self {
+        self.skeleton_service = Some(skeleton_service);
+        self
+    }
+    
+    /// Habilita las contraseñas de aplicación para la autenticación Basic.
+    /// Con `allow_account_password` a `false`, los clientes DAV y de la API
+    /// solo pueden entrar con ellas y no con la contraseña de la cuenta.
+    pub fn with_app_password_service(
+        mut self,
+        app_password_service: Arc<AppPasswordService>,
+        allow_account_password: bool,
+    ) -> Self {
+        self.app_password_service = Some(app_password_service);
+        self.basic_account_password = allow_account_password;
+        self
+    }
+    
+    /// Autentica las cuentas de un directorio externo. Sus usuarios locales
+    /// se crean en el primer acceso y su correo y rol se sincronizan en cada uno.
+    pub fn with_directory(mut self, directory: Arc<DirectoryAuthService>) -> Self {
+        self.directory = Some(directory);
+        self
+    }
+    
+    /// Deja constancia en el registro de auditoría de los accesos, correctos
+    /// y fallidos, y de los cambios de rol
+    pub fn with_audit_log(mut self, audit_log: Arc<dyn AuditLogPort>) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+    
+    /// Publica las cuentas nuevas como eventos de dominio, por ejemplo para
+    /// los webhooks
+    pub fn with_event_bus(mut self, event_bus: Arc<dyn EventBusPort>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+    
+    fn publish_user_created(&self, user: &User) {
+        if let Some(event_bus) = &self.event_bus {
+            event_bus.publish(DomainEvent::UserCreated {
+                user_id: user.id().to_string(),
+                username: user.username().to_string(),
+                email: user.email().to_string(),
+                role: user.role().to_string(),
+            });
+        }
+    }
+    
+    async fn audit(&self, event: AuditEvent) {
+        if let Some(audit_log) = &self.audit_log {
+            audit_log.record(event).await;
+        }
+    }
+    
+    /// Si las cuentas pueden entrar con la contraseña guardada localmente
+    fn local_passwords_allowed(&self) -> bool {
+        self.directory.as_ref().is_none_or(|directory| directory.allows_local_users())
+    }
+    
+    /// Comprueba las credenciales en el directorio y devuelve el usuario local
+    /// sincronizado. `None` si no hay directorio, si no conoce la cuenta o si
+    /// no está disponible, para que se intente con las cuentas locales.
+    async fn directory_user(&self, username: &str, password: &str) -> Result<Option<User>, DomainError> {
+        let Some(directory) = &self.directory else {
+            return Ok(None);
+        };
+        
+        let account = match directory.authenticate(username, password).await {
+            Ok(Some(account)) => account,
+            Ok(None) => return Ok(None),
+            Err(e) if e.kind == ErrorKind::AccessDenied => return Err(e),
+            Err(e) => {
+                tracing::error!("No se pudo consultar el directorio: {}", e);
+                return Ok(None);
+            }
+        };
+        
+        self.sync_directory_account(account).await.map(Some)
+    }
+    
+    /// Crea o actualiza el usuario local de una cuenta del directorio. Un
+    /// usuario local con el mismo nombre que no se creó desde el directorio
+    /// no se toca: hasta que un administrador lo vincule, el acceso se rechaza
+    /// para que nadie del directorio se quede con una cuenta ajena y su rol.
+    async fn sync_directory_account(&self, account: DirectoryAccount) -> Result<User, DomainError> {
+        // Un correo que ya usa otra cuenta no se puede asignar
+        let email_owner = self.user_storage.get_user_by_email(&account.email).await
+            .ok()
+            .map(|user| user.id().to_string());
+        
+        if let Ok(mut user) = self.user_storage.get_user_by_username(&account.username).await {
+            if user.auth_source() != AuthSource::Directory {
+                tracing::warn!(
+                    "La cuenta del directorio {} coincide con un usuario local no vinculado; se rechaza el acceso",
+                    account.username
+                );
+                return Err(DomainError::access_denied(
+                    "Auth",
+                    "La cuenta local no está vinculada al directorio; un administrador debe vincularla",
+                ));
+            }
+            let email_free = email_owner.as_deref().is_none_or(|owner| owner == user.id());
+            let email_changed = email_free && user.email() != account.email;
+            if email_changed || user.role() != account.role {
+                if email_changed {
+                    user.update_email(account.email.clone());
+                }
+                user.update_role(account.role);
+                user = self.user_storage.update_user(user).await?;
+            }
+            return Ok(user);
+        }
+        
+        let email = if email_owner.is_some() {
+            format!("{}@ldap.invalid", account.username)
+        } else {
+            account.email.clone()
+        };
+        // La contraseña local no se comunica a nadie: la cuenta entra por el directorio
+        let created = self.register(RegisterDto {
+            username: account.username.clone(),
+            email,
+            password: random_password(),
+            role: Some("user".to_string()),
+        }).await?;
+        
+        let mut user = self.user_storage.get_user_by_id(&created.id).await?;
+        user.set_auth_source(AuthSource::Directory);
+        user.update_role(account.role);
+        let user = self.user_storage.update_user(user).await?;
+        tracing::info!("Usuario {} creado en su primer acceso desde el directorio", account.username);
+        Ok(user)
+    }
+    
+    /// Aplica los esqueletos de los grupos del usuario en su carpeta personal
+    /// recién creada. Los errores se registran sin interrumpir el alta del usuario.
+    async fn provision_home_skeleton(&self, user: &User, home_folder_id: &str) {
+        if let Some(skeleton_service) = &self.skeleton_service {
+            if let Err(e) = skeleton_service.apply_skeleton(home_folder_id, user.id()).await {
+                tracing::error!("No se pudo aplicar el esqueleto para el usuario {}: {}", user.id(), e);
+            }
+        }
+    }
+    
+    /// Vuelve a aplicar los esqueletos en la carpeta personal de un usuario
+    /// existente, por ejemplo tras añadirlo a un grupo. Solo se crean los
+    /// elementos que faltan.
+    pub async fn apply_user_skeleton(&self, user_id: &str) -> Result<SkeletonApplyResultDto, DomainError> {
+        let skeleton_service = self.skeleton_service.as_ref().ok_or_else(|| DomainError::new(
+            ErrorKind::UnsupportedOperation,
+            "Skeleton",
+            "El servicio de esqueletos no está configurado"
+        ))?;
+        let folder_service = self.folder_service.as_ref().ok_or_else(|| DomainError::new(
+            ErrorKind::UnsupportedOperation,
+            "Skeleton",
+            "El servicio de carpetas no está configurado"
+        ))?;
+        
+        let user = self.user_storage.get_user_by_id(user_id).await?;
+        let home_folder_name = format!("Mi Carpeta - {}", user.username());
+        let home_folder = folder_service.list_folders(None).await?
+            .into_iter()
+            .find(|f| f.name == home_folder_name)
+            .ok_or_else(|| DomainError::not_found("Folder", home_folder_name))?;
+        
+        skeleton_service.apply_skeleton(&home_folder.id, user.id()).await
+    }
+    
     pub async fn register(&self, dto: RegisterDto) -> Result<UserDto, DomainError> {
         // Verificar usuario duplicado
         if self.user_storage.get_user_by_username(&dto.username).await.is_ok() {
@@ -104,13 +330,9 @@ impl AuthApplicationService {
         }
         
         // Determinar rol y cuota según el tipo de usuario
-        // Si se proporciona un rol explícito de "admin", usar rol de administrador
+        // Si se proporciona un rol explícito ("admin", "user" o "guest"), usarlo
         let role = if let Some(role_str) = &dto.role {
-            if role_str.to_lowercase() == "admin" {
-                UserRole::Admin
-            } else {
-                UserRole::User
-            }
+            UserRole::parse(role_str).unwrap_or(UserRole::User)
         } else {
             // Caso especial: si el nombre es "admin", asignar rol de admin aunque no se especifique
             if dto.username.to_lowercase() == "admin" {
@@ -142,6 +364,7 @@ impl AuthApplicationService {
         
         // Guardar usuario
         let created_user = self.user_storage.create_user(user).await?;
+        self.publish_user_created(&created_user);
         
         // Crear carpeta personal para el usuario
         if let Some(folder_service) = &self.folder_service {
@@ -159,6 +382,8 @@ impl AuthApplicationService {
                         folder.id
                     );
                     
+                    self.provision_home_skeleton(&created_user, &folder.id).await;
+                    
                     // Aquí se podría guardar la asociación de la carpeta al usuario
                     // por ejemplo, en una tabla de relación carpeta-usuario
                 },
@@ -183,9 +408,80 @@ impl AuthApplicationService {
         Ok(UserDto::from(created_user))
     }
     
-    pub async fn login(&self, dto: LoginDto) -> Result<AuthResponseDto, DomainError> {
+    /// Inicia sesión con usuario y contraseña. Los intentos rechazados quedan
+    /// en el registro de auditoría con el nombre de usuario probado, y tras
+    /// varios seguidos la cuenta se bloquea un tiempo
+    pub async fn login(&self, dto: LoginDto, client: SessionClient) -> Result<AuthResponseDto, DomainError> {
+        let username = dto.username.clone();
+        let ip_address = client.ip_address.clone();
+        let result = if self.login_throttle.is_locked(&username) {
+            Err(Self::locked_out())
+        } else {
+            let result = self.login_with_password(dto, client).await;
+            self.throttle_attempt(&username, &result);
+            result
+        };
+        if let Err(e) = &result {
+            self.audit_login_failure(Some(username), ip_address, e.message.clone()).await;
+        }
+        result
+    }
+    
+    /// Deja constancia en el registro de auditoría de un acceso rechazado,
+    /// con el nombre de usuario probado si se conoce
+    pub async fn audit_login_failure(&self, username: Option<String>, ip_address: Option<String>, details: String) {
+        self.audit(AuditEvent::new(
+            AuditAction::LoginFailed,
+            None,
+            username,
+            None,
+            ip_address,
+            false,
+            Some(details),
+        )).await;
+    }
+    
+    fn locked_out() -> DomainError {
+        DomainError::new(
+            ErrorKind::AccessDenied,
+            "Auth",
+            "Demasiados intentos fallidos; inténtalo más tarde"
+        )
+    }
+    
+    /// Anota en el limitador el resultado de un intento con credenciales; los
+    /// errores internos no cuentan como fallos
+    fn throttle_attempt<T>(&self, username: &str, result: &Result<T, DomainError>) {
+        match result {
+            Ok(_) => self.login_throttle.record_success(username),
+            Err(e) if e.kind == ErrorKind::AccessDenied => self.login_throttle.record_failure(username),
+            Err(_) => {}
+        }
+    }
+    
+    async fn login_with_password(&self, dto: LoginDto, client: SessionClient) -> Result<AuthResponseDto, DomainError> {
+        // Las cuentas del directorio externo se comprueban allí
+        if let Some(user) = self.directory_user(&dto.username, &dto.password).await? {
+            if !user.is_active() {
+                return Err(DomainError::new(
+                    ErrorKind::AccessDenied,
+                    "Auth",
+                    "Cuenta desactivada"
+                ));
+            }
+            return self.start_session(user, client).await;
+        }
+        
+        if !self.local_passwords_allowed() {
+            return Err(DomainError::new(
+                ErrorKind::AccessDenied,
+                "Auth",
+                "Credenciales inválidas"
+            ));
+        }
+        
         // Buscar usuario
-        let mut user = self.user_storage
+        let user = self.user_storage
             .get_user_by_username(&dto.username)
             .await
             .map_err(|_| DomainError::new(
@@ -219,26 +515,55 @@ impl AuthApplicationService {
             ));
         }
         
+        self.start_session(user, client).await
+    }
+    
+    /// Abre una sesión para un usuario autenticado por otro medio, como un
+    /// proveedor OpenID Connect
+    pub async fn login_user(&self, user_id: &str, client: SessionClient) -> Result<AuthResponseDto, DomainError> {
+        let user = self.user_storage.get_user_by_id(user_id).await?;
+        
+        if !user.is_active() {
+            return Err(DomainError::new(
+                ErrorKind::AccessDenied,
+                "Auth",
+                "Cuenta desactivada"
+            ));
+        }
+        
+        self.start_session(user, client).await
+    }
+    
+    /// Registra el acceso de un usuario ya autenticado y genera sus tokens
+    async fn start_session(&self, mut user: User, client: SessionClient) -> Result<AuthResponseDto, DomainError> {
         // Actualizar último login
         user.register_login();
         self.user_storage.update_user(user.clone()).await?;
         
-        // Generar tokens
-        let access_token = self.auth_service.generate_access_token(&user)
-            .map_err(DomainError::from)?;
-        
-        let refresh_token = self.auth_service.generate_refresh_token();
-        
         // Guardar sesión
+        let refresh_token = self.auth_service.generate_refresh_token();
         let session = Session::new(
             user.id().to_string(),
             refresh_token.clone(),
-            None, // IP (se puede añadir desde la capa HTTP)
-            None, // User-Agent (se puede añadir desde la capa HTTP)
+            client.ip_address.clone(),
+            client.user_agent,
             self.auth_service.refresh_token_expiry_days(),
         );
         
-        self.session_storage.create_session(session).await?;
+        self.session_storage.create_session(session.clone()).await?;
+        self.audit(AuditEvent::new(
+            AuditAction::Login,
+            Some(user.id().to_string()),
+            Some(user.username().to_string()),
+            None,
+            client.ip_address,
+            true,
+            None,
+        )).await;
+        
+        // El token de acceso lleva la sesión para dejar de valer al revocarla
+        let access_token = self.auth_service.generate_access_token(&user, Some(session.id()))
+            .map_err(DomainError::from)?;
         
         // Respuesta de autenticación
         Ok(AuthResponseDto {
@@ -250,14 +575,17 @@ impl AuthApplicationService {
         })
     }
     
-    pub async fn refresh_token(&self, dto: RefreshTokenDto) -> Result<AuthResponseDto, DomainError> {
+    /// Genera tokens nuevos para una sesión. La sesión conserva su ID, de
+    /// modo que sigue siendo el mismo dispositivo en la lista de sesiones, y
+    /// el token de actualización usado deja de valer
+    pub async fn refresh_token(&self, dto: RefreshTokenDto, client: SessionClient) -> Result<AuthResponseDto, DomainError> {
         // Obtener sesión válida
         let session = self.session_storage
             .get_session_by_refresh_token(&dto.refresh_token)
             .await?;
         
         // Verificar si la sesión está expirada o revocada
-        if session.is_expired() || session.is_revoked() {
+        if !session.is_active() {
             return Err(DomainError::new(
                 ErrorKind::AccessDenied,
                 "Auth",
@@ -279,25 +607,19 @@ impl AuthApplicationService {
             ));
         }
         
-        // Revocar sesión actual
-        self.session_storage.revoke_session(session.id()).await?;
-        
-        // Generar nuevos tokens
-        let access_token = self.auth_service.generate_access_token(&user)
-            .map_err(DomainError::from)?;
-        
+        // Rotar el token de actualización
         let new_refresh_token = self.auth_service.generate_refresh_token();
+        let expires_at = chrono::Utc::now() + chrono::Duration::days(self.auth_service.refresh_token_expiry_days());
+        self.session_storage.rotate_refresh_token(
+            session.id(),
+            &new_refresh_token,
+            expires_at,
+            client.ip_address.as_deref(),
+            client.user_agent.as_deref(),
+        ).await?;
         
-        // Crear nueva sesión
-        let new_session = Session::new(
-            user.id().to_string(),
-            new_refresh_token.clone(),
-            None,
-            None,
-            self.auth_service.refresh_token_expiry_days(),
-        );
-        
-        self.session_storage.create_session(new_session).await?;
+        let access_token = self.auth_service.generate_access_token(&user, Some(session.id()))
+            .map_err(DomainError::from)?;
         
         Ok(AuthResponseDto {
             user: UserDto::from(user),
@@ -371,10 +693,195 @@ impl AuthApplicationService {
         
         // Opcional: revocar todas las sesiones para forzar re-login con nueva contraseña
         self.session_storage.revoke_all_user_sessions(user_id).await?;
+        self.forget_basic_credentials();
         
         Ok(())
     }
     
+    /// Identifica al usuario de un token de acceso (cabecera `Bearer`)
+    pub async fn authenticate_token(&self, token: &str) -> Result<UserDto, DomainError> {
+        let claims = self.auth_service.validate_token(token).map_err(DomainError::from)?;
+        if let Some(session_id) = &claims.sid {
+            self.check_session_activity(session_id).await?;
+        }
+        let user = self.user_storage.get_user_by_id(&claims.sub).await
+            .map_err(|_| DomainError::new(ErrorKind::AccessDenied, "Auth", "Token inválido"))?;
+        if !user.is_active() {
+            return Err(DomainError::new(ErrorKind::AccessDenied, "Auth", "Cuenta desactivada"));
+        }
+        Ok(UserDto::from(user))
+    }
+    
+    /// Rechaza los tokens de una sesión revocada y anota su actividad
+    async fn check_session_activity(&self, session_id: &str) -> Result<(), DomainError> {
+        let session = self.session_storage.get_session_by_id(session_id).await
+            .map_err(|_| DomainError::new(ErrorKind::AccessDenied, "Auth", "Sesión expirada o inválida"))?;
+        if !session.is_active() {
+            return Err(DomainError::new(ErrorKind::AccessDenied, "Auth", "Sesión expirada o inválida"));
+        }
+        
+        let idle = chrono::Utc::now() - session.last_active_at();
+        if idle.num_seconds() >= SESSION_ACTIVITY_RESOLUTION_SECS {
+            if let Err(e) = self.session_storage.touch_session(session_id).await {
+                tracing::warn!("No se pudo anotar la actividad de la sesión {}: {}", session_id, e);
+            }
+        }
+        Ok(())
+    }
+    
+    /// Sesión de un token de acceso, si el token es válido y se emitió para una
+    pub fn token_session_id(&self, token: &str) -> Option<String> {
+        self.auth_service.validate_token(token).ok().and_then(|claims| claims.sid)
+    }
+    
+    /// Sesiones activas de un usuario, las más recientes primero;
+    /// `current_session_id` marca aquella desde la que se consulta
+    pub async fn list_sessions(&self, user_id: &str, current_session_id: Option<&str>) -> Result<Vec<SessionDto>, DomainError> {
+        let mut sessions: Vec<Session> = self.session_storage.get_sessions_by_user_id(user_id).await?
+            .into_iter()
+            .filter(Session::is_active)
+            .collect();
+        sessions.sort_by(|a, b| b.last_active_at().cmp(&a.last_active_at()));
+        
+        Ok(sessions.into_iter().map(|session| SessionDto {
+            device: describe_device(session.user_agent.as_deref()),
+            current: current_session_id == Some(session.id()),
+            id: session.id,
+            ip_address: session.ip_address,
+            user_agent: session.user_agent,
+            created_at: session.created_at,
+            last_active_at: session.last_active_at,
+            expires_at: session.expires_at,
+        }).collect())
+    }
+    
+    /// Cierra una sesión del usuario; sus tokens dejan de valer en el acto
+    pub async fn revoke_user_session(&self, user_id: &str, session_id: &str) -> Result<(), DomainError> {
+        // Las sesiones de otro usuario se tratan como inexistentes
+        let session = self.session_storage.get_session_by_id(session_id).await
+            .ok()
+            .filter(|session| session.user_id() == user_id && session.is_active())
+            .ok_or_else(|| DomainError::not_found("Session", session_id))?;
+        
+        self.session_storage.revoke_session(session.id()).await
+    }
+    
+    /// Cierra todas las sesiones del usuario salvo la actual. Sin sesión
+    /// actual, como al llamar con una contraseña de aplicación, las cierra todas
+    pub async fn revoke_other_sessions(&self, user_id: &str, current_session_id: Option<&str>) -> Result<u64, DomainError> {
+        match current_session_id {
+            Some(current) => self.session_storage.revoke_other_user_sessions(user_id, current).await,
+            None => self.session_storage.revoke_all_user_sessions(user_id).await,
+        }
+    }
+    
+    /// Identifica al usuario de unas credenciales HTTP Basic, las que usan los
+    /// clientes WebDAV, CalDAV y CardDAV y los scripts que llaman a la API.
+    ///
+    /// La contraseña puede ser una contraseña de aplicación del usuario o, si
+    /// está permitido, la de su cuenta. No crea sesión: los clientes DAV
+    /// envían las credenciales en cada petición. Los fallos cuentan para el
+    /// mismo bloqueo que el acceso con contraseña y quedan en el registro de
+    /// auditoría; los aciertos no, ya que llegan con cada petición.
+    pub async fn authenticate_basic(&self, username: &str, password: &str, client_ip: Option<&str>) -> Result<UserDto, DomainError> {
+        let key = sha256_hex(format!("{}\0{}", username, password).as_bytes());
+        let cached = self.basic_credentials.lock().unwrap()
+            .get(&key)
+            .filter(|(_, _, verified_at)| verified_at.elapsed() < BASIC_CREDENTIALS_TTL)
+            .map(|(user_id, version, _)| (user_id.clone(), version.clone()));
+        if let Some((user_id, version)) = cached {
+            // Un cambio de contraseña o de rol, una desactivación o un borrado
+            // invalidan lo recordado, los haga quien los haga
+            match self.user_storage.get_user_by_id(&user_id).await {
+                Ok(user) if user.is_active() && credential_version(&user) == version => return Ok(UserDto::from(user)),
+                _ => {
+                    self.basic_credentials.lock().unwrap().remove(&key);
+                }
+            }
+        }
+        
+        let result = if self.login_throttle.is_locked(username) {
+            Err(Self::locked_out())
+        } else {
+            let result = self.verify_basic_credentials(username, password).await;
+            self.throttle_attempt(username, &result);
+            result
+        };
+        let user = match result {
+            Ok(user) => user,
+            Err(e) => {
+                self.audit_login_failure(
+                    Some(username.to_string()),
+                    client_ip.map(str::to_string),
+                    format!("HTTP Basic: {}", e.message),
+                ).await;
+                return Err(e);
+            }
+        };
+        
+        let version = credential_version(&user);
+        let user = UserDto::from(user);
+        let mut cache = self.basic_credentials.lock().unwrap();
+        cache.retain(|_, (_, _, verified_at)| verified_at.elapsed() < BASIC_CREDENTIALS_TTL);
+        cache.insert(key, (user.id.clone(), version, Instant::now()));
+        Ok(user)
+    }
+    
+    /// Comprueba unas credenciales Basic sin pasar por la caché
+    async fn verify_basic_credentials(&self, username: &str, password: &str) -> Result<User, DomainError> {
+        let invalid = || DomainError::new(ErrorKind::AccessDenied, "Auth", "Credenciales inválidas");
+        let disabled = || DomainError::new(ErrorKind::AccessDenied, "Auth", "Cuenta desactivada");
+        let local_user = self.user_storage.get_user_by_username(username).await.ok();
+        
+        // Las contraseñas de aplicación valen también para cuentas del directorio
+        let mut user = None;
+        if let (Some(local), Some(service)) = (&local_user, &self.app_password_service) {
+            if service.verify(local.id(), password).await? {
+                user = local_user.clone();
+            }
+        }
+        if user.is_none() && self.basic_account_password {
+            user = self.directory_user(username, password).await.map_err(|_| invalid())?;
+            if user.is_none() && self.local_passwords_allowed() {
+                user = local_user.filter(|local| local.verify_password(password).unwrap_or(false));
+            }
+        }
+        let user = user.ok_or_else(invalid)?;
+        if !user.is_active() {
+            return Err(disabled());
+        }
+        Ok(user)
+    }
+    
+    /// Genera una contraseña de aplicación; la respuesta es la única vez que
+    /// se ve la contraseña
+    pub async fn create_app_password(&self, user_id: &str, dto: CreateAppPasswordDto) -> Result<AppPasswordCreatedDto, DomainError> {
+        self.app_password_service()?.create(user_id, dto).await
+    }
+    
+    /// Lista las contraseñas de aplicación de un usuario
+    pub async fn list_app_passwords(&self, user_id: &str) -> Result<Vec<AppPasswordDto>, DomainError> {
+        self.app_password_service()?.list(user_id).await
+    }
+    
+    /// Revoca una contraseña de aplicación; deja de servir al momento
+    pub async fn revoke_app_password(&self, user_id: &str, id: &str) -> Result<(), DomainError> {
+        self.app_password_service()?.revoke(user_id, id).await?;
+        self.forget_basic_credentials();
+        Ok(())
+    }
+    
+    fn app_password_service(&self) -> Result<&Arc<AppPasswordService>, DomainError> {
+        self.app_password_service.as_ref()
+            .ok_or_else(|| DomainError::internal_error("AppPassword", "App passwords are not available"))
+    }
+    
+    /// Olvida las credenciales Basic recordadas, para que una contraseña
+    /// cambiada o revocada deje de servir sin esperar a que caduquen
+    pub fn forget_basic_credentials(&self) {
+        self.basic_credentials.lock().unwrap().clear();
+    }
+    
     pub async fn get_user(&self, user_id: &str) -> Result<UserDto, DomainError> {
         let user = self.user_storage.get_user_by_id(user_id).await?;
         Ok(UserDto::from(user))
@@ -477,6 +984,7 @@ impl AuthApplicationService {
         
         // 4. Save the new admin user
         let created_user = self.user_storage.create_user(user).await?;
+        self.publish_user_created(&created_user);
         
         // 5. Create personal folder for the new admin if folder service is available
         if let Some(folder_service) = &self.folder_service {
@@ -493,6 +1001,8 @@ impl AuthApplicationService {
                         folder.name, 
                         folder.id
                     );
+                    
+                    self.provision_home_skeleton(&created_user, &folder.id).await;
                 },
                 Err(e) => {
                     tracing::error!(
@@ -508,8 +1018,141 @@ impl AuthApplicationService {
         Ok(UserDto::from(created_user))
     }
     
-    pub async fn list_users(&self, limit: i64, offset: i64) -> Result<Vec<UserDto>, DomainError> {
+    /// Lista los usuarios; solo para quien puede gestionarlos
+    pub async fn list_users(&self, caller: &Caller, limit: i64, offset: i64) -> Result<Vec<UserDto>, DomainError> {
+        caller.require(Permission::ManageUsers)?;
         let users = self.user_storage.list_users(limit, offset).await?;
         Ok(users.into_iter().map(UserDto::from).collect())
     }
-}
\ No newline at end of file
+    
+    /// Cambia el rol de un usuario.
+    ///
+    /// Un administrador no puede quitarse el rol a sí mismo ni dejar la
+    /// instancia sin administradores. Se cierran las sesiones del usuario
+    /// para que los tokens emitidos con el rol anterior dejen de servir.
+    pub async fn change_role(&self, caller: &Caller, user_id: &str, role: &str) -> Result<UserDto, DomainError> {
+        caller.require(Permission::ManageUsers)?;
+        let role = UserRole::parse(role)
+            .ok_or_else(|| DomainError::validation_error(format!("Rol desconocido: {}", role)))?;
+        
+        let mut user = self.user_storage.get_user_by_id(user_id).await?;
+        if user.role() == role {
+            return Ok(UserDto::from(user));
+        }
+        if user.role() == UserRole::Admin {
+            if caller.user_id == user.id() {
+                return Err(DomainError::validation_error("No puedes quitarte el rol de administrador"));
+            }
+            if self.count_admin_users().await? <= 1 {
+                return Err(DomainError::validation_error("Debe quedar al menos un administrador"));
+            }
+        }
+        
+        let previous_role = user.role();
+        user.update_role(role);
+        let user = self.user_storage.update_user(user).await?;
+        self.session_storage.revoke_all_user_sessions(user.id()).await?;
+        self.forget_basic_credentials();
+        tracing::info!("Rol de {} cambiado a {} por {}", user.username(), role, caller.user_id);
+        self.audit(AuditEvent::new(
+            AuditAction::RoleChanged,
+            Some(caller.user_id.clone()),
+            None,
+            Some(user.username().to_string()),
+            None,
+            true,
+            Some(format!("{} -> {}", previous_role, role)),
+        )).await;
+        Ok(UserDto::from(user))
+    }
+    
+    /// Vincula una cuenta local con la del mismo nombre en el directorio
+    /// externo, o deshace el vínculo.
+    ///
+    /// Una cuenta vinculada se autentica en el directorio, que decide su
+    /// correo y su rol; al deshacer el vínculo vuelve a su contraseña local.
+    /// Se cierran las sesiones del usuario, como al cambiar el rol.
+    pub async fn set_directory_link(&self, caller: &Caller, user_id: &str, linked: bool) -> Result<UserDto, DomainError> {
+        caller.require(Permission::ManageUsers)?;
+        let source = if linked { AuthSource::Directory } else { AuthSource::Local };
+        
+        let mut user = self.user_storage.get_user_by_id(user_id).await?;
+        if user.auth_source() == source {
+            return Ok(UserDto::from(user));
+        }
+        
+        user.set_auth_source(source);
+        let user = self.user_storage.update_user(user).await?;
+        self.session_storage.revoke_all_user_sessions(user.id()).await?;
+        self.forget_basic_credentials();
+        tracing::info!("Cuenta {} {} al directorio por {}", user.username(), if linked { "vinculada" } else { "desvinculada" }, caller.user_id);
+        Ok(UserDto::from(user))
+    }
+}
+/// Contraseña local aleatoria para las cuentas que se autentican fuera
+/// Nombre legible del dispositivo de una sesión a partir de su User-Agent
+fn describe_device(user_agent: Option<&str>) -> String {
+    let Some(user_agent) = user_agent.filter(|ua| !ua.trim().is_empty()) else {
+        return "Dispositivo desconocido".to_string();
+    };
+    
+    // El orden importa: Edge y Opera se anuncian también como Chrome, y Chrome como Safari
+    let browser = [
+        ("Edg/", "Edge"),
+        ("OPR/", "Opera"),
+        ("Firefox/", "Firefox"),
+        ("Chrome/", "Chrome"),
+        ("Safari/", "Safari"),
+        ("curl/", "curl"),
+    ]
+    .iter()
+    .find(|(token, _)| user_agent.contains(token))
+    .map(|(_, name)| *name);
+    
+    let system = [
+        ("Android", "Android"),
+        ("iPhone", "iOS"),
+        ("iPad", "iPadOS"),
+        ("Windows", "Windows"),
+        ("Mac OS X", "macOS"),
+        ("CrOS", "ChromeOS"),
+        ("Linux", "Linux"),
+    ]
+    .iter()
+    .find(|(token, _)| user_agent.contains(token))
+    .map(|(_, name)| *name);
+    
+    match (browser, system) {
+        (Some(browser), Some(system)) => format!("{} en {}", browser, system),
+        (Some(name), None) | (None, Some(name)) => name.to_string(),
+        // Clientes propios, como "OxiCloud-Desktop/1.2"
+        (None, None) => user_agent.split(['/', ' ']).next().unwrap_or(user_agent).to_string(),
+    }
+}
+
+fn random_password() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_device() {
+        let firefox = "Mozilla/5.0 (X11; Linux x86_64; rv:125.0) Gecko/20100101 Firefox/125.0";
+        assert_eq!(describe_device(Some(firefox)), "Firefox en Linux");
+
+        let edge = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36 Edg/124.0.0.0";
+        assert_eq!(describe_device(Some(edge)), "Edge en Windows");
+
+        let safari = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_4 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.4 Mobile/15E148 Safari/604.1";
+        assert_eq!(describe_device(Some(safari)), "Safari en iOS");
+
+        assert_eq!(describe_device(Some("OxiCloud-Desktop/1.2")), "OxiCloud-Desktop");
+        assert_eq!(describe_device(Some("  ")), "Dispositivo desconocido");
+        assert_eq!(describe_device(None), "Dispositivo desconocido");
+    }
+}
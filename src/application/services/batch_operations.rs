@@ -9,7 +9,10 @@ use crate::application::services::folder_service::FolderService;
 use crate::domain::services::path_service::StoragePath;
 use crate::common::errors::DomainError;
 use crate::common::config::AppConfig;
-use crate::application::ports::inbound::FolderUseCase;
+use crate::application::ports::inbound::{FileUseCase, FolderUseCase};
+use crate::application::services::free_name_service::FreeNameService;
+use crate::domain::services::i18n_service::Locale;
+use crate::domain::services::naming_service::NamingPattern;
 use crate::application::dtos::file_dto::FileDto;
 use crate::application::dtos::folder_dto::FolderDto;
 
@@ -109,6 +112,7 @@ impl EntityIdentifier {
 pub struct BatchOperationService {
     file_service: Arc<FileService>,
     folder_service: Arc<FolderService>,
+    free_names: Arc<FreeNameService>,
     config: AppConfig,
     semaphore: Arc<Semaphore>,
 }
@@ -123,14 +127,24 @@ impl BatchOperationService {
         // Limitar la concurrencia basada en la configuración
         let max_concurrency = config.concurrency.max_concurrent_files;
         
+        let free_names = Arc::new(FreeNameService::new(file_service.clone(), folder_service.clone()));
+        
         Self {
             file_service,
             folder_service,
+            free_names,
             config,
             semaphore: Arc::new(Semaphore::new(max_concurrency)),
         }
     }
     
+    /// Usa un servicio de nombres libres compartido con otras operaciones, de
+    /// modo que sus reservas no colisionen
+    pub fn with_free_name_service(mut self, free_names: Arc<FreeNameService>) -> Self {
+        self.free_names = free_names;
+        self
+    }
+    
     /// Crea una nueva instancia con la configuración por defecto
     pub fn default(
         file_service: Arc<FileService>, 
@@ -139,11 +153,15 @@ impl BatchOperationService {
         Self::new(file_service, folder_service, AppConfig::default())
     }
     
-    /// Copia múltiples archivos en paralelo
+    /// Copia múltiples archivos en paralelo.
+    ///
+    /// Cada copia recibe un nombre libre en la carpeta destino: `(copia)` si
+    /// se copia en la misma carpeta, numerado si el nombre ya existe.
     pub async fn copy_files(
         &self,
         file_ids: Vec<String>,
         target_folder_id: Option<String>,
+        locale: Locale,
     ) -> Result<BatchResult<FileDto>, BatchOperationError> {
         info!("Iniciando copia en lote de {} archivos", file_ids.len());
         let start_time = std::time::Instant::now();
@@ -161,6 +179,7 @@ impl BatchOperationService {
         // Definir la operación a realizar para cada archivo
         let operations = file_ids.into_iter().map(|file_id| {
             let file_service = self.file_service.clone();
+            let free_names = self.free_names.clone();
             let target_folder = target_folder_id.clone();
             let semaphore = self.semaphore.clone();
             
@@ -168,7 +187,23 @@ impl BatchOperationService {
                 // Adquirir permiso del semáforo
                 let permit = semaphore.acquire().await.unwrap();
                 
-                let copy_result = file_service.move_file(&file_id, target_folder.clone()).await;
+                let copy_result = async {
+                    let source = file_service.get_file(&file_id).await?;
+                    let pattern = if source.folder_id == target_folder {
+                        NamingPattern::Copy
+                    } else {
+                        NamingPattern::Numbered
+                    };
+                    
+                    // La reserva se mantiene hasta crear el archivo
+                    let reservation = free_names
+                        .reserve_name(target_folder.as_deref(), &source.name, pattern, locale)
+                        .await?;
+                    file_service
+                        .copy_file(&file_id, reservation.name().to_string(), target_folder.clone())
+                        .await
+                        .map_err(DomainError::from)
+                }.await;
                 
                 // Liberar el permiso explícitamente (también se libera al hacer drop)
                 drop(permit);
@@ -0,0 +1,333 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::application::dtos::calendar_dto::{
+    CalendarDto, CalendarEventDto, CalendarImportResultDto, CalendarJsonExportDto, DuplicateUidPolicy,
+    EventOccurrenceDto, RejectedEventDto, CALENDAR_JSON_EXPORT_SCHEMA,
+};
+use crate::application::ports::calendar_ical_ports::{CalendarExport, CalendarICalUseCase, EventExpansion};
+use crate::application::services::dav_access_service::DavAccessService;
+use crate::common::errors::DomainError;
+use crate::domain::entities::calendar_event::CalendarEvent;
+use crate::domain::repositories::address_book_repository::AddressBookRepository;
+use crate::domain::repositories::calendar_event_repository::CalendarEventRepository;
+use crate::domain::repositories::calendar_repository::CalendarRepository;
+use crate::domain::services::ical_bundle_service::{merge_calendar, split_calendar, RejectedEvent};
+use crate::domain::services::icalendar_service::{ICalComponent, ICalProperty};
+use crate::domain::services::recurrence_service::Recurrence;
+use crate::domain::services::timezone_service::{normalize_to_utc, VTimeZone};
+
+/// Número máximo de objetos que se aceptan en una importación
+pub const MAX_IMPORT_OBJECTS: usize = 10_000;
+
+/// Número máximo de repeticiones de una exportación JSON expandida
+pub const MAX_EXPANDED_OCCURRENCES: usize = 10_000;
+
+/// Servicio de importación y exportación de calendarios completos en
+/// formato iCalendar (.ics).
+///
+/// La importación separa el fichero en un objeto por UID y crea los eventos
+/// nuevos de una sola vez en el repositorio; los UID que ya existen en el
+/// calendario se omiten o se sobrescriben según la política elegida.
+pub struct CalendarICalService {
+    access: DavAccessService,
+    calendar_repository: Arc<dyn CalendarRepository>,
+    event_repository: Arc<dyn CalendarEventRepository>,
+}
+
+impl CalendarICalService {
+    /// Crea un nuevo servicio de importación y exportación
+    pub fn new(
+        calendar_repository: Arc<dyn CalendarRepository>,
+        event_repository: Arc<dyn CalendarEventRepository>,
+        address_book_repository: Arc<dyn AddressBookRepository>,
+    ) -> Self {
+        Self {
+            access: DavAccessService::new(calendar_repository.clone(), address_book_repository),
+            calendar_repository,
+            event_repository,
+        }
+    }
+
+    fn parse_id(calendar_id: &str) -> Result<Uuid, DomainError> {
+        Uuid::parse_str(calendar_id)
+            .map_err(|_| DomainError::validation_error(format!("Invalid calendar ID: {}", calendar_id)))
+    }
+}
+
+/// Repeticiones de un evento que se solapan con `[start, end)`, como mucho
+/// `limit`. Las excepciones guardadas con el evento (VEVENT con
+/// RECURRENCE-ID) sustituyen a la repetición que reemplazan con sus propias
+/// horas, resumen y lugar, aunque se hayan movido fuera del intervalo
+fn expand_event(
+    event: &CalendarEvent,
+    calendar_timezone: Option<&VTimeZone>,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    limit: usize,
+) -> Vec<EventOccurrenceDto> {
+    let series = event.recurrence();
+    let recurring = series.is_recurring();
+    let occurrence = |recurrence_id: Option<DateTime<Utc>>, occurrence_start: DateTime<Utc>| EventOccurrenceDto {
+        event_id: event.id().to_string(),
+        ical_uid: event.ical_uid().to_string(),
+        recurrence_id,
+        summary: event.summary().to_string(),
+        description: event.description().map(str::to_string),
+        location: event.location().map(str::to_string),
+        start_time: occurrence_start,
+        end_time: occurrence_start + series.duration,
+        all_day: event.all_day(),
+        timezone: event.timezone().map(str::to_string),
+        overridden: false,
+    };
+
+    let mut overrides = Vec::new();
+    if let Some(mut calendar) = ICalComponent::parse(event.ical_data()) {
+        normalize_to_utc(&mut calendar, calendar_timezone);
+        for component in calendar.components_named("VEVENT") {
+            let Some((recurrence_id, _)) = component.property("RECURRENCE-ID").and_then(ICalProperty::date_time) else {
+                continue;
+            };
+            let Some(instance) = Recurrence::from_component(component) else { continue };
+            let mut edited = occurrence(Some(recurrence_id), instance.start);
+            edited.end_time = instance.start + instance.duration;
+            if let Some(summary) = component.property("SUMMARY") {
+                edited.summary = summary.text();
+            }
+            if let Some(location) = component.property("LOCATION") {
+                edited.location = Some(location.text());
+            }
+            edited.overridden = true;
+            overrides.push(edited);
+        }
+    }
+
+    let mut occurrences: Vec<EventOccurrenceDto> = series.occurrences(Some(start), Some(end), limit)
+        .into_iter()
+        .filter(|instance| !overrides.iter().any(|edited| edited.recurrence_id == Some(*instance)))
+        .map(|instance| occurrence(recurring.then_some(instance), instance))
+        .collect();
+    occurrences.extend(overrides.into_iter().filter(|edited| edited.start_time < end && edited.end_time > start));
+    occurrences.sort_by_key(|occurrence| occurrence.start_time);
+    occurrences.truncate(limit);
+    occurrences
+}
+
+impl From<RejectedEvent> for RejectedEventDto {
+    fn from(rejected: RejectedEvent) -> Self {
+        Self {
+            index: rejected.index,
+            uid: rejected.uid,
+            reason: rejected.reason,
+        }
+    }
+}
+
+#[async_trait]
+impl CalendarICalUseCase for CalendarICalService {
+    async fn import_calendar(
+        &self,
+        user_id: &str,
+        is_admin: bool,
+        calendar_id: &str,
+        ical_data: &str,
+        on_duplicate: DuplicateUidPolicy,
+    ) -> Result<CalendarImportResultDto, DomainError> {
+        let id = Self::parse_id(calendar_id)?;
+        self.access.check_calendar(user_id, is_admin, &id, true).await?;
+
+        let split = split_calendar(ical_data)
+            .ok_or_else(|| DomainError::validation_error("The imported file is not an iCalendar VCALENDAR"))?;
+        if split.objects.len() > MAX_IMPORT_OBJECTS {
+            return Err(DomainError::validation_error(format!(
+                "The imported file has {} events; at most {} can be imported at once",
+                split.objects.len(), MAX_IMPORT_OBJECTS
+            )));
+        }
+
+        let calendar = self.calendar_repository.find_calendar_by_id(&id).await?;
+        let calendar_timezone = calendar.vtimezone();
+        let mut existing: HashMap<String, CalendarEvent> = self.event_repository.list_events_by_calendar(&id).await?
+            .into_iter()
+            .map(|event| (event.ical_uid().to_string(), event))
+            .collect();
+
+        let mut result = CalendarImportResultDto {
+            duplicates: split.duplicates.into_iter().map(RejectedEventDto::from).collect(),
+            ..Default::default()
+        };
+        let mut new_events = Vec::new();
+        let mut replacements = Vec::new();
+
+        for object in split.objects {
+            let parsed = match CalendarEvent::from_ical_with_timezone(id, object.ical_data, calendar_timezone.as_ref()) {
+                Ok(event) => event,
+                Err(e) => {
+                    result.errors.push(RejectedEventDto { index: object.index, uid: Some(object.uid), reason: e.message });
+                    continue;
+                }
+            };
+
+            match (existing.remove(parsed.ical_uid()), on_duplicate) {
+                (None, _) => new_events.push(parsed),
+                (Some(_), DuplicateUidPolicy::Skip) => result.duplicates.push(RejectedEventDto {
+                    index: object.index,
+                    uid: Some(object.uid),
+                    reason: "An event with this UID already exists in the calendar".to_string(),
+                }),
+                (Some(stored), DuplicateUidPolicy::Replace) => {
+                    // Se conserva el ID para que las URL de los clientes sigan valiendo
+                    let replacement = CalendarEvent::with_id(
+                        *stored.id(),
+                        id,
+                        parsed.summary().to_string(),
+                        parsed.description().map(str::to_string),
+                        parsed.location().map(str::to_string),
+                        *parsed.start_time(),
+                        *parsed.end_time(),
+                        parsed.all_day(),
+                        parsed.rrule().map(str::to_string),
+                        parsed.ical_uid().to_string(),
+                        parsed.ical_data().to_string(),
+                        *stored.created_at(),
+                        Utc::now(),
+                    )?
+                    .with_timezone(parsed.timezone().map(str::to_string))
+                    .with_alarms(parsed.alarms().to_vec());
+                    replacements.push(replacement);
+                }
+            }
+        }
+
+        result.imported = new_events.len();
+        if !new_events.is_empty() {
+            self.event_repository.create_events(new_events).await?;
+        }
+        for replacement in replacements {
+            self.event_repository.update_event(replacement).await?;
+            result.replaced += 1;
+        }
+
+        result.duplicates.sort_by_key(|rejected| rejected.index);
+        tracing::info!(
+            "Imported {} events into calendar {} ({} replaced, {} duplicates, {} errors)",
+            result.imported, calendar_id, result.replaced, result.duplicates.len(), result.errors.len()
+        );
+        Ok(result)
+    }
+
+    async fn export_calendar(&self, user_id: &str, is_admin: bool, calendar_id: &str) -> Result<CalendarExport, DomainError> {
+        let id = Self::parse_id(calendar_id)?;
+        self.access.check_calendar(user_id, is_admin, &id, false).await?;
+
+        let calendar = self.calendar_repository.find_calendar_by_id(&id).await?;
+        let mut events = self.event_repository.list_events_by_calendar(&id).await?;
+        events.sort_by(|a, b| a.start_time().cmp(b.start_time()).then_with(|| a.ical_uid().cmp(b.ical_uid())));
+
+        // La zona del calendario va primero para que la usen también los eventos con horas flotantes
+        let tzid = calendar.vtimezone().map(|timezone| timezone.tzid);
+        let ical_data = merge_calendar(
+            Some(calendar.name()),
+            tzid.as_deref(),
+            calendar.timezone().into_iter().chain(events.iter().map(|event| event.ical_data())),
+        );
+        Ok(CalendarExport { name: calendar.name().to_string(), ical_data })
+    }
+
+    async fn export_calendar_json(
+        &self,
+        user_id: &str,
+        is_admin: bool,
+        calendar_id: &str,
+        expansion: EventExpansion,
+        include_ical: bool,
+    ) -> Result<CalendarJsonExportDto, DomainError> {
+        let id = Self::parse_id(calendar_id)?;
+        self.access.check_calendar(user_id, is_admin, &id, false).await?;
+
+        let calendar = self.calendar_repository.find_calendar_by_id(&id).await?;
+        let calendar_timezone = calendar.vtimezone();
+        let mut events = self.event_repository.list_events_by_calendar(&id).await?;
+        events.sort_by(|a, b| a.start_time().cmp(b.start_time()).then_with(|| a.ical_uid().cmp(b.ical_uid())));
+
+        let mut export = CalendarJsonExportDto {
+            schema: CALENDAR_JSON_EXPORT_SCHEMA.to_string(),
+            exported_at: Utc::now(),
+            calendar: CalendarDto::from(calendar),
+            events: None,
+            occurrences: None,
+            range_start: None,
+            range_end: None,
+        };
+        match expansion {
+            EventExpansion::Raw => {
+                export.events = Some(events.into_iter()
+                    .map(|event| {
+                        let mut dto = CalendarEventDto::from(event);
+                        if !include_ical {
+                            dto.ical_data.clear();
+                        }
+                        dto
+                    })
+                    .collect());
+            }
+            EventExpansion::Expanded { start, end } => {
+                if end <= start {
+                    return Err(DomainError::validation_error("The end of the range must be after its start"));
+                }
+                let mut occurrences = Vec::new();
+                for event in &events {
+                    // Se pide una más para saber si el intervalo se pasa del límite
+                    let remaining = MAX_EXPANDED_OCCURRENCES + 1 - occurrences.len();
+                    occurrences.extend(expand_event(event, calendar_timezone.as_ref(), start, end, remaining));
+                    if occurrences.len() > MAX_EXPANDED_OCCURRENCES {
+                        return Err(DomainError::validation_error(format!(
+                            "The range has more than {} occurrences; export a shorter one",
+                            MAX_EXPANDED_OCCURRENCES
+                        )));
+                    }
+                }
+                occurrences.sort_by(|a, b| a.start_time.cmp(&b.start_time).then_with(|| a.ical_uid.cmp(&b.ical_uid)));
+                export.occurrences = Some(occurrences);
+                export.range_start = Some(start);
+                export.range_end = Some(end);
+            }
+        }
+        Ok(export)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_expand_event_applies_overrides() {
+        let ical = "BEGIN:VCALENDAR\r\n\
+            BEGIN:VEVENT\r\nUID:standup\r\nDTSTART:20250101T090000Z\r\nDTEND:20250101T100000Z\r\n\
+            RRULE:FREQ=DAILY;COUNT=5\r\nSUMMARY:Standup\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nUID:standup\r\nRECURRENCE-ID:20250103T090000Z\r\nDTSTART:20250103T110000Z\r\n\
+            DTEND:20250103T113000Z\r\nSUMMARY:Late standup\r\nEND:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+        let event = CalendarEvent::from_ical(Uuid::new_v4(), ical.to_string()).unwrap();
+        let at = |day, hour, minute| Utc.with_ymd_and_hms(2025, 1, day, hour, minute, 0).unwrap();
+
+        let occurrences = expand_event(&event, None, at(2, 0, 0), at(4, 0, 0), 10);
+        assert_eq!(occurrences.len(), 2);
+        assert_eq!((occurrences[0].start_time, occurrences[0].end_time), (at(2, 9, 0), at(2, 10, 0)));
+        assert_eq!(occurrences[0].recurrence_id, Some(at(2, 9, 0)));
+        assert!(!occurrences[0].overridden);
+        assert_eq!(occurrences[1].summary, "Late standup");
+        assert_eq!((occurrences[1].start_time, occurrences[1].end_time), (at(3, 11, 0), at(3, 11, 30)));
+        assert_eq!(occurrences[1].recurrence_id, Some(at(3, 9, 0)));
+        assert!(occurrences[1].overridden);
+
+        assert_eq!(expand_event(&event, None, at(1, 0, 0), at(9, 0, 0), 3).len(), 3);
+    }
+}
@@ -7,18 +7,44 @@ use crate::application::dtos::calendar_dto::{
     CalendarDto, CalendarEventDto, CreateCalendarDto, UpdateCalendarDto,
     CreateEventDto, UpdateEventDto, CreateEventICalDto
 };
+use crate::application::dtos::notification_dto::ShareEventDto;
 use crate::application::ports::calendar_ports::{CalendarStoragePort, CalendarUseCase};
+use crate::application::ports::notification_ports::NotificationUseCase;
 use crate::interfaces::middleware::auth::CurrentUser;
 use crate::common::errors::{DomainError, ErrorKind};
+use crate::domain::entities::share_notification::{ShareChange, SharedItemKind};
+use crate::domain::services::calendar_filter_service::CompFilter;
 
 pub struct CalendarService {
     calendar_storage: Arc<dyn CalendarStoragePort>,
+    notifications: Option<Arc<dyn NotificationUseCase>>,
 }
 
 impl CalendarService {
     pub fn new(calendar_storage: Arc<dyn CalendarStoragePort>) -> Self {
         Self {
             calendar_storage,
+            notifications: None,
+        }
+    }
+
+    /// Tells users when a calendar is shared with them or unshared
+    pub fn with_notifications(mut self, notifications: Arc<dyn NotificationUseCase>) -> Self {
+        self.notifications = Some(notifications);
+        self
+    }
+
+    /// Notifies the user a calendar was shared with or taken from
+    async fn notify_share(&self, calendar: &CalendarDto, change: ShareChange, recipient: &str) {
+        if let Some(notifications) = &self.notifications {
+            notifications.notify_share(&[recipient.to_string()], ShareEventDto {
+                change,
+                item_kind: SharedItemKind::Calendar,
+                item_id: calendar.id.clone(),
+                item_name: calendar.name.clone(),
+                actor_id: Some(calendar.owner_id.clone()),
+                actor_name: None,
+            }).await;
         }
     }
 }
@@ -138,7 +164,9 @@ impl CalendarUseCase for CalendarService {
             )),
         }
         
-        self.calendar_storage.share_calendar(calendar_id, user_id, access_level).await
+        self.calendar_storage.share_calendar(calendar_id, user_id, access_level).await?;
+        self.notify_share(&calendar, ShareChange::Shared, user_id).await;
+        Ok(())
     }
     
     async fn remove_calendar_sharing(&self, calendar_id: &str, user_id: &str) -> Result<(), DomainError> {
@@ -156,7 +184,9 @@ impl CalendarUseCase for CalendarService {
             ));
         }
         
-        self.calendar_storage.remove_calendar_sharing(calendar_id, user_id).await
+        self.calendar_storage.remove_calendar_sharing(calendar_id, user_id).await?;
+        self.notify_share(&calendar, ShareChange::Unshared, user_id).await;
+        Ok(())
     }
     
     async fn get_calendar_shares(&self, calendar_id: &str) -> Result<Vec<(String, String)>, DomainError> {
@@ -177,6 +207,64 @@ impl CalendarUseCase for CalendarService {
         self.calendar_storage.get_calendar_shares(calendar_id).await
     }
     
+    async fn share_calendar_with_group(&self, calendar_id: &str, group_id: &str, access_level: &str) -> Result<(), DomainError> {
+        let current_user_id = "current_user_id";  // This should come from middleware
+        
+        // Only the owner can share the calendar
+        let calendar = self.calendar_storage.get_calendar(calendar_id).await?;
+        if calendar.owner_id != current_user_id {
+            return Err(DomainError::new(
+                ErrorKind::AccessDenied,
+                "Calendar",
+                "Only the calendar owner can change sharing settings"
+            ));
+        }
+        
+        // Validate access_level
+        match access_level {
+            "read" | "write" | "owner" => {},
+            _ => return Err(DomainError::new(
+                ErrorKind::InvalidInput,
+                "Calendar",
+                format!("Invalid access level: {}. Valid values are: read, write, owner", access_level)
+            )),
+        }
+        
+        self.calendar_storage.share_calendar_with_group(calendar_id, group_id, access_level).await
+    }
+    
+    async fn remove_calendar_group_sharing(&self, calendar_id: &str, group_id: &str) -> Result<(), DomainError> {
+        let current_user_id = "current_user_id";  // This should come from middleware
+        
+        // Only the owner can change sharing settings
+        let calendar = self.calendar_storage.get_calendar(calendar_id).await?;
+        if calendar.owner_id != current_user_id {
+            return Err(DomainError::new(
+                ErrorKind::AccessDenied,
+                "Calendar",
+                "Only the calendar owner can change sharing settings"
+            ));
+        }
+        
+        self.calendar_storage.remove_calendar_group_sharing(calendar_id, group_id).await
+    }
+    
+    async fn get_calendar_group_shares(&self, calendar_id: &str) -> Result<Vec<(String, String)>, DomainError> {
+        let current_user_id = "current_user_id";  // This should come from middleware
+        
+        // Only the owner can view sharing settings
+        let calendar = self.calendar_storage.get_calendar(calendar_id).await?;
+        if calendar.owner_id != current_user_id {
+            return Err(DomainError::new(
+                ErrorKind::AccessDenied,
+                "Calendar",
+                "Only the calendar owner can view sharing settings"
+            ));
+        }
+        
+        self.calendar_storage.get_calendar_group_shares(calendar_id).await
+    }
+    
     async fn create_event(&self, event: CreateEventDto) -> Result<CalendarEventDto, DomainError> {
         let user_id = "current_user_id";  // This should come from middleware
         
@@ -326,4 +414,38 @@ impl CalendarUseCase for CalendarService {
         
         self.calendar_storage.get_events_in_time_range(calendar_id, &start, &end).await
     }
+    
+    async fn query_events(&self, calendar_id: &str, filter: &CompFilter) -> Result<Vec<CalendarEventDto>, DomainError> {
+        let user_id = "current_user_id";  // This should come from middleware
+        
+        // Check if user has access to the calendar
+        let has_access = self.calendar_storage.check_calendar_access(calendar_id, user_id).await?;
+        
+        // Check if calendar is public
+        let calendar = self.calendar_storage.get_calendar(calendar_id).await?;
+        
+        if !has_access && !calendar.is_public {
+            return Err(DomainError::new(
+                ErrorKind::AccessDenied,
+                "Calendar",
+                "You don't have permission to view events in this calendar"
+            ));
+        }
+        
+        // Narrow the candidates in storage when the filter has an event time range;
+        // open-ended ranges extend to the limits of what the database can store
+        let candidates = match filter.event_time_range() {
+            Some(range) => {
+                let start = range.start.unwrap_or(DateTime::<Utc>::MIN_UTC);
+                let end = range.end.unwrap_or(DateTime::<Utc>::MAX_UTC);
+                self.calendar_storage.get_events_in_time_range(calendar_id, &start, &end).await?
+            },
+            None => self.calendar_storage.list_events_by_calendar(calendar_id).await?,
+        };
+        
+        // Evaluate the complete filter on each event's iCalendar data
+        Ok(candidates.into_iter()
+            .filter(|event| filter.matches_ical(&event.ical_data))
+            .collect())
+    }
 }
\ No newline at end of file
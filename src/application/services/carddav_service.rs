@@ -0,0 +1,252 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::application::dtos::address_book_dto::AddressBookDto;
+use crate::application::dtos::contact_dto::VCardObjectDto;
+use crate::application::ports::carddav_ports::CardDavUseCase;
+use crate::application::ports::contact_photo_ports::ContactPhotoStoragePort;
+use crate::application::ports::event_bus_ports::{DomainEvent, EventBusPort};
+use crate::application::services::contact_service::ContactService;
+use crate::application::services::dav_access_service::DavAccessService;
+use crate::common::errors::DomainError;
+use crate::domain::repositories::address_book_repository::AddressBookRepository;
+use crate::domain::repositories::calendar_repository::CalendarRepository;
+use crate::domain::repositories::contact_repository::ContactRepository;
+use crate::domain::services::icalendar_service::ICalComponent;
+use crate::domain::services::vcard_filter_service::AddressbookFilter;
+
+/// Tamaño máximo de una vCard subida por PUT
+pub const MAX_VCARD_BYTES: usize = 1024 * 1024;
+
+/// Si la cabecera If-Match o If-None-Match incluye la ETag (o `*`)
+fn etag_listed(header: &str, etag: &str) -> bool {
+    header.split(',')
+        .map(|tag| tag.trim())
+        .any(|tag| tag == "*" || tag.strip_prefix("W/").unwrap_or(tag).trim_matches('"') == etag)
+}
+
+/// Servicio del protocolo CardDAV (RFC 6352).
+///
+/// Cada contacto es un recurso `{uid}.vcf` de su libreta; el nombre del
+/// recurso es el que eligió el cliente al crearlo y se guarda como UID del
+/// contacto, aunque la vCard declare otro. La ETag cambia con cada escritura.
+pub struct CardDavService {
+    access: DavAccessService,
+    address_book_repository: Arc<dyn AddressBookRepository>,
+    contact_repository: Arc<dyn ContactRepository>,
+    photo_storage: Option<Arc<dyn ContactPhotoStoragePort>>,
+    event_bus: Option<Arc<dyn EventBusPort>>,
+}
+
+impl CardDavService {
+    /// Crea un nuevo servicio CardDAV
+    pub fn new(
+        calendar_repository: Arc<dyn CalendarRepository>,
+        address_book_repository: Arc<dyn AddressBookRepository>,
+        contact_repository: Arc<dyn ContactRepository>,
+    ) -> Self {
+        Self {
+            access: DavAccessService::new(calendar_repository, address_book_repository.clone()),
+            address_book_repository,
+            contact_repository,
+            photo_storage: None,
+            event_bus: None,
+        }
+    }
+
+    /// Guarda también en el almacenamiento las fotos incrustadas en las vCards
+    pub fn with_photo_storage(mut self, photo_storage: Arc<dyn ContactPhotoStoragePort>) -> Self {
+        self.photo_storage = Some(photo_storage);
+        self
+    }
+
+    /// Publica los contactos borrados en el bus de eventos de dominio
+    pub fn with_event_bus(mut self, event_bus: Arc<dyn EventBusPort>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    fn parse_id(address_book_id: &str) -> Result<Uuid, DomainError> {
+        Uuid::parse_str(address_book_id)
+            .map_err(|_| DomainError::validation_error(format!("Invalid address book ID: {}", address_book_id)))
+    }
+
+    /// Comprueba el acceso a la libreta y devuelve su id
+    async fn checked_id(&self, user_id: &str, is_admin: bool, address_book_id: &str, write: bool) -> Result<Uuid, DomainError> {
+        let id = Self::parse_id(address_book_id)?;
+        self.access.check_address_book(user_id, is_admin, &id, write).await?;
+        Ok(id)
+    }
+}
+
+#[async_trait]
+impl CardDavUseCase for CardDavService {
+    async fn list_address_books(&self, user_id: &str) -> Result<Vec<AddressBookDto>, DomainError> {
+        let mut address_books = self.address_book_repository.get_address_books_by_owner(user_id).await?;
+        for shared in self.address_book_repository.get_shared_address_books(user_id).await? {
+            if !address_books.iter().any(|address_book| address_book.id == shared.id) {
+                address_books.push(shared);
+            }
+        }
+        Ok(address_books.into_iter().map(AddressBookDto::from).collect())
+    }
+
+    async fn get_address_book(&self, user_id: &str, is_admin: bool, address_book_id: &str) -> Result<AddressBookDto, DomainError> {
+        let id = self.checked_id(user_id, is_admin, address_book_id, false).await?;
+        self.address_book_repository.get_address_book_by_id(&id).await?
+            .map(AddressBookDto::from)
+            .ok_or_else(|| DomainError::not_found("Address book", address_book_id))
+    }
+
+    async fn list_vcards(&self, user_id: &str, is_admin: bool, address_book_id: &str) -> Result<Vec<VCardObjectDto>, DomainError> {
+        let id = self.checked_id(user_id, is_admin, address_book_id, false).await?;
+        Ok(self.contact_repository.get_contacts_by_address_book(&id).await?
+            .into_iter()
+            .map(VCardObjectDto::from)
+            .collect())
+    }
+
+    async fn query_vcards(
+        &self,
+        user_id: &str,
+        is_admin: bool,
+        address_book_id: &str,
+        filter: &AddressbookFilter,
+    ) -> Result<Vec<VCardObjectDto>, DomainError> {
+        let id = self.checked_id(user_id, is_admin, address_book_id, false).await?;
+        Ok(self.contact_repository.get_contacts_by_address_book(&id).await?
+            .into_iter()
+            .filter(|contact| filter.matches_vcard(&contact.vcard))
+            .map(VCardObjectDto::from)
+            .collect())
+    }
+
+    async fn get_vcards(
+        &self,
+        user_id: &str,
+        is_admin: bool,
+        address_book_id: &str,
+        uids: &[String],
+    ) -> Result<Vec<(String, Option<VCardObjectDto>)>, DomainError> {
+        let id = self.checked_id(user_id, is_admin, address_book_id, false).await?;
+        let mut vcards = Vec::with_capacity(uids.len());
+        for uid in uids {
+            let contact = self.contact_repository.get_contact_by_uid(&id, uid).await?;
+            vcards.push((uid.clone(), contact.map(VCardObjectDto::from)));
+        }
+        Ok(vcards)
+    }
+
+    async fn get_vcard(&self, user_id: &str, is_admin: bool, address_book_id: &str, uid: &str) -> Result<VCardObjectDto, DomainError> {
+        let id = self.checked_id(user_id, is_admin, address_book_id, false).await?;
+        self.contact_repository.get_contact_by_uid(&id, uid).await?
+            .map(VCardObjectDto::from)
+            .ok_or_else(|| DomainError::not_found("Contact", uid))
+    }
+
+    async fn put_vcard(
+        &self,
+        user_id: &str,
+        is_admin: bool,
+        address_book_id: &str,
+        uid: &str,
+        vcard: &str,
+        if_match: Option<&str>,
+        if_none_match: Option<&str>,
+    ) -> Result<(VCardObjectDto, bool), DomainError> {
+        let id = self.checked_id(user_id, is_admin, address_book_id, true).await?;
+        if uid.trim().is_empty() || uid.contains('/') {
+            return Err(DomainError::validation_error(format!("Invalid contact resource name: {}", uid)));
+        }
+        if vcard.len() > MAX_VCARD_BYTES {
+            return Err(DomainError::validation_error("The vCard is too large"));
+        }
+        if !ICalComponent::parse(vcard).is_some_and(|card| card.name == "VCARD") {
+            return Err(DomainError::validation_error("The body is not a vCard"));
+        }
+
+        let existing = self.contact_repository.get_contact_by_uid(&id, uid).await?;
+        match (&existing, if_match, if_none_match) {
+            (Some(contact), _, Some(header)) if etag_listed(header, &contact.etag) => {
+                return Err(DomainError::precondition_failed("Contact", format!("Contact {} already exists", uid)));
+            }
+            (Some(contact), Some(header), _) if !etag_listed(header, &contact.etag) => {
+                return Err(DomainError::precondition_failed("Contact", format!("Contact {} has been modified", uid)));
+            }
+            (None, Some(_), _) => {
+                return Err(DomainError::precondition_failed("Contact", format!("Contact {} does not exist", uid)));
+            }
+            _ => {}
+        }
+
+        let mut contact = ContactService::parse_vcard(vcard)?;
+        contact.address_book_id = id;
+        contact.uid = uid.to_string();
+        contact.etag = Uuid::new_v4().to_string();
+        contact.updated_at = Utc::now();
+
+        let (contact, created) = match existing {
+            Some(previous) => {
+                contact.id = previous.id;
+                contact.created_at = previous.created_at;
+                (self.contact_repository.update_contact(contact).await?, false)
+            }
+            None => {
+                contact.created_at = contact.updated_at;
+                (self.contact_repository.create_contact(contact).await?, true)
+            }
+        };
+        if let Some(photo_storage) = &self.photo_storage {
+            ContactService::sync_stored_photo(photo_storage.as_ref(), &contact.id.to_string(), &contact.vcard).await?;
+        }
+        Ok((VCardObjectDto::from(contact), created))
+    }
+
+    async fn delete_vcard(
+        &self,
+        user_id: &str,
+        is_admin: bool,
+        address_book_id: &str,
+        uid: &str,
+        if_match: Option<&str>,
+    ) -> Result<(), DomainError> {
+        let id = self.checked_id(user_id, is_admin, address_book_id, true).await?;
+        let contact = self.contact_repository.get_contact_by_uid(&id, uid).await?
+            .ok_or_else(|| DomainError::not_found("Contact", uid))?;
+        if if_match.is_some_and(|header| !etag_listed(header, &contact.etag)) {
+            return Err(DomainError::precondition_failed("Contact", format!("Contact {} has been modified", uid)));
+        }
+        self.contact_repository.delete_contact(&contact.id).await?;
+        if let Some(photo_storage) = &self.photo_storage {
+            photo_storage.delete_photo(&contact.id.to_string()).await?;
+        }
+        if let Some(event_bus) = &self.event_bus {
+            if let Some(address_book) = self.address_book_repository.get_address_book_by_id(&id).await? {
+                event_bus.publish(DomainEvent::ContactDeleted {
+                    contact_id: contact.id.to_string(),
+                    address_book_id: id.to_string(),
+                    uid: contact.uid,
+                    owner_id: address_book.owner_id,
+                    deleted_by: user_id.to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_etag_listed() {
+        assert!(etag_listed("\"abc\"", "abc"));
+        assert!(etag_listed("W/\"x\", \"abc\"", "abc"));
+        assert!(etag_listed("*", "abc"));
+        assert!(!etag_listed("\"abd\"", "abc"));
+    }
+}
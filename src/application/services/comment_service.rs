@@ -0,0 +1,403 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::application::dtos::comment_dto::{CommentDto, CommentQueryDto, CreateCommentDto, UpdateCommentDto};
+use crate::application::dtos::notification_dto::MentionEventDto;
+use crate::application::dtos::pagination::{PaginatedResponseDto, PaginationRequestDto};
+use crate::application::ports::auth_ports::UserStoragePort;
+use crate::application::ports::comment_ports::CommentUseCase;
+use crate::application::ports::inbound::{FileUseCase, FolderUseCase};
+use crate::application::ports::notification_ports::NotificationUseCase;
+use crate::application::ports::share_ports::{ShareAccessUseCase, ShareRecipient, ShareTarget};
+use crate::common::errors::{DomainError, ErrorKind};
+use crate::domain::entities::comment::{extract_mentions, Comment, CommentTargetKind};
+use crate::domain::entities::share::SharePermissions;
+use crate::domain::entities::user::{User, UserRole};
+use crate::domain::repositories::comment_repository::CommentRepository;
+
+/// Comentarios por página si la consulta no indica otro tamaño
+const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// Caracteres del comentario que se incluyen en el aviso de una mención
+const MENTION_EXCERPT_LENGTH: usize = 140;
+
+/// Servicio de comentarios sobre archivos y carpetas.
+///
+/// Un `@usuario` en el texto avisa al usuario mencionado en su centro de
+/// notificaciones. Las menciones a usuarios que no existen o que no pueden
+/// leer el elemento se ignoran, igual que las que el autor se hace a sí mismo.
+/// Para leer los comentarios hay que poder leer el elemento y para escribirlos,
+/// modificarlo; los administradores llegan a todos.
+pub struct CommentService {
+    repository: Arc<dyn CommentRepository>,
+    files: Arc<dyn FileUseCase>,
+    folders: Arc<dyn FolderUseCase>,
+    users: Arc<dyn UserStoragePort>,
+    share_access: Arc<dyn ShareAccessUseCase>,
+    notifications: Option<Arc<dyn NotificationUseCase>>,
+}
+
+impl CommentService {
+    pub fn new(
+        repository: Arc<dyn CommentRepository>,
+        files: Arc<dyn FileUseCase>,
+        folders: Arc<dyn FolderUseCase>,
+        users: Arc<dyn UserStoragePort>,
+        share_access: Arc<dyn ShareAccessUseCase>,
+    ) -> Self {
+        Self {
+            repository,
+            files,
+            folders,
+            users,
+            share_access,
+            notifications: None,
+        }
+    }
+
+    /// Avisa a los usuarios mencionados en los comentarios
+    pub fn with_notifications(mut self, notifications: Arc<dyn NotificationUseCase>) -> Self {
+        self.notifications = Some(notifications);
+        self
+    }
+
+    /// Nombre del archivo o la carpeta; falla si no existe
+    async fn item_name(&self, item_kind: CommentTargetKind, item_id: &str) -> Result<String, DomainError> {
+        match item_kind {
+            CommentTargetKind::File => self.files.get_file(item_id).await.map(|file| file.name),
+            CommentTargetKind::Folder => self.folders.get_folder(item_id).await.map(|folder| folder.name),
+        }
+    }
+
+    /// Comprueba que el usuario tenga los permisos indicados sobre el elemento
+    async fn ensure_access(
+        &self,
+        user: ShareRecipient<'_>,
+        is_admin: bool,
+        item_kind: CommentTargetKind,
+        item_id: &str,
+        required: SharePermissions,
+    ) -> Result<(), DomainError> {
+        if is_admin {
+            return Ok(());
+        }
+        let target = match item_kind {
+            CommentTargetKind::File => ShareTarget::File(item_id.to_string()),
+            CommentTargetKind::Folder => ShareTarget::Folder(item_id.to_string()),
+        };
+        self.share_access.ensure_access(user, &target, required).await
+    }
+
+    /// Si un usuario mencionado puede leer el elemento comentado
+    async fn can_read(&self, user: &User, item_kind: CommentTargetKind, item_id: &str) -> bool {
+        let recipient = ShareRecipient { user_id: user.id(), username: user.username(), email: user.email() };
+        let is_admin = user.role() == UserRole::Admin;
+        self.ensure_access(recipient, is_admin, item_kind, item_id, SharePermissions::READ).await.is_ok()
+    }
+
+    /// IDs de los usuarios mencionados en el texto que pueden leer el
+    /// elemento, sin el autor
+    async fn resolve_mentions(&self, author_id: &str, item_kind: CommentTargetKind, item_id: &str, message: &str) -> Vec<String> {
+        let mut mentions: Vec<String> = Vec::new();
+        for username in extract_mentions(message) {
+            match self.users.get_user_by_username(&username).await {
+                Ok(user) => {
+                    if user.id() != author_id
+                        && !mentions.iter().any(|id| id == user.id())
+                        && self.can_read(&user, item_kind, item_id).await
+                    {
+                        mentions.push(user.id().to_string());
+                    }
+                }
+                Err(e) if e.kind == ErrorKind::NotFound => {}
+                Err(e) => tracing::warn!("No se pudo resolver la mención de @{}: {}", username, e),
+            }
+        }
+        mentions
+    }
+
+    /// Avisa de un comentario a los usuarios indicados
+    async fn notify_mentions(&self, comment: &Comment, recipients: &[String]) {
+        let Some(notifications) = &self.notifications else { return };
+        if recipients.is_empty() {
+            return;
+        }
+
+        let item_name = match self.item_name(comment.item_kind, &comment.item_id).await {
+            Ok(name) => name,
+            Err(e) => {
+                tracing::warn!("No se pudo avisar de las menciones del comentario {}: {}", comment.id, e);
+                return;
+            }
+        };
+
+        notifications.notify_mention(recipients, MentionEventDto {
+            comment_id: comment.id.clone(),
+            item_kind: comment.item_kind,
+            item_id: comment.item_id.clone(),
+            item_name,
+            actor_id: comment.author_id.clone(),
+            actor_name: comment.author_name.clone(),
+            excerpt: comment.message.chars().take(MENTION_EXCERPT_LENGTH).collect(),
+        }).await;
+    }
+
+    async fn load(&self, comment_id: &str) -> Result<Comment, DomainError> {
+        self.repository.get_comment(comment_id).await?
+            .ok_or_else(|| DomainError::not_found("Comment", comment_id))
+    }
+}
+
+#[async_trait]
+impl CommentUseCase for CommentService {
+    async fn list_comments(
+        &self,
+        caller: ShareRecipient<'_>,
+        is_admin: bool,
+        item_kind: CommentTargetKind,
+        item_id: &str,
+        query: CommentQueryDto,
+    ) -> Result<PaginatedResponseDto<CommentDto>, DomainError> {
+        self.ensure_access(caller, is_admin, item_kind, item_id, SharePermissions::READ).await?;
+        self.item_name(item_kind, item_id).await?;
+
+        let pagination = PaginationRequestDto {
+            page: query.page,
+            page_size: query.page_size.unwrap_or(DEFAULT_PAGE_SIZE),
+        }.validate_and_adjust();
+
+        let (comments, total) = self.repository
+            .list_comments(item_kind, item_id, pagination.limit() as i64, pagination.offset() as i64)
+            .await?;
+
+        Ok(PaginatedResponseDto::new(
+            comments.into_iter().map(CommentDto::from).collect(),
+            pagination.page,
+            pagination.page_size,
+            total.max(0) as usize,
+        ))
+    }
+
+    async fn create_comment(
+        &self,
+        author: ShareRecipient<'_>,
+        is_admin: bool,
+        item_kind: CommentTargetKind,
+        item_id: &str,
+        request: CreateCommentDto,
+    ) -> Result<CommentDto, DomainError> {
+        self.ensure_access(author, is_admin, item_kind, item_id, SharePermissions::WRITE).await?;
+        self.item_name(item_kind, item_id).await?;
+
+        let mut comment = Comment::new(
+            item_kind,
+            item_id.to_string(),
+            author.user_id.to_string(),
+            author.username.to_string(),
+            &request.message,
+        )?;
+        comment.mentions = self.resolve_mentions(author.user_id, item_kind, item_id, &comment.message).await;
+        self.repository.create_comment(&comment).await?;
+
+        self.notify_mentions(&comment, &comment.mentions).await;
+        Ok(CommentDto::from(comment))
+    }
+
+    async fn update_comment(&self, user_id: &str, comment_id: &str, request: UpdateCommentDto) -> Result<CommentDto, DomainError> {
+        let mut comment = self.load(comment_id).await?;
+        if comment.author_id != user_id {
+            return Err(DomainError::access_denied("Comment", "Solo el autor puede editar el comentario"));
+        }
+
+        comment.edit(&request.message)?;
+        let previous = std::mem::take(&mut comment.mentions);
+        comment.mentions = self.resolve_mentions(user_id, comment.item_kind, &comment.item_id, &comment.message).await;
+        self.repository.update_comment(&comment).await?;
+
+        // Quien ya estaba mencionado recibió el aviso al escribirse el comentario
+        let added: Vec<String> = comment.mentions.iter()
+            .filter(|id| !previous.contains(id))
+            .cloned()
+            .collect();
+        self.notify_mentions(&comment, &added).await;
+        Ok(CommentDto::from(comment))
+    }
+
+    async fn delete_comment(&self, user_id: &str, is_admin: bool, comment_id: &str) -> Result<(), DomainError> {
+        let comment = self.load(comment_id).await?;
+        if comment.author_id != user_id && !is_admin {
+            return Err(DomainError::access_denied("Comment", "Solo el autor o un administrador pueden borrar el comentario"));
+        }
+
+        if !self.repository.delete_comment(comment_id).await? {
+            return Err(DomainError::not_found("Comment", comment_id));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use crate::application::dtos::notification_dto::{NotificationContentDto, NotificationDto};
+    use crate::application::services::file_service::FileService;
+    use crate::application::services::folder_service::FolderService;
+    use crate::application::services::notification_service::NotificationService;
+    use crate::application::services::test_access::HomeOnlyAccess;
+    use crate::application::services::test_users::MemoryUsers;
+    use crate::domain::repositories::comment_repository::CommentRepositoryResult;
+
+    #[derive(Default)]
+    struct InMemoryCommentRepository {
+        comments: Mutex<Vec<Comment>>,
+    }
+
+    #[async_trait]
+    impl CommentRepository for InMemoryCommentRepository {
+        async fn create_comment(&self, comment: &Comment) -> CommentRepositoryResult<()> {
+            self.comments.lock().unwrap().push(comment.clone());
+            Ok(())
+        }
+        async fn update_comment(&self, comment: &Comment) -> CommentRepositoryResult<()> {
+            let mut comments = self.comments.lock().unwrap();
+            let stored = comments.iter_mut().find(|stored| stored.id == comment.id)
+                .ok_or_else(|| DomainError::not_found("Comment", comment.id.clone()))?;
+            *stored = comment.clone();
+            Ok(())
+        }
+        async fn get_comment(&self, id: &str) -> CommentRepositoryResult<Option<Comment>> {
+            Ok(self.comments.lock().unwrap().iter().find(|comment| comment.id == id).cloned())
+        }
+        async fn list_comments(
+            &self,
+            item_kind: CommentTargetKind,
+            item_id: &str,
+            limit: i64,
+            offset: i64,
+        ) -> CommentRepositoryResult<(Vec<Comment>, i64)> {
+            let comments: Vec<Comment> = self.comments.lock().unwrap().iter()
+                .filter(|comment| comment.item_kind == item_kind && comment.item_id == item_id)
+                .cloned()
+                .collect();
+            let total = comments.len() as i64;
+            Ok((comments.into_iter().skip(offset as usize).take(limit as usize).collect(), total))
+        }
+        async fn delete_comment(&self, id: &str) -> CommentRepositoryResult<bool> {
+            let mut comments = self.comments.lock().unwrap();
+            let before = comments.len();
+            comments.retain(|comment| comment.id != id);
+            Ok(comments.len() != before)
+        }
+    }
+
+    fn service(users: Arc<MemoryUsers>, notifications: Arc<NotificationService>) -> CommentService {
+        service_with_access(users, notifications, Arc::new(HomeOnlyAccess::default()))
+    }
+
+    fn service_with_access(
+        users: Arc<MemoryUsers>,
+        notifications: Arc<NotificationService>,
+        access: Arc<HomeOnlyAccess>,
+    ) -> CommentService {
+        CommentService::new(
+            Arc::new(InMemoryCommentRepository::default()),
+            Arc::new(FileService::new_stub()),
+            Arc::new(FolderService::new_stub()),
+            users,
+            access,
+        ).with_notifications(notifications)
+    }
+
+    /// Identidad de un usuario de `MemoryUsers`
+    fn recipient<'a>(id: &'a str, username: &'a str, email: &'a str) -> ShareRecipient<'a> {
+        ShareRecipient { user_id: id, username, email }
+    }
+
+    fn message(text: &str) -> CreateCommentDto {
+        CreateCommentDto { message: text.to_string() }
+    }
+
+    fn mentions_of(notifications: &[NotificationDto]) -> usize {
+        notifications.iter()
+            .filter(|notification| matches!(notification.content, NotificationContentDto::Mention(_)))
+            .count()
+    }
+
+    #[tokio::test]
+    async fn test_mentions_notify_once() {
+        let users = Arc::new(MemoryUsers::with_usernames(&["ana", "bob", "carla"]));
+        let notifications = Arc::new(NotificationService::new());
+        let service = service(users.clone(), notifications.clone());
+        let (ana, bob, carla) = (users.id_of("ana"), users.id_of("bob"), users.id_of("carla"));
+        let author = recipient(&ana, "ana", "ana@example.com");
+
+        // Ni las menciones a uno mismo ni a usuarios desconocidos cuentan
+        let comment = service.create_comment(author, false, CommentTargetKind::File, "f1", message("@bob mira esto, @ana @nadie")).await.unwrap();
+        assert_eq!(comment.mentions, vec![bob.clone()]);
+        assert_eq!(mentions_of(&notifications.list_notifications(Some(&bob)).await), 1);
+        assert_eq!(mentions_of(&notifications.list_notifications(Some(&ana)).await), 0);
+
+        // Al editar solo se avisa a los mencionados por primera vez
+        let edited = service.update_comment(&ana, &comment.id, UpdateCommentDto { message: "@bob y @carla, mirad esto".to_string() }).await.unwrap();
+        assert_eq!(edited.mentions, vec![bob.clone(), carla.clone()]);
+        assert!(edited.edited_at.is_some());
+        assert_eq!(mentions_of(&notifications.list_notifications(Some(&bob)).await), 1);
+        assert_eq!(mentions_of(&notifications.list_notifications(Some(&carla)).await), 1);
+    }
+
+    #[tokio::test]
+    async fn test_only_author_edits_and_author_or_admin_deletes() {
+        let users = Arc::new(MemoryUsers::with_usernames(&["ana", "bob"]));
+        let service = service(users.clone(), Arc::new(NotificationService::new()));
+        let (ana, bob) = (users.id_of("ana"), users.id_of("bob"));
+        let author = recipient(&ana, "ana", "ana@example.com");
+
+        let first = service.create_comment(author, false, CommentTargetKind::Folder, "d1", message("Primero")).await.unwrap();
+        let second = service.create_comment(author, false, CommentTargetKind::Folder, "d1", message("Segundo")).await.unwrap();
+
+        let denied = service.update_comment(&bob, &first.id, UpdateCommentDto { message: "Mío".to_string() }).await;
+        assert_eq!(denied.unwrap_err().kind, ErrorKind::AccessDenied);
+        let denied = service.delete_comment(&bob, false, &first.id).await;
+        assert_eq!(denied.unwrap_err().kind, ErrorKind::AccessDenied);
+
+        service.delete_comment(&bob, true, &first.id).await.unwrap();
+        service.delete_comment(&ana, false, &second.id).await.unwrap();
+        let missing = service.delete_comment(&ana, false, &second.id).await;
+        assert_eq!(missing.unwrap_err().kind, ErrorKind::NotFound);
+
+        let page = service.list_comments(author, false, CommentTargetKind::Folder, "d1", CommentQueryDto::default()).await.unwrap();
+        assert!(page.items.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_comments_follow_access_to_the_item() {
+        let users = Arc::new(MemoryUsers::with_usernames(&["ana", "bob", "carla"]));
+        let notifications = Arc::new(NotificationService::new());
+        let access = Arc::new(HomeOnlyAccess::default());
+        let service = service_with_access(users.clone(), notifications.clone(), access.clone());
+        let (ana, bob, carla) = (users.id_of("ana"), users.id_of("bob"), users.id_of("carla"));
+        let ana_user = recipient(&ana, "ana", "ana@example.com");
+        let bob_user = recipient(&bob, "bob", "bob@example.com");
+        let plan = "/Mi Carpeta - ana/plan.txt";
+        access.grant("bob", ShareTarget::File(plan.to_string()), SharePermissions::READ);
+
+        // Solo se avisa a quien puede leer el archivo
+        let comment = service.create_comment(ana_user, false, CommentTargetKind::File, plan, message("@bob @carla revisadlo")).await.unwrap();
+        assert_eq!(comment.mentions, vec![bob.clone()]);
+        assert_eq!(mentions_of(&notifications.list_notifications(Some(&carla)).await), 0);
+
+        // Bob puede leer los comentarios, pero no escribirlos
+        let page = service.list_comments(bob_user, false, CommentTargetKind::File, plan, CommentQueryDto::default()).await.unwrap();
+        assert_eq!(page.items.len(), 1);
+        let denied = service.create_comment(bob_user, false, CommentTargetKind::File, plan, message("Visto")).await;
+        assert_eq!(denied.unwrap_err().kind, ErrorKind::AccessDenied);
+
+        // Carla no llega al archivo; un administrador sí
+        let carla_user = recipient(&carla, "carla", "carla@example.com");
+        let denied = service.list_comments(carla_user, false, CommentTargetKind::File, plan, CommentQueryDto::default()).await;
+        assert_eq!(denied.unwrap_err().kind, ErrorKind::AccessDenied);
+        service.create_comment(carla_user, true, CommentTargetKind::File, plan, message("Revisado")).await.unwrap();
+    }
+}
@@ -0,0 +1,244 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::application::dtos::address_book_dto::AddressBookDto;
+use crate::application::dtos::contact_dto::{
+    AddressBookJsonExportDto, ContactDto, ContactImportFormat, ContactImportResultDto, RejectedContactDto,
+    ADDRESS_BOOK_JSON_EXPORT_SCHEMA,
+};
+use crate::application::ports::contact_import_ports::{AddressBookExport, ContactImportUseCase};
+use crate::application::ports::contact_photo_ports::ContactPhotoStoragePort;
+use crate::application::services::contact_service::ContactService;
+use crate::application::services::dav_access_service::DavAccessService;
+use crate::common::errors::DomainError;
+use crate::domain::entities::contact::Contact;
+use crate::domain::repositories::address_book_repository::AddressBookRepository;
+use crate::domain::repositories::calendar_repository::CalendarRepository;
+use crate::domain::repositories::contact_repository::ContactRepository;
+use crate::domain::services::contact_import_service::{csv_record_to_vcard, parse_csv, split_vcards, CsvField};
+
+/// Número máximo de contactos que se aceptan en una importación
+pub const MAX_IMPORT_CONTACTS: usize = 10_000;
+
+/// Contactos que se guardan en cada transacción
+const IMPORT_BATCH_SIZE: usize = 100;
+
+/// Registro del fichero ya convertido a vCard, o el motivo por el que no se pudo leer
+struct ImportRecord {
+    index: usize,
+    vcard: Result<String, String>,
+}
+
+/// Servicio de importación masiva de contactos desde ficheros .vcf y .csv,
+/// y de exportación de libretas completas como un único .vcf.
+///
+/// Las filas de un CSV se convierten en vCards y a partir de ahí se tratan
+/// igual que las de un .vcf. Los contactos se guardan por lotes en una
+/// transacción cada uno; si un lote falla se reintenta contacto a contacto
+/// para saber cuál es el que no se puede guardar.
+pub struct ContactImportService {
+    access: DavAccessService,
+    address_book_repository: Arc<dyn AddressBookRepository>,
+    contact_repository: Arc<dyn ContactRepository>,
+    photo_storage: Option<Arc<dyn ContactPhotoStoragePort>>,
+}
+
+impl ContactImportService {
+    /// Crea un nuevo servicio de importación de contactos
+    pub fn new(
+        calendar_repository: Arc<dyn CalendarRepository>,
+        address_book_repository: Arc<dyn AddressBookRepository>,
+        contact_repository: Arc<dyn ContactRepository>,
+    ) -> Self {
+        Self {
+            access: DavAccessService::new(calendar_repository, address_book_repository.clone()),
+            address_book_repository,
+            contact_repository,
+            photo_storage: None,
+        }
+    }
+
+    /// Guarda también en el almacenamiento las fotos incrustadas en las vCards
+    pub fn with_photo_storage(mut self, photo_storage: Arc<dyn ContactPhotoStoragePort>) -> Self {
+        self.photo_storage = Some(photo_storage);
+        self
+    }
+
+    fn parse_id(address_book_id: &str) -> Result<Uuid, DomainError> {
+        Uuid::parse_str(address_book_id)
+            .map_err(|_| DomainError::validation_error(format!("Invalid address book ID: {}", address_book_id)))
+    }
+
+    /// Convierte las filas de un CSV en vCards; la primera fila es la cabecera
+    fn csv_records(data: &str, columns: Option<&[String]>) -> Result<Vec<ImportRecord>, DomainError> {
+        let mut rows = parse_csv(data).into_iter();
+        let header = rows.next()
+            .ok_or_else(|| DomainError::validation_error("The imported CSV file is empty"))?;
+
+        let fields: Vec<Option<CsvField>> = match columns {
+            Some(columns) => columns.iter()
+                .map(|name| match name.trim() {
+                    "" => Ok(None),
+                    name => CsvField::parse(name)
+                        .map(Some)
+                        .ok_or_else(|| DomainError::validation_error(format!("Unknown contact field in the column mapping: {}", name))),
+                })
+                .collect::<Result<_, _>>()?,
+            None => header.iter().map(|name| CsvField::from_header(name)).collect(),
+        };
+        if fields.iter().all(Option::is_none) {
+            return Err(DomainError::validation_error("No CSV column matches a contact field; provide a column mapping"));
+        }
+
+        Ok(rows.enumerate()
+            .map(|(index, row)| ImportRecord { index, vcard: csv_record_to_vcard(&fields, &row) })
+            .collect())
+    }
+
+    /// Guarda un lote; si la transacción falla prueba con cada contacto por
+    /// separado y anota los que no se pueden guardar
+    async fn store_batch(&self, batch: Vec<(usize, Contact)>, result: &mut ContactImportResultDto) -> Vec<Contact> {
+        let contacts: Vec<Contact> = batch.iter().map(|(_, contact)| contact.clone()).collect();
+        match self.contact_repository.create_contacts(contacts).await {
+            Ok(created) => created,
+            Err(e) => {
+                tracing::warn!("Contact import batch failed, retrying one by one: {}", e);
+                let mut created = Vec::with_capacity(batch.len());
+                for (index, contact) in batch {
+                    let uid = contact.uid.clone();
+                    match self.contact_repository.create_contact(contact).await {
+                        Ok(contact) => created.push(contact),
+                        Err(e) => result.errors.push(RejectedContactDto { index, uid: Some(uid), reason: e.message }),
+                    }
+                }
+                created
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ContactImportUseCase for ContactImportService {
+    async fn import_contacts(
+        &self,
+        user_id: &str,
+        is_admin: bool,
+        address_book_id: &str,
+        data: &str,
+        format: ContactImportFormat,
+        columns: Option<&[String]>,
+    ) -> Result<ContactImportResultDto, DomainError> {
+        let id = Self::parse_id(address_book_id)?;
+        self.access.check_address_book(user_id, is_admin, &id, true).await?;
+
+        let records = match format {
+            ContactImportFormat::Vcf => split_vcards(data).into_iter()
+                .enumerate()
+                .map(|(index, vcard)| ImportRecord { index, vcard: Ok(vcard) })
+                .collect(),
+            ContactImportFormat::Csv => Self::csv_records(data, columns)?,
+        };
+        if records.is_empty() {
+            return Err(DomainError::validation_error("The imported file does not contain any contact"));
+        }
+        if records.len() > MAX_IMPORT_CONTACTS {
+            return Err(DomainError::validation_error(format!(
+                "The imported file has {} contacts; at most {} can be imported at once",
+                records.len(), MAX_IMPORT_CONTACTS
+            )));
+        }
+
+        let mut used_uids: HashSet<String> = self.contact_repository.get_contacts_by_address_book(&id).await?
+            .into_iter()
+            .map(|contact| contact.uid)
+            .collect();
+        let mut result = ContactImportResultDto { total: records.len(), ..Default::default() };
+        let mut pending = Vec::new();
+
+        for record in records {
+            let parsed = record.vcard.and_then(|vcard| ContactService::parse_vcard(&vcard).map_err(|e| e.message));
+            let mut contact = match parsed {
+                Ok(contact) => contact,
+                Err(reason) => {
+                    result.errors.push(RejectedContactDto { index: record.index, uid: None, reason });
+                    continue;
+                }
+            };
+            if !used_uids.insert(contact.uid.clone()) {
+                result.duplicates.push(RejectedContactDto {
+                    index: record.index,
+                    uid: Some(contact.uid),
+                    reason: "A contact with this UID already exists in the address book".to_string(),
+                });
+                continue;
+            }
+
+            let now = Utc::now();
+            contact.id = Uuid::new_v4();
+            contact.address_book_id = id;
+            contact.created_at = now;
+            contact.updated_at = now;
+            pending.push((record.index, contact));
+        }
+
+        let mut pending = pending.into_iter().peekable();
+        while pending.peek().is_some() {
+            let batch: Vec<(usize, Contact)> = pending.by_ref().take(IMPORT_BATCH_SIZE).collect();
+            let created = self.store_batch(batch, &mut result).await;
+            result.imported += created.len();
+            if let Some(photo_storage) = &self.photo_storage {
+                for contact in &created {
+                    ContactService::sync_stored_photo(photo_storage.as_ref(), &contact.id.to_string(), &contact.vcard).await?;
+                }
+            }
+        }
+
+        result.errors.sort_by_key(|rejected| rejected.index);
+        tracing::info!(
+            "Imported {} of {} contacts into address book {} ({} duplicates, {} errors)",
+            result.imported, result.total, address_book_id, result.duplicates.len(), result.errors.len()
+        );
+        Ok(result)
+    }
+
+    async fn export_address_book(&self, user_id: &str, is_admin: bool, address_book_id: &str) -> Result<AddressBookExport, DomainError> {
+        let id = Self::parse_id(address_book_id)?;
+        self.access.check_address_book(user_id, is_admin, &id, false).await?;
+
+        let address_book = self.address_book_repository.get_address_book_by_id(&id).await?
+            .ok_or_else(|| DomainError::not_found("Address book", address_book_id))?;
+        let mut contacts = self.contact_repository.get_contacts_by_address_book(&id).await?;
+        contacts.sort_by(|a, b| a.full_name.cmp(&b.full_name).then_with(|| a.uid.cmp(&b.uid)));
+
+        // Algunas vCards guardadas terminan sin salto de línea y se pegarían a la siguiente
+        let vcards = contacts.into_iter()
+            .map(|contact| {
+                let mut vcard = contact.vcard.trim_end().to_string();
+                vcard.push_str("\r\n");
+                vcard
+            })
+            .collect();
+        Ok(AddressBookExport { name: address_book.name, vcards })
+    }
+
+    async fn export_address_book_json(&self, user_id: &str, is_admin: bool, address_book_id: &str) -> Result<AddressBookJsonExportDto, DomainError> {
+        let id = Self::parse_id(address_book_id)?;
+        self.access.check_address_book(user_id, is_admin, &id, false).await?;
+
+        let address_book = self.address_book_repository.get_address_book_by_id(&id).await?
+            .ok_or_else(|| DomainError::not_found("Address book", address_book_id))?;
+        let mut contacts = self.contact_repository.get_contacts_by_address_book(&id).await?;
+        contacts.sort_by(|a, b| a.full_name.cmp(&b.full_name).then_with(|| a.uid.cmp(&b.uid)));
+
+        Ok(AddressBookJsonExportDto {
+            schema: ADDRESS_BOOK_JSON_EXPORT_SCHEMA.to_string(),
+            exported_at: Utc::now(),
+            address_book: AddressBookDto::from(address_book),
+            contacts: contacts.into_iter().map(ContactDto::from).collect(),
+        })
+    }
+}
@@ -1,28 +1,47 @@
 use async_trait::async_trait;
-use chrono::Utc;
+use base64::Engine;
+use chrono::{NaiveDate, Utc};
 use sqlx::types::Uuid;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::application::dtos::address_book_dto::{
     AddressBookDto, CreateAddressBookDto, UpdateAddressBookDto,
-    ShareAddressBookDto, UnshareAddressBookDto
+    ShareAddressBookDto, UnshareAddressBookDto,
+    ShareAddressBookWithGroupDto, UnshareAddressBookFromGroupDto
 };
 use crate::application::dtos::contact_dto::{
     ContactDto, CreateContactDto, UpdateContactDto, CreateContactVCardDto,
     ContactGroupDto, CreateContactGroupDto, UpdateContactGroupDto, GroupMembershipDto,
-    EmailDto, PhoneDto, AddressDto
+    EmailDto, PhoneDto, AddressDto, ContactPhotosRequestDto, ContactPhotoDto
 };
+use crate::application::dtos::notification_dto::ShareEventDto;
 use crate::application::ports::carddav_ports::{AddressBookUseCase, ContactUseCase};
+use crate::application::ports::contact_photo_ports::{ContactPhoto, ContactPhotoStoragePort};
+use crate::application::ports::event_bus_ports::{DomainEvent, EventBusPort};
+use crate::application::ports::notification_ports::NotificationUseCase;
 use crate::application::ports::storage_ports::StorageUseCase;
 use crate::common::errors::{DomainError, ErrorContext};
 use crate::domain::entities::contact::{AddressBook, Contact, ContactGroup, Email, Phone, Address};
+use crate::domain::entities::share_notification::{ShareChange, SharedItemKind};
 use crate::domain::repositories::address_book_repository::AddressBookRepository;
 use crate::domain::repositories::contact_repository::{ContactRepository, ContactGroupRepository};
+use crate::domain::services::vcard_photo_service::{self, PhotoSource, VCardPhoto};
+use crate::domain::services::vcard_service::{escape_text, VCard, VCardProperty};
+
+/// Maximum number of contacts accepted in one photo batch request
+const MAX_PHOTO_BATCH: usize = 200;
+
+/// Largest contact photo accepted, decoded
+pub const MAX_CONTACT_PHOTO_BYTES: usize = 2 * 1024 * 1024;
 
 pub struct ContactService {
     address_book_repository: Arc<dyn AddressBookRepository>,
     contact_repository: Arc<dyn ContactRepository>,
     contact_group_repository: Arc<dyn ContactGroupRepository>,
+    photo_storage: Option<Arc<dyn ContactPhotoStoragePort>>,
+    notifications: Option<Arc<dyn NotificationUseCase>>,
+    event_bus: Option<Arc<dyn EventBusPort>>,
 }
 
 impl ContactService {
@@ -35,6 +54,60 @@ impl ContactService {
             address_book_repository,
             contact_repository,
             contact_group_repository,
+            photo_storage: None,
+            notifications: None,
+            event_bus: None,
+        }
+    }
+
+    /// Keeps contact photos in the storage backend as well as in the vCard
+    pub fn with_photo_storage(mut self, photo_storage: Arc<dyn ContactPhotoStoragePort>) -> Self {
+        self.photo_storage = Some(photo_storage);
+        self
+    }
+
+    /// Tells users when an address book is shared with them or unshared
+    pub fn with_notifications(mut self, notifications: Arc<dyn NotificationUseCase>) -> Self {
+        self.notifications = Some(notifications);
+        self
+    }
+
+    /// Publishes deleted contacts as domain events
+    pub fn with_event_bus(mut self, event_bus: Arc<dyn EventBusPort>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    /// Notifies the user an address book was shared with or taken from
+    async fn notify_share(&self, address_book: &AddressBook, change: ShareChange, recipient: &str) {
+        if let Some(notifications) = &self.notifications {
+            notifications.notify_share(&[recipient.to_string()], ShareEventDto {
+                change,
+                item_kind: SharedItemKind::AddressBook,
+                item_id: address_book.id.to_string(),
+                item_name: address_book.name.clone(),
+                actor_id: Some(address_book.owner_id.clone()),
+                actor_name: None,
+            }).await;
+        }
+    }
+
+    /// Mirrors the embedded photo of a vCard into the photo storage, or
+    /// forgets the stored one when the vCard no longer embeds a photo
+    pub(crate) async fn sync_stored_photo(
+        photo_storage: &dyn ContactPhotoStoragePort,
+        contact_id: &str,
+        vcard: &str,
+    ) -> Result<(), DomainError> {
+        let photo = vcard_photo_service::find_photo(vcard).and_then(|photo| photo.decode());
+        match photo {
+            Some(content) if content.len() <= MAX_CONTACT_PHOTO_BYTES => {
+                let media_type = vcard_photo_service::sniff_image_type(&content)
+                    .unwrap_or("application/octet-stream")
+                    .to_string();
+                photo_storage.store_photo(contact_id, &ContactPhoto { content, media_type }).await
+            }
+            _ => photo_storage.delete_photo(contact_id).await,
         }
     }
 
@@ -49,9 +122,8 @@ impl ContactService {
             return Ok(address_book);
         }
 
-        // Check if address book is shared with user
-        let shares = self.address_book_repository.get_address_book_shares(address_book_id).await?;
-        if shares.iter().any(|(id, _)| id == user_id) {
+        // Check if address book is shared with user, directly or through a group
+        if self.address_book_repository.get_user_address_book_share(address_book_id, user_id).await?.is_some() {
             return Ok(address_book);
         }
 
@@ -63,6 +135,21 @@ impl ContactService {
         Err(DomainError::unauthorized("You don't have access to this address book"))
     }
 
+    /// Parses an address book ID and checks the user owns the address book
+    async fn owned_address_book_id(&self, address_book_id: &str, user_id: &str, denied: &str) -> Result<Uuid, DomainError> {
+        let id = Uuid::parse_str(address_book_id)
+            .map_err(|_| DomainError::validation_error("Invalid address book ID format"))?;
+
+        let address_book = self.address_book_repository.get_address_book_by_id(&id)
+            .await?
+            .ok_or_else(|| DomainError::not_found("Address book", "not found"))?;
+
+        if address_book.owner_id != user_id {
+            return Err(DomainError::unauthorized(denied));
+        }
+        Ok(id)
+    }
+
     async fn check_address_book_write_access(&self, address_book_id: &Uuid, user_id: &str) -> Result<AddressBook, DomainError> {
         let address_book = self.address_book_repository.get_address_book_by_id(address_book_id)
             .await?
@@ -74,165 +161,323 @@ impl ContactService {
         }
 
         // Check if address book is shared with user with write access
-        let shares = self.address_book_repository.get_address_book_shares(address_book_id).await?;
-        if shares.iter().any(|(id, can_write)| id == user_id && *can_write) {
+        if self.address_book_repository.get_user_address_book_share(address_book_id, user_id).await? == Some(true) {
             return Ok(address_book);
         }
 
         Err(DomainError::unauthorized("You don't have write access to this address book"))
     }
 
-    fn parse_vcard(&self, vcard_data: &str) -> Result<Contact, DomainError> {
-        // This is a simplified vCard parser - a real implementation would use a proper vCard library
-        // For now, we'll create a basic contact with minimal data
-        
+    /// Returns the vCard, dropping embedded photos above the client's limit
+    fn negotiated_vcard(vcard: String, max_photo_bytes: Option<usize>) -> String {
+        match max_photo_bytes {
+            Some(max_bytes) => vcard_photo_service::strip_large_photos(&vcard, max_bytes),
+            None => vcard,
+        }
+    }
+
+    /// Parses a vCard (2.1, 3.0 or 4.0) into a contact, keeping the original
+    /// data so properties without a contact field are not lost
+    pub(crate) fn parse_vcard(vcard_data: &str) -> Result<Contact, DomainError> {
+        let card = VCard::parse(vcard_data)?;
         let mut contact = Contact::default();
-        
-        let lines: Vec<&str> = vcard_data.lines().collect();
-        
-        for i in 0..lines.len() {
-            let line = lines[i].trim();
-            
-            if line.starts_with("FN:") {
-                contact.full_name = Some(line[3..].to_string());
-            } else if line.starts_with("N:") {
-                let parts: Vec<&str> = line[2..].split(';').collect();
-                if parts.len() >= 2 {
-                    contact.last_name = Some(parts[0].to_string());
-                    contact.first_name = Some(parts[1].to_string());
-                }
-            } else if line.starts_with("EMAIL") {
-                let value = line.split(':').nth(1).unwrap_or("");
-                if !value.is_empty() {
-                    let email_type = if line.contains("TYPE=HOME") {
-                        "home"
-                    } else if line.contains("TYPE=WORK") {
-                        "work"
-                    } else {
-                        "other"
-                    };
-                    
-                    contact.email.push(Email {
-                        email: value.to_string(),
-                        r#type: email_type.to_string(),
-                        is_primary: contact.email.is_empty(), // First one is primary
-                    });
-                }
-            } else if line.starts_with("TEL") {
-                let value = line.split(':').nth(1).unwrap_or("");
-                if !value.is_empty() {
-                    let phone_type = if line.contains("TYPE=CELL") || line.contains("TYPE=MOBILE") {
-                        "mobile"
-                    } else if line.contains("TYPE=HOME") {
-                        "home"
-                    } else if line.contains("TYPE=WORK") {
-                        "work"
-                    } else if line.contains("TYPE=FAX") {
-                        "fax"
-                    } else {
-                        "other"
-                    };
-                    
-                    contact.phone.push(Phone {
-                        number: value.to_string(),
-                        r#type: phone_type.to_string(),
-                        is_primary: contact.phone.is_empty(), // First one is primary
-                    });
-                }
-            } else if line.starts_with("ORG:") {
-                contact.organization = Some(line[4..].to_string());
-            } else if line.starts_with("TITLE:") {
-                contact.title = Some(line[6..].to_string());
-            } else if line.starts_with("NOTE:") {
-                contact.notes = Some(line[5..].to_string());
-            } else if line.starts_with("UID:") {
-                contact.uid = line[4..].to_string();
+
+        if let Some(uid) = card.text("UID").filter(|uid| !uid.trim().is_empty()) {
+            contact.uid = uid.trim().to_string();
+        }
+        contact.full_name = card.text("FN").filter(|name| !name.is_empty());
+        if let Some(name) = card.property("N") {
+            let components = name.components();
+            contact.last_name = components.first().filter(|part| !part.is_empty()).cloned();
+            contact.first_name = components.get(1).filter(|part| !part.is_empty()).cloned();
+        }
+        contact.nickname = card.property("NICKNAME").and_then(|nickname| nickname.list().into_iter().next());
+        contact.organization = card.property("ORG")
+            .and_then(|org| org.components().into_iter().next())
+            .filter(|org| !org.is_empty());
+        contact.title = card.text("TITLE").filter(|title| !title.is_empty());
+        contact.notes = card.text("NOTE").filter(|notes| !notes.is_empty());
+        contact.birthday = card.property("BDAY").and_then(|bday| parse_vcard_date(&bday.value));
+        contact.anniversary = card.property("ANNIVERSARY").or_else(|| card.property("X-ANNIVERSARY"))
+            .and_then(|anniversary| parse_vcard_date(&anniversary.value));
+        // Only linked photos fit in photo_url; embedded ones stay in the vCard
+        contact.photo_url = card.properties_named("PHOTO")
+            .map(|photo| photo.value.trim())
+            .find(|value| value.starts_with("http://") || value.starts_with("https://"))
+            .map(str::to_string);
+
+        for email in card.properties_named("EMAIL") {
+            let value = email.text();
+            if !value.trim().is_empty() {
+                contact.email.push(Email {
+                    email: value.trim().to_string(),
+                    r#type: email_type(email).to_string(),
+                    is_primary: email.is_preferred(),
+                });
             }
         }
-        
+        for tel in card.properties_named("TEL") {
+            let number = phone_number(tel);
+            if !number.is_empty() {
+                contact.phone.push(Phone {
+                    number,
+                    r#type: phone_type(tel).to_string(),
+                    is_primary: tel.is_preferred(),
+                });
+            }
+        }
+        for adr in card.properties_named("ADR") {
+            let components = adr.components();
+            let part = |index: usize| components.get(index).filter(|part| !part.is_empty()).cloned();
+            contact.address.push(Address {
+                street: part(2),
+                city: part(3),
+                state: part(4),
+                postal_code: part(5),
+                country: part(6),
+                r#type: email_type(adr).to_string(),
+                is_primary: adr.is_preferred(),
+            });
+        }
+
+        // Without an explicit preference the first one is primary
+        if !contact.email.iter().any(|email| email.is_primary) {
+            if let Some(first) = contact.email.first_mut() {
+                first.is_primary = true;
+            }
+        }
+        if !contact.phone.iter().any(|phone| phone.is_primary) {
+            if let Some(first) = contact.phone.first_mut() {
+                first.is_primary = true;
+            }
+        }
+
         // Store the original vCard data
         contact.vcard = vcard_data.to_string();
         contact.etag = Uuid::new_v4().to_string();
-        
+
         Ok(contact)
     }
 
+    /// Writes the contact fields into its stored vCard. Properties the contact
+    /// has no field for (CATEGORIES, URL, X- extensions, embedded photos...)
+    /// are kept, as are the groups and parameters of unchanged values.
     fn generate_vcard(&self, contact: &Contact) -> String {
-        let mut vcard = String::from("BEGIN:VCARD\r\nVERSION:3.0\r\n");
-        
-        // UID
-        vcard.push_str(&format!("UID:{}\r\n", contact.uid));
-        
-        // Name fields
-        if let Some(full_name) = &contact.full_name {
-            vcard.push_str(&format!("FN:{}\r\n", full_name));
-        }
-        
-        let last_name = contact.last_name.clone().unwrap_or_default();
-        let first_name = contact.first_name.clone().unwrap_or_default();
-        vcard.push_str(&format!("N:{};{};;;\r\n", last_name, first_name));
-        
-        // Email addresses
-        for email in &contact.email {
-            vcard.push_str(&format!("EMAIL;TYPE={}:{}\r\n", 
-                email.r#type.to_uppercase(),
-                email.email));
-        }
-        
-        // Phone numbers
-        for phone in &contact.phone {
-            let tel_type = match phone.r#type.as_str() {
-                "mobile" => "CELL",
-                "home" => "HOME",
-                "work" => "WORK",
-                "fax" => "FAX",
-                _ => "OTHER",
-            };
-            vcard.push_str(&format!("TEL;TYPE={}:{}\r\n", tel_type, phone.number));
-        }
-        
-        // Addresses
-        for addr in &contact.address {
-            let addr_type = addr.r#type.to_uppercase();
-            let street = addr.street.clone().unwrap_or_default();
-            let city = addr.city.clone().unwrap_or_default();
-            let state = addr.state.clone().unwrap_or_default();
-            let postal_code = addr.postal_code.clone().unwrap_or_default();
-            let country = addr.country.clone().unwrap_or_default();
-            
-            vcard.push_str(&format!("ADR;TYPE={}:;;{};{};{};{};{}\r\n", 
-                addr_type, street, city, state, postal_code, country));
-        }
-        
-        // Organization
-        if let Some(org) = &contact.organization {
-            vcard.push_str(&format!("ORG:{}\r\n", org));
-        }
-        
-        // Title
-        if let Some(title) = &contact.title {
-            vcard.push_str(&format!("TITLE:{}\r\n", title));
+        let mut card = VCard::parse(&contact.vcard).unwrap_or_default();
+        if card.property("VERSION").is_none() {
+            card.properties.insert(0, VCardProperty::new("VERSION", "3.0"));
         }
-        
-        // Notes
-        if let Some(notes) = &contact.notes {
-            vcard.push_str(&format!("NOTE:{}\r\n", notes));
+        let version_4 = card.version() == "4.0";
+
+        set_property(&mut card, "UID", Some(escape_text(&contact.uid)));
+
+        let full_name = contact.full_name.clone().or_else(|| {
+            let name = [contact.first_name.as_deref(), contact.last_name.as_deref()]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(" ");
+            (!name.is_empty()).then_some(name)
+        });
+        set_property(&mut card, "FN", full_name.map(|name| escape_text(&name)));
+
+        // Keep the additional names, prefixes and suffixes of N
+        let mut name = card.property("N").map(|n| n.components()).unwrap_or_default();
+        name.resize(name.len().max(5), String::new());
+        name[0] = contact.last_name.clone().unwrap_or_default();
+        name[1] = contact.first_name.clone().unwrap_or_default();
+        set_structured(&mut card, "N", &name);
+
+        set_property(&mut card, "NICKNAME", contact.nickname.as_deref().map(escape_text));
+
+        let emails = contact.email.iter()
+            .map(|email| {
+                let property = VCardProperty::text_value("EMAIL", &email.email)
+                    .with_param("TYPE", &email.r#type.to_uppercase());
+                with_preference(property, email.is_primary, version_4)
+            })
+            .collect();
+        merge_properties(&mut card, "EMAIL", emails, |property| property.text().trim().to_string());
+
+        let phones = contact.phone.iter()
+            .map(|phone| {
+                let tel_type = match phone.r#type.as_str() {
+                    "mobile" => "CELL",
+                    "home" => "HOME",
+                    "work" => "WORK",
+                    "fax" => "FAX",
+                    _ => "OTHER",
+                };
+                let property = VCardProperty::text_value("TEL", &phone.number).with_param("TYPE", tel_type);
+                with_preference(property, phone.is_primary, version_4)
+            })
+            .collect();
+        merge_properties(&mut card, "TEL", phones, phone_number);
+
+        let addresses = contact.address.iter()
+            .map(|addr| {
+                let components: Vec<String> = [None, None, addr.street.clone(), addr.city.clone(), addr.state.clone(), addr.postal_code.clone(), addr.country.clone()]
+                    .into_iter()
+                    .map(Option::unwrap_or_default)
+                    .collect();
+                let mut property = VCardProperty::new("ADR", String::new()).with_param("TYPE", &addr.r#type.to_uppercase());
+                property.set_components(&components);
+                with_preference(property, addr.is_primary, version_4)
+            })
+            .collect();
+        merge_properties(&mut card, "ADR", addresses, |property| {
+            property.components().get(2..).map(|parts| parts.join(";")).unwrap_or_default()
+        });
+
+        // Keep the organizational units of ORG
+        match &contact.organization {
+            Some(org) => {
+                let mut components = card.property("ORG").map(|o| o.components()).unwrap_or_default();
+                if components.is_empty() {
+                    components.push(String::new());
+                }
+                components[0] = org.clone();
+                set_structured(&mut card, "ORG", &components);
+            }
+            None => set_property(&mut card, "ORG", None),
         }
-        
-        // Birthday
-        if let Some(birthday) = &contact.birthday {
-            vcard.push_str(&format!("BDAY:{}\r\n", birthday.format("%Y%m%d")));
+
+        set_property(&mut card, "TITLE", contact.title.as_deref().map(escape_text));
+        set_property(&mut card, "NOTE", contact.notes.as_deref().map(escape_text));
+        set_property(&mut card, "BDAY", contact.birthday.map(|date| date.format("%Y%m%d").to_string()));
+        // ANNIVERSARY only exists in vCard 4.0; 3.0 clients read X-ANNIVERSARY
+        let anniversary_property = if version_4 { "ANNIVERSARY" } else { "X-ANNIVERSARY" };
+        set_property(&mut card, anniversary_property, contact.anniversary.map(|date| date.format("%Y%m%d").to_string()));
+
+        if let Some(photo_url) = &contact.photo_url {
+            if !card.properties_named("PHOTO").any(|photo| photo.value.trim() == photo_url) {
+                card.properties.retain(|property| !property.name.eq_ignore_ascii_case("PHOTO"));
+                let photo = VCardProperty::new("PHOTO", photo_url.clone());
+                card.properties.push(if version_4 { photo } else { photo.with_param("VALUE", "uri") });
+            }
         }
-        
+
         // Revision (last update)
-        vcard.push_str(&format!("REV:{}\r\n", contact.updated_at.format("%Y%m%dT%H%M%SZ")));
-        
-        vcard.push_str("END:VCARD\r\n");
-        
-        vcard
+        set_property(&mut card, "REV", Some(contact.updated_at.format("%Y%m%dT%H%M%SZ").to_string()));
+
+        card.to_vcard()
+    }
+}
+
+/// Replaces the value of the first property with that name (keeping its group
+/// and parameters), adds it if missing, or removes every occurrence for `None`
+fn set_property(card: &mut VCard, name: &str, value: Option<String>) {
+    match value {
+        Some(value) => match card.properties.iter_mut().find(|property| property.name.eq_ignore_ascii_case(name)) {
+            Some(property) => property.value = value,
+            None => card.properties.push(VCardProperty::new(name, value)),
+        },
+        None => card.properties.retain(|property| !property.name.eq_ignore_ascii_case(name)),
+    }
+}
+
+fn set_structured(card: &mut VCard, name: &str, components: &[String]) {
+    let mut property = VCardProperty::new(name, String::new());
+    property.set_components(components);
+    // Leave the value untouched when only its escaping would change
+    if card.property(name).is_some_and(|existing| existing.components() == components) {
+        return;
+    }
+    set_property(card, name, Some(property.value));
+}
+
+/// Marks a new property as preferred the way its vCard version expects
+fn with_preference(property: VCardProperty, primary: bool, version_4: bool) -> VCardProperty {
+    match (primary, version_4) {
+        (false, _) => property,
+        (true, true) => property.with_param("PREF", "1"),
+        (true, false) => property.with_param("TYPE", "pref"),
+    }
+}
+
+/// Replaces the properties with that name by `entries`. An existing property
+/// with the same value and type is kept as is, so its group, labels and
+/// parameters survive the update.
+fn merge_properties(
+    card: &mut VCard,
+    name: &str,
+    entries: Vec<VCardProperty>,
+    key: impl Fn(&VCardProperty) -> String,
+) {
+    let position = card.properties.iter()
+        .position(|property| property.name.eq_ignore_ascii_case(name))
+        .unwrap_or(card.properties.len());
+    let mut existing: Vec<Option<VCardProperty>> = Vec::new();
+    card.properties.retain(|property| {
+        let matches = property.name.eq_ignore_ascii_case(name);
+        if matches {
+            existing.push(Some(property.clone()));
+        }
+        !matches
+    });
+
+    let merged: Vec<VCardProperty> = entries.into_iter()
+        .map(|property| {
+            existing.iter_mut()
+                .find(|old| old.as_ref().is_some_and(|old| key(old) == key(&property) && kind(old) == kind(&property)))
+                .and_then(Option::take)
+                .unwrap_or(property)
+        })
+        .collect();
+    let position = position.min(card.properties.len());
+    card.properties.splice(position..position, merged);
+}
+
+/// Contact type (home, work, mobile...) a property maps to
+fn kind(property: &VCardProperty) -> &'static str {
+    match property.name.as_str() {
+        "TEL" => phone_type(property),
+        _ => email_type(property),
+    }
+}
+
+fn email_type(property: &VCardProperty) -> &'static str {
+    let types = property.types();
+    if types.iter().any(|t| t == "home") {
+        "home"
+    } else if types.iter().any(|t| t == "work") {
+        "work"
+    } else {
+        "other"
+    }
+}
+
+fn phone_type(property: &VCardProperty) -> &'static str {
+    let types = property.types();
+    let has = |name: &str| types.iter().any(|t| t == name);
+    if has("cell") || has("mobile") {
+        "mobile"
+    } else if has("fax") {
+        "fax"
+    } else if has("home") {
+        "home"
+    } else if has("work") {
+        "work"
+    } else {
+        "other"
     }
 }
 
+/// Phone number of a TEL property; vCard 4.0 writes it as a `tel:` URI
+fn phone_number(property: &VCardProperty) -> String {
+    let value = property.text();
+    let value = value.trim();
+    value.strip_prefix("tel:").unwrap_or(value).trim().to_string()
+}
+
+/// Parses a vCard date (`19850412`, `1985-04-12`, optionally with a time);
+/// dates without a year (`--0412`) can't be stored
+fn parse_vcard_date(value: &str) -> Option<NaiveDate> {
+    let date = value.trim().split('T').next().unwrap_or_default();
+    NaiveDate::parse_from_str(date, "%Y%m%d").ok()
+        .or_else(|| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+}
+
 #[async_trait]
 impl AddressBookUseCase for ContactService {
     async fn create_address_book(&self, dto: CreateAddressBookDto) -> Result<AddressBookDto, DomainError> {
@@ -248,6 +493,7 @@ impl AddressBookUseCase for ContactService {
             is_public: dto.is_public.unwrap_or(false),
             created_at: now,
             updated_at: now,
+            sync_revision: 0,
         };
 
         let created_address_book = self.address_book_repository.create_address_book(address_book).await?;
@@ -271,6 +517,7 @@ impl AddressBookUseCase for ContactService {
             is_public: update.is_public.unwrap_or(address_book.is_public),
             created_at: address_book.created_at,
             updated_at: Utc::now(),
+            sync_revision: address_book.sync_revision,
         };
 
         let result = self.address_book_repository.update_address_book(updated_address_book).await?;
@@ -362,6 +609,7 @@ impl AddressBookUseCase for ContactService {
         }
 
         self.address_book_repository.share_address_book(&id, &dto.user_id, dto.can_write).await?;
+        self.notify_share(&address_book, ShareChange::Shared, &dto.user_id).await;
         Ok(())
     }
 
@@ -379,6 +627,7 @@ impl AddressBookUseCase for ContactService {
         }
 
         self.address_book_repository.unshare_address_book(&id, &dto.user_id).await?;
+        self.notify_share(&address_book, ShareChange::Unshared, &dto.user_id).await;
         Ok(())
     }
 
@@ -398,6 +647,23 @@ impl AddressBookUseCase for ContactService {
         let shares = self.address_book_repository.get_address_book_shares(&id).await?;
         Ok(shares)
     }
+
+    async fn share_address_book_with_group(&self, dto: ShareAddressBookWithGroupDto, user_id: &str) -> Result<(), DomainError> {
+        let id = self.owned_address_book_id(&dto.address_book_id, user_id, "Only the owner can share an address book").await?;
+        self.address_book_repository.share_address_book_with_group(&id, &dto.group_id, dto.can_write).await?;
+        Ok(())
+    }
+
+    async fn unshare_address_book_from_group(&self, dto: UnshareAddressBookFromGroupDto, user_id: &str) -> Result<(), DomainError> {
+        let id = self.owned_address_book_id(&dto.address_book_id, user_id, "Only the owner can unshare an address book").await?;
+        self.address_book_repository.unshare_address_book_from_group(&id, &dto.group_id).await?;
+        Ok(())
+    }
+
+    async fn get_address_book_group_shares(&self, address_book_id: &str, user_id: &str) -> Result<Vec<(String, bool)>, DomainError> {
+        let id = self.owned_address_book_id(address_book_id, user_id, "Only the owner can view address book shares").await?;
+        self.address_book_repository.get_address_book_group_shares(&id).await
+    }
 }
 
 #[async_trait]
@@ -483,7 +749,7 @@ impl ContactUseCase for ContactService {
         self.check_address_book_write_access(&address_book_id, &dto.user_id).await?;
 
         // Parse vCard data
-        let mut contact = self.parse_vcard(&dto.vcard)?;
+        let mut contact = Self::parse_vcard(&dto.vcard)?;
         
         // Set address book ID
         contact.address_book_id = address_book_id;
@@ -500,6 +766,9 @@ impl ContactUseCase for ContactService {
         
         // Create the contact
         let created_contact = self.contact_repository.create_contact(contact).await?;
+        if let Some(photo_storage) = &self.photo_storage {
+            Self::sync_stored_photo(photo_storage.as_ref(), &created_contact.id.to_string(), &created_contact.vcard).await?;
+        }
         Ok(ContactDto::from(created_contact))
     }
 
@@ -600,10 +869,22 @@ impl ContactUseCase for ContactService {
             .ok_or_else(|| DomainError::not_found("Contact", "not found"))?;
 
         // Check if user has write access to the address book
-        self.check_address_book_write_access(&contact.address_book_id, user_id).await?;
+        let address_book = self.check_address_book_write_access(&contact.address_book_id, user_id).await?;
 
         // Delete the contact
         self.contact_repository.delete_contact(&id).await?;
+        if let Some(photo_storage) = &self.photo_storage {
+            photo_storage.delete_photo(contact_id).await?;
+        }
+        if let Some(event_bus) = &self.event_bus {
+            event_bus.publish(DomainEvent::ContactDeleted {
+                contact_id: contact.id.to_string(),
+                address_book_id: contact.address_book_id.to_string(),
+                uid: contact.uid,
+                owner_id: address_book.owner_id,
+                deleted_by: user_id.to_string(),
+            });
+        }
         Ok(())
     }
 
@@ -827,7 +1108,7 @@ impl ContactUseCase for ContactService {
         Ok(dtos)
     }
 
-    async fn get_contact_vcard(&self, contact_id: &str, user_id: &str) -> Result<String, DomainError> {
+    async fn get_contact_vcard(&self, contact_id: &str, user_id: &str, max_photo_bytes: Option<usize>) -> Result<String, DomainError> {
         let id = Uuid::parse_str(contact_id)
             .map_err(|_| DomainError::validation_error("Invalid contact ID format"))?;
 
@@ -840,10 +1121,10 @@ impl ContactUseCase for ContactService {
         self.check_address_book_access(&contact.address_book_id, user_id).await?;
 
         // Return the vCard data
-        Ok(contact.vcard)
+        Ok(Self::negotiated_vcard(contact.vcard, max_photo_bytes))
     }
 
-    async fn get_contacts_as_vcards(&self, address_book_id: &str, user_id: &str) -> Result<Vec<(String, String)>, DomainError> {
+    async fn get_contacts_as_vcards(&self, address_book_id: &str, user_id: &str, max_photo_bytes: Option<usize>) -> Result<Vec<(String, String)>, DomainError> {
         let id = Uuid::parse_str(address_book_id)
             .map_err(|_| DomainError::validation_error("Invalid address book ID format"))?;
 
@@ -855,11 +1136,135 @@ impl ContactUseCase for ContactService {
         
         // Convert to Vec<(id, vcard)>
         let vcards = contacts.into_iter()
-            .map(|contact| (contact.id.to_string(), contact.vcard))
+            .map(|contact| (contact.id.to_string(), Self::negotiated_vcard(contact.vcard, max_photo_bytes)))
             .collect();
         
         Ok(vcards)
     }
+
+    async fn get_contact_photos(&self, request: ContactPhotosRequestDto) -> Result<Vec<ContactPhotoDto>, DomainError> {
+        if request.contact_ids.len() > MAX_PHOTO_BATCH {
+            return Err(DomainError::validation_error(format!(
+                "At most {} contacts can be requested at once", MAX_PHOTO_BATCH
+            )));
+        }
+
+        // Access is checked once per address book; contacts the user cannot read are left out
+        let mut readable: HashMap<Uuid, bool> = HashMap::new();
+        let mut photos = Vec::with_capacity(request.contact_ids.len());
+
+        for contact_id in &request.contact_ids {
+            let id = Uuid::parse_str(contact_id)
+                .map_err(|_| DomainError::validation_error("Invalid contact ID format"))?;
+
+            let Some(contact) = self.contact_repository.get_contact_by_id(&id).await? else {
+                continue;
+            };
+
+            let can_read = match readable.get(&contact.address_book_id) {
+                Some(can_read) => *can_read,
+                None => {
+                    let can_read = self.check_address_book_access(&contact.address_book_id, &request.user_id).await.is_ok();
+                    readable.insert(contact.address_book_id, can_read);
+                    can_read
+                }
+            };
+            if !can_read {
+                continue;
+            }
+
+            let photo = vcard_photo_service::find_photo(&contact.vcard).or_else(|| {
+                contact.photo_url.clone().map(|url| VCardPhoto { media_type: None, source: PhotoSource::Uri(url) })
+            });
+
+            photos.push(match photo {
+                Some(photo) => ContactPhotoDto {
+                    contact_id: contact.id.to_string(),
+                    hash: Some(photo.hash()),
+                    size: photo.size(),
+                    media_type: photo.media_type.clone(),
+                    data: match &photo.source {
+                        PhotoSource::Inline(data) if request.include_data => Some(data.clone()),
+                        _ => None,
+                    },
+                    uri: match photo.source {
+                        PhotoSource::Uri(uri) => Some(uri),
+                        PhotoSource::Inline(_) => None,
+                    },
+                },
+                None => ContactPhotoDto {
+                    contact_id: contact.id.to_string(),
+                    hash: None,
+                    media_type: None,
+                    size: None,
+                    data: None,
+                    uri: None,
+                },
+            });
+        }
+
+        Ok(photos)
+    }
+
+    async fn get_contact_photo(&self, contact_id: &str, user_id: &str) -> Result<ContactPhoto, DomainError> {
+        let id = Uuid::parse_str(contact_id)
+            .map_err(|_| DomainError::validation_error("Invalid contact ID format"))?;
+
+        let contact = self.contact_repository.get_contact_by_id(&id)
+            .await?
+            .ok_or_else(|| DomainError::not_found("Contact", "not found"))?;
+        self.check_address_book_access(&contact.address_book_id, user_id).await?;
+
+        if let Some(photo_storage) = &self.photo_storage {
+            if let Some(photo) = photo_storage.get_photo(contact_id).await? {
+                return Ok(photo);
+            }
+        }
+
+        // Contacts stored before the photo storage keep the photo only in the vCard
+        let photo = vcard_photo_service::find_photo(&contact.vcard)
+            .ok_or_else(|| DomainError::not_found("Contact photo", contact_id))?;
+        let content = photo.decode()
+            .ok_or_else(|| DomainError::not_found("Contact photo", contact_id))?;
+        let media_type = vcard_photo_service::sniff_image_type(&content)
+            .map(str::to_string)
+            .or(photo.media_type)
+            .unwrap_or_else(|| "application/octet-stream".to_string());
+        Ok(ContactPhoto { content, media_type })
+    }
+
+    async fn set_contact_photo(&self, contact_id: &str, user_id: &str, content: Vec<u8>) -> Result<ContactDto, DomainError> {
+        let id = Uuid::parse_str(contact_id)
+            .map_err(|_| DomainError::validation_error("Invalid contact ID format"))?;
+
+        if content.len() > MAX_CONTACT_PHOTO_BYTES {
+            return Err(DomainError::validation_error(format!(
+                "Contact photos can't be larger than {} bytes", MAX_CONTACT_PHOTO_BYTES
+            )));
+        }
+        let media_type = vcard_photo_service::sniff_image_type(&content)
+            .ok_or_else(|| DomainError::validation_error("The photo must be a JPEG, PNG, GIF or WebP image"))?;
+
+        let mut contact = self.contact_repository.get_contact_by_id(&id)
+            .await?
+            .ok_or_else(|| DomainError::not_found("Contact", "not found"))?;
+        self.check_address_book_write_access(&contact.address_book_id, user_id).await?;
+
+        let photo = ContactPhoto { content, media_type: media_type.to_string() };
+        if let Some(photo_storage) = &self.photo_storage {
+            photo_storage.store_photo(contact_id, &photo).await?;
+        }
+
+        // The vCard carries the photo inline so every client receives it
+        let vcard = if contact.vcard.trim().is_empty() { self.generate_vcard(&contact) } else { contact.vcard.clone() };
+        contact.vcard = vcard_photo_service::embed_photo(&vcard, &photo.content, &photo.media_type)?;
+        contact.photo_url = None;
+        contact.etag = Uuid::new_v4().to_string();
+        contact.updated_at = Utc::now();
+
+        let result = self.contact_repository.update_contact(contact).await?;
+        Ok(ContactDto::from(result))
+    }
 }
 
 #[async_trait]
@@ -945,6 +1350,36 @@ impl StorageUseCase for ContactService {
                 let result = self.get_address_book_shares(address_book_id, user_id).await?;
                 Ok(serde_json::to_value(result).unwrap())
             },
+            "share_address_book_with_group" => {
+                let dto: ShareAddressBookWithGroupDto = serde_json::from_value(params.clone())
+                    .map_err(|e| DomainError::validation_error(format!("Invalid parameters: {}", e)))?;
+                
+                let user_id = params["user_id"].as_str()
+                    .ok_or_else(|| DomainError::validation_error("Missing user_id parameter"))?;
+                
+                self.share_address_book_with_group(dto, user_id).await?;
+                Ok(serde_json::Value::Null)
+            },
+            "unshare_address_book_from_group" => {
+                let dto: UnshareAddressBookFromGroupDto = serde_json::from_value(params.clone())
+                    .map_err(|e| DomainError::validation_error(format!("Invalid parameters: {}", e)))?;
+                
+                let user_id = params["user_id"].as_str()
+                    .ok_or_else(|| DomainError::validation_error("Missing user_id parameter"))?;
+                
+                self.unshare_address_book_from_group(dto, user_id).await?;
+                Ok(serde_json::Value::Null)
+            },
+            "get_address_book_group_shares" => {
+                let address_book_id = params["address_book_id"].as_str()
+                    .ok_or_else(|| DomainError::validation_error("Missing address_book_id parameter"))?;
+                
+                let user_id = params["user_id"].as_str()
+                    .ok_or_else(|| DomainError::validation_error("Missing user_id parameter"))?;
+                
+                let result = self.get_address_book_group_shares(address_book_id, user_id).await?;
+                Ok(serde_json::to_value(result).unwrap())
+            },
 
             // Contact operations
             "create_contact" => {
@@ -1114,7 +1549,9 @@ impl StorageUseCase for ContactService {
                 let user_id = params["user_id"].as_str()
                     .ok_or_else(|| DomainError::validation_error("Missing user_id parameter"))?;
                 
-                let result = self.get_contact_vcard(contact_id, user_id).await?;
+                let max_photo_bytes = params["max_photo_bytes"].as_u64().map(|max| max as usize);
+                
+                let result = self.get_contact_vcard(contact_id, user_id, max_photo_bytes).await?;
                 Ok(serde_json::to_value(result).unwrap())
             },
             "get_contacts_as_vcards" => {
@@ -1124,7 +1561,43 @@ impl StorageUseCase for ContactService {
                 let user_id = params["user_id"].as_str()
                     .ok_or_else(|| DomainError::validation_error("Missing user_id parameter"))?;
                 
-                let result = self.get_contacts_as_vcards(address_book_id, user_id).await?;
+                let max_photo_bytes = params["max_photo_bytes"].as_u64().map(|max| max as usize);
+                
+                let result = self.get_contacts_as_vcards(address_book_id, user_id, max_photo_bytes).await?;
+                Ok(serde_json::to_value(result).unwrap())
+            },
+            "get_contact_photos" => {
+                let dto: ContactPhotosRequestDto = serde_json::from_value(params.clone())
+                    .map_err(|e| DomainError::validation_error(format!("Invalid parameters: {}", e)))?;
+                
+                let result = self.get_contact_photos(dto).await?;
+                Ok(serde_json::to_value(result).unwrap())
+            },
+            "get_contact_photo" => {
+                let contact_id = params["contact_id"].as_str()
+                    .ok_or_else(|| DomainError::validation_error("Missing contact_id parameter"))?;
+                
+                let user_id = params["user_id"].as_str()
+                    .ok_or_else(|| DomainError::validation_error("Missing user_id parameter"))?;
+                
+                let photo = self.get_contact_photo(contact_id, user_id).await?;
+                Ok(serde_json::json!({
+                    "media_type": photo.media_type,
+                    "data": base64::engine::general_purpose::STANDARD.encode(&photo.content),
+                }))
+            },
+            "set_contact_photo" => {
+                let contact_id = params["contact_id"].as_str()
+                    .ok_or_else(|| DomainError::validation_error("Missing contact_id parameter"))?;
+                
+                let user_id = params["user_id"].as_str()
+                    .ok_or_else(|| DomainError::validation_error("Missing user_id parameter"))?;
+                
+                let content = params["data"].as_str()
+                    .and_then(|data| base64::engine::general_purpose::STANDARD.decode(data).ok())
+                    .ok_or_else(|| DomainError::validation_error("Missing or invalid data parameter"))?;
+                
+                let result = self.set_contact_photo(contact_id, user_id, content).await?;
                 Ok(serde_json::to_value(result).unwrap())
             },
             
@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::common::errors::DomainError;
+use crate::domain::repositories::address_book_repository::AddressBookRepository;
+use crate::domain::repositories::calendar_repository::CalendarRepository;
+
+/// Comprobaciones de acceso a calendarios y libretas de direcciones
+/// compartidas por los servicios que leen objetos DAV fuera de los
+/// manejadores CalDAV/CardDAV.
+///
+/// Los administradores tienen acceso a todo; leer exige ser propietario,
+/// tener la colección compartida o que sea pública, y escribir exige ser
+/// propietario o tenerla compartida con permiso de escritura. Lo compartido
+/// con un grupo vale para todos sus miembros.
+#[derive(Clone)]
+pub struct DavAccessService {
+    calendar_repository: Arc<dyn CalendarRepository>,
+    address_book_repository: Arc<dyn AddressBookRepository>,
+}
+
+impl DavAccessService {
+    pub fn new(
+        calendar_repository: Arc<dyn CalendarRepository>,
+        address_book_repository: Arc<dyn AddressBookRepository>,
+    ) -> Self {
+        Self {
+            calendar_repository,
+            address_book_repository,
+        }
+    }
+
+    /// Comprueba el acceso a un calendario
+    pub async fn check_calendar(&self, user_id: &str, is_admin: bool, calendar_id: &Uuid, write: bool) -> Result<(), DomainError> {
+        let calendar = self.calendar_repository.find_calendar_by_id(calendar_id).await?;
+        if is_admin || calendar.owner_id() == user_id {
+            return Ok(());
+        }
+        let allowed = if write {
+            self.calendar_repository.get_user_calendar_access_levels(calendar_id, user_id).await?
+                .iter()
+                .any(|level| level == "write" || level == "owner")
+        } else {
+            self.calendar_repository.user_has_calendar_access(calendar_id, user_id).await?
+        };
+        if allowed {
+            Ok(())
+        } else {
+            Err(DomainError::access_denied("Calendar", "You don't have access to this calendar"))
+        }
+    }
+
+    /// Comprueba el acceso a una libreta de direcciones
+    pub async fn check_address_book(&self, user_id: &str, is_admin: bool, address_book_id: &Uuid, write: bool) -> Result<(), DomainError> {
+        let address_book = self.address_book_repository.get_address_book_by_id(address_book_id).await?
+            .ok_or_else(|| DomainError::not_found("Address book", address_book_id.to_string()))?;
+        if is_admin || address_book.owner_id == user_id || (!write && address_book.is_public) {
+            return Ok(());
+        }
+        let allowed = self.address_book_repository.get_user_address_book_share(address_book_id, user_id).await?
+            .is_some_and(|can_write| can_write || !write);
+        if allowed {
+            Ok(())
+        } else {
+            Err(DomainError::access_denied("Address book", "You don't have access to this address book"))
+        }
+    }
+}
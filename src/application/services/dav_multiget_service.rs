@@ -0,0 +1,289 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use base64::Engine;
+use uuid::Uuid;
+
+use crate::application::dtos::dav_multiget_dto::{MultigetItemDto, MultigetResponseDto};
+use crate::application::ports::dav_multiget_ports::DavMultigetUseCase;
+use crate::application::ports::inbound::FileUseCase;
+use crate::application::ports::share_ports::{ShareAccessUseCase, ShareRecipient, ShareTarget};
+use crate::application::services::dav_access_service::DavAccessService;
+use crate::common::errors::{DomainError, ErrorKind};
+use crate::domain::entities::share::SharePermissions;
+use crate::domain::repositories::address_book_repository::AddressBookRepository;
+use crate::domain::repositories::calendar_event_repository::CalendarEventRepository;
+use crate::domain::repositories::calendar_repository::CalendarRepository;
+use crate::domain::repositories::contact_repository::ContactRepository;
+use crate::domain::services::path_codec_service::decode_path;
+
+/// Número máximo de hrefs por petición
+pub const MAX_HREFS: usize = 100;
+
+/// Tamaño máximo de un archivo devuelto en línea (el resto se descarga aparte)
+pub const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Recurso DAV al que apunta un href
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DavHref {
+    /// Archivo de WebDAV, con la ruta tal como la resuelve el manejador WebDAV
+    File(String),
+    /// Evento de CalDAV (`/api/caldav/{calendar_id}/{uid}.ics`)
+    Event { calendar_id: Uuid, uid: String },
+    /// Contacto de CardDAV (`/api/carddav/addressbooks/{address_book_id}/{uid}.vcf`;
+    /// también sin el segmento `addressbooks`)
+    Contact { address_book_id: Uuid, uid: String },
+}
+
+impl DavHref {
+    /// Interpreta un href como URL completa o ruta absoluta, con o sin el
+    /// prefijo `/api` (o `/api/v1`)
+    pub fn parse(href: &str) -> Option<Self> {
+        let mut path = href.trim();
+        if let Some(scheme_end) = path.find("://") {
+            let rest = &path[scheme_end + 3..];
+            path = rest.find('/').map(|i| &rest[i..]).unwrap_or("/");
+        }
+        let path = path.split(['?', '#']).next().unwrap_or_default();
+        let path = path.strip_prefix("/api/v1")
+            .or_else(|| path.strip_prefix("/api"))
+            .unwrap_or(path);
+        let path = decode_path(path).ok()?;
+
+        let (endpoint, rest) = path.trim_start_matches('/').split_once('/')?;
+        let rest = rest.trim_end_matches('/');
+        match endpoint {
+            "webdav" if !rest.is_empty() => Some(DavHref::File(rest.to_string())),
+            "caldav" => {
+                let (calendar_id, uid) = split_object(rest, ".ics")?;
+                Some(DavHref::Event { calendar_id, uid })
+            }
+            "carddav" => {
+                let rest = rest.strip_prefix("addressbooks/").unwrap_or(rest);
+                let (address_book_id, uid) = split_object(rest, ".vcf")?;
+                Some(DavHref::Contact { address_book_id, uid })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Separa `{collection_id}/{uid}{extension}`
+fn split_object(rest: &str, extension: &str) -> Option<(Uuid, String)> {
+    let (collection, object) = rest.split_once('/')?;
+    let uid = object.strip_suffix(extension)?;
+    if uid.is_empty() || uid.contains('/') {
+        return None;
+    }
+    Some((Uuid::parse_str(collection).ok()?, uid.to_string()))
+}
+
+/// Estado HTTP equivalente a un error de dominio
+fn error_status(error: &DomainError) -> u16 {
+    match error.kind {
+        ErrorKind::NotFound => 404,
+        ErrorKind::AccessDenied => 403,
+        ErrorKind::InvalidInput => 400,
+        _ => 500,
+    }
+}
+
+fn text_item(href: &str, etag: String, content_type: &str, body: String) -> MultigetItemDto {
+    MultigetItemDto {
+        href: href.to_string(),
+        status: 200,
+        etag: Some(etag),
+        content_type: Some(content_type.to_string()),
+        body: Some(body),
+        encoding: Some("utf-8".to_string()),
+        error: None,
+    }
+}
+
+/// Servicio de lectura en bloque de recursos DAV.
+///
+/// Devuelve en JSON lo mismo que devolvería un REPORT multiget de DAV, para
+/// que la interfaz web no tenga que generar ni interpretar XML. Los errores de
+/// cada href se informan en su entrada y no hacen fallar la petición. Los
+/// archivos de otros usuarios solo se devuelven si sus enlaces dan lectura.
+pub struct DavMultigetService {
+    file_service: Arc<dyn FileUseCase>,
+    share_access: Arc<dyn ShareAccessUseCase>,
+    access: Option<DavAccessService>,
+    event_repository: Option<Arc<dyn CalendarEventRepository>>,
+    contact_repository: Option<Arc<dyn ContactRepository>>,
+}
+
+impl DavMultigetService {
+    /// Crea un servicio que solo resuelve archivos de WebDAV
+    pub fn new(file_service: Arc<dyn FileUseCase>, share_access: Arc<dyn ShareAccessUseCase>) -> Self {
+        Self {
+            file_service,
+            share_access,
+            access: None,
+            event_repository: None,
+            contact_repository: None,
+        }
+    }
+
+    /// Habilita la resolución de eventos y contactos
+    pub fn with_dav_repositories(
+        mut self,
+        calendar_repository: Arc<dyn CalendarRepository>,
+        event_repository: Arc<dyn CalendarEventRepository>,
+        address_book_repository: Arc<dyn AddressBookRepository>,
+        contact_repository: Arc<dyn ContactRepository>,
+    ) -> Self {
+        self.access = Some(DavAccessService::new(calendar_repository, address_book_repository));
+        self.event_repository = Some(event_repository);
+        self.contact_repository = Some(contact_repository);
+        self
+    }
+
+    async fn get_file(&self, recipient: ShareRecipient<'_>, is_admin: bool, href: &str, path: &str) -> Result<MultigetItemDto, DomainError> {
+        // La misma comprobación que hace la capa de permisos en /webdav
+        if !is_admin {
+            self.share_access.ensure_access(recipient, &ShareTarget::Path(path.to_string()), SharePermissions::READ).await?;
+        }
+        let file = self.file_service.get_file_by_path(path).await?;
+        let etag = format!("\"{}\"", file.id);
+        if file.size > MAX_FILE_BYTES {
+            return Ok(MultigetItemDto {
+                etag: Some(etag),
+                content_type: Some(file.mime_type),
+                ..MultigetItemDto::error(href, 413, "File too large to be returned inline")
+            });
+        }
+
+        let content = self.file_service.get_file_content(&file.id).await?;
+        let (body, encoding) = match String::from_utf8(content) {
+            Ok(text) => (text, "utf-8"),
+            Err(e) => (base64::engine::general_purpose::STANDARD.encode(e.into_bytes()), "base64"),
+        };
+        Ok(MultigetItemDto {
+            href: href.to_string(),
+            status: 200,
+            etag: Some(etag),
+            content_type: Some(file.mime_type),
+            body: Some(body),
+            encoding: Some(encoding.to_string()),
+            error: None,
+        })
+    }
+
+    async fn get_event(&self, href: &str, user_id: &str, is_admin: bool, calendar_id: &Uuid, uid: &str) -> Result<MultigetItemDto, DomainError> {
+        let (Some(access), Some(events)) = (&self.access, &self.event_repository) else {
+            return Err(DomainError::not_found("Event", uid));
+        };
+        access.check_calendar(user_id, is_admin, calendar_id, false).await?;
+        let event = events.find_event_by_ical_uid(calendar_id, uid).await?
+            .ok_or_else(|| DomainError::not_found("Event", uid))?;
+        let etag = format!("\"{}-{}\"", event.id(), event.updated_at().timestamp());
+        Ok(text_item(href, etag, "text/calendar; charset=utf-8", event.ical_data().to_string()))
+    }
+
+    async fn get_contact(&self, href: &str, user_id: &str, is_admin: bool, address_book_id: &Uuid, uid: &str) -> Result<MultigetItemDto, DomainError> {
+        let (Some(access), Some(contacts)) = (&self.access, &self.contact_repository) else {
+            return Err(DomainError::not_found("Contact", uid));
+        };
+        access.check_address_book(user_id, is_admin, address_book_id, false).await?;
+        let contact = contacts.get_contact_by_uid(address_book_id, uid).await?
+            .ok_or_else(|| DomainError::not_found("Contact", uid))?;
+        Ok(text_item(href, format!("\"{}\"", contact.etag), "text/vcard; charset=utf-8", contact.vcard))
+    }
+
+    async fn get_one(&self, recipient: ShareRecipient<'_>, is_admin: bool, href: &str) -> MultigetItemDto {
+        let user_id = recipient.user_id;
+        let result = match DavHref::parse(href) {
+            None => return MultigetItemDto::error(href, 400, "Unsupported href"),
+            Some(DavHref::File(path)) => self.get_file(recipient, is_admin, href, &path).await,
+            Some(DavHref::Event { calendar_id, uid }) => self.get_event(href, user_id, is_admin, &calendar_id, &uid).await,
+            Some(DavHref::Contact { address_book_id, uid }) => self.get_contact(href, user_id, is_admin, &address_book_id, &uid).await,
+        };
+        result.unwrap_or_else(|e| MultigetItemDto::error(href, error_status(&e), e.to_string()))
+    }
+}
+
+#[async_trait]
+impl DavMultigetUseCase for DavMultigetService {
+    async fn multiget(&self, recipient: ShareRecipient<'_>, is_admin: bool, hrefs: &[String]) -> Result<MultigetResponseDto, DomainError> {
+        if hrefs.len() > MAX_HREFS {
+            return Err(DomainError::validation_error(format!(
+                "A multiget request accepts at most {} hrefs", MAX_HREFS
+            )));
+        }
+
+        let mut responses = Vec::with_capacity(hrefs.len());
+        for href in hrefs {
+            responses.push(self.get_one(recipient, is_admin, href).await);
+        }
+        Ok(MultigetResponseDto { responses })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::services::file_service::FileService;
+    use crate::application::services::test_access::HomeOnlyAccess;
+
+    #[test]
+    fn parses_file_hrefs() {
+        assert_eq!(DavHref::parse("/api/webdav/Docs/a.txt"), Some(DavHref::File("Docs/a.txt".to_string())));
+        assert_eq!(DavHref::parse("https://cloud.example/api/v1/webdav/Docs/a.txt?x=1"), Some(DavHref::File("Docs/a.txt".to_string())));
+        assert_eq!(DavHref::parse("/webdav/Docs/"), Some(DavHref::File("Docs".to_string())));
+        assert_eq!(DavHref::parse("/api/webdav/"), None);
+    }
+
+    #[test]
+    fn decodes_hrefs() {
+        assert_eq!(
+            DavHref::parse("/api/webdav/Mi%20Carpeta/100%25%20%23one%2Btwo%20%F0%9F%8E%89%20"),
+            Some(DavHref::File("Mi Carpeta/100% #one+two 🎉 ".to_string()))
+        );
+        let id = Uuid::new_v4();
+        assert_eq!(
+            DavHref::parse(&format!("/api/caldav/{}/a%2Bb%40host.ics", id)),
+            Some(DavHref::Event { calendar_id: id, uid: "a+b@host".to_string() })
+        );
+        assert_eq!(DavHref::parse("/api/webdav/bad%zz"), None);
+    }
+
+    #[test]
+    fn parses_calendar_and_contact_hrefs() {
+        let id = Uuid::new_v4();
+        assert_eq!(
+            DavHref::parse(&format!("/api/caldav/{}/event-1.ics", id)),
+            Some(DavHref::Event { calendar_id: id, uid: "event-1".to_string() })
+        );
+        assert_eq!(
+            DavHref::parse(&format!("http://localhost:8085/api/carddav/{}/c1.vcf", id)),
+            Some(DavHref::Contact { address_book_id: id, uid: "c1".to_string() })
+        );
+        assert_eq!(
+            DavHref::parse(&format!("/api/carddav/addressbooks/{}/c1.vcf", id)),
+            Some(DavHref::Contact { address_book_id: id, uid: "c1".to_string() })
+        );
+        assert_eq!(DavHref::parse(&format!("/api/caldav/{}/event-1.vcf", id)), None);
+        assert_eq!(DavHref::parse("/api/caldav/not-a-uuid/event-1.ics"), None);
+        assert_eq!(DavHref::parse("/api/files/123"), None);
+    }
+
+    #[tokio::test]
+    async fn refuses_files_of_other_users() {
+        let service = DavMultigetService::new(Arc::new(FileService::new_stub()), Arc::new(HomeOnlyAccess::default()));
+        let alice = ShareRecipient { user_id: "alice-id", username: "alice", email: "alice@example.com" };
+        let hrefs = vec![
+            "/webdav/Mi%20Carpeta%20-%20bob/private.txt".to_string(),
+            "/webdav/Mi%20Carpeta%20-%20alice/notes.txt".to_string(),
+        ];
+
+        let response = service.multiget(alice, false, &hrefs).await.unwrap();
+        assert_eq!(response.responses[0].status, 403);
+        assert!(response.responses[0].body.is_none());
+        assert_eq!(response.responses[1].status, 200);
+
+        let response = service.multiget(alice, true, &hrefs[..1]).await.unwrap();
+        assert_eq!(response.responses[0].status, 200);
+    }
+}
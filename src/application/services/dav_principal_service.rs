@@ -0,0 +1,261 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::application::dtos::dav_principal_dto::{
+    PrincipalDto, PrincipalKind, PrincipalSearchDto, PrincipalSearchProperty, PrincipalSearchResultDto, PropertyMatchDto,
+};
+use crate::application::ports::auth_ports::UserStoragePort;
+use crate::application::ports::dav_principal_ports::DavPrincipalUseCase;
+use crate::common::errors::{DomainError, ErrorKind};
+use crate::domain::entities::user::User;
+
+/// Número máximo de principales por búsqueda; si hay más, el REPORT se trunca (507)
+pub const MAX_PRINCIPAL_RESULTS: usize = 50;
+
+/// Longitud mínima del texto de búsqueda para usuarios no administradores,
+/// para que no se pueda recorrer el directorio letra a letra
+pub const MIN_SEARCH_LENGTH: usize = 3;
+
+/// Grupos conocidos y su nombre visible. Mientras no existan grupos reales,
+/// cada rol hace de grupo.
+const GROUPS: [(&str, &str); 2] = [("admin", "Administrators"), ("user", "Users")];
+
+/// Servicio de búsqueda de principales DAV (usuarios y grupos) para que los
+/// clientes puedan localizar con quién compartir.
+///
+/// Reglas de privacidad para usuarios no administradores:
+/// - los textos de búsqueda más cortos que `MIN_SEARCH_LENGTH` no devuelven nada;
+/// - el correo solo se busca por coincidencia exacta y solo se muestra si el
+///   usuario lo escribió completo o es el suyo;
+/// - solo ven los grupos a los que pertenecen, sin su lista de miembros;
+/// - los usuarios desactivados nunca aparecen.
+pub struct DavPrincipalService {
+    user_storage: Arc<dyn UserStoragePort>,
+}
+
+/// Principal encontrado junto a la clave por la que se ordena y combina
+type Matches = BTreeMap<(PrincipalKind, String), PrincipalDto>;
+
+impl DavPrincipalService {
+    /// Crea un nuevo servicio de principales
+    pub fn new(user_storage: Arc<dyn UserStoragePort>) -> Self {
+        Self { user_storage }
+    }
+
+    /// Principales que cumplen un único criterio
+    async fn match_property(
+        &self,
+        requester: &User,
+        is_admin: bool,
+        property_match: &PropertyMatchDto,
+    ) -> Result<Matches, DomainError> {
+        let text = property_match.text.trim().to_lowercase();
+        let mut matches = Matches::new();
+        if text.is_empty() || (!is_admin && text.chars().count() < MIN_SEARCH_LENGTH) {
+            return Ok(matches);
+        }
+
+        match property_match.property {
+            PrincipalSearchProperty::DisplayName => {
+                let users = self.user_storage.search_users(&text, MAX_PRINCIPAL_RESULTS as i64 + 1).await?;
+                // El repositorio también busca en el correo; aquí solo cuenta el nombre
+                for user in users.iter().filter(|user| user.username().to_lowercase().contains(&text)) {
+                    let principal = self.user_principal(user, requester, is_admin, false);
+                    matches.insert((PrincipalKind::User, principal.name.clone()), principal);
+                }
+
+                let requester_group = requester.role().to_string();
+                for (name, display_name) in GROUPS {
+                    let visible = is_admin || name == requester_group;
+                    let matched = name.contains(&text) || display_name.to_lowercase().contains(&text);
+                    if visible && matched {
+                        let principal = self.group_principal(name, display_name, is_admin).await?;
+                        matches.insert((PrincipalKind::Group, principal.name.clone()), principal);
+                    }
+                }
+            }
+            PrincipalSearchProperty::CalendarUserAddress => {
+                let text = text.strip_prefix("mailto:").unwrap_or(&text);
+                let users = if is_admin {
+                    self.user_storage.search_users(text, MAX_PRINCIPAL_RESULTS as i64 + 1).await?
+                        .into_iter()
+                        .filter(|user| user.email().to_lowercase().contains(text))
+                        .collect()
+                } else {
+                    match self.user_storage.get_user_by_email(text).await {
+                        Ok(user) if user.is_active() => vec![user],
+                        Ok(_) => Vec::new(),
+                        Err(e) if e.kind == ErrorKind::NotFound => Vec::new(),
+                        Err(e) => return Err(e),
+                    }
+                };
+                for user in &users {
+                    let exact = user.email().eq_ignore_ascii_case(text);
+                    let principal = self.user_principal(user, requester, is_admin, exact);
+                    matches.insert((PrincipalKind::User, principal.name.clone()), principal);
+                }
+            }
+        }
+
+        Ok(matches)
+    }
+
+    /// Principal de un usuario, mostrando el correo solo cuando procede
+    fn user_principal(&self, user: &User, requester: &User, is_admin: bool, email_given: bool) -> PrincipalDto {
+        let show_email = is_admin || email_given || user.id() == requester.id();
+        PrincipalDto {
+            kind: PrincipalKind::User,
+            name: user.username().to_string(),
+            display_name: user.username().to_string(),
+            email: show_email.then(|| user.email().to_string()),
+            group_membership: vec![user.role().to_string()],
+            group_member_set: Some(Vec::new()),
+        }
+    }
+
+    /// Principal de un grupo; los miembros solo se listan a los administradores
+    async fn group_principal(&self, name: &str, display_name: &str, is_admin: bool) -> Result<PrincipalDto, DomainError> {
+        let group_member_set = if is_admin {
+            let members = self.user_storage.list_users_by_role(name).await?;
+            let mut usernames: Vec<String> = members.iter()
+                .filter(|user| user.is_active())
+                .map(|user| user.username().to_string())
+                .collect();
+            usernames.sort();
+            Some(usernames)
+        } else {
+            None
+        };
+
+        Ok(PrincipalDto {
+            kind: PrincipalKind::Group,
+            name: name.to_string(),
+            display_name: display_name.to_string(),
+            email: None,
+            group_membership: Vec::new(),
+            group_member_set,
+        })
+    }
+}
+
+#[async_trait]
+impl DavPrincipalUseCase for DavPrincipalService {
+    async fn search_principals(
+        &self,
+        user_id: &str,
+        role: &str,
+        search: &PrincipalSearchDto,
+    ) -> Result<PrincipalSearchResultDto, DomainError> {
+        if search.matches.is_empty() {
+            return Err(DomainError::validation_error("principal-property-search needs at least one property-search"));
+        }
+        let requester = self.user_storage.get_user_by_id(user_id).await?;
+        let is_admin = role == "admin";
+
+        let mut combined: Option<Matches> = None;
+        for property_match in &search.matches {
+            let matches = self.match_property(&requester, is_admin, property_match).await?;
+            combined = Some(match combined {
+                None => matches,
+                // Si un mismo usuario aparece por nombre y por correo exacto, se conserva el correo
+                Some(previous) if search.match_all => {
+                    previous.into_iter()
+                        .filter_map(|(key, mut principal)| {
+                            let other = matches.get(&key)?;
+                            principal.email = principal.email.or_else(|| other.email.clone());
+                            Some((key, principal))
+                        })
+                        .collect()
+                }
+                Some(mut previous) => {
+                    for (key, principal) in matches {
+                        let entry = previous.entry(key).or_insert_with(|| principal.clone());
+                        if entry.email.is_none() {
+                            entry.email = principal.email;
+                        }
+                    }
+                    previous
+                }
+            });
+        }
+
+        let mut principals: Vec<PrincipalDto> = combined.unwrap_or_default().into_values().collect();
+        let truncated = principals.len() > MAX_PRINCIPAL_RESULTS;
+        principals.truncate(MAX_PRINCIPAL_RESULTS);
+
+        Ok(PrincipalSearchResultDto { principals, truncated })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::services::test_users::MemoryUsers;
+    use crate::domain::entities::user::UserRole;
+
+    fn search(matches: &[(PrincipalSearchProperty, &str)], match_all: bool) -> PrincipalSearchDto {
+        PrincipalSearchDto {
+            matches: matches.iter()
+                .map(|(property, text)| PropertyMatchDto { property: *property, text: text.to_string() })
+                .collect(),
+            match_all,
+        }
+    }
+
+    #[tokio::test]
+    async fn hides_emails_and_other_groups_from_regular_users() {
+        let users = Arc::new(MemoryUsers::default());
+        let alice = users.add("alice", UserRole::User, 1024);
+        users.add("alicia", UserRole::Admin, 1024);
+        let service = DavPrincipalService::new(users);
+
+        let result = service.search_principals(&alice, "user", &search(&[(PrincipalSearchProperty::DisplayName, "ali")], false))
+            .await.unwrap();
+        let names: Vec<_> = result.principals.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["alice", "alicia"]);
+        assert_eq!(result.principals[0].email.as_deref(), Some("alice@example.com"));
+        assert_eq!(result.principals[1].email, None);
+        assert_eq!(result.principals[1].group_membership, ["admin"]);
+
+        // Textos cortos y grupos ajenos no devuelven nada
+        let short = service.search_principals(&alice, "user", &search(&[(PrincipalSearchProperty::DisplayName, "al")], false))
+            .await.unwrap();
+        assert!(short.principals.is_empty());
+        let groups = service.search_principals(&alice, "user", &search(&[(PrincipalSearchProperty::DisplayName, "admin")], false))
+            .await.unwrap();
+        assert!(groups.principals.is_empty());
+
+        // El correo solo se encuentra completo, y entonces se muestra
+        let partial = service.search_principals(&alice, "user", &search(&[(PrincipalSearchProperty::CalendarUserAddress, "alicia@")], false))
+            .await.unwrap();
+        assert!(partial.principals.is_empty());
+        let exact = service.search_principals(&alice, "user", &search(&[(PrincipalSearchProperty::CalendarUserAddress, "mailto:alicia@example.com")], false))
+            .await.unwrap();
+        assert_eq!(exact.principals[0].email.as_deref(), Some("alicia@example.com"));
+    }
+
+    #[tokio::test]
+    async fn admins_see_group_members_and_allof_intersects() {
+        let users = Arc::new(MemoryUsers::default());
+        let admin = users.add("root", UserRole::Admin, 1024);
+        users.add("bob", UserRole::User, 1024);
+        users.add("bobby", UserRole::User, 1024);
+        let service = DavPrincipalService::new(users);
+
+        let groups = service.search_principals(&admin, "admin", &search(&[(PrincipalSearchProperty::DisplayName, "users")], false))
+            .await.unwrap();
+        assert_eq!(groups.principals.len(), 1);
+        assert_eq!(groups.principals[0].kind, PrincipalKind::Group);
+        assert_eq!(groups.principals[0].group_member_set, Some(vec!["bob".to_string(), "bobby".to_string()]));
+
+        let both = service.search_principals(&admin, "admin", &search(&[
+            (PrincipalSearchProperty::DisplayName, "bo"),
+            (PrincipalSearchProperty::CalendarUserAddress, "bobby@"),
+        ], true)).await.unwrap();
+        let names: Vec<_> = both.principals.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["bobby"]);
+        assert!(!both.truncated);
+    }
+}
@@ -0,0 +1,177 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::application::dtos::calendar_dto::CalendarEventDto;
+use crate::application::dtos::contact_dto::VCardObjectDto;
+use crate::application::dtos::dav_sync_dto::{AddressBookSyncDto, CalendarSyncDto, SyncOutcome};
+use crate::application::ports::dav_sync_ports::DavSyncUseCase;
+use crate::application::services::dav_access_service::DavAccessService;
+use crate::common::errors::{DomainError, ErrorKind};
+use crate::domain::repositories::address_book_repository::AddressBookRepository;
+use crate::domain::repositories::calendar_event_repository::CalendarEventRepository;
+use crate::domain::repositories::calendar_repository::CalendarRepository;
+use crate::domain::repositories::contact_repository::ContactRepository;
+use crate::domain::services::sync_token_service::{collapse_changes, SyncToken};
+
+/// Número máximo de objetos por respuesta sync-collection; el resto se
+/// devuelve en peticiones sucesivas (507 en el REPORT)
+pub const MAX_SYNC_RESULTS: usize = 1000;
+
+/// Servicio de sincronización incremental (RFC 6578) de calendarios y
+/// libretas de direcciones.
+///
+/// Los cambios salen del diario que mantienen los triggers de
+/// `caldav.calendar_changes` y `carddav.address_book_changes`; el sync-token
+/// es la revisión de la colección hasta la que el cliente está al día.
+pub struct DavSyncService {
+    access: DavAccessService,
+    calendar_repository: Arc<dyn CalendarRepository>,
+    event_repository: Arc<dyn CalendarEventRepository>,
+    address_book_repository: Arc<dyn AddressBookRepository>,
+    contact_repository: Arc<dyn ContactRepository>,
+}
+
+impl DavSyncService {
+    /// Crea un nuevo servicio de sincronización
+    pub fn new(
+        calendar_repository: Arc<dyn CalendarRepository>,
+        event_repository: Arc<dyn CalendarEventRepository>,
+        address_book_repository: Arc<dyn AddressBookRepository>,
+        contact_repository: Arc<dyn ContactRepository>,
+    ) -> Self {
+        Self {
+            access: DavAccessService::new(calendar_repository.clone(), address_book_repository.clone()),
+            calendar_repository,
+            event_repository,
+            address_book_repository,
+            contact_repository,
+        }
+    }
+}
+
+#[async_trait]
+impl DavSyncUseCase for DavSyncService {
+    async fn sync_calendar(
+        &self,
+        user_id: &str,
+        is_admin: bool,
+        calendar_id: &str,
+        sync_token: &str,
+        limit: Option<usize>,
+    ) -> Result<SyncOutcome<CalendarSyncDto>, DomainError> {
+        let id = Uuid::parse_str(calendar_id)
+            .map_err(|_| DomainError::validation_error(format!("Invalid calendar ID: {}", calendar_id)))?;
+        self.access.check_calendar(user_id, is_admin, &id, false).await?;
+
+        // La revisión se lee antes que los eventos: un cambio concurrente
+        // se volverá a enviar en la siguiente sincronización, pero no se pierde
+        let current_revision = self.calendar_repository.find_calendar_by_id(&id).await?.sync_revision();
+
+        // Sincronización inicial: todo el calendario
+        if sync_token.trim().is_empty() {
+            let events = self.event_repository.list_events_by_calendar(&id).await?;
+            return Ok(SyncOutcome::Changes(CalendarSyncDto {
+                sync_token: SyncToken::new(id, current_revision).to_uri(),
+                events: events.into_iter().map(CalendarEventDto::from).collect(),
+                deleted_uids: Vec::new(),
+                truncated: false,
+            }));
+        }
+
+        let Some(token) = SyncToken::parse(sync_token) else {
+            return Ok(SyncOutcome::InvalidToken);
+        };
+        let oldest_change = self.event_repository.get_oldest_change_revision(&id).await?;
+        if !token.is_valid_for(&id, current_revision, oldest_change) {
+            return Ok(SyncOutcome::InvalidToken);
+        }
+
+        let changes = self.event_repository.get_changes_since(&id, token.revision).await?;
+        let limit = limit.unwrap_or(MAX_SYNC_RESULTS).clamp(1, MAX_SYNC_RESULTS);
+        let delta = collapse_changes(&changes, token.revision, limit);
+
+        let mut events = Vec::with_capacity(delta.changed.len());
+        let mut deleted_uids = delta.deleted;
+        for (event_id, uid) in delta.changed {
+            // El evento pudo cambiar de calendario o borrarse después de la
+            // última revisión leída; para este cliente ya no existe
+            match self.event_repository.find_event_by_id(&event_id).await {
+                Ok(event) if event.calendar_id() == &id && event.ical_uid() == uid => {
+                    events.push(CalendarEventDto::from(event));
+                }
+                Ok(_) => deleted_uids.push(uid),
+                Err(e) if e.kind == ErrorKind::NotFound => deleted_uids.push(uid),
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(SyncOutcome::Changes(CalendarSyncDto {
+            sync_token: SyncToken::new(id, delta.revision).to_uri(),
+            events,
+            deleted_uids,
+            truncated: delta.truncated,
+        }))
+    }
+
+    async fn sync_address_book(
+        &self,
+        user_id: &str,
+        is_admin: bool,
+        address_book_id: &str,
+        sync_token: &str,
+        limit: Option<usize>,
+    ) -> Result<SyncOutcome<AddressBookSyncDto>, DomainError> {
+        let id = Uuid::parse_str(address_book_id)
+            .map_err(|_| DomainError::validation_error(format!("Invalid address book ID: {}", address_book_id)))?;
+        self.access.check_address_book(user_id, is_admin, &id, false).await?;
+
+        // Igual que en los calendarios, la revisión se lee antes que los contactos
+        let current_revision = self.address_book_repository.get_address_book_by_id(&id).await?
+            .ok_or_else(|| DomainError::not_found("Address book", address_book_id))?
+            .sync_revision;
+
+        // Sincronización inicial: toda la libreta
+        if sync_token.trim().is_empty() {
+            let contacts = self.contact_repository.get_contacts_by_address_book(&id).await?;
+            return Ok(SyncOutcome::Changes(AddressBookSyncDto {
+                sync_token: SyncToken::new(id, current_revision).to_uri(),
+                vcards: contacts.into_iter().map(VCardObjectDto::from).collect(),
+                deleted_uids: Vec::new(),
+                truncated: false,
+            }));
+        }
+
+        let Some(token) = SyncToken::parse(sync_token) else {
+            return Ok(SyncOutcome::InvalidToken);
+        };
+        let oldest_change = self.contact_repository.get_oldest_change_revision(&id).await?;
+        if !token.is_valid_for(&id, current_revision, oldest_change) {
+            return Ok(SyncOutcome::InvalidToken);
+        }
+
+        let changes = self.contact_repository.get_changes_since(&id, token.revision).await?;
+        let limit = limit.unwrap_or(MAX_SYNC_RESULTS).clamp(1, MAX_SYNC_RESULTS);
+        let delta = collapse_changes(&changes, token.revision, limit);
+
+        let mut vcards = Vec::with_capacity(delta.changed.len());
+        let mut deleted_uids = delta.deleted;
+        for (contact_id, uid) in delta.changed {
+            // El contacto pudo moverse de libreta o borrarse después de la revisión leída
+            match self.contact_repository.get_contact_by_id(&contact_id).await? {
+                Some(contact) if contact.address_book_id == id && contact.uid == uid => {
+                    vcards.push(VCardObjectDto::from(contact));
+                }
+                _ => deleted_uids.push(uid),
+            }
+        }
+
+        Ok(SyncOutcome::Changes(AddressBookSyncDto {
+            sync_token: SyncToken::new(id, delta.revision).to_uri(),
+            vcards,
+            deleted_uids,
+            truncated: delta.truncated,
+        }))
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::application::dtos::dav_transfer_dto::{DavTransferDto, DavTransferState};
+use crate::application::dtos::notification_dto::{JobFailure, JobKind, RetryActionDto};
+use crate::application::ports::dav_transfer_ports::DavTransferUseCase;
+use crate::application::ports::notification_ports::NotificationUseCase;
+use crate::common::errors::DomainError;
+use crate::domain::services::ownership_service::TransferOperation;
+use crate::domain::services::path_codec_service::encode_path;
+
+/// Trabajo en curso o terminado y el usuario que lo inició
+struct TrackedTransfer {
+    user_id: String,
+    transfer: DavTransferDto,
+}
+
+/// Servicio de las copias y movimientos WebDAV de carpetas grandes.
+///
+/// Un COPY o MOVE de un árbol de varios gigas puede tardar minutos y los
+/// clientes cortan la conexión mucho antes. Por encima del umbral el handler
+/// responde `202 Accepted` con la URL de estado y sigue en segundo plano; aquí
+/// se guarda cómo va cada trabajo y con qué estado habría respondido, y los
+/// fallos llegan al centro de notificaciones como los de las exportaciones.
+/// Los trabajos se guardan en memoria y se olvidan pasado un tiempo.
+pub struct DavTransferService {
+    threshold_bytes: u64,
+    result_ttl: Duration,
+    transfers: Mutex<HashMap<String, TrackedTransfer>>,
+    notifications: Option<Arc<dyn NotificationUseCase>>,
+}
+
+impl DavTransferService {
+    /// Crea el servicio; `threshold_mb` es el tamaño a partir del cual un
+    /// trabajo va a segundo plano y `result_ttl_secs` cuánto se conserva su
+    /// resultado una vez terminado
+    pub fn new(threshold_mb: u64, result_ttl_secs: u64) -> Self {
+        Self {
+            threshold_bytes: threshold_mb * 1024 * 1024,
+            result_ttl: Duration::seconds(result_ttl_secs as i64),
+            transfers: Mutex::new(HashMap::new()),
+            notifications: None,
+        }
+    }
+
+    /// Notifica los trabajos que fallan
+    pub fn with_notifications(mut self, notifications: Arc<dyn NotificationUseCase>) -> Self {
+        self.notifications = Some(notifications);
+        self
+    }
+
+    /// Olvida los trabajos terminados hace más de `result_ttl`
+    fn prune(&self, transfers: &mut HashMap<String, TrackedTransfer>) {
+        let cutoff = Utc::now() - self.result_ttl;
+        transfers.retain(|_, tracked| tracked.transfer.finished_at.is_none_or(|finished| finished > cutoff));
+    }
+}
+
+#[async_trait]
+impl DavTransferUseCase for DavTransferService {
+    fn async_threshold(&self) -> u64 {
+        self.threshold_bytes
+    }
+
+    async fn start(
+        &self,
+        user_id: &str,
+        operation: TransferOperation,
+        source: &str,
+        destination: &str,
+        size: u64,
+    ) -> DavTransferDto {
+        let transfer = DavTransferDto {
+            id: Uuid::new_v4().to_string(),
+            operation: match operation {
+                TransferOperation::Copy => "copy",
+                TransferOperation::Move => "move",
+            }.to_string(),
+            source: source.to_string(),
+            destination: destination.to_string(),
+            size,
+            state: DavTransferState::Running,
+            created_at: Utc::now(),
+            finished_at: None,
+            status: None,
+            error: None,
+        };
+        tracing::info!("WebDAV {} de {} a {} ({} bytes) en segundo plano: {}",
+            transfer.operation, source, destination, size, transfer.id);
+
+        let mut transfers = self.transfers.lock().await;
+        self.prune(&mut transfers);
+        transfers.insert(transfer.id.clone(), TrackedTransfer { user_id: user_id.to_string(), transfer: transfer.clone() });
+        transfer
+    }
+
+    async fn finish(&self, transfer_id: &str, status: u16, error: Option<String>) {
+        let (user_id, transfer) = {
+            let mut transfers = self.transfers.lock().await;
+            let Some(tracked) = transfers.get_mut(transfer_id) else {
+                return;
+            };
+            let transfer = &mut tracked.transfer;
+            transfer.state = if error.is_some() { DavTransferState::Failed } else { DavTransferState::Completed };
+            transfer.finished_at = Some(Utc::now());
+            transfer.status = Some(status);
+            transfer.error = error;
+            (tracked.user_id.clone(), transfer.clone())
+        };
+
+        let Some(error) = transfer.error else {
+            tracing::info!("WebDAV {} {} terminado", transfer.operation, transfer.id);
+            return;
+        };
+        if let Some(notifications) = &self.notifications {
+            let (kind, method) = match transfer.operation.as_str() {
+                "move" => (JobKind::WebDavMove, "MOVE"),
+                _ => (JobKind::WebDavCopy, "COPY"),
+            };
+            notifications.report_job_failure(Some(&user_id), JobFailure {
+                job_id: transfer.id,
+                kind,
+                error,
+                retry: RetryActionDto {
+                    method: method.to_string(),
+                    href: format!("/webdav/{}", encode_path(&transfer.source)),
+                    requires_upload: false,
+                },
+            }).await;
+        }
+    }
+
+    async fn get_transfer(&self, user_id: &str, transfer_id: &str) -> Result<DavTransferDto, DomainError> {
+        let transfers = self.transfers.lock().await;
+        transfers.get(transfer_id)
+            .filter(|tracked| tracked.user_id == user_id)
+            .map(|tracked| tracked.transfer.clone())
+            .ok_or_else(|| DomainError::not_found("WebDAV transfer", transfer_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_transfer_lifecycle() {
+        let service = DavTransferService::new(1, 3600);
+        assert_eq!(service.async_threshold(), 1024 * 1024);
+
+        let transfer = service.start("ana", TransferOperation::Copy, "Mi Carpeta - ana/fotos", "Mi Carpeta - ana/copia", 5).await;
+        assert_eq!(transfer.state, DavTransferState::Running);
+        assert!(service.get_transfer("bob", &transfer.id).await.is_err());
+
+        service.finish(&transfer.id, 412, Some("Destination already exists".to_string())).await;
+        let finished = service.get_transfer("ana", &transfer.id).await.unwrap();
+        assert_eq!(finished.state, DavTransferState::Failed);
+        assert_eq!(finished.status, Some(412));
+        assert!(finished.finished_at.is_some());
+    }
+}
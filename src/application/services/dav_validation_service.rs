@@ -0,0 +1,284 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::application::dtos::dav_validation_dto::{RepairJobDto, ValidationIssueDto, ValidationReportDto};
+use crate::application::ports::dav_validation_ports::{DavValidationUseCase, ValidationRequester};
+use crate::application::services::dav_access_service::DavAccessService;
+use crate::common::errors::DomainError;
+use crate::domain::repositories::address_book_repository::AddressBookRepository;
+use crate::domain::repositories::calendar_event_repository::CalendarEventRepository;
+use crate::domain::repositories::calendar_repository::CalendarRepository;
+use crate::domain::repositories::contact_repository::ContactRepository;
+use crate::domain::services::dav_validation_service::{
+    self, apply_fix, ItemIssue, StoredItem,
+};
+
+/// Tipo de colección que se valida
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CollectionType {
+    Calendar,
+    AddressBook,
+}
+
+impl CollectionType {
+    fn as_str(self) -> &'static str {
+        match self {
+            CollectionType::Calendar => "calendar",
+            CollectionType::AddressBook => "address_book",
+        }
+    }
+}
+
+/// Elemento leído de una colección con los datos necesarios para validarlo
+struct LoadedItem {
+    id: String,
+    uid: String,
+    data: String,
+    full_name: Option<String>,
+}
+
+/// Servicio de validación de calendarios y libretas de direcciones.
+///
+/// Los informes se calculan al vuelo sobre los datos iCalendar/vCard
+/// almacenados; la reparación se ejecuta en segundo plano y reescribe solo
+/// los elementos con reparaciones automáticas.
+#[derive(Clone)]
+pub struct DavValidationService {
+    access: DavAccessService,
+    event_repository: Arc<dyn CalendarEventRepository>,
+    contact_repository: Arc<dyn ContactRepository>,
+    jobs: Arc<RwLock<HashMap<String, (String, RepairJobDto)>>>,
+}
+
+impl DavValidationService {
+    /// Crea un nuevo servicio de validación
+    pub fn new(
+        calendar_repository: Arc<dyn CalendarRepository>,
+        event_repository: Arc<dyn CalendarEventRepository>,
+        address_book_repository: Arc<dyn AddressBookRepository>,
+        contact_repository: Arc<dyn ContactRepository>,
+    ) -> Self {
+        Self {
+            access: DavAccessService::new(calendar_repository, address_book_repository),
+            event_repository,
+            contact_repository,
+            jobs: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    async fn load_items(&self, collection: CollectionType, collection_id: &Uuid) -> Result<Vec<LoadedItem>, DomainError> {
+        Ok(match collection {
+            CollectionType::Calendar => self.event_repository.list_events_by_calendar(collection_id).await?
+                .into_iter()
+                .map(|event| LoadedItem {
+                    id: event.id().to_string(),
+                    uid: event.ical_uid().to_string(),
+                    data: event.ical_data().to_string(),
+                    full_name: None,
+                })
+                .collect(),
+            CollectionType::AddressBook => self.contact_repository.get_contacts_by_address_book(collection_id).await?
+                .into_iter()
+                .map(|contact| LoadedItem {
+                    id: contact.id.to_string(),
+                    uid: contact.uid,
+                    data: contact.vcard,
+                    full_name: contact.full_name,
+                })
+                .collect(),
+        })
+    }
+
+    /// Valida los elementos de una colección
+    fn find_issues(collection: CollectionType, items: &[LoadedItem]) -> HashMap<String, Vec<ItemIssue>> {
+        let stored: Vec<StoredItem> = items.iter()
+            .map(|item| StoredItem { id: &item.id, stored_uid: &item.uid, data: &item.data })
+            .collect();
+        match collection {
+            CollectionType::Calendar => dav_validation_service::validate_calendar_items(&stored),
+            CollectionType::AddressBook => {
+                let full_names: HashMap<String, String> = items.iter()
+                    .filter_map(|item| item.full_name.clone().map(|name| (item.id.clone(), name)))
+                    .collect();
+                dav_validation_service::validate_address_book_items(&stored, &full_names)
+            }
+        }
+    }
+
+    async fn validate(&self, collection: CollectionType, collection_id: &Uuid) -> Result<ValidationReportDto, DomainError> {
+        let items = self.load_items(collection, collection_id).await?;
+        let mut issues = Self::find_issues(collection, &items);
+
+        // Mismo orden que la colección para que los informes sean estables
+        let mut report = Vec::new();
+        for item in &items {
+            for issue in issues.remove(&item.id).unwrap_or_default() {
+                report.push(ValidationIssueDto {
+                    item_id: item.id.clone(),
+                    uid: item.uid.clone(),
+                    kind: issue.kind.as_str().to_string(),
+                    message: issue.message,
+                    fix: issue.fix.as_ref().map(|fix| fix.as_str().to_string()),
+                    suggestion: issue.fix.as_ref().map(|fix| fix.suggestion()),
+                });
+            }
+        }
+
+        Ok(ValidationReportDto {
+            collection_id: collection_id.to_string(),
+            collection_type: collection.as_str().to_string(),
+            scanned: items.len() as u64,
+            fixable: report.iter().filter(|issue| issue.fix.is_some()).count() as u64,
+            issues: report,
+        })
+    }
+
+    /// Guarda los datos reparados de un elemento
+    async fn store_repaired(&self, collection: CollectionType, item_id: &str, data: String) -> Result<(), DomainError> {
+        let id = parse_id(item_id)?;
+        match collection {
+            CollectionType::Calendar => {
+                let mut event = self.event_repository.find_event_by_id(&id).await?;
+                event.update_ical_data(data)?;
+                self.event_repository.update_event(event).await?;
+            }
+            CollectionType::AddressBook => {
+                let mut contact = self.contact_repository.get_contact_by_id(&id).await?
+                    .ok_or_else(|| DomainError::not_found("Contact", item_id))?;
+                contact.vcard = data;
+                // Los clientes deben volver a descargar la tarjeta
+                contact.etag = Uuid::new_v4().to_string();
+                self.contact_repository.update_contact(contact).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn update_job(&self, job_id: &str, update: impl FnOnce(&mut RepairJobDto)) {
+        if let Some((_, job)) = self.jobs.write().await.get_mut(job_id) {
+            update(job);
+        }
+    }
+
+    /// Aplica todas las reparaciones automáticas de una colección
+    async fn run_repair(&self, job_id: &str, collection: CollectionType, collection_id: Uuid) -> Result<(), DomainError> {
+        let items = self.load_items(collection, &collection_id).await?;
+        let mut issues = Self::find_issues(collection, &items);
+
+        for item in items {
+            let Some(item_issues) = issues.remove(&item.id) else { continue };
+
+            let mut data = item.data.clone();
+            let mut unfixable = 0;
+            for issue in &item_issues {
+                match issue.fix.as_ref().and_then(|fix| apply_fix(&data, fix)) {
+                    Some(fixed) => data = fixed,
+                    None => unfixable += 1,
+                }
+            }
+            self.update_job(job_id, |job| job.unfixable += unfixable).await;
+            if data == item.data {
+                continue;
+            }
+
+            match self.store_repaired(collection, &item.id, data).await {
+                Ok(()) => self.update_job(job_id, |job| job.repaired += 1).await,
+                Err(e) => {
+                    tracing::warn!("Repair job {} could not store item {}: {}", job_id, item.id, e);
+                    self.update_job(job_id, |job| job.failed += 1).await;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn start_repair(&self, requester: &ValidationRequester, collection: CollectionType, collection_id: Uuid) -> Result<RepairJobDto, DomainError> {
+        {
+            let jobs = self.jobs.read().await;
+            let running = jobs.values()
+                .find(|(_, job)| job.collection_id == collection_id.to_string() && job.status == "running");
+            if let Some((_, job)) = running {
+                return Ok(job.clone());
+            }
+        }
+
+        let job = RepairJobDto {
+            id: Uuid::new_v4().to_string(),
+            collection_id: collection_id.to_string(),
+            status: "running".to_string(),
+            repaired: 0,
+            failed: 0,
+            unfixable: 0,
+            error: None,
+            started_at: now_secs(),
+            finished_at: None,
+        };
+        self.jobs.write().await.insert(job.id.clone(), (requester.user_id.clone(), job.clone()));
+
+        let service = self.clone();
+        let job_id = job.id.clone();
+        tokio::spawn(async move {
+            let result = service.run_repair(&job_id, collection, collection_id).await;
+            service.update_job(&job_id, |job| {
+                job.finished_at = Some(now_secs());
+                match result {
+                    Ok(()) => job.status = "completed".to_string(),
+                    Err(e) => {
+                        job.status = "failed".to_string();
+                        job.error = Some(e.to_string());
+                    }
+                }
+            }).await;
+            tracing::info!("Repair job {} finished for {} {}", job_id, collection.as_str(), collection_id);
+        });
+
+        Ok(job)
+    }
+}
+
+fn parse_id(id: &str) -> Result<Uuid, DomainError> {
+    Uuid::parse_str(id).map_err(|_| DomainError::validation_error(format!("Invalid ID: {}", id)))
+}
+
+fn now_secs() -> u64 {
+    chrono::Utc::now().timestamp().max(0) as u64
+}
+
+#[async_trait]
+impl DavValidationUseCase for DavValidationService {
+    async fn validate_calendar(&self, requester: &ValidationRequester, calendar_id: &str) -> Result<ValidationReportDto, DomainError> {
+        let id = parse_id(calendar_id)?;
+        self.access.check_calendar(&requester.user_id, requester.is_admin, &id, false).await?;
+        self.validate(CollectionType::Calendar, &id).await
+    }
+
+    async fn validate_address_book(&self, requester: &ValidationRequester, address_book_id: &str) -> Result<ValidationReportDto, DomainError> {
+        let id = parse_id(address_book_id)?;
+        self.access.check_address_book(&requester.user_id, requester.is_admin, &id, false).await?;
+        self.validate(CollectionType::AddressBook, &id).await
+    }
+
+    async fn start_calendar_repair(&self, requester: &ValidationRequester, calendar_id: &str) -> Result<RepairJobDto, DomainError> {
+        let id = parse_id(calendar_id)?;
+        self.access.check_calendar(&requester.user_id, requester.is_admin, &id, true).await?;
+        self.start_repair(requester, CollectionType::Calendar, id).await
+    }
+
+    async fn start_address_book_repair(&self, requester: &ValidationRequester, address_book_id: &str) -> Result<RepairJobDto, DomainError> {
+        let id = parse_id(address_book_id)?;
+        self.access.check_address_book(&requester.user_id, requester.is_admin, &id, true).await?;
+        self.start_repair(requester, CollectionType::AddressBook, id).await
+    }
+
+    async fn get_repair_job(&self, requester: &ValidationRequester, job_id: &str) -> Result<RepairJobDto, DomainError> {
+        let jobs = self.jobs.read().await;
+        match jobs.get(job_id) {
+            Some((owner, job)) if owner == &requester.user_id || requester.is_admin => Ok(job.clone()),
+            _ => Err(DomainError::not_found("RepairJob", job_id)),
+        }
+    }
+}
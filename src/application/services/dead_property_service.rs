@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::application::ports::dead_property_ports::DeadPropertyUseCase;
+use crate::common::errors::DomainError;
+use crate::domain::entities::dead_property::DeadProperty;
+use crate::domain::repositories::dead_property_repository::DeadPropertyRepository;
+
+/// Espacio de nombres reservado para las propiedades vivas de WebDAV
+const DAV_NAMESPACE: &str = "DAV:";
+
+/// Tamaño máximo del valor de una propiedad
+const MAX_VALUE_BYTES: usize = 64 * 1024;
+
+/// Longitud máxima del nombre de una propiedad (columna VARCHAR(255))
+const MAX_NAME_LENGTH: usize = 255;
+
+/// Servicio de propiedades muertas de WebDAV.
+///
+/// Guarda las propiedades que los clientes fijan con PROPPATCH, asociadas al
+/// ID del fichero o carpeta, para devolverlas después en PROPFIND.
+pub struct DeadPropertyService {
+    repository: Arc<dyn DeadPropertyRepository>,
+}
+
+impl DeadPropertyService {
+    pub fn new(repository: Arc<dyn DeadPropertyRepository>) -> Self {
+        Self { repository }
+    }
+
+    /// Comprueba que se puede guardar o borrar una propiedad con este nombre
+    fn validate_name(namespace: &str, name: &str) -> Result<(), DomainError> {
+        if namespace == DAV_NAMESPACE {
+            return Err(DomainError::access_denied(
+                "Property",
+                format!("'{}' is a protected DAV: property", name),
+            ));
+        }
+        if name.is_empty() || name.len() > MAX_NAME_LENGTH {
+            return Err(DomainError::validation_error(format!("Invalid property name '{}'", name)));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DeadPropertyUseCase for DeadPropertyService {
+    async fn get_properties(&self, resource_ids: &[String]) -> Result<HashMap<String, Vec<DeadProperty>>, DomainError> {
+        self.repository.get_properties(resource_ids).await
+    }
+
+    async fn patch_properties(
+        &self,
+        resource_id: &str,
+        set: Vec<DeadProperty>,
+        remove: Vec<(String, String)>,
+    ) -> Result<(), DomainError> {
+        // PROPPATCH es atómico: se valida todo antes de guardar nada
+        for property in &set {
+            Self::validate_name(&property.namespace, &property.name)?;
+            if property.value.as_ref().is_some_and(|value| value.len() > MAX_VALUE_BYTES) {
+                return Err(DomainError::validation_error(format!(
+                    "Value of property '{}' exceeds {} bytes",
+                    property.name, MAX_VALUE_BYTES
+                )));
+            }
+        }
+        for (namespace, name) in &remove {
+            Self::validate_name(namespace, name)?;
+        }
+
+        if set.is_empty() && remove.is_empty() {
+            return Ok(());
+        }
+
+        self.repository.patch_properties(resource_id, &set, &remove).await
+    }
+
+    async fn copy_properties(&self, from_resource_id: &str, to_resource_id: &str) -> Result<(), DomainError> {
+        self.repository.copy_properties(from_resource_id, to_resource_id).await
+    }
+
+    async fn remove_properties(&self, resource_id: &str) -> Result<(), DomainError> {
+        self.repository.delete_properties(resource_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::errors::ErrorKind;
+    use crate::domain::repositories::dead_property_repository::DeadPropertyRepositoryResult;
+
+    #[derive(Default)]
+    struct InMemoryDeadPropertyRepository {
+        properties: std::sync::Mutex<HashMap<String, Vec<DeadProperty>>>,
+    }
+
+    #[async_trait]
+    impl DeadPropertyRepository for InMemoryDeadPropertyRepository {
+        async fn get_properties(&self, resource_ids: &[String]) -> DeadPropertyRepositoryResult<HashMap<String, Vec<DeadProperty>>> {
+            let properties = self.properties.lock().unwrap();
+            Ok(resource_ids.iter()
+                .filter_map(|id| properties.get(id).filter(|p| !p.is_empty()).map(|p| (id.clone(), p.clone())))
+                .collect())
+        }
+
+        async fn patch_properties(
+            &self,
+            resource_id: &str,
+            set: &[DeadProperty],
+            remove: &[(String, String)],
+        ) -> DeadPropertyRepositoryResult<()> {
+            let mut properties = self.properties.lock().unwrap();
+            let stored = properties.entry(resource_id.to_string()).or_default();
+            for property in set {
+                stored.retain(|p| !p.is(&property.namespace, &property.name));
+                stored.push(property.clone());
+            }
+            stored.retain(|p| !remove.iter().any(|(namespace, name)| p.is(namespace, name)));
+            Ok(())
+        }
+
+        async fn copy_properties(&self, from_resource_id: &str, to_resource_id: &str) -> DeadPropertyRepositoryResult<()> {
+            let mut properties = self.properties.lock().unwrap();
+            let copied = properties.get(from_resource_id).cloned().unwrap_or_default();
+            properties.insert(to_resource_id.to_string(), copied);
+            Ok(())
+        }
+
+        async fn delete_properties(&self, resource_id: &str) -> DeadPropertyRepositoryResult<()> {
+            self.properties.lock().unwrap().remove(resource_id);
+            Ok(())
+        }
+    }
+
+    fn service() -> DeadPropertyService {
+        DeadPropertyService::new(Arc::new(InMemoryDeadPropertyRepository::default()))
+    }
+
+    fn property(name: &str, value: &str) -> DeadProperty {
+        DeadProperty::new("http://example.com/ns", name, Some(value.to_string()))
+    }
+
+    #[tokio::test]
+    async fn test_set_overwrite_and_remove() {
+        let service = service();
+        service.patch_properties("file-1", vec![property("color", "red"), property("tag", "x")], vec![]).await.unwrap();
+        service.patch_properties(
+            "file-1",
+            vec![property("color", "blue")],
+            vec![("http://example.com/ns".to_string(), "tag".to_string())],
+        ).await.unwrap();
+
+        let stored = service.get_properties(&["file-1".to_string(), "file-2".to_string()]).await.unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored["file-1"], vec![property("color", "blue")]);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_patch_stores_nothing() {
+        let service = service();
+        let err = service.patch_properties(
+            "file-1",
+            vec![property("color", "red"), DeadProperty::new("DAV:", "displayname", Some("x".to_string()))],
+            vec![],
+        ).await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::AccessDenied);
+
+        let too_big = property("blob", &"a".repeat(MAX_VALUE_BYTES + 1));
+        let err = service.patch_properties("file-1", vec![too_big], vec![]).await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidInput);
+
+        assert!(service.get_properties(&["file-1".to_string()]).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_copy_and_remove() {
+        let service = service();
+        service.patch_properties("a", vec![property("color", "red")], vec![]).await.unwrap();
+        service.copy_properties("a", "b").await.unwrap();
+        service.remove_properties("a").await.unwrap();
+
+        let stored = service.get_properties(&["a".to_string(), "b".to_string()]).await.unwrap();
+        assert!(!stored.contains_key("a"));
+        assert_eq!(stored["b"], vec![property("color", "red")]);
+    }
+}
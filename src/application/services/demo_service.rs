@@ -0,0 +1,382 @@
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Days, NaiveTime, Utc};
+use tokio::time;
+use uuid::Uuid;
+
+use crate::application::dtos::demo_dto::{DemoInfoDto, DemoResetReportDto};
+use crate::application::dtos::user_dto::RegisterDto;
+use crate::application::ports::auth_ports::UserStoragePort;
+use crate::application::ports::demo_ports::DemoUseCase;
+use crate::application::ports::inbound::FolderUseCase;
+use crate::application::services::auth_application_service::AuthApplicationService;
+use crate::application::services::user_skeleton_service::UserSkeletonService;
+use crate::common::config::DemoConfig;
+use crate::common::errors::{DomainError, ErrorKind};
+use crate::domain::entities::calendar::Calendar;
+use crate::domain::entities::calendar_event::CalendarEvent;
+use crate::domain::entities::contact::{AddressBook, Contact, Email, Phone};
+use crate::domain::entities::user::{AuthSource, User, UserRole};
+use crate::domain::repositories::address_book_repository::AddressBookRepository;
+use crate::domain::repositories::calendar_event_repository::CalendarEventRepository;
+use crate::domain::repositories::calendar_repository::CalendarRepository;
+use crate::domain::repositories::contact_repository::ContactRepository;
+
+/// Grupo de esqueleto con los ficheros de ejemplo de la cuenta de demostración
+pub const DEMO_SKELETON_GROUP: &str = "demo";
+
+/// Eventos de ejemplo: (título, días desde hoy, hora de inicio, minutos, lugar, RRULE).
+/// Sin hora de inicio el evento dura el día entero.
+const SAMPLE_EVENTS: &[(&str, u64, Option<(u32, u32)>, i64, Option<&str>, Option<&str>)] = &[
+    ("Welcome to OxiCloud", 0, Some((9, 0)), 30, None, None),
+    ("Team meeting", 1, Some((10, 0)), 60, Some("Meeting room 2"), Some("FREQ=WEEKLY")),
+    ("Lunch with Ana", 2, Some((13, 0)), 60, Some("Café Central"), None),
+    ("Project deadline", 5, None, 0, None, None),
+];
+
+/// Contactos de ejemplo: (nombre, apellido, correo, teléfono, organización)
+const SAMPLE_CONTACTS: &[(&str, &str, &str, &str, &str)] = &[
+    ("Ana", "García", "ana.garcia@example.com", "+34 600 000 001", "OxiCloud"),
+    ("John", "Smith", "john.smith@example.com", "+44 20 7946 0000", "Example Ltd"),
+    ("Marie", "Dupont", "marie.dupont@example.org", "+33 1 23 45 67 89", "Exemple SA"),
+];
+
+/// VEVENT de ejemplo con fechas relativas al momento de la restauración, para
+/// que el calendario de demostración siempre tenga eventos próximos
+fn sample_event_ical(index: usize, now: DateTime<Utc>) -> Option<String> {
+    let (summary, days, start, minutes, location, rrule) = SAMPLE_EVENTS.get(index)?;
+    let day = now.date_naive().checked_add_days(Days::new(*days))?;
+
+    let mut ical = format!(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//OxiCloud//Demo//EN\r\nBEGIN:VEVENT\r\nUID:demo-{}-{}@oxicloud\r\nDTSTAMP:{}\r\nSUMMARY:{}\r\n",
+        index, Uuid::new_v4().simple(), now.format("%Y%m%dT%H%M%SZ"), summary,
+    );
+    match start {
+        Some((hour, minute)) => {
+            let start = day.and_time(NaiveTime::from_hms_opt(*hour, *minute, 0)?).and_utc();
+            let end = start + chrono::Duration::minutes(*minutes);
+            ical.push_str(&format!("DTSTART:{}\r\nDTEND:{}\r\n", start.format("%Y%m%dT%H%M%SZ"), end.format("%Y%m%dT%H%M%SZ")));
+        }
+        None => {
+            let end = day.checked_add_days(Days::new(1))?;
+            ical.push_str(&format!("DTSTART;VALUE=DATE:{}\r\nDTEND;VALUE=DATE:{}\r\n", day.format("%Y%m%d"), end.format("%Y%m%d")));
+        }
+    }
+    if let Some(location) = location {
+        ical.push_str(&format!("LOCATION:{}\r\n", location));
+    }
+    if let Some(rrule) = rrule {
+        ical.push_str(&format!("RRULE:{}\r\n", rrule));
+    }
+    ical.push_str("END:VEVENT\r\nEND:VCALENDAR\r\n");
+    Some(ical)
+}
+
+/// Contacto de ejemplo con su vCard
+fn sample_contact(address_book_id: Uuid, index: usize) -> Option<Contact> {
+    let (first_name, last_name, email, phone, organization) = SAMPLE_CONTACTS.get(index)?;
+    let full_name = format!("{} {}", first_name, last_name);
+    let uid = format!("demo-{}@oxicloud", Uuid::new_v4());
+    let vcard = format!(
+        "BEGIN:VCARD\r\nVERSION:3.0\r\nUID:{}\r\nFN:{}\r\nN:{};{};;;\r\nEMAIL;TYPE=WORK:{}\r\nTEL;TYPE=CELL:{}\r\nORG:{}\r\nEND:VCARD\r\n",
+        uid, full_name, last_name, first_name, email, phone, organization,
+    );
+
+    Some(Contact {
+        address_book_id,
+        uid,
+        full_name: Some(full_name),
+        first_name: Some(first_name.to_string()),
+        last_name: Some(last_name.to_string()),
+        email: vec![Email { email: email.to_string(), r#type: "work".to_string(), is_primary: true }],
+        phone: vec![Phone { number: phone.to_string(), r#type: "mobile".to_string(), is_primary: true }],
+        organization: Some(organization.to_string()),
+        vcard,
+        ..Default::default()
+    })
+}
+
+/// Comprueba que la cuenta la creó el modo demostración y se puede borrar
+fn ensure_demo_account(user: &User) -> Result<(), DomainError> {
+    if user.role() == UserRole::Admin {
+        return Err(DomainError::access_denied(
+            "Demo",
+            format!("La cuenta '{}' es de administrador y no se restaura", user.username()),
+        ));
+    }
+    if user.auth_source() != AuthSource::Demo {
+        return Err(DomainError::access_denied(
+            "Demo",
+            format!("La cuenta '{}' no la creó el modo demostración y no se restaura", user.username()),
+        ));
+    }
+    Ok(())
+}
+
+/// Estado de las restauraciones para la página de acceso
+#[derive(Default)]
+struct DemoStatus {
+    last_reset_at: Option<DateTime<Utc>>,
+}
+
+/// Servicio del modo demostración.
+///
+/// Mantiene una cuenta de ejemplo con ficheros (el esqueleto `demo`), un
+/// calendario y una libreta de direcciones. Cada restauración borra la
+/// cuenta con todos sus datos y la vuelve a crear desde cero, de modo que lo
+/// que hagan los visitantes dura como mucho hasta la siguiente restauración.
+///
+/// La cuenta queda marcada al crearla (origen `demo`) y la restauración solo
+/// borra cuentas con esa marca: una cuenta real con el nombre configurado, y
+/// en particular una de administrador, nunca se toca. No se ejecutan dos
+/// restauraciones a la vez.
+pub struct DemoService {
+    config: DemoConfig,
+    auth_service: Arc<AuthApplicationService>,
+    user_storage: Arc<dyn UserStoragePort>,
+    folder_service: Arc<dyn FolderUseCase>,
+    skeleton_service: Arc<UserSkeletonService>,
+    calendar_repository: Arc<dyn CalendarRepository>,
+    event_repository: Arc<dyn CalendarEventRepository>,
+    address_book_repository: Arc<dyn AddressBookRepository>,
+    contact_repository: Arc<dyn ContactRepository>,
+    reset_lock: tokio::sync::Mutex<()>,
+    status: RwLock<DemoStatus>,
+}
+
+impl DemoService {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        config: DemoConfig,
+        auth_service: Arc<AuthApplicationService>,
+        user_storage: Arc<dyn UserStoragePort>,
+        folder_service: Arc<dyn FolderUseCase>,
+        skeleton_service: Arc<UserSkeletonService>,
+        calendar_repository: Arc<dyn CalendarRepository>,
+        event_repository: Arc<dyn CalendarEventRepository>,
+        address_book_repository: Arc<dyn AddressBookRepository>,
+        contact_repository: Arc<dyn ContactRepository>,
+    ) -> Self {
+        Self {
+            config,
+            auth_service,
+            user_storage,
+            folder_service,
+            skeleton_service,
+            calendar_repository,
+            event_repository,
+            address_book_repository,
+            contact_repository,
+            reset_lock: tokio::sync::Mutex::new(()),
+            status: RwLock::new(DemoStatus::default()),
+        }
+    }
+
+    /// Restaura la cuenta al arrancar y después periódicamente; con intervalo
+    /// 0 solo se restaura al arrancar y a petición
+    pub fn start_reset_job(self: Arc<Self>) {
+        let interval_minutes = self.config.reset_interval_minutes;
+        if interval_minutes == 0 {
+            tracing::info!("Modo demostración: restauración solo al arrancar y bajo petición");
+        } else {
+            tracing::info!("Modo demostración: restauración cada {} minutos", interval_minutes);
+        }
+
+        tokio::spawn(async move {
+            if interval_minutes == 0 {
+                if let Err(e) = self.reset().await {
+                    tracing::error!("Error al preparar la cuenta de demostración: {}", e);
+                }
+                return;
+            }
+            // El primer tick es inmediato, así que la cuenta queda lista al arrancar
+            let mut interval = time::interval(Duration::from_secs(interval_minutes * 60));
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.reset().await {
+                    tracing::error!("Error en la restauración programada de la cuenta de demostración: {}", e);
+                }
+            }
+        });
+    }
+
+    fn home_folder_name(&self) -> String {
+        format!("Mi Carpeta - {}", self.config.username)
+    }
+
+    /// Borra la cuenta de demostración con sus ficheros, calendarios y contactos
+    async fn remove_demo_account(&self) -> Result<(), DomainError> {
+        match self.user_storage.get_user_by_username(&self.config.username).await {
+            Ok(user) => {
+                ensure_demo_account(&user)?;
+                for calendar in self.calendar_repository.list_calendars_by_owner(user.id()).await? {
+                    self.calendar_repository.delete_calendar(calendar.id()).await?;
+                }
+                for address_book in self.address_book_repository.get_address_books_by_owner(user.id()).await? {
+                    self.address_book_repository.delete_address_book(&address_book.id).await?;
+                }
+                self.user_storage.delete_user(user.id()).await?;
+            }
+            Err(e) if e.kind == ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+
+        // La carpeta personal puede quedar aunque ya no exista el usuario
+        let home_folder_name = self.home_folder_name();
+        for folder in self.folder_service.list_folders(None).await? {
+            if folder.name == home_folder_name {
+                self.folder_service.delete_folder(&folder.id).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Crea el calendario de ejemplo; devuelve cuántos eventos tiene
+    async fn seed_calendar(&self, user_id: &str, now: DateTime<Utc>) -> Result<usize, DomainError> {
+        let calendar = Calendar::new(
+            "Demo".to_string(),
+            user_id.to_string(),
+            Some("Sample events of the demo account".to_string()),
+            Some("#3b82f6".to_string()),
+        )?;
+        let calendar = self.calendar_repository.create_calendar(calendar).await?;
+
+        let events = (0..SAMPLE_EVENTS.len())
+            .filter_map(|index| sample_event_ical(index, now))
+            .map(|ical| CalendarEvent::from_ical(*calendar.id(), ical))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(self.event_repository.create_events(events).await?.len())
+    }
+
+    /// Crea la libreta de direcciones de ejemplo; devuelve cuántos contactos tiene
+    async fn seed_contacts(&self, user_id: &str) -> Result<usize, DomainError> {
+        let address_book = self.address_book_repository.create_address_book(AddressBook {
+            name: "Demo contacts".to_string(),
+            owner_id: user_id.to_string(),
+            description: Some("Sample contacts of the demo account".to_string()),
+            ..Default::default()
+        }).await?;
+
+        let mut created = 0;
+        for contact in (0..SAMPLE_CONTACTS.len()).filter_map(|index| sample_contact(address_book.id, index)) {
+            self.contact_repository.create_contact(contact).await?;
+            created += 1;
+        }
+        Ok(created)
+    }
+}
+
+#[async_trait]
+impl DemoUseCase for DemoService {
+    async fn reset(&self) -> Result<DemoResetReportDto, DomainError> {
+        let Ok(_guard) = self.reset_lock.try_lock() else {
+            return Err(DomainError::new(ErrorKind::Locked, "Demo", "A reset of the demo account is already running"));
+        };
+        let started_at = Utc::now();
+
+        self.remove_demo_account().await?;
+
+        // El alta crea la carpeta personal con el esqueleto de su rol
+        let user = self.auth_service.register(RegisterDto {
+            username: self.config.username.clone(),
+            email: self.config.email.clone(),
+            password: self.config.password.clone(),
+            role: Some("user".to_string()),
+        }).await?;
+        // La marca permite borrar la cuenta en la siguiente restauración
+        let mut account = self.user_storage.get_user_by_id(&user.id).await?;
+        account.set_auth_source(AuthSource::Demo);
+        self.user_storage.update_user(account).await?;
+
+        let home_folder_name = self.home_folder_name();
+        let home_folder = self.folder_service.list_folders(None).await?
+            .into_iter()
+            .find(|folder| folder.name == home_folder_name)
+            .ok_or_else(|| DomainError::not_found("Folder", home_folder_name))?;
+        let skeleton = self.skeleton_service.apply_named_skeleton(&home_folder.id, DEMO_SKELETON_GROUP).await?;
+
+        let events_created = self.seed_calendar(&user.id, started_at).await?;
+        let contacts_created = self.seed_contacts(&user.id).await?;
+
+        let report = DemoResetReportDto {
+            user_id: user.id,
+            started_at,
+            finished_at: Utc::now(),
+            folders_created: skeleton.folders_created,
+            files_created: skeleton.files_created,
+            calendars_created: 1,
+            events_created,
+            address_books_created: 1,
+            contacts_created,
+        };
+        self.status.write().unwrap().last_reset_at = Some(report.finished_at);
+
+        tracing::info!(
+            "Cuenta de demostración '{}' restaurada: {} carpetas, {} ficheros, {} eventos, {} contactos",
+            self.config.username, report.folders_created, report.files_created, report.events_created, report.contacts_created
+        );
+        Ok(report)
+    }
+
+    async fn info(&self) -> DemoInfoDto {
+        let last_reset_at = self.status.read().unwrap().last_reset_at;
+        let interval = self.config.reset_interval_minutes;
+        DemoInfoDto {
+            username: self.config.username.clone(),
+            password: self.config.password.clone(),
+            reset_interval_minutes: interval,
+            last_reset_at,
+            next_reset_at: last_reset_at
+                .filter(|_| interval > 0)
+                .map(|last| last + chrono::Duration::minutes(interval as i64)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_events_are_valid_and_upcoming() {
+        let now = Utc::now();
+        let calendar_id = Uuid::new_v4();
+        for index in 0..SAMPLE_EVENTS.len() {
+            let ical = sample_event_ical(index, now).unwrap();
+            let event = CalendarEvent::from_ical(calendar_id, ical).unwrap();
+            assert_eq!(event.summary(), SAMPLE_EVENTS[index].0);
+            assert!(event.start_time().date_naive() >= now.date_naive());
+        }
+        assert!(sample_event_ical(SAMPLE_EVENTS.len(), now).is_none());
+    }
+
+    #[test]
+    fn test_sample_contacts_carry_their_vcard() {
+        let address_book_id = Uuid::new_v4();
+        let contact = sample_contact(address_book_id, 0).unwrap();
+        assert_eq!(contact.address_book_id, address_book_id);
+        assert_eq!(contact.full_name.as_deref(), Some("Ana García"));
+        assert!(contact.vcard.contains(&format!("UID:{}\r\n", contact.uid)));
+        assert!(contact.vcard.contains("EMAIL;TYPE=WORK:ana.garcia@example.com\r\n"));
+    }
+
+    #[test]
+    fn test_only_marked_accounts_are_removed() {
+        let mut user = User::new(
+            "demo".to_string(),
+            "demo@oxicloud.local".to_string(),
+            "Password123!".to_string(),
+            UserRole::User,
+            0,
+        ).unwrap();
+        assert_eq!(ensure_demo_account(&user).unwrap_err().kind, ErrorKind::AccessDenied);
+
+        user.set_auth_source(AuthSource::Demo);
+        assert!(ensure_demo_account(&user).is_ok());
+
+        user.update_role(UserRole::Admin);
+        assert_eq!(ensure_demo_account(&user).unwrap_err().kind, ErrorKind::AccessDenied);
+    }
+}
@@ -0,0 +1,153 @@
+use std::sync::Arc;
+
+use crate::application::ports::directory_ports::{DirectoryAuthPort, DirectoryUser};
+use crate::common::errors::DomainError;
+use crate::domain::entities::user::UserRole;
+
+/// Datos de la cuenta local que corresponde a un usuario del directorio
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectoryAccount {
+    pub username: String,
+    pub email: String,
+    pub role: UserRole,
+}
+
+/// Servicio de autenticación contra un directorio externo (LDAP, Active
+/// Directory).
+///
+/// Comprueba la contraseña en el directorio y traduce la cuenta a los datos
+/// de su usuario local: nombre, correo y rol según la pertenencia al grupo de
+/// administradores. Si hay un grupo requerido, las cuentas que no estén en él
+/// no pueden entrar aunque la contraseña sea correcta.
+pub struct DirectoryAuthService {
+    directory: Arc<dyn DirectoryAuthPort>,
+    admin_group: Option<String>,
+    required_group: Option<String>,
+    allow_local_users: bool,
+}
+
+impl DirectoryAuthService {
+    pub fn new(
+        directory: Arc<dyn DirectoryAuthPort>,
+        admin_group: Option<String>,
+        required_group: Option<String>,
+        allow_local_users: bool,
+    ) -> Self {
+        Self { directory, admin_group, required_group, allow_local_users }
+    }
+
+    /// Si las cuentas que no están en el directorio pueden entrar con su contraseña local
+    pub fn allows_local_users(&self) -> bool {
+        self.allow_local_users
+    }
+
+    /// Autentica en el directorio. `None` si el directorio no conoce al usuario.
+    pub async fn authenticate(&self, username: &str, password: &str) -> Result<Option<DirectoryAccount>, DomainError> {
+        let Some(user) = self.directory.authenticate(username, password).await? else {
+            return Ok(None);
+        };
+
+        if let Some(required) = &self.required_group {
+            if !in_group(&user.groups, required) {
+                tracing::warn!("La cuenta {} no pertenece al grupo {}", user.dn, required);
+                return Err(DomainError::access_denied("Auth", "La cuenta no tiene acceso a este servidor"));
+            }
+        }
+
+        Ok(Some(self.account(user)))
+    }
+
+    fn account(&self, user: DirectoryUser) -> DirectoryAccount {
+        let role = match &self.admin_group {
+            Some(group) if in_group(&user.groups, group) => UserRole::Admin,
+            _ => UserRole::User,
+        };
+        // Sin correo en el directorio se usa una dirección que nunca se entregará
+        let email = user.email
+            .filter(|email| email.contains('@'))
+            .unwrap_or_else(|| format!("{}@ldap.invalid", user.username));
+
+        DirectoryAccount { username: user.username, email, role }
+    }
+}
+
+/// Comprueba la pertenencia a un grupo dado por su DN completo o por su nombre
+fn in_group(groups: &[String], wanted: &str) -> bool {
+    groups.iter().any(|group| group.eq_ignore_ascii_case(wanted) || group_name(group).eq_ignore_ascii_case(wanted))
+}
+
+/// Nombre de un grupo: el valor del primer RDN de su DN (`cn=admins,dc=...` → `admins`)
+fn group_name(dn: &str) -> &str {
+    let first = dn.split(',').next().unwrap_or(dn);
+    first.split_once('=').map(|(_, name)| name.trim()).unwrap_or(first)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    struct StaticDirectory(Option<DirectoryUser>);
+
+    #[async_trait]
+    impl DirectoryAuthPort for StaticDirectory {
+        async fn authenticate(&self, _username: &str, _password: &str) -> Result<Option<DirectoryUser>, DomainError> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn directory_user(groups: &[&str]) -> DirectoryUser {
+        DirectoryUser {
+            dn: "uid=ana,ou=people,dc=example,dc=org".to_string(),
+            username: "ana".to_string(),
+            email: None,
+            groups: groups.iter().map(|group| group.to_string()).collect(),
+        }
+    }
+
+    fn service(user: Option<DirectoryUser>, required_group: Option<&str>) -> DirectoryAuthService {
+        DirectoryAuthService::new(
+            Arc::new(StaticDirectory(user)),
+            Some("Admins".to_string()),
+            required_group.map(str::to_string),
+            true,
+        )
+    }
+
+    #[test]
+    fn test_group_matching() {
+        let groups = vec!["cn=Admins,ou=groups,dc=example,dc=org".to_string(), "staff".to_string()];
+        assert!(in_group(&groups, "admins"));
+        assert!(in_group(&groups, "CN=admins,OU=groups,DC=example,DC=org"));
+        assert!(in_group(&groups, "staff"));
+        assert!(!in_group(&groups, "example"));
+    }
+
+    #[tokio::test]
+    async fn test_account_mapping() {
+        let account = service(Some(directory_user(&["cn=admins,dc=example,dc=org"])), None)
+            .authenticate("ana", "secret").await.unwrap().unwrap();
+        assert_eq!(account, DirectoryAccount {
+            username: "ana".to_string(),
+            email: "ana@ldap.invalid".to_string(),
+            role: UserRole::Admin,
+        });
+
+        let account = service(Some(directory_user(&[])), None)
+            .authenticate("ana", "secret").await.unwrap().unwrap();
+        assert_eq!(account.role, UserRole::User);
+
+        assert!(service(None, None).authenticate("ana", "secret").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_required_group() {
+        let result = service(Some(directory_user(&["cn=staff,dc=example,dc=org"])), Some("cloud-users"))
+            .authenticate("ana", "secret").await;
+        assert!(result.is_err());
+
+        let result = service(Some(directory_user(&["cn=cloud-users,dc=example,dc=org"])), Some("cloud-users"))
+            .authenticate("ana", "secret").await;
+        assert!(result.unwrap().is_some());
+    }
+}
@@ -0,0 +1,388 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::application::dtos::activity_dto::ActivityTargetDto;
+use crate::application::ports::activity_ports::ActivityUseCase;
+use crate::application::ports::auth_ports::UserStoragePort;
+use crate::application::ports::event_bus_ports::{DomainEvent, DomainEventSubscriber};
+use crate::application::ports::inbound::SearchUseCase;
+use crate::application::ports::realtime_ports::{RealtimeAudience, RealtimeEvent, RealtimeEventPort};
+use crate::common::errors::{DomainError, ErrorKind};
+use crate::domain::entities::activity::{ActivityAction, ActivityTargetKind};
+use crate::domain::entities::user::User;
+use crate::domain::services::ownership_service::owner_of_path;
+
+/// Mantiene al día las búsquedas: un fichero nuevo invalida los resultados
+/// guardados en caché, que no lo incluirían
+pub struct SearchIndexSubscriber {
+    search: Arc<dyn SearchUseCase>,
+}
+
+impl SearchIndexSubscriber {
+    pub fn new(search: Arc<dyn SearchUseCase>) -> Self {
+        Self { search }
+    }
+}
+
+#[async_trait]
+impl DomainEventSubscriber for SearchIndexSubscriber {
+    fn name(&self) -> &'static str {
+        "search_index"
+    }
+
+    async fn handle(&self, event: &DomainEvent) -> Result<(), DomainError> {
+        match event {
+            DomainEvent::FileUploaded { .. } | DomainEvent::FileUpdated { .. } | DomainEvent::FileDeleted { .. } => {
+                self.search.clear_search_cache().await
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Avisa al momento a los clientes conectados por `/ws` del dueño de lo que
+/// cambia
+pub struct RealtimeNotificationSubscriber {
+    realtime_events: Arc<dyn RealtimeEventPort>,
+}
+
+impl RealtimeNotificationSubscriber {
+    pub fn new(realtime_events: Arc<dyn RealtimeEventPort>) -> Self {
+        Self { realtime_events }
+    }
+}
+
+#[async_trait]
+impl DomainEventSubscriber for RealtimeNotificationSubscriber {
+    fn name(&self) -> &'static str {
+        "realtime_notifications"
+    }
+
+    async fn handle(&self, event: &DomainEvent) -> Result<(), DomainError> {
+        let (audience, realtime_event) = match event {
+            DomainEvent::FileUploaded { file_id, name, folder_id, path, .. } => {
+                // Los ficheros fuera de una carpeta personal no tienen a quién avisar
+                let Some(owner) = owner_of_path(path) else {
+                    return Ok(());
+                };
+                (RealtimeAudience::Username(owner.to_string()), RealtimeEvent::FileCreated {
+                    file_id: file_id.clone(),
+                    name: name.clone(),
+                    folder_id: folder_id.clone(),
+                })
+            }
+            DomainEvent::FileUpdated { file_id, name, folder_id, path, .. } => {
+                let Some(owner) = owner_of_path(path) else {
+                    return Ok(());
+                };
+                (RealtimeAudience::Username(owner.to_string()), RealtimeEvent::FileUpdated {
+                    file_id: file_id.clone(),
+                    name: name.clone(),
+                    folder_id: folder_id.clone(),
+                })
+            }
+            DomainEvent::FileDeleted { file_id, folder_id, path, .. } => {
+                let Some(owner) = owner_of_path(path) else {
+                    return Ok(());
+                };
+                (RealtimeAudience::Username(owner.to_string()), RealtimeEvent::FileDeleted {
+                    file_id: file_id.clone(),
+                    folder_id: folder_id.clone(),
+                })
+            }
+            // Los destinatarios de un enlace ya reciben su aviso de las
+            // notificaciones, y las cuentas nuevas no tienen clientes conectados.
+            // Los cambios de carpetas se ven al volver a listar
+            DomainEvent::ShareCreated { .. }
+            | DomainEvent::UserCreated { .. }
+            | DomainEvent::FolderRenamed { .. }
+            | DomainEvent::FolderDeleted { .. }
+            | DomainEvent::ItemRestored { .. } => return Ok(()),
+            DomainEvent::ContactDeleted { contact_id, address_book_id, owner_id, .. } => {
+                (RealtimeAudience::User(owner_id.clone()), RealtimeEvent::ContactDeleted {
+                    address_book_id: address_book_id.clone(),
+                    contact_id: contact_id.clone(),
+                })
+            }
+            DomainEvent::CalendarEventUpdated { event_id, calendar_id, ical_uid, owner_id } => {
+                (RealtimeAudience::User(owner_id.clone()), RealtimeEvent::CalendarEventChanged {
+                    calendar_id: calendar_id.clone(),
+                    event_id: event_id.clone(),
+                    ical_uid: ical_uid.clone(),
+                })
+            }
+        };
+        self.realtime_events.publish(audience, realtime_event);
+        Ok(())
+    }
+}
+
+/// Escribe en la actividad de los usuarios lo que pasa con sus archivos y
+/// carpetas, llegue por la API, WebDAV, subidas por partes u operaciones en
+/// lote.
+///
+/// Los eventos de ficheros y carpetas no dicen quién actuó, así que se
+/// atribuyen al dueño de la carpeta personal que los contiene; los enlaces, a
+/// quien los creó.
+pub struct ActivityFeedSubscriber {
+    activity: Arc<dyn ActivityUseCase>,
+    users: Arc<dyn UserStoragePort>,
+}
+
+impl ActivityFeedSubscriber {
+    pub fn new(activity: Arc<dyn ActivityUseCase>, users: Arc<dyn UserStoragePort>) -> Self {
+        Self { activity, users }
+    }
+
+    /// Dueño de una ruta; `None` fuera de las carpetas personales o si la
+    /// cuenta ya no existe
+    async fn owner(&self, path: &str) -> Result<Option<User>, DomainError> {
+        let Some(username) = owner_of_path(path) else {
+            return Ok(None);
+        };
+        match self.users.get_user_by_username(username).await {
+            Ok(user) => Ok(Some(user)),
+            Err(e) if e.kind == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn record_for_owner(
+        &self,
+        path: &str,
+        action: ActivityAction,
+        target: ActivityTargetDto,
+        details: Option<String>,
+    ) -> Result<(), DomainError> {
+        if let Some(owner) = self.owner(path).await? {
+            self.activity.record(owner.id(), owner.username(), action, target, details).await;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DomainEventSubscriber for ActivityFeedSubscriber {
+    fn name(&self) -> &'static str {
+        "activity_feed"
+    }
+
+    async fn handle(&self, event: &DomainEvent) -> Result<(), DomainError> {
+        let file = |file_id: &String, name: &String, folder_id: &Option<String>| ActivityTargetDto {
+            kind: ActivityTargetKind::File,
+            id: file_id.clone(),
+            name: name.clone(),
+            folder_id: folder_id.clone(),
+        };
+        match event {
+            DomainEvent::FileUploaded { file_id, name, folder_id, path, .. } => {
+                self.record_for_owner(path, ActivityAction::Upload, file(file_id, name, folder_id), None).await
+            }
+            DomainEvent::FileUpdated { file_id, name, folder_id, path, .. } => {
+                self.record_for_owner(path, ActivityAction::Update, file(file_id, name, folder_id), None).await
+            }
+            DomainEvent::FileDeleted { file_id, name, folder_id, path } => {
+                self.record_for_owner(path, ActivityAction::Delete, file(file_id, name, folder_id), None).await
+            }
+            DomainEvent::FolderRenamed { folder_id, name, previous_name, parent_id, path } => {
+                let target = ActivityTargetDto {
+                    kind: ActivityTargetKind::Folder,
+                    id: folder_id.clone(),
+                    name: name.clone(),
+                    folder_id: parent_id.clone(),
+                };
+                // El nombre anterior es lo que explica un renombrado
+                self.record_for_owner(path, ActivityAction::Rename, target, Some(previous_name.clone())).await
+            }
+            DomainEvent::FolderDeleted { folder_id, name, parent_id, path } => {
+                let target = ActivityTargetDto {
+                    kind: ActivityTargetKind::Folder,
+                    id: folder_id.clone(),
+                    name: name.clone(),
+                    folder_id: parent_id.clone(),
+                };
+                self.record_for_owner(path, ActivityAction::Delete, target, None).await
+            }
+            DomainEvent::ItemRestored { item_id, item_type, name, path } => {
+                let Some(kind) = ActivityTargetKind::parse(item_type) else {
+                    return Ok(());
+                };
+                // Ya recuperado, el elemento dice en qué carpeta quedó
+                let target = match self.activity.describe_target(kind, item_id).await {
+                    Some(target) => target,
+                    None => ActivityTargetDto { kind, id: item_id.clone(), name: name.clone(), folder_id: None },
+                };
+                self.record_for_owner(path, ActivityAction::Restore, target, None).await
+            }
+            DomainEvent::ShareCreated { item_id, item_type, created_by, .. } => {
+                let Some(kind) = ActivityTargetKind::parse(item_type) else {
+                    return Ok(());
+                };
+                let Some(target) = self.activity.describe_target(kind, item_id).await else {
+                    return Ok(());
+                };
+                let creator = self.users.get_user_by_id(created_by).await?;
+                self.activity.record(creator.id(), creator.username(), ActivityAction::Share, target, None).await;
+                Ok(())
+            }
+            DomainEvent::UserCreated { .. } | DomainEvent::ContactDeleted { .. } | DomainEvent::CalendarEventUpdated { .. } => Ok(()),
+        }
+    }
+}
+
+/// Deja constancia de cada evento en el log, con el destino `audit` para
+/// poder separarlo del resto
+pub struct AuditLogSubscriber;
+
+#[async_trait]
+impl DomainEventSubscriber for AuditLogSubscriber {
+    fn name(&self) -> &'static str {
+        "audit_log"
+    }
+
+    async fn handle(&self, event: &DomainEvent) -> Result<(), DomainError> {
+        match event {
+            DomainEvent::FileUploaded { file_id, path, size, .. } => {
+                tracing::info!(target: "audit", "Fichero subido: {} ({}, {} bytes)", path, file_id, size);
+            }
+            DomainEvent::FileUpdated { file_id, path, size, .. } => {
+                tracing::info!(target: "audit", "Fichero modificado: {} ({}, {} bytes)", path, file_id, size);
+            }
+            DomainEvent::FileDeleted { file_id, path, .. } => {
+                tracing::info!(target: "audit", "Fichero borrado: {} ({})", path, file_id);
+            }
+            DomainEvent::FolderRenamed { folder_id, path, previous_name, .. } => {
+                tracing::info!(target: "audit", "Carpeta renombrada: {} ({}), antes {}", path, folder_id, previous_name);
+            }
+            DomainEvent::FolderDeleted { folder_id, path, .. } => {
+                tracing::info!(target: "audit", "Carpeta borrada: {} ({})", path, folder_id);
+            }
+            DomainEvent::ItemRestored { item_id, item_type, path, .. } => {
+                tracing::info!(target: "audit", "Recuperado de la papelera: {} {} ({})", item_type, path, item_id);
+            }
+            DomainEvent::ShareCreated { share_id, item_type, item_id, created_by } => {
+                tracing::info!(target: "audit", "Enlace {} creado por {} para {} {}", share_id, created_by, item_type, item_id);
+            }
+            DomainEvent::UserCreated { user_id, username, role, .. } => {
+                tracing::info!(target: "audit", "Usuario {} ({}) creado con el rol {}", username, user_id, role);
+            }
+            DomainEvent::ContactDeleted { contact_id, address_book_id, deleted_by, .. } => {
+                tracing::info!(target: "audit", "Contacto {} borrado de la libreta {} por {}", contact_id, address_book_id, deleted_by);
+            }
+            DomainEvent::CalendarEventUpdated { event_id, calendar_id, .. } => {
+                tracing::info!(target: "audit", "Evento {} actualizado en el calendario {}", event_id, calendar_id);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use crate::application::dtos::activity_dto::{ActivityDto, ActivityQueryDto};
+    use crate::application::dtos::pagination::PaginatedResponseDto;
+    use crate::application::services::realtime_event_service::RealtimeEventService;
+    use crate::application::services::test_users::MemoryUsers;
+
+    /// Actividad que solo apunta lo registrado: (usuario, acción, elemento, detalle)
+    #[derive(Default)]
+    struct RecordedActivity(Mutex<Vec<(String, ActivityAction, String, Option<String>)>>);
+
+    #[async_trait]
+    impl ActivityUseCase for RecordedActivity {
+        async fn describe_target(&self, kind: ActivityTargetKind, id: &str) -> Option<ActivityTargetDto> {
+            Some(ActivityTargetDto { kind, id: id.to_string(), name: format!("name of {}", id), folder_id: None })
+        }
+
+        async fn record(&self, _actor_id: &str, actor_name: &str, action: ActivityAction, target: ActivityTargetDto, details: Option<String>) {
+            self.0.lock().unwrap().push((actor_name.to_string(), action, target.name, details));
+        }
+
+        async fn list_user_activity(&self, _user_id: &str, _query: ActivityQueryDto) -> Result<PaginatedResponseDto<ActivityDto>, DomainError> {
+            unimplemented!()
+        }
+
+        async fn list_folder_activity(
+            &self,
+            _username: &str,
+            _is_admin: bool,
+            _folder_id: &str,
+            _query: ActivityQueryDto,
+        ) -> Result<PaginatedResponseDto<ActivityDto>, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_activity_is_recorded_for_every_change() {
+        let users = Arc::new(MemoryUsers::with_usernames(&["ana", "bob"]));
+        let activity = Arc::new(RecordedActivity::default());
+        let subscriber = ActivityFeedSubscriber::new(activity.clone(), users.clone());
+
+        let events = [
+            // Subida por WebDAV o por partes: solo llega el evento
+            DomainEvent::FileUploaded {
+                file_id: "f1".to_string(),
+                name: "informe.pdf".to_string(),
+                folder_id: None,
+                path: "Mi Carpeta - ana/informe.pdf".to_string(),
+                size: 10,
+            },
+            DomainEvent::FolderRenamed {
+                folder_id: "d1".to_string(),
+                name: "fotos".to_string(),
+                previous_name: "imagenes".to_string(),
+                parent_id: None,
+                path: "Mi Carpeta - ana/fotos".to_string(),
+            },
+            // Fuera de las carpetas personales no hay a quién atribuirlo
+            DomainEvent::FileDeleted {
+                file_id: "f2".to_string(),
+                name: "suelto.txt".to_string(),
+                folder_id: None,
+                path: "suelto.txt".to_string(),
+            },
+            DomainEvent::ShareCreated {
+                share_id: "s1".to_string(),
+                item_id: "f3".to_string(),
+                item_type: "file".to_string(),
+                created_by: users.id_of("bob"),
+            },
+        ];
+        for event in &events {
+            subscriber.handle(event).await.unwrap();
+        }
+
+        assert_eq!(*activity.0.lock().unwrap(), vec![
+            ("ana".to_string(), ActivityAction::Upload, "informe.pdf".to_string(), None),
+            ("ana".to_string(), ActivityAction::Rename, "fotos".to_string(), Some("imagenes".to_string())),
+            ("bob".to_string(), ActivityAction::Share, "name of f3".to_string(), None),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_uploads_reach_the_owner_of_the_home_folder() {
+        let realtime_events = Arc::new(RealtimeEventService::new());
+        let mut received = realtime_events.subscribe();
+        let subscriber = RealtimeNotificationSubscriber::new(realtime_events.clone());
+
+        subscriber.handle(&DomainEvent::FileUploaded {
+            file_id: "f1".to_string(),
+            name: "informe.pdf".to_string(),
+            folder_id: Some("d1".to_string()),
+            path: "Mi Carpeta - ana/trabajo/informe.pdf".to_string(),
+            size: 10,
+        }).await.unwrap();
+
+        let message = received.recv().await.unwrap();
+        assert!(message.audience.includes("id-ana", "ana"));
+        assert_eq!(message.event, RealtimeEvent::FileCreated {
+            file_id: "f1".to_string(),
+            name: "informe.pdf".to_string(),
+            folder_id: Some("d1".to_string()),
+        });
+    }
+}
@@ -0,0 +1,164 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::application::dtos::file_dto::FileDto;
+use crate::application::dtos::photo_dto::{SimilarPhotoDto, SimilarPhotoGroupDto, MAX_SIMILARITY_DISTANCE};
+use crate::application::ports::image_fingerprint_ports::{DuplicatePhotoUseCase, ImageFingerprint, ImageFingerprintPort};
+use crate::application::ports::inbound::FileUseCase;
+use crate::common::errors::{DomainError, ErrorKind};
+
+/// Servicio que agrupa fotos duplicadas o visualmente similares de un usuario
+pub struct DuplicatePhotoService {
+    fingerprint_service: Arc<dyn ImageFingerprintPort>,
+    file_service: Arc<dyn FileUseCase>,
+}
+
+impl DuplicatePhotoService {
+    /// Crea un nuevo servicio de búsqueda de fotos similares
+    pub fn new(fingerprint_service: Arc<dyn ImageFingerprintPort>, file_service: Arc<dyn FileUseCase>) -> Self {
+        Self { fingerprint_service, file_service }
+    }
+}
+
+/// Agrupa las huellas cuya distancia es como máximo `max_distance`.
+///
+/// Usa enlace simple (union-find): dos fotos acaban en el mismo grupo si
+/// existe una cadena de fotos similares entre ellas. Solo se devuelven los
+/// grupos con más de un elemento.
+fn group_similar(fingerprints: &[ImageFingerprint], max_distance: u32) -> Vec<Vec<usize>> {
+    fn find(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    let mut parent: Vec<usize> = (0..fingerprints.len()).collect();
+    for i in 0..fingerprints.len() {
+        for j in i + 1..fingerprints.len() {
+            if fingerprints[i].distance(&fingerprints[j]) <= max_distance {
+                let (a, b) = (find(&mut parent, i), find(&mut parent, j));
+                if a != b {
+                    parent[b] = a;
+                }
+            }
+        }
+    }
+
+    let mut groups: std::collections::BTreeMap<usize, Vec<usize>> = std::collections::BTreeMap::new();
+    for i in 0..fingerprints.len() {
+        let root = find(&mut parent, i);
+        groups.entry(root).or_default().push(i);
+    }
+    groups.into_values().filter(|g| g.len() > 1).collect()
+}
+
+fn hex_color(color: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+#[async_trait]
+impl DuplicatePhotoUseCase for DuplicatePhotoService {
+    async fn find_similar_photos(
+        &self,
+        username: &str,
+        max_distance: u32,
+    ) -> Result<Vec<SimilarPhotoGroupDto>, DomainError> {
+        let max_distance = max_distance.min(MAX_SIMILARITY_DISTANCE);
+        let home_prefix = format!("Mi Carpeta - {}", username);
+
+        // Keep only photos that still exist inside the user's home folder
+        let mut photos: Vec<(FileDto, ImageFingerprint)> = Vec::new();
+        for (file_id, fingerprint) in self.fingerprint_service.list_fingerprints().await? {
+            match self.file_service.get_file(&file_id).await {
+                Ok(file) => {
+                    let path = file.path.trim_start_matches('/');
+                    if path == home_prefix || path.starts_with(&format!("{}/", home_prefix)) {
+                        photos.push((file, fingerprint));
+                    }
+                },
+                Err(e) if e.kind == ErrorKind::NotFound => {
+                    // Stale entry left by a file removed outside the file service
+                    if let Err(e) = self.fingerprint_service.remove_fingerprint(&file_id).await {
+                        tracing::warn!("Could not drop stale fingerprint {}: {}", file_id, e);
+                    }
+                },
+                Err(e) => tracing::warn!("Could not resolve fingerprinted file {}: {}", file_id, e),
+            }
+        }
+
+        let fingerprints: Vec<ImageFingerprint> = photos.iter().map(|(_, f)| *f).collect();
+        let mut groups: Vec<SimilarPhotoGroupDto> = group_similar(&fingerprints, max_distance)
+            .into_iter()
+            .map(|mut members| {
+                // The largest copy is the one worth keeping
+                members.sort_by(|a, b| photos[*b].0.size.cmp(&photos[*a].0.size));
+                let reference = photos[members[0]].1;
+
+                let photos: Vec<SimilarPhotoDto> = members
+                    .iter()
+                    .map(|&i| {
+                        let (file, fingerprint) = &photos[i];
+                        SimilarPhotoDto {
+                            id: file.id.clone(),
+                            name: file.name.clone(),
+                            path: file.path.clone(),
+                            size: file.size,
+                            modified_at: file.modified_at,
+                            dominant_color: hex_color(fingerprint.dominant_color),
+                            distance: reference.distance(fingerprint),
+                        }
+                    })
+                    .collect();
+
+                SimilarPhotoGroupDto {
+                    max_distance: photos.iter().map(|p| p.distance).max().unwrap_or(0),
+                    reclaimable_bytes: photos.iter().skip(1).map(|p| p.size).sum(),
+                    photos,
+                }
+            })
+            .collect();
+
+        groups.sort_by(|a, b| b.reclaimable_bytes.cmp(&a.reclaimable_bytes));
+        Ok(groups)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fingerprint(hash: u64) -> ImageFingerprint {
+        ImageFingerprint { perceptual_hash: hash, dominant_color: [0, 0, 0] }
+    }
+
+    #[test]
+    fn test_group_similar() {
+        let fingerprints = vec![
+            fingerprint(0b0000),
+            fingerprint(u64::MAX),
+            fingerprint(0b0011),
+            fingerprint(u64::MAX ^ 1),
+            fingerprint(0x00FF_00FF_00FF_00FF),
+        ];
+
+        let groups = group_similar(&fingerprints, 2);
+        assert_eq!(groups, vec![vec![0, 2], vec![1, 3]]);
+
+        // Only identical hashes with a zero threshold
+        assert!(group_similar(&fingerprints, 0).is_empty());
+    }
+
+    #[test]
+    fn test_group_similar_chains_neighbours() {
+        let fingerprints = vec![fingerprint(0b000), fingerprint(0b001), fingerprint(0b011)];
+        assert_eq!(group_similar(&fingerprints, 1), vec![vec![0, 1, 2]]);
+    }
+
+    #[test]
+    fn test_hex_color() {
+        assert_eq!(hex_color([255, 8, 0]), "#ff0800");
+    }
+}
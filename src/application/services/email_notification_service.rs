@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration as StdDuration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use tokio::sync::mpsc;
+
+use crate::application::dtos::notification_preferences_dto::{EmailPreferencesDto, UpdateEmailPreferencesDto};
+use crate::application::ports::auth_ports::UserStoragePort;
+use crate::application::ports::email_notification_ports::{EmailEvent, EmailNotificationPort, NotificationPreferencesUseCase};
+use crate::application::ports::mail_ports::{MailSenderPort, OutgoingMail};
+use crate::common::errors::DomainError;
+use crate::domain::entities::notification_preferences::{EmailEventKind, NotificationPreferences};
+use crate::domain::entities::share_notification::SharedItemKind;
+use crate::domain::repositories::notification_preferences_repository::NotificationPreferencesRepository;
+use crate::domain::services::email_template_service::{format_size, EmailTemplate};
+use crate::domain::services::template_placeholder_service::TemplateValues;
+
+/// Correos pendientes que caben en la cola; si se llena, los nuevos se descartan
+const QUEUE_CAPACITY: usize = 1000;
+
+/// Intentos de entrega de cada correo
+const MAX_DELIVERY_ATTEMPTS: u32 = 3;
+
+/// Espera antes del primer reintento; se duplica en cada uno
+const RETRY_DELAY: StdDuration = StdDuration::from_secs(30);
+
+/// Tiempo mínimo entre dos avisos de descarga del mismo enlace
+const DOWNLOAD_NOTICE_INTERVAL_SECS: i64 = 3600;
+
+/// Tiempo mínimo entre dos avisos de cuota al mismo usuario
+const QUOTA_NOTICE_INTERVAL_SECS: i64 = 86400;
+
+/// Todos los sucesos, para cargar sus plantillas
+const EVENT_KINDS: [EmailEventKind; 3] = [
+    EmailEventKind::ShareReceived,
+    EmailEventKind::ShareDownloaded,
+    EmailEventKind::QuotaWarning,
+];
+
+/// Aviso pendiente de enviar
+struct QueuedEmail {
+    user_id: String,
+    event: EmailEvent,
+}
+
+/// Servicio de avisos por correo a los usuarios.
+///
+/// Los demás servicios encolan los sucesos sin esperar y un trabajador los
+/// envía de uno en uno: comprueba las preferencias del usuario, rellena la
+/// plantilla del suceso y reintenta la entrega si el servidor SMTP falla.
+/// Para no inundar el buzón, las descargas de un mismo enlace se avisan como
+/// mucho una vez por hora y la cuota casi llena una vez al día. La cola vive
+/// en memoria: lo que quede pendiente al reiniciar se pierde.
+pub struct EmailNotificationService {
+    mail_sender: Arc<dyn MailSenderPort>,
+    user_storage: Arc<dyn UserStoragePort>,
+    preferences: Arc<dyn NotificationPreferencesRepository>,
+    /// URL pública del servidor para los enlaces de los correos
+    base_url: String,
+    templates: HashMap<EmailEventKind, EmailTemplate>,
+    queue: mpsc::Sender<QueuedEmail>,
+    /// Cola de avisos, hasta que la recoge `start_worker`
+    receiver: Mutex<Option<mpsc::Receiver<QueuedEmail>>>,
+    // Clave: suceso y usuario o enlace avisado; valor: último aviso
+    last_notices: Mutex<HashMap<String, DateTime<Utc>>>,
+}
+
+impl EmailNotificationService {
+    pub fn new(
+        mail_sender: Arc<dyn MailSenderPort>,
+        user_storage: Arc<dyn UserStoragePort>,
+        preferences: Arc<dyn NotificationPreferencesRepository>,
+        base_url: String,
+    ) -> Self {
+        let (queue, receiver) = mpsc::channel(QUEUE_CAPACITY);
+        Self {
+            mail_sender,
+            user_storage,
+            preferences,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            templates: EVENT_KINDS.iter().map(|kind| (*kind, EmailTemplate::builtin(*kind))).collect(),
+            queue,
+            receiver: Mutex::new(Some(receiver)),
+            last_notices: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sustituye las plantillas incluidas por las de la carpeta
+    /// (`<suceso>.txt`); las que falten o no se puedan leer se conservan
+    pub fn with_templates_dir(mut self, dir: &Path) -> Self {
+        for kind in EVENT_KINDS {
+            let path = dir.join(format!("{}.txt", kind.as_str()));
+            let Ok(text) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            match EmailTemplate::parse(&text) {
+                Some(template) => {
+                    tracing::info!("Plantilla de correo {} cargada de {}", kind.as_str(), path.display());
+                    self.templates.insert(kind, template);
+                }
+                None => tracing::warn!("La plantilla {} no empieza por \"Subject:\"; se usa la incluida", path.display()),
+            }
+        }
+        self
+    }
+
+    /// Arranca el trabajador que envía los correos encolados
+    pub fn start_worker(self: Arc<Self>) {
+        let Some(mut receiver) = self.receiver.lock().ok().and_then(|mut receiver| receiver.take()) else {
+            return;
+        };
+        tokio::spawn(async move {
+            while let Some(email) = receiver.recv().await {
+                if let Err(e) = self.deliver(&email).await {
+                    tracing::warn!("No se pudo enviar el aviso {} a {}: {}", email.event.kind().as_str(), email.user_id, e);
+                }
+            }
+        });
+    }
+
+    /// Clave de limitación del suceso, si se limita
+    fn throttle_key(user_id: &str, event: &EmailEvent) -> Option<(String, i64)> {
+        match event {
+            EmailEvent::ShareReceived { .. } => None,
+            EmailEvent::ShareDownloaded { share_id, .. } => {
+                Some((format!("share_downloaded:{}", share_id), DOWNLOAD_NOTICE_INTERVAL_SECS))
+            }
+            EmailEvent::QuotaWarning { .. } => {
+                Some((format!("quota_warning:{}", user_id), QUOTA_NOTICE_INTERVAL_SECS))
+            }
+        }
+    }
+
+    /// Indica si el suceso se puede avisar ya y, si es así, anota el aviso
+    fn take_slot(&self, user_id: &str, event: &EmailEvent) -> bool {
+        let Some((key, interval_secs)) = Self::throttle_key(user_id, event) else {
+            return true;
+        };
+        let Ok(mut last_notices) = self.last_notices.lock() else {
+            return true;
+        };
+        let now = Utc::now();
+        let interval = Duration::seconds(interval_secs);
+        last_notices.retain(|_, sent_at| now - *sent_at < Duration::seconds(QUOTA_NOTICE_INTERVAL_SECS));
+        if last_notices.get(&key).is_some_and(|sent_at| now - *sent_at < interval) {
+            return false;
+        }
+        last_notices.insert(key, now);
+        true
+    }
+
+    async fn load_preferences(&self, user_id: &str) -> Result<NotificationPreferences, DomainError> {
+        Ok(self.preferences.get_preferences(user_id).await?
+            .unwrap_or_else(|| NotificationPreferences::defaults(user_id)))
+    }
+
+    /// Rellena la plantilla del suceso para un usuario
+    fn render(&self, username: &str, event: &EmailEvent) -> (String, String) {
+        let values = TemplateValues::new()
+            .with("username", username)
+            .with("link", self.base_url.as_str());
+        let values = match event {
+            EmailEvent::ShareReceived { item_kind, item_name, actor_name } => values
+                .with("item_kind", match item_kind {
+                    SharedItemKind::File => "file",
+                    SharedItemKind::Folder => "folder",
+                    SharedItemKind::Calendar => "calendar",
+                    SharedItemKind::AddressBook => "address book",
+                })
+                .with("item_name", item_name.as_str())
+                .with("actor", actor_name.as_deref().unwrap_or("Someone")),
+            EmailEvent::ShareDownloaded { item_name, download_count, .. } => values
+                .with("item_name", item_name.as_str())
+                .with("download_count", download_count.to_string()),
+            EmailEvent::QuotaWarning { used_bytes, quota_bytes } => values
+                .with("used", format_size(*used_bytes))
+                .with("quota", format_size(*quota_bytes))
+                .with("percent", (used_bytes.saturating_mul(100) / (*quota_bytes).max(1)).to_string()),
+        };
+
+        let kind = event.kind();
+        match self.templates.get(&kind) {
+            Some(template) => template.render(&values),
+            None => EmailTemplate::builtin(kind).render(&values),
+        }
+    }
+
+    /// Envía un aviso si el usuario lo quiere, reintentando los fallos de entrega
+    async fn deliver(&self, email: &QueuedEmail) -> Result<(), DomainError> {
+        let kind = email.event.kind();
+        if !self.load_preferences(&email.user_id).await?.allows(kind) {
+            return Ok(());
+        }
+        let user = self.user_storage.get_user_by_id(&email.user_id).await?;
+        if !user.is_active() || user.email().trim().is_empty() {
+            return Ok(());
+        }
+
+        let (subject, body) = self.render(user.username(), &email.event);
+        let mail = OutgoingMail { to: user.email().to_string(), subject, body };
+        let mut delay = RETRY_DELAY;
+        let mut attempt = 1;
+        loop {
+            match self.mail_sender.send(mail.clone()).await {
+                Ok(()) => {
+                    tracing::debug!("Aviso {} enviado a {}", kind.as_str(), user.username());
+                    return Ok(());
+                }
+                Err(e) if attempt < MAX_DELIVERY_ATTEMPTS => {
+                    tracing::warn!("Fallo enviando el aviso {} a {} (intento {}): {}", kind.as_str(), user.username(), attempt, e);
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl EmailNotificationPort for EmailNotificationService {
+    fn notify(&self, user_id: &str, event: EmailEvent) {
+        if !self.take_slot(user_id, &event) {
+            return;
+        }
+        let kind = event.kind();
+        if let Err(e) = self.queue.try_send(QueuedEmail { user_id: user_id.to_string(), event }) {
+            tracing::warn!("Se descarta el aviso {} a {}: {}", kind.as_str(), user_id, e);
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationPreferencesUseCase for EmailNotificationService {
+    async fn get_preferences(&self, user_id: &str) -> Result<EmailPreferencesDto, DomainError> {
+        Ok(self.load_preferences(user_id).await?.into())
+    }
+
+    async fn update_preferences(&self, user_id: &str, update: UpdateEmailPreferencesDto) -> Result<EmailPreferencesDto, DomainError> {
+        let mut preferences = self.load_preferences(user_id).await?;
+        if let Some(value) = update.share_received {
+            preferences.share_received = value;
+        }
+        if let Some(value) = update.share_downloaded {
+            preferences.share_downloaded = value;
+        }
+        if let Some(value) = update.quota_warning {
+            preferences.quota_warning = value;
+        }
+        preferences.updated_at = Utc::now();
+        self.preferences.save_preferences(&preferences).await?;
+        Ok(preferences.into())
+    }
+}
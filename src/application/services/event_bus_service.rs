@@ -0,0 +1,128 @@
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::mpsc;
+
+use crate::application::ports::event_bus_ports::{DomainEvent, DomainEventSubscriber, EventBusPort};
+
+/// Eventos pendientes que caben en la cola; si se llena, los nuevos se descartan
+const QUEUE_CAPACITY: usize = 10_000;
+
+/// Bus de eventos de dominio dentro del proceso.
+///
+/// Los servicios publican lo que hacen sin conocer a quien le interesa y un
+/// único repartidor entrega cada evento a todos los suscriptores, de uno en
+/// uno y en el orden en que se publicaron. Un suscriptor que falla no afecta
+/// a los demás ni a quien publicó. La cola vive en memoria: lo que quede
+/// pendiente al reiniciar se pierde.
+pub struct InProcessEventBus {
+    queue: mpsc::Sender<DomainEvent>,
+    /// Cola de eventos, hasta que la recoge `start_dispatcher`
+    receiver: Mutex<Option<mpsc::Receiver<DomainEvent>>>,
+}
+
+impl InProcessEventBus {
+    pub fn new() -> Self {
+        let (queue, receiver) = mpsc::channel(QUEUE_CAPACITY);
+        Self {
+            queue,
+            receiver: Mutex::new(Some(receiver)),
+        }
+    }
+
+    /// Arranca el repartidor. Los eventos publicados antes esperan en la
+    /// cola, así que los servicios pueden publicar aunque los suscriptores se
+    /// creen más tarde
+    pub fn start_dispatcher(&self, subscribers: Vec<Arc<dyn DomainEventSubscriber>>) {
+        let Some(mut receiver) = self.receiver.lock().ok().and_then(|mut receiver| receiver.take()) else {
+            return;
+        };
+        tokio::spawn(async move {
+            while let Some(event) = receiver.recv().await {
+                dispatch(&subscribers, &event).await;
+            }
+        });
+    }
+}
+
+impl Default for InProcessEventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Entrega un evento a todos los suscriptores
+async fn dispatch(subscribers: &[Arc<dyn DomainEventSubscriber>], event: &DomainEvent) {
+    for subscriber in subscribers {
+        if let Err(e) = subscriber.handle(event).await {
+            tracing::warn!("El suscriptor {} falló con el evento {}: {}", subscriber.name(), event.name(), e);
+        }
+    }
+}
+
+impl EventBusPort for InProcessEventBus {
+    fn publish(&self, event: DomainEvent) {
+        let name = event.name();
+        if let Err(e) = self.queue.try_send(event) {
+            tracing::warn!("Se descarta el evento {}: {}", name, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use crate::common::errors::DomainError;
+
+    struct Recorder {
+        events: mpsc::UnboundedSender<DomainEvent>,
+    }
+
+    #[async_trait]
+    impl DomainEventSubscriber for Recorder {
+        fn name(&self) -> &'static str {
+            "recorder"
+        }
+
+        async fn handle(&self, event: &DomainEvent) -> Result<(), DomainError> {
+            let _ = self.events.send(event.clone());
+            Ok(())
+        }
+    }
+
+    struct Failing;
+
+    #[async_trait]
+    impl DomainEventSubscriber for Failing {
+        fn name(&self) -> &'static str {
+            "failing"
+        }
+
+        async fn handle(&self, _event: &DomainEvent) -> Result<(), DomainError> {
+            Err(DomainError::internal_error("Test", "always fails"))
+        }
+    }
+
+    fn uploaded(file_id: &str) -> DomainEvent {
+        DomainEvent::FileUploaded {
+            file_id: file_id.to_string(),
+            name: "informe.pdf".to_string(),
+            folder_id: None,
+            path: "Mi Carpeta - ana/informe.pdf".to_string(),
+            size: 10,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_events_published_before_start_reach_every_subscriber_in_order() {
+        let bus = InProcessEventBus::new();
+        bus.publish(uploaded("f1"));
+        bus.publish(uploaded("f2"));
+
+        let (events, mut received) = mpsc::unbounded_channel();
+        bus.start_dispatcher(vec![Arc::new(Failing), Arc::new(Recorder { events })]);
+
+        assert_eq!(received.recv().await, Some(uploaded("f1")));
+        assert_eq!(received.recv().await, Some(uploaded("f2")));
+    }
+}
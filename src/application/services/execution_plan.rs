@@ -0,0 +1,85 @@
+use crate::application::dtos::execution_plan_dto::{ExecutionPlanDto, PlannedActionDto};
+
+/// Pasos que se listan como máximo en un plan; el resto solo se cuenta
+pub const MAX_LISTED_ACTIONS: usize = 1000;
+
+/// Plan de ejecución de una operación destructiva de administración.
+///
+/// La operación anota aquí cada paso justo antes de darlo y solo lo da si
+/// `step` devuelve `true`. En una simulación (`?dry_run=true`) devuelve
+/// siempre `false`: el mismo código que borra decide qué se borraría, así que
+/// el plan no puede desviarse de lo que haría una ejecución real.
+pub struct ExecutionPlan {
+    plan: ExecutionPlanDto,
+}
+
+impl ExecutionPlan {
+    pub fn new(operation: &str, dry_run: bool) -> Self {
+        Self {
+            plan: ExecutionPlanDto {
+                operation: operation.to_string(),
+                dry_run,
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.plan.dry_run
+    }
+
+    /// Anota un paso; devuelve si hay que darlo de verdad
+    pub fn step(&mut self, action: &str, target: impl Into<String>, bytes: Option<u64>) -> bool {
+        *self.plan.counts.entry(action.to_string()).or_default() += 1;
+        self.plan.affected_bytes += bytes.unwrap_or(0);
+        if self.plan.actions.len() < MAX_LISTED_ACTIONS {
+            self.plan.actions.push(PlannedActionDto {
+                action: action.to_string(),
+                target: target.into(),
+                bytes,
+            });
+        } else {
+            self.plan.truncated = true;
+        }
+        !self.plan.dry_run
+    }
+
+    /// Anota un paso que afecta a `count` elementos de una vez, como un
+    /// borrado en bloque; devuelve si hay que darlo de verdad
+    pub fn bulk_step(&mut self, action: &str, target: impl Into<String>, count: u64) -> bool {
+        let execute = self.step(action, target, None);
+        *self.plan.counts.entry(action.to_string()).or_default() += count.saturating_sub(1);
+        execute
+    }
+
+    pub fn finish(self) -> ExecutionPlanDto {
+        self.plan
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dry_run_counts_without_executing() {
+        let mut plan = ExecutionPlan::new("storage_gc", true);
+        assert!(!plan.step("remove_temp_file", "a/.tmpAbC123", Some(10)));
+        for i in 0..MAX_LISTED_ACTIONS {
+            plan.step("remove_placeholder", format!("file-{}", i), None);
+        }
+
+        let plan = plan.finish();
+        assert!(plan.dry_run);
+        assert_eq!(plan.counts["remove_temp_file"], 1);
+        assert_eq!(plan.counts["remove_placeholder"], MAX_LISTED_ACTIONS as u64);
+        assert_eq!(plan.affected_bytes, 10);
+        assert_eq!(plan.actions.len(), MAX_LISTED_ACTIONS);
+        assert!(plan.truncated);
+
+        let mut plan = ExecutionPlan::new("integrity_repair", false);
+        assert!(plan.step("delete_user", "ana", None));
+        assert!(plan.bulk_step("remove_group_memberships", "orphaned memberships", 5));
+        assert_eq!(plan.finish().counts["remove_group_memberships"], 5);
+    }
+}
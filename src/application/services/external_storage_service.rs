@@ -0,0 +1,948 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::Arc;
+use async_trait::async_trait;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::application::dtos::external_storage_dto::{
+    ExternalChangeDto, ExternalEntryDto, ExternalMountDto, ExternalMoveDto, ExternalMoveRequestDto,
+    ExternalTransferJobDto,
+};
+use crate::application::ports::external_storage_ports::{
+    ExternalByteStream, ExternalEntry, ExternalMountUser, ExternalStoragePort, ExternalStorageUseCase,
+};
+use crate::application::services::user_group_service::UserGroupService;
+use crate::common::errors::{DomainError, ErrorKind, Result};
+
+/// Who is using mounts through the folder tree in the current request
+#[derive(Debug, Clone)]
+pub enum ExternalMountCaller {
+    /// A signed-in user, limited to the mounts allowed to them
+    User(ExternalMountUser),
+    /// A public share link, already validated by the share service, which
+    /// only ever reaches the shared item
+    SharedLink,
+}
+
+tokio::task_local! {
+    static MOUNT_CALLER: ExternalMountCaller;
+}
+
+/// Runs `future` with `caller` as the user of the mounts shown in the folder
+/// tree. Outside such a scope mounts are neither listed nor reachable.
+pub async fn with_mount_caller<F: std::future::Future>(caller: ExternalMountCaller, future: F) -> F::Output {
+    MOUNT_CALLER.scope(caller, future).await
+}
+
+/// Caller set by the enclosing `with_mount_caller`, if any
+pub fn current_mount_caller() -> Option<ExternalMountCaller> {
+    MOUNT_CALLER.try_with(Clone::clone).ok()
+}
+
+/// Transfer jobs shared with the background tasks that run them
+type TransferJobs = Arc<RwLock<HashMap<String, ExternalTransferJobDto>>>;
+
+/// Listing snapshot used for change detection: path -> (is_dir, size, modified_at)
+type MountSnapshot = HashMap<String, (bool, u64, u64)>;
+
+/// Change detection state kept for each mount
+#[derive(Default)]
+struct MountState {
+    snapshot: Option<MountSnapshot>,
+    changes: VecDeque<ExternalChangeDto>,
+    status: Option<&'static str>,
+    last_error: Option<String>,
+    last_checked_at: Option<u64>,
+}
+
+/// Users and groups allowed on a mount; empty means every signed-in user
+#[derive(Debug, Clone, Default)]
+struct MountAccess {
+    users: Vec<String>,
+    groups: Vec<String>,
+}
+
+/// Service exposing external storage backends (SFTP, SMB, ...) as named mounts
+pub struct ExternalStorageService {
+    mounts: BTreeMap<String, Arc<dyn ExternalStoragePort>>,
+    access: HashMap<String, MountAccess>,
+    user_groups: Option<Arc<UserGroupService>>,
+    state: RwLock<HashMap<String, MountState>>,
+    transfers: TransferJobs,
+    scan_depth: usize,
+    max_tracked_changes: usize,
+}
+
+impl ExternalStorageService {
+    /// Creates a service without mounts
+    pub fn new() -> Self {
+        Self {
+            mounts: BTreeMap::new(),
+            access: HashMap::new(),
+            user_groups: None,
+            state: RwLock::new(HashMap::new()),
+            transfers: Arc::new(RwLock::new(HashMap::new())),
+            scan_depth: 3,
+            max_tracked_changes: 500,
+        }
+    }
+
+    /// Configures how deep change detection scans and how many changes are kept
+    pub fn with_change_tracking(mut self, scan_depth: usize, max_tracked_changes: usize) -> Self {
+        self.scan_depth = scan_depth.max(1);
+        self.max_tracked_changes = max_tracked_changes.max(1);
+        self
+    }
+
+    /// Registers a backend under the given mount name
+    pub fn with_mount(mut self, name: &str, backend: Arc<dyn ExternalStoragePort>) -> Self {
+        info!("Registering external {} mount '{}'", backend.backend(), name);
+        self.mounts.insert(name.to_string(), backend);
+        self
+    }
+
+    /// Restricts a mount to the given usernames and group ids
+    pub fn with_mount_access(mut self, name: &str, users: Vec<String>, groups: Vec<String>) -> Self {
+        self.access.insert(name.to_string(), MountAccess { users, groups });
+        self
+    }
+
+    /// Resolves group membership for mounts restricted to groups
+    pub fn with_groups(mut self, user_groups: Arc<UserGroupService>) -> Self {
+        self.user_groups = Some(user_groups);
+        self
+    }
+
+    /// Number of registered mounts
+    pub fn mount_count(&self) -> usize {
+        self.mounts.len()
+    }
+
+    /// Names of the registered mounts
+    pub fn mount_names(&self) -> Vec<String> {
+        self.mounts.keys().cloned().collect()
+    }
+
+    /// Whether a mount with the given name is registered
+    pub fn has_mount(&self, name: &str) -> bool {
+        self.mounts.contains_key(name)
+    }
+
+    /// Lists a mount recursively up to the configured depth and records the
+    /// differences with the previous scan. The first scan only establishes a
+    /// baseline. Returns the number of changes detected.
+    pub async fn scan_for_changes(&self, name: &str) -> Result<usize> {
+        let backend = self.get_mount(name)?;
+        let now = chrono::Utc::now().timestamp().max(0) as u64;
+
+        let result = self.take_snapshot(backend.as_ref()).await;
+
+        let mut state = self.state.write().await;
+        let mount_state = state.entry(name.to_string()).or_default();
+        mount_state.last_checked_at = Some(now);
+
+        let snapshot = match result {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                let status = match e.kind {
+                    ErrorKind::Unavailable | ErrorKind::Timeout => "unreachable",
+                    _ => "error",
+                };
+                if mount_state.status != Some(status) {
+                    warn!("External mount '{}' is {}: {}", name, status, e);
+                }
+                mount_state.status = Some(status);
+                mount_state.last_error = Some(e.message.clone());
+                return Err(e);
+            }
+        };
+
+        if mount_state.status.is_some_and(|s| s != "online") {
+            info!("External mount '{}' is reachable again", name);
+        }
+        mount_state.status = Some("online");
+        mount_state.last_error = None;
+
+        let detected = match mount_state.snapshot.as_ref() {
+            Some(previous) => diff_snapshots(previous, &snapshot),
+            None => Vec::new(),
+        };
+        let count = detected.len();
+
+        for (path, change, is_dir) in detected {
+            mount_state.changes.push_back(ExternalChangeDto {
+                mount: name.to_string(),
+                path,
+                change: change.to_string(),
+                is_dir,
+                detected_at: now,
+            });
+        }
+        while mount_state.changes.len() > self.max_tracked_changes {
+            mount_state.changes.pop_front();
+        }
+        mount_state.snapshot = Some(snapshot);
+
+        Ok(count)
+    }
+
+    async fn take_snapshot(&self, backend: &dyn ExternalStoragePort) -> Result<MountSnapshot> {
+        let mut snapshot = MountSnapshot::new();
+        let mut pending = vec![(String::new(), 1usize)];
+
+        while let Some((dir, depth)) = pending.pop() {
+            for entry in backend.list(&dir).await? {
+                if entry.is_dir && depth < self.scan_depth {
+                    pending.push((entry.path.clone(), depth + 1));
+                }
+                snapshot.insert(entry.path, (entry.is_dir, entry.size, entry.modified_at));
+            }
+        }
+
+        Ok(snapshot)
+    }
+
+    fn get_mount(&self, name: &str) -> Result<&Arc<dyn ExternalStoragePort>> {
+        self.mounts
+            .get(name)
+            .ok_or_else(|| DomainError::not_found("ExternalMount", name))
+    }
+
+    /// Whether the user may use a mount. Administrators always can; a mount
+    /// without allowed users or groups is open to every signed-in user.
+    pub async fn can_access(&self, user: &ExternalMountUser, name: &str) -> Result<bool> {
+        if !self.mounts.contains_key(name) {
+            return Ok(false);
+        }
+        let access = match self.access.get(name) {
+            Some(access) if !user.is_admin => access,
+            _ => return Ok(true),
+        };
+        if access.users.is_empty() && access.groups.is_empty() {
+            return Ok(true);
+        }
+        if access.users.iter().any(|allowed| allowed == &user.username) {
+            return Ok(true);
+        }
+        match &self.user_groups {
+            Some(groups) if !access.groups.is_empty() => groups.is_member_of_any(&user.id, &access.groups).await,
+            _ => Ok(false),
+        }
+    }
+
+    /// Backend of a mount the user may use. Mounts the user may not use are
+    /// reported as not found so their names do not leak. Per-user mounts
+    /// only show the user's own data, administrators included.
+    pub async fn mount_for(&self, user: &ExternalMountUser, name: &str, write: bool) -> Result<Arc<dyn ExternalStoragePort>> {
+        if !self.can_access(user, name).await? {
+            return Err(DomainError::not_found("ExternalMount", name));
+        }
+        let mount = if write { self.get_writable_mount(name)? } else { self.get_mount(name)? };
+        if mount.is_per_user() {
+            return mount.for_user(&user.username);
+        }
+        Ok(mount.clone())
+    }
+
+    /// Backend of a mount for the caller of the folder tree
+    pub async fn mount_for_caller(&self, caller: &ExternalMountCaller, name: &str, write: bool) -> Result<Arc<dyn ExternalStoragePort>> {
+        match caller {
+            ExternalMountCaller::User(user) => self.mount_for(user, name, write).await,
+            ExternalMountCaller::SharedLink => {
+                let mount = if write { self.get_writable_mount(name)? } else { self.get_mount(name)? };
+                // The paths of a per-user mount only make sense for its user
+                if mount.is_per_user() {
+                    return Err(DomainError::not_found("ExternalMount", name));
+                }
+                Ok(mount.clone())
+            }
+        }
+    }
+
+    /// Names of the mounts the user may use and whether each is read-only
+    pub async fn accessible_mounts(&self, user: &ExternalMountUser) -> Result<Vec<(String, bool)>> {
+        let mut mounts = Vec::new();
+        for (name, backend) in &self.mounts {
+            if self.can_access(user, name).await? {
+                mounts.push((name.clone(), backend.is_read_only()));
+            }
+        }
+        Ok(mounts)
+    }
+
+    fn get_writable_mount(&self, name: &str) -> Result<&Arc<dyn ExternalStoragePort>> {
+        let mount = self.get_mount(name)?;
+        if mount.is_read_only() {
+            return Err(DomainError::access_denied(
+                "ExternalMount",
+                format!("Mount '{}' is read-only", name),
+            ));
+        }
+        Ok(mount)
+    }
+
+    /// Normalizes a user supplied path relative to the mount root.
+    ///
+    /// Empty and `.` segments are dropped; `..` segments and control
+    /// characters are rejected so a path can never escape the mount.
+    pub fn normalize_path(path: &str) -> Result<String> {
+        let mut segments = Vec::new();
+        for segment in path.split(['/', '\\']) {
+            match segment {
+                "" | "." => continue,
+                ".." => {
+                    return Err(DomainError::new(
+                        ErrorKind::InvalidInput,
+                        "ExternalMount",
+                        "Path must not contain '..' segments",
+                    ))
+                }
+                s if s.chars().any(|c| c.is_control()) => {
+                    return Err(DomainError::new(
+                        ErrorKind::InvalidInput,
+                        "ExternalMount",
+                        "Path contains invalid characters",
+                    ))
+                }
+                s => segments.push(s),
+            }
+        }
+        Ok(segments.join("/"))
+    }
+}
+
+impl Default for ExternalStorageService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ExternalStorageUseCase for ExternalStorageService {
+    async fn list_mounts(&self, user: &ExternalMountUser) -> Vec<ExternalMountDto> {
+        let accessible = match self.accessible_mounts(user).await {
+            Ok(accessible) => accessible,
+            Err(e) => {
+                warn!("Could not resolve the external mounts of {}: {}", user.username, e);
+                Vec::new()
+            }
+        };
+        let state = self.state.read().await;
+        self.mounts
+            .iter()
+            .filter(|(name, _)| accessible.iter().any(|(allowed, _)| allowed == *name))
+            .map(|(name, backend)| {
+                let mount_state = state.get(name);
+                ExternalMountDto {
+                    name: name.clone(),
+                    backend: backend.backend().to_string(),
+                    read_only: backend.is_read_only(),
+                    status: mount_state.and_then(|s| s.status).unwrap_or("unknown").to_string(),
+                    last_error: mount_state.and_then(|s| s.last_error.clone()),
+                    last_checked_at: mount_state.and_then(|s| s.last_checked_at),
+                }
+            })
+            .collect()
+    }
+
+    async fn list_directory(&self, user: &ExternalMountUser, mount: &str, path: &str) -> Result<Vec<ExternalEntryDto>> {
+        let backend = self.mount_for(user, mount, false).await?;
+        let path = Self::normalize_path(path)?;
+
+        let mut entries = backend.list(&path).await?;
+        entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+
+        Ok(entries
+            .into_iter()
+            .map(|entry| ExternalEntryDto::from_entry(mount, entry))
+            .collect())
+    }
+
+    async fn download_file(&self, user: &ExternalMountUser, mount: &str, path: &str) -> Result<(ExternalEntryDto, ExternalByteStream)> {
+        let backend = self.mount_for(user, mount, false).await?;
+        let path = Self::normalize_path(path)?;
+
+        let entry = backend.stat(&path).await?;
+        if entry.is_dir {
+            return Err(DomainError::new(
+                ErrorKind::InvalidInput,
+                "ExternalMount",
+                format!("'{}' is a directory", path),
+            ));
+        }
+
+        let stream = backend.read_stream(&path).await?;
+        Ok((ExternalEntryDto::from_entry(mount, entry), stream))
+    }
+
+    async fn upload_file(&self, user: &ExternalMountUser, mount: &str, path: &str, content: ExternalByteStream) -> Result<ExternalEntryDto> {
+        let backend = self.mount_for(user, mount, true).await?;
+        let path = Self::normalize_path(path)?;
+        if path.is_empty() {
+            return Err(DomainError::new(
+                ErrorKind::InvalidInput,
+                "ExternalMount",
+                "A file path is required",
+            ));
+        }
+
+        let written = backend.write_stream(&path, content).await?;
+        info!("Uploaded {} bytes to {}:{}", written, mount, path);
+
+        let entry = backend.stat(&path).await?;
+        Ok(ExternalEntryDto::from_entry(mount, entry))
+    }
+
+    async fn create_directory(&self, user: &ExternalMountUser, mount: &str, path: &str) -> Result<ExternalEntryDto> {
+        let backend = self.mount_for(user, mount, true).await?;
+        let path = Self::normalize_path(path)?;
+
+        backend.create_dir(&path).await?;
+        let entry = backend.stat(&path).await?;
+        Ok(ExternalEntryDto::from_entry(mount, entry))
+    }
+
+    async fn delete_entry(&self, user: &ExternalMountUser, mount: &str, path: &str) -> Result<()> {
+        let backend = self.mount_for(user, mount, true).await?;
+        let path = Self::normalize_path(path)?;
+        if path.is_empty() {
+            return Err(DomainError::new(
+                ErrorKind::InvalidInput,
+                "ExternalMount",
+                "The mount root cannot be deleted",
+            ));
+        }
+
+        backend.delete(&path).await
+    }
+
+    async fn list_changes(&self, user: &ExternalMountUser, mount: &str, since: Option<u64>) -> Result<Vec<ExternalChangeDto>> {
+        self.mount_for(user, mount, false).await?;
+        // Scans see every user's data; of a per-user mount only the top
+        // level (the snapshots themselves) is reported
+        let per_user = self.get_mount(mount)?.is_per_user();
+        let state = self.state.read().await;
+
+        Ok(state
+            .get(mount)
+            .map(|s| {
+                s.changes
+                    .iter()
+                    .filter(|c| since.is_none_or(|since| c.detected_at > since))
+                    .filter(|c| !per_user || !c.path.contains('/'))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn move_entry(&self, user: &ExternalMountUser, request: ExternalMoveRequestDto) -> Result<ExternalMoveDto> {
+        let to_mount = request.to_mount.unwrap_or_else(|| request.from_mount.clone());
+        let source = self.mount_for(user, &request.from_mount, true).await?;
+        let target = self.mount_for(user, &to_mount, true).await?;
+        let from_path = Self::normalize_path(&request.from_path)?;
+        let to_path = Self::normalize_path(&request.to_path)?;
+        if from_path.is_empty() || to_path.is_empty() {
+            return Err(DomainError::new(
+                ErrorKind::InvalidInput,
+                "ExternalMount",
+                "The mount root cannot be moved",
+            ));
+        }
+
+        let entry = source.stat(&from_path).await?;
+        match target.stat(&to_path).await {
+            Ok(_) => return Err(DomainError::already_exists("ExternalFile", &to_path)),
+            Err(e) if e.kind == ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+
+        // Same backend: only the directory entry changes, no bytes are copied
+        if to_mount == request.from_mount {
+            if entry.is_dir && to_path.starts_with(&format!("{}/", from_path)) {
+                return Err(DomainError::new(
+                    ErrorKind::InvalidInput,
+                    "ExternalMount",
+                    "A directory cannot be moved into itself",
+                ));
+            }
+
+            source.rename(&from_path, &to_path).await?;
+            info!("Moved {}:{} to {} in place", to_mount, from_path, to_path);
+            let moved = source.stat(&to_path).await?;
+            return Ok(ExternalMoveDto {
+                strategy: "metadata".to_string(),
+                entry: Some(ExternalEntryDto::from_entry(&to_mount, moved)),
+                job: None,
+            });
+        }
+
+        let job = ExternalTransferJobDto {
+            id: uuid::Uuid::new_v4().to_string(),
+            from_mount: request.from_mount,
+            from_path,
+            to_mount,
+            to_path,
+            status: "running".to_string(),
+            total_files: 0,
+            transferred_files: 0,
+            transferred_bytes: 0,
+            error: None,
+            started_at: chrono::Utc::now().timestamp().max(0) as u64,
+            finished_at: None,
+            started_by: user.id.clone(),
+        };
+        self.transfers.write().await.insert(job.id.clone(), job.clone());
+
+        info!(
+            "Starting transfer {} from {}:{} to {}:{}",
+            job.id, job.from_mount, job.from_path, job.to_mount, job.to_path
+        );
+        tokio::spawn(run_transfer(source, target, entry, job.clone(), self.transfers.clone()));
+
+        Ok(ExternalMoveDto {
+            strategy: "transfer".to_string(),
+            entry: None,
+            job: Some(job),
+        })
+    }
+
+    async fn list_transfers(&self, user: &ExternalMountUser) -> Vec<ExternalTransferJobDto> {
+        let mut jobs: Vec<ExternalTransferJobDto> = self.transfers.read().await.values()
+            .filter(|job| user.is_admin || job.started_by == user.id)
+            .cloned()
+            .collect();
+        jobs.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        jobs
+    }
+
+    async fn get_transfer(&self, user: &ExternalMountUser, id: &str) -> Result<ExternalTransferJobDto> {
+        self.transfers
+            .read()
+            .await
+            .get(id)
+            .filter(|job| user.is_admin || job.started_by == user.id)
+            .cloned()
+            .ok_or_else(|| DomainError::not_found("TransferJob", id))
+    }
+}
+
+/// Joins a path relative to a mount with a child path
+fn join_path(base: &str, child: &str) -> String {
+    match (base.is_empty(), child.is_empty()) {
+        (true, _) => child.to_string(),
+        (false, true) => base.to_string(),
+        (false, false) => format!("{}/{}", base, child),
+    }
+}
+
+/// Lists everything below the source entry as (relative path, is_dir, size),
+/// parents always before their children
+async fn plan_transfer(
+    source: &dyn ExternalStoragePort,
+    root: &ExternalEntry,
+) -> Result<Vec<(String, bool, u64)>> {
+    if !root.is_dir {
+        return Ok(vec![(String::new(), false, root.size)]);
+    }
+
+    let mut plan = vec![(String::new(), true, 0)];
+    let mut pending = VecDeque::from([String::new()]);
+    while let Some(relative) = pending.pop_front() {
+        let mut entries = source.list(&join_path(&root.path, &relative)).await?;
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        for entry in entries {
+            let child = join_path(&relative, &entry.name);
+            if entry.is_dir {
+                pending.push_back(child.clone());
+            }
+            plan.push((child, entry.is_dir, entry.size));
+        }
+    }
+    Ok(plan)
+}
+
+/// Copies every planned entry and checks the size of each written file,
+/// recording what was created so it can be rolled back
+async fn copy_verified(
+    source: &dyn ExternalStoragePort,
+    target: &dyn ExternalStoragePort,
+    plan: &[(String, bool, u64)],
+    job: &ExternalTransferJobDto,
+    transfers: &TransferJobs,
+    created: &mut Vec<String>,
+) -> Result<()> {
+    for (relative, is_dir, size) in plan {
+        let to = join_path(&job.to_path, relative);
+        if *is_dir {
+            target.create_dir(&to).await?;
+            created.push(to);
+            continue;
+        }
+
+        let content = source.read_stream(&join_path(&job.from_path, relative)).await?;
+        let written = target.write_stream(&to, content).await;
+        created.push(to.clone());
+        let written = written?;
+
+        let copied = target.stat(&to).await?;
+        if written != *size || copied.size != *size {
+            return Err(DomainError::internal_error(
+                "TransferJob",
+                format!("Verification failed for '{}': expected {} bytes, found {}", to, size, copied.size),
+            ));
+        }
+
+        if let Some(state) = transfers.write().await.get_mut(&job.id) {
+            state.transferred_files += 1;
+            state.transferred_bytes += size;
+        }
+    }
+    Ok(())
+}
+
+/// Runs a move between two mounts: copy with verification, then delete the
+/// source. Any failure while copying removes what was already written.
+async fn run_transfer(
+    source: Arc<dyn ExternalStoragePort>,
+    target: Arc<dyn ExternalStoragePort>,
+    root: ExternalEntry,
+    job: ExternalTransferJobDto,
+    transfers: TransferJobs,
+) {
+    let mut created = Vec::new();
+    let result = match plan_transfer(source.as_ref(), &root).await {
+        Ok(plan) => {
+            if let Some(state) = transfers.write().await.get_mut(&job.id) {
+                state.total_files = plan.iter().filter(|(_, is_dir, _)| !is_dir).count() as u64;
+            }
+            copy_verified(source.as_ref(), target.as_ref(), &plan, &job, &transfers, &mut created)
+                .await
+                .map(|_| plan)
+        }
+        Err(e) => Err(e),
+    };
+
+    let (status, error) = match result {
+        Ok(plan) => {
+            // Children come after their parents in the plan, so delete backwards
+            let mut cleanup_error = None;
+            for (relative, _, _) in plan.iter().rev() {
+                let path = join_path(&job.from_path, relative);
+                if let Err(e) = source.delete(&path).await {
+                    cleanup_error = Some(format!("Copied, but the source could not be removed: {}", e));
+                    break;
+                }
+            }
+            match cleanup_error {
+                None => ("completed", None),
+                Some(message) => ("failed", Some(message)),
+            }
+        }
+        Err(e) => {
+            warn!("Transfer {} failed, rolling back {} entries: {}", job.id, created.len(), e);
+            let mut rolled_back = true;
+            for path in created.iter().rev() {
+                if let Err(rollback_error) = target.delete(path).await {
+                    if rollback_error.kind != ErrorKind::NotFound {
+                        warn!("Could not roll back {}:{}: {}", job.to_mount, path, rollback_error);
+                        rolled_back = false;
+                    }
+                }
+            }
+            (if rolled_back { "rolled_back" } else { "failed" }, Some(e.message))
+        }
+    };
+
+    info!("Transfer {} finished: {}", job.id, status);
+    if let Some(state) = transfers.write().await.get_mut(&job.id) {
+        state.status = status.to_string();
+        state.error = error;
+        state.finished_at = Some(chrono::Utc::now().timestamp().max(0) as u64);
+    }
+}
+
+/// Compares two listing snapshots and returns (path, change, is_dir) tuples
+/// sorted by path
+fn diff_snapshots(previous: &MountSnapshot, current: &MountSnapshot) -> Vec<(String, &'static str, bool)> {
+    let mut changes = Vec::new();
+
+    for (path, &(is_dir, size, modified_at)) in current {
+        match previous.get(path) {
+            None => changes.push((path.clone(), "created", is_dir)),
+            Some(&(was_dir, old_size, old_modified)) => {
+                if was_dir != is_dir {
+                    changes.push((path.clone(), "deleted", was_dir));
+                    changes.push((path.clone(), "created", is_dir));
+                } else if !is_dir && (old_size != size || old_modified != modified_at) {
+                    changes.push((path.clone(), "modified", is_dir));
+                }
+            }
+        }
+    }
+
+    for (path, &(was_dir, _, _)) in previous {
+        if !current.contains_key(path) {
+            changes.push((path.clone(), "deleted", was_dir));
+        }
+    }
+
+    changes.sort_by(|a, b| a.0.cmp(&b.0));
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(ExternalStorageService::normalize_path("").unwrap(), "");
+        assert_eq!(ExternalStorageService::normalize_path("/a//b/./c/").unwrap(), "a/b/c");
+        assert_eq!(ExternalStorageService::normalize_path("a\\b").unwrap(), "a/b");
+    }
+
+    #[test]
+    fn test_normalize_path_rejects_traversal() {
+        assert!(ExternalStorageService::normalize_path("a/../../etc").is_err());
+        assert!(ExternalStorageService::normalize_path("a/b\nc").is_err());
+    }
+
+    #[test]
+    fn test_diff_snapshots() {
+        let mut previous = MountSnapshot::new();
+        previous.insert("docs".to_string(), (true, 0, 10));
+        previous.insert("docs/a.txt".to_string(), (false, 5, 10));
+        previous.insert("old.txt".to_string(), (false, 1, 10));
+
+        let mut current = MountSnapshot::new();
+        current.insert("docs".to_string(), (true, 0, 20));
+        current.insert("docs/a.txt".to_string(), (false, 7, 20));
+        current.insert("new.txt".to_string(), (false, 1, 20));
+
+        let changes = diff_snapshots(&previous, &current);
+        assert_eq!(changes, vec![
+            ("docs/a.txt".to_string(), "modified", false),
+            ("new.txt".to_string(), "created", false),
+            ("old.txt".to_string(), "deleted", false),
+        ]);
+    }
+
+    /// In-memory backend: path -> Some(content) for files, None for directories
+    #[derive(Default)]
+    struct MemoryBackend {
+        entries: std::sync::Mutex<BTreeMap<String, Option<Vec<u8>>>>,
+        fail_writes_to: Option<String>,
+    }
+
+    impl MemoryBackend {
+        fn with(entries: &[(&str, Option<&[u8]>)]) -> Self {
+            let backend = Self::default();
+            for (path, content) in entries {
+                backend.entries.lock().unwrap().insert(path.to_string(), content.map(|c| c.to_vec()));
+            }
+            backend
+        }
+
+        fn paths(&self) -> Vec<String> {
+            self.entries.lock().unwrap().keys().cloned().collect()
+        }
+
+        fn entry(path: &str, content: &Option<Vec<u8>>) -> ExternalEntry {
+            ExternalEntry {
+                name: path.rsplit('/').next().unwrap_or_default().to_string(),
+                path: path.to_string(),
+                is_dir: content.is_none(),
+                size: content.as_ref().map(|c| c.len() as u64).unwrap_or(0),
+                modified_at: 0,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ExternalStoragePort for MemoryBackend {
+        fn backend(&self) -> &'static str {
+            "memory"
+        }
+
+        async fn list(&self, path: &str) -> Result<Vec<ExternalEntry>> {
+            let prefix = if path.is_empty() { String::new() } else { format!("{}/", path) };
+            Ok(self.entries.lock().unwrap().iter()
+                .filter(|(p, _)| p.starts_with(&prefix) && !p[prefix.len()..].contains('/'))
+                .map(|(p, c)| Self::entry(p, c))
+                .collect())
+        }
+
+        async fn stat(&self, path: &str) -> Result<ExternalEntry> {
+            self.entries.lock().unwrap().get(path)
+                .map(|c| Self::entry(path, c))
+                .ok_or_else(|| DomainError::not_found("ExternalFile", path))
+        }
+
+        async fn read_stream(&self, path: &str) -> Result<ExternalByteStream> {
+            let content = self.entries.lock().unwrap().get(path).cloned().flatten()
+                .ok_or_else(|| DomainError::not_found("ExternalFile", path))?;
+            Ok(Box::pin(futures::stream::iter(vec![Ok(bytes::Bytes::from(content))])))
+        }
+
+        async fn write_stream(&self, path: &str, mut content: ExternalByteStream) -> Result<u64> {
+            use futures::StreamExt;
+            let mut data = Vec::new();
+            while let Some(chunk) = content.next().await {
+                data.extend_from_slice(&chunk.unwrap());
+            }
+            if self.fail_writes_to.as_deref() == Some(path) {
+                data.truncate(1);
+            }
+            let written = data.len() as u64;
+            self.entries.lock().unwrap().insert(path.to_string(), Some(data));
+            Ok(written)
+        }
+
+        async fn create_dir(&self, path: &str) -> Result<()> {
+            self.entries.lock().unwrap().insert(path.to_string(), None);
+            Ok(())
+        }
+
+        async fn delete(&self, path: &str) -> Result<()> {
+            self.entries.lock().unwrap().remove(path)
+                .map(|_| ())
+                .ok_or_else(|| DomainError::not_found("ExternalFile", path))
+        }
+
+        async fn rename(&self, from: &str, to: &str) -> Result<()> {
+            let mut entries = self.entries.lock().unwrap();
+            let moved: Vec<String> = entries.keys()
+                .filter(|p| *p == from || p.starts_with(&format!("{}/", from)))
+                .cloned()
+                .collect();
+            for path in moved {
+                let content = entries.remove(&path).unwrap();
+                entries.insert(format!("{}{}", to, &path[from.len()..]), content);
+            }
+            Ok(())
+        }
+    }
+
+    fn user(name: &str) -> ExternalMountUser {
+        ExternalMountUser { id: format!("id-{}", name), username: name.to_string(), is_admin: false }
+    }
+
+    fn move_request(from_mount: &str, from_path: &str, to_mount: &str, to_path: &str) -> ExternalMoveRequestDto {
+        ExternalMoveRequestDto {
+            from_mount: from_mount.to_string(),
+            from_path: from_path.to_string(),
+            to_mount: Some(to_mount.to_string()),
+            to_path: to_path.to_string(),
+        }
+    }
+
+    async fn wait_for_transfer(service: &ExternalStorageService, id: &str) -> ExternalTransferJobDto {
+        for _ in 0..100 {
+            let job = service.get_transfer(&user("ana"), id).await.unwrap();
+            if job.status != "running" {
+                return job;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        panic!("transfer {} did not finish", id);
+    }
+
+    #[tokio::test]
+    async fn test_move_within_mount_is_metadata_only() {
+        let backend = Arc::new(MemoryBackend::with(&[("docs", None), ("docs/a.txt", Some(b"abc"))]));
+        let service = ExternalStorageService::new().with_mount("a", backend.clone());
+
+        let result = service.move_entry(&user("ana"), move_request("a", "docs", "a", "archive")).await.unwrap();
+        assert_eq!(result.strategy, "metadata");
+        assert!(result.job.is_none());
+        assert_eq!(backend.paths(), vec!["archive", "archive/a.txt"]);
+    }
+
+    #[tokio::test]
+    async fn test_move_between_mounts_runs_verified_transfer() {
+        let source = Arc::new(MemoryBackend::with(&[
+            ("docs", None),
+            ("docs/a.txt", Some(b"abc")),
+            ("docs/sub", None),
+            ("docs/sub/b.txt", Some(b"defg")),
+        ]));
+        let target = Arc::new(MemoryBackend::default());
+        let service = ExternalStorageService::new()
+            .with_mount("a", source.clone())
+            .with_mount("b", target.clone());
+
+        let result = service.move_entry(&user("ana"), move_request("a", "docs", "b", "copied")).await.unwrap();
+        assert_eq!(result.strategy, "transfer");
+
+        let job = wait_for_transfer(&service, &result.job.unwrap().id).await;
+        assert_eq!(job.status, "completed");
+        assert_eq!((job.total_files, job.transferred_files, job.transferred_bytes), (2, 2, 7));
+        assert!(source.paths().is_empty());
+        assert_eq!(target.paths(), vec!["copied", "copied/a.txt", "copied/sub", "copied/sub/b.txt"]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_transfer_is_rolled_back() {
+        let source = Arc::new(MemoryBackend::with(&[
+            ("docs", None),
+            ("docs/a.txt", Some(b"abc")),
+            ("docs/b.txt", Some(b"defg")),
+        ]));
+        let target = Arc::new(MemoryBackend {
+            fail_writes_to: Some("copied/b.txt".to_string()),
+            ..Default::default()
+        });
+        let service = ExternalStorageService::new()
+            .with_mount("a", source.clone())
+            .with_mount("b", target.clone());
+
+        let result = service.move_entry(&user("ana"), move_request("a", "docs", "b", "copied")).await.unwrap();
+        let job = wait_for_transfer(&service, &result.job.unwrap().id).await;
+
+        assert_eq!(job.status, "rolled_back");
+        assert!(job.error.unwrap().contains("Verification failed"));
+        assert!(target.paths().is_empty());
+        assert_eq!(source.paths().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_mount_is_not_found() {
+        let service = ExternalStorageService::new();
+        let err = service.list_directory(&user("ana"), "missing", "").await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_restricted_mount_is_hidden_from_other_users() {
+        let service = ExternalStorageService::new()
+            .with_mount("open", Arc::new(MemoryBackend::default()))
+            .with_mount("finance", Arc::new(MemoryBackend::with(&[("q1.xlsx", Some(b"1"))])))
+            .with_mount_access("finance", vec!["ana".to_string()], Vec::new());
+
+        let names = |mounts: Vec<ExternalMountDto>| mounts.into_iter().map(|m| m.name).collect::<Vec<_>>();
+        assert_eq!(names(service.list_mounts(&user("ana")).await), vec!["finance", "open"]);
+        assert_eq!(names(service.list_mounts(&user("bob")).await), vec!["open"]);
+
+        let err = service.list_directory(&user("bob"), "finance", "").await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::NotFound);
+
+        let admin = ExternalMountUser { is_admin: true, ..user("root") };
+        assert_eq!(service.list_directory(&admin, "finance", "").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_transfers_are_visible_to_their_owner() {
+        let source = Arc::new(MemoryBackend::with(&[("a.txt", Some(b"abc"))]));
+        let service = ExternalStorageService::new()
+            .with_mount("a", source)
+            .with_mount("b", Arc::new(MemoryBackend::default()));
+
+        let result = service.move_entry(&user("ana"), move_request("a", "a.txt", "b", "a.txt")).await.unwrap();
+        let id = result.job.unwrap().id;
+
+        assert_eq!(service.list_transfers(&user("ana")).await.len(), 1);
+        assert!(service.list_transfers(&user("bob")).await.is_empty());
+        assert_eq!(service.get_transfer(&user("bob"), &id).await.unwrap_err().kind, ErrorKind::NotFound);
+    }
+}
@@ -0,0 +1,303 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use tokio::sync::RwLock;
+
+use crate::application::dtos::feature_flag_dto::{FeatureFlagDto, UpdateFeatureFlagDto};
+use crate::application::ports::feature_flag_ports::{FeatureFlagUseCase, FlagSubject};
+use crate::common::errors::{DomainError, ErrorKind};
+use crate::domain::entities::feature_flag::{FeatureFlag, FlagOverride, FlagScope, MAX_FLAG_KEY_LENGTH};
+use crate::domain::repositories::feature_flag_repository::FeatureFlagRepository;
+
+/// Longitud máxima del grupo o usuario de una excepción (columna VARCHAR(255))
+const MAX_SUBJECT_LENGTH: usize = 255;
+
+/// Flags cargados del repositorio y el momento en que se leyeron
+struct CachedFlags {
+    loaded_at: Instant,
+    flags: Arc<HashMap<String, FeatureFlag>>,
+}
+
+/// Servicio de feature flags.
+///
+/// Los flags viven en el repositorio y se guardan en memoria durante
+/// `cache_ttl`, así que consultarlos en cada petición no cuesta una consulta
+/// a la base de datos. Los cambios hechos desde esta instancia se ven al
+/// momento; los de otras instancias, al caducar la caché.
+pub struct FeatureFlagService {
+    repository: Arc<dyn FeatureFlagRepository>,
+    cache_ttl: Duration,
+    cache: RwLock<Option<CachedFlags>>,
+}
+
+impl FeatureFlagService {
+    pub fn new(repository: Arc<dyn FeatureFlagRepository>, cache_ttl: Duration) -> Self {
+        Self {
+            repository,
+            cache_ttl,
+            cache: RwLock::new(None),
+        }
+    }
+
+    /// Flags vigentes, recargados si la caché ha caducado.
+    ///
+    /// Si el repositorio falla se siguen usando los últimos flags leídos.
+    async fn flags(&self) -> Arc<HashMap<String, FeatureFlag>> {
+        if let Some(cached) = self.cache.read().await.as_ref() {
+            if cached.loaded_at.elapsed() < self.cache_ttl {
+                return cached.flags.clone();
+            }
+        }
+
+        let mut cache = self.cache.write().await;
+        // Otra tarea puede haberlos recargado mientras esperábamos
+        if let Some(cached) = cache.as_ref() {
+            if cached.loaded_at.elapsed() < self.cache_ttl {
+                return cached.flags.clone();
+            }
+        }
+
+        match self.repository.list_flags().await {
+            Ok(flags) => {
+                let flags = Arc::new(flags.into_iter().map(|flag| (flag.key.clone(), flag)).collect());
+                *cache = Some(CachedFlags { loaded_at: Instant::now(), flags: Arc::clone(&flags) });
+                flags
+            },
+            Err(e) => {
+                tracing::warn!("Could not load feature flags, using cached values: {}", e);
+                cache.as_ref().map(|cached| cached.flags.clone()).unwrap_or_default()
+            },
+        }
+    }
+
+    /// Descarta la caché para que la siguiente consulta vea los cambios
+    async fn invalidate(&self) {
+        *self.cache.write().await = None;
+    }
+
+    /// Lee un flag directamente del repositorio, sin pasar por la caché
+    async fn load_flag(&self, key: &str) -> Result<FeatureFlag, DomainError> {
+        self.repository.list_flags().await?
+            .into_iter()
+            .find(|flag| flag.key == key)
+            .ok_or_else(|| DomainError::not_found("FeatureFlag", key))
+    }
+
+    fn validate_key(key: &str) -> Result<(), DomainError> {
+        if !FeatureFlag::is_valid_key(key) {
+            return Err(DomainError::validation_error(format!(
+                "Invalid flag key '{}': use up to {} lowercase letters, digits, '.', '_' or '-'",
+                key, MAX_FLAG_KEY_LENGTH
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl FeatureFlagUseCase for FeatureFlagService {
+    async fn is_enabled(&self, key: &str, subject: &FlagSubject) -> bool {
+        self.flags().await
+            .get(key)
+            .is_some_and(|flag| flag.is_enabled_for(&subject.user_id, &subject.groups))
+    }
+
+    async fn evaluate_all(&self, subject: &FlagSubject) -> BTreeMap<String, bool> {
+        self.flags().await
+            .values()
+            .map(|flag| (flag.key.clone(), flag.is_enabled_for(&subject.user_id, &subject.groups)))
+            .collect()
+    }
+
+    async fn list_flags(&self) -> Result<Vec<FeatureFlagDto>, DomainError> {
+        let flags = self.repository.list_flags().await?;
+        Ok(flags.into_iter().map(FeatureFlagDto::from).collect())
+    }
+
+    async fn upsert_flag(&self, key: &str, update: UpdateFeatureFlagDto) -> Result<FeatureFlagDto, DomainError> {
+        Self::validate_key(key)?;
+        if update.rollout_percentage.is_some_and(|p| p > 100) {
+            return Err(DomainError::validation_error("rollout_percentage must be between 0 and 100"));
+        }
+
+        let mut flag = match self.load_flag(key).await {
+            Ok(flag) => flag,
+            Err(e) if e.kind == ErrorKind::NotFound => FeatureFlag::new(key.to_string(), None),
+            Err(e) => return Err(e),
+        };
+        if let Some(description) = update.description {
+            flag.description = Some(description).filter(|d| !d.trim().is_empty());
+        }
+        if let Some(enabled) = update.enabled {
+            flag.enabled = enabled;
+        }
+        if let Some(rollout_percentage) = update.rollout_percentage {
+            flag.rollout_percentage = rollout_percentage;
+        }
+        flag.updated_at = Utc::now();
+
+        self.repository.save_flag(&flag).await?;
+        self.invalidate().await;
+        tracing::info!(
+            "Feature flag '{}' set to enabled={} rollout={}%",
+            flag.key, flag.enabled, flag.rollout_percentage
+        );
+        Ok(FeatureFlagDto::from(flag))
+    }
+
+    async fn delete_flag(&self, key: &str) -> Result<(), DomainError> {
+        if !self.repository.delete_flag(key).await? {
+            return Err(DomainError::not_found("FeatureFlag", key));
+        }
+        self.invalidate().await;
+        tracing::info!("Feature flag '{}' deleted", key);
+        Ok(())
+    }
+
+    async fn set_override(
+        &self,
+        key: &str,
+        scope: FlagScope,
+        subject: &str,
+        enabled: bool,
+    ) -> Result<FeatureFlagDto, DomainError> {
+        let subject = subject.trim();
+        if subject.is_empty() || subject.len() > MAX_SUBJECT_LENGTH {
+            return Err(DomainError::validation_error(format!("Invalid {} '{}'", scope.as_str(), subject)));
+        }
+        // Las excepciones solo tienen sentido sobre un flag existente
+        self.load_flag(key).await?;
+
+        let flag_override = FlagOverride { scope, subject: subject.to_string(), enabled };
+        self.repository.set_override(key, &flag_override).await?;
+        self.invalidate().await;
+        Ok(FeatureFlagDto::from(self.load_flag(key).await?))
+    }
+
+    async fn remove_override(&self, key: &str, scope: FlagScope, subject: &str) -> Result<FeatureFlagDto, DomainError> {
+        if !self.repository.remove_override(key, scope, subject).await? {
+            return Err(DomainError::not_found("FeatureFlagOverride", format!("{}/{}/{}", key, scope.as_str(), subject)));
+        }
+        self.invalidate().await;
+        Ok(FeatureFlagDto::from(self.load_flag(key).await?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use crate::domain::repositories::feature_flag_repository::FeatureFlagRepositoryResult;
+
+    #[derive(Default)]
+    struct InMemoryFeatureFlagRepository {
+        flags: Mutex<Vec<FeatureFlag>>,
+        reads: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl FeatureFlagRepository for InMemoryFeatureFlagRepository {
+        async fn list_flags(&self) -> FeatureFlagRepositoryResult<Vec<FeatureFlag>> {
+            self.reads.fetch_add(1, Ordering::SeqCst);
+            Ok(self.flags.lock().unwrap().clone())
+        }
+
+        async fn save_flag(&self, flag: &FeatureFlag) -> FeatureFlagRepositoryResult<()> {
+            let mut flags = self.flags.lock().unwrap();
+            match flags.iter_mut().find(|f| f.key == flag.key) {
+                Some(existing) => {
+                    let overrides = std::mem::take(&mut existing.overrides);
+                    *existing = FeatureFlag { overrides, ..flag.clone() };
+                },
+                None => flags.push(FeatureFlag { overrides: Vec::new(), ..flag.clone() }),
+            }
+            Ok(())
+        }
+
+        async fn delete_flag(&self, key: &str) -> FeatureFlagRepositoryResult<bool> {
+            let mut flags = self.flags.lock().unwrap();
+            let before = flags.len();
+            flags.retain(|f| f.key != key);
+            Ok(flags.len() != before)
+        }
+
+        async fn set_override(&self, key: &str, flag_override: &FlagOverride) -> FeatureFlagRepositoryResult<()> {
+            let mut flags = self.flags.lock().unwrap();
+            let flag = flags.iter_mut().find(|f| f.key == key).unwrap();
+            flag.overrides.retain(|o| o.scope != flag_override.scope || o.subject != flag_override.subject);
+            flag.overrides.push(flag_override.clone());
+            Ok(())
+        }
+
+        async fn remove_override(&self, key: &str, scope: FlagScope, subject: &str) -> FeatureFlagRepositoryResult<bool> {
+            let mut flags = self.flags.lock().unwrap();
+            let Some(flag) = flags.iter_mut().find(|f| f.key == key) else { return Ok(false) };
+            let before = flag.overrides.len();
+            flag.overrides.retain(|o| o.scope != scope || o.subject != subject);
+            Ok(flag.overrides.len() != before)
+        }
+    }
+
+    fn enable() -> UpdateFeatureFlagDto {
+        UpdateFeatureFlagDto { enabled: Some(true), ..Default::default() }
+    }
+
+    #[tokio::test]
+    async fn test_flags_are_cached_and_writes_invalidate() {
+        let repository = Arc::new(InMemoryFeatureFlagRepository::default());
+        let service = FeatureFlagService::new(repository.clone(), Duration::from_secs(3600));
+        let alice = FlagSubject::new("alice", vec!["user".to_string()]);
+
+        assert!(!service.is_enabled("new-sync", &alice).await);
+        assert!(!service.is_enabled("new-sync", &alice).await);
+        assert_eq!(repository.reads.load(Ordering::SeqCst), 1);
+
+        service.upsert_flag("new-sync", enable()).await.unwrap();
+        assert!(service.is_enabled("new-sync", &alice).await);
+
+        service.set_override("new-sync", FlagScope::User, "alice", false).await.unwrap();
+        assert!(!service.is_enabled("new-sync", &alice).await);
+        assert_eq!(service.evaluate_all(&alice).await.get("new-sync"), Some(&false));
+
+        service.remove_override("new-sync", FlagScope::User, "alice").await.unwrap();
+        service.delete_flag("new-sync").await.unwrap();
+        assert!(!service.is_enabled("new-sync", &alice).await);
+    }
+
+    #[tokio::test]
+    async fn test_upsert_keeps_omitted_fields() {
+        let service = FeatureFlagService::new(Arc::new(InMemoryFeatureFlagRepository::default()), Duration::ZERO);
+        service.upsert_flag("zip.v2", UpdateFeatureFlagDto {
+            description: Some("New ZIP engine".to_string()),
+            rollout_percentage: Some(10),
+            ..Default::default()
+        }).await.unwrap();
+
+        let flag = service.upsert_flag("zip.v2", enable()).await.unwrap();
+        assert!(flag.enabled);
+        assert_eq!(flag.rollout_percentage, 10);
+        assert_eq!(flag.description.as_deref(), Some("New ZIP engine"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_requests_are_rejected() {
+        let service = FeatureFlagService::new(Arc::new(InMemoryFeatureFlagRepository::default()), Duration::ZERO);
+
+        let err = service.upsert_flag("Bad Key", enable()).await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidInput);
+
+        let too_much = UpdateFeatureFlagDto { rollout_percentage: Some(101), ..Default::default() };
+        let err = service.upsert_flag("ok", too_much).await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidInput);
+
+        let err = service.set_override("missing", FlagScope::Group, "beta", true).await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::NotFound);
+
+        let err = service.delete_flag("missing").await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::NotFound);
+    }
+}
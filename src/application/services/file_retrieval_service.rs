@@ -46,4 +46,8 @@ impl FileRetrievalUseCase for FileRetrievalService {
     async fn get_file_stream(&self, id: &str) -> Result<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>, DomainError> {
         self.file_repository.get_file_stream(id).await
     }
+    
+    async fn get_file_range(&self, id: &str, start: u64, length: u64) -> Result<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>, DomainError> {
+        self.file_repository.get_file_range(id, start, length).await
+    }
 }
\ No newline at end of file
@@ -1,3 +1,4 @@
+use std::path::Path;
 use std::sync::Arc;
 use thiserror::Error;
 use async_trait::async_trait;
@@ -6,9 +7,19 @@ use crate::domain::repositories::file_repository::FileRepositoryError;
 use crate::application::dtos::file_dto::FileDto;
 use crate::application::ports::inbound::FileUseCase;
 use crate::application::ports::outbound::FileStoragePort;
+use crate::application::ports::placeholder_ports::ImagePlaceholderPort;
+use crate::application::ports::image_fingerprint_ports::ImageFingerprintPort;
+use crate::application::ports::file_attribute_ports::FileAttributePort;
+use crate::application::ports::image_tagging_ports::ImageTaggingUseCase;
+use crate::application::ports::hidden_file_ports::HiddenFileRulesPort;
+use crate::application::ports::content_hash_ports::{ContentHash, ContentHashPort};
+use crate::application::ports::event_bus_ports::{DomainEvent, EventBusPort};
+use crate::domain::services::content_digest_service::sha256_hex;
 use crate::common::errors::DomainError;
 use futures::Stream;
 use bytes::Bytes;
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncReadExt;
 
 /**
  * File service-specific error types.
@@ -39,6 +50,24 @@ pub enum FileServiceError {
     InternalError(String),
 }
 
+/// Largest image read back into memory for previews after an upload from disk
+const MAX_STREAMED_IMAGE_PROCESSING_BYTES: u64 = 64 * 1024 * 1024;
+
+/// SHA-256 of a local file, read in chunks
+async fn sha256_of_file(path: &Path) -> std::io::Result<String> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
 /**
  * Converts repository errors to service errors.
  * 
@@ -112,12 +141,201 @@ pub type FileServiceResult<T> = Result<T, FileServiceError>;
 pub struct FileService {
     /// Repository responsible for file storage operations
     file_repository: Arc<dyn FileStoragePort>,
+    /// Optional generator of image placeholders embedded in listings
+    placeholder_service: Option<Arc<dyn ImagePlaceholderPort>>,
+    /// Optional perceptual fingerprinting used to find similar photos
+    fingerprint_service: Option<Arc<dyn ImageFingerprintPort>>,
+    /// Optional labeling of images by an external inference service
+    tagging_service: Option<Arc<dyn ImageTaggingUseCase>>,
+    /// Optional store of extended attributes set by WebDAV clients
+    attribute_service: Option<Arc<dyn FileAttributePort>>,
+    /// Optional instance rules for dotfiles and system files
+    hidden_file_rules: Option<Arc<dyn HiddenFileRulesPort>>,
+    /// Optional store of content hashes exposed on downloads
+    content_hash_service: Option<Arc<dyn ContentHashPort>>,
+    /// Optional bus that tells other services, and through them connected
+    /// clients, about new, changed and deleted files
+    event_bus: Option<Arc<dyn EventBusPort>>,
 }
 
 impl FileService {
     /// Creates a new file service
     pub fn new(file_repository: Arc<dyn FileStoragePort>) -> Self {
-        Self { file_repository }
+        Self {
+            file_repository,
+            placeholder_service: None,
+            fingerprint_service: None,
+            tagging_service: None,
+            attribute_service: None,
+            hidden_file_rules: None,
+            content_hash_service: None,
+            event_bus: None,
+        }
+    }
+    
+    /// Enables image placeholders, computed when image content is stored
+    pub fn with_placeholder_service(mut self, placeholder_service: Arc<dyn ImagePlaceholderPort>) -> Self {
+        self.placeholder_service = Some(placeholder_service);
+        self
+    }
+    
+    /// Enables perceptual fingerprints, computed when image content is stored
+    pub fn with_fingerprint_service(mut self, fingerprint_service: Arc<dyn ImageFingerprintPort>) -> Self {
+        self.fingerprint_service = Some(fingerprint_service);
+        self
+    }
+    
+    /// Enables image tagging for users who opted in
+    pub fn with_tagging_service(mut self, tagging_service: Arc<dyn ImageTaggingUseCase>) -> Self {
+        self.tagging_service = Some(tagging_service);
+        self
+    }
+    
+    /// Drops stored extended attributes together with their files
+    pub fn with_attribute_service(mut self, attribute_service: Arc<dyn FileAttributePort>) -> Self {
+        self.attribute_service = Some(attribute_service);
+        self
+    }
+    
+    /// Rejects uploads and hides listings of dotfiles and system files per instance rules
+    pub fn with_hidden_file_rules(mut self, hidden_file_rules: Arc<dyn HiddenFileRulesPort>) -> Self {
+        self.hidden_file_rules = Some(hidden_file_rules);
+        self
+    }
+    
+    /// Records the SHA-256 of uploaded content so downloads can expose it
+    pub fn with_content_hash_service(mut self, content_hash_service: Arc<dyn ContentHashPort>) -> Self {
+        self.content_hash_service = Some(content_hash_service);
+        self
+    }
+    
+    /// Publishes uploads, changes and deletions as domain events for search,
+    /// real-time notifications, auditing and webhooks
+    pub fn with_event_bus(mut self, event_bus: Arc<dyn EventBusPort>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+    
+    fn publish_uploaded(&self, file: &FileDto) {
+        if let Some(event_bus) = &self.event_bus {
+            event_bus.publish(DomainEvent::FileUploaded {
+                file_id: file.id.clone(),
+                name: file.name.clone(),
+                folder_id: file.folder_id.clone(),
+                path: file.path.clone(),
+                size: file.size,
+            });
+        }
+    }
+    
+    fn publish_deleted(&self, file: &FileDto) {
+        if let Some(event_bus) = &self.event_bus {
+            event_bus.publish(DomainEvent::FileDeleted {
+                file_id: file.id.clone(),
+                name: file.name.clone(),
+                folder_id: file.folder_id.clone(),
+                path: file.path.clone(),
+            });
+        }
+    }
+    
+    fn publish_updated(&self, file: &FileDto) {
+        if let Some(event_bus) = &self.event_bus {
+            event_bus.publish(DomainEvent::FileUpdated {
+                file_id: file.id.clone(),
+                name: file.name.clone(),
+                folder_id: file.folder_id.clone(),
+                path: file.path.clone(),
+                size: file.size,
+            });
+        }
+    }
+    
+    /// Fails when the instance rules reject files with this name
+    fn check_upload_name(&self, name: &str) -> FileServiceResult<()> {
+        if let Some(rules) = &self.hidden_file_rules {
+            rules.instance_rules().check_upload(name)?;
+        }
+        Ok(())
+    }
+    
+    /// Computes the placeholder and fingerprint of freshly stored content.
+    ///
+    /// Failures are only logged: image processing must never fail an upload.
+    async fn process_image_content(&self, file: &mut FileDto, content: &[u8]) {
+        if let Some(placeholder_service) = &self.placeholder_service {
+            match placeholder_service.generate_placeholder(&file.id, &file.mime_type, content).await {
+                Ok(placeholder) => file.placeholder = placeholder,
+                Err(e) => tracing::warn!("Could not compute placeholder for file {}: {}", file.id, e),
+            }
+        }
+        if let Some(fingerprint_service) = &self.fingerprint_service {
+            if let Err(e) = fingerprint_service.index_image(&file.id, &file.mime_type, content).await {
+                tracing::warn!("Could not fingerprint file {}: {}", file.id, e);
+            }
+        }
+        if let Some(tagging_service) = &self.tagging_service {
+            if file.mime_type.starts_with("image/") {
+                // Inference may be slow, so it never delays the upload response
+                let tagging_service = tagging_service.clone();
+                let file = file.clone();
+                let content = content.to_vec();
+                tokio::spawn(async move {
+                    if let Err(e) = tagging_service.tag_stored_file(&file, &content).await {
+                        tracing::warn!("Could not tag file {}: {}", file.id, e);
+                    }
+                });
+            }
+        }
+    }
+    
+    /// Stores the hash of content just written for `file`.
+    ///
+    /// Failures are only logged: the hash is recomputed on the next download.
+    async fn record_content_hash(&self, file: &FileDto, content: &[u8]) {
+        if let Some(content_hash_service) = &self.content_hash_service {
+            let hash = ContentHash { sha256: sha256_hex(content), size: content.len() as u64, modified_at: file.modified_at };
+            if let Err(e) = content_hash_service.store_hash(&file.id, hash).await {
+                tracing::warn!("Could not store content hash for file {}: {}", file.id, e);
+            }
+        }
+    }
+    
+    /// SHA-256 of the current content of a file, if one is stored for this version
+    pub async fn get_content_hash(&self, file: &FileDto) -> Option<String> {
+        let content_hash_service = self.content_hash_service.as_ref()?;
+        match content_hash_service.get_hash(&file.id).await {
+            Ok(hash) => hash.filter(|hash| hash.matches(file)).map(|hash| hash.sha256),
+            Err(e) => {
+                tracing::warn!("Could not load content hash for file {}: {}", file.id, e);
+                None
+            }
+        }
+    }
+    
+    /// Stores a hash computed while sending the whole file
+    pub async fn store_content_hash(&self, file: &FileDto, sha256: String) {
+        if let Some(content_hash_service) = &self.content_hash_service {
+            let hash = ContentHash { sha256, size: file.size, modified_at: file.modified_at };
+            if let Err(e) = content_hash_service.store_hash(&file.id, hash).await {
+                tracing::warn!("Could not store content hash for file {}: {}", file.id, e);
+            }
+        }
+    }
+    
+    /// Fills the stored placeholders into a listing
+    async fn fill_placeholders(&self, files: &mut [FileDto]) {
+        if let Some(placeholder_service) = &self.placeholder_service {
+            let ids: Vec<String> = files.iter().map(|f| f.id.clone()).collect();
+            match placeholder_service.get_placeholders(&ids).await {
+                Ok(mut placeholders) => {
+                    for file in files.iter_mut() {
+                        file.placeholder = placeholders.remove(&file.id);
+                    }
+                },
+                Err(e) => tracing::warn!("Could not load image placeholders: {}", e),
+            }
+        }
     }
     
     /// Creates a stub implementation for testing and middleware
@@ -169,6 +387,15 @@ impl FileService {
                 Ok(Box::new(empty_stream))
             }
             
+            async fn get_file_range(&self, _id: &str, _start: u64, _length: u64) -> Result<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>, DomainError> {
+                let empty_stream = futures::stream::empty();
+                Ok(Box::new(empty_stream))
+            }
+            
+            async fn write_file_range(&self, _id: &str, _offset: u64, _content: &[u8]) -> Result<FileDto, DomainError> {
+                Ok(FileDto::empty())
+            }
+            
             async fn move_file(&self, _file_id: &str, _folder_id: Option<String>) -> Result<FileDto, DomainError> {
                 Ok(FileDto::empty())
             }
@@ -186,16 +413,68 @@ impl FileService {
         content: Vec<u8>,
     ) -> FileServiceResult<FileDto>
     {
-        let file = self.file_repository.save_file(name, folder_id, content_type, content).await
+        self.check_upload_name(&name)?;
+        let file = self.file_repository.save_file(name, folder_id, content_type, content.clone()).await
+            .map_err(FileServiceError::from)?;
+        let mut dto = FileDto::from(file);
+        self.process_image_content(&mut dto, &content).await;
+        self.record_content_hash(&dto, &content).await;
+        self.publish_uploaded(&dto);
+        Ok(dto)
+    }
+    
+    /// Uploads a new file from a local file without loading it into memory.
+    /// Image previews are only generated for images small enough to read whole
+    pub async fn upload_file_from_path(
+        &self,
+        name: String,
+        folder_id: Option<String>,
+        content_type: String,
+        source: &Path,
+    ) -> FileServiceResult<FileDto>
+    {
+        self.check_upload_name(&name)?;
+        let file = self.file_repository.save_file_from_path(name, folder_id, content_type, source).await
+            .map_err(FileServiceError::from)?;
+        let mut dto = FileDto::from(file);
+        if dto.mime_type.starts_with("image/") && dto.size <= MAX_STREAMED_IMAGE_PROCESSING_BYTES {
+            match tokio::fs::read(source).await {
+                Ok(content) => self.process_image_content(&mut dto, &content).await,
+                Err(e) => tracing::warn!("Could not read {} for image processing: {}", source.display(), e),
+            }
+        }
+        if self.content_hash_service.is_some() {
+            match sha256_of_file(source).await {
+                Ok(sha256) => self.store_content_hash(&dto, sha256).await,
+                Err(e) => tracing::warn!("Could not hash file {}: {}", dto.id, e),
+            }
+        }
+        self.publish_uploaded(&dto);
+        Ok(dto)
+    }
+    
+    /// Copies a file into a folder under a new name without loading it into memory.
+    /// The copy keeps the content hash of the source; image previews are not generated
+    pub async fn copy_file(&self, file_id: &str, name: String, folder_id: Option<String>) -> FileServiceResult<FileDto> {
+        self.check_upload_name(&name)?;
+        let source = self.get_file(file_id).await?;
+        let file = self.file_repository.copy_file(file_id, name, folder_id).await
             .map_err(FileServiceError::from)?;
-        Ok(FileDto::from(file))
+        let dto = FileDto::from(file);
+        if let Some(sha256) = self.get_content_hash(&source).await {
+            self.store_content_hash(&dto, sha256).await;
+        }
+        self.publish_uploaded(&dto);
+        Ok(dto)
     }
     
     /// Gets a file by ID
     pub async fn get_file(&self, id: &str) -> FileServiceResult<FileDto> {
         let file = self.file_repository.get_file(id).await
             .map_err(FileServiceError::from)?;
-        Ok(FileDto::from(file))
+        let mut dto = FileDto::from(file);
+        self.fill_placeholders(std::slice::from_mut(&mut dto)).await;
+        Ok(dto)
     }
     
     /// Gets a file by path (needed for WebDAV)
@@ -204,12 +483,15 @@ impl FileService {
         // First, normalize the path (remove leading/trailing slashes)
         let path = path.trim_start_matches('/').trim_end_matches('/');
         
-        // List all files and find the one with matching path
-        let all_files = self.list_files(None).await?;
+        // List all files and find the one with matching path; hidden files
+        // stay reachable by path even though listings leave them out
+        let all_files = self.file_repository.list_files(None).await
+            .map_err(FileServiceError::from)?;
         
-        for file in all_files {
+        for mut file in all_files.into_iter().map(FileDto::from) {
             let file_path = file.path.trim_start_matches('/').trim_end_matches('/');
             if file_path == path || file_path.ends_with(&format!("/{}", path)) || path.ends_with(&format!("/{}", file_path)) {
+                self.fill_placeholders(std::slice::from_mut(&mut file)).await;
                 return Ok(file);
             }
         }
@@ -220,6 +502,8 @@ impl FileService {
     
     /// Creates or updates a file at a specific path (needed for WebDAV)
     pub async fn create_file(&self, parent_path: &str, filename: &str, content: &[u8], content_type: &str) -> FileServiceResult<FileDto> {
+        self.check_upload_name(filename)?;
+        
         // Get parent folder ID if parent path is not empty
         let parent_id = if !parent_path.is_empty() {
             match self.file_repository.get_parent_folder_id(parent_path).await {
@@ -238,18 +522,33 @@ impl FileService {
             content.to_vec()
         ).await.map_err(FileServiceError::from)?;
         
-        Ok(FileDto::from(file))
+        let mut dto = FileDto::from(file);
+        self.process_image_content(&mut dto, content).await;
+        self.record_content_hash(&dto, content).await;
+        self.publish_uploaded(&dto);
+        Ok(dto)
     }
     
     /// Updates an existing file (needed for WebDAV)
     pub async fn update_file(&self, path: &str, content: &[u8]) -> FileServiceResult<()> {
         // First, try to get the file by path
         match self.get_file_by_path(path).await {
-            Ok(file) => {
+            Ok(mut file) => {
                 // Update the file content
                 self.file_repository.update_file_content(&file.id, content.to_vec())
                     .await
-                    .map_err(FileServiceError::from)
+                    .map_err(FileServiceError::from)?;
+                self.process_image_content(&mut file, content).await;
+                // The hash belongs to the new version, with its own modification time
+                match self.file_repository.get_file(&file.id).await {
+                    Ok(updated) => {
+                        let updated = FileDto::from(updated);
+                        self.record_content_hash(&updated, content).await;
+                        self.publish_updated(&updated);
+                    }
+                    Err(_) => self.publish_updated(&file),
+                }
+                Ok(())
             },
             Err(_) => {
                 // If file doesn't exist, extract filename and parent path and create it
@@ -272,13 +571,53 @@ impl FileService {
     pub async fn list_files(&self, folder_id: Option<&str>) -> FileServiceResult<Vec<FileDto>> {
         let files = self.file_repository.list_files(folder_id).await
             .map_err(FileServiceError::from)?;
-        Ok(files.into_iter().map(FileDto::from).collect())
+        let mut files: Vec<FileDto> = files.into_iter().map(FileDto::from).collect();
+        if let Some(rules) = &self.hidden_file_rules {
+            let rules = rules.instance_rules();
+            files.retain(|file| !rules.is_hidden(&file.name));
+        }
+        self.fill_placeholders(&mut files).await;
+        Ok(files)
     }
     
     /// Deletes a file
     pub async fn delete_file(&self, id: &str) -> FileServiceResult<()> {
+        // The path that names the owner is gone once the file is deleted
+        let deleted = match &self.event_bus {
+            Some(_) => self.file_repository.get_file(id).await.ok().map(FileDto::from),
+            None => None,
+        };
         self.file_repository.delete_file(id).await
-            .map_err(FileServiceError::from)
+            .map_err(FileServiceError::from)?;
+        if let Some(file) = &deleted {
+            self.publish_deleted(file);
+        }
+        if let Some(placeholder_service) = &self.placeholder_service {
+            if let Err(e) = placeholder_service.remove_placeholder(id).await {
+                tracing::warn!("Could not remove placeholder for file {}: {}", id, e);
+            }
+        }
+        if let Some(fingerprint_service) = &self.fingerprint_service {
+            if let Err(e) = fingerprint_service.remove_fingerprint(id).await {
+                tracing::warn!("Could not remove fingerprint for file {}: {}", id, e);
+            }
+        }
+        if let Some(tagging_service) = &self.tagging_service {
+            if let Err(e) = tagging_service.remove_file_labels(id).await {
+                tracing::warn!("Could not remove labels for file {}: {}", id, e);
+            }
+        }
+        if let Some(attribute_service) = &self.attribute_service {
+            if let Err(e) = attribute_service.remove_attributes(id).await {
+                tracing::warn!("Could not remove attributes for file {}: {}", id, e);
+            }
+        }
+        if let Some(content_hash_service) = &self.content_hash_service {
+            if let Err(e) = content_hash_service.remove_hash(id).await {
+                tracing::warn!("Could not remove content hash for file {}: {}", id, e);
+            }
+        }
+        Ok(())
     }
     
     /// Gets file content as bytes - use for small files only
@@ -293,6 +632,22 @@ impl FileService {
             .map_err(FileServiceError::from)
     }
     
+    /// Gets a byte range of a file as stream - used for HTTP Range requests
+    pub async fn get_file_range(&self, id: &str, start: u64, length: u64) -> FileServiceResult<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>> {
+        self.file_repository.get_file_range(id, start, length).await
+            .map_err(FileServiceError::from)
+    }
+    
+    /// Overwrites part of a file starting at `offset`, extending it if needed.
+    /// Image previews are not regenerated since the full content is not at hand
+    pub async fn write_file_range(&self, id: &str, offset: u64, content: &[u8]) -> FileServiceResult<FileDto> {
+        self.file_repository.write_file_range(id, offset, content).await
+            .map_err(FileServiceError::from)?;
+        let file = self.get_file(id).await?;
+        self.publish_updated(&file);
+        Ok(file)
+    }
+    
     /// Moves a file to a new folder using filesystem operations directly
     pub async fn move_file(&self, file_id: &str, folder_id: Option<String>) -> FileServiceResult<FileDto> {
         tracing::info!("Moving file with ID: {} to folder: {:?}", file_id, folder_id);
@@ -307,7 +662,9 @@ impl FileService {
         tracing::info!("File moved successfully: {} (ID: {}) to folder: {:?}", 
                        moved_file.name(), moved_file.id(), moved_file.folder_id());
         
-        Ok(FileDto::from(moved_file))
+        let dto = FileDto::from(moved_file);
+        self.publish_updated(&dto);
+        Ok(dto)
     }
 }
 
@@ -324,6 +681,17 @@ impl FileUseCase for FileService {
             .map_err(DomainError::from)
     }
     
+    async fn upload_file_from_path(
+        &self,
+        name: String,
+        folder_id: Option<String>,
+        content_type: String,
+        source: &Path,
+    ) -> Result<FileDto, DomainError> {
+        FileService::upload_file_from_path(self, name, folder_id, content_type, source).await
+            .map_err(DomainError::from)
+    }
+    
     async fn get_file(&self, id: &str) -> Result<FileDto, DomainError> {
         FileService::get_file(self, id).await
             .map_err(DomainError::from)
@@ -364,6 +732,16 @@ impl FileUseCase for FileService {
             .map_err(DomainError::from)
     }
     
+    async fn get_file_range(&self, id: &str, start: u64, length: u64) -> Result<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>, DomainError> {
+        FileService::get_file_range(self, id, start, length).await
+            .map_err(DomainError::from)
+    }
+    
+    async fn write_file_range(&self, id: &str, offset: u64, content: &[u8]) -> Result<FileDto, DomainError> {
+        FileService::write_file_range(self, id, offset, content).await
+            .map_err(DomainError::from)
+    }
+    
     async fn move_file(&self, file_id: &str, folder_id: Option<String>) -> Result<FileDto, DomainError> {
         FileService::move_file(self, file_id, folder_id).await
             .map_err(DomainError::from)
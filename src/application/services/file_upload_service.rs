@@ -3,32 +3,24 @@ use async_trait::async_trait;
 
 use crate::application::dtos::file_dto::FileDto;
 use crate::application::ports::file_ports::FileUploadUseCase;
+use crate::application::ports::quota_ports::StorageQuotaUseCase;
 use crate::application::ports::storage_ports::FileWritePort;
-use crate::common::errors::DomainError;
+use crate::common::errors::{DomainError, ErrorKind};
 use crate::application::ports::storage_ports::StorageUsagePort;
+use crate::domain::services::ownership_service::owner_of_path;
 use tracing::{debug, warn};
 
 /// Helper function to extract username from folder path string
 fn extract_username_from_path(path: &str) -> Option<String> {
-    // Check if path contains the folder pattern
-    if !path.contains("Mi Carpeta - ") {
-        return None;
-    }
-    
-    // Split by the pattern and get the second part
-    let parts: Vec<&str> = path.split("Mi Carpeta - ").collect();
-    if parts.len() <= 1 {
-        return None;
-    }
-    
-    // Trim and return as owned String
-    Some(parts[1].trim().to_string())
+    // Only the home folder segment names the owner, not the subfolders under it
+    owner_of_path(path).map(str::to_string)
 }
 
 /// Servicio para operaciones de subida de archivos
 pub struct FileUploadService {
     file_repository: Arc<dyn FileWritePort>,
     storage_usage_service: Option<Arc<dyn StorageUsagePort>>,
+    quota_service: Option<Arc<dyn StorageQuotaUseCase>>,
 }
 
 impl FileUploadService {
@@ -37,6 +29,7 @@ impl FileUploadService {
         Self { 
             file_repository,
             storage_usage_service: None,
+            quota_service: None,
         }
     }
     
@@ -54,6 +47,36 @@ impl FileUploadService {
         Self {
             file_repository: Arc::new(crate::infrastructure::repositories::FileFsWriteRepository::default_stub()),
             storage_usage_service: None,
+            quota_service: None,
+        }
+    }
+    
+    /// Configura el servicio de cuotas que limita lo que cada usuario puede subir
+    pub fn with_quota_service(mut self, quota_service: Arc<dyn StorageQuotaUseCase>) -> Self {
+        self.quota_service = Some(quota_service);
+        self
+    }
+    
+    /// Comprueba que la subida cabe en la cuota del dueño de la carpeta.
+    /// Devuelve el ID del usuario para registrar el uso al terminar; las
+    /// carpetas que no pertenecen a ningún usuario no tienen cuota
+    async fn check_quota(&self, folder_id: Option<&str>, size: u64) -> Result<Option<String>, DomainError> {
+        let (Some(quota_service), Some(folder_id)) = (&self.quota_service, folder_id) else {
+            return Ok(None);
+        };
+        let Ok(folder_path) = self.file_repository.get_folder_path_str(folder_id).await else {
+            return Ok(None);
+        };
+        let Some(username) = extract_username_from_path(&folder_path) else {
+            return Ok(None);
+        };
+        match quota_service.check_quota_for_username(&username, size).await {
+            Ok(user_id) => Ok(Some(user_id)),
+            Err(e) if e.kind == ErrorKind::QuotaExceeded => Err(e),
+            Err(e) => {
+                warn!("Could not check the storage quota of {}: {}", username, e);
+                Ok(None)
+            }
         }
     }
 }
@@ -67,9 +90,19 @@ impl FileUploadUseCase for FileUploadService {
         content_type: String,
         content: Vec<u8>,
     ) -> Result<FileDto, DomainError> {
+        // Reject the upload before writing anything if it does not fit the quota
+        let size = content.len() as u64;
+        let quota_owner = self.check_quota(folder_id.as_deref(), size).await?;
+        
         // Upload the file
         let file = self.file_repository.save_file(name, folder_id, content_type, content).await?;
         
+        if let (Some(quota_service), Some(user_id)) = (&self.quota_service, quota_owner) {
+            if let Err(e) = quota_service.record_usage(&user_id, size as i64).await {
+                warn!("Failed to record storage usage for {}: {}", user_id, e);
+            }
+        }
+        
         // Extract the owner's user ID if available
         // We could make this more explicit by adding a user_id parameter
         if let Some(storage_service) = &self.storage_usage_service {
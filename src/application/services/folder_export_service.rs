@@ -0,0 +1,212 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use futures::StreamExt;
+use sha2::{Digest, Sha256};
+use tracing::info;
+
+use crate::application::dtos::folder_dto::{FolderExportFormat, FolderManifestDto, FolderManifestEntryDto};
+use crate::application::ports::inbound::{FileUseCase, FolderUseCase};
+use crate::application::ports::share_ports::ShareUseCase;
+use crate::common::errors::DomainError;
+use crate::domain::entities::share::ShareItemType;
+
+/// Columnas del CSV, en el orden de `FolderManifestEntryDto`
+const CSV_HEADER: &str = "path,name,item_type,id,size,mime_type,modified_at,checksum,share_count";
+
+/// Escapa un campo CSV (RFC 4180).
+///
+/// Los valores que empiezan por `=`, `+`, `-` o `@` se prefijan con una
+/// comilla simple para que las hojas de cálculo no los interpreten como
+/// fórmulas al abrir el listado.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// Convierte un manifiesto en CSV con una fila por elemento
+pub fn manifest_to_csv(manifest: &FolderManifestDto) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    csv.push_str("\r\n");
+    for entry in &manifest.entries {
+        let fields = [
+            csv_field(&entry.path),
+            csv_field(&entry.name),
+            entry.item_type.clone(),
+            csv_field(&entry.id),
+            entry.size.to_string(),
+            csv_field(entry.mime_type.as_deref().unwrap_or("")),
+            entry.modified_at.to_string(),
+            entry.checksum.clone().unwrap_or_default(),
+            entry.share_count.to_string(),
+        ];
+        csv.push_str(&fields.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+/// Servicio que genera el listado recursivo del contenido de una carpeta
+/// (nombre, tamaño, fecha, suma de comprobación y enlaces compartidos), para
+/// auditorías o para procesarlo con herramientas externas.
+///
+/// Las sumas SHA-256 se calculan leyendo cada fichero en streaming, así que
+/// el coste es proporcional al tamaño de la carpeta.
+pub struct FolderExportService {
+    folder_service: Arc<dyn FolderUseCase>,
+    file_service: Arc<dyn FileUseCase>,
+    share_service: Option<Arc<dyn ShareUseCase>>,
+}
+
+impl FolderExportService {
+    pub fn new(folder_service: Arc<dyn FolderUseCase>, file_service: Arc<dyn FileUseCase>) -> Self {
+        Self {
+            folder_service,
+            file_service,
+            share_service: None,
+        }
+    }
+
+    /// Incluye cuántos enlaces compartidos tiene cada elemento
+    pub fn with_share_service(mut self, share_service: Option<Arc<dyn ShareUseCase>>) -> Self {
+        self.share_service = share_service;
+        self
+    }
+
+    /// Recorre la carpeta y todas sus subcarpetas
+    pub async fn manifest(&self, folder_id: &str) -> Result<FolderManifestDto, DomainError> {
+        let root = self.folder_service.get_folder(folder_id).await?;
+        info!("Generando listado de la carpeta: {} (ID: {})", root.name, root.id);
+
+        let mut entries = Vec::new();
+        // Carpetas pendientes con su ruta relativa a la exportada
+        let mut pending = vec![(String::new(), root.id.clone())];
+        // Evita ciclos si el árbol de carpetas estuviera corrupto
+        let mut processed = HashSet::new();
+
+        while let Some((prefix, id)) = pending.pop() {
+            if !processed.insert(id.clone()) {
+                continue;
+            }
+
+            let mut subfolders = self.folder_service.list_folders(Some(&id)).await?;
+            subfolders.sort_by(|a, b| a.name.cmp(&b.name));
+            let mut files = self.file_service.list_files(Some(&id)).await?;
+            files.sort_by(|a, b| a.name.cmp(&b.name));
+
+            for folder in &subfolders {
+                let path = format!("{}{}", prefix, folder.name);
+                entries.push(FolderManifestEntryDto {
+                    share_count: self.share_count(&folder.id, ShareItemType::Folder).await?,
+                    path,
+                    name: folder.name.clone(),
+                    item_type: "folder".to_string(),
+                    id: folder.id.clone(),
+                    size: 0,
+                    mime_type: None,
+                    modified_at: folder.modified_at,
+                    checksum: None,
+                });
+            }
+            for file in files {
+                entries.push(FolderManifestEntryDto {
+                    path: format!("{}{}", prefix, file.name),
+                    checksum: Some(self.checksum(&file.id).await?),
+                    share_count: self.share_count(&file.id, ShareItemType::File).await?,
+                    name: file.name,
+                    item_type: "file".to_string(),
+                    id: file.id,
+                    size: file.size,
+                    mime_type: Some(file.mime_type),
+                    modified_at: file.modified_at,
+                });
+            }
+
+            for folder in subfolders.into_iter().rev() {
+                pending.push((format!("{}{}/", prefix, folder.name), folder.id));
+            }
+        }
+
+        Ok(FolderManifestDto {
+            folder_id: root.id,
+            folder_name: root.name,
+            generated_at: chrono::Utc::now().timestamp().max(0) as u64,
+            entries,
+        })
+    }
+
+    /// Genera el listado en el formato pedido; devuelve el nombre sugerido y el contenido
+    pub async fn export(&self, folder_id: &str, format: FolderExportFormat) -> Result<(String, String), DomainError> {
+        let manifest = self.manifest(folder_id).await?;
+        let file_name = format!("{}-contents.{}", manifest.folder_name, format.extension());
+        let content = match format {
+            FolderExportFormat::Csv => manifest_to_csv(&manifest),
+            FolderExportFormat::Json => serde_json::to_string_pretty(&manifest)
+                .map_err(|e| DomainError::internal_error("FolderExport", format!("Failed to serialize the manifest: {}", e)))?,
+        };
+        Ok((file_name, content))
+    }
+
+    /// SHA-256 del contenido de un fichero, leído por bloques
+    async fn checksum(&self, file_id: &str) -> Result<String, DomainError> {
+        let mut content = Box::into_pin(self.file_service.get_file_stream(file_id).await?);
+        let mut hasher = Sha256::new();
+        while let Some(chunk) = content.next().await {
+            let chunk = chunk.map_err(|e| DomainError::internal_error("FolderExport", format!("Failed to read file {}: {}", file_id, e)))?;
+            hasher.update(&chunk);
+        }
+        Ok(format!("{:x}", hasher.finalize()))
+    }
+
+    async fn share_count(&self, item_id: &str, item_type: ShareItemType) -> Result<usize, DomainError> {
+        match &self.share_service {
+            Some(shares) => Ok(shares.get_shared_links_for_item(item_id, &item_type).await?.len()),
+            None => Ok(0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_escapes_fields_and_formulas() {
+        assert_eq!(csv_field("report.pdf"), "report.pdf");
+        assert_eq!(csv_field("a, b.txt"), "\"a, b.txt\"");
+        assert_eq!(csv_field("say \"hi\".txt"), "\"say \"\"hi\"\".txt\"");
+        assert_eq!(csv_field("=SUM(A1).csv"), "'=SUM(A1).csv");
+    }
+
+    #[test]
+    fn test_manifest_to_csv() {
+        let manifest = FolderManifestDto {
+            folder_id: "root".to_string(),
+            folder_name: "Docs".to_string(),
+            generated_at: 0,
+            entries: vec![FolderManifestEntryDto {
+                path: "Reports/q1.pdf".to_string(),
+                name: "q1.pdf".to_string(),
+                item_type: "file".to_string(),
+                id: "f1".to_string(),
+                size: 42,
+                mime_type: Some("application/pdf".to_string()),
+                modified_at: 1700000000,
+                checksum: Some("abc".to_string()),
+                share_count: 1,
+            }],
+        };
+        assert_eq!(
+            manifest_to_csv(&manifest),
+            format!("{}\r\nReports/q1.pdf,q1.pdf,file,f1,42,application/pdf,1700000000,abc,1\r\n", CSV_HEADER)
+        );
+    }
+}
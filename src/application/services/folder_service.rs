@@ -1,21 +1,142 @@
 use std::sync::Arc;
 use async_trait::async_trait;
 use crate::domain::services::path_service::StoragePath;
-use crate::application::dtos::folder_dto::{CreateFolderDto, RenameFolderDto, MoveFolderDto, FolderDto};
+use crate::application::dtos::folder_dto::{CreateFolderDto, RenameFolderDto, MoveFolderDto, CopyFolderDto, FolderDto};
+use crate::application::ports::dead_property_ports::DeadPropertyUseCase;
+use crate::application::ports::event_bus_ports::{DomainEvent, EventBusPort};
+use crate::application::ports::file_attribute_ports::FileAttributePort;
 use crate::application::ports::inbound::FolderUseCase;
-use crate::application::ports::outbound::FolderStoragePort;
+use crate::application::ports::outbound::{FileStoragePort, FolderStoragePort};
 use crate::application::transactions::storage_transaction::StorageTransaction;
 use crate::common::errors::{DomainError, ErrorKind};
 
 /// Implementación del caso de uso para operaciones de carpetas
 pub struct FolderService {
     folder_storage: Arc<dyn FolderStoragePort>,
+    file_storage: Option<Arc<dyn FileStoragePort>>,
+    file_attributes: Option<Arc<dyn FileAttributePort>>,
+    dead_properties: Option<Arc<dyn DeadPropertyUseCase>>,
+    event_bus: Option<Arc<dyn EventBusPort>>,
 }
 
 impl FolderService {
     /// Crea un nuevo servicio de carpetas
     pub fn new(folder_storage: Arc<dyn FolderStoragePort>) -> Self {
-        Self { folder_storage }
+        Self {
+            folder_storage,
+            file_storage: None,
+            file_attributes: None,
+            dead_properties: None,
+            event_bus: None,
+        }
+    }
+    
+    /// Añade el almacenamiento de archivos, necesario para copiar carpetas con contenido
+    pub fn with_file_storage(mut self, file_storage: Arc<dyn FileStoragePort>) -> Self {
+        self.file_storage = Some(file_storage);
+        self
+    }
+    
+    /// Añade los servicios de metadatos que se duplican al copiar carpetas
+    pub fn with_metadata(
+        mut self,
+        file_attributes: Option<Arc<dyn FileAttributePort>>,
+        dead_properties: Option<Arc<dyn DeadPropertyUseCase>>,
+    ) -> Self {
+        self.file_attributes = file_attributes;
+        self.dead_properties = dead_properties;
+        self
+    }
+    
+    /// Publica los renombrados y borrados de carpetas como eventos de dominio
+    pub fn with_event_bus(mut self, event_bus: Arc<dyn EventBusPort>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+    
+    fn publish(&self, event: DomainEvent) {
+        if let Some(event_bus) = &self.event_bus {
+            event_bus.publish(event);
+        }
+    }
+    
+    /// Comprueba que `target_id` no es la carpeta `source_id` ni una de sus descendientes
+    async fn ensure_not_descendant(&self, source_id: &str, target_id: &str) -> Result<(), DomainError> {
+        let mut current = Some(target_id.to_string());
+        while let Some(folder_id) = current {
+            if folder_id == source_id {
+                return Err(DomainError::new(
+                    ErrorKind::InvalidInput,
+                    "Folder",
+                    "Cannot copy a folder into itself or one of its subfolders"
+                ));
+            }
+            let folder = self.folder_storage.get_folder(&folder_id).await?;
+            current = folder.parent_id().map(String::from);
+        }
+        Ok(())
+    }
+    
+    /// Copia recursivamente el contenido de `source_id` dentro de `target_id`,
+    /// anotando en `created` los IDs de todo lo que crea
+    async fn copy_contents(
+        &self,
+        file_storage: &Arc<dyn FileStoragePort>,
+        source_id: &str,
+        target_id: &str,
+        created: &mut Vec<String>,
+    ) -> Result<(), DomainError> {
+        let mut pending = vec![(source_id.to_string(), target_id.to_string())];
+        
+        while let Some((from, to)) = pending.pop() {
+            for file in file_storage.list_files(Some(&from)).await? {
+                let content = file_storage.get_file_content(file.id()).await?;
+                let copy = file_storage.save_file(
+                    file.name().to_string(),
+                    Some(to.clone()),
+                    file.mime_type().to_string(),
+                    content,
+                ).await?;
+                created.push(copy.id().to_string());
+                
+                if let Some(attributes) = &self.file_attributes {
+                    attributes.copy_attributes(file.id(), copy.id()).await?;
+                }
+                self.copy_dead_properties(file.id(), copy.id()).await?;
+            }
+            
+            for folder in self.folder_storage.list_folders(Some(&from)).await? {
+                let copy = self.folder_storage.create_folder(folder.name().to_string(), Some(to.clone())).await?;
+                created.push(copy.id().to_string());
+                self.copy_dead_properties(folder.id(), copy.id()).await?;
+                pending.push((folder.id().to_string(), copy.id().to_string()));
+            }
+        }
+        
+        Ok(())
+    }
+    
+    async fn copy_dead_properties(&self, from_id: &str, to_id: &str) -> Result<(), DomainError> {
+        match &self.dead_properties {
+            Some(dead_properties) => dead_properties.copy_properties(from_id, to_id).await,
+            None => Ok(()),
+        }
+    }
+    
+    /// Deshace una copia incompleta: borra la carpeta creada (con todo lo
+    /// copiado dentro) y los metadatos asociados
+    async fn discard_copy(&self, root_id: &str, created: &[String]) {
+        for id in created {
+            if let Some(attributes) = &self.file_attributes {
+                let _ = attributes.remove_attributes(id).await;
+            }
+            if let Some(dead_properties) = &self.dead_properties {
+                let _ = dead_properties.remove_properties(id).await;
+            }
+        }
+        if let Err(e) = self.folder_storage.delete_folder(root_id).await {
+            tracing::error!("No se pudo deshacer la copia incompleta de la carpeta {}: {}", root_id, e);
+        }
     }
     
     /// Creates a stub implementation for testing and middleware
@@ -61,6 +182,10 @@ impl FolderService {
                 Ok(FolderDto::empty())
             }
             
+            async fn copy_folder(&self, _id: &str, _dto: CopyFolderDto) -> Result<FolderDto, DomainError> {
+                Ok(FolderDto::empty())
+            }
+            
             async fn delete_folder(&self, _id: &str) -> Result<(), DomainError> {
                 Ok(())
             }
@@ -221,8 +346,16 @@ impl FolderUseCase for FolderService {
         let folder = self.folder_storage.get_folder(id)
             .await
             .map_err(|e| DomainError::internal_error("FolderStorage", format!("Failed to get renamed folder with ID: {}: {}", id, e)))?;
+        let dto = FolderDto::from(folder);
         
-        Ok(FolderDto::from(folder))
+        self.publish(DomainEvent::FolderRenamed {
+            folder_id: dto.id.clone(),
+            name: dto.name.clone(),
+            previous_name: existing_folder.name().to_string(),
+            parent_id: dto.parent_id.clone(),
+            path: dto.path.clone(),
+        });
+        Ok(dto)
     }
     
     /// Mueve una carpeta a un nuevo padre
@@ -300,10 +433,52 @@ impl FolderUseCase for FolderService {
         Ok(FolderDto::from(folder))
     }
     
+    /// Copia una carpeta con todo su contenido. Si algo falla a mitad de la
+    /// copia se elimina lo ya copiado, de modo que no quedan copias parciales.
+    async fn copy_folder(&self, id: &str, dto: CopyFolderDto) -> Result<FolderDto, DomainError> {
+        let file_storage = self.file_storage.clone().ok_or_else(|| {
+            DomainError::internal_error("FolderService", "File storage is not configured for folder copies")
+        })?;
+        
+        let source_folder = self.folder_storage.get_folder(id).await?;
+        
+        if let Some(parent_id) = &dto.parent_id {
+            if self.folder_storage.get_folder(parent_id).await.is_err() {
+                return Err(DomainError::not_found("Folder", parent_id));
+            }
+            self.ensure_not_descendant(id, parent_id).await?;
+        }
+        
+        let name = dto.name.unwrap_or_else(|| source_folder.name().to_string());
+        if name.is_empty() {
+            return Err(DomainError::new(
+                ErrorKind::InvalidInput,
+                "Folder",
+                "Folder name cannot be empty"
+            ));
+        }
+        
+        let root = self.folder_storage.create_folder(name, dto.parent_id).await?;
+        
+        let mut created = Vec::new();
+        let result = match self.copy_dead_properties(id, root.id()).await {
+            Ok(()) => self.copy_contents(&file_storage, id, root.id(), &mut created).await,
+            Err(e) => Err(e),
+        };
+        
+        if let Err(e) = result {
+            created.push(root.id().to_string());
+            self.discard_copy(root.id(), &created).await;
+            return Err(e);
+        }
+        
+        Ok(FolderDto::from(root))
+    }
+    
     /// Elimina una carpeta
     async fn delete_folder(&self, id: &str) -> Result<(), DomainError> {
         // Verificar que la carpeta existe
-        let _folder = self.folder_storage.get_folder(id)
+        let folder = self.folder_storage.get_folder(id)
             .await
             .map_err(|e| DomainError::internal_error("FolderStorage", format!("Failed to get folder with ID: {} for deletion: {}", id, e)))?;
         
@@ -312,6 +487,280 @@ impl FolderUseCase for FolderService {
         // Eliminar la carpeta
         self.folder_storage.delete_folder(id)
             .await
-            .map_err(|e| DomainError::internal_error("FolderStorage", format!("Failed to delete folder with ID: {}: {}", id, e)))
+            .map_err(|e| DomainError::internal_error("FolderStorage", format!("Failed to delete folder with ID: {}: {}", id, e)))?;
+        
+        self.publish(DomainEvent::FolderDeleted {
+            folder_id: folder.id().to_string(),
+            name: folder.name().to_string(),
+            parent_id: folder.parent_id().map(String::from),
+            path: folder.path_string().to_string(),
+        });
+        Ok(())
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use bytes::Bytes;
+    use futures::Stream;
+    use crate::domain::entities::file::File;
+    use crate::domain::entities::folder::Folder;
+
+    /// Almacenamiento en memoria de carpetas y archivos; falla al guardar
+    /// cualquier archivo llamado `broken.bin`
+    #[derive(Default)]
+    struct MemoryStorage {
+        folders: Mutex<HashMap<String, (String, Option<String>)>>,
+        files: Mutex<HashMap<String, (String, Option<String>, Vec<u8>)>>,
+        next_id: Mutex<u32>,
     }
-}
\ No newline at end of file
+
+    impl MemoryStorage {
+        fn new_id(&self) -> String {
+            let mut next_id = self.next_id.lock().unwrap();
+            *next_id += 1;
+            format!("id-{}", next_id)
+        }
+
+        fn folder(&self, id: &str) -> Result<Folder, DomainError> {
+            let folders = self.folders.lock().unwrap();
+            let (name, parent_id) = folders.get(id).ok_or_else(|| DomainError::not_found("Folder", id))?;
+            Ok(Folder::new(id.to_string(), name.clone(), StoragePath::from_string(name), parent_id.clone()).unwrap())
+        }
+
+        fn file(&self, id: &str) -> Result<File, DomainError> {
+            let files = self.files.lock().unwrap();
+            let (name, folder_id, content) = files.get(id).ok_or_else(|| DomainError::not_found("File", id))?;
+            Ok(File::new(
+                id.to_string(),
+                name.clone(),
+                StoragePath::from_string(name),
+                content.len() as u64,
+                "text/plain".to_string(),
+                folder_id.clone(),
+            ).unwrap())
+        }
+
+        fn folder_names(&self, parent_id: Option<&str>) -> Vec<String> {
+            let mut names: Vec<String> = self.folders.lock().unwrap().values()
+                .filter(|(_, parent)| parent.as_deref() == parent_id)
+                .map(|(name, _)| name.clone())
+                .collect();
+            names.sort();
+            names
+        }
+
+        fn child_folder_id(&self, parent_id: Option<&str>, name: &str) -> String {
+            self.folders.lock().unwrap().iter()
+                .find(|(_, (folder_name, parent))| folder_name == name && parent.as_deref() == parent_id)
+                .map(|(id, _)| id.clone())
+                .unwrap()
+        }
+
+        fn file_contents(&self, folder_id: &str) -> Vec<(String, Vec<u8>)> {
+            let mut files: Vec<(String, Vec<u8>)> = self.files.lock().unwrap().values()
+                .filter(|(_, folder, _)| folder.as_deref() == Some(folder_id))
+                .map(|(name, _, content)| (name.clone(), content.clone()))
+                .collect();
+            files.sort();
+            files
+        }
+    }
+
+    #[async_trait]
+    impl FolderStoragePort for MemoryStorage {
+        async fn create_folder(&self, name: String, parent_id: Option<String>) -> Result<Folder, DomainError> {
+            if self.folder_names(parent_id.as_deref()).contains(&name) {
+                return Err(DomainError::already_exists("Folder", name));
+            }
+            let id = self.new_id();
+            self.folders.lock().unwrap().insert(id.clone(), (name, parent_id));
+            self.folder(&id)
+        }
+
+        async fn get_folder(&self, id: &str) -> Result<Folder, DomainError> {
+            self.folder(id)
+        }
+
+        async fn get_folder_by_path(&self, storage_path: &StoragePath) -> Result<Folder, DomainError> {
+            Err(DomainError::not_found("Folder", storage_path.to_string()))
+        }
+
+        async fn list_folders(&self, parent_id: Option<&str>) -> Result<Vec<Folder>, DomainError> {
+            let ids: Vec<String> = self.folders.lock().unwrap().iter()
+                .filter(|(_, (_, parent))| parent.as_deref() == parent_id)
+                .map(|(id, _)| id.clone())
+                .collect();
+            ids.iter().map(|id| self.folder(id)).collect()
+        }
+
+        async fn list_folders_paginated(
+            &self,
+            parent_id: Option<&str>,
+            _offset: usize,
+            _limit: usize,
+            _include_total: bool
+        ) -> Result<(Vec<Folder>, Option<usize>), DomainError> {
+            Ok((self.list_folders(parent_id).await?, None))
+        }
+
+        async fn rename_folder(&self, id: &str, _new_name: String) -> Result<Folder, DomainError> {
+            self.folder(id)
+        }
+
+        async fn move_folder(&self, id: &str, _new_parent_id: Option<&str>) -> Result<Folder, DomainError> {
+            self.folder(id)
+        }
+
+        async fn delete_folder(&self, id: &str) -> Result<(), DomainError> {
+            for child in self.list_folders(Some(id)).await? {
+                self.delete_folder(child.id()).await?;
+            }
+            self.files.lock().unwrap().retain(|_, (_, folder_id, _)| folder_id.as_deref() != Some(id));
+            self.folders.lock().unwrap().remove(id);
+            Ok(())
+        }
+
+        async fn folder_exists(&self, _storage_path: &StoragePath) -> Result<bool, DomainError> {
+            Ok(false)
+        }
+
+        async fn get_folder_path(&self, id: &str) -> Result<StoragePath, DomainError> {
+            Ok(self.folder(id)?.storage_path().clone())
+        }
+    }
+
+    #[async_trait]
+    impl FileStoragePort for MemoryStorage {
+        async fn save_file(
+            &self,
+            name: String,
+            folder_id: Option<String>,
+            _content_type: String,
+            content: Vec<u8>,
+        ) -> Result<File, DomainError> {
+            if name == "broken.bin" {
+                return Err(DomainError::internal_error("File", "disk full"));
+            }
+            let id = self.new_id();
+            self.files.lock().unwrap().insert(id.clone(), (name, folder_id, content));
+            self.file(&id)
+        }
+
+        async fn get_file(&self, id: &str) -> Result<File, DomainError> {
+            self.file(id)
+        }
+
+        async fn list_files(&self, folder_id: Option<&str>) -> Result<Vec<File>, DomainError> {
+            let ids: Vec<String> = self.files.lock().unwrap().iter()
+                .filter(|(_, (_, folder, _))| folder.as_deref() == folder_id)
+                .map(|(id, _)| id.clone())
+                .collect();
+            ids.iter().map(|id| self.file(id)).collect()
+        }
+
+        async fn delete_file(&self, id: &str) -> Result<(), DomainError> {
+            self.files.lock().unwrap().remove(id);
+            Ok(())
+        }
+
+        async fn get_file_content(&self, id: &str) -> Result<Vec<u8>, DomainError> {
+            let files = self.files.lock().unwrap();
+            files.get(id).map(|(_, _, content)| content.clone()).ok_or_else(|| DomainError::not_found("File", id))
+        }
+
+        async fn get_file_stream(&self, _id: &str) -> Result<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>, DomainError> {
+            unimplemented!()
+        }
+
+        async fn get_file_range(&self, _id: &str, _start: u64, _length: u64) -> Result<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>, DomainError> {
+            unimplemented!()
+        }
+
+        async fn move_file(&self, file_id: &str, _target_folder_id: Option<String>) -> Result<File, DomainError> {
+            self.file(file_id)
+        }
+
+        async fn get_file_path(&self, id: &str) -> Result<StoragePath, DomainError> {
+            Ok(self.file(id)?.storage_path().clone())
+        }
+
+        async fn get_parent_folder_id(&self, _path: &str) -> Result<String, DomainError> {
+            unimplemented!()
+        }
+
+        async fn update_file_content(&self, _file_id: &str, _content: Vec<u8>) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+    }
+
+    /// Crea `Projects/{plan.txt, Drafts/{draft.txt, Old/old.txt}}` y devuelve el ID de `Projects`
+    async fn sample_tree(storage: &MemoryStorage) -> String {
+        let projects = FolderStoragePort::create_folder(storage, "Projects".to_string(), None).await.unwrap();
+        let drafts = FolderStoragePort::create_folder(storage, "Drafts".to_string(), Some(projects.id().to_string())).await.unwrap();
+        let old = FolderStoragePort::create_folder(storage, "Old".to_string(), Some(drafts.id().to_string())).await.unwrap();
+        for (name, folder) in [("plan.txt", &projects), ("draft.txt", &drafts), ("old.txt", &old)] {
+            storage.save_file(name.to_string(), Some(folder.id().to_string()), "text/plain".to_string(), name.as_bytes().to_vec()).await.unwrap();
+        }
+        projects.id().to_string()
+    }
+
+    fn service(storage: &Arc<MemoryStorage>) -> FolderService {
+        FolderService::new(storage.clone()).with_file_storage(storage.clone())
+    }
+
+    #[tokio::test]
+    async fn copy_folder_duplicates_the_whole_tree() {
+        let storage = Arc::new(MemoryStorage::default());
+        let projects = sample_tree(&storage).await;
+
+        let copy = service(&storage)
+            .copy_folder(&projects, CopyFolderDto { parent_id: None, name: Some("Projects (copy)".to_string()) })
+            .await
+            .unwrap();
+
+        assert_eq!(copy.name, "Projects (copy)");
+        assert_eq!(storage.file_contents(&copy.id), vec![("plan.txt".to_string(), b"plan.txt".to_vec())]);
+        let drafts = storage.child_folder_id(Some(&copy.id), "Drafts");
+        assert_eq!(storage.file_contents(&drafts), vec![("draft.txt".to_string(), b"draft.txt".to_vec())]);
+        let old = storage.child_folder_id(Some(&drafts), "Old");
+        assert_eq!(storage.file_contents(&old), vec![("old.txt".to_string(), b"old.txt".to_vec())]);
+        // El original sigue intacto
+        assert_eq!(storage.folder_names(None), vec!["Projects".to_string(), "Projects (copy)".to_string()]);
+        assert_eq!(storage.file_contents(&projects).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn failed_copy_leaves_nothing_behind() {
+        let storage = Arc::new(MemoryStorage::default());
+        let projects = sample_tree(&storage).await;
+        let old = storage.child_folder_id(Some(&storage.child_folder_id(Some(&projects), "Drafts")), "Old");
+        storage.files.lock().unwrap().insert("broken".to_string(), ("broken.bin".to_string(), Some(old), vec![0]));
+        let files_before = storage.files.lock().unwrap().len();
+
+        let result = service(&storage)
+            .copy_folder(&projects, CopyFolderDto { parent_id: None, name: Some("Backup".to_string()) })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(storage.folder_names(None), vec!["Projects".to_string()]);
+        assert_eq!(storage.files.lock().unwrap().len(), files_before);
+    }
+
+    #[tokio::test]
+    async fn copy_folder_into_its_own_subfolder_is_rejected() {
+        let storage = Arc::new(MemoryStorage::default());
+        let projects = sample_tree(&storage).await;
+        let drafts = storage.child_folder_id(Some(&projects), "Drafts");
+
+        let err = service(&storage)
+            .copy_folder(&projects, CopyFolderDto { parent_id: Some(drafts.clone()), name: None })
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.kind, ErrorKind::InvalidInput);
+        assert_eq!(storage.folder_names(Some(&drafts)), vec!["Old".to_string()]);
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use crate::application::ports::inbound::{FileUseCase, FolderUseCase};
+use crate::common::errors::DomainError;
+use crate::domain::services::i18n_service::Locale;
+use crate::domain::services::naming_service::{self, NamingPattern};
+
+/// Nombres reservados por operaciones en curso: (carpeta, nombre en minúsculas)
+type ReservationSet = Arc<Mutex<HashSet<(String, String)>>>;
+
+/// Nombre libre reservado en una carpeta.
+///
+/// Mientras la reserva exista ninguna otra operación obtendrá el mismo
+/// nombre; se libera al soltarla, una vez creado el fichero o al fallar.
+#[derive(Debug)]
+pub struct NameReservation {
+    name: String,
+    key: (String, String),
+    reservations: ReservationSet,
+}
+
+impl NameReservation {
+    /// Nombre reservado
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Drop for NameReservation {
+    fn drop(&mut self) {
+        if let Ok(mut reservations) = self.reservations.lock() {
+            reservations.remove(&self.key);
+        }
+    }
+}
+
+/// Servicio que centraliza la búsqueda de nombres libres para copias,
+/// copias en conflicto, importaciones y extracciones
+pub struct FreeNameService {
+    file_service: Arc<dyn FileUseCase>,
+    folder_service: Arc<dyn FolderUseCase>,
+    reservations: ReservationSet,
+}
+
+impl FreeNameService {
+    /// Crea un nuevo servicio de nombres libres
+    pub fn new(file_service: Arc<dyn FileUseCase>, folder_service: Arc<dyn FolderUseCase>) -> Self {
+        Self {
+            file_service,
+            folder_service,
+            reservations: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// Busca y reserva un nombre libre en la carpeta.
+    ///
+    /// Se consideran ocupados los nombres de ficheros y carpetas existentes y
+    /// los reservados por otras operaciones, sin distinguir mayúsculas.
+    pub async fn reserve_name(
+        &self,
+        folder_id: Option<&str>,
+        desired: &str,
+        pattern: NamingPattern,
+        locale: Locale,
+    ) -> Result<NameReservation, DomainError> {
+        let desired = desired.trim();
+        if desired.is_empty() || desired.contains('/') || desired.contains('\\') {
+            return Err(DomainError::validation_error("A valid file name is required"));
+        }
+
+        let mut existing: HashSet<String> = self.file_service
+            .list_files(folder_id)
+            .await?
+            .into_iter()
+            .map(|f| f.name.to_lowercase())
+            .collect();
+        existing.extend(
+            self.folder_service
+                .list_folders(folder_id)
+                .await?
+                .into_iter()
+                .map(|f| f.name.to_lowercase()),
+        );
+
+        let folder_key = folder_id.unwrap_or_default().to_string();
+        let mut reservations = self.reservations
+            .lock()
+            .map_err(|_| DomainError::internal_error("FreeName", "Reservation registry poisoned"))?;
+
+        let name = naming_service::find_free_name(desired, pattern, locale, |candidate| {
+            let lower = candidate.to_lowercase();
+            existing.contains(&lower) || reservations.contains(&(folder_key.clone(), lower))
+        })
+        .ok_or_else(|| DomainError::internal_error("FreeName", format!("No free name found for '{}'", desired)))?;
+
+        let key = (folder_key, name.to_lowercase());
+        reservations.insert(key.clone());
+
+        Ok(NameReservation {
+            name,
+            key,
+            reservations: self.reservations.clone(),
+        })
+    }
+}
@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::application::ports::event_bus_ports::{DomainEvent, DomainEventSubscriber};
+use crate::application::ports::full_text_ports::{FullTextIndexPort, TextExtractionPort};
+use crate::application::ports::storage_ports::FileStoragePort;
+use crate::common::errors::DomainError;
+
+/// Caracteres de texto que se indexan como mucho por fichero
+const MAX_INDEXED_CHARS: usize = 1_000_000;
+
+/// Mantiene el índice de contenido al día con los eventos de ficheros: extrae
+/// el texto de los documentos subidos o modificados y quita del índice los
+/// que se borran
+pub struct FullTextIndexService {
+    index: Arc<dyn FullTextIndexPort>,
+    extractor: Arc<dyn TextExtractionPort>,
+    file_repository: Arc<dyn FileStoragePort>,
+    /// Los ficheros mayores no se leen para no cargarlos enteros en memoria
+    max_file_bytes: u64,
+}
+
+impl FullTextIndexService {
+    pub fn new(
+        index: Arc<dyn FullTextIndexPort>,
+        extractor: Arc<dyn TextExtractionPort>,
+        file_repository: Arc<dyn FileStoragePort>,
+        max_file_bytes: u64,
+    ) -> Self {
+        Self { index, extractor, file_repository, max_file_bytes }
+    }
+
+    /// Indexa el contenido de un fichero. Con `replace`, si ya no se puede
+    /// indexar se quita lo que hubiera de una versión anterior
+    async fn index_file(&self, file_id: &str, name: &str, size: u64, replace: bool) -> Result<(), DomainError> {
+        let text = if self.extractor.supports(name) && size <= self.max_file_bytes {
+            let content = self.file_repository.get_file_content(file_id).await?;
+            self.extractor.extract_text(name, content).await?
+        } else {
+            None
+        };
+
+        match text.filter(|text| !text.trim().is_empty()) {
+            Some(text) => self.index.index_document(file_id, name, truncate_chars(text, MAX_INDEXED_CHARS)).await,
+            None if replace => self.index.remove_document(file_id).await,
+            None => Ok(()),
+        }
+    }
+}
+
+/// Recorta el texto a `max_chars` caracteres sin partir ninguno
+fn truncate_chars(mut text: String, max_chars: usize) -> String {
+    if let Some((end, _)) = text.char_indices().nth(max_chars) {
+        text.truncate(end);
+    }
+    text
+}
+
+#[async_trait]
+impl DomainEventSubscriber for FullTextIndexService {
+    fn name(&self) -> &'static str {
+        "full_text_index"
+    }
+
+    async fn handle(&self, event: &DomainEvent) -> Result<(), DomainError> {
+        match event {
+            DomainEvent::FileUploaded { file_id, name, size, .. } => {
+                self.index_file(file_id, name, *size, false).await
+            }
+            DomainEvent::FileUpdated { file_id, name, size, .. } => {
+                self.index_file(file_id, name, *size, true).await
+            }
+            DomainEvent::FileDeleted { file_id, .. } => self.index.remove_document(file_id).await,
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_chars_keeps_whole_characters() {
+        assert_eq!(truncate_chars("año".to_string(), 2), "añ");
+        assert_eq!(truncate_chars("año".to_string(), 10), "año");
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::HashSet;
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use tokio::sync::Semaphore;
+
+use crate::application::dtos::file_dto::FileDto;
+use crate::application::dtos::gallery_dto::{GalleryItemDto, GalleryPageDto};
+use crate::application::ports::gallery_ports::{GalleryUseCase, GalleryWindow};
+use crate::application::ports::image_preview_ports::{ImagePreviewRequest, ImagePreviewUseCase, PREVIEW_MIME_TYPES};
+use crate::application::ports::inbound::FileUseCase;
+use crate::common::errors::DomainError;
+
+/// Previsualizaciones adelantadas que se generan a la vez. Se deja margen
+/// para que las peticiones interactivas no esperen a las adelantadas
+const PREFETCH_CONCURRENCY: usize = 1;
+
+/// Servicio que pagina las carpetas de fotos para la galería y calienta la
+/// caché de previsualizaciones con las imágenes que vienen a continuación
+pub struct GalleryService {
+    file_service: Arc<dyn FileUseCase>,
+    image_previews: Arc<dyn ImagePreviewUseCase>,
+    /// Previsualizaciones adelantadas pendientes, por fichero y tamaño, para
+    /// no encolar dos veces la misma mientras el usuario sigue desplazándose
+    in_flight: Arc<Mutex<HashSet<String>>>,
+    permits: Arc<Semaphore>,
+}
+
+impl GalleryService {
+    /// Crea el servicio sobre el de previsualizaciones, cuya caché comparte
+    pub fn new(file_service: Arc<dyn FileUseCase>, image_previews: Arc<dyn ImagePreviewUseCase>) -> Self {
+        Self {
+            file_service,
+            image_previews,
+            in_flight: Arc::new(Mutex::new(HashSet::new())),
+            permits: Arc::new(Semaphore::new(PREFETCH_CONCURRENCY)),
+        }
+    }
+
+    /// Genera en segundo plano las previsualizaciones de `files`, de una en
+    /// una y en orden, para que las más cercanas estén listas antes
+    fn prefetch(&self, files: Vec<String>, thumbnail: ImagePreviewRequest) -> usize {
+        let queued: Vec<String> = match self.in_flight.lock() {
+            Ok(mut in_flight) => files
+                .into_iter()
+                .filter(|id| in_flight.insert(prefetch_key(id, &thumbnail)))
+                .collect(),
+            Err(_) => return 0,
+        };
+        let count = queued.len();
+        if queued.is_empty() {
+            return 0;
+        }
+
+        let image_previews = self.image_previews.clone();
+        let in_flight = self.in_flight.clone();
+        let permits = self.permits.clone();
+        tokio::spawn(async move {
+            let Ok(_permit) = permits.acquire_owned().await else {
+                return;
+            };
+            for file_id in queued {
+                if let Err(e) = image_previews.render_preview(&file_id, thumbnail).await {
+                    tracing::debug!("No se pudo adelantar la previsualización de {}: {}", file_id, e);
+                }
+                if let Ok(mut in_flight) = in_flight.lock() {
+                    in_flight.remove(&prefetch_key(&file_id, &thumbnail));
+                }
+            }
+        });
+        count
+    }
+}
+
+/// Imágenes de la página y las que se adelantan tras ella, acotadas a `total`
+fn gallery_ranges(total: usize, offset: usize, limit: usize, prefetch: usize) -> (Range<usize>, Range<usize>) {
+    let start = offset.min(total);
+    let end = start.saturating_add(limit).min(total);
+    let prefetch_end = end.saturating_add(prefetch).min(total);
+    (start..end, end..prefetch_end)
+}
+
+/// URL de la previsualización con los mismos parámetros con los que se
+/// adelanta, de modo que el navegador la encuentra en la caché
+fn thumbnail_url(file_id: &str, thumbnail: &ImagePreviewRequest) -> String {
+    let mut params = Vec::new();
+    if let Some(width) = thumbnail.width {
+        params.push(format!("w={}", width));
+    }
+    if let Some(height) = thumbnail.height {
+        params.push(format!("h={}", height));
+    }
+    params.push(format!("fit={}", thumbnail.fit.as_str()));
+    if let Some(quality) = thumbnail.quality {
+        params.push(format!("quality={}", quality.as_str()));
+    }
+    format!("/api/files/{}/image?{}", file_id, params.join("&"))
+}
+
+fn prefetch_key(file_id: &str, thumbnail: &ImagePreviewRequest) -> String {
+    format!("{}:{:?}", file_id, thumbnail)
+}
+
+fn to_item(file: FileDto, thumbnail: &ImagePreviewRequest) -> GalleryItemDto {
+    GalleryItemDto {
+        thumbnail_url: thumbnail_url(&file.id, thumbnail),
+        id: file.id,
+        name: file.name,
+        mime_type: file.mime_type,
+        size: file.size,
+        modified_at: file.modified_at,
+    }
+}
+
+#[async_trait]
+impl GalleryUseCase for GalleryService {
+    async fn gallery_page(&self, window: GalleryWindow, thumbnail: ImagePreviewRequest) -> Result<GalleryPageDto, DomainError> {
+        let images: Vec<FileDto> = self.file_service
+            .list_files(window.folder_id.as_deref())
+            .await?
+            .into_iter()
+            .filter(|file| PREVIEW_MIME_TYPES.contains(&file.mime_type.as_str()))
+            .collect();
+
+        let total = images.len();
+        let (page, ahead) = gallery_ranges(total, window.offset, window.limit, window.prefetch);
+        let next_offset = (page.end < total).then_some(page.end);
+        let prefetched = self.prefetch(images[ahead].iter().map(|file| file.id.clone()).collect(), thumbnail);
+
+        Ok(GalleryPageDto {
+            folder_id: window.folder_id,
+            offset: page.start,
+            total,
+            items: images[page].iter().cloned().map(|file| to_item(file, &thumbnail)).collect(),
+            next_offset,
+            prefetched,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::ports::image_preview_ports::{ImageFit, PreviewQuality};
+
+    #[test]
+    fn test_gallery_ranges() {
+        assert_eq!(gallery_ranges(100, 0, 20, 30), (0..20, 20..50));
+        // La ventana adelantada no pasa del final de la carpeta
+        assert_eq!(gallery_ranges(100, 60, 20, 30), (60..80, 80..100));
+        assert_eq!(gallery_ranges(100, 90, 20, 30), (90..100, 100..100));
+        assert_eq!(gallery_ranges(100, 150, 20, 30), (100..100, 100..100));
+        assert_eq!(gallery_ranges(10, usize::MAX, usize::MAX, usize::MAX), (10..10, 10..10));
+    }
+
+    #[test]
+    fn test_thumbnail_url_matches_preview_query() {
+        let thumbnail = ImagePreviewRequest { width: Some(256), height: Some(256), fit: ImageFit::Cover, quality: None };
+        assert_eq!(thumbnail_url("abc", &thumbnail), "/api/files/abc/image?w=256&h=256&fit=cover");
+
+        let thumbnail = ImagePreviewRequest { width: None, height: Some(200), fit: ImageFit::Contain, quality: Some(PreviewQuality::Fast) };
+        assert_eq!(thumbnail_url("abc", &thumbnail), "/api/files/abc/image?h=200&fit=contain&quality=fast");
+    }
+}
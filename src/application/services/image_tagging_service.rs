@@ -0,0 +1,411 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use sqlx::{PgPool, Row};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::application::dtos::file_dto::FileDto;
+use crate::application::dtos::image_tagging_dto::{
+    ImageLabelDto, ImageTaggingSettingsDto, LabeledFileDto, TaggingJobDto,
+};
+use crate::application::ports::image_tagging_ports::{ImageLabelingPort, ImageTaggingUseCase};
+use crate::application::ports::outbound::{FileStoragePort, FolderStoragePort};
+use crate::common::errors::{DomainError, ErrorKind};
+use crate::domain::services::ownership_service::{owner_of_path, HOME_FOLDER_PREFIX};
+
+/// Imágenes más grandes no se envían al servicio de inferencia
+const MAX_TAGGING_SOURCE_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Servicio de etiquetado de imágenes mediante un servicio de inferencia externo.
+///
+/// El etiquetado es opcional por usuario: solo se envían imágenes de usuarios
+/// que lo han activado, y al desactivarlo se borran sus etiquetas.
+#[derive(Clone)]
+pub struct ImageTaggingService {
+    db_pool: Arc<PgPool>,
+    labeler: Arc<dyn ImageLabelingPort>,
+    file_repository: Arc<dyn FileStoragePort>,
+    folder_repository: Arc<dyn FolderStoragePort>,
+    min_confidence: f32,
+    max_labels: usize,
+    jobs: Arc<RwLock<HashMap<String, (String, TaggingJobDto)>>>,
+}
+
+impl ImageTaggingService {
+    /// Crea un nuevo servicio de etiquetado
+    pub fn new(
+        db_pool: Arc<PgPool>,
+        labeler: Arc<dyn ImageLabelingPort>,
+        file_repository: Arc<dyn FileStoragePort>,
+        folder_repository: Arc<dyn FolderStoragePort>,
+    ) -> Self {
+        Self {
+            db_pool,
+            labeler,
+            file_repository,
+            folder_repository,
+            min_confidence: 0.5,
+            max_labels: 10,
+            jobs: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Configura la confianza mínima y el máximo de etiquetas por imagen
+    pub fn with_limits(mut self, min_confidence: f32, max_labels: usize) -> Self {
+        self.min_confidence = min_confidence;
+        self.max_labels = max_labels.max(1);
+        self
+    }
+
+    async fn is_enabled(&self, user_id: &str) -> Result<bool, DomainError> {
+        let row = sqlx::query("SELECT enabled FROM auth.user_image_tagging WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_optional(&*self.db_pool)
+            .await
+            .map_err(|e| DomainError::database_error(format!("Failed to read tagging setting: {}", e)))?;
+        Ok(row.map(|r| r.get::<bool, _>("enabled")).unwrap_or(false))
+    }
+
+    /// Envía una imagen al servicio de inferencia y reemplaza sus etiquetas.
+    ///
+    /// Devuelve el número de etiquetas guardadas.
+    async fn label_and_store(&self, user_id: &str, file_id: &str, mime_type: &str, content: Vec<u8>) -> Result<usize, DomainError> {
+        let labels = filter_labels(
+            self.labeler.label_image(mime_type, content).await?,
+            self.min_confidence,
+            self.max_labels,
+        );
+
+        let mut tx = self.db_pool.begin().await
+            .map_err(|e| DomainError::database_error(format!("Failed to start transaction: {}", e)))?;
+
+        sqlx::query("DELETE FROM auth.image_labels WHERE user_id = $1 AND file_id = $2")
+            .bind(user_id)
+            .bind(file_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| DomainError::database_error(format!("Failed to replace labels: {}", e)))?;
+
+        for label in &labels {
+            sqlx::query(
+                "INSERT INTO auth.image_labels (user_id, file_id, label, confidence) VALUES ($1, $2, $3, $4)"
+            )
+            .bind(user_id)
+            .bind(file_id)
+            .bind(&label.label)
+            .bind(label.confidence)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| DomainError::database_error(format!("Failed to store label: {}", e)))?;
+        }
+
+        tx.commit().await
+            .map_err(|e| DomainError::database_error(format!("Failed to commit labels: {}", e)))?;
+
+        Ok(labels.len())
+    }
+
+    async fn update_job(&self, job_id: &str, update: impl FnOnce(&mut TaggingJobDto)) {
+        if let Some((_, job)) = self.jobs.write().await.get_mut(job_id) {
+            update(job);
+        }
+    }
+
+    /// Recorre la carpeta personal del usuario etiquetando todas las imágenes
+    async fn run_backfill(&self, job_id: &str, user_id: &str, home_folder_id: String) -> Result<(), DomainError> {
+        let mut pending = vec![home_folder_id];
+
+        while let Some(folder_id) = pending.pop() {
+            // Stop as soon as the user opts out
+            if !self.is_enabled(user_id).await? {
+                return Err(DomainError::access_denied("ImageTagging", "Image tagging was disabled"));
+            }
+
+            for folder in self.folder_repository.list_folders(Some(&folder_id)).await? {
+                pending.push(folder.id().to_string());
+            }
+
+            for file in self.file_repository.list_files(Some(&folder_id)).await? {
+                if !file.mime_type().starts_with("image/") || file.size() > MAX_TAGGING_SOURCE_BYTES {
+                    continue;
+                }
+
+                let result = match self.file_repository.get_file_content(file.id()).await {
+                    Ok(content) => self.label_and_store(user_id, file.id(), file.mime_type(), content).await,
+                    Err(e) => Err(e),
+                };
+
+                match result {
+                    Ok(count) => self.update_job(job_id, |job| {
+                        job.processed += 1;
+                        if count > 0 {
+                            job.labeled += 1;
+                        }
+                    }).await,
+                    Err(e) => {
+                        tracing::warn!("Tagging backfill could not label file {}: {}", file.id(), e);
+                        self.update_job(job_id, |job| {
+                            job.processed += 1;
+                            job.failed += 1;
+                        }).await;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Normaliza las etiquetas, descarta las de baja confianza y conserva las mejores
+fn filter_labels(labels: Vec<ImageLabelDto>, min_confidence: f32, max_labels: usize) -> Vec<ImageLabelDto> {
+    let mut best: HashMap<String, f32> = HashMap::new();
+    for label in labels {
+        let name = label.label.trim().to_lowercase();
+        if name.is_empty() || label.confidence < min_confidence {
+            continue;
+        }
+        let entry = best.entry(name).or_insert(label.confidence);
+        *entry = entry.max(label.confidence);
+    }
+
+    let mut labels: Vec<ImageLabelDto> = best
+        .into_iter()
+        .map(|(label, confidence)| ImageLabelDto { label, confidence })
+        .collect();
+    labels.sort_by(|a, b| b.confidence.total_cmp(&a.confidence).then_with(|| a.label.cmp(&b.label)));
+    labels.truncate(max_labels);
+    labels
+}
+
+fn now_secs() -> u64 {
+    chrono::Utc::now().timestamp().max(0) as u64
+}
+
+#[async_trait]
+impl ImageTaggingUseCase for ImageTaggingService {
+    async fn get_settings(&self, user_id: &str) -> Result<ImageTaggingSettingsDto, DomainError> {
+        Ok(ImageTaggingSettingsDto { enabled: self.is_enabled(user_id).await?, labels_deleted: 0 })
+    }
+
+    async fn set_enabled(&self, user_id: &str, enabled: bool) -> Result<ImageTaggingSettingsDto, DomainError> {
+        sqlx::query(
+            r#"
+            INSERT INTO auth.user_image_tagging (user_id, enabled, updated_at)
+            VALUES ($1, $2, CURRENT_TIMESTAMP)
+            ON CONFLICT (user_id) DO UPDATE SET enabled = $2, updated_at = CURRENT_TIMESTAMP
+            "#
+        )
+        .bind(user_id)
+        .bind(enabled)
+        .execute(&*self.db_pool)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to update tagging setting: {}", e)))?;
+
+        let mut labels_deleted = 0;
+        if !enabled {
+            // Opting out removes every label derived from the user's images
+            labels_deleted = sqlx::query("DELETE FROM auth.image_labels WHERE user_id = $1")
+                .bind(user_id)
+                .execute(&*self.db_pool)
+                .await
+                .map_err(|e| DomainError::database_error(format!("Failed to delete labels: {}", e)))?
+                .rows_affected();
+            tracing::info!("Image tagging disabled for user {}, deleted {} labels", user_id, labels_deleted);
+        }
+
+        Ok(ImageTaggingSettingsDto { enabled, labels_deleted })
+    }
+
+    async fn tag_stored_file(&self, file: &FileDto, content: &[u8]) -> Result<(), DomainError> {
+        if !file.mime_type.starts_with("image/") || file.size > MAX_TAGGING_SOURCE_BYTES {
+            return Ok(());
+        }
+        let Some(username) = owner_of_path(&file.path) else {
+            return Ok(());
+        };
+
+        let row = sqlx::query(
+            r#"
+            SELECT u.id
+            FROM auth.users u
+            JOIN auth.user_image_tagging t ON t.user_id = u.id
+            WHERE u.username = $1 AND t.enabled
+            "#
+        )
+        .bind(username)
+        .fetch_optional(&*self.db_pool)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to resolve file owner: {}", e)))?;
+
+        if let Some(row) = row {
+            let user_id: String = row.get("id");
+            self.label_and_store(&user_id, &file.id, &file.mime_type, content.to_vec()).await?;
+        }
+        Ok(())
+    }
+
+    async fn remove_file_labels(&self, file_id: &str) -> Result<(), DomainError> {
+        sqlx::query("DELETE FROM auth.image_labels WHERE file_id = $1")
+            .bind(file_id)
+            .execute(&*self.db_pool)
+            .await
+            .map_err(|e| DomainError::database_error(format!("Failed to delete labels: {}", e)))?;
+        Ok(())
+    }
+
+    async fn get_file_labels(&self, user_id: &str, file_id: &str) -> Result<Vec<ImageLabelDto>, DomainError> {
+        let rows = sqlx::query(
+            "SELECT label, confidence FROM auth.image_labels WHERE user_id = $1 AND file_id = $2 ORDER BY confidence DESC"
+        )
+        .bind(user_id)
+        .bind(file_id)
+        .fetch_all(&*self.db_pool)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to fetch labels: {}", e)))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ImageLabelDto { label: row.get("label"), confidence: row.get("confidence") })
+            .collect())
+    }
+
+    async fn search_by_label(&self, user_id: &str, label: &str) -> Result<Vec<LabeledFileDto>, DomainError> {
+        let label = label.trim().to_lowercase();
+        if label.is_empty() || label == "*" {
+            return Err(DomainError::validation_error("A label is required"));
+        }
+
+        // A trailing '*' turns the search into a prefix match
+        let pattern = match label.strip_suffix('*') {
+            Some(prefix) => format!("{}%", prefix.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")),
+            None => label.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"),
+        };
+
+        let rows = sqlx::query(
+            r#"
+            SELECT file_id, label, confidence
+            FROM auth.image_labels
+            WHERE user_id = $1 AND label LIKE $2
+            ORDER BY confidence DESC
+            "#
+        )
+        .bind(user_id)
+        .bind(&pattern)
+        .fetch_all(&*self.db_pool)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to search labels: {}", e)))?;
+
+        let mut results: Vec<LabeledFileDto> = Vec::new();
+        for row in rows {
+            let file_id: String = row.get("file_id");
+            // A file matching several labels is listed once, with its best label
+            if results.iter().any(|r| r.file.id == file_id) {
+                continue;
+            }
+            match self.file_repository.get_file(&file_id).await {
+                Ok(file) => results.push(LabeledFileDto {
+                    file: FileDto::from(file),
+                    label: row.get("label"),
+                    confidence: row.get("confidence"),
+                }),
+                Err(e) if e.kind == ErrorKind::NotFound => {
+                    // Labels of files removed outside the file service
+                    self.remove_file_labels(&file_id).await?;
+                },
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(results)
+    }
+
+    async fn start_backfill(&self, user_id: &str, username: &str) -> Result<TaggingJobDto, DomainError> {
+        if !self.is_enabled(user_id).await? {
+            return Err(DomainError::access_denied(
+                "ImageTagging",
+                "Image tagging must be enabled before running a backfill",
+            ));
+        }
+
+        {
+            let jobs = self.jobs.read().await;
+            if let Some((_, job)) = jobs.values().find(|(owner, job)| owner == user_id && job.status == "running") {
+                return Ok(job.clone());
+            }
+        }
+
+        let home_folder_name = format!("{}{}", HOME_FOLDER_PREFIX, username);
+        let home_folder = self.folder_repository.list_folders(None).await?
+            .into_iter()
+            .find(|f| f.name() == home_folder_name)
+            .ok_or_else(|| DomainError::not_found("Folder", home_folder_name))?;
+
+        let job = TaggingJobDto {
+            id: Uuid::new_v4().to_string(),
+            status: "running".to_string(),
+            processed: 0,
+            labeled: 0,
+            failed: 0,
+            error: None,
+            started_at: now_secs(),
+            finished_at: None,
+        };
+        self.jobs.write().await.insert(job.id.clone(), (user_id.to_string(), job.clone()));
+
+        let service = self.clone();
+        let job_id = job.id.clone();
+        let user_id = user_id.to_string();
+        let home_folder_id = home_folder.id().to_string();
+        tokio::spawn(async move {
+            let result = service.run_backfill(&job_id, &user_id, home_folder_id).await;
+            service.update_job(&job_id, |job| {
+                job.finished_at = Some(now_secs());
+                match result {
+                    Ok(()) => job.status = "completed".to_string(),
+                    Err(e) => {
+                        job.status = "failed".to_string();
+                        job.error = Some(e.to_string());
+                    }
+                }
+            }).await;
+            tracing::info!("Tagging backfill {} finished for user {}", job_id, user_id);
+        });
+
+        Ok(job)
+    }
+
+    async fn get_backfill_job(&self, user_id: &str, job_id: &str) -> Result<TaggingJobDto, DomainError> {
+        let jobs = self.jobs.read().await;
+        match jobs.get(job_id) {
+            Some((owner, job)) if owner == user_id => Ok(job.clone()),
+            _ => Err(DomainError::not_found("TaggingJob", job_id)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label(name: &str, confidence: f32) -> ImageLabelDto {
+        ImageLabelDto { label: name.to_string(), confidence }
+    }
+
+    #[test]
+    fn test_filter_labels() {
+        let labels = vec![
+            label("Dog", 0.9),
+            label(" dog ", 0.95),
+            label("cat", 0.3),
+            label("beach", 0.7),
+            label("", 0.99),
+            label("sky", 0.6),
+        ];
+
+        let filtered = filter_labels(labels, 0.5, 2);
+        assert_eq!(filtered, vec![label("dog", 0.95), label("beach", 0.7)]);
+    }
+}
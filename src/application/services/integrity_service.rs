@@ -0,0 +1,367 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use tracing::{info, warn};
+
+use crate::application::dtos::execution_plan_dto::ExecutionPlanDto;
+use crate::application::dtos::integrity_dto::IntegrityReportDto;
+use crate::application::ports::auth_ports::UserStoragePort;
+use crate::application::ports::integrity_ports::{IntegrityStoragePort, IntegrityUseCase};
+use crate::application::ports::outbound::{FileStoragePort, FolderStoragePort};
+use crate::application::ports::share_ports::ShareStoragePort;
+use crate::application::services::execution_plan::ExecutionPlan;
+use crate::application::services::user_group_service::UserGroupService;
+use crate::common::errors::{DomainError, ErrorKind};
+use crate::domain::entities::share::{Share, ShareItemType};
+use crate::domain::repositories::trash_repository::TrashRepository;
+
+/// Qué hacer con un enlace limitado a grupos de usuarios
+#[derive(Debug, PartialEq, Eq)]
+enum GroupRepair {
+    /// Todos sus grupos existen
+    Keep,
+    /// Se quitan los grupos borrados y se conservan estos
+    Prune(Vec<String>),
+    /// Sin los grupos borrados no quedaría nadie autorizado: quitar la lista
+    /// abriría el enlace a cualquiera, así que se elimina
+    Remove,
+}
+
+fn group_repair(share: &Share, deleted_groups: &HashSet<String>) -> GroupRepair {
+    if !share.allowed_groups.iter().any(|group| deleted_groups.contains(group)) {
+        return GroupRepair::Keep;
+    }
+    let remaining: Vec<String> = share.allowed_groups
+        .iter()
+        .filter(|group| !deleted_groups.contains(*group))
+        .cloned()
+        .collect();
+    if remaining.is_empty() && share.allowed_emails.is_empty() {
+        GroupRepair::Remove
+    } else {
+        GroupRepair::Prune(remaining)
+    }
+}
+
+/// `Ok(false)` solo cuando el almacenamiento confirma que no existe: un fallo
+/// pasajero no debe hacer que se borren referencias válidas
+fn confirmed_missing<T>(result: Result<T, DomainError>) -> Result<bool, DomainError> {
+    match result {
+        Ok(_) => Ok(false),
+        Err(e) if e.kind == ErrorKind::NotFound => Ok(true),
+        Err(e) => Err(e),
+    }
+}
+
+/// Comprobación de integridad de las referencias guardadas.
+///
+/// Busca enlaces compartidos de ficheros, carpetas, usuarios o grupos que ya
+/// no existen, favoritos de elementos borrados y miembros de grupos cuyo
+/// usuario ya no existe. Sin reparar solo los cuenta, para revisarlos antes.
+///
+/// Los elementos en la papelera cuentan como existentes, porque se pueden
+/// restaurar con sus enlaces y favoritos.
+pub struct IntegrityService {
+    file_repository: Arc<dyn FileStoragePort>,
+    folder_repository: Arc<dyn FolderStoragePort>,
+    shares: Option<Arc<dyn ShareStoragePort>>,
+    trash_repository: Option<Arc<dyn TrashRepository>>,
+    user_storage: Option<Arc<dyn UserStoragePort>>,
+    groups: Option<Arc<UserGroupService>>,
+    storage: Option<Arc<dyn IntegrityStoragePort>>,
+    run_lock: tokio::sync::Mutex<()>,
+}
+
+impl IntegrityService {
+    pub fn new(file_repository: Arc<dyn FileStoragePort>, folder_repository: Arc<dyn FolderStoragePort>) -> Self {
+        Self {
+            file_repository,
+            folder_repository,
+            shares: None,
+            trash_repository: None,
+            user_storage: None,
+            groups: None,
+            storage: None,
+            run_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Comprueba los enlaces compartidos
+    pub fn with_shares(mut self, shares: Arc<dyn ShareStoragePort>) -> Self {
+        self.shares = Some(shares);
+        self
+    }
+
+    /// Respeta lo que está en la papelera
+    pub fn with_trash_repository(mut self, trash_repository: Arc<dyn TrashRepository>) -> Self {
+        self.trash_repository = Some(trash_repository);
+        self
+    }
+
+    /// Comprueba que los creadores de los enlaces siguen existiendo
+    pub fn with_user_storage(mut self, user_storage: Arc<dyn UserStoragePort>) -> Self {
+        self.user_storage = Some(user_storage);
+        self
+    }
+
+    /// Comprueba los grupos a los que se limitan los enlaces
+    pub fn with_groups(mut self, groups: Arc<UserGroupService>) -> Self {
+        self.groups = Some(groups);
+        self
+    }
+
+    /// Comprueba los favoritos y los miembros de grupos
+    pub fn with_storage(mut self, storage: Arc<dyn IntegrityStoragePort>) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    /// IDs originales de lo que está en la papelera
+    async fn trashed_ids(&self) -> Result<HashSet<String>, DomainError> {
+        let Some(trash) = &self.trash_repository else {
+            return Ok(HashSet::new());
+        };
+        Ok(trash.get_all_items().await?
+            .into_iter()
+            .map(|item| item.original_id.to_string())
+            .collect())
+    }
+
+    async fn item_missing(&self, item_id: &str, item_type: &ShareItemType, trashed: &HashSet<String>) -> Result<bool, DomainError> {
+        if trashed.contains(item_id) {
+            return Ok(false);
+        }
+        match item_type {
+            ShareItemType::File => confirmed_missing(self.file_repository.get_file(item_id).await),
+            ShareItemType::Folder => confirmed_missing(self.folder_repository.get_folder(item_id).await),
+        }
+    }
+
+    async fn check_shares(&self, plan: &mut ExecutionPlan, trashed: &HashSet<String>, report: &mut IntegrityReportDto) {
+        let Some(shares) = &self.shares else { return };
+        let all_shares = match shares.find_all_shares().await {
+            Ok(all_shares) => all_shares,
+            Err(e) => {
+                report.errors.push(format!("Enlaces compartidos: {}", e));
+                return;
+            }
+        };
+
+        let mut missing_owners: HashMap<String, bool> = HashMap::new();
+        let mut deleted_groups: HashSet<String> = HashSet::new();
+        let mut checked_groups: HashSet<String> = HashSet::new();
+
+        for share in all_shares {
+            report.shares_checked += 1;
+
+            match self.item_missing(&share.item_id, &share.item_type, trashed).await {
+                Ok(true) => {
+                    report.orphaned_shares += 1;
+                    if plan.step("remove_share", share.id.as_str(), None) {
+                        self.remove_share(shares, &share, report).await;
+                    }
+                    continue;
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    report.errors.push(format!("Enlace {}: {}", share.id, e));
+                    continue;
+                }
+            }
+
+            if let Some(user_storage) = &self.user_storage {
+                let missing = match missing_owners.get(&share.created_by) {
+                    Some(missing) => Ok(*missing),
+                    None => confirmed_missing(user_storage.get_user_by_id(&share.created_by).await),
+                };
+                match missing {
+                    Ok(missing) => {
+                        missing_owners.insert(share.created_by.clone(), missing);
+                        if missing {
+                            report.ownerless_shares += 1;
+                            if plan.step("remove_share", share.id.as_str(), None) {
+                                self.remove_share(shares, &share, report).await;
+                            }
+                            continue;
+                        }
+                    }
+                    Err(e) => {
+                        report.errors.push(format!("Enlace {}: {}", share.id, e));
+                        continue;
+                    }
+                }
+            }
+
+            if let Some(groups) = &self.groups {
+                for group in &share.allowed_groups {
+                    if checked_groups.insert(group.clone()) {
+                        match confirmed_missing(groups.ensure_group_exists(group).await) {
+                            Ok(true) => {
+                                deleted_groups.insert(group.clone());
+                            }
+                            Ok(false) => {}
+                            Err(e) => report.errors.push(format!("Grupo {}: {}", group, e)),
+                        }
+                    }
+                }
+                let repair_action = group_repair(&share, &deleted_groups);
+                if repair_action != GroupRepair::Keep {
+                    report.shares_with_deleted_groups += 1;
+                }
+                match repair_action {
+                    GroupRepair::Keep => {}
+                    GroupRepair::Prune(remaining) => {
+                        if !plan.step("prune_share_groups", share.id.as_str(), None) {
+                            continue;
+                        }
+                        let mut repaired = share.clone();
+                        repaired.allowed_groups = remaining;
+                        if let Err(e) = shares.update_share(&repaired).await {
+                            report.errors.push(format!("Enlace {}: {}", share.id, e));
+                        }
+                    }
+                    GroupRepair::Remove => {
+                        if plan.step("remove_share", share.id.as_str(), None) {
+                            self.remove_share(shares, &share, report).await;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    async fn remove_share(&self, shares: &Arc<dyn ShareStoragePort>, share: &Share, report: &mut IntegrityReportDto) {
+        match shares.delete_share(&share.id).await {
+            Ok(()) => info!("Eliminado el enlace compartido roto {} de {}", share.id, share.item_id),
+            Err(e) => report.errors.push(format!("Enlace {}: {}", share.id, e)),
+        }
+    }
+
+    async fn check_favorites(&self, plan: &mut ExecutionPlan, trashed: &HashSet<String>, report: &mut IntegrityReportDto) {
+        let Some(storage) = &self.storage else { return };
+        let favorites = match storage.list_favorites().await {
+            Ok(favorites) => favorites,
+            Err(e) => {
+                report.errors.push(format!("Favoritos: {}", e));
+                return;
+            }
+        };
+
+        let mut dangling = Vec::new();
+        for favorite in favorites {
+            // Tipos desconocidos no se tocan: no sabemos dónde buscarlos
+            let Ok(item_type) = ShareItemType::try_from(favorite.item_type.as_str()) else {
+                continue;
+            };
+            match self.item_missing(&favorite.item_id, &item_type, trashed).await {
+                Ok(true) => dangling.push(favorite.id),
+                Ok(false) => {}
+                Err(e) => report.errors.push(format!("Favorito {}: {}", favorite.id, e)),
+            }
+        }
+
+        report.dangling_favorites = dangling.len() as u64;
+        for id in &dangling {
+            plan.step("remove_favorite", id.to_string(), None);
+        }
+        if !plan.is_dry_run() && !dangling.is_empty() {
+            if let Err(e) = storage.delete_favorites(&dangling).await {
+                report.errors.push(format!("Favoritos: {}", e));
+            }
+        }
+    }
+
+    async fn check_memberships(&self, plan: &mut ExecutionPlan, report: &mut IntegrityReportDto) {
+        let Some(storage) = &self.storage else { return };
+        let result = if plan.is_dry_run() {
+            storage.count_orphaned_memberships().await
+        } else {
+            storage.delete_orphaned_memberships().await
+        };
+        match result {
+            Ok(count) => {
+                report.orphaned_memberships = count;
+                if count > 0 {
+                    plan.bulk_step("remove_group_memberships", "memberships of deleted users", count);
+                }
+            }
+            Err(e) => report.errors.push(format!("Miembros de grupos: {}", e)),
+        }
+    }
+
+    /// Hace la comprobación anotando en el plan lo que hay que reparar; solo
+    /// repara si el plan no es una simulación
+    async fn run_check(&self, plan: &mut ExecutionPlan) -> Result<IntegrityReportDto, DomainError> {
+        let repair = !plan.is_dry_run();
+        let Ok(_guard) = self.run_lock.try_lock() else {
+            return Err(DomainError::locked("Integrity", "An integrity check is already running"));
+        };
+
+        let mut report = IntegrityReportDto {
+            started_at: Utc::now().timestamp() as u64,
+            repaired: repair,
+            ..Default::default()
+        };
+        // Sin saber qué hay en la papelera se borrarían enlaces restaurables
+        let trashed = self.trashed_ids().await?;
+
+        self.check_shares(plan, &trashed, &mut report).await;
+        self.check_favorites(plan, &trashed, &mut report).await;
+        self.check_memberships(plan, &mut report).await;
+
+        report.finished_at = Utc::now().timestamp() as u64;
+        let found = report.orphaned_shares + report.ownerless_shares + report.shares_with_deleted_groups
+            + report.dangling_favorites + report.orphaned_memberships;
+        if found > 0 {
+            info!(
+                "Comprobación de integridad{}: {} enlaces huérfanos, {} sin creador, {} con grupos borrados, {} favoritos rotos, {} miembros huérfanos",
+                if repair { " con reparación" } else { "" },
+                report.orphaned_shares, report.ownerless_shares, report.shares_with_deleted_groups,
+                report.dangling_favorites, report.orphaned_memberships,
+            );
+        }
+        if !report.errors.is_empty() {
+            warn!("La comprobación de integridad no pudo revisar {} referencias", report.errors.len());
+        }
+        Ok(report)
+    }
+}
+
+#[async_trait]
+impl IntegrityUseCase for IntegrityService {
+    async fn check(&self, repair: bool) -> Result<IntegrityReportDto, DomainError> {
+        self.run_check(&mut ExecutionPlan::new("integrity_repair", !repair)).await
+    }
+
+    async fn dry_run_repair(&self) -> Result<ExecutionPlanDto, DomainError> {
+        let mut plan = ExecutionPlan::new("integrity_repair", true);
+        self.run_check(&mut plan).await?;
+        Ok(plan.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn share(groups: &[&str], emails: &[&str]) -> Share {
+        let mut share = Share::new("file-1".to_string(), ShareItemType::File, "user-1".to_string(), None, None, None).unwrap();
+        share.allowed_groups = groups.iter().map(|g| g.to_string()).collect();
+        share.allowed_emails = emails.iter().map(|e| e.to_string()).collect();
+        share
+    }
+
+    #[test]
+    fn test_group_repair() {
+        let deleted: HashSet<String> = ["gone".to_string()].into();
+
+        assert_eq!(group_repair(&share(&[], &[]), &deleted), GroupRepair::Keep);
+        assert_eq!(group_repair(&share(&["team"], &[]), &deleted), GroupRepair::Keep);
+        assert_eq!(group_repair(&share(&["gone", "team"], &[]), &deleted), GroupRepair::Prune(vec!["team".to_string()]));
+        // Las direcciones permitidas siguen limitando el enlace
+        assert_eq!(group_repair(&share(&["gone"], &["a@example.com"]), &deleted), GroupRepair::Prune(vec![]));
+        assert_eq!(group_repair(&share(&["gone"], &[]), &deleted), GroupRepair::Remove);
+    }
+}
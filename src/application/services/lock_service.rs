@@ -0,0 +1,294 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+
+use crate::application::ports::lock_ports::{LockRequest, LockUseCase};
+use crate::common::errors::DomainError;
+use crate::domain::entities::lock::{is_descendant, normalize_path, parent_path, ResourceLock};
+use crate::domain::repositories::lock_repository::LockRepository;
+
+/// Servicio de bloqueos WebDAV.
+///
+/// Los bloqueos se guardan en el repositorio para que sobrevivan a un
+/// reinicio; el servicio decide si un bloqueo nuevo choca con los existentes
+/// y si una escritura trae los tokens que necesita.
+pub struct LockService {
+    lock_repository: Arc<dyn LockRepository>,
+    default_timeout_secs: u64,
+    max_timeout_secs: u64,
+    /// Serializa la comprobación de conflictos y la creación de bloqueos
+    create_mutex: Mutex<()>,
+}
+
+impl LockService {
+    pub fn new(lock_repository: Arc<dyn LockRepository>, default_timeout_secs: u64, max_timeout_secs: u64) -> Self {
+        let max_timeout_secs = max_timeout_secs.max(1);
+        Self {
+            lock_repository,
+            default_timeout_secs: default_timeout_secs.clamp(1, max_timeout_secs),
+            max_timeout_secs,
+            create_mutex: Mutex::new(()),
+        }
+    }
+
+    /// Duración efectiva de un bloqueo, acotada al máximo del servidor
+    fn timeout(&self, requested: Option<u64>) -> u64 {
+        requested.unwrap_or(self.default_timeout_secs).clamp(1, self.max_timeout_secs)
+    }
+}
+
+#[async_trait]
+impl LockUseCase for LockService {
+    async fn lock(&self, request: LockRequest) -> Result<ResourceLock, DomainError> {
+        let path = normalize_path(&request.path);
+        let lock = ResourceLock::new(
+            &path,
+            request.owner_id,
+            request.owner_info,
+            request.exclusive,
+            request.infinite_depth,
+            self.timeout(request.timeout_secs),
+        );
+
+        let _guard = self.create_mutex.lock().await;
+        let existing = self.lock_repository.find_locks_affecting(&path).await?;
+        if existing.iter().any(|other| other.conflicts_with(&path, lock.exclusive, lock.infinite_depth)) {
+            return Err(DomainError::locked("Lock", format!("'{}' is already locked", path)));
+        }
+
+        self.lock_repository.create_lock(lock).await
+    }
+
+    async fn refresh_lock(
+        &self,
+        path: &str,
+        user_id: &str,
+        tokens: &[String],
+        timeout_secs: Option<u64>,
+    ) -> Result<ResourceLock, DomainError> {
+        let path = normalize_path(path);
+
+        for token in tokens {
+            let Some(mut lock) = self.lock_repository.get_lock(token).await? else { continue };
+            if lock.covers(&path) && lock.owner_id == user_id {
+                lock.refresh(self.timeout(timeout_secs));
+                self.lock_repository.update_lock_expiry(&lock.token, lock.timeout_secs, lock.expires_at).await?;
+                return Ok(lock);
+            }
+        }
+
+        Err(DomainError::not_found("Lock", path))
+    }
+
+    async fn unlock(&self, path: &str, user_id: &str, token: &str) -> Result<(), DomainError> {
+        let path = normalize_path(path);
+        let lock = self.lock_repository.get_lock(token).await?
+            .ok_or_else(|| DomainError::not_found("Lock", token))?;
+
+        if !lock.covers(&path) {
+            return Err(DomainError::validation_error(format!("Lock token does not apply to '{}'", path)));
+        }
+        if lock.owner_id != user_id {
+            return Err(DomainError::access_denied("Lock", "Only the lock owner can remove it"));
+        }
+
+        self.lock_repository.delete_lock(token).await?;
+        Ok(())
+    }
+
+    async fn check_write(
+        &self,
+        path: &str,
+        user_id: &str,
+        tokens: &[String],
+        changes_membership: bool,
+    ) -> Result<(), DomainError> {
+        let path = normalize_path(path);
+        let parent = parent_path(&path);
+
+        let locks: Vec<ResourceLock> = self.lock_repository.find_locks_affecting(&path).await?
+            .into_iter()
+            .filter(|lock| {
+                lock.covers(&path)
+                    || (changes_membership && (parent == Some(lock.path.as_str()) || is_descendant(&lock.path, &path)))
+            })
+            .collect();
+
+        let held = |lock: &ResourceLock| lock.owner_id == user_id && tokens.contains(&lock.token);
+
+        // Every exclusive lock needs its token; for shared locks one of the
+        // resource's shared locks is enough
+        let blocking = locks.iter().find(|lock| {
+            !held(lock)
+                && (lock.exclusive
+                    || !locks.iter().any(|other| !other.exclusive && other.path == lock.path && held(other)))
+        });
+
+        match blocking {
+            Some(lock) => Err(DomainError::locked(
+                "Lock",
+                format!("'{}' is locked", if lock.path.is_empty() { "/" } else { &lock.path }),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    async fn release_locks(&self, path: &str) -> Result<(), DomainError> {
+        self.lock_repository.delete_locks_under(&normalize_path(path)).await?;
+        Ok(())
+    }
+
+    async fn expire_locks(&self) -> Result<u64, DomainError> {
+        self.lock_repository.delete_expired_locks().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Utc};
+    use crate::common::errors::ErrorKind;
+    use crate::domain::entities::lock::path_and_ancestors;
+    use crate::domain::repositories::lock_repository::LockRepositoryResult;
+
+    #[derive(Default)]
+    struct InMemoryLockRepository {
+        locks: std::sync::Mutex<Vec<ResourceLock>>,
+    }
+
+    #[async_trait]
+    impl LockRepository for InMemoryLockRepository {
+        async fn create_lock(&self, lock: ResourceLock) -> LockRepositoryResult<ResourceLock> {
+            self.locks.lock().unwrap().push(lock.clone());
+            Ok(lock)
+        }
+
+        async fn get_lock(&self, token: &str) -> LockRepositoryResult<Option<ResourceLock>> {
+            Ok(self.locks.lock().unwrap().iter().find(|l| l.token == token && l.expires_at > Utc::now()).cloned())
+        }
+
+        async fn find_locks_affecting(&self, path: &str) -> LockRepositoryResult<Vec<ResourceLock>> {
+            let ancestors = path_and_ancestors(path);
+            Ok(self.locks.lock().unwrap().iter()
+                .filter(|l| l.expires_at > Utc::now() && (ancestors.contains(&l.path) || is_descendant(&l.path, path)))
+                .cloned()
+                .collect())
+        }
+
+        async fn update_lock_expiry(&self, token: &str, timeout_secs: u64, expires_at: DateTime<Utc>) -> LockRepositoryResult<()> {
+            if let Some(lock) = self.locks.lock().unwrap().iter_mut().find(|l| l.token == token) {
+                lock.timeout_secs = timeout_secs;
+                lock.expires_at = expires_at;
+            }
+            Ok(())
+        }
+
+        async fn delete_lock(&self, token: &str) -> LockRepositoryResult<bool> {
+            let mut locks = self.locks.lock().unwrap();
+            let before = locks.len();
+            locks.retain(|l| l.token != token);
+            Ok(locks.len() != before)
+        }
+
+        async fn delete_locks_under(&self, path: &str) -> LockRepositoryResult<u64> {
+            let mut locks = self.locks.lock().unwrap();
+            let before = locks.len();
+            locks.retain(|l| l.path != path && !is_descendant(&l.path, path));
+            Ok((before - locks.len()) as u64)
+        }
+
+        async fn delete_expired_locks(&self) -> LockRepositoryResult<u64> {
+            let mut locks = self.locks.lock().unwrap();
+            let before = locks.len();
+            locks.retain(|l| l.expires_at > Utc::now());
+            Ok((before - locks.len()) as u64)
+        }
+    }
+
+    fn service() -> LockService {
+        LockService::new(Arc::new(InMemoryLockRepository::default()), 600, 3600)
+    }
+
+    fn request(path: &str, user: &str, exclusive: bool, infinite_depth: bool) -> LockRequest {
+        LockRequest {
+            path: path.to_string(),
+            owner_id: user.to_string(),
+            owner_info: None,
+            exclusive,
+            infinite_depth,
+            timeout_secs: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_conflicting_locks_are_rejected() {
+        let service = service();
+        let lock = service.lock(request("/docs/a.txt", "alice", true, false)).await.unwrap();
+        assert_eq!(lock.path, "docs/a.txt");
+        assert_eq!(lock.timeout_secs, 600);
+
+        let err = service.lock(request("docs", "bob", true, true)).await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Locked);
+
+        // A depth 0 lock on the folder does not reach the file
+        service.lock(request("docs", "bob", true, false)).await.unwrap();
+
+        // Shared locks coexist
+        service.lock(request("shared.txt", "alice", false, false)).await.unwrap();
+        service.lock(request("shared.txt", "bob", false, false)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_writes_need_the_lock_token() {
+        let service = service();
+        let lock = service.lock(request("docs", "alice", true, true)).await.unwrap();
+        let tokens = vec![lock.token.clone()];
+
+        let err = service.check_write("docs/a.txt", "alice", &[], false).await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Locked);
+        service.check_write("docs/a.txt", "alice", &tokens, false).await.unwrap();
+
+        // Another user cannot borrow the token
+        assert!(service.check_write("docs/a.txt", "bob", &tokens, false).await.is_err());
+        service.check_write("other.txt", "bob", &[], true).await.unwrap();
+
+        // Deleting the parent of a locked resource also needs the token
+        assert!(service.check_write("", "bob", &[], true).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_depth_zero_lock_protects_membership() {
+        let service = service();
+        let lock = service.lock(request("docs", "alice", true, false)).await.unwrap();
+
+        // Updating an existing member is fine, creating or deleting one is not
+        service.check_write("docs/a.txt", "bob", &[], false).await.unwrap();
+        assert!(service.check_write("docs/a.txt", "bob", &[], true).await.is_err());
+        service.check_write("docs/a.txt", "alice", &[lock.token], true).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_refresh_and_unlock() {
+        let service = service();
+        let lock = service.lock(request("a.txt", "alice", true, false)).await.unwrap();
+        let tokens = vec![lock.token.clone()];
+
+        let refreshed = service.refresh_lock("a.txt", "alice", &tokens, Some(86400)).await.unwrap();
+        assert_eq!(refreshed.timeout_secs, 3600);
+        assert_eq!(service.refresh_lock("b.txt", "alice", &tokens, None).await.unwrap_err().kind, ErrorKind::NotFound);
+
+        assert_eq!(service.unlock("a.txt", "bob", &lock.token).await.unwrap_err().kind, ErrorKind::AccessDenied);
+        assert_eq!(service.unlock("b.txt", "alice", &lock.token).await.unwrap_err().kind, ErrorKind::InvalidInput);
+        service.unlock("a.txt", "alice", &lock.token).await.unwrap();
+        service.check_write("a.txt", "bob", &[], true).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_release_locks_under_path() {
+        let service = service();
+        service.lock(request("docs/a.txt", "alice", true, false)).await.unwrap();
+        service.release_locks("docs").await.unwrap();
+        service.check_write("docs/a.txt", "bob", &[], true).await.unwrap();
+    }
+}
@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Intentos fallidos seguidos tras los que se bloquea una cuenta
+pub const MAX_FAILED_LOGINS: u32 = 5;
+
+/// Tiempo que dura el bloqueo, contado desde el último intento fallido
+pub const LOGIN_LOCKOUT: Duration = Duration::from_secs(15 * 60);
+
+/// Fallos recientes de una cuenta
+struct Failures {
+    count: u32,
+    last_failure: Instant,
+}
+
+/// Limita los intentos de acceso fallidos por nombre de usuario.
+///
+/// Tras `max_failures` fallos seguidos la cuenta queda bloqueada hasta que
+/// pasa `lockout` sin más fallos; un acceso correcto pone la cuenta a cero.
+/// Lo comparten el acceso con contraseña y la autenticación Basic (contraseña
+/// de la cuenta o de aplicación), así que un cliente DAV no sirve para probar
+/// contraseñas sin límite. El estado vive en memoria.
+pub struct LoginThrottle {
+    max_failures: u32,
+    lockout: Duration,
+    failures: Mutex<HashMap<String, Failures>>,
+}
+
+impl LoginThrottle {
+    pub fn new(max_failures: u32, lockout: Duration) -> Self {
+        Self {
+            max_failures: max_failures.max(1),
+            lockout,
+            failures: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Los nombres de usuario se comparan sin distinguir mayúsculas
+    fn key(username: &str) -> String {
+        username.trim().to_lowercase()
+    }
+
+    /// Si la cuenta está bloqueada por demasiados fallos
+    pub fn is_locked(&self, username: &str) -> bool {
+        let failures = self.failures.lock().unwrap();
+        failures.get(&Self::key(username)).is_some_and(|entry| {
+            entry.count >= self.max_failures && entry.last_failure.elapsed() < self.lockout
+        })
+    }
+
+    /// Anota un intento fallido
+    pub fn record_failure(&self, username: &str) {
+        let mut failures = self.failures.lock().unwrap();
+        let lockout = self.lockout;
+        failures.retain(|_, entry| entry.last_failure.elapsed() < lockout);
+        let entry = failures.entry(Self::key(username)).or_insert(Failures { count: 0, last_failure: Instant::now() });
+        entry.count += 1;
+        entry.last_failure = Instant::now();
+    }
+
+    /// Olvida los fallos de una cuenta tras un acceso correcto
+    pub fn record_success(&self, username: &str) {
+        self.failures.lock().unwrap().remove(&Self::key(username));
+    }
+}
+
+impl Default for LoginThrottle {
+    fn default() -> Self {
+        Self::new(MAX_FAILED_LOGINS, LOGIN_LOCKOUT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locks_after_repeated_failures() {
+        let throttle = LoginThrottle::new(3, Duration::from_secs(60));
+        for _ in 0..2 {
+            throttle.record_failure("Alice");
+        }
+        assert!(!throttle.is_locked("alice"));
+
+        throttle.record_failure("alice");
+        assert!(throttle.is_locked("ALICE"));
+        assert!(!throttle.is_locked("bob"));
+
+        throttle.record_success("alice");
+        assert!(!throttle.is_locked("alice"));
+    }
+
+    #[test]
+    fn test_lockout_expires() {
+        let throttle = LoginThrottle::new(1, Duration::from_millis(0));
+        throttle.record_failure("alice");
+        assert!(!throttle.is_locked("alice"));
+    }
+}
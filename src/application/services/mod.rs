@@ -1,24 +1,82 @@
+pub mod announcement_service;
+pub mod archive_service;
 pub mod auth_application_service;
 pub mod batch_operations;
 pub mod calendar_service;
+pub mod calendar_ical_service;
+pub mod carddav_service;
 pub mod contact_service;
+pub mod contact_import_service;
+pub mod dav_access_service;
+pub mod dav_multiget_service;
+pub mod dav_principal_service;
+pub mod dav_sync_service;
+pub mod dav_validation_service;
+pub mod dead_property_service;
+pub mod demo_service;
+pub mod directory_auth_service;
+pub mod duplicate_photo_service;
+pub mod external_storage_service;
 pub mod favorites_service;
+pub mod feature_flag_service;
 pub mod file_management_service;
 pub mod file_retrieval_service;
 pub mod file_service;
 pub mod file_upload_service;
 pub mod file_use_case_factory;
+pub mod folder_export_service;
 pub mod folder_service;
+pub mod free_name_service;
+pub mod gallery_service;
 pub mod i18n_application_service;
+pub mod image_tagging_service;
+pub mod lock_service;
+pub mod login_throttle;
+pub mod ownership_service;
+pub mod quota_policy_service;
+pub mod quota_service;
 pub mod recent_service;
+pub mod scheduling_service;
 pub mod search_service;
+pub mod share_access_service;
+pub mod share_email_access_service;
 pub mod share_service;
 pub mod storage_mediator;
 pub mod storage_usage_service;
+pub mod sync_conflict_service;
+pub mod theme_service;
 pub mod trash_service;
+pub mod undo_service;
+pub mod presence_service;
+pub mod user_quarantine_service;
+pub mod app_password_service;
+pub mod template_service;
+pub mod oidc_service;
+pub mod notification_service;
+pub mod upload_session_service;
+pub mod user_group_service;
+pub mod user_skeleton_service;
+pub mod zip_export_service;
+pub mod dav_transfer_service;
+pub mod integrity_service;
+pub mod email_notification_service;
+pub mod execution_plan;
+pub mod realtime_event_service;
+pub mod event_bus_service;
+pub mod domain_event_subscribers;
+pub mod activity_service;
+pub mod audit_log_service;
+pub mod webhook_service;
+pub mod full_text_index_service;
+pub mod tag_service;
+pub mod comment_service;
 
 #[cfg(test)]
 mod trash_service_test;
+#[cfg(test)]
+pub(crate) mod test_users;
+#[cfg(test)]
+pub(crate) mod test_access;
 
 // Re-exportar para facilitar acceso
 pub use file_upload_service::FileUploadService;
@@ -0,0 +1,268 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::application::dtos::notification_dto::{JobFailure, MentionEventDto, NotificationContentDto, NotificationDto, ShareEventDto};
+use crate::application::ports::email_notification_ports::{EmailEvent, EmailNotificationPort};
+use crate::application::ports::notification_ports::NotificationUseCase;
+use crate::application::ports::realtime_ports::{RealtimeAudience, RealtimeEvent, RealtimeEventPort};
+use crate::common::errors::DomainError;
+use crate::domain::entities::share_notification::{ShareChange, ShareNotification};
+use crate::domain::repositories::share_notification_repository::ShareNotificationRepository;
+
+/// Notificaciones que se conservan por usuario; al superarlas se descartan
+/// las más antiguas
+const MAX_NOTIFICATIONS_PER_USER: usize = 100;
+
+/// Centro de notificaciones de los usuarios.
+///
+/// Cuando una descarga ZIP, una importación o una exportación falla, el error
+/// queda aquí con su causa y la petición que lo reintenta hasta que el
+/// usuario lo descarta, en lugar de perderse en los logs. Los fallos se
+/// guardan en memoria, igual que los tokens de deshacer.
+///
+/// También avisa a los usuarios de lo que otros comparten con ellos o dejan
+/// de compartir. Esos avisos van a base de datos si hay repositorio, para que
+/// sobrevivan a un reinicio y esperen a quien no ha entrado todavía, y lo
+/// compartido se avisa además por correo si está configurado y al momento a
+/// las sesiones abiertas del destinatario.
+///
+/// Las menciones en comentarios se guardan en memoria como los fallos: el
+/// comentario sigue ahí aunque el aviso se pierda en un reinicio.
+pub struct NotificationService {
+    // Clave: ID de usuario (vacío si la autenticación está desactivada)
+    notifications: Mutex<HashMap<String, VecDeque<NotificationDto>>>,
+    repository: Option<Arc<dyn ShareNotificationRepository>>,
+    email: Option<Arc<dyn EmailNotificationPort>>,
+    realtime_events: Option<Arc<dyn RealtimeEventPort>>,
+}
+
+impl NotificationService {
+    pub fn new() -> Self {
+        Self {
+            notifications: Mutex::new(HashMap::new()),
+            repository: None,
+            email: None,
+            realtime_events: None,
+        }
+    }
+
+    /// Guarda los avisos de compartición en base de datos
+    pub fn with_repository(mut self, repository: Arc<dyn ShareNotificationRepository>) -> Self {
+        self.repository = Some(repository);
+        self
+    }
+
+    /// Avisa también por correo de lo que se comparte con cada usuario
+    pub fn with_email(mut self, email: Arc<dyn EmailNotificationPort>) -> Self {
+        self.email = Some(email);
+        self
+    }
+
+    /// Avisa al momento a los clientes conectados de lo que se comparte
+    pub fn with_realtime_events(mut self, realtime_events: Arc<dyn RealtimeEventPort>) -> Self {
+        self.realtime_events = Some(realtime_events);
+        self
+    }
+
+    fn user_key(user_id: Option<&str>) -> String {
+        user_id.unwrap_or_default().to_string()
+    }
+
+    async fn push(&self, user_id: Option<&str>, notification: NotificationDto) {
+        let mut notifications = self.notifications.lock().await;
+        let user_notifications = notifications.entry(Self::user_key(user_id)).or_default();
+        user_notifications.push_front(notification);
+        user_notifications.truncate(MAX_NOTIFICATIONS_PER_USER);
+    }
+
+    /// Repositorio de avisos, si lo hay y la petición tiene usuario
+    fn stored<'a>(&self, user_id: Option<&'a str>) -> Option<(&Arc<dyn ShareNotificationRepository>, &'a str)> {
+        self.repository.as_ref().zip(user_id)
+    }
+}
+
+impl Default for NotificationService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl NotificationUseCase for NotificationService {
+    async fn report_job_failure(&self, user_id: Option<&str>, failure: JobFailure) -> NotificationDto {
+        tracing::warn!("Job {} ({:?}) failed: {}", failure.job_id, failure.kind, failure.error);
+        let notification = NotificationDto {
+            id: Uuid::new_v4().to_string(),
+            content: NotificationContentDto::JobFailure {
+                job_id: failure.job_id,
+                kind: failure.kind,
+                error: failure.error,
+                retry: failure.retry,
+            },
+            created_at: Utc::now(),
+            read: false,
+        };
+
+        self.push(user_id, notification.clone()).await;
+        notification
+    }
+
+    async fn notify_share(&self, recipients: &[String], event: ShareEventDto) {
+        if let Some(email) = self.email.as_ref().filter(|_| event.change == ShareChange::Shared) {
+            for user_id in recipients {
+                email.notify(user_id, EmailEvent::ShareReceived {
+                    item_kind: event.item_kind,
+                    item_name: event.item_name.clone(),
+                    actor_name: event.actor_name.clone(),
+                });
+            }
+        }
+
+        if let Some(realtime_events) = self.realtime_events.as_ref().filter(|_| event.change == ShareChange::Shared) {
+            for user_id in recipients {
+                realtime_events.publish(RealtimeAudience::User(user_id.clone()), RealtimeEvent::ShareReceived {
+                    item_kind: event.item_kind,
+                    item_id: event.item_id.clone(),
+                    item_name: event.item_name.clone(),
+                    actor_name: event.actor_name.clone(),
+                });
+            }
+        }
+
+        let notifications: Vec<ShareNotification> = recipients.iter()
+            .map(|user_id| ShareNotification::new(
+                user_id.clone(),
+                event.change,
+                event.item_kind,
+                event.item_id.clone(),
+                event.item_name.clone(),
+                event.actor_id.clone(),
+                event.actor_name.clone(),
+            ))
+            .collect();
+
+        match &self.repository {
+            Some(repository) => {
+                if let Err(e) = repository.create_notifications(&notifications).await {
+                    tracing::warn!("No se pudieron guardar los avisos de compartición de {}: {}", event.item_id, e);
+                }
+            }
+            None => {
+                for notification in notifications {
+                    let user_id = notification.user_id.clone();
+                    self.push(Some(&user_id), notification.into()).await;
+                }
+            }
+        }
+    }
+
+    async fn notify_mention(&self, recipients: &[String], event: MentionEventDto) {
+        for user_id in recipients {
+            let notification = NotificationDto {
+                id: Uuid::new_v4().to_string(),
+                content: NotificationContentDto::Mention(event.clone()),
+                created_at: Utc::now(),
+                read: false,
+            };
+            self.push(Some(user_id), notification).await;
+        }
+    }
+
+    async fn list_notifications(&self, user_id: Option<&str>) -> Vec<NotificationDto> {
+        let mut list: Vec<NotificationDto> = {
+            let notifications = self.notifications.lock().await;
+            notifications.get(&Self::user_key(user_id))
+                .map(|user_notifications| user_notifications.iter().cloned().collect())
+                .unwrap_or_default()
+        };
+
+        if let Some((repository, user_id)) = self.stored(user_id) {
+            match repository.list_notifications(user_id, MAX_NOTIFICATIONS_PER_USER as i64).await {
+                Ok(stored) => {
+                    list.extend(stored.into_iter().map(NotificationDto::from));
+                    list.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+                    list.truncate(MAX_NOTIFICATIONS_PER_USER);
+                }
+                Err(e) => tracing::warn!("No se pudieron leer los avisos de compartición de {}: {}", user_id, e),
+            }
+        }
+
+        list
+    }
+
+    async fn mark_read(&self, user_id: Option<&str>, notification_id: &str) -> Result<NotificationDto, DomainError> {
+        {
+            let mut notifications = self.notifications.lock().await;
+            let notification = notifications.get_mut(&Self::user_key(user_id))
+                .and_then(|user_notifications| user_notifications.iter_mut().find(|n| n.id == notification_id));
+            if let Some(notification) = notification {
+                notification.read = true;
+                return Ok(notification.clone());
+            }
+        }
+
+        if let Some((repository, user_id)) = self.stored(user_id) {
+            if let Some(notification) = repository.mark_read(user_id, notification_id, Utc::now()).await? {
+                return Ok(notification.into());
+            }
+        }
+
+        Err(DomainError::not_found("Notification", notification_id))
+    }
+
+    async fn dismiss(&self, user_id: Option<&str>, notification_id: &str) -> Result<(), DomainError> {
+        {
+            let mut notifications = self.notifications.lock().await;
+            if let Some(user_notifications) = notifications.get_mut(&Self::user_key(user_id)) {
+                let before = user_notifications.len();
+                user_notifications.retain(|n| n.id != notification_id);
+                if user_notifications.len() != before {
+                    return Ok(());
+                }
+            }
+        }
+
+        if let Some((repository, user_id)) = self.stored(user_id) {
+            if repository.delete_notification(user_id, notification_id).await? {
+                return Ok(());
+            }
+        }
+
+        Err(DomainError::not_found("Notification", notification_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::share_notification::SharedItemKind;
+
+    #[tokio::test]
+    async fn test_share_notices_without_repository() {
+        let service = NotificationService::new();
+        service.notify_share(&["ana".to_string(), "bob".to_string()], ShareEventDto {
+            change: ShareChange::Shared,
+            item_kind: SharedItemKind::Folder,
+            item_id: "d1".to_string(),
+            item_name: "Fotos".to_string(),
+            actor_id: Some("carla".to_string()),
+            actor_name: Some("carla".to_string()),
+        }).await;
+
+        let notices = service.list_notifications(Some("ana")).await;
+        assert_eq!(notices.len(), 1);
+        assert!(matches!(&notices[0].content, NotificationContentDto::Share(event) if event.item_name == "Fotos"));
+
+        let read = service.mark_read(Some("ana"), &notices[0].id).await.unwrap();
+        assert!(read.read);
+        assert!(service.dismiss(Some("bob"), &notices[0].id).await.is_err());
+        service.dismiss(Some("ana"), &notices[0].id).await.unwrap();
+        assert!(service.list_notifications(Some("ana")).await.is_empty());
+        assert_eq!(service.list_notifications(Some("bob")).await.len(), 1);
+    }
+}
@@ -0,0 +1,302 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::{DateTime, Duration, Utc};
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use tokio::sync::Mutex;
+
+use crate::application::dtos::user_dto::{AuthResponseDto, RegisterDto, SessionClient};
+use crate::application::ports::auth_ports::{OidcIdentityStoragePort, UserStoragePort};
+use crate::application::ports::oidc_ports::{OidcClaims, OidcProviderPort};
+use crate::application::services::auth_application_service::AuthApplicationService;
+use crate::common::errors::DomainError;
+use crate::domain::entities::oidc_identity::OidcIdentity;
+
+/// Tiempo que tiene el usuario para volver del proveedor tras iniciar el acceso
+pub const PENDING_LOGIN_TTL_MINUTES: i64 = 10;
+
+/// Longitud máxima de un nombre de usuario local
+const MAX_USERNAME_LEN: usize = 32;
+
+/// Inicio de sesión en curso, a la espera de que el proveedor devuelva al
+/// usuario con el código de autorización
+struct PendingLogin {
+    nonce: String,
+    code_verifier: String,
+    /// Usuario local al que se vinculará la cuenta, si se está vinculando
+    link_user_id: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+/// Servicio de inicio de sesión con un proveedor OpenID Connect.
+///
+/// Implementa el flujo de código de autorización con PKCE. Al volver del
+/// proveedor, la cuenta externa se resuelve a un usuario local en este orden:
+/// la cuenta ya vinculada, el usuario que pidió vincularla, el usuario local
+/// con el mismo correo (solo si el proveedor lo ha verificado) y, si está
+/// permitido, un usuario nuevo creado en ese momento.
+///
+/// El `state` de cada acceso se guarda además en una cookie del navegador
+/// que lo inicia, y la vuelta del proveedor solo se acepta en ese navegador:
+/// así nadie puede hacer que otro abra una sesión o vincule una cuenta ajena.
+pub struct OidcLoginService {
+    provider: Arc<dyn OidcProviderPort>,
+    identity_storage: Arc<dyn OidcIdentityStoragePort>,
+    user_storage: Arc<dyn UserStoragePort>,
+    auth_service: Arc<AuthApplicationService>,
+    auto_provision: bool,
+    provider_name: String,
+    /// Si la cookie del `state` solo viaja por HTTPS
+    secure_cookie: bool,
+    // Clave: parámetro `state` enviado al proveedor
+    pending: Mutex<HashMap<String, PendingLogin>>,
+}
+
+impl OidcLoginService {
+    pub fn new(
+        provider: Arc<dyn OidcProviderPort>,
+        identity_storage: Arc<dyn OidcIdentityStoragePort>,
+        user_storage: Arc<dyn UserStoragePort>,
+        auth_service: Arc<AuthApplicationService>,
+        auto_provision: bool,
+        provider_name: String,
+    ) -> Self {
+        Self {
+            provider,
+            identity_storage,
+            user_storage,
+            auth_service,
+            auto_provision,
+            provider_name,
+            secure_cookie: false,
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Marca la cookie del `state` como `Secure`, cuando el retorno es HTTPS
+    pub fn with_secure_cookie(mut self, secure_cookie: bool) -> Self {
+        self.secure_cookie = secure_cookie;
+        self
+    }
+
+    /// Nombre del proveedor para mostrar en la página de acceso
+    pub fn provider_name(&self) -> &str {
+        &self.provider_name
+    }
+
+    /// Si la cookie del `state` solo debe enviarse por HTTPS
+    pub fn secure_cookie(&self) -> bool {
+        self.secure_cookie
+    }
+
+    /// Inicia un acceso y devuelve la URL del proveedor a la que se envía al
+    /// navegador junto con el `state`, que el navegador debe guardar para la
+    /// vuelta. Con `link_user_id`, la cuenta externa se vincula a ese usuario
+    /// en lugar de abrir una sesión con otro.
+    pub async fn begin_login(&self, link_user_id: Option<String>) -> Result<(String, String), DomainError> {
+        let state = random_token();
+        let nonce = random_token();
+        let code_verifier = random_token();
+        let code_challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()));
+
+        let url = self.provider.authorization_url(&state, &nonce, &code_challenge).await?;
+
+        let now = Utc::now();
+        let mut pending = self.pending.lock().await;
+        pending.retain(|_, login| !is_expired(login, now));
+        pending.insert(state.clone(), PendingLogin { nonce, code_verifier, link_user_id, created_at: now });
+        Ok((url, state))
+    }
+
+    /// Completa el acceso con el código devuelto por el proveedor y abre una
+    /// sesión para el usuario local correspondiente. `browser_state` es el
+    /// `state` que guardó el navegador al iniciar el acceso. Los accesos
+    /// rechazados quedan en el registro de auditoría.
+    pub async fn complete_login(
+        &self,
+        code: &str,
+        state: &str,
+        browser_state: Option<&str>,
+        client: SessionClient,
+    ) -> Result<AuthResponseDto, DomainError> {
+        let ip_address = client.ip_address.clone();
+        let mut claimed_user = None;
+        let result = self.complete_login_for(code, state, browser_state, client, &mut claimed_user).await;
+        if let Err(e) = &result {
+            self.auth_service.audit_login_failure(claimed_user, ip_address, format!("OIDC: {}", e.message)).await;
+        }
+        result
+    }
+
+    /// Completa el acceso; deja en `claimed_user` la cuenta que dice ser el
+    /// usuario en cuanto el proveedor la da, para la auditoría
+    async fn complete_login_for(
+        &self,
+        code: &str,
+        state: &str,
+        browser_state: Option<&str>,
+        client: SessionClient,
+        claimed_user: &mut Option<String>,
+    ) -> Result<AuthResponseDto, DomainError> {
+        if !browser_state.is_some_and(|browser_state| bool::from(browser_state.as_bytes().ct_eq(state.as_bytes()))) {
+            return Err(DomainError::access_denied("OIDC", "Login attempt was not started in this browser"));
+        }
+        let login = self.pending.lock().await.remove(state)
+            .filter(|login| !is_expired(login, Utc::now()))
+            .ok_or_else(|| DomainError::access_denied("OIDC", "Unknown or expired login attempt"))?;
+
+        let claims = self.provider.exchange_code(code, &login.code_verifier).await?;
+        *claimed_user = claims.username.clone()
+            .or_else(|| claims.email.clone())
+            .or_else(|| Some(claims.subject.clone()));
+        if claims.nonce.as_deref() != Some(login.nonce.as_str()) {
+            return Err(DomainError::access_denied("OIDC", "ID token nonce does not match"));
+        }
+
+        let user_id = self.resolve_user(&claims, login.link_user_id).await?;
+        self.auth_service.login_user(&user_id, client).await
+    }
+
+    /// Busca o crea el usuario local de una cuenta externa
+    async fn resolve_user(&self, claims: &OidcClaims, link_user_id: Option<String>) -> Result<String, DomainError> {
+        if let Some(identity) = self.identity_storage.find_identity(&claims.issuer, &claims.subject).await? {
+            if link_user_id.is_some_and(|link_user_id| link_user_id != identity.user_id) {
+                return Err(DomainError::validation_error("This account is already linked to another user"));
+            }
+            if let Err(e) = self.identity_storage.touch_identity(&claims.issuer, &claims.subject).await {
+                tracing::warn!("No se pudo anotar el acceso de la cuenta OIDC {}: {}", claims.subject, e);
+            }
+            return Ok(identity.user_id);
+        }
+
+        let user_id = if let Some(user_id) = link_user_id {
+            user_id
+        } else if let Some(user_id) = self.find_user_by_verified_email(claims).await {
+            user_id
+        } else if self.auto_provision {
+            self.provision_user(claims).await?
+        } else {
+            return Err(DomainError::access_denied("OIDC", "No local account is linked to this identity"));
+        };
+
+        let now = Utc::now();
+        self.identity_storage.link_identity(OidcIdentity {
+            issuer: claims.issuer.clone(),
+            subject: claims.subject.clone(),
+            user_id: user_id.clone(),
+            email: claims.email.clone(),
+            created_at: now,
+            last_login_at: Some(now),
+        }).await?;
+
+        tracing::info!("Cuenta OIDC {} de {} vinculada al usuario {}", claims.subject, claims.issuer, user_id);
+        Ok(user_id)
+    }
+
+    /// Usuario local con el mismo correo; solo se confía en correos verificados
+    /// para que nadie se apropie de una cuenta cambiando su correo en el proveedor
+    async fn find_user_by_verified_email(&self, claims: &OidcClaims) -> Option<String> {
+        let email = claims.email.as_deref().filter(|_| claims.email_verified)?;
+        self.user_storage.get_user_by_email(email).await.ok().map(|user| user.id().to_string())
+    }
+
+    /// Crea el usuario local de una cuenta externa que entra por primera vez
+    async fn provision_user(&self, claims: &OidcClaims) -> Result<String, DomainError> {
+        let base = sanitize_username(
+            claims.username.as_deref()
+                .or(claims.email.as_deref().and_then(|email| email.split('@').next()))
+                .or(claims.name.as_deref())
+                .unwrap_or(&claims.subject),
+        );
+
+        let mut username = None;
+        for attempt in 1..=100 {
+            let candidate = username_candidate(&base, attempt);
+            // "admin" recibiría el rol de administrador al registrarse
+            if candidate.eq_ignore_ascii_case("admin") {
+                continue;
+            }
+            if self.user_storage.get_user_by_username(&candidate).await.is_err() {
+                username = Some(candidate);
+                break;
+            }
+        }
+        let username = username.ok_or_else(|| DomainError::internal_error("OIDC", "Could not find a free username"))?;
+
+        // Sin correo del proveedor se usa una dirección que nunca se entregará
+        let email = claims.email.clone()
+            .unwrap_or_else(|| format!("{}@oidc.invalid", username));
+
+        // La contraseña local no se comunica a nadie: la cuenta solo entra por el proveedor
+        let user = self.auth_service.register(RegisterDto {
+            username,
+            email,
+            password: random_token(),
+            role: Some("user".to_string()),
+        }).await?;
+
+        tracing::info!("Usuario {} creado en su primer acceso por OIDC", user.username);
+        Ok(user.id)
+    }
+}
+
+fn is_expired(login: &PendingLogin, now: DateTime<Utc>) -> bool {
+    now - login.created_at > Duration::minutes(PENDING_LOGIN_TTL_MINUTES)
+}
+
+/// Valor aleatorio de 256 bits codificado para usarse en URLs
+fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Convierte un claim en un nombre de usuario válido: solo letras, dígitos,
+/// `.`, `-` y `_`, de 3 a 32 caracteres
+fn sanitize_username(raw: &str) -> String {
+    let mut username: String = raw.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '_' })
+        .collect();
+    username = username.trim_matches(|c| matches!(c, '.' | '-' | '_')).to_string();
+    username.truncate(MAX_USERNAME_LEN);
+    if username.len() < 3 {
+        username = format!("user_{}", username);
+    }
+    username
+}
+
+/// Nombre a probar en cada intento: el original y después con un sufijo numérico
+fn username_candidate(base: &str, attempt: usize) -> String {
+    if attempt == 1 {
+        return base.to_string();
+    }
+    let suffix = format!("-{}", attempt);
+    let mut candidate = base.to_string();
+    candidate.truncate(MAX_USERNAME_LEN - suffix.len());
+    candidate + &suffix
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_username() {
+        assert_eq!(sanitize_username("jane.doe"), "jane.doe");
+        assert_eq!(sanitize_username("Jane Doe!"), "Jane_Doe");
+        assert_eq!(sanitize_username("jo"), "user_jo");
+        assert_eq!(sanitize_username("ñ"), "user_");
+        assert_eq!(sanitize_username(&"a".repeat(40)).len(), MAX_USERNAME_LEN);
+    }
+
+    #[test]
+    fn test_username_candidates_stay_within_limit() {
+        assert_eq!(username_candidate("jane", 1), "jane");
+        assert_eq!(username_candidate("jane", 2), "jane-2");
+        assert_eq!(username_candidate(&"a".repeat(32), 15).len(), MAX_USERNAME_LEN);
+    }
+}
@@ -0,0 +1,200 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::application::ports::auth_ports::UserStoragePort;
+use crate::application::ports::favorites_ports::FavoritesUseCase;
+use crate::application::ports::inbound::{FileUseCase, FolderUseCase};
+use crate::application::ports::ownership_ports::{ItemOwner, ItemOwnershipUseCase, TransferItem, TransferPlan};
+use crate::application::ports::quota_ports::StorageQuotaUseCase;
+use crate::application::ports::share_ports::ShareUseCase;
+use crate::common::errors::DomainError;
+use crate::domain::entities::share::ShareItemType;
+use crate::domain::services::ownership_service::{owner_of_path, CrossUserMovePolicy, TransferOperation};
+
+/// Servicio que aplica las reglas de propiedad al mover o copiar elementos
+/// entre carpetas personales de distintos usuarios.
+///
+/// El propietario de un elemento es el de la carpeta personal que lo contiene.
+/// Una copia es siempre del propietario del destino, que paga su espacio. Un
+/// movimiento a la carpeta de otro usuario sigue la política de la instancia:
+/// transferir el elemento, convertirlo en una copia o rechazarlo. Al
+/// transferirlo el espacio pasa de una cuenta a otra y se retiran los enlaces
+/// compartidos y favoritos del propietario anterior, que ya no tiene acceso.
+pub struct OwnershipService {
+    user_repository: Arc<dyn UserStoragePort>,
+    file_service: Arc<dyn FileUseCase>,
+    folder_service: Arc<dyn FolderUseCase>,
+    policy: CrossUserMovePolicy,
+    quota_service: Option<Arc<dyn StorageQuotaUseCase>>,
+    share_service: Option<Arc<dyn ShareUseCase>>,
+    favorites_service: Option<Arc<dyn FavoritesUseCase>>,
+}
+
+impl OwnershipService {
+    /// Crea un nuevo servicio de propiedad con la política de la instancia
+    pub fn new(
+        user_repository: Arc<dyn UserStoragePort>,
+        file_service: Arc<dyn FileUseCase>,
+        folder_service: Arc<dyn FolderUseCase>,
+        policy: CrossUserMovePolicy,
+    ) -> Self {
+        Self {
+            user_repository,
+            file_service,
+            folder_service,
+            policy,
+            quota_service: None,
+            share_service: None,
+            favorites_service: None,
+        }
+    }
+
+    /// Cobra el espacio de las transferencias a la cuenta correspondiente
+    pub fn with_quota_service(mut self, quota_service: Arc<dyn StorageQuotaUseCase>) -> Self {
+        self.quota_service = Some(quota_service);
+        self
+    }
+
+    /// Retira los enlaces compartidos del propietario anterior al transferir
+    pub fn with_share_service(mut self, share_service: Arc<dyn ShareUseCase>) -> Self {
+        self.share_service = Some(share_service);
+        self
+    }
+
+    /// Quita los favoritos del propietario anterior al transferir
+    pub fn with_favorites_service(mut self, favorites_service: Arc<dyn FavoritesUseCase>) -> Self {
+        self.favorites_service = Some(favorites_service);
+        self
+    }
+
+    /// Propietario de una ruta; `None` si está fuera de las carpetas
+    /// personales o su usuario ya no existe
+    async fn owner(&self, path: &str) -> Option<ItemOwner> {
+        let username = owner_of_path(path)?;
+        match self.user_repository.get_user_by_username(username).await {
+            Ok(user) => Some(ItemOwner {
+                user_id: user.id().to_string(),
+                username: user.username().to_string(),
+            }),
+            Err(e) => {
+                tracing::warn!("Could not resolve the owner of {}: {}", path, e);
+                None
+            }
+        }
+    }
+
+    /// Tamaño de una carpeta y de todo lo que contiene, junto con sus IDs
+    async fn collect_folder(&self, folder_id: &str, recursive: bool) -> Result<(u64, Vec<(String, ShareItemType)>), DomainError> {
+        let mut size = 0;
+        let mut items = vec![(folder_id.to_string(), ShareItemType::Folder)];
+        if !recursive {
+            return Ok((size, items));
+        }
+
+        let mut pending = vec![folder_id.to_string()];
+        while let Some(current) = pending.pop() {
+            for file in self.file_service.list_files(Some(&current)).await? {
+                size += file.size;
+                items.push((file.id, ShareItemType::File));
+            }
+            for folder in self.folder_service.list_folders(Some(&current)).await? {
+                items.push((folder.id.clone(), ShareItemType::Folder));
+                pending.push(folder.id);
+            }
+        }
+        Ok((size, items))
+    }
+
+    async fn record_usage(&self, user_id: &str, delta_bytes: i64) {
+        if let Some(quotas) = &self.quota_service {
+            if let Err(e) = quotas.record_usage(user_id, delta_bytes).await {
+                tracing::warn!("Could not record storage usage for {}: {}", user_id, e);
+            }
+        }
+    }
+
+    /// Retira los enlaces que no creó el nuevo propietario y los favoritos
+    /// del anterior; un fallo solo deja restos que ya no dan acceso a nada útil
+    async fn revoke_previous_owner(&self, plan: &TransferPlan, from: &ItemOwner, to: &ItemOwner) {
+        for (item_id, item_type) in &plan.items {
+            if let Some(shares) = &self.share_service {
+                match shares.get_shared_links_for_item(item_id, item_type).await {
+                    Ok(links) => {
+                        for link in links.into_iter().filter(|link| link.created_by != to.user_id) {
+                            if let Err(e) = shares.delete_shared_link(&link.id).await {
+                                tracing::warn!("Could not revoke shared link {} of {}: {}", link.id, item_id, e);
+                            }
+                        }
+                    }
+                    Err(e) => tracing::warn!("Could not list the shared links of {}: {}", item_id, e),
+                }
+            }
+            if let Some(favorites) = &self.favorites_service {
+                if let Err(e) = favorites.remove_from_favorites(&from.user_id, item_id, &item_type.to_string()).await {
+                    tracing::warn!("Could not remove {} from the favorites of {}: {}", item_id, from.username, e);
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ItemOwnershipUseCase for OwnershipService {
+    async fn plan_transfer(
+        &self,
+        actor_id: &str,
+        operation: TransferOperation,
+        item: TransferItem,
+        source_path: &str,
+        destination_path: &str,
+    ) -> Result<TransferPlan, DomainError> {
+        let source_owner = self.owner(source_path).await;
+        let destination_owner = self.owner(destination_path).await;
+
+        let operation = self.policy
+            .resolve(
+                operation,
+                source_owner.as_ref().map(|owner| owner.username.as_str()),
+                destination_owner.as_ref().map(|owner| owner.username.as_str()),
+            )
+            .ok_or_else(|| DomainError::access_denied(
+                "Ownership",
+                "Items cannot be moved into another user's folder; copy them instead",
+            ))?;
+
+        let (size, items) = match item {
+            TransferItem::File { id, size } => (size, vec![(id, ShareItemType::File)]),
+            TransferItem::Folder { id, recursive } => self.collect_folder(&id, recursive).await?,
+        };
+
+        let mut plan = TransferPlan { operation, source_owner, destination_owner, charged_user_id: None, size, items };
+        plan.charged_user_id = match operation {
+            TransferOperation::Copy => Some(plan.destination_owner.as_ref()
+                .map_or_else(|| actor_id.to_string(), |owner| owner.user_id.clone())),
+            TransferOperation::Move if plan.transfers_ownership() => plan.destination_owner.as_ref().map(|owner| owner.user_id.clone()),
+            TransferOperation::Move => None,
+        };
+
+        if let (Some(quotas), Some(user_id)) = (&self.quota_service, &plan.charged_user_id) {
+            if plan.size > 0 {
+                quotas.check_quota(user_id, plan.size).await?;
+            }
+        }
+        Ok(plan)
+    }
+
+    async fn complete_transfer(&self, plan: &TransferPlan) {
+        if let Some(user_id) = &plan.charged_user_id {
+            self.record_usage(user_id, plan.size as i64).await;
+        }
+        if let (true, Some(from), Some(to)) = (plan.transfers_ownership(), &plan.source_owner, &plan.destination_owner) {
+            self.record_usage(&from.user_id, -(plan.size as i64)).await;
+            self.revoke_previous_owner(plan, from, to).await;
+            tracing::info!(
+                "Transferred {} item(s) ({} bytes) from {} to {}",
+                plan.items.len(), plan.size, from.username, to.username
+            );
+        }
+    }
+}
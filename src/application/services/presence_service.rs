@@ -0,0 +1,393 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use tokio::sync::{broadcast, Mutex};
+
+use crate::application::dtos::presence_dto::{
+    PresenceDto, PresenceEventDto, PresenceEventKind, PresenceHeartbeatDto,
+};
+use crate::application::ports::inbound::FileUseCase;
+use crate::application::ports::presence_ports::PresenceUseCase;
+use crate::application::ports::share_ports::{ShareAccessUseCase, ShareRecipient, ShareTarget};
+use crate::common::errors::DomainError;
+use crate::domain::entities::share::SharePermissions;
+
+/// Tiempo sin latidos tras el que se da por cerrado un fichero. La interfaz
+/// envía uno cada 20 segundos, así que se toleran un par de latidos perdidos.
+const PRESENCE_TTL_SECS: i64 = 60;
+
+/// Eventos que se guardan para los suscriptores lentos antes de descartarlos
+const EVENT_CAPACITY: usize = 256;
+
+/// Ficheros que un usuario puede tener abiertos, o vigilar en un mismo flujo
+/// de eventos, a la vez
+const MAX_FILES_PER_USER: usize = 64;
+
+/// Servicio que sabe quién tiene abierto cada fichero.
+///
+/// La presencia se mantiene con latidos de la interfaz web o de una sesión
+/// WOPI y caduca cuando dejan de llegar. Cada cambio se publica a los
+/// suscriptores para que la interfaz avise al momento de que otra persona
+/// está editando. Todo vive en memoria: tras un reinicio, el siguiente latido
+/// vuelve a anunciar a cada usuario.
+///
+/// Solo se anuncia o consulta la presencia en ficheros que existen y que el
+/// usuario puede leer.
+pub struct PresenceService {
+    // Clave: ID de fichero; dentro, ID de usuario
+    files: Mutex<HashMap<String, HashMap<String, PresenceDto>>>,
+    events: broadcast::Sender<PresenceEventDto>,
+    ttl: Duration,
+    file_service: Arc<dyn FileUseCase>,
+    share_access: Arc<dyn ShareAccessUseCase>,
+}
+
+impl PresenceService {
+    pub fn new(file_service: Arc<dyn FileUseCase>, share_access: Arc<dyn ShareAccessUseCase>) -> Self {
+        let (events, _) = broadcast::channel(EVENT_CAPACITY);
+        Self {
+            files: Mutex::new(HashMap::new()),
+            events,
+            ttl: Duration::seconds(PRESENCE_TTL_SECS),
+            file_service,
+            share_access,
+        }
+    }
+
+    /// Falla si el fichero no existe o el usuario no puede leerlo
+    async fn ensure_readable(&self, caller: ShareRecipient<'_>, is_admin: bool, file_id: &str) -> Result<(), DomainError> {
+        self.file_service.get_file(file_id).await?;
+        if is_admin {
+            return Ok(());
+        }
+        self.share_access.ensure_access(caller, &ShareTarget::File(file_id.to_string()), SharePermissions::READ).await
+    }
+
+    /// Recibe todos los cambios de presencia, sin filtrar
+    pub fn subscribe(&self) -> broadcast::Receiver<PresenceEventDto> {
+        self.events.subscribe()
+    }
+
+    /// Lanza la tarea que anuncia la salida de quienes dejaron de enviar latidos
+    pub fn start_expiry_job(self: Arc<Self>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(PRESENCE_TTL_SECS as u64 / 2));
+            loop {
+                interval.tick().await;
+                let mut files = self.files.lock().await;
+                self.expire(&mut files, Utc::now());
+            }
+        });
+    }
+
+    fn publish(&self, event: PresenceEventKind, presence: PresenceDto) {
+        // Sin suscriptores el envío falla, y no importa
+        let _ = self.events.send(PresenceEventDto { event, presence });
+    }
+
+    /// Quita las presencias caducadas y anuncia su salida
+    fn expire(&self, files: &mut HashMap<String, HashMap<String, PresenceDto>>, now: DateTime<Utc>) {
+        let deadline = now - self.ttl;
+        files.retain(|_, users| {
+            let expired: Vec<String> = users.iter()
+                .filter(|(_, presence)| presence.last_seen < deadline)
+                .map(|(user_id, _)| user_id.clone())
+                .collect();
+            for user_id in expired {
+                if let Some(presence) = users.remove(&user_id) {
+                    self.publish(PresenceEventKind::Left, presence);
+                }
+            }
+            !users.is_empty()
+        });
+    }
+
+    fn sorted(users: Option<&HashMap<String, PresenceDto>>) -> Vec<PresenceDto> {
+        let mut presences: Vec<PresenceDto> = users.map(|users| users.values().cloned().collect()).unwrap_or_default();
+        presences.sort_by(|a, b| a.since.cmp(&b.since).then_with(|| a.username.cmp(&b.username)));
+        presences
+    }
+
+    /// Quita la presencia más antigua de un usuario si ya tiene abiertos
+    /// tantos ficheros como se permite
+    fn make_room(&self, files: &mut HashMap<String, HashMap<String, PresenceDto>>, user_id: &str) {
+        let open: Vec<(&String, DateTime<Utc>)> = files.iter()
+            .filter_map(|(file_id, users)| users.get(user_id).map(|presence| (file_id, presence.last_seen)))
+            .collect();
+        if open.len() < MAX_FILES_PER_USER {
+            return;
+        }
+        let Some(oldest) = open.iter().min_by_key(|(_, last_seen)| *last_seen).map(|(file_id, _)| (*file_id).clone()) else {
+            return;
+        };
+        if let Some(users) = files.get_mut(&oldest) {
+            if let Some(presence) = users.remove(user_id) {
+                self.publish(PresenceEventKind::Left, presence);
+            }
+            if users.is_empty() {
+                files.remove(&oldest);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl PresenceUseCase for PresenceService {
+    async fn heartbeat(
+        &self,
+        caller: ShareRecipient<'_>,
+        is_admin: bool,
+        file_id: &str,
+        heartbeat: PresenceHeartbeatDto,
+    ) -> Result<Vec<PresenceDto>, DomainError> {
+        self.ensure_readable(caller, is_admin, file_id).await?;
+        let user_id = caller.user_id;
+        let now = Utc::now();
+        let mut files = self.files.lock().await;
+        self.expire(&mut files, now);
+        if !files.get(file_id).is_some_and(|users| users.contains_key(user_id)) {
+            self.make_room(&mut files, user_id);
+        }
+
+        let users = files.entry(file_id.to_string()).or_default();
+        match users.get_mut(user_id) {
+            Some(presence) => {
+                presence.last_seen = now;
+                if presence.mode != heartbeat.mode || presence.source != heartbeat.source {
+                    presence.mode = heartbeat.mode;
+                    presence.source = heartbeat.source;
+                    self.publish(PresenceEventKind::Updated, presence.clone());
+                }
+            }
+            None => {
+                let presence = PresenceDto {
+                    file_id: file_id.to_string(),
+                    user_id: user_id.to_string(),
+                    username: caller.username.to_string(),
+                    mode: heartbeat.mode,
+                    source: heartbeat.source,
+                    since: now,
+                    last_seen: now,
+                };
+                users.insert(user_id.to_string(), presence.clone());
+                self.publish(PresenceEventKind::Joined, presence);
+            }
+        }
+        Ok(Self::sorted(files.get(file_id)))
+    }
+
+    async fn leave(&self, file_id: &str, user_id: &str) {
+        let mut files = self.files.lock().await;
+        let Some(users) = files.get_mut(file_id) else {
+            return;
+        };
+        if let Some(presence) = users.remove(user_id) {
+            self.publish(PresenceEventKind::Left, presence);
+        }
+        if users.is_empty() {
+            files.remove(file_id);
+        }
+    }
+
+    async fn list(&self, caller: ShareRecipient<'_>, is_admin: bool, file_id: &str) -> Result<Vec<PresenceDto>, DomainError> {
+        self.ensure_readable(caller, is_admin, file_id).await?;
+        let mut files = self.files.lock().await;
+        self.expire(&mut files, Utc::now());
+        Ok(Self::sorted(files.get(file_id)))
+    }
+
+    async fn watch(
+        &self,
+        caller: ShareRecipient<'_>,
+        is_admin: bool,
+        file_ids: &[String],
+    ) -> Result<broadcast::Receiver<PresenceEventDto>, DomainError> {
+        if file_ids.len() > MAX_FILES_PER_USER {
+            return Err(DomainError::validation_error(format!(
+                "At most {} files can be watched at once",
+                MAX_FILES_PER_USER
+            )));
+        }
+        for file_id in file_ids {
+            self.ensure_readable(caller, is_admin, file_id).await?;
+        }
+        Ok(self.subscribe())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use bytes::Bytes;
+    use futures::Stream;
+    use crate::application::dtos::file_dto::FileDto;
+    use crate::application::dtos::presence_dto::PresenceMode;
+    use crate::application::services::test_access::HomeOnlyAccess;
+    use crate::common::errors::ErrorKind;
+
+    /// Ficheros existentes; sus IDs son sus rutas, como en `HomeOnlyAccess`
+    struct KnownFiles(HashSet<String>);
+
+    impl KnownFiles {
+        fn with(paths: &[&str]) -> Self {
+            Self(paths.iter().map(|path| path.to_string()).collect())
+        }
+    }
+
+    #[async_trait]
+    impl FileUseCase for KnownFiles {
+        async fn upload_file(&self, _name: String, _folder_id: Option<String>, _content_type: String, _content: Vec<u8>) -> Result<FileDto, DomainError> {
+            unimplemented!()
+        }
+
+        async fn get_file(&self, id: &str) -> Result<FileDto, DomainError> {
+            if self.0.contains(id) {
+                Ok(FileDto { id: id.to_string(), path: id.to_string(), ..FileDto::empty() })
+            } else {
+                Err(DomainError::not_found("File", id))
+            }
+        }
+
+        async fn get_file_by_path(&self, path: &str) -> Result<FileDto, DomainError> {
+            self.get_file(path).await
+        }
+
+        async fn create_file(&self, _parent_path: &str, _filename: &str, _content: &[u8], _content_type: &str) -> Result<FileDto, DomainError> {
+            unimplemented!()
+        }
+
+        async fn update_file(&self, _path: &str, _content: &[u8]) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+
+        async fn list_files(&self, _folder_id: Option<&str>) -> Result<Vec<FileDto>, DomainError> {
+            unimplemented!()
+        }
+
+        async fn delete_file(&self, _id: &str) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+
+        async fn get_file_content(&self, _id: &str) -> Result<Vec<u8>, DomainError> {
+            unimplemented!()
+        }
+
+        async fn get_file_stream(&self, _id: &str) -> Result<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>, DomainError> {
+            unimplemented!()
+        }
+
+        async fn get_file_range(&self, _id: &str, _start: u64, _length: u64) -> Result<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>, DomainError> {
+            unimplemented!()
+        }
+
+        async fn write_file_range(&self, _id: &str, _offset: u64, _content: &[u8]) -> Result<FileDto, DomainError> {
+            unimplemented!()
+        }
+
+        async fn move_file(&self, _file_id: &str, _folder_id: Option<String>) -> Result<FileDto, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    const ALICE_FILE: &str = "Mi Carpeta - alice/notes.md";
+    const BOB_FILE: &str = "Mi Carpeta - bob/plan.md";
+
+    fn service(access: HomeOnlyAccess) -> PresenceService {
+        PresenceService::new(Arc::new(KnownFiles::with(&[ALICE_FILE, BOB_FILE])), Arc::new(access))
+    }
+
+    fn alice() -> ShareRecipient<'static> {
+        ShareRecipient { user_id: "alice-id", username: "alice", email: "alice@example.com" }
+    }
+
+    fn bob() -> ShareRecipient<'static> {
+        ShareRecipient { user_id: "bob-id", username: "bob", email: "bob@example.com" }
+    }
+
+    fn editing() -> PresenceHeartbeatDto {
+        PresenceHeartbeatDto { mode: PresenceMode::Editing, ..Default::default() }
+    }
+
+    #[tokio::test]
+    async fn test_heartbeats_publish_joins_updates_and_leaves() {
+        let access = HomeOnlyAccess::default();
+        access.grant("bob", ShareTarget::File(ALICE_FILE.to_string()), SharePermissions::READ);
+        let service = service(access);
+        let mut events = service.subscribe();
+
+        service.heartbeat(alice(), false, ALICE_FILE, PresenceHeartbeatDto::default()).await.unwrap();
+        let presences = service.heartbeat(bob(), false, ALICE_FILE, editing()).await.unwrap();
+        assert_eq!(presences.len(), 2);
+        // A repeated heartbeat only refreshes the presence
+        service.heartbeat(bob(), false, ALICE_FILE, editing()).await.unwrap();
+        service.heartbeat(alice(), false, ALICE_FILE, editing()).await.unwrap();
+        service.leave(ALICE_FILE, "bob-id").await;
+
+        let kinds: Vec<_> = std::iter::from_fn(|| events.try_recv().ok())
+            .map(|event| (event.event, event.presence.username))
+            .collect();
+        assert_eq!(kinds, vec![
+            (PresenceEventKind::Joined, "alice".to_string()),
+            (PresenceEventKind::Joined, "bob".to_string()),
+            (PresenceEventKind::Updated, "alice".to_string()),
+            (PresenceEventKind::Left, "bob".to_string()),
+        ]);
+        assert_eq!(service.list(alice(), false, ALICE_FILE).await.unwrap().len(), 1);
+        assert!(service.list(bob(), false, BOB_FILE).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_presence_requires_read_access_to_an_existing_file() {
+        let service = service(HomeOnlyAccess::default());
+        service.heartbeat(alice(), false, ALICE_FILE, editing()).await.unwrap();
+
+        let err = service.heartbeat(bob(), false, ALICE_FILE, editing()).await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::AccessDenied);
+        let err = service.list(bob(), false, ALICE_FILE).await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::AccessDenied);
+        let err = service.watch(bob(), false, &[BOB_FILE.to_string(), ALICE_FILE.to_string()]).await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::AccessDenied);
+        let err = service.heartbeat(alice(), false, "Mi Carpeta - alice/missing.md", editing()).await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::NotFound);
+
+        // Administrators see every file
+        assert_eq!(service.list(bob(), true, ALICE_FILE).await.unwrap().len(), 1);
+        assert!(service.watch(bob(), false, &[BOB_FILE.to_string()]).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_open_files_per_user_are_capped() {
+        let paths: Vec<String> = (0..=MAX_FILES_PER_USER).map(|i| format!("Mi Carpeta - alice/{}.md", i)).collect();
+        let known: Vec<&str> = paths.iter().map(String::as_str).collect();
+        let service = PresenceService::new(Arc::new(KnownFiles::with(&known)), Arc::new(HomeOnlyAccess::default()));
+
+        for path in &paths {
+            service.heartbeat(alice(), false, path, editing()).await.unwrap();
+        }
+        // The oldest presence makes room for the newest
+        assert_eq!(service.list(alice(), false, &paths[MAX_FILES_PER_USER]).await.unwrap().len(), 1);
+        assert_eq!(service.files.lock().await.len(), MAX_FILES_PER_USER);
+
+        let err = service.watch(alice(), false, &paths).await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidInput);
+    }
+
+    #[tokio::test]
+    async fn test_presence_expires_without_heartbeats() {
+        let service = service(HomeOnlyAccess::default());
+        service.heartbeat(alice(), false, ALICE_FILE, editing()).await.unwrap();
+        let mut events = service.subscribe();
+
+        let mut files = service.files.lock().await;
+        service.expire(&mut files, Utc::now() + Duration::seconds(PRESENCE_TTL_SECS + 1));
+        assert!(files.is_empty());
+        drop(files);
+
+        let event = events.try_recv().unwrap();
+        assert_eq!(event.event, PresenceEventKind::Left);
+        assert_eq!(event.presence.user_id, "alice-id");
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use tokio::sync::RwLock;
+
+use crate::application::dtos::quota_dto::{QuotaPolicyDto, SetQuotaPolicyDto};
+use crate::common::errors::DomainError;
+use crate::domain::entities::quota_policy::{resolve_effective_quota, EffectiveQuota, QuotaPolicy, MAX_POLICY_GROUP_LENGTH};
+use crate::domain::entities::user::User;
+use crate::domain::repositories::quota_policy_repository::QuotaPolicyRepository;
+
+/// Políticas cargadas del repositorio y el momento en que se leyeron
+struct CachedPolicies {
+    loaded_at: Instant,
+    policies: Arc<Vec<QuotaPolicy>>,
+}
+
+/// Servicio que resuelve la cuota efectiva de cada usuario.
+///
+/// Combina la cuota fijada para el usuario, las políticas de sus grupos y la
+/// cuota que recibió al crear la cuenta (ver `resolve_effective_quota`). Los
+/// grupos de un usuario son, como en los feature flags y los anuncios, el
+/// nombre de su rol. Las políticas se guardan en memoria durante `cache_ttl`
+/// porque se consultan en cada escritura.
+pub struct QuotaPolicyService {
+    repository: Arc<dyn QuotaPolicyRepository>,
+    cache_ttl: Duration,
+    cache: RwLock<Option<CachedPolicies>>,
+}
+
+impl QuotaPolicyService {
+    pub fn new(repository: Arc<dyn QuotaPolicyRepository>, cache_ttl: Duration) -> Self {
+        Self {
+            repository,
+            cache_ttl,
+            cache: RwLock::new(None),
+        }
+    }
+
+    /// Grupos de un usuario a efectos de cuota
+    pub fn user_groups(user: &User) -> Vec<String> {
+        vec![user.role().to_string()]
+    }
+
+    /// Políticas vigentes, recargadas si la caché ha caducado
+    async fn policies(&self) -> Result<Arc<Vec<QuotaPolicy>>, DomainError> {
+        if let Some(cached) = self.cache.read().await.as_ref() {
+            if cached.loaded_at.elapsed() < self.cache_ttl {
+                return Ok(cached.policies.clone());
+            }
+        }
+
+        let mut cache = self.cache.write().await;
+        if let Some(cached) = cache.as_ref() {
+            if cached.loaded_at.elapsed() < self.cache_ttl {
+                return Ok(cached.policies.clone());
+            }
+        }
+
+        match self.repository.list_policies().await {
+            Ok(policies) => {
+                let policies = Arc::new(policies);
+                *cache = Some(CachedPolicies { loaded_at: Instant::now(), policies: Arc::clone(&policies) });
+                Ok(policies)
+            },
+            // Sin políticas leídas no se puede decidir la cuota de nadie
+            Err(e) => match cache.as_ref() {
+                Some(cached) => {
+                    tracing::warn!("Could not load quota policies, using cached values: {}", e);
+                    Ok(cached.policies.clone())
+                },
+                None => Err(e),
+            },
+        }
+    }
+
+    /// Descarta la caché para que la siguiente consulta vea los cambios
+    async fn invalidate(&self) {
+        *self.cache.write().await = None;
+    }
+
+    /// Cuota que se aplica a un usuario
+    pub async fn effective_quota(&self, user: &User) -> Result<EffectiveQuota, DomainError> {
+        let user_override = self.repository.get_user_override(user.id()).await?;
+        let policies = self.policies().await?;
+        Ok(resolve_effective_quota(user_override, &policies, &Self::user_groups(user), user.storage_quota_bytes()))
+    }
+
+    /// Cuotas efectivas de varios usuarios con una sola lectura del repositorio
+    pub async fn effective_quotas(&self, users: &[User]) -> Result<HashMap<String, EffectiveQuota>, DomainError> {
+        let overrides = self.repository.list_user_overrides().await?;
+        let policies = self.policies().await?;
+        Ok(users.iter().map(|user| {
+            let quota = resolve_effective_quota(
+                overrides.get(user.id()).copied(),
+                &policies,
+                &Self::user_groups(user),
+                user.storage_quota_bytes(),
+            );
+            (user.id().to_string(), quota)
+        }).collect())
+    }
+
+    pub async fn list_policies(&self) -> Result<Vec<QuotaPolicyDto>, DomainError> {
+        let policies = self.repository.list_policies().await?;
+        Ok(policies.into_iter().map(QuotaPolicyDto::from).collect())
+    }
+
+    pub async fn set_policy(&self, group: &str, dto: SetQuotaPolicyDto) -> Result<QuotaPolicyDto, DomainError> {
+        let group = group.trim();
+        if group.is_empty() || group.len() > MAX_POLICY_GROUP_LENGTH {
+            return Err(DomainError::validation_error(format!("Invalid group '{}'", group)));
+        }
+
+        let policy = QuotaPolicy {
+            group: group.to_string(),
+            quota_bytes: dto.quota_bytes,
+            priority: dto.priority,
+            updated_at: Utc::now(),
+        };
+        self.repository.save_policy(&policy).await?;
+        self.invalidate().await;
+        tracing::info!(
+            "Política de cuota del grupo '{}': {} bytes (prioridad {})",
+            policy.group, policy.quota_bytes, policy.priority
+        );
+        Ok(QuotaPolicyDto::from(policy))
+    }
+
+    pub async fn delete_policy(&self, group: &str) -> Result<(), DomainError> {
+        if !self.repository.delete_policy(group).await? {
+            return Err(DomainError::not_found("QuotaPolicy", group));
+        }
+        self.invalidate().await;
+        tracing::info!("Política de cuota del grupo '{}' eliminada", group);
+        Ok(())
+    }
+
+    /// Fija la cuota de un usuario por encima de las políticas de sus grupos
+    pub async fn set_user_override(&self, user_id: &str, quota_bytes: i64) -> Result<(), DomainError> {
+        self.repository.set_user_override(user_id, quota_bytes).await
+    }
+
+    /// Quita la cuota fijada de un usuario para que vuelvan a aplicarse las políticas
+    pub async fn remove_user_override(&self, user_id: &str) -> Result<(), DomainError> {
+        if !self.repository.remove_user_override(user_id).await? {
+            return Err(DomainError::not_found("QuotaOverride", user_id));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use async_trait::async_trait;
+    use crate::domain::entities::quota_policy::QuotaSource;
+    use crate::domain::entities::user::UserRole;
+    use crate::domain::repositories::quota_policy_repository::QuotaPolicyRepositoryResult;
+
+    #[derive(Default)]
+    struct InMemoryQuotaPolicyRepository {
+        policies: Mutex<Vec<QuotaPolicy>>,
+        overrides: Mutex<HashMap<String, i64>>,
+        reads: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl QuotaPolicyRepository for InMemoryQuotaPolicyRepository {
+        async fn list_policies(&self) -> QuotaPolicyRepositoryResult<Vec<QuotaPolicy>> {
+            self.reads.fetch_add(1, Ordering::SeqCst);
+            Ok(self.policies.lock().unwrap().clone())
+        }
+        async fn save_policy(&self, policy: &QuotaPolicy) -> QuotaPolicyRepositoryResult<()> {
+            let mut policies = self.policies.lock().unwrap();
+            policies.retain(|p| p.group != policy.group);
+            policies.push(policy.clone());
+            Ok(())
+        }
+        async fn delete_policy(&self, group: &str) -> QuotaPolicyRepositoryResult<bool> {
+            let mut policies = self.policies.lock().unwrap();
+            let before = policies.len();
+            policies.retain(|p| p.group != group);
+            Ok(policies.len() < before)
+        }
+        async fn get_user_override(&self, user_id: &str) -> QuotaPolicyRepositoryResult<Option<i64>> {
+            Ok(self.overrides.lock().unwrap().get(user_id).copied())
+        }
+        async fn list_user_overrides(&self) -> QuotaPolicyRepositoryResult<HashMap<String, i64>> {
+            Ok(self.overrides.lock().unwrap().clone())
+        }
+        async fn set_user_override(&self, user_id: &str, quota_bytes: i64) -> QuotaPolicyRepositoryResult<()> {
+            self.overrides.lock().unwrap().insert(user_id.to_string(), quota_bytes);
+            Ok(())
+        }
+        async fn remove_user_override(&self, user_id: &str) -> QuotaPolicyRepositoryResult<bool> {
+            Ok(self.overrides.lock().unwrap().remove(user_id).is_some())
+        }
+    }
+
+    fn user(username: &str, role: UserRole) -> User {
+        User::new(username.to_string(), format!("{}@example.com", username), "Password123!".to_string(), role, 1000).unwrap()
+    }
+
+    fn policy(quota_bytes: i64) -> SetQuotaPolicyDto {
+        SetQuotaPolicyDto { quota_bytes, priority: 0 }
+    }
+
+    #[tokio::test]
+    async fn test_policy_and_override_resolution() {
+        let service = QuotaPolicyService::new(Arc::new(InMemoryQuotaPolicyRepository::default()), Duration::from_secs(60));
+        let alice = user("alice", UserRole::User);
+        let root = user("root", UserRole::Admin);
+
+        assert_eq!(service.effective_quota(&alice).await.unwrap().source, QuotaSource::Default);
+
+        service.set_policy("user", policy(5000)).await.unwrap();
+        let quota = service.effective_quota(&alice).await.unwrap();
+        assert_eq!((quota.quota_bytes, quota.group.as_deref()), (5000, Some("user")));
+        assert_eq!(service.effective_quota(&root).await.unwrap().quota_bytes, 1000);
+
+        service.set_user_override(alice.id(), 200).await.unwrap();
+        let quotas = service.effective_quotas(&[alice.clone(), root.clone()]).await.unwrap();
+        assert_eq!(quotas[alice.id()].source, QuotaSource::User);
+        assert_eq!(quotas[root.id()].source, QuotaSource::Default);
+
+        service.remove_user_override(alice.id()).await.unwrap();
+        assert!(service.remove_user_override(alice.id()).await.is_err());
+        service.delete_policy("user").await.unwrap();
+        assert_eq!(service.effective_quota(&alice).await.unwrap().quota_bytes, 1000);
+    }
+
+    #[tokio::test]
+    async fn test_policies_are_cached_until_changed() {
+        let repository = Arc::new(InMemoryQuotaPolicyRepository::default());
+        let service = QuotaPolicyService::new(repository.clone(), Duration::from_secs(60));
+        let alice = user("alice", UserRole::User);
+
+        service.effective_quota(&alice).await.unwrap();
+        service.effective_quota(&alice).await.unwrap();
+        assert_eq!(repository.reads.load(Ordering::SeqCst), 1);
+
+        service.set_policy("user", policy(-1)).await.unwrap();
+        assert!(service.effective_quota(&alice).await.unwrap().is_unlimited());
+        assert_eq!(repository.reads.load(Ordering::SeqCst), 2);
+
+        assert!(service.set_policy("  ", policy(1)).await.is_err());
+    }
+}
@@ -0,0 +1,416 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+
+use crate::application::dtos::quota_dto::{
+    GroupQuotaReportDto, QuotaPolicyDto, QuotaReportDto, SetQuotaPolicyDto, StorageQuotaDto, UserQuotaReportDto,
+};
+use crate::application::ports::auth_ports::{Caller, UserStoragePort};
+use crate::application::ports::email_notification_ports::{EmailEvent, EmailNotificationPort};
+use crate::application::ports::quota_ports::StorageQuotaUseCase;
+use crate::application::services::quota_policy_service::QuotaPolicyService;
+use crate::common::errors::{DomainError, ErrorKind};
+use crate::domain::entities::quota_policy::{EffectiveQuota, QuotaSource};
+use crate::domain::entities::user::{Permission, User};
+
+/// Usuarios leídos por página al preparar el informe de cuotas
+const REPORT_PAGE_SIZE: i64 = 500;
+
+/// Servicio de cuotas de almacenamiento por usuario.
+///
+/// Compara cada escritura con el uso guardado en el usuario y lo incrementa
+/// al terminarla, sin recorrer sus carpetas; el servicio de uso de
+/// almacenamiento lo recalcula periódicamente y corrige cualquier desvío.
+/// Con políticas configuradas, el límite es la cuota efectiva que resuelve
+/// `QuotaPolicyService`; sin ellas, la cuota guardada en el usuario.
+pub struct QuotaService {
+    user_repository: Arc<dyn UserStoragePort>,
+    policies: Option<Arc<QuotaPolicyService>>,
+    /// Serializa las actualizaciones del uso para no perder incrementos
+    usage_mutex: Mutex<()>,
+    /// Avisos por correo y porcentaje de la cuota a partir del que se envían
+    quota_warning: Option<(Arc<dyn EmailNotificationPort>, u8)>,
+}
+
+impl QuotaService {
+    pub fn new(user_repository: Arc<dyn UserStoragePort>) -> Self {
+        Self {
+            user_repository,
+            policies: None,
+            usage_mutex: Mutex::new(()),
+            quota_warning: None,
+        }
+    }
+
+    /// Configura las políticas de cuota por grupo
+    pub fn with_policies(mut self, policies: Arc<QuotaPolicyService>) -> Self {
+        self.policies = Some(policies);
+        self
+    }
+
+    /// Avisa por correo a los usuarios cuyo uso alcanza `warning_percent` de su cuota
+    pub fn with_email_notifications(mut self, email: Arc<dyn EmailNotificationPort>, warning_percent: u8) -> Self {
+        self.quota_warning = Some((email, warning_percent.clamp(1, 100)));
+        self
+    }
+
+    fn policies(&self) -> Result<&Arc<QuotaPolicyService>, DomainError> {
+        self.policies.as_ref().ok_or_else(|| DomainError::new(
+            ErrorKind::UnsupportedOperation,
+            "QuotaPolicy",
+            "Quota policies are not configured",
+        ))
+    }
+
+    async fn effective_quota(&self, user: &User) -> Result<EffectiveQuota, DomainError> {
+        match &self.policies {
+            Some(policies) => policies.effective_quota(user).await,
+            None => Ok(Self::stored_quota(user)),
+        }
+    }
+
+    fn stored_quota(user: &User) -> EffectiveQuota {
+        EffectiveQuota { quota_bytes: user.storage_quota_bytes(), source: QuotaSource::Default, group: None }
+    }
+
+    async fn ensure_fits(&self, user: &User, additional_bytes: u64) -> Result<(), DomainError> {
+        let quota = self.effective_quota(user).await?;
+        if quota.has_room_for(user.storage_used_bytes(), additional_bytes) {
+            return Ok(());
+        }
+        Err(DomainError::quota_exceeded(
+            "User",
+            format!(
+                "Storage quota exceeded for {}: {} of {} bytes used, {} more requested",
+                user.username(),
+                user.storage_used_bytes(),
+                quota.quota_bytes,
+                additional_bytes
+            ),
+        ).with_id(user.id()))
+    }
+
+    /// Avisa al usuario si su uso acaba de cruzar el umbral de aviso de la cuota
+    async fn warn_if_almost_full(&self, user: &User, previous_usage: i64, usage: i64) {
+        let Some((email, percent)) = &self.quota_warning else {
+            return;
+        };
+        let quota = match self.effective_quota(user).await {
+            Ok(quota) => quota,
+            Err(e) => {
+                tracing::warn!("No se pudo resolver la cuota de {} para avisar: {}", user.username(), e);
+                return;
+            }
+        };
+        if quota.is_unlimited() || quota.quota_bytes == 0 {
+            return;
+        }
+
+        let threshold = (quota.quota_bytes as i128 * *percent as i128 / 100) as i64;
+        if previous_usage < threshold && usage >= threshold {
+            email.notify(user.id(), EmailEvent::QuotaWarning {
+                used_bytes: usage as u64,
+                quota_bytes: quota.quota_bytes as u64,
+            });
+        }
+    }
+
+    async fn all_users(&self) -> Result<Vec<User>, DomainError> {
+        let mut users = Vec::new();
+        loop {
+            let page = self.user_repository.list_users(REPORT_PAGE_SIZE, users.len() as i64).await?;
+            let done = (page.len() as i64) < REPORT_PAGE_SIZE;
+            users.extend(page);
+            if done {
+                return Ok(users);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl StorageQuotaUseCase for QuotaService {
+    async fn get_quota(&self, caller: &Caller, user_id: &str) -> Result<StorageQuotaDto, DomainError> {
+        // Cada usuario puede ver su propia cuota
+        if caller.user_id != user_id {
+            caller.require(Permission::ManageQuotas)?;
+        }
+        let user = self.user_repository.get_user_by_id(user_id).await?;
+        let quota = self.effective_quota(&user).await?;
+        Ok(StorageQuotaDto::new(&user, &quota))
+    }
+
+    async fn set_quota(&self, caller: &Caller, user_id: &str, quota_bytes: i64) -> Result<StorageQuotaDto, DomainError> {
+        caller.require(Permission::ManageQuotas)?;
+        let mut user = self.user_repository.get_user_by_id(user_id).await?;
+        match &self.policies {
+            // La cuota guardada en el usuario queda como valor por defecto
+            Some(policies) => policies.set_user_override(user.id(), quota_bytes).await?,
+            None => {
+                user.update_storage_quota(quota_bytes);
+                user = self.user_repository.update_user(user).await?;
+            },
+        }
+        self.get_quota(caller, user.id()).await
+    }
+
+    async fn check_quota(&self, user_id: &str, additional_bytes: u64) -> Result<(), DomainError> {
+        let user = self.user_repository.get_user_by_id(user_id).await?;
+        self.ensure_fits(&user, additional_bytes).await
+    }
+
+    async fn check_quota_for_username(&self, username: &str, additional_bytes: u64) -> Result<String, DomainError> {
+        let user = self.user_repository.get_user_by_username(username).await?;
+        self.ensure_fits(&user, additional_bytes).await?;
+        Ok(user.id().to_string())
+    }
+
+    async fn record_usage(&self, user_id: &str, delta_bytes: i64) -> Result<(), DomainError> {
+        if delta_bytes == 0 {
+            return Ok(());
+        }
+        let _guard = self.usage_mutex.lock().await;
+        let user = self.user_repository.get_user_by_id(user_id).await?;
+        let usage = user.storage_used_bytes().saturating_add(delta_bytes).max(0);
+        self.user_repository.update_storage_usage(user_id, usage).await?;
+        if delta_bytes > 0 {
+            self.warn_if_almost_full(&user, user.storage_used_bytes(), usage).await;
+        }
+        Ok(())
+    }
+
+    async fn clear_quota_override(&self, caller: &Caller, user_id: &str) -> Result<StorageQuotaDto, DomainError> {
+        caller.require(Permission::ManageQuotas)?;
+        let user = self.user_repository.get_user_by_id(user_id).await?;
+        self.policies()?.remove_user_override(user.id()).await?;
+        self.get_quota(caller, user.id()).await
+    }
+
+    async fn list_policies(&self, caller: &Caller) -> Result<Vec<QuotaPolicyDto>, DomainError> {
+        caller.require(Permission::ManageQuotas)?;
+        self.policies()?.list_policies().await
+    }
+
+    async fn set_policy(&self, caller: &Caller, group: &str, dto: SetQuotaPolicyDto) -> Result<QuotaPolicyDto, DomainError> {
+        caller.require(Permission::ManageQuotas)?;
+        self.policies()?.set_policy(group, dto).await
+    }
+
+    async fn delete_policy(&self, caller: &Caller, group: &str) -> Result<(), DomainError> {
+        caller.require(Permission::ManageQuotas)?;
+        self.policies()?.delete_policy(group).await
+    }
+
+    async fn allocation_report(&self, caller: &Caller) -> Result<QuotaReportDto, DomainError> {
+        caller.require(Permission::ManageQuotas)?;
+        let users = self.all_users().await?;
+        let (mut quotas, policies) = match &self.policies {
+            Some(service) => (service.effective_quotas(&users).await?, service.list_policies().await?),
+            None => (Default::default(), Vec::new()),
+        };
+
+        // Todos los grupos con política aparecen aunque no tengan miembros
+        let mut groups: BTreeMap<String, GroupQuotaReportDto> = policies.into_iter()
+            .map(|policy| (policy.group.clone(), GroupQuotaReportDto {
+                group: policy.group.clone(),
+                policy: Some(policy),
+                users: 0,
+                allocated_bytes: 0,
+                used_bytes: 0,
+                unlimited_users: 0,
+                over_quota_users: 0,
+            }))
+            .collect();
+
+        let mut report_users = Vec::with_capacity(users.len());
+        for user in &users {
+            let quota = quotas.remove(user.id()).unwrap_or_else(|| Self::stored_quota(user));
+            let group = QuotaPolicyService::user_groups(user).into_iter().next().unwrap_or_default();
+            let used_bytes = user.storage_used_bytes();
+
+            let entry = groups.entry(group.clone()).or_insert_with(|| GroupQuotaReportDto {
+                group: group.clone(),
+                policy: None,
+                users: 0,
+                allocated_bytes: 0,
+                used_bytes: 0,
+                unlimited_users: 0,
+                over_quota_users: 0,
+            });
+            entry.users += 1;
+            entry.used_bytes = entry.used_bytes.saturating_add(used_bytes);
+            if quota.is_unlimited() {
+                entry.unlimited_users += 1;
+            } else {
+                entry.allocated_bytes = entry.allocated_bytes.saturating_add(quota.quota_bytes);
+                if used_bytes > quota.quota_bytes {
+                    entry.over_quota_users += 1;
+                }
+            }
+
+            let usage_percent = (!quota.is_unlimited()).then(|| match quota.quota_bytes {
+                0 if used_bytes > 0 => 100.0,
+                0 => 0.0,
+                limit => used_bytes as f64 * 100.0 / limit as f64,
+            });
+            report_users.push(UserQuotaReportDto {
+                user_id: user.id().to_string(),
+                username: user.username().to_string(),
+                group,
+                quota_bytes: quota.quota_bytes,
+                source: quota.source,
+                policy_group: quota.group,
+                used_bytes,
+                usage_percent,
+            });
+        }
+        report_users.sort_by(|a, b| b.usage_percent.unwrap_or(-1.0).total_cmp(&a.usage_percent.unwrap_or(-1.0))
+            .then_with(|| b.used_bytes.cmp(&a.used_bytes)));
+
+        let groups: Vec<GroupQuotaReportDto> = groups.into_values().collect();
+        Ok(QuotaReportDto {
+            allocated_bytes: groups.iter().fold(0i64, |total, group| total.saturating_add(group.allocated_bytes)),
+            used_bytes: groups.iter().fold(0i64, |total, group| total.saturating_add(group.used_bytes)),
+            unlimited_users: groups.iter().map(|group| group.unlimited_users).sum(),
+            over_quota_users: groups.iter().map(|group| group.over_quota_users).sum(),
+            groups,
+            users: report_users,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex as StdMutex;
+
+    use crate::application::services::test_users::MemoryUsers;
+    use crate::domain::entities::user::UserRole;
+
+    fn admin() -> Caller {
+        Caller::new("root", UserRole::Admin)
+    }
+
+    fn user(username: &str, quota: i64, used: i64) -> User {
+        let mut user = User::new(
+            username.to_string(),
+            format!("{}@example.com", username),
+            "Password123!".to_string(),
+            UserRole::User,
+            quota,
+        ).unwrap();
+        user.update_storage_used(used);
+        user
+    }
+
+    fn with_user(username: &str, quota: i64, used: i64) -> (Arc<MemoryUsers>, String) {
+        let users = MemoryUsers::default();
+        let id = users.insert(user(username, quota, used));
+        (Arc::new(users), id)
+    }
+
+    #[tokio::test]
+    async fn rejects_writes_past_the_quota() {
+        let (users, id) = with_user("alice", 1000, 900);
+        let service = QuotaService::new(users);
+
+        assert!(service.check_quota(&id, 100).await.is_ok());
+        let err = service.check_quota(&id, 101).await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::QuotaExceeded);
+        assert_eq!(service.check_quota_for_username("alice", 100).await.unwrap(), id);
+        assert_eq!(service.check_quota_for_username("alice", 101).await.unwrap_err().kind, ErrorKind::QuotaExceeded);
+    }
+
+    #[tokio::test]
+    async fn recorded_usage_counts_against_the_quota() {
+        let (users, id) = with_user("alice", 1000, 0);
+        let service = QuotaService::new(users);
+
+        service.record_usage(&id, 600).await.unwrap();
+        service.record_usage(&id, 300).await.unwrap();
+        assert!(service.check_quota(&id, 200).await.is_err());
+
+        service.record_usage(&id, -500).await.unwrap();
+        let quota = service.get_quota(&admin(), &id).await.unwrap();
+        assert_eq!(quota.used_bytes, 400);
+        assert_eq!(quota.available_bytes, Some(600));
+    }
+
+    #[derive(Default)]
+    struct RecordingEmails {
+        sent: StdMutex<Vec<(String, u64)>>,
+    }
+
+    impl EmailNotificationPort for RecordingEmails {
+        fn notify(&self, user_id: &str, event: EmailEvent) {
+            if let EmailEvent::QuotaWarning { used_bytes, .. } = event {
+                self.sent.lock().unwrap().push((user_id.to_string(), used_bytes));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn warns_once_when_crossing_the_threshold() {
+        let (users, id) = with_user("alice", 1000, 0);
+        let emails = Arc::new(RecordingEmails::default());
+        let service = QuotaService::new(users).with_email_notifications(emails.clone(), 90);
+
+        service.record_usage(&id, 850).await.unwrap();
+        assert!(emails.sent.lock().unwrap().is_empty());
+        service.record_usage(&id, 60).await.unwrap();
+        service.record_usage(&id, 10).await.unwrap();
+        assert_eq!(*emails.sent.lock().unwrap(), vec![(id.clone(), 910)]);
+    }
+
+    #[tokio::test]
+    async fn negative_quota_means_unlimited() {
+        let (users, id) = with_user("alice", 1000, 900);
+        let service = QuotaService::new(users);
+
+        let quota = service.set_quota(&admin(), &id, -1).await.unwrap();
+        assert_eq!(quota.available_bytes, None);
+        assert!(service.check_quota(&id, u64::MAX).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn reports_allocation_against_usage() {
+        let (users, alice) = with_user("alice", 1000, 1200);
+        for (username, quota, used) in [("bob", 1000, 250), ("carol", -1, 5000)] {
+            users.insert(user(username, quota, used));
+        }
+        let service = QuotaService::new(users);
+
+        let report = service.allocation_report(&admin()).await.unwrap();
+        assert_eq!((report.allocated_bytes, report.used_bytes), (2000, 6450));
+        assert_eq!((report.unlimited_users, report.over_quota_users), (1, 1));
+        assert_eq!(report.groups.len(), 1);
+        assert_eq!(report.groups[0].group, "user");
+        assert_eq!(report.groups[0].users, 3);
+
+        // Los más llenos primero y los ilimitados al final
+        assert_eq!(report.users[0].user_id, alice);
+        assert_eq!(report.users[0].usage_percent, Some(120.0));
+        assert_eq!(report.users[2].username, "carol");
+        assert_eq!(report.users[2].usage_percent, None);
+
+        assert_eq!(service.list_policies(&admin()).await.unwrap_err().kind, ErrorKind::UnsupportedOperation);
+    }
+
+    #[tokio::test]
+    async fn only_admins_manage_quotas() {
+        let (users, id) = with_user("alice", 1000, 0);
+        let service = QuotaService::new(users);
+        let alice = Caller::new(id.clone(), UserRole::User);
+
+        // Cada usuario ve su propia cuota, pero no la cambia ni ve la de otros
+        assert_eq!(service.get_quota(&alice, &id).await.unwrap().quota_bytes, 1000);
+        assert_eq!(service.set_quota(&alice, &id, -1).await.unwrap_err().kind, ErrorKind::AccessDenied);
+        let bob = Caller::new("bob", UserRole::User);
+        assert_eq!(service.get_quota(&bob, &id).await.unwrap_err().kind, ErrorKind::AccessDenied);
+        assert_eq!(service.allocation_report(&alice).await.unwrap_err().kind, ErrorKind::AccessDenied);
+
+        assert_eq!(service.set_quota(&admin(), &id, 2000).await.unwrap().quota_bytes, 2000);
+    }
+}
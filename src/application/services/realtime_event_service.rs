@@ -0,0 +1,69 @@
+use chrono::Utc;
+use tokio::sync::broadcast;
+
+use crate::application::ports::realtime_ports::{RealtimeAudience, RealtimeEvent, RealtimeEventPort, RealtimeMessage};
+
+/// Eventos que se guardan para los suscriptores lentos antes de descartarlos
+const EVENT_CAPACITY: usize = 1024;
+
+/// Bus de eventos en tiempo real.
+///
+/// Los servicios publican aquí lo que cambia (ficheros, elementos
+/// compartidos, eventos de calendario) y cada conexión `/ws` se suscribe y
+/// reenvía a su cliente los eventos de su usuario, para que la interfaz se
+/// actualice sin consultar cada poco. No se guarda nada: lo publicado sin
+/// nadie conectado se pierde.
+pub struct RealtimeEventService {
+    events: broadcast::Sender<RealtimeMessage>,
+}
+
+impl RealtimeEventService {
+    pub fn new() -> Self {
+        let (events, _) = broadcast::channel(EVENT_CAPACITY);
+        Self { events }
+    }
+}
+
+impl Default for RealtimeEventService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RealtimeEventPort for RealtimeEventService {
+    fn publish(&self, audience: RealtimeAudience, event: RealtimeEvent) {
+        // Sin suscriptores el envío falla, y no importa
+        let _ = self.events.send(RealtimeMessage { audience, event, at: Utc::now() });
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<RealtimeMessage> {
+        self.events.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_subscribers_receive_published_events() {
+        let service = RealtimeEventService::new();
+        // Sin suscriptores no falla
+        service.publish(RealtimeAudience::User("ana".to_string()), RealtimeEvent::FileDeleted { file_id: "f0".to_string(), folder_id: None });
+
+        let mut events = service.subscribe();
+        service.publish(RealtimeAudience::Username("ana".to_string()), RealtimeEvent::FileDeleted {
+            file_id: "f1".to_string(),
+            folder_id: Some("d1".to_string()),
+        });
+
+        let message = events.recv().await.unwrap();
+        assert!(message.audience.includes("id-ana", "ana"));
+        assert!(!message.audience.includes("ana", "bob"));
+
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(json["type"], "file_deleted");
+        assert_eq!(json["file_id"], "f1");
+        assert!(json.get("audience").is_none());
+    }
+}
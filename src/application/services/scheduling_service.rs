@@ -0,0 +1,273 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::application::dtos::calendar_dto::CalendarEventDto;
+use crate::application::dtos::scheduling_dto::{ScheduleRecipientDto, SchedulingMessageDto};
+use crate::application::ports::auth_ports::UserStoragePort;
+use crate::application::ports::event_bus_ports::{DomainEvent, EventBusPort};
+use crate::application::ports::scheduling_ports::SchedulingUseCase;
+use crate::application::services::dav_access_service::DavAccessService;
+use crate::common::errors::{DomainError, ErrorKind};
+use crate::domain::entities::calendar_event::CalendarEvent;
+use crate::domain::entities::event_attendee::ParticipationStatus;
+use crate::domain::entities::scheduling_message::{ITipMethod, SchedulingMessage};
+use crate::domain::repositories::address_book_repository::AddressBookRepository;
+use crate::domain::repositories::calendar_event_repository::CalendarEventRepository;
+use crate::domain::repositories::calendar_repository::CalendarRepository;
+use crate::domain::repositories::scheduling_repository::SchedulingRepository;
+use crate::domain::services::itip_service::{build_message, build_reply, strip_method, ITipMessage};
+
+/// Estado iTIP de un mensaje entregado en la bandeja de entrada (RFC 6638, 3.2.10)
+const STATUS_DELIVERED: &str = "1.2;Delivered";
+/// Estado iTIP de un destinatario sin cuenta en este servidor
+const STATUS_INVALID_USER: &str = "3.7;Invalid calendar user";
+
+/// Servicio de planificación iTIP entre usuarios del servidor.
+///
+/// Las invitaciones (REQUEST), cancelaciones (CANCEL) y respuestas (REPLY)
+/// se entregan en la bandeja de entrada de planificación de cada destinatario.
+/// Cuando llega un REPLY se actualiza además el estado de participación del
+/// asistente en la copia del evento del organizador. Cada evento que cambia
+/// se publica en el bus de eventos de dominio.
+pub struct SchedulingService {
+    access: DavAccessService,
+    calendar_repository: Arc<dyn CalendarRepository>,
+    event_repository: Arc<dyn CalendarEventRepository>,
+    scheduling_repository: Arc<dyn SchedulingRepository>,
+    user_storage: Arc<dyn UserStoragePort>,
+    event_bus: Option<Arc<dyn EventBusPort>>,
+}
+
+impl SchedulingService {
+    /// Crea un nuevo servicio de planificación
+    pub fn new(
+        calendar_repository: Arc<dyn CalendarRepository>,
+        event_repository: Arc<dyn CalendarEventRepository>,
+        address_book_repository: Arc<dyn AddressBookRepository>,
+        scheduling_repository: Arc<dyn SchedulingRepository>,
+        user_storage: Arc<dyn UserStoragePort>,
+    ) -> Self {
+        Self {
+            access: DavAccessService::new(calendar_repository.clone(), address_book_repository),
+            calendar_repository,
+            event_repository,
+            scheduling_repository,
+            user_storage,
+            event_bus: None,
+        }
+    }
+
+    /// Publica los eventos de calendario que cambian
+    pub fn with_event_bus(mut self, event_bus: Arc<dyn EventBusPort>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    /// Publica el cambio de un evento del calendario de `owner_id`
+    fn publish_change(&self, owner_id: &str, event: &CalendarEvent) {
+        if let Some(event_bus) = &self.event_bus {
+            event_bus.publish(DomainEvent::CalendarEventUpdated {
+                event_id: event.id().to_string(),
+                calendar_id: event.calendar_id().to_string(),
+                ical_uid: event.ical_uid().to_string(),
+                owner_id: owner_id.to_string(),
+            });
+        }
+    }
+
+    fn parse_id(calendar_id: &str) -> Result<Uuid, DomainError> {
+        Uuid::parse_str(calendar_id)
+            .map_err(|_| DomainError::validation_error(format!("Invalid calendar ID: {}", calendar_id)))
+    }
+
+    /// Correo del usuario, que es su dirección de usuario de calendario
+    async fn user_email(&self, user_id: &str) -> Result<String, DomainError> {
+        Ok(self.user_storage.get_user_by_id(user_id).await?.email().to_lowercase())
+    }
+
+    /// Entrega un mensaje a sus destinatarios, que dependen del método:
+    /// los asistentes en REQUEST y CANCEL, el organizador en REPLY
+    async fn deliver(&self, sender: &str, message: ITipMessage) -> Result<Vec<ScheduleRecipientDto>, DomainError> {
+        let organizer = message.organizer.as_ref()
+            .map(|organizer| organizer.email.clone())
+            .ok_or_else(|| DomainError::validation_error("The scheduling message has no ORGANIZER"))?;
+
+        let recipients: Vec<String> = match message.method {
+            ITipMethod::Request | ITipMethod::Cancel => {
+                if organizer != sender {
+                    return Err(DomainError::access_denied("SchedulingMessage", "Only the organizer can send invitations and cancellations"));
+                }
+                message.attendees.iter()
+                    .map(|attendee| attendee.email.clone())
+                    .filter(|email| *email != organizer)
+                    .collect()
+            }
+            ITipMethod::Reply => {
+                if message.attendees.len() != 1 || message.attendees[0].email != sender {
+                    return Err(DomainError::access_denied("SchedulingMessage", "A reply can only carry the participation of its sender"));
+                }
+                vec![organizer]
+            }
+        };
+
+        let mut results = Vec::with_capacity(recipients.len());
+        for recipient in recipients {
+            let user = match self.user_storage.get_user_by_email(&recipient).await {
+                Ok(user) => user,
+                Err(e) if e.kind == ErrorKind::NotFound => {
+                    results.push(ScheduleRecipientDto {
+                        recipient: format!("mailto:{}", recipient),
+                        request_status: STATUS_INVALID_USER.to_string(),
+                    });
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+
+            self.scheduling_repository.deliver_message(&SchedulingMessage::new(
+                user.id().to_string(),
+                message.method,
+                message.uid.clone(),
+                sender.to_string(),
+                message.ical_data.clone(),
+            )).await?;
+            if message.method == ITipMethod::Reply {
+                self.apply_reply(user.id(), &message).await?;
+            }
+
+            results.push(ScheduleRecipientDto {
+                recipient: format!("mailto:{}", recipient),
+                request_status: STATUS_DELIVERED.to_string(),
+            });
+        }
+
+        tracing::info!("Delivered iTIP {} for {} from {} to {} recipients", message.method.as_str(), message.uid, sender, results.len());
+        Ok(results)
+    }
+
+    /// Actualiza el estado de participación en las copias del evento que
+    /// tiene el organizador en sus calendarios
+    async fn apply_reply(&self, organizer_id: &str, message: &ITipMessage) -> Result<(), DomainError> {
+        let Some(attendee) = message.attendees.first() else {
+            return Ok(());
+        };
+
+        for calendar in self.calendar_repository.list_calendars_by_owner(organizer_id).await? {
+            if let Some(mut event) = self.event_repository.find_event_by_ical_uid(calendar.id(), &message.uid).await? {
+                if event.update_participation(&attendee.email, attendee.partstat) {
+                    let event = self.event_repository.update_event(event).await?;
+                    self.publish_change(organizer_id, &event);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SchedulingUseCase for SchedulingService {
+    async fn send_message(&self, user_id: &str, ical_data: &str) -> Result<Vec<ScheduleRecipientDto>, DomainError> {
+        let message = ITipMessage::parse(ical_data)
+            .ok_or_else(|| DomainError::validation_error("The posted data is not an iTIP REQUEST, REPLY or CANCEL message"))?;
+        let sender = self.user_email(user_id).await?;
+        self.deliver(&sender, message).await
+    }
+
+    async fn send_invitations(
+        &self,
+        user_id: &str,
+        is_admin: bool,
+        calendar_id: &str,
+        ical_uid: &str,
+    ) -> Result<Vec<ScheduleRecipientDto>, DomainError> {
+        let id = Self::parse_id(calendar_id)?;
+        self.access.check_calendar(user_id, is_admin, &id, true).await?;
+
+        let event = self.event_repository.find_event_by_ical_uid(&id, ical_uid).await?
+            .ok_or_else(|| DomainError::not_found("CalendarEvent", ical_uid))?;
+        if event.attendees().is_empty() {
+            return Err(DomainError::validation_error("The event has no attendees to invite"));
+        }
+
+        let message = build_message(event.ical_data(), ITipMethod::Request)
+            .and_then(|data| ITipMessage::parse(&data))
+            .ok_or_else(|| DomainError::validation_error("The event cannot be sent as an invitation"))?;
+        let sender = self.user_email(user_id).await?;
+        self.deliver(&sender, message).await
+    }
+
+    async fn respond(
+        &self,
+        user_id: &str,
+        is_admin: bool,
+        calendar_id: &str,
+        ical_uid: &str,
+        partstat: &str,
+    ) -> Result<CalendarEventDto, DomainError> {
+        let partstat = ParticipationStatus::parse(partstat)
+            .ok_or_else(|| DomainError::validation_error(format!("Invalid participation status: {}", partstat)))?;
+        let id = Self::parse_id(calendar_id)?;
+        self.access.check_calendar(user_id, is_admin, &id, true).await?;
+        let email = self.user_email(user_id).await?;
+
+        // La última invitación recibida trae la versión más reciente del evento
+        let invitation = self.scheduling_repository.list_messages(user_id).await?
+            .into_iter()
+            .filter(|message| message.method == ITipMethod::Request && message.ical_uid == ical_uid)
+            .last();
+
+        let existing = self.event_repository.find_event_by_ical_uid(&id, ical_uid).await?;
+        let mut event = match (existing, &invitation) {
+            (Some(mut event), Some(invitation)) => {
+                event.update_ical_data(strip_method(&invitation.ical_data))?;
+                event
+            }
+            (Some(event), None) => event,
+            (None, Some(invitation)) => {
+                let calendar = self.calendar_repository.find_calendar_by_id(&id).await?;
+                let event = CalendarEvent::from_ical_with_timezone(id, strip_method(&invitation.ical_data), calendar.vtimezone().as_ref())?;
+                self.event_repository.create_event(event).await?
+            }
+            (None, None) => return Err(DomainError::not_found("CalendarEvent", ical_uid)),
+        };
+
+        if !event.update_participation(&email, partstat) {
+            return Err(DomainError::validation_error("You are not an attendee of this event"));
+        }
+        let event = self.event_repository.update_event(event).await?;
+        self.publish_change(user_id, &event);
+
+        let organizer = event.organizer().map(|organizer| organizer.email.clone());
+        if organizer.is_some_and(|organizer| organizer != email) {
+            if let Some(reply) = build_reply(event.ical_data(), &email, partstat).and_then(|data| ITipMessage::parse(&data)) {
+                self.deliver(&email, reply).await?;
+            }
+        }
+        self.scheduling_repository.delete_messages_for_uid(user_id, ical_uid).await?;
+
+        Ok(CalendarEventDto::from(event))
+    }
+
+    async fn list_inbox(&self, user_id: &str) -> Result<Vec<SchedulingMessageDto>, DomainError> {
+        Ok(self.scheduling_repository.list_messages(user_id).await?
+            .into_iter()
+            .map(SchedulingMessageDto::from)
+            .collect())
+    }
+
+    async fn get_inbox_message(&self, user_id: &str, message_id: &str) -> Result<SchedulingMessageDto, DomainError> {
+        self.scheduling_repository.get_message(user_id, message_id).await?
+            .map(SchedulingMessageDto::from)
+            .ok_or_else(|| DomainError::not_found("SchedulingMessage", message_id))
+    }
+
+    async fn delete_inbox_message(&self, user_id: &str, message_id: &str) -> Result<(), DomainError> {
+        if self.scheduling_repository.delete_message(user_id, message_id).await? {
+            Ok(())
+        } else {
+            Err(DomainError::not_found("SchedulingMessage", message_id))
+        }
+    }
+}
@@ -1,16 +1,52 @@
 use std::sync::Arc;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::time::{Duration, Instant};
 use std::sync::Mutex;
 use async_trait::async_trait;
 use tokio::time;
 
-use crate::common::errors::Result;
-use crate::application::dtos::search_dto::{SearchCriteriaDto, SearchResultsDto};
+use crate::common::errors::{DomainError, Result};
+use crate::application::dtos::search_dto::{
+    ContentMatchDto, SearchCriteriaDto, SearchResultGroupDto, SearchResultType, SearchResultsDto,
+    UnifiedSearchQueryDto, UnifiedSearchResultsDto,
+};
+use crate::application::dtos::calendar_dto::CalendarEventDto;
+use crate::application::dtos::contact_dto::ContactDto;
 use crate::application::dtos::file_dto::FileDto;
 use crate::application::dtos::folder_dto::FolderDto;
 use crate::application::ports::inbound::SearchUseCase;
 use crate::application::ports::outbound::{FileStoragePort, FolderStoragePort};
+use crate::application::ports::hidden_file_ports::HiddenFileRulesPort;
+use crate::application::ports::full_text_ports::FullTextIndexPort;
+use crate::application::ports::share_ports::{ShareAccessUseCase, ShareRecipient, ShareTarget};
+use crate::domain::repositories::address_book_repository::AddressBookRepository;
+use crate::domain::repositories::calendar_event_repository::CalendarEventRepository;
+use crate::domain::repositories::calendar_repository::CalendarRepository;
+use crate::domain::repositories::contact_repository::ContactRepository;
+use crate::domain::repositories::tag_repository::TagRepository;
+use crate::domain::entities::share::SharePermissions;
+use crate::domain::entities::tag::TaggedItemType;
+use crate::domain::services::ownership_service::owner_of_path;
+
+/// Coincidencias de contenido que se piden como mucho al índice
+const MAX_CONTENT_HITS: usize = 1000;
+
+/// Resultados que se devuelven como mucho en cada grupo de la búsqueda unificada
+const MAX_GROUP_LIMIT: usize = 100;
+
+/// Repositorios de calendarios y contactos en los que busca la búsqueda unificada
+struct DavSearchRepositories {
+    calendar_repository: Arc<dyn CalendarRepository>,
+    event_repository: Arc<dyn CalendarEventRepository>,
+    address_book_repository: Arc<dyn AddressBookRepository>,
+    contact_repository: Arc<dyn ContactRepository>,
+}
+
+/// IDs de los archivos y carpetas que llevan todas las etiquetas pedidas
+struct TaggedItemIds {
+    files: HashSet<String>,
+    folders: HashSet<String>,
+}
 
 /**
  * Implementación del servicio de búsqueda para archivos y carpetas.
@@ -27,6 +63,9 @@ pub struct SearchService {
     /// Repositorio para operaciones con carpetas
     folder_repository: Arc<dyn FolderStoragePort>,
     
+    /// Enlaces compartidos que dan acceso a elementos de otros usuarios
+    share_access: Arc<dyn ShareAccessUseCase>,
+    
     /// Caché de resultados de búsqueda con tiempo de expiración
     search_cache: Arc<Mutex<HashMap<SearchCacheKey, CachedSearchResult>>>,
     
@@ -35,6 +74,18 @@ pub struct SearchService {
     
     /// Tamaño máximo de la caché (número de resultados almacenados)
     max_cache_size: usize,
+    
+    /// Reglas de ficheros ocultos que excluyen resultados
+    hidden_file_rules: Option<Arc<dyn HiddenFileRulesPort>>,
+    
+    /// Índice del contenido de los documentos; sin él no se busca por contenido
+    full_text: Option<Arc<dyn FullTextIndexPort>>,
+    
+    /// Calendarios y contactos; sin ellos la búsqueda unificada solo devuelve archivos
+    dav: Option<DavSearchRepositories>,
+    
+    /// Etiquetas de los elementos; sin ellas no se filtra por etiqueta
+    tags: Option<Arc<dyn TagRepository>>,
 }
 
 /// Clave para la caché de búsqueda
@@ -62,21 +113,28 @@ impl SearchService {
      * 
      * @param file_repository Repositorio para operaciones con archivos
      * @param folder_repository Repositorio para operaciones con carpetas
+     * @param share_access Comprobación de los enlaces compartidos con quien busca
      * @param cache_ttl Tiempo de vida de la caché en segundos (0 para desactivar)
      * @param max_cache_size Tamaño máximo de la caché
      */
     pub fn new(
         file_repository: Arc<dyn FileStoragePort>,
         folder_repository: Arc<dyn FolderStoragePort>,
+        share_access: Arc<dyn ShareAccessUseCase>,
         cache_ttl: u64,
         max_cache_size: usize,
     ) -> Self {
         let search_service = Self {
             file_repository,
             folder_repository,
+            share_access,
             search_cache: Arc::new(Mutex::new(HashMap::new())),
             cache_ttl,
             max_cache_size,
+            hidden_file_rules: None,
+            full_text: None,
+            dav: None,
+            tags: None,
         };
         
         // Iniciar tarea de limpieza de caché si TTL > 0
@@ -87,6 +145,60 @@ impl SearchService {
         search_service
     }
     
+    /**
+     * Excluye de los resultados los ficheros ocultos según las reglas de la instancia.
+     * 
+     * @param hidden_file_rules Reglas de ficheros ocultos y de sistema
+     */
+    pub fn with_hidden_file_rules(mut self, hidden_file_rules: Arc<dyn HiddenFileRulesPort>) -> Self {
+        self.hidden_file_rules = Some(hidden_file_rules);
+        self
+    }
+    
+    /**
+     * Permite buscar en el contenido de los documentos indexados.
+     * 
+     * @param full_text Índice del contenido de los ficheros
+     */
+    pub fn with_full_text(mut self, full_text: Arc<dyn FullTextIndexPort>) -> Self {
+        self.full_text = Some(full_text);
+        self
+    }
+    
+    /**
+     * Permite buscar también en los contactos y eventos de calendario del usuario.
+     * 
+     * @param calendar_repository Repositorio de calendarios
+     * @param event_repository Repositorio de eventos de calendario
+     * @param address_book_repository Repositorio de libretas de direcciones
+     * @param contact_repository Repositorio de contactos
+     */
+    pub fn with_dav_repositories(
+        mut self,
+        calendar_repository: Arc<dyn CalendarRepository>,
+        event_repository: Arc<dyn CalendarEventRepository>,
+        address_book_repository: Arc<dyn AddressBookRepository>,
+        contact_repository: Arc<dyn ContactRepository>,
+    ) -> Self {
+        self.dav = Some(DavSearchRepositories {
+            calendar_repository,
+            event_repository,
+            address_book_repository,
+            contact_repository,
+        });
+        self
+    }
+    
+    /**
+     * Permite filtrar los resultados por etiquetas.
+     * 
+     * @param tags Repositorio de etiquetas
+     */
+    pub fn with_tags(mut self, tags: Arc<dyn TagRepository>) -> Self {
+        self.tags = Some(tags);
+        self
+    }
+    
     /**
      * Inicia una tarea asíncrona para limpiar entradas expiradas de la caché.
      * 
@@ -207,8 +319,14 @@ impl SearchService {
      * @return Archivos que cumplen con los criterios
      */
     fn filter_files(&self, files: Vec<FileDto>, criteria: &SearchCriteriaDto) -> Vec<FileDto> {
+        let hidden_rules = self.hidden_file_rules.as_ref().map(|rules| rules.instance_rules());
         files.into_iter()
             .filter(|file| {
+                // Excluir ficheros ocultos y de sistema
+                if hidden_rules.is_some_and(|rules| rules.is_hidden(&file.name)) {
+                    return false;
+                }
+                
                 // Filtrar por nombre
                 if let Some(name_query) = &criteria.name_contains {
                     if !file.name.to_lowercase().contains(&name_query.to_lowercase()) {
@@ -320,6 +438,24 @@ impl SearchService {
             .collect()
     }
     
+    /**
+     * Comprueba si el usuario puede ver un elemento en los resultados.
+     * 
+     * Puede ver lo suyo, lo que no está en ninguna carpeta personal y lo que
+     * otro usuario le ha compartido con permiso de lectura.
+     * 
+     * @param caller Usuario que busca
+     * @param is_admin Si el usuario es administrador
+     * @param target Archivo o carpeta encontrado
+     * @param path Ruta del elemento
+     */
+    async fn can_read(&self, caller: ShareRecipient<'_>, is_admin: bool, target: ShareTarget, path: &str) -> bool {
+        if is_admin || owner_of_path(path).is_none_or(|owner| owner == caller.username) {
+            return true;
+        }
+        self.share_access.ensure_access(caller, &target, SharePermissions::READ).await.is_ok()
+    }
+    
     /**
      * Implementación de la búsqueda recursiva a través de carpetas.
      * 
@@ -374,6 +510,160 @@ impl SearchService {
         Ok(())
         }).await
     }
+    
+    /**
+     * Búsqueda en el contenido de los documentos indexados.
+     * 
+     * Los ficheros se devuelven por relevancia, cada uno con un fragmento del
+     * texto que coincide, y se les aplican el resto de criterios.
+     * 
+     * @param query Texto a buscar en el contenido
+     * @param criteria Criterios de búsqueda
+     * @param caller Usuario que busca
+     * @param is_admin Si el usuario es administrador
+     * @return Resultados de la búsqueda, sin carpetas
+     */
+    async fn search_content(
+        &self,
+        query: &str,
+        criteria: &SearchCriteriaDto,
+        caller: ShareRecipient<'_>,
+        is_admin: bool,
+    ) -> Result<SearchResultsDto> {
+        let full_text = self.full_text.as_ref().ok_or_else(|| {
+            DomainError::unavailable("Search", "Content search is not enabled")
+        })?;
+        
+        // Ruta de la carpeta en la que buscar de forma recursiva
+        let folder_path = match (&criteria.folder_id, criteria.recursive) {
+            (Some(folder_id), true) => {
+                Some(self.folder_repository.get_folder(folder_id).await?.path_string().to_string())
+            }
+            _ => None,
+        };
+        
+        let tagged = self.tagged_items(criteria).await?;
+        let mut files = Vec::new();
+        let mut snippets = HashMap::new();
+        for hit in full_text.search(query, MAX_CONTENT_HITS).await? {
+            // El índice puede ir por detrás de un borrado
+            let file = match self.file_repository.get_file(&hit.file_id).await {
+                Ok(file) => FileDto::from(file),
+                Err(_) => continue,
+            };
+            
+            let in_scope = match (&criteria.folder_id, &folder_path) {
+                (None, _) => true,
+                (Some(_), Some(folder_path)) => file.path.starts_with(&format!("{}/", folder_path)),
+                (Some(folder_id), None) => file.folder_id.as_ref() == Some(folder_id),
+            };
+            let has_tags = tagged.as_ref().is_none_or(|tagged| tagged.files.contains(&file.id));
+            if !in_scope || !has_tags {
+                continue;
+            }
+            // El índice no sabe de quién es cada documento
+            if self.can_read(caller, is_admin, ShareTarget::File(file.id.clone()), &file.path).await {
+                snippets.insert(hit.file_id.clone(), ContentMatchDto {
+                    file_id: hit.file_id,
+                    score: hit.score,
+                    snippet: hit.snippet,
+                });
+                files.push(file);
+            }
+        }
+        
+        let files = self.filter_files(files, criteria);
+        let total_count = files.len();
+        let page: Vec<FileDto> = files.into_iter().skip(criteria.offset).take(criteria.limit).collect();
+        let content_matches = page.iter()
+            .filter_map(|file| snippets.remove(&file.id))
+            .collect();
+        
+        let mut results = SearchResultsDto::new(
+            page,
+            Vec::new(),
+            criteria.limit,
+            criteria.offset,
+            Some(total_count),
+        );
+        results.content_matches = content_matches;
+        Ok(results)
+    }
+    
+    /**
+     * Elementos que llevan todas las etiquetas de los criterios.
+     * 
+     * @param criteria Criterios de búsqueda
+     * @return `None` si no se filtra por etiqueta
+     */
+    async fn tagged_items(&self, criteria: &SearchCriteriaDto) -> Result<Option<TaggedItemIds>> {
+        let Some(tag_ids) = criteria.tags.as_ref().filter(|tags| !tags.is_empty()) else {
+            return Ok(None);
+        };
+        let tags = self.tags.as_ref().ok_or_else(|| {
+            DomainError::unavailable("Search", "Tag filtering is not enabled")
+        })?;
+        
+        // Una etiqueta repetida no debe exigir dos asignaciones
+        let mut tag_ids = tag_ids.clone();
+        tag_ids.sort();
+        tag_ids.dedup();
+        
+        let mut tagged = TaggedItemIds { files: HashSet::new(), folders: HashSet::new() };
+        for item in tags.find_items_with_all_tags(&tag_ids).await? {
+            match item.item_type {
+                TaggedItemType::File => tagged.files.insert(item.item_id),
+                TaggedItemType::Folder => tagged.folders.insert(item.item_id),
+            };
+        }
+        Ok(Some(tagged))
+    }
+    
+    /**
+     * Contactos que coinciden con el texto en las libretas propias y
+     * compartidas con el usuario. Se consulta cada libreta a la vez.
+     * 
+     * @param dav Repositorios de calendarios y contactos
+     * @param query Texto a buscar
+     * @param user_id Usuario que busca
+     * @param limit Número máximo de contactos
+     */
+    async fn search_contacts(dav: &DavSearchRepositories, query: &str, user_id: &str, limit: usize) -> Result<Vec<ContactDto>> {
+        let mut address_books = dav.address_book_repository.get_address_books_by_owner(user_id).await?;
+        address_books.extend(dav.address_book_repository.get_shared_address_books(user_id).await?);
+        let mut seen = HashSet::new();
+        address_books.retain(|address_book| seen.insert(address_book.id));
+        
+        let per_book = futures::future::try_join_all(address_books.iter().map(|address_book| {
+            dav.contact_repository.search_contacts(&address_book.id, query)
+        })).await?;
+        
+        Ok(per_book.into_iter()
+            .flatten()
+            .take(limit)
+            .map(ContactDto::from)
+            .collect())
+    }
+    
+    /**
+     * Eventos que coinciden con el texto en los calendarios propios y
+     * compartidos con el usuario.
+     * 
+     * @param dav Repositorios de calendarios y contactos
+     * @param query Texto a buscar
+     * @param user_id Usuario que busca
+     * @param limit Número máximo de eventos
+     */
+    async fn search_events(dav: &DavSearchRepositories, query: &str, user_id: &str, limit: usize) -> Result<Vec<CalendarEventDto>> {
+        let mut calendars = dav.calendar_repository.list_calendars_by_owner(user_id).await?;
+        calendars.extend(dav.calendar_repository.list_calendars_shared_with_user(user_id).await?);
+        let mut calendar_ids: Vec<_> = calendars.iter().map(|calendar| *calendar.id()).collect();
+        calendar_ids.sort();
+        calendar_ids.dedup();
+        
+        let events = dav.event_repository.search_events(&calendar_ids, query, limit as i64).await?;
+        Ok(events.into_iter().map(CalendarEventDto::from).collect())
+    }
 }
 
 #[async_trait]
@@ -382,18 +672,25 @@ impl SearchUseCase for SearchService {
      * Realiza una búsqueda basada en los criterios especificados.
      * 
      * @param criteria Criterios de búsqueda
+     * @param caller Usuario que busca
+     * @param is_admin Si el usuario es administrador
      * @return Resultados de la búsqueda
      */
-    async fn search(&self, criteria: SearchCriteriaDto) -> Result<SearchResultsDto> {
-        // TODO: Obtener ID de usuario del contexto de autenticación
-        let user_id = "default-user";
-        let cache_key = self.create_cache_key(&criteria, user_id);
+    async fn search(&self, criteria: SearchCriteriaDto, caller: ShareRecipient<'_>, is_admin: bool) -> Result<SearchResultsDto> {
+        let cache_key = self.create_cache_key(&criteria, caller.user_id);
         
         // Intentar obtener resultados de la caché
         if let Some(cached_results) = self.get_from_cache(&cache_key) {
             return Ok(cached_results);
         }
         
+        // La búsqueda por contenido la resuelve el índice de texto completo
+        if let Some(content) = criteria.content.as_deref().map(str::trim).filter(|content| !content.is_empty()) {
+            let search_results = self.search_content(content, &criteria, caller, is_admin).await?;
+            self.store_in_cache(cache_key, search_results.clone());
+            return Ok(search_results);
+        }
+        
         // Inicializar colecciones para resultados
         let mut found_files: Vec<FileDto> = Vec::new();
         let mut found_folders: Vec<FolderDto> = Vec::new();
@@ -406,6 +703,30 @@ impl SearchUseCase for SearchService {
             &mut found_folders,
         ).await?;
         
+        // Los permisos se filtran al final para no perder lo compartido dentro
+        // de carpetas ajenas
+        let mut readable_files = Vec::with_capacity(found_files.len());
+        for file in found_files {
+            if self.can_read(caller, is_admin, ShareTarget::File(file.id.clone()), &file.path).await {
+                readable_files.push(file);
+            }
+        }
+        let mut found_files = readable_files;
+        let mut readable_folders = Vec::with_capacity(found_folders.len());
+        for folder in found_folders {
+            if self.can_read(caller, is_admin, ShareTarget::Folder(folder.id.clone()), &folder.path).await {
+                readable_folders.push(folder);
+            }
+        }
+        let mut found_folders = readable_folders;
+        
+        // Las etiquetas se filtran al final: la búsqueda recursiva solo entra
+        // en las carpetas que cumplen los criterios
+        if let Some(tagged) = self.tagged_items(&criteria).await? {
+            found_files.retain(|file| tagged.files.contains(&file.id));
+            found_folders.retain(|folder| tagged.folders.contains(&folder.id));
+        }
+        
         // Aplicar paginación
         let total_count = found_files.len() + found_folders.len();
         
@@ -459,6 +780,74 @@ impl SearchUseCase for SearchService {
         Ok(search_results)
     }
     
+    /**
+     * Busca a la vez en archivos, contactos y eventos de calendario.
+     * 
+     * Cada tipo se busca en paralelo y tiene su propio grupo en la respuesta.
+     * Los contactos y eventos solo se buscan si el servicio tiene acceso a
+     * ellos; si no, sus grupos no aparecen.
+     * 
+     * @param query Texto a buscar, tipos de resultado y límite por grupo
+     * @param caller Usuario que busca; se consultan sus libretas y calendarios
+     * y solo los archivos que puede leer
+     * @param is_admin Si el usuario es administrador
+     * @return Resultados agrupados por tipo
+     */
+    async fn search_all(&self, query: UnifiedSearchQueryDto, caller: ShareRecipient<'_>, is_admin: bool) -> Result<UnifiedSearchResultsDto> {
+        let text = query.query.trim();
+        if text.is_empty() {
+            return Err(DomainError::validation_error("The search query must not be empty"));
+        }
+        let limit = query.limit.clamp(1, MAX_GROUP_LIMIT);
+        let dav = self.dav.as_ref();
+        
+        let files = async {
+            if !query.includes(SearchResultType::Files) {
+                return Ok(None);
+            }
+            let criteria = SearchCriteriaDto {
+                name_contains: Some(text.to_string()),
+                limit,
+                ..Default::default()
+            };
+            self.search(criteria, caller, is_admin).await.map(Some)
+        };
+        let contacts = async {
+            match dav {
+                Some(dav) if query.includes(SearchResultType::Contacts) => {
+                    Self::search_contacts(dav, text, caller.user_id, limit).await.map(Some)
+                }
+                _ => Ok(None),
+            }
+        };
+        let events = async {
+            match dav {
+                Some(dav) if query.includes(SearchResultType::CalendarEvents) => {
+                    Self::search_events(dav, text, caller.user_id, limit).await.map(Some)
+                }
+                _ => Ok(None),
+            }
+        };
+        let (files, contacts, events) = tokio::try_join!(files, contacts, events)?;
+        
+        let mut groups = Vec::new();
+        if let Some(results) = files {
+            groups.push(SearchResultGroupDto::Files(results.files));
+            groups.push(SearchResultGroupDto::Folders(results.folders));
+        }
+        if let Some(contacts) = contacts {
+            groups.push(SearchResultGroupDto::Contacts(contacts));
+        }
+        if let Some(events) = events {
+            groups.push(SearchResultGroupDto::CalendarEvents(events));
+        }
+        
+        Ok(UnifiedSearchResultsDto {
+            query: text.to_string(),
+            groups,
+        })
+    }
+    
     /**
      * Limpia la caché de resultados de búsqueda.
      * 
@@ -480,10 +869,14 @@ impl SearchService {
         
         #[async_trait]
         impl SearchUseCase for SearchServiceStub {
-            async fn search(&self, _criteria: SearchCriteriaDto) -> Result<SearchResultsDto> {
+            async fn search(&self, _criteria: SearchCriteriaDto, _caller: ShareRecipient<'_>, _is_admin: bool) -> Result<SearchResultsDto> {
                 Ok(SearchResultsDto::empty())
             }
             
+            async fn search_all(&self, query: UnifiedSearchQueryDto, _caller: ShareRecipient<'_>, _is_admin: bool) -> Result<UnifiedSearchResultsDto> {
+                Ok(UnifiedSearchResultsDto { query: query.query, groups: Vec::new() })
+            }
+            
             async fn clear_search_cache(&self) -> Result<()> {
                 Ok(())
             }
@@ -491,4 +884,197 @@ impl SearchService {
         
         SearchServiceStub
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use futures::Stream;
+    use crate::application::ports::full_text_ports::FullTextHit;
+    use crate::application::services::test_access::HomeOnlyAccess;
+    use crate::domain::entities::file::File;
+    use crate::domain::entities::folder::Folder;
+    use crate::domain::services::path_service::StoragePath;
+
+    /// Carpetas personales de `alice` y `bob` con un archivo en cada una; el
+    /// ID de cada elemento es su ruta
+    struct TwoHomes {
+        folders: Vec<Folder>,
+        files: Vec<File>,
+    }
+
+    impl TwoHomes {
+        fn new() -> Self {
+            let folders = ["alice", "bob"].iter()
+                .map(|user| {
+                    let name = format!("Mi Carpeta - {}", user);
+                    let path = StoragePath::from_string(&name);
+                    Folder::new(path.to_string(), name, path, None).unwrap()
+                })
+                .collect();
+            let files = [("alice", "budget.txt"), ("bob", "budget-2024.txt"), ("bob", "shared-budget.txt")].iter()
+                .map(|(user, name)| {
+                    let folder = StoragePath::from_string(&format!("Mi Carpeta - {}", user));
+                    let path = folder.join(name);
+                    File::new(path.to_string(), name.to_string(), path, 10, "text/plain".to_string(), Some(folder.to_string())).unwrap()
+                })
+                .collect();
+            Self { folders, files }
+        }
+    }
+
+    #[async_trait]
+    impl FolderStoragePort for TwoHomes {
+        async fn create_folder(&self, _name: String, _parent_id: Option<String>) -> Result<Folder> {
+            unimplemented!()
+        }
+        async fn get_folder(&self, id: &str) -> Result<Folder> {
+            self.folders.iter().find(|folder| folder.id() == id).cloned()
+                .ok_or_else(|| DomainError::not_found("Folder", id))
+        }
+        async fn get_folder_by_path(&self, storage_path: &StoragePath) -> Result<Folder> {
+            self.get_folder(&storage_path.to_string()).await
+        }
+        async fn list_folders(&self, parent_id: Option<&str>) -> Result<Vec<Folder>> {
+            Ok(self.folders.iter().filter(|folder| folder.parent_id() == parent_id).cloned().collect())
+        }
+        async fn list_folders_paginated(
+            &self,
+            parent_id: Option<&str>,
+            _offset: usize,
+            _limit: usize,
+            _include_total: bool
+        ) -> Result<(Vec<Folder>, Option<usize>)> {
+            Ok((self.list_folders(parent_id).await?, None))
+        }
+        async fn rename_folder(&self, _id: &str, _new_name: String) -> Result<Folder> {
+            unimplemented!()
+        }
+        async fn move_folder(&self, _id: &str, _new_parent_id: Option<&str>) -> Result<Folder> {
+            unimplemented!()
+        }
+        async fn delete_folder(&self, _id: &str) -> Result<()> {
+            unimplemented!()
+        }
+        async fn folder_exists(&self, storage_path: &StoragePath) -> Result<bool> {
+            Ok(self.get_folder_by_path(storage_path).await.is_ok())
+        }
+        async fn get_folder_path(&self, id: &str) -> Result<StoragePath> {
+            Ok(self.get_folder(id).await?.storage_path().clone())
+        }
+    }
+
+    #[async_trait]
+    impl FileStoragePort for TwoHomes {
+        async fn save_file(&self, _name: String, _folder_id: Option<String>, _content_type: String, _content: Vec<u8>) -> Result<File> {
+            unimplemented!()
+        }
+        async fn get_file(&self, id: &str) -> Result<File> {
+            self.files.iter().find(|file| file.id() == id).cloned()
+                .ok_or_else(|| DomainError::not_found("File", id))
+        }
+        async fn list_files(&self, folder_id: Option<&str>) -> Result<Vec<File>> {
+            Ok(self.files.iter().filter(|file| file.folder_id() == folder_id).cloned().collect())
+        }
+        async fn delete_file(&self, _id: &str) -> Result<()> {
+            unimplemented!()
+        }
+        async fn get_file_content(&self, _id: &str) -> Result<Vec<u8>> {
+            unimplemented!()
+        }
+        async fn get_file_stream(&self, _id: &str) -> Result<Box<dyn Stream<Item = std::result::Result<Bytes, std::io::Error>> + Send>> {
+            unimplemented!()
+        }
+        async fn get_file_range(&self, _id: &str, _start: u64, _length: u64) -> Result<Box<dyn Stream<Item = std::result::Result<Bytes, std::io::Error>> + Send>> {
+            unimplemented!()
+        }
+        async fn move_file(&self, _file_id: &str, _target_folder_id: Option<String>) -> Result<File> {
+            unimplemented!()
+        }
+        async fn get_file_path(&self, id: &str) -> Result<StoragePath> {
+            Ok(self.get_file(id).await?.storage_path().clone())
+        }
+        async fn get_parent_folder_id(&self, _path: &str) -> Result<String> {
+            unimplemented!()
+        }
+        async fn update_file_content(&self, _file_id: &str, _content: Vec<u8>) -> Result<()> {
+            unimplemented!()
+        }
+    }
+
+    /// Índice en el que todos los archivos contienen cualquier texto
+    struct MatchEverything(Vec<String>);
+
+    #[async_trait]
+    impl FullTextIndexPort for MatchEverything {
+        async fn index_document(&self, _file_id: &str, _name: &str, _text: String) -> Result<()> {
+            Ok(())
+        }
+        async fn remove_document(&self, _file_id: &str) -> Result<()> {
+            Ok(())
+        }
+        async fn search(&self, query: &str, _limit: usize) -> Result<Vec<FullTextHit>> {
+            Ok(self.0.iter()
+                .map(|file_id| FullTextHit { file_id: file_id.clone(), score: 1.0, snippet: format!("<b>{}</b>", query) })
+                .collect())
+        }
+    }
+
+    fn file_ids(results: &SearchResultsDto) -> Vec<&str> {
+        let mut ids: Vec<&str> = results.files.iter().map(|file| file.id.as_str()).collect();
+        ids.sort();
+        ids
+    }
+
+    #[tokio::test]
+    async fn results_only_include_what_the_caller_may_read() {
+        let storage = Arc::new(TwoHomes::new());
+        let all_files: Vec<String> = storage.files.iter().map(|file| file.id().to_string()).collect();
+        let access = Arc::new(HomeOnlyAccess::default());
+        access.grant("alice", ShareTarget::File("/Mi Carpeta - bob/shared-budget.txt".to_string()), SharePermissions::READ);
+        let service = SearchService::new(storage.clone(), storage.clone(), access, 0, 10)
+            .with_full_text(Arc::new(MatchEverything(all_files)));
+        let alice = ShareRecipient { user_id: "alice-id", username: "alice", email: "alice@example.com" };
+        let visible_to_alice = vec!["/Mi Carpeta - alice/budget.txt", "/Mi Carpeta - bob/shared-budget.txt"];
+
+        let homes = SearchCriteriaDto { name_contains: Some("Mi Carpeta".to_string()), ..Default::default() };
+        let results = service.search(homes, alice, false).await.unwrap();
+        assert_eq!(results.folders.len(), 1);
+        assert_eq!(results.folders[0].name, "Mi Carpeta - alice");
+
+        let in_bob_home = SearchCriteriaDto {
+            name_contains: Some("budget".to_string()),
+            folder_id: Some("/Mi Carpeta - bob".to_string()),
+            ..Default::default()
+        };
+        let results = service.search(in_bob_home, alice, false).await.unwrap();
+        assert_eq!(file_ids(&results), vec!["/Mi Carpeta - bob/shared-budget.txt"]);
+
+        let by_content = SearchCriteriaDto { content: Some("budget".to_string()), ..Default::default() };
+        let results = service.search(by_content.clone(), alice, false).await.unwrap();
+        assert_eq!(file_ids(&results), visible_to_alice);
+        assert_eq!(results.content_matches.len(), 2);
+        assert!(results.content_matches.iter().all(|hit| hit.file_id != "/Mi Carpeta - bob/budget-2024.txt"));
+
+        // Los administradores lo encuentran todo
+        let results = service.search(by_content, alice, true).await.unwrap();
+        assert_eq!(results.files.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn unified_search_only_lists_readable_folders() {
+        let storage = Arc::new(TwoHomes::new());
+        let service = SearchService::new(storage.clone(), storage, Arc::new(HomeOnlyAccess::default()), 0, 10);
+        let bob = ShareRecipient { user_id: "bob-id", username: "bob", email: "bob@example.com" };
+        let query = UnifiedSearchQueryDto { query: "Mi Carpeta".to_string(), types: Vec::new(), limit: 10 };
+
+        let results = service.search_all(query, bob, false).await.unwrap();
+        let folders: Vec<&str> = results.groups.iter()
+            .flat_map(|group| match group {
+                SearchResultGroupDto::Folders(folders) => folders.iter().map(|folder| folder.name.as_str()).collect(),
+                _ => Vec::new(),
+            })
+            .collect();
+        assert_eq!(folders, vec!["Mi Carpeta - bob"]);
+    }
+}
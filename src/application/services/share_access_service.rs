@@ -0,0 +1,160 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::application::ports::outbound::{FileStoragePort, FolderStoragePort};
+use crate::application::ports::share_ports::{ShareAccessUseCase, ShareRecipient, ShareStoragePort, ShareTarget};
+use crate::application::services::user_group_service::UserGroupService;
+use crate::common::errors::DomainError;
+use crate::domain::entities::folder::Folder;
+use crate::domain::entities::share::{normalize_email, ShareItemType, SharePermissions};
+use crate::domain::services::ownership_service::owner_of_path;
+use crate::domain::services::path_service::StoragePath;
+
+/// Carpetas que se recorren como mucho hacia arriba desde un elemento
+const MAX_FOLDER_DEPTH: usize = 64;
+
+/// Elemento que una petición tiene delante: su ruta y, del propio elemento
+/// hacia arriba, los identificadores que un enlace puede compartir
+struct ResolvedItem {
+    path: String,
+    chain: Vec<(String, ShareItemType)>,
+}
+
+/// Servicio que decide qué puede hacer un destinatario de enlaces con los
+/// ficheros de otro usuario.
+///
+/// El dueño de una carpeta personal y los administradores no pasan por aquí
+/// con restricciones: el resto solo llega a esos ficheros a través de
+/// enlaces dirigidos a su correo o a alguno de sus grupos, y puede hacer lo
+/// que sumen los permisos de todos los enlaces vigentes del elemento y de
+/// las carpetas que lo contienen. Los enlaces abiertos a cualquiera no
+/// cuentan: se usan desde la página pública, no con la sesión del usuario.
+pub struct ShareAccessService {
+    share_repository: Arc<dyn ShareStoragePort>,
+    file_repository: Arc<dyn FileStoragePort>,
+    folder_repository: Arc<dyn FolderStoragePort>,
+    user_groups: Option<Arc<UserGroupService>>,
+}
+
+impl ShareAccessService {
+    pub fn new(
+        share_repository: Arc<dyn ShareStoragePort>,
+        file_repository: Arc<dyn FileStoragePort>,
+        folder_repository: Arc<dyn FolderStoragePort>,
+    ) -> Self {
+        Self { share_repository, file_repository, folder_repository, user_groups: None }
+    }
+
+    /// Reconoce como destinatarios a los miembros de los grupos de un enlace
+    pub fn with_groups(mut self, user_groups: Arc<UserGroupService>) -> Self {
+        self.user_groups = Some(user_groups);
+        self
+    }
+
+    /// Añade a la cadena la carpeta y todas las que la contienen
+    async fn push_folders(&self, chain: &mut Vec<(String, ShareItemType)>, folder: Folder) {
+        let mut parent_id = folder.parent_id().map(str::to_string);
+        chain.push((folder.id().to_string(), ShareItemType::Folder));
+        for _ in 0..MAX_FOLDER_DEPTH {
+            let Some(id) = parent_id.take() else { break };
+            let Ok(parent) = self.folder_repository.get_folder(&id).await else { break };
+            parent_id = parent.parent_id().map(str::to_string);
+            chain.push((id, ShareItemType::Folder));
+        }
+    }
+
+    /// Resuelve el elemento. `None` si no existe: el handler responderá
+    async fn resolve(&self, target: &ShareTarget) -> Option<ResolvedItem> {
+        let mut chain = Vec::new();
+        match target {
+            ShareTarget::File(id) => {
+                let file = self.file_repository.get_file(id).await.ok()?;
+                chain.push((file.id().to_string(), ShareItemType::File));
+                if let Some(folder_id) = file.folder_id() {
+                    if let Ok(folder) = self.folder_repository.get_folder(folder_id).await {
+                        self.push_folders(&mut chain, folder).await;
+                    }
+                }
+                Some(ResolvedItem { path: file.path_string().to_string(), chain })
+            }
+            ShareTarget::Folder(id) => {
+                let folder = self.folder_repository.get_folder(id).await.ok()?;
+                let path = folder.path_string().to_string();
+                self.push_folders(&mut chain, folder).await;
+                Some(ResolvedItem { path, chain })
+            }
+            ShareTarget::Path(path) => {
+                // La ruta puede no existir todavía (una subida, un MKCOL):
+                // cuenta la carpeta existente más cercana
+                let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+                for depth in (1..=segments.len()).rev() {
+                    let folder_path = StoragePath::from_string(&segments[..depth].join("/"));
+                    let Ok(folder) = self.folder_repository.get_folder_by_path(&folder_path).await else {
+                        continue;
+                    };
+                    if depth + 1 == segments.len() {
+                        let name = segments[depth];
+                        if let Ok(files) = self.file_repository.list_files(Some(folder.id())).await {
+                            if let Some(file) = files.iter().find(|file| file.name() == name) {
+                                chain.push((file.id().to_string(), ShareItemType::File));
+                            }
+                        }
+                    }
+                    self.push_folders(&mut chain, folder).await;
+                    break;
+                }
+                Some(ResolvedItem { path: path.clone(), chain })
+            }
+        }
+    }
+
+    /// Grupos del usuario, para encontrar los enlaces dirigidos a ellos
+    async fn recipient_groups(&self, recipient: &ShareRecipient<'_>) -> Result<Vec<String>, DomainError> {
+        match &self.user_groups {
+            Some(groups) => groups.group_ids_of(recipient.user_id).await,
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl ShareAccessUseCase for ShareAccessService {
+    async fn ensure_access(
+        &self,
+        recipient: ShareRecipient<'_>,
+        target: &ShareTarget,
+        required: SharePermissions,
+    ) -> Result<(), DomainError> {
+        // Las rutas con dueño a la vista se deciden sin tocar el almacenamiento
+        if let ShareTarget::Path(path) = target {
+            if owner_of_path(path).is_none_or(|owner| owner == recipient.username) {
+                return Ok(());
+            }
+        }
+
+        let Some(item) = self.resolve(target).await else {
+            return Ok(());
+        };
+        if owner_of_path(&item.path).is_none_or(|owner| owner == recipient.username) {
+            return Ok(());
+        }
+
+        let mut granted = SharePermissions::empty();
+        let groups = self.recipient_groups(&recipient).await?;
+        let shares = self.share_repository
+            .find_shares_for_recipient(&item.chain, &normalize_email(recipient.email), &groups)
+            .await?;
+        for share in shares.iter().filter(|share| !share.is_expired()) {
+            granted |= share.permissions;
+            if granted.contains(required) {
+                return Ok(());
+            }
+        }
+
+        Err(DomainError::access_denied(
+            "Share",
+            format!("Your shares do not allow this operation on {}", item.path),
+        ))
+    }
+}
@@ -0,0 +1,355 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::{DateTime, Duration, Utc};
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use tokio::sync::Mutex;
+
+use crate::application::ports::mail_ports::{MailSenderPort, OutgoingMail};
+use crate::common::errors::DomainError;
+use crate::domain::entities::share::normalize_email;
+
+/// Fallos seguidos que bloquean un correo en un enlace
+const MAX_CODE_ATTEMPTS: u32 = 5;
+
+/// Tiempo que un correo queda bloqueado tras agotar los intentos; también
+/// el tiempo sin actividad tras el que se olvidan sus fallos
+const LOCKOUT_SECS: i64 = 15 * 60;
+
+/// Tiempo mínimo entre dos códigos para el mismo correo y enlace
+const CODE_RESEND_INTERVAL_SECS: i64 = 60;
+
+/// Cada cuánto se purgan los códigos, límites y sesiones caducados
+const EXPIRY_INTERVAL_SECS: u64 = 60;
+
+/// Clave de los códigos y los límites: (id del enlace, correo normalizado)
+type CodeKey = (String, String);
+
+/// Código de un solo uso pendiente de introducir
+struct PendingCode {
+    code_hash: Vec<u8>,
+    expires_at: DateTime<Utc>,
+}
+
+/// Reenvíos y fallos de un correo en un enlace. Se guardan aparte del código
+/// pendiente, de modo que pedir otro código no reinicia ni el intervalo de
+/// reenvío ni los fallos acumulados
+#[derive(Default)]
+struct Throttle {
+    last_sent: Option<DateTime<Utc>>,
+    failures: u32,
+    locked_until: Option<DateTime<Utc>>,
+    /// A partir de aquí el límite se olvida
+    expires_at: DateTime<Utc>,
+}
+
+impl Throttle {
+    fn is_locked(&self, now: DateTime<Utc>) -> bool {
+        self.locked_until.is_some_and(|until| until > now)
+    }
+
+    fn is_resend_too_soon(&self, now: DateTime<Utc>) -> bool {
+        self.last_sent.is_some_and(|sent| now - sent < Duration::seconds(CODE_RESEND_INTERVAL_SECS))
+    }
+
+    /// Anota un código incorrecto y bloquea el correo al agotar los intentos
+    fn record_failure(&mut self, now: DateTime<Utc>) {
+        self.failures += 1;
+        if self.failures >= MAX_CODE_ATTEMPTS {
+            self.locked_until = Some(now + Duration::seconds(LOCKOUT_SECS));
+        }
+        self.touch(now);
+    }
+
+    fn touch(&mut self, now: DateTime<Utc>) {
+        self.expires_at = self.expires_at.max(now + Duration::seconds(LOCKOUT_SECS));
+    }
+}
+
+/// Sesión de un visitante que ha verificado su correo; solo abre un enlace
+struct EmailSession {
+    share_id: String,
+    email: String,
+    expires_at: DateTime<Utc>,
+}
+
+/// Resultado de pedir un código
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodeRequestOutcome {
+    Sent,
+    /// Ya se envió un código hace muy poco, o el correo está bloqueado por
+    /// demasiados fallos; no se manda otro
+    Throttled,
+}
+
+/// Sesión concedida tras verificar un código
+#[derive(Debug, Clone)]
+pub struct GrantedEmailSession {
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Servicio de acceso a enlaces compartidos verificando el correo del
+/// visitante.
+///
+/// El visitante pide un código de seis cifras que se le envía por correo y,
+/// al introducirlo, obtiene una sesión temporal que solo vale para ese
+/// enlace. Los códigos se guardan como hash y caducan; no se reenvían más de
+/// uno por minuto y, tras cinco fallos seguidos, el correo queda bloqueado
+/// en ese enlace durante un rato aunque pida códigos nuevos. Todo el estado
+/// vive en memoria y una tarea periódica purga lo caducado: al reiniciar el
+/// servidor los visitantes vuelven a verificar su correo.
+pub struct ShareEmailAccessService {
+    mail_sender: Arc<dyn MailSenderPort>,
+    code_ttl: Duration,
+    session_ttl: Duration,
+    // Se bloquea antes que `codes` cuando hacen falta los dos
+    throttles: Mutex<HashMap<CodeKey, Throttle>>,
+    codes: Mutex<HashMap<CodeKey, PendingCode>>,
+    // Clave: token de la sesión
+    sessions: Mutex<HashMap<String, EmailSession>>,
+}
+
+impl ShareEmailAccessService {
+    pub fn new(mail_sender: Arc<dyn MailSenderPort>, code_ttl_secs: u64, session_secs: u64) -> Self {
+        Self {
+            mail_sender,
+            code_ttl: Duration::seconds(code_ttl_secs as i64),
+            session_ttl: Duration::seconds(session_secs as i64),
+            throttles: Mutex::new(HashMap::new()),
+            codes: Mutex::new(HashMap::new()),
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Lanza la tarea que olvida los códigos, límites y sesiones caducados
+    pub fn start_expiry_job(self: Arc<Self>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(EXPIRY_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+                self.expire(Utc::now()).await;
+            }
+        });
+    }
+
+    async fn expire(&self, now: DateTime<Utc>) {
+        self.throttles.lock().await.retain(|_, throttle| throttle.expires_at > now);
+        self.codes.lock().await.retain(|_, pending| pending.expires_at > now);
+        self.sessions.lock().await.retain(|_, session| session.expires_at > now);
+    }
+
+    /// Genera un código para el correo y lo envía con el enlace al que da acceso
+    pub async fn send_code(&self, share_id: &str, email: &str, share_url: &str) -> Result<CodeRequestOutcome, DomainError> {
+        let now = Utc::now();
+        let email = normalize_email(email);
+        let key = (share_id.to_string(), email.clone());
+        let code = random_code();
+        {
+            let mut throttles = self.throttles.lock().await;
+            let throttle = current_throttle(&mut throttles, &key, now);
+            if throttle.is_locked(now) || throttle.is_resend_too_soon(now) {
+                return Ok(CodeRequestOutcome::Throttled);
+            }
+            throttle.last_sent = Some(now);
+            throttle.touch(now);
+            self.codes.lock().await.insert(key.clone(), PendingCode {
+                code_hash: hash_code(&code),
+                expires_at: now + self.code_ttl,
+            });
+        }
+
+        let mail = OutgoingMail {
+            to: email,
+            subject: format!("Your access code: {}", code),
+            body: format!(
+                "Use this code to open the shared link {}:\n\n    {}\n\nThe code expires in {} minutes. \
+                 If you did not request it, you can ignore this message.\n",
+                share_url,
+                code,
+                self.code_ttl.num_minutes().max(1),
+            ),
+        };
+        if let Err(e) = self.mail_sender.send(mail).await {
+            // Sin correo entregado no tiene sentido esperar el código ni
+            // hacer esperar otro intento; los fallos acumulados se mantienen
+            let mut throttles = self.throttles.lock().await;
+            if let Some(throttle) = throttles.get_mut(&key) {
+                throttle.last_sent = None;
+            }
+            self.codes.lock().await.remove(&key);
+            return Err(e);
+        }
+        Ok(CodeRequestOutcome::Sent)
+    }
+
+    /// Comprueba un código y, si es correcto, abre una sesión para el enlace
+    pub async fn verify_code(&self, share_id: &str, email: &str, code: &str) -> Result<GrantedEmailSession, DomainError> {
+        let now = Utc::now();
+        let email = normalize_email(email);
+        let key = (share_id.to_string(), email.clone());
+        {
+            let mut throttles = self.throttles.lock().await;
+            let throttle = current_throttle(&mut throttles, &key, now);
+            // Bloqueado, ni siquiera el código correcto abre el enlace
+            if throttle.is_locked(now) {
+                return Err(invalid_code());
+            }
+
+            let mut codes = self.codes.lock().await;
+            let matches = codes.get(&key)
+                .filter(|pending| pending.expires_at > now)
+                .is_some_and(|pending| bool::from(pending.code_hash.ct_eq(&hash_code(code.trim()))));
+            if !matches {
+                throttle.record_failure(now);
+                if throttle.is_locked(now) {
+                    codes.remove(&key);
+                }
+                return Err(invalid_code());
+            }
+            codes.remove(&key);
+            throttle.failures = 0;
+        }
+
+        let token = random_token();
+        let expires_at = now + self.session_ttl;
+        self.sessions.lock().await.insert(token.clone(), EmailSession {
+            share_id: share_id.to_string(),
+            email,
+            expires_at,
+        });
+        Ok(GrantedEmailSession { token, expires_at })
+    }
+
+    /// Correo verificado de una sesión, si sigue vigente y es de este enlace
+    pub async fn session_email(&self, share_id: &str, token: &str) -> Option<String> {
+        let sessions = self.sessions.lock().await;
+        sessions.get(token)
+            .filter(|session| session.share_id == share_id && session.expires_at > Utc::now())
+            .map(|session| session.email.clone())
+    }
+}
+
+/// Límite vigente de un correo en un enlace; uno caducado que la purga aún
+/// no ha quitado cuenta como nuevo
+fn current_throttle<'a>(throttles: &'a mut HashMap<CodeKey, Throttle>, key: &CodeKey, now: DateTime<Utc>) -> &'a mut Throttle {
+    let throttle = throttles.entry(key.clone()).or_default();
+    if throttle.expires_at <= now {
+        *throttle = Throttle::default();
+    }
+    throttle
+}
+
+fn invalid_code() -> DomainError {
+    DomainError::access_denied("Share", "Invalid or expired code")
+}
+
+/// Código de seis cifras
+fn random_code() -> String {
+    format!("{:06}", OsRng.next_u32() % 1_000_000)
+}
+
+fn hash_code(code: &str) -> Vec<u8> {
+    Sha256::digest(code.as_bytes()).to_vec()
+}
+
+/// Valor aleatorio de 256 bits codificado para usarse en cabeceras
+fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    /// Guarda los correos en lugar de enviarlos
+    #[derive(Default)]
+    struct RecordingMailSender {
+        sent: std::sync::Mutex<Vec<OutgoingMail>>,
+    }
+
+    #[async_trait]
+    impl MailSenderPort for RecordingMailSender {
+        async fn send(&self, mail: OutgoingMail) -> Result<(), DomainError> {
+            self.sent.lock().unwrap().push(mail);
+            Ok(())
+        }
+    }
+
+    fn sent_code(sender: &RecordingMailSender) -> String {
+        let sent = sender.sent.lock().unwrap();
+        sent.last().unwrap().subject.rsplit(' ').next().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_code_opens_a_session_for_one_share() {
+        let sender = Arc::new(RecordingMailSender::default());
+        let service = ShareEmailAccessService::new(sender.clone(), 600, 3600);
+
+        let outcome = service.send_code("share1", "ana@example.com", "http://localhost/s/abc").await.unwrap();
+        assert_eq!(outcome, CodeRequestOutcome::Sent);
+        let outcome = service.send_code("share1", "ana@example.com", "http://localhost/s/abc").await.unwrap();
+        assert_eq!(outcome, CodeRequestOutcome::Throttled);
+        assert_eq!(sender.sent.lock().unwrap().len(), 1);
+
+        let code = sent_code(&sender);
+        assert!(service.verify_code("share1", "bob@example.com", &code).await.is_err());
+        let session = service.verify_code("share1", "ana@example.com", &code).await.unwrap();
+        // Los códigos son de un solo uso
+        assert!(service.verify_code("share1", "ana@example.com", &code).await.is_err());
+
+        assert_eq!(service.session_email("share1", &session.token).await.as_deref(), Some("ana@example.com"));
+        assert!(service.session_email("share2", &session.token).await.is_none());
+        assert!(service.session_email("share1", "unknown").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_emails_are_locked_out_after_too_many_attempts() {
+        let sender = Arc::new(RecordingMailSender::default());
+        let service = ShareEmailAccessService::new(sender.clone(), 600, 3600);
+        service.send_code("share1", "ana@example.com", "http://localhost/s/abc").await.unwrap();
+        let code = sent_code(&sender);
+        let wrong = if code == "000000" { "000001" } else { "000000" };
+
+        for _ in 0..MAX_CODE_ATTEMPTS {
+            assert!(service.verify_code("share1", "ana@example.com", wrong).await.is_err());
+        }
+        assert!(service.verify_code("share1", "ana@example.com", &code).await.is_err());
+
+        // Pedir otro código no levanta el bloqueo, ni siquiera pasado el
+        // intervalo de reenvío
+        service.throttles.lock().await.values_mut().for_each(|throttle| throttle.last_sent = None);
+        let outcome = service.send_code("share1", "ana@example.com", "http://localhost/s/abc").await.unwrap();
+        assert_eq!(outcome, CodeRequestOutcome::Throttled);
+        assert_eq!(sender.sent.lock().unwrap().len(), 1);
+
+        // La purga olvida el bloqueo cuando caduca
+        service.expire(Utc::now() + Duration::seconds(LOCKOUT_SECS + 1)).await;
+        let outcome = service.send_code("share1", "ana@example.com", "http://localhost/s/abc").await.unwrap();
+        assert_eq!(outcome, CodeRequestOutcome::Sent);
+        let code = sent_code(&sender);
+        assert!(service.verify_code("share1", "ana@example.com", &code).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_emails_are_normalized() {
+        let sender = Arc::new(RecordingMailSender::default());
+        let service = ShareEmailAccessService::new(sender.clone(), 600, 3600);
+
+        service.send_code("share1", " Ana@Example.com ", "http://localhost/s/abc").await.unwrap();
+        assert_eq!(sender.sent.lock().unwrap()[0].to, "ana@example.com");
+        let outcome = service.send_code("share1", "ana@example.com", "http://localhost/s/abc").await.unwrap();
+        assert_eq!(outcome, CodeRequestOutcome::Throttled);
+
+        let code = sent_code(&sender);
+        let session = service.verify_code("share1", "ANA@example.com", &code).await.unwrap();
+        assert_eq!(service.session_email("share1", &session.token).await.as_deref(), Some("ana@example.com"));
+    }
+}
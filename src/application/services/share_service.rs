@@ -1,21 +1,48 @@
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use qrcode::{EcLevel, QrCode};
 use thiserror::Error;
 
 use crate::{
     application::{
         dtos::{
+            notification_dto::ShareEventDto,
             pagination::PaginatedResponseDto,
-            share_dto::{CreateShareDto, ShareDto, UpdateShareDto},
+            share_dto::{
+                CreateShareDto, PublicShareMetadataDto, QrCodeFormat, ShareAccessLogEntryDto, ShareDto,
+                ShareEmailSessionDto, ShareQrCodeDto, SharedFolderListingDto, SharedItemDto, ShareThumbnailDto,
+                UpdateShareDto,
+            },
         },
         ports::{
+            auth_ports::UserStoragePort,
+            email_notification_ports::{EmailEvent, EmailNotificationPort},
+            event_bus_ports::{DomainEvent, EventBusPort},
+            image_preview_ports::{ImageFit, ImagePreview, ImagePreviewRequest, ImagePreviewUseCase, PREVIEW_MIME_TYPES},
+            notification_ports::NotificationUseCase,
             outbound::{FileStoragePort, FolderStoragePort},
-            share_ports::{ShareStoragePort, ShareUseCase},
+            share_ports::{ShareAccessLogPort, ShareCredentials, ShareStoragePort, SharedFileContent, ShareUseCase},
+        },
+        services::{
+            share_email_access_service::{CodeRequestOutcome, ShareEmailAccessService},
+            user_group_service::UserGroupService,
         },
     },
     common::{config::AppConfig, errors::DomainError},
-    domain::entities::share::{Share, ShareItemType, SharePermissions},
+    domain::{
+        entities::{
+            share::{normalize_email, LinkPreview, Share, ShareItemType},
+            share_access_log::{ShareAccessEntry, ShareAccessEvent},
+            share_notification::{ShareChange, SharedItemKind},
+        },
+        services::{
+            i18n_service::Locale,
+            naming_service::{self, NamingPattern},
+        },
+    },
+    infrastructure::services::image_preview_service,
 };
 
 #[derive(Debug, Error)]
@@ -53,11 +80,36 @@ impl From<ShareServiceError> for DomainError {
     }
 }
 
+/// Tamaño de las miniaturas de las vistas previas de enlaces (proporción 1.91:1 de Open Graph)
+pub const LINK_THUMBNAIL_WIDTH: u32 = 600;
+pub const LINK_THUMBNAIL_HEIGHT: u32 = 315;
+
+/// Píxeles por módulo máximos de los códigos QR
+pub const MAX_QR_SCALE: usize = 32;
+
+/// Entradas del registro de accesos que se devuelven como máximo
+const ACCESS_LOG_LIMIT: usize = 500;
+
+/// Mensaje de los enlaces que piden verificar el correo del visitante
+const EMAIL_VERIFICATION_REQUIRED: &str = "Email verification required";
+
+/// Niveles de carpetas que se recorren como mucho para comprobar que algo
+/// está dentro de una carpeta compartida
+const MAX_FOLDER_DEPTH: usize = 256;
+
 pub struct ShareService {
     config: Arc<AppConfig>,
     share_repository: Arc<dyn ShareStoragePort>,
     file_repository: Arc<dyn FileStoragePort>,
     folder_repository: Arc<dyn FolderStoragePort>,
+    image_previews: Option<Arc<dyn ImagePreviewUseCase>>,
+    user_storage: Option<Arc<dyn UserStoragePort>>,
+    email_access: Option<Arc<ShareEmailAccessService>>,
+    access_log: Option<Arc<dyn ShareAccessLogPort>>,
+    groups: Option<Arc<UserGroupService>>,
+    notifications: Option<Arc<dyn NotificationUseCase>>,
+    email_notifications: Option<Arc<dyn EmailNotificationPort>>,
+    event_bus: Option<Arc<dyn EventBusPort>>,
 }
 
 impl ShareService {
@@ -72,9 +124,251 @@ impl ShareService {
             share_repository,
             file_repository,
             folder_repository,
+            image_previews: None,
+            user_storage: None,
+            email_access: None,
+            access_log: None,
+            groups: None,
+            notifications: None,
+            email_notifications: None,
+            event_bus: None,
+        }
+    }
+
+    /// Activa las miniaturas de las vistas previas de enlaces
+    pub fn with_image_previews(mut self, image_previews: Arc<dyn ImagePreviewUseCase>) -> Self {
+        self.image_previews = Some(image_previews);
+        self
+    }
+
+    /// Desactiva los enlaces de los usuarios desactivados, por ejemplo los
+    /// borrados que están en cuarentena
+    pub fn with_user_storage(mut self, user_storage: Arc<dyn UserStoragePort>) -> Self {
+        self.user_storage = Some(user_storage);
+        self
+    }
+
+    /// Permite enlaces a los que se entra verificando el correo con un
+    /// código de un solo uso
+    pub fn with_email_access(mut self, email_access: Arc<ShareEmailAccessService>) -> Self {
+        self.email_access = Some(email_access);
+        self
+    }
+
+    /// Anota los accesos a los enlaces y las verificaciones de correo
+    pub fn with_access_log(mut self, access_log: Arc<dyn ShareAccessLogPort>) -> Self {
+        self.access_log = Some(access_log);
+        self
+    }
+
+    /// Permite limitar los enlaces a los miembros de grupos de usuarios
+    pub fn with_groups(mut self, groups: Arc<UserGroupService>) -> Self {
+        self.groups = Some(groups);
+        self
+    }
+
+    /// Avisa a los destinatarios de los enlaces cuando se les comparte algo o
+    /// se deja de compartir
+    pub fn with_notifications(mut self, notifications: Arc<dyn NotificationUseCase>) -> Self {
+        self.notifications = Some(notifications);
+        self
+    }
+
+    /// Avisa por correo a quien creó un enlace cuando se descarga algo de él
+    pub fn with_email_notifications(mut self, email_notifications: Arc<dyn EmailNotificationPort>) -> Self {
+        self.email_notifications = Some(email_notifications);
+        self
+    }
+
+    /// Publica los enlaces nuevos como eventos de dominio, por ejemplo para
+    /// los webhooks
+    pub fn with_event_bus(mut self, event_bus: Arc<dyn EventBusPort>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    /// Usuarios a los que va dirigido un enlace: las cuentas de sus correos
+    /// admitidos y los miembros de sus grupos, salvo quien lo creó. Vacío si
+    /// no hay a quién avisar
+    async fn recipients(&self, share: &Share) -> HashSet<String> {
+        let mut recipients = HashSet::new();
+        let (Some(_), Some(user_storage)) = (&self.notifications, &self.user_storage) else {
+            return recipients;
+        };
+        for email in &share.allowed_emails {
+            if let Ok(user) = user_storage.get_user_by_email(email).await {
+                recipients.insert(user.id().to_string());
+            }
+        }
+        if let Some(group_service) = self.groups.as_ref().filter(|_| !share.allowed_groups.is_empty()) {
+            match group_service.member_ids(&share.allowed_groups).await {
+                Ok(members) => recipients.extend(members),
+                Err(e) => tracing::warn!("No se pudo obtener los miembros de los grupos del enlace {}: {}", share.id, e),
+            }
+        }
+        recipients.remove(&share.created_by);
+        recipients
+    }
+
+    /// Avisa a los usuarios de un cambio en un enlace; un fallo no deshace el cambio
+    async fn notify(&self, share: &Share, change: ShareChange, recipients: HashSet<String>) {
+        let Some(notifications) = &self.notifications else {
+            return;
+        };
+        if recipients.is_empty() {
+            return;
+        }
+
+        let (item_kind, item_name) = match share.item_type {
+            ShareItemType::File => (
+                SharedItemKind::File,
+                self.file_repository.get_file(&share.item_id).await.ok().map(|file| file.name().to_string()),
+            ),
+            ShareItemType::Folder => (
+                SharedItemKind::Folder,
+                self.folder_repository.get_folder(&share.item_id).await.ok().map(|folder| folder.name().to_string()),
+            ),
+        };
+        let actor_name = match &self.user_storage {
+            Some(user_storage) => user_storage.get_user_by_id(&share.created_by).await.ok()
+                .map(|user| user.username().to_string()),
+            None => None,
+        };
+
+        let recipients: Vec<String> = recipients.into_iter().collect();
+        notifications.notify_share(&recipients, ShareEventDto {
+            change,
+            item_kind,
+            item_id: share.item_id.clone(),
+            item_name: item_name.unwrap_or_else(|| share.item_id.clone()),
+            actor_id: Some(share.created_by.clone()),
+            actor_name,
+        }).await;
+    }
+
+    /// Anota un suceso en el registro de accesos; un fallo no impide el acceso
+    async fn log_access(&self, share: &Share, event: ShareAccessEvent, email: Option<&str>, client_ip: Option<&str>) {
+        if let Some(access_log) = &self.access_log {
+            if let Err(e) = access_log.record(ShareAccessEntry::new(&share.id, event, email, client_ip)).await {
+                tracing::warn!("No se pudo anotar el acceso al enlace {}: {}", share.id, e);
+            }
+        }
+    }
+
+    /// Servicio de verificación de correo, o error si no hay servidor de correo
+    fn email_access(&self) -> Result<&Arc<ShareEmailAccessService>, ShareServiceError> {
+        self.email_access.as_ref()
+            .ok_or_else(|| ShareServiceError::Validation("Email verification needs a mail server (OXICLOUD_SMTP_HOST)".to_string()))
+    }
+
+    /// Valida y normaliza la lista de correos admitidos de un enlace
+    fn validate_allowed_emails(emails: Vec<String>) -> Result<Vec<String>, ShareServiceError> {
+        emails.into_iter()
+            .map(|email| normalize_email(&email))
+            .filter(|email| !email.is_empty())
+            .map(|email| if is_valid_email(&email) {
+                Ok(email)
+            } else {
+                Err(ShareServiceError::Validation(format!("Invalid email address: {}", email)))
+            })
+            .collect()
+    }
+
+    /// Comprueba que los grupos admitidos de un enlace existen
+    async fn validate_allowed_groups(&self, groups: Vec<String>) -> Result<Vec<String>, DomainError> {
+        if groups.is_empty() {
+            return Ok(groups);
+        }
+        let (Some(group_service), Some(_)) = (&self.groups, &self.user_storage) else {
+            return Err(ShareServiceError::Validation("User groups are not available".to_string()).into());
+        };
+        for group in &groups {
+            group_service.ensure_group_exists(group).await?;
+        }
+        Ok(groups)
+    }
+
+    /// Si un correo puede verificarse para el enlace: está en la lista o es
+    /// el de la cuenta de un miembro de alguno de los grupos admitidos
+    async fn email_allowed(&self, share: &Share, email: &str) -> bool {
+        if share.allows_email(email) {
+            return true;
+        }
+        let (Some(group_service), Some(user_storage)) = (&self.groups, &self.user_storage) else {
+            return false;
+        };
+        if share.allowed_groups.is_empty() {
+            return false;
+        }
+        let Ok(user) = user_storage.get_user_by_email(email).await else {
+            return false;
+        };
+        if !user.is_active() {
+            return false;
+        }
+        match group_service.is_member_of_any(user.id(), &share.allowed_groups).await {
+            Ok(member) => member,
+            Err(e) => {
+                tracing::warn!("No se pudo comprobar los grupos del enlace {}: {}", share.id, e);
+                false
+            }
         }
     }
 
+    /// Comprueba que quien creó el enlace sigue activo. Los enlaces de
+    /// usuarios que no existen (creados sin autenticación) siguen valiendo.
+    async fn ensure_owner_active(&self, share: &Share) -> Result<(), DomainError> {
+        if let Some(user_storage) = &self.user_storage {
+            if let Ok(user) = user_storage.get_user_by_id(&share.created_by).await {
+                if !user.is_active() {
+                    return Err(Self::share_unavailable());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// URL base de los enlaces compartidos
+    fn base_url(&self) -> String {
+        self.config.public_share.base_url.clone()
+            .unwrap_or_else(|| format!("http://{}:{}", self.config.server_host, self.config.server_port))
+    }
+
+    /// Enlace accesible sin autenticación; todos los fallos dan el mismo error
+    /// para no revelar qué tokens existen
+    async fn find_public_share(&self, token: &str) -> Result<Share, DomainError> {
+        let share = self.share_repository.find_share_by_token(token).await
+            .map_err(|_| Self::share_unavailable())?;
+        if share.is_expired() {
+            return Err(Self::share_unavailable());
+        }
+        self.ensure_owner_active(&share).await?;
+        Ok(share)
+    }
+
+    fn share_unavailable() -> DomainError {
+        DomainError::not_found("Share", "Shared link not available")
+    }
+
+    /// Dibuja un código QR como PNG en blanco y negro
+    fn render_qr_png(code: &QrCode, scale: u32) -> Vec<u8> {
+        let image = code
+            .render::<image::Luma<u8>>()
+            .quiet_zone(true)
+            .module_dimensions(scale, scale)
+            .build();
+        image_preview_service::encode_png(&image::DynamicImage::ImageLuma8(image), false)
+    }
+
+    /// Si se puede mostrar la miniatura de un fichero compartido
+    fn thumbnail_allowed(&self, share: &Share, mime_type: &str) -> bool {
+        share.link_preview == LinkPreview::Thumbnail
+            && share.password_hash.is_none()
+            && !share.email_verification
+            && self.image_previews.is_some()
+            && PREVIEW_MIME_TYPES.contains(&mime_type)
+    }
+
     /// Verifica que el elemento a compartir existe
     async fn verify_item_exists(
         &self,
@@ -99,10 +393,100 @@ impl ShareService {
     }
 
     /// Hash de contraseña
-    fn hash_password(&self, password: &str) -> String {
-        // En una implementación real, usar un algoritmo seguro como bcrypt
-        // Para simplificar, solo devolvemos la misma contraseña
-        password.to_string()
+    fn hash_password(&self, password: &str) -> Result<String, ShareServiceError> {
+        Share::hash_password(password).map_err(|e| ShareServiceError::Validation(e.to_string()))
+    }
+
+    /// Sustituye la contraseña en claro de un enlace anterior a los hashes por
+    /// su hash. `password` es la contraseña ya verificada
+    async fn rehash_legacy_password(&self, share: &Share, password: &str) -> Result<(), DomainError> {
+        if !share.has_legacy_password() {
+            return Ok(());
+        }
+        let password_hash = self.hash_password(password)?;
+        self.share_repository.update_share(&share.clone().with_password(Some(password_hash))).await?;
+        Ok(())
+    }
+
+    /// Migra las contraseñas guardadas en claro antes de que se guardaran
+    /// hashes. Se llama al arrancar; devuelve cuántos enlaces se han migrado
+    pub async fn hash_legacy_passwords(&self) -> Result<usize, DomainError> {
+        let mut migrated = 0;
+        for share in self.share_repository.find_all_shares().await? {
+            if let Some(password) = share.password_hash.clone().filter(|_| share.has_legacy_password()) {
+                self.rehash_legacy_password(&share, &password).await?;
+                migrated += 1;
+            }
+        }
+        Ok(migrated)
+    }
+
+    /// Correo con el que el visitante verificó un enlace que lo exige, o
+    /// `None` si el enlace no pide verificación
+    async fn session_email(&self, share: &Share, email_session: Option<&str>) -> Result<Option<String>, ShareServiceError> {
+        if !share.email_verification {
+            return Ok(None);
+        }
+        let email = match (&self.email_access, email_session) {
+            (Some(email_access), Some(session)) => email_access.session_email(&share.id, session).await,
+            _ => None,
+        };
+        email.map(Some).ok_or_else(|| ShareServiceError::AccessDenied(EMAIL_VERIFICATION_REQUIRED.to_string()))
+    }
+
+    /// Abre el contenido de un enlace: comprueba que no ha caducado, que su
+    /// creador sigue activo, que permite leer o subir según `uploading`, la
+    /// contraseña y la sesión de correo. Devuelve el enlace y el correo verificado
+    async fn open_shared_content(&self, token: &str, credentials: &ShareCredentials<'_>, uploading: bool) -> Result<(Share, Option<String>), DomainError> {
+        let share = self
+            .share_repository
+            .find_share_by_token(token)
+            .await
+            .map_err(|e| ShareServiceError::NotFound(format!("Share with token {} not found: {}", token, e)))?;
+
+        if share.is_expired() {
+            return Err(ShareServiceError::Expired.into());
+        }
+        self.ensure_owner_active(&share).await?;
+        if uploading && !share.permissions.upload() {
+            return Err(ShareServiceError::AccessDenied("This link does not accept uploads".to_string()).into());
+        }
+        if !uploading && !share.permissions.read() {
+            return Err(ShareServiceError::AccessDenied("This link does not allow reading its content".to_string()).into());
+        }
+
+        if share.password_hash.is_some() {
+            match credentials.password {
+                Some(password) if share.verify_password(password) => {
+                    if let Err(e) = self.rehash_legacy_password(&share, password).await {
+                        tracing::warn!("No se pudo guardar el hash de la contraseña del enlace {}: {}", share.id, e);
+                    }
+                }
+                Some(_) => {
+                    self.log_access(&share, ShareAccessEvent::PasswordRejected, None, credentials.client_ip).await;
+                    return Err(ShareServiceError::InvalidPassword("Invalid share password".to_string()).into());
+                }
+                None => return Err(ShareServiceError::InvalidPassword("Share password required".to_string()).into()),
+            }
+        }
+
+        let email = self.session_email(&share, credentials.email_session).await?;
+        Ok((share, email))
+    }
+
+    /// Si la carpeta `folder_id` es `root_id` o está dentro de ella
+    async fn is_within_folder(&self, folder_id: &str, root_id: &str) -> Result<bool, DomainError> {
+        let mut current = Some(folder_id.to_string());
+        for _ in 0..MAX_FOLDER_DEPTH {
+            let Some(id) = current else {
+                return Ok(false);
+            };
+            if id == root_id {
+                return Ok(true);
+            }
+            current = self.folder_repository.get_folder(&id).await?.parent_id().map(str::to_string);
+        }
+        Ok(false)
     }
 }
 
@@ -124,7 +508,23 @@ impl ShareUseCase for ShareService {
         let permissions = dto.permissions.map(|p| p.to_entity());
 
         // Hash de contraseña si existe
-        let password_hash = dto.password.map(|p| self.hash_password(&p));
+        let password_hash = dto.password.map(|p| self.hash_password(&p)).transpose()?;
+
+        // Nivel de detalle de las vistas previas del enlace
+        let link_preview = match dto.link_preview.as_deref() {
+            Some(value) => LinkPreview::try_from(value)
+                .map_err(|e| ShareServiceError::Validation(e.to_string()))?,
+            None => LinkPreview::default(),
+        };
+
+        // Verificación del correo de los visitantes; limitar el enlace a
+        // grupos la activa, ya que es como se identifica a sus miembros
+        let allowed_groups = self.validate_allowed_groups(dto.allowed_groups.unwrap_or_default()).await?;
+        let email_verification = dto.email_verification.unwrap_or(false) || !allowed_groups.is_empty();
+        if email_verification {
+            self.email_access()?;
+        }
+        let allowed_emails = Self::validate_allowed_emails(dto.allowed_emails.unwrap_or_default())?;
 
         // Crear la entidad Share
         let share = Share::new(
@@ -135,7 +535,10 @@ impl ShareUseCase for ShareService {
             password_hash,
             dto.expires_at,
         )
-        .map_err(|e| ShareServiceError::Validation(e.to_string()))?;
+        .map_err(|e| ShareServiceError::Validation(e.to_string()))?
+        .with_link_preview(link_preview)
+        .with_email_verification(email_verification, allowed_emails)
+        .with_allowed_groups(allowed_groups);
 
         // Guardar en el repositorio
         let saved_share = self
@@ -144,8 +547,19 @@ impl ShareUseCase for ShareService {
             .await
             .map_err(|e| ShareServiceError::Repository(e.to_string()))?;
 
+        let recipients = self.recipients(&saved_share).await;
+        self.notify(&saved_share, ShareChange::Shared, recipients).await;
+        if let Some(event_bus) = &self.event_bus {
+            event_bus.publish(DomainEvent::ShareCreated {
+                share_id: saved_share.id.clone(),
+                item_id: saved_share.item_id.clone(),
+                item_type: saved_share.item_type.to_string(),
+                created_by: saved_share.created_by.clone(),
+            });
+        }
+
         // Convertir la entidad a DTO para la respuesta
-        Ok(ShareDto::from_entity(&saved_share, &format!("http://{}:{}", self.config.server_host, self.config.server_port)))
+        Ok(ShareDto::from_entity(&saved_share, &self.base_url()))
     }
 
     async fn get_shared_link(&self, id: &str) -> Result<ShareDto, DomainError> {
@@ -160,9 +574,40 @@ impl ShareUseCase for ShareService {
         if share.is_expired() {
             return Err(ShareServiceError::Expired.into());
         }
+        self.ensure_owner_active(&share).await?;
 
         // Convertir la entidad a DTO para la respuesta
-        Ok(ShareDto::from_entity(&share, &format!("http://{}:{}", self.config.server_host, self.config.server_port)))
+        Ok(ShareDto::from_entity(&share, &self.base_url()))
+    }
+
+    async fn get_shared_link_qr_code(
+        &self,
+        id: &str,
+        user_id: &str,
+        format: QrCodeFormat,
+        scale: usize,
+    ) -> Result<ShareQrCodeDto, DomainError> {
+        let share = self.get_shared_link(id).await?;
+        if share.created_by != user_id {
+            return Err(ShareServiceError::AccessDenied("Only the owner of a share can get its QR code".to_string()).into());
+        }
+
+        // Solo se codifica la URL pública; la contraseña nunca va en el código
+        let code = QrCode::with_error_correction_level(share.url.as_bytes(), EcLevel::M)
+            .map_err(|e| ShareServiceError::Validation(format!("Cannot encode the link as a QR code: {}", e)))?;
+        let scale = scale.clamp(1, MAX_QR_SCALE);
+        let (content, mime_type) = match format {
+            QrCodeFormat::Png => (Self::render_qr_png(&code, scale as u32), "image/png"),
+            QrCodeFormat::Svg => {
+                let svg = code
+                    .render::<qrcode::render::svg::Color>()
+                    .quiet_zone(true)
+                    .module_dimensions(scale as u32, scale as u32)
+                    .build();
+                (svg.into_bytes(), "image/svg+xml")
+            }
+        };
+        Ok(ShareQrCodeDto { content, mime_type, share })
     }
 
     async fn get_shared_link_by_token(&self, token: &str) -> Result<ShareDto, DomainError> {
@@ -177,9 +622,79 @@ impl ShareUseCase for ShareService {
         if share.is_expired() {
             return Err(ShareServiceError::Expired.into());
         }
+        self.ensure_owner_active(&share).await?;
 
         // Convertir la entidad a DTO para la respuesta
-        Ok(ShareDto::from_entity(&share, &format!("http://{}:{}", self.config.server_host, self.config.server_port)))
+        Ok(ShareDto::from_entity(&share, &self.base_url()))
+    }
+
+    async fn get_public_share_metadata(&self, token: &str) -> Result<PublicShareMetadataDto, DomainError> {
+        let share = self.find_public_share(token).await?;
+        let base_url = self.base_url();
+        let requires_password = share.password_hash.is_some();
+        let requires_email = share.email_verification;
+        let mut metadata = PublicShareMetadataDto {
+            url: format!("{}/s/{}", base_url, share.token),
+            item_type: share.item_type.to_string(),
+            name: None,
+            size: None,
+            mime_type: None,
+            preview_available: false,
+            requires_password,
+            requires_email,
+            upload_only: share.is_file_drop(),
+            thumbnail: None,
+        };
+        // Un enlace con contraseña, con verificación de correo u oculto no desvela nada del elemento
+        let reveal = !requires_password && !requires_email && share.link_preview != LinkPreview::Hidden;
+
+        match share.item_type {
+            ShareItemType::File => {
+                let file = self.file_repository.get_file(&share.item_id).await
+                    .map_err(|_| Self::share_unavailable())?;
+                if reveal {
+                    metadata.preview_available = PREVIEW_MIME_TYPES.contains(&file.mime_type());
+                    metadata.name = Some(file.name().to_string());
+                    metadata.size = Some(file.size());
+                    metadata.mime_type = Some(file.mime_type().to_string());
+                }
+                if self.thumbnail_allowed(&share, file.mime_type()) {
+                    metadata.thumbnail = Some(ShareThumbnailDto {
+                        url: format!("{}/api/public/shares/{}/thumbnail", base_url, share.token),
+                        width: LINK_THUMBNAIL_WIDTH,
+                        height: LINK_THUMBNAIL_HEIGHT,
+                    });
+                }
+            }
+            ShareItemType::Folder => {
+                let folder = self.folder_repository.get_folder(&share.item_id).await
+                    .map_err(|_| Self::share_unavailable())?;
+                if reveal {
+                    metadata.name = Some(folder.name().to_string());
+                }
+            }
+        }
+        Ok(metadata)
+    }
+
+    async fn get_public_share_thumbnail(&self, token: &str) -> Result<ImagePreview, DomainError> {
+        let share = self.find_public_share(token).await?;
+        let image_previews = match (&share.item_type, &self.image_previews) {
+            (ShareItemType::File, Some(image_previews)) => image_previews,
+            _ => return Err(Self::share_unavailable()),
+        };
+        let file = self.file_repository.get_file(&share.item_id).await
+            .map_err(|_| Self::share_unavailable())?;
+        if !self.thumbnail_allowed(&share, file.mime_type()) {
+            return Err(Self::share_unavailable());
+        }
+
+        image_previews.render_preview(&share.item_id, ImagePreviewRequest {
+            width: Some(LINK_THUMBNAIL_WIDTH),
+            height: Some(LINK_THUMBNAIL_HEIGHT),
+            fit: ImageFit::Cover,
+            quality: None,
+        }).await.map_err(|_| Self::share_unavailable())
     }
 
     async fn get_shared_links_for_item(
@@ -200,7 +715,7 @@ impl ShareUseCase for ShareService {
         // Convertir las entidades a DTOs para la respuesta
         let share_dtos = active_shares
             .iter()
-            .map(|s| ShareDto::from_entity(s, &format!("http://{}:{}", self.config.server_host, self.config.server_port)))
+            .map(|s| ShareDto::from_entity(s, &self.base_url()))
             .collect();
 
         Ok(share_dtos)
@@ -217,15 +732,12 @@ impl ShareUseCase for ShareService {
             .find_share_by_id(id)
             .await
             .map_err(|e| ShareServiceError::NotFound(format!("Share with ID {} not found: {}", id, e)))?;
+        let previous_recipients = self.recipients(&share).await;
 
         // Actualizar permisos si se proporcionan
         if let Some(permissions_dto) = dto.permissions {
-            let permissions = SharePermissions::new(
-                permissions_dto.read,
-                permissions_dto.write,
-                permissions_dto.reshare,
-            );
-            share = share.with_permissions(permissions);
+            share = share.with_permissions(permissions_dto.to_entity())
+                .map_err(|e| ShareServiceError::Validation(e.to_string()))?;
         }
 
         // Actualizar contraseña si se proporciona
@@ -233,7 +745,7 @@ impl ShareUseCase for ShareService {
             let password_hash = if password.is_empty() {
                 None
             } else {
-                Some(self.hash_password(&password))
+                Some(self.hash_password(&password)?)
             };
             share = share.with_password(password_hash);
         }
@@ -243,6 +755,36 @@ impl ShareUseCase for ShareService {
             share = share.with_expiration(dto.expires_at);
         }
 
+        // Actualizar las vistas previas del enlace si se proporcionan
+        if let Some(link_preview) = dto.link_preview.as_deref() {
+            let link_preview = LinkPreview::try_from(link_preview)
+                .map_err(|e| ShareServiceError::Validation(e.to_string()))?;
+            share = share.with_link_preview(link_preview);
+        }
+
+        // Actualizar la verificación del correo si se proporciona
+        if dto.email_verification.is_some() || dto.allowed_emails.is_some() || dto.allowed_groups.is_some() {
+            let allowed_groups = match dto.allowed_groups {
+                Some(groups) => self.validate_allowed_groups(groups).await?,
+                None => share.allowed_groups.clone(),
+            };
+            let email_verification = dto.email_verification.unwrap_or(share.email_verification);
+            if !email_verification && !allowed_groups.is_empty() {
+                return Err(ShareServiceError::Validation(
+                    "Shares limited to groups need email verification".to_string()
+                ).into());
+            }
+            if email_verification {
+                self.email_access()?;
+            }
+            let allowed_emails = match dto.allowed_emails {
+                Some(emails) => Self::validate_allowed_emails(emails)?,
+                None => share.allowed_emails.clone(),
+            };
+            share = share.with_email_verification(email_verification, allowed_emails)
+                .with_allowed_groups(allowed_groups);
+        }
+
         // Guardar los cambios
         let updated_share = self
             .share_repository
@@ -250,17 +792,35 @@ impl ShareUseCase for ShareService {
             .await
             .map_err(|e| ShareServiceError::Repository(e.to_string()))?;
 
+        // Avisar a quien entra en el enlace y a quien sale de él
+        let recipients = self.recipients(&updated_share).await;
+        let added = recipients.difference(&previous_recipients).cloned().collect();
+        let removed = previous_recipients.difference(&recipients).cloned().collect();
+        self.notify(&updated_share, ShareChange::Shared, added).await;
+        self.notify(&updated_share, ShareChange::Unshared, removed).await;
+
         // Convertir la entidad a DTO para la respuesta
-        Ok(ShareDto::from_entity(&updated_share, &format!("http://{}:{}", self.config.server_host, self.config.server_port)))
+        Ok(ShareDto::from_entity(&updated_share, &self.base_url()))
     }
 
     async fn delete_shared_link(&self, id: &str) -> Result<(), DomainError> {
+        // Destinatarios a los que avisar; un enlace caducado ya no daba acceso
+        let share = match &self.notifications {
+            Some(_) => self.share_repository.find_share_by_id(id).await.ok().filter(|share| !share.is_expired()),
+            None => None,
+        };
+
         // Eliminar el enlace compartido
         self.share_repository
             .delete_share(id)
             .await
             .map_err(|e| ShareServiceError::Repository(e.to_string()))?;
 
+        if let Some(share) = share {
+            let recipients = self.recipients(&share).await;
+            self.notify(&share, ShareChange::Unshared, recipients).await;
+        }
+
         Ok(())
     }
 
@@ -283,7 +843,7 @@ impl ShareUseCase for ShareService {
         // Convertir las entidades a DTOs
         let share_dtos: Vec<ShareDto> = shares
             .iter()
-            .map(|s| ShareDto::from_entity(s, &format!("http://{}:{}", self.config.server_host, self.config.server_port)))
+            .map(|s| ShareDto::from_entity(s, &self.base_url()))
             .collect();
 
         // Crear el resultado paginado
@@ -313,9 +873,16 @@ impl ShareUseCase for ShareService {
         if share.is_expired() {
             return Err(ShareServiceError::Expired.into());
         }
+        self.ensure_owner_active(&share).await?;
 
         // Verificar la contraseña
-        Ok(share.verify_password(password))
+        if !share.verify_password(password) {
+            return Ok(false);
+        }
+        if let Err(e) = self.rehash_legacy_password(&share, password).await {
+            tracing::warn!("No se pudo guardar el hash de la contraseña del enlace {}: {}", share.id, e);
+        }
+        Ok(true)
     }
 
     async fn register_shared_link_access(&self, token: &str) -> Result<(), DomainError> {
@@ -330,6 +897,7 @@ impl ShareUseCase for ShareService {
         if share.is_expired() {
             return Err(ShareServiceError::Expired.into());
         }
+        self.ensure_owner_active(&share).await?;
 
         // Incrementar el contador de accesos
         let updated_share = share.increment_access_count();
@@ -342,6 +910,277 @@ impl ShareUseCase for ShareService {
 
         Ok(())
     }
+
+    async fn access_shared_link(
+        &self,
+        token: &str,
+        email_session: Option<&str>,
+        client_ip: Option<&str>,
+    ) -> Result<ShareDto, DomainError> {
+        let share = self
+            .share_repository
+            .find_share_by_token(token)
+            .await
+            .map_err(|e| ShareServiceError::NotFound(format!("Share with token {} not found: {}", token, e)))?;
+
+        if share.is_expired() {
+            return Err(ShareServiceError::Expired.into());
+        }
+        self.ensure_owner_active(&share).await?;
+
+        // Los enlaces con verificación de correo solo se abren con una sesión de ese enlace
+        let email = self.session_email(&share, email_session).await?;
+
+        let updated_share = self.share_repository
+            .update_share(&share.increment_access_count())
+            .await
+            .map_err(|e| ShareServiceError::Repository(e.to_string()))?;
+        self.log_access(&updated_share, ShareAccessEvent::Accessed, email.as_deref(), client_ip).await;
+
+        // Los visitantes no ven a quién más se permite el acceso
+        let mut dto = ShareDto::from_entity(&updated_share, &self.base_url());
+        dto.allowed_emails.clear();
+        dto.allowed_groups.clear();
+        Ok(dto)
+    }
+
+    async fn request_share_email_code(
+        &self,
+        token: &str,
+        email: &str,
+        client_ip: Option<&str>,
+    ) -> Result<(), DomainError> {
+        let share = self.find_public_share(token).await?;
+        if !share.email_verification {
+            return Err(Self::share_unavailable());
+        }
+        let email = normalize_email(email);
+        if !is_valid_email(&email) {
+            return Err(ShareServiceError::Validation("Invalid email address".to_string()).into());
+        }
+
+        // Mismo resultado para los correos no admitidos, sin enviar nada
+        if !self.email_allowed(&share, &email).await {
+            self.log_access(&share, ShareAccessEvent::EmailRejected, Some(&email), client_ip).await;
+            return Ok(());
+        }
+
+        let share_url = format!("{}/s/{}", self.base_url(), share.token);
+        if self.email_access()?.send_code(&share.id, &email, &share_url).await? == CodeRequestOutcome::Sent {
+            self.log_access(&share, ShareAccessEvent::CodeSent, Some(&email), client_ip).await;
+        }
+        Ok(())
+    }
+
+    async fn verify_share_email_code(
+        &self,
+        token: &str,
+        email: &str,
+        code: &str,
+        client_ip: Option<&str>,
+    ) -> Result<ShareEmailSessionDto, DomainError> {
+        let share = self.find_public_share(token).await?;
+        if !share.email_verification {
+            return Err(Self::share_unavailable());
+        }
+        let email = normalize_email(email);
+
+        match self.email_access()?.verify_code(&share.id, &email, code).await {
+            Ok(session) => {
+                self.log_access(&share, ShareAccessEvent::Verified, Some(&email), client_ip).await;
+                Ok(ShareEmailSessionDto {
+                    session_token: session.token,
+                    email,
+                    expires_at: session.expires_at.timestamp().max(0) as u64,
+                })
+            }
+            Err(e) => {
+                self.log_access(&share, ShareAccessEvent::CodeRejected, Some(&email), client_ip).await;
+                Err(e)
+            }
+        }
+    }
+
+    async fn get_share_access_log(&self, id: &str, user_id: &str) -> Result<Vec<ShareAccessLogEntryDto>, DomainError> {
+        let share = self
+            .share_repository
+            .find_share_by_id(id)
+            .await
+            .map_err(|e| ShareServiceError::NotFound(format!("Share with ID {} not found: {}", id, e)))?;
+
+        // El registro revela correos e IPs de los visitantes: solo para el propietario
+        if share.created_by != user_id {
+            return Err(ShareServiceError::AccessDenied("Only the owner of a share can read its access log".to_string()).into());
+        }
+
+        let Some(access_log) = &self.access_log else {
+            return Ok(Vec::new());
+        };
+        let entries = access_log.list_for_share(&share.id, ACCESS_LOG_LIMIT).await?;
+        Ok(entries.into_iter().map(ShareAccessLogEntryDto::from).collect())
+    }
+
+    async fn download_shared_file(
+        &self,
+        token: &str,
+        file_id: Option<&str>,
+        credentials: ShareCredentials<'_>,
+    ) -> Result<SharedFileContent, DomainError> {
+        let (share, email) = self.open_shared_content(token, &credentials, false).await?;
+
+        let file_id = match (&share.item_type, file_id) {
+            (ShareItemType::File, None) => share.item_id.as_str(),
+            (ShareItemType::File, Some(file_id)) if file_id == share.item_id => file_id,
+            (ShareItemType::Folder, Some(file_id)) => file_id,
+            (ShareItemType::File, Some(file_id)) => {
+                return Err(ShareServiceError::ItemNotFound(format!("File {} is not part of this share", file_id)).into());
+            }
+            (ShareItemType::Folder, None) => {
+                return Err(ShareServiceError::Validation("Folder shares need the ID of the file to download".to_string()).into());
+            }
+        };
+        let not_shared = || ShareServiceError::ItemNotFound(format!("File {} is not part of this share", file_id));
+
+        let file = self.file_repository.get_file(file_id).await.map_err(|_| not_shared())?;
+        if share.item_type == ShareItemType::Folder {
+            let inside = match file.folder_id() {
+                Some(folder_id) => self.is_within_folder(folder_id, &share.item_id).await?,
+                None => false,
+            };
+            if !inside {
+                return Err(not_shared().into());
+            }
+        }
+
+        let content = self.file_repository.get_file_stream(file_id).await?;
+        let updated_share = self.share_repository
+            .update_share(&share.increment_download_count())
+            .await
+            .map_err(|e| ShareServiceError::Repository(e.to_string()))?;
+        self.log_access(&updated_share, ShareAccessEvent::Downloaded, email.as_deref(), credentials.client_ip).await;
+        if let Some(email_notifications) = &self.email_notifications {
+            email_notifications.notify(&updated_share.created_by, EmailEvent::ShareDownloaded {
+                share_id: updated_share.id.clone(),
+                item_name: file.name().to_string(),
+                download_count: updated_share.download_count,
+            });
+        }
+
+        Ok(SharedFileContent {
+            name: file.name().to_string(),
+            mime_type: file.mime_type().to_string(),
+            size: file.size(),
+            content,
+        })
+    }
+
+    async fn list_shared_folder(
+        &self,
+        token: &str,
+        folder_id: Option<&str>,
+        credentials: ShareCredentials<'_>,
+    ) -> Result<SharedFolderListingDto, DomainError> {
+        let (share, _) = self.open_shared_content(token, &credentials, false).await?;
+        if share.item_type != ShareItemType::Folder {
+            return Err(ShareServiceError::Validation("Only folder shares can be listed".to_string()).into());
+        }
+
+        let folder_id = folder_id.unwrap_or(&share.item_id);
+        let not_shared = || ShareServiceError::ItemNotFound(format!("Folder {} is not part of this share", folder_id));
+        if !self.is_within_folder(folder_id, &share.item_id).await.map_err(|_| not_shared())? {
+            return Err(not_shared().into());
+        }
+
+        let folder = self.folder_repository.get_folder(folder_id).await.map_err(|_| not_shared())?;
+        let folders = self.folder_repository.list_folders(Some(folder_id)).await?
+            .into_iter()
+            .map(|folder| SharedItemDto {
+                id: folder.id().to_string(),
+                name: folder.name().to_string(),
+                item_type: ShareItemType::Folder.to_string(),
+                size: None,
+                mime_type: None,
+                modified_at: folder.modified_at(),
+            })
+            .collect();
+        let files = self.file_repository.list_files(Some(folder_id)).await?
+            .into_iter()
+            .map(|file| SharedItemDto {
+                id: file.id().to_string(),
+                name: file.name().to_string(),
+                item_type: ShareItemType::File.to_string(),
+                size: Some(file.size()),
+                mime_type: Some(file.mime_type().to_string()),
+                modified_at: file.modified_at(),
+            })
+            .collect();
+
+        Ok(SharedFolderListingDto {
+            folder_id: folder.id().to_string(),
+            name: folder.name().to_string(),
+            // Por encima de la carpeta compartida no se enseña nada
+            parent_id: folder.parent_id().filter(|_| folder.id() != share.item_id).map(str::to_string),
+            folders,
+            files,
+        })
+    }
+
+    async fn upload_to_shared_folder(
+        &self,
+        token: &str,
+        file_name: &str,
+        content_type: &str,
+        content: Vec<u8>,
+        credentials: ShareCredentials<'_>,
+    ) -> Result<SharedItemDto, DomainError> {
+        let (share, email) = self.open_shared_content(token, &credentials, true).await?;
+        if share.item_type != ShareItemType::Folder {
+            return Err(ShareServiceError::Validation("Only folder shares accept uploads".to_string()).into());
+        }
+
+        let file_name = file_name.trim();
+        if file_name.is_empty() || file_name == "." || file_name == ".." || file_name.contains(['/', '\\', '\0']) {
+            return Err(ShareServiceError::Validation(format!("Invalid file name: {}", file_name)).into());
+        }
+
+        // Nunca se sustituye nada: en un buzón el visitante no ve qué hay en
+        // la carpeta, así que un nombre repetido recibe un número
+        let taken: HashSet<String> = self.folder_repository.list_folders(Some(&share.item_id)).await?
+            .iter()
+            .map(|folder| folder.name().to_string())
+            .chain(self.file_repository.list_files(Some(&share.item_id)).await?.iter().map(|file| file.name().to_string()))
+            .collect();
+        let name = naming_service::find_free_name(file_name, NamingPattern::Numbered, Locale::English, |candidate| taken.contains(candidate))
+            .ok_or_else(|| ShareServiceError::Validation(format!("No free name left for {}", file_name)))?;
+
+        let file = self.file_repository
+            .save_file(name, Some(share.item_id.clone()), content_type.to_string(), content)
+            .await?;
+        self.log_access(&share, ShareAccessEvent::Uploaded, email.as_deref(), credentials.client_ip).await;
+
+        Ok(SharedItemDto {
+            id: file.id().to_string(),
+            name: file.name().to_string(),
+            item_type: ShareItemType::File.to_string(),
+            size: Some(file.size()),
+            mime_type: Some(file.mime_type().to_string()),
+            modified_at: file.modified_at(),
+        })
+    }
+}
+
+/// Comprobación básica de una dirección de correo ya normalizada
+fn is_valid_email(email: &str) -> bool {
+    let Some((local, domain)) = email.split_once('@') else {
+        return false;
+    };
+    email.len() <= 254
+        && !local.is_empty()
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !domain.contains('@')
+        && !email.chars().any(|c| c.is_whitespace() || c.is_control() || matches!(c, '<' | '>' | ',' | ';'))
 }
 
 #[cfg(test)]
@@ -538,6 +1377,10 @@ mod tests {
             
             Ok((paginated, total))
         }
+        
+        async fn find_all_shares(&self) -> Result<Vec<Share>, DomainError> {
+            Ok(self.shares.lock().unwrap().values().cloned().collect())
+        }
     }
 
     #[tokio::test]
@@ -570,7 +1413,13 @@ mod tests {
                 read: true,
                 write: false,
                 reshare: false,
+                delete: false,
+                upload: false,
             }),
+            link_preview: None,
+            email_verification: None,
+            allowed_emails: None,
+            allowed_groups: None,
         };
         
         let result = service.create_shared_link("user123", dto).await;
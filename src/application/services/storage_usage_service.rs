@@ -5,6 +5,8 @@ use crate::common::errors::DomainError;
 use crate::application::ports::auth_ports::UserStoragePort;
 use crate::domain::repositories::file_repository::FileRepository;
 use crate::application::ports::storage_ports::StorageUsagePort;
+use crate::application::ports::hidden_file_ports::HiddenFileRulesPort;
+use crate::domain::services::hidden_file_service::HiddenFileRules;
 use tracing::{info, error, debug};
 
 /**
@@ -16,6 +18,7 @@ use tracing::{info, error, debug};
 pub struct StorageUsageService {
     file_repository: Arc<dyn FileRepository>,
     user_repository: Arc<dyn UserStoragePort>,
+    hidden_file_rules: Option<Arc<dyn HiddenFileRulesPort>>,
 }
 
 impl StorageUsageService {
@@ -27,9 +30,16 @@ impl StorageUsageService {
         Self {
             file_repository,
             user_repository,
+            hidden_file_rules: None,
         }
     }
     
+    /// Leaves hidden and system files out of the usage, following each user's rules
+    pub fn with_hidden_file_rules(mut self, hidden_file_rules: Arc<dyn HiddenFileRulesPort>) -> Self {
+        self.hidden_file_rules = Some(hidden_file_rules);
+        self
+    }
+    
     /// Calculates and updates storage usage for a specific user
     pub async fn update_user_storage_usage(&self, user_id: &str) -> Result<i64, DomainError> {
         info!("Updating storage usage for user: {}", user_id);
@@ -39,7 +49,8 @@ impl StorageUsageService {
         let username = user.username();
         
         // Calculate storage usage for this user
-        let total_usage = self.calculate_user_storage_usage(username).await?;
+        let hidden_rules = self.hidden_file_rules.as_ref().map(|rules| rules.rules_for_user(user_id));
+        let total_usage = self.calculate_user_storage_usage(username, hidden_rules).await?;
         
         // Update the user's storage usage in the database
         self.user_repository.update_storage_usage(user_id, total_usage).await?;
@@ -50,7 +61,11 @@ impl StorageUsageService {
     }
     
     /// Calculates a user's storage usage based on their home folder
-    async fn calculate_user_storage_usage(&self, username: &str) -> Result<i64, DomainError> {
+    async fn calculate_user_storage_usage(
+        &self,
+        username: &str,
+        hidden_rules: Option<HiddenFileRules>,
+    ) -> Result<i64, DomainError> {
         debug!("Calculating storage for user: {}", username);
 
         // First, try to find the user's home folder
@@ -77,7 +92,7 @@ impl StorageUsageService {
         // If we found the home folder, calculate total size
         if let Some(folder_id) = home_folder_id {
             // Calculate recursively
-            total_usage = self.calculate_folder_size(&folder_id).await?;
+            total_usage = self.calculate_folder_size(&folder_id, hidden_rules).await?;
         } else {
             // If no home folder found, just return 0
             debug!("No home folder found for user: {}", username);
@@ -87,11 +102,16 @@ impl StorageUsageService {
     }
     
     /// Recursively calculates the size of a folder and all its contents
-    async fn calculate_folder_size(&self, folder_id: &str) -> Result<i64, DomainError> {
+    async fn calculate_folder_size(
+        &self,
+        folder_id: &str,
+        hidden_rules: Option<HiddenFileRules>,
+    ) -> Result<i64, DomainError> {
         // Implementation with explicit boxing to handle recursion in async functions
         async fn inner_calculate_size(
             repo: Arc<dyn FileRepository>,
             folder_id: &str,
+            hidden_rules: Option<HiddenFileRules>,
         ) -> Result<i64, DomainError> {
             let mut total_size: i64 = 0;
             
@@ -108,7 +128,7 @@ impl StorageUsageService {
                     let repo_clone = repo.clone(); // Clone the repository
                     
                     // Use Box::pin to handle recursive async call
-                    let subfolder_size_future = Box::pin(inner_calculate_size(repo_clone, &subfolder_id));
+                    let subfolder_size_future = Box::pin(inner_calculate_size(repo_clone, &subfolder_id, hidden_rules));
                     
                     match subfolder_size_future.await {
                         Ok(size) => {
@@ -119,6 +139,9 @@ impl StorageUsageService {
                             // Continue with other folders even if one fails
                         }
                     }
+                } else if hidden_rules.is_some_and(|rules| rules.is_hidden(file.name())) {
+                    // Hidden and system files do not count towards the quota
+                    continue;
                 } else {
                     // Add file size to total
                     total_size += file.size() as i64;
@@ -130,7 +153,7 @@ impl StorageUsageService {
         
         // Start the calculation with a clone of our repository reference
         let repo_clone = Arc::clone(&self.file_repository);
-        inner_calculate_size(repo_clone, folder_id).await
+        inner_calculate_size(repo_clone, folder_id, hidden_rules).await
     }
 }
 
@@ -191,6 +214,7 @@ impl Clone for StorageUsageService {
         Self {
             file_repository: Arc::clone(&self.file_repository),
             user_repository: Arc::clone(&self.user_repository),
+            hidden_file_rules: self.hidden_file_rules.clone(),
         }
     }
 }
\ No newline at end of file
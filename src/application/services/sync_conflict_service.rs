@@ -0,0 +1,345 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use crate::application::dtos::sync_conflict_dto::SyncConflictDto;
+use crate::application::ports::inbound::FileUseCase;
+use crate::application::ports::lock_ports::LockUseCase;
+use crate::application::ports::sync_conflict_ports::SyncConflictUseCase;
+use crate::common::errors::DomainError;
+use crate::domain::entities::sync_conflict::{ConflictKind, ConflictResolution, SyncConflict};
+use crate::domain::repositories::sync_conflict_repository::SyncConflictRepository;
+
+/// Servicio del panel de conflictos de sincronización.
+///
+/// Reúne los conflictos que detectan WebDAV y el servicio de bloqueos
+/// (copias en conflicto subidas por los clientes, escrituras rechazadas y
+/// LOCK fallidos) y aplica la resolución que elija el usuario.
+pub struct SyncConflictService {
+    repository: Arc<dyn SyncConflictRepository>,
+    file_service: Arc<dyn FileUseCase>,
+    lock_service: Option<Arc<dyn LockUseCase>>,
+}
+
+impl SyncConflictService {
+    pub fn new(repository: Arc<dyn SyncConflictRepository>, file_service: Arc<dyn FileUseCase>) -> Self {
+        Self {
+            repository,
+            file_service,
+            lock_service: None,
+        }
+    }
+
+    /// Respeta los bloqueos WebDAV al resolver conflictos
+    pub fn with_lock_service(mut self, lock_service: Arc<dyn LockUseCase>) -> Self {
+        self.lock_service = Some(lock_service);
+        self
+    }
+
+    /// Falla si otro usuario tiene bloqueado el recurso
+    async fn check_write(&self, path: &str, user_id: &str, changes_membership: bool) -> Result<(), DomainError> {
+        match &self.lock_service {
+            Some(locks) => locks.check_write(path, user_id, &[], changes_membership).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Sustituye el original por el contenido de la copia en conflicto y borra la copia
+    async fn keep_mine(&self, user_id: &str, path: &str, conflict_path: &str) -> Result<(), DomainError> {
+        self.check_write(path, user_id, false).await?;
+        self.check_write(conflict_path, user_id, true).await?;
+
+        let copy = self.file_service.get_file_by_path(conflict_path).await?;
+        let content = self.file_service.get_file_content(&copy.id).await?;
+        self.file_service.update_file(path, &content).await?;
+        self.file_service.delete_file(&copy.id).await
+    }
+
+    /// Descarta la copia en conflicto si todavía existe
+    async fn keep_theirs(&self, user_id: &str, conflict_path: &str) -> Result<(), DomainError> {
+        let copy = match self.file_service.get_file_by_path(conflict_path).await {
+            Ok(copy) => copy,
+            Err(_) => return Ok(()),
+        };
+
+        self.check_write(conflict_path, user_id, true).await?;
+        self.file_service.delete_file(&copy.id).await
+    }
+}
+
+#[async_trait]
+impl SyncConflictUseCase for SyncConflictService {
+    async fn record_conflict(
+        &self,
+        user_id: &str,
+        kind: ConflictKind,
+        path: &str,
+        conflict_path: Option<&str>,
+        detail: Option<String>,
+    ) -> Result<(), DomainError> {
+        let conflict = SyncConflict::new(
+            user_id.to_string(),
+            kind,
+            path.to_string(),
+            conflict_path.map(str::to_string),
+            detail,
+        );
+        self.repository.record_conflict(conflict).await?;
+        Ok(())
+    }
+
+    async fn list_conflicts(&self, user_id: &str, include_resolved: bool) -> Result<Vec<SyncConflictDto>, DomainError> {
+        let conflicts = self.repository.list_conflicts(user_id, include_resolved).await?;
+        Ok(conflicts.into_iter().map(SyncConflictDto::from).collect())
+    }
+
+    async fn resolve_conflict(
+        &self,
+        user_id: &str,
+        conflict_id: &str,
+        resolution: ConflictResolution,
+    ) -> Result<SyncConflictDto, DomainError> {
+        let mut conflict = self.repository.get_conflict(user_id, conflict_id).await?
+            .ok_or_else(|| DomainError::not_found("SyncConflict", conflict_id))?;
+
+        if conflict.is_resolved() {
+            return Err(DomainError::validation_error(format!(
+                "Conflict {} is already resolved",
+                conflict_id
+            )));
+        }
+        if !conflict.kind.resolutions().contains(&resolution) {
+            return Err(DomainError::validation_error(format!(
+                "'{}' cannot be applied to a {} conflict",
+                resolution.as_str(),
+                conflict.kind.as_str()
+            )));
+        }
+
+        // Solo las copias en conflicto dejan ficheros que haya que tocar
+        if let Some(conflict_path) = conflict.conflict_path.clone() {
+            match resolution {
+                ConflictResolution::KeepMine => self.keep_mine(user_id, &conflict.path, &conflict_path).await?,
+                ConflictResolution::KeepTheirs => self.keep_theirs(user_id, &conflict_path).await?,
+                ConflictResolution::KeepBoth => {}
+            }
+        }
+
+        let resolved_at = Utc::now();
+        self.repository.mark_resolved(&conflict.id, resolution, resolved_at).await?;
+
+        conflict.resolved_at = Some(resolved_at);
+        conflict.resolution = Some(resolution);
+        Ok(SyncConflictDto::from(conflict))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use bytes::Bytes;
+    use chrono::DateTime;
+    use futures::Stream;
+
+    use crate::application::dtos::file_dto::FileDto;
+    use crate::common::errors::ErrorKind;
+    use crate::domain::repositories::sync_conflict_repository::SyncConflictRepositoryResult;
+
+    #[derive(Default)]
+    struct InMemorySyncConflictRepository {
+        conflicts: Mutex<Vec<SyncConflict>>,
+    }
+
+    #[async_trait]
+    impl SyncConflictRepository for InMemorySyncConflictRepository {
+        async fn record_conflict(&self, conflict: SyncConflict) -> SyncConflictRepositoryResult<SyncConflict> {
+            let mut conflicts = self.conflicts.lock().unwrap();
+            if let Some(open) = conflicts.iter_mut().find(|c| {
+                !c.is_resolved() && c.user_id == conflict.user_id && c.kind == conflict.kind
+                    && c.path == conflict.path && c.conflict_path == conflict.conflict_path
+            }) {
+                open.occurrences += 1;
+                open.last_seen_at = conflict.last_seen_at;
+                open.detail = conflict.detail;
+                return Ok(open.clone());
+            }
+            conflicts.push(conflict.clone());
+            Ok(conflict)
+        }
+
+        async fn list_conflicts(&self, user_id: &str, include_resolved: bool) -> SyncConflictRepositoryResult<Vec<SyncConflict>> {
+            Ok(self.conflicts.lock().unwrap().iter()
+                .filter(|c| c.user_id == user_id && (include_resolved || !c.is_resolved()))
+                .cloned()
+                .collect())
+        }
+
+        async fn get_conflict(&self, user_id: &str, id: &str) -> SyncConflictRepositoryResult<Option<SyncConflict>> {
+            Ok(self.conflicts.lock().unwrap().iter().find(|c| c.user_id == user_id && c.id == id).cloned())
+        }
+
+        async fn mark_resolved(&self, id: &str, resolution: ConflictResolution, resolved_at: DateTime<Utc>) -> SyncConflictRepositoryResult<()> {
+            if let Some(conflict) = self.conflicts.lock().unwrap().iter_mut().find(|c| c.id == id) {
+                conflict.resolution = Some(resolution);
+                conflict.resolved_at = Some(resolved_at);
+            }
+            Ok(())
+        }
+    }
+
+    /// Ficheros en memoria indexados por ruta; la ruta hace de ID
+    #[derive(Default)]
+    struct InMemoryFiles {
+        files: Mutex<HashMap<String, Vec<u8>>>,
+    }
+
+    impl InMemoryFiles {
+        fn with(files: &[(&str, &str)]) -> Self {
+            Self {
+                files: Mutex::new(files.iter().map(|(p, c)| (p.to_string(), c.as_bytes().to_vec())).collect()),
+            }
+        }
+
+        fn content(&self, path: &str) -> Option<String> {
+            self.files.lock().unwrap().get(path).map(|c| String::from_utf8(c.clone()).unwrap())
+        }
+
+        fn dto(path: &str) -> FileDto {
+            FileDto { id: path.to_string(), path: path.to_string(), ..FileDto::empty() }
+        }
+    }
+
+    #[async_trait]
+    impl FileUseCase for InMemoryFiles {
+        async fn upload_file(&self, _name: String, _folder_id: Option<String>, _content_type: String, _content: Vec<u8>) -> Result<FileDto, DomainError> {
+            unimplemented!()
+        }
+
+        async fn get_file(&self, id: &str) -> Result<FileDto, DomainError> {
+            self.get_file_by_path(id).await
+        }
+
+        async fn get_file_by_path(&self, path: &str) -> Result<FileDto, DomainError> {
+            if self.files.lock().unwrap().contains_key(path) {
+                Ok(Self::dto(path))
+            } else {
+                Err(DomainError::not_found("File", path))
+            }
+        }
+
+        async fn create_file(&self, _parent_path: &str, _filename: &str, _content: &[u8], _content_type: &str) -> Result<FileDto, DomainError> {
+            unimplemented!()
+        }
+
+        async fn update_file(&self, path: &str, content: &[u8]) -> Result<(), DomainError> {
+            self.files.lock().unwrap().insert(path.to_string(), content.to_vec());
+            Ok(())
+        }
+
+        async fn list_files(&self, _folder_id: Option<&str>) -> Result<Vec<FileDto>, DomainError> {
+            unimplemented!()
+        }
+
+        async fn delete_file(&self, id: &str) -> Result<(), DomainError> {
+            self.files.lock().unwrap().remove(id);
+            Ok(())
+        }
+
+        async fn get_file_content(&self, id: &str) -> Result<Vec<u8>, DomainError> {
+            self.files.lock().unwrap().get(id).cloned().ok_or_else(|| DomainError::not_found("File", id))
+        }
+
+        async fn get_file_stream(&self, _id: &str) -> Result<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>, DomainError> {
+            unimplemented!()
+        }
+
+        async fn get_file_range(&self, _id: &str, _start: u64, _length: u64) -> Result<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>, DomainError> {
+            unimplemented!()
+        }
+
+        async fn write_file_range(&self, _id: &str, _offset: u64, _content: &[u8]) -> Result<FileDto, DomainError> {
+            unimplemented!()
+        }
+
+        async fn move_file(&self, _file_id: &str, _folder_id: Option<String>) -> Result<FileDto, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    const COPY: &str = "docs/report (conflicted copy 2025-04-25).txt";
+
+    fn service(files: Arc<InMemoryFiles>) -> SyncConflictService {
+        SyncConflictService::new(Arc::new(InMemorySyncConflictRepository::default()), files)
+    }
+
+    async fn record_copy(service: &SyncConflictService) -> String {
+        service.record_conflict("alice", ConflictKind::ConflictedCopy, "docs/report.txt", Some(COPY), None).await.unwrap();
+        service.list_conflicts("alice", false).await.unwrap()[0].id.clone()
+    }
+
+    #[tokio::test]
+    async fn test_repeated_conflicts_are_counted() {
+        let service = service(Arc::new(InMemoryFiles::default()));
+        for _ in 0..3 {
+            service.record_conflict("alice", ConflictKind::RejectedWrite, "a.txt", None, Some("locked".to_string())).await.unwrap();
+        }
+
+        let conflicts = service.list_conflicts("alice", false).await.unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].occurrences, 3);
+        assert_eq!(conflicts[0].available_resolutions, vec![ConflictResolution::KeepTheirs]);
+        assert!(service.list_conflicts("bob", true).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_keep_mine_replaces_original() {
+        let files = Arc::new(InMemoryFiles::with(&[("docs/report.txt", "theirs"), (COPY, "mine")]));
+        let service = service(files.clone());
+        let id = record_copy(&service).await;
+
+        let resolved = service.resolve_conflict("alice", &id, ConflictResolution::KeepMine).await.unwrap();
+        assert_eq!(resolved.resolution, Some(ConflictResolution::KeepMine));
+        assert!(resolved.available_resolutions.is_empty());
+        assert_eq!(files.content("docs/report.txt").as_deref(), Some("mine"));
+        assert_eq!(files.content(COPY), None);
+
+        assert!(service.list_conflicts("alice", false).await.unwrap().is_empty());
+        assert_eq!(service.list_conflicts("alice", true).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_keep_theirs_and_keep_both() {
+        let files = Arc::new(InMemoryFiles::with(&[("docs/report.txt", "theirs"), (COPY, "mine")]));
+        let service = service(files.clone());
+
+        let id = record_copy(&service).await;
+        service.resolve_conflict("alice", &id, ConflictResolution::KeepBoth).await.unwrap();
+        assert_eq!(files.content(COPY).as_deref(), Some("mine"));
+
+        let id = record_copy(&service).await;
+        service.resolve_conflict("alice", &id, ConflictResolution::KeepTheirs).await.unwrap();
+        assert_eq!(files.content("docs/report.txt").as_deref(), Some("theirs"));
+        assert_eq!(files.content(COPY), None);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_resolutions_are_rejected() {
+        let service = service(Arc::new(InMemoryFiles::default()));
+        service.record_conflict("alice", ConflictKind::LockContention, "a.txt", None, None).await.unwrap();
+        let id = service.list_conflicts("alice", false).await.unwrap()[0].id.clone();
+
+        let err = service.resolve_conflict("alice", &id, ConflictResolution::KeepMine).await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidInput);
+
+        let err = service.resolve_conflict("bob", &id, ConflictResolution::KeepTheirs).await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::NotFound);
+
+        service.resolve_conflict("alice", &id, ConflictResolution::KeepTheirs).await.unwrap();
+        let err = service.resolve_conflict("alice", &id, ConflictResolution::KeepTheirs).await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidInput);
+    }
+}
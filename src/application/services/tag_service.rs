@@ -0,0 +1,281 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::application::dtos::tag_dto::{CreateTagDto, TagAssignmentDto, TagDto, UpdateTagDto};
+use crate::application::ports::inbound::{FileUseCase, FolderUseCase, SearchUseCase};
+use crate::application::ports::tag_ports::TagUseCase;
+use crate::common::errors::DomainError;
+use crate::domain::entities::tag::{Tag, TaggedItem, TaggedItemType};
+use crate::domain::repositories::tag_repository::TagRepository;
+
+/// Servicio de etiquetas de archivos y carpetas.
+///
+/// Cada usuario define sus propias etiquetas, con nombre y color, y se las
+/// pone a los archivos y carpetas que quiera. Las etiquetas de un usuario
+/// solo las ve él: otro usuario que acceda al mismo archivo no las ve.
+pub struct TagService {
+    repository: Arc<dyn TagRepository>,
+    files: Arc<dyn FileUseCase>,
+    folders: Arc<dyn FolderUseCase>,
+    search: Option<Arc<dyn SearchUseCase>>,
+}
+
+impl TagService {
+    pub fn new(
+        repository: Arc<dyn TagRepository>,
+        files: Arc<dyn FileUseCase>,
+        folders: Arc<dyn FolderUseCase>,
+    ) -> Self {
+        Self {
+            repository,
+            files,
+            folders,
+            search: None,
+        }
+    }
+
+    /// Vacía la caché de búsqueda cuando cambian las etiquetas, ya que los
+    /// resultados pueden estar filtrados por ellas
+    pub fn with_search(mut self, search: Arc<dyn SearchUseCase>) -> Self {
+        self.search = Some(search);
+        self
+    }
+
+    /// Carga una etiqueta del usuario. La de otro usuario se trata como
+    /// inexistente para no revelar que existe
+    async fn load_own(&self, user_id: &str, tag_id: &str) -> Result<Tag, DomainError> {
+        self.repository.get_tag(tag_id).await?
+            .filter(|tag| tag.owner_id == user_id)
+            .ok_or_else(|| DomainError::not_found("Tag", tag_id))
+    }
+
+    /// Comprueba que el archivo o la carpeta existe
+    async fn ensure_item_exists(&self, item: &TaggedItem) -> Result<(), DomainError> {
+        match item.item_type {
+            TaggedItemType::File => self.files.get_file(&item.item_id).await.map(|_| ()),
+            TaggedItemType::Folder => self.folders.get_folder(&item.item_id).await.map(|_| ()),
+        }
+    }
+
+    async fn invalidate_search(&self) {
+        if let Some(search) = &self.search {
+            if let Err(e) = search.clear_search_cache().await {
+                tracing::warn!("No se pudo vaciar la caché de búsqueda: {}", e);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl TagUseCase for TagService {
+    async fn list_tags(&self, user_id: &str) -> Result<Vec<TagDto>, DomainError> {
+        let tags = self.repository.list_tags(user_id).await?;
+        Ok(tags.into_iter().map(TagDto::from).collect())
+    }
+
+    async fn create_tag(&self, user_id: &str, request: CreateTagDto) -> Result<TagDto, DomainError> {
+        let tag = Tag::new(user_id.to_string(), &request.name, request.color.as_deref())?;
+        self.repository.create_tag(&tag).await?;
+        Ok(TagDto::from(tag))
+    }
+
+    async fn update_tag(&self, user_id: &str, tag_id: &str, request: UpdateTagDto) -> Result<TagDto, DomainError> {
+        let mut tag = self.load_own(user_id, tag_id).await?;
+        if let Some(name) = &request.name {
+            tag.rename(name)?;
+        }
+        if let Some(color) = &request.color {
+            tag.set_color(color)?;
+        }
+        self.repository.update_tag(&tag).await?;
+        Ok(TagDto::from(tag))
+    }
+
+    async fn delete_tag(&self, user_id: &str, tag_id: &str) -> Result<(), DomainError> {
+        self.load_own(user_id, tag_id).await?;
+        if !self.repository.delete_tag(tag_id).await? {
+            return Err(DomainError::not_found("Tag", tag_id));
+        }
+        self.invalidate_search().await;
+        Ok(())
+    }
+
+    async fn list_tagged_items(&self, user_id: &str, tag_id: &str) -> Result<Vec<TagAssignmentDto>, DomainError> {
+        self.load_own(user_id, tag_id).await?;
+        let items = self.repository.list_tagged_items(tag_id).await?;
+        Ok(items.into_iter().map(TagAssignmentDto::from).collect())
+    }
+
+    async fn assign_tag(&self, user_id: &str, tag_id: &str, item: TagAssignmentDto) -> Result<(), DomainError> {
+        self.load_own(user_id, tag_id).await?;
+        let item = TaggedItem::from(item);
+        self.ensure_item_exists(&item).await?;
+        if self.repository.assign_tag(tag_id, &item).await? {
+            self.invalidate_search().await;
+        }
+        Ok(())
+    }
+
+    async fn unassign_tag(&self, user_id: &str, tag_id: &str, item: TagAssignmentDto) -> Result<(), DomainError> {
+        self.load_own(user_id, tag_id).await?;
+        let item = TaggedItem::from(item);
+        if !self.repository.unassign_tag(tag_id, &item).await? {
+            return Err(DomainError::not_found("TagAssignment", format!("{}/{}", tag_id, item.item_id)));
+        }
+        self.invalidate_search().await;
+        Ok(())
+    }
+
+    async fn get_item_tags(&self, user_id: &str, item_type: TaggedItemType, item_id: &str) -> Result<Vec<TagDto>, DomainError> {
+        let item = TaggedItem { item_id: item_id.to_string(), item_type };
+        self.ensure_item_exists(&item).await?;
+        let mut tags = self.repository.get_tags_for_items(user_id, &[item.item_id]).await?;
+        Ok(tags.remove(item_id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(TagDto::from)
+            .collect())
+    }
+
+    async fn get_tags_for_items(&self, user_id: &str, item_ids: &[String]) -> Result<HashMap<String, Vec<TagDto>>, DomainError> {
+        let tags = self.repository.get_tags_for_items(user_id, item_ids).await?;
+        Ok(tags.into_iter()
+            .map(|(item_id, tags)| (item_id, tags.into_iter().map(TagDto::from).collect()))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+    use std::sync::Mutex;
+    use crate::application::services::file_service::FileService;
+    use crate::application::services::folder_service::FolderService;
+    use crate::common::errors::ErrorKind;
+    use crate::domain::repositories::tag_repository::TagRepositoryResult;
+
+    #[derive(Default)]
+    struct InMemoryTagRepository {
+        tags: Mutex<HashMap<String, Tag>>,
+        assignments: Mutex<BTreeSet<(String, String, &'static str)>>,
+    }
+
+    impl InMemoryTagRepository {
+        fn item_of(item_id: &str, item_type: &str) -> TaggedItem {
+            TaggedItem { item_id: item_id.to_string(), item_type: TaggedItemType::parse(item_type).unwrap() }
+        }
+    }
+
+    #[async_trait]
+    impl TagRepository for InMemoryTagRepository {
+        async fn create_tag(&self, tag: &Tag) -> TagRepositoryResult<()> {
+            let mut tags = self.tags.lock().unwrap();
+            if tags.values().any(|existing| existing.owner_id == tag.owner_id && existing.name.to_lowercase() == tag.name.to_lowercase()) {
+                return Err(DomainError::already_exists("Tag", tag.name.clone()));
+            }
+            tags.insert(tag.id.clone(), tag.clone());
+            Ok(())
+        }
+        async fn update_tag(&self, tag: &Tag) -> TagRepositoryResult<()> {
+            self.tags.lock().unwrap().insert(tag.id.clone(), tag.clone());
+            Ok(())
+        }
+        async fn get_tag(&self, id: &str) -> TagRepositoryResult<Option<Tag>> {
+            Ok(self.tags.lock().unwrap().get(id).cloned())
+        }
+        async fn list_tags(&self, owner_id: &str) -> TagRepositoryResult<Vec<Tag>> {
+            Ok(self.tags.lock().unwrap().values().filter(|tag| tag.owner_id == owner_id).cloned().collect())
+        }
+        async fn delete_tag(&self, id: &str) -> TagRepositoryResult<bool> {
+            self.assignments.lock().unwrap().retain(|(tag_id, _, _)| tag_id != id);
+            Ok(self.tags.lock().unwrap().remove(id).is_some())
+        }
+        async fn assign_tag(&self, tag_id: &str, item: &TaggedItem) -> TagRepositoryResult<bool> {
+            Ok(self.assignments.lock().unwrap().insert((tag_id.to_string(), item.item_id.clone(), item.item_type.as_str())))
+        }
+        async fn unassign_tag(&self, tag_id: &str, item: &TaggedItem) -> TagRepositoryResult<bool> {
+            Ok(self.assignments.lock().unwrap().remove(&(tag_id.to_string(), item.item_id.clone(), item.item_type.as_str())))
+        }
+        async fn list_tagged_items(&self, tag_id: &str) -> TagRepositoryResult<Vec<TaggedItem>> {
+            Ok(self.assignments.lock().unwrap().iter()
+                .filter(|(id, _, _)| id == tag_id)
+                .map(|(_, item_id, item_type)| Self::item_of(item_id, item_type))
+                .collect())
+        }
+        async fn get_tags_for_items(&self, owner_id: &str, item_ids: &[String]) -> TagRepositoryResult<HashMap<String, Vec<Tag>>> {
+            let tags = self.tags.lock().unwrap();
+            let mut result: HashMap<String, Vec<Tag>> = HashMap::new();
+            for (tag_id, item_id, _) in self.assignments.lock().unwrap().iter() {
+                if let Some(tag) = tags.get(tag_id).filter(|tag| tag.owner_id == owner_id && item_ids.contains(item_id)) {
+                    result.entry(item_id.clone()).or_default().push(tag.clone());
+                }
+            }
+            Ok(result)
+        }
+        async fn find_items_with_all_tags(&self, tag_ids: &[String]) -> TagRepositoryResult<Vec<TaggedItem>> {
+            let assignments = self.assignments.lock().unwrap();
+            let items: BTreeSet<_> = assignments.iter()
+                .filter(|(_, item_id, item_type)| tag_ids.iter().all(|tag_id| {
+                    assignments.contains(&(tag_id.clone(), item_id.clone(), *item_type))
+                }))
+                .map(|(_, item_id, item_type)| (item_id.as_str(), *item_type))
+                .collect();
+            Ok(items.into_iter().map(|(item_id, item_type)| Self::item_of(item_id, item_type)).collect())
+        }
+    }
+
+    fn service() -> TagService {
+        TagService::new(
+            Arc::new(InMemoryTagRepository::default()),
+            Arc::new(FileService::new_stub()),
+            Arc::new(FolderService::new_stub()),
+        )
+    }
+
+    fn file(id: &str) -> TagAssignmentDto {
+        TagAssignmentDto { item_id: id.to_string(), item_type: TaggedItemType::File }
+    }
+
+    #[tokio::test]
+    async fn test_tags_are_private_to_their_owner() {
+        let service = service();
+        let tag = service.create_tag("alice", CreateTagDto { name: "Facturas".to_string(), color: Some("#00FF00".to_string()) }).await.unwrap();
+        assert_eq!(tag.color, "#00ff00");
+
+        let duplicate = service.create_tag("alice", CreateTagDto { name: "facturas".to_string(), color: None }).await;
+        assert_eq!(duplicate.unwrap_err().kind, ErrorKind::AlreadyExists);
+        // Otro usuario puede usar el mismo nombre
+        service.create_tag("bob", CreateTagDto { name: "Facturas".to_string(), color: None }).await.unwrap();
+
+        let foreign = service.assign_tag("bob", &tag.id, file("f1")).await;
+        assert_eq!(foreign.unwrap_err().kind, ErrorKind::NotFound);
+        let foreign = service.update_tag("bob", &tag.id, UpdateTagDto { name: Some("Mías".to_string()), color: None }).await;
+        assert_eq!(foreign.unwrap_err().kind, ErrorKind::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_assignments() {
+        let service = service();
+        let tag = service.create_tag("alice", CreateTagDto { name: "Urgente".to_string(), color: None }).await.unwrap();
+
+        service.assign_tag("alice", &tag.id, file("f1")).await.unwrap();
+        // Poner la misma etiqueta dos veces no es un error
+        service.assign_tag("alice", &tag.id, file("f1")).await.unwrap();
+        assert_eq!(service.list_tagged_items("alice", &tag.id).await.unwrap(), vec![file("f1")]);
+
+        let tags = service.get_item_tags("alice", TaggedItemType::File, "f1").await.unwrap();
+        assert_eq!(tags.iter().map(|tag| tag.name.as_str()).collect::<Vec<_>>(), vec!["Urgente"]);
+        assert!(service.get_item_tags("bob", TaggedItemType::File, "f1").await.unwrap().is_empty());
+
+        service.unassign_tag("alice", &tag.id, file("f1")).await.unwrap();
+        let missing = service.unassign_tag("alice", &tag.id, file("f1")).await;
+        assert_eq!(missing.unwrap_err().kind, ErrorKind::NotFound);
+
+        service.assign_tag("alice", &tag.id, file("f2")).await.unwrap();
+        service.delete_tag("alice", &tag.id).await.unwrap();
+        assert!(service.get_tags_for_items("alice", &["f2".to_string()]).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,195 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Local;
+
+use crate::application::dtos::file_dto::FileDto;
+use crate::application::dtos::folder_dto::FolderDto;
+use crate::application::dtos::template_dto::{NewFromTemplateDto, TemplateDto, TemplateScope};
+use crate::application::ports::inbound::{FileUseCase, FolderUseCase};
+use crate::application::ports::template_ports::{StoredTemplate, TemplateStorePort, TemplateUseCase, TemplateUser};
+use crate::application::services::free_name_service::FreeNameService;
+use crate::common::errors::DomainError;
+use crate::domain::services::i18n_service::Locale;
+use crate::domain::services::naming_service::NamingPattern;
+use crate::domain::services::ownership_service::{owner_of_path, HOME_FOLDER_PREFIX};
+use crate::domain::services::template_placeholder_service::{fill_placeholders, TemplateValues};
+
+/// Carpeta de la carpeta personal donde cada usuario guarda sus plantillas
+pub const USER_TEMPLATES_FOLDER: &str = "Templates";
+
+/// Prefijos de los identificadores de plantilla según su origen
+const GLOBAL_PREFIX: &str = "global:";
+const USER_PREFIX: &str = "user:";
+
+/// Servicio de la galería de plantillas del menú "Nuevo documento".
+///
+/// Ofrece las plantillas que gestiona el administrador y las que cada usuario
+/// guarda en la carpeta `Templates` de su carpeta personal. Crear un
+/// documento copia la plantilla en la carpeta indicada, con un nombre libre,
+/// y rellena sus marcas con la fecha, la hora, el usuario y el título.
+pub struct TemplateService {
+    store: Arc<dyn TemplateStorePort>,
+    file_service: Arc<dyn FileUseCase>,
+    folder_service: Arc<dyn FolderUseCase>,
+    free_names: Arc<FreeNameService>,
+}
+
+impl TemplateService {
+    pub fn new(
+        store: Arc<dyn TemplateStorePort>,
+        file_service: Arc<dyn FileUseCase>,
+        folder_service: Arc<dyn FolderUseCase>,
+        free_names: Arc<FreeNameService>,
+    ) -> Self {
+        Self { store, file_service, folder_service, free_names }
+    }
+
+    fn home_folder_path(username: &str) -> String {
+        format!("{}{}", HOME_FOLDER_PREFIX, username)
+    }
+
+    /// Carpeta de plantillas del usuario, si la ha creado
+    async fn user_templates_folder(&self, username: &str) -> Option<FolderDto> {
+        let path = format!("{}/{}", Self::home_folder_path(username), USER_TEMPLATES_FOLDER);
+        self.folder_service.get_folder_by_path(&path).await.ok()
+    }
+
+    fn global_dto(template: StoredTemplate) -> TemplateDto {
+        TemplateDto {
+            id: format!("{}{}", GLOBAL_PREFIX, template.name),
+            mime_type: mime_guess::from_path(&template.name).first_or_octet_stream().to_string(),
+            name: template.name,
+            scope: TemplateScope::Global,
+            size: template.size,
+        }
+    }
+
+    /// Nombre y contenido de una plantilla a partir de su identificador
+    async fn load_template(&self, username: &str, template_id: &str) -> Result<(String, Vec<u8>), DomainError> {
+        if let Some(name) = template_id.strip_prefix(GLOBAL_PREFIX) {
+            let content = self.store.read_template(name).await?;
+            return Ok((name.to_string(), content));
+        }
+
+        let file_id = template_id.strip_prefix(USER_PREFIX)
+            .ok_or_else(|| DomainError::validation_error(format!("Invalid template id: {}", template_id)))?;
+        // Solo valen los ficheros de la carpeta de plantillas del propio usuario
+        let folder = self.user_templates_folder(username).await
+            .ok_or_else(|| DomainError::not_found("Template", template_id))?;
+        let file = self.file_service.get_file(file_id).await
+            .ok()
+            .filter(|file| file.folder_id.as_deref() == Some(folder.id.as_str()))
+            .ok_or_else(|| DomainError::not_found("Template", template_id))?;
+
+        let content = self.file_service.get_file_content(&file.id).await?;
+        Ok((file.name, content))
+    }
+
+    /// Carpeta donde se crea el documento; por defecto, la carpeta personal
+    async fn target_folder(&self, username: &str, folder_id: Option<&str>) -> Result<FolderDto, DomainError> {
+        let folder = match folder_id {
+            Some(folder_id) => self.folder_service.get_folder(folder_id).await?,
+            None => self.folder_service.get_folder_by_path(&Self::home_folder_path(username)).await?,
+        };
+        if owner_of_path(&folder.path).is_some_and(|owner| owner != username) {
+            return Err(DomainError::access_denied("Template", "Documents can only be created in your own folders"));
+        }
+        Ok(folder)
+    }
+}
+
+/// Nombre del documento nuevo: el pedido, con la extensión de la plantilla
+/// si le falta, o el de la plantilla
+fn document_name(requested: Option<&str>, template_name: &str) -> String {
+    let Some(requested) = requested.map(str::trim).filter(|name| !name.is_empty()) else {
+        return template_name.to_string();
+    };
+    match template_name.rsplit_once('.') {
+        Some((_, extension)) if !requested.to_lowercase().ends_with(&format!(".{}", extension.to_lowercase())) => {
+            format!("{}.{}", requested, extension)
+        }
+        _ => requested.to_string(),
+    }
+}
+
+#[async_trait]
+impl TemplateUseCase for TemplateService {
+    async fn list_templates(&self, username: &str) -> Result<Vec<TemplateDto>, DomainError> {
+        let mut templates = self.list_global_templates().await?;
+
+        if let Some(folder) = self.user_templates_folder(username).await {
+            let mut files = self.file_service.list_files(Some(&folder.id)).await?;
+            files.sort_by_key(|file| file.name.to_lowercase());
+            templates.extend(files.into_iter().map(|file| TemplateDto {
+                id: format!("{}{}", USER_PREFIX, file.id),
+                name: file.name,
+                scope: TemplateScope::User,
+                mime_type: file.mime_type,
+                size: file.size,
+            }));
+        }
+
+        Ok(templates)
+    }
+
+    async fn create_from_template(&self, user: &TemplateUser, request: NewFromTemplateDto, locale: Locale) -> Result<FileDto, DomainError> {
+        let (template_name, content) = self.load_template(&user.username, &request.template_id).await?;
+        let folder = self.target_folder(&user.username, request.folder_id.as_deref()).await?;
+
+        let desired = document_name(request.name.as_deref(), &template_name);
+        let reservation = self.free_names
+            .reserve_name(Some(&folder.id), &desired, NamingPattern::Numbered, locale)
+            .await?;
+        let name = reservation.name().to_string();
+
+        let now = Local::now();
+        let title = name.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(&name).to_string();
+        let values = TemplateValues::new()
+            .with("date", now.format("%Y-%m-%d").to_string())
+            .with("time", now.format("%H:%M").to_string())
+            .with("username", user.username.clone())
+            .with("email", user.email.clone())
+            .with("title", title);
+        let content = fill_placeholders(&template_name, content, &values);
+
+        let mime_type = mime_guess::from_path(&name).first_or_octet_stream().to_string();
+        let file = self.file_service.upload_file(name, Some(folder.id.clone()), mime_type, content).await?;
+
+        tracing::info!("Documento {} creado por {} a partir de la plantilla {}", file.id, user.username, request.template_id);
+        Ok(file)
+    }
+
+    async fn list_global_templates(&self) -> Result<Vec<TemplateDto>, DomainError> {
+        let templates = self.store.list_templates().await?;
+        Ok(templates.into_iter().map(Self::global_dto).collect())
+    }
+
+    async fn save_global_template(&self, name: &str, content: &[u8]) -> Result<TemplateDto, DomainError> {
+        let template = self.store.save_template(name.trim(), content).await?;
+        tracing::info!("Plantilla global {} guardada ({} bytes)", template.name, template.size);
+        Ok(Self::global_dto(template))
+    }
+
+    async fn delete_global_template(&self, name: &str) -> Result<(), DomainError> {
+        if !self.store.delete_template(name).await? {
+            return Err(DomainError::not_found("Template", name));
+        }
+        tracing::info!("Plantilla global {} eliminada", name);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_document_name_keeps_the_template_extension() {
+        assert_eq!(document_name(None, "Report.odt"), "Report.odt");
+        assert_eq!(document_name(Some("  "), "Report.odt"), "Report.odt");
+        assert_eq!(document_name(Some("Minutes"), "Report.odt"), "Minutes.odt");
+        assert_eq!(document_name(Some("Minutes.ODT"), "Report.odt"), "Minutes.ODT");
+        assert_eq!(document_name(Some("Minutes"), "README"), "Minutes");
+    }
+}
@@ -0,0 +1,48 @@
+use std::sync::Mutex;
+use async_trait::async_trait;
+
+use crate::application::ports::share_ports::{ShareAccessUseCase, ShareRecipient, ShareTarget};
+use crate::common::errors::DomainError;
+use crate::domain::entities::share::SharePermissions;
+use crate::domain::services::ownership_service::owner_of_path;
+
+/// Permisos de enlaces para las pruebas de los servicios.
+///
+/// Los IDs de archivos y carpetas son sus rutas: cada usuario llega a lo que
+/// hay en su carpeta personal y a lo que se le conceda con `grant`.
+#[derive(Default)]
+pub struct HomeOnlyAccess {
+    grants: Mutex<Vec<(String, ShareTarget, SharePermissions)>>,
+}
+
+impl HomeOnlyAccess {
+    /// Concede a un usuario permisos sobre un elemento de otro
+    pub fn grant(&self, username: &str, target: ShareTarget, permissions: SharePermissions) {
+        self.grants.lock().unwrap().push((username.to_string(), target, permissions));
+    }
+}
+
+#[async_trait]
+impl ShareAccessUseCase for HomeOnlyAccess {
+    async fn ensure_access(
+        &self,
+        recipient: ShareRecipient<'_>,
+        target: &ShareTarget,
+        required: SharePermissions,
+    ) -> Result<(), DomainError> {
+        let path = match target {
+            ShareTarget::File(path) | ShareTarget::Folder(path) | ShareTarget::Path(path) => path,
+        };
+        if owner_of_path(path).is_none_or(|owner| owner == recipient.username) {
+            return Ok(());
+        }
+        let granted = self.grants.lock().unwrap().iter().any(|(username, granted_target, permissions)| {
+            username == recipient.username && granted_target == target && permissions.contains(required)
+        });
+        if granted {
+            Ok(())
+        } else {
+            Err(DomainError::access_denied("Share", format!("Your shares do not allow this operation on {}", path)))
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use std::sync::Mutex;
+use async_trait::async_trait;
+
+use crate::application::ports::auth_ports::UserStoragePort;
+use crate::common::errors::DomainError;
+use crate::domain::entities::user::{User, UserRole};
+
+/// Almacén de usuarios en memoria para las pruebas de los servicios
+#[derive(Default)]
+pub struct MemoryUsers {
+    users: Mutex<Vec<User>>,
+}
+
+impl MemoryUsers {
+    /// Usuarios normales sin cuota, uno por nombre
+    pub fn with_usernames(usernames: &[&str]) -> Self {
+        let users = Self::default();
+        for username in usernames {
+            users.add(username, UserRole::User, 0);
+        }
+        users
+    }
+
+    /// Añade un usuario y devuelve su ID
+    pub fn add(&self, username: &str, role: UserRole, quota: i64) -> String {
+        let user = User::new(
+            username.to_string(),
+            format!("{}@example.com", username),
+            "Password123!".to_string(),
+            role,
+            quota,
+        ).unwrap();
+        self.insert(user)
+    }
+
+    /// Añade un usuario ya construido y devuelve su ID
+    pub fn insert(&self, user: User) -> String {
+        let id = user.id().to_string();
+        self.users.lock().unwrap().push(user);
+        id
+    }
+
+    pub fn id_of(&self, username: &str) -> String {
+        self.find(|user| user.username() == username).unwrap().id().to_string()
+    }
+
+    fn find(&self, predicate: impl Fn(&User) -> bool) -> Result<User, DomainError> {
+        self.users.lock().unwrap().iter().find(|user| predicate(user)).cloned()
+            .ok_or_else(|| DomainError::not_found("User", "unknown"))
+    }
+
+    fn modify(&self, user_id: &str, change: impl FnOnce(&mut User)) -> Result<(), DomainError> {
+        let mut users = self.users.lock().unwrap();
+        let user = users.iter_mut().find(|user| user.id() == user_id)
+            .ok_or_else(|| DomainError::not_found("User", user_id))?;
+        change(user);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl UserStoragePort for MemoryUsers {
+    async fn create_user(&self, user: User) -> Result<User, DomainError> {
+        if self.find(|existing| existing.username() == user.username()).is_ok() {
+            return Err(DomainError::already_exists("User", user.username()));
+        }
+        self.insert(user.clone());
+        Ok(user)
+    }
+    async fn get_user_by_id(&self, id: &str) -> Result<User, DomainError> {
+        self.find(|user| user.id() == id)
+    }
+    async fn get_user_by_username(&self, username: &str) -> Result<User, DomainError> {
+        self.find(|user| user.username() == username)
+    }
+    async fn get_user_by_email(&self, email: &str) -> Result<User, DomainError> {
+        self.find(|user| user.email() == email)
+    }
+    async fn update_user(&self, user: User) -> Result<User, DomainError> {
+        let id = user.id().to_string();
+        self.modify(&id, |stored| *stored = user.clone())?;
+        Ok(user)
+    }
+    async fn update_storage_usage(&self, user_id: &str, usage_bytes: i64) -> Result<(), DomainError> {
+        self.modify(user_id, |user| user.update_storage_used(usage_bytes))
+    }
+    async fn list_users(&self, limit: i64, offset: i64) -> Result<Vec<User>, DomainError> {
+        let mut users = self.users.lock().unwrap().clone();
+        users.sort_by(|a, b| a.username().cmp(b.username()));
+        Ok(users.into_iter().skip(offset as usize).take(limit as usize).collect())
+    }
+    async fn list_users_by_role(&self, role: &str) -> Result<Vec<User>, DomainError> {
+        Ok(self.users.lock().unwrap().iter().filter(|user| user.role().to_string() == role).cloned().collect())
+    }
+    async fn search_users(&self, query: &str, limit: i64) -> Result<Vec<User>, DomainError> {
+        Ok(self.users.lock().unwrap().iter()
+            .filter(|user| user.is_active() && (user.username().contains(query) || user.email().contains(query)))
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+    async fn delete_user(&self, user_id: &str) -> Result<(), DomainError> {
+        self.users.lock().unwrap().retain(|user| user.id() != user_id);
+        Ok(())
+    }
+    async fn change_password(&self, user_id: &str, password_hash: &str) -> Result<(), DomainError> {
+        self.modify(user_id, |user| {
+            *user = User::from_data(
+                user.id().to_string(),
+                user.username().to_string(),
+                user.email().to_string(),
+                password_hash.to_string(),
+                user.role(),
+                user.storage_quota_bytes(),
+                user.storage_used_bytes(),
+                user.created_at(),
+                user.updated_at(),
+                user.last_login_at(),
+                user.is_active(),
+                user.auth_source(),
+            );
+        })
+    }
+}
@@ -0,0 +1,347 @@
+use std::fmt::Write;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use tokio::sync::RwLock;
+
+use crate::application::dtos::theme_dto::{ThemeDto, ThemeLogoDto, UpdateThemeDto};
+use crate::application::ports::theme_ports::ThemeUseCase;
+use crate::common::errors::DomainError;
+use crate::domain::entities::theme::{
+    InstanceTheme, ThemeLogo, LOGO_CONTENT_TYPES, MAX_INSTANCE_NAME_LENGTH, MAX_LOGIN_MESSAGE_LENGTH, MAX_LOGO_BYTES,
+};
+use crate::domain::repositories::theme_repository::ThemeRepository;
+
+/// Selectores de la interfaz web que usan el color principal como fondo
+const PRIMARY_BACKGROUND_SELECTORS: &[&str] = &[
+    ".logo", ".user-avatar", ".storage-fill", ".progress-fill", ".search-button", ".search-box button",
+    ".btn-primary", ".primary", ".button.primary", ".auth-logo-icon", ".auth-button", ".step-number.active",
+];
+
+/// Selectores que usan el color de resalte como fondo
+const ACCENT_BACKGROUND_SELECTORS: &[&str] = &[
+    ".search-button:hover", ".search-box button:hover", ".primary:hover", ".button.primary:hover", ".auth-button:hover",
+];
+
+/// Tema cargado del repositorio y el momento en que se leyó
+struct CachedTheme {
+    loaded_at: Instant,
+    theme: Arc<InstanceTheme>,
+}
+
+/// Servicio de imagen de marca de la instancia.
+///
+/// El tema se pide en cada carga de página, así que se guarda en memoria
+/// durante `cache_ttl`. Los cambios hechos desde esta instancia se ven al
+/// momento; los de otras instancias, al caducar la caché.
+pub struct ThemeService {
+    repository: Arc<dyn ThemeRepository>,
+    cache_ttl: Duration,
+    cache: RwLock<Option<CachedTheme>>,
+}
+
+impl ThemeService {
+    pub fn new(repository: Arc<dyn ThemeRepository>, cache_ttl: Duration) -> Self {
+        Self {
+            repository,
+            cache_ttl,
+            cache: RwLock::new(None),
+        }
+    }
+
+    /// Tema vigente, recargado si la caché ha caducado.
+    ///
+    /// Si el repositorio falla se sigue usando el último tema leído, o la
+    /// marca original si no se leyó ninguno.
+    async fn theme(&self) -> Arc<InstanceTheme> {
+        if let Some(cached) = self.cache.read().await.as_ref() {
+            if cached.loaded_at.elapsed() < self.cache_ttl {
+                return cached.theme.clone();
+            }
+        }
+
+        let mut cache = self.cache.write().await;
+        // Otra tarea puede haberlo recargado mientras esperábamos
+        if let Some(cached) = cache.as_ref() {
+            if cached.loaded_at.elapsed() < self.cache_ttl {
+                return cached.theme.clone();
+            }
+        }
+
+        match self.repository.get_theme().await {
+            Ok(theme) => {
+                let theme = Arc::new(theme.unwrap_or_default());
+                *cache = Some(CachedTheme { loaded_at: Instant::now(), theme: Arc::clone(&theme) });
+                theme
+            },
+            Err(e) => {
+                tracing::warn!("Could not load the instance theme, using cached values: {}", e);
+                cache.as_ref().map(|cached| cached.theme.clone()).unwrap_or_default()
+            },
+        }
+    }
+
+    /// Descarta la caché para que la siguiente consulta vea los cambios
+    async fn invalidate(&self) {
+        *self.cache.write().await = None;
+    }
+
+    /// Lee el tema directamente del repositorio, sin pasar por la caché
+    async fn load_theme(&self) -> Result<InstanceTheme, DomainError> {
+        Ok(self.repository.get_theme().await?.unwrap_or_default())
+    }
+
+    /// Guarda el tema con una nueva versión y devuelve cómo queda
+    async fn save(&self, mut theme: InstanceTheme) -> Result<ThemeDto, DomainError> {
+        theme.updated_at = Utc::now();
+        self.repository.save_theme(&theme).await?;
+        self.invalidate().await;
+        Ok(ThemeDto::from(&theme))
+    }
+
+    fn validate_color(field: &str, color: &str) -> Result<String, DomainError> {
+        let color = color.trim();
+        if !InstanceTheme::is_valid_color(color) {
+            return Err(DomainError::validation_error(format!(
+                "Invalid {} '{}': use a hex color such as #ff5e3a",
+                field, color
+            )));
+        }
+        Ok(InstanceTheme::normalize_color(color))
+    }
+
+    /// Comprueba que el contenido corresponde de verdad al formato declarado
+    fn validate_logo(content_type: &str, data: &[u8]) -> Result<(), DomainError> {
+        if !LOGO_CONTENT_TYPES.contains(&content_type) {
+            return Err(DomainError::validation_error(format!(
+                "Unsupported logo format '{}': use one of {}",
+                content_type, LOGO_CONTENT_TYPES.join(", ")
+            )));
+        }
+        if data.is_empty() || data.len() > MAX_LOGO_BYTES {
+            return Err(DomainError::validation_error(format!(
+                "The logo must be between 1 and {} bytes",
+                MAX_LOGO_BYTES
+            )));
+        }
+
+        let matches_format = match content_type {
+            "image/png" => data.starts_with(b"\x89PNG\r\n\x1a\n"),
+            "image/jpeg" => data.starts_with(&[0xFF, 0xD8, 0xFF]),
+            "image/webp" => data.len() > 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"WEBP",
+            _ => std::str::from_utf8(data).is_ok_and(|text| text.contains("<svg")),
+        };
+        if !matches_format {
+            return Err(DomainError::validation_error(format!("The logo is not a valid {} image", content_type)));
+        }
+        Ok(())
+    }
+
+    /// Hoja de estilos que aplica los colores del tema sobre la interfaz web.
+    ///
+    /// Solo admite colores ya validados, así que no puede inyectar reglas.
+    pub fn render_stylesheet(theme: &InstanceTheme) -> String {
+        let mut css = String::new();
+        let _ = writeln!(css, "/* {} theme, generated by OxiCloud */", theme.instance_name.replace("*/", ""));
+        let _ = writeln!(css, ":root {{");
+        let _ = writeln!(css, "    --oxicloud-primary: {};", theme.primary_color);
+        let _ = writeln!(css, "    --oxicloud-accent: {};", theme.accent_color);
+        let _ = writeln!(css, "}}");
+        let _ = writeln!(css, "{} {{ background-color: var(--oxicloud-primary); }}", PRIMARY_BACKGROUND_SELECTORS.join(", "));
+        let _ = writeln!(css, "{} {{ background-color: var(--oxicloud-accent); }}", ACCENT_BACKGROUND_SELECTORS.join(", "));
+        let _ = writeln!(css, ".logout-btn:hover, .auth-toggle-link {{ color: var(--oxicloud-primary); }}");
+        let _ = writeln!(css, ".dropzone.active, .auth-input:focus {{ border-color: var(--oxicloud-primary); }}");
+        css
+    }
+}
+
+#[async_trait]
+impl ThemeUseCase for ThemeService {
+    async fn get_theme(&self) -> ThemeDto {
+        ThemeDto::from(self.theme().await.as_ref())
+    }
+
+    async fn stylesheet(&self) -> (String, i64) {
+        let theme = self.theme().await;
+        (Self::render_stylesheet(&theme), theme.version())
+    }
+
+    async fn get_logo(&self) -> Option<ThemeLogoDto> {
+        let theme = self.theme().await;
+        theme.logo.as_ref().map(|logo| ThemeLogoDto {
+            content_type: logo.content_type.clone(),
+            data: logo.data.clone(),
+            version: theme.version(),
+        })
+    }
+
+    async fn update_theme(&self, update: UpdateThemeDto) -> Result<ThemeDto, DomainError> {
+        let mut theme = self.load_theme().await?;
+
+        if let Some(instance_name) = update.instance_name {
+            let instance_name = instance_name.trim();
+            if instance_name.is_empty() || instance_name.chars().count() > MAX_INSTANCE_NAME_LENGTH {
+                return Err(DomainError::validation_error(format!(
+                    "The instance name must have between 1 and {} characters",
+                    MAX_INSTANCE_NAME_LENGTH
+                )));
+            }
+            theme.instance_name = instance_name.to_string();
+        }
+        if let Some(color) = update.primary_color {
+            theme.primary_color = Self::validate_color("primary_color", &color)?;
+        }
+        if let Some(color) = update.accent_color {
+            theme.accent_color = Self::validate_color("accent_color", &color)?;
+        }
+        if let Some(login_message) = update.login_message {
+            if login_message.chars().count() > MAX_LOGIN_MESSAGE_LENGTH {
+                return Err(DomainError::validation_error(format!(
+                    "The login message cannot exceed {} characters",
+                    MAX_LOGIN_MESSAGE_LENGTH
+                )));
+            }
+            theme.login_message = Some(login_message).filter(|m| !m.trim().is_empty());
+        }
+
+        let dto = self.save(theme).await?;
+        tracing::info!("Instance theme updated: '{}'", dto.instance_name);
+        Ok(dto)
+    }
+
+    async fn set_logo(&self, content_type: &str, data: Vec<u8>) -> Result<ThemeDto, DomainError> {
+        let content_type = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+        Self::validate_logo(&content_type, &data)?;
+
+        // Guardar primero el tema crea la fila si aún no existe y cambia la versión
+        let mut theme = self.load_theme().await?;
+        let logo = ThemeLogo { content_type, data };
+        let dto = self.save(theme.clone()).await?;
+        self.repository.set_logo(Some(&logo)).await?;
+        self.invalidate().await;
+
+        theme.logo = Some(logo);
+        theme.updated_at = dto.updated_at;
+        tracing::info!("Instance logo replaced ({} bytes)", theme.logo.as_ref().map_or(0, |l| l.data.len()));
+        Ok(ThemeDto::from(&theme))
+    }
+
+    async fn remove_logo(&self) -> Result<ThemeDto, DomainError> {
+        let theme = self.load_theme().await?;
+        if theme.logo.is_none() {
+            return Err(DomainError::not_found("ThemeLogo", "logo"));
+        }
+        self.repository.set_logo(None).await?;
+        self.save(InstanceTheme { logo: None, ..theme }).await
+    }
+
+    async fn reset_theme(&self) -> Result<ThemeDto, DomainError> {
+        let dto = self.save(InstanceTheme::default()).await?;
+        self.repository.set_logo(None).await?;
+        self.invalidate().await;
+        tracing::info!("Instance theme reset to the default branding");
+        Ok(dto)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use crate::common::errors::ErrorKind;
+    use crate::domain::repositories::theme_repository::ThemeRepositoryResult;
+
+    #[derive(Default)]
+    struct InMemoryThemeRepository {
+        theme: Mutex<Option<InstanceTheme>>,
+        reads: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ThemeRepository for InMemoryThemeRepository {
+        async fn get_theme(&self) -> ThemeRepositoryResult<Option<InstanceTheme>> {
+            self.reads.fetch_add(1, Ordering::SeqCst);
+            Ok(self.theme.lock().unwrap().clone())
+        }
+
+        async fn save_theme(&self, theme: &InstanceTheme) -> ThemeRepositoryResult<()> {
+            let mut stored = self.theme.lock().unwrap();
+            let logo = stored.as_mut().and_then(|t| t.logo.take());
+            *stored = Some(InstanceTheme { logo, ..theme.clone() });
+            Ok(())
+        }
+
+        async fn set_logo(&self, logo: Option<&ThemeLogo>) -> ThemeRepositoryResult<()> {
+            if let Some(theme) = self.theme.lock().unwrap().as_mut() {
+                theme.logo = logo.cloned();
+            }
+            Ok(())
+        }
+    }
+
+    fn service(repository: Arc<InMemoryThemeRepository>) -> ThemeService {
+        ThemeService::new(repository, Duration::from_secs(3600))
+    }
+
+    #[tokio::test]
+    async fn test_theme_is_cached_and_updates_invalidate() {
+        let repository = Arc::new(InMemoryThemeRepository::default());
+        let service = service(repository.clone());
+
+        assert_eq!(service.get_theme().await.instance_name, "OxiCloud");
+        assert_eq!(service.get_theme().await.logo_url, None);
+        assert_eq!(repository.reads.load(Ordering::SeqCst), 1);
+
+        let updated = service.update_theme(UpdateThemeDto {
+            instance_name: Some("  Acme Cloud ".to_string()),
+            primary_color: Some("#0A0".to_string()),
+            login_message: Some("Authorized staff only".to_string()),
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(updated.instance_name, "Acme Cloud");
+        assert_eq!(updated.primary_color, "#00aa00");
+        assert_eq!(updated.accent_color, "#e64a29");
+
+        let (css, _) = service.stylesheet().await;
+        assert!(css.contains("--oxicloud-primary: #00aa00;"));
+        assert_eq!(service.get_theme().await.login_message.as_deref(), Some("Authorized staff only"));
+    }
+
+    #[tokio::test]
+    async fn test_logo_upload_and_reset() {
+        let service = service(Arc::new(InMemoryThemeRepository::default()));
+        let svg = br#"<?xml version="1.0"?><svg xmlns="http://www.w3.org/2000/svg"/>"#.to_vec();
+
+        let theme = service.set_logo("image/svg+xml; charset=utf-8", svg.clone()).await.unwrap();
+        assert!(theme.logo_url.unwrap().starts_with("/api/theme/logo?v="));
+        let logo = service.get_logo().await.unwrap();
+        assert_eq!(logo.content_type, "image/svg+xml");
+        assert_eq!(logo.data, svg);
+
+        service.reset_theme().await.unwrap();
+        assert!(service.get_logo().await.is_none());
+        let err = service.remove_logo().await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_requests_are_rejected() {
+        let service = service(Arc::new(InMemoryThemeRepository::default()));
+
+        let bad_color = UpdateThemeDto { accent_color: Some("red; } body { display: none".to_string()), ..Default::default() };
+        assert_eq!(service.update_theme(bad_color).await.unwrap_err().kind, ErrorKind::InvalidInput);
+
+        let empty_name = UpdateThemeDto { instance_name: Some("   ".to_string()), ..Default::default() };
+        assert_eq!(service.update_theme(empty_name).await.unwrap_err().kind, ErrorKind::InvalidInput);
+
+        let err = service.set_logo("image/png", b"not a png".to_vec()).await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidInput);
+        let err = service.set_logo("text/html", b"<svg onload=alert(1)>".to_vec()).await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidInput);
+        let err = service.set_logo("image/png", vec![0; MAX_LOGO_BYTES + 1]).await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidInput);
+    }
+}
@@ -4,6 +4,7 @@ use uuid::Uuid;
 use tracing::{debug, error, info, instrument};
 
 use crate::application::dtos::trash_dto::TrashedItemDto;
+use crate::application::ports::event_bus_ports::{DomainEvent, EventBusPort};
 use crate::application::ports::trash_ports::TrashUseCase;
 use crate::common::errors::{Result, DomainError, ErrorKind};
 use crate::domain::entities::trashed_item::{TrashedItem, TrashedItemType};
@@ -36,6 +37,9 @@ pub struct TrashService {
     
     /// Number of days items should be kept in trash before automatic cleanup
     retention_days: u32,
+    
+    /// Bus that trashed and restored items are published on
+    event_bus: Option<Arc<dyn EventBusPort>>,
 }
 
 impl TrashService {
@@ -50,6 +54,19 @@ impl TrashService {
             file_repository,
             folder_repository,
             retention_days,
+            event_bus: None,
+        }
+    }
+
+    /// Publishes items moved to and restored from the trash as domain events
+    pub fn with_event_bus(mut self, event_bus: Arc<dyn EventBusPort>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    fn publish(&self, event: DomainEvent) {
+        if let Some(event_bus) = &self.event_bus {
+            event_bus.publish(event);
         }
     }
 
@@ -200,6 +217,12 @@ impl TrashUseCase for TrashService {
                 }
                 
                 info!("File completely moved to trash: {}", item_id);
+                self.publish(DomainEvent::FileDeleted {
+                    file_id: item_id.to_string(),
+                    name: file.name().to_string(),
+                    folder_id: file.folder_id().map(String::from),
+                    path: file.path_string().to_string(),
+                });
                 Ok(())
             },
             "folder" => {
@@ -242,6 +265,12 @@ impl TrashUseCase for TrashService {
                     ))?;
                 
                 debug!("Folder moved to trash: {}", item_id);
+                self.publish(DomainEvent::FolderDeleted {
+                    folder_id: item_id.to_string(),
+                    name: folder.name().to_string(),
+                    parent_id: folder.parent_id().map(String::from),
+                    path: folder.path_string().to_string(),
+                });
                 Ok(())
             },
             _ => Err(DomainError::validation_error(format!("Invalid item type: {}", item_type))),
@@ -358,6 +387,15 @@ impl TrashUseCase for TrashService {
                 }
                 
                 info!("Item successfully restored from trash: {}", trash_id);
+                self.publish(DomainEvent::ItemRestored {
+                    item_id: item.original_id.to_string(),
+                    item_type: match item.item_type {
+                        TrashedItemType::File => "file".to_string(),
+                        TrashedItemType::Folder => "folder".to_string(),
+                    },
+                    name: item.name.clone(),
+                    path: item.original_path.clone(),
+                });
                 Ok(())
             },
             Ok(None) => {
@@ -86,6 +86,10 @@ impl TrashRepository for MockTrashRepository {
             .collect();
         Ok(expired)
     }
+
+    async fn get_all_items(&self) -> Result<Vec<TrashedItem>> {
+        Ok(self.trash_items.lock().unwrap().values().cloned().collect())
+    }
 }
 
 struct MockFileRepository {
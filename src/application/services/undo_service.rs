@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::application::dtos::folder_dto::{MoveFolderDto, RenameFolderDto};
+use crate::application::dtos::undo_dto::{UndoResultDto, UndoTokenDto};
+use crate::application::ports::inbound::{FileUseCase, FolderUseCase};
+use crate::application::ports::trash_ports::TrashUseCase;
+use crate::application::ports::undo_ports::{UndoAction, UndoUseCase};
+use crate::common::errors::DomainError;
+
+/// Operación pendiente de deshacer
+struct PendingUndo {
+    user_id: Option<String>,
+    action: UndoAction,
+    expires_at: DateTime<Utc>,
+}
+
+/// Servicio que permite deshacer borrados, movimientos y renombrados durante
+/// unos minutos.
+///
+/// Cada operación registra cómo revertirla (sacar el elemento de la papelera
+/// o devolverlo a su carpeta o nombre anterior) y recibe un token de un solo
+/// uso. Los tokens se guardan en memoria: no sobreviven a un reinicio, igual
+/// que la sesión en la que se hizo la operación.
+pub struct UndoService {
+    file_service: Arc<dyn FileUseCase>,
+    folder_service: Arc<dyn FolderUseCase>,
+    trash_service: Option<Arc<dyn TrashUseCase>>,
+    window: Duration,
+    pending: Mutex<HashMap<String, PendingUndo>>,
+}
+
+impl UndoService {
+    /// Crea un nuevo servicio cuyos tokens valen durante `window_minutes`
+    pub fn new(
+        file_service: Arc<dyn FileUseCase>,
+        folder_service: Arc<dyn FolderUseCase>,
+        window_minutes: u64,
+    ) -> Self {
+        Self {
+            file_service,
+            folder_service,
+            trash_service: None,
+            window: Duration::minutes(window_minutes as i64),
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Permite deshacer los borrados que pasan por la papelera
+    pub fn with_trash_service(mut self, trash_service: Arc<dyn TrashUseCase>) -> Self {
+        self.trash_service = Some(trash_service);
+        self
+    }
+
+    /// Ejecuta la operación inversa
+    async fn revert(&self, action: UndoAction) -> Result<UndoResultDto, DomainError> {
+        match action {
+            UndoAction::RestoreFromTrash { item_id, item_type, trash_user_id } => {
+                let trash = self.trash_service.as_ref()
+                    .ok_or_else(|| DomainError::internal_error("Undo", "The trash is not available"))?;
+                // El ID de la papelera no se conoce al borrar; se busca por el ID original
+                let trashed = trash.get_trash_items(&trash_user_id).await?
+                    .into_iter()
+                    .find(|item| item.original_id == item_id)
+                    .ok_or_else(|| DomainError::not_found("Trashed item", item_id.clone()))?;
+                trash.restore_item(&trashed.id, &trash_user_id).await?;
+                Ok(UndoResultDto { action: "restore".to_string(), item_id, item_type })
+            }
+            UndoAction::MoveFile { file_id, folder_id } => {
+                self.file_service.move_file(&file_id, folder_id).await?;
+                Ok(UndoResultDto { action: "move".to_string(), item_id: file_id, item_type: "file".to_string() })
+            }
+            UndoAction::MoveFolder { folder_id, parent_id } => {
+                self.folder_service.move_folder(&folder_id, MoveFolderDto { parent_id }).await?;
+                Ok(UndoResultDto { action: "move".to_string(), item_id: folder_id, item_type: "folder".to_string() })
+            }
+            UndoAction::RenameFolder { folder_id, name } => {
+                self.folder_service.rename_folder(&folder_id, RenameFolderDto { name }).await?;
+                Ok(UndoResultDto { action: "rename".to_string(), item_id: folder_id, item_type: "folder".to_string() })
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl UndoUseCase for UndoService {
+    async fn register(&self, user_id: Option<&str>, action: UndoAction) -> UndoTokenDto {
+        let now = Utc::now();
+        let token = Uuid::new_v4().to_string();
+        let expires_at = now + self.window;
+
+        let mut pending = self.pending.lock().await;
+        pending.retain(|_, undo| undo.expires_at > now);
+        pending.insert(token.clone(), PendingUndo {
+            user_id: user_id.map(str::to_string),
+            action,
+            expires_at,
+        });
+        UndoTokenDto { token, expires_at }
+    }
+
+    async fn undo(&self, user_id: Option<&str>, token: &str) -> Result<UndoResultDto, DomainError> {
+        let action = {
+            let mut pending = self.pending.lock().await;
+            // Un token de otro usuario se trata como inexistente para no revelar nada
+            let undo = match pending.remove(token) {
+                Some(undo) if undo.user_id.as_deref() == user_id => undo,
+                Some(undo) => {
+                    pending.insert(token.to_string(), undo);
+                    return Err(DomainError::not_found("Undo token", token));
+                }
+                None => return Err(DomainError::not_found("Undo token", token)),
+            };
+            if undo.expires_at <= Utc::now() {
+                return Err(DomainError::not_found("Undo token", token));
+            }
+            undo.action
+        };
+
+        let result = self.revert(action).await?;
+        tracing::info!("Undid {} of {} {}", result.action, result.item_type, result.item_id);
+        Ok(result)
+    }
+}
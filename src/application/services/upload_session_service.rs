@@ -0,0 +1,625 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use tokio::fs;
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+use uuid::Uuid;
+
+use crate::application::dtos::file_dto::FileDto;
+use crate::application::dtos::upload_session_dto::{CreateUploadSessionDto, UploadRecoveryReport, UploadSessionDto};
+use crate::application::ports::inbound::FileUseCase;
+use crate::application::ports::quota_ports::StorageQuotaUseCase;
+use crate::application::ports::upload_session_ports::{ChunkStream, UploadSessionUseCase};
+use crate::common::errors::{DomainError, ErrorKind};
+
+/// Extensión de los ficheros temporales de las subidas en curso
+const PART_EXTENSION: &str = "part";
+
+/// Servicio de subidas por partes reanudables.
+///
+/// El estado de cada sesión (offset, fichero temporal, caducidad) se guarda en
+/// PostgreSQL para que una subida a medias sobreviva a un reinicio del
+/// servidor; los datos recibidos se acumulan en un fichero temporal.
+pub struct UploadSessionService {
+    db_pool: Arc<PgPool>,
+    file_service: Arc<dyn FileUseCase>,
+    temp_dir: PathBuf,
+    session_ttl: chrono::Duration,
+    max_upload_size: u64,
+    quota_service: Option<Arc<dyn StorageQuotaUseCase>>,
+    /// Sesiones que están recibiendo datos en este momento
+    active_writes: Mutex<HashSet<String>>,
+}
+
+/// Reserva de escritura sobre una sesión; se libera al soltarla
+struct WriteClaim<'a> {
+    active_writes: &'a Mutex<HashSet<String>>,
+    session_id: String,
+}
+
+impl Drop for WriteClaim<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut active) = self.active_writes.lock() {
+            active.remove(&self.session_id);
+        }
+    }
+}
+
+/// Sesión tal como está guardada en la base de datos
+struct StoredSession {
+    dto: UploadSessionDto,
+    temp_path: PathBuf,
+}
+
+impl UploadSessionService {
+    /// Crea el servicio guardando los ficheros temporales en `temp_dir`
+    pub fn new(
+        db_pool: Arc<PgPool>,
+        file_service: Arc<dyn FileUseCase>,
+        temp_dir: PathBuf,
+        session_ttl_hours: u64,
+        max_upload_size: u64,
+    ) -> Self {
+        Self {
+            db_pool,
+            file_service,
+            temp_dir,
+            session_ttl: chrono::Duration::hours(session_ttl_hours.max(1) as i64),
+            max_upload_size,
+            quota_service: None,
+            active_writes: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Comprueba la cuota al abrir y al completar cada sesión y cobra el
+    /// archivo resultante al usuario
+    pub fn with_quota_service(mut self, quota_service: Arc<dyn StorageQuotaUseCase>) -> Self {
+        self.quota_service = Some(quota_service);
+        self
+    }
+
+    async fn check_quota(&self, user_id: &str, bytes: u64) -> Result<(), DomainError> {
+        match &self.quota_service {
+            Some(quotas) => quotas.check_quota(user_id, bytes).await,
+            None => Ok(()),
+        }
+    }
+
+    fn row_to_session(row: &PgRow) -> StoredSession {
+        StoredSession {
+            dto: UploadSessionDto {
+                id: row.get("id"),
+                name: row.get("filename"),
+                folder_id: row.get("folder_id"),
+                content_type: row.get("content_type"),
+                total_size: row.get::<i64, _>("total_size").max(0) as u64,
+                offset: row.get::<i64, _>("upload_offset").max(0) as u64,
+                expires_at: row.get("expires_at"),
+            },
+            temp_path: PathBuf::from(row.get::<String, _>("temp_path")),
+        }
+    }
+
+    async fn load_session(&self, user_id: &str, session_id: &str) -> Result<StoredSession, DomainError> {
+        let row = sqlx::query(
+            "SELECT id, filename, folder_id, content_type, total_size, upload_offset, temp_path, expires_at
+             FROM auth.upload_sessions WHERE id = $1 AND user_id = $2"
+        )
+        .bind(session_id)
+        .bind(user_id)
+        .fetch_optional(&*self.db_pool)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to read upload session: {}", e)))?
+        .ok_or_else(|| DomainError::not_found("UploadSession", session_id))?;
+
+        Ok(Self::row_to_session(&row))
+    }
+
+    async fn delete_session(&self, session_id: &str, temp_path: &Path) -> Result<(), DomainError> {
+        sqlx::query("DELETE FROM auth.upload_sessions WHERE id = $1")
+            .bind(session_id)
+            .execute(&*self.db_pool)
+            .await
+            .map_err(|e| DomainError::database_error(format!("Failed to delete upload session: {}", e)))?;
+        remove_temp_file(temp_path).await;
+        Ok(())
+    }
+
+    fn expiry_from_now(&self) -> DateTime<Utc> {
+        Utc::now() + self.session_ttl
+    }
+
+    /// Impide que dos peticiones escriban a la vez en la misma sesión; la
+    /// segunda recibe `Locked` en lugar de esperar
+    fn claim_write(&self, session_id: &str) -> Result<WriteClaim<'_>, DomainError> {
+        let mut active = self.active_writes.lock()
+            .map_err(|_| DomainError::internal_error("UploadSession", "Upload write registry is poisoned"))?;
+        if !active.insert(session_id.to_string()) {
+            return Err(DomainError::locked("UploadSession", format!("Upload session {} is already receiving data", session_id)));
+        }
+        Ok(WriteClaim { active_writes: &self.active_writes, session_id: session_id.to_string() })
+    }
+}
+
+/// Borra un fichero temporal ignorando que ya no exista
+async fn remove_temp_file(path: &Path) {
+    if let Err(e) = fs::remove_file(path).await {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            tracing::warn!("Could not remove upload data {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// Escribe un bloque en la posición `offset`, descartando antes cualquier
+/// byte posterior que no llegara a confirmarse
+async fn write_chunk_at(path: &Path, offset: u64, data: &[u8]) -> std::io::Result<()> {
+    let mut file = fs::OpenOptions::new().write(true).create(true).truncate(false).open(path).await?;
+    file.set_len(offset).await?;
+    file.seek(std::io::SeekFrom::Start(offset)).await?;
+    file.write_all(data).await?;
+    file.flush().await?;
+    file.sync_all().await
+}
+
+/// Escribe un stream a partir de `offset` sin pasar de `limit` bytes.
+///
+/// Devuelve los bytes escritos y sincronizados junto con el error que cortó
+/// la escritura, si lo hubo: lo recibido antes de un corte se conserva para
+/// que el cliente reanude desde ahí.
+async fn write_stream_at(path: &Path, offset: u64, limit: u64, mut data: ChunkStream) -> (u64, Option<DomainError>) {
+    let storage_error = |e: std::io::Error| DomainError::internal_error("UploadSession", format!("Failed to store chunk: {}", e));
+
+    let mut file = match fs::OpenOptions::new().write(true).create(true).truncate(false).open(path).await {
+        Ok(file) => file,
+        Err(e) => return (0, Some(storage_error(e))),
+    };
+    if let Err(e) = file.set_len(offset).await {
+        return (0, Some(storage_error(e)));
+    }
+    if let Err(e) = file.seek(std::io::SeekFrom::Start(offset)).await {
+        return (0, Some(storage_error(e)));
+    }
+
+    let mut written = 0u64;
+    let mut failure = None;
+    while let Some(chunk) = data.next().await {
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                failure = Some(DomainError::internal_error("UploadSession", format!("Upload interrupted: {}", e)));
+                break;
+            }
+        };
+        if written + chunk.len() as u64 > limit {
+            failure = Some(DomainError::validation_error("Chunk exceeds the declared file size"));
+            break;
+        }
+        if let Err(e) = file.write_all(&chunk).await {
+            failure = Some(storage_error(e));
+            break;
+        }
+        written += chunk.len() as u64;
+    }
+
+    // Una escritura a medias puede haber dejado bytes sin contar; se recortan
+    let synced = async {
+        file.set_len(offset + written).await?;
+        file.flush().await?;
+        file.sync_all().await
+    }.await;
+    match synced {
+        Ok(()) => (written, failure),
+        Err(e) => (0, Some(storage_error(e))),
+    }
+}
+
+/// Offset que debe usarse al reanudar según la longitud real del fichero
+/// temporal, o `None` si el offset guardado es válido.
+///
+/// Los bytes que superan el offset guardado no llegaron a confirmarse y se
+/// descartan; si el fichero es más corto (escritura perdida), se retrocede.
+fn revalidated_offset(recorded: u64, actual_len: u64) -> Option<u64> {
+    if actual_len == recorded {
+        None
+    } else {
+        Some(recorded.min(actual_len))
+    }
+}
+
+#[async_trait]
+impl UploadSessionUseCase for UploadSessionService {
+    async fn create_session(&self, user_id: &str, dto: CreateUploadSessionDto) -> Result<UploadSessionDto, DomainError> {
+        let name = dto.name.trim();
+        if name.is_empty() || name.contains('/') || name.contains('\\') {
+            return Err(DomainError::validation_error("A valid file name is required"));
+        }
+        if dto.total_size > self.max_upload_size {
+            return Err(DomainError::validation_error(format!(
+                "File exceeds the maximum upload size of {} bytes",
+                self.max_upload_size
+            )));
+        }
+        self.check_quota(user_id, dto.total_size).await?;
+
+        let id = Uuid::new_v4().to_string();
+        let temp_path = self.temp_dir.join(format!("{}.{}", id, PART_EXTENSION));
+        fs::create_dir_all(&self.temp_dir).await
+            .map_err(|e| DomainError::internal_error("UploadSession", format!("Failed to create upload directory: {}", e)))?;
+        fs::File::create(&temp_path).await
+            .map_err(|e| DomainError::internal_error("UploadSession", format!("Failed to create upload data: {}", e)))?;
+
+        let expires_at = self.expiry_from_now();
+        let inserted = sqlx::query(
+            "INSERT INTO auth.upload_sessions
+                (id, user_id, filename, folder_id, content_type, total_size, temp_path, expires_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"
+        )
+        .bind(&id)
+        .bind(user_id)
+        .bind(name)
+        .bind(&dto.folder_id)
+        .bind(&dto.content_type)
+        .bind(dto.total_size as i64)
+        .bind(temp_path.to_string_lossy().to_string())
+        .bind(expires_at)
+        .execute(&*self.db_pool)
+        .await;
+
+        if let Err(e) = inserted {
+            remove_temp_file(&temp_path).await;
+            return Err(DomainError::database_error(format!("Failed to create upload session: {}", e)));
+        }
+
+        tracing::info!("Upload session {} started for {} ({} bytes)", id, name, dto.total_size);
+        Ok(UploadSessionDto {
+            id,
+            name: name.to_string(),
+            folder_id: dto.folder_id,
+            content_type: dto.content_type,
+            total_size: dto.total_size,
+            offset: 0,
+            expires_at,
+        })
+    }
+
+    async fn get_session(&self, user_id: &str, session_id: &str) -> Result<UploadSessionDto, DomainError> {
+        Ok(self.load_session(user_id, session_id).await?.dto)
+    }
+
+    async fn append_chunk(
+        &self,
+        user_id: &str,
+        session_id: &str,
+        offset: u64,
+        data: Bytes,
+    ) -> Result<UploadSessionDto, DomainError> {
+        let _claim = self.claim_write(session_id)?;
+
+        // Lock the row so concurrent chunks for the same session are serialized
+        let mut tx = self.db_pool.begin().await
+            .map_err(|e| DomainError::database_error(format!("Failed to start transaction: {}", e)))?;
+
+        let row = sqlx::query(
+            "SELECT id, filename, folder_id, content_type, total_size, upload_offset, temp_path, expires_at
+             FROM auth.upload_sessions WHERE id = $1 AND user_id = $2 FOR UPDATE"
+        )
+        .bind(session_id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to read upload session: {}", e)))?
+        .ok_or_else(|| DomainError::not_found("UploadSession", session_id))?;
+        let mut session = Self::row_to_session(&row);
+
+        if offset != session.dto.offset {
+            return Err(DomainError::new(
+                ErrorKind::AlreadyExists,
+                "UploadSession",
+                format!("Offset mismatch: upload continues at byte {}", session.dto.offset),
+            ));
+        }
+        let new_offset = offset + data.len() as u64;
+        if new_offset > session.dto.total_size {
+            return Err(DomainError::validation_error("Chunk exceeds the declared file size"));
+        }
+
+        write_chunk_at(&session.temp_path, offset, &data).await
+            .map_err(|e| DomainError::internal_error("UploadSession", format!("Failed to store chunk: {}", e)))?;
+
+        let expires_at = self.expiry_from_now();
+        sqlx::query(
+            "UPDATE auth.upload_sessions SET upload_offset = $1, updated_at = NOW(), expires_at = $2 WHERE id = $3"
+        )
+        .bind(new_offset as i64)
+        .bind(expires_at)
+        .bind(session_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to update upload session: {}", e)))?;
+
+        tx.commit().await
+            .map_err(|e| DomainError::database_error(format!("Failed to commit upload session: {}", e)))?;
+
+        session.dto.offset = new_offset;
+        session.dto.expires_at = expires_at;
+        Ok(session.dto)
+    }
+
+    async fn append_stream(
+        &self,
+        user_id: &str,
+        session_id: &str,
+        offset: u64,
+        data: ChunkStream,
+    ) -> Result<UploadSessionDto, DomainError> {
+        // The body may take minutes to arrive, so instead of holding a row lock
+        // the write is claimed in memory and the offset updated conditionally
+        let _claim = self.claim_write(session_id)?;
+        let mut session = self.load_session(user_id, session_id).await?;
+
+        if offset != session.dto.offset {
+            return Err(DomainError::new(
+                ErrorKind::AlreadyExists,
+                "UploadSession",
+                format!("Offset mismatch: upload continues at byte {}", session.dto.offset),
+            ));
+        }
+
+        let remaining = session.dto.total_size - offset;
+        let (written, failure) = write_stream_at(&session.temp_path, offset, remaining, data).await;
+
+        if written > 0 || failure.is_none() {
+            let new_offset = offset + written;
+            let expires_at = self.expiry_from_now();
+            let updated = sqlx::query(
+                "UPDATE auth.upload_sessions SET upload_offset = $1, updated_at = NOW(), expires_at = $2
+                 WHERE id = $3 AND upload_offset = $4"
+            )
+            .bind(new_offset as i64)
+            .bind(expires_at)
+            .bind(session_id)
+            .bind(offset as i64)
+            .execute(&*self.db_pool)
+            .await
+            .map_err(|e| DomainError::database_error(format!("Failed to update upload session: {}", e)))?;
+
+            if updated.rows_affected() == 0 {
+                return Err(DomainError::new(
+                    ErrorKind::AlreadyExists,
+                    "UploadSession",
+                    "Upload session changed while the chunk was being received",
+                ));
+            }
+
+            session.dto.offset = new_offset;
+            session.dto.expires_at = expires_at;
+        }
+
+        match failure {
+            Some(e) => {
+                tracing::warn!("Upload session {} stopped at byte {}: {}", session_id, session.dto.offset, e);
+                Err(e)
+            }
+            None => Ok(session.dto),
+        }
+    }
+
+    async fn complete_session(&self, user_id: &str, session_id: &str) -> Result<FileDto, DomainError> {
+        let session = self.load_session(user_id, session_id).await?;
+        if session.dto.offset != session.dto.total_size {
+            return Err(DomainError::validation_error(format!(
+                "Upload incomplete: {} of {} bytes received",
+                session.dto.offset, session.dto.total_size
+            )));
+        }
+
+        let data_len = fs::metadata(&session.temp_path).await
+            .map_err(|e| DomainError::internal_error("UploadSession", format!("Failed to read upload data: {}", e)))?
+            .len();
+        if data_len != session.dto.total_size {
+            return Err(DomainError::internal_error(
+                "UploadSession",
+                format!("Upload data has {} bytes, expected {}", data_len, session.dto.total_size),
+            ));
+        }
+        // Other uploads may have used the space since the session was opened
+        self.check_quota(user_id, data_len).await?;
+
+        let file = self.file_service
+            .upload_file_from_path(
+                session.dto.name.clone(),
+                session.dto.folder_id.clone(),
+                session.dto.content_type.clone(),
+                &session.temp_path,
+            )
+            .await?;
+        if let Some(quotas) = &self.quota_service {
+            if let Err(e) = quotas.record_usage(user_id, data_len as i64).await {
+                tracing::warn!("Could not record storage usage for {}: {}", user_id, e);
+            }
+        }
+
+        self.delete_session(session_id, &session.temp_path).await?;
+        tracing::info!("Upload session {} completed as file {}", session_id, file.id);
+        Ok(file)
+    }
+
+    async fn cancel_session(&self, user_id: &str, session_id: &str) -> Result<(), DomainError> {
+        let session = self.load_session(user_id, session_id).await?;
+        self.delete_session(session_id, &session.temp_path).await
+    }
+
+    async fn recover_sessions(&self) -> Result<UploadRecoveryReport, DomainError> {
+        let rows = sqlx::query(
+            "SELECT id, filename, folder_id, content_type, total_size, upload_offset, temp_path, expires_at
+             FROM auth.upload_sessions"
+        )
+        .fetch_all(&*self.db_pool)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to list upload sessions: {}", e)))?;
+
+        let mut report = UploadRecoveryReport::default();
+        let mut known = HashSet::new();
+
+        for row in &rows {
+            let session = Self::row_to_session(row);
+            let actual_len = match fs::metadata(&session.temp_path).await {
+                Ok(metadata) => metadata.len(),
+                Err(_) => {
+                    tracing::warn!("Dropping upload session {}: temporary data is gone", session.dto.id);
+                    self.delete_session(&session.dto.id, &session.temp_path).await?;
+                    report.dropped += 1;
+                    continue;
+                }
+            };
+            known.insert(session.temp_path.clone());
+
+            if let Some(offset) = revalidated_offset(session.dto.offset, actual_len) {
+                if actual_len > offset {
+                    let file = fs::OpenOptions::new().write(true).open(&session.temp_path).await
+                        .map_err(|e| DomainError::internal_error("UploadSession", format!("Failed to open upload data: {}", e)))?;
+                    file.set_len(offset).await
+                        .map_err(|e| DomainError::internal_error("UploadSession", format!("Failed to truncate upload data: {}", e)))?;
+                }
+                sqlx::query("UPDATE auth.upload_sessions SET upload_offset = $1 WHERE id = $2")
+                    .bind(offset as i64)
+                    .bind(&session.dto.id)
+                    .execute(&*self.db_pool)
+                    .await
+                    .map_err(|e| DomainError::database_error(format!("Failed to update upload session: {}", e)))?;
+                report.adjusted += 1;
+            }
+            report.resumed += 1;
+        }
+
+        // Temporary files without a session were left by a crash before the insert
+        if let Ok(mut entries) = fs::read_dir(&self.temp_dir).await {
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                let path = entry.path();
+                if path.extension().is_some_and(|ext| ext == PART_EXTENSION) && !known.contains(&path) {
+                    remove_temp_file(&path).await;
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    async fn expire_sessions(&self) -> Result<usize, DomainError> {
+        let rows = sqlx::query("DELETE FROM auth.upload_sessions WHERE expires_at < NOW() RETURNING temp_path")
+            .fetch_all(&*self.db_pool)
+            .await
+            .map_err(|e| DomainError::database_error(format!("Failed to expire upload sessions: {}", e)))?;
+
+        for row in &rows {
+            remove_temp_file(Path::new(&row.get::<String, _>("temp_path"))).await;
+        }
+        Ok(rows.len())
+    }
+
+    fn temp_dir(&self) -> &Path {
+        &self.temp_dir
+    }
+
+    async fn list_temp_files(&self) -> Result<Vec<PathBuf>, DomainError> {
+        let rows = sqlx::query("SELECT temp_path FROM auth.upload_sessions")
+            .fetch_all(&*self.db_pool)
+            .await
+            .map_err(|e| DomainError::database_error(format!("Failed to list upload sessions: {}", e)))?;
+
+        Ok(rows.iter().map(|row| PathBuf::from(row.get::<String, _>("temp_path"))).collect())
+    }
+
+    fn max_upload_size(&self) -> u64 {
+        self.max_upload_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::services::file_service::FileService;
+    use crate::application::services::quota_service::QuotaService;
+    use crate::application::services::test_users::MemoryUsers;
+    use crate::domain::entities::user::UserRole;
+
+    #[test]
+    fn test_revalidated_offset() {
+        assert_eq!(revalidated_offset(100, 100), None);
+        // Bytes written after the last confirmed offset are discarded
+        assert_eq!(revalidated_offset(100, 150), Some(100));
+        // A shorter file moves the offset back
+        assert_eq!(revalidated_offset(100, 40), Some(40));
+    }
+
+    #[tokio::test]
+    async fn test_write_chunk_at_discards_unconfirmed_bytes() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("upload.part");
+
+        write_chunk_at(&path, 0, b"hello").await.unwrap();
+        write_chunk_at(&path, 5, b" wor").await.unwrap();
+        // Retrying from a confirmed offset overwrites the partial tail
+        write_chunk_at(&path, 5, b" world").await.unwrap();
+
+        assert_eq!(fs::read(&path).await.unwrap(), b"hello world");
+    }
+
+    #[tokio::test]
+    async fn test_write_stream_at_keeps_bytes_received_before_failure() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("upload.part");
+        write_chunk_at(&path, 0, b"hello").await.unwrap();
+
+        let interrupted: ChunkStream = Box::pin(futures::stream::iter(vec![
+            Ok(Bytes::from_static(b" wor")),
+            Err(std::io::Error::new(std::io::ErrorKind::ConnectionReset, "client went away")),
+        ]));
+        let (written, failure) = write_stream_at(&path, 5, 6, interrupted).await;
+        assert_eq!(written, 4);
+        assert!(failure.is_some());
+        assert_eq!(fs::read(&path).await.unwrap(), b"hello wor");
+
+        // A chunk past the declared size is cut at the last complete chunk
+        let oversized: ChunkStream = Box::pin(futures::stream::iter(vec![
+            Ok(Bytes::from_static(b"ld")),
+            Ok(Bytes::from_static(b"!!")),
+        ]));
+        let (written, failure) = write_stream_at(&path, 9, 2, oversized).await;
+        assert_eq!(written, 2);
+        assert_eq!(failure.unwrap().kind, ErrorKind::InvalidInput);
+        assert_eq!(fs::read(&path).await.unwrap(), b"hello world");
+    }
+
+    #[tokio::test]
+    async fn test_create_session_refuses_uploads_over_quota() {
+        let temp = tempfile::tempdir().unwrap();
+        let users = Arc::new(MemoryUsers::default());
+        let alice = users.add("alice", UserRole::User, 1024);
+        // The quota is checked before any database query
+        let pool = PgPool::connect_lazy("postgres://unused@localhost/unused").unwrap();
+        let service = UploadSessionService::new(
+            Arc::new(pool),
+            Arc::new(FileService::new_stub()),
+            temp.path().to_path_buf(),
+            24,
+            1024 * 1024,
+        ).with_quota_service(Arc::new(QuotaService::new(users)));
+
+        let err = service.create_session(&alice, CreateUploadSessionDto {
+            name: "big.bin".to_string(),
+            folder_id: None,
+            content_type: "application/octet-stream".to_string(),
+            total_size: 2048,
+        }).await.unwrap_err();
+        assert_eq!(err.kind, ErrorKind::QuotaExceeded);
+        assert_eq!(std::fs::read_dir(temp.path()).unwrap().count(), 0);
+    }
+}
@@ -0,0 +1,277 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::application::dtos::user_group_dto::{CreateUserGroupDto, UpdateUserGroupDto, UserGroupDto};
+use crate::application::ports::auth_ports::{Caller, UserStoragePort};
+use crate::application::ports::user_group_ports::UserGroupUseCase;
+use crate::common::errors::DomainError;
+use crate::domain::entities::user::Permission;
+use crate::domain::entities::user_group::UserGroup;
+use crate::domain::repositories::user_group_repository::UserGroupRepository;
+
+/// Servicio de grupos de usuarios.
+///
+/// Los administradores crean los grupos y deciden quién pertenece a ellos;
+/// los demás servicios los usan para compartir con todo un equipo de una vez
+/// (enlaces, calendarios y libretas de direcciones). La pertenencia se
+/// consulta en cada acceso, así que quien entra en un grupo ve enseguida lo
+/// compartido con él y quien sale deja de verlo.
+pub struct UserGroupService {
+    repository: Arc<dyn UserGroupRepository>,
+    user_storage: Arc<dyn UserStoragePort>,
+}
+
+impl UserGroupService {
+    pub fn new(repository: Arc<dyn UserGroupRepository>, user_storage: Arc<dyn UserStoragePort>) -> Self {
+        Self { repository, user_storage }
+    }
+
+    /// Comprueba que un grupo existe, para validar a quién se comparte
+    pub async fn ensure_group_exists(&self, group_id: &str) -> Result<(), DomainError> {
+        self.load(group_id).await.map(|_| ())
+    }
+
+    /// Si el usuario pertenece a alguno de los grupos
+    pub async fn is_member_of_any(&self, user_id: &str, group_ids: &[String]) -> Result<bool, DomainError> {
+        if group_ids.is_empty() {
+            return Ok(false);
+        }
+        let groups = self.repository.list_groups_for_user(user_id).await?;
+        Ok(groups.iter().any(|group| group_ids.contains(&group.id)))
+    }
+
+    /// IDs de los grupos a los que pertenece el usuario
+    pub async fn group_ids_of(&self, user_id: &str) -> Result<Vec<String>, DomainError> {
+        let groups = self.repository.list_groups_for_user(user_id).await?;
+        Ok(groups.into_iter().map(|group| group.id).collect())
+    }
+
+    /// Miembros de los grupos, sin repetir, para avisarles de lo compartido
+    pub async fn member_ids(&self, group_ids: &[String]) -> Result<Vec<String>, DomainError> {
+        let mut members = Vec::new();
+        for group_id in group_ids {
+            for member in self.repository.list_members(group_id).await? {
+                if !members.contains(&member) {
+                    members.push(member);
+                }
+            }
+        }
+        Ok(members)
+    }
+
+    async fn load(&self, group_id: &str) -> Result<UserGroup, DomainError> {
+        self.repository.get_group(group_id).await?
+            .ok_or_else(|| DomainError::not_found("UserGroup", group_id))
+    }
+
+    async fn to_dto(&self, group: UserGroup, show_members: bool) -> Result<UserGroupDto, DomainError> {
+        let members = self.repository.list_members(&group.id).await?;
+        Ok(UserGroupDto::new(group, &members, show_members))
+    }
+}
+
+#[async_trait]
+impl UserGroupUseCase for UserGroupService {
+    async fn list_groups(&self, caller: &Caller) -> Result<Vec<UserGroupDto>, DomainError> {
+        let show_members = caller.has_permission(Permission::ManageUsers);
+        let mut dtos = Vec::new();
+        for group in self.repository.list_groups().await? {
+            dtos.push(self.to_dto(group, show_members).await?);
+        }
+        Ok(dtos)
+    }
+
+    async fn my_groups(&self, caller: &Caller) -> Result<Vec<UserGroupDto>, DomainError> {
+        let mut dtos = Vec::new();
+        for group in self.repository.list_groups_for_user(&caller.user_id).await? {
+            dtos.push(self.to_dto(group, true).await?);
+        }
+        Ok(dtos)
+    }
+
+    async fn get_group(&self, caller: &Caller, group_id: &str) -> Result<UserGroupDto, DomainError> {
+        let group = self.load(group_id).await?;
+        let dto = self.to_dto(group, true).await?;
+        let is_member = dto.members.as_ref().is_some_and(|members| members.contains(&caller.user_id));
+        if !is_member {
+            caller.require(Permission::ManageUsers)?;
+        }
+        Ok(dto)
+    }
+
+    async fn create_group(&self, caller: &Caller, request: CreateUserGroupDto) -> Result<UserGroupDto, DomainError> {
+        caller.require(Permission::ManageUsers)?;
+        let group = UserGroup::new(&request.name, request.description)?;
+        // Se comprueban los miembros antes de crear nada
+        for user_id in &request.members {
+            self.user_storage.get_user_by_id(user_id).await?;
+        }
+
+        self.repository.create_group(&group).await?;
+        for user_id in &request.members {
+            self.repository.add_member(&group.id, user_id).await?;
+        }
+        tracing::info!("Grupo '{}' creado por {} con {} miembros", group.name, caller.user_id, request.members.len());
+        self.to_dto(group, true).await
+    }
+
+    async fn update_group(&self, caller: &Caller, group_id: &str, request: UpdateUserGroupDto) -> Result<UserGroupDto, DomainError> {
+        caller.require(Permission::ManageUsers)?;
+        let mut group = self.load(group_id).await?;
+        if let Some(name) = &request.name {
+            group.rename(name)?;
+        }
+        if request.description.is_some() {
+            group.set_description(request.description)?;
+        }
+        self.repository.update_group(&group).await?;
+        self.to_dto(group, true).await
+    }
+
+    async fn delete_group(&self, caller: &Caller, group_id: &str) -> Result<(), DomainError> {
+        caller.require(Permission::ManageUsers)?;
+        if !self.repository.delete_group(group_id).await? {
+            return Err(DomainError::not_found("UserGroup", group_id));
+        }
+        tracing::info!("Grupo {} eliminado por {}", group_id, caller.user_id);
+        Ok(())
+    }
+
+    async fn add_member(&self, caller: &Caller, group_id: &str, user_id: &str) -> Result<UserGroupDto, DomainError> {
+        caller.require(Permission::ManageUsers)?;
+        let group = self.load(group_id).await?;
+        self.user_storage.get_user_by_id(user_id).await?;
+        if self.repository.add_member(group_id, user_id).await? {
+            tracing::info!("Usuario {} añadido al grupo '{}'", user_id, group.name);
+        }
+        self.to_dto(group, true).await
+    }
+
+    async fn remove_member(&self, caller: &Caller, group_id: &str, user_id: &str) -> Result<UserGroupDto, DomainError> {
+        caller.require(Permission::ManageUsers)?;
+        let group = self.load(group_id).await?;
+        if !self.repository.remove_member(group_id, user_id).await? {
+            return Err(DomainError::not_found("UserGroupMember", user_id));
+        }
+        tracing::info!("Usuario {} quitado del grupo '{}'", user_id, group.name);
+        self.to_dto(group, true).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{BTreeSet, HashMap};
+    use std::sync::Mutex;
+    use crate::common::errors::ErrorKind;
+    use crate::application::services::test_users::MemoryUsers;
+    use crate::domain::entities::user::UserRole;
+    use crate::domain::repositories::user_group_repository::UserGroupRepositoryResult;
+
+    #[derive(Default)]
+    struct InMemoryUserGroupRepository {
+        groups: Mutex<HashMap<String, UserGroup>>,
+        members: Mutex<BTreeSet<(String, String)>>,
+    }
+
+    #[async_trait]
+    impl UserGroupRepository for InMemoryUserGroupRepository {
+        async fn create_group(&self, group: &UserGroup) -> UserGroupRepositoryResult<()> {
+            let mut groups = self.groups.lock().unwrap();
+            if groups.values().any(|existing| existing.name == group.name) {
+                return Err(DomainError::already_exists("UserGroup", group.name.clone()));
+            }
+            groups.insert(group.id.clone(), group.clone());
+            Ok(())
+        }
+        async fn update_group(&self, group: &UserGroup) -> UserGroupRepositoryResult<()> {
+            self.groups.lock().unwrap().insert(group.id.clone(), group.clone());
+            Ok(())
+        }
+        async fn get_group(&self, id: &str) -> UserGroupRepositoryResult<Option<UserGroup>> {
+            Ok(self.groups.lock().unwrap().get(id).cloned())
+        }
+        async fn list_groups(&self) -> UserGroupRepositoryResult<Vec<UserGroup>> {
+            Ok(self.groups.lock().unwrap().values().cloned().collect())
+        }
+        async fn delete_group(&self, id: &str) -> UserGroupRepositoryResult<bool> {
+            self.members.lock().unwrap().retain(|(group_id, _)| group_id != id);
+            Ok(self.groups.lock().unwrap().remove(id).is_some())
+        }
+        async fn add_member(&self, group_id: &str, user_id: &str) -> UserGroupRepositoryResult<bool> {
+            Ok(self.members.lock().unwrap().insert((group_id.to_string(), user_id.to_string())))
+        }
+        async fn remove_member(&self, group_id: &str, user_id: &str) -> UserGroupRepositoryResult<bool> {
+            Ok(self.members.lock().unwrap().remove(&(group_id.to_string(), user_id.to_string())))
+        }
+        async fn list_members(&self, group_id: &str) -> UserGroupRepositoryResult<Vec<String>> {
+            Ok(self.members.lock().unwrap().iter()
+                .filter(|(id, _)| id == group_id)
+                .map(|(_, user_id)| user_id.clone())
+                .collect())
+        }
+        async fn list_groups_for_user(&self, user_id: &str) -> UserGroupRepositoryResult<Vec<UserGroup>> {
+            let members = self.members.lock().unwrap();
+            Ok(self.groups.lock().unwrap().values()
+                .filter(|group| members.contains(&(group.id.clone(), user_id.to_string())))
+                .cloned()
+                .collect())
+        }
+    }
+
+    /// Servicio con tres usuarios; devuelve también sus IDs
+    fn service() -> (UserGroupService, [String; 3]) {
+        let users = MemoryUsers::with_usernames(&["ana", "luis", "marta"]);
+        let ids = ["ana", "luis", "marta"].map(|username| users.id_of(username));
+        let service = UserGroupService::new(Arc::new(InMemoryUserGroupRepository::default()), Arc::new(users));
+        (service, ids)
+    }
+
+    fn create(name: &str, members: &[&str]) -> CreateUserGroupDto {
+        CreateUserGroupDto {
+            name: name.to_string(),
+            description: None,
+            members: members.iter().map(|member| member.to_string()).collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_membership() {
+        let (service, [ana_id, luis_id, _]) = service();
+        let admin = Caller::new("root", UserRole::Admin);
+        let ana = Caller::new(ana_id.as_str(), UserRole::User);
+
+        let group = service.create_group(&admin, create("Diseño", &[ana_id.as_str()])).await.unwrap();
+        assert_eq!(group.members.as_deref(), Some(std::slice::from_ref(&ana_id)));
+        assert!(service.create_group(&admin, create("Ventas", &["nadie"])).await.is_err());
+        assert_eq!(service.create_group(&admin, create("Diseño", &[])).await.unwrap_err().kind, ErrorKind::AlreadyExists);
+
+        service.add_member(&admin, &group.id, &luis_id).await.unwrap();
+        assert!(service.is_member_of_any(&luis_id, std::slice::from_ref(&group.id)).await.unwrap());
+        service.remove_member(&admin, &group.id, &luis_id).await.unwrap();
+        assert!(!service.is_member_of_any(&luis_id, std::slice::from_ref(&group.id)).await.unwrap());
+
+        assert_eq!(service.my_groups(&ana).await.unwrap().len(), 1);
+        service.delete_group(&admin, &group.id).await.unwrap();
+        assert!(service.my_groups(&ana).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_only_admins_manage_groups() {
+        let (service, [ana_id, luis_id, _]) = service();
+        let admin = Caller::new("root", UserRole::Admin);
+        let ana = Caller::new(ana_id.as_str(), UserRole::User);
+        let luis = Caller::new(luis_id.as_str(), UserRole::User);
+
+        assert_eq!(service.create_group(&ana, create("Equipo", &[])).await.unwrap_err().kind, ErrorKind::AccessDenied);
+        let group = service.create_group(&admin, create("Equipo", &[ana_id.as_str()])).await.unwrap();
+        assert_eq!(service.add_member(&ana, &group.id, &luis_id).await.unwrap_err().kind, ErrorKind::AccessDenied);
+
+        // Todos ven los grupos para poder compartir con ellos, pero no sus miembros
+        let listed = service.list_groups(&luis).await.unwrap();
+        assert_eq!((listed[0].member_count, listed[0].members.is_none()), (1, true));
+        assert!(service.get_group(&ana, &group.id).await.is_ok());
+        assert_eq!(service.get_group(&luis, &group.id).await.unwrap_err().kind, ErrorKind::AccessDenied);
+    }
+}
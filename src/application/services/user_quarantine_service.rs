@@ -0,0 +1,172 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use tokio::time;
+
+use crate::application::dtos::execution_plan_dto::ExecutionPlanDto;
+use crate::application::dtos::user_dto::{QuarantinedUserDto, UserDto};
+use crate::application::ports::auth_ports::{SessionStoragePort, UserQuarantineStoragePort, UserStoragePort};
+use crate::application::ports::inbound::FolderUseCase;
+use crate::application::ports::user_quarantine_ports::UserQuarantineUseCase;
+use crate::application::services::auth_application_service::AuthApplicationService;
+use crate::application::services::execution_plan::ExecutionPlan;
+use crate::common::errors::DomainError;
+use crate::domain::entities::user_quarantine::UserQuarantine;
+
+/// Cada cuánto se buscan usuarios cuyo periodo de conservación ha terminado
+const PURGE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Servicio de borrado de usuarios en dos pasos.
+///
+/// Borrar a un usuario lo desactiva, cierra sus sesiones y lo pone en
+/// cuarentena: no puede entrar y sus enlaces compartidos dejan de funcionar,
+/// pero su carpeta personal, calendarios y contactos se conservan durante
+/// `retention_days`. Pasado ese tiempo, o cuando un administrador lo decide,
+/// se elimina definitivamente; los calendarios, contactos y demás filas se
+/// borran en cascada con el usuario.
+pub struct UserQuarantineService {
+    auth_service: Arc<AuthApplicationService>,
+    user_storage: Arc<dyn UserStoragePort>,
+    session_storage: Arc<dyn SessionStoragePort>,
+    quarantine_storage: Arc<dyn UserQuarantineStoragePort>,
+    folder_service: Arc<dyn FolderUseCase>,
+    retention_days: u64,
+}
+
+impl UserQuarantineService {
+    pub fn new(
+        auth_service: Arc<AuthApplicationService>,
+        user_storage: Arc<dyn UserStoragePort>,
+        session_storage: Arc<dyn SessionStoragePort>,
+        quarantine_storage: Arc<dyn UserQuarantineStoragePort>,
+        folder_service: Arc<dyn FolderUseCase>,
+        retention_days: u64,
+    ) -> Self {
+        Self {
+            auth_service,
+            user_storage,
+            session_storage,
+            quarantine_storage,
+            folder_service,
+            retention_days,
+        }
+    }
+
+    /// Lanza la tarea que purga cada hora los usuarios con la cuarentena vencida
+    pub fn start_purge_job(self: Arc<Self>) {
+        tracing::info!("Usuarios borrados conservados durante {} días antes de purgarlos", self.retention_days);
+        tokio::spawn(async move {
+            let mut interval = time::interval(PURGE_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.purge_expired().await {
+                    tracing::error!("Error al purgar los usuarios en cuarentena: {}", e);
+                }
+            }
+        });
+    }
+
+    async fn find_quarantine(&self, user_id: &str) -> Result<UserQuarantine, DomainError> {
+        self.quarantine_storage.get_quarantine(user_id).await?
+            .ok_or_else(|| DomainError::not_found("Quarantined user", user_id))
+    }
+
+    async fn to_dto(&self, quarantine: UserQuarantine) -> Result<QuarantinedUserDto, DomainError> {
+        let user = self.user_storage.get_user_by_id(&quarantine.user_id).await?;
+        Ok(QuarantinedUserDto {
+            user: UserDto::from(user),
+            quarantined_at: quarantine.quarantined_at,
+            purge_after: quarantine.purge_after,
+            quarantined_by: quarantine.quarantined_by,
+        })
+    }
+}
+
+#[async_trait]
+impl UserQuarantineUseCase for UserQuarantineService {
+    async fn quarantine_user(&self, admin_id: &str, admin_username: &str, user_id: &str) -> Result<QuarantinedUserDto, DomainError> {
+        if admin_id == user_id {
+            return Err(DomainError::validation_error("Administrators cannot delete their own account"));
+        }
+        // Borrar de nuevo a un usuario en cuarentena no alarga el plazo
+        if let Some(quarantine) = self.quarantine_storage.get_quarantine(user_id).await? {
+            return self.to_dto(quarantine).await;
+        }
+
+        let mut user = self.user_storage.get_user_by_id(user_id).await?;
+        user.deactivate();
+        self.user_storage.update_user(user).await?;
+        self.session_storage.revoke_all_user_sessions(user_id).await?;
+        self.auth_service.forget_basic_credentials();
+
+        let now = Utc::now();
+        let quarantine = self.quarantine_storage.save_quarantine(UserQuarantine {
+            user_id: user_id.to_string(),
+            quarantined_at: now,
+            purge_after: now + chrono::Duration::days(self.retention_days as i64),
+            quarantined_by: admin_username.to_string(),
+        }).await?;
+
+        tracing::info!("Usuario {} borrado por {}; sus datos se purgarán el {}", user_id, admin_username, quarantine.purge_after);
+        self.to_dto(quarantine).await
+    }
+
+    async fn list_quarantined(&self) -> Result<Vec<QuarantinedUserDto>, DomainError> {
+        let mut users = Vec::new();
+        for quarantine in self.quarantine_storage.list_quarantines().await? {
+            users.push(self.to_dto(quarantine).await?);
+        }
+        Ok(users)
+    }
+
+    async fn reactivate_user(&self, user_id: &str) -> Result<UserDto, DomainError> {
+        self.find_quarantine(user_id).await?;
+
+        let mut user = self.user_storage.get_user_by_id(user_id).await?;
+        user.activate();
+        let user = self.user_storage.update_user(user).await?;
+        self.quarantine_storage.delete_quarantine(user_id).await?;
+
+        tracing::info!("Usuario {} reactivado", user_id);
+        Ok(UserDto::from(user))
+    }
+
+    async fn purge_user(&self, user_id: &str, dry_run: bool) -> Result<ExecutionPlanDto, DomainError> {
+        self.find_quarantine(user_id).await?;
+        let user = self.user_storage.get_user_by_id(user_id).await?;
+        let mut plan = ExecutionPlan::new("user_purge", dry_run);
+
+        let home_folder_name = format!("Mi Carpeta - {}", user.username());
+        let used_bytes = Some(user.storage_used_bytes().max(0) as u64);
+        for folder in self.folder_service.list_folders(None).await? {
+            if folder.name == home_folder_name && plan.step("delete_folder", folder.name.as_str(), used_bytes) {
+                self.folder_service.delete_folder(&folder.id).await?;
+            }
+        }
+        // La cuarentena, las sesiones, los calendarios y los contactos se borran en cascada
+        if !plan.step("delete_user", user.username(), None) {
+            return Ok(plan.finish());
+        }
+        self.user_storage.delete_user(user_id).await?;
+
+        tracing::info!("Usuario {} ({}) purgado definitivamente", user_id, user.username());
+        Ok(plan.finish())
+    }
+
+    async fn purge_expired(&self) -> Result<usize, DomainError> {
+        let now = Utc::now();
+        let mut purged = 0;
+        for quarantine in self.quarantine_storage.list_quarantines().await? {
+            if !quarantine.is_expired(now) {
+                continue;
+            }
+            match self.purge_user(&quarantine.user_id, false).await {
+                Ok(_) => purged += 1,
+                Err(e) => tracing::error!("No se pudo purgar al usuario {}: {}", quarantine.user_id, e),
+            }
+        }
+        Ok(purged)
+    }
+}
@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::application::dtos::folder_dto::CreateFolderDto;
+use crate::application::dtos::user_dto::SkeletonApplyResultDto;
+use crate::application::ports::inbound::{FileUseCase, FolderUseCase};
+use crate::application::ports::skeleton_ports::{SkeletonEntry, SkeletonSourcePort};
+use crate::application::services::user_group_service::UserGroupService;
+use crate::common::errors::{DomainError, ErrorKind};
+
+/// Esqueleto de los usuarios cuyos grupos no tienen uno propio
+pub const DEFAULT_SKELETON: &str = "default";
+
+/// Servicio que copia el esqueleto de carpetas y ficheros iniciales en la
+/// carpeta personal de un usuario.
+///
+/// Cada grupo de usuarios puede tener su esqueleto, con el ID del grupo como
+/// nombre; un usuario recibe los de todos sus grupos y, si ninguno tiene, el
+/// esqueleto por defecto. La aplicación es idempotente: las carpetas
+/// existentes se reutilizan y los ficheros que ya existen nunca se
+/// sobrescriben, por lo que puede volver a aplicarse para completar
+/// esqueletos actualizados o los de un grupo al que el usuario acaba de entrar.
+pub struct UserSkeletonService {
+    source: Arc<dyn SkeletonSourcePort>,
+    folder_service: Arc<dyn FolderUseCase>,
+    file_service: Arc<dyn FileUseCase>,
+    user_groups: Option<Arc<UserGroupService>>,
+}
+
+impl UserSkeletonService {
+    pub fn new(
+        source: Arc<dyn SkeletonSourcePort>,
+        folder_service: Arc<dyn FolderUseCase>,
+        file_service: Arc<dyn FileUseCase>,
+    ) -> Self {
+        Self { source, folder_service, file_service, user_groups: None }
+    }
+
+    /// Consulta los grupos de cada usuario para elegir sus esqueletos
+    pub fn with_groups(mut self, user_groups: Arc<UserGroupService>) -> Self {
+        self.user_groups = Some(user_groups);
+        self
+    }
+
+    /// Esqueletos que corresponden al usuario por los grupos a los que pertenece
+    async fn skeletons_for(&self, user_id: &str) -> Result<Vec<(String, Vec<SkeletonEntry>)>, DomainError> {
+        let group_ids = match &self.user_groups {
+            Some(user_groups) => user_groups.group_ids_of(user_id).await?,
+            None => Vec::new(),
+        };
+
+        let mut skeletons = Vec::new();
+        for group_id in group_ids {
+            if let Some(entries) = self.source.load_skeleton(&group_id).await? {
+                skeletons.push((group_id, entries));
+            }
+        }
+        if skeletons.is_empty() {
+            skeletons.extend(self.default_skeleton().await?);
+        }
+        Ok(skeletons)
+    }
+
+    async fn default_skeleton(&self) -> Result<Option<(String, Vec<SkeletonEntry>)>, DomainError> {
+        let entries = self.source.load_skeleton(DEFAULT_SKELETON).await?;
+        Ok(entries.map(|entries| (DEFAULT_SKELETON.to_string(), entries)))
+    }
+
+    /// Aplica los esqueletos del usuario dentro de su carpeta personal
+    pub async fn apply_skeleton(&self, home_folder_id: &str, user_id: &str) -> Result<SkeletonApplyResultDto, DomainError> {
+        let skeletons = self.skeletons_for(user_id).await?;
+        self.apply_all(home_folder_id, skeletons).await
+    }
+
+    /// Aplica un esqueleto por su nombre, o el de por defecto si no existe,
+    /// como el de la cuenta de demostración
+    pub async fn apply_named_skeleton(&self, home_folder_id: &str, name: &str) -> Result<SkeletonApplyResultDto, DomainError> {
+        let skeleton = match self.source.load_skeleton(name).await? {
+            Some(entries) => Some((name.to_string(), entries)),
+            None => self.default_skeleton().await?,
+        };
+        self.apply_all(home_folder_id, skeleton.into_iter().collect()).await
+    }
+
+    async fn apply_all(
+        &self,
+        home_folder_id: &str,
+        skeletons: Vec<(String, Vec<SkeletonEntry>)>,
+    ) -> Result<SkeletonApplyResultDto, DomainError> {
+        let mut result = SkeletonApplyResultDto {
+            home_folder_id: home_folder_id.to_string(),
+            ..Default::default()
+        };
+
+        for (name, entries) in skeletons {
+            self.apply_entries(home_folder_id, entries, &mut result).await?;
+            result.skeletons.push(name);
+        }
+
+        tracing::info!(
+            "Esqueletos {:?} aplicados en la carpeta {}: {} carpetas, {} ficheros, {} omitidos",
+            result.skeletons, home_folder_id, result.folders_created, result.files_created, result.skipped
+        );
+
+        Ok(result)
+    }
+
+    /// Crea en la carpeta personal las entradas de un esqueleto que faltan
+    async fn apply_entries(
+        &self,
+        home_folder_id: &str,
+        entries: Vec<SkeletonEntry>,
+        result: &mut SkeletonApplyResultDto,
+    ) -> Result<(), DomainError> {
+        // Ruta relativa -> ID de carpeta ya resuelta
+        let mut folder_ids: HashMap<String, String> = HashMap::new();
+        folder_ids.insert(String::new(), home_folder_id.to_string());
+
+        for entry in entries {
+            let (parent_path, name) = match entry.relative_path.rsplit_once('/') {
+                Some((parent, name)) => (parent.to_string(), name.to_string()),
+                None => (String::new(), entry.relative_path.clone()),
+            };
+
+            let parent_id = match folder_ids.get(&parent_path) {
+                Some(id) => id.clone(),
+                None => {
+                    tracing::warn!("Carpeta padre no resuelta para la entrada del esqueleto: {}", entry.relative_path);
+                    result.skipped += 1;
+                    continue;
+                }
+            };
+
+            if entry.is_dir {
+                let existing = self.folder_service.list_folders(Some(&parent_id)).await?
+                    .into_iter()
+                    .find(|f| f.name == name);
+
+                let folder_id = match existing {
+                    Some(folder) => {
+                        result.skipped += 1;
+                        folder.id
+                    },
+                    None => {
+                        let folder = self.folder_service.create_folder(CreateFolderDto {
+                            name: name.clone(),
+                            parent_id: Some(parent_id.clone()),
+                        }).await?;
+                        result.folders_created += 1;
+                        folder.id
+                    }
+                };
+                folder_ids.insert(entry.relative_path.clone(), folder_id);
+            } else {
+                let exists = self.file_service.list_files(Some(&parent_id)).await?
+                    .iter()
+                    .any(|f| f.name == name);
+
+                if exists {
+                    result.skipped += 1;
+                    continue;
+                }
+
+                let content_type = mime_guess::from_path(&name).first_or_octet_stream().to_string();
+                match self.file_service.upload_file(
+                    name.clone(),
+                    Some(parent_id.clone()),
+                    content_type,
+                    entry.content.unwrap_or_default(),
+                ).await {
+                    Ok(_) => result.files_created += 1,
+                    // Otro proceso pudo crear el fichero entre la comprobación y la subida
+                    Err(e) if e.kind == ErrorKind::AlreadyExists => result.skipped += 1,
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,508 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration as StdDuration;
+
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::{Duration, Utc};
+use rand_core::{OsRng, RngCore};
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+use crate::application::dtos::pagination::{PaginatedResponseDto, PaginationRequestDto};
+use crate::application::dtos::webhook_dto::{
+    CreateWebhookDto, UpdateWebhookDto, WebhookCreatedDto, WebhookDeliveryDto, WebhookDto,
+};
+use crate::application::ports::event_bus_ports::{DomainEvent, DomainEventSubscriber};
+use crate::application::ports::webhook_ports::{WebhookSenderPort, WebhookUseCase};
+use crate::common::errors::DomainError;
+use crate::domain::entities::webhook::{Webhook, WebhookDelivery, WebhookEvent};
+use crate::domain::repositories::webhook_repository::WebhookRepository;
+use crate::domain::services::content_digest_service::hmac_sha256_hex;
+
+/// Intentos de un envío antes de darlo por fallido
+const MAX_DELIVERY_ATTEMPTS: u32 = 6;
+
+/// Espera antes del primer reintento; se dobla en cada uno
+const RETRY_DELAY: Duration = Duration::seconds(30);
+
+/// Cada cuánto busca el trabajador reintentos pendientes si nadie lo despierta
+const POLL_INTERVAL: StdDuration = StdDuration::from_secs(15);
+
+/// Envíos que el trabajador recoge de una vez
+const DELIVERY_BATCH: i64 = 50;
+
+/// Longitud mínima de un secreto elegido por el administrador
+const MIN_SECRET_LENGTH: usize = 16;
+
+/// Cabeceras de cada envío
+pub const EVENT_HEADER: &str = "X-OxiCloud-Event";
+pub const DELIVERY_HEADER: &str = "X-OxiCloud-Delivery";
+pub const SIGNATURE_HEADER: &str = "X-OxiCloud-Signature";
+
+/// Webhooks: avisa por HTTP a las direcciones registradas por los
+/// administradores de los archivos creados y borrados, los enlaces nuevos y
+/// las cuentas nuevas.
+///
+/// Se suscribe al bus de eventos de dominio y guarda un envío pendiente por
+/// cada webhook interesado; un trabajador los envía firmados con HMAC-SHA256
+/// y reintenta los fallidos cada vez más espaciados. Los envíos quedan en
+/// base de datos, así que los pendientes sobreviven a un reinicio.
+pub struct WebhookService {
+    repository: Arc<dyn WebhookRepository>,
+    sender: Arc<dyn WebhookSenderPort>,
+    /// Despierta al trabajador cuando hay envíos nuevos
+    wake: Notify,
+    worker_started: AtomicBool,
+}
+
+impl WebhookService {
+    pub fn new(repository: Arc<dyn WebhookRepository>, sender: Arc<dyn WebhookSenderPort>) -> Self {
+        Self {
+            repository,
+            sender,
+            wake: Notify::new(),
+            worker_started: AtomicBool::new(false),
+        }
+    }
+
+    /// Arranca el trabajador que hace los envíos pendientes
+    pub fn start_worker(self: Arc<Self>) {
+        if self.worker_started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        tokio::spawn(async move {
+            loop {
+                self.deliver_due().await;
+                tokio::select! {
+                    _ = self.wake.notified() => {}
+                    _ = tokio::time::sleep(POLL_INTERVAL) => {}
+                }
+            }
+        });
+    }
+
+    /// Hace los envíos cuyo intento ya toca
+    pub async fn deliver_due(&self) {
+        loop {
+            let due = match self.repository.due_deliveries(Utc::now(), DELIVERY_BATCH).await {
+                Ok(due) => due,
+                Err(e) => {
+                    tracing::error!("No se pudieron obtener los envíos de webhooks pendientes: {}", e);
+                    return;
+                }
+            };
+            let batch = due.len() as i64;
+            for delivery in due {
+                self.attempt(delivery).await;
+            }
+            if batch < DELIVERY_BATCH {
+                return;
+            }
+        }
+    }
+
+    /// Intenta un envío y guarda el resultado
+    async fn attempt(&self, mut delivery: WebhookDelivery) {
+        let webhook = match self.repository.get_webhook(&delivery.webhook_id).await {
+            Ok(Some(webhook)) => webhook,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::warn!("No se pudo obtener el webhook {}: {}", delivery.webhook_id, e);
+                return;
+            }
+        };
+
+        if !webhook.active {
+            // Sin reintentos: el webhook no debe recibir nada mientras esté desactivado
+            delivery.fail(None, "Webhook desactivado".to_string(), delivery.attempts + 1, RETRY_DELAY);
+        } else {
+            let headers = [
+                (EVENT_HEADER, delivery.event.as_str().to_string()),
+                (DELIVERY_HEADER, delivery.id.clone()),
+                (SIGNATURE_HEADER, sign(&webhook.secret, &delivery.payload)),
+            ];
+            match self.sender.send(&webhook.url, &headers, &delivery.payload).await {
+                Ok(status) if (200..300).contains(&status) => delivery.succeed(status),
+                Ok(status) => delivery.fail(Some(status), format!("HTTP {}", status), MAX_DELIVERY_ATTEMPTS, RETRY_DELAY),
+                Err(e) => delivery.fail(None, e.message.clone(), MAX_DELIVERY_ATTEMPTS, RETRY_DELAY),
+            }
+            if let Some(error) = &delivery.error {
+                tracing::warn!(
+                    "Envío {} de {} a {} fallido (intento {}): {}",
+                    delivery.id, delivery.event.as_str(), webhook.url, delivery.attempts, error
+                );
+            }
+        }
+
+        if let Err(e) = self.repository.update_delivery(&delivery).await {
+            tracing::error!("No se pudo guardar el resultado del envío {}: {}", delivery.id, e);
+        }
+    }
+
+    async fn find_webhook(&self, id: &str) -> Result<Webhook, DomainError> {
+        self.repository.get_webhook(id).await?
+            .ok_or_else(|| DomainError::not_found("Webhook", id))
+    }
+}
+
+/// Valor de la cabecera de firma: `sha256=` y el HMAC del cuerpo
+pub fn sign(secret: &str, payload: &str) -> String {
+    format!("sha256={}", hmac_sha256_hex(secret.as_bytes(), payload.as_bytes()))
+}
+
+fn random_secret() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn validate_url(url: &str) -> Result<String, DomainError> {
+    let url = url.trim();
+    let parsed = url::Url::parse(url)
+        .map_err(|_| DomainError::validation_error("Webhook URL is not valid"))?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        return Err(DomainError::validation_error("Webhook URL must be an http or https URL"));
+    }
+    Ok(url.to_string())
+}
+
+fn validate_events(mut events: Vec<WebhookEvent>) -> Result<Vec<WebhookEvent>, DomainError> {
+    events.sort_by_key(|event| event.as_str());
+    events.dedup();
+    if events.is_empty() {
+        return Err(DomainError::validation_error("A webhook needs at least one event"));
+    }
+    Ok(events)
+}
+
+/// Suceso de webhook y datos que lleva un evento de dominio; `None` si no
+/// interesa a los webhooks
+fn webhook_payload(event: &DomainEvent) -> Option<(WebhookEvent, serde_json::Value)> {
+    match event {
+        DomainEvent::FileUploaded { file_id, name, folder_id, path, size } => Some((
+            WebhookEvent::FileCreated,
+            serde_json::json!({ "file_id": file_id, "name": name, "folder_id": folder_id, "path": path, "size": size }),
+        )),
+        DomainEvent::FileDeleted { file_id, name, folder_id, path } => Some((
+            WebhookEvent::FileDeleted,
+            serde_json::json!({ "file_id": file_id, "name": name, "folder_id": folder_id, "path": path }),
+        )),
+        DomainEvent::ShareCreated { share_id, item_id, item_type, created_by } => Some((
+            WebhookEvent::ShareCreated,
+            serde_json::json!({ "share_id": share_id, "item_id": item_id, "item_type": item_type, "created_by": created_by }),
+        )),
+        DomainEvent::UserCreated { user_id, username, email, role } => Some((
+            WebhookEvent::UserCreated,
+            serde_json::json!({ "user_id": user_id, "username": username, "email": email, "role": role }),
+        )),
+        DomainEvent::FileUpdated { .. }
+        | DomainEvent::FolderRenamed { .. }
+        | DomainEvent::FolderDeleted { .. }
+        | DomainEvent::ItemRestored { .. }
+        | DomainEvent::ContactDeleted { .. }
+        | DomainEvent::CalendarEventUpdated { .. } => None,
+    }
+}
+
+#[async_trait]
+impl DomainEventSubscriber for WebhookService {
+    fn name(&self) -> &'static str {
+        "webhooks"
+    }
+
+    /// Guarda un envío pendiente por cada webhook suscrito al suceso; el
+    /// envío en sí lo hace el trabajador
+    async fn handle(&self, event: &DomainEvent) -> Result<(), DomainError> {
+        let Some((webhook_event, data)) = webhook_payload(event) else {
+            return Ok(());
+        };
+        let webhooks = self.repository.list_webhooks_for_event(webhook_event).await?;
+        if webhooks.is_empty() {
+            return Ok(());
+        }
+
+        let occurred_at = Utc::now();
+        for webhook in webhooks {
+            let id = Uuid::new_v4().to_string();
+            let payload = serde_json::json!({
+                "id": id,
+                "event": webhook_event.as_str(),
+                "occurred_at": occurred_at,
+                "data": data,
+            });
+            let delivery = WebhookDelivery::new(id, webhook.id, webhook_event, payload.to_string());
+            self.repository.create_delivery(&delivery).await?;
+        }
+        self.wake.notify_one();
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl WebhookUseCase for WebhookService {
+    async fn create_webhook(&self, dto: CreateWebhookDto) -> Result<WebhookCreatedDto, DomainError> {
+        let url = validate_url(&dto.url)?;
+        let events = validate_events(dto.events)?;
+        let secret = match dto.secret {
+            Some(secret) if secret.len() < MIN_SECRET_LENGTH => {
+                return Err(DomainError::validation_error(format!(
+                    "Webhook secret must be at least {} characters long", MIN_SECRET_LENGTH
+                )));
+            }
+            Some(secret) => secret,
+            None => random_secret(),
+        };
+        let description = dto.description.filter(|description| !description.trim().is_empty());
+
+        let webhook = Webhook::new(url, events, secret.clone(), description);
+        self.repository.create_webhook(&webhook).await?;
+        tracing::info!("Webhook {} registrado para {}", webhook.id, webhook.url);
+
+        Ok(WebhookCreatedDto { webhook: WebhookDto::from(webhook), secret })
+    }
+
+    async fn list_webhooks(&self) -> Result<Vec<WebhookDto>, DomainError> {
+        Ok(self.repository.list_webhooks().await?.into_iter().map(WebhookDto::from).collect())
+    }
+
+    async fn get_webhook(&self, id: &str) -> Result<WebhookDto, DomainError> {
+        Ok(WebhookDto::from(self.find_webhook(id).await?))
+    }
+
+    async fn update_webhook(&self, id: &str, dto: UpdateWebhookDto) -> Result<WebhookDto, DomainError> {
+        let mut webhook = self.find_webhook(id).await?;
+        if let Some(url) = dto.url {
+            webhook.url = validate_url(&url)?;
+        }
+        if let Some(events) = dto.events {
+            webhook.events = validate_events(events)?;
+        }
+        if let Some(description) = dto.description {
+            webhook.description = Some(description).filter(|description| !description.trim().is_empty());
+        }
+        if let Some(active) = dto.active {
+            webhook.active = active;
+        }
+        webhook.updated_at = Utc::now();
+
+        self.repository.update_webhook(&webhook).await?;
+        Ok(WebhookDto::from(webhook))
+    }
+
+    async fn delete_webhook(&self, id: &str) -> Result<(), DomainError> {
+        self.repository.delete_webhook(id).await?;
+        tracing::info!("Webhook {} borrado", id);
+        Ok(())
+    }
+
+    async fn list_deliveries(
+        &self,
+        webhook_id: &str,
+        pagination: PaginationRequestDto,
+    ) -> Result<PaginatedResponseDto<WebhookDeliveryDto>, DomainError> {
+        self.find_webhook(webhook_id).await?;
+        let pagination = pagination.validate_and_adjust();
+
+        let (deliveries, total) = self.repository
+            .list_deliveries(webhook_id, pagination.limit() as i64, pagination.offset() as i64)
+            .await?;
+
+        Ok(PaginatedResponseDto::new(
+            deliveries.into_iter().map(WebhookDeliveryDto::from).collect(),
+            pagination.page,
+            pagination.page_size,
+            total.max(0) as usize,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use chrono::DateTime;
+    use crate::domain::entities::webhook::DeliveryStatus;
+    use crate::domain::repositories::webhook_repository::WebhookRepositoryResult;
+
+    #[derive(Default)]
+    struct InMemoryWebhooks {
+        webhooks: Mutex<Vec<Webhook>>,
+        deliveries: Mutex<Vec<WebhookDelivery>>,
+    }
+
+    #[async_trait]
+    impl WebhookRepository for InMemoryWebhooks {
+        async fn create_webhook(&self, webhook: &Webhook) -> WebhookRepositoryResult<()> {
+            self.webhooks.lock().unwrap().push(webhook.clone());
+            Ok(())
+        }
+
+        async fn update_webhook(&self, webhook: &Webhook) -> WebhookRepositoryResult<()> {
+            let mut webhooks = self.webhooks.lock().unwrap();
+            let stored = webhooks.iter_mut().find(|stored| stored.id == webhook.id)
+                .ok_or_else(|| DomainError::not_found("Webhook", webhook.id.clone()))?;
+            *stored = webhook.clone();
+            Ok(())
+        }
+
+        async fn delete_webhook(&self, id: &str) -> WebhookRepositoryResult<()> {
+            self.webhooks.lock().unwrap().retain(|webhook| webhook.id != id);
+            self.deliveries.lock().unwrap().retain(|delivery| delivery.webhook_id != id);
+            Ok(())
+        }
+
+        async fn get_webhook(&self, id: &str) -> WebhookRepositoryResult<Option<Webhook>> {
+            Ok(self.webhooks.lock().unwrap().iter().find(|webhook| webhook.id == id).cloned())
+        }
+
+        async fn list_webhooks(&self) -> WebhookRepositoryResult<Vec<Webhook>> {
+            Ok(self.webhooks.lock().unwrap().clone())
+        }
+
+        async fn list_webhooks_for_event(&self, event: WebhookEvent) -> WebhookRepositoryResult<Vec<Webhook>> {
+            Ok(self.webhooks.lock().unwrap().iter().filter(|webhook| webhook.subscribes_to(event)).cloned().collect())
+        }
+
+        async fn create_delivery(&self, delivery: &WebhookDelivery) -> WebhookRepositoryResult<()> {
+            self.deliveries.lock().unwrap().push(delivery.clone());
+            Ok(())
+        }
+
+        async fn update_delivery(&self, delivery: &WebhookDelivery) -> WebhookRepositoryResult<()> {
+            let mut deliveries = self.deliveries.lock().unwrap();
+            if let Some(stored) = deliveries.iter_mut().find(|stored| stored.id == delivery.id) {
+                *stored = delivery.clone();
+            }
+            Ok(())
+        }
+
+        async fn due_deliveries(&self, now: DateTime<Utc>, limit: i64) -> WebhookRepositoryResult<Vec<WebhookDelivery>> {
+            Ok(self.deliveries.lock().unwrap().iter()
+                .filter(|delivery| delivery.status == DeliveryStatus::Pending && delivery.next_attempt_at.is_some_and(|at| at <= now))
+                .take(limit as usize)
+                .cloned()
+                .collect())
+        }
+
+        async fn list_deliveries(&self, webhook_id: &str, limit: i64, offset: i64) -> WebhookRepositoryResult<(Vec<WebhookDelivery>, i64)> {
+            let matching: Vec<WebhookDelivery> = self.deliveries.lock().unwrap().iter().rev()
+                .filter(|delivery| delivery.webhook_id == webhook_id)
+                .cloned()
+                .collect();
+            let total = matching.len() as i64;
+            Ok((matching.into_iter().skip(offset as usize).take(limit as usize).collect(), total))
+        }
+    }
+
+    /// Responde siempre con el mismo código y guarda lo que se envía
+    struct RecordingSender {
+        status: u16,
+        sent: Mutex<Vec<(String, Vec<(String, String)>, String)>>,
+    }
+
+    #[async_trait]
+    impl WebhookSenderPort for RecordingSender {
+        async fn send(&self, url: &str, headers: &[(&str, String)], body: &str) -> Result<u16, DomainError> {
+            let headers = headers.iter().map(|(name, value)| (name.to_string(), value.clone())).collect();
+            self.sent.lock().unwrap().push((url.to_string(), headers, body.to_string()));
+            Ok(self.status)
+        }
+    }
+
+    fn service(status: u16) -> (WebhookService, Arc<InMemoryWebhooks>, Arc<RecordingSender>) {
+        let repository = Arc::new(InMemoryWebhooks::default());
+        let sender = Arc::new(RecordingSender { status, sent: Mutex::new(Vec::new()) });
+        (WebhookService::new(repository.clone(), sender.clone()), repository, sender)
+    }
+
+    fn user_created() -> DomainEvent {
+        DomainEvent::UserCreated {
+            user_id: "u1".to_string(),
+            username: "ana".to_string(),
+            email: "ana@example.com".to_string(),
+            role: "user".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deliveries_are_signed_with_the_webhook_secret() {
+        let (service, repository, sender) = service(204);
+        let created = service.create_webhook(CreateWebhookDto {
+            url: "https://hooks.example.com/oxicloud".to_string(),
+            events: vec![WebhookEvent::UserCreated],
+            description: None,
+            secret: Some("un secreto bastante largo".to_string()),
+        }).await.unwrap();
+
+        // Solo el suceso al que se suscribió genera envíos
+        service.handle(&DomainEvent::ShareCreated {
+            share_id: "s1".to_string(),
+            item_id: "f1".to_string(),
+            item_type: "file".to_string(),
+            created_by: "u1".to_string(),
+        }).await.unwrap();
+        service.handle(&user_created()).await.unwrap();
+        service.deliver_due().await;
+
+        let sent = sender.sent.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        let (url, headers, body) = &sent[0];
+        assert_eq!(url, "https://hooks.example.com/oxicloud");
+        let header = |name: &str| headers.iter().find(|(header, _)| header == name).map(|(_, value)| value.clone());
+        assert_eq!(header(EVENT_HEADER).as_deref(), Some("user.created"));
+        assert_eq!(header(SIGNATURE_HEADER), Some(sign(&created.secret, body)));
+
+        let payload: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(payload["data"]["username"], "ana");
+        assert_eq!(header(DELIVERY_HEADER).as_deref(), payload["id"].as_str());
+
+        let delivery = &repository.deliveries.lock().unwrap()[0];
+        assert_eq!(delivery.status, DeliveryStatus::Succeeded);
+        assert_eq!(delivery.response_status, Some(204));
+    }
+
+    #[tokio::test]
+    async fn test_failed_deliveries_are_retried_later() {
+        let (service, repository, sender) = service(503);
+        let created = service.create_webhook(CreateWebhookDto {
+            url: "http://hooks.example.com/".to_string(),
+            events: vec![WebhookEvent::UserCreated, WebhookEvent::UserCreated],
+            description: Some("CRM".to_string()),
+            secret: None,
+        }).await.unwrap();
+        assert_eq!(created.webhook.events, vec![WebhookEvent::UserCreated]);
+        assert!(created.secret.len() >= MIN_SECRET_LENGTH);
+
+        service.handle(&user_created()).await.unwrap();
+        service.deliver_due().await;
+        // El reintento no toca todavía
+        service.deliver_due().await;
+        assert_eq!(sender.sent.lock().unwrap().len(), 1);
+
+        let page = service.list_deliveries(&created.webhook.id, PaginationRequestDto::default()).await.unwrap();
+        assert_eq!(page.pagination.total_items, 1);
+        let delivery = &page.items[0];
+        assert_eq!(delivery.status, DeliveryStatus::Pending);
+        assert_eq!(delivery.attempts, 1);
+        assert_eq!(delivery.response_status, Some(503));
+        assert!(delivery.next_attempt_at.is_some_and(|at| at > Utc::now()));
+        assert_eq!(repository.deliveries.lock().unwrap()[0].error.as_deref(), Some("HTTP 503"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_webhooks_are_rejected() {
+        let (service, _, _) = service(200);
+        let create = |url: &str, events: Vec<WebhookEvent>, secret: Option<&str>| CreateWebhookDto {
+            url: url.to_string(),
+            events,
+            description: None,
+            secret: secret.map(str::to_string),
+        };
+        assert!(service.create_webhook(create("ftp://example.com", vec![WebhookEvent::FileCreated], None)).await.is_err());
+        assert!(service.create_webhook(create("https://example.com", vec![], None)).await.is_err());
+        assert!(service.create_webhook(create("https://example.com", vec![WebhookEvent::FileCreated], Some("corto"))).await.is_err());
+        assert!(service.get_webhook("missing").await.is_err());
+    }
+}
@@ -0,0 +1,792 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use tokio::fs;
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::mpsc;
+use tokio::time;
+use uuid::Uuid;
+
+use crate::application::dtos::notification_dto::{JobFailure, JobKind, RetryActionDto};
+use crate::application::dtos::zip_export_dto::{CreateZipExportDto, ZipExportJobDto, ZipExportStatus};
+use crate::application::ports::auth_ports::UserStoragePort;
+use crate::application::ports::inbound::{FileUseCase, FolderUseCase};
+use crate::application::ports::notification_ports::NotificationUseCase;
+use crate::application::ports::share_ports::{ShareAccessUseCase, ShareRecipient, ShareTarget};
+use crate::application::ports::zip_export_ports::{ZipExportDownload, ZipExportUseCase};
+use crate::common::errors::{DomainError, ErrorKind};
+use crate::domain::entities::share::SharePermissions;
+use crate::domain::entities::user::UserRole;
+use crate::infrastructure::services::zip_stream_writer::{ZipCheckpoint, ZipStreamWriter};
+
+/// Extensión de los ZIP en construcción o terminados
+const ARCHIVE_EXTENSION: &str = "zip";
+
+/// Bytes del ZIP que se acumulan en memoria antes de escribirlos al disco
+const FLUSH_THRESHOLD: usize = 1024 * 1024;
+
+/// Bytes añadidos al ZIP entre dos puntos de control
+const CHECKPOINT_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Entradas añadidas al ZIP entre dos puntos de control
+const CHECKPOINT_ENTRIES: usize = 1_000;
+
+/// Elementos que puede tener una selección
+const MAX_SELECTION: usize = 10_000;
+
+/// Cada cuánto se borran los ZIP caducados
+const EXPIRY_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Audiencia de los tokens de descarga, para que no valgan como otros JWT
+const DOWNLOAD_AUDIENCE: &str = "zip-export";
+
+/// Columnas con las que se construye el DTO de un trabajo
+const JOB_COLUMNS: &str = "id, file_name, status, jsonb_array_length(entries) AS total_entries, next_entry,
+     archive_size, error, created_at, updated_at, expires_at";
+
+/// Entrada del ZIP decidida al recorrer la selección
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct PlannedEntry {
+    /// Ruta dentro del ZIP; la de los directorios termina en `/`
+    path: String,
+    /// Fichero cuyo contenido se copia; `None` en los directorios
+    file_id: Option<String>,
+    size: u64,
+    modified_at: u64,
+}
+
+/// Lo que necesita el trabajador para construir o continuar un ZIP
+struct StoredWork {
+    user_id: String,
+    status: ZipExportStatus,
+    file_ids: Vec<String>,
+    folder_ids: Vec<String>,
+    entries: Option<Vec<PlannedEntry>>,
+    next_entry: usize,
+    checkpoint: Option<ZipCheckpoint>,
+    temp_path: PathBuf,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DownloadClaims {
+    sub: String,
+    aud: String,
+    exp: i64,
+}
+
+/// Servicio de exportaciones ZIP en segundo plano.
+///
+/// Para selecciones demasiado grandes para generarse durante una petición,
+/// el ZIP se construye en un fichero temporal entrada a entrada. Cada cierto
+/// número de bytes o de entradas se sincroniza el fichero y se guarda en
+/// PostgreSQL un punto de control con el estado del escritor, de modo que
+/// tras un reinicio el trabajo continúa donde lo dejó en lugar de empezar de
+/// cero. Al terminar, el ZIP se descarga con un enlace firmado que caduca
+/// junto con el propio fichero.
+///
+/// El usuario tiene que poder leer todo lo que selecciona: se comprueba al
+/// crear el trabajo y otra vez al construir el ZIP, por si entretanto le
+/// retiraron un enlace compartido.
+pub struct ZipExportService {
+    db_pool: Arc<PgPool>,
+    folder_service: Arc<dyn FolderUseCase>,
+    file_service: Arc<dyn FileUseCase>,
+    users: Arc<dyn UserStoragePort>,
+    share_access: Arc<dyn ShareAccessUseCase>,
+    notifications: Option<Arc<dyn NotificationUseCase>>,
+    temp_dir: PathBuf,
+    archive_ttl: chrono::Duration,
+    signing_secret: String,
+    queue: mpsc::UnboundedSender<String>,
+    /// Cola de trabajos, hasta que la recoge `start_jobs`
+    receiver: Mutex<Option<mpsc::UnboundedReceiver<String>>>,
+}
+
+impl ZipExportService {
+    /// Crea el servicio guardando los ZIP en `temp_dir`; los enlaces se
+    /// firman con `signing_secret`
+    pub fn new(
+        db_pool: Arc<PgPool>,
+        folder_service: Arc<dyn FolderUseCase>,
+        file_service: Arc<dyn FileUseCase>,
+        users: Arc<dyn UserStoragePort>,
+        share_access: Arc<dyn ShareAccessUseCase>,
+        temp_dir: PathBuf,
+        archive_ttl_hours: u64,
+        signing_secret: String,
+    ) -> Self {
+        let (queue, receiver) = mpsc::unbounded_channel();
+        Self {
+            db_pool,
+            folder_service,
+            file_service,
+            users,
+            share_access,
+            notifications: None,
+            temp_dir,
+            archive_ttl: chrono::Duration::hours(archive_ttl_hours.max(1) as i64),
+            signing_secret,
+            queue,
+            receiver: Mutex::new(Some(receiver)),
+        }
+    }
+
+    /// Informa de los trabajos fallidos en el centro de notificaciones
+    pub fn with_notifications(mut self, notifications: Arc<dyn NotificationUseCase>) -> Self {
+        self.notifications = Some(notifications);
+        self
+    }
+
+    /// Vuelve a encolar los trabajos que un reinicio dejó a medias y arranca
+    /// el trabajador, que los construye de uno en uno y borra los caducados
+    pub fn start_jobs(self: Arc<Self>) {
+        let Some(mut receiver) = self.receiver.lock().ok().and_then(|mut receiver| receiver.take()) else {
+            return;
+        };
+        tokio::spawn(async move {
+            match self.recover_jobs().await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!("Reanudando {} exportaciones ZIP interrumpidas", count),
+                Err(e) => tracing::error!("Error recuperando las exportaciones ZIP: {}", e),
+            }
+
+            let mut expiry = time::interval(EXPIRY_INTERVAL);
+            loop {
+                tokio::select! {
+                    Some(job_id) = receiver.recv() => self.run_job(&job_id).await,
+                    _ = expiry.tick() => match self.expire_jobs().await {
+                        Ok(0) => {}
+                        Ok(count) => tracing::info!("Eliminadas {} exportaciones ZIP caducadas", count),
+                        Err(e) => tracing::error!("Error eliminando las exportaciones ZIP caducadas: {}", e),
+                    },
+                }
+            }
+        });
+    }
+
+    fn row_to_dto(&self, row: &PgRow) -> ZipExportJobDto {
+        let id: String = row.get("id");
+        let status = ZipExportStatus::parse(&row.get::<String, _>("status")).unwrap_or(ZipExportStatus::Failed);
+        let expires_at: DateTime<Utc> = row.get("expires_at");
+        let download_url = match status {
+            ZipExportStatus::Completed => sign_download_token(&self.signing_secret, &id, expires_at)
+                .ok()
+                .map(|token| format!("/api/exports/{}/download?token={}", id, token)),
+            _ => None,
+        };
+
+        ZipExportJobDto {
+            file_name: row.get("file_name"),
+            status,
+            total_entries: row.get::<Option<i32>, _>("total_entries").map(|total| total.max(0) as u64),
+            completed_entries: row.get::<i32, _>("next_entry").max(0) as u64,
+            archive_size: row.get::<i64, _>("archive_size").max(0) as u64,
+            error: row.get("error"),
+            download_url,
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            expires_at,
+            id,
+        }
+    }
+
+    async fn load_job(&self, user_id: &str, job_id: &str) -> Result<ZipExportJobDto, DomainError> {
+        let row = sqlx::query(&format!("SELECT {} FROM auth.zip_export_jobs WHERE id = $1 AND user_id = $2", JOB_COLUMNS))
+            .bind(job_id)
+            .bind(user_id)
+            .fetch_optional(&*self.db_pool)
+            .await
+            .map_err(|e| DomainError::database_error(format!("Failed to read ZIP export: {}", e)))?
+            .ok_or_else(|| DomainError::not_found("ZipExport", job_id))?;
+
+        Ok(self.row_to_dto(&row))
+    }
+
+    async fn load_work(&self, job_id: &str) -> Result<Option<StoredWork>, DomainError> {
+        let row = sqlx::query(
+            "SELECT user_id, status, file_ids, folder_ids, entries, next_entry, checkpoint, temp_path
+             FROM auth.zip_export_jobs WHERE id = $1"
+        )
+        .bind(job_id)
+        .fetch_optional(&*self.db_pool)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to read ZIP export: {}", e)))?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let corrupt = |e: serde_json::Error| DomainError::internal_error("ZipExport", format!("Corrupt ZIP export state: {}", e));
+        Ok(Some(StoredWork {
+            user_id: row.get("user_id"),
+            status: ZipExportStatus::parse(&row.get::<String, _>("status")).unwrap_or(ZipExportStatus::Failed),
+            file_ids: serde_json::from_value(row.get("file_ids")).map_err(corrupt)?,
+            folder_ids: serde_json::from_value(row.get("folder_ids")).map_err(corrupt)?,
+            entries: row.get::<Option<serde_json::Value>, _>("entries")
+                .map(serde_json::from_value)
+                .transpose()
+                .map_err(corrupt)?,
+            next_entry: row.get::<i32, _>("next_entry").max(0) as usize,
+            checkpoint: row.get::<Option<serde_json::Value>, _>("checkpoint")
+                .map(serde_json::from_value)
+                .transpose()
+                .map_err(corrupt)?,
+            temp_path: PathBuf::from(row.get::<String, _>("temp_path")),
+        }))
+    }
+
+    /// Comprueba que el usuario pueda leer cada elemento de la selección;
+    /// los administradores llegan a todos
+    async fn ensure_selection_access(&self, user_id: &str, file_ids: &[String], folder_ids: &[String]) -> Result<(), DomainError> {
+        let user = self.users.get_user_by_id(user_id).await?;
+        if user.role() == UserRole::Admin {
+            return Ok(());
+        }
+        let recipient = ShareRecipient { user_id: user.id(), username: user.username(), email: user.email() };
+        let targets = folder_ids.iter().map(|id| ShareTarget::Folder(id.clone()))
+            .chain(file_ids.iter().map(|id| ShareTarget::File(id.clone())));
+        for target in targets {
+            self.share_access.ensure_access(recipient, &target, SharePermissions::READ).await?;
+        }
+        Ok(())
+    }
+
+    /// Recorre la selección y decide las entradas del ZIP. Los elementos de
+    /// primer nivel con el mismo nombre se numeran para no pisarse
+    async fn plan(&self, file_ids: &[String], folder_ids: &[String]) -> Result<Vec<PlannedEntry>, DomainError> {
+        let mut entries = Vec::new();
+        let mut used_names = HashSet::new();
+
+        for folder_id in folder_ids {
+            let root = self.folder_service.get_folder(folder_id).await?;
+            let root_path = unique_name(&mut used_names, &root.name);
+            let mut pending = vec![(root_path, root)];
+            // Evita ciclos si el árbol de carpetas estuviera corrupto
+            let mut processed = HashSet::new();
+
+            while let Some((path, folder)) = pending.pop() {
+                if !processed.insert(folder.id.clone()) {
+                    continue;
+                }
+                entries.push(PlannedEntry {
+                    path: format!("{}/", path),
+                    file_id: None,
+                    size: 0,
+                    modified_at: folder.modified_at,
+                });
+                for file in self.file_service.list_files(Some(&folder.id)).await? {
+                    entries.push(PlannedEntry {
+                        path: format!("{}/{}", path, file.name),
+                        file_id: Some(file.id),
+                        size: file.size,
+                        modified_at: file.modified_at,
+                    });
+                }
+                for subfolder in self.folder_service.list_folders(Some(&folder.id)).await?.into_iter().rev() {
+                    pending.push((format!("{}/{}", path, subfolder.name), subfolder));
+                }
+            }
+        }
+
+        for file_id in file_ids {
+            let file = self.file_service.get_file(file_id).await?;
+            entries.push(PlannedEntry {
+                path: unique_name(&mut used_names, &file.name),
+                file_id: Some(file.id),
+                size: file.size,
+                modified_at: file.modified_at,
+            });
+        }
+
+        Ok(entries)
+    }
+
+    /// Construye un trabajo y anota el resultado; los fallos quedan en el
+    /// trabajo y en el centro de notificaciones
+    async fn run_job(&self, job_id: &str) {
+        match self.build_archive(job_id).await {
+            Ok(true) => tracing::info!("Exportación ZIP {} completada", job_id),
+            Ok(false) => {}
+            Err(e) => {
+                tracing::warn!("Exportación ZIP {} detenida: {}", job_id, e);
+                self.mark_failed(job_id, &e.to_string()).await;
+            }
+        }
+    }
+
+    /// Continúa el ZIP desde el último punto de control. Devuelve `false` si
+    /// no había nada que hacer o el trabajo se canceló mientras tanto
+    async fn build_archive(&self, job_id: &str) -> Result<bool, DomainError> {
+        let Some(work) = self.load_work(job_id).await? else {
+            return Ok(false);
+        };
+        if matches!(work.status, ZipExportStatus::Completed | ZipExportStatus::Failed) {
+            return Ok(false);
+        }
+        self.ensure_selection_access(&work.user_id, &work.file_ids, &work.folder_ids).await?;
+
+        let entries = match work.entries {
+            Some(entries) => entries,
+            None => {
+                let entries = self.plan(&work.file_ids, &work.folder_ids).await?;
+                let planned = sqlx::query(
+                    "UPDATE auth.zip_export_jobs
+                     SET entries = $2, next_entry = 0, checkpoint = NULL, status = 'running', updated_at = NOW()
+                     WHERE id = $1 AND status = 'pending'"
+                )
+                .bind(job_id)
+                .bind(serde_json::to_value(&entries).map_err(|e| storage_error(e.to_string()))?)
+                .execute(&*self.db_pool)
+                .await
+                .map_err(|e| DomainError::database_error(format!("Failed to save ZIP export plan: {}", e)))?;
+                if planned.rows_affected() == 0 {
+                    return Ok(false);
+                }
+                entries
+            }
+        };
+
+        // Lo escrito tras el último punto de control no está confirmado y se descarta
+        let mut zip = match work.checkpoint {
+            Some(checkpoint) => ZipStreamWriter::resume(checkpoint),
+            None => ZipStreamWriter::new(),
+        };
+        let mut next_entry = if zip.offset() == 0 { 0 } else { work.next_entry.min(entries.len()) };
+        fs::create_dir_all(&self.temp_dir).await.map_err(|e| storage_error(e.to_string()))?;
+        let mut file = open_at(&work.temp_path, zip.offset()).await.map_err(|e| storage_error(e.to_string()))?;
+
+        let mut checkpoint_offset = zip.offset();
+        let mut checkpoint_entry = next_entry;
+        while next_entry < entries.len() {
+            let entry = &entries[next_entry];
+            match &entry.file_id {
+                None => zip.add_directory(&entry.path, entry.modified_at).map_err(|e| storage_error(e.to_string()))?,
+                Some(file_id) => {
+                    // Lo borrado desde que se recorrió la selección se omite
+                    let content = match self.file_service.get_file_stream(file_id).await {
+                        Ok(content) => content,
+                        Err(e) if e.kind == ErrorKind::NotFound => {
+                            tracing::warn!("Omitiendo {} de la exportación ZIP {}: ya no existe", entry.path, job_id);
+                            next_entry += 1;
+                            continue;
+                        }
+                        Err(e) => return Err(e),
+                    };
+                    let mut content = Box::into_pin(content);
+                    zip.start_file(&entry.path, entry.modified_at, entry.size).map_err(|e| storage_error(e.to_string()))?;
+                    while let Some(chunk) = content.next().await {
+                        let chunk = chunk.map_err(|e| storage_error(format!("Failed to read {}: {}", entry.path, e)))?;
+                        zip.write(&chunk).map_err(|e| storage_error(e.to_string()))?;
+                        if zip.buffered_len() >= FLUSH_THRESHOLD {
+                            file.write_all(&zip.take_output()).await.map_err(|e| storage_error(e.to_string()))?;
+                        }
+                    }
+                    zip.finish_file().map_err(|e| storage_error(e.to_string()))?;
+                }
+            }
+            next_entry += 1;
+
+            if zip.offset() - checkpoint_offset >= CHECKPOINT_BYTES || next_entry - checkpoint_entry >= CHECKPOINT_ENTRIES {
+                file.write_all(&zip.take_output()).await.map_err(|e| storage_error(e.to_string()))?;
+                file.sync_all().await.map_err(|e| storage_error(e.to_string()))?;
+                let checkpoint = zip.checkpoint().map_err(|e| storage_error(e.to_string()))?;
+                if !self.save_checkpoint(job_id, next_entry, &checkpoint).await? {
+                    remove_archive(&work.temp_path).await;
+                    return Ok(false);
+                }
+                checkpoint_offset = checkpoint.offset;
+                checkpoint_entry = next_entry;
+            }
+        }
+
+        zip.finish().map_err(|e| storage_error(e.to_string()))?;
+        file.write_all(&zip.take_output()).await.map_err(|e| storage_error(e.to_string()))?;
+        file.sync_all().await.map_err(|e| storage_error(e.to_string()))?;
+
+        let completed = sqlx::query(
+            "UPDATE auth.zip_export_jobs
+             SET status = 'completed', next_entry = $2, checkpoint = NULL, archive_size = $3,
+                 completed_at = NOW(), updated_at = NOW(), expires_at = $4
+             WHERE id = $1 AND status = 'running'"
+        )
+        .bind(job_id)
+        .bind(next_entry as i32)
+        .bind(zip.offset() as i64)
+        .bind(Utc::now() + self.archive_ttl)
+        .execute(&*self.db_pool)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to complete ZIP export: {}", e)))?;
+
+        if completed.rows_affected() == 0 {
+            remove_archive(&work.temp_path).await;
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Guarda un punto de control y aplaza la caducidad. Devuelve `false` si
+    /// el trabajo ya no está en marcha porque se canceló
+    async fn save_checkpoint(&self, job_id: &str, next_entry: usize, checkpoint: &ZipCheckpoint) -> Result<bool, DomainError> {
+        let saved = sqlx::query(
+            "UPDATE auth.zip_export_jobs
+             SET next_entry = $2, checkpoint = $3, archive_size = $4, updated_at = NOW(), expires_at = $5
+             WHERE id = $1 AND status = 'running'"
+        )
+        .bind(job_id)
+        .bind(next_entry as i32)
+        .bind(serde_json::to_value(checkpoint).map_err(|e| storage_error(e.to_string()))?)
+        .bind(checkpoint.offset as i64)
+        .bind(Utc::now() + self.archive_ttl)
+        .execute(&*self.db_pool)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to save ZIP export checkpoint: {}", e)))?;
+
+        Ok(saved.rows_affected() > 0)
+    }
+
+    async fn mark_failed(&self, job_id: &str, error: &str) {
+        let failed = sqlx::query(
+            "UPDATE auth.zip_export_jobs SET status = 'failed', error = $2, updated_at = NOW()
+             WHERE id = $1 RETURNING user_id"
+        )
+        .bind(job_id)
+        .bind(error)
+        .fetch_optional(&*self.db_pool)
+        .await;
+
+        let user_id: String = match failed {
+            Ok(Some(row)) => row.get("user_id"),
+            Ok(None) => return,
+            Err(e) => {
+                tracing::error!("No se pudo marcar como fallida la exportación ZIP {}: {}", job_id, e);
+                return;
+            }
+        };
+        if let Some(notifications) = &self.notifications {
+            notifications.report_job_failure(Some(&user_id), JobFailure {
+                job_id: job_id.to_string(),
+                kind: JobKind::ZipExport,
+                error: error.to_string(),
+                retry: RetryActionDto {
+                    method: "POST".to_string(),
+                    href: format!("/api/exports/{}/resume", job_id),
+                    requires_upload: false,
+                },
+            }).await;
+        }
+    }
+
+    /// Encola los trabajos pendientes o en marcha y borra los ZIP sin trabajo
+    async fn recover_jobs(&self) -> Result<usize, DomainError> {
+        let rows = sqlx::query(
+            "SELECT id FROM auth.zip_export_jobs WHERE status IN ('pending', 'running') ORDER BY created_at"
+        )
+        .fetch_all(&*self.db_pool)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to list ZIP exports: {}", e)))?;
+
+        for row in &rows {
+            let _ = self.queue.send(row.get("id"));
+        }
+
+        // Ficheros que quedaron de un fallo antes de guardar su trabajo
+        let known: HashSet<PathBuf> = sqlx::query("SELECT temp_path FROM auth.zip_export_jobs")
+            .fetch_all(&*self.db_pool)
+            .await
+            .map_err(|e| DomainError::database_error(format!("Failed to list ZIP exports: {}", e)))?
+            .iter()
+            .map(|row| PathBuf::from(row.get::<String, _>("temp_path")))
+            .collect();
+        if let Ok(mut dir) = fs::read_dir(&self.temp_dir).await {
+            while let Ok(Some(entry)) = dir.next_entry().await {
+                let path = entry.path();
+                if path.extension().is_some_and(|ext| ext == ARCHIVE_EXTENSION) && !known.contains(&path) {
+                    remove_archive(&path).await;
+                }
+            }
+        }
+
+        Ok(rows.len())
+    }
+
+    /// Borra los trabajos caducados que no están en marcha, con sus ZIP
+    async fn expire_jobs(&self) -> Result<usize, DomainError> {
+        let rows = sqlx::query(
+            "DELETE FROM auth.zip_export_jobs WHERE expires_at < NOW() AND status <> 'running' RETURNING temp_path"
+        )
+        .fetch_all(&*self.db_pool)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to expire ZIP exports: {}", e)))?;
+
+        for row in &rows {
+            remove_archive(Path::new(&row.get::<String, _>("temp_path"))).await;
+        }
+        Ok(rows.len())
+    }
+}
+
+fn storage_error(message: impl Into<String>) -> DomainError {
+    DomainError::internal_error("ZipExport", message)
+}
+
+/// Abre el ZIP para seguir escribiendo en `offset`, descartando lo posterior
+async fn open_at(path: &Path, offset: u64) -> std::io::Result<fs::File> {
+    let mut file = fs::OpenOptions::new().write(true).create(true).truncate(false).open(path).await?;
+    file.set_len(offset).await?;
+    file.seek(std::io::SeekFrom::Start(offset)).await?;
+    Ok(file)
+}
+
+/// Borra un ZIP ignorando que ya no exista
+async fn remove_archive(path: &Path) {
+    if let Err(e) = fs::remove_file(path).await {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            tracing::warn!("Could not remove ZIP export {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// Nombre de primer nivel que no coincide con otro de la selección; los
+/// repetidos se numeran antes de la extensión: `foto (2).jpg`
+fn unique_name(used: &mut HashSet<String>, name: &str) -> String {
+    let (stem, extension) = match name.rfind('.') {
+        Some(dot) if dot > 0 => name.split_at(dot),
+        _ => (name, ""),
+    };
+    let mut candidate = name.to_string();
+    let mut counter = 2;
+    while !used.insert(candidate.clone()) {
+        candidate = format!("{} ({}){}", stem, counter, extension);
+        counter += 1;
+    }
+    candidate
+}
+
+/// Firma el enlace de descarga de un trabajo; caduca con el ZIP
+fn sign_download_token(secret: &str, job_id: &str, expires_at: DateTime<Utc>) -> Result<String, DomainError> {
+    let claims = DownloadClaims {
+        sub: job_id.to_string(),
+        aud: DOWNLOAD_AUDIENCE.to_string(),
+        exp: expires_at.timestamp(),
+    };
+    jsonwebtoken::encode(&Header::new(Algorithm::HS256), &claims, &EncodingKey::from_secret(secret.as_bytes()))
+        .map_err(|e| DomainError::internal_error("ZipExport", format!("Failed to sign download link: {}", e)))
+}
+
+/// Si `token` es un enlace de descarga vigente de ese trabajo
+fn verify_download_token(secret: &str, job_id: &str, token: &str) -> bool {
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.set_audience(&[DOWNLOAD_AUDIENCE]);
+    validation.leeway = 0;
+    jsonwebtoken::decode::<DownloadClaims>(token, &DecodingKey::from_secret(secret.as_bytes()), &validation)
+        .is_ok_and(|data| data.claims.sub == job_id)
+}
+
+#[async_trait]
+impl ZipExportUseCase for ZipExportService {
+    async fn create_job(&self, user_id: &str, dto: CreateZipExportDto) -> Result<ZipExportJobDto, DomainError> {
+        if dto.file_ids.is_empty() && dto.folder_ids.is_empty() {
+            return Err(DomainError::validation_error("Select at least one file or folder to export"));
+        }
+        if dto.file_ids.len() + dto.folder_ids.len() > MAX_SELECTION {
+            return Err(DomainError::validation_error(format!("A ZIP export can include at most {} items", MAX_SELECTION)));
+        }
+        let name = dto.name.as_deref().map(str::trim).filter(|name| !name.is_empty()).unwrap_or("export");
+        if name.contains('/') || name.contains('\\') {
+            return Err(DomainError::validation_error("A valid archive name is required"));
+        }
+
+        // La selección se comprueba ahora; su contenido se recorre en segundo plano
+        self.ensure_selection_access(user_id, &dto.file_ids, &dto.folder_ids).await?;
+        for folder_id in &dto.folder_ids {
+            self.folder_service.get_folder(folder_id).await?;
+        }
+        for file_id in &dto.file_ids {
+            self.file_service.get_file(file_id).await?;
+        }
+
+        let id = Uuid::new_v4().to_string();
+        let temp_path = self.temp_dir.join(format!("{}.{}", id, ARCHIVE_EXTENSION));
+        let row = sqlx::query(&format!(
+            "INSERT INTO auth.zip_export_jobs (id, user_id, file_name, file_ids, folder_ids, temp_path, expires_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             RETURNING {}",
+            JOB_COLUMNS
+        ))
+        .bind(&id)
+        .bind(user_id)
+        .bind(format!("{}.zip", name))
+        .bind(serde_json::json!(dto.file_ids))
+        .bind(serde_json::json!(dto.folder_ids))
+        .bind(temp_path.to_string_lossy().to_string())
+        .bind(Utc::now() + self.archive_ttl)
+        .fetch_one(&*self.db_pool)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to create ZIP export: {}", e)))?;
+
+        let _ = self.queue.send(id.clone());
+        tracing::info!(
+            "Exportación ZIP {} encolada: {} ficheros y {} carpetas",
+            id, dto.file_ids.len(), dto.folder_ids.len()
+        );
+        Ok(self.row_to_dto(&row))
+    }
+
+    async fn get_job(&self, user_id: &str, job_id: &str) -> Result<ZipExportJobDto, DomainError> {
+        self.load_job(user_id, job_id).await
+    }
+
+    async fn list_jobs(&self, user_id: &str) -> Result<Vec<ZipExportJobDto>, DomainError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM auth.zip_export_jobs WHERE user_id = $1 ORDER BY created_at DESC",
+            JOB_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(&*self.db_pool)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to list ZIP exports: {}", e)))?;
+
+        Ok(rows.iter().map(|row| self.row_to_dto(row)).collect())
+    }
+
+    async fn resume_job(&self, user_id: &str, job_id: &str) -> Result<ZipExportJobDto, DomainError> {
+        // Sin plan vuelve a recorrer la selección; con él sigue en 'running'
+        let resumed = sqlx::query(
+            "UPDATE auth.zip_export_jobs
+             SET status = CASE WHEN entries IS NULL THEN 'pending' ELSE 'running' END,
+                 error = NULL, updated_at = NOW(), expires_at = $3
+             WHERE id = $1 AND user_id = $2 AND status = 'failed'"
+        )
+        .bind(job_id)
+        .bind(user_id)
+        .bind(Utc::now() + self.archive_ttl)
+        .execute(&*self.db_pool)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to resume ZIP export: {}", e)))?;
+
+        if resumed.rows_affected() == 0 {
+            let job = self.load_job(user_id, job_id).await?;
+            return Err(DomainError::validation_error(format!(
+                "Only failed exports can be resumed; this one is {}",
+                job.status.as_str()
+            )));
+        }
+
+        let _ = self.queue.send(job_id.to_string());
+        self.load_job(user_id, job_id).await
+    }
+
+    async fn cancel_job(&self, user_id: &str, job_id: &str) -> Result<(), DomainError> {
+        let row = sqlx::query("DELETE FROM auth.zip_export_jobs WHERE id = $1 AND user_id = $2 RETURNING temp_path")
+            .bind(job_id)
+            .bind(user_id)
+            .fetch_optional(&*self.db_pool)
+            .await
+            .map_err(|e| DomainError::database_error(format!("Failed to cancel ZIP export: {}", e)))?
+            .ok_or_else(|| DomainError::not_found("ZipExport", job_id))?;
+
+        // Si el trabajador lo está construyendo, se detiene en el siguiente punto de control
+        remove_archive(Path::new(&row.get::<String, _>("temp_path"))).await;
+        Ok(())
+    }
+
+    async fn open_download(&self, job_id: &str, token: &str) -> Result<ZipExportDownload, DomainError> {
+        let invalid = || DomainError::access_denied("ZipExport", "Invalid or expired download link");
+        if !verify_download_token(&self.signing_secret, job_id, token) {
+            return Err(invalid());
+        }
+
+        let row = sqlx::query(
+            "SELECT file_name, temp_path FROM auth.zip_export_jobs
+             WHERE id = $1 AND status = 'completed' AND expires_at > NOW()"
+        )
+        .bind(job_id)
+        .fetch_optional(&*self.db_pool)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to read ZIP export: {}", e)))?
+        .ok_or_else(invalid)?;
+
+        let file = fs::File::open(row.get::<String, _>("temp_path")).await.map_err(|_| invalid())?;
+        let size = file.metadata().await.map_err(|e| storage_error(e.to_string()))?.len();
+        Ok(ZipExportDownload {
+            file_name: row.get("file_name"),
+            size,
+            content: Box::pin(tokio_util::io::ReaderStream::new(file)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::services::file_service::FileService;
+    use crate::application::services::folder_service::FolderService;
+    use crate::application::services::test_access::HomeOnlyAccess;
+    use crate::application::services::test_users::MemoryUsers;
+
+    #[test]
+    fn test_unique_name() {
+        let mut used = HashSet::new();
+        assert_eq!(unique_name(&mut used, "foto.jpg"), "foto.jpg");
+        assert_eq!(unique_name(&mut used, "foto.jpg"), "foto (2).jpg");
+        assert_eq!(unique_name(&mut used, "foto.jpg"), "foto (3).jpg");
+        assert_eq!(unique_name(&mut used, "Informes"), "Informes");
+        assert_eq!(unique_name(&mut used, "Informes"), "Informes (2)");
+        assert_eq!(unique_name(&mut used, ".bashrc"), ".bashrc");
+        assert_eq!(unique_name(&mut used, ".bashrc"), ".bashrc (2)");
+    }
+
+    #[test]
+    fn test_download_token() {
+        let expires_at = Utc::now() + chrono::Duration::hours(1);
+        let token = sign_download_token("secreto", "job-1", expires_at).unwrap();
+        assert!(verify_download_token("secreto", "job-1", &token));
+
+        // Otro trabajo, otra clave o un enlace caducado no valen
+        assert!(!verify_download_token("secreto", "job-2", &token));
+        assert!(!verify_download_token("otro", "job-1", &token));
+        let expired = sign_download_token("secreto", "job-1", Utc::now() - chrono::Duration::minutes(1)).unwrap();
+        assert!(!verify_download_token("secreto", "job-1", &expired));
+    }
+
+    #[tokio::test]
+    async fn test_selection_of_another_user_is_refused() {
+        let users = Arc::new(MemoryUsers::with_usernames(&["alice", "bob"]));
+        let alice = users.id_of("alice");
+        let admin = users.add("root", UserRole::Admin, 0);
+        // Las comprobaciones van antes de cualquier consulta a la base de datos
+        let pool = PgPool::connect_lazy("postgres://unused@localhost/unused").unwrap();
+        let service = ZipExportService::new(
+            Arc::new(pool),
+            Arc::new(FolderService::new_stub()),
+            Arc::new(FileService::new_stub()),
+            users,
+            Arc::new(HomeOnlyAccess::default()),
+            std::env::temp_dir(),
+            1,
+            "secreto".to_string(),
+        );
+        let selection = |file_ids: &[&str], folder_ids: &[&str]| CreateZipExportDto {
+            file_ids: file_ids.iter().map(|id| id.to_string()).collect(),
+            folder_ids: folder_ids.iter().map(|id| id.to_string()).collect(),
+            name: None,
+        };
+
+        let denied = service.create_job(&alice, selection(&["/Mi Carpeta - alice/a.txt", "/Mi Carpeta - bob/b.txt"], &[])).await;
+        assert_eq!(denied.unwrap_err().kind, ErrorKind::AccessDenied);
+        let denied = service.create_job(&alice, selection(&[], &["/Mi Carpeta - bob"])).await;
+        assert_eq!(denied.unwrap_err().kind, ErrorKind::AccessDenied);
+
+        let files = vec!["/Mi Carpeta - bob/b.txt".to_string()];
+        service.ensure_selection_access(&admin, &files, &[]).await.unwrap();
+        service.ensure_selection_access(&alice, &[], &["/Mi Carpeta - alice".to_string()]).await.unwrap();
+    }
+}
@@ -5,14 +5,25 @@ use sqlx::PgPool;
 use crate::domain::services::auth_service::AuthService;
 use crate::application::services::auth_application_service::AuthApplicationService;
 use crate::application::services::folder_service::FolderService;
-use crate::infrastructure::repositories::{UserPgRepository, SessionPgRepository};
+use crate::application::services::user_skeleton_service::UserSkeletonService;
+use crate::application::services::app_password_service::AppPasswordService;
+use crate::application::services::oidc_service::OidcLoginService;
+use crate::application::services::directory_auth_service::DirectoryAuthService;
+use crate::application::services::audit_log_service::AuditLogService;
+use crate::application::ports::event_bus_ports::EventBusPort;
+use crate::infrastructure::repositories::{UserPgRepository, SessionPgRepository, AppPasswordPgRepository, OidcIdentityPgRepository};
+use crate::infrastructure::repositories::pg::AuditLogPgRepository;
+use crate::infrastructure::services::oidc_client::OidcHttpClient;
+use crate::infrastructure::services::ldap_client::LdapDirectoryClient;
 use crate::common::config::AppConfig;
 use crate::common::di::AuthServices;
 
 pub async fn create_auth_services(
     config: &AppConfig, 
     pool: Arc<PgPool>,
-    folder_service: Option<Arc<FolderService>>
+    folder_service: Option<Arc<FolderService>>,
+    skeleton_service: Option<Arc<UserSkeletonService>>,
+    event_bus: Option<Arc<dyn EventBusPort>>,
 ) -> Result<AuthServices> {
     // Crear servicio de dominio de autenticación
     let auth_service = Arc::new(AuthService::new(
@@ -25,23 +36,76 @@ pub async fn create_auth_services(
     let user_repository = Arc::new(UserPgRepository::new(pool.clone()));
     let session_repository = Arc::new(SessionPgRepository::new(pool.clone()));
     
+    let app_password_service = Arc::new(AppPasswordService::new(Arc::new(AppPasswordPgRepository::new(pool.clone()))));
+    
+    // Registro de auditoría de solo añadir
+    let audit_log_service = Arc::new(AuditLogService::new(Arc::new(AuditLogPgRepository::new(pool.clone()))));
+    
     // Crear servicio de aplicación de autenticación
     let mut auth_app_service = AuthApplicationService::new(
-        user_repository,
+        user_repository.clone(),
         session_repository,
         auth_service.clone(),
-    );
+    )
+    .with_app_password_service(app_password_service, config.auth.dav_allow_account_password)
+    .with_audit_log(audit_log_service.clone());
     
     // Configurar servicio de carpetas si está disponible
     if let Some(folder_svc) = folder_service {
         auth_app_service = auth_app_service.with_folder_service(folder_svc);
     }
     
+    // Configurar esqueleto de carpetas para usuarios nuevos si está disponible
+    if let Some(skeleton_svc) = skeleton_service {
+        auth_app_service = auth_app_service.with_skeleton_service(skeleton_svc);
+    }
+    
+    // Publicar las cuentas nuevas en el bus de eventos de dominio
+    if let Some(bus) = event_bus {
+        auth_app_service = auth_app_service.with_event_bus(bus);
+    }
+    
+    // Configurar autenticación contra el directorio LDAP si está activada
+    if config.ldap.enabled {
+        let directory = LdapDirectoryClient::new(config.ldap.clone())?;
+        tracing::info!("Autenticación LDAP activada con {} (base {})", config.ldap.url, config.ldap.base_dn);
+        auth_app_service = auth_app_service.with_directory(Arc::new(DirectoryAuthService::new(
+            Arc::new(directory),
+            config.ldap.admin_group.clone(),
+            config.ldap.required_group.clone(),
+            config.ldap.allow_local_users,
+        )));
+    }
+    
     // Empaquetar servicio en Arc
     let auth_application_service = Arc::new(auth_app_service);
     
+    // Configurar inicio de sesión con el proveedor OpenID Connect si está activado
+    let oidc_service = if config.oidc.enabled {
+        let redirect_uri = config.oidc.redirect_uri.clone().unwrap_or_else(|| {
+            let base_url = config.public_share.base_url.clone()
+                .unwrap_or_else(|| format!("http://{}:{}", config.server_host, config.server_port));
+            format!("{}/api/auth/oidc/callback", base_url.trim_end_matches('/'))
+        });
+        let provider = OidcHttpClient::new(config.oidc.clone(), redirect_uri.clone())?;
+        
+        tracing::info!("Inicio de sesión OIDC con {} activado (retorno: {})", config.oidc.issuer_url, redirect_uri);
+        Some(Arc::new(OidcLoginService::new(
+            Arc::new(provider),
+            Arc::new(OidcIdentityPgRepository::new(pool.clone())),
+            user_repository,
+            auth_application_service.clone(),
+            config.oidc.auto_provision,
+            config.oidc.provider_name.clone(),
+        ).with_secure_cookie(redirect_uri.starts_with("https://"))))
+    } else {
+        None
+    };
+    
     Ok(AuthServices {
         auth_service,
         auth_application_service,
+        oidc_service,
+        audit_log_service,
     })
 }
\ No newline at end of file
@@ -0,0 +1,217 @@
+use std::fs::File;
+use std::io::{self, Cursor, Seek, SeekFrom, Write};
+
+use axum::body::{Body, Bytes};
+use futures::StreamExt;
+use tokio_util::io::ReaderStream;
+
+use crate::common::errors::AppError;
+
+/// Límites de memoria para componer cuerpos de petición y de respuesta
+#[derive(Debug, Clone, Copy)]
+pub struct MemoryLimits {
+    /// Bytes que una respuesta puede ocupar en memoria antes de volcarse a disco
+    pub response_memory: usize,
+    /// Tamaño máximo de un cuerpo XML de petición (PROPFIND, PROPPATCH, LOCK...)
+    pub xml_body: usize,
+    /// Tamaño máximo de un archivo subido en una sola petición
+    pub upload_body: usize,
+}
+
+enum Storage {
+    Memory(Cursor<Vec<u8>>),
+    Disk(File),
+}
+
+/// Buffer que se mantiene en memoria hasta `memory_limit` bytes y a partir de
+/// ahí continúa en un archivo temporal anónimo, que desaparece al soltarlo.
+///
+/// Implementa `Write` y `Seek`, así que sirve tanto para escritores XML como
+/// para `ZipWriter`. Opcionalmente rechaza escrituras más allá de `max_size`.
+pub struct BoundedBuffer {
+    storage: Storage,
+    memory_limit: usize,
+    max_size: Option<u64>,
+}
+
+impl BoundedBuffer {
+    /// Crea un buffer que vuelca a disco al superar `memory_limit` bytes
+    pub fn new(memory_limit: usize) -> Self {
+        Self {
+            storage: Storage::Memory(Cursor::new(Vec::new())),
+            memory_limit,
+            max_size: None,
+        }
+    }
+
+    /// Limita el tamaño total del contenido, incluido lo volcado a disco
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    /// Tamaño actual del contenido en bytes
+    pub fn len(&self) -> io::Result<u64> {
+        match &self.storage {
+            Storage::Memory(cursor) => Ok(cursor.get_ref().len() as u64),
+            Storage::Disk(file) => file.metadata().map(|metadata| metadata.len()),
+        }
+    }
+
+    /// Indica si el buffer no contiene datos
+    pub fn is_empty(&self) -> io::Result<bool> {
+        self.len().map(|len| len == 0)
+    }
+
+    /// Indica si el contenido ya se ha volcado a disco
+    pub fn is_spilled(&self) -> bool {
+        matches!(self.storage, Storage::Disk(_))
+    }
+
+    fn position(&mut self) -> io::Result<u64> {
+        match &mut self.storage {
+            Storage::Memory(cursor) => Ok(cursor.position()),
+            Storage::Disk(file) => file.stream_position(),
+        }
+    }
+
+    /// Pasa el contenido en memoria a un archivo temporal conservando la posición
+    fn spill(&mut self) -> io::Result<()> {
+        if let Storage::Memory(cursor) = &self.storage {
+            let position = cursor.position();
+            let mut file = tempfile::tempfile()?;
+            file.write_all(cursor.get_ref())?;
+            file.seek(SeekFrom::Start(position))?;
+            self.storage = Storage::Disk(file);
+        }
+        Ok(())
+    }
+
+    /// Convierte el buffer en el cuerpo de una respuesta; si se volcó a disco
+    /// el archivo temporal se sirve en streaming
+    pub fn into_body(self) -> io::Result<Body> {
+        match self.storage {
+            Storage::Memory(cursor) => Ok(Body::from(cursor.into_inner())),
+            Storage::Disk(mut file) => {
+                file.seek(SeekFrom::Start(0))?;
+                let stream = ReaderStream::new(tokio::fs::File::from_std(file));
+                Ok(Body::from_stream(stream))
+            }
+        }
+    }
+}
+
+impl Write for BoundedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let end = self.position()? + buf.len() as u64;
+        if let Some(max_size) = self.max_size {
+            if end > max_size {
+                return Err(io::Error::other(format!("content exceeds the limit of {} bytes", max_size)));
+            }
+        }
+        if end > self.memory_limit as u64 {
+            self.spill()?;
+        }
+        match &mut self.storage {
+            Storage::Memory(cursor) => cursor.write(buf),
+            Storage::Disk(file) => file.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.storage {
+            Storage::Memory(_) => Ok(()),
+            Storage::Disk(file) => file.flush(),
+        }
+    }
+}
+
+impl Seek for BoundedBuffer {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match &mut self.storage {
+            Storage::Memory(cursor) => cursor.seek(pos),
+            Storage::Disk(file) => file.seek(pos),
+        }
+    }
+}
+
+/// Lee el cuerpo completo de una petición sin superar `limit` bytes.
+///
+/// Devuelve 413 en cuanto el cliente declara o envía más datos de los
+/// permitidos, sin llegar a acumularlos.
+pub async fn read_body(body: Body, limit: usize) -> Result<Bytes, AppError> {
+    let too_large = || AppError::new(
+        axum::http::StatusCode::PAYLOAD_TOO_LARGE,
+        format!("Request body exceeds the limit of {} bytes", limit),
+        "PayloadTooLarge",
+    );
+
+    if http_body::Body::size_hint(&body).lower() > limit as u64 {
+        return Err(too_large());
+    }
+
+    let mut collected = Vec::new();
+    let mut stream = body.into_data_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| AppError::bad_request(format!("Failed to read request body: {}", e)))?;
+        if collected.len() + chunk.len() > limit {
+            return Err(too_large());
+        }
+        collected.extend_from_slice(&chunk);
+    }
+    Ok(Bytes::from(collected))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use http_body_util::BodyExt;
+
+    #[test]
+    fn spills_to_disk_past_the_memory_limit() {
+        let mut buffer = BoundedBuffer::new(8);
+        buffer.write_all(b"12345").unwrap();
+        assert!(!buffer.is_spilled());
+
+        buffer.write_all(b"67890").unwrap();
+        assert!(buffer.is_spilled());
+        assert_eq!(buffer.len().unwrap(), 10);
+
+        // Las escrituras tras un seek sobrescriben, como en un archivo
+        buffer.seek(SeekFrom::Start(0)).unwrap();
+        buffer.write_all(b"ab").unwrap();
+        let Storage::Disk(mut file) = buffer.storage else { unreachable!() };
+        let mut content = String::new();
+        file.seek(SeekFrom::Start(0)).unwrap();
+        file.read_to_string(&mut content).unwrap();
+        assert_eq!(content, "ab34567890");
+    }
+
+    #[test]
+    fn rejects_writes_past_the_max_size() {
+        let mut buffer = BoundedBuffer::new(4).with_max_size(6);
+        buffer.write_all(b"1234").unwrap();
+        assert!(buffer.write_all(b"567").is_err());
+        assert_eq!(buffer.len().unwrap(), 4);
+    }
+
+    #[tokio::test]
+    async fn read_body_rejects_oversized_bodies() {
+        assert_eq!(read_body(Body::from("<propfind/>"), 64).await.unwrap(), Bytes::from("<propfind/>"));
+
+        let err = read_body(Body::from(vec![b'x'; 65]), 64).await.unwrap_err();
+        assert_eq!(err.status_code, axum::http::StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn spilled_buffer_streams_its_content() {
+        let mut buffer = BoundedBuffer::new(4);
+        buffer.write_all(b"streamed from disk").unwrap();
+        assert!(buffer.is_spilled());
+
+        let body = buffer.into_body().unwrap();
+        let bytes = body.collect().await.unwrap().to_bytes();
+        assert_eq!(bytes, Bytes::from("streamed from disk"));
+    }
+}
@@ -2,6 +2,13 @@ use std::time::Duration;
 use std::path::PathBuf;
 use std::env;
 
+use serde::Deserialize;
+
+use crate::common::bounded_buffer::MemoryLimits;
+use crate::domain::services::hidden_file_service::{HiddenFilePolicy, HiddenFileRules};
+use crate::domain::services::ownership_service::CrossUserMovePolicy;
+use crate::domain::services::path_codec_service::FilenameFallback;
+
 /// Configuración de caché
 #[derive(Debug, Clone)]
 pub struct CacheConfig {
@@ -98,6 +105,12 @@ pub struct ResourceConfig {
     pub chunk_size_bytes: usize,
     /// Límite de tamaño de archivo para cargar en memoria (MB)
     pub max_in_memory_file_size_mb: u64,
+    /// Tamaño máximo aceptado para una subida (MB)
+    pub max_upload_size_mb: u64,
+    /// Memoria que puede ocupar una respuesta (PROPFIND, ZIP) antes de volcarse a disco (MB)
+    pub response_memory_limit_mb: u64,
+    /// Tamaño máximo de los cuerpos XML de las peticiones WebDAV (MB)
+    pub max_xml_body_mb: u64,
 }
 
 impl Default for ResourceConfig {
@@ -107,6 +120,9 @@ impl Default for ResourceConfig {
             large_dir_threshold_entries: 1000,  // 1000 entradas
             chunk_size_bytes: 1024 * 1024,      // 1 MB
             max_in_memory_file_size_mb: 50,     // 50 MB
+            max_upload_size_mb: 10 * 1024,      // 10 GB
+            response_memory_limit_mb: 32,       // 32 MB
+            max_xml_body_mb: 10,                // 10 MB
         }
     }
 }
@@ -117,6 +133,20 @@ impl ResourceConfig {
         bytes / (1024 * 1024)
     }
 
+    /// Tamaño máximo aceptado para una subida en bytes
+    pub fn max_upload_size_bytes(&self) -> u64 {
+        self.max_upload_size_mb * 1024 * 1024
+    }
+
+    /// Límites de memoria para leer peticiones y componer respuestas
+    pub fn memory_limits(&self) -> MemoryLimits {
+        MemoryLimits {
+            response_memory: (self.response_memory_limit_mb * 1024 * 1024) as usize,
+            xml_body: (self.max_xml_body_mb * 1024 * 1024) as usize,
+            upload_body: usize::try_from(self.max_upload_size_bytes()).unwrap_or(usize::MAX),
+        }
+    }
+
     /// Determina si un archivo es considerado grande
     pub fn is_large_file(&self, size_bytes: u64) -> bool {
         self.bytes_to_mb(size_bytes) >= self.large_file_threshold_mb
@@ -206,6 +236,20 @@ pub struct StorageConfig {
     pub parallel_threshold: usize,
     /// Días de retención para archivos en la papelera
     pub trash_retention_days: u32,
+    /// Horas sin actividad tras las que caduca una subida por partes
+    pub upload_session_ttl_hours: u64,
+    /// Horas que se conserva un ZIP exportado en segundo plano, y durante las
+    /// que vale su enlace de descarga
+    pub zip_export_ttl_hours: u64,
+    /// Horas entre pasadas de la recolección de basura (0 = solo bajo petición)
+    pub gc_interval_hours: u64,
+    /// Horas que tiene que pasar algo sin cambios antes de que la recolección lo borre
+    pub gc_grace_period_hours: u64,
+    /// Qué ocurre al mover algo a la carpeta personal de otro usuario
+    pub cross_user_move_policy: CrossUserMovePolicy,
+    /// Minutos durante los que se puede deshacer un borrado, movimiento o
+    /// renombrado (0 = sin tokens para deshacer)
+    pub undo_window_minutes: u64,
 }
 
 impl Default for StorageConfig {
@@ -215,6 +259,12 @@ impl Default for StorageConfig {
             chunk_size: 1024 * 1024,      // 1 MB
             parallel_threshold: 100 * 1024 * 1024, // 100 MB
             trash_retention_days: 30,     // 30 días
+            upload_session_ttl_hours: 24, // 1 día
+            zip_export_ttl_hours: 24,
+            gc_interval_hours: 24,
+            gc_grace_period_hours: 24,
+            cross_user_move_policy: CrossUserMovePolicy::default(),
+            undo_window_minutes: 10,
         }
     }
 }
@@ -252,6 +302,13 @@ pub struct AuthConfig {
     pub refresh_token_expiry_secs: i64,
     pub hash_memory_cost: u32,
     pub hash_time_cost: u32,
+    /// Si los clientes WebDAV, CalDAV y CardDAV, y los de la API que usan
+    /// HTTP Basic, pueden autenticarse con la contraseña de la cuenta; si no,
+    /// solo con contraseñas de aplicación
+    pub dav_allow_account_password: bool,
+    /// Días que se conservan los datos de un usuario borrado antes de
+    /// eliminarlos definitivamente
+    pub user_quarantine_days: u64,
 }
 
 impl Default for AuthConfig {
@@ -262,6 +319,8 @@ impl Default for AuthConfig {
             refresh_token_expiry_secs: 2592000, // 30 días
             hash_memory_cost: 65536, // 64MB
             hash_time_cost: 3,
+            dav_allow_account_password: true,
+            user_quarantine_days: 30,
         }
     }
 }
@@ -274,6 +333,14 @@ pub struct FeaturesConfig {
     pub enable_file_sharing: bool,
     pub enable_trash: bool,
     pub enable_search: bool,
+    pub enable_image_placeholders: bool,
+    pub enable_image_fingerprints: bool,
+    pub enable_extended_attributes: bool,
+    pub enable_content_hashes: bool,
+    pub enable_full_text_search: bool,
+    pub enable_webdav: bool,
+    pub enable_caldav: bool,
+    pub enable_carddav: bool,
 }
 
 impl Default for FeaturesConfig {
@@ -284,6 +351,480 @@ impl Default for FeaturesConfig {
             enable_file_sharing: true,  // Enable file sharing by default
             enable_trash: true,  // Enable trash feature
             enable_search: true, // Enable search feature
+            enable_image_placeholders: true, // Blurhash placeholders for images
+            enable_image_fingerprints: true, // Perceptual hashes for similar photo search
+            enable_extended_attributes: false, // Mode bits and Finder metadata over WebDAV
+            enable_content_hashes: true, // SHA-256 exposed on downloads for integrity checks
+            enable_full_text_search: false, // Tantivy index of document contents
+            enable_webdav: true, // /api/webdav
+            enable_caldav: true, // /api/caldav
+            enable_carddav: true, // /api/carddav
+        }
+    }
+}
+
+/// Configuración de un punto de montaje de almacenamiento externo
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExternalMountConfig {
+    /// Nombre del montaje, usado en las rutas de la API
+    pub name: String,
+    /// Tipo de backend (por ejemplo "sftp")
+    pub backend: String,
+    /// Host remoto
+    #[serde(default)]
+    pub host: Option<String>,
+    /// Puerto remoto (por defecto el del protocolo)
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// Recurso compartido remoto (SMB)
+    #[serde(default)]
+    pub share: Option<String>,
+    /// Ruta raíz dentro del almacenamiento remoto
+    #[serde(default)]
+    pub root: String,
+    /// Clave de las credenciales en el almacén de credenciales
+    #[serde(default)]
+    pub credential: Option<String>,
+    /// Montaje de solo lectura
+    #[serde(default)]
+    pub read_only: bool,
+    /// Intervalo de detección de cambios en segundos (desactivada si no se indica)
+    #[serde(default)]
+    pub poll_interval_secs: Option<u64>,
+    /// Subdirectorio dentro de cada instantánea (por ejemplo "snapshot" en snapper)
+    #[serde(default)]
+    pub snapshot_subdir: Option<String>,
+    /// Usuarios (por nombre) que pueden usar el montaje
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+    /// Grupos (por ID) cuyos miembros pueden usar el montaje. Si no se indican
+    /// usuarios ni grupos, cualquier usuario autenticado puede usarlo; los
+    /// administradores pueden siempre
+    #[serde(default)]
+    pub allowed_groups: Vec<String>,
+}
+
+/// Configuración de almacenamiento externo
+#[derive(Debug, Clone)]
+pub struct ExternalStorageConfig {
+    /// Puntos de montaje configurados
+    pub mounts: Vec<ExternalMountConfig>,
+    /// Directorio para sockets de control y ficheros temporales
+    pub spool_dir: PathBuf,
+    /// Segundos que una conexión ociosa se mantiene abierta
+    pub connection_idle_secs: u64,
+    /// Máximo de sesiones concurrentes por montaje
+    pub max_sessions_per_mount: usize,
+    /// Profundidad máxima de directorios revisados al detectar cambios
+    pub change_scan_depth: usize,
+    /// Máximo de cambios recientes conservados por montaje
+    pub max_tracked_changes: usize,
+}
+
+impl Default for ExternalStorageConfig {
+    fn default() -> Self {
+        Self {
+            mounts: Vec::new(),
+            spool_dir: env::temp_dir().join("oxicloud-external"),
+            connection_idle_secs: 300, // 5 minutos
+            max_sessions_per_mount: 4,
+            change_scan_depth: 3,
+            max_tracked_changes: 500,
+        }
+    }
+}
+
+/// Configuración del etiquetado de imágenes mediante un servicio de inferencia externo
+#[derive(Debug, Clone)]
+pub struct ImageTaggingConfig {
+    /// URL del servicio de inferencia (desactivado si no se indica)
+    pub inference_url: Option<String>,
+    /// Timeout de cada petición de inferencia en segundos
+    pub timeout_secs: u64,
+    /// Confianza mínima para guardar una etiqueta
+    pub min_confidence: f32,
+    /// Máximo de etiquetas guardadas por imagen
+    pub max_labels: usize,
+}
+
+impl Default for ImageTaggingConfig {
+    fn default() -> Self {
+        Self {
+            inference_url: None,
+            timeout_secs: 30,
+            min_confidence: 0.5,
+            max_labels: 10,
+        }
+    }
+}
+
+/// Configuración de las vistas previas de imágenes redimensionadas bajo demanda
+#[derive(Debug, Clone)]
+pub struct ImagePreviewConfig {
+    /// Calidad por defecto: `fast` o `high`
+    pub quality: String,
+    /// Lado máximo en píxeles que se puede solicitar
+    pub max_dimension: u32,
+    /// Megapíxeles máximos de la imagen de origen
+    pub max_source_megapixels: u32,
+    /// Renderizados simultáneos
+    pub max_concurrent: usize,
+    /// Tamaño de la caché de vistas previas (MB)
+    pub cache_size_mb: u64,
+}
+
+impl Default for ImagePreviewConfig {
+    fn default() -> Self {
+        Self {
+            quality: "high".to_string(),
+            max_dimension: 2048,
+            max_source_megapixels: 40,
+            max_concurrent: 2,
+            cache_size_mb: 64,
+        }
+    }
+}
+
+/// Configuración del modo de captura de peticiones DAV para diagnóstico
+#[derive(Debug, Clone)]
+pub struct DavCaptureConfig {
+    /// Duración máxima de una captura (minutos)
+    pub max_minutes: u32,
+    /// Bytes máximos guardados de cada cuerpo de petición o respuesta (KB)
+    pub max_body_kb: usize,
+    /// Tamaño máximo de todo lo capturado para un usuario (MB)
+    pub max_capture_mb: u64,
+}
+
+impl Default for DavCaptureConfig {
+    fn default() -> Self {
+        Self {
+            max_minutes: 60,
+            max_body_kb: 256,
+            max_capture_mb: 50,
+        }
+    }
+}
+
+/// Configuración de los bloqueos WebDAV
+#[derive(Debug, Clone)]
+pub struct WebDavLockConfig {
+    /// Duración de un bloqueo cuando el cliente no pide ninguna (segundos)
+    pub default_timeout_secs: u64,
+    /// Duración máxima de un bloqueo, también para `Timeout: Infinite` (segundos)
+    pub max_timeout_secs: u64,
+}
+
+impl Default for WebDavLockConfig {
+    fn default() -> Self {
+        Self {
+            default_timeout_secs: 3600,
+            max_timeout_secs: 7 * 24 * 3600,
+        }
+    }
+}
+
+/// Configuración de las copias y movimientos WebDAV en segundo plano
+#[derive(Debug, Clone)]
+pub struct WebDavTransferConfig {
+    /// Tamaño a partir del cual un COPY o MOVE de carpeta responde 202 y
+    /// sigue en segundo plano (MB; 0 lo desactiva)
+    pub async_threshold_mb: u64,
+    /// Tiempo que se conserva el resultado de un trabajo terminado (segundos)
+    pub result_ttl_secs: u64,
+}
+
+impl Default for WebDavTransferConfig {
+    fn default() -> Self {
+        Self {
+            async_threshold_mb: 1024,
+            result_ttl_secs: 24 * 3600,
+        }
+    }
+}
+
+/// Configuración de los feature flags guardados en base de datos
+#[derive(Debug, Clone)]
+pub struct FeatureFlagConfig {
+    /// Tiempo que se reutilizan los flags cargados antes de volver a leerlos (segundos)
+    pub cache_ttl_secs: u64,
+}
+
+impl Default for FeatureFlagConfig {
+    fn default() -> Self {
+        Self { cache_ttl_secs: 30 }
+    }
+}
+
+/// Configuración de las versiones de la API REST
+#[derive(Debug, Clone, Default)]
+pub struct ApiConfig {
+    /// Fecha a partir de la cual las rutas sin versión (`/api/...`) dejarán de
+    /// funcionar; se anuncia en la cabecera `Sunset`
+    pub legacy_sunset: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Reglas de la instancia para ficheros ocultos y de sistema
+#[derive(Debug, Clone)]
+pub struct HiddenFilesConfig {
+    /// Política para los nombres que empiezan por punto: `store`, `hide` o `reject`
+    pub dotfiles: String,
+    /// Política para `.DS_Store`, `Thumbs.db`, `~$` y similares
+    pub system_files: String,
+}
+
+impl Default for HiddenFilesConfig {
+    fn default() -> Self {
+        Self {
+            dotfiles: "hide".to_string(),
+            system_files: "store".to_string(),
+        }
+    }
+}
+
+impl HiddenFilesConfig {
+    /// Reglas de la instancia; los valores no reconocidos usan los de por defecto
+    pub fn rules(&self) -> HiddenFileRules {
+        let defaults = HiddenFileRules::default();
+        HiddenFileRules {
+            dotfiles: HiddenFilePolicy::parse(&self.dotfiles).unwrap_or(defaults.dotfiles),
+            system_files: HiddenFilePolicy::parse(&self.system_files).unwrap_or(defaults.system_files),
+        }
+    }
+}
+
+/// Configuración de los nombres de fichero en las descargas
+#[derive(Debug, Clone)]
+pub struct DownloadConfig {
+    /// Cómo se forma la versión ASCII del nombre para los clientes que no
+    /// entienden `filename*`: `transliterate` o `replace`
+    pub filename_fallback: String,
+}
+
+impl Default for DownloadConfig {
+    fn default() -> Self {
+        Self { filename_fallback: "transliterate".to_string() }
+    }
+}
+
+impl DownloadConfig {
+    /// Modo de la instancia; los valores no reconocidos usan el de por defecto
+    pub fn filename_fallback(&self) -> FilenameFallback {
+        FilenameFallback::parse(&self.filename_fallback).unwrap_or(FilenameFallback::Transliterate)
+    }
+}
+
+/// Configuración del modo demostración: una cuenta de ejemplo con datos que
+/// se restauran periódicamente
+#[derive(Debug, Clone)]
+pub struct DemoConfig {
+    /// Activa el modo demostración
+    pub enabled: bool,
+    /// Nombre de la cuenta de demostración
+    pub username: String,
+    /// Contraseña de la cuenta, que se muestra en la página de acceso
+    pub password: String,
+    /// Correo de la cuenta
+    pub email: String,
+    /// Cada cuánto se restauran los datos (minutos); 0 solo a petición
+    pub reset_interval_minutes: u64,
+}
+
+impl Default for DemoConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            username: "demo".to_string(),
+            password: "oxicloud-demo".to_string(),
+            email: "demo@oxicloud.local".to_string(),
+            reset_interval_minutes: 60,
+        }
+    }
+}
+
+/// Límites de la API pública de metadatos de enlaces compartidos
+#[derive(Debug, Clone)]
+pub struct PublicShareConfig {
+    /// Peticiones permitidas por dirección IP en cada ventana
+    pub metadata_requests: u32,
+    /// Duración de la ventana (segundos)
+    pub metadata_window_secs: u64,
+    /// URL pública del servidor para los enlaces de las vistas previas
+    /// (Open Graph, oEmbed); sin ella se usa `http://host:puerto`
+    pub base_url: Option<String>,
+    /// Validez de los códigos enviados por correo a los visitantes (segundos)
+    pub email_code_ttl_secs: u64,
+    /// Duración de la sesión que obtiene un visitante al verificar su correo (segundos)
+    pub email_session_secs: u64,
+}
+
+impl Default for PublicShareConfig {
+    fn default() -> Self {
+        Self {
+            metadata_requests: 30,
+            metadata_window_secs: 60,
+            base_url: None,
+            email_code_ttl_secs: 600,
+            email_session_secs: 3600,
+        }
+    }
+}
+
+/// Autenticación contra un directorio LDAP o Active Directory.
+///
+/// La contraseña se comprueba haciendo bind con el DN del usuario, que se
+/// busca antes con la cuenta de servicio. Los usuarios del directorio se
+/// crean o actualizan en la base de datos local al entrar.
+#[derive(Debug, Clone)]
+pub struct LdapConfig {
+    /// Activa la autenticación contra el directorio
+    pub enabled: bool,
+    /// `ldap://host:389` o `ldaps://host:636`
+    pub url: String,
+    /// Pasa a TLS con StartTLS en las conexiones `ldap://`
+    pub starttls: bool,
+    /// Cuenta de servicio para buscar usuarios; vacía para búsquedas anónimas
+    pub bind_dn: String,
+    pub bind_password: String,
+    /// Base de la búsqueda de usuarios
+    pub base_dn: String,
+    /// Filtro de búsqueda; `{username}` se sustituye por el nombre escapado
+    pub user_filter: String,
+    /// Atributos que se copian al usuario local
+    pub username_attribute: String,
+    pub email_attribute: String,
+    /// Atributo del usuario con sus grupos (`memberOf`)
+    pub group_attribute: String,
+    /// Búsqueda opcional de grupos para directorios sin `memberOf`;
+    /// `{dn}` y `{username}` se sustituyen por los del usuario
+    pub group_filter: Option<String>,
+    /// Base de la búsqueda de grupos; por defecto, la de usuarios
+    pub group_base_dn: Option<String>,
+    /// Grupo cuyos miembros son administradores (DN o nombre)
+    pub admin_group: Option<String>,
+    /// Grupo al que hay que pertenecer para entrar (DN o nombre)
+    pub required_group: Option<String>,
+    /// Si las cuentas locales que no están en el directorio pueden entrar
+    pub allow_local_users: bool,
+    /// Tiempo máximo de cada operación con el servidor (segundos)
+    pub timeout_secs: u64,
+}
+
+impl Default for LdapConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: "ldap://localhost:389".to_string(),
+            starttls: false,
+            bind_dn: String::new(),
+            bind_password: String::new(),
+            base_dn: String::new(),
+            user_filter: "(&(objectClass=person)(uid={username}))".to_string(),
+            username_attribute: "uid".to_string(),
+            email_attribute: "mail".to_string(),
+            group_attribute: "memberOf".to_string(),
+            group_filter: None,
+            group_base_dn: None,
+            admin_group: None,
+            required_group: None,
+            allow_local_users: true,
+            timeout_secs: 10,
+        }
+    }
+}
+
+/// Seguridad de la conexión con el servidor SMTP
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpSecurity {
+    /// Sin cifrar (solo para servidores locales)
+    None,
+    /// Conexión en claro que pasa a TLS con STARTTLS (puerto 587)
+    StartTls,
+    /// TLS desde el principio (puerto 465)
+    Tls,
+}
+
+/// Envío de correo por SMTP, usado por ejemplo para los códigos de acceso
+/// a los enlaces compartidos
+#[derive(Debug, Clone)]
+pub struct MailConfig {
+    /// Servidor SMTP; sin él no se envía correo
+    pub smtp_host: Option<String>,
+    pub smtp_port: u16,
+    pub security: SmtpSecurity,
+    /// Usuario y contraseña de AUTH PLAIN; sin usuario no se autentica
+    pub username: Option<String>,
+    pub password: String,
+    /// Remitente de los mensajes
+    pub from: String,
+    /// Avisa por correo a los usuarios de lo que comparten con ellos, de las
+    /// descargas de sus enlaces y de la cuota casi llena
+    pub notifications: bool,
+    /// Carpeta con plantillas propias de esos avisos (`share_received.txt`,
+    /// `share_downloaded.txt`, `quota_warning.txt`); las que falten usan las
+    /// incluidas
+    pub templates_path: Option<PathBuf>,
+    /// Porcentaje de la cuota a partir del cual se avisa de que está casi llena
+    pub quota_warning_percent: u8,
+}
+
+impl Default for MailConfig {
+    fn default() -> Self {
+        Self {
+            smtp_host: None,
+            smtp_port: 587,
+            security: SmtpSecurity::StartTls,
+            username: None,
+            password: String::new(),
+            from: "OxiCloud <noreply@localhost>".to_string(),
+            notifications: true,
+            templates_path: None,
+            quota_warning_percent: 90,
+        }
+    }
+}
+
+/// Inicio de sesión con un proveedor OpenID Connect externo (Keycloak,
+/// Authentik...) mediante el flujo de código de autorización
+#[derive(Debug, Clone)]
+pub struct OidcConfig {
+    /// Activa el inicio de sesión con el proveedor
+    pub enabled: bool,
+    /// URL del emisor; la configuración se descubre en
+    /// `<emisor>/.well-known/openid-configuration`
+    pub issuer_url: String,
+    pub client_id: String,
+    pub client_secret: String,
+    /// URL de retorno registrada en el proveedor; sin ella se usa
+    /// `<URL pública>/api/auth/oidc/callback`
+    pub redirect_uri: Option<String>,
+    /// Ámbitos que se piden, separados por espacios
+    pub scopes: String,
+    /// Claim del que se toma el nombre de los usuarios nuevos
+    pub username_claim: String,
+    /// Si se crea la cuenta la primera vez que entra alguien sin cuenta local
+    pub auto_provision: bool,
+    /// Nombre del proveedor en el botón de la página de acceso
+    pub provider_name: String,
+    /// Algoritmos de firma aceptados en los ID tokens. Vacío usa los que
+    /// anuncia el proveedor, o RS256 y ES256; los HS* solo se aceptan si se
+    /// indican aquí
+    pub id_token_algorithms: Vec<String>,
+}
+
+impl Default for OidcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            issuer_url: String::new(),
+            client_id: String::new(),
+            client_secret: String::new(),
+            redirect_uri: None,
+            scopes: "openid profile email".to_string(),
+            username_claim: "preferred_username".to_string(),
+            auto_provision: true,
+            provider_name: "SSO".to_string(),
+            id_token_algorithms: Vec::new(),
         }
     }
 }
@@ -295,6 +836,11 @@ pub struct AppConfig {
     pub storage_path: PathBuf,
     /// Ruta del directorio de archivos estáticos
     pub static_path: PathBuf,
+    /// Ruta del directorio con los esqueletos de carpetas para usuarios nuevos
+    pub skeleton_path: PathBuf,
+    /// Ruta del directorio con las plantillas de documentos que gestiona el
+    /// administrador y que se ofrecen a todos los usuarios
+    pub templates_path: PathBuf,
     /// Puerto del servidor
     pub server_port: u16,
     /// Host del servidor
@@ -315,6 +861,36 @@ pub struct AppConfig {
     pub auth: AuthConfig,
     /// Configuración de funcionalidades
     pub features: FeaturesConfig,
+    /// Configuración de almacenamiento externo
+    pub external_storage: ExternalStorageConfig,
+    /// Configuración del etiquetado de imágenes
+    pub image_tagging: ImageTaggingConfig,
+    /// Configuración de las vistas previas de imágenes
+    pub image_preview: ImagePreviewConfig,
+    /// Configuración de la captura de peticiones DAV
+    pub dav_capture: DavCaptureConfig,
+    /// Configuración de ficheros ocultos y de sistema
+    pub hidden_files: HiddenFilesConfig,
+    /// Configuración de los nombres de fichero en las descargas
+    pub downloads: DownloadConfig,
+    /// Configuración de los bloqueos WebDAV
+    pub webdav_locks: WebDavLockConfig,
+    /// Configuración de las copias y movimientos WebDAV en segundo plano
+    pub webdav_transfers: WebDavTransferConfig,
+    /// Configuración de los feature flags
+    pub feature_flags: FeatureFlagConfig,
+    /// Configuración de las versiones de la API
+    pub api: ApiConfig,
+    /// Configuración del modo demostración
+    pub demo: DemoConfig,
+    /// Configuración de la API pública de enlaces compartidos
+    pub public_share: PublicShareConfig,
+    /// Configuración del inicio de sesión con OpenID Connect
+    pub oidc: OidcConfig,
+    /// Configuración del envío de correo
+    pub mail: MailConfig,
+    /// Configuración de la autenticación contra LDAP / Active Directory
+    pub ldap: LdapConfig,
 }
 
 impl Default for AppConfig {
@@ -322,7 +898,9 @@ impl Default for AppConfig {
         Self {
             storage_path: PathBuf::from("./storage"),
             static_path: PathBuf::from("./static"),
-            server_port: 8085,
+            skeleton_path: PathBuf::from("./skeleton"),
+            templates_path: PathBuf::from("./templates"),
+            server_port: 8086,
             server_host: "127.0.0.1".to_string(),
             cache: CacheConfig::default(),
             timeouts: TimeoutConfig::default(),
@@ -332,6 +910,21 @@ impl Default for AppConfig {
             database: DatabaseConfig::default(),
             auth: AuthConfig::default(),
             features: FeaturesConfig::default(),
+            external_storage: ExternalStorageConfig::default(),
+            image_tagging: ImageTaggingConfig::default(),
+            image_preview: ImagePreviewConfig::default(),
+            dav_capture: DavCaptureConfig::default(),
+            hidden_files: HiddenFilesConfig::default(),
+            downloads: DownloadConfig::default(),
+            webdav_locks: WebDavLockConfig::default(),
+            webdav_transfers: WebDavTransferConfig::default(),
+            feature_flags: FeatureFlagConfig::default(),
+            api: ApiConfig::default(),
+            demo: DemoConfig::default(),
+            public_share: PublicShareConfig::default(),
+            oidc: OidcConfig::default(),
+            mail: MailConfig::default(),
+            ldap: LdapConfig::default(),
         }
     }
 }
@@ -349,6 +942,14 @@ impl AppConfig {
             config.static_path = PathBuf::from(static_path);
         }
             
+        if let Ok(skeleton_path) = env::var("OXICLOUD_SKELETON_PATH") {
+            config.skeleton_path = PathBuf::from(skeleton_path);
+        }
+            
+        if let Ok(templates_path) = env::var("OXICLOUD_TEMPLATES_PATH") {
+            config.templates_path = PathBuf::from(templates_path);
+        }
+            
         if let Ok(server_port) = env::var("OXICLOUD_SERVER_PORT") {
             if let Ok(port) = server_port.parse::<u16>() {
                 config.server_port = port;
@@ -359,6 +960,69 @@ impl AppConfig {
             config.server_host = server_host;
         }
         
+        if let Ok(max_upload_size) = env::var("OXICLOUD_MAX_UPLOAD_SIZE_MB")
+            .map(|v| v.parse::<u64>()) {
+            if let Ok(val) = max_upload_size {
+                config.resources.max_upload_size_mb = val;
+            }
+        }
+        
+        if let Ok(response_memory_limit) = env::var("OXICLOUD_RESPONSE_MEMORY_LIMIT_MB")
+            .map(|v| v.parse::<u64>()) {
+            if let Ok(val) = response_memory_limit {
+                config.resources.response_memory_limit_mb = val;
+            }
+        }
+        
+        if let Ok(max_xml_body) = env::var("OXICLOUD_MAX_XML_BODY_MB")
+            .map(|v| v.parse::<u64>()) {
+            if let Ok(val) = max_xml_body {
+                config.resources.max_xml_body_mb = val;
+            }
+        }
+        
+        if let Ok(upload_session_ttl) = env::var("OXICLOUD_UPLOAD_SESSION_TTL_HOURS")
+            .map(|v| v.parse::<u64>()) {
+            if let Ok(val) = upload_session_ttl {
+                config.storage.upload_session_ttl_hours = val.max(1);
+            }
+        }
+        
+        if let Ok(zip_export_ttl) = env::var("OXICLOUD_ZIP_EXPORT_TTL_HOURS")
+            .map(|v| v.parse::<u64>()) {
+            if let Ok(val) = zip_export_ttl {
+                config.storage.zip_export_ttl_hours = val.max(1);
+            }
+        }
+        
+        if let Ok(gc_interval) = env::var("OXICLOUD_STORAGE_GC_INTERVAL_HOURS")
+            .map(|v| v.parse::<u64>()) {
+            if let Ok(val) = gc_interval {
+                config.storage.gc_interval_hours = val;
+            }
+        }
+        
+        if let Ok(gc_grace) = env::var("OXICLOUD_STORAGE_GC_GRACE_HOURS")
+            .map(|v| v.parse::<u64>()) {
+            if let Ok(val) = gc_grace {
+                config.storage.gc_grace_period_hours = val.max(1);
+            }
+        }
+        
+        if let Ok(policy) = env::var("OXICLOUD_CROSS_USER_MOVE_POLICY") {
+            match CrossUserMovePolicy::parse(&policy) {
+                Some(policy) => config.storage.cross_user_move_policy = policy,
+                None => tracing::warn!("Invalid OXICLOUD_CROSS_USER_MOVE_POLICY value: {}", policy),
+            }
+        }
+        
+        if let Ok(undo_window) = env::var("OXICLOUD_UNDO_WINDOW_MINUTES")
+            .map(|v| v.parse::<u64>()) {
+            if let Ok(val) = undo_window {
+                config.storage.undo_window_minutes = val;
+            }
+        }
+        
         // Configuración de Database
         if let Ok(connection_string) = env::var("OXICLOUD_DB_CONNECTION_STRING") {
             config.database.connection_string = connection_string;
@@ -397,6 +1061,20 @@ impl AppConfig {
             }
         }
         
+        if let Ok(allow) = env::var("OXICLOUD_DAV_ALLOW_ACCOUNT_PASSWORD")
+            .map(|v| v.parse::<bool>()) {
+            if let Ok(val) = allow {
+                config.auth.dav_allow_account_password = val;
+            }
+        }
+        
+        if let Ok(days) = env::var("OXICLOUD_USER_QUARANTINE_DAYS")
+            .map(|v| v.parse::<u64>()) {
+            if let Ok(val) = days {
+                config.auth.user_quarantine_days = val;
+            }
+        }
+        
         // Feature flags
         if let Ok(enable_auth) = env::var("OXICLOUD_ENABLE_AUTH")
             .map(|v| v.parse::<bool>()) {
@@ -433,6 +1111,485 @@ impl AppConfig {
             }
         }
         
+        if let Ok(enable_image_placeholders) = env::var("OXICLOUD_ENABLE_IMAGE_PLACEHOLDERS")
+            .map(|v| v.parse::<bool>()) {
+            if let Ok(val) = enable_image_placeholders {
+                config.features.enable_image_placeholders = val;
+            }
+        }
+        
+        if let Ok(enable_image_fingerprints) = env::var("OXICLOUD_ENABLE_IMAGE_FINGERPRINTS")
+            .map(|v| v.parse::<bool>()) {
+            if let Ok(val) = enable_image_fingerprints {
+                config.features.enable_image_fingerprints = val;
+            }
+        }
+        
+        if let Ok(enable_extended_attributes) = env::var("OXICLOUD_ENABLE_EXTENDED_ATTRIBUTES")
+            .map(|v| v.parse::<bool>()) {
+            if let Ok(val) = enable_extended_attributes {
+                config.features.enable_extended_attributes = val;
+            }
+        }
+        
+        if let Ok(enable_content_hashes) = env::var("OXICLOUD_ENABLE_CONTENT_HASHES")
+            .map(|v| v.parse::<bool>()) {
+            if let Ok(val) = enable_content_hashes {
+                config.features.enable_content_hashes = val;
+            }
+        }
+        
+        if let Ok(enable_full_text_search) = env::var("OXICLOUD_ENABLE_FULL_TEXT_SEARCH")
+            .map(|v| v.parse::<bool>()) {
+            if let Ok(val) = enable_full_text_search {
+                config.features.enable_full_text_search = val;
+            }
+        }
+        
+        for (var, flag) in [
+            ("OXICLOUD_ENABLE_WEBDAV", &mut config.features.enable_webdav),
+            ("OXICLOUD_ENABLE_CALDAV", &mut config.features.enable_caldav),
+            ("OXICLOUD_ENABLE_CARDDAV", &mut config.features.enable_carddav),
+        ] {
+            if let Ok(Ok(val)) = env::var(var).map(|v| v.parse::<bool>()) {
+                *flag = val;
+            }
+        }
+        
+        // Almacenamiento externo
+        if let Ok(mounts) = env::var("OXICLOUD_EXTERNAL_MOUNTS") {
+            match serde_json::from_str::<Vec<ExternalMountConfig>>(&mounts) {
+                Ok(val) => config.external_storage.mounts = val,
+                Err(e) => tracing::warn!("Invalid OXICLOUD_EXTERNAL_MOUNTS value: {}", e),
+            }
+        }
+        
+        if let Ok(spool_dir) = env::var("OXICLOUD_EXTERNAL_SPOOL_DIR") {
+            config.external_storage.spool_dir = PathBuf::from(spool_dir);
+        }
+        
+        if let Ok(idle_secs) = env::var("OXICLOUD_EXTERNAL_CONNECTION_IDLE_SECS")
+            .map(|v| v.parse::<u64>()) {
+            if let Ok(val) = idle_secs {
+                config.external_storage.connection_idle_secs = val;
+            }
+        }
+        
+        if let Ok(max_sessions) = env::var("OXICLOUD_EXTERNAL_MAX_SESSIONS")
+            .map(|v| v.parse::<usize>()) {
+            if let Ok(val) = max_sessions {
+                config.external_storage.max_sessions_per_mount = val.max(1);
+            }
+        }
+        
+        // Etiquetado de imágenes
+        if let Ok(url) = env::var("OXICLOUD_IMAGE_TAGGING_URL") {
+            if !url.trim().is_empty() {
+                config.image_tagging.inference_url = Some(url.trim().to_string());
+            }
+        }
+        
+        if let Ok(timeout) = env::var("OXICLOUD_IMAGE_TAGGING_TIMEOUT_SECS")
+            .map(|v| v.parse::<u64>()) {
+            if let Ok(val) = timeout {
+                config.image_tagging.timeout_secs = val;
+            }
+        }
+        
+        if let Ok(min_confidence) = env::var("OXICLOUD_IMAGE_TAGGING_MIN_CONFIDENCE")
+            .map(|v| v.parse::<f32>()) {
+            if let Ok(val) = min_confidence {
+                config.image_tagging.min_confidence = val.clamp(0.0, 1.0);
+            }
+        }
+        
+        // Vistas previas de imágenes
+        if let Ok(quality) = env::var("OXICLOUD_IMAGE_PREVIEW_QUALITY") {
+            match quality.trim().to_lowercase().as_str() {
+                "fast" | "high" => config.image_preview.quality = quality.trim().to_lowercase(),
+                _ => tracing::warn!("Invalid OXICLOUD_IMAGE_PREVIEW_QUALITY value: {}", quality),
+            }
+        }
+        
+        if let Ok(max_dimension) = env::var("OXICLOUD_IMAGE_PREVIEW_MAX_DIMENSION")
+            .map(|v| v.parse::<u32>()) {
+            if let Ok(val) = max_dimension {
+                config.image_preview.max_dimension = val.max(1);
+            }
+        }
+        
+        if let Ok(max_megapixels) = env::var("OXICLOUD_IMAGE_PREVIEW_MAX_MEGAPIXELS")
+            .map(|v| v.parse::<u32>()) {
+            if let Ok(val) = max_megapixels {
+                config.image_preview.max_source_megapixels = val.max(1);
+            }
+        }
+        
+        if let Ok(max_concurrent) = env::var("OXICLOUD_IMAGE_PREVIEW_MAX_CONCURRENT")
+            .map(|v| v.parse::<usize>()) {
+            if let Ok(val) = max_concurrent {
+                config.image_preview.max_concurrent = val.max(1);
+            }
+        }
+        
+        if let Ok(cache_size) = env::var("OXICLOUD_IMAGE_PREVIEW_CACHE_MB")
+            .map(|v| v.parse::<u64>()) {
+            if let Ok(val) = cache_size {
+                config.image_preview.cache_size_mb = val;
+            }
+        }
+        
+        // Captura de peticiones DAV
+        if let Ok(max_minutes) = env::var("OXICLOUD_DAV_CAPTURE_MAX_MINUTES")
+            .map(|v| v.parse::<u32>()) {
+            if let Ok(val) = max_minutes {
+                config.dav_capture.max_minutes = val.max(1);
+            }
+        }
+        
+        if let Ok(max_body) = env::var("OXICLOUD_DAV_CAPTURE_MAX_BODY_KB")
+            .map(|v| v.parse::<usize>()) {
+            if let Ok(val) = max_body {
+                config.dav_capture.max_body_kb = val;
+            }
+        }
+        
+        if let Ok(max_capture) = env::var("OXICLOUD_DAV_CAPTURE_MAX_MB")
+            .map(|v| v.parse::<u64>()) {
+            if let Ok(val) = max_capture {
+                config.dav_capture.max_capture_mb = val.max(1);
+            }
+        }
+        
+        // Ficheros ocultos y de sistema
+        if let Ok(policy) = env::var("OXICLOUD_HIDDEN_DOTFILES") {
+            match HiddenFilePolicy::parse(&policy) {
+                Some(_) => config.hidden_files.dotfiles = policy.trim().to_lowercase(),
+                None => tracing::warn!("Invalid OXICLOUD_HIDDEN_DOTFILES value: {}", policy),
+            }
+        }
+        
+        if let Ok(policy) = env::var("OXICLOUD_HIDDEN_SYSTEM_FILES") {
+            match HiddenFilePolicy::parse(&policy) {
+                Some(_) => config.hidden_files.system_files = policy.trim().to_lowercase(),
+                None => tracing::warn!("Invalid OXICLOUD_HIDDEN_SYSTEM_FILES value: {}", policy),
+            }
+        }
+        
+        // Nombres de fichero en las descargas
+        if let Ok(fallback) = env::var("OXICLOUD_DOWNLOAD_FILENAME_FALLBACK") {
+            match FilenameFallback::parse(&fallback) {
+                Some(_) => config.downloads.filename_fallback = fallback.trim().to_lowercase(),
+                None => tracing::warn!("Invalid OXICLOUD_DOWNLOAD_FILENAME_FALLBACK value: {}", fallback),
+            }
+        }
+        
+        // Bloqueos WebDAV
+        if let Ok(timeout) = env::var("OXICLOUD_WEBDAV_LOCK_DEFAULT_TIMEOUT")
+            .map(|v| v.parse::<u64>()) {
+            if let Ok(val) = timeout {
+                config.webdav_locks.default_timeout_secs = val.max(1);
+            }
+        }
+        
+        if let Ok(timeout) = env::var("OXICLOUD_WEBDAV_LOCK_MAX_TIMEOUT")
+            .map(|v| v.parse::<u64>()) {
+            if let Ok(val) = timeout {
+                config.webdav_locks.max_timeout_secs = val.max(1);
+            }
+        }
+        
+        // Copias y movimientos WebDAV en segundo plano
+        if let Ok(threshold) = env::var("OXICLOUD_WEBDAV_ASYNC_TRANSFER_MB")
+            .map(|v| v.parse::<u64>()) {
+            if let Ok(val) = threshold {
+                config.webdav_transfers.async_threshold_mb = val;
+            }
+        }
+        
+        if let Ok(ttl) = env::var("OXICLOUD_WEBDAV_TRANSFER_RESULT_TTL")
+            .map(|v| v.parse::<u64>()) {
+            if let Ok(val) = ttl {
+                config.webdav_transfers.result_ttl_secs = val.max(60);
+            }
+        }
+        
+        if let Ok(ttl) = env::var("OXICLOUD_FEATURE_FLAG_CACHE_TTL")
+            .map(|v| v.parse::<u64>()) {
+            if let Ok(val) = ttl {
+                config.feature_flags.cache_ttl_secs = val;
+            }
+        }
+        
+        // Retirada de las rutas sin versión: fecha (2027-06-30) o instante RFC 3339
+        if let Ok(sunset) = env::var("OXICLOUD_API_LEGACY_SUNSET") {
+            let parsed = chrono::DateTime::parse_from_rfc3339(sunset.trim())
+                .map(|date| date.with_timezone(&chrono::Utc))
+                .ok()
+                .or_else(|| chrono::NaiveDate::parse_from_str(sunset.trim(), "%Y-%m-%d").ok()
+                    .and_then(|date| date.and_hms_opt(0, 0, 0))
+                    .map(|date| date.and_utc()));
+            match parsed {
+                Some(date) => config.api.legacy_sunset = Some(date),
+                None => tracing::warn!("Invalid OXICLOUD_API_LEGACY_SUNSET value: {}", sunset),
+            }
+        }
+        
+        // Modo demostración
+        if let Ok(enabled) = env::var("OXICLOUD_DEMO_MODE")
+            .map(|v| v.parse::<bool>()) {
+            if let Ok(val) = enabled {
+                config.demo.enabled = val;
+            }
+        }
+        
+        if let Ok(username) = env::var("OXICLOUD_DEMO_USERNAME") {
+            config.demo.username = username;
+        }
+        
+        if let Ok(password) = env::var("OXICLOUD_DEMO_PASSWORD") {
+            config.demo.password = password;
+        }
+        
+        if let Ok(email) = env::var("OXICLOUD_DEMO_EMAIL") {
+            config.demo.email = email;
+        }
+        
+        if let Ok(interval) = env::var("OXICLOUD_DEMO_RESET_INTERVAL_MINUTES")
+            .map(|v| v.parse::<u64>()) {
+            if let Ok(val) = interval {
+                config.demo.reset_interval_minutes = val;
+            }
+        }
+        
+        // Metadatos públicos de enlaces compartidos
+        if let Ok(requests) = env::var("OXICLOUD_PUBLIC_SHARE_RATE_LIMIT")
+            .map(|v| v.parse::<u32>()) {
+            if let Ok(val) = requests {
+                config.public_share.metadata_requests = val.max(1);
+            }
+        }
+        
+        if let Ok(window) = env::var("OXICLOUD_PUBLIC_SHARE_RATE_WINDOW_SECS")
+            .map(|v| v.parse::<u64>()) {
+            if let Ok(val) = window {
+                config.public_share.metadata_window_secs = val.max(1);
+            }
+        }
+        
+        if let Ok(base_url) = env::var("OXICLOUD_PUBLIC_BASE_URL") {
+            let base_url = base_url.trim().trim_end_matches('/');
+            if !base_url.is_empty() {
+                config.public_share.base_url = Some(base_url.to_string());
+            }
+        }
+        
+        // Inicio de sesión con OpenID Connect
+        if let Ok(enabled) = env::var("OXICLOUD_OIDC_ENABLED")
+            .map(|v| v.parse::<bool>()) {
+            if let Ok(val) = enabled {
+                config.oidc.enabled = val;
+            }
+        }
+        
+        if let Ok(issuer_url) = env::var("OXICLOUD_OIDC_ISSUER_URL") {
+            config.oidc.issuer_url = issuer_url.trim().trim_end_matches('/').to_string();
+        }
+        
+        if let Ok(client_id) = env::var("OXICLOUD_OIDC_CLIENT_ID") {
+            config.oidc.client_id = client_id;
+        }
+        
+        if let Ok(client_secret) = env::var("OXICLOUD_OIDC_CLIENT_SECRET") {
+            config.oidc.client_secret = client_secret;
+        }
+        
+        if let Ok(redirect_uri) = env::var("OXICLOUD_OIDC_REDIRECT_URI") {
+            if !redirect_uri.trim().is_empty() {
+                config.oidc.redirect_uri = Some(redirect_uri.trim().to_string());
+            }
+        }
+        
+        if let Ok(scopes) = env::var("OXICLOUD_OIDC_SCOPES") {
+            config.oidc.scopes = scopes;
+        }
+        
+        if let Ok(claim) = env::var("OXICLOUD_OIDC_USERNAME_CLAIM") {
+            config.oidc.username_claim = claim;
+        }
+        
+        if let Ok(auto_provision) = env::var("OXICLOUD_OIDC_AUTO_PROVISION")
+            .map(|v| v.parse::<bool>()) {
+            if let Ok(val) = auto_provision {
+                config.oidc.auto_provision = val;
+            }
+        }
+        
+        if let Ok(provider_name) = env::var("OXICLOUD_OIDC_PROVIDER_NAME") {
+            config.oidc.provider_name = provider_name;
+        }
+        
+        if let Ok(algorithms) = env::var("OXICLOUD_OIDC_ID_TOKEN_ALGORITHMS") {
+            config.oidc.id_token_algorithms = algorithms
+                .split(',')
+                .map(|algorithm| algorithm.trim().to_uppercase())
+                .filter(|algorithm| !algorithm.is_empty())
+                .collect();
+        }
+        
+        // Acceso a enlaces compartidos verificando el correo
+        if let Ok(ttl) = env::var("OXICLOUD_SHARE_EMAIL_CODE_TTL_SECS")
+            .map(|v| v.parse::<u64>()) {
+            if let Ok(val) = ttl {
+                config.public_share.email_code_ttl_secs = val.max(60);
+            }
+        }
+        
+        if let Ok(duration) = env::var("OXICLOUD_SHARE_EMAIL_SESSION_SECS")
+            .map(|v| v.parse::<u64>()) {
+            if let Ok(val) = duration {
+                config.public_share.email_session_secs = val.max(60);
+            }
+        }
+        
+        // Envío de correo por SMTP
+        if let Ok(host) = env::var("OXICLOUD_SMTP_HOST") {
+            if !host.trim().is_empty() {
+                config.mail.smtp_host = Some(host.trim().to_string());
+            }
+        }
+        
+        if let Ok(security) = env::var("OXICLOUD_SMTP_SECURITY") {
+            match security.to_lowercase().as_str() {
+                "none" | "plain" => config.mail.security = SmtpSecurity::None,
+                "starttls" => config.mail.security = SmtpSecurity::StartTls,
+                "tls" | "ssl" => {
+                    config.mail.security = SmtpSecurity::Tls;
+                    config.mail.smtp_port = 465;
+                }
+                other => tracing::warn!("Invalid OXICLOUD_SMTP_SECURITY value: {}", other),
+            }
+        }
+        
+        if let Ok(port) = env::var("OXICLOUD_SMTP_PORT")
+            .map(|v| v.parse::<u16>()) {
+            if let Ok(val) = port {
+                config.mail.smtp_port = val;
+            }
+        }
+        
+        if let Ok(username) = env::var("OXICLOUD_SMTP_USERNAME") {
+            if !username.is_empty() {
+                config.mail.username = Some(username);
+            }
+        }
+        
+        if let Ok(password) = env::var("OXICLOUD_SMTP_PASSWORD") {
+            config.mail.password = password;
+        }
+        
+        if let Ok(from) = env::var("OXICLOUD_SMTP_FROM") {
+            config.mail.from = from;
+        }
+        
+        // Avisos por correo a los usuarios
+        if let Ok(enabled) = env::var("OXICLOUD_EMAIL_NOTIFICATIONS")
+            .map(|v| v.parse::<bool>()) {
+            if let Ok(val) = enabled {
+                config.mail.notifications = val;
+            }
+        }
+        
+        if let Ok(path) = env::var("OXICLOUD_EMAIL_TEMPLATES_PATH") {
+            if !path.trim().is_empty() {
+                config.mail.templates_path = Some(PathBuf::from(path.trim()));
+            }
+        }
+        
+        if let Ok(percent) = env::var("OXICLOUD_EMAIL_QUOTA_WARNING_PERCENT")
+            .map(|v| v.parse::<u8>()) {
+            if let Ok(val) = percent {
+                config.mail.quota_warning_percent = val.clamp(1, 100);
+            }
+        }
+        
+        // Autenticación contra LDAP / Active Directory
+        if let Ok(enabled) = env::var("OXICLOUD_LDAP_ENABLED")
+            .map(|v| v.parse::<bool>()) {
+            if let Ok(val) = enabled {
+                config.ldap.enabled = val;
+            }
+        }
+        
+        if let Ok(url) = env::var("OXICLOUD_LDAP_URL") {
+            config.ldap.url = url.trim().to_string();
+        }
+        
+        if let Ok(starttls) = env::var("OXICLOUD_LDAP_STARTTLS")
+            .map(|v| v.parse::<bool>()) {
+            if let Ok(val) = starttls {
+                config.ldap.starttls = val;
+            }
+        }
+        
+        if let Ok(bind_dn) = env::var("OXICLOUD_LDAP_BIND_DN") {
+            config.ldap.bind_dn = bind_dn;
+        }
+        
+        if let Ok(bind_password) = env::var("OXICLOUD_LDAP_BIND_PASSWORD") {
+            config.ldap.bind_password = bind_password;
+        }
+        
+        if let Ok(base_dn) = env::var("OXICLOUD_LDAP_BASE_DN") {
+            config.ldap.base_dn = base_dn;
+        }
+        
+        if let Ok(filter) = env::var("OXICLOUD_LDAP_USER_FILTER") {
+            config.ldap.user_filter = filter;
+        }
+        
+        if let Ok(attribute) = env::var("OXICLOUD_LDAP_USERNAME_ATTRIBUTE") {
+            config.ldap.username_attribute = attribute;
+        }
+        
+        if let Ok(attribute) = env::var("OXICLOUD_LDAP_EMAIL_ATTRIBUTE") {
+            config.ldap.email_attribute = attribute;
+        }
+        
+        if let Ok(attribute) = env::var("OXICLOUD_LDAP_GROUP_ATTRIBUTE") {
+            config.ldap.group_attribute = attribute;
+        }
+        
+        if let Ok(filter) = env::var("OXICLOUD_LDAP_GROUP_FILTER") {
+            config.ldap.group_filter = Some(filter).filter(|f| !f.trim().is_empty());
+        }
+        
+        if let Ok(base_dn) = env::var("OXICLOUD_LDAP_GROUP_BASE_DN") {
+            config.ldap.group_base_dn = Some(base_dn).filter(|dn| !dn.trim().is_empty());
+        }
+        
+        if let Ok(group) = env::var("OXICLOUD_LDAP_ADMIN_GROUP") {
+            config.ldap.admin_group = Some(group).filter(|g| !g.trim().is_empty());
+        }
+        
+        if let Ok(group) = env::var("OXICLOUD_LDAP_REQUIRED_GROUP") {
+            config.ldap.required_group = Some(group).filter(|g| !g.trim().is_empty());
+        }
+        
+        if let Ok(allow) = env::var("OXICLOUD_LDAP_ALLOW_LOCAL_USERS")
+            .map(|v| v.parse::<bool>()) {
+            if let Ok(val) = allow {
+                config.ldap.allow_local_users = val;
+            }
+        }
+        
+        if let Ok(timeout) = env::var("OXICLOUD_LDAP_TIMEOUT_SECS")
+            .map(|v| v.parse::<u64>()) {
+            if let Ok(val) = timeout {
+                config.ldap.timeout_secs = val.max(1);
+            }
+        }
+        
         config
     }
     
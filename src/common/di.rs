@@ -5,6 +5,8 @@ use sqlx::PgPool;
 
 use crate::domain::services::auth_service::AuthService;
 use crate::application::services::auth_application_service::AuthApplicationService;
+use crate::application::services::oidc_service::OidcLoginService;
+use crate::application::services::audit_log_service::AuditLogService;
 
 use crate::domain::services::path_service::PathService;
 use crate::infrastructure::repositories::folder_fs_repository::FolderFsRepository;
@@ -196,7 +198,7 @@ impl AppServiceFactory {
         // Servicios principales
         let folder_service = Arc::new(FolderService::new(
             repos.folder_repository.clone()
-        ));
+        ).with_file_storage(repos.file_repository.clone()));
         
         // Antiguo servicio único
         let file_service = Arc::new(FileService::new(
@@ -297,6 +299,10 @@ pub struct ApplicationServices {
 pub struct AuthServices {
     pub auth_service: Arc<AuthService>,
     pub auth_application_service: Arc<AuthApplicationService>,
+    /// Inicio de sesión con el proveedor OpenID Connect, si está configurado
+    pub oidc_service: Option<Arc<OidcLoginService>>,
+    /// Registro de auditoría de accesos, cambios de rol y acciones de administración
+    pub audit_log_service: Arc<AuditLogService>,
 }
 
 /// Estado global de la aplicación para dependency injection
@@ -314,6 +320,21 @@ pub struct AppState {
     pub storage_usage_service: Option<Arc<dyn crate::application::ports::storage_ports::StorageUsagePort>>,
     pub calendar_service: Option<Arc<dyn crate::application::ports::storage_ports::StorageUseCase>>,
     pub contact_service: Option<Arc<dyn crate::application::ports::storage_ports::StorageUseCase>>,
+    pub file_attribute_service: Option<Arc<dyn crate::application::ports::file_attribute_ports::FileAttributePort>>,
+    pub hidden_file_rules: Option<Arc<dyn crate::application::ports::hidden_file_ports::HiddenFileRulesPort>>,
+    pub lock_service: Option<Arc<dyn crate::application::ports::lock_ports::LockUseCase>>,
+    pub dead_property_service: Option<Arc<dyn crate::application::ports::dead_property_ports::DeadPropertyUseCase>>,
+    pub sync_conflict_service: Option<Arc<dyn crate::application::ports::sync_conflict_ports::SyncConflictUseCase>>,
+    pub feature_flags: Option<Arc<dyn crate::application::ports::feature_flag_ports::FeatureFlagUseCase>>,
+    pub quota_service: Option<Arc<dyn crate::application::ports::quota_ports::StorageQuotaUseCase>>,
+    pub ownership_service: Option<Arc<dyn crate::application::ports::ownership_ports::ItemOwnershipUseCase>>,
+    pub undo_service: Option<Arc<dyn crate::application::ports::undo_ports::UndoUseCase>>,
+    pub dav_sync_service: Option<Arc<dyn crate::application::ports::dav_sync_ports::DavSyncUseCase>>,
+    pub dav_principal_service: Option<Arc<dyn crate::application::ports::dav_principal_ports::DavPrincipalUseCase>>,
+    pub scheduling_service: Option<Arc<dyn crate::application::ports::scheduling_ports::SchedulingUseCase>>,
+    pub carddav_service: Option<Arc<dyn crate::application::ports::carddav_ports::CardDavUseCase>>,
+    pub dav_transfer_service: Option<Arc<dyn crate::application::ports::dav_transfer_ports::DavTransferUseCase>>,
+    pub tag_service: Option<Arc<dyn crate::application::ports::tag_ports::TagUseCase>>,
 }
 
 impl Default for AppState {
@@ -420,6 +441,11 @@ impl Default for AppState {
                 let empty_stream = futures::stream::empty::<Result<bytes::Bytes, std::io::Error>>();
                 Ok(Box::new(empty_stream))
             }
+            
+            async fn get_file_range(&self, _id: &str, _start: u64, _length: u64) -> Result<Box<dyn futures::Stream<Item = Result<bytes::Bytes, std::io::Error>> + Send>, crate::common::errors::DomainError> {
+                let empty_stream = futures::stream::empty::<Result<bytes::Bytes, std::io::Error>>();
+                Ok(Box::new(empty_stream))
+            }
         }
         
         struct DummyFileWritePort;
@@ -486,6 +512,11 @@ impl Default for AppState {
                 Ok(Box::new(empty_stream))
             }
             
+            async fn get_file_range(&self, _id: &str, _start: u64, _length: u64) -> Result<Box<dyn futures::Stream<Item = Result<bytes::Bytes, std::io::Error>> + Send>, crate::common::errors::DomainError> {
+                let empty_stream = futures::stream::empty::<Result<bytes::Bytes, std::io::Error>>();
+                Ok(Box::new(empty_stream))
+            }
+            
             async fn move_file(&self, _file_id: &str, _target_folder_id: Option<String>) -> Result<crate::domain::entities::file::File, crate::common::errors::DomainError> {
                 Ok(crate::domain::entities::file::File::default())
             }
@@ -615,6 +646,10 @@ impl Default for AppState {
                 Ok(crate::application::dtos::folder_dto::FolderDto::default())
             }
             
+            async fn copy_folder(&self, _id: &str, _dto: crate::application::dtos::folder_dto::CopyFolderDto) -> Result<crate::application::dtos::folder_dto::FolderDto, crate::common::errors::DomainError> {
+                Ok(crate::application::dtos::folder_dto::FolderDto::default())
+            }
+            
             async fn delete_folder(&self, _id: &str) -> Result<(), crate::common::errors::DomainError> {
                 Ok(())
             }
@@ -667,6 +702,15 @@ impl Default for AppState {
                 Ok(Box::new(empty_stream))
             }
             
+            async fn get_file_range(&self, _id: &str, _start: u64, _length: u64) -> Result<Box<dyn futures::Stream<Item = Result<bytes::Bytes, std::io::Error>> + Send>, crate::common::errors::DomainError> {
+                let empty_stream = futures::stream::empty::<Result<bytes::Bytes, std::io::Error>>();
+                Ok(Box::new(empty_stream))
+            }
+            
+            async fn write_file_range(&self, _id: &str, _offset: u64, _content: &[u8]) -> Result<crate::application::dtos::file_dto::FileDto, crate::common::errors::DomainError> {
+                Ok(crate::application::dtos::file_dto::FileDto::default())
+            }
+            
             async fn move_file(&self, _file_id: &str, _folder_id: Option<String>) -> Result<crate::application::dtos::file_dto::FileDto, crate::common::errors::DomainError> {
                 Ok(crate::application::dtos::file_dto::FileDto::default())
             }
@@ -706,6 +750,11 @@ impl Default for AppState {
                 let empty_stream = futures::stream::empty::<Result<bytes::Bytes, std::io::Error>>();
                 Ok(Box::new(empty_stream))
             }
+            
+            async fn get_file_range(&self, _id: &str, _start: u64, _length: u64) -> Result<Box<dyn futures::Stream<Item = Result<bytes::Bytes, std::io::Error>> + Send>, crate::common::errors::DomainError> {
+                let empty_stream = futures::stream::empty::<Result<bytes::Bytes, std::io::Error>>();
+                Ok(Box::new(empty_stream))
+            }
         }
         
         struct DummyFileManagementUseCase;
@@ -789,11 +838,22 @@ impl Default for AppState {
         impl crate::application::ports::inbound::SearchUseCase for DummySearchUseCase {
             async fn search(
                 &self, 
-                _criteria: crate::application::dtos::search_dto::SearchCriteriaDto
+                _criteria: crate::application::dtos::search_dto::SearchCriteriaDto,
+                _caller: crate::application::ports::share_ports::ShareRecipient<'_>,
+                _is_admin: bool
             ) -> Result<crate::application::dtos::search_dto::SearchResultsDto, crate::common::errors::DomainError> {
                 Ok(crate::application::dtos::search_dto::SearchResultsDto::empty())
             }
             
+            async fn search_all(
+                &self,
+                query: crate::application::dtos::search_dto::UnifiedSearchQueryDto,
+                _caller: crate::application::ports::share_ports::ShareRecipient<'_>,
+                _is_admin: bool
+            ) -> Result<crate::application::dtos::search_dto::UnifiedSearchResultsDto, crate::common::errors::DomainError> {
+                Ok(crate::application::dtos::search_dto::UnifiedSearchResultsDto { query: query.query, groups: Vec::new() })
+            }
+            
             async fn clear_search_cache(&self) -> Result<(), crate::common::errors::DomainError> {
                 Ok(())
             }
@@ -829,6 +889,21 @@ impl Default for AppState {
             storage_usage_service: None,
             calendar_service: None,
             contact_service: None,
+            file_attribute_service: None,
+            hidden_file_rules: None,
+            lock_service: None,
+            dead_property_service: None,
+            sync_conflict_service: None,
+            feature_flags: None,
+            quota_service: None,
+            ownership_service: None,
+            undo_service: None,
+            dav_sync_service: None,
+            dav_principal_service: None,
+            scheduling_service: None,
+            carddav_service: None,
+            dav_transfer_service: None,
+            tag_service: None,
         }
     }
 }
@@ -852,6 +927,21 @@ impl AppState {
             storage_usage_service: None,
             calendar_service: None,
             contact_service: None,
+            file_attribute_service: None,
+            hidden_file_rules: None,
+            lock_service: None,
+            dead_property_service: None,
+            sync_conflict_service: None,
+            feature_flags: None,
+            quota_service: None,
+            ownership_service: None,
+            undo_service: None,
+            dav_sync_service: None,
+            dav_principal_service: None,
+            scheduling_service: None,
+            carddav_service: None,
+            dav_transfer_service: None,
+            tag_service: None,
         }
     }
     
@@ -899,4 +989,79 @@ impl AppState {
         self.contact_service = Some(contact_service);
         self
     }
+    
+    pub fn with_file_attribute_service(mut self, file_attribute_service: Arc<dyn crate::application::ports::file_attribute_ports::FileAttributePort>) -> Self {
+        self.file_attribute_service = Some(file_attribute_service);
+        self
+    }
+    
+    pub fn with_hidden_file_rules(mut self, hidden_file_rules: Arc<dyn crate::application::ports::hidden_file_ports::HiddenFileRulesPort>) -> Self {
+        self.hidden_file_rules = Some(hidden_file_rules);
+        self
+    }
+    
+    pub fn with_lock_service(mut self, lock_service: Arc<dyn crate::application::ports::lock_ports::LockUseCase>) -> Self {
+        self.lock_service = Some(lock_service);
+        self
+    }
+    
+    pub fn with_dead_property_service(mut self, dead_property_service: Arc<dyn crate::application::ports::dead_property_ports::DeadPropertyUseCase>) -> Self {
+        self.dead_property_service = Some(dead_property_service);
+        self
+    }
+    
+    pub fn with_sync_conflict_service(mut self, sync_conflict_service: Arc<dyn crate::application::ports::sync_conflict_ports::SyncConflictUseCase>) -> Self {
+        self.sync_conflict_service = Some(sync_conflict_service);
+        self
+    }
+    
+    pub fn with_feature_flags(mut self, feature_flags: Arc<dyn crate::application::ports::feature_flag_ports::FeatureFlagUseCase>) -> Self {
+        self.feature_flags = Some(feature_flags);
+        self
+    }
+    
+    pub fn with_quota_service(mut self, quota_service: Arc<dyn crate::application::ports::quota_ports::StorageQuotaUseCase>) -> Self {
+        self.quota_service = Some(quota_service);
+        self
+    }
+    
+    pub fn with_ownership_service(mut self, ownership_service: Arc<dyn crate::application::ports::ownership_ports::ItemOwnershipUseCase>) -> Self {
+        self.ownership_service = Some(ownership_service);
+        self
+    }
+    
+    pub fn with_undo_service(mut self, undo_service: Arc<dyn crate::application::ports::undo_ports::UndoUseCase>) -> Self {
+        self.undo_service = Some(undo_service);
+        self
+    }
+    
+    pub fn with_dav_sync_service(mut self, dav_sync_service: Arc<dyn crate::application::ports::dav_sync_ports::DavSyncUseCase>) -> Self {
+        self.dav_sync_service = Some(dav_sync_service);
+        self
+    }
+    
+    pub fn with_dav_principal_service(mut self, dav_principal_service: Arc<dyn crate::application::ports::dav_principal_ports::DavPrincipalUseCase>) -> Self {
+        self.dav_principal_service = Some(dav_principal_service);
+        self
+    }
+    
+    pub fn with_scheduling_service(mut self, scheduling_service: Arc<dyn crate::application::ports::scheduling_ports::SchedulingUseCase>) -> Self {
+        self.scheduling_service = Some(scheduling_service);
+        self
+    }
+    
+    pub fn with_carddav_service(mut self, carddav_service: Arc<dyn crate::application::ports::carddav_ports::CardDavUseCase>) -> Self {
+        self.carddav_service = Some(carddav_service);
+        self
+    }
+    
+    pub fn with_dav_transfer_service(mut self, dav_transfer_service: Arc<dyn crate::application::ports::dav_transfer_ports::DavTransferUseCase>) -> Self {
+        self.dav_transfer_service = Some(dav_transfer_service);
+        self
+    }
+    
+    pub fn with_tag_service(mut self, tag_service: Arc<dyn crate::application::ports::tag_ports::TagUseCase>) -> Self {
+        self.tag_service = Some(tag_service);
+        self
+    }
 }
\ No newline at end of file
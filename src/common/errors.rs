@@ -26,6 +26,14 @@ pub enum ErrorKind {
     UnsupportedOperation,
     /// Error de base de datos
     DatabaseError,
+    /// Servicio o recurso remoto no disponible
+    Unavailable,
+    /// Recurso bloqueado por otro cliente (bloqueos WebDAV)
+    Locked,
+    /// Cuota de almacenamiento del usuario agotada
+    QuotaExceeded,
+    /// Una condición de la petición (If-Match, If-None-Match) no se cumple
+    PreconditionFailed,
 }
 
 impl Display for ErrorKind {
@@ -40,6 +48,10 @@ impl Display for ErrorKind {
             ErrorKind::NotImplemented => write!(f, "Not Implemented"),
             ErrorKind::UnsupportedOperation => write!(f, "Unsupported Operation"),
             ErrorKind::DatabaseError => write!(f, "Database Error"),
+            ErrorKind::Unavailable => write!(f, "Unavailable"),
+            ErrorKind::Locked => write!(f, "Locked"),
+            ErrorKind::QuotaExceeded => write!(f, "Quota Exceeded"),
+            ErrorKind::PreconditionFailed => write!(f, "Precondition Failed"),
         }
     }
 }
@@ -121,6 +133,42 @@ impl DomainError {
         }
     }
     
+    /// Crea un error de recurso no disponible
+    pub fn unavailable<S: Into<String>>(entity_type: &'static str, message: S) -> Self {
+        Self::new(
+            ErrorKind::Unavailable,
+            entity_type,
+            message,
+        )
+    }
+    
+    /// Crea un error de recurso bloqueado
+    pub fn locked<S: Into<String>>(entity_type: &'static str, message: S) -> Self {
+        Self::new(
+            ErrorKind::Locked,
+            entity_type,
+            message,
+        )
+    }
+    
+    /// Crea un error de cuota de almacenamiento superada
+    pub fn quota_exceeded<S: Into<String>>(entity_type: &'static str, message: S) -> Self {
+        Self::new(
+            ErrorKind::QuotaExceeded,
+            entity_type,
+            message,
+        )
+    }
+    
+    /// Crea un error de precondición de la petición no cumplida
+    pub fn precondition_failed<S: Into<String>>(entity_type: &'static str, message: S) -> Self {
+        Self::new(
+            ErrorKind::PreconditionFailed,
+            entity_type,
+            message,
+        )
+    }
+    
     /// Crea un error interno
     pub fn internal_error<S: Into<String>>(entity_type: &'static str, message: S) -> Self {
         Self {
@@ -336,6 +384,10 @@ impl From<DomainError> for AppError {
             ErrorKind::NotImplemented => axum::http::StatusCode::NOT_IMPLEMENTED,
             ErrorKind::UnsupportedOperation => axum::http::StatusCode::METHOD_NOT_ALLOWED,
             ErrorKind::DatabaseError => axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorKind::Unavailable => axum::http::StatusCode::SERVICE_UNAVAILABLE,
+            ErrorKind::Locked => axum::http::StatusCode::LOCKED,
+            ErrorKind::QuotaExceeded => axum::http::StatusCode::INSUFFICIENT_STORAGE,
+            ErrorKind::PreconditionFailed => axum::http::StatusCode::PRECONDITION_FAILED,
         };
         
         Self {
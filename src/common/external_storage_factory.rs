@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use crate::application::ports::credential_ports::CredentialVaultPort;
+use crate::application::ports::external_storage_ports::ExternalStoragePort;
+use crate::application::services::external_storage_service::ExternalStorageService;
+use crate::common::config::{ExternalMountConfig, ExternalStorageConfig};
+use crate::common::errors::DomainError;
+use crate::infrastructure::services::sftp_storage_service::SftpStorageAdapter;
+use crate::infrastructure::services::smb_storage_service::SmbStorageAdapter;
+use crate::infrastructure::services::snapshot_storage_service::SnapshotStorageAdapter;
+
+/// Crea el servicio de almacenamiento externo con los montajes configurados.
+///
+/// Los montajes que no se pueden inicializar se registran en el log y se
+/// omiten para no impedir el arranque del servidor.
+pub async fn create_external_storage_service(
+    config: &ExternalStorageConfig,
+    vault: Arc<dyn CredentialVaultPort>,
+) -> ExternalStorageService {
+    let mut service = ExternalStorageService::new()
+        .with_change_tracking(config.change_scan_depth, config.max_tracked_changes);
+
+    for mount in &config.mounts {
+        match create_backend(mount, config, vault.as_ref()).await {
+            Ok(backend) => {
+                service = service
+                    .with_mount(&mount.name, backend)
+                    .with_mount_access(&mount.name, mount.allowed_users.clone(), mount.allowed_groups.clone());
+            },
+            Err(e) => {
+                tracing::error!("Failed to initialize external mount '{}': {}", mount.name, e);
+            }
+        }
+    }
+
+    service
+}
+
+async fn create_backend(
+    mount: &ExternalMountConfig,
+    config: &ExternalStorageConfig,
+    vault: &dyn CredentialVaultPort,
+) -> Result<Arc<dyn ExternalStoragePort>, DomainError> {
+    // Obtener credenciales del almacén si el montaje las referencia
+    let credential = match &mount.credential {
+        Some(key) => Some(vault.get_credential(key).await?),
+        None => None,
+    };
+
+    match mount.backend.as_str() {
+        "sftp" => Ok(Arc::new(SftpStorageAdapter::new(mount, credential, config)?)),
+        "smb" | "cifs" => Ok(Arc::new(SmbStorageAdapter::new(mount, credential, config)?)),
+        // Las instantáneas son siempre de solo lectura
+        "snapshot" => Ok(Arc::new(SnapshotStorageAdapter::new(mount)?)),
+        other => Err(DomainError::validation_error(format!(
+            "Unsupported external storage backend: {}", other
+        ))),
+    }
+}
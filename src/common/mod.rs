@@ -1,6 +1,8 @@
 pub mod errors;
+pub mod bounded_buffer;
 pub mod config;
 pub mod cache;
 pub mod di;
 pub mod db;
-pub mod auth_factory;
\ No newline at end of file
+pub mod auth_factory;
+pub mod external_storage_factory;
\ No newline at end of file
@@ -0,0 +1,130 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+/// Acción que queda registrada en la actividad
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityAction {
+    Upload,
+    /// Cambio del contenido o traslado de un archivo
+    Update,
+    Rename,
+    /// Envío a la papelera o borrado definitivo
+    Delete,
+    Share,
+    /// Recuperación desde la papelera
+    Restore,
+}
+
+impl ActivityAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Upload => "upload",
+            Self::Update => "update",
+            Self::Rename => "rename",
+            Self::Delete => "delete",
+            Self::Share => "share",
+            Self::Restore => "restore",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "upload" => Some(Self::Upload),
+            "update" => Some(Self::Update),
+            "rename" => Some(Self::Rename),
+            "delete" => Some(Self::Delete),
+            "share" => Some(Self::Share),
+            "restore" => Some(Self::Restore),
+            _ => None,
+        }
+    }
+}
+
+/// Tipo del elemento sobre el que se actúa
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityTargetKind {
+    File,
+    Folder,
+}
+
+impl ActivityTargetKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::File => "file",
+            Self::Folder => "folder",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "file" => Some(Self::File),
+            "folder" => Some(Self::Folder),
+            _ => None,
+        }
+    }
+}
+
+/// Algo que un usuario hizo con un archivo o una carpeta. Se guardan los
+/// nombres del momento, porque el elemento puede cambiar de nombre o
+/// desaparecer después
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Activity {
+    pub id: String,
+    pub actor_id: String,
+    pub actor_name: String,
+    pub action: ActivityAction,
+    pub target_kind: ActivityTargetKind,
+    pub target_id: String,
+    pub target_name: String,
+    /// Carpeta que contiene el elemento; `None` en la raíz
+    pub folder_id: Option<String>,
+    /// Dato adicional de la acción, como el nombre anterior en un renombrado
+    pub details: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Activity {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        actor_id: String,
+        actor_name: String,
+        action: ActivityAction,
+        target_kind: ActivityTargetKind,
+        target_id: String,
+        target_name: String,
+        folder_id: Option<String>,
+        details: Option<String>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            actor_id,
+            actor_name,
+            action,
+            target_kind,
+            target_id,
+            target_name,
+            folder_id,
+            details,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_round_trip() {
+        for action in [ActivityAction::Upload, ActivityAction::Rename, ActivityAction::Delete, ActivityAction::Share, ActivityAction::Restore] {
+            assert_eq!(ActivityAction::parse(action.as_str()), Some(action));
+        }
+        for kind in [ActivityTargetKind::File, ActivityTargetKind::Folder] {
+            assert_eq!(ActivityTargetKind::parse(kind.as_str()), Some(kind));
+        }
+        assert_eq!(ActivityAction::parse("download"), None);
+    }
+}
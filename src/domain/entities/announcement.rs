@@ -0,0 +1,157 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+/// Longitud máxima del título de un anuncio (columna VARCHAR(200))
+pub const MAX_TITLE_LENGTH: usize = 200;
+
+/// Longitud máxima del texto de un anuncio
+pub const MAX_MESSAGE_LENGTH: usize = 10_000;
+
+/// Gravedad de un anuncio; decide cómo se muestra y cuál ocupa el banner
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnnouncementSeverity {
+    #[default]
+    Info,
+    Warning,
+    Critical,
+}
+
+impl AnnouncementSeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Critical => "critical",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "info" => Some(Self::Info),
+            "warning" => Some(Self::Warning),
+            "critical" => Some(Self::Critical),
+            _ => None,
+        }
+    }
+}
+
+/// Mensaje que un administrador difunde a todos los usuarios o a algunos grupos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Announcement {
+    pub id: String,
+    pub title: String,
+    pub message: String,
+    pub severity: AnnouncementSeverity,
+    /// Inicio de la ventana en la que se muestra
+    pub starts_at: DateTime<Utc>,
+    /// Fin de la ventana; sin fin se muestra hasta que se borre
+    pub ends_at: Option<DateTime<Utc>>,
+    /// Grupos destinatarios; vacío significa todos los usuarios
+    pub target_groups: Vec<String>,
+    /// Administrador que lo creó
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Announcement {
+    pub fn new(
+        title: String,
+        message: String,
+        severity: AnnouncementSeverity,
+        starts_at: DateTime<Utc>,
+        ends_at: Option<DateTime<Utc>>,
+        target_groups: Vec<String>,
+        created_by: Option<String>,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            title,
+            message,
+            severity,
+            starts_at,
+            ends_at,
+            target_groups,
+            created_by,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Comprueba si el anuncio está dentro de su ventana en un instante
+    pub fn is_active_at(&self, now: DateTime<Utc>) -> bool {
+        self.starts_at <= now && self.ends_at.is_none_or(|ends_at| now < ends_at)
+    }
+
+    /// Comprueba si va dirigido a alguien que pertenece a `groups`
+    pub fn targets(&self, groups: &[String]) -> bool {
+        self.target_groups.is_empty() || self.target_groups.iter().any(|group| groups.contains(group))
+    }
+}
+
+/// Lecturas de un anuncio frente a los usuarios a los que va dirigido
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AnnouncementAcknowledgment {
+    /// Usuarios destinatarios que lo han leído
+    pub read_count: i64,
+    /// Usuarios activos a los que va dirigido
+    pub target_count: i64,
+}
+
+impl AnnouncementAcknowledgment {
+    /// Fracción (0-1) de destinatarios que lo han leído
+    pub fn rate(&self) -> f64 {
+        if self.target_count <= 0 {
+            return 0.0;
+        }
+        (self.read_count as f64 / self.target_count as f64).min(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn announcement(ends_in: Option<Duration>, groups: &[&str]) -> Announcement {
+        let now = Utc::now();
+        Announcement::new(
+            "Maintenance".to_string(),
+            "Downtime tonight".to_string(),
+            AnnouncementSeverity::Warning,
+            now - Duration::hours(1),
+            ends_in.map(|d| now + d),
+            groups.iter().map(|g| g.to_string()).collect(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_active_window() {
+        let now = Utc::now();
+        assert!(announcement(None, &[]).is_active_at(now));
+        assert!(announcement(Some(Duration::hours(1)), &[]).is_active_at(now));
+        assert!(!announcement(Some(Duration::hours(-1) + Duration::minutes(30)), &[]).is_active_at(now));
+        assert!(!announcement(None, &[]).is_active_at(now - Duration::hours(2)));
+    }
+
+    #[test]
+    fn test_targets_groups() {
+        let user = vec!["user".to_string()];
+        assert!(announcement(None, &[]).targets(&user));
+        assert!(announcement(None, &["admin", "user"]).targets(&user));
+        assert!(!announcement(None, &["admin"]).targets(&user));
+    }
+
+    #[test]
+    fn test_severity_order_and_rate() {
+        assert!(AnnouncementSeverity::Critical > AnnouncementSeverity::Warning);
+        assert_eq!(AnnouncementSeverity::parse("critical"), Some(AnnouncementSeverity::Critical));
+        assert_eq!(AnnouncementSeverity::parse("urgent"), None);
+        assert_eq!(AnnouncementAcknowledgment { read_count: 1, target_count: 4 }.rate(), 0.25);
+        assert_eq!(AnnouncementAcknowledgment::default().rate(), 0.0);
+    }
+}
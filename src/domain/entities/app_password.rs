@@ -0,0 +1,109 @@
+use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
+use argon2::password_hash::SaltString;
+use chrono::{DateTime, Utc};
+use rand_core::{OsRng, RngCore};
+use uuid::Uuid;
+
+use crate::common::errors::DomainError;
+
+/// Caracteres de las contraseñas generadas: 32 símbolos sin los que se
+/// confunden al copiarlos a mano (`l`, `o`, `0`, `1`)
+const ALPHABET: &[u8; 32] = b"abcdefghijkmnpqrstuvwxyz23456789";
+
+/// Grupos de caracteres de una contraseña generada (`xxxxx-xxxxx-...`)
+const GROUPS: usize = 4;
+const GROUP_LEN: usize = 5;
+
+/// Contraseña de aplicación: credencial propia de un dispositivo o cliente
+/// (WebDAV, CalDAV, CardDAV o un script que usa la API), que se puede revocar
+/// sin cambiar la contraseña de la cuenta.
+///
+/// Solo se guarda el hash; la contraseña en claro se muestra una vez, al
+/// generarla.
+#[derive(Debug, Clone)]
+pub struct AppPassword {
+    pub id: String,
+    pub user_id: String,
+    /// Nombre que el usuario da al cliente ("Móvil", "Thunderbird"...)
+    pub label: String,
+    pub password_hash: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl AppPassword {
+    /// Genera una contraseña nueva para `user_id`; devuelve la entidad y la
+    /// contraseña en claro
+    pub fn generate(user_id: &str, label: &str) -> Result<(Self, String), DomainError> {
+        let label = label.trim();
+        if label.is_empty() || label.chars().count() > 100 {
+            return Err(DomainError::validation_error("The label must have between 1 and 100 characters"));
+        }
+
+        let mut bytes = [0u8; GROUPS * GROUP_LEN];
+        OsRng.fill_bytes(&mut bytes);
+        let password = bytes
+            .chunks(GROUP_LEN)
+            .map(|group| group.iter().map(|b| ALPHABET[(b & 31) as usize] as char).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("-");
+
+        let salt = SaltString::generate(&mut OsRng);
+        let password_hash = Argon2::default().hash_password(password.as_bytes(), &salt)
+            .map_err(|e| DomainError::internal_error("AppPassword", format!("Error al generar hash: {}", e)))?
+            .to_string();
+
+        let app_password = Self {
+            id: Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            label: label.to_string(),
+            password_hash,
+            created_at: Utc::now(),
+            last_used_at: None,
+        };
+        Ok((app_password, password))
+    }
+
+    /// Comprueba una contraseña contra el hash guardado. Los guiones son
+    /// opcionales y no se distinguen mayúsculas, para perdonar la copia a mano.
+    pub fn verify(&self, password: &str) -> bool {
+        let compact: String = password.chars().filter(|c| *c != '-' && !c.is_whitespace()).collect();
+        if compact.len() != GROUPS * GROUP_LEN {
+            return false;
+        }
+        let normalized = compact.to_lowercase()
+            .as_bytes()
+            .chunks(GROUP_LEN)
+            .map(|group| String::from_utf8_lossy(group).into_owned())
+            .collect::<Vec<_>>()
+            .join("-");
+
+        PasswordHash::new(&self.password_hash)
+            .map(|hash| Argon2::default().verify_password(normalized.as_bytes(), &hash).is_ok())
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_password_verifies() {
+        let (app_password, password) = AppPassword::generate("user-1", " Phone ").unwrap();
+        assert_eq!(app_password.label, "Phone");
+        assert_eq!(password.len(), GROUPS * GROUP_LEN + GROUPS - 1);
+        assert!(password.bytes().all(|b| b == b'-' || ALPHABET.contains(&b)));
+
+        assert!(app_password.verify(&password));
+        assert!(app_password.verify(&password.replace('-', "").to_uppercase()));
+        assert!(!app_password.verify("abcde-abcde-abcde-abcde"));
+        assert!(!app_password.verify(""));
+    }
+
+    #[test]
+    fn test_label_is_required() {
+        assert!(AppPassword::generate("user-1", "  ").is_err());
+        assert!(AppPassword::generate("user-1", &"x".repeat(101)).is_err());
+    }
+}
@@ -0,0 +1,95 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+/// Tipo de suceso de seguridad que queda en el registro de auditoría
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    /// Inicio de sesión correcto, con contraseña o con un proveedor externo
+    Login,
+    /// Intento de inicio de sesión rechazado
+    LoginFailed,
+    /// Cambio del rol, y con él de los permisos, de un usuario
+    RoleChanged,
+    /// Petición de un administrador que modifica algo
+    AdminAction,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Login => "login",
+            Self::LoginFailed => "login_failed",
+            Self::RoleChanged => "role_changed",
+            Self::AdminAction => "admin_action",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "login" => Some(Self::Login),
+            "login_failed" => Some(Self::LoginFailed),
+            "role_changed" => Some(Self::RoleChanged),
+            "admin_action" => Some(Self::AdminAction),
+            _ => None,
+        }
+    }
+}
+
+/// Entrada del registro de auditoría. Una vez guardada no se modifica ni se
+/// borra, ni siquiera al borrar al usuario que menciona
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEvent {
+    pub id: String,
+    pub occurred_at: DateTime<Utc>,
+    pub action: AuditAction,
+    /// Usuario que actuó; `None` si no se llegó a identificar
+    pub actor_id: Option<String>,
+    /// Nombre de usuario, también el que se intentó en un acceso fallido
+    pub actor_name: Option<String>,
+    /// Sobre qué se actuó: el usuario cuyo rol cambia o la ruta de
+    /// administración
+    pub target: Option<String>,
+    pub ip_address: Option<String>,
+    pub success: bool,
+    /// Causa de un fallo o detalle del cambio
+    pub details: Option<String>,
+}
+
+impl AuditEvent {
+    pub fn new(
+        action: AuditAction,
+        actor_id: Option<String>,
+        actor_name: Option<String>,
+        target: Option<String>,
+        ip_address: Option<String>,
+        success: bool,
+        details: Option<String>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            occurred_at: Utc::now(),
+            action,
+            actor_id,
+            actor_name,
+            target,
+            ip_address,
+            success,
+            details,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_round_trip() {
+        for action in [AuditAction::Login, AuditAction::LoginFailed, AuditAction::RoleChanged, AuditAction::AdminAction] {
+            assert_eq!(AuditAction::parse(action.as_str()), Some(action));
+        }
+        assert_eq!(AuditAction::parse("logout"), None);
+    }
+}
@@ -14,6 +14,7 @@ use chrono::{DateTime, Utc};
 use thiserror::Error;
 
 use crate::common::errors::{Result, DomainError, ErrorKind};
+use crate::domain::services::timezone_service::VTimeZone;
 
 /**
  * Error types specific to calendar operations.
@@ -64,6 +65,14 @@ pub struct Calendar {
     
     /// Optional list of custom properties (for extended CalDAV support)
     custom_properties: std::collections::HashMap<String, String>,
+    
+    /// Revision of the calendar contents, bumped by the change journal
+    /// whenever an event is created, modified or deleted
+    sync_revision: i64,
+    
+    /// CalDAV calendar-timezone property: an iCalendar object with a single
+    /// VTIMEZONE, used to interpret floating times (None means UTC)
+    timezone: Option<String>,
 }
 
 impl Calendar {
@@ -130,6 +139,8 @@ impl Calendar {
             created_at: now,
             updated_at: now,
             custom_properties: std::collections::HashMap::new(),
+            sync_revision: 0,
+            timezone: None,
         })
     }
     
@@ -181,6 +192,8 @@ impl Calendar {
             created_at,
             updated_at,
             custom_properties: std::collections::HashMap::new(),
+            sync_revision: 0,
+            timezone: None,
         })
     }
     
@@ -231,6 +244,53 @@ impl Calendar {
         &self.custom_properties
     }
     
+    /// Returns the revision of the calendar contents
+    pub fn sync_revision(&self) -> i64 {
+        self.sync_revision
+    }
+    
+    /// Returns the calendar-timezone property, if the calendar has one
+    pub fn timezone(&self) -> Option<&str> {
+        self.timezone.as_deref()
+    }
+    
+    /// Returns the parsed VTIMEZONE of the calendar, if it has one
+    pub fn vtimezone(&self) -> Option<VTimeZone> {
+        self.timezone.as_deref().and_then(VTimeZone::parse)
+    }
+    
+    /**
+     * Returns the collection tag (CalendarServer `getctag`).
+     * 
+     * The ctag changes whenever any event in the calendar changes, so clients
+     * can skip a full synchronization when it matches the one they stored.
+     */
+    pub fn ctag(&self) -> String {
+        format!("{}-{}", self.id.simple(), self.sync_revision)
+    }
+    
+    /**
+     * Sets the revision loaded from storage.
+     * 
+     * @param sync_revision Current revision of the calendar contents
+     * @return The calendar with the given revision
+     */
+    pub fn with_sync_revision(mut self, sync_revision: i64) -> Self {
+        self.sync_revision = sync_revision;
+        self
+    }
+    
+    /**
+     * Sets the timezone loaded from storage.
+     * 
+     * @param timezone calendar-timezone property of the calendar
+     * @return The calendar with the given timezone
+     */
+    pub fn with_timezone(mut self, timezone: Option<String>) -> Self {
+        self.timezone = timezone;
+        self
+    }
+    
     // Setters and Mutators
     
     /**
@@ -295,6 +355,33 @@ impl Calendar {
         Ok(())
     }
     
+    /**
+     * Updates the calendar's timezone.
+     * 
+     * The value must be an iCalendar object with a VTIMEZONE component, as
+     * sent in the CalDAV calendar-timezone property; it is stored re-emitted
+     * as a VCALENDAR containing only that VTIMEZONE.
+     * 
+     * @param timezone New calendar-timezone value, or None for UTC
+     * @return Result indicating success or containing a domain error
+     */
+    pub fn update_timezone(&mut self, timezone: Option<String>) -> Result<()> {
+        let timezone = match timezone.filter(|value| !value.trim().is_empty()) {
+            Some(value) => Some(VTimeZone::parse(&value)
+                .ok_or_else(|| DomainError::new(
+                    ErrorKind::InvalidInput,
+                    "Calendar",
+                    "Timezone must contain a valid VTIMEZONE component",
+                ))?
+                .to_calendar()),
+            None => None,
+        };
+        
+        self.timezone = timezone;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+    
     /**
      * Sets a custom property for extended CalDAV support.
      * 
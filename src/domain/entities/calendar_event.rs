@@ -10,10 +10,15 @@
  */
 
 use uuid::Uuid;
-use chrono::{DateTime, Utc, Duration, TimeZone};
+use chrono::{DateTime, Utc, Duration};
 use thiserror::Error;
 
 use crate::common::errors::{Result, DomainError, ErrorKind};
+use crate::domain::entities::event_alarm::EventAlarm;
+use crate::domain::entities::event_attendee::{set_participation, EventAttendee, EventOrganizer, ParticipationStatus};
+use crate::domain::services::icalendar_service::{parse_duration, ICalComponent};
+use crate::domain::services::recurrence_service::{Recurrence, RecurrenceRule};
+use crate::domain::services::timezone_service::{normalize_to_utc, VTimeZone};
 
 /**
  * Error types specific to calendar event operations.
@@ -78,6 +83,19 @@ pub struct CalendarEvent {
     /// Complete iCalendar data (VEVENT component)
     ical_data: String,
     
+    /// TZID of DTSTART (None for UTC, floating and all-day events);
+    /// start and end times are always stored in UTC
+    timezone: Option<String>,
+    
+    /// Alarms (VALARM components) of the event, also kept in the iCalendar data
+    alarms: Vec<EventAlarm>,
+    
+    /// Organizer of a scheduled event (ORGANIZER property)
+    organizer: Option<EventOrganizer>,
+    
+    /// Attendees of a scheduled event (ATTENDEE properties), also kept in the iCalendar data
+    attendees: Vec<EventAttendee>,
+    
     /// Time when the event was created
     created_at: DateTime<Utc>,
     
@@ -162,6 +180,10 @@ impl CalendarEvent {
             rrule,
             ical_uid: Uuid::new_v4().to_string(),
             ical_data,
+            timezone: None,
+            alarms: Vec::new(),
+            organizer: None,
+            attendees: Vec::new(),
             created_at: now,
             updated_at: now,
         })
@@ -230,6 +252,10 @@ impl CalendarEvent {
             rrule,
             ical_uid,
             ical_data,
+            timezone: None,
+            alarms: Vec::new(),
+            organizer: None,
+            attendees: Vec::new(),
             created_at,
             updated_at,
         })
@@ -244,57 +270,38 @@ impl CalendarEvent {
      * @return Result containing the new CalendarEvent or a domain error
      */
     pub fn from_ical(calendar_id: Uuid, ical_data: String) -> Result<Self> {
-        // This implementation would require a proper iCalendar parser
-        // For brevity, we're using a simplified version here
-        
-        // Extract required fields from iCalendar data
-        let summary = Self::extract_ical_property(&ical_data, "SUMMARY")
-            .ok_or_else(|| DomainError::new(
-                ErrorKind::InvalidInput,
-                "CalendarEvent",
-                "Missing SUMMARY in iCalendar data",
-            ))?;
-        
-        let dtstart = Self::extract_ical_property(&ical_data, "DTSTART")
-            .ok_or_else(|| DomainError::new(
-                ErrorKind::InvalidInput,
-                "CalendarEvent",
-                "Missing DTSTART in iCalendar data",
-            ))?;
-        
-        let dtend = Self::extract_ical_property(&ical_data, "DTEND")
-            .ok_or_else(|| DomainError::new(
-                ErrorKind::InvalidInput,
-                "CalendarEvent",
-                "Missing DTEND in iCalendar data",
-            ))?;
-        
-        // Parse dates (simplified)
-        let start_time = Self::parse_ical_datetime(&dtstart)
-            .map_err(|e| DomainError::new(
-                ErrorKind::InvalidInput,
-                "CalendarEvent",
-                format!("Invalid DTSTART: {}", e),
-            ))?;
-        
-        let end_time = Self::parse_ical_datetime(&dtend)
-            .map_err(|e| DomainError::new(
-                ErrorKind::InvalidInput,
-                "CalendarEvent",
-                format!("Invalid DTEND: {}", e),
-            ))?;
+        Self::from_ical_with_timezone(calendar_id, ical_data, None)
+    }
+    
+    /**
+     * Creates a calendar event from iCalendar data, converting DTSTART and
+     * DTEND to UTC.
+     * 
+     * Times with a TZID are resolved with the VTIMEZONE components of the
+     * data or, failing that, with the calendar timezone; floating times are
+     * interpreted in the calendar timezone (RFC 4791, 4.1). Unknown zones
+     * fall back to UTC.
+     * 
+     * @param calendar_id ID of the calendar this event belongs to
+     * @param ical_data Complete iCalendar data (VEVENT component)
+     * @param calendar_timezone Timezone of the calendar, if it has one
+     * @return Result containing the new CalendarEvent or a domain error
+     */
+    pub fn from_ical_with_timezone(calendar_id: Uuid, ical_data: String, calendar_timezone: Option<&VTimeZone>) -> Result<Self> {
+        let invalid = |message: &str| DomainError::new(ErrorKind::InvalidInput, "CalendarEvent", message);
         
-        // Determine if all-day event (simplified check)
-        let all_day = dtstart.contains("VALUE=DATE") && !dtstart.contains("T");
+        let fields = ICalEventFields::parse(&ical_data, calendar_timezone)
+            .ok_or_else(|| invalid("iCalendar data must contain a VEVENT component"))?;
+        let summary = fields.summary.ok_or_else(|| invalid("Missing SUMMARY in iCalendar data"))?;
+        let (start_time, all_day) = fields.start.ok_or_else(|| invalid("Missing DTSTART in iCalendar data"))?;
+        let end_time = fields.end.ok_or_else(|| invalid("Missing DTEND in iCalendar data"))?;
         
-        // Extract optional fields
-        let description = Self::extract_ical_property(&ical_data, "DESCRIPTION");
-        let location = Self::extract_ical_property(&ical_data, "LOCATION");
-        let rrule = Self::extract_ical_property(&ical_data, "RRULE");
+        if end_time < start_time {
+            return Err(invalid("End time cannot be before start time"));
+        }
         
         // Extract UID or generate a new one
-        let ical_uid = Self::extract_ical_property(&ical_data, "UID")
-            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        let ical_uid = fields.uid.unwrap_or_else(|| Uuid::new_v4().to_string());
         
         let now = Utc::now();
         
@@ -302,14 +309,18 @@ impl CalendarEvent {
             id: Uuid::new_v4(),
             calendar_id,
             summary,
-            description,
-            location,
+            description: fields.description,
+            location: fields.location,
             start_time,
             end_time,
             all_day,
-            rrule,
+            rrule: fields.rrule,
             ical_uid,
             ical_data,
+            timezone: fields.timezone,
+            alarms: fields.alarms,
+            organizer: fields.organizer,
+            attendees: fields.attendees,
             created_at: now,
             updated_at: now,
         })
@@ -367,6 +378,61 @@ impl CalendarEvent {
         &self.ical_uid
     }
     
+    /// Returns the TZID of the event's start time, if it has one
+    pub fn timezone(&self) -> Option<&str> {
+        self.timezone.as_deref()
+    }
+    
+    /**
+     * Sets the timezone loaded from storage.
+     * 
+     * @param timezone TZID of the event's start time
+     * @return The event with the given timezone
+     */
+    pub fn with_timezone(mut self, timezone: Option<String>) -> Self {
+        self.timezone = timezone;
+        self
+    }
+    
+    /// Returns the event's alarms
+    pub fn alarms(&self) -> &[EventAlarm] {
+        &self.alarms
+    }
+    
+    /**
+     * Sets the alarms loaded from storage.
+     * 
+     * @param alarms Alarms of the event
+     * @return The event with the given alarms
+     */
+    pub fn with_alarms(mut self, alarms: Vec<EventAlarm>) -> Self {
+        self.alarms = alarms;
+        self
+    }
+    
+    /// Returns the organizer of the event, if it is a scheduled event
+    pub fn organizer(&self) -> Option<&EventOrganizer> {
+        self.organizer.as_ref()
+    }
+    
+    /// Returns the event's attendees
+    pub fn attendees(&self) -> &[EventAttendee] {
+        &self.attendees
+    }
+    
+    /**
+     * Sets the organizer and attendees loaded from storage.
+     * 
+     * @param organizer Organizer of the event
+     * @param attendees Attendees of the event
+     * @return The event with the given organizer and attendees
+     */
+    pub fn with_attendees(mut self, organizer: Option<EventOrganizer>, attendees: Vec<EventAttendee>) -> Self {
+        self.organizer = organizer;
+        self.attendees = attendees;
+        self
+    }
+    
     /// Returns the complete iCalendar data for the event
     pub fn ical_data(&self) -> &str {
         &self.ical_data
@@ -465,9 +531,10 @@ impl CalendarEvent {
         
         self.start_time = start_time;
         self.end_time = end_time;
+        self.timezone = None;
         self.updated_at = Utc::now();
         
-        // Update iCalendar data
+        // Update iCalendar data (times are written in UTC)
         let start_str = if self.all_day {
             format!("{}T000000Z", start_time.format("%Y%m%d"))
         } else {
@@ -493,6 +560,7 @@ impl CalendarEvent {
      */
     pub fn update_all_day(&mut self, all_day: bool) {
         self.all_day = all_day;
+        self.timezone = None;
         self.updated_at = Utc::now();
         
         // Update iCalendar data
@@ -542,6 +610,67 @@ impl CalendarEvent {
         Ok(())
     }
     
+    /**
+     * Replaces the event's alarms.
+     * 
+     * The VALARM components of the iCalendar data are rewritten so that the
+     * alarms reach CalDAV clients on their next sync.
+     * 
+     * @param alarms New alarms for the event
+     */
+    pub fn update_alarms(&mut self, alarms: Vec<EventAlarm>) {
+        let offset = self.ical_data.find("BEGIN:VEVENT").unwrap_or(0);
+        
+        // Remove the current VALARM components
+        while let Some(begin) = self.ical_data[offset..].find("BEGIN:VALARM").map(|p| offset + p) {
+            let Some(end) = self.ical_data[begin..].find("END:VALARM").map(|p| begin + p + "END:VALARM".len()) else {
+                break;
+            };
+            let end = match self.ical_data[end..].find('\n') {
+                Some(p) if self.ical_data[end..end + p].trim().is_empty() => end + p + 1,
+                _ => end,
+            };
+            self.ical_data.replace_range(begin..end, "");
+        }
+        
+        // Add the new ones before END:VEVENT
+        let alarms_data: String = alarms.iter().map(|alarm| alarm.to_ical(&self.summary)).collect();
+        let end_pos = self.ical_data[offset..].find("END:VEVENT")
+            .map(|p| offset + p)
+            .unwrap_or(self.ical_data.len());
+        self.ical_data.insert_str(end_pos, &alarms_data);
+        
+        self.alarms = alarms;
+        self.updated_at = Utc::now();
+    }
+    
+    /**
+     * Changes the participation status of an attendee.
+     * 
+     * The PARTSTAT of the attendee is rewritten in every VEVENT of the
+     * iCalendar data (the master and its exceptions).
+     * 
+     * @param email Address of the attendee, without `mailto:`
+     * @param partstat New participation status
+     * @return false if the address is not an attendee of the event
+     */
+    pub fn update_participation(&mut self, email: &str, partstat: ParticipationStatus) -> bool {
+        let Some(mut calendar) = ICalComponent::parse(&self.ical_data) else {
+            return false;
+        };
+        if !set_participation(&mut calendar, email, partstat) {
+            return false;
+        }
+        
+        let email = email.to_lowercase();
+        for attendee in self.attendees.iter_mut().filter(|attendee| attendee.email == email) {
+            attendee.partstat = partstat;
+        }
+        self.ical_data = calendar.to_ical();
+        self.updated_at = Utc::now();
+        true
+    }
+    
     /**
      * Updates the complete iCalendar data for the event.
      * Also updates the event properties based on the new iCalendar data.
@@ -560,34 +689,27 @@ impl CalendarEvent {
         }
         
         // Extract and update properties from iCalendar data
-        if let Some(summary) = Self::extract_ical_property(&ical_data, "SUMMARY") {
-            self.summary = summary;
-        }
-        
-        self.description = Self::extract_ical_property(&ical_data, "DESCRIPTION");
-        self.location = Self::extract_ical_property(&ical_data, "LOCATION");
-        
-        if let Some(dtstart) = Self::extract_ical_property(&ical_data, "DTSTART") {
-            if let Ok(start_time) = Self::parse_ical_datetime(&dtstart) {
+        if let Some(fields) = ICalEventFields::parse(&ical_data, None) {
+            if let Some(summary) = fields.summary {
+                self.summary = summary;
+            }
+            self.description = fields.description;
+            self.location = fields.location;
+            if let Some((start_time, all_day)) = fields.start {
                 self.start_time = start_time;
+                self.all_day = all_day;
+                self.timezone = fields.timezone;
             }
-        }
-        
-        if let Some(dtend) = Self::extract_ical_property(&ical_data, "DTEND") {
-            if let Ok(end_time) = Self::parse_ical_datetime(&dtend) {
+            if let Some(end_time) = fields.end {
                 self.end_time = end_time;
             }
-        }
-        
-        // Update all-day status based on DTSTART
-        if let Some(dtstart) = Self::extract_ical_property(&ical_data, "DTSTART") {
-            self.all_day = dtstart.contains("VALUE=DATE") && !dtstart.contains("T");
-        }
-        
-        self.rrule = Self::extract_ical_property(&ical_data, "RRULE");
-        
-        if let Some(uid) = Self::extract_ical_property(&ical_data, "UID") {
-            self.ical_uid = uid;
+            self.rrule = fields.rrule;
+            self.alarms = fields.alarms;
+            self.organizer = fields.organizer;
+            self.attendees = fields.attendees;
+            if let Some(uid) = fields.uid {
+                self.ical_uid = uid;
+            }
         }
         
         self.ical_data = ical_data;
@@ -614,134 +736,52 @@ impl CalendarEvent {
      * @return true if the event occurs within the range, false otherwise
      */
     pub fn occurs_in_range(&self, start: &DateTime<Utc>, end: &DateTime<Utc>) -> bool {
-        // Basic case: event directly overlaps with range
-        if self.start_time <= *end && self.end_time >= *start {
-            return true;
-        }
-        
-        // If event has recurrence, check if any recurrence occurs in range
-        // Note: A full implementation would need a proper recurrence rule parser
-        if let Some(rrule) = &self.rrule {
-            // Simplified check for demonstration
-            // A real implementation would need to generate recurrence instances
-            // and check if any fall within the range
-            
-            // For now, we'll just check if the recurrence hasn't ended
-            // or if it ended after the start of our range
-            if let Some(until_pos) = rrule.find("UNTIL=") {
-                let until_start = until_pos + 6; // "UNTIL=" is 6 chars
-                if let Some(until_end) = rrule[until_start..].find(';') {
-                    let until_str = &rrule[until_start..until_start+until_end];
-                    if let Ok(until_date) = Self::parse_ical_datetime(&until_str) {
-                        return until_date >= *start;
-                    }
-                } else {
-                    // UNTIL is the last part of the rule
-                    let until_str = &rrule[until_start..];
-                    if let Ok(until_date) = Self::parse_ical_datetime(&until_str) {
-                        return until_date >= *start;
-                    }
-                }
-            } else {
-                // No UNTIL specified, so recurrence continues indefinitely
-                return true;
-            }
-        }
-        
-        false
+        self.recurrence().overlaps(Some(*start), Some(*end))
     }
     
-    // Helper methods for iCalendar operations
-    
     /**
-     * Extracts a property value from iCalendar data.
+     * Returns the recurrence set of the event.
+     * 
+     * Start, duration and RRULE come from the entity fields; RDATE and EXDATE
+     * are only stored in the iCalendar data.
      * 
-     * @param ical_data The iCalendar data to search in
-     * @param property_name The name of the property to extract
-     * @return Option containing the property value if found
+     * @return The event's recurrence, with a single occurrence if it does not repeat
      */
-    fn extract_ical_property(ical_data: &str, property_name: &str) -> Option<String> {
-        // Find the property in the iCalendar data
-        let search_str = format!("\n{}:", property_name);
-        let search_str_alt = format!("\r\n{}:", property_name);
-        
-        let pos = ical_data.find(&search_str)
-            .or_else(|| ical_data.find(&search_str_alt));
-        
-        if let Some(pos) = pos {
-            // Find the start of the value
-            let value_start = pos + search_str.len();
-            
-            // Find the end of the value (next line or end of string)
-            let value_end = ical_data[value_start..]
-                .find('\n')
-                .map(|p| value_start + p)
-                .unwrap_or_else(|| ical_data.len());
-            
-            // Extract and return the value
-            let value = ical_data[value_start..value_end].trim();
-            if !value.is_empty() {
-                return Some(value.to_string());
+    pub fn recurrence(&self) -> Recurrence {
+        let rule = self.rrule.as_deref().and_then(|rule| RecurrenceRule::parse(rule).ok());
+        let mut recurrence = Recurrence::new(self.start_time, self.duration(), rule);
+        let component = ICalComponent::parse(&self.ical_data).and_then(|mut calendar| {
+            normalize_to_utc(&mut calendar, None);
+            if calendar.name == "VEVENT" {
+                Some(calendar)
+            } else {
+                calendar.components_named("VEVENT").next().cloned()
             }
+        });
+        if let Some(parsed) = component.as_ref().and_then(Recurrence::from_component) {
+            recurrence.rdates = parsed.rdates;
+            recurrence.exdates = parsed.exdates;
         }
-        
-        None
+        recurrence
     }
     
+    // Helper methods for iCalendar operations
+    
     /**
-     * Parses an iCalendar datetime string into a DateTime object.
+     * Finds the line of a VEVENT property in the event's iCalendar data.
      * 
-     * @param datetime The iCalendar datetime string to parse
-     * @return Result containing the parsed DateTime or an error
+     * The search starts at the VEVENT so that properties of the VTIMEZONE
+     * components (which also have DTSTART) are left alone.
+     * 
+     * @param property_name The name of the property to find
+     * @return Position of the newline that precedes the property, if found
      */
-    fn parse_ical_datetime(datetime: &str) -> std::result::Result<DateTime<Utc>, String> {
-        // Handle VALUE=DATE format
-        if datetime.contains("VALUE=DATE") {
-            let date_str = datetime.split(':').last().unwrap_or("");
-            if date_str.len() != 8 {
-                return Err("Invalid date format".to_string());
-            }
-            
-            let year = date_str[0..4].parse::<i32>()
-                .map_err(|_| "Invalid year".to_string())?;
-            let month = date_str[4..6].parse::<u32>()
-                .map_err(|_| "Invalid month".to_string())?;
-            let day = date_str[6..8].parse::<u32>()
-                .map_err(|_| "Invalid day".to_string())?;
-            
-            return match chrono::NaiveDate::from_ymd_opt(year, month, day) {
-                Some(date) => Ok(Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap())),
-                None => Err("Invalid date components".to_string()),
-            };
-        }
-        
-        // Handle standard UTC format (20230101T120000Z)
-        let datetime_str = datetime.split(':').last().unwrap_or(datetime);
-        if datetime_str.len() < 15 || !datetime_str.ends_with('Z') {
-            return Err("Invalid datetime format".to_string());
-        }
-        
-        let year = datetime_str[0..4].parse::<i32>()
-            .map_err(|_| "Invalid year".to_string())?;
-        let month = datetime_str[4..6].parse::<u32>()
-            .map_err(|_| "Invalid month".to_string())?;
-        let day = datetime_str[6..8].parse::<u32>()
-            .map_err(|_| "Invalid day".to_string())?;
-        
-        let hour = datetime_str[9..11].parse::<u32>()
-            .map_err(|_| "Invalid hour".to_string())?;
-        let minute = datetime_str[11..13].parse::<u32>()
-            .map_err(|_| "Invalid minute".to_string())?;
-        let second = datetime_str[13..15].parse::<u32>()
-            .map_err(|_| "Invalid second".to_string())?;
-        
-        match chrono::NaiveDate::from_ymd_opt(year, month, day) {
-            Some(date) => match date.and_hms_opt(hour, minute, second) {
-                Some(datetime) => Ok(Utc.from_utc_datetime(&datetime)),
-                None => Err("Invalid time components".to_string()),
-            },
-            None => Err("Invalid date components".to_string()),
-        }
+    fn find_ical_property(&self, property_name: &str) -> Option<usize> {
+        let offset = self.ical_data.find("BEGIN:VEVENT").unwrap_or(0);
+        let event = &self.ical_data[offset..];
+        event.find(&format!("\n{}:", property_name))
+            .or_else(|| event.find(&format!("\n{};", property_name)))
+            .map(|pos| offset + pos)
     }
     
     /**
@@ -751,16 +791,12 @@ impl CalendarEvent {
      * @param value The new value for the property
      */
     fn update_ical_property(&mut self, property_name: &str, value: &str) {
-        let search_str = format!("\n{}:", property_name);
-        let search_str_alt = format!("\r\n{}:", property_name);
-        
-        // Check if property exists
-        let pos = self.ical_data.find(&search_str)
-            .or_else(|| self.ical_data.find(&search_str_alt));
+        // Check if property exists, with or without parameters (e.g. TZID)
+        let pos = self.find_ical_property(property_name);
         
         if let Some(pos) = pos {
-            // Find the start of the value
-            let value_start = pos + search_str.len();
+            // Parameters are replaced along with the value
+            let value_start = pos + 1 + property_name.len();
             
             // Find the end of the value (next line or end of string)
             let value_end = self.ical_data[value_start..]
@@ -771,7 +807,7 @@ impl CalendarEvent {
             // Replace the value
             let before = &self.ical_data[..value_start];
             let after = &self.ical_data[value_end..];
-            self.ical_data = format!("{}{}{}", before, value, after);
+            self.ical_data = format!("{}:{}{}", before, value, after);
         } else {
             // Property doesn't exist, add it before END:VEVENT
             let end_pos = self.ical_data.find("END:VEVENT")
@@ -789,12 +825,8 @@ impl CalendarEvent {
      * @param property_name The name of the property to remove
      */
     fn remove_ical_property(&mut self, property_name: &str) {
-        let search_str = format!("\n{}:", property_name);
-        let search_str_alt = format!("\r\n{}:", property_name);
-        
-        // Check if property exists
-        let pos = self.ical_data.find(&search_str)
-            .or_else(|| self.ical_data.find(&search_str_alt));
+        // Check if property exists, with or without parameters
+        let pos = self.find_ical_property(property_name);
         
         if let Some(pos) = pos {
             // Find the end of the value (next line or end of string)
@@ -809,4 +841,156 @@ impl CalendarEvent {
             self.ical_data = format!("{}{}", before, after);
         }
     }
-}
\ No newline at end of file
+}
+
+/// Event fields read from the VEVENT of an iCalendar object
+struct ICalEventFields {
+    summary: Option<String>,
+    description: Option<String>,
+    location: Option<String>,
+    /// Start in UTC and whether it is a date without time
+    start: Option<(DateTime<Utc>, bool)>,
+    /// End in UTC, from DTEND or DTSTART plus DURATION
+    end: Option<DateTime<Utc>>,
+    rrule: Option<String>,
+    uid: Option<String>,
+    /// TZID of DTSTART as sent by the client
+    timezone: Option<String>,
+    alarms: Vec<EventAlarm>,
+    organizer: Option<EventOrganizer>,
+    attendees: Vec<EventAttendee>,
+}
+
+impl ICalEventFields {
+    /// Reads the first VEVENT, with its times converted to UTC; `None` if
+    /// the data has no VEVENT
+    fn parse(ical_data: &str, calendar_timezone: Option<&VTimeZone>) -> Option<Self> {
+        let mut calendar = ICalComponent::parse(ical_data)?;
+        let find_event = |calendar: &ICalComponent| -> Option<ICalComponent> {
+            if calendar.name == "VEVENT" {
+                Some(calendar.clone())
+            } else {
+                calendar.components_named("VEVENT").next().cloned()
+            }
+        };
+        
+        let timezone = find_event(&calendar)?
+            .property("DTSTART")
+            .filter(|dtstart| !dtstart.value.trim().ends_with(['Z', 'z']))
+            .and_then(|dtstart| dtstart.param("TZID"))
+            .map(|tzid| tzid.to_string());
+        
+        normalize_to_utc(&mut calendar, calendar_timezone);
+        let event = find_event(&calendar)?;
+        let text = |name: &str| event.property(name)
+            .map(|property| property.text())
+            .filter(|value| !value.is_empty());
+        
+        let start = event.property("DTSTART").and_then(|property| property.date_time());
+        let end = event.property("DTEND")
+            .and_then(|property| property.date_time())
+            .map(|(end, _)| end)
+            .or_else(|| {
+                let duration = event.property("DURATION").and_then(|property| parse_duration(&property.value))?;
+                start.map(|(start, _)| start + duration)
+            });
+        
+        Some(Self {
+            summary: text("SUMMARY"),
+            description: text("DESCRIPTION"),
+            location: text("LOCATION"),
+            timezone: timezone.filter(|_| start.is_some_and(|(_, is_date)| !is_date)),
+            start,
+            end,
+            rrule: event.property("RRULE").map(|property| property.value.trim().to_string()),
+            uid: event.property("UID").map(|property| property.value.trim().to_string()).filter(|uid| !uid.is_empty()),
+            alarms: EventAlarm::from_event(&event),
+            organizer: EventOrganizer::from_event(&event),
+            attendees: EventAttendee::from_event(&event),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const ZONED_EVENT: &str = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VTIMEZONE\r\nTZID:America/New_York\r\nBEGIN:DAYLIGHT\r\nTZOFFSETFROM:-0500\r\nTZOFFSETTO:-0400\r\nDTSTART:20070311T020000\r\nRRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=2SU\r\nEND:DAYLIGHT\r\nBEGIN:STANDARD\r\nTZOFFSETFROM:-0400\r\nTZOFFSETTO:-0500\r\nDTSTART:20071104T020000\r\nRRULE:FREQ=YEARLY;BYMONTH=11;BYDAY=1SU\r\nEND:STANDARD\r\nEND:VTIMEZONE\r\nBEGIN:VEVENT\r\nUID:standup@example.com\r\nSUMMARY:Standup\\, daily\r\nDTSTART;TZID=America/New_York:20240612T093000\r\nDTEND;TZID=America/New_York:20240612T094500\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+
+    #[test]
+    fn from_ical_converts_zoned_times_to_utc() {
+        let event = CalendarEvent::from_ical(Uuid::new_v4(), ZONED_EVENT.to_string()).unwrap();
+        assert_eq!(event.summary(), "Standup, daily");
+        assert_eq!(event.ical_uid(), "standup@example.com");
+        assert_eq!(*event.start_time(), Utc.with_ymd_and_hms(2024, 6, 12, 13, 30, 0).unwrap());
+        assert_eq!(*event.end_time(), Utc.with_ymd_and_hms(2024, 6, 12, 13, 45, 0).unwrap());
+        assert_eq!(event.timezone(), Some("America/New_York"));
+        assert!(!event.all_day());
+    }
+
+    #[test]
+    fn from_ical_uses_calendar_timezone_for_floating_times() {
+        let zone = VTimeZone::parse(ZONED_EVENT).unwrap();
+        let data = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:x\r\nSUMMARY:Lunch\r\nDTSTART:20240110T120000\r\nDURATION:PT1H\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        
+        let event = CalendarEvent::from_ical_with_timezone(Uuid::new_v4(), data.to_string(), Some(&zone)).unwrap();
+        assert_eq!(*event.start_time(), Utc.with_ymd_and_hms(2024, 1, 10, 17, 0, 0).unwrap());
+        assert_eq!(*event.end_time(), Utc.with_ymd_and_hms(2024, 1, 10, 18, 0, 0).unwrap());
+        assert_eq!(event.timezone(), None);
+        
+        let utc = CalendarEvent::from_ical(Uuid::new_v4(), data.to_string()).unwrap();
+        assert_eq!(*utc.start_time(), Utc.with_ymd_and_hms(2024, 1, 10, 12, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn alarms_round_trip_through_ical_data() {
+        let data = ZONED_EVENT.replace(
+            "END:VEVENT",
+            "BEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER:-PT10M\r\nDESCRIPTION:Standup\r\nEND:VALARM\r\nEND:VEVENT",
+        );
+        let mut event = CalendarEvent::from_ical(Uuid::new_v4(), data).unwrap();
+        assert_eq!(event.alarms(), &[EventAlarm::display_before_start(10, Some("Standup".to_string()))]);
+        
+        event.update_alarms(vec![EventAlarm::display_before_start(30, None)]);
+        assert_eq!(event.ical_data().matches("BEGIN:VALARM").count(), 1);
+        assert!(event.ical_data().contains("TRIGGER:-PT30M\r\nDESCRIPTION:Standup\\, daily\r\nEND:VALARM\r\nEND:VEVENT"));
+        
+        let reparsed = CalendarEvent::from_ical(Uuid::new_v4(), event.ical_data().to_string()).unwrap();
+        assert_eq!(reparsed.alarms()[0].trigger, event.alarms()[0].trigger);
+        
+        event.update_alarms(Vec::new());
+        assert!(!event.ical_data().contains("VALARM"));
+    }
+
+    #[test]
+    fn attendees_are_parsed_and_participation_updated() {
+        let data = ZONED_EVENT.replace(
+            "END:VEVENT",
+            "ORGANIZER:mailto:ana@example.com\r\nATTENDEE;RSVP=TRUE:mailto:john@example.com\r\nEND:VEVENT",
+        );
+        let mut event = CalendarEvent::from_ical(Uuid::new_v4(), data).unwrap();
+        assert_eq!(event.organizer().unwrap().email, "ana@example.com");
+        assert_eq!(event.attendees()[0].partstat, ParticipationStatus::NeedsAction);
+        
+        assert!(event.update_participation("john@example.com", ParticipationStatus::Accepted));
+        assert!(!event.update_participation("eve@example.com", ParticipationStatus::Accepted));
+        assert_eq!(event.attendees()[0].partstat, ParticipationStatus::Accepted);
+        
+        let reparsed = CalendarEvent::from_ical(Uuid::new_v4(), event.ical_data().to_string()).unwrap();
+        assert_eq!(reparsed.attendees(), event.attendees());
+        assert_eq!(*reparsed.start_time(), *event.start_time());
+    }
+    
+    #[test]
+    fn update_time_range_replaces_zoned_properties() {
+        let mut event = CalendarEvent::from_ical(Uuid::new_v4(), ZONED_EVENT.to_string()).unwrap();
+        let start = Utc.with_ymd_and_hms(2024, 6, 13, 8, 0, 0).unwrap();
+        event.update_time_range(start, start + Duration::minutes(15)).unwrap();
+        
+        assert_eq!(event.timezone(), None);
+        assert!(event.ical_data().contains("\nDTSTART:20240613T080000Z"));
+        assert!(!event.ical_data().contains("DTSTART;TZID"));
+        assert_eq!(event.recurrence().start, start);
+    }
+}
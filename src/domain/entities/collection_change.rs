@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Operación registrada en el diario de cambios de una colección
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeOperation {
+    Created,
+    Modified,
+    Deleted,
+}
+
+impl ChangeOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Modified => "modified",
+            Self::Deleted => "deleted",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "created" => Some(Self::Created),
+            "modified" => Some(Self::Modified),
+            "deleted" => Some(Self::Deleted),
+            _ => None,
+        }
+    }
+}
+
+/// Entrada del diario de cambios de un calendario o una libreta de direcciones.
+///
+/// Cada cambio de un evento o contacto incrementa la revisión de su colección;
+/// la revisión es la base de los sync-token de RFC 6578.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollectionChange {
+    pub revision: i64,
+    pub object_id: Uuid,
+    pub object_uid: String,
+    pub operation: ChangeOperation,
+    pub changed_at: DateTime<Utc>,
+}
@@ -0,0 +1,141 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use crate::common::errors::DomainError;
+
+/// Longitud máxima del texto de un comentario, en caracteres
+pub const MAX_COMMENT_LENGTH: usize = 5_000;
+
+/// Tipo del elemento comentado
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommentTargetKind {
+    File,
+    Folder,
+}
+
+impl CommentTargetKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::File => "file",
+            Self::Folder => "folder",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "file" => Some(Self::File),
+            "folder" => Some(Self::Folder),
+            _ => None,
+        }
+    }
+}
+
+/// Comentario de un usuario sobre un archivo o una carpeta. Se guarda el
+/// nombre del autor del momento para mostrarlo aunque la cuenta desaparezca
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Comment {
+    pub id: String,
+    pub item_kind: CommentTargetKind,
+    pub item_id: String,
+    pub author_id: String,
+    pub author_name: String,
+    pub message: String,
+    /// IDs de los usuarios mencionados con `@usuario`
+    pub mentions: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    /// Última edición; `None` si no se ha editado
+    pub edited_at: Option<DateTime<Utc>>,
+}
+
+impl Comment {
+    pub fn new(
+        item_kind: CommentTargetKind,
+        item_id: String,
+        author_id: String,
+        author_name: String,
+        message: &str,
+    ) -> Result<Self, DomainError> {
+        Ok(Self {
+            id: Uuid::new_v4().to_string(),
+            item_kind,
+            item_id,
+            author_id,
+            author_name,
+            message: validate_message(message)?,
+            mentions: Vec::new(),
+            created_at: Utc::now(),
+            edited_at: None,
+        })
+    }
+
+    pub fn edit(&mut self, message: &str) -> Result<(), DomainError> {
+        self.message = validate_message(message)?;
+        self.edited_at = Some(Utc::now());
+        Ok(())
+    }
+}
+
+fn validate_message(message: &str) -> Result<String, DomainError> {
+    let message = message.trim();
+    if message.is_empty() || message.chars().count() > MAX_COMMENT_LENGTH {
+        return Err(DomainError::validation_error(format!(
+            "El comentario debe tener entre 1 y {} caracteres", MAX_COMMENT_LENGTH
+        )));
+    }
+    Ok(message.to_string())
+}
+
+/// Nombres de usuario mencionados con `@usuario` en un texto, sin repetir y
+/// en el orden en que aparecen.
+///
+/// La arroba debe ir al principio o tras un carácter que no sea letra ni
+/// número, para no tomar por mención una dirección de correo. Un punto o un
+/// guion al final se considera puntuación del texto.
+pub fn extract_mentions(message: &str) -> Vec<String> {
+    let is_name_char = |c: char| c.is_alphanumeric() || matches!(c, '_' | '.' | '-');
+
+    let mut mentions: Vec<String> = Vec::new();
+    let mut previous: Option<char> = None;
+    for (start, c) in message.char_indices() {
+        if c == '@' && !previous.is_some_and(char::is_alphanumeric) {
+            let rest = &message[start + 1..];
+            let end = rest.find(|c: char| !is_name_char(c)).unwrap_or(rest.len());
+            let name = rest[..end].trim_end_matches(['.', '-']);
+            if !name.is_empty() && !mentions.iter().any(|mention| mention == name) {
+                mentions.push(name.to_string());
+            }
+        }
+        previous = Some(c);
+    }
+    mentions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_mentions() {
+        assert_eq!(
+            extract_mentions("@ana revisa esto con @luis.g. Gracias, @ana"),
+            vec!["ana", "luis.g"]
+        );
+        assert_eq!(extract_mentions("(cc @bob-smith)"), vec!["bob-smith"]);
+        // Las direcciones de correo y las arrobas sueltas no son menciones
+        assert!(extract_mentions("escribe a ana@example.com o @ ").is_empty());
+    }
+
+    #[test]
+    fn test_message_validation() {
+        let mut comment = Comment::new(CommentTargetKind::File, "f1".to_string(), "u1".to_string(), "ana".to_string(), "  Hola ").unwrap();
+        assert_eq!(comment.message, "Hola");
+        assert!(comment.edited_at.is_none());
+
+        assert!(comment.edit(" ").is_err());
+        assert!(comment.edit(&"x".repeat(MAX_COMMENT_LENGTH + 1)).is_err());
+        comment.edit("Adiós").unwrap();
+        assert!(comment.edited_at.is_some());
+    }
+}
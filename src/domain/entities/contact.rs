@@ -12,6 +12,18 @@ pub struct AddressBook {
     pub is_public: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Revision of the contents, bumped by the change journal whenever a
+    /// contact is created, modified or deleted
+    #[serde(default)]
+    pub sync_revision: i64,
+}
+
+impl AddressBook {
+    /// Collection tag (CalendarServer `getctag`); changes whenever any
+    /// contact in the address book changes
+    pub fn ctag(&self) -> String {
+        format!("{}-{}", self.id.simple(), self.sync_revision)
+    }
 }
 
 impl Default for AddressBook {
@@ -25,6 +37,7 @@ impl Default for AddressBook {
             is_public: false,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            sync_revision: 0,
         }
     }
 }
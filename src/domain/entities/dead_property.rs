@@ -0,0 +1,28 @@
+use serde::{Serialize, Deserialize};
+
+/// Propiedad muerta de WebDAV (RFC 4918): el servidor no la interpreta, solo
+/// la guarda tal como la envió el cliente con PROPPATCH y la devuelve en PROPFIND
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeadProperty {
+    /// Espacio de nombres XML de la propiedad (puede estar vacío)
+    pub namespace: String,
+    /// Nombre local de la propiedad
+    pub name: String,
+    /// Valor de texto; `None` si el cliente la envió vacía
+    pub value: Option<String>,
+}
+
+impl DeadProperty {
+    pub fn new(namespace: impl Into<String>, name: impl Into<String>, value: Option<String>) -> Self {
+        Self {
+            namespace: namespace.into(),
+            name: name.into(),
+            value,
+        }
+    }
+
+    /// Indica si la propiedad tiene este nombre cualificado
+    pub fn is(&self, namespace: &str, name: &str) -> bool {
+        self.namespace == namespace && self.name == name
+    }
+}
@@ -0,0 +1,274 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::services::icalendar_service::{parse_date_time, parse_duration, unescape_text, ICalComponent};
+
+/// Acción de un aviso (RFC 5545, 3.8.6.1)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum AlarmAction {
+    Display,
+    Audio,
+    Email,
+}
+
+impl AlarmAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Display => "DISPLAY",
+            Self::Audio => "AUDIO",
+            Self::Email => "EMAIL",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_uppercase().as_str() {
+            "DISPLAY" => Some(Self::Display),
+            "AUDIO" => Some(Self::Audio),
+            "EMAIL" => Some(Self::Email),
+            _ => None,
+        }
+    }
+}
+
+/// Extremo del evento al que se refiere un aviso relativo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum AlarmRelated {
+    #[default]
+    Start,
+    End,
+}
+
+/// Momento en que salta un aviso (RFC 5545, 3.8.6.3)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum AlarmTrigger {
+    /// Desplazamiento en segundos respecto al inicio o al fin del evento;
+    /// negativo antes de él
+    Relative { offset_seconds: i64, related: AlarmRelated },
+    /// Instante fijo
+    Absolute { at: DateTime<Utc> },
+}
+
+impl AlarmTrigger {
+    /// Valor de la propiedad TRIGGER con sus parámetros (`;RELATED=END:-PT5M`)
+    fn to_ical(self) -> String {
+        match self {
+            Self::Relative { offset_seconds, related } => {
+                let related = match related {
+                    AlarmRelated::Start => "",
+                    AlarmRelated::End => ";RELATED=END",
+                };
+                format!("{}:{}", related, format_duration(offset_seconds))
+            }
+            Self::Absolute { at } => format!(";VALUE=DATE-TIME:{}", at.format("%Y%m%dT%H%M%SZ")),
+        }
+    }
+}
+
+/// Aviso (VALARM) de un evento de calendario
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventAlarm {
+    pub action: AlarmAction,
+    pub trigger: AlarmTrigger,
+    pub description: Option<String>,
+    /// Asunto de los avisos por correo
+    pub summary: Option<String>,
+    /// Repeticiones adicionales después del primer aviso
+    #[serde(default)]
+    pub repeat: u32,
+    /// Segundos entre repeticiones
+    pub repeat_interval_seconds: Option<i64>,
+    /// UID del aviso (RFC 9074), para que los clientes lo reconozcan al sincronizar
+    pub uid: Option<String>,
+}
+
+impl EventAlarm {
+    /// Crea un aviso que se muestra `minutes_before` minutos antes del inicio
+    pub fn display_before_start(minutes_before: i64, description: Option<String>) -> Self {
+        Self {
+            action: AlarmAction::Display,
+            trigger: AlarmTrigger::Relative { offset_seconds: -minutes_before * 60, related: AlarmRelated::Start },
+            description,
+            summary: None,
+            repeat: 0,
+            repeat_interval_seconds: None,
+            uid: None,
+        }
+    }
+
+    /// Interpreta un VALARM; `None` si la acción no está soportada o falta el TRIGGER
+    pub fn from_component(component: &ICalComponent) -> Option<Self> {
+        if component.name != "VALARM" {
+            return None;
+        }
+        let action = AlarmAction::parse(&component.property("ACTION")?.value)?;
+
+        let trigger_property = component.property("TRIGGER")?;
+        let is_absolute = trigger_property.param("VALUE")
+            .is_some_and(|value| value.eq_ignore_ascii_case("DATE-TIME"));
+        let trigger = if is_absolute {
+            AlarmTrigger::Absolute { at: parse_date_time(&trigger_property.value, false)?.0 }
+        } else {
+            let related = match trigger_property.param("RELATED") {
+                Some(related) if related.eq_ignore_ascii_case("END") => AlarmRelated::End,
+                _ => AlarmRelated::Start,
+            };
+            let offset = parse_duration(&trigger_property.value)?;
+            AlarmTrigger::Relative { offset_seconds: offset.num_seconds(), related }
+        };
+
+        let text = |name: &str| component.property(name)
+            .map(|property| unescape_text(&property.value))
+            .filter(|value| !value.is_empty());
+        let repeat_interval_seconds = component.property("DURATION")
+            .and_then(|property| parse_duration(&property.value))
+            .map(|duration| duration.num_seconds());
+        // REPEAT y DURATION solo tienen sentido juntos
+        let repeat = component.property("REPEAT")
+            .and_then(|property| property.value.trim().parse::<u32>().ok())
+            .filter(|_| repeat_interval_seconds.is_some())
+            .unwrap_or(0);
+
+        Some(Self {
+            action,
+            trigger,
+            description: text("DESCRIPTION"),
+            summary: text("SUMMARY"),
+            repeat,
+            repeat_interval_seconds: repeat_interval_seconds.filter(|_| repeat > 0),
+            uid: component.property("UID")
+                .or_else(|| component.property("X-WR-ALARMUID"))
+                .map(|property| property.value.trim().to_string()),
+        })
+    }
+
+    /// Avisos de un VEVENT en el orden en que aparecen
+    pub fn from_event(event: &ICalComponent) -> Vec<Self> {
+        event.components_named("VALARM").filter_map(Self::from_component).collect()
+    }
+
+    /// Instante del primer aviso para un evento con ese inicio y fin
+    pub fn trigger_time(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> DateTime<Utc> {
+        match self.trigger {
+            AlarmTrigger::Relative { offset_seconds, related: AlarmRelated::Start } => start + Duration::seconds(offset_seconds),
+            AlarmTrigger::Relative { offset_seconds, related: AlarmRelated::End } => end + Duration::seconds(offset_seconds),
+            AlarmTrigger::Absolute { at } => at,
+        }
+    }
+
+    /// Componente VALARM en formato iCalendar, con finales CRLF.
+    ///
+    /// DISPLAY y EMAIL exigen DESCRIPTION, y EMAIL también SUMMARY; si faltan
+    /// se usa `event_summary` para que el componente sea válido.
+    pub fn to_ical(&self, event_summary: &str) -> String {
+        let mut lines = vec!["BEGIN:VALARM".to_string()];
+        if let Some(uid) = &self.uid {
+            lines.push(format!("UID:{}", uid));
+        }
+        lines.push(format!("ACTION:{}", self.action.as_str()));
+        lines.push(format!("TRIGGER{}", self.trigger.to_ical()));
+
+        let description = self.description.as_deref()
+            .or((self.action != AlarmAction::Audio).then_some(event_summary));
+        if let Some(description) = description {
+            lines.push(format!("DESCRIPTION:{}", escape_text(description)));
+        }
+        let summary = self.summary.as_deref()
+            .or((self.action == AlarmAction::Email).then_some(event_summary));
+        if let Some(summary) = summary {
+            lines.push(format!("SUMMARY:{}", escape_text(summary)));
+        }
+        if let (true, Some(interval)) = (self.repeat > 0, self.repeat_interval_seconds) {
+            lines.push(format!("REPEAT:{}", self.repeat));
+            lines.push(format!("DURATION:{}", format_duration(interval)));
+        }
+        lines.push("END:VALARM".to_string());
+
+        let mut out = lines.join("\r\n");
+        out.push_str("\r\n");
+        out
+    }
+}
+
+/// Escapa un valor de texto (RFC 5545, 3.3.11)
+fn escape_text(value: &str) -> String {
+    value.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Formatea una duración en segundos como `-PT15M`, `P1D` o `PT1H30M`
+fn format_duration(seconds: i64) -> String {
+    let sign = if seconds < 0 { "-" } else { "" };
+    let mut rest = seconds.unsigned_abs();
+    let days = rest / 86_400;
+    rest %= 86_400;
+    let (hours, minutes, secs) = (rest / 3600, rest % 3600 / 60, rest % 60);
+
+    let mut out = format!("{}P", sign);
+    if days > 0 {
+        out.push_str(&format!("{}D", days));
+    }
+    if rest > 0 || days == 0 {
+        out.push('T');
+        if hours > 0 {
+            out.push_str(&format!("{}H", hours));
+        }
+        if minutes > 0 {
+            out.push_str(&format!("{}M", minutes));
+        }
+        if secs > 0 || rest == 0 {
+            out.push_str(&format!("{}S", secs));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn parses_relative_and_absolute_alarms() {
+        let event = ICalComponent::parse(
+            "BEGIN:VEVENT\r\nBEGIN:VALARM\r\nUID:a1\r\nACTION:DISPLAY\r\nTRIGGER:-PT15M\r\nDESCRIPTION:Soon\\, really\r\nREPEAT:2\r\nDURATION:PT5M\r\nEND:VALARM\r\n\
+             BEGIN:VALARM\r\nACTION:AUDIO\r\nTRIGGER;VALUE=DATE-TIME:20240110T083000Z\r\nEND:VALARM\r\n\
+             BEGIN:VALARM\r\nACTION:PROCEDURE\r\nTRIGGER:-PT1M\r\nEND:VALARM\r\nEND:VEVENT\r\n",
+        ).unwrap();
+
+        let alarms = EventAlarm::from_event(&event);
+        assert_eq!(alarms.len(), 2);
+        assert_eq!(alarms[0].trigger, AlarmTrigger::Relative { offset_seconds: -900, related: AlarmRelated::Start });
+        assert_eq!(alarms[0].description.as_deref(), Some("Soon, really"));
+        assert_eq!((alarms[0].repeat, alarms[0].repeat_interval_seconds), (2, Some(300)));
+        assert_eq!(alarms[0].uid.as_deref(), Some("a1"));
+        let at = Utc.with_ymd_and_hms(2024, 1, 10, 8, 30, 0).unwrap();
+        assert_eq!(alarms[1].trigger, AlarmTrigger::Absolute { at });
+        assert_eq!(alarms[1].trigger_time(at, at), at);
+    }
+
+    #[test]
+    fn round_trips_through_ical() {
+        let alarm = EventAlarm {
+            action: AlarmAction::Email,
+            trigger: AlarmTrigger::Relative { offset_seconds: -5400, related: AlarmRelated::End },
+            description: None,
+            summary: Some("Reminder".to_string()),
+            repeat: 0,
+            repeat_interval_seconds: None,
+            uid: None,
+        };
+        let ical = alarm.to_ical("Team meeting");
+        assert!(ical.contains("TRIGGER;RELATED=END:-PT1H30M\r\n"));
+        assert!(ical.contains("DESCRIPTION:Team meeting\r\n"));
+
+        let parsed = EventAlarm::from_component(&ICalComponent::parse(&ical).unwrap()).unwrap();
+        assert_eq!(parsed, EventAlarm { description: Some("Team meeting".to_string()), ..alarm });
+        assert_eq!(format_duration(0), "PT0S");
+        assert_eq!(format_duration(86_400 * 2), "P2D");
+    }
+}
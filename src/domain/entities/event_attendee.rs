@@ -0,0 +1,207 @@
+use serde::{Deserialize, Serialize};
+
+use crate::domain::services::icalendar_service::{ICalComponent, ICalProperty};
+
+/// Estado de participación de un asistente (RFC 5545, 3.2.12)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum ParticipationStatus {
+    #[default]
+    NeedsAction,
+    Accepted,
+    Declined,
+    Tentative,
+    Delegated,
+}
+
+impl ParticipationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NeedsAction => "NEEDS-ACTION",
+            Self::Accepted => "ACCEPTED",
+            Self::Declined => "DECLINED",
+            Self::Tentative => "TENTATIVE",
+            Self::Delegated => "DELEGATED",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_uppercase().as_str() {
+            "NEEDS-ACTION" => Some(Self::NeedsAction),
+            "ACCEPTED" => Some(Self::Accepted),
+            "DECLINED" => Some(Self::Declined),
+            "TENTATIVE" => Some(Self::Tentative),
+            "DELEGATED" => Some(Self::Delegated),
+            _ => None,
+        }
+    }
+}
+
+/// Papel de un asistente en el evento (RFC 5545, 3.2.16)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum AttendeeRole {
+    Chair,
+    #[default]
+    ReqParticipant,
+    OptParticipant,
+    NonParticipant,
+}
+
+impl AttendeeRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Chair => "CHAIR",
+            Self::ReqParticipant => "REQ-PARTICIPANT",
+            Self::OptParticipant => "OPT-PARTICIPANT",
+            Self::NonParticipant => "NON-PARTICIPANT",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_uppercase().as_str() {
+            "CHAIR" => Some(Self::Chair),
+            "REQ-PARTICIPANT" => Some(Self::ReqParticipant),
+            "OPT-PARTICIPANT" => Some(Self::OptParticipant),
+            "NON-PARTICIPANT" => Some(Self::NonParticipant),
+            _ => None,
+        }
+    }
+}
+
+/// Correo de una dirección de usuario de calendario (`mailto:ana@example.com`),
+/// en minúsculas para poder compararlo; `None` si no es una dirección mailto
+pub fn calendar_user_email(address: &str) -> Option<String> {
+    let address = address.trim();
+    let email = address.get(..7)
+        .filter(|scheme| scheme.eq_ignore_ascii_case("mailto:"))
+        .map(|_| address[7..].trim())?;
+    (!email.is_empty() && email.contains('@')).then(|| email.to_lowercase())
+}
+
+/// Organizador de un evento con asistentes (propiedad ORGANIZER)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventOrganizer {
+    pub email: String,
+    /// Nombre visible (parámetro CN)
+    pub common_name: Option<String>,
+}
+
+impl EventOrganizer {
+    /// Organizador de un VEVENT; `None` si no tiene o no es una dirección mailto
+    pub fn from_event(event: &ICalComponent) -> Option<Self> {
+        let property = event.property("ORGANIZER")?;
+        Some(Self {
+            email: calendar_user_email(&property.value)?,
+            common_name: property.param("CN").map(str::to_string).filter(|name| !name.is_empty()),
+        })
+    }
+}
+
+/// Asistente de un evento (propiedad ATTENDEE)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventAttendee {
+    pub email: String,
+    /// Nombre visible (parámetro CN)
+    pub common_name: Option<String>,
+    #[serde(default)]
+    pub role: AttendeeRole,
+    #[serde(default)]
+    pub partstat: ParticipationStatus,
+    /// Si el organizador espera respuesta
+    #[serde(default)]
+    pub rsvp: bool,
+}
+
+impl EventAttendee {
+    /// Interpreta una propiedad ATTENDEE; `None` si no es una dirección mailto
+    pub fn from_property(property: &ICalProperty) -> Option<Self> {
+        Some(Self {
+            email: calendar_user_email(&property.value)?,
+            common_name: property.param("CN").map(str::to_string).filter(|name| !name.is_empty()),
+            role: property.param("ROLE").and_then(AttendeeRole::parse).unwrap_or_default(),
+            partstat: property.param("PARTSTAT").and_then(ParticipationStatus::parse).unwrap_or_default(),
+            rsvp: property.param("RSVP").is_some_and(|rsvp| rsvp.eq_ignore_ascii_case("TRUE")),
+        })
+    }
+
+    /// Asistentes de un VEVENT en el orden en que aparecen, sin repetir correos
+    pub fn from_event(event: &ICalComponent) -> Vec<Self> {
+        let mut attendees: Vec<Self> = Vec::new();
+        for attendee in event.properties_named("ATTENDEE").filter_map(Self::from_property) {
+            if !attendees.iter().any(|known| known.email == attendee.email) {
+                attendees.push(attendee);
+            }
+        }
+        attendees
+    }
+}
+
+/// Cambia el PARTSTAT de un asistente en todos los VEVENT de un objeto
+/// (el principal y sus excepciones). Devuelve `false` si no es asistente.
+pub fn set_participation(calendar: &mut ICalComponent, email: &str, partstat: ParticipationStatus) -> bool {
+    let email = email.to_lowercase();
+    let mut found = false;
+    let events: Vec<&mut ICalComponent> = if calendar.name == "VEVENT" {
+        vec![calendar]
+    } else {
+        calendar.components.iter_mut().filter(|component| component.name == "VEVENT").collect()
+    };
+
+    for event in events {
+        for property in event.properties.iter_mut().filter(|property| property.name == "ATTENDEE") {
+            if calendar_user_email(&property.value).as_deref() != Some(email.as_str()) {
+                continue;
+            }
+            property.params.retain(|(name, _)| !name.eq_ignore_ascii_case("PARTSTAT"));
+            property.params.push(("PARTSTAT".to_string(), partstat.as_str().to_string()));
+            found = true;
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INVITATION: &str = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:meeting@example.com\r\nSUMMARY:Planning\r\nDTSTART:20250110T090000Z\r\nDTEND:20250110T100000Z\r\nORGANIZER;CN=Ana:mailto:Ana@Example.com\r\nATTENDEE;CN=Ana;ROLE=CHAIR;PARTSTAT=ACCEPTED:mailto:ana@example.com\r\nATTENDEE;CN=\"Doe, John\";RSVP=TRUE:MAILTO:john@example.com\r\nATTENDEE:urn:uuid:room-1\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+
+    #[test]
+    fn test_parses_organizer_and_attendees() {
+        let calendar = ICalComponent::parse(INVITATION).unwrap();
+        let event = calendar.components_named("VEVENT").next().unwrap();
+
+        let organizer = EventOrganizer::from_event(event).unwrap();
+        assert_eq!(organizer.email, "ana@example.com");
+        assert_eq!(organizer.common_name.as_deref(), Some("Ana"));
+
+        let attendees = EventAttendee::from_event(event);
+        assert_eq!(attendees.len(), 2);
+        assert_eq!(attendees[0].role, AttendeeRole::Chair);
+        assert_eq!(attendees[0].partstat, ParticipationStatus::Accepted);
+        assert_eq!(attendees[1].email, "john@example.com");
+        assert_eq!(attendees[1].common_name.as_deref(), Some("Doe, John"));
+        assert_eq!(attendees[1].partstat, ParticipationStatus::NeedsAction);
+        assert!(attendees[1].rsvp);
+    }
+
+    #[test]
+    fn test_set_participation_rewrites_partstat() {
+        let mut calendar = ICalComponent::parse(INVITATION).unwrap();
+        assert!(set_participation(&mut calendar, "John@example.com", ParticipationStatus::Declined));
+        assert!(!set_participation(&mut calendar, "eve@example.com", ParticipationStatus::Accepted));
+
+        let event = calendar.components_named("VEVENT").next().unwrap();
+        assert_eq!(EventAttendee::from_event(event)[1].partstat, ParticipationStatus::Declined);
+        assert!(calendar.to_ical().contains("PARTSTAT=DECLINED"));
+    }
+
+    #[test]
+    fn test_calendar_user_email() {
+        assert_eq!(calendar_user_email("mailto:Ana@Example.com").as_deref(), Some("ana@example.com"));
+        assert_eq!(calendar_user_email("urn:uuid:1"), None);
+        assert_eq!(calendar_user_email("mailto:"), None);
+        assert_eq!(ParticipationStatus::parse("tentative"), Some(ParticipationStatus::Tentative));
+    }
+}
@@ -0,0 +1,165 @@
+use serde::{Serialize, Deserialize};
+use sha2::{Digest, Sha256};
+use chrono::{DateTime, Utc};
+
+/// Longitud máxima de la clave de un flag (columna VARCHAR(100))
+pub const MAX_FLAG_KEY_LENGTH: usize = 100;
+
+/// Ámbito de una excepción a la regla global de un flag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlagScope {
+    /// Aplica a todos los usuarios de un grupo
+    Group,
+    /// Aplica a un único usuario y tiene prioridad sobre los grupos
+    User,
+}
+
+impl FlagScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Group => "group",
+            Self::User => "user",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "group" => Some(Self::Group),
+            "user" => Some(Self::User),
+            _ => None,
+        }
+    }
+}
+
+/// Activa o desactiva un flag para un grupo o un usuario concreto
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlagOverride {
+    pub scope: FlagScope,
+    /// Nombre del grupo o ID del usuario
+    pub subject: String,
+    pub enabled: bool,
+}
+
+/// Interruptor para desplegar de forma gradual un subsistema
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureFlag {
+    pub key: String,
+    pub description: Option<String>,
+    /// Regla global para quien no tiene excepciones
+    pub enabled: bool,
+    /// Porcentaje de usuarios (0-100) que ven el flag activo cuando está activado
+    pub rollout_percentage: u8,
+    pub overrides: Vec<FlagOverride>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl FeatureFlag {
+    /// Crea un flag desactivado con despliegue completo
+    pub fn new(key: String, description: Option<String>) -> Self {
+        Self {
+            key,
+            description,
+            enabled: false,
+            rollout_percentage: 100,
+            overrides: Vec::new(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Comprueba que la clave solo usa minúsculas, dígitos, `.`, `_` y `-`
+    pub fn is_valid_key(key: &str) -> bool {
+        !key.is_empty()
+            && key.len() <= MAX_FLAG_KEY_LENGTH
+            && key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '.' | '_' | '-'))
+    }
+
+    /// Decide si el flag está activo para un usuario.
+    ///
+    /// Manda la excepción del usuario; si no la hay, basta con que uno de sus
+    /// grupos tenga el flag activado, y si solo hay grupos que lo desactivan
+    /// queda desactivado. Sin excepciones se aplica la regla global con el
+    /// porcentaje de despliegue.
+    pub fn is_enabled_for(&self, user_id: &str, groups: &[String]) -> bool {
+        if let Some(user) = self.overrides.iter().find(|o| o.scope == FlagScope::User && o.subject == user_id) {
+            return user.enabled;
+        }
+
+        let mut group_overrides = self.overrides.iter()
+            .filter(|o| o.scope == FlagScope::Group && groups.contains(&o.subject))
+            .peekable();
+        if group_overrides.peek().is_some() {
+            return group_overrides.any(|o| o.enabled);
+        }
+
+        self.enabled && rollout_bucket(&self.key, user_id) < self.rollout_percentage
+    }
+}
+
+/// Cubo estable (0-99) de un usuario para un flag.
+///
+/// Depende de la clave para que cada flag reparta a los usuarios de forma
+/// distinta, y no cambia entre reinicios ni entre instancias.
+pub fn rollout_bucket(key: &str, user_id: &str) -> u8 {
+    let digest = Sha256::new()
+        .chain_update(key.as_bytes())
+        .chain_update(b":")
+        .chain_update(user_id.as_bytes())
+        .finalize();
+    (u16::from_be_bytes([digest[0], digest[1]]) % 100) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flag(enabled: bool, rollout_percentage: u8, overrides: Vec<FlagOverride>) -> FeatureFlag {
+        FeatureFlag { enabled, rollout_percentage, overrides, ..FeatureFlag::new("new-sync".to_string(), None) }
+    }
+
+    fn set(scope: FlagScope, subject: &str, enabled: bool) -> FlagOverride {
+        FlagOverride { scope, subject: subject.to_string(), enabled }
+    }
+
+    #[test]
+    fn test_overrides_take_precedence() {
+        let groups = vec!["beta".to_string(), "staff".to_string()];
+        let flag = flag(false, 100, vec![
+            set(FlagScope::Group, "beta", true),
+            set(FlagScope::Group, "staff", false),
+            set(FlagScope::User, "u2", false),
+        ]);
+
+        assert!(flag.is_enabled_for("u1", &groups));
+        assert!(!flag.is_enabled_for("u2", &groups));
+        assert!(!flag.is_enabled_for("u1", &["staff".to_string()]));
+        assert!(!flag.is_enabled_for("u1", &[]));
+    }
+
+    #[test]
+    fn test_rollout_percentage() {
+        let users: Vec<String> = (0..1000).map(|i| format!("user-{}", i)).collect();
+        let count = |flag: &FeatureFlag| users.iter().filter(|u| flag.is_enabled_for(u, &[])).count();
+
+        assert_eq!(count(&flag(true, 0, vec![])), 0);
+        assert_eq!(count(&flag(true, 100, vec![])), 1000);
+        assert_eq!(count(&flag(false, 100, vec![])), 0);
+
+        let quarter = count(&flag(true, 25, vec![]));
+        assert!((180..320).contains(&quarter), "{} users in a 25% rollout", quarter);
+
+        // Widening the rollout keeps the users that already had the flag
+        let narrow = flag(true, 10, vec![]);
+        let wide = flag(true, 50, vec![]);
+        assert!(users.iter().all(|u| !narrow.is_enabled_for(u, &[]) || wide.is_enabled_for(u, &[])));
+    }
+
+    #[test]
+    fn test_key_validation() {
+        assert!(FeatureFlag::is_valid_key("webdav.range-requests_v2"));
+        assert!(!FeatureFlag::is_valid_key(""));
+        assert!(!FeatureFlag::is_valid_key("Upper"));
+        assert!(!FeatureFlag::is_valid_key("with space"));
+        assert!(!FeatureFlag::is_valid_key(&"a".repeat(MAX_FLAG_KEY_LENGTH + 1)));
+    }
+}
@@ -0,0 +1,148 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc, Duration};
+
+/// Bloqueo WebDAV (RFC 4918) sobre un fichero o una carpeta
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceLock {
+    /// Token `opaquelocktoken:` que el cliente envía en la cabecera If
+    pub token: String,
+    /// Ruta del recurso bloqueado, sin barras al principio ni al final
+    pub path: String,
+    /// Usuario que creó el bloqueo
+    pub owner_id: String,
+    /// Contenido del elemento `owner` que envió el cliente
+    pub owner_info: Option<String>,
+    pub exclusive: bool,
+    /// `Depth: infinity`: el bloqueo cubre también todo lo que hay dentro
+    pub infinite_depth: bool,
+    pub timeout_secs: u64,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Quita las barras sobrantes para comparar rutas
+pub fn normalize_path(path: &str) -> String {
+    path.trim_matches('/').to_string()
+}
+
+/// Indica si `path` está dentro de la carpeta `ancestor`
+pub fn is_descendant(path: &str, ancestor: &str) -> bool {
+    if ancestor.is_empty() {
+        !path.is_empty()
+    } else {
+        path.len() > ancestor.len() && path.starts_with(ancestor) && path.as_bytes()[ancestor.len()] == b'/'
+    }
+}
+
+/// Carpeta que contiene `path`; `None` para la raíz
+pub fn parent_path(path: &str) -> Option<&str> {
+    if path.is_empty() {
+        None
+    } else {
+        Some(path.rfind('/').map(|idx| &path[..idx]).unwrap_or(""))
+    }
+}
+
+/// La ruta y todas las carpetas que la contienen, hasta la raíz
+pub fn path_and_ancestors(path: &str) -> Vec<String> {
+    let mut paths = vec![path.to_string()];
+    let mut current = path;
+    while let Some(parent) = parent_path(current) {
+        paths.push(parent.to_string());
+        current = parent;
+    }
+    paths
+}
+
+impl ResourceLock {
+    pub fn new(
+        path: &str,
+        owner_id: String,
+        owner_info: Option<String>,
+        exclusive: bool,
+        infinite_depth: bool,
+        timeout_secs: u64,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            token: format!("opaquelocktoken:{}", Uuid::new_v4()),
+            path: normalize_path(path),
+            owner_id,
+            owner_info,
+            exclusive,
+            infinite_depth,
+            timeout_secs,
+            created_at: now,
+            expires_at: now + Duration::seconds(timeout_secs as i64),
+        }
+    }
+
+    /// Renueva el bloqueo por otros `timeout_secs` segundos
+    pub fn refresh(&mut self, timeout_secs: u64) {
+        self.timeout_secs = timeout_secs;
+        self.expires_at = Utc::now() + Duration::seconds(timeout_secs as i64);
+    }
+
+    /// Indica si el bloqueo protege el recurso `path`
+    pub fn covers(&self, path: &str) -> bool {
+        self.path == path || (self.infinite_depth && is_descendant(path, &self.path))
+    }
+
+    /// Indica si un bloqueo nuevo sobre `path` chocaría con este.
+    ///
+    /// Dos bloqueos compartidos conviven; si alguno es exclusivo chocan cuando
+    /// uno de los dos alcanza al recurso del otro.
+    pub fn conflicts_with(&self, path: &str, exclusive: bool, infinite_depth: bool) -> bool {
+        if !self.exclusive && !exclusive {
+            return false;
+        }
+        self.covers(path) || (infinite_depth && is_descendant(&self.path, path))
+    }
+
+    /// Valor de la cabecera `Timeout` para este bloqueo
+    pub fn timeout_header(&self) -> String {
+        format!("Second-{}", self.timeout_secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lock(path: &str, exclusive: bool, infinite_depth: bool) -> ResourceLock {
+        ResourceLock::new(path, "user".to_string(), None, exclusive, infinite_depth, 60)
+    }
+
+    #[test]
+    fn test_paths() {
+        assert_eq!(normalize_path("/docs/a.txt/"), "docs/a.txt");
+        assert!(is_descendant("docs/a.txt", "docs"));
+        assert!(is_descendant("docs", ""));
+        assert!(!is_descendant("docs2/a.txt", "docs"));
+        assert!(!is_descendant("docs", "docs"));
+        assert_eq!(path_and_ancestors("a/b/c"), vec!["a/b/c", "a/b", "a", ""]);
+        assert_eq!(parent_path("a"), Some(""));
+        assert_eq!(parent_path(""), None);
+    }
+
+    #[test]
+    fn test_covers_depends_on_depth() {
+        assert!(lock("docs", true, true).covers("docs/a/b.txt"));
+        assert!(!lock("docs", true, false).covers("docs/a.txt"));
+        assert!(lock("docs", true, false).covers("docs"));
+        assert!(lock("/docs/", true, false).covers("docs"));
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let exclusive = lock("docs/a.txt", true, false);
+        assert!(exclusive.conflicts_with("docs/a.txt", false, false));
+        assert!(exclusive.conflicts_with("docs", true, true));
+        assert!(!exclusive.conflicts_with("docs", true, false));
+
+        let shared = lock("docs", false, true);
+        assert!(!shared.conflicts_with("docs/a.txt", false, false));
+        assert!(shared.conflicts_with("docs/a.txt", true, false));
+    }
+}
@@ -1,9 +1,32 @@
 pub mod calendar;
 pub mod calendar_event;
+pub mod event_alarm;
+pub mod event_attendee;
 pub mod contact;
 pub mod file;
 pub mod folder;
 pub mod user;
+pub mod user_group;
 pub mod session;
+pub mod app_password;
+pub mod user_quarantine;
+pub mod oidc_identity;
 pub mod share;
-pub mod trashed_item;
\ No newline at end of file
+pub mod share_access_log;
+pub mod trashed_item;
+pub mod lock;
+pub mod dead_property;
+pub mod feature_flag;
+pub mod quota_policy;
+pub mod sync_conflict;
+pub mod collection_change;
+pub mod theme;
+pub mod announcement;
+pub mod scheduling_message;
+pub mod share_notification;
+pub mod notification_preferences;
+pub mod activity;
+pub mod audit_event;
+pub mod webhook;
+pub mod tag;
+pub mod comment;
@@ -0,0 +1,66 @@
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Utc};
+
+/// Sucesos que se pueden avisar por correo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmailEventKind {
+    /// Otro usuario ha compartido algo con el destinatario
+    ShareReceived,
+    /// Alguien ha descargado un fichero de un enlace del destinatario
+    ShareDownloaded,
+    /// El destinatario está cerca de agotar su cuota
+    QuotaWarning,
+}
+
+impl EmailEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ShareReceived => "share_received",
+            Self::ShareDownloaded => "share_downloaded",
+            Self::QuotaWarning => "quota_warning",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "share_received" => Some(Self::ShareReceived),
+            "share_downloaded" => Some(Self::ShareDownloaded),
+            "quota_warning" => Some(Self::QuotaWarning),
+            _ => None,
+        }
+    }
+}
+
+/// Correos que quiere recibir un usuario. Quien no ha tocado sus
+/// preferencias los recibe todos
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationPreferences {
+    pub user_id: String,
+    pub share_received: bool,
+    pub share_downloaded: bool,
+    pub quota_warning: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl NotificationPreferences {
+    /// Preferencias de un usuario que no las ha cambiado
+    pub fn defaults(user_id: impl Into<String>) -> Self {
+        Self {
+            user_id: user_id.into(),
+            share_received: true,
+            share_downloaded: true,
+            quota_warning: true,
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Indica si el usuario quiere el correo de este suceso
+    pub fn allows(&self, kind: EmailEventKind) -> bool {
+        match kind {
+            EmailEventKind::ShareReceived => self.share_received,
+            EmailEventKind::ShareDownloaded => self.share_downloaded,
+            EmailEventKind::QuotaWarning => self.quota_warning,
+        }
+    }
+}
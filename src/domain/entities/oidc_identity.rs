@@ -0,0 +1,16 @@
+use chrono::{DateTime, Utc};
+
+/// Cuenta de un proveedor OpenID Connect vinculada a un usuario local.
+///
+/// Se identifica por el emisor y el `sub`, que el proveedor nunca cambia; el
+/// nombre de usuario y el correo pueden cambiar sin romper el vínculo.
+#[derive(Debug, Clone)]
+pub struct OidcIdentity {
+    pub issuer: String,
+    pub subject: String,
+    pub user_id: String,
+    /// Correo que informó el proveedor al vincular la cuenta
+    pub email: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_login_at: Option<DateTime<Utc>>,
+}
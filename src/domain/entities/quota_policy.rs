@@ -0,0 +1,130 @@
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Utc};
+
+/// Longitud máxima del nombre de grupo de una política (columna VARCHAR(255))
+pub const MAX_POLICY_GROUP_LENGTH: usize = 255;
+
+/// Cuota por defecto para los usuarios de un grupo
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuotaPolicy {
+    pub group: String,
+    /// Bytes permitidos; negativo es sin límite
+    pub quota_bytes: i64,
+    /// Con varios grupos gana la política de mayor prioridad
+    pub priority: i32,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// De dónde sale la cuota efectiva de un usuario
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotaSource {
+    /// Cuota fijada a mano para el usuario
+    User,
+    /// Política de uno de sus grupos
+    Group,
+    /// Cuota asignada al crear la cuenta
+    Default,
+}
+
+/// Cuota que se aplica a un usuario tras resolver las políticas
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EffectiveQuota {
+    /// Bytes permitidos; negativo es sin límite
+    pub quota_bytes: i64,
+    pub source: QuotaSource,
+    /// Grupo de la política aplicada, si viene de un grupo
+    pub group: Option<String>,
+}
+
+impl EffectiveQuota {
+    pub fn is_unlimited(&self) -> bool {
+        self.quota_bytes < 0
+    }
+
+    /// Comprueba si caben `additional_bytes` más sobre `used_bytes`
+    pub fn has_room_for(&self, used_bytes: i64, additional_bytes: u64) -> bool {
+        if self.is_unlimited() {
+            return true;
+        }
+        let additional = i64::try_from(additional_bytes).unwrap_or(i64::MAX);
+        used_bytes.saturating_add(additional) <= self.quota_bytes
+    }
+}
+
+/// Resuelve la cuota efectiva de un usuario.
+///
+/// Manda la cuota fijada para el usuario. Si no la hay, se aplica la política
+/// de mayor prioridad entre las de sus grupos y, a igual prioridad, la más
+/// generosa (sin límite antes que cualquier tamaño). Sin políticas queda la
+/// cuota que recibió al crear la cuenta.
+pub fn resolve_effective_quota(
+    user_override: Option<i64>,
+    policies: &[QuotaPolicy],
+    groups: &[String],
+    default_bytes: i64,
+) -> EffectiveQuota {
+    if let Some(quota_bytes) = user_override {
+        return EffectiveQuota { quota_bytes, source: QuotaSource::User, group: None };
+    }
+
+    let best = policies.iter()
+        .filter(|policy| groups.contains(&policy.group))
+        .max_by_key(|policy| (policy.priority, policy.quota_bytes < 0, policy.quota_bytes));
+    match best {
+        Some(policy) => EffectiveQuota {
+            quota_bytes: policy.quota_bytes,
+            source: QuotaSource::Group,
+            group: Some(policy.group.clone()),
+        },
+        None => EffectiveQuota { quota_bytes: default_bytes, source: QuotaSource::Default, group: None },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GB: i64 = 1024 * 1024 * 1024;
+
+    fn policy(group: &str, quota_bytes: i64, priority: i32) -> QuotaPolicy {
+        QuotaPolicy { group: group.to_string(), quota_bytes, priority, updated_at: Utc::now() }
+    }
+
+    fn groups(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_precedence() {
+        let policies = vec![policy("staff", 100 * GB, 10), policy("students", 10 * GB, 0), policy("user", 5 * GB, 0)];
+
+        let quota = resolve_effective_quota(Some(GB), &policies, &groups(&["staff"]), 2 * GB);
+        assert_eq!(quota, EffectiveQuota { quota_bytes: GB, source: QuotaSource::User, group: None });
+
+        // La prioridad manda aunque la cuota sea menor
+        let policies_by_priority = vec![policy("staff", 100 * GB, 0), policy("students", 10 * GB, 10)];
+        let quota = resolve_effective_quota(None, &policies_by_priority, &groups(&["staff", "students"]), 2 * GB);
+        assert_eq!(quota.group.as_deref(), Some("students"));
+
+        // A igual prioridad, la más generosa
+        let quota = resolve_effective_quota(None, &policies, &groups(&["students", "user"]), 2 * GB);
+        assert_eq!((quota.quota_bytes, quota.source), (10 * GB, QuotaSource::Group));
+        let unlimited = vec![policy("students", 10 * GB, 0), policy("user", -1, 0)];
+        assert!(resolve_effective_quota(None, &unlimited, &groups(&["students", "user"]), 2 * GB).is_unlimited());
+
+        let quota = resolve_effective_quota(None, &policies, &groups(&["admin"]), 2 * GB);
+        assert_eq!(quota, EffectiveQuota { quota_bytes: 2 * GB, source: QuotaSource::Default, group: None });
+    }
+
+    #[test]
+    fn test_has_room_for() {
+        let quota = EffectiveQuota { quota_bytes: 1000, source: QuotaSource::Group, group: None };
+        assert!(quota.has_room_for(900, 100));
+        assert!(!quota.has_room_for(900, 101));
+        assert!(!quota.has_room_for(0, u64::MAX));
+
+        let unlimited = EffectiveQuota { quota_bytes: -1, ..quota };
+        assert!(unlimited.has_room_for(i64::MAX, u64::MAX));
+    }
+}
@@ -0,0 +1,65 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+/// Método iTIP de un mensaje de planificación (RFC 5546, 1.4)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ITipMethod {
+    /// El organizador invita o envía cambios a los asistentes
+    Request,
+    /// Un asistente contesta al organizador con su estado de participación
+    Reply,
+    /// El organizador cancela el evento o retira a asistentes
+    Cancel,
+}
+
+impl ITipMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Request => "REQUEST",
+            Self::Reply => "REPLY",
+            Self::Cancel => "CANCEL",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_uppercase().as_str() {
+            "REQUEST" => Some(Self::Request),
+            "REPLY" => Some(Self::Reply),
+            "CANCEL" => Some(Self::Cancel),
+            _ => None,
+        }
+    }
+}
+
+/// Mensaje iTIP entregado en la bandeja de entrada de planificación de un
+/// usuario (RFC 6638, 2.2)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchedulingMessage {
+    pub id: String,
+    /// Destinatario
+    pub user_id: String,
+    pub method: ITipMethod,
+    /// UID del evento al que se refiere
+    pub ical_uid: String,
+    /// Correo de quien lo envió
+    pub sender: String,
+    /// VCALENDAR con la propiedad METHOD
+    pub ical_data: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl SchedulingMessage {
+    pub fn new(user_id: String, method: ITipMethod, ical_uid: String, sender: String, ical_data: String) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            method,
+            ical_uid,
+            sender,
+            ical_data,
+            created_at: Utc::now(),
+        }
+    }
+}
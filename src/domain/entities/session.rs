@@ -11,6 +11,8 @@ pub struct Session {
     pub ip_address: Option<String>,
     pub user_agent: Option<String>,
     pub created_at: DateTime<Utc>,
+    /// Último refresco o petición con un token de la sesión
+    pub last_active_at: DateTime<Utc>,
     pub revoked: bool,
 }
 
@@ -31,6 +33,7 @@ impl Session {
             ip_address,
             user_agent,
             created_at: now,
+            last_active_at: now,
             revoked: false,
         }
     }
@@ -56,6 +59,15 @@ impl Session {
         self.created_at
     }
     
+    pub fn last_active_at(&self) -> DateTime<Utc> {
+        self.last_active_at
+    }
+    
+    /// Sesión que todavía permite refrescar tokens
+    pub fn is_active(&self) -> bool {
+        !self.is_revoked() && !self.is_expired()
+    }
+    
     pub fn is_expired(&self) -> bool {
         Utc::now() > self.expires_at
     }
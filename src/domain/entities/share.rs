@@ -1,4 +1,8 @@
 use std::time::{SystemTime, UNIX_EPOCH};
+use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
+use argon2::password_hash::SaltString;
+use rand_core::OsRng;
+use subtle::ConstantTimeEq;
 use thiserror::Error;
 use uuid::Uuid;
 
@@ -14,13 +18,112 @@ pub struct Share {
     pub created_at: u64,
     pub created_by: String,
     pub access_count: u64,
+    /// Times the shared content was downloaded through the public link
+    pub download_count: u64,
+    pub link_preview: LinkPreview,
+    /// Visitors must prove an email address with a one-time code before
+    /// they can open the share
+    pub email_verification: bool,
+    /// Addresses allowed to verify; empty, with no allowed groups, means
+    /// any address
+    pub allowed_emails: Vec<String>,
+    /// User groups whose members may verify with their account address
+    pub allowed_groups: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct SharePermissions {
-    pub read: bool,
-    pub write: bool,
-    pub reshare: bool,
+/// What the audience of a share may do with the shared item and, for
+/// folders, everything inside it. Stored as a bitmask.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SharePermissions(u8);
+
+impl SharePermissions {
+    pub const READ: Self = Self(1);
+    /// Change the content, rename, create and upload inside folders
+    pub const WRITE: Self = Self(1 << 1);
+    pub const RESHARE: Self = Self(1 << 2);
+    /// Delete, or move out of the share, the item or anything inside it
+    pub const DELETE: Self = Self(1 << 3);
+    /// Visitors of the public link may upload files into a shared folder.
+    /// Without `READ` the link is a file drop: uploads only, no listing
+    /// and no downloads
+    pub const UPLOAD: Self = Self(1 << 4);
+
+    const ALL: u8 = 0b1_1111;
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Permissions from their bitmask; unknown bits are dropped
+    pub fn from_bits(bits: u8) -> Self {
+        Self(bits & Self::ALL)
+    }
+
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Whether every permission of `other` is granted
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Grants or withdraws the permissions of `other`
+    pub fn set(mut self, other: Self, granted: bool) -> Self {
+        if granted {
+            self.0 |= other.0;
+        } else {
+            self.0 &= !other.0;
+        }
+        self
+    }
+
+    pub fn read(self) -> bool {
+        self.contains(Self::READ)
+    }
+
+    pub fn write(self) -> bool {
+        self.contains(Self::WRITE)
+    }
+
+    pub fn reshare(self) -> bool {
+        self.contains(Self::RESHARE)
+    }
+
+    pub fn delete(self) -> bool {
+        self.contains(Self::DELETE)
+    }
+
+    pub fn upload(self) -> bool {
+        self.contains(Self::UPLOAD)
+    }
+}
+
+impl std::ops::BitOr for SharePermissions {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl std::ops::BitOrAssign for SharePermissions {
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
+
+/// How much of the shared item a link preview (Open Graph, oEmbed) reveals.
+/// Password-protected shares never reveal anything, whatever the setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LinkPreview {
+    /// Generic preview that does not even reveal the item name
+    Hidden,
+    /// Name, size and type of the item
+    #[default]
+    Details,
+    /// Details plus an image thumbnail of the file
+    Thumbnail,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -53,6 +156,11 @@ impl Share {
             return Err(ShareError::ValidationError("Item ID cannot be empty".to_string()));
         }
 
+        // Only folders have somewhere to upload into
+        if item_type == ShareItemType::File && permissions.is_some_and(|p| p.upload()) {
+            return Err(ShareError::ValidationError("Only folder shares can accept uploads".to_string()));
+        }
+
         // Validate expiration date if provided
         if let Some(expires) = expires_at {
             let now = SystemTime::now()
@@ -77,20 +185,29 @@ impl Share {
             token: Uuid::new_v4().to_string(),
             password_hash,
             expires_at,
-            permissions: permissions.unwrap_or(SharePermissions {
-                read: true,
-                write: false,
-                reshare: false,
-            }),
+            permissions: permissions.unwrap_or(SharePermissions::READ),
             created_at: now,
             created_by,
             access_count: 0,
+            download_count: 0,
+            link_preview: LinkPreview::default(),
+            email_verification: false,
+            allowed_emails: Vec::new(),
+            allowed_groups: Vec::new(),
         })
     }
 
-    pub fn with_permissions(mut self, permissions: SharePermissions) -> Self {
+    pub fn with_permissions(mut self, permissions: SharePermissions) -> Result<Self, ShareError> {
+        if self.item_type == ShareItemType::File && permissions.upload() {
+            return Err(ShareError::ValidationError("Only folder shares can accept uploads".to_string()));
+        }
         self.permissions = permissions;
-        self
+        Ok(self)
+    }
+
+    /// Whether visitors may only upload into the shared folder
+    pub fn is_file_drop(&self) -> bool {
+        self.permissions.upload() && !self.permissions.read()
     }
 
     pub fn with_password(mut self, password_hash: Option<String>) -> Self {
@@ -103,6 +220,46 @@ impl Share {
         self
     }
 
+    pub fn with_link_preview(mut self, link_preview: LinkPreview) -> Self {
+        self.link_preview = link_preview;
+        self
+    }
+
+    /// Turns email verification on or off; addresses are normalized and
+    /// deduplicated
+    pub fn with_email_verification(mut self, enabled: bool, allowed_emails: Vec<String>) -> Self {
+        let mut allowed_emails: Vec<String> = allowed_emails.iter()
+            .map(|email| normalize_email(email))
+            .filter(|email| !email.is_empty())
+            .collect();
+        allowed_emails.sort();
+        allowed_emails.dedup();
+        self.email_verification = enabled;
+        self.allowed_emails = allowed_emails;
+        self
+    }
+
+    /// Limits the share to the members of some user groups; they still
+    /// have to verify their address
+    pub fn with_allowed_groups(mut self, mut allowed_groups: Vec<String>) -> Self {
+        allowed_groups.retain(|group| !group.is_empty());
+        allowed_groups.sort();
+        allowed_groups.dedup();
+        self.allowed_groups = allowed_groups;
+        self
+    }
+
+    /// Whether only some addresses or groups may open the share
+    pub fn restricts_audience(&self) -> bool {
+        !self.allowed_emails.is_empty() || !self.allowed_groups.is_empty()
+    }
+
+    /// Whether an address may verify itself for this share without being
+    /// a member of one of the allowed groups
+    pub fn allows_email(&self, email: &str) -> bool {
+        !self.restricts_audience() || self.allowed_emails.contains(&normalize_email(email))
+    }
+
     pub fn with_token(mut self, token: String) -> Self {
         self.token = token;
         self
@@ -126,25 +283,62 @@ impl Share {
         self
     }
 
+    pub fn increment_download_count(mut self) -> Self {
+        self.download_count += 1;
+        self
+    }
+
+    /// Hashes a share password with Argon2, like account passwords
+    pub fn hash_password(password: &str) -> Result<String, ShareError> {
+        let salt = SaltString::generate(&mut OsRng);
+        Argon2::default()
+            .hash_password(password.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|e| ShareError::ValidationError(format!("Could not hash the share password: {}", e)))
+    }
+
     pub fn verify_password(&self, password: &str) -> bool {
         match &self.password_hash {
-            Some(hash) => {
-                // In a real implementation, use a proper password hashing function like bcrypt
-                // For simplicity, we're just comparing strings here
-                hash == password
-            }
+            Some(hash) => match PasswordHash::new(hash) {
+                Ok(parsed) => Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok(),
+                // Shares created before passwords were hashed kept them as they
+                // were typed, until they are rehashed
+                Err(_) => bool::from(hash.as_bytes().ct_eq(password.as_bytes())),
+            },
             None => true,
         }
     }
+
+    /// Whether the password was stored as typed, before passwords were hashed
+    pub fn has_legacy_password(&self) -> bool {
+        self.password_hash.as_deref().is_some_and(|hash| PasswordHash::new(hash).is_err())
+    }
+}
+
+/// Canonical form of an email address used for comparisons
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
 }
 
 impl SharePermissions {
     pub fn new(read: bool, write: bool, reshare: bool) -> Self {
-        Self {
-            read,
-            write,
-            reshare,
-        }
+        Self::empty()
+            .set(Self::READ, read)
+            .set(Self::WRITE, write)
+            .set(Self::RESHARE, reshare)
+    }
+
+    /// Permissions of a file drop link: visitors upload and see nothing
+    pub fn file_drop() -> Self {
+        Self::UPLOAD
+    }
+
+    pub fn with_upload(self, upload: bool) -> Self {
+        self.set(Self::UPLOAD, upload)
+    }
+
+    pub fn with_delete(self, delete: bool) -> Self {
+        self.set(Self::DELETE, delete)
     }
 }
 
@@ -169,6 +363,29 @@ impl TryFrom<&str> for ShareItemType {
     }
 }
 
+impl ToString for LinkPreview {
+    fn to_string(&self) -> String {
+        match self {
+            LinkPreview::Hidden => "hidden".to_string(),
+            LinkPreview::Details => "details".to_string(),
+            LinkPreview::Thumbnail => "thumbnail".to_string(),
+        }
+    }
+}
+
+impl TryFrom<&str> for LinkPreview {
+    type Error = ShareError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s.to_lowercase().as_str() {
+            "hidden" | "none" => Ok(LinkPreview::Hidden),
+            "details" => Ok(LinkPreview::Details),
+            "thumbnail" => Ok(LinkPreview::Thumbnail),
+            _ => Err(ShareError::ValidationError(format!("Invalid link preview setting: {}", s))),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -188,9 +405,7 @@ mod tests {
         assert_eq!(share.item_id, "test_file_id");
         assert_eq!(share.item_type, ShareItemType::File);
         assert_eq!(share.created_by, "user123");
-        assert_eq!(share.permissions.read, true);
-        assert_eq!(share.permissions.write, false);
-        assert_eq!(share.permissions.reshare, false);
+        assert_eq!(share.permissions, SharePermissions::READ);
         assert!(share.password_hash.is_none());
         assert!(share.expires_at.is_none());
         assert_eq!(share.access_count, 0);
@@ -231,6 +446,52 @@ mod tests {
         assert!(share_result.is_err());
     }
     
+    #[test]
+    fn test_share_password() {
+        let hash = Share::hash_password("secret").unwrap();
+        assert_ne!(hash, "secret");
+        let share = Share::new("test_file_id".to_string(), ShareItemType::File, "user123".to_string(), None, Some(hash), None)
+            .unwrap();
+        assert!(share.verify_password("secret"));
+        assert!(!share.verify_password("Secret"));
+        assert!(!share.has_legacy_password());
+
+        // Passwords stored before hashing still open their shares until rehashed
+        let legacy = share.with_password(Some("secret".to_string()));
+        assert!(legacy.has_legacy_password());
+        assert!(legacy.verify_password("secret"));
+        assert!(!legacy.verify_password("other"));
+        assert!(!legacy.verify_password("secret2"));
+    }
+
+    #[test]
+    fn test_permission_bits() {
+        let permissions = SharePermissions::new(true, true, false).with_delete(true);
+        assert!(permissions.read() && permissions.write() && permissions.delete());
+        assert!(!permissions.reshare() && !permissions.upload());
+        assert!(permissions.contains(SharePermissions::READ | SharePermissions::DELETE));
+        assert!(!permissions.contains(SharePermissions::READ | SharePermissions::RESHARE));
+        assert!(permissions.contains(SharePermissions::empty()));
+        assert_eq!(SharePermissions::from_bits(permissions.bits()), permissions);
+        assert_eq!(SharePermissions::from_bits(0xFF).bits(), 0b1_1111);
+        assert_eq!(permissions.set(SharePermissions::WRITE, false), SharePermissions::READ | SharePermissions::DELETE);
+    }
+
+    #[test]
+    fn test_file_drop_share() {
+        let share = Share::new("folder_id".to_string(), ShareItemType::Folder, "user123".to_string(), Some(SharePermissions::file_drop()), None, None)
+            .unwrap();
+        assert!(share.is_file_drop());
+        // Uploads alongside reading are an ordinary writable share
+        let share = share.with_permissions(SharePermissions::new(true, false, false).with_upload(true)).unwrap();
+        assert!(!share.is_file_drop());
+
+        let file_share = Share::new("file_id".to_string(), ShareItemType::File, "user123".to_string(), Some(SharePermissions::file_drop()), None, None);
+        assert!(file_share.is_err());
+        let file_share = Share::new("file_id".to_string(), ShareItemType::File, "user123".to_string(), None, None, None).unwrap();
+        assert!(file_share.with_permissions(SharePermissions::file_drop()).is_err());
+    }
+
     #[test]
     fn test_share_item_type_conversion() {
         assert_eq!(ShareItemType::File.to_string(), "file");
@@ -241,4 +502,32 @@ mod tests {
         assert_eq!(ShareItemType::try_from("FILE").unwrap(), ShareItemType::File);
         assert!(ShareItemType::try_from("invalid").is_err());
     }
+
+    #[test]
+    fn test_email_allow_list() {
+        let share = Share::new("test_file_id".to_string(), ShareItemType::File, "user123".to_string(), None, None, None)
+            .unwrap();
+        assert!(!share.email_verification);
+        assert!(share.allows_email("anyone@example.com"));
+
+        let share = share.with_email_verification(true, vec![" Ana@Example.com".to_string(), "ana@example.com".to_string(), "".to_string()]);
+        assert_eq!(share.allowed_emails, vec!["ana@example.com"]);
+        assert!(share.allows_email("ANA@example.com "));
+        assert!(!share.allows_email("bob@example.com"));
+
+        // Limited to a group, no address passes on its own
+        let share = share.with_email_verification(true, Vec::new()).with_allowed_groups(vec!["team".to_string(), "team".to_string()]);
+        assert_eq!(share.allowed_groups, vec!["team"]);
+        assert!(!share.allows_email("bob@example.com"));
+    }
+
+    #[test]
+    fn test_link_preview_conversion() {
+        assert_eq!(LinkPreview::default(), LinkPreview::Details);
+        for preview in [LinkPreview::Hidden, LinkPreview::Details, LinkPreview::Thumbnail] {
+            assert_eq!(LinkPreview::try_from(preview.to_string().as_str()).unwrap(), preview);
+        }
+        assert_eq!(LinkPreview::try_from("None").unwrap(), LinkPreview::Hidden);
+        assert!(LinkPreview::try_from("everything").is_err());
+    }
 }
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+
+/// Suceso anotado en el registro de accesos de un enlace compartido
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShareAccessEvent {
+    /// Se abrió el enlace
+    Accessed,
+    /// Se envió un código de un solo uso al correo del visitante
+    CodeSent,
+    /// Se pidió un código para un correo que el enlace no admite
+    EmailRejected,
+    /// Se introdujo un código incorrecto o caducado
+    CodeRejected,
+    /// El visitante verificó su correo y obtuvo una sesión
+    Verified,
+    /// Se descargó el contenido compartido
+    Downloaded,
+    /// Se intentó abrir el contenido con una contraseña incorrecta
+    PasswordRejected,
+    /// Un visitante subió un fichero a la carpeta compartida
+    Uploaded,
+}
+
+impl ShareAccessEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ShareAccessEvent::Accessed => "accessed",
+            ShareAccessEvent::CodeSent => "code_sent",
+            ShareAccessEvent::EmailRejected => "email_rejected",
+            ShareAccessEvent::CodeRejected => "code_rejected",
+            ShareAccessEvent::Verified => "verified",
+            ShareAccessEvent::Downloaded => "downloaded",
+            ShareAccessEvent::PasswordRejected => "password_rejected",
+            ShareAccessEvent::Uploaded => "uploaded",
+        }
+    }
+}
+
+/// Entrada del registro de accesos de un enlace compartido
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareAccessEntry {
+    pub share_id: String,
+    pub event: ShareAccessEvent,
+    /// Correo del visitante, si lo ha indicado
+    pub email: Option<String>,
+    pub client_ip: Option<String>,
+    /// Segundos desde la época Unix, como el resto de fechas de los enlaces
+    pub timestamp: u64,
+}
+
+impl ShareAccessEntry {
+    pub fn new(share_id: &str, event: ShareAccessEvent, email: Option<&str>, client_ip: Option<&str>) -> Self {
+        Self {
+            share_id: share_id.to_string(),
+            event,
+            email: email.map(str::to_string),
+            client_ip: client_ip.map(str::to_string),
+            timestamp: chrono::Utc::now().timestamp().max(0) as u64,
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+/// Cambio que se comunica al destinatario
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShareChange {
+    /// Se ha compartido algo con el usuario
+    Shared,
+    /// Algo que tenía compartido ya no lo está
+    Unshared,
+}
+
+impl ShareChange {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Shared => "shared",
+            Self::Unshared => "unshared",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "shared" => Some(Self::Shared),
+            "unshared" => Some(Self::Unshared),
+            _ => None,
+        }
+    }
+}
+
+/// Tipo de lo compartido
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SharedItemKind {
+    File,
+    Folder,
+    Calendar,
+    AddressBook,
+}
+
+impl SharedItemKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::File => "file",
+            Self::Folder => "folder",
+            Self::Calendar => "calendar",
+            Self::AddressBook => "address_book",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "file" => Some(Self::File),
+            "folder" => Some(Self::Folder),
+            "calendar" => Some(Self::Calendar),
+            "address_book" => Some(Self::AddressBook),
+            _ => None,
+        }
+    }
+}
+
+/// Aviso a un usuario de que algo se ha compartido con él o ha dejado de
+/// estarlo. Se guarda hasta que el usuario lo descarta
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareNotification {
+    pub id: String,
+    /// Destinatario del aviso
+    pub user_id: String,
+    pub change: ShareChange,
+    pub item_kind: SharedItemKind,
+    pub item_id: String,
+    /// Nombre de lo compartido en el momento del aviso
+    pub item_name: String,
+    /// Usuario que compartió, si se conoce
+    pub actor_id: Option<String>,
+    pub actor_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+impl ShareNotification {
+    pub fn new(
+        user_id: String,
+        change: ShareChange,
+        item_kind: SharedItemKind,
+        item_id: String,
+        item_name: String,
+        actor_id: Option<String>,
+        actor_name: Option<String>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            change,
+            item_kind,
+            item_id,
+            item_name,
+            actor_id,
+            actor_name,
+            created_at: Utc::now(),
+            read_at: None,
+        }
+    }
+
+    pub fn is_read(&self) -> bool {
+        self.read_at.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_round_trip() {
+        for change in [ShareChange::Shared, ShareChange::Unshared] {
+            assert_eq!(ShareChange::parse(change.as_str()), Some(change));
+        }
+        for kind in [SharedItemKind::File, SharedItemKind::Folder, SharedItemKind::Calendar, SharedItemKind::AddressBook] {
+            assert_eq!(SharedItemKind::parse(kind.as_str()), Some(kind));
+        }
+        assert_eq!(SharedItemKind::parse("album"), None);
+    }
+}
@@ -0,0 +1,151 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+/// Tipo de conflicto de sincronización detectado
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictKind {
+    /// Un cliente subió una copia en conflicto junto al original
+    ConflictedCopy,
+    /// Una escritura (PUT, DELETE, MOVE...) se rechazó porque otro tenía el recurso bloqueado
+    RejectedWrite,
+    /// Un LOCK falló porque el recurso ya estaba bloqueado
+    LockContention,
+}
+
+impl ConflictKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ConflictedCopy => "conflicted_copy",
+            Self::RejectedWrite => "rejected_write",
+            Self::LockContention => "lock_contention",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "conflicted_copy" => Some(Self::ConflictedCopy),
+            "rejected_write" => Some(Self::RejectedWrite),
+            "lock_contention" => Some(Self::LockContention),
+            _ => None,
+        }
+    }
+
+    /// Resoluciones que admite este tipo de conflicto.
+    ///
+    /// Cuando la escritura se rechazó los datos del usuario nunca llegaron al
+    /// servidor, así que solo cabe quedarse con la versión existente.
+    pub fn resolutions(&self) -> &'static [ConflictResolution] {
+        match self {
+            Self::ConflictedCopy => &[
+                ConflictResolution::KeepMine,
+                ConflictResolution::KeepTheirs,
+                ConflictResolution::KeepBoth,
+            ],
+            Self::RejectedWrite | Self::LockContention => &[ConflictResolution::KeepTheirs],
+        }
+    }
+}
+
+/// Cómo resolvió el usuario un conflicto
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolution {
+    /// La copia en conflicto sustituye al original
+    KeepMine,
+    /// Se conserva el original y se descarta la copia
+    KeepTheirs,
+    /// Se conservan los dos ficheros
+    KeepBoth,
+}
+
+impl ConflictResolution {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::KeepMine => "keep_mine",
+            Self::KeepTheirs => "keep_theirs",
+            Self::KeepBoth => "keep_both",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "keep_mine" => Some(Self::KeepMine),
+            "keep_theirs" => Some(Self::KeepTheirs),
+            "keep_both" => Some(Self::KeepBoth),
+            _ => None,
+        }
+    }
+}
+
+/// Conflicto de sincronización de un usuario
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncConflict {
+    pub id: String,
+    pub user_id: String,
+    pub kind: ConflictKind,
+    /// Ruta del recurso afectado (el original en las copias en conflicto)
+    pub path: String,
+    /// Ruta de la copia en conflicto
+    pub conflict_path: Option<String>,
+    /// Explicación legible (p. ej. quién tiene el bloqueo)
+    pub detail: Option<String>,
+    /// Veces que se ha repetido el mismo conflicto sin resolver
+    pub occurrences: i32,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub resolution: Option<ConflictResolution>,
+}
+
+impl SyncConflict {
+    pub fn new(
+        user_id: String,
+        kind: ConflictKind,
+        path: String,
+        conflict_path: Option<String>,
+        detail: Option<String>,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            kind,
+            path,
+            conflict_path,
+            detail,
+            occurrences: 1,
+            created_at: now,
+            last_seen_at: now,
+            resolved_at: None,
+            resolution: None,
+        }
+    }
+
+    pub fn is_resolved(&self) -> bool {
+        self.resolved_at.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_round_trip() {
+        for kind in [ConflictKind::ConflictedCopy, ConflictKind::RejectedWrite, ConflictKind::LockContention] {
+            assert_eq!(ConflictKind::parse(kind.as_str()), Some(kind));
+        }
+        for resolution in ConflictKind::ConflictedCopy.resolutions() {
+            assert_eq!(ConflictResolution::parse(resolution.as_str()), Some(*resolution));
+        }
+        assert_eq!(ConflictResolution::parse("keep_all"), None);
+    }
+
+    #[test]
+    fn test_rejected_writes_can_only_be_dismissed() {
+        assert_eq!(ConflictKind::RejectedWrite.resolutions(), &[ConflictResolution::KeepTheirs]);
+        assert!(ConflictKind::ConflictedCopy.resolutions().contains(&ConflictResolution::KeepMine));
+    }
+}
@@ -0,0 +1,135 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use crate::common::errors::DomainError;
+
+/// Longitud máxima del nombre de una etiqueta, en caracteres (columna VARCHAR(64))
+pub const MAX_TAG_NAME_LENGTH: usize = 64;
+
+/// Color de las etiquetas creadas sin indicar ninguno
+pub const DEFAULT_TAG_COLOR: &str = "#808080";
+
+/// Tipo del elemento etiquetado
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaggedItemType {
+    File,
+    Folder,
+}
+
+impl TaggedItemType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::File => "file",
+            Self::Folder => "folder",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "file" => Some(Self::File),
+            "folder" => Some(Self::Folder),
+            _ => None,
+        }
+    }
+}
+
+/// Archivo o carpeta al que se ha puesto una etiqueta
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TaggedItem {
+    pub item_id: String,
+    pub item_type: TaggedItemType,
+}
+
+/// Etiqueta definida por un usuario para clasificar sus archivos y carpetas.
+/// Cada usuario tiene las suyas y no puede repetir nombre
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tag {
+    pub id: String,
+    pub owner_id: String,
+    pub name: String,
+    /// Color en formato `#rrggbb`, en minúsculas
+    pub color: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Tag {
+    pub fn new(owner_id: String, name: &str, color: Option<&str>) -> Result<Self, DomainError> {
+        let now = Utc::now();
+        Ok(Self {
+            id: Uuid::new_v4().to_string(),
+            owner_id,
+            name: validate_name(name)?,
+            color: validate_color(color.unwrap_or(DEFAULT_TAG_COLOR))?,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    pub fn rename(&mut self, name: &str) -> Result<(), DomainError> {
+        self.name = validate_name(name)?;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    pub fn set_color(&mut self, color: &str) -> Result<(), DomainError> {
+        self.color = validate_color(color)?;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+}
+
+fn validate_name(name: &str) -> Result<String, DomainError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_TAG_NAME_LENGTH {
+        return Err(DomainError::validation_error(format!(
+            "El nombre de la etiqueta debe tener entre 1 y {} caracteres", MAX_TAG_NAME_LENGTH
+        )));
+    }
+    Ok(name.to_string())
+}
+
+/// Acepta `#rrggbb` en mayúsculas o minúsculas y lo guarda en minúsculas
+fn validate_color(color: &str) -> Result<String, DomainError> {
+    let color = color.trim();
+    let valid = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if !valid {
+        return Err(DomainError::validation_error(format!(
+            "Color de etiqueta no válido: {}. Se espera el formato #rrggbb", color
+        )));
+    }
+    Ok(color.to_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_validation() {
+        let mut tag = Tag::new("user-1".to_string(), "  Facturas ", None).unwrap();
+        assert_eq!(tag.name, "Facturas");
+        assert_eq!(tag.color, DEFAULT_TAG_COLOR);
+
+        tag.set_color("#FF8800").unwrap();
+        assert_eq!(tag.color, "#ff8800");
+        assert!(tag.set_color("ff8800").is_err());
+        assert!(tag.set_color("#ff88zz").is_err());
+
+        assert!(Tag::new("user-1".to_string(), " ", None).is_err());
+        assert!(tag.rename(&"ñ".repeat(MAX_TAG_NAME_LENGTH)).is_ok());
+        assert!(tag.rename(&"ñ".repeat(MAX_TAG_NAME_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn test_item_type_round_trip() {
+        for item_type in [TaggedItemType::File, TaggedItemType::Folder] {
+            assert_eq!(TaggedItemType::parse(item_type.as_str()), Some(item_type));
+        }
+        assert_eq!(TaggedItemType::parse("calendar"), None);
+    }
+}
@@ -0,0 +1,103 @@
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Utc};
+
+/// Longitud máxima del nombre de la instancia (columna VARCHAR(100))
+pub const MAX_INSTANCE_NAME_LENGTH: usize = 100;
+
+/// Longitud máxima del mensaje de la página de inicio de sesión
+pub const MAX_LOGIN_MESSAGE_LENGTH: usize = 2000;
+
+/// Tamaño máximo del logo en bytes
+pub const MAX_LOGO_BYTES: usize = 512 * 1024;
+
+/// Formatos de imagen admitidos para el logo
+pub const LOGO_CONTENT_TYPES: [&str; 4] = ["image/png", "image/jpeg", "image/svg+xml", "image/webp"];
+
+/// Logo de la instancia tal y como se subió
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThemeLogo {
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+/// Imagen de marca de la instancia, configurable por los administradores
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceTheme {
+    pub instance_name: String,
+    /// Color principal en formato `#rrggbb`
+    pub primary_color: String,
+    /// Color de resalte (botones al pasar el ratón, enlaces) en formato `#rrggbb`
+    pub accent_color: String,
+    /// Mensaje que se muestra en la página de inicio de sesión
+    pub login_message: Option<String>,
+    #[serde(skip)]
+    pub logo: Option<ThemeLogo>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Default for InstanceTheme {
+    /// Marca original de OxiCloud, usada mientras nadie la cambie
+    fn default() -> Self {
+        Self {
+            instance_name: "OxiCloud".to_string(),
+            primary_color: "#ff5e3a".to_string(),
+            accent_color: "#e64a29".to_string(),
+            login_message: None,
+            logo: None,
+            updated_at: DateTime::<Utc>::UNIX_EPOCH,
+        }
+    }
+}
+
+impl InstanceTheme {
+    /// Comprueba que un color tiene el formato `#rrggbb` o `#rgb`.
+    ///
+    /// Al acabar en la hoja de estilos generada, no se admite nada más para
+    /// que un valor no pueda inyectar reglas CSS.
+    pub fn is_valid_color(color: &str) -> bool {
+        match color.strip_prefix('#') {
+            Some(hex) => matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit()),
+            None => false,
+        }
+    }
+
+    /// Normaliza un color válido a `#rrggbb` en minúsculas
+    pub fn normalize_color(color: &str) -> String {
+        let hex = color.trim_start_matches('#').to_ascii_lowercase();
+        if hex.len() == 3 {
+            hex.chars().fold(String::from("#"), |mut out, c| {
+                out.push(c);
+                out.push(c);
+                out
+            })
+        } else {
+            format!("#{}", hex)
+        }
+    }
+
+    /// Versión del tema para invalidar cachés del navegador al cambiarlo
+    pub fn version(&self) -> i64 {
+        self.updated_at.timestamp()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_validation() {
+        assert!(InstanceTheme::is_valid_color("#ff5e3a"));
+        assert!(InstanceTheme::is_valid_color("#ABC"));
+        assert!(!InstanceTheme::is_valid_color("ff5e3a"));
+        assert!(!InstanceTheme::is_valid_color("#ff5e3"));
+        assert!(!InstanceTheme::is_valid_color("#fff;}body{display:none"));
+        assert!(!InstanceTheme::is_valid_color("red"));
+    }
+
+    #[test]
+    fn test_color_normalization() {
+        assert_eq!(InstanceTheme::normalize_color("#ABC"), "#aabbcc");
+        assert_eq!(InstanceTheme::normalize_color("#FF5E3A"), "#ff5e3a");
+    }
+}
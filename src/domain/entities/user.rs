@@ -27,6 +27,91 @@ pub type UserResult<T> = Result<T, UserError>;
 pub enum UserRole {
     Admin,
     User,
+    /// Cuenta invitada: solo puede leer sus archivos y lo que se comparte con ella
+    Guest,
+}
+
+/// Dónde se comprueba la contraseña de una cuenta
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthSource {
+    /// Contraseña guardada en OxiCloud
+    Local,
+    /// Directorio externo (LDAP, Active Directory), que decide también el
+    /// correo y el rol
+    Directory,
+    /// Cuenta del modo demostración, con contraseña local. Solo las cuentas
+    /// marcadas así se borran al restaurar la demostración
+    Demo,
+}
+
+impl AuthSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuthSource::Local => "local",
+            AuthSource::Directory => "directory",
+            AuthSource::Demo => "demo",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "local" => Some(AuthSource::Local),
+            "directory" => Some(AuthSource::Directory),
+            "demo" => Some(AuthSource::Demo),
+            _ => None,
+        }
+    }
+}
+
+/// Acciones que dependen del rol del usuario
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    /// Listar usuarios y cambiar sus roles
+    ManageUsers,
+    /// Consultar y cambiar cuotas y políticas de cuota
+    ManageQuotas,
+    /// Configuración de la instancia: tema, anuncios, flags, mantenimiento...
+    ManageInstance,
+    /// Subir, modificar, mover y borrar archivos y carpetas, y también
+    /// calendarios y contactos por CalDAV y CardDAV
+    WriteFiles,
+    /// Crear enlaces y recursos compartidos
+    CreateShares,
+}
+
+impl UserRole {
+    /// Todos los roles, del más al menos privilegiado
+    pub const ALL: [UserRole; 3] = [UserRole::Admin, UserRole::User, UserRole::Guest];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "admin" => Some(UserRole::Admin),
+            "user" => Some(UserRole::User),
+            "guest" => Some(UserRole::Guest),
+            _ => None,
+        }
+    }
+
+    /// Permisos que concede el rol
+    pub fn permissions(&self) -> &'static [Permission] {
+        match self {
+            UserRole::Admin => &[
+                Permission::ManageUsers,
+                Permission::ManageQuotas,
+                Permission::ManageInstance,
+                Permission::WriteFiles,
+                Permission::CreateShares,
+            ],
+            UserRole::User => &[Permission::WriteFiles, Permission::CreateShares],
+            UserRole::Guest => &[],
+        }
+    }
+
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.permissions().contains(&permission)
+    }
 }
 
 impl std::fmt::Display for UserRole {
@@ -34,6 +119,7 @@ impl std::fmt::Display for UserRole {
         match self {
             UserRole::Admin => write!(f, "admin"),
             UserRole::User => write!(f, "user"),
+            UserRole::Guest => write!(f, "guest"),
         }
     }
 }
@@ -52,6 +138,7 @@ pub struct User {
     updated_at: DateTime<Utc>,
     last_login_at: Option<DateTime<Utc>>,
     active: bool,
+    auth_source: AuthSource,
 }
 
 impl User {
@@ -102,6 +189,7 @@ impl User {
             updated_at: now,
             last_login_at: None,
             active: true,
+            auth_source: AuthSource::Local,
         })
     }
     
@@ -118,6 +206,7 @@ impl User {
         updated_at: DateTime<Utc>,
         last_login_at: Option<DateTime<Utc>>,
         active: bool,
+        auth_source: AuthSource,
     ) -> Self {
         Self {
             id,
@@ -131,6 +220,7 @@ impl User {
             updated_at,
             last_login_at,
             active,
+            auth_source,
         }
     }
     
@@ -175,6 +265,10 @@ impl User {
         self.active
     }
     
+    pub fn auth_source(&self) -> AuthSource {
+        self.auth_source
+    }
+    
     pub fn password_hash(&self) -> &str {
         &self.password_hash
     }
@@ -211,6 +305,30 @@ impl User {
         self.updated_at = Utc::now();
     }
     
+    // Cambiar la cuota de almacenamiento (negativa = sin límite)
+    pub fn update_storage_quota(&mut self, storage_quota_bytes: i64) {
+        self.storage_quota_bytes = storage_quota_bytes;
+        self.updated_at = Utc::now();
+    }
+    
+    // Cambiar el correo, por ejemplo al sincronizarlo con un directorio externo
+    pub fn update_email(&mut self, email: String) {
+        self.email = email;
+        self.updated_at = Utc::now();
+    }
+    
+    // Cambiar el rol, por ejemplo según los grupos de un directorio externo
+    pub fn update_role(&mut self, role: UserRole) {
+        self.role = role;
+        self.updated_at = Utc::now();
+    }
+    
+    // Cambiar dónde se autentica la cuenta, por ejemplo al vincularla al directorio
+    pub fn set_auth_source(&mut self, auth_source: AuthSource) {
+        self.auth_source = auth_source;
+        self.updated_at = Utc::now();
+    }
+    
     // Registrar login
     pub fn register_login(&mut self) {
         let now = Utc::now();
@@ -0,0 +1,87 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use crate::common::errors::DomainError;
+
+/// Longitud máxima del nombre de un grupo (columna VARCHAR(255))
+pub const MAX_GROUP_NAME_LENGTH: usize = 255;
+
+/// Longitud máxima de la descripción de un grupo
+pub const MAX_GROUP_DESCRIPTION_LENGTH: usize = 1_000;
+
+/// Grupo de usuarios, por ejemplo un equipo, con el que se comparte de una vez
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserGroup {
+    pub id: String,
+    /// Nombre único del grupo
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl UserGroup {
+    pub fn new(name: &str, description: Option<String>) -> Result<Self, DomainError> {
+        let now = Utc::now();
+        Ok(Self {
+            id: Uuid::new_v4().to_string(),
+            name: validate_name(name)?,
+            description: validate_description(description)?,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    pub fn rename(&mut self, name: &str) -> Result<(), DomainError> {
+        self.name = validate_name(name)?;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    pub fn set_description(&mut self, description: Option<String>) -> Result<(), DomainError> {
+        self.description = validate_description(description)?;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+}
+
+fn validate_name(name: &str) -> Result<String, DomainError> {
+    let name = name.trim();
+    if name.is_empty() || name.len() > MAX_GROUP_NAME_LENGTH {
+        return Err(DomainError::validation_error(format!(
+            "El nombre del grupo debe tener entre 1 y {} caracteres", MAX_GROUP_NAME_LENGTH
+        )));
+    }
+    Ok(name.to_string())
+}
+
+/// Una descripción vacía se guarda como ninguna
+fn validate_description(description: Option<String>) -> Result<Option<String>, DomainError> {
+    let description = description
+        .map(|description| description.trim().to_string())
+        .filter(|description| !description.is_empty());
+    if description.as_ref().is_some_and(|description| description.len() > MAX_GROUP_DESCRIPTION_LENGTH) {
+        return Err(DomainError::validation_error(format!(
+            "La descripción del grupo no puede superar {} caracteres", MAX_GROUP_DESCRIPTION_LENGTH
+        )));
+    }
+    Ok(description)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_validation() {
+        let mut group = UserGroup::new("  Marketing ", Some("   ".to_string())).unwrap();
+        assert_eq!(group.name, "Marketing");
+        assert_eq!(group.description, None);
+
+        assert!(UserGroup::new(" ", None).is_err());
+        assert!(group.rename(&"x".repeat(MAX_GROUP_NAME_LENGTH + 1)).is_err());
+        group.rename("Ventas").unwrap();
+        assert_eq!(group.name, "Ventas");
+    }
+}
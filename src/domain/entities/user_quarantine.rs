@@ -0,0 +1,19 @@
+use chrono::{DateTime, Utc};
+
+/// Usuario borrado que está en cuarentena: la cuenta está desactivada y sus
+/// datos se conservan hasta `purge_after`, cuando se eliminan definitivamente
+#[derive(Debug, Clone)]
+pub struct UserQuarantine {
+    pub user_id: String,
+    pub quarantined_at: DateTime<Utc>,
+    pub purge_after: DateTime<Utc>,
+    /// Administrador que borró al usuario
+    pub quarantined_by: String,
+}
+
+impl UserQuarantine {
+    /// Si ya ha pasado el periodo de conservación
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.purge_after <= now
+    }
+}
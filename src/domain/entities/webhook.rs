@@ -0,0 +1,208 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use chrono::{DateTime, Duration, Utc};
+
+/// Suceso al que se puede suscribir un webhook
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WebhookEvent {
+    #[serde(rename = "file.created")]
+    FileCreated,
+    #[serde(rename = "file.deleted")]
+    FileDeleted,
+    #[serde(rename = "share.created")]
+    ShareCreated,
+    #[serde(rename = "user.created")]
+    UserCreated,
+}
+
+impl WebhookEvent {
+    pub const ALL: [WebhookEvent; 4] = [Self::FileCreated, Self::FileDeleted, Self::ShareCreated, Self::UserCreated];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::FileCreated => "file.created",
+            Self::FileDeleted => "file.deleted",
+            Self::ShareCreated => "share.created",
+            Self::UserCreated => "user.created",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "file.created" => Some(Self::FileCreated),
+            "file.deleted" => Some(Self::FileDeleted),
+            "share.created" => Some(Self::ShareCreated),
+            "user.created" => Some(Self::UserCreated),
+            _ => None,
+        }
+    }
+}
+
+/// Dirección HTTP a la que el servidor avisa de los sucesos elegidos.
+///
+/// Cada envío va firmado con `secret`, que por eso se guarda en claro.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: String,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub secret: String,
+    pub description: Option<String>,
+    /// Los webhooks desactivados no reciben envíos nuevos
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Webhook {
+    pub fn new(url: String, events: Vec<WebhookEvent>, secret: String, description: Option<String>) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            url,
+            events,
+            secret,
+            description,
+            active: true,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn subscribes_to(&self, event: WebhookEvent) -> bool {
+        self.active && self.events.contains(&event)
+    }
+}
+
+/// Estado de un envío
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    /// Por enviar o a la espera de un reintento
+    Pending,
+    Succeeded,
+    /// Se agotaron los intentos
+    Failed,
+}
+
+impl DeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Succeeded => "succeeded",
+            Self::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(Self::Pending),
+            "succeeded" => Some(Self::Succeeded),
+            "failed" => Some(Self::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// Envío de un suceso a un webhook, con el resultado del último intento
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub webhook_id: String,
+    pub event: WebhookEvent,
+    /// Cuerpo JSON que se envía y se firma, igual en todos los intentos
+    pub payload: String,
+    pub status: DeliveryStatus,
+    pub attempts: u32,
+    /// Cuándo toca el siguiente intento; `None` si ya no hay más
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    /// Código HTTP de la última respuesta
+    pub response_status: Option<u16>,
+    /// Por qué falló el último intento
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+impl WebhookDelivery {
+    /// Envío pendiente con el identificador que lleva el cuerpo
+    pub fn new(id: String, webhook_id: String, event: WebhookEvent, payload: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id,
+            webhook_id,
+            event,
+            payload,
+            status: DeliveryStatus::Pending,
+            attempts: 0,
+            next_attempt_at: Some(now),
+            response_status: None,
+            error: None,
+            created_at: now,
+            delivered_at: None,
+        }
+    }
+
+    /// Anota un intento con respuesta correcta
+    pub fn succeed(&mut self, response_status: u16) {
+        let now = Utc::now();
+        self.attempts += 1;
+        self.status = DeliveryStatus::Succeeded;
+        self.response_status = Some(response_status);
+        self.error = None;
+        self.next_attempt_at = None;
+        self.delivered_at = Some(now);
+    }
+
+    /// Anota un intento fallido y programa el siguiente, cada vez al doble
+    /// de distancia, hasta agotar `max_attempts`
+    pub fn fail(&mut self, response_status: Option<u16>, error: String, max_attempts: u32, retry_delay: Duration) {
+        self.attempts += 1;
+        self.response_status = response_status;
+        self.error = Some(error);
+        if self.attempts >= max_attempts {
+            self.status = DeliveryStatus::Failed;
+            self.next_attempt_at = None;
+        } else {
+            let factor = 1i32 << (self.attempts - 1).min(16);
+            self.next_attempt_at = Some(Utc::now() + retry_delay * factor);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_round_trip() {
+        for event in WebhookEvent::ALL {
+            assert_eq!(WebhookEvent::parse(event.as_str()), Some(event));
+            assert_eq!(serde_json::to_value(event).unwrap(), event.as_str());
+        }
+        assert_eq!(WebhookEvent::parse("file.updated"), None);
+    }
+
+    #[test]
+    fn test_retries_back_off_until_exhausted() {
+        let mut delivery = WebhookDelivery::new("d1".to_string(), "w1".to_string(), WebhookEvent::FileCreated, "{}".to_string());
+        let delay = Duration::seconds(30);
+
+        let before = Utc::now();
+        delivery.fail(Some(500), "HTTP 500".to_string(), 3, delay);
+        let first = delivery.next_attempt_at.unwrap() - before;
+        assert_eq!(delivery.status, DeliveryStatus::Pending);
+        assert!(first >= delay && first < delay * 2);
+
+        let before = Utc::now();
+        delivery.fail(None, "timeout".to_string(), 3, delay);
+        let second = delivery.next_attempt_at.unwrap() - before;
+        assert!(second >= delay * 2 && second < delay * 3);
+        assert_eq!(delivery.response_status, None);
+
+        delivery.fail(Some(502), "HTTP 502".to_string(), 3, delay);
+        assert_eq!(delivery.status, DeliveryStatus::Failed);
+        assert_eq!(delivery.attempts, 3);
+        assert!(delivery.next_attempt_at.is_none());
+    }
+}
@@ -0,0 +1,29 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use crate::domain::entities::activity::{Activity, ActivityAction, ActivityTargetKind};
+use crate::common::errors::DomainError;
+
+pub type ActivityRepositoryResult<T> = Result<T, DomainError>;
+
+/// Criterios para listar la actividad; los campos vacíos no filtran
+#[derive(Debug, Clone, Default)]
+pub struct ActivityFilter {
+    /// Solo lo que hizo este usuario
+    pub actor_id: Option<String>,
+    /// Solo lo ocurrido en esta carpeta o con la propia carpeta
+    pub folder_id: Option<String>,
+    pub action: Option<ActivityAction>,
+    pub target_kind: Option<ActivityTargetKind>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+#[async_trait]
+pub trait ActivityRepository: Send + Sync + 'static {
+    /// Registra una acción
+    async fn record(&self, activity: &Activity) -> ActivityRepositoryResult<()>;
+
+    /// Obtiene una página de la actividad que cumple el filtro, la más
+    /// reciente primero, junto con el total de entradas que lo cumplen
+    async fn list(&self, filter: &ActivityFilter, limit: i64, offset: i64) -> ActivityRepositoryResult<(Vec<Activity>, i64)>;
+}
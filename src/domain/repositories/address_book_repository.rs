@@ -19,4 +19,10 @@ pub trait AddressBookRepository: Send + Sync + 'static {
     async fn share_address_book(&self, address_book_id: &Uuid, user_id: &str, can_write: bool) -> AddressBookRepositoryResult<()>;
     async fn unshare_address_book(&self, address_book_id: &Uuid, user_id: &str) -> AddressBookRepositoryResult<()>;
     async fn get_address_book_shares(&self, address_book_id: &Uuid) -> AddressBookRepositoryResult<Vec<(String, bool)>>;
+    async fn share_address_book_with_group(&self, address_book_id: &Uuid, group_id: &str, can_write: bool) -> AddressBookRepositoryResult<()>;
+    async fn unshare_address_book_from_group(&self, address_book_id: &Uuid, group_id: &str) -> AddressBookRepositoryResult<()>;
+    async fn get_address_book_group_shares(&self, address_book_id: &Uuid) -> AddressBookRepositoryResult<Vec<(String, bool)>>;
+    /// Whether the address book is shared with the user, directly or through
+    /// one of their groups, and if any of those shares allows writing
+    async fn get_user_address_book_share(&self, address_book_id: &Uuid, user_id: &str) -> AddressBookRepositoryResult<Option<bool>>;
 }
\ No newline at end of file
@@ -0,0 +1,33 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use crate::domain::entities::announcement::{Announcement, AnnouncementAcknowledgment};
+use crate::common::errors::DomainError;
+
+pub type AnnouncementRepositoryResult<T> = Result<T, DomainError>;
+
+#[async_trait]
+pub trait AnnouncementRepository: Send + Sync + 'static {
+    /// Obtiene todos los anuncios, los más recientes primero
+    async fn list_announcements(&self) -> AnnouncementRepositoryResult<Vec<Announcement>>;
+
+    /// Obtiene los anuncios cuya ventana incluye `now`
+    async fn list_active(&self, now: DateTime<Utc>) -> AnnouncementRepositoryResult<Vec<Announcement>>;
+
+    /// Obtiene un anuncio
+    async fn get_announcement(&self, id: &str) -> AnnouncementRepositoryResult<Option<Announcement>>;
+
+    /// Crea o actualiza un anuncio
+    async fn save_announcement(&self, announcement: &Announcement) -> AnnouncementRepositoryResult<()>;
+
+    /// Elimina un anuncio y sus lecturas. Devuelve `false` si no existía
+    async fn delete_announcement(&self, id: &str) -> AnnouncementRepositoryResult<bool>;
+
+    /// Registra que un usuario ha leído un anuncio; repetirlo no cambia la fecha
+    async fn mark_read(&self, id: &str, user_id: &str, read_at: DateTime<Utc>) -> AnnouncementRepositoryResult<()>;
+
+    /// De los anuncios indicados, devuelve los que el usuario ya ha leído
+    async fn read_announcements(&self, user_id: &str, ids: &[String]) -> AnnouncementRepositoryResult<Vec<String>>;
+
+    /// Cuenta las lecturas y los usuarios activos a los que va dirigido un anuncio
+    async fn acknowledgment(&self, announcement: &Announcement) -> AnnouncementRepositoryResult<AnnouncementAcknowledgment>;
+}
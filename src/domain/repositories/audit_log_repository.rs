@@ -0,0 +1,28 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use crate::domain::entities::audit_event::{AuditAction, AuditEvent};
+use crate::common::errors::DomainError;
+
+pub type AuditLogRepositoryResult<T> = Result<T, DomainError>;
+
+/// Criterios para consultar el registro; los campos vacíos no filtran
+#[derive(Debug, Clone, Default)]
+pub struct AuditLogFilter {
+    /// ID o nombre del usuario que actuó
+    pub user: Option<String>,
+    pub action: Option<AuditAction>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// Registro de auditoría de solo añadir: no hay forma de modificar ni de
+/// borrar lo guardado
+#[async_trait]
+pub trait AuditLogRepository: Send + Sync + 'static {
+    /// Añade una entrada al registro
+    async fn append(&self, event: &AuditEvent) -> AuditLogRepositoryResult<()>;
+
+    /// Obtiene una página de las entradas que cumplen el filtro, las más
+    /// recientes primero, junto con el total de entradas que lo cumplen
+    async fn query(&self, filter: &AuditLogFilter, limit: i64, offset: i64) -> AuditLogRepositoryResult<(Vec<AuditEvent>, i64)>;
+}
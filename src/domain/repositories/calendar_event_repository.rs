@@ -3,6 +3,7 @@ use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use crate::common::errors::DomainError;
 use crate::domain::entities::calendar_event::CalendarEvent;
+use crate::domain::entities::collection_change::CollectionChange;
 
 pub type CalendarEventRepositoryResult<T> = Result<T, DomainError>;
 
@@ -12,6 +13,9 @@ pub trait CalendarEventRepository: Send + Sync + 'static {
     /// Creates a new calendar event
     async fn create_event(&self, event: CalendarEvent) -> CalendarEventRepositoryResult<CalendarEvent>;
     
+    /// Creates several events in one transaction; if one fails, none is created
+    async fn create_events(&self, events: Vec<CalendarEvent>) -> CalendarEventRepositoryResult<Vec<CalendarEvent>>;
+    
     /// Updates an existing calendar event
     async fn update_event(&self, event: CalendarEvent) -> CalendarEventRepositoryResult<CalendarEvent>;
     
@@ -27,6 +31,10 @@ pub trait CalendarEventRepository: Send + Sync + 'static {
     /// Finds events in a calendar by their summary/title (partial match)
     async fn find_events_by_summary(&self, calendar_id: &Uuid, summary: &str) -> CalendarEventRepositoryResult<Vec<CalendarEvent>>;
     
+    /// Finds events of several calendars whose summary, description or location
+    /// contain the query, soonest first, up to `limit`
+    async fn search_events(&self, calendar_ids: &[Uuid], query: &str, limit: i64) -> CalendarEventRepositoryResult<Vec<CalendarEvent>>;
+    
     /// Gets events in a specific time range for a calendar
     async fn get_events_in_time_range(
         &self, 
@@ -59,4 +67,10 @@ pub trait CalendarEventRepository: Send + Sync + 'static {
         start: &DateTime<Utc>,
         end: &DateTime<Utc>
     ) -> CalendarEventRepositoryResult<Vec<CalendarEvent>>;
+    
+    /// Lists the change journal entries of a calendar after a revision, oldest first
+    async fn get_changes_since(&self, calendar_id: &Uuid, revision: i64) -> CalendarEventRepositoryResult<Vec<CollectionChange>>;
+    
+    /// Oldest revision still kept in the change journal of a calendar
+    async fn get_oldest_change_revision(&self, calendar_id: &Uuid) -> CalendarEventRepositoryResult<Option<i64>>;
 }
\ No newline at end of file
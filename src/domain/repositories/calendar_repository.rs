@@ -26,13 +26,14 @@ pub trait CalendarRepository: Send + Sync + 'static {
     /// Finds a calendar by name and owner
     async fn find_calendar_by_name_and_owner(&self, name: &str, owner_id: &str) -> CalendarRepositoryResult<Calendar>;
     
-    /// Lists calendars shared with a specific user
+    /// Lists calendars shared with a specific user, directly or through one
+    /// of their groups
     async fn list_calendars_shared_with_user(&self, user_id: &str) -> CalendarRepositoryResult<Vec<Calendar>>;
     
     /// List public calendars
     async fn list_public_calendars(&self, limit: i64, offset: i64) -> CalendarRepositoryResult<Vec<Calendar>>;
     
-    /// Checks if a user has access to a calendar
+    /// Checks if a user has access to a calendar, including through their groups
     async fn user_has_calendar_access(&self, calendar_id: &Uuid, user_id: &str) -> CalendarRepositoryResult<bool>;
     
     /// Gets a custom property for a calendar
@@ -55,4 +56,16 @@ pub trait CalendarRepository: Send + Sync + 'static {
     
     /// Get calendar sharing information (who has access to this calendar)
     async fn get_calendar_shares(&self, calendar_id: &Uuid) -> CalendarRepositoryResult<Vec<(String, String)>>;
-}
\ No newline at end of file
+    
+    /// Share calendar with every member of a user group
+    async fn share_calendar_with_group(&self, calendar_id: &Uuid, group_id: &str, access_level: &str) -> CalendarRepositoryResult<()>;
+    
+    /// Remove calendar sharing for a user group
+    async fn remove_calendar_group_sharing(&self, calendar_id: &Uuid, group_id: &str) -> CalendarRepositoryResult<()>;
+    
+    /// Get the user groups this calendar is shared with and their access levels
+    async fn get_calendar_group_shares(&self, calendar_id: &Uuid) -> CalendarRepositoryResult<Vec<(String, String)>>;
+    
+    /// Access levels a user has been granted on a calendar, directly or through their groups
+    async fn get_user_calendar_access_levels(&self, calendar_id: &Uuid, user_id: &str) -> CalendarRepositoryResult<Vec<String>>;
+}
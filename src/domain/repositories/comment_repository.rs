@@ -0,0 +1,30 @@
+use async_trait::async_trait;
+use crate::domain::entities::comment::{Comment, CommentTargetKind};
+use crate::common::errors::DomainError;
+
+pub type CommentRepositoryResult<T> = Result<T, DomainError>;
+
+#[async_trait]
+pub trait CommentRepository: Send + Sync + 'static {
+    /// Guarda un comentario nuevo
+    async fn create_comment(&self, comment: &Comment) -> CommentRepositoryResult<()>;
+
+    /// Guarda el texto, las menciones y la fecha de edición de un comentario
+    async fn update_comment(&self, comment: &Comment) -> CommentRepositoryResult<()>;
+
+    /// Busca un comentario por su ID
+    async fn get_comment(&self, id: &str) -> CommentRepositoryResult<Option<Comment>>;
+
+    /// Obtiene una página de los comentarios de un elemento, el más antiguo
+    /// primero, junto con el total
+    async fn list_comments(
+        &self,
+        item_kind: CommentTargetKind,
+        item_id: &str,
+        limit: i64,
+        offset: i64,
+    ) -> CommentRepositoryResult<(Vec<Comment>, i64)>;
+
+    /// Elimina un comentario. Devuelve `false` si no existía
+    async fn delete_comment(&self, id: &str) -> CommentRepositoryResult<bool>;
+}
@@ -3,6 +3,7 @@ use sqlx::types::Uuid;
 use std::result::Result;
 
 use crate::common::errors::DomainError;
+use crate::domain::entities::collection_change::CollectionChange;
 use crate::domain::entities::contact::{Contact, ContactGroup};
 
 pub type ContactRepositoryResult<T> = Result<T, DomainError>;
@@ -10,6 +11,8 @@ pub type ContactRepositoryResult<T> = Result<T, DomainError>;
 #[async_trait]
 pub trait ContactRepository: Send + Sync + 'static {
     async fn create_contact(&self, contact: Contact) -> ContactRepositoryResult<Contact>;
+    /// Creates several contacts at once; if one fails, none is created
+    async fn create_contacts(&self, contacts: Vec<Contact>) -> ContactRepositoryResult<Vec<Contact>>;
     async fn update_contact(&self, contact: Contact) -> ContactRepositoryResult<Contact>;
     async fn delete_contact(&self, id: &Uuid) -> ContactRepositoryResult<()>;
     async fn get_contact_by_id(&self, id: &Uuid) -> ContactRepositoryResult<Option<Contact>>;
@@ -18,6 +21,10 @@ pub trait ContactRepository: Send + Sync + 'static {
     async fn get_contacts_by_email(&self, email: &str) -> ContactRepositoryResult<Vec<Contact>>;
     async fn get_contacts_by_group(&self, group_id: &Uuid) -> ContactRepositoryResult<Vec<Contact>>;
     async fn search_contacts(&self, address_book_id: &Uuid, query: &str) -> ContactRepositoryResult<Vec<Contact>>;
+    /// Lists the change journal entries of an address book after a revision, oldest first
+    async fn get_changes_since(&self, address_book_id: &Uuid, revision: i64) -> ContactRepositoryResult<Vec<CollectionChange>>;
+    /// Oldest revision still kept in the change journal of an address book
+    async fn get_oldest_change_revision(&self, address_book_id: &Uuid) -> ContactRepositoryResult<Option<i64>>;
 }
 
 #[async_trait]
@@ -0,0 +1,29 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use crate::domain::entities::dead_property::DeadProperty;
+use crate::common::errors::DomainError;
+
+pub type DeadPropertyRepositoryResult<T> = Result<T, DomainError>;
+
+#[async_trait]
+pub trait DeadPropertyRepository: Send + Sync + 'static {
+    /// Obtiene las propiedades de varios recursos, indexadas por ID de recurso.
+    ///
+    /// Los recursos sin propiedades no aparecen en el resultado.
+    async fn get_properties(&self, resource_ids: &[String]) -> DeadPropertyRepositoryResult<HashMap<String, Vec<DeadProperty>>>;
+
+    /// Guarda y elimina propiedades de un recurso en una sola transacción
+    async fn patch_properties(
+        &self,
+        resource_id: &str,
+        set: &[DeadProperty],
+        remove: &[(String, String)],
+    ) -> DeadPropertyRepositoryResult<()>;
+
+    /// Copia todas las propiedades de un recurso a otro, sustituyendo las que ya tuviera
+    async fn copy_properties(&self, from_resource_id: &str, to_resource_id: &str) -> DeadPropertyRepositoryResult<()>;
+
+    /// Elimina todas las propiedades de un recurso
+    async fn delete_properties(&self, resource_id: &str) -> DeadPropertyRepositoryResult<()>;
+}
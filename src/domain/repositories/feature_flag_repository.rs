@@ -0,0 +1,23 @@
+use async_trait::async_trait;
+use crate::domain::entities::feature_flag::{FeatureFlag, FlagOverride, FlagScope};
+use crate::common::errors::DomainError;
+
+pub type FeatureFlagRepositoryResult<T> = Result<T, DomainError>;
+
+#[async_trait]
+pub trait FeatureFlagRepository: Send + Sync + 'static {
+    /// Obtiene todos los flags con sus excepciones
+    async fn list_flags(&self) -> FeatureFlagRepositoryResult<Vec<FeatureFlag>>;
+
+    /// Crea o actualiza la regla global de un flag (sin tocar sus excepciones)
+    async fn save_flag(&self, flag: &FeatureFlag) -> FeatureFlagRepositoryResult<()>;
+
+    /// Elimina un flag y sus excepciones. Devuelve `false` si no existía
+    async fn delete_flag(&self, key: &str) -> FeatureFlagRepositoryResult<bool>;
+
+    /// Crea o actualiza una excepción de un flag existente
+    async fn set_override(&self, key: &str, flag_override: &FlagOverride) -> FeatureFlagRepositoryResult<()>;
+
+    /// Elimina una excepción. Devuelve `false` si no existía
+    async fn remove_override(&self, key: &str, scope: FlagScope, subject: &str) -> FeatureFlagRepositoryResult<bool>;
+}
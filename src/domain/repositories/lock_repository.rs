@@ -0,0 +1,31 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use crate::domain::entities::lock::ResourceLock;
+use crate::common::errors::DomainError;
+
+pub type LockRepositoryResult<T> = Result<T, DomainError>;
+
+#[async_trait]
+pub trait LockRepository: Send + Sync + 'static {
+    /// Guarda un bloqueo nuevo
+    async fn create_lock(&self, lock: ResourceLock) -> LockRepositoryResult<ResourceLock>;
+
+    /// Obtiene un bloqueo vigente por su token
+    async fn get_lock(&self, token: &str) -> LockRepositoryResult<Option<ResourceLock>>;
+
+    /// Obtiene los bloqueos vigentes sobre la ruta, sobre las carpetas que la
+    /// contienen y sobre todo lo que hay dentro de ella
+    async fn find_locks_affecting(&self, path: &str) -> LockRepositoryResult<Vec<ResourceLock>>;
+
+    /// Cambia la caducidad de un bloqueo
+    async fn update_lock_expiry(&self, token: &str, timeout_secs: u64, expires_at: DateTime<Utc>) -> LockRepositoryResult<()>;
+
+    /// Elimina un bloqueo; devuelve si existía
+    async fn delete_lock(&self, token: &str) -> LockRepositoryResult<bool>;
+
+    /// Elimina los bloqueos de la ruta y de todo lo que hay dentro de ella
+    async fn delete_locks_under(&self, path: &str) -> LockRepositoryResult<u64>;
+
+    /// Elimina bloqueos caducados
+    async fn delete_expired_locks(&self) -> LockRepositoryResult<u64>;
+}
@@ -7,4 +7,20 @@ pub mod folder_repository;
 pub mod session_repository;
 pub mod share_repository;
 pub mod trash_repository;
-pub mod user_repository;
\ No newline at end of file
+pub mod user_repository;
+pub mod user_group_repository;
+pub mod lock_repository;
+pub mod dead_property_repository;
+pub mod feature_flag_repository;
+pub mod quota_policy_repository;
+pub mod sync_conflict_repository;
+pub mod theme_repository;
+pub mod announcement_repository;
+pub mod scheduling_repository;
+pub mod share_notification_repository;
+pub mod notification_preferences_repository;
+pub mod activity_repository;
+pub mod audit_log_repository;
+pub mod webhook_repository;
+pub mod tag_repository;
+pub mod comment_repository;
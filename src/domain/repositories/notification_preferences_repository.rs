@@ -0,0 +1,14 @@
+use async_trait::async_trait;
+use crate::domain::entities::notification_preferences::NotificationPreferences;
+use crate::common::errors::DomainError;
+
+pub type NotificationPreferencesRepositoryResult<T> = Result<T, DomainError>;
+
+#[async_trait]
+pub trait NotificationPreferencesRepository: Send + Sync + 'static {
+    /// Obtiene las preferencias de un usuario; `None` si nunca las ha cambiado
+    async fn get_preferences(&self, user_id: &str) -> NotificationPreferencesRepositoryResult<Option<NotificationPreferences>>;
+
+    /// Guarda las preferencias de un usuario, creándolas si no existían
+    async fn save_preferences(&self, preferences: &NotificationPreferences) -> NotificationPreferencesRepositoryResult<()>;
+}
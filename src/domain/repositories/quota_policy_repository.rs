@@ -0,0 +1,31 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use crate::domain::entities::quota_policy::QuotaPolicy;
+use crate::common::errors::DomainError;
+
+pub type QuotaPolicyRepositoryResult<T> = Result<T, DomainError>;
+
+#[async_trait]
+pub trait QuotaPolicyRepository: Send + Sync + 'static {
+    /// Obtiene las políticas de cuota de todos los grupos
+    async fn list_policies(&self) -> QuotaPolicyRepositoryResult<Vec<QuotaPolicy>>;
+
+    /// Crea o actualiza la política de un grupo
+    async fn save_policy(&self, policy: &QuotaPolicy) -> QuotaPolicyRepositoryResult<()>;
+
+    /// Elimina la política de un grupo. Devuelve `false` si no existía
+    async fn delete_policy(&self, group: &str) -> QuotaPolicyRepositoryResult<bool>;
+
+    /// Cuota fijada a mano para un usuario, si la tiene
+    async fn get_user_override(&self, user_id: &str) -> QuotaPolicyRepositoryResult<Option<i64>>;
+
+    /// Cuotas fijadas a mano, por ID de usuario
+    async fn list_user_overrides(&self) -> QuotaPolicyRepositoryResult<HashMap<String, i64>>;
+
+    /// Fija la cuota de un usuario por encima de las políticas de sus grupos
+    async fn set_user_override(&self, user_id: &str, quota_bytes: i64) -> QuotaPolicyRepositoryResult<()>;
+
+    /// Quita la cuota fijada de un usuario. Devuelve `false` si no tenía
+    async fn remove_user_override(&self, user_id: &str) -> QuotaPolicyRepositoryResult<bool>;
+}
@@ -0,0 +1,23 @@
+use async_trait::async_trait;
+use crate::domain::entities::scheduling_message::SchedulingMessage;
+use crate::common::errors::DomainError;
+
+pub type SchedulingRepositoryResult<T> = Result<T, DomainError>;
+
+#[async_trait]
+pub trait SchedulingRepository: Send + Sync + 'static {
+    /// Entrega un mensaje en la bandeja de entrada de su destinatario
+    async fn deliver_message(&self, message: &SchedulingMessage) -> SchedulingRepositoryResult<()>;
+
+    /// Obtiene la bandeja de entrada de un usuario, los mensajes más antiguos primero
+    async fn list_messages(&self, user_id: &str) -> SchedulingRepositoryResult<Vec<SchedulingMessage>>;
+
+    /// Obtiene un mensaje de la bandeja de entrada de un usuario
+    async fn get_message(&self, user_id: &str, id: &str) -> SchedulingRepositoryResult<Option<SchedulingMessage>>;
+
+    /// Elimina un mensaje de la bandeja de entrada. Devuelve `false` si no existía
+    async fn delete_message(&self, user_id: &str, id: &str) -> SchedulingRepositoryResult<bool>;
+
+    /// Elimina los mensajes de un usuario sobre un evento, una vez procesados
+    async fn delete_messages_for_uid(&self, user_id: &str, ical_uid: &str) -> SchedulingRepositoryResult<u64>;
+}
@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use crate::domain::entities::session::Session;
 use crate::common::errors::DomainError;
 
@@ -50,9 +51,26 @@ pub trait SessionRepository: Send + Sync + 'static {
     /// Revoca una sesión específica
     async fn revoke_session(&self, session_id: &str) -> SessionRepositoryResult<()>;
     
+    /// Sustituye el token de actualización de una sesión sin cambiar su ID,
+    /// de modo que la sesión sigue identificando al mismo dispositivo
+    async fn rotate_refresh_token(
+        &self,
+        session_id: &str,
+        refresh_token: &str,
+        expires_at: DateTime<Utc>,
+        ip_address: Option<&str>,
+        user_agent: Option<&str>,
+    ) -> SessionRepositoryResult<()>;
+    
+    /// Anota actividad en una sesión
+    async fn touch_session(&self, session_id: &str) -> SessionRepositoryResult<()>;
+    
     /// Revoca todas las sesiones de un usuario
     async fn revoke_all_user_sessions(&self, user_id: &str) -> SessionRepositoryResult<u64>;
     
+    /// Revoca todas las sesiones de un usuario salvo `keep_session_id`
+    async fn revoke_other_user_sessions(&self, user_id: &str, keep_session_id: &str) -> SessionRepositoryResult<u64>;
+    
     /// Elimina sesiones expiradas
     async fn delete_expired_sessions(&self) -> SessionRepositoryResult<u64>;
 }
\ No newline at end of file
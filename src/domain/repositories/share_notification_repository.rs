@@ -0,0 +1,21 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use crate::domain::entities::share_notification::ShareNotification;
+use crate::common::errors::DomainError;
+
+pub type ShareNotificationRepositoryResult<T> = Result<T, DomainError>;
+
+#[async_trait]
+pub trait ShareNotificationRepository: Send + Sync + 'static {
+    /// Guarda los avisos de una vez, uno por destinatario
+    async fn create_notifications(&self, notifications: &[ShareNotification]) -> ShareNotificationRepositoryResult<()>;
+
+    /// Obtiene los avisos de un usuario, los más recientes primero
+    async fn list_notifications(&self, user_id: &str, limit: i64) -> ShareNotificationRepositoryResult<Vec<ShareNotification>>;
+
+    /// Marca un aviso del usuario como leído; `None` si no existe
+    async fn mark_read(&self, user_id: &str, id: &str, read_at: DateTime<Utc>) -> ShareNotificationRepositoryResult<Option<ShareNotification>>;
+
+    /// Elimina un aviso del usuario; `false` si no existía
+    async fn delete_notification(&self, user_id: &str, id: &str) -> ShareNotificationRepositoryResult<bool>;
+}
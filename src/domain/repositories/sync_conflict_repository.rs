@@ -0,0 +1,27 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use crate::domain::entities::sync_conflict::{ConflictResolution, SyncConflict};
+use crate::common::errors::DomainError;
+
+pub type SyncConflictRepositoryResult<T> = Result<T, DomainError>;
+
+#[async_trait]
+pub trait SyncConflictRepository: Send + Sync + 'static {
+    /// Guarda un conflicto nuevo. Si el usuario ya tiene uno igual sin
+    /// resolver (mismo tipo y rutas) se incrementan sus ocurrencias en su lugar
+    async fn record_conflict(&self, conflict: SyncConflict) -> SyncConflictRepositoryResult<SyncConflict>;
+
+    /// Obtiene los conflictos de un usuario, los más recientes primero
+    async fn list_conflicts(&self, user_id: &str, include_resolved: bool) -> SyncConflictRepositoryResult<Vec<SyncConflict>>;
+
+    /// Obtiene un conflicto de un usuario
+    async fn get_conflict(&self, user_id: &str, id: &str) -> SyncConflictRepositoryResult<Option<SyncConflict>>;
+
+    /// Marca un conflicto como resuelto
+    async fn mark_resolved(
+        &self,
+        id: &str,
+        resolution: ConflictResolution,
+        resolved_at: DateTime<Utc>,
+    ) -> SyncConflictRepositoryResult<()>;
+}
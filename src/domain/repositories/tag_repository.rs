@@ -0,0 +1,40 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use crate::domain::entities::tag::{Tag, TaggedItem};
+use crate::common::errors::DomainError;
+
+pub type TagRepositoryResult<T> = Result<T, DomainError>;
+
+#[async_trait]
+pub trait TagRepository: Send + Sync + 'static {
+    /// Crea una etiqueta; falla si su dueño ya tiene otra con el mismo nombre
+    async fn create_tag(&self, tag: &Tag) -> TagRepositoryResult<()>;
+
+    /// Guarda el nombre y el color de una etiqueta
+    async fn update_tag(&self, tag: &Tag) -> TagRepositoryResult<()>;
+
+    /// Busca una etiqueta por su ID
+    async fn get_tag(&self, id: &str) -> TagRepositoryResult<Option<Tag>>;
+
+    /// Etiquetas de un usuario, por nombre
+    async fn list_tags(&self, owner_id: &str) -> TagRepositoryResult<Vec<Tag>>;
+
+    /// Elimina una etiqueta con todas sus asignaciones. Devuelve `false` si no existía
+    async fn delete_tag(&self, id: &str) -> TagRepositoryResult<bool>;
+
+    /// Pone una etiqueta a un elemento. Devuelve `false` si ya la tenía
+    async fn assign_tag(&self, tag_id: &str, item: &TaggedItem) -> TagRepositoryResult<bool>;
+
+    /// Quita una etiqueta de un elemento. Devuelve `false` si no la tenía
+    async fn unassign_tag(&self, tag_id: &str, item: &TaggedItem) -> TagRepositoryResult<bool>;
+
+    /// Elementos que llevan una etiqueta, de la asignación más reciente a la más antigua
+    async fn list_tagged_items(&self, tag_id: &str) -> TagRepositoryResult<Vec<TaggedItem>>;
+
+    /// Etiquetas de un usuario puestas en varios elementos, indexadas por ID de elemento
+    async fn get_tags_for_items(&self, owner_id: &str, item_ids: &[String]) -> TagRepositoryResult<HashMap<String, Vec<Tag>>>;
+
+    /// Elementos que llevan todas las etiquetas indicadas
+    async fn find_items_with_all_tags(&self, tag_ids: &[String]) -> TagRepositoryResult<Vec<TaggedItem>>;
+}
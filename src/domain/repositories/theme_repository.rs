@@ -0,0 +1,17 @@
+use async_trait::async_trait;
+use crate::domain::entities::theme::{InstanceTheme, ThemeLogo};
+use crate::common::errors::DomainError;
+
+pub type ThemeRepositoryResult<T> = Result<T, DomainError>;
+
+#[async_trait]
+pub trait ThemeRepository: Send + Sync + 'static {
+    /// Obtiene el tema de la instancia con su logo, o `None` si nunca se configuró
+    async fn get_theme(&self) -> ThemeRepositoryResult<Option<InstanceTheme>>;
+
+    /// Guarda el nombre, los colores y el mensaje del tema (sin tocar el logo)
+    async fn save_theme(&self, theme: &InstanceTheme) -> ThemeRepositoryResult<()>;
+
+    /// Sustituye el logo, o lo quita con `None`
+    async fn set_logo(&self, logo: Option<&ThemeLogo>) -> ThemeRepositoryResult<()>;
+}
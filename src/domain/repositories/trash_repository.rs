@@ -13,4 +13,5 @@ pub trait TrashRepository: Send + Sync {
     async fn delete_permanently(&self, id: &Uuid, user_id: &Uuid) -> Result<()>;
     async fn clear_trash(&self, user_id: &Uuid) -> Result<()>;
     async fn get_expired_items(&self) -> Result<Vec<TrashedItem>>;
+    async fn get_all_items(&self) -> Result<Vec<TrashedItem>>;
 }
\ No newline at end of file
@@ -0,0 +1,36 @@
+use async_trait::async_trait;
+use crate::domain::entities::user_group::UserGroup;
+use crate::common::errors::DomainError;
+
+pub type UserGroupRepositoryResult<T> = Result<T, DomainError>;
+
+#[async_trait]
+pub trait UserGroupRepository: Send + Sync + 'static {
+    /// Crea un grupo; falla si ya hay otro con el mismo nombre
+    async fn create_group(&self, group: &UserGroup) -> UserGroupRepositoryResult<()>;
+
+    /// Guarda el nombre y la descripción de un grupo
+    async fn update_group(&self, group: &UserGroup) -> UserGroupRepositoryResult<()>;
+
+    /// Busca un grupo por su ID
+    async fn get_group(&self, id: &str) -> UserGroupRepositoryResult<Option<UserGroup>>;
+
+    /// Obtiene todos los grupos, por nombre
+    async fn list_groups(&self) -> UserGroupRepositoryResult<Vec<UserGroup>>;
+
+    /// Elimina un grupo con sus miembros y lo que se compartía con él.
+    /// Devuelve `false` si no existía
+    async fn delete_group(&self, id: &str) -> UserGroupRepositoryResult<bool>;
+
+    /// Añade un usuario a un grupo. Devuelve `false` si ya era miembro
+    async fn add_member(&self, group_id: &str, user_id: &str) -> UserGroupRepositoryResult<bool>;
+
+    /// Quita a un usuario de un grupo. Devuelve `false` si no era miembro
+    async fn remove_member(&self, group_id: &str, user_id: &str) -> UserGroupRepositoryResult<bool>;
+
+    /// IDs de los miembros de un grupo
+    async fn list_members(&self, group_id: &str) -> UserGroupRepositoryResult<Vec<String>>;
+
+    /// Grupos de los que es miembro un usuario
+    async fn list_groups_for_user(&self, user_id: &str) -> UserGroupRepositoryResult<Vec<UserGroup>>;
+}
@@ -89,6 +89,9 @@ pub trait UserRepository: Send + Sync + 'static {
     /// Lista usuarios por rol (admin o user)
     async fn list_users_by_role(&self, role: &str) -> UserRepositoryResult<Vec<User>>;
     
+    /// Busca usuarios activos cuyo nombre de usuario o correo contenga el texto
+    async fn search_users(&self, query: &str, limit: i64) -> UserRepositoryResult<Vec<User>>;
+    
     /// Elimina un usuario
     async fn delete_user(&self, user_id: &str) -> UserRepositoryResult<()>;
 }
\ No newline at end of file
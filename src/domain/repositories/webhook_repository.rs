@@ -0,0 +1,39 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use crate::domain::entities::webhook::{Webhook, WebhookDelivery, WebhookEvent};
+use crate::common::errors::DomainError;
+
+pub type WebhookRepositoryResult<T> = Result<T, DomainError>;
+
+/// Webhooks registrados y registro de sus envíos
+#[async_trait]
+pub trait WebhookRepository: Send + Sync + 'static {
+    async fn create_webhook(&self, webhook: &Webhook) -> WebhookRepositoryResult<()>;
+
+    /// Guarda los cambios de un webhook; falla si no existe
+    async fn update_webhook(&self, webhook: &Webhook) -> WebhookRepositoryResult<()>;
+
+    /// Borra un webhook junto con sus envíos; falla si no existe
+    async fn delete_webhook(&self, id: &str) -> WebhookRepositoryResult<()>;
+
+    async fn get_webhook(&self, id: &str) -> WebhookRepositoryResult<Option<Webhook>>;
+
+    /// Todos los webhooks, los más antiguos primero
+    async fn list_webhooks(&self) -> WebhookRepositoryResult<Vec<Webhook>>;
+
+    /// Webhooks activos suscritos a un suceso
+    async fn list_webhooks_for_event(&self, event: WebhookEvent) -> WebhookRepositoryResult<Vec<Webhook>>;
+
+    async fn create_delivery(&self, delivery: &WebhookDelivery) -> WebhookRepositoryResult<()>;
+
+    /// Guarda el resultado de un intento de envío
+    async fn update_delivery(&self, delivery: &WebhookDelivery) -> WebhookRepositoryResult<()>;
+
+    /// Envíos pendientes cuyo siguiente intento ya toca, los más antiguos
+    /// primero
+    async fn due_deliveries(&self, now: DateTime<Utc>, limit: i64) -> WebhookRepositoryResult<Vec<WebhookDelivery>>;
+
+    /// Página de los envíos de un webhook, los más recientes primero, junto
+    /// con el total
+    async fn list_deliveries(&self, webhook_id: &str, limit: i64, offset: i64) -> WebhookRepositoryResult<(Vec<WebhookDelivery>, i64)>;
+}
@@ -35,6 +35,11 @@ pub struct TokenClaims {
     
     /// User role for authorization checks
     pub role: String,    
+    
+    /// Login session the token was issued for, so revoking the session
+    /// also rejects its access tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
 }
 
 /**
@@ -130,7 +135,7 @@ impl AuthService {
         }
     }
     
-    pub fn generate_access_token(&self, user: &User) -> Result<String, AuthError> {
+    pub fn generate_access_token(&self, user: &User, session_id: Option<&str>) -> Result<String, AuthError> {
         let now = Utc::now().timestamp();
         
         // Log information for debugging
@@ -149,6 +154,7 @@ impl AuthService {
             username: user.username().to_string(),
             email: user.email().to_string(),
             role: format!("{}", user.role()),
+            sid: session_id.map(str::to_string),
         };
         
         // Log JWT claims for debugging
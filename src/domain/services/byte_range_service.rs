@@ -0,0 +1,311 @@
+use chrono::{DateTime, Utc};
+
+/// Número máximo de rangos atendidos en una petición; con más se envía el fichero entero
+pub const MAX_RANGES: usize = 16;
+
+/// Rango de bytes con ambos extremos incluidos
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl ByteRange {
+    /// Número de bytes del rango
+    pub fn length(&self) -> u64 {
+        self.end - self.start + 1
+    }
+
+    /// Valor de la cabecera `Content-Range` para este rango
+    pub fn content_range(&self, total: u64) -> String {
+        format!("bytes {}-{}/{}", self.start, self.end, total)
+    }
+}
+
+/// Qué parte del fichero hay que enviar
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RangeSelection {
+    /// Sin cabecera Range (o ignorada): el fichero entero con 200
+    Full,
+    /// Uno o varios rangos ordenados y sin solapes: 206
+    Partial(Vec<ByteRange>),
+    /// Ningún rango cae dentro del fichero: 416
+    Unsatisfiable,
+}
+
+/// Interpreta una cabecera `Range` (RFC 9110, sección 14.2).
+///
+/// Las cabeceras mal formadas o con otra unidad se ignoran, como pide la
+/// RFC. Los rangos solapados o contiguos se fusionan.
+pub fn parse_range(value: &str, total: u64) -> RangeSelection {
+    let Some((unit, specs)) = value.trim().split_once('=') else {
+        return RangeSelection::Full;
+    };
+    if !unit.trim().eq_ignore_ascii_case("bytes") {
+        return RangeSelection::Full;
+    }
+
+    let mut ranges = Vec::new();
+    for spec in specs.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let Some((first, last)) = spec.split_once('-') else {
+            return RangeSelection::Full;
+        };
+        let (first, last) = (first.trim(), last.trim());
+
+        let range = if first.is_empty() {
+            // Sufijo: los últimos N bytes
+            let Ok(suffix) = last.parse::<u64>() else {
+                return RangeSelection::Full;
+            };
+            if suffix == 0 || total == 0 {
+                continue;
+            }
+            ByteRange { start: total.saturating_sub(suffix), end: total - 1 }
+        } else {
+            let Ok(start) = first.parse::<u64>() else {
+                return RangeSelection::Full;
+            };
+            let end = if last.is_empty() {
+                u64::MAX
+            } else {
+                match last.parse::<u64>() {
+                    Ok(end) if end >= start => end,
+                    _ => return RangeSelection::Full,
+                }
+            };
+            if start >= total {
+                continue;
+            }
+            ByteRange { start, end: end.min(total - 1) }
+        };
+        ranges.push(range);
+    }
+
+    if ranges.is_empty() {
+        return if specs.trim().is_empty() { RangeSelection::Full } else { RangeSelection::Unsatisfiable };
+    }
+
+    ranges.sort_by_key(|r| r.start);
+    let mut merged: Vec<ByteRange> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(previous) if range.start <= previous.end.saturating_add(1) => {
+                previous.end = previous.end.max(range.end);
+            },
+            _ => merged.push(range),
+        }
+    }
+
+    if merged.len() > MAX_RANGES {
+        return RangeSelection::Full;
+    }
+    RangeSelection::Partial(merged)
+}
+
+/// Comprueba una cabecera `If-Range` contra el ETag y la fecha de modificación.
+///
+/// Los ETag débiles nunca coinciden. Las fechas se comparan al segundo,
+/// que es la precisión de `Last-Modified`.
+pub fn if_range_matches(value: &str, etag: &str, last_modified: DateTime<Utc>) -> bool {
+    let value = value.trim();
+    if value.starts_with("W/") {
+        return false;
+    }
+    if value.starts_with('"') {
+        return value == etag;
+    }
+    DateTime::parse_from_rfc2822(value)
+        .map(|date| date.timestamp() == last_modified.timestamp())
+        .unwrap_or(false)
+}
+
+/// Decide qué enviar a partir de las cabeceras `Range` e `If-Range`.
+///
+/// Si `If-Range` no coincide el fichero cambió desde que el cliente empezó
+/// a descargarlo, así que se envía entero.
+pub fn select_ranges(
+    range: Option<&str>,
+    if_range: Option<&str>,
+    total: u64,
+    etag: &str,
+    last_modified: DateTime<Utc>,
+) -> RangeSelection {
+    let Some(range) = range else {
+        return RangeSelection::Full;
+    };
+    if if_range.is_some_and(|value| !if_range_matches(value, etag, last_modified)) {
+        return RangeSelection::Full;
+    }
+    parse_range(range, total)
+}
+
+/// Destino de una escritura parcial sobre un fichero existente
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteRange {
+    /// A partir de `start`; `end` (incluido) y `total` se comprueban contra el cuerpo
+    At { start: u64, end: Option<u64>, total: Option<u64> },
+    /// Sustituye los últimos N bytes
+    FromEnd(u64),
+    /// Añade el cuerpo al final del fichero
+    Append,
+}
+
+impl WriteRange {
+    /// Calcula dónde empieza la escritura de `length` bytes en un fichero de
+    /// `size` bytes. Devuelve `None` si el rango no encaja con el cuerpo o
+    /// dejaría un hueco tras el final del fichero (416).
+    pub fn offset(&self, size: u64, length: u64) -> Option<u64> {
+        match *self {
+            WriteRange::At { start, end, total } => {
+                if start > size {
+                    return None;
+                }
+                if end.is_some_and(|end| end.checked_sub(start).map(|span| span + 1) != Some(length)) {
+                    return None;
+                }
+                if total.is_some_and(|total| total != size.max(start + length)) {
+                    return None;
+                }
+                Some(start)
+            },
+            WriteRange::FromEnd(count) => {
+                (count == length && count <= size).then(|| size - count)
+            },
+            WriteRange::Append => Some(size),
+        }
+    }
+}
+
+/// Interpreta una cabecera `Content-Range` de petición (`bytes 0-99/1000`,
+/// `bytes 0-99/*`), como la envían PATCH y el PUT parcial de Apache
+pub fn parse_content_range(value: &str) -> Option<WriteRange> {
+    let (unit, spec) = value.trim().split_once(' ')?;
+    if !unit.eq_ignore_ascii_case("bytes") {
+        return None;
+    }
+    let (range, total) = spec.trim().split_once('/')?;
+    let (start, end) = range.split_once('-')?;
+    let start = start.trim().parse::<u64>().ok()?;
+    let end = end.trim().parse::<u64>().ok().filter(|end| *end >= start)?;
+    let total = match total.trim() {
+        "*" => None,
+        total => Some(total.parse::<u64>().ok().filter(|total| *total > end)?),
+    };
+    Some(WriteRange::At { start, end: Some(end), total })
+}
+
+/// Interpreta la cabecera `X-Update-Range` de las actualizaciones parciales
+/// de SabreDAV: `bytes=first-last`, `bytes=first-`, `bytes=-N` o `append`
+pub fn parse_update_range(value: &str) -> Option<WriteRange> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("append") {
+        return Some(WriteRange::Append);
+    }
+    let (unit, spec) = value.split_once('=')?;
+    if !unit.trim().eq_ignore_ascii_case("bytes") {
+        return None;
+    }
+    let (first, last) = spec.trim().split_once('-')?;
+    let (first, last) = (first.trim(), last.trim());
+    if first.is_empty() {
+        return last.parse::<u64>().ok().filter(|count| *count > 0).map(WriteRange::FromEnd);
+    }
+    let start = first.parse::<u64>().ok()?;
+    let end = if last.is_empty() {
+        None
+    } else {
+        Some(last.parse::<u64>().ok().filter(|end| *end >= start)?)
+    };
+    Some(WriteRange::At { start, end, total: None })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn partial(ranges: &[(u64, u64)]) -> RangeSelection {
+        RangeSelection::Partial(ranges.iter().map(|&(start, end)| ByteRange { start, end }).collect())
+    }
+
+    #[test]
+    fn test_single_ranges() {
+        assert_eq!(parse_range("bytes=0-99", 1000), partial(&[(0, 99)]));
+        assert_eq!(parse_range("bytes=900-", 1000), partial(&[(900, 999)]));
+        assert_eq!(parse_range("bytes=-100", 1000), partial(&[(900, 999)]));
+        assert_eq!(parse_range("bytes=-5000", 1000), partial(&[(0, 999)]));
+        assert_eq!(parse_range("bytes=990-2000", 1000), partial(&[(990, 999)]));
+    }
+
+    #[test]
+    fn test_multiple_ranges_are_merged() {
+        assert_eq!(parse_range("bytes=500-599, 0-99", 1000), partial(&[(0, 99), (500, 599)]));
+        assert_eq!(parse_range("bytes=0-99,100-199,150-300", 1000), partial(&[(0, 300)]));
+    }
+
+    #[test]
+    fn test_invalid_and_unsatisfiable() {
+        assert_eq!(parse_range("items=0-1", 1000), RangeSelection::Full);
+        assert_eq!(parse_range("bytes=abc", 1000), RangeSelection::Full);
+        assert_eq!(parse_range("bytes=10-5", 1000), RangeSelection::Full);
+        assert_eq!(parse_range("bytes=1000-", 1000), RangeSelection::Unsatisfiable);
+        assert_eq!(parse_range("bytes=-0", 1000), RangeSelection::Unsatisfiable);
+        assert_eq!(parse_range("bytes=0-", 0), RangeSelection::Unsatisfiable);
+
+        let many = (0..=MAX_RANGES).map(|i| format!("{}-{}", i * 10, i * 10)).collect::<Vec<_>>().join(",");
+        assert_eq!(parse_range(&format!("bytes={}", many), 1000), RangeSelection::Full);
+    }
+
+    #[test]
+    fn test_if_range() {
+        let modified = DateTime::parse_from_rfc2822("Tue, 22 Apr 2025 10:00:00 GMT").unwrap().with_timezone(&Utc);
+        assert!(if_range_matches("\"abc\"", "\"abc\"", modified));
+        assert!(!if_range_matches("\"old\"", "\"abc\"", modified));
+        assert!(!if_range_matches("W/\"abc\"", "\"abc\"", modified));
+        assert!(if_range_matches("Tue, 22 Apr 2025 10:00:00 GMT", "\"abc\"", modified));
+        assert!(!if_range_matches("Mon, 21 Apr 2025 10:00:00 GMT", "\"abc\"", modified));
+
+        assert_eq!(
+            select_ranges(Some("bytes=0-9"), Some("\"old\""), 100, "\"abc\"", modified),
+            RangeSelection::Full
+        );
+        assert_eq!(
+            select_ranges(Some("bytes=0-9"), Some("\"abc\""), 100, "\"abc\"", modified),
+            partial(&[(0, 9)])
+        );
+    }
+
+    #[test]
+    fn test_write_ranges() {
+        assert_eq!(
+            parse_content_range("bytes 10-19/100"),
+            Some(WriteRange::At { start: 10, end: Some(19), total: Some(100) })
+        );
+        assert_eq!(parse_content_range("bytes 0-9/*"), Some(WriteRange::At { start: 0, end: Some(9), total: None }));
+        assert_eq!(parse_content_range("bytes 10-5/100"), None);
+        assert_eq!(parse_content_range("bytes 0-99/50"), None);
+        assert_eq!(parse_content_range("bytes=0-9"), None);
+
+        assert_eq!(parse_update_range("append"), Some(WriteRange::Append));
+        assert_eq!(parse_update_range("bytes=-4"), Some(WriteRange::FromEnd(4)));
+        assert_eq!(parse_update_range("bytes=5-"), Some(WriteRange::At { start: 5, end: None, total: None }));
+        assert_eq!(parse_update_range("bytes=-0"), None);
+        assert_eq!(parse_update_range("lines=1-2"), None);
+    }
+
+    #[test]
+    fn test_write_range_offsets() {
+        // Sobrescribe dentro del fichero y puede extenderlo sin dejar huecos
+        assert_eq!(parse_content_range("bytes 10-19/100").unwrap().offset(100, 10), Some(10));
+        assert_eq!(parse_content_range("bytes 95-104/105").unwrap().offset(100, 10), Some(95));
+        assert_eq!(parse_content_range("bytes 100-109/*").unwrap().offset(100, 10), Some(100));
+        assert_eq!(parse_content_range("bytes 101-110/*").unwrap().offset(100, 10), None);
+        // El rango debe coincidir con el cuerpo y el total con el tamaño resultante
+        assert_eq!(parse_content_range("bytes 10-19/100").unwrap().offset(100, 5), None);
+        assert_eq!(parse_content_range("bytes 10-19/200").unwrap().offset(100, 10), None);
+
+        assert_eq!(WriteRange::Append.offset(100, 7), Some(100));
+        assert_eq!(WriteRange::FromEnd(4).offset(100, 4), Some(96));
+        assert_eq!(WriteRange::FromEnd(4).offset(2, 4), None);
+        assert_eq!(WriteRange::At { start: 5, end: None, total: None }.offset(100, 200), Some(5));
+    }
+}
@@ -0,0 +1,406 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::domain::services::icalendar_service::{parse_duration, ICalComponent, ICalProperty};
+use crate::domain::services::recurrence_service::Recurrence;
+use crate::domain::services::timezone_service::normalize_to_utc;
+
+/// Intervalo `[start, end)` de un filtro; un extremo ausente queda abierto
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TimeRange {
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl TimeRange {
+    pub fn new(start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>) -> Self {
+        Self { start, end }
+    }
+
+    /// Si el periodo `[start, end)` se solapa con el intervalo
+    pub fn overlaps(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> bool {
+        self.end.is_none_or(|range_end| start < range_end)
+            && self.start.is_none_or(|range_start| end > range_start)
+    }
+
+    /// Si un instante cae dentro del intervalo
+    pub fn contains(&self, instant: DateTime<Utc>) -> bool {
+        self.start.is_none_or(|start| instant >= start) && self.end.is_none_or(|end| instant < end)
+    }
+}
+
+/// Colación de las comparaciones de texto (RFC 4790)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Collation {
+    /// `i;ascii-casemap`: sin distinguir mayúsculas ASCII (la de por defecto)
+    #[default]
+    AsciiCasemap,
+    /// `i;unicode-casemap`: sin distinguir mayúsculas Unicode
+    UnicodeCasemap,
+    /// `i;octet`: byte a byte
+    Octet,
+}
+
+impl Collation {
+    /// Colación por su nombre; `None` si el servidor no la soporta
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "i;ascii-casemap" => Some(Self::AsciiCasemap),
+            "i;unicode-casemap" => Some(Self::UnicodeCasemap),
+            "i;octet" => Some(Self::Octet),
+            _ => None,
+        }
+    }
+}
+
+/// Coincidencia de texto por subcadena (RFC 4791, 9.7.5)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextMatch {
+    pub text: String,
+    pub collation: Collation,
+    /// `negate-condition="yes"`: coincide cuando el texto no aparece
+    pub negate: bool,
+}
+
+impl TextMatch {
+    pub fn matches(&self, value: &str) -> bool {
+        let found = match self.collation {
+            Collation::Octet => value.contains(&self.text),
+            Collation::AsciiCasemap => value.to_ascii_lowercase().contains(&self.text.to_ascii_lowercase()),
+            Collation::UnicodeCasemap => value.to_lowercase().contains(&self.text.to_lowercase()),
+        };
+        found != self.negate
+    }
+}
+
+/// Filtro sobre un parámetro de una propiedad (RFC 4791, 9.7.3)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamFilter {
+    pub name: String,
+    pub is_not_defined: bool,
+    pub text_match: Option<TextMatch>,
+}
+
+impl ParamFilter {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_ascii_uppercase(),
+            is_not_defined: false,
+            text_match: None,
+        }
+    }
+
+    fn matches(&self, property: &ICalProperty) -> bool {
+        match property.param(&self.name) {
+            None => self.is_not_defined,
+            Some(_) if self.is_not_defined => false,
+            Some(value) => self.text_match.as_ref().is_none_or(|text_match| text_match.matches(value)),
+        }
+    }
+}
+
+/// Filtro sobre las propiedades de un componente (RFC 4791, 9.7.2)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropFilter {
+    pub name: String,
+    pub is_not_defined: bool,
+    pub time_range: Option<TimeRange>,
+    pub text_match: Option<TextMatch>,
+    pub param_filters: Vec<ParamFilter>,
+}
+
+impl PropFilter {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_ascii_uppercase(),
+            is_not_defined: false,
+            time_range: None,
+            text_match: None,
+            param_filters: Vec::new(),
+        }
+    }
+
+    fn matches(&self, component: &ICalComponent) -> bool {
+        let mut properties = component.properties_named(&self.name).peekable();
+        if self.is_not_defined {
+            return properties.peek().is_none();
+        }
+        properties.any(|property| self.matches_property(property))
+    }
+
+    fn matches_property(&self, property: &ICalProperty) -> bool {
+        if let Some(range) = &self.time_range {
+            let Some((instant, _)) = property.date_time() else { return false };
+            if !range.contains(instant) {
+                return false;
+            }
+        }
+        if let Some(text_match) = &self.text_match {
+            if !text_match.matches(&property.text()) {
+                return false;
+            }
+        }
+        self.param_filters.iter().all(|filter| filter.matches(property))
+    }
+}
+
+/// Filtro sobre un componente y su contenido (RFC 4791, 9.7.1)
+///
+/// El filtro de un `calendar-query` empieza siempre por `VCALENDAR`, que se
+/// compara con el propio objeto; el resto se comparan con los
+/// subcomponentes de su componente padre y basta con que uno cumpla.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompFilter {
+    pub name: String,
+    pub is_not_defined: bool,
+    pub time_range: Option<TimeRange>,
+    pub prop_filters: Vec<PropFilter>,
+    pub comp_filters: Vec<CompFilter>,
+}
+
+impl CompFilter {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_ascii_uppercase(),
+            is_not_defined: false,
+            time_range: None,
+            prop_filters: Vec::new(),
+            comp_filters: Vec::new(),
+        }
+    }
+
+    /// Filtro que selecciona los eventos que se solapan con un intervalo
+    pub fn events_in_range(range: TimeRange) -> Self {
+        let mut event = Self::new("VEVENT");
+        event.time_range = Some(range);
+        let mut calendar = Self::new("VCALENDAR");
+        calendar.comp_filters.push(event);
+        calendar
+    }
+
+    /// Evalúa el filtro sobre un objeto de calendario completo
+    pub fn matches(&self, calendar: &ICalComponent) -> bool {
+        if !calendar.name.eq_ignore_ascii_case(&self.name) {
+            return self.is_not_defined;
+        }
+        !self.is_not_defined && self.matches_content(calendar)
+    }
+
+    /// Evalúa el filtro sobre el texto iCalendar de un objeto; los datos que
+    /// no se pueden interpretar no coinciden con ningún filtro.
+    ///
+    /// Las fechas con TZID se pasan antes a UTC con los VTIMEZONE del objeto.
+    pub fn matches_ical(&self, ical_data: &str) -> bool {
+        ICalComponent::parse(ical_data).is_some_and(|mut calendar| {
+            normalize_to_utc(&mut calendar, None);
+            self.matches(&calendar)
+        })
+    }
+
+    /// Intervalo que debe solaparse con algún evento del objeto, útil para
+    /// acotar la consulta a la base de datos antes de evaluar el filtro
+    pub fn event_time_range(&self) -> Option<TimeRange> {
+        self.comp_filters.iter()
+            .find(|filter| filter.name == "VEVENT" && !filter.is_not_defined)
+            .and_then(|filter| filter.time_range)
+    }
+
+    fn matches_in(&self, parent: &ICalComponent) -> bool {
+        let mut components = parent.components_named(&self.name).peekable();
+        if self.is_not_defined {
+            return components.peek().is_none();
+        }
+        components.any(|component| self.matches_content(component))
+    }
+
+    fn matches_content(&self, component: &ICalComponent) -> bool {
+        if let Some(range) = &self.time_range {
+            if !component_overlaps(component, range) {
+                return false;
+            }
+        }
+        self.prop_filters.iter().all(|filter| filter.matches(component))
+            && self.comp_filters.iter().all(|filter| filter.matches_in(component))
+    }
+}
+
+fn date_time_of(component: &ICalComponent, name: &str) -> Option<(DateTime<Utc>, bool)> {
+    component.property(name).and_then(|property| property.date_time())
+}
+
+fn duration_of(component: &ICalComponent) -> Option<Duration> {
+    component.property("DURATION").and_then(|property| parse_duration(&property.value))
+}
+
+/// Si un componente se solapa con el intervalo según las reglas de la
+/// RFC 4791, 9.9.
+///
+/// Las series recurrentes (RRULE o RDATE) se expanden y coinciden si alguna
+/// de sus repeticiones, descontadas las EXDATE, se solapa con el intervalo.
+fn component_overlaps(component: &ICalComponent, range: &TimeRange) -> bool {
+    match component.name.as_str() {
+        "VEVENT" => event_overlaps(component, range),
+        "VTODO" => todo_overlaps(component, range),
+        "VJOURNAL" => match date_time_of(component, "DTSTART") {
+            Some((start, true)) => range.overlaps(start, start + Duration::days(1)),
+            Some((start, false)) => range.contains(start),
+            None => false,
+        },
+        "VFREEBUSY" => match (date_time_of(component, "DTSTART"), date_time_of(component, "DTEND")) {
+            (Some((start, _)), Some((end, _))) => range.overlaps(start, end),
+            _ => false,
+        },
+        "VALARM" => match component.property("TRIGGER") {
+            // Los avisos relativos dependen del evento; se aceptan
+            Some(trigger) if trigger.param("VALUE").is_some_and(|v| v.eq_ignore_ascii_case("DATE-TIME")) => {
+                trigger.date_time().is_some_and(|(instant, _)| range.contains(instant))
+            }
+            _ => true,
+        },
+        _ => true,
+    }
+}
+
+fn event_overlaps(event: &ICalComponent, range: &TimeRange) -> bool {
+    let Some((start, is_date)) = date_time_of(event, "DTSTART") else { return false };
+    let end = match (date_time_of(event, "DTEND"), duration_of(event)) {
+        (Some((end, _)), _) => end,
+        (None, Some(duration)) => start + duration,
+        (None, None) if is_date => start + Duration::days(1),
+        (None, None) => start,
+    };
+
+    if is_recurring(event) {
+        return series_overlaps(event, range);
+    }
+    if end > start {
+        range.overlaps(start, end)
+    } else {
+        // Eventos sin duración: cuentan si su instante de inicio cae en el intervalo
+        range.start.is_none_or(|range_start| start >= range_start)
+            && range.end.is_none_or(|range_end| start < range_end)
+    }
+}
+
+fn todo_overlaps(todo: &ICalComponent, range: &TimeRange) -> bool {
+    let start = date_time_of(todo, "DTSTART").map(|(start, _)| start);
+    let due = date_time_of(todo, "DUE").map(|(due, _)| due)
+        .or_else(|| start.zip(duration_of(todo)).map(|(start, duration)| start + duration));
+    if start.is_some() && is_recurring(todo) {
+        return series_overlaps(todo, range);
+    }
+    match (start, due) {
+        (Some(start), Some(due)) => range.overlaps(start, due.max(start + Duration::seconds(1))),
+        (Some(start), None) => range.end.is_none_or(|end| start < end) && range.start.is_none_or(|range_start| start >= range_start),
+        (None, Some(due)) => range.overlaps(due - Duration::seconds(1), due),
+        (None, None) => {
+            // Tareas sin fechas: se usan las de creación y finalización si existen
+            let created = date_time_of(todo, "CREATED").map(|(instant, _)| instant);
+            let completed = date_time_of(todo, "COMPLETED").map(|(instant, _)| instant);
+            match (created, completed) {
+                (Some(created), Some(completed)) => range.overlaps(created, completed.max(created + Duration::seconds(1))),
+                (Some(instant), None) | (None, Some(instant)) => range.contains(instant),
+                (None, None) => true,
+            }
+        }
+    }
+}
+
+fn is_recurring(component: &ICalComponent) -> bool {
+    component.property("RRULE").is_some() || component.property("RDATE").is_some()
+}
+
+/// Si alguna repetición de la serie se solapa con el intervalo
+fn series_overlaps(component: &ICalComponent, range: &TimeRange) -> bool {
+    Recurrence::from_component(component).is_some_and(|series| series.overlaps(range.start, range.end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn calendar(event_lines: &str) -> ICalComponent {
+        ICalComponent::parse(&format!(
+            "BEGIN:VCALENDAR\nVERSION:2.0\nBEGIN:VEVENT\nUID:1\n{}\nEND:VEVENT\nEND:VCALENDAR\n",
+            event_lines
+        )).unwrap()
+    }
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, day, hour, 0, 0).unwrap()
+    }
+
+    fn event_filter(configure: impl FnOnce(&mut CompFilter)) -> CompFilter {
+        let mut event = CompFilter::new("VEVENT");
+        configure(&mut event);
+        let mut filter = CompFilter::new("VCALENDAR");
+        filter.comp_filters.push(event);
+        filter
+    }
+
+    #[test]
+    fn time_range_follows_event_duration() {
+        let meeting = calendar("DTSTART:20240110T090000Z\nDURATION:PT1H");
+        assert!(CompFilter::events_in_range(TimeRange::new(Some(at(10, 9)), Some(at(10, 10)))).matches(&meeting));
+        assert!(!CompFilter::events_in_range(TimeRange::new(Some(at(10, 10)), None)).matches(&meeting));
+
+        // Un día completo sin DTEND dura hasta el día siguiente
+        let holiday = calendar("DTSTART;VALUE=DATE:20240110");
+        assert!(CompFilter::events_in_range(TimeRange::new(Some(at(10, 23)), Some(at(11, 0)))).matches(&holiday));
+        assert!(!CompFilter::events_in_range(TimeRange::new(Some(at(11, 0)), None)).matches(&holiday));
+
+        // Las series coinciden solo en los intervalos donde cae alguna repetición
+        let weekly = calendar("DTSTART:20240101T090000Z\nDTEND:20240101T100000Z\nRRULE:FREQ=WEEKLY");
+        assert!(CompFilter::events_in_range(TimeRange::new(Some(at(22, 9)), Some(at(22, 10)))).matches(&weekly));
+        assert!(!CompFilter::events_in_range(TimeRange::new(Some(at(20, 0)), Some(at(21, 0)))).matches(&weekly));
+        let skipped = calendar("DTSTART:20240101T090000Z\nDTEND:20240101T100000Z\nRRULE:FREQ=WEEKLY\nEXDATE:20240122T090000Z");
+        assert!(!CompFilter::events_in_range(TimeRange::new(Some(at(22, 0)), Some(at(23, 0)))).matches(&skipped));
+    }
+
+    #[test]
+    fn prop_filters_match_text_and_parameters() {
+        let event = calendar("DTSTART:20240110T090000Z\nSUMMARY:Quarterly Review\nATTENDEE;PARTSTAT=NEEDS-ACTION:mailto:ana@example.com");
+
+        let summary = |text: &str, collation: Collation, negate: bool| event_filter(|filter| {
+            let mut prop = PropFilter::new("SUMMARY");
+            prop.text_match = Some(TextMatch { text: text.to_string(), collation, negate });
+            filter.prop_filters.push(prop);
+        });
+        assert!(summary("review", Collation::AsciiCasemap, false).matches(&event));
+        assert!(!summary("review", Collation::Octet, false).matches(&event));
+        assert!(summary("standup", Collation::AsciiCasemap, true).matches(&event));
+
+        let partstat = |value: &str| event_filter(|filter| {
+            let mut prop = PropFilter::new("ATTENDEE");
+            let mut param = ParamFilter::new("PARTSTAT");
+            param.text_match = Some(TextMatch { text: value.to_string(), collation: Collation::default(), negate: false });
+            prop.param_filters.push(param);
+            filter.prop_filters.push(prop);
+        });
+        assert!(partstat("needs-action").matches(&event));
+        assert!(!partstat("accepted").matches(&event));
+    }
+
+    #[test]
+    fn is_not_defined_requires_absence() {
+        let event = calendar("DTSTART:20240110T090000Z");
+        let without_alarms = event_filter(|filter| {
+            let mut alarm = CompFilter::new("VALARM");
+            alarm.is_not_defined = true;
+            filter.comp_filters.push(alarm);
+        });
+        assert!(without_alarms.matches(&event));
+
+        let without_summary = event_filter(|filter| {
+            let mut prop = PropFilter::new("SUMMARY");
+            prop.is_not_defined = true;
+            filter.prop_filters.push(prop);
+        });
+        assert!(without_summary.matches(&event));
+        assert!(!without_summary.matches(&calendar("DTSTART:20240110T090000Z\nSUMMARY:x")));
+
+        // Un VTODO no pasa un filtro de eventos
+        let todo = ICalComponent::parse("BEGIN:VCALENDAR\nBEGIN:VTODO\nUID:t\nEND:VTODO\nEND:VCALENDAR").unwrap();
+        assert!(!event_filter(|_| {}).matches(&todo));
+    }
+}
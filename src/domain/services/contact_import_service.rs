@@ -0,0 +1,333 @@
+use uuid::Uuid;
+
+use crate::domain::services::vcard_service::{VCard, VCardProperty};
+
+/// Separa un fichero .vcf con varias vCards en el texto de cada una.
+///
+/// Se ignora lo que haya fuera de BEGIN:VCARD/END:VCARD; las vCards anidadas
+/// (AGENT de la 2.1) se quedan dentro de la que las contiene. Una vCard a la
+/// que le falta el END final al acabar el fichero se devuelve igualmente.
+pub fn split_vcards(data: &str) -> Vec<String> {
+    let mut cards = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+
+    for line in data.lines() {
+        let trimmed = line.trim();
+        let is_begin = trimmed.eq_ignore_ascii_case("BEGIN:VCARD");
+        if depth == 0 && !is_begin {
+            continue;
+        }
+        if is_begin {
+            depth += 1;
+        } else if trimmed.eq_ignore_ascii_case("END:VCARD") {
+            depth -= 1;
+        }
+        current.push_str(line.trim_end_matches('\r'));
+        current.push_str("\r\n");
+        if depth == 0 {
+            cards.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        cards.push(current);
+    }
+    cards
+}
+
+/// Campo de contacto al que se asigna una columna de un CSV
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvField {
+    Uid,
+    FullName,
+    FirstName,
+    LastName,
+    Nickname,
+    Email,
+    HomeEmail,
+    WorkEmail,
+    Phone,
+    MobilePhone,
+    HomePhone,
+    WorkPhone,
+    Organization,
+    Title,
+    Notes,
+    Birthday,
+    Street,
+    City,
+    State,
+    PostalCode,
+    Country,
+    Url,
+}
+
+impl CsvField {
+    /// Campo con ese nombre (`first_name`, `email`...) tal y como se usa al
+    /// indicar las columnas a mano
+    pub fn parse(name: &str) -> Option<Self> {
+        let field = match name.trim().to_ascii_lowercase().as_str() {
+            "uid" => Self::Uid,
+            "full_name" => Self::FullName,
+            "first_name" => Self::FirstName,
+            "last_name" => Self::LastName,
+            "nickname" => Self::Nickname,
+            "email" => Self::Email,
+            "home_email" => Self::HomeEmail,
+            "work_email" => Self::WorkEmail,
+            "phone" => Self::Phone,
+            "mobile_phone" => Self::MobilePhone,
+            "home_phone" => Self::HomePhone,
+            "work_phone" => Self::WorkPhone,
+            "organization" => Self::Organization,
+            "title" => Self::Title,
+            "notes" => Self::Notes,
+            "birthday" => Self::Birthday,
+            "street" => Self::Street,
+            "city" => Self::City,
+            "state" => Self::State,
+            "postal_code" => Self::PostalCode,
+            "country" => Self::Country,
+            "url" => Self::Url,
+            _ => return None,
+        };
+        Some(field)
+    }
+
+    /// Campo que corresponde a una cabecera de CSV: los nombres de los campos
+    /// y las cabeceras habituales de las exportaciones de Google y Outlook
+    pub fn from_header(header: &str) -> Option<Self> {
+        if let Some(field) = Self::parse(header) {
+            return Some(field);
+        }
+        let key: String = header.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect();
+        let field = match key.as_str() {
+            "name" | "fullname" | "displayname" => Self::FullName,
+            "firstname" | "givenname" => Self::FirstName,
+            "lastname" | "familyname" | "surname" => Self::LastName,
+            "email" | "emailaddress" | "email1value" | "mail" => Self::Email,
+            "homeemail" | "email2address" => Self::HomeEmail,
+            "workemail" | "email3address" => Self::WorkEmail,
+            "phone" | "telephone" | "phonenumber" | "phone1value" | "primaryphone" => Self::Phone,
+            "mobile" | "mobilephone" | "cellphone" => Self::MobilePhone,
+            "homephone" => Self::HomePhone,
+            "workphone" | "businessphone" => Self::WorkPhone,
+            "organization" | "organisation" | "company" | "organization1name" => Self::Organization,
+            "title" | "jobtitle" | "organization1title" => Self::Title,
+            "notes" | "note" => Self::Notes,
+            "birthday" => Self::Birthday,
+            "street" | "homestreet" | "address1street" => Self::Street,
+            "city" | "homecity" | "address1city" => Self::City,
+            "state" | "region" | "homestate" | "address1region" => Self::State,
+            "postalcode" | "zip" | "zipcode" | "homepostalcode" | "address1postalcode" => Self::PostalCode,
+            "country" | "homecountry" | "countryregion" | "address1country" => Self::Country,
+            "url" | "website" | "webpage" | "website1value" => Self::Url,
+            _ => return None,
+        };
+        Some(field)
+    }
+}
+
+/// Lee un CSV (RFC 4180) como lista de registros.
+///
+/// Acepta campos entre comillas con comillas dobladas y saltos de línea, y
+/// `;` como separador si la primera línea tiene más `;` que `,` (lo que
+/// exportan las hojas de cálculo en muchos idiomas). Se quitan el BOM y las
+/// líneas vacías.
+pub fn parse_csv(data: &str) -> Vec<Vec<String>> {
+    let data = data.strip_prefix('\u{feff}').unwrap_or(data);
+    let delimiter = detect_delimiter(data);
+
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = data.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if in_quotes => in_quotes = false,
+            '"' if field.is_empty() => in_quotes = true,
+            '\r' | '\n' if !in_quotes => {
+                if c == '\r' && chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+                record.push(std::mem::take(&mut field));
+                push_record(&mut records, std::mem::take(&mut record));
+            }
+            c if c == delimiter && !in_quotes => record.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        push_record(&mut records, record);
+    }
+    records
+}
+
+fn push_record(records: &mut Vec<Vec<String>>, record: Vec<String>) {
+    if record.iter().any(|field| !field.trim().is_empty()) {
+        records.push(record);
+    }
+}
+
+fn detect_delimiter(data: &str) -> char {
+    let mut commas = 0;
+    let mut semicolons = 0;
+    let mut in_quotes = false;
+    for c in data.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => commas += 1,
+            ';' if !in_quotes => semicolons += 1,
+            '\n' if !in_quotes => break,
+            _ => {}
+        }
+    }
+    if semicolons > commas { ';' } else { ',' }
+}
+
+/// Genera la vCard 3.0 de un registro de CSV según las columnas asignadas.
+///
+/// Las columnas sin campo o vacías se ignoran; si el registro no tiene
+/// nombre, correo, teléfono ni organización devuelve el motivo del error.
+/// Sin columna UID se genera uno nuevo.
+pub fn csv_record_to_vcard(columns: &[Option<CsvField>], record: &[String]) -> Result<String, String> {
+    let value = |wanted: CsvField| -> Option<&str> {
+        columns.iter()
+            .zip(record)
+            .find(|(field, value)| **field == Some(wanted) && !value.trim().is_empty())
+            .map(|(_, value)| value.trim())
+    };
+
+    let first_name = value(CsvField::FirstName).unwrap_or_default();
+    let last_name = value(CsvField::LastName).unwrap_or_default();
+    let full_name = value(CsvField::FullName).map(str::to_string).or_else(|| {
+        let name = [first_name, last_name].iter().filter(|part| !part.is_empty()).copied().collect::<Vec<_>>().join(" ");
+        (!name.is_empty()).then_some(name)
+    });
+    let organization = value(CsvField::Organization);
+
+    let mut card = VCard::default();
+    card.properties.push(VCardProperty::new("VERSION", "3.0"));
+    let uid = value(CsvField::Uid).map(str::to_string).unwrap_or_else(|| Uuid::new_v4().to_string());
+    card.properties.push(VCardProperty::text_value("UID", &uid));
+    // FN es obligatorio; sin nombre se usa la organización o el correo
+    let display_name = full_name.as_deref()
+        .or(organization)
+        .or_else(|| value(CsvField::Email))
+        .or_else(|| value(CsvField::HomeEmail))
+        .or_else(|| value(CsvField::WorkEmail));
+    card.properties.push(VCardProperty::text_value("FN", display_name.unwrap_or_default()));
+    let mut name = VCardProperty::new("N", String::new());
+    name.set_components(&[last_name.to_string(), first_name.to_string(), String::new(), String::new(), String::new()]);
+    card.properties.push(name);
+
+    let mut has_contact_data = full_name.is_some() || organization.is_some();
+    for (field, property, kind) in [
+        (CsvField::Email, "EMAIL", None),
+        (CsvField::HomeEmail, "EMAIL", Some("HOME")),
+        (CsvField::WorkEmail, "EMAIL", Some("WORK")),
+        (CsvField::Phone, "TEL", None),
+        (CsvField::MobilePhone, "TEL", Some("CELL")),
+        (CsvField::HomePhone, "TEL", Some("HOME")),
+        (CsvField::WorkPhone, "TEL", Some("WORK")),
+    ] {
+        if let Some(value) = value(field) {
+            let property = VCardProperty::text_value(property, value);
+            card.properties.push(match kind {
+                Some(kind) => property.with_param("TYPE", kind),
+                None => property,
+            });
+            has_contact_data = true;
+        }
+    }
+    if !has_contact_data {
+        return Err("The record has no name, email, phone or organization".to_string());
+    }
+
+    if let Some(nickname) = value(CsvField::Nickname) {
+        card.properties.push(VCardProperty::text_value("NICKNAME", nickname));
+    }
+    if let Some(organization) = organization {
+        let mut org = VCardProperty::new("ORG", String::new());
+        org.set_components(&[organization.to_string()]);
+        card.properties.push(org);
+    }
+    for (field, property) in [(CsvField::Title, "TITLE"), (CsvField::Notes, "NOTE"), (CsvField::Url, "URL")] {
+        if let Some(value) = value(field) {
+            card.properties.push(VCardProperty::text_value(property, value));
+        }
+    }
+    if let Some(birthday) = value(CsvField::Birthday) {
+        card.properties.push(VCardProperty::new("BDAY", birthday.to_string()));
+    }
+
+    let address: Vec<String> = [CsvField::Street, CsvField::City, CsvField::State, CsvField::PostalCode, CsvField::Country]
+        .into_iter()
+        .map(|field| value(field).unwrap_or_default().to_string())
+        .collect();
+    if address.iter().any(|part| !part.is_empty()) {
+        let mut adr = VCardProperty::new("ADR", String::new()).with_param("TYPE", "HOME");
+        let components: Vec<String> = [String::new(), String::new()].into_iter().chain(address).collect();
+        adr.set_components(&components);
+        card.properties.push(adr);
+    }
+
+    Ok(card.to_vcard())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_vcards() {
+        let data = "garbage\r\nBEGIN:VCARD\r\nVERSION:3.0\r\nFN:Ana\r\nEND:VCARD\r\n\r\nbegin:vcard\nFN:Luis\nAGENT:BEGIN:VCARD\nend:vcard\nBEGIN:VCARD\nFN:Sin fin\n";
+        let cards = split_vcards(data);
+        assert_eq!(cards.len(), 3);
+        assert_eq!(cards[0], "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Ana\r\nEND:VCARD\r\n");
+        assert!(cards[1].contains("FN:Luis"));
+        assert!(cards[2].starts_with("BEGIN:VCARD") && cards[2].contains("FN:Sin fin"));
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let data = "\u{feff}Name,Email,Notes\r\n\"Pérez, Ana\",ana@example.com,\"Dice \"\"hola\"\"\nen dos líneas\"\r\n\r\nLuis,,\n";
+        let records = parse_csv(data);
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[1], vec!["Pérez, Ana", "ana@example.com", "Dice \"hola\"\nen dos líneas"]);
+        assert_eq!(records[2], vec!["Luis", "", ""]);
+
+        let records = parse_csv("First Name;Last Name\nAna;Pérez");
+        assert_eq!(records[1], vec!["Ana", "Pérez"]);
+    }
+
+    #[test]
+    fn test_csv_record_to_vcard() {
+        let columns: Vec<Option<CsvField>> = ["Given Name", "Family Name", "E-mail Address", "Mobile Phone", "Ignored"]
+            .iter()
+            .map(|header| CsvField::from_header(header))
+            .collect();
+        assert_eq!(columns[4], None);
+
+        let record: Vec<String> = ["Ana", "Pérez", "ana@example.com", "+34 600", "x"].iter().map(|v| v.to_string()).collect();
+        let card = VCard::parse(&csv_record_to_vcard(&columns, &record).unwrap()).unwrap();
+        assert_eq!(card.text("FN").as_deref(), Some("Ana Pérez"));
+        assert_eq!(card.property("N").unwrap().components()[..2], ["Pérez".to_string(), "Ana".to_string()]);
+        assert_eq!(card.text("EMAIL").as_deref(), Some("ana@example.com"));
+        assert_eq!(card.property("TEL").unwrap().types(), vec!["cell".to_string()]);
+        assert!(card.property("UID").is_some());
+
+        let empty: Vec<String> = vec![String::new(); 5];
+        assert!(csv_record_to_vcard(&columns, &empty).is_err());
+    }
+}
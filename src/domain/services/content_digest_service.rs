@@ -0,0 +1,90 @@
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+/// Nombre del algoritmo SHA-256 en las cabeceras `Digest` y `Want-Digest` (RFC 5843)
+pub const SHA256_DIGEST_NAME: &str = "SHA-256";
+
+/// SHA-256 de un contenido en hexadecimal
+pub fn sha256_hex(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+/// HMAC-SHA256 (RFC 2104) de un contenido con una clave, en hexadecimal
+pub fn hmac_sha256_hex(key: &[u8], content: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC admite claves de cualquier longitud");
+    mac.update(content);
+    format!("{:x}", mac.finalize().into_bytes())
+}
+
+/// Valor de la cabecera `Digest` (RFC 3230) para un SHA-256 en hexadecimal:
+/// `SHA-256=` seguido del hash en base64. `None` si el hash no es válido.
+pub fn digest_header_value(sha256_hex: &str) -> Option<String> {
+    if sha256_hex.len() != 64 {
+        return None;
+    }
+    let bytes = (0..64)
+        .step_by(2)
+        .map(|i| u8::from_str_radix(sha256_hex.get(i..i + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    Some(format!("{}={}", SHA256_DIGEST_NAME, base64::engine::general_purpose::STANDARD.encode(bytes)))
+}
+
+/// Si se puede enviar un `Digest` SHA-256 según la cabecera `Want-Digest`.
+///
+/// Sin cabecera el servidor puede enviarlo igualmente (RFC 3230, 4.3.2); con
+/// ella solo si pide SHA-256 con una calidad mayor que cero.
+pub fn wants_sha256(want_digest: Option<&str>) -> bool {
+    let Some(want_digest) = want_digest else { return true };
+    want_digest.split(',').any(|item| {
+        let mut parts = item.split(';');
+        let algorithm = parts.next().unwrap_or_default().trim();
+        let quality = parts
+            .filter_map(|param| param.trim().strip_prefix("q="))
+            .find_map(|q| q.trim().parse::<f32>().ok())
+            .unwrap_or(1.0);
+        algorithm.eq_ignore_ascii_case(SHA256_DIGEST_NAME) && quality > 0.0
+    })
+}
+
+/// Si la cabecera `TE` acepta campos de trailer
+pub fn accepts_trailers(te: Option<&str>) -> bool {
+    te.is_some_and(|te| te.split(',').any(|item| item.split(';').next().unwrap_or_default().trim().eq_ignore_ascii_case("trailers")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digest_header_value() {
+        let hex = sha256_hex(b"hello");
+        assert_eq!(hex, "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824");
+        assert_eq!(digest_header_value(&hex).as_deref(), Some("SHA-256=LPJNul+wow4m6DsqxbninhsWHlwfp0JecwQzYpOLmCQ="));
+        assert_eq!(digest_header_value("abc"), None);
+        assert_eq!(digest_header_value(&"zz".repeat(32)), None);
+    }
+
+    #[test]
+    fn test_hmac_sha256_hex() {
+        // Caso 2 de la RFC 4231
+        assert_eq!(
+            hmac_sha256_hex(b"Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_want_digest_and_te() {
+        assert!(wants_sha256(None));
+        assert!(wants_sha256(Some("sha-256")));
+        assert!(wants_sha256(Some("MD5;q=0.3, SHA-256;q=1")));
+        assert!(!wants_sha256(Some("MD5")));
+        assert!(!wants_sha256(Some("SHA-256;q=0")));
+
+        assert!(accepts_trailers(Some("trailers")));
+        assert!(accepts_trailers(Some("gzip, trailers;q=1")));
+        assert!(!accepts_trailers(Some("gzip")));
+        assert!(!accepts_trailers(None));
+    }
+}
@@ -0,0 +1,510 @@
+use std::collections::HashMap;
+
+use crate::domain::services::icalendar_service::{parse_date_time, unfold_lines, ICalComponent};
+
+/// Componentes de un objeto de calendario que describen el elemento
+const CALENDAR_ITEM_COMPONENTS: &[&str] = &["VEVENT", "VTODO", "VJOURNAL"];
+
+/// Frecuencias admitidas en una RRULE (RFC 5545, 3.3.10)
+const RRULE_FREQUENCIES: &[&str] = &["SECONDLY", "MINUTELY", "HOURLY", "DAILY", "WEEKLY", "MONTHLY", "YEARLY"];
+
+const WEEKDAYS: &[&str] = &["MO", "TU", "WE", "TH", "FR", "SA", "SU"];
+
+/// Tipo de problema encontrado en un elemento de calendario o de contactos
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IssueKind {
+    /// El objeto no tiene UID
+    MissingUid,
+    /// Otro elemento de la misma colección declara el mismo UID
+    DuplicateUid,
+    /// La regla de recurrencia no es válida
+    BrokenRrule,
+    /// El objeto iCalendar no se puede interpretar o no tiene ningún evento
+    MalformedICalendar,
+    /// La vCard no se puede interpretar o le faltan propiedades obligatorias
+    MalformedVCard,
+}
+
+impl IssueKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            IssueKind::MissingUid => "missing_uid",
+            IssueKind::DuplicateUid => "duplicate_uid",
+            IssueKind::BrokenRrule => "broken_rrule",
+            IssueKind::MalformedICalendar => "malformed_icalendar",
+            IssueKind::MalformedVCard => "malformed_vcard",
+        }
+    }
+}
+
+/// Reparación automática de un problema
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationFix {
+    /// Escribe este UID en el objeto, añadiéndolo o reemplazando el que tenga
+    SetUid(String),
+    /// Elimina la regla de recurrencia; el evento queda como evento único
+    RemoveRrule,
+    /// Añade a la vCard el VERSION y el FN que le falten
+    CompleteVCard { full_name: String },
+}
+
+impl ValidationFix {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ValidationFix::SetUid(_) => "set_uid",
+            ValidationFix::RemoveRrule => "remove_rrule",
+            ValidationFix::CompleteVCard { .. } => "complete_vcard",
+        }
+    }
+
+    /// Descripción de la reparación para mostrarla al usuario
+    pub fn suggestion(&self) -> String {
+        match self {
+            ValidationFix::SetUid(uid) => format!("Set the UID to {}", uid),
+            ValidationFix::RemoveRrule => "Remove the recurrence rule and keep the first occurrence".to_string(),
+            ValidationFix::CompleteVCard { full_name } => format!("Add the missing VERSION/FN properties (FN:{})", full_name),
+        }
+    }
+}
+
+/// Problema de un elemento y su reparación, si la hay
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemIssue {
+    pub kind: IssueKind,
+    pub message: String,
+    pub fix: Option<ValidationFix>,
+}
+
+impl ItemIssue {
+    fn new(kind: IssueKind, message: impl Into<String>, fix: Option<ValidationFix>) -> Self {
+        Self { kind, message: message.into(), fix }
+    }
+}
+
+/// Elemento almacenado de una colección: su UID en base de datos y sus datos
+#[derive(Debug, Clone)]
+pub struct StoredItem<'a> {
+    pub id: &'a str,
+    /// UID con el que se guardó; es único dentro de la colección
+    pub stored_uid: &'a str,
+    pub data: &'a str,
+}
+
+/// Valida los eventos de un calendario.
+///
+/// Devuelve los problemas de cada elemento, indexados por su id. Los UID
+/// repetidos se detectan sobre el UID escrito en los datos; la reparación
+/// propuesta es escribir el UID almacenado, que ya es único.
+pub fn validate_calendar_items(items: &[StoredItem]) -> HashMap<String, Vec<ItemIssue>> {
+    validate_items(items, validate_icalendar)
+}
+
+/// Valida los contactos de una libreta de direcciones
+pub fn validate_address_book_items(items: &[StoredItem], full_names: &HashMap<String, String>) -> HashMap<String, Vec<ItemIssue>> {
+    validate_items(items, |item| {
+        validate_vcard(item, full_names.get(item.id).map(String::as_str))
+    })
+}
+
+fn validate_items<'a>(
+    items: &[StoredItem<'a>],
+    validate: impl Fn(&StoredItem<'a>) -> (Option<String>, Vec<ItemIssue>),
+) -> HashMap<String, Vec<ItemIssue>> {
+    let mut issues: HashMap<String, Vec<ItemIssue>> = HashMap::new();
+    let mut by_uid: HashMap<String, Vec<&StoredItem>> = HashMap::new();
+
+    for item in items {
+        let (uid, item_issues) = validate(item);
+        if let Some(uid) = uid {
+            by_uid.entry(uid).or_default().push(item);
+        }
+        if !item_issues.is_empty() {
+            issues.insert(item.id.to_string(), item_issues);
+        }
+    }
+
+    for (uid, holders) in by_uid {
+        if holders.len() < 2 {
+            continue;
+        }
+        for item in holders {
+            // El elemento guardado con ese UID lo conserva
+            let fix = (item.stored_uid != uid).then(|| ValidationFix::SetUid(item.stored_uid.to_string()));
+            issues.entry(item.id.to_string()).or_default().push(ItemIssue::new(
+                IssueKind::DuplicateUid,
+                format!("UID {} is used by another item of the collection", uid),
+                fix,
+            ));
+        }
+    }
+    issues
+}
+
+/// Valida un objeto iCalendar y devuelve también el UID que declara
+fn validate_icalendar(item: &StoredItem) -> (Option<String>, Vec<ItemIssue>) {
+    let Some(calendar) = ICalComponent::parse(item.data) else {
+        return (None, vec![ItemIssue::new(IssueKind::MalformedICalendar, "The data is not an iCalendar object", None)]);
+    };
+    // Se aceptan tanto VCALENDAR como un VEVENT suelto
+    let components: Vec<&ICalComponent> = if CALENDAR_ITEM_COMPONENTS.contains(&calendar.name.as_str()) {
+        vec![&calendar]
+    } else {
+        calendar.components.iter()
+            .filter(|component| CALENDAR_ITEM_COMPONENTS.contains(&component.name.as_str()))
+            .collect()
+    };
+    if components.is_empty() {
+        return (None, vec![ItemIssue::new(IssueKind::MalformedICalendar, "The object has no VEVENT, VTODO or VJOURNAL", None)]);
+    }
+
+    let mut issues = Vec::new();
+    let uid = components.iter()
+        .find_map(|component| component.property("UID"))
+        .map(|property| property.value.trim().to_string())
+        .filter(|uid| !uid.is_empty());
+    if uid.is_none() {
+        issues.push(ItemIssue::new(
+            IssueKind::MissingUid,
+            "The object has no UID",
+            Some(ValidationFix::SetUid(item.stored_uid.to_string())),
+        ));
+    }
+
+    for component in components {
+        for rrule in component.properties_named("RRULE") {
+            if let Err(reason) = validate_rrule(&rrule.value) {
+                issues.push(ItemIssue::new(
+                    IssueKind::BrokenRrule,
+                    format!("Invalid RRULE {}: {}", rrule.value, reason),
+                    Some(ValidationFix::RemoveRrule),
+                ));
+            }
+        }
+    }
+    (uid, issues)
+}
+
+/// Valida una vCard; `full_name` es el nombre guardado del contacto, con el
+/// que se completa un FN que falte
+fn validate_vcard(item: &StoredItem, full_name: Option<&str>) -> (Option<String>, Vec<ItemIssue>) {
+    let card = ICalComponent::parse(item.data).filter(|card| card.name == "VCARD");
+    let Some(card) = card else {
+        return (None, vec![ItemIssue::new(IssueKind::MalformedVCard, "The data is not a vCard", None)]);
+    };
+
+    let mut issues = Vec::new();
+    let uid = card.property("UID")
+        .map(|property| property.value.trim().to_string())
+        .filter(|uid| !uid.is_empty());
+    if uid.is_none() {
+        issues.push(ItemIssue::new(
+            IssueKind::MissingUid,
+            "The vCard has no UID",
+            Some(ValidationFix::SetUid(item.stored_uid.to_string())),
+        ));
+    }
+
+    let version = card.property("VERSION").map(|property| property.value.trim().to_string());
+    let has_fn = card.property("FN").is_some_and(|property| !property.value.trim().is_empty());
+    let version_ok = matches!(version.as_deref(), Some("3.0") | Some("4.0"));
+    if !version_ok || !has_fn {
+        let mut missing = Vec::new();
+        if !version_ok {
+            missing.push(match version {
+                Some(version) => format!("unsupported VERSION {}", version),
+                None => "no VERSION".to_string(),
+            });
+        }
+        if !has_fn {
+            missing.push("no FN".to_string());
+        }
+        let full_name = full_name.filter(|name| !name.trim().is_empty())
+            .map(str::to_string)
+            .or_else(|| vcard_display_name(&card));
+        issues.push(ItemIssue::new(
+            IssueKind::MalformedVCard,
+            format!("The vCard has {}", missing.join(" and ")),
+            full_name.map(|full_name| ValidationFix::CompleteVCard { full_name }),
+        ));
+    }
+    (uid, issues)
+}
+
+/// Nombre a mostrar deducido de N, ORG o EMAIL
+fn vcard_display_name(card: &ICalComponent) -> Option<String> {
+    if let Some(name) = card.property("N") {
+        // N: apellidos;nombre;adicionales;prefijos;sufijos
+        let parts: Vec<String> = name.value.split(';').map(|part| part.trim().to_string()).collect();
+        let display = [parts.get(3), parts.get(1), parts.get(2), parts.first(), parts.get(4)]
+            .into_iter()
+            .flatten()
+            .filter(|part| !part.is_empty())
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !display.is_empty() {
+            return Some(display);
+        }
+    }
+    ["ORG", "EMAIL"].iter()
+        .filter_map(|name| card.property(name))
+        .map(|property| property.text().split(';').next().unwrap_or_default().trim().to_string())
+        .find(|value| !value.is_empty())
+}
+
+/// Comprueba la sintaxis de una RRULE (RFC 5545, 3.3.10)
+pub fn validate_rrule(rule: &str) -> Result<(), String> {
+    let mut frequency = None;
+    let mut has_count = false;
+    let mut has_until = false;
+
+    for part in rule.trim().split(';').filter(|part| !part.is_empty()) {
+        let (name, value) = part.split_once('=').ok_or_else(|| format!("{} is not NAME=VALUE", part))?;
+        let name = name.trim().to_ascii_uppercase();
+        let value = value.trim();
+        let values = || value.split(',').map(str::trim);
+        match name.as_str() {
+            "FREQ" => {
+                let freq = value.to_ascii_uppercase();
+                if !RRULE_FREQUENCIES.contains(&freq.as_str()) {
+                    return Err(format!("unknown FREQ {}", value));
+                }
+                frequency = Some(freq);
+            }
+            "COUNT" => {
+                has_count = true;
+                positive(value, "COUNT")?;
+            }
+            "INTERVAL" => positive(value, "INTERVAL")?,
+            "UNTIL" => {
+                has_until = true;
+                if parse_date_time(value, false).is_none() {
+                    return Err(format!("UNTIL {} is not a date", value));
+                }
+            }
+            "BYDAY" => {
+                for day in values() {
+                    let split = day.len().saturating_sub(2);
+                    let (Some(ordinal), Some(weekday)) = (day.get(..split), day.get(split..)) else {
+                        return Err(format!("invalid BYDAY {}", day));
+                    };
+                    if !WEEKDAYS.contains(&weekday.to_ascii_uppercase().as_str()) || !ordinal_in(ordinal, 1, 53, true) {
+                        return Err(format!("invalid BYDAY {}", day));
+                    }
+                }
+            }
+            "WKST" if !WEEKDAYS.contains(&value.to_ascii_uppercase().as_str()) => {
+                return Err(format!("invalid WKST {}", value));
+            }
+            "BYMONTH" => in_range(values(), "BYMONTH", 1, 12, false)?,
+            "BYMONTHDAY" => in_range(values(), "BYMONTHDAY", 1, 31, true)?,
+            "BYYEARDAY" => in_range(values(), "BYYEARDAY", 1, 366, true)?,
+            "BYWEEKNO" => in_range(values(), "BYWEEKNO", 1, 53, true)?,
+            "BYSETPOS" => in_range(values(), "BYSETPOS", 1, 366, true)?,
+            "BYHOUR" => in_range(values(), "BYHOUR", 0, 23, false)?,
+            "BYMINUTE" => in_range(values(), "BYMINUTE", 0, 59, false)?,
+            "BYSECOND" => in_range(values(), "BYSECOND", 0, 60, false)?,
+            // Extensiones de otros estándares (RSCALE, SKIP...) y X-
+            _ => {}
+        }
+    }
+
+    if frequency.is_none() {
+        return Err("FREQ is required".to_string());
+    }
+    if has_count && has_until {
+        return Err("COUNT and UNTIL cannot be combined".to_string());
+    }
+    Ok(())
+}
+
+fn positive(value: &str, name: &str) -> Result<(), String> {
+    match value.parse::<u32>() {
+        Ok(n) if n > 0 => Ok(()),
+        _ => Err(format!("{} must be a positive integer", name)),
+    }
+}
+
+/// Número con signo opcional cuyo valor absoluto está en [min, max]; vacío solo si se permite
+fn ordinal_in(value: &str, min: i64, max: i64, allow_empty: bool) -> bool {
+    if value.is_empty() {
+        return allow_empty;
+    }
+    value.parse::<i64>().is_ok_and(|n| n != 0 && (min..=max).contains(&n.abs()))
+}
+
+fn in_range<'a>(values: impl Iterator<Item = &'a str>, name: &str, min: i64, max: i64, signed: bool) -> Result<(), String> {
+    for value in values {
+        let valid = if signed {
+            ordinal_in(value, min, max, false)
+        } else {
+            value.parse::<i64>().is_ok_and(|n| (min..=max).contains(&n))
+        };
+        if !valid {
+            return Err(format!("{} {} is out of range", name, value));
+        }
+    }
+    Ok(())
+}
+
+/// Aplica una reparación a los datos de un elemento.
+///
+/// Trabaja línea a línea para no tocar lo que no entiende; devuelve los datos
+/// desplegados y con finales CRLF, o `None` si la reparación no aplica.
+pub fn apply_fix(data: &str, fix: &ValidationFix) -> Option<String> {
+    let lines = unfold_lines(data);
+    let mut output: Vec<String> = Vec::with_capacity(lines.len() + 2);
+    let mut changed = false;
+
+    match fix {
+        ValidationFix::SetUid(uid) => {
+            // Componentes abiertos: si es un elemento, dónde empieza y si ya tiene UID
+            let mut stack: Vec<(bool, usize, bool)> = Vec::new();
+            for line in lines {
+                match property_name(&line).as_str() {
+                    "BEGIN" => {
+                        let component = line_value(&line).to_ascii_uppercase();
+                        let is_item = component == "VCARD" || CALENDAR_ITEM_COMPONENTS.contains(&component.as_str());
+                        stack.push((is_item, output.len(), false));
+                    }
+                    "END" => {
+                        if let Some((true, begin, false)) = stack.pop() {
+                            output.insert(begin + 1, format!("UID:{}", uid));
+                            changed = true;
+                        }
+                    }
+                    "UID" => {
+                        if let Some((true, _, seen)) = stack.last_mut() {
+                            *seen = true;
+                            changed |= line_value(&line) != uid;
+                            output.push(format!("UID:{}", uid));
+                            continue;
+                        }
+                    }
+                    _ => {}
+                }
+                output.push(line);
+            }
+        }
+        ValidationFix::RemoveRrule => {
+            for line in lines {
+                if property_name(&line) == "RRULE" {
+                    changed = true;
+                } else {
+                    output.push(line);
+                }
+            }
+        }
+        ValidationFix::CompleteVCard { full_name } => {
+            let has_version = lines.iter().any(|line| property_name(line) == "VERSION" && matches!(line_value(line).trim(), "3.0" | "4.0"));
+            let has_fn = lines.iter().any(|line| property_name(line) == "FN" && !line_value(line).trim().is_empty());
+            for line in lines {
+                let name = property_name(&line);
+                if (name == "VERSION" && !has_version) || (name == "FN" && !has_fn) {
+                    continue;
+                }
+                let is_begin = name == "BEGIN" && line_value(&line).eq_ignore_ascii_case("VCARD");
+                output.push(line);
+                if is_begin {
+                    if !has_version {
+                        output.push("VERSION:3.0".to_string());
+                        changed = true;
+                    }
+                    if !has_fn {
+                        output.push(format!("FN:{}", escape_text(full_name)));
+                        changed = true;
+                    }
+                }
+            }
+        }
+    }
+
+    changed.then(|| {
+        let mut data = output.join("\r\n");
+        data.push_str("\r\n");
+        data
+    })
+}
+
+/// Nombre de la propiedad de una línea, en mayúsculas
+fn property_name(line: &str) -> String {
+    let end = line.find([';', ':']).unwrap_or(line.len());
+    line[..end].trim().to_ascii_uppercase()
+}
+
+fn line_value(line: &str) -> &str {
+    line.split_once(':').map(|(_, value)| value.trim()).unwrap_or_default()
+}
+
+fn escape_text(value: &str) -> String {
+    value.replace('\\', "\\\\").replace(';', "\\;").replace(',', "\\,").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item<'a>(id: &'a str, stored_uid: &'a str, data: &'a str) -> StoredItem<'a> {
+        StoredItem { id, stored_uid, data }
+    }
+
+    #[test]
+    fn validates_rrules() {
+        assert!(validate_rrule("FREQ=WEEKLY;BYDAY=MO,WE,-1FR;COUNT=10").is_ok());
+        assert!(validate_rrule("FREQ=MONTHLY;BYMONTHDAY=-1;UNTIL=20241231T000000Z").is_ok());
+        assert!(validate_rrule("BYDAY=MO").is_err());
+        assert!(validate_rrule("FREQ=FORTNIGHTLY").is_err());
+        assert!(validate_rrule("FREQ=DAILY;COUNT=3;UNTIL=20240101").is_err());
+        assert!(validate_rrule("FREQ=DAILY;BYHOUR=24").is_err());
+        assert!(validate_rrule("FREQ=WEEKLY;BYDAY=XX").is_err());
+    }
+
+    #[test]
+    fn reports_calendar_issues_with_fixes() {
+        let a = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:same\r\nDTSTART:20240101T100000Z\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        let b = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:same\r\nRRULE:FREQ=NEVER\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        let c = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nSUMMARY:No uid\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        let d = "not a calendar";
+        let issues = validate_calendar_items(&[
+            item("a", "same", a),
+            item("b", "b-uid", b),
+            item("c", "c-uid", c),
+            item("d", "d-uid", d),
+        ]);
+
+        // El elemento guardado con el UID repetido lo conserva
+        assert_eq!(issues["a"][0].kind, IssueKind::DuplicateUid);
+        assert_eq!(issues["a"][0].fix, None);
+
+        let kinds: Vec<IssueKind> = issues["b"].iter().map(|issue| issue.kind).collect();
+        assert_eq!(kinds, vec![IssueKind::BrokenRrule, IssueKind::DuplicateUid]);
+        assert_eq!(issues["b"][1].fix, Some(ValidationFix::SetUid("b-uid".to_string())));
+
+        assert_eq!(issues["c"][0].kind, IssueKind::MissingUid);
+        assert_eq!(issues["d"][0].kind, IssueKind::MalformedICalendar);
+
+        let repaired = apply_fix(b, &ValidationFix::RemoveRrule).unwrap();
+        let repaired = apply_fix(&repaired, &issues["b"][1].fix.clone().unwrap()).unwrap();
+        assert!(validate_calendar_items(&[item("b", "b-uid", &repaired)]).is_empty());
+
+        let repaired = apply_fix(c, &ValidationFix::SetUid("c-uid".to_string())).unwrap();
+        assert!(repaired.contains("BEGIN:VEVENT\r\nUID:c-uid\r\n"));
+    }
+
+    #[test]
+    fn completes_malformed_vcards() {
+        let card = "BEGIN:VCARD\nUID:1\nN:Doe;John;;Dr.;\nEND:VCARD\n";
+        let issues = validate_address_book_items(&[item("x", "1", card)], &HashMap::new());
+        let issue = &issues["x"][0];
+        assert_eq!(issue.kind, IssueKind::MalformedVCard);
+        assert_eq!(issue.fix, Some(ValidationFix::CompleteVCard { full_name: "Dr. John Doe".to_string() }));
+
+        let repaired = apply_fix(card, issue.fix.as_ref().unwrap()).unwrap();
+        assert_eq!(repaired, "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Dr. John Doe\r\nUID:1\r\nN:Doe;John;;Dr.;\r\nEND:VCARD\r\n");
+        assert!(validate_address_book_items(&[item("x", "1", &repaired)], &HashMap::new()).is_empty());
+
+        let issues = validate_address_book_items(&[item("y", "2", "garbage")], &HashMap::new());
+        assert_eq!(issues["y"][0].kind, IssueKind::MalformedVCard);
+        assert_eq!(issues["y"][0].fix, None);
+    }
+}
@@ -0,0 +1,116 @@
+use crate::domain::entities::notification_preferences::EmailEventKind;
+use crate::domain::services::template_placeholder_service::{fill_text, TemplateValues};
+
+/// Prefijo de la primera línea de las plantillas, que lleva el asunto
+const SUBJECT_PREFIX: &str = "Subject:";
+
+/// Plantilla de un correo de aviso con marcas `{{nombre}}` en el asunto y en
+/// el cuerpo.
+///
+/// Las plantillas propias son ficheros de texto cuya primera línea es
+/// `Subject: ...`, seguida de una línea en blanco y del cuerpo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailTemplate {
+    pub subject: String,
+    pub body: String,
+}
+
+impl EmailTemplate {
+    /// Plantilla incluida para cada suceso
+    pub fn builtin(kind: EmailEventKind) -> Self {
+        let (subject, body) = match kind {
+            EmailEventKind::ShareReceived => (
+                "{{actor}} shared \"{{item_name}}\" with you",
+                "Hello {{username}},\n\n\
+                 {{actor}} shared the {{item_kind}} \"{{item_name}}\" with you.\n\n\
+                 Open it at {{link}}\n",
+            ),
+            EmailEventKind::ShareDownloaded => (
+                "\"{{item_name}}\" was downloaded from your shared link",
+                "Hello {{username}},\n\n\
+                 Someone downloaded \"{{item_name}}\" from a link you shared. \
+                 The link has been downloaded {{download_count}} times so far.\n\n\
+                 You can review or remove your shared links at {{link}}\n",
+            ),
+            EmailEventKind::QuotaWarning => (
+                "Your storage is almost full",
+                "Hello {{username}},\n\n\
+                 You are using {{used}} of your {{quota}} ({{percent}}%). \
+                 When it is full you will not be able to upload more files.\n\n\
+                 Free some space at {{link}}\n",
+            ),
+        };
+        Self { subject: subject.to_string(), body: body.to_string() }
+    }
+
+    /// Lee una plantilla propia; `None` si no empieza por el asunto
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.strip_prefix('\u{feff}').unwrap_or(text).replace("\r\n", "\n");
+        let (first_line, rest) = text.split_once('\n').unwrap_or((text.as_str(), ""));
+        let subject = first_line.strip_prefix(SUBJECT_PREFIX)?.trim();
+        if subject.is_empty() {
+            return None;
+        }
+        Some(Self {
+            subject: subject.to_string(),
+            body: rest.strip_prefix('\n').unwrap_or(rest).to_string(),
+        })
+    }
+
+    /// Rellena el asunto y el cuerpo. El asunto queda en una sola línea
+    /// aunque algún valor tenga saltos
+    pub fn render(&self, values: &TemplateValues) -> (String, String) {
+        let subject = fill_text(&self.subject, values)
+            .split(['\r', '\n'])
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        (subject, fill_text(&self.body, values))
+    }
+}
+
+/// Tamaño legible para personas, como `1.5 GB`
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_render() {
+        let template = EmailTemplate::parse("Subject: {{actor}} te ha compartido {{item_name}}\r\n\r\nHola {{username}}\r\n").unwrap();
+        assert_eq!(template.body, "Hola {{username}}\n");
+
+        let values = TemplateValues::new()
+            .with("actor", "carla")
+            .with("item_name", "Fotos\nde verano")
+            .with("username", "ana");
+        let (subject, body) = template.render(&values);
+        assert_eq!(subject, "carla te ha compartido Fotos de verano");
+        assert_eq!(body, "Hola ana\n");
+
+        assert!(EmailTemplate::parse("Hola {{username}}").is_none());
+        assert!(EmailTemplate::parse("Subject:\n\nHola").is_none());
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GB");
+    }
+}
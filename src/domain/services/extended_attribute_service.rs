@@ -0,0 +1,128 @@
+use crate::common::errors::DomainError;
+
+/// Espacio de nombres de las propiedades de mod_dav (Apache)
+pub const APACHE_PROPS_NAMESPACE: &str = "http://apache.org/dav/props/";
+
+/// Espacio de nombres propio de OxiCloud
+pub const OXICLOUD_NAMESPACE: &str = "http://oxicloud.org/ns";
+
+/// Tamaño máximo del valor de un atributo, en bytes
+pub const MAX_ATTRIBUTE_VALUE_LEN: usize = 4096;
+
+/// Atributos extendidos que los clientes WebDAV pueden guardar en un fichero
+/// para no perderlos al hacer ida y vuelta con el servidor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExtendedAttribute {
+    /// Bit de ejecución (`T`/`F`), como `executable` de mod_dav
+    Executable,
+    /// Permisos POSIX en octal (`0755`)
+    UnixMode,
+    /// Bloque FinderInfo de macOS, codificado por el cliente
+    FinderInfo,
+    /// Etiquetas del Finder de macOS, separadas por saltos de línea
+    FinderTags,
+}
+
+impl ExtendedAttribute {
+    /// Todos los atributos soportados
+    pub const ALL: [ExtendedAttribute; 4] = [
+        ExtendedAttribute::Executable,
+        ExtendedAttribute::UnixMode,
+        ExtendedAttribute::FinderInfo,
+        ExtendedAttribute::FinderTags,
+    ];
+
+    /// Busca el atributo correspondiente al nombre local de una propiedad
+    pub fn from_property(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|attr| attr.property_name() == name)
+    }
+
+    /// Nombre local de la propiedad DAV
+    pub fn property_name(&self) -> &'static str {
+        match self {
+            Self::Executable => "executable",
+            Self::UnixMode => "unix-mode",
+            Self::FinderInfo => "finder-info",
+            Self::FinderTags => "finder-tags",
+        }
+    }
+
+    /// Espacio de nombres con el que se devuelve la propiedad
+    pub fn namespace(&self) -> &'static str {
+        match self {
+            Self::Executable => APACHE_PROPS_NAMESPACE,
+            _ => OXICLOUD_NAMESPACE,
+        }
+    }
+
+    /// Valida el valor recibido del cliente y lo devuelve en forma canónica
+    pub fn normalize(&self, value: &str) -> Result<String, DomainError> {
+        let value = value.trim();
+        if value.len() > MAX_ATTRIBUTE_VALUE_LEN {
+            return Err(DomainError::validation_error(format!(
+                "Value of '{}' exceeds {} bytes",
+                self.property_name(),
+                MAX_ATTRIBUTE_VALUE_LEN
+            )));
+        }
+
+        match self {
+            Self::Executable => match value.to_ascii_lowercase().as_str() {
+                "t" | "true" | "1" => Ok("T".to_string()),
+                "f" | "false" | "0" => Ok("F".to_string()),
+                _ => Err(DomainError::validation_error("executable must be T or F")),
+            },
+            Self::UnixMode => {
+                let digits = value.strip_prefix("0o").unwrap_or(value);
+                match u32::from_str_radix(digits, 8) {
+                    Ok(mode) if !digits.is_empty() && mode <= 0o7777 => Ok(format!("{:04o}", mode)),
+                    _ => Err(DomainError::validation_error("unix-mode must be an octal mode up to 7777")),
+                }
+            }
+            Self::FinderInfo | Self::FinderTags => {
+                if value.chars().any(|c| c.is_control() && c != '\n') {
+                    return Err(DomainError::validation_error(format!(
+                        "Value of '{}' contains control characters",
+                        self.property_name()
+                    )));
+                }
+                Ok(value.to_string())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_property() {
+        assert_eq!(ExtendedAttribute::from_property("executable"), Some(ExtendedAttribute::Executable));
+        assert_eq!(ExtendedAttribute::from_property("unix-mode"), Some(ExtendedAttribute::UnixMode));
+        assert_eq!(ExtendedAttribute::from_property("Win32CreationTime"), None);
+    }
+
+    #[test]
+    fn test_normalize_executable() {
+        assert_eq!(ExtendedAttribute::Executable.normalize("t").unwrap(), "T");
+        assert_eq!(ExtendedAttribute::Executable.normalize("false").unwrap(), "F");
+        assert!(ExtendedAttribute::Executable.normalize("yes").is_err());
+    }
+
+    #[test]
+    fn test_normalize_unix_mode() {
+        assert_eq!(ExtendedAttribute::UnixMode.normalize("755").unwrap(), "0755");
+        assert_eq!(ExtendedAttribute::UnixMode.normalize("0o4755").unwrap(), "4755");
+        assert!(ExtendedAttribute::UnixMode.normalize("0999").is_err());
+        assert!(ExtendedAttribute::UnixMode.normalize("17777").is_err());
+        assert!(ExtendedAttribute::UnixMode.normalize("").is_err());
+    }
+
+    #[test]
+    fn test_normalize_finder_metadata() {
+        assert_eq!(ExtendedAttribute::FinderTags.normalize("Red\nWork").unwrap(), "Red\nWork");
+        assert!(ExtendedAttribute::FinderTags.normalize("bad\u{0}tag").is_err());
+        assert!(ExtendedAttribute::FinderInfo.normalize(&"A".repeat(MAX_ATTRIBUTE_VALUE_LEN + 1)).is_err());
+    }
+}
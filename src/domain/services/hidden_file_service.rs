@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+
+use crate::common::errors::{DomainError, ErrorKind};
+
+/// Ficheros de sistema que crean los exploradores y suites ofimáticas
+const SYSTEM_FILE_NAMES: &[&str] = &[".ds_store", "thumbs.db", "ehthumbs.db", "desktop.ini", ".localized"];
+
+/// Qué hacer con un tipo de fichero oculto o de sistema
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HiddenFilePolicy {
+    /// Se guarda y se muestra como cualquier otro fichero
+    Store,
+    /// Se guarda, pero no aparece en listados, búsquedas ni cuotas
+    Hide,
+    /// No se permite subirlo
+    Reject,
+}
+
+impl HiddenFilePolicy {
+    /// Interpreta el nombre de una política (`store`, `hide` o `reject`)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "store" | "allow" => Some(Self::Store),
+            "hide" | "exclude" => Some(Self::Hide),
+            "reject" | "deny" => Some(Self::Reject),
+            _ => None,
+        }
+    }
+}
+
+/// Tipo de fichero al que se aplican las reglas
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HiddenFileKind {
+    /// Nombre que empieza por punto (`.bashrc`, `.env`)
+    Dotfile,
+    /// Metadatos de sistema: `.DS_Store`, `Thumbs.db`, ficheros AppleDouble
+    /// (`._foto.jpg`) y temporales de Office (`~$informe.docx`)
+    System,
+}
+
+/// Clasifica un nombre de fichero; `None` para los ficheros normales
+pub fn classify(name: &str) -> Option<HiddenFileKind> {
+    let lower = name.to_lowercase();
+    if SYSTEM_FILE_NAMES.contains(&lower.as_str())
+        || lower.starts_with("._")
+        || lower.starts_with("~$")
+        || (lower.starts_with(".~lock.") && lower.ends_with('#'))
+    {
+        Some(HiddenFileKind::System)
+    } else if lower.starts_with('.') {
+        Some(HiddenFileKind::Dotfile)
+    } else {
+        None
+    }
+}
+
+/// Reglas de ficheros ocultos y de sistema de la instancia o de un usuario
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HiddenFileRules {
+    pub dotfiles: HiddenFilePolicy,
+    pub system_files: HiddenFilePolicy,
+}
+
+impl Default for HiddenFileRules {
+    /// Los dotfiles nunca se han mostrado en los listados; se mantiene así por defecto
+    fn default() -> Self {
+        Self {
+            dotfiles: HiddenFilePolicy::Hide,
+            system_files: HiddenFilePolicy::Store,
+        }
+    }
+}
+
+impl HiddenFileRules {
+    /// Política que se aplica a un nombre de fichero
+    pub fn policy_for(&self, name: &str) -> HiddenFilePolicy {
+        match classify(name) {
+            Some(HiddenFileKind::Dotfile) => self.dotfiles,
+            Some(HiddenFileKind::System) => self.system_files,
+            None => HiddenFilePolicy::Store,
+        }
+    }
+
+    /// Indica si el fichero debe quedar fuera de listados, búsquedas y cuotas
+    pub fn is_hidden(&self, name: &str) -> bool {
+        self.policy_for(name) != HiddenFilePolicy::Store
+    }
+
+    /// Comprueba que se puede subir un fichero con este nombre
+    pub fn check_upload(&self, name: &str) -> Result<(), DomainError> {
+        if self.policy_for(name) == HiddenFilePolicy::Reject {
+            return Err(DomainError::new(
+                ErrorKind::AccessDenied,
+                "File",
+                format!("Files named '{}' are not allowed on this server", name),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Combina dos conjuntos de reglas quedándose con la más estricta de cada tipo.
+    ///
+    /// Un usuario puede ocultar o rechazar más ficheros que la instancia, pero
+    /// no volver a permitir lo que la instancia rechaza.
+    pub fn strictest(self, other: HiddenFileRules) -> HiddenFileRules {
+        HiddenFileRules {
+            dotfiles: self.dotfiles.max(other.dotfiles),
+            system_files: self.system_files.max(other.system_files),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify(".DS_Store"), Some(HiddenFileKind::System));
+        assert_eq!(classify("Thumbs.db"), Some(HiddenFileKind::System));
+        assert_eq!(classify("._photo.jpg"), Some(HiddenFileKind::System));
+        assert_eq!(classify("~$report.docx"), Some(HiddenFileKind::System));
+        assert_eq!(classify(".~lock.notes.odt#"), Some(HiddenFileKind::System));
+        assert_eq!(classify(".bashrc"), Some(HiddenFileKind::Dotfile));
+        assert_eq!(classify("report.docx"), None);
+        assert_eq!(classify("a.DS_Store"), None);
+    }
+
+    #[test]
+    fn test_policies() {
+        let rules = HiddenFileRules { dotfiles: HiddenFilePolicy::Store, system_files: HiddenFilePolicy::Reject };
+        assert!(!rules.is_hidden(".env"));
+        assert!(rules.is_hidden("desktop.ini"));
+        assert!(rules.check_upload("desktop.ini").is_err());
+        assert!(rules.check_upload(".env").is_ok());
+        assert!(rules.check_upload("photo.jpg").is_ok());
+    }
+
+    #[test]
+    fn test_strictest() {
+        let instance = HiddenFileRules { dotfiles: HiddenFilePolicy::Hide, system_files: HiddenFilePolicy::Store };
+        let user = HiddenFileRules { dotfiles: HiddenFilePolicy::Store, system_files: HiddenFilePolicy::Reject };
+        assert_eq!(
+            instance.strictest(user),
+            HiddenFileRules { dotfiles: HiddenFilePolicy::Hide, system_files: HiddenFilePolicy::Reject }
+        );
+    }
+
+    #[test]
+    fn test_parse_policy() {
+        assert_eq!(HiddenFilePolicy::parse("Reject"), Some(HiddenFilePolicy::Reject));
+        assert_eq!(HiddenFilePolicy::parse("exclude"), Some(HiddenFilePolicy::Hide));
+        assert_eq!(HiddenFilePolicy::parse("maybe"), None);
+    }
+}
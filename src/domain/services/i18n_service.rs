@@ -46,6 +46,16 @@ impl Locale {
     pub fn default() -> Self {
         Locale::English
     }
+    
+    /// Picks the first supported language of an Accept-Language header
+    pub fn from_accept_language(header: &str) -> Self {
+        header
+            .split(',')
+            .filter_map(|part| part.split(';').next())
+            .filter_map(|tag| tag.trim().split('-').next())
+            .find_map(Self::from_str)
+            .unwrap_or_else(Self::default)
+    }
 }
 
 /// Interface for i18n service (primary port)
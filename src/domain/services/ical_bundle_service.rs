@@ -0,0 +1,242 @@
+use std::collections::HashSet;
+
+use uuid::Uuid;
+
+use crate::domain::services::icalendar_service::{ICalComponent, ICalProperty};
+
+/// PRODID de los objetos iCalendar que genera OxiCloud
+pub const OXICLOUD_PRODID: &str = "-//OxiCloud//NONSGML Calendar//EN";
+
+/// Objeto de calendario extraído de un fichero .ics: todos los VEVENT con el
+/// mismo UID (el evento y sus excepciones con RECURRENCE-ID) junto a los
+/// VTIMEZONE que usan, listo para guardarse como un único evento.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarObject {
+    pub uid: String,
+    /// VCALENDAR con los componentes del objeto
+    pub ical_data: String,
+    /// Posición (desde 0) del primer VEVENT del objeto en el fichero
+    pub index: usize,
+}
+
+/// VEVENT del fichero que no se importa
+#[derive(Debug, Clone, PartialEq)]
+pub struct RejectedEvent {
+    pub index: usize,
+    pub uid: Option<String>,
+    pub reason: String,
+}
+
+/// Resultado de separar un fichero .ics en objetos de calendario
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SplitCalendar {
+    pub objects: Vec<CalendarObject>,
+    /// VEVENT repetidos (mismo UID y RECURRENCE-ID que otro anterior)
+    pub duplicates: Vec<RejectedEvent>,
+}
+
+/// Separa un VCALENDAR con varios VEVENT en un objeto por UID (RFC 4791, 4.1).
+///
+/// Los VEVENT sin UID reciben uno nuevo para que el evento guardado y el
+/// exportado coincidan. Un VEVENT con el mismo UID y RECURRENCE-ID que otro
+/// anterior se descarta como duplicado. Devuelve `None` si los datos no son
+/// un VCALENDAR.
+pub fn split_calendar(data: &str) -> Option<SplitCalendar> {
+    let calendar = ICalComponent::parse(data)?;
+    if calendar.name != "VCALENDAR" {
+        return None;
+    }
+
+    let timezones: Vec<&ICalComponent> = calendar.components_named("VTIMEZONE").collect();
+    // Objetos en orden de aparición, con sus VEVENT
+    let mut groups: Vec<(String, usize, Vec<ICalComponent>)> = Vec::new();
+    let mut seen: HashSet<(String, Option<String>)> = HashSet::new();
+    let mut result = SplitCalendar::default();
+
+    for (index, event) in calendar.components_named("VEVENT").enumerate() {
+        let mut event = event.clone();
+        let uid = match event.property("UID").map(|uid| uid.value.trim().to_string()) {
+            Some(uid) if !uid.is_empty() => uid,
+            _ => {
+                let uid = Uuid::new_v4().to_string();
+                event.properties.retain(|property| property.name != "UID");
+                event.properties.push(ICalProperty { name: "UID".to_string(), params: Vec::new(), value: uid.clone() });
+                uid
+            }
+        };
+
+        let recurrence_id = event.property("RECURRENCE-ID").map(|property| property.value.trim().to_string());
+        if !seen.insert((uid.clone(), recurrence_id)) {
+            result.duplicates.push(RejectedEvent {
+                index,
+                uid: Some(uid),
+                reason: "Duplicate UID in the imported file".to_string(),
+            });
+            continue;
+        }
+
+        // El evento principal va delante de sus excepciones, que es el que se indexa
+        let is_master = event.property("RECURRENCE-ID").is_none();
+        match groups.iter_mut().find(|(group_uid, _, _)| *group_uid == uid) {
+            Some((_, _, events)) if is_master => events.insert(0, event),
+            Some((_, _, events)) => events.push(event),
+            None => groups.push((uid, index, vec![event])),
+        }
+    }
+
+    for (uid, index, events) in groups {
+        let mut object = ICalComponent::new("VCALENDAR");
+        object.properties = calendar_header(&calendar);
+        let used: HashSet<&str> = events.iter().flat_map(referenced_timezones).collect();
+        object.components.extend(
+            timezones.iter()
+                .filter(|timezone| timezone.property("TZID").is_some_and(|tzid| used.contains(tzid.value.as_str())))
+                .map(|timezone| (*timezone).clone()),
+        );
+        object.components.extend(events);
+        result.objects.push(CalendarObject { uid, ical_data: object.to_ical(), index });
+    }
+
+    Some(result)
+}
+
+/// Une los objetos guardados de un calendario en un único VCALENDAR.
+///
+/// Acepta tanto VCALENDAR completos como VEVENT sueltos; cada VTIMEZONE se
+/// incluye una sola vez. `name` se publica como X-WR-CALNAME y `timezone`
+/// como X-WR-TIMEZONE, que es lo que usan los clientes al importar.
+pub fn merge_calendar<'a>(
+    name: Option<&str>,
+    timezone: Option<&str>,
+    objects: impl IntoIterator<Item = &'a str>,
+) -> String {
+    let mut calendar = ICalComponent::new("VCALENDAR");
+    let mut header = vec![
+        ("VERSION", "2.0".to_string()),
+        ("PRODID", OXICLOUD_PRODID.to_string()),
+        ("CALSCALE", "GREGORIAN".to_string()),
+    ];
+    if let Some(name) = name {
+        header.push(("X-WR-CALNAME", escape_text(name)));
+    }
+    if let Some(timezone) = timezone {
+        header.push(("X-WR-TIMEZONE", timezone.to_string()));
+    }
+    calendar.properties = header.into_iter()
+        .map(|(name, value)| ICalProperty { name: name.to_string(), params: Vec::new(), value })
+        .collect();
+
+    let mut timezone_ids: HashSet<String> = HashSet::new();
+    let mut timezones = Vec::new();
+    let mut components = Vec::new();
+    for data in objects {
+        let Some(object) = ICalComponent::parse(data) else { continue };
+        let parts = if object.name == "VCALENDAR" { object.components } else { vec![object] };
+        for part in parts {
+            match part.name.as_str() {
+                "VTIMEZONE" => {
+                    let tzid = part.property("TZID").map(|tzid| tzid.value.clone()).unwrap_or_default();
+                    if timezone_ids.insert(tzid) {
+                        timezones.push(part);
+                    }
+                }
+                "VEVENT" | "VTODO" | "VJOURNAL" => components.push(part),
+                _ => {}
+            }
+        }
+    }
+    calendar.components = timezones;
+    calendar.components.extend(components);
+    calendar.to_ical()
+}
+
+/// Propiedades de cabecera que se conservan en cada objeto importado
+fn calendar_header(calendar: &ICalComponent) -> Vec<ICalProperty> {
+    let mut header: Vec<ICalProperty> = calendar.properties.iter()
+        .filter(|property| matches!(property.name.as_str(), "VERSION" | "PRODID" | "CALSCALE"))
+        .cloned()
+        .collect();
+    if !header.iter().any(|property| property.name == "VERSION") {
+        header.insert(0, ICalProperty { name: "VERSION".to_string(), params: Vec::new(), value: "2.0".to_string() });
+    }
+    if !header.iter().any(|property| property.name == "PRODID") {
+        header.push(ICalProperty { name: "PRODID".to_string(), params: Vec::new(), value: OXICLOUD_PRODID.to_string() });
+    }
+    header
+}
+
+/// TZID usados por las propiedades de un VEVENT y sus subcomponentes
+fn referenced_timezones(event: &ICalComponent) -> Vec<&str> {
+    let mut tzids: Vec<&str> = event.properties.iter().filter_map(|property| property.param("TZID")).collect();
+    for component in &event.components {
+        tzids.extend(referenced_timezones(component));
+    }
+    tzids
+}
+
+/// Escapa un valor de texto (RFC 5545, 3.3.11)
+fn escape_text(value: &str) -> String {
+    value.replace('\\', "\\\\").replace(';', "\\;").replace(',', "\\,").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BERLIN: &str = "BEGIN:VTIMEZONE\r\nTZID:Europe/Berlin\r\nBEGIN:STANDARD\r\nDTSTART:19701025T030000\r\nTZOFFSETFROM:+0200\r\nTZOFFSETTO:+0100\r\nEND:STANDARD\r\nEND:VTIMEZONE\r\n";
+
+    #[test]
+    fn test_split_groups_by_uid_and_reports_duplicates() {
+        let data = format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Example//EN\r\nX-WR-CALNAME:Work\r\n{}\
+             BEGIN:VEVENT\r\nUID:a\r\nSUMMARY:Standup\r\nDTSTART;TZID=Europe/Berlin:20250101T090000\r\nRRULE:FREQ=DAILY\r\nEND:VEVENT\r\n\
+             BEGIN:VEVENT\r\nUID:b\r\nSUMMARY:Lunch\r\nDTSTART:20250101T120000Z\r\nEND:VEVENT\r\n\
+             BEGIN:VEVENT\r\nUID:a\r\nRECURRENCE-ID;TZID=Europe/Berlin:20250102T090000\r\nSUMMARY:Moved\r\nDTSTART:20250102T100000Z\r\nEND:VEVENT\r\n\
+             BEGIN:VEVENT\r\nUID:b\r\nSUMMARY:Lunch again\r\nDTSTART:20250101T120000Z\r\nEND:VEVENT\r\n\
+             BEGIN:VEVENT\r\nSUMMARY:No UID\r\nDTSTART:20250103T120000Z\r\nEND:VEVENT\r\n\
+             END:VCALENDAR\r\n",
+            BERLIN
+        );
+
+        let split = split_calendar(&data).unwrap();
+        let uids: Vec<_> = split.objects.iter().map(|o| o.uid.as_str()).collect();
+        assert_eq!(&uids[..2], ["a", "b"]);
+        assert_eq!(split.objects.len(), 3);
+        assert_eq!(split.duplicates, vec![RejectedEvent {
+            index: 3,
+            uid: Some("b".to_string()),
+            reason: "Duplicate UID in the imported file".to_string(),
+        }]);
+
+        let standup = &split.objects[0].ical_data;
+        assert_eq!(standup.matches("BEGIN:VEVENT").count(), 2);
+        assert!(standup.contains("TZID:Europe/Berlin"));
+        assert!(standup.contains("PRODID:-//Example//EN"));
+        assert!(!standup.contains("X-WR-CALNAME"));
+        assert!(!split.objects[1].ical_data.contains("VTIMEZONE"));
+
+        // The generated UID is written into the stored data
+        let generated = &split.objects[2];
+        assert_eq!(generated.index, 4);
+        assert!(generated.ical_data.contains(&format!("UID:{}", generated.uid)));
+
+        assert!(split_calendar("BEGIN:VEVENT\r\nUID:x\r\nEND:VEVENT\r\n").is_none());
+    }
+
+    #[test]
+    fn test_merge_deduplicates_timezones() {
+        let first = format!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{}BEGIN:VEVENT\r\nUID:a\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n", BERLIN);
+        let second = format!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{}BEGIN:VEVENT\r\nUID:b\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n", BERLIN);
+        let bare = "BEGIN:VEVENT\r\nUID:c\r\nEND:VEVENT\r\n";
+
+        let merged = merge_calendar(Some("Work, home"), Some("Europe/Berlin"), [first.as_str(), second.as_str(), bare]);
+        assert!(merged.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//OxiCloud//NONSGML Calendar//EN\r\n"));
+        assert!(merged.contains("X-WR-CALNAME:Work\\, home\r\n"));
+        assert_eq!(merged.matches("BEGIN:VTIMEZONE").count(), 1);
+        assert_eq!(merged.matches("BEGIN:VEVENT").count(), 3);
+        assert!(merged.ends_with("END:VCALENDAR\r\n"));
+
+        // An export can be imported again without losing events
+        assert_eq!(split_calendar(&merged).unwrap().objects.len(), 3);
+    }
+}
@@ -0,0 +1,329 @@
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+
+/// Propiedad de un componente iCalendar (RFC 5545, 3.1): `NOMBRE;PARAM=valor:valor`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ICalProperty {
+    /// Nombre en mayúsculas
+    pub name: String,
+    /// Parámetros con el nombre en mayúsculas y el valor sin comillas
+    pub params: Vec<(String, String)>,
+    /// Valor tal y como aparece, sin desescapar
+    pub value: String,
+}
+
+impl ICalProperty {
+    /// Valor de un parámetro, sin distinguir mayúsculas en el nombre
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.iter()
+            .find(|(param, _)| param.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Valor de texto con las secuencias de escape resueltas (RFC 5545, 3.3.11)
+    pub fn text(&self) -> String {
+        unescape_text(&self.value)
+    }
+
+    /// Fecha de una propiedad DATE o DATE-TIME y si es una fecha sin hora.
+    ///
+    /// Las horas flotantes y las que llevan TZID se interpretan como UTC;
+    /// `timezone_service::resolve_date_time` las convierte con su zona.
+    pub fn date_time(&self) -> Option<(DateTime<Utc>, bool)> {
+        let is_date = self.param("VALUE").is_some_and(|v| v.eq_ignore_ascii_case("DATE"));
+        parse_date_time(&self.value, is_date)
+    }
+}
+
+/// Componente iCalendar (VCALENDAR, VEVENT, VALARM...) con sus propiedades y subcomponentes
+#[derive(Debug, Clone, PartialEq)]
+pub struct ICalComponent {
+    /// Nombre en mayúsculas
+    pub name: String,
+    pub properties: Vec<ICalProperty>,
+    pub components: Vec<ICalComponent>,
+}
+
+impl ICalComponent {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_ascii_uppercase(),
+            properties: Vec::new(),
+            components: Vec::new(),
+        }
+    }
+
+    /// Interpreta el primer componente de un objeto iCalendar.
+    ///
+    /// Es tolerante con lo que envían los clientes: acepta finales de línea
+    /// LF o CRLF, ignora las líneas que no entiende y cierra los componentes
+    /// que se quedaron abiertos. Devuelve `None` si no hay ningún `BEGIN`.
+    pub fn parse(data: &str) -> Option<Self> {
+        let mut stack: Vec<ICalComponent> = Vec::new();
+        for line in unfold_lines(data) {
+            let Some(property) = parse_content_line(&line) else { continue };
+            match property.name.as_str() {
+                "BEGIN" => stack.push(ICalComponent::new(property.value.trim())),
+                "END" => {
+                    let name = property.value.trim().to_ascii_uppercase();
+                    // Cierra también los componentes intermedios sin END
+                    while let Some(component) = stack.pop() {
+                        let closed = component.name == name;
+                        match stack.last_mut() {
+                            Some(parent) => parent.components.push(component),
+                            None => return Some(component),
+                        }
+                        if closed {
+                            break;
+                        }
+                    }
+                }
+                _ => {
+                    if let Some(component) = stack.last_mut() {
+                        component.properties.push(property);
+                    }
+                }
+            }
+        }
+        // Componentes sin cerrar al terminar los datos
+        let mut root = stack.pop()?;
+        while let Some(mut parent) = stack.pop() {
+            parent.components.push(root);
+            root = parent;
+        }
+        Some(root)
+    }
+
+    /// Primera propiedad con ese nombre
+    pub fn property(&self, name: &str) -> Option<&ICalProperty> {
+        self.properties.iter().find(|property| property.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Todas las propiedades con ese nombre
+    pub fn properties_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a ICalProperty> + 'a {
+        self.properties.iter().filter(move |property| property.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Subcomponentes con ese nombre
+    pub fn components_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a ICalComponent> + 'a {
+        self.components.iter().filter(move |component| component.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Serializa el componente con finales CRLF y las líneas plegadas a 75 octetos
+    pub fn to_ical(&self) -> String {
+        let mut out = String::new();
+        self.write_ical(&mut out);
+        out
+    }
+
+    fn write_ical(&self, out: &mut String) {
+        out.push_str(&format!("BEGIN:{}\r\n", self.name));
+        for property in &self.properties {
+            let mut line = property.name.clone();
+            for (name, value) in &property.params {
+                if value.contains([':', ';', ',']) {
+                    line.push_str(&format!(";{}=\"{}\"", name, value));
+                } else {
+                    line.push_str(&format!(";{}={}", name, value));
+                }
+            }
+            line.push(':');
+            line.push_str(&property.value);
+            fold_line(&line, out);
+        }
+        for component in &self.components {
+            component.write_ical(out);
+        }
+        out.push_str(&format!("END:{}\r\n", self.name));
+    }
+}
+
+/// Pliega una línea de contenido (RFC 5545, 3.1) sin partir caracteres UTF-8
+pub fn fold_line(line: &str, out: &mut String) {
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            length = 1;
+        }
+        out.push(c);
+        length += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+/// Une las líneas plegadas (RFC 5545, 3.1): las que empiezan por espacio o
+/// tabulador continúan la anterior
+pub fn unfold_lines(data: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in data.split('\n') {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+        match (raw.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(previous)) => previous.push_str(continuation),
+            _ if raw.is_empty() => {}
+            _ => lines.push(raw.to_string()),
+        }
+    }
+    lines
+}
+
+/// Separa una línea de contenido en nombre, parámetros y valor, respetando
+/// los valores de parámetro entre comillas
+fn parse_content_line(line: &str) -> Option<ICalProperty> {
+    let mut in_quotes = false;
+    let mut value_start = None;
+    let mut separators = Vec::new();
+    for (index, c) in line.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ';' if !in_quotes => separators.push(index),
+            ':' if !in_quotes => {
+                value_start = Some(index);
+                break;
+            }
+            _ => {}
+        }
+    }
+    let value_start = value_start?;
+    let head = &line[..value_start];
+    let name_end = separators.first().copied().unwrap_or(value_start);
+    let name = head[..name_end].trim().to_ascii_uppercase();
+    if name.is_empty() {
+        return None;
+    }
+
+    let mut params = Vec::new();
+    for (i, start) in separators.iter().enumerate() {
+        let end = separators.get(i + 1).copied().unwrap_or(value_start);
+        let param = &line[start + 1..end];
+        if let Some((key, value)) = param.split_once('=') {
+            params.push((key.trim().to_ascii_uppercase(), value.trim().trim_matches('"').to_string()));
+        }
+    }
+
+    Some(ICalProperty {
+        name,
+        params,
+        value: line[value_start + 1..].to_string(),
+    })
+}
+
+/// Interpreta un valor DATE (`20240110`) o DATE-TIME (`20240110T090000Z`);
+/// devuelve también si era una fecha sin hora
+pub fn parse_date_time(value: &str, is_date: bool) -> Option<(DateTime<Utc>, bool)> {
+    let value = value.trim();
+    if is_date || value.len() == 8 {
+        return NaiveDate::parse_from_str(value, "%Y%m%d").ok()
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .map(|date| (date.and_utc(), true));
+    }
+    let local = value.trim_end_matches(['Z', 'z']);
+    NaiveDateTime::parse_from_str(local, "%Y%m%dT%H%M%S").ok()
+        .map(|date| (date.and_utc(), false))
+}
+
+/// Resuelve `\\`, `\;`, `\,` y `\n` de un valor de texto
+pub fn unescape_text(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => text.push('\n'),
+            Some(escaped) => text.push(escaped),
+            None => text.push('\\'),
+        }
+    }
+    text
+}
+
+/// Interpreta una duración iCalendar (RFC 5545, 3.3.6): `P1W`, `PT15M`, `-P1DT2H`
+pub fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let (negative, rest) = match value.as_bytes().first()? {
+        b'-' => (true, &value[1..]),
+        b'+' => (false, &value[1..]),
+        _ => (false, value),
+    };
+    let rest = rest.strip_prefix(['P', 'p'])?;
+
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    let mut in_time = false;
+    let mut any = false;
+    for c in rest.chars() {
+        match c.to_ascii_uppercase() {
+            'T' => in_time = true,
+            d if d.is_ascii_digit() => number.push(d),
+            unit => {
+                let amount: i64 = number.parse().ok()?;
+                number.clear();
+                any = true;
+                total += match (unit, in_time) {
+                    ('W', false) => Duration::weeks(amount),
+                    ('D', false) => Duration::days(amount),
+                    ('H', true) => Duration::hours(amount),
+                    ('M', true) => Duration::minutes(amount),
+                    ('S', true) => Duration::seconds(amount),
+                    _ => return None,
+                };
+            }
+        }
+    }
+    if !any || !number.is_empty() {
+        return None;
+    }
+    Some(if negative { -total } else { total })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const EVENT: &str = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:1@example.com\r\nSUMMARY:Team meeting\\, weekly\r\nATTENDEE;CN=\"Doe; John\";PARTSTAT=ACCEPTED:mailto:john@example.com\r\nDESCRIPTION:First line\r\n  continued\r\nDTSTART;TZID=Europe/Madrid:20240110T090000\r\nBEGIN:VALARM\r\nTRIGGER:-PT15M\r\nEND:VALARM\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+
+    #[test]
+    fn parses_components_properties_and_parameters() {
+        let calendar = ICalComponent::parse(EVENT).unwrap();
+        assert_eq!(calendar.name, "VCALENDAR");
+        let event = calendar.components_named("VEVENT").next().unwrap();
+        assert_eq!(event.property("summary").unwrap().text(), "Team meeting, weekly");
+        assert_eq!(event.property("DESCRIPTION").unwrap().value, "First line continued");
+
+        let attendee = event.property("ATTENDEE").unwrap();
+        assert_eq!(attendee.param("cn"), Some("Doe; John"));
+        assert_eq!(attendee.value, "mailto:john@example.com");
+
+        let (start, is_date) = event.property("DTSTART").unwrap().date_time().unwrap();
+        assert_eq!(start, Utc.with_ymd_and_hms(2024, 1, 10, 9, 0, 0).unwrap());
+        assert!(!is_date);
+        assert_eq!(event.components_named("VALARM").count(), 1);
+    }
+
+    #[test]
+    fn serializes_with_folding_and_quoted_parameters() {
+        let calendar = ICalComponent::parse(EVENT).unwrap();
+        let serialized = calendar.to_ical();
+        assert!(serialized.contains("ATTENDEE;CN=\"Doe; John\";PARTSTAT=ACCEPTED:mailto:john@example.com\r\n"));
+        assert!(serialized.lines().all(|line| line.len() <= 76));
+        assert_eq!(ICalComponent::parse(&serialized), Some(calendar));
+    }
+
+    #[test]
+    fn closes_unterminated_components() {
+        let calendar = ICalComponent::parse("BEGIN:VCALENDAR\nBEGIN:VEVENT\nUID:x\n").unwrap();
+        assert_eq!(calendar.components[0].property("UID").unwrap().value, "x");
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("PT15M"), Some(Duration::minutes(15)));
+        assert_eq!(parse_duration("-P1DT2H"), Some(-(Duration::days(1) + Duration::hours(2))));
+        assert_eq!(parse_duration("P2W"), Some(Duration::weeks(2)));
+        assert_eq!(parse_duration("P"), None);
+        assert_eq!(parse_duration("PT5"), None);
+    }
+}
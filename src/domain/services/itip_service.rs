@@ -0,0 +1,209 @@
+use chrono::Utc;
+
+use crate::domain::entities::event_attendee::{calendar_user_email, EventAttendee, EventOrganizer, ParticipationStatus};
+use crate::domain::entities::scheduling_message::ITipMethod;
+use crate::domain::services::ical_bundle_service::OXICLOUD_PRODID;
+use crate::domain::services::icalendar_service::{ICalComponent, ICalProperty};
+
+/// Mensaje iTIP (RFC 5546) con los datos necesarios para entregarlo
+#[derive(Debug, Clone, PartialEq)]
+pub struct ITipMessage {
+    pub method: ITipMethod,
+    pub uid: String,
+    /// SEQUENCE del evento principal; 0 si no la tiene
+    pub sequence: i64,
+    pub organizer: Option<EventOrganizer>,
+    /// Asistentes de todos los VEVENT del mensaje, sin repetir
+    pub attendees: Vec<EventAttendee>,
+    /// VCALENDAR tal y como se recibió
+    pub ical_data: String,
+}
+
+impl ITipMessage {
+    /// Interpreta un mensaje iTIP: un VCALENDAR con METHOD y al menos un
+    /// VEVENT con UID. Devuelve `None` si falta algo de eso o el método no
+    /// está soportado
+    pub fn parse(data: &str) -> Option<Self> {
+        let calendar = ICalComponent::parse(data)?;
+        if calendar.name != "VCALENDAR" {
+            return None;
+        }
+        let method = ITipMethod::parse(&calendar.property("METHOD")?.value)?;
+
+        let events: Vec<&ICalComponent> = calendar.components_named("VEVENT").collect();
+        // El evento principal, o la primera excepción si solo se envían excepciones
+        let master = events.iter()
+            .find(|event| event.property("RECURRENCE-ID").is_none())
+            .or_else(|| events.first())?;
+        let uid = master.property("UID").map(|uid| uid.value.trim().to_string()).filter(|uid| !uid.is_empty())?;
+
+        let mut attendees: Vec<EventAttendee> = Vec::new();
+        for attendee in events.iter().flat_map(|event| EventAttendee::from_event(event)) {
+            if !attendees.iter().any(|known| known.email == attendee.email) {
+                attendees.push(attendee);
+            }
+        }
+
+        Some(Self {
+            method,
+            uid,
+            sequence: master.property("SEQUENCE").and_then(|sequence| sequence.value.trim().parse().ok()).unwrap_or(0),
+            organizer: EventOrganizer::from_event(master),
+            attendees,
+            ical_data: data.to_string(),
+        })
+    }
+}
+
+/// Genera un REQUEST o un CANCEL del organizador a partir del objeto
+/// guardado de un evento.
+///
+/// Se quitan los avisos, que son de cada usuario, y en CANCEL se marcan los
+/// VEVENT como cancelados. Para REPLY hay que usar `build_reply`.
+pub fn build_message(object: &str, method: ITipMethod) -> Option<String> {
+    let source = as_calendar(ICalComponent::parse(object)?);
+    let mut message = message_calendar(method);
+
+    for component in source.components {
+        match component.name.as_str() {
+            "VTIMEZONE" => message.components.push(component),
+            "VEVENT" => {
+                let mut event = component;
+                event.components.retain(|sub| sub.name != "VALARM");
+                set_property(&mut event, "DTSTAMP", &Utc::now().format("%Y%m%dT%H%M%SZ").to_string());
+                if method == ITipMethod::Cancel {
+                    set_property(&mut event, "STATUS", "CANCELLED");
+                }
+                message.components.push(event);
+            }
+            _ => {}
+        }
+    }
+
+    message.components.iter().any(|component| component.name == "VEVENT").then(|| message.to_ical())
+}
+
+/// Genera el REPLY de un asistente con su nuevo estado de participación.
+///
+/// Cada VEVENT conserva solo lo que identifica la instancia y el evento
+/// (UID, RECURRENCE-ID, fechas, SEQUENCE, ORGANIZER) y el ATTENDEE de quien
+/// contesta (RFC 5546, 3.2.3). `None` si no es asistente del evento.
+pub fn build_reply(object: &str, attendee_email: &str, partstat: ParticipationStatus) -> Option<String> {
+    let attendee_email = attendee_email.to_lowercase();
+    let source = as_calendar(ICalComponent::parse(object)?);
+    let mut message = message_calendar(ITipMethod::Reply);
+
+    for component in source.components {
+        match component.name.as_str() {
+            "VTIMEZONE" => message.components.push(component),
+            "VEVENT" => {
+                let Some(attendee) = component.properties_named("ATTENDEE")
+                    .find(|property| calendar_user_email(&property.value).as_deref() == Some(attendee_email.as_str()))
+                else {
+                    continue;
+                };
+                let mut attendee = attendee.clone();
+                attendee.params.retain(|(name, _)| !matches!(name.as_str(), "PARTSTAT" | "RSVP"));
+                attendee.params.push(("PARTSTAT".to_string(), partstat.as_str().to_string()));
+
+                let mut reply = ICalComponent::new("VEVENT");
+                reply.properties = component.properties.iter()
+                    .filter(|property| matches!(
+                        property.name.as_str(),
+                        "UID" | "RECURRENCE-ID" | "DTSTART" | "DTEND" | "DURATION" | "SEQUENCE" | "ORGANIZER" | "SUMMARY"
+                    ))
+                    .cloned()
+                    .collect();
+                reply.properties.push(attendee);
+                set_property(&mut reply, "DTSTAMP", &Utc::now().format("%Y%m%dT%H%M%SZ").to_string());
+                message.components.push(reply);
+            }
+            _ => {}
+        }
+    }
+
+    message.components.iter().any(|component| component.name == "VEVENT").then(|| message.to_ical())
+}
+
+/// Quita la propiedad METHOD de un mensaje iTIP para guardarlo como objeto
+/// de calendario (RFC 4791, 4.1). Si no se puede interpretar se devuelve tal cual
+pub fn strip_method(data: &str) -> String {
+    match ICalComponent::parse(data) {
+        Some(mut calendar) if calendar.name == "VCALENDAR" => {
+            calendar.properties.retain(|property| property.name != "METHOD");
+            calendar.to_ical()
+        }
+        _ => data.to_string(),
+    }
+}
+
+/// Envuelve un VEVENT suelto en un VCALENDAR
+fn as_calendar(component: ICalComponent) -> ICalComponent {
+    if component.name == "VCALENDAR" {
+        return component;
+    }
+    let mut calendar = ICalComponent::new("VCALENDAR");
+    calendar.components.push(component);
+    calendar
+}
+
+/// VCALENDAR vacío con la cabecera de un mensaje iTIP
+fn message_calendar(method: ITipMethod) -> ICalComponent {
+    let mut calendar = ICalComponent::new("VCALENDAR");
+    for (name, value) in [("VERSION", "2.0"), ("PRODID", OXICLOUD_PRODID), ("METHOD", method.as_str())] {
+        calendar.properties.push(ICalProperty { name: name.to_string(), params: Vec::new(), value: value.to_string() });
+    }
+    calendar
+}
+
+/// Sustituye el valor de una propiedad o la añade si no existe
+fn set_property(component: &mut ICalComponent, name: &str, value: &str) {
+    component.properties.retain(|property| property.name != name);
+    component.properties.push(ICalProperty { name: name.to_string(), params: Vec::new(), value: value.to_string() });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EVENT: &str = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Example//EN\r\nBEGIN:VEVENT\r\nUID:planning@example.com\r\nSEQUENCE:2\r\nSUMMARY:Planning\r\nDTSTART:20250110T090000Z\r\nDTEND:20250110T100000Z\r\nDESCRIPTION:Agenda\r\nORGANIZER;CN=Ana:mailto:ana@example.com\r\nATTENDEE;PARTSTAT=ACCEPTED:mailto:ana@example.com\r\nATTENDEE;RSVP=TRUE:mailto:john@example.com\r\nBEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER:-PT10M\r\nEND:VALARM\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+
+    #[test]
+    fn test_request_round_trip() {
+        let request = build_message(EVENT, ITipMethod::Request).unwrap();
+        assert!(request.contains("METHOD:REQUEST\r\n"));
+        assert!(!request.contains("VALARM"));
+
+        let message = ITipMessage::parse(&request).unwrap();
+        assert_eq!(message.method, ITipMethod::Request);
+        assert_eq!(message.uid, "planning@example.com");
+        assert_eq!(message.sequence, 2);
+        assert_eq!(message.organizer.unwrap().email, "ana@example.com");
+        assert_eq!(message.attendees.len(), 2);
+
+        let stored = strip_method(&request);
+        assert!(!stored.contains("METHOD"));
+        assert!(ITipMessage::parse(&stored).is_none());
+    }
+
+    #[test]
+    fn test_cancel_marks_events_cancelled() {
+        let cancel = build_message(EVENT, ITipMethod::Cancel).unwrap();
+        assert!(cancel.contains("METHOD:CANCEL\r\n"));
+        assert!(cancel.contains("STATUS:CANCELLED\r\n"));
+    }
+
+    #[test]
+    fn test_reply_only_carries_the_replying_attendee() {
+        let reply = build_reply(EVENT, "John@Example.com", ParticipationStatus::Tentative).unwrap();
+        let message = ITipMessage::parse(&reply).unwrap();
+        assert_eq!(message.method, ITipMethod::Reply);
+        assert_eq!(message.attendees.len(), 1);
+        assert_eq!(message.attendees[0].email, "john@example.com");
+        assert_eq!(message.attendees[0].partstat, ParticipationStatus::Tentative);
+        assert!(!message.attendees[0].rsvp);
+        assert!(!reply.contains("DESCRIPTION"));
+
+        assert!(build_reply(EVENT, "eve@example.com", ParticipationStatus::Accepted).is_none());
+    }
+}
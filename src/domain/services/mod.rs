@@ -1,3 +1,25 @@
 pub mod i18n_service;
+pub mod naming_service;
 pub mod path_service;
-pub mod auth_service;
\ No newline at end of file
+pub mod auth_service;
+pub mod extended_attribute_service;
+pub mod hidden_file_service;
+pub mod vcard_photo_service;
+pub mod byte_range_service;
+pub mod icalendar_service;
+pub mod calendar_filter_service;
+pub mod dav_validation_service;
+pub mod recurrence_service;
+pub mod sync_token_service;
+pub mod timezone_service;
+pub mod ical_bundle_service;
+pub mod itip_service;
+pub mod vcard_filter_service;
+pub mod vcard_service;
+pub mod contact_import_service;
+pub mod content_digest_service;
+pub mod ownership_service;
+pub mod path_codec_service;
+pub mod template_placeholder_service;
+pub mod transliteration_service;
+pub mod email_template_service;
@@ -0,0 +1,223 @@
+use chrono::NaiveDate;
+
+use crate::domain::services::i18n_service::Locale;
+
+/// Número máximo de candidatos probados antes de rendirse
+pub const MAX_NAME_ATTEMPTS: u32 = 10_000;
+
+/// Extensiones compuestas que se conservan enteras al añadir sufijos
+const COMPOUND_EXTENSIONS: &[&str] = &[".tar.gz", ".tar.bz2", ".tar.xz", ".tar.zst"];
+
+/// Patrón usado para derivar un nombre libre a partir del deseado
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NamingPattern {
+    /// `informe (2).pdf`, `informe (3).pdf`, ... (subidas, importaciones, extracciones)
+    Numbered,
+    /// `informe (copia).pdf`, `informe (copia 2).pdf`, ... (copias en la misma carpeta)
+    Copy,
+    /// `informe (copia en conflicto 2025-04-21).pdf` (ediciones concurrentes)
+    ConflictCopy(NaiveDate),
+}
+
+impl NamingPattern {
+    /// Interpreta el nombre de patrón recibido de un cliente
+    pub fn parse(value: &str, today: NaiveDate) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "numbered" => Some(Self::Numbered),
+            "copy" => Some(Self::Copy),
+            "conflict" => Some(Self::ConflictCopy(today)),
+            _ => None,
+        }
+    }
+}
+
+fn copy_label(locale: Locale) -> &'static str {
+    match locale {
+        Locale::English => "copy",
+        Locale::Spanish => "copia",
+    }
+}
+
+fn conflict_label(locale: Locale) -> &'static str {
+    match locale {
+        Locale::English => "conflicted copy",
+        Locale::Spanish => "copia en conflicto",
+    }
+}
+
+/// Separa un nombre en base y extensión (con el punto).
+///
+/// Los ficheros ocultos sin más puntos (`.bashrc`) no tienen extensión.
+pub fn split_extension(name: &str) -> (&str, &str) {
+    let lower = name.to_lowercase();
+    for ext in COMPOUND_EXTENSIONS {
+        if lower.len() > ext.len() && lower.ends_with(ext) {
+            let at = name.len() - ext.len();
+            return (&name[..at], &name[at..]);
+        }
+    }
+    match name.rfind('.') {
+        Some(0) | None => (name, ""),
+        Some(at) => (&name[..at], &name[at..]),
+    }
+}
+
+/// Quita un sufijo generado previamente por el mismo patrón, para que copiar
+/// `a (copia).txt` produzca `a (copia 2).txt` y no `a (copia) (copia).txt`
+fn strip_generated_suffix<'a>(stem: &'a str, pattern: NamingPattern, locale: Locale) -> &'a str {
+    let Some(inner_start) = stem.rfind(" (") else { return stem };
+    let Some(inner) = stem[inner_start + 2..].strip_suffix(')') else { return stem };
+
+    let generated = match pattern {
+        NamingPattern::Numbered => inner.parse::<u32>().is_ok_and(|n| n >= 2),
+        NamingPattern::Copy => {
+            let label = copy_label(locale);
+            inner == label
+                || inner
+                    .strip_prefix(label)
+                    .and_then(|rest| rest.strip_prefix(' '))
+                    .is_some_and(|n| n.parse::<u32>().is_ok())
+        }
+        NamingPattern::ConflictCopy(_) => inner.starts_with(conflict_label(locale)),
+    };
+
+    if generated {
+        &stem[..inner_start]
+    } else {
+        stem
+    }
+}
+
+/// Candidato número `attempt` (empezando en 0) para el nombre deseado.
+///
+/// Con el patrón `Numbered` el intento 0 es el propio nombre; el resto de
+/// patrones siempre añaden un sufijo.
+pub fn candidate_name(desired: &str, pattern: NamingPattern, attempt: u32, locale: Locale) -> String {
+    let (stem, extension) = split_extension(desired);
+
+    match pattern {
+        NamingPattern::Numbered => {
+            if attempt == 0 {
+                return desired.to_string();
+            }
+            let base = strip_generated_suffix(stem, pattern, locale);
+            format!("{} ({}){}", base, attempt + 1, extension)
+        }
+        NamingPattern::Copy => {
+            let base = strip_generated_suffix(stem, pattern, locale);
+            let label = copy_label(locale);
+            match attempt {
+                0 => format!("{} ({}){}", base, label, extension),
+                n => format!("{} ({} {}){}", base, label, n + 1, extension),
+            }
+        }
+        NamingPattern::ConflictCopy(date) => {
+            let base = strip_generated_suffix(stem, pattern, locale);
+            let label = format!("{} {}", conflict_label(locale), date.format("%Y-%m-%d"));
+            match attempt {
+                0 => format!("{} ({}){}", base, label, extension),
+                n => format!("{} ({} {}){}", base, label, n + 1, extension),
+            }
+        }
+    }
+}
+
+/// Devuelve el primer candidato que no está ocupado.
+///
+/// `is_taken` recibe cada candidato; el orden de prueba es determinista, por
+/// lo que el mismo estado de la carpeta siempre produce el mismo nombre.
+pub fn find_free_name(
+    desired: &str,
+    pattern: NamingPattern,
+    locale: Locale,
+    mut is_taken: impl FnMut(&str) -> bool,
+) -> Option<String> {
+    (0..MAX_NAME_ATTEMPTS)
+        .map(|attempt| candidate_name(desired, pattern, attempt, locale))
+        .find(|candidate| !is_taken(candidate))
+}
+
+/// Nombre del original del que procede una copia en conflicto, o `None` si
+/// el nombre no sigue el patrón en ninguno de los idiomas.
+///
+/// `notas (conflicted copy 2025-04-21 2).md` → `notas.md`
+pub fn conflict_copy_original(name: &str) -> Option<String> {
+    let (stem, extension) = split_extension(name);
+    let inner_start = stem.rfind(" (")?;
+    let inner = stem[inner_start + 2..].strip_suffix(')')?;
+
+    let is_conflict = [Locale::English, Locale::Spanish].into_iter().any(|locale| {
+        let Some(rest) = inner.strip_prefix(conflict_label(locale)).and_then(|r| r.strip_prefix(' ')) else {
+            return false;
+        };
+        let (date, counter) = rest.split_once(' ').unwrap_or((rest, ""));
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok() && (counter.is_empty() || counter.parse::<u32>().is_ok())
+    });
+
+    (is_conflict && inner_start > 0).then(|| format!("{}{}", &stem[..inner_start], extension))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_extension() {
+        assert_eq!(split_extension("report.pdf"), ("report", ".pdf"));
+        assert_eq!(split_extension("backup.TAR.GZ"), ("backup", ".TAR.GZ"));
+        assert_eq!(split_extension(".bashrc"), (".bashrc", ""));
+        assert_eq!(split_extension("Makefile"), ("Makefile", ""));
+    }
+
+    #[test]
+    fn test_numbered_candidates() {
+        let taken = ["report.pdf", "report (2).pdf"];
+        let name = find_free_name("report.pdf", NamingPattern::Numbered, Locale::English, |c| taken.contains(&c));
+        assert_eq!(name.as_deref(), Some("report (3).pdf"));
+
+        // A free name is kept as is
+        let name = find_free_name("new.txt", NamingPattern::Numbered, Locale::English, |_| false);
+        assert_eq!(name.as_deref(), Some("new.txt"));
+    }
+
+    #[test]
+    fn test_copy_candidates_are_locale_aware() {
+        assert_eq!(candidate_name("a.txt", NamingPattern::Copy, 0, Locale::English), "a (copy).txt");
+        assert_eq!(candidate_name("a.txt", NamingPattern::Copy, 1, Locale::Spanish), "a (copia 2).txt");
+
+        // Copying a copy does not stack suffixes
+        assert_eq!(candidate_name("a (copy).txt", NamingPattern::Copy, 1, Locale::English), "a (copy 2).txt");
+        assert_eq!(candidate_name("a (copy 3).txt", NamingPattern::Copy, 0, Locale::English), "a (copy).txt");
+    }
+
+    #[test]
+    fn test_conflict_candidates() {
+        let date = NaiveDate::from_ymd_opt(2025, 4, 21).unwrap();
+        assert_eq!(
+            candidate_name("notes.md", NamingPattern::ConflictCopy(date), 0, Locale::English),
+            "notes (conflicted copy 2025-04-21).md"
+        );
+        assert_eq!(
+            candidate_name("notes.md", NamingPattern::ConflictCopy(date), 1, Locale::Spanish),
+            "notes (copia en conflicto 2025-04-21 2).md"
+        );
+    }
+
+    #[test]
+    fn test_conflict_copy_original() {
+        assert_eq!(conflict_copy_original("notes (conflicted copy 2025-04-21).md").as_deref(), Some("notes.md"));
+        assert_eq!(conflict_copy_original("notes (copia en conflicto 2025-04-21 2).md").as_deref(), Some("notes.md"));
+        assert_eq!(conflict_copy_original("backup (conflicted copy 2025-04-21).tar.gz").as_deref(), Some("backup.tar.gz"));
+        assert_eq!(conflict_copy_original("notes (copy).md"), None);
+        assert_eq!(conflict_copy_original("notes (conflicted copy soon).md"), None);
+        assert_eq!(conflict_copy_original("notes.md"), None);
+    }
+
+    #[test]
+    fn test_parse_pattern() {
+        let today = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        assert_eq!(NamingPattern::parse("Copy", today), Some(NamingPattern::Copy));
+        assert_eq!(NamingPattern::parse("conflict", today), Some(NamingPattern::ConflictCopy(today)));
+        assert_eq!(NamingPattern::parse("other", today), None);
+    }
+}
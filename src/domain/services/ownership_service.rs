@@ -0,0 +1,90 @@
+/// Prefijo de la carpeta personal de cada usuario (`Mi Carpeta - {usuario}`)
+pub const HOME_FOLDER_PREFIX: &str = "Mi Carpeta - ";
+
+/// Usuario propietario de una ruta: el de la carpeta personal que la contiene.
+/// `None` para las rutas que no están dentro de ninguna carpeta personal.
+pub fn owner_of_path(path: &str) -> Option<&str> {
+    path.split('/')
+        .find_map(|segment| segment.strip_prefix(HOME_FOLDER_PREFIX))
+        .map(str::trim)
+        .filter(|username| !username.is_empty())
+}
+
+/// Operación que lleva un elemento de una carpeta a otra
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferOperation {
+    Move,
+    Copy,
+}
+
+/// Qué ocurre al mover un elemento a la carpeta personal de otro usuario
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CrossUserMovePolicy {
+    /// El elemento pasa a ser del propietario del destino, que paga su espacio;
+    /// los enlaces compartidos y favoritos del propietario anterior se retiran
+    #[default]
+    Transfer,
+    /// El destino recibe una copia que pertenece a su propietario y el
+    /// original se queda donde estaba, con sus enlaces y favoritos
+    Copy,
+    /// No se permite mover elementos entre usuarios
+    Deny,
+}
+
+impl CrossUserMovePolicy {
+    /// Interpreta el nombre de una política (`transfer`, `copy` o `deny`)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "transfer" => Some(Self::Transfer),
+            "copy" => Some(Self::Copy),
+            "deny" | "reject" => Some(Self::Deny),
+            _ => None,
+        }
+    }
+
+    /// Operación que se hace realmente para un movimiento o copia entre
+    /// `source_owner` y `destination_owner`; `None` si no está permitida.
+    ///
+    /// Solo cuenta como cambio de usuario cuando se conocen los dos
+    /// propietarios y son distintos. Las copias siempre están permitidas
+    /// porque el original no cambia de manos.
+    pub fn resolve(self, operation: TransferOperation, source_owner: Option<&str>, destination_owner: Option<&str>) -> Option<TransferOperation> {
+        let cross_user = matches!((source_owner, destination_owner), (Some(from), Some(to)) if from != to);
+        match (operation, cross_user, self) {
+            (TransferOperation::Move, true, Self::Deny) => None,
+            (TransferOperation::Move, true, Self::Copy) => Some(TransferOperation::Copy),
+            _ => Some(operation),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_owner_of_path() {
+        assert_eq!(owner_of_path("Mi Carpeta - alice"), Some("alice"));
+        assert_eq!(owner_of_path("Mi Carpeta - alice/Fotos/2024"), Some("alice"));
+        assert_eq!(owner_of_path("/Mi Carpeta - bob/doc.txt"), Some("bob"));
+        assert_eq!(owner_of_path("Compartido/doc.txt"), None);
+        assert_eq!(owner_of_path("Mi Carpeta - "), None);
+    }
+
+    #[test]
+    fn test_resolve_policy() {
+        use TransferOperation::*;
+        let (alice, bob) = (Some("alice"), Some("bob"));
+
+        assert_eq!(CrossUserMovePolicy::Transfer.resolve(Move, alice, bob), Some(Move));
+        assert_eq!(CrossUserMovePolicy::Copy.resolve(Move, alice, bob), Some(Copy));
+        assert_eq!(CrossUserMovePolicy::Deny.resolve(Move, alice, bob), None);
+        assert_eq!(CrossUserMovePolicy::Deny.resolve(Copy, alice, bob), Some(Copy));
+        // Dentro de la misma carpeta personal, o sin propietario conocido, no cambia nada
+        assert_eq!(CrossUserMovePolicy::Deny.resolve(Move, alice, alice), Some(Move));
+        assert_eq!(CrossUserMovePolicy::Copy.resolve(Move, alice, None), Some(Move));
+
+        assert_eq!(CrossUserMovePolicy::parse(" Copy "), Some(CrossUserMovePolicy::Copy));
+        assert_eq!(CrossUserMovePolicy::parse("steal"), None);
+    }
+}
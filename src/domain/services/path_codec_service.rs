@@ -0,0 +1,232 @@
+//! Codificación de rutas en URLs (hrefs de WebDAV, CalDAV y CardDAV, cabecera
+//! Destination) y en cabeceras de descarga.
+//!
+//! Los nombres se guardan decodificados; solo se codifican al formar una URL
+//! y se decodifican al leerla, siempre aquí, para que `%`, `#`, `+`, los
+//! espacios finales o los emoji sobrevivan al viaje de ida y vuelta.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::common::errors::DomainError;
+use crate::domain::services::transliteration_service::transliterate;
+
+/// Si un byte puede ir sin codificar en un segmento: los caracteres no
+/// reservados de RFC 3986. El resto se codifica aunque no haga falta, porque
+/// algunos clientes tratan `+` como espacio o cortan en `;`.
+fn is_unreserved(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~')
+}
+
+/// Codifica un nombre para usarlo como segmento de una URL; `/` también se
+/// codifica, ya que dentro de un nombre no separa segmentos
+pub fn encode_segment(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for &byte in segment.as_bytes() {
+        if is_unreserved(byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// Codifica una ruta segmento a segmento, conservando las `/` que los separan
+pub fn encode_path(path: &str) -> String {
+    path.split('/').map(encode_segment).collect::<Vec<_>>().join("/")
+}
+
+/// Decodifica un segmento de URL. Se rechazan las secuencias `%` mal formadas,
+/// el UTF-8 inválido y los segmentos que, una vez decodificados, contienen `/`
+/// o NUL, que no pueden formar parte de un nombre.
+pub fn decode_segment(segment: &str) -> Result<String, DomainError> {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let byte = bytes.get(i + 1..i + 3)
+                .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| DomainError::validation_error(format!("Invalid percent-encoding in '{}'", segment)))?;
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    let decoded = String::from_utf8(decoded)
+        .map_err(|_| DomainError::validation_error(format!("'{}' is not valid UTF-8 once decoded", segment)))?;
+    if decoded.contains(['/', '\0']) {
+        return Err(DomainError::validation_error(format!("'{}' decodes to a name with '/' or NUL", segment)));
+    }
+    Ok(decoded)
+}
+
+/// Decodifica una ruta de URL segmento a segmento
+pub fn decode_path(path: &str) -> Result<String, DomainError> {
+    Ok(path.split('/').map(decode_segment).collect::<Result<Vec<_>, _>>()?.join("/"))
+}
+
+/// Cómo se forma la versión ASCII del nombre que va en `filename`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilenameFallback {
+    /// Se escribe con letras latinas: `Ñandú.pdf` pasa a `Nandu.pdf`
+    Transliterate,
+    /// Cada carácter que no es ASCII se cambia por `_`
+    Replace,
+}
+
+impl FilenameFallback {
+    /// Interpreta el nombre de un modo (`transliterate` o `replace`)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "transliterate" | "ascii" => Some(Self::Transliterate),
+            "replace" | "underscore" => Some(Self::Replace),
+            _ => None,
+        }
+    }
+}
+
+/// Modo de la instancia; se fija una vez al arrancar
+static FILENAME_FALLBACK: AtomicU8 = AtomicU8::new(0);
+
+/// Fija el modo con el que `content_disposition` forma `filename`
+pub fn set_filename_fallback(fallback: FilenameFallback) {
+    let value = match fallback {
+        FilenameFallback::Transliterate => 0,
+        FilenameFallback::Replace => 1,
+    };
+    FILENAME_FALLBACK.store(value, Ordering::Relaxed);
+}
+
+fn filename_fallback() -> FilenameFallback {
+    match FILENAME_FALLBACK.load(Ordering::Relaxed) {
+        1 => FilenameFallback::Replace,
+        _ => FilenameFallback::Transliterate,
+    }
+}
+
+/// Valor de Content-Disposition para descargar `filename` (RFC 6266), con el
+/// modo de la instancia para la versión ASCII.
+///
+/// `filename` lleva una versión ASCII del nombre y, si no es idéntica,
+/// `filename*` lleva el nombre completo codificado (RFC 8187).
+pub fn content_disposition(disposition: &str, filename: &str) -> String {
+    content_disposition_with(disposition, filename, filename_fallback())
+}
+
+/// Como `content_disposition`, con un modo concreto para la versión ASCII
+pub fn content_disposition_with(disposition: &str, filename: &str, fallback: FilenameFallback) -> String {
+    let ascii = match fallback {
+        FilenameFallback::Transliterate => transliterate(filename),
+        FilenameFallback::Replace => filename.to_string(),
+    };
+    // Las comillas y la barra invertida romperían la cadena entrecomillada
+    let ascii: String = ascii.chars()
+        .map(|c| if (c.is_ascii_graphic() && c != '"' && c != '\\') || c == ' ' { c } else { '_' })
+        .collect();
+    if ascii == filename {
+        format!("{}; filename=\"{}\"", disposition, filename)
+    } else {
+        format!("{}; filename=\"{}\"; filename*=UTF-8''{}", disposition, ascii, encode_segment(filename))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAMES: &[&str] = &[
+        "plain.txt",
+        "100% done.txt",
+        "issue #42",
+        "a+b=c.txt",
+        "trailing space ",
+        "  leading",
+        "emoji 🎉📁",
+        "ñandú é ü.pdf",
+        "semi;colon,comma&amp?query",
+        "quote\"back\\slash'",
+        "%2F-looking.txt",
+        "~tilde_under-dash.",
+        "tab\tnewline\n",
+        "",
+    ];
+
+    #[test]
+    fn test_segments_round_trip() {
+        for name in NAMES {
+            let encoded = encode_segment(name);
+            assert!(encoded.bytes().all(|b| is_unreserved(b) || b == b'%'), "{:?} -> {}", name, encoded);
+            assert_eq!(decode_segment(&encoded).unwrap(), *name);
+        }
+        assert_eq!(encode_segment("100% done #1+2"), "100%25%20done%20%231%2B2");
+        assert_eq!(encode_segment("🎉"), "%F0%9F%8E%89");
+    }
+
+    #[test]
+    fn test_paths_round_trip() {
+        let path = NAMES.iter().filter(|name| !name.is_empty()).copied().collect::<Vec<_>>().join("/");
+        let encoded = encode_path(&path);
+        assert_eq!(encoded.matches('/').count(), path.matches('/').count());
+        assert_eq!(decode_path(&encoded).unwrap(), path);
+        assert_eq!(encode_path("Mi Carpeta - bob/docs/"), "Mi%20Carpeta%20-%20bob/docs/");
+        assert_eq!(decode_path("Mi%20Carpeta%20-%20bob/docs/").unwrap(), "Mi Carpeta - bob/docs/");
+    }
+
+    #[test]
+    fn test_decode_accepts_unencoded_input() {
+        // Clients send spaces, '+' and non-ASCII characters without encoding
+        assert_eq!(decode_segment("a+b c").unwrap(), "a+b c");
+        assert_eq!(decode_segment("ñandú").unwrap(), "ñandú");
+        assert_eq!(decode_segment("%c3%b1").unwrap(), "ñ");
+    }
+
+    #[test]
+    fn test_decode_rejects_malformed_input() {
+        assert!(decode_segment("100%").is_err());
+        assert!(decode_segment("%4").is_err());
+        assert!(decode_segment("%zz").is_err());
+        assert!(decode_segment("%+1").is_err());
+        assert!(decode_segment("%FF%FE").is_err());
+        assert!(decode_segment("a%2Fb").is_err());
+        assert!(decode_segment("a%00b").is_err());
+        assert!(decode_path("docs/%2E%2E%2Fetc").is_err());
+    }
+
+    #[test]
+    fn test_content_disposition() {
+        assert_eq!(content_disposition("attachment", "report.pdf"), "attachment; filename=\"report.pdf\"");
+        assert_eq!(content_disposition("inline", "a b.png"), "inline; filename=\"a b.png\"");
+        assert_eq!(
+            content_disposition("attachment", "fiesta \"🎉\".txt"),
+            "attachment; filename=\"fiesta ___.txt\"; filename*=UTF-8''fiesta%20%22%F0%9F%8E%89%22.txt"
+        );
+    }
+
+    #[test]
+    fn test_content_disposition_fallbacks() {
+        assert_eq!(
+            content_disposition_with("attachment", "Ñandú.pdf", FilenameFallback::Transliterate),
+            "attachment; filename=\"Nandu.pdf\"; filename*=UTF-8''%C3%91and%C3%BA.pdf"
+        );
+        assert_eq!(
+            content_disposition_with("attachment", "Ñandú.pdf", FilenameFallback::Replace),
+            "attachment; filename=\"_and_.pdf\"; filename*=UTF-8''%C3%91and%C3%BA.pdf"
+        );
+        assert_eq!(
+            content_disposition_with("attachment", "Отчёт «итог».ics", FilenameFallback::Transliterate),
+            "attachment; filename=\"Otchet _itog_.ics\"; filename*=UTF-8''%D0%9E%D1%82%D1%87%D1%91%D1%82%20%C2%AB%D0%B8%D1%82%D0%BE%D0%B3%C2%BB.ics"
+        );
+        for name in NAMES {
+            let value = content_disposition_with("attachment", name, FilenameFallback::Transliterate);
+            assert!(value.is_ascii() && !value.contains(['\t', '\n']), "{:?} -> {}", name, value);
+        }
+        assert_eq!(FilenameFallback::parse(" Replace "), Some(FilenameFallback::Replace));
+        assert_eq!(FilenameFallback::parse("latin"), None);
+    }
+}
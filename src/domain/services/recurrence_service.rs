@@ -0,0 +1,531 @@
+use std::ops::ControlFlow;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc, Weekday};
+
+use crate::domain::services::icalendar_service::{parse_date_time, parse_duration, ICalComponent, ICalProperty};
+
+/// Periodos que se recorren como máximo al expandir una regla; evita bucles
+/// largos con reglas que casi nunca producen repeticiones
+const MAX_PERIODS: u32 = 100_000;
+
+/// Frecuencia de una regla de recurrencia
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Secondly,
+    Minutely,
+    Hourly,
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Frequency {
+    fn parse(value: &str) -> Option<Self> {
+        Some(match value.to_ascii_uppercase().as_str() {
+            "SECONDLY" => Frequency::Secondly,
+            "MINUTELY" => Frequency::Minutely,
+            "HOURLY" => Frequency::Hourly,
+            "DAILY" => Frequency::Daily,
+            "WEEKLY" => Frequency::Weekly,
+            "MONTHLY" => Frequency::Monthly,
+            "YEARLY" => Frequency::Yearly,
+            _ => return None,
+        })
+    }
+
+    /// Duración de un periodo, para las frecuencias de longitud fija
+    fn fixed_step(self) -> Option<Duration> {
+        match self {
+            Frequency::Secondly => Some(Duration::seconds(1)),
+            Frequency::Minutely => Some(Duration::minutes(1)),
+            Frequency::Hourly => Some(Duration::hours(1)),
+            Frequency::Daily => Some(Duration::days(1)),
+            Frequency::Weekly => Some(Duration::weeks(1)),
+            Frequency::Monthly | Frequency::Yearly => None,
+        }
+    }
+}
+
+/// Día de la semana de BYDAY con su ordinal opcional (`-1FR`, `2MO`, `TU`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeekdayNum {
+    pub ordinal: Option<i32>,
+    pub weekday: Weekday,
+}
+
+/// Regla de recurrencia (RFC 5545, 3.3.10).
+///
+/// Se expanden FREQ, INTERVAL, COUNT, UNTIL, BYDAY, BYMONTHDAY, BYMONTH,
+/// BYSETPOS y WKST. BYHOUR, BYMINUTE, BYSECOND, BYYEARDAY y BYWEEKNO se
+/// ignoran: las repeticiones conservan la hora de DTSTART.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub count: Option<u32>,
+    pub until: Option<DateTime<Utc>>,
+    pub by_day: Vec<WeekdayNum>,
+    pub by_month_day: Vec<i32>,
+    pub by_month: Vec<u32>,
+    pub by_set_pos: Vec<i32>,
+    pub week_start: Weekday,
+}
+
+impl RecurrenceRule {
+    /// Interpreta el valor de una propiedad RRULE
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut rule = RecurrenceRule {
+            frequency: Frequency::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_month: Vec::new(),
+            by_set_pos: Vec::new(),
+            week_start: Weekday::Mon,
+        };
+        let mut has_frequency = false;
+
+        for part in value.trim().split(';').filter(|part| !part.is_empty()) {
+            let (name, value) = part.split_once('=').ok_or_else(|| format!("{} is not NAME=VALUE", part))?;
+            let value = value.trim();
+            match name.trim().to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    rule.frequency = Frequency::parse(value).ok_or_else(|| format!("unknown FREQ {}", value))?;
+                    has_frequency = true;
+                }
+                "INTERVAL" => rule.interval = positive(value, "INTERVAL")?,
+                "COUNT" => rule.count = Some(positive(value, "COUNT")?),
+                "UNTIL" => {
+                    rule.until = Some(parse_date_time(value, false)
+                        .map(|(until, is_date)| if is_date { until + Duration::days(1) - Duration::seconds(1) } else { until })
+                        .ok_or_else(|| format!("UNTIL {} is not a date", value))?);
+                }
+                "BYDAY" => {
+                    rule.by_day = value.split(',')
+                        .map(|day| parse_weekday_num(day.trim()).ok_or_else(|| format!("invalid BYDAY {}", day)))
+                        .collect::<Result<_, _>>()?;
+                }
+                "BYMONTHDAY" => rule.by_month_day = numbers(value, "BYMONTHDAY", 31, true)?,
+                "BYMONTH" => {
+                    rule.by_month = numbers(value, "BYMONTH", 12, false)?.into_iter().map(|month| month as u32).collect();
+                }
+                "BYSETPOS" => rule.by_set_pos = numbers(value, "BYSETPOS", 366, true)?,
+                "WKST" => rule.week_start = parse_weekday(value).ok_or_else(|| format!("invalid WKST {}", value))?,
+                _ => {}
+            }
+        }
+
+        if !has_frequency {
+            return Err("FREQ is required".to_string());
+        }
+        if rule.count.is_some() && rule.until.is_some() {
+            return Err("COUNT and UNTIL cannot be combined".to_string());
+        }
+        Ok(rule)
+    }
+
+    /// Recorre las repeticiones de la regla a partir de `dtstart`, en orden.
+    ///
+    /// DTSTART cuenta siempre como la primera repetición. `from` permite
+    /// saltar los periodos anteriores cuando la regla no tiene COUNT, y el
+    /// recorrido termina cuando `visit` devuelve `Break`, al agotar la regla
+    /// o tras `MAX_PERIODS` periodos.
+    pub fn expand(&self, dtstart: DateTime<Utc>, from: Option<DateTime<Utc>>, mut visit: impl FnMut(DateTime<Utc>) -> ControlFlow<()>) {
+        let mut emitted = 1u32;
+        if visit(dtstart).is_break() || self.count.is_some_and(|count| count <= 1) {
+            return;
+        }
+
+        let first_period = match (from, self.count, self.frequency.fixed_step()) {
+            (Some(from), None, Some(step)) if from > dtstart => {
+                let step = step * self.interval as i32;
+                ((from - dtstart).num_seconds() / step.num_seconds().max(1)).saturating_sub(1).max(0) as u64
+            }
+            _ => 0,
+        };
+
+        for period in first_period..first_period + MAX_PERIODS as u64 {
+            let Some((period_start, candidates)) = self.period_candidates(dtstart, period * self.interval as u64) else {
+                return;
+            };
+            if self.until.is_some_and(|until| period_start > until) {
+                return;
+            }
+            for candidate in candidates {
+                if candidate <= dtstart {
+                    continue;
+                }
+                if self.until.is_some_and(|until| candidate > until) {
+                    return;
+                }
+                emitted += 1;
+                if visit(candidate).is_break() || self.count.is_some_and(|count| emitted >= count) {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Inicio del periodo `offset` (ya multiplicado por INTERVAL) y sus
+    /// repeticiones candidatas, ordenadas
+    fn period_candidates(&self, dtstart: DateTime<Utc>, offset: u64) -> Option<(DateTime<Utc>, Vec<DateTime<Utc>>)> {
+        let time = dtstart.time();
+        let date = dtstart.date_naive();
+        let offset_i = i64::try_from(offset).ok()?;
+
+        let (period_start, mut dates): (DateTime<Utc>, Vec<DateTime<Utc>>) = match self.frequency {
+            Frequency::Secondly | Frequency::Minutely | Frequency::Hourly | Frequency::Daily => {
+                let step = self.frequency.fixed_step()?;
+                let instant = dtstart.checked_add_signed(step * i32::try_from(offset_i).ok()?)?;
+                let day = instant.date_naive();
+                let keep = self.month_matches(day)
+                    && (self.by_month_day.is_empty() || self.by_month_day.iter().any(|&d| resolve_month_day(day.year(), day.month(), d) == Some(day.day())))
+                    && (self.by_day.is_empty() || self.by_day.iter().any(|d| d.weekday == day.weekday()));
+                (instant, if keep { vec![instant] } else { Vec::new() })
+            }
+            Frequency::Weekly => {
+                let back = (7 + date.weekday().num_days_from_monday() - self.week_start.num_days_from_monday()) % 7;
+                let week = date.checked_sub_signed(Duration::days(back as i64))?
+                    .checked_add_signed(Duration::weeks(offset_i))?;
+                let weekdays: Vec<Weekday> = if self.by_day.is_empty() {
+                    vec![date.weekday()]
+                } else {
+                    self.by_day.iter().map(|d| d.weekday).collect()
+                };
+                let days = weekdays.into_iter()
+                    .filter_map(|weekday| {
+                        let ahead = (7 + weekday.num_days_from_monday() - self.week_start.num_days_from_monday()) % 7;
+                        week.checked_add_signed(Duration::days(ahead as i64))
+                    })
+                    .filter(|day| self.month_matches(*day))
+                    .map(|day| day.and_time(time).and_utc())
+                    .collect();
+                (week.and_time(time).and_utc(), days)
+            }
+            Frequency::Monthly => {
+                let months = date.year() as i64 * 12 + date.month0() as i64 + offset_i;
+                let (year, month) = (i32::try_from(months.div_euclid(12)).ok()?, months.rem_euclid(12) as u32 + 1);
+                let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+                let days = if self.month_matches(first) { self.days_in_month(year, month, date.day()) } else { Vec::new() };
+                (first.and_time(time).and_utc(), days.into_iter().map(|day| day.and_time(time).and_utc()).collect())
+            }
+            Frequency::Yearly => {
+                let year = date.year().checked_add(i32::try_from(offset_i).ok()?)?;
+                let first = NaiveDate::from_ymd_opt(year, 1, 1)?;
+                let days: Vec<NaiveDate> = if self.by_month.is_empty() && self.by_month_day.is_empty() && !self.by_day.is_empty() {
+                    // BYDAY sin BYMONTH: los ordinales cuentan dentro del año
+                    let year_days: Vec<NaiveDate> = first.iter_days().take_while(|day| day.year() == year).collect();
+                    weekdays_in(&year_days, &self.by_day)
+                } else if self.by_month.is_empty() && self.by_month_day.is_empty() {
+                    NaiveDate::from_ymd_opt(year, date.month(), date.day()).into_iter().collect()
+                } else {
+                    let months: Vec<u32> = if self.by_month.is_empty() { (1..=12).collect() } else { self.by_month.clone() };
+                    months.into_iter().flat_map(|month| self.days_in_month(year, month, date.day())).collect()
+                };
+                (first.and_time(time).and_utc(), days.into_iter().map(|day| day.and_time(time).and_utc()).collect())
+            }
+        };
+
+        dates.sort();
+        dates.dedup();
+        if !self.by_set_pos.is_empty() {
+            let all = std::mem::take(&mut dates);
+            for &position in &self.by_set_pos {
+                let index = if position > 0 { position as i64 - 1 } else { all.len() as i64 + position as i64 };
+                if let Some(date) = usize::try_from(index).ok().and_then(|index| all.get(index)) {
+                    dates.push(*date);
+                }
+            }
+            dates.sort();
+            dates.dedup();
+        }
+        Some((period_start, dates))
+    }
+
+    fn month_matches(&self, day: NaiveDate) -> bool {
+        self.by_month.is_empty() || self.by_month.contains(&day.month())
+    }
+
+    /// Días de un mes que cumplen BYMONTHDAY y BYDAY; sin ninguno de los dos,
+    /// el día de DTSTART (si el mes lo tiene)
+    fn days_in_month(&self, year: i32, month: u32, start_day: u32) -> Vec<NaiveDate> {
+        if !self.by_month_day.is_empty() {
+            return self.by_month_day.iter()
+                .filter_map(|&day| resolve_month_day(year, month, day))
+                .filter_map(|day| NaiveDate::from_ymd_opt(year, month, day))
+                .filter(|day| self.by_day.is_empty() || self.by_day.iter().any(|d| d.weekday == day.weekday()))
+                .collect();
+        }
+        if !self.by_day.is_empty() {
+            let Some(first) = NaiveDate::from_ymd_opt(year, month, 1) else { return Vec::new() };
+            let month_days: Vec<NaiveDate> = first.iter_days().take_while(|day| day.month() == month).collect();
+            return weekdays_in(&month_days, &self.by_day);
+        }
+        NaiveDate::from_ymd_opt(year, month, start_day).into_iter().collect()
+    }
+}
+
+/// Días de `days` que corresponden a los BYDAY, respetando sus ordinales
+fn weekdays_in(days: &[NaiveDate], by_day: &[WeekdayNum]) -> Vec<NaiveDate> {
+    let mut result = Vec::new();
+    for day in by_day {
+        let matching: Vec<NaiveDate> = days.iter().copied().filter(|d| d.weekday() == day.weekday).collect();
+        match day.ordinal {
+            None => result.extend(matching),
+            Some(n) => {
+                let index = if n > 0 { n as i64 - 1 } else { matching.len() as i64 + n as i64 };
+                if let Some(date) = usize::try_from(index).ok().and_then(|index| matching.get(index)) {
+                    result.push(*date);
+                }
+            }
+        }
+    }
+    result
+}
+
+/// Día del mes de un BYMONTHDAY; los negativos cuentan desde el final
+fn resolve_month_day(year: i32, month: u32, day: i32) -> Option<u32> {
+    let first_next = if month == 12 {
+        NaiveDate::from_ymd_opt(year + 1, 1, 1)?
+    } else {
+        NaiveDate::from_ymd_opt(year, month + 1, 1)?
+    };
+    let last = first_next.pred_opt()?.day() as i32;
+    let resolved = if day < 0 { last + day + 1 } else { day };
+    (1..=last).contains(&resolved).then_some(resolved as u32)
+}
+
+fn parse_weekday(value: &str) -> Option<Weekday> {
+    Some(match value.to_ascii_uppercase().as_str() {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+fn parse_weekday_num(value: &str) -> Option<WeekdayNum> {
+    let split = value.len().checked_sub(2)?;
+    let weekday = parse_weekday(value.get(split..)?)?;
+    let ordinal = match value.get(..split)? {
+        "" => None,
+        ordinal => Some(ordinal.parse::<i32>().ok().filter(|n| *n != 0 && n.abs() <= 53)?),
+    };
+    Some(WeekdayNum { ordinal, weekday })
+}
+
+fn positive(value: &str, name: &str) -> Result<u32, String> {
+    value.parse::<u32>().ok().filter(|n| *n > 0).ok_or_else(|| format!("{} must be a positive integer", name))
+}
+
+fn numbers(value: &str, name: &str, max: i32, signed: bool) -> Result<Vec<i32>, String> {
+    value.split(',')
+        .map(|n| {
+            n.trim().parse::<i32>().ok()
+                .filter(|n| *n != 0 && n.abs() <= max && (signed || *n > 0))
+                .ok_or_else(|| format!("{} {} is out of range", name, n))
+        })
+        .collect()
+}
+
+/// Fecha de RDATE/EXDATE; las fechas sin hora afectan a todo el día
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecurrenceDate {
+    pub instant: DateTime<Utc>,
+    pub is_date: bool,
+}
+
+impl RecurrenceDate {
+    fn matches(&self, occurrence: DateTime<Utc>) -> bool {
+        if self.is_date {
+            self.instant.date_naive() == occurrence.date_naive()
+        } else {
+            self.instant == occurrence
+        }
+    }
+}
+
+/// Serie de repeticiones de un componente: DTSTART, duración, RRULE, RDATE y EXDATE
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recurrence {
+    pub start: DateTime<Utc>,
+    pub duration: Duration,
+    pub rule: Option<RecurrenceRule>,
+    pub rdates: Vec<RecurrenceDate>,
+    pub exdates: Vec<RecurrenceDate>,
+}
+
+impl Recurrence {
+    pub fn new(start: DateTime<Utc>, duration: Duration, rule: Option<RecurrenceRule>) -> Self {
+        Self { start, duration, rule, rdates: Vec::new(), exdates: Vec::new() }
+    }
+
+    /// Serie de un VEVENT o VTODO; `None` si no tiene DTSTART.
+    ///
+    /// Una RRULE que no se puede interpretar se ignora y el componente
+    /// queda como un único evento.
+    pub fn from_component(component: &ICalComponent) -> Option<Self> {
+        let (start, is_date) = component.property("DTSTART").and_then(ICalProperty::date_time)?;
+        let end = component.property("DTEND")
+            .or_else(|| component.property("DUE"))
+            .and_then(ICalProperty::date_time)
+            .map(|(end, _)| end);
+        let duration = match (end, component.property("DURATION").and_then(|p| parse_duration(&p.value))) {
+            (Some(end), _) => end - start,
+            (None, Some(duration)) => duration,
+            (None, None) if is_date => Duration::days(1),
+            (None, None) => Duration::zero(),
+        };
+        let rule = component.property("RRULE").and_then(|rule| RecurrenceRule::parse(&rule.value).ok());
+        Some(Self {
+            start,
+            duration: duration.max(Duration::zero()),
+            rule,
+            rdates: recurrence_dates(component, "RDATE"),
+            exdates: recurrence_dates(component, "EXDATE"),
+        })
+    }
+
+    /// Si la serie tiene más de una repetición posible
+    pub fn is_recurring(&self) -> bool {
+        self.rule.is_some() || !self.rdates.is_empty()
+    }
+
+    /// Inicios de las repeticiones que se solapan con `[range_start, range_end)`,
+    /// ordenados y sin las fechas excluidas; como mucho `limit`
+    pub fn occurrences(&self, range_start: Option<DateTime<Utc>>, range_end: Option<DateTime<Utc>>, limit: usize) -> Vec<DateTime<Utc>> {
+        let overlaps = |start: DateTime<Utc>| {
+            let end = start + self.duration;
+            let before_end = range_end.is_none_or(|range_end| start < range_end);
+            let after_start = range_start.is_none_or(|range_start| {
+                // Las repeticiones sin duración cuentan por su instante de inicio
+                if self.duration.is_zero() { start >= range_start } else { end > range_start }
+            });
+            before_end && after_start
+        };
+        let excluded = |start: DateTime<Utc>| self.exdates.iter().any(|exdate| exdate.matches(start));
+
+        let mut found = Vec::new();
+        let mut visit = |start: DateTime<Utc>| {
+            if range_end.is_some_and(|range_end| start >= range_end) {
+                return ControlFlow::Break(());
+            }
+            if overlaps(start) && !excluded(start) {
+                found.push(start);
+            }
+            if found.len() >= limit { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+        };
+        match &self.rule {
+            Some(rule) => rule.expand(self.start, range_start.map(|start| start - self.duration), &mut visit),
+            None => { let _ = visit(self.start); }
+        }
+
+        for rdate in &self.rdates {
+            if overlaps(rdate.instant) && !excluded(rdate.instant) && !found.contains(&rdate.instant) {
+                found.push(rdate.instant);
+            }
+        }
+        found.sort();
+        found.truncate(limit);
+        found
+    }
+
+    /// Si alguna repetición se solapa con el intervalo
+    pub fn overlaps(&self, range_start: Option<DateTime<Utc>>, range_end: Option<DateTime<Utc>>) -> bool {
+        !self.occurrences(range_start, range_end, 1).is_empty()
+    }
+}
+
+/// Fechas de todas las propiedades RDATE o EXDATE, que admiten varias
+/// separadas por comas
+fn recurrence_dates(component: &ICalComponent, name: &str) -> Vec<RecurrenceDate> {
+    component.properties_named(name)
+        .flat_map(|property| {
+            let is_date = property.param("VALUE").is_some_and(|v| v.eq_ignore_ascii_case("DATE"));
+            property.value.split(',')
+                // Los periodos (VALUE=PERIOD) se reducen a su inicio
+                .filter_map(move |value| parse_date_time(value.split('/').next().unwrap_or_default(), is_date))
+                .map(|(instant, is_date)| RecurrenceDate { instant, is_date })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(year: i32, month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, 0, 0).unwrap()
+    }
+
+    fn expand(rule: &str, start: DateTime<Utc>, limit: usize) -> Vec<DateTime<Utc>> {
+        let rule = RecurrenceRule::parse(rule).unwrap();
+        Recurrence::new(start, Duration::hours(1), Some(rule)).occurrences(None, None, limit)
+    }
+
+    #[test]
+    fn expands_daily_and_weekly_rules() {
+        // 2024-01-01 es lunes
+        let start = at(2024, 1, 1, 9);
+        assert_eq!(expand("FREQ=DAILY;INTERVAL=2;COUNT=3", start, 10), vec![start, at(2024, 1, 3, 9), at(2024, 1, 5, 9)]);
+        assert_eq!(
+            expand("FREQ=WEEKLY;BYDAY=MO,WE,FR;UNTIL=20240108T090000Z", start, 10),
+            vec![start, at(2024, 1, 3, 9), at(2024, 1, 5, 9), at(2024, 1, 8, 9)],
+        );
+        assert_eq!(expand("FREQ=WEEKLY;INTERVAL=2", start, 3), vec![start, at(2024, 1, 15, 9), at(2024, 1, 29, 9)]);
+    }
+
+    #[test]
+    fn expands_monthly_and_yearly_rules() {
+        // Último viernes de cada mes
+        let start = at(2024, 1, 26, 9);
+        assert_eq!(expand("FREQ=MONTHLY;BYDAY=-1FR", start, 3), vec![start, at(2024, 2, 23, 9), at(2024, 3, 29, 9)]);
+
+        // Los meses sin día 31 se saltan
+        let start = at(2024, 1, 31, 9);
+        assert_eq!(expand("FREQ=MONTHLY", start, 3), vec![start, at(2024, 3, 31, 9), at(2024, 5, 31, 9)]);
+
+        // Último día laborable del mes
+        let start = at(2024, 1, 31, 9);
+        assert_eq!(
+            expand("FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1", start, 3),
+            vec![start, at(2024, 2, 29, 9), at(2024, 3, 29, 9)],
+        );
+
+        // Cuarto jueves de noviembre
+        let start = at(2024, 11, 28, 12);
+        assert_eq!(expand("FREQ=YEARLY;BYMONTH=11;BYDAY=4TH", start, 2), vec![start, at(2025, 11, 27, 12)]);
+
+        // 29 de febrero solo en los bisiestos
+        let start = at(2024, 2, 29, 0);
+        assert_eq!(expand("FREQ=YEARLY", start, 2), vec![start, at(2028, 2, 29, 0)]);
+    }
+
+    #[test]
+    fn applies_exdates_and_ranges() {
+        let event = ICalComponent::parse(
+            "BEGIN:VEVENT\nDTSTART:20240101T090000Z\nDTEND:20240101T100000Z\nRRULE:FREQ=DAILY;COUNT=5\nEXDATE:20240102T090000Z,20240104T090000Z\nRDATE:20240110T090000Z\nEND:VEVENT\n",
+        ).unwrap();
+        let series = Recurrence::from_component(&event).unwrap();
+        assert_eq!(
+            series.occurrences(None, None, 10),
+            vec![at(2024, 1, 1, 9), at(2024, 1, 3, 9), at(2024, 1, 5, 9), at(2024, 1, 10, 9)],
+        );
+        assert_eq!(series.occurrences(Some(at(2024, 1, 3, 9)), Some(at(2024, 1, 6, 0)), 10), vec![at(2024, 1, 3, 9), at(2024, 1, 5, 9)]);
+        assert!(series.overlaps(Some(at(2024, 1, 5, 9)), Some(at(2024, 1, 5, 10))));
+        assert!(!series.overlaps(Some(at(2024, 1, 2, 0)), Some(at(2024, 1, 3, 0))));
+
+        // Una serie infinita se puede consultar lejos de su inicio
+        let daily = Recurrence::new(at(2000, 1, 1, 9), Duration::hours(1), Some(RecurrenceRule::parse("FREQ=DAILY").unwrap()));
+        assert_eq!(daily.occurrences(Some(at(2030, 6, 1, 0)), Some(at(2030, 6, 2, 0)), 10), vec![at(2030, 6, 1, 9)]);
+    }
+}
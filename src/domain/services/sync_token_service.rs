@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use crate::domain::entities::collection_change::{ChangeOperation, CollectionChange};
+
+/// Prefijo de los sync-token (RFC 6578 exige que sean URIs)
+const SYNC_TOKEN_PREFIX: &str = "http://oxicloud.org/ns/sync/";
+
+/// Sync-token de una colección: la revisión hasta la que el cliente está al día
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyncToken {
+    pub collection_id: Uuid,
+    pub revision: i64,
+}
+
+impl SyncToken {
+    pub fn new(collection_id: Uuid, revision: i64) -> Self {
+        Self { collection_id, revision }
+    }
+
+    /// Interpreta un sync-token generado por `to_uri`
+    pub fn parse(token: &str) -> Option<Self> {
+        let (collection_id, revision) = token.trim().strip_prefix(SYNC_TOKEN_PREFIX)?.split_once('/')?;
+        let revision = revision.parse::<i64>().ok().filter(|revision| *revision >= 0)?;
+        Some(Self::new(Uuid::parse_str(collection_id).ok()?, revision))
+    }
+
+    pub fn to_uri(&self) -> String {
+        format!("{}{}/{}", SYNC_TOKEN_PREFIX, self.collection_id, self.revision)
+    }
+
+    /// Si el diario permite calcular los cambios desde este token.
+    ///
+    /// El token tiene que ser de la misma colección, no puede ser posterior a
+    /// la revisión actual y, si hubo cambios desde entonces, el diario tiene
+    /// que conservar todos (las revisiones son consecutivas, así que basta con
+    /// que la más antigua conservada sea la siguiente al token o anterior).
+    pub fn is_valid_for(&self, collection_id: &Uuid, current_revision: i64, oldest_change: Option<i64>) -> bool {
+        if &self.collection_id != collection_id || self.revision > current_revision {
+            return false;
+        }
+        self.revision == current_revision
+            || oldest_change.is_some_and(|oldest| oldest <= self.revision + 1)
+    }
+}
+
+/// Cambios de una colección resumidos por objeto
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SyncDelta {
+    /// Objetos creados o modificados (ID, UID), en orden de su primer cambio
+    pub changed: Vec<(Uuid, String)>,
+    /// UIDs de los objetos eliminados
+    pub deleted: Vec<String>,
+    /// Revisión hasta la que llegan los cambios; base del nuevo sync-token
+    pub revision: i64,
+    /// Si quedaron cambios fuera por superar el límite de resultados
+    pub truncated: bool,
+}
+
+/// Resume los cambios posteriores a `since` quedándose con la última operación
+/// de cada objeto.
+///
+/// Como mucho se devuelven `limit` objetos; si hay más, el resumen se corta en
+/// la última revisión que cabe entera y se marca como truncado para que el
+/// cliente pida el resto con el nuevo token.
+pub fn collapse_changes(changes: &[CollectionChange], since: i64, limit: usize) -> SyncDelta {
+    let mut changes: Vec<&CollectionChange> = changes.iter()
+        .filter(|change| change.revision > since)
+        .collect();
+    changes.sort_by_key(|change| change.revision);
+
+    let mut order: Vec<&str> = Vec::new();
+    let mut latest: HashMap<&str, &CollectionChange> = HashMap::new();
+    let mut revision = since;
+    let mut truncated = false;
+
+    for change in changes {
+        let uid = change.object_uid.as_str();
+        if !latest.contains_key(uid) {
+            if order.len() == limit {
+                truncated = true;
+                break;
+            }
+            order.push(uid);
+        }
+        latest.insert(uid, change);
+        revision = change.revision;
+    }
+
+    let mut delta = SyncDelta { revision, truncated, ..SyncDelta::default() };
+    for uid in order {
+        let change = latest[uid];
+        match change.operation {
+            ChangeOperation::Deleted => delta.deleted.push(uid.to_string()),
+            ChangeOperation::Created | ChangeOperation::Modified => {
+                delta.changed.push((change.object_id, uid.to_string()))
+            }
+        }
+    }
+    delta
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn change(revision: i64, uid: &str, operation: ChangeOperation) -> CollectionChange {
+        CollectionChange {
+            revision,
+            object_id: Uuid::from_u128(uid.len() as u128),
+            object_uid: uid.to_string(),
+            operation,
+            changed_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn sync_token_round_trip_and_validity() {
+        let calendar_id = Uuid::new_v4();
+        let token = SyncToken::new(calendar_id, 7);
+        assert_eq!(SyncToken::parse(&token.to_uri()), Some(token));
+        assert_eq!(SyncToken::parse("http://oxicloud.org/ns/sync/nope/7"), None);
+        assert_eq!(SyncToken::parse(&format!("http://oxicloud.org/ns/sync/{}/-1", calendar_id)), None);
+
+        assert!(token.is_valid_for(&calendar_id, 7, None));
+        assert!(token.is_valid_for(&calendar_id, 9, Some(1)));
+        assert!(token.is_valid_for(&calendar_id, 9, Some(8)));
+        // Cambios ya purgados del diario, de otra colección o del futuro
+        assert!(!token.is_valid_for(&calendar_id, 9, Some(9)));
+        assert!(!token.is_valid_for(&calendar_id, 9, None));
+        assert!(!token.is_valid_for(&Uuid::new_v4(), 7, None));
+        assert!(!token.is_valid_for(&calendar_id, 6, Some(1)));
+    }
+
+    #[test]
+    fn collapse_keeps_last_operation_per_object() {
+        let changes = vec![
+            change(3, "a", ChangeOperation::Created),
+            change(4, "bb", ChangeOperation::Created),
+            change(5, "a", ChangeOperation::Modified),
+            change(6, "bb", ChangeOperation::Deleted),
+            change(7, "ccc", ChangeOperation::Modified),
+        ];
+
+        let delta = collapse_changes(&changes, 3, 100);
+        assert_eq!(delta.changed, vec![
+            (Uuid::from_u128(1), "a".to_string()),
+            (Uuid::from_u128(3), "ccc".to_string()),
+        ]);
+        assert_eq!(delta.deleted, vec!["bb".to_string()]);
+        assert_eq!(delta.revision, 7);
+        assert!(!delta.truncated);
+    }
+
+    #[test]
+    fn collapse_truncates_at_limit() {
+        let changes = vec![
+            change(1, "a", ChangeOperation::Created),
+            change(2, "bb", ChangeOperation::Created),
+            change(3, "a", ChangeOperation::Modified),
+            change(4, "ccc", ChangeOperation::Created),
+        ];
+
+        let delta = collapse_changes(&changes, 0, 2);
+        assert_eq!(delta.changed.len(), 2);
+        assert_eq!(delta.revision, 3);
+        assert!(delta.truncated);
+
+        let rest = collapse_changes(&changes, delta.revision, 2);
+        assert_eq!(rest.changed, vec![(Uuid::from_u128(3), "ccc".to_string())]);
+        assert!(!rest.truncated);
+    }
+}
@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+use std::io::{Cursor, Read, Write};
+
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+/// Cabecera de los ficheros ZIP (ODF y OOXML son ZIP con partes XML)
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+/// Extensiones de texto cuyo contenido es marcado y necesita escapar los valores
+const MARKUP_EXTENSIONS: &[&str] = &["html", "htm", "xml", "svg", "xhtml", "fodt", "fods", "fodp"];
+
+/// Valores con los que se rellenan las marcas `{{nombre}}` de una plantilla
+#[derive(Debug, Clone, Default)]
+pub struct TemplateValues {
+    values: HashMap<String, String>,
+}
+
+impl TemplateValues {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Añade un valor; el nombre no distingue mayúsculas
+    pub fn with(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.values.insert(name.to_lowercase(), value.into());
+        self
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(&name.trim().to_lowercase()).map(String::as_str)
+    }
+}
+
+/// Rellena las marcas de una plantilla.
+///
+/// En los documentos de oficina (ODF, OOXML) se sustituyen las marcas de las
+/// partes XML y el resto del paquete se copia tal cual; en los ficheros de
+/// texto, en todo el contenido. Los demás ficheros y los paquetes que no se
+/// pueden leer se devuelven sin cambios. Las marcas desconocidas se conservan.
+pub fn fill_placeholders(file_name: &str, content: Vec<u8>, values: &TemplateValues) -> Vec<u8> {
+    if content.starts_with(ZIP_MAGIC) {
+        return match fill_package(&content, values) {
+            Ok(filled) => filled,
+            Err(e) => {
+                tracing::warn!("No se pudieron rellenar las marcas de la plantilla {}: {}", file_name, e);
+                content
+            }
+        };
+    }
+
+    let extension = file_name.rsplit_once('.').map(|(_, ext)| ext.to_lowercase()).unwrap_or_default();
+    let escape = MARKUP_EXTENSIONS.contains(&extension.as_str());
+    match String::from_utf8(content) {
+        Ok(text) => replace_placeholders(&text, values, escape).into_bytes(),
+        Err(e) => e.into_bytes(),
+    }
+}
+
+/// Rellena las marcas de un texto plano sin escapar los valores, como los
+/// asuntos y cuerpos de los correos
+pub fn fill_text(text: &str, values: &TemplateValues) -> String {
+    replace_placeholders(text, values, false)
+}
+
+/// Sustituye las marcas de las partes XML de un paquete ZIP conservando el
+/// orden y la compresión de cada entrada (ODF exige que `mimetype` vaya
+/// primero y sin comprimir)
+fn fill_package(content: &[u8], values: &TemplateValues) -> zip::result::ZipResult<Vec<u8>> {
+    let mut archive = ZipArchive::new(Cursor::new(content))?;
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        let name = entry.name().to_string();
+        let options = SimpleFileOptions::default().compression_method(entry.compression());
+
+        if entry.is_dir() {
+            writer.add_directory(name, options)?;
+            continue;
+        }
+
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data)?;
+        if name.ends_with(".xml") {
+            if let Ok(xml) = std::str::from_utf8(&data) {
+                data = replace_placeholders(xml, values, true).into_bytes();
+            }
+        }
+
+        writer.start_file(name, options)?;
+        writer.write_all(&data)?;
+    }
+
+    Ok(writer.finish()?.into_inner())
+}
+
+/// Sustituye las marcas `{{nombre}}` conocidas de un texto
+fn replace_placeholders(text: &str, values: &TemplateValues, escape: bool) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            rest = &rest[start..];
+            break;
+        };
+
+        match values.get(&after[..end]) {
+            Some(value) if escape => result.push_str(&escape_markup(value)),
+            Some(value) => result.push_str(value),
+            None => result.push_str(&rest[start..start + 2 + end + 2]),
+        }
+        rest = &after[end + 2..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
+fn escape_markup(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values() -> TemplateValues {
+        TemplateValues::new()
+            .with("date", "2025-05-04")
+            .with("username", "alice")
+            .with("title", "Q&A <draft>")
+    }
+
+    #[test]
+    fn test_text_placeholders() {
+        let filled = fill_placeholders("notes.md", b"# {{title}}\n{{ Date }} by {{username}} {{unknown}} {{".to_vec(), &values());
+        assert_eq!(String::from_utf8(filled).unwrap(), "# Q&A <draft>\n2025-05-04 by alice {{unknown}} {{");
+
+        let filled = fill_placeholders("page.html", b"<h1>{{title}}</h1>".to_vec(), &values());
+        assert_eq!(filled, b"<h1>Q&amp;A &lt;draft&gt;</h1>");
+    }
+
+    #[test]
+    fn test_binary_content_is_untouched() {
+        let content = vec![0xff, 0xfe, b'{', b'{', b'd', b'a', b't', b'e', b'}', b'}'];
+        assert_eq!(fill_placeholders("image.png", content.clone(), &values()), content);
+    }
+
+    #[test]
+    fn test_office_package_placeholders() {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let stored = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        writer.start_file("mimetype", stored).unwrap();
+        writer.write_all(b"application/vnd.oasis.opendocument.text").unwrap();
+        writer.start_file("content.xml", SimpleFileOptions::default()).unwrap();
+        writer.write_all(b"<text>{{title}} - {{date}}</text>").unwrap();
+        writer.start_file("Pictures/logo.png", SimpleFileOptions::default()).unwrap();
+        writer.write_all(b"{{date}}").unwrap();
+        let package = writer.finish().unwrap().into_inner();
+
+        let filled = fill_placeholders("report.odt", package, &values());
+        let mut archive = ZipArchive::new(Cursor::new(filled)).unwrap();
+
+        let mimetype = archive.by_index(0).unwrap();
+        assert_eq!(mimetype.name(), "mimetype");
+        assert_eq!(mimetype.compression(), zip::CompressionMethod::Stored);
+        drop(mimetype);
+
+        let mut content = String::new();
+        archive.by_name("content.xml").unwrap().read_to_string(&mut content).unwrap();
+        assert_eq!(content, "<text>Q&amp;A &lt;draft&gt; - 2025-05-04</text>");
+
+        let mut picture = String::new();
+        archive.by_name("Pictures/logo.png").unwrap().read_to_string(&mut picture).unwrap();
+        assert_eq!(picture, "{{date}}");
+    }
+}
@@ -0,0 +1,347 @@
+use std::ops::ControlFlow;
+
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+
+use crate::domain::services::icalendar_service::{parse_date_time, ICalComponent, ICalProperty};
+use crate::domain::services::recurrence_service::RecurrenceRule;
+
+/// Propiedades cuyos valores DATE-TIME pueden llevar TZID (RFC 5545, 3.2.19)
+const ZONED_PROPERTIES: &[&str] = &["DTSTART", "DTEND", "DUE", "RECURRENCE-ID", "RDATE", "EXDATE"];
+
+/// TZID que no necesitan VTIMEZONE para interpretarse
+const UTC_TZIDS: &[&str] = &["UTC", "GMT", "Etc/UTC", "Etc/GMT", "Z"];
+
+/// Formato de un DATE-TIME en UTC
+const UTC_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// Observancia STANDARD o DAYLIGHT de un VTIMEZONE (RFC 5545, 3.6.5)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Observance {
+    pub daylight: bool,
+    /// Primera transición, en la hora local anterior a ella
+    pub start: NaiveDateTime,
+    /// Desplazamiento respecto a UTC antes de la transición, en segundos
+    pub offset_from: i32,
+    /// Desplazamiento respecto a UTC después de la transición, en segundos
+    pub offset_to: i32,
+    pub rule: Option<RecurrenceRule>,
+    /// Transiciones adicionales (RDATE), en hora local
+    pub rdates: Vec<NaiveDateTime>,
+}
+
+impl Observance {
+    fn from_component(component: &ICalComponent) -> Option<Self> {
+        let daylight = match component.name.as_str() {
+            "STANDARD" => false,
+            "DAYLIGHT" => true,
+            _ => return None,
+        };
+        let (start, _) = component.property("DTSTART").and_then(ICalProperty::date_time)?;
+        let offset_to = parse_utc_offset(&component.property("TZOFFSETTO")?.value)?;
+        let offset_from = component.property("TZOFFSETFROM")
+            .and_then(|property| parse_utc_offset(&property.value))
+            .unwrap_or(offset_to);
+        let rdates = component.properties_named("RDATE")
+            .flat_map(|property| property.value.split(',').map(str::to_string).collect::<Vec<_>>())
+            .filter_map(|value| parse_date_time(&value, false).map(|(local, _)| local.naive_utc()))
+            .collect();
+        Some(Self {
+            daylight,
+            start: start.naive_utc(),
+            offset_from,
+            offset_to,
+            rule: component.property("RRULE").and_then(|rule| RecurrenceRule::parse(&rule.value).ok()),
+            rdates,
+        })
+    }
+
+    /// Instante UTC de una transición dada en hora local
+    fn transition_at(&self, local: NaiveDateTime) -> DateTime<Utc> {
+        (local - Duration::seconds(self.offset_from as i64)).and_utc()
+    }
+
+    /// Última transición de la observancia anterior o igual a `instant`
+    fn last_transition(&self, instant: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        // La regla se expande en hora local, como si fuera UTC
+        let limit = instant.naive_utc() + Duration::seconds(self.offset_from as i64);
+        let mut last = None;
+        let mut visit = |local: NaiveDateTime| {
+            if local <= limit && last.is_none_or(|previous| local > previous) {
+                last = Some(local);
+            }
+        };
+
+        match &self.rule {
+            Some(rule) => rule.expand(self.start.and_utc(), None, |candidate| {
+                if candidate.naive_utc() > limit {
+                    return ControlFlow::Break(());
+                }
+                visit(candidate.naive_utc());
+                ControlFlow::Continue(())
+            }),
+            None => visit(self.start),
+        }
+        self.rdates.iter().for_each(|rdate| visit(*rdate));
+        last.map(|local| self.transition_at(local))
+    }
+}
+
+/// Zona horaria definida por un componente VTIMEZONE
+#[derive(Debug, Clone, PartialEq)]
+pub struct VTimeZone {
+    pub tzid: String,
+    pub observances: Vec<Observance>,
+    /// Componente original, para volver a emitirlo sin perder propiedades
+    component: ICalComponent,
+}
+
+impl VTimeZone {
+    /// Interpreta un VTIMEZONE; `None` si no tiene TZID ni observancias válidas
+    pub fn from_component(component: &ICalComponent) -> Option<Self> {
+        if component.name != "VTIMEZONE" {
+            return None;
+        }
+        let tzid = component.property("TZID")?.value.trim().to_string();
+        let observances: Vec<Observance> = component.components.iter()
+            .filter_map(Observance::from_component)
+            .collect();
+        if tzid.is_empty() || observances.is_empty() {
+            return None;
+        }
+        Some(Self { tzid, observances, component: component.clone() })
+    }
+
+    /// Interpreta el valor de la propiedad CalDAV calendar-timezone: un
+    /// VCALENDAR con un único VTIMEZONE (o el VTIMEZONE suelto)
+    pub fn parse(data: &str) -> Option<Self> {
+        let root = ICalComponent::parse(data)?;
+        if root.name == "VTIMEZONE" {
+            return Self::from_component(&root);
+        }
+        let zone = root.components_named("VTIMEZONE").find_map(Self::from_component);
+        zone
+    }
+
+    /// Desplazamiento respecto a UTC, en segundos, vigente en un instante
+    pub fn offset_at(&self, instant: DateTime<Utc>) -> i32 {
+        let latest = self.observances.iter()
+            .filter_map(|observance| observance.last_transition(instant).map(|at| (at, observance.offset_to)))
+            .max_by_key(|(at, _)| *at);
+        match latest {
+            Some((_, offset)) => offset,
+            // Antes de la primera transición rige el desplazamiento de partida
+            None => self.observances.iter()
+                .min_by_key(|observance| observance.transition_at(observance.start))
+                .map(|observance| observance.offset_from)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Convierte una hora local de la zona a UTC.
+    ///
+    /// Una hora repetida al retrasar el reloj es la primera de las dos, y una
+    /// hora que no existe al adelantarlo se interpreta con el desplazamiento
+    /// anterior al salto (RFC 5545, 3.3.5).
+    pub fn to_utc(&self, local: NaiveDateTime) -> DateTime<Utc> {
+        let mut offsets: Vec<i32> = self.observances.iter()
+            .flat_map(|observance| [observance.offset_from, observance.offset_to])
+            .collect();
+        offsets.sort_unstable_by(|a, b| b.cmp(a));
+        offsets.dedup();
+
+        // Con el mayor desplazamiento sale el instante UTC más temprano
+        for offset in &offsets {
+            let instant = (local - Duration::seconds(*offset as i64)).and_utc();
+            if self.offset_at(instant) == *offset {
+                return instant;
+            }
+        }
+        let before_gap = offsets.last().copied().unwrap_or_default();
+        (local - Duration::seconds(before_gap as i64)).and_utc()
+    }
+
+    /// Componente VTIMEZONE en formato iCalendar
+    pub fn to_ical(&self) -> String {
+        self.component.to_ical()
+    }
+
+    /// Valor de la propiedad calendar-timezone: un VCALENDAR con este VTIMEZONE
+    pub fn to_calendar(&self) -> String {
+        format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//OxiCloud//NONSGML Calendar//EN\r\n{}END:VCALENDAR\r\n",
+            self.to_ical()
+        )
+    }
+}
+
+/// Interpreta un desplazamiento UTC-OFFSET (`+0100`, `-053000`) en segundos
+pub fn parse_utc_offset(value: &str) -> Option<i32> {
+    let value = value.trim();
+    let sign = match value.as_bytes().first()? {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    let digits = &value[1..];
+    if !(digits.len() == 4 || digits.len() == 6) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let hours: i32 = digits[0..2].parse().ok()?;
+    let minutes: i32 = digits[2..4].parse().ok()?;
+    let seconds: i32 = digits.get(4..6).map_or(Some(0), |s| s.parse().ok())?;
+    if minutes >= 60 || seconds >= 60 {
+        return None;
+    }
+    Some(sign * (hours * 3600 + minutes * 60 + seconds))
+}
+
+/// Zonas horarias definidas en un objeto iCalendar
+pub fn timezones_of(calendar: &ICalComponent) -> Vec<VTimeZone> {
+    calendar.components_named("VTIMEZONE").filter_map(VTimeZone::from_component).collect()
+}
+
+/// Convierte a UTC una hora local con su TZID.
+///
+/// Sin TZID la hora es flotante y se interpreta en `floating` (la zona del
+/// calendario) si la hay. `None` si no se conoce la zona: el llamante la
+/// trata como UTC.
+pub fn local_to_utc(
+    local: NaiveDateTime,
+    tzid: Option<&str>,
+    timezones: &[VTimeZone],
+    floating: Option<&VTimeZone>,
+) -> Option<DateTime<Utc>> {
+    let zone = match tzid {
+        Some(tzid) if UTC_TZIDS.iter().any(|utc| utc.eq_ignore_ascii_case(tzid)) => return Some(local.and_utc()),
+        Some(tzid) => timezones.iter()
+            .chain(floating)
+            .find(|zone| zone.tzid == tzid)?,
+        None => floating?,
+    };
+    Some(zone.to_utc(local))
+}
+
+/// Fecha de una propiedad DATE o DATE-TIME en UTC, resolviendo su TZID con
+/// las zonas del objeto o la del calendario
+pub fn resolve_date_time(
+    property: &ICalProperty,
+    timezones: &[VTimeZone],
+    floating: Option<&VTimeZone>,
+) -> Option<(DateTime<Utc>, bool)> {
+    let (instant, is_date) = property.date_time()?;
+    if is_date || property.value.trim().ends_with(['Z', 'z']) {
+        return Some((instant, is_date));
+    }
+    let utc = local_to_utc(instant.naive_utc(), property.param("TZID"), timezones, floating);
+    Some((utc.unwrap_or(instant), false))
+}
+
+/// Reescribe en UTC las fechas con TZID (y las flotantes, si el calendario
+/// tiene zona) de todos los componentes salvo los VTIMEZONE.
+///
+/// Así los filtros, las repeticiones y los campos del evento trabajan con
+/// instantes reales. Las fechas con un TZID desconocido se dejan como están.
+pub fn normalize_to_utc(calendar: &mut ICalComponent, floating: Option<&VTimeZone>) {
+    let timezones = timezones_of(calendar);
+    normalize_component(calendar, &timezones, floating);
+}
+
+fn normalize_component(component: &mut ICalComponent, timezones: &[VTimeZone], floating: Option<&VTimeZone>) {
+    if component.name == "VTIMEZONE" {
+        return;
+    }
+    for property in &mut component.properties {
+        if ZONED_PROPERTIES.contains(&property.name.as_str()) {
+            normalize_property(property, timezones, floating);
+        }
+    }
+    for child in &mut component.components {
+        normalize_component(child, timezones, floating);
+    }
+}
+
+fn normalize_property(property: &mut ICalProperty, timezones: &[VTimeZone], floating: Option<&VTimeZone>) {
+    if property.param("VALUE").is_some_and(|value| value.eq_ignore_ascii_case("DATE")) {
+        return;
+    }
+    let tzid = property.param("TZID").map(str::to_string);
+    let mut converted = false;
+    let convert = |part: &str, converted: &mut bool| -> String {
+        let part = part.trim();
+        if part.len() == 8 || part.ends_with(['Z', 'z']) {
+            return part.to_string();
+        }
+        match parse_date_time(part, false)
+            .and_then(|(local, _)| local_to_utc(local.naive_utc(), tzid.as_deref(), timezones, floating))
+        {
+            Some(utc) => {
+                *converted = true;
+                utc.format(UTC_FORMAT).to_string()
+            }
+            None => part.to_string(),
+        }
+    };
+
+    // Las listas van separadas por comas y los periodos por '/'
+    let value = property.value.split(',')
+        .map(|item| item.split('/')
+            .map(|part| convert(part, &mut converted))
+            .collect::<Vec<_>>()
+            .join("/"))
+        .collect::<Vec<_>>()
+        .join(",");
+    if converted {
+        property.value = value;
+        property.params.retain(|(name, _)| name != "TZID");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, TimeZone};
+
+    const MADRID: &str = "BEGIN:VCALENDAR\r\nBEGIN:VTIMEZONE\r\nTZID:Europe/Madrid\r\nBEGIN:DAYLIGHT\r\nTZOFFSETFROM:+0100\r\nTZOFFSETTO:+0200\r\nTZNAME:CEST\r\nDTSTART:19700329T020000\r\nRRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU\r\nEND:DAYLIGHT\r\nBEGIN:STANDARD\r\nTZOFFSETFROM:+0200\r\nTZOFFSETTO:+0100\r\nTZNAME:CET\r\nDTSTART:19701025T030000\r\nRRULE:FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU\r\nEND:STANDARD\r\nEND:VTIMEZONE\r\nBEGIN:VEVENT\r\nUID:1\r\nDTSTART;TZID=Europe/Madrid:20240710T090000\r\nEXDATE;TZID=Europe/Madrid:20240110T090000,20240111T090000\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+
+    fn local(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap().and_hms_opt(h, min, 0).unwrap()
+    }
+
+    #[test]
+    fn converts_local_times_across_daylight_saving() {
+        let zone = VTimeZone::parse(MADRID).unwrap();
+        assert_eq!(zone.tzid, "Europe/Madrid");
+        assert_eq!(zone.to_utc(local(2024, 1, 10, 9, 0)), Utc.with_ymd_and_hms(2024, 1, 10, 8, 0, 0).unwrap());
+        assert_eq!(zone.to_utc(local(2024, 7, 10, 9, 0)), Utc.with_ymd_and_hms(2024, 7, 10, 7, 0, 0).unwrap());
+        // 2024-03-31 02:30 no existe; 2024-10-27 02:30 ocurre dos veces
+        assert_eq!(zone.to_utc(local(2024, 3, 31, 2, 30)), Utc.with_ymd_and_hms(2024, 3, 31, 1, 30, 0).unwrap());
+        assert_eq!(zone.to_utc(local(2024, 10, 27, 2, 30)), Utc.with_ymd_and_hms(2024, 10, 27, 0, 30, 0).unwrap());
+        assert_eq!(zone.offset_at(Utc.with_ymd_and_hms(1960, 1, 1, 0, 0, 0).unwrap()), 3600);
+    }
+
+    #[test]
+    fn normalizes_zoned_properties_to_utc() {
+        let mut calendar = ICalComponent::parse(MADRID).unwrap();
+        normalize_to_utc(&mut calendar, None);
+        let event = calendar.components_named("VEVENT").next().unwrap();
+        let start = event.property("DTSTART").unwrap();
+        assert_eq!(start.value, "20240710T070000Z");
+        assert_eq!(start.param("TZID"), None);
+        assert_eq!(event.property("EXDATE").unwrap().value, "20240110T080000Z,20240111T080000Z");
+        // Los VTIMEZONE se conservan para volver a emitirlos
+        assert!(timezones_of(&calendar)[0].to_calendar().contains("TZID:Europe/Madrid\r\n"));
+    }
+
+    #[test]
+    fn resolves_floating_and_unknown_zones() {
+        let zone = VTimeZone::parse(MADRID).unwrap();
+        let floating = ICalComponent::parse("BEGIN:VEVENT\nDTSTART:20240110T090000\nDTEND;TZID=America/Lima:20240110T100000\nEND:VEVENT").unwrap();
+        let start = floating.property("DTSTART").unwrap();
+        assert_eq!(resolve_date_time(start, &[], Some(&zone)).unwrap().0, Utc.with_ymd_and_hms(2024, 1, 10, 8, 0, 0).unwrap());
+        assert_eq!(resolve_date_time(start, &[], None).unwrap().0, Utc.with_ymd_and_hms(2024, 1, 10, 9, 0, 0).unwrap());
+        let end = floating.property("DTEND").unwrap();
+        assert_eq!(resolve_date_time(end, &[zone], None).unwrap().0, Utc.with_ymd_and_hms(2024, 1, 10, 10, 0, 0).unwrap());
+        assert_eq!(parse_utc_offset("-0530"), Some(-19800));
+        assert_eq!(parse_utc_offset("0100"), None);
+    }
+}
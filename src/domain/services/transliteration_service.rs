@@ -0,0 +1,211 @@
+//! Transliteración de nombres de fichero a ASCII.
+//!
+//! Algunos clientes ignoran `filename*` (RFC 8187) en Content-Disposition y
+//! solo leen `filename`, que tiene que ser ASCII. En vez de cambiar cada
+//! carácter por `_`, se escribe con letras latinas: `Ñandú` pasa a `Nandu`,
+//! `Привет` a `Privet` y `Αθήνα` a `Athina`, de modo que el nombre sigue
+//! siendo reconocible.
+
+/// Equivalente ASCII de un carácter latino con diacríticos o ligadura
+fn latin(c: char) -> Option<&'static str> {
+    Some(match c {
+        'À' | 'Á' | 'Â' | 'Ã' | 'Ā' | 'Ă' | 'Ą' => "A",
+        'à' | 'á' | 'â' | 'ã' | 'ā' | 'ă' | 'ą' => "a",
+        'Ä' => "Ae",
+        'ä' => "ae",
+        'Å' => "A",
+        'å' => "a",
+        'Æ' => "AE",
+        'æ' => "ae",
+        'Ç' | 'Ć' | 'Ĉ' | 'Ċ' | 'Č' => "C",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'Ð' | 'Ď' | 'Đ' => "D",
+        'ð' | 'ď' | 'đ' => "d",
+        'È' | 'É' | 'Ê' | 'Ë' | 'Ē' | 'Ĕ' | 'Ė' | 'Ę' | 'Ě' => "E",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'Ĝ' | 'Ğ' | 'Ġ' | 'Ģ' => "G",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'Ĥ' | 'Ħ' => "H",
+        'ĥ' | 'ħ' => "h",
+        'Ì' | 'Í' | 'Î' | 'Ï' | 'Ĩ' | 'Ī' | 'Ĭ' | 'Į' | 'İ' => "I",
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'Ĳ' => "IJ",
+        'ĳ' => "ij",
+        'Ĵ' => "J",
+        'ĵ' => "j",
+        'Ķ' => "K",
+        'ķ' => "k",
+        'Ĺ' | 'Ļ' | 'Ľ' | 'Ŀ' | 'Ł' => "L",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'Ñ' | 'Ń' | 'Ņ' | 'Ň' => "N",
+        'ñ' | 'ń' | 'ņ' | 'ň' => "n",
+        'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ō' | 'Ŏ' | 'Ő' | 'Ø' => "O",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ō' | 'ŏ' | 'ő' | 'ø' => "o",
+        'Ö' => "Oe",
+        'ö' => "oe",
+        'Œ' => "OE",
+        'œ' => "oe",
+        'Ŕ' | 'Ŗ' | 'Ř' => "R",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'Ś' | 'Ŝ' | 'Ş' | 'Š' | 'Ș' => "S",
+        'ś' | 'ŝ' | 'ş' | 'š' | 'ș' => "s",
+        'ß' => "ss",
+        'Ţ' | 'Ť' | 'Ŧ' | 'Ț' => "T",
+        'ţ' | 'ť' | 'ŧ' | 'ț' => "t",
+        'Þ' => "Th",
+        'þ' => "th",
+        'Ù' | 'Ú' | 'Û' | 'Ũ' | 'Ū' | 'Ŭ' | 'Ů' | 'Ű' | 'Ų' => "U",
+        'ù' | 'ú' | 'û' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'Ü' => "Ue",
+        'ü' => "ue",
+        'Ŵ' => "W",
+        'ŵ' => "w",
+        'Ý' | 'Ŷ' | 'Ÿ' => "Y",
+        'ý' | 'ŷ' | 'ÿ' => "y",
+        'Ź' | 'Ż' | 'Ž' => "Z",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    })
+}
+
+/// Equivalente ASCII de una letra cirílica (rusa, ucraniana, bielorrusa,
+/// búlgara y serbia), según la romanización ICAO de los pasaportes
+fn cyrillic(c: char) -> Option<&'static str> {
+    Some(match c {
+        'А' => "A", 'а' => "a",
+        'Б' => "B", 'б' => "b",
+        'В' => "V", 'в' => "v",
+        'Г' => "G", 'г' => "g",
+        'Ґ' => "G", 'ґ' => "g",
+        'Д' => "D", 'д' => "d",
+        'Ђ' => "Dj", 'ђ' => "dj",
+        'Е' => "E", 'е' => "e",
+        'Ё' => "E", 'ё' => "e",
+        'Є' => "Ie", 'є' => "ie",
+        'Ж' => "Zh", 'ж' => "zh",
+        'З' => "Z", 'з' => "z",
+        'И' => "I", 'и' => "i",
+        'І' => "I", 'і' => "i",
+        'Ї' => "I", 'ї' => "i",
+        'Й' => "I", 'й' => "i",
+        'Ј' => "J", 'ј' => "j",
+        'К' => "K", 'к' => "k",
+        'Л' => "L", 'л' => "l",
+        'Љ' => "Lj", 'љ' => "lj",
+        'М' => "M", 'м' => "m",
+        'Н' => "N", 'н' => "n",
+        'Њ' => "Nj", 'њ' => "nj",
+        'О' => "O", 'о' => "o",
+        'П' => "P", 'п' => "p",
+        'Р' => "R", 'р' => "r",
+        'С' => "S", 'с' => "s",
+        'Т' => "T", 'т' => "t",
+        'Ћ' => "C", 'ћ' => "c",
+        'У' => "U", 'у' => "u",
+        'Ў' => "U", 'ў' => "u",
+        'Ф' => "F", 'ф' => "f",
+        'Х' => "Kh", 'х' => "kh",
+        'Ц' => "Ts", 'ц' => "ts",
+        'Ч' => "Ch", 'ч' => "ch",
+        'Џ' => "Dz", 'џ' => "dz",
+        'Ш' => "Sh", 'ш' => "sh",
+        'Щ' => "Shch", 'щ' => "shch",
+        'Ъ' => "Ie", 'ъ' => "ie",
+        'Ы' => "Y", 'ы' => "y",
+        'Ь' | 'ь' => "",
+        'Э' => "E", 'э' => "e",
+        'Ю' => "Iu", 'ю' => "iu",
+        'Я' => "Ia", 'я' => "ia",
+        _ => return None,
+    })
+}
+
+/// Equivalente ASCII de una letra griega (ISO 843, sin los acentos)
+fn greek(c: char) -> Option<&'static str> {
+    Some(match c {
+        'Α' | 'Ά' => "A", 'α' | 'ά' => "a",
+        'Β' => "V", 'β' => "v",
+        'Γ' => "G", 'γ' => "g",
+        'Δ' => "D", 'δ' => "d",
+        'Ε' | 'Έ' => "E", 'ε' | 'έ' => "e",
+        'Ζ' => "Z", 'ζ' => "z",
+        'Η' | 'Ή' => "I", 'η' | 'ή' => "i",
+        'Θ' => "Th", 'θ' => "th",
+        'Ι' | 'Ί' | 'Ϊ' => "I", 'ι' | 'ί' | 'ϊ' | 'ΐ' => "i",
+        'Κ' => "K", 'κ' => "k",
+        'Λ' => "L", 'λ' => "l",
+        'Μ' => "M", 'μ' => "m",
+        'Ν' => "N", 'ν' => "n",
+        'Ξ' => "X", 'ξ' => "x",
+        'Ο' | 'Ό' => "O", 'ο' | 'ό' => "o",
+        'Π' => "P", 'π' => "p",
+        'Ρ' => "R", 'ρ' => "r",
+        'Σ' => "S", 'σ' | 'ς' => "s",
+        'Τ' => "T", 'τ' => "t",
+        'Υ' | 'Ύ' | 'Ϋ' => "Y", 'υ' | 'ύ' | 'ϋ' | 'ΰ' => "y",
+        'Φ' => "F", 'φ' => "f",
+        'Χ' => "Ch", 'χ' => "ch",
+        'Ψ' => "Ps", 'ψ' => "ps",
+        'Ω' | 'Ώ' => "O", 'ω' | 'ώ' => "o",
+        _ => return None,
+    })
+}
+
+/// Signos tipográficos habituales en nombres de fichero
+fn punctuation(c: char) -> Option<&'static str> {
+    Some(match c {
+        '‘' | '’' | '‚' | '′' => "'",
+        '“' | '”' | '„' | '«' | '»' => "\"",
+        '‐' | '‑' | '‒' | '–' | '—' | '−' => "-",
+        '…' => "...",
+        '\u{00A0}' | '\u{2007}' | '\u{202F}' => " ",
+        '€' => "EUR",
+        '£' => "GBP",
+        '©' => "(c)",
+        '®' => "(R)",
+        '™' => "TM",
+        '°' => "deg",
+        '×' => "x",
+        _ => return None,
+    })
+}
+
+/// Escribe `name` solo con caracteres ASCII imprimibles. Lo que no tiene
+/// equivalente (ideogramas, emoji, controles) se cambia por `_`
+pub fn transliterate(name: &str) -> String {
+    let mut ascii = String::with_capacity(name.len());
+    for c in name.chars() {
+        if c.is_ascii_graphic() || c == ' ' {
+            ascii.push(c);
+        } else if let Some(replacement) = latin(c).or_else(|| cyrillic(c)).or_else(|| greek(c)).or_else(|| punctuation(c)) {
+            ascii.push_str(replacement);
+        } else {
+            ascii.push('_');
+        }
+    }
+    ascii
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transliterate_scripts() {
+        assert_eq!(transliterate("plain name.txt"), "plain name.txt");
+        assert_eq!(transliterate("Ñandú é çà.pdf"), "Nandu e ca.pdf");
+        assert_eq!(transliterate("Größe Übung.odt"), "Groesse Uebung.odt");
+        assert_eq!(transliterate("Łódź Søren Æbleø"), "Lodz Soren AEbleo");
+        assert_eq!(transliterate("Привет, мир.docx"), "Privet, mir.docx");
+        assert_eq!(transliterate("Щука й Юля"), "Shchuka i Iulia");
+        assert_eq!(transliterate("Αθήνα.jpg"), "Athina.jpg");
+        assert_eq!(transliterate("Informe – «final»…"), "Informe - \"final\"...");
+    }
+
+    #[test]
+    fn test_transliterate_unknown_characters() {
+        assert_eq!(transliterate("報告 🎉.txt"), "__ _.txt");
+        assert_eq!(transliterate("tab\there"), "tab_here");
+        assert!(transliterate("東京").is_ascii());
+    }
+}
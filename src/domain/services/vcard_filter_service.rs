@@ -0,0 +1,236 @@
+use crate::domain::services::calendar_filter_service::Collation;
+use crate::domain::services::icalendar_service::{ICalComponent, ICalProperty};
+
+/// Forma de comparar el texto de un `text-match` (RFC 6352, 10.5.4)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatchType {
+    Equals,
+    #[default]
+    Contains,
+    StartsWith,
+    EndsWith,
+}
+
+impl MatchType {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "equals" => Some(Self::Equals),
+            "contains" => Some(Self::Contains),
+            "starts-with" => Some(Self::StartsWith),
+            "ends-with" => Some(Self::EndsWith),
+            _ => None,
+        }
+    }
+}
+
+/// Cómo se combinan las condiciones de un filtro (atributo `test`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FilterTest {
+    /// Basta con que se cumpla una (la de por defecto)
+    #[default]
+    AnyOf,
+    /// Se tienen que cumplir todas
+    AllOf,
+}
+
+impl FilterTest {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "anyof" => Some(Self::AnyOf),
+            "allof" => Some(Self::AllOf),
+            _ => None,
+        }
+    }
+
+    fn combine(self, mut results: impl Iterator<Item = bool>) -> bool {
+        match self {
+            Self::AnyOf => results.any(|result| result),
+            Self::AllOf => results.all(|result| result),
+        }
+    }
+}
+
+/// Comparación de texto de un filtro de contactos (RFC 6352, 10.5.4)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardTextMatch {
+    pub text: String,
+    pub collation: Collation,
+    pub match_type: MatchType,
+    /// `negate-condition="yes"`: coincide cuando la comparación falla
+    pub negate: bool,
+}
+
+impl CardTextMatch {
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            collation: Collation::default(),
+            match_type: MatchType::default(),
+            negate: false,
+        }
+    }
+
+    pub fn matches(&self, value: &str) -> bool {
+        let (value, text) = match self.collation {
+            Collation::Octet => (value.to_string(), self.text.clone()),
+            Collation::AsciiCasemap => (value.to_ascii_lowercase(), self.text.to_ascii_lowercase()),
+            Collation::UnicodeCasemap => (value.to_lowercase(), self.text.to_lowercase()),
+        };
+        let found = match self.match_type {
+            MatchType::Equals => value == text,
+            MatchType::Contains => value.contains(&text),
+            MatchType::StartsWith => value.starts_with(&text),
+            MatchType::EndsWith => value.ends_with(&text),
+        };
+        found != self.negate
+    }
+}
+
+/// Filtro sobre un parámetro de una propiedad (RFC 6352, 10.5.2)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardParamFilter {
+    pub name: String,
+    pub is_not_defined: bool,
+    pub text_match: Option<CardTextMatch>,
+}
+
+impl CardParamFilter {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_ascii_uppercase(),
+            is_not_defined: false,
+            text_match: None,
+        }
+    }
+
+    fn matches(&self, property: &ICalProperty) -> bool {
+        match property.param(&self.name) {
+            None => self.is_not_defined,
+            Some(_) if self.is_not_defined => false,
+            // TYPE puede traer varios valores separados por comas
+            Some(value) => self.text_match.as_ref().is_none_or(|text_match| {
+                text_match.matches(value) || value.split(',').any(|part| text_match.matches(part.trim()))
+            }),
+        }
+    }
+}
+
+/// Filtro sobre las propiedades de una vCard (RFC 6352, 10.5.1)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardPropFilter {
+    pub name: String,
+    pub test: FilterTest,
+    pub is_not_defined: bool,
+    pub text_matches: Vec<CardTextMatch>,
+    pub param_filters: Vec<CardParamFilter>,
+}
+
+impl CardPropFilter {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_ascii_uppercase(),
+            test: FilterTest::default(),
+            is_not_defined: false,
+            text_matches: Vec::new(),
+            param_filters: Vec::new(),
+        }
+    }
+
+    fn matches(&self, card: &ICalComponent) -> bool {
+        let mut properties = card.properties.iter()
+            .filter(|property| property_has_name(property, &self.name))
+            .peekable();
+        if self.is_not_defined {
+            return properties.peek().is_none();
+        }
+        properties.any(|property| self.matches_property(property))
+    }
+
+    /// Sin condiciones basta con que la propiedad exista
+    fn matches_property(&self, property: &ICalProperty) -> bool {
+        if self.text_matches.is_empty() && self.param_filters.is_empty() {
+            return true;
+        }
+        let value = property.text();
+        let text_results = self.text_matches.iter().map(|text_match| text_match.matches(&value));
+        let param_results = self.param_filters.iter().map(|filter| filter.matches(property));
+        self.test.combine(text_results.chain(param_results))
+    }
+}
+
+/// Filtro de un `addressbook-query` (RFC 6352, 10.5). Sin condiciones
+/// selecciona todas las vCards de la libreta.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct AddressbookFilter {
+    pub test: FilterTest,
+    pub prop_filters: Vec<CardPropFilter>,
+}
+
+impl AddressbookFilter {
+    pub fn matches(&self, card: &ICalComponent) -> bool {
+        if !card.name.eq_ignore_ascii_case("VCARD") {
+            return false;
+        }
+        if self.prop_filters.is_empty() {
+            return true;
+        }
+        self.test.combine(self.prop_filters.iter().map(|filter| filter.matches(card)))
+    }
+
+    /// Evalúa el filtro sobre el texto de una vCard; los datos que no se
+    /// pueden interpretar no coinciden con ningún filtro
+    pub fn matches_vcard(&self, vcard: &str) -> bool {
+        ICalComponent::parse(vcard).is_some_and(|card| self.matches(&card))
+    }
+}
+
+/// Compara el nombre de una propiedad sin tener en cuenta su grupo
+/// (`item1.EMAIL` es una propiedad EMAIL)
+fn property_has_name(property: &ICalProperty, name: &str) -> bool {
+    let own_name = property.name.rsplit_once('.').map_or(property.name.as_str(), |(_, name)| name);
+    own_name.eq_ignore_ascii_case(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARD: &str = "BEGIN:VCARD\r\nVERSION:3.0\r\nUID:ana\r\nFN:Ana García\r\nN:García;Ana;;;\r\nitem1.EMAIL;TYPE=WORK,INTERNET:ana@example.com\r\nTEL;TYPE=CELL:+34 600 000 000\r\nEND:VCARD\r\n";
+
+    fn prop_filter(name: &str, configure: impl FnOnce(&mut CardPropFilter)) -> AddressbookFilter {
+        let mut filter = CardPropFilter::new(name);
+        configure(&mut filter);
+        AddressbookFilter { test: FilterTest::AnyOf, prop_filters: vec![filter] }
+    }
+
+    #[test]
+    fn test_text_match_types() {
+        let mut text_match = CardTextMatch::new("ana");
+        text_match.match_type = MatchType::StartsWith;
+        assert!(prop_filter("FN", |filter| filter.text_matches.push(text_match.clone())).matches_vcard(CARD));
+        text_match.match_type = MatchType::Equals;
+        assert!(!prop_filter("FN", |filter| filter.text_matches.push(text_match.clone())).matches_vcard(CARD));
+        text_match.negate = true;
+        assert!(prop_filter("FN", |filter| filter.text_matches.push(text_match)).matches_vcard(CARD));
+    }
+
+    #[test]
+    fn test_grouped_properties_and_params() {
+        let mut param = CardParamFilter::new("type");
+        param.text_match = Some(CardTextMatch { match_type: MatchType::Equals, ..CardTextMatch::new("work") });
+        assert!(prop_filter("EMAIL", |filter| filter.param_filters.push(param)).matches_vcard(CARD));
+        assert!(prop_filter("NICKNAME", |filter| filter.is_not_defined = true).matches_vcard(CARD));
+        assert!(!prop_filter("TEL", |filter| filter.is_not_defined = true).matches_vcard(CARD));
+    }
+
+    #[test]
+    fn test_allof_requires_every_prop_filter() {
+        let mut filter = prop_filter("EMAIL", |filter| filter.text_matches.push(CardTextMatch::new("example.com")));
+        filter.prop_filters.push(CardPropFilter { text_matches: vec![CardTextMatch::new("Juan")], ..CardPropFilter::new("FN") });
+        assert!(filter.matches_vcard(CARD));
+        filter.test = FilterTest::AllOf;
+        assert!(!filter.matches_vcard(CARD));
+        assert!(AddressbookFilter::default().matches_vcard(CARD));
+        assert!(!AddressbookFilter::default().matches_vcard("not a card"));
+    }
+}
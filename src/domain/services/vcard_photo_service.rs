@@ -0,0 +1,279 @@
+use base64::Engine;
+use sha2::{Digest, Sha256};
+
+use crate::common::errors::DomainError;
+use crate::domain::services::vcard_service::{VCard, VCardProperty};
+
+/// Preferencia (RFC 7240) con la que un cliente CardDAV pide que se omitan
+/// las fotos que superen un tamaño: `Prefer: photo-max-size=65536`
+pub const PHOTO_SIZE_PREFERENCE: &str = "photo-max-size";
+
+/// Cómo viene la foto dentro de la vCard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PhotoSource {
+    /// Imagen incrustada en base64 (`ENCODING=b` o URI `data:`)
+    Inline(String),
+    /// Enlace externo a la imagen
+    Uri(String),
+}
+
+/// Propiedad PHOTO de una vCard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VCardPhoto {
+    pub media_type: Option<String>,
+    pub source: PhotoSource,
+}
+
+impl VCardPhoto {
+    /// Tamaño aproximado de la imagen ya decodificada; `None` si es un enlace
+    pub fn size(&self) -> Option<usize> {
+        match &self.source {
+            PhotoSource::Inline(data) => {
+                let padding = data.bytes().rev().take_while(|b| *b == b'=').count();
+                Some((data.len() * 3 / 4).saturating_sub(padding))
+            }
+            PhotoSource::Uri(_) => None,
+        }
+    }
+
+    /// Imagen decodificada; `None` si es un enlace o el base64 no es válido
+    pub fn decode(&self) -> Option<Vec<u8>> {
+        match &self.source {
+            PhotoSource::Inline(data) => base64::engine::general_purpose::STANDARD.decode(data).ok(),
+            PhotoSource::Uri(_) => None,
+        }
+    }
+
+    /// Huella SHA-256 de la foto, para que los clientes sepan si ha cambiado
+    /// sin descargarla
+    pub fn hash(&self) -> String {
+        let value = match &self.source {
+            PhotoSource::Inline(data) | PhotoSource::Uri(data) => data,
+        };
+        format!("{:x}", Sha256::digest(value.as_bytes()))
+    }
+}
+
+/// Agrupa las líneas físicas de la vCard en propiedades, uniendo las
+/// continuaciones (líneas que empiezan por espacio o tabulador)
+fn properties(vcard: &str) -> Vec<Vec<&str>> {
+    let mut properties: Vec<Vec<&str>> = Vec::new();
+    for line in vcard.split_inclusive('\n') {
+        match properties.last_mut() {
+            Some(current) if line.starts_with([' ', '\t']) => current.push(line),
+            _ => properties.push(vec![line]),
+        }
+    }
+    properties
+}
+
+/// Une una propiedad plegada en una sola línea lógica
+fn unfold(lines: &[&str]) -> String {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let line = line.trim_end_matches(['\r', '\n']);
+            if i == 0 { line } else { &line[1..] }
+        })
+        .collect()
+}
+
+/// Separa `grupo.NOMBRE;PARAMS:valor` en nombre (sin grupo, en mayúsculas),
+/// parámetros y valor
+fn split_property(line: &str) -> Option<(String, &str, &str)> {
+    let mut quoted = false;
+    let colon = line.char_indices().find_map(|(i, c)| {
+        match c {
+            '"' => quoted = !quoted,
+            ':' if !quoted => return Some(i),
+            _ => {}
+        }
+        None
+    })?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let (name, params) = head.split_once(';').unwrap_or((head, ""));
+    let name = name.rsplit('.').next().unwrap_or(name).to_ascii_uppercase();
+    Some((name, params, value))
+}
+
+fn parse_photo(params: &str, value: &str) -> VCardPhoto {
+    let value = value.trim();
+
+    // vCard 4: PHOTO:data:image/jpeg;base64,...
+    if let Some(data_uri) = value.strip_prefix("data:") {
+        if let Some((meta, data)) = data_uri.split_once(',') {
+            if let Some(media_type) = meta.strip_suffix(";base64") {
+                return VCardPhoto {
+                    media_type: (!media_type.is_empty()).then(|| media_type.to_ascii_lowercase()),
+                    source: PhotoSource::Inline(data.split_whitespace().collect()),
+                };
+            }
+        }
+    }
+
+    // vCard 2.1/3: PHOTO;ENCODING=b;TYPE=JPEG:... o PHOTO;ENCODING=BASE64;JPEG:...
+    let mut inline = false;
+    let mut media_type = None;
+    for param in params.split(';') {
+        let (key, val) = match param.split_once('=') {
+            Some((key, val)) => (key.trim().to_ascii_uppercase(), val.trim().trim_matches('"')),
+            None => ("TYPE".to_string(), param.trim()),
+        };
+        match key.as_str() {
+            "ENCODING" if matches!(val.to_ascii_lowercase().as_str(), "b" | "base64") => inline = true,
+            "TYPE" | "MEDIATYPE" if !val.is_empty() => {
+                let val = val.to_ascii_lowercase();
+                media_type = Some(if val.contains('/') { val } else { format!("image/{}", val) });
+            }
+            _ => {}
+        }
+    }
+
+    VCardPhoto {
+        media_type,
+        source: if inline {
+            PhotoSource::Inline(value.split_whitespace().collect())
+        } else {
+            PhotoSource::Uri(value.to_string())
+        },
+    }
+}
+
+/// Devuelve la primera foto de la vCard, si la tiene
+pub fn find_photo(vcard: &str) -> Option<VCardPhoto> {
+    properties(vcard).iter().find_map(|lines| {
+        let line = unfold(lines);
+        let (name, params, value) = split_property(&line)?;
+        (name == "PHOTO").then(|| parse_photo(params, value))
+    })
+}
+
+/// Quita de la vCard las fotos incrustadas que superen `max_bytes`.
+///
+/// Los enlaces se conservan porque no pesan; el resto de la vCard se devuelve
+/// sin tocar, con sus saltos de línea y plegados originales.
+pub fn strip_large_photos(vcard: &str, max_bytes: usize) -> String {
+    properties(vcard)
+        .into_iter()
+        .filter(|lines| {
+            let line = unfold(lines);
+            match split_property(&line) {
+                Some((name, params, value)) if name == "PHOTO" => {
+                    parse_photo(params, value).size().is_none_or(|size| size <= max_bytes)
+                }
+                _ => true,
+            }
+        })
+        .flatten()
+        .collect()
+}
+
+/// Reconoce el formato de una imagen por su cabecera; solo se aceptan los
+/// formatos que entienden los clientes de contactos
+pub fn sniff_image_type(content: &[u8]) -> Option<&'static str> {
+    match content {
+        [0xFF, 0xD8, 0xFF, ..] => Some("image/jpeg"),
+        [0x89, b'P', b'N', b'G', ..] => Some("image/png"),
+        [b'G', b'I', b'F', b'8', ..] => Some("image/gif"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some("image/webp"),
+        _ => None,
+    }
+}
+
+/// Sustituye las fotos de la vCard por la imagen incrustada en base64, con
+/// la sintaxis de su versión: `PHOTO;ENCODING=b;TYPE=JPEG:` en la 3.0 y una
+/// URI `data:` en la 4.0
+pub fn embed_photo(vcard: &str, content: &[u8], media_type: &str) -> Result<String, DomainError> {
+    let mut card = VCard::parse(vcard)?;
+    let data = base64::engine::general_purpose::STANDARD.encode(content);
+    let photo = if card.version() == "4.0" {
+        VCardProperty::new("PHOTO", format!("data:{};base64,{}", media_type, data))
+    } else {
+        let subtype = media_type.rsplit('/').next().unwrap_or(media_type).to_ascii_uppercase();
+        VCardProperty::new("PHOTO", data)
+            .with_param("ENCODING", "b")
+            .with_param("TYPE", &subtype)
+    };
+
+    let position = card.properties.iter()
+        .position(|property| property.name == "PHOTO")
+        .unwrap_or(card.properties.len());
+    card.properties.retain(|property| property.name != "PHOTO");
+    card.properties.insert(position.min(card.properties.len()), photo);
+    Ok(card.to_vcard())
+}
+
+/// Lee el tamaño máximo de foto de una cabecera `Prefer`
+pub fn photo_size_preference(prefer: &str) -> Option<usize> {
+    prefer.split([',', ';']).find_map(|token| {
+        let (name, value) = token.split_once('=')?;
+        if name.trim().eq_ignore_ascii_case(PHOTO_SIZE_PREFERENCE) {
+            value.trim().trim_matches('"').parse().ok()
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VCARD3: &str = "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Ana\r\nPHOTO;ENCODING=b;TYPE=JPEG:/9j/4AAQSkZJ\r\n RgABAQAAAQ==\r\nEMAIL:ana@example.com\r\nEND:VCARD\r\n";
+
+    #[test]
+    fn test_find_inline_photo() {
+        let photo = find_photo(VCARD3).unwrap();
+        assert_eq!(photo.media_type.as_deref(), Some("image/jpeg"));
+        assert_eq!(photo.source, PhotoSource::Inline("/9j/4AAQSkZJRgABAQAAAQ==".to_string()));
+        assert_eq!(photo.size(), Some(16));
+        assert_eq!(photo.hash().len(), 64);
+
+        let vcard4 = "BEGIN:VCARD\nVERSION:4.0\nitem1.PHOTO:data:image/png;base64,iVBORw0K\nEND:VCARD\n";
+        let photo = find_photo(vcard4).unwrap();
+        assert_eq!(photo.media_type.as_deref(), Some("image/png"));
+        assert_eq!(photo.size(), Some(6));
+
+        let linked = find_photo("BEGIN:VCARD\nPHOTO;VALUE=uri:https://example.com/a.jpg\nEND:VCARD\n").unwrap();
+        assert_eq!(linked.source, PhotoSource::Uri("https://example.com/a.jpg".to_string()));
+        assert_eq!(linked.size(), None);
+
+        assert!(find_photo("BEGIN:VCARD\nFN:Sin foto\nEND:VCARD\n").is_none());
+    }
+
+    #[test]
+    fn test_strip_large_photos() {
+        let stripped = strip_large_photos(VCARD3, 8);
+        assert_eq!(stripped, "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Ana\r\nEMAIL:ana@example.com\r\nEND:VCARD\r\n");
+
+        // Small photos and links are kept untouched
+        assert_eq!(strip_large_photos(VCARD3, 1024), VCARD3);
+        let linked = "BEGIN:VCARD\nPHOTO:https://example.com/a.jpg\nEND:VCARD\n";
+        assert_eq!(strip_large_photos(linked, 0), linked);
+    }
+
+    #[test]
+    fn test_embed_photo() {
+        let jpeg = [0xFF, 0xD8, 0xFF, 0xE0, 0x00];
+        assert_eq!(sniff_image_type(&jpeg), Some("image/jpeg"));
+        assert_eq!(sniff_image_type(b"plain text"), None);
+
+        let vcard = embed_photo(VCARD3, &jpeg, "image/jpeg").unwrap();
+        let photo = find_photo(&vcard).unwrap();
+        assert_eq!(photo.media_type.as_deref(), Some("image/jpeg"));
+        assert_eq!(photo.decode().unwrap(), jpeg);
+        assert!(vcard.contains("EMAIL:ana@example.com\r\n"));
+
+        let vcard4 = embed_photo("BEGIN:VCARD\nVERSION:4.0\nFN:Ana\nEND:VCARD\n", &jpeg, "image/jpeg").unwrap();
+        assert!(vcard4.contains("PHOTO:data:image/jpeg;base64,/9j/4AA=\r\n"));
+    }
+
+    #[test]
+    fn test_photo_size_preference() {
+        assert_eq!(photo_size_preference("photo-max-size=65536"), Some(65536));
+        assert_eq!(photo_size_preference("return=minimal, Photo-Max-Size=\"0\""), Some(0));
+        assert_eq!(photo_size_preference("return=minimal"), None);
+        assert_eq!(photo_size_preference("photo-max-size=big"), None);
+    }
+}
@@ -0,0 +1,421 @@
+use crate::common::errors::DomainError;
+use crate::domain::services::icalendar_service::{fold_line, unescape_text, unfold_lines};
+
+/// Parámetro de una propiedad vCard con sus valores ya separados por comas
+/// y sin comillas ni codificación RFC 6868
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VCardParam {
+    /// Nombre en mayúsculas
+    pub name: String,
+    pub values: Vec<String>,
+}
+
+/// Propiedad de una vCard (RFC 6350, 3.3): `[grupo.]NOMBRE;PARAM=valor:valor`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VCardProperty {
+    /// Grupo (`item1` en `item1.EMAIL`), tal y como aparece
+    pub group: Option<String>,
+    /// Nombre en mayúsculas
+    pub name: String,
+    pub params: Vec<VCardParam>,
+    /// Valor tal y como aparece, sin desescapar
+    pub value: String,
+}
+
+impl VCardProperty {
+    /// Crea una propiedad con un valor ya escapado
+    pub fn new(name: &str, value: impl Into<String>) -> Self {
+        Self {
+            group: None,
+            name: name.to_ascii_uppercase(),
+            params: Vec::new(),
+            value: value.into(),
+        }
+    }
+
+    /// Crea una propiedad de texto escapando el valor
+    pub fn text_value(name: &str, text: &str) -> Self {
+        Self::new(name, escape_text(text))
+    }
+
+    /// Añade un parámetro
+    pub fn with_param(mut self, name: &str, value: &str) -> Self {
+        self.params.push(VCardParam { name: name.to_ascii_uppercase(), values: vec![value.to_string()] });
+        self
+    }
+
+    /// Primer valor de un parámetro, sin distinguir mayúsculas en el nombre
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.iter()
+            .filter(|param| param.name.eq_ignore_ascii_case(name))
+            .find_map(|param| param.values.first())
+            .map(String::as_str)
+    }
+
+    /// Todos los valores de un parámetro, aunque aparezca varias veces
+    /// (`TYPE=work;TYPE=voice` y `TYPE=work,voice` son lo mismo)
+    pub fn param_values<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.params.iter()
+            .filter(move |param| param.name.eq_ignore_ascii_case(name))
+            .flat_map(|param| param.values.iter().map(String::as_str))
+    }
+
+    /// Valores de TYPE en minúsculas; `TYPE="voice,home"` son dos valores
+    pub fn types(&self) -> Vec<String> {
+        self.param_values("TYPE")
+            .flat_map(|value| value.split(','))
+            .map(|value| value.trim().to_ascii_lowercase())
+            .filter(|value| !value.is_empty())
+            .collect()
+    }
+
+    /// Si el cliente la marcó como preferida (`PREF=1` en 4.0, `TYPE=pref` en 3.0)
+    pub fn is_preferred(&self) -> bool {
+        self.param("PREF").is_some() || self.types().iter().any(|value| value == "pref")
+    }
+
+    /// Valor de texto con las secuencias de escape resueltas
+    pub fn text(&self) -> String {
+        unescape_text(&self.value)
+    }
+
+    /// Componentes de un valor estructurado (N, ADR, ORG), separados por `;`
+    pub fn components(&self) -> Vec<String> {
+        split_unescaped(&self.value, ';').iter().map(|part| unescape_text(part)).collect()
+    }
+
+    /// Valores de una lista (CATEGORIES, NICKNAME), separados por `,`
+    pub fn list(&self) -> Vec<String> {
+        split_unescaped(&self.value, ',').iter()
+            .map(|part| unescape_text(part))
+            .filter(|value| !value.is_empty())
+            .collect()
+    }
+
+    /// Sustituye el valor por un texto estructurado, escapando cada componente
+    pub fn set_components(&mut self, components: &[String]) {
+        self.value = components.iter().map(|part| escape_text(part)).collect::<Vec<_>>().join(";");
+    }
+
+    fn to_line(&self) -> String {
+        let mut line = match &self.group {
+            Some(group) => format!("{}.{}", group, self.name),
+            None => self.name.clone(),
+        };
+        for param in &self.params {
+            let values: Vec<String> = param.values.iter().map(|value| encode_param_value(value)).collect();
+            line.push_str(&format!(";{}={}", param.name, values.join(",")));
+        }
+        line.push(':');
+        line.push_str(&self.value);
+        line
+    }
+}
+
+/// vCard (RFC 6350) como lista ordenada de propiedades.
+///
+/// Conserva todas las propiedades, también las desconocidas y las `X-`, así
+/// que interpretar y volver a serializar una vCard no pierde información.
+/// Entiende las versiones 2.1, 3.0 y 4.0: líneas plegadas, grupos, parámetros
+/// entre comillas o con varios valores, TYPE sin nombre de la 2.1 y valores
+/// QUOTED-PRINTABLE.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct VCard {
+    pub properties: Vec<VCardProperty>,
+}
+
+impl VCard {
+    /// Interpreta la primera vCard de los datos
+    pub fn parse(data: &str) -> Result<Self, DomainError> {
+        let mut card: Option<VCard> = None;
+        let mut lines = unfold_lines(data).into_iter();
+        while let Some(mut line) = lines.next() {
+            // En QUOTED-PRINTABLE un `=` final une la línea con la siguiente
+            if is_quoted_printable(&line) {
+                while line.ends_with('=') {
+                    let Some(next) = lines.next() else { break };
+                    line.pop();
+                    line.push_str(&next);
+                }
+            }
+            let Some(property) = parse_content_line(&line) else { continue };
+            match (property.name.as_str(), card.as_mut()) {
+                ("BEGIN", None) if property.value.trim().eq_ignore_ascii_case("VCARD") => {
+                    card = Some(VCard::default());
+                }
+                ("END", Some(_)) if property.value.trim().eq_ignore_ascii_case("VCARD") => {
+                    return Ok(card.unwrap_or_default());
+                }
+                (_, Some(card)) => card.properties.push(property),
+                (_, None) => {}
+            }
+        }
+        match card {
+            // Se acepta una vCard a la que le falta el END final
+            Some(card) if !card.properties.is_empty() => Ok(card),
+            _ => Err(DomainError::validation_error("The data does not contain a vCard")),
+        }
+    }
+
+    /// Versión declarada; 3.0 si no declara ninguna
+    pub fn version(&self) -> &str {
+        self.property("VERSION").map_or("3.0", |property| property.value.trim())
+    }
+
+    /// Primera propiedad con ese nombre, con o sin grupo
+    pub fn property(&self, name: &str) -> Option<&VCardProperty> {
+        self.properties.iter().find(|property| property.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Todas las propiedades con ese nombre
+    pub fn properties_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a VCardProperty> + 'a {
+        self.properties.iter().filter(move |property| property.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Texto de la primera propiedad con ese nombre
+    pub fn text(&self, name: &str) -> Option<String> {
+        self.property(name).map(VCardProperty::text)
+    }
+
+    /// Serializa la vCard con finales CRLF y las líneas plegadas a 75 octetos
+    pub fn to_vcard(&self) -> String {
+        let mut out = String::from("BEGIN:VCARD\r\n");
+        for property in &self.properties {
+            fold_line(&property.to_line(), &mut out);
+        }
+        out.push_str("END:VCARD\r\n");
+        out
+    }
+}
+
+/// Escapa un valor de texto (RFC 6350, 3.4)
+pub fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Separa por un delimitador que no esté escapado con `\`, dejando los
+/// trozos todavía escapados
+fn split_unescaped(value: &str, delimiter: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+    for (index, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            c if c == delimiter => {
+                parts.push(&value[start..index]);
+                start = index + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&value[start..]);
+    parts
+}
+
+fn is_quoted_printable(line: &str) -> bool {
+    let head = line.split(':').next().unwrap_or_default().to_ascii_uppercase();
+    head.contains("QUOTED-PRINTABLE")
+}
+
+/// Separa una línea de contenido en grupo, nombre, parámetros y valor
+fn parse_content_line(line: &str) -> Option<VCardProperty> {
+    let mut in_quotes = false;
+    let mut value_start = None;
+    let mut separators = Vec::new();
+    for (index, c) in line.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ';' if !in_quotes => separators.push(index),
+            ':' if !in_quotes => {
+                value_start = Some(index);
+                break;
+            }
+            _ => {}
+        }
+    }
+    let value_start = value_start?;
+    let name_end = separators.first().copied().unwrap_or(value_start);
+    let full_name = line[..name_end].trim();
+    let (group, name) = match full_name.rsplit_once('.') {
+        Some((group, name)) => (Some(group.to_string()), name),
+        None => (None, full_name),
+    };
+    if name.is_empty() {
+        return None;
+    }
+
+    let mut params: Vec<VCardParam> = Vec::new();
+    for (i, start) in separators.iter().enumerate() {
+        let end = separators.get(i + 1).copied().unwrap_or(value_start);
+        let raw = line[start + 1..end].trim();
+        if raw.is_empty() {
+            continue;
+        }
+        let (param_name, raw_values) = match raw.split_once('=') {
+            Some((param_name, raw_values)) => (param_name.trim().to_ascii_uppercase(), raw_values),
+            // vCard 2.1: `TEL;CELL;VOICE:` son valores de TYPE sin nombre
+            None if matches!(raw.to_ascii_uppercase().as_str(), "QUOTED-PRINTABLE" | "BASE64" | "8BIT") => {
+                ("ENCODING".to_string(), raw)
+            }
+            None => ("TYPE".to_string(), raw),
+        };
+        params.push(VCardParam { name: param_name, values: parse_param_values(raw_values) });
+    }
+
+    let mut value = line[value_start + 1..].to_string();
+    let quoted_printable = params.iter()
+        .any(|param| param.name == "ENCODING" && param.values.iter().any(|v| v.eq_ignore_ascii_case("QUOTED-PRINTABLE")));
+    if quoted_printable {
+        value = decode_quoted_printable(&value);
+        params.retain(|param| param.name != "ENCODING" && param.name != "CHARSET");
+    }
+
+    Some(VCardProperty {
+        group,
+        name: name.to_ascii_uppercase(),
+        params,
+        value,
+    })
+}
+
+/// Separa los valores de un parámetro por comas fuera de las comillas y
+/// resuelve la codificación `^n`, `^^` y `^'` (RFC 6868)
+fn parse_param_values(raw: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    for c in raw.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => values.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    values.push(current);
+    values.into_iter()
+        .map(|value| decode_caret(value.trim()))
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+fn decode_caret(value: &str) -> String {
+    let mut decoded = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '^' {
+            decoded.push(c);
+            continue;
+        }
+        match chars.peek() {
+            Some('n') | Some('N') => decoded.push('\n'),
+            Some('^') => decoded.push('^'),
+            Some('\'') => decoded.push('"'),
+            _ => {
+                decoded.push('^');
+                continue;
+            }
+        }
+        chars.next();
+    }
+    decoded
+}
+
+/// Escribe un valor de parámetro, entre comillas si hace falta
+fn encode_param_value(value: &str) -> String {
+    let encoded = value.replace('^', "^^").replace('\n', "^n").replace('"', "^'");
+    if encoded.contains([':', ';', ',']) {
+        format!("\"{}\"", encoded)
+    } else {
+        encoded
+    }
+}
+
+/// Decodifica un valor QUOTED-PRINTABLE de la vCard 2.1 y lo deja escapado
+/// como un valor de texto de la 3.0
+fn decode_quoted_printable(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'=' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+            if let Some(byte) = hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    let text = String::from_utf8_lossy(&decoded).replace("\r\n", "\n");
+    // Los `;` que separan componentes siguen siendo separadores
+    text.split(';').map(escape_text).collect::<Vec<_>>().join(";")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARD: &str = "BEGIN:VCARD\r\nVERSION:3.0\r\nUID:ana\r\nFN:Ana García\\, PhD\r\nN:García;Ana;María;Dra.;\r\nNOTE:Primera línea\\nsegunda con un texto lo bastante largo para plegarse\r\n  en dos líneas\r\nitem1.EMAIL;TYPE=WORK,INTERNET;TYPE=pref:ana@example.com\r\nitem1.X-ABLabel:Oficina\r\nCATEGORIES:Amigos,Trabajo\\, equipo\r\nX-CUSTOM;X-PARAM=\"a:b\":valor\r\nEND:VCARD\r\n";
+
+    #[test]
+    fn test_parse_folded_escaped_and_structured_values() {
+        let card = VCard::parse(CARD).unwrap();
+        assert_eq!(card.version(), "3.0");
+        assert_eq!(card.text("FN").unwrap(), "Ana García, PhD");
+        assert_eq!(card.text("NOTE").unwrap(), "Primera línea\nsegunda con un texto lo bastante largo para plegarse en dos líneas");
+        assert_eq!(card.property("N").unwrap().components(), vec!["García", "Ana", "María", "Dra.", ""]);
+        assert_eq!(card.property("CATEGORIES").unwrap().list(), vec!["Amigos", "Trabajo, equipo"]);
+
+        let email = card.property("EMAIL").unwrap();
+        assert_eq!(email.group.as_deref(), Some("item1"));
+        assert_eq!(email.types(), vec!["work", "internet", "pref"]);
+        assert!(email.is_preferred());
+        assert_eq!(card.property("X-CUSTOM").unwrap().param("x-param"), Some("a:b"));
+    }
+
+    #[test]
+    fn test_round_trip_keeps_every_property() {
+        let card = VCard::parse(CARD).unwrap();
+        let serialized = card.to_vcard();
+        assert!(serialized.lines().all(|line| line.trim_end_matches('\r').len() <= 75));
+        assert_eq!(VCard::parse(&serialized).unwrap(), card);
+        assert!(serialized.contains("item1.X-ABLABEL:Oficina\r\n"));
+        assert!(serialized.contains("X-CUSTOM;X-PARAM=\"a:b\":valor\r\n"));
+    }
+
+    #[test]
+    fn test_vcard_21_and_40_forms() {
+        let old = "BEGIN:VCARD\nVERSION:2.1\nTEL;CELL;VOICE:+34 600\nNOTE;ENCODING=QUOTED-PRINTABLE;CHARSET=UTF-8:Caf=C3=A9 y =\nm=C3=A1s\nEND:VCARD\n";
+        let card = VCard::parse(old).unwrap();
+        assert_eq!(card.property("TEL").unwrap().types(), vec!["cell", "voice"]);
+        let note = card.property("NOTE").unwrap();
+        assert_eq!(note.text(), "Café y más");
+        assert!(note.params.is_empty());
+
+        let modern = "BEGIN:VCARD\r\nVERSION:4.0\r\nTEL;VALUE=uri;PREF=1;TYPE=\"voice,home\":tel:+1-555\r\nEMAIL;LABEL=\"Line^nTwo ^'x^'\":a@b.c\r\nEND:VCARD\r\n";
+        let card = VCard::parse(modern).unwrap();
+        assert_eq!(card.version(), "4.0");
+        let tel = card.property("TEL").unwrap();
+        assert_eq!(tel.types(), vec!["voice", "home"]);
+        assert!(tel.is_preferred());
+        assert_eq!(card.property("EMAIL").unwrap().param("LABEL"), Some("Line\nTwo \"x\""));
+        assert_eq!(VCard::parse(&card.to_vcard()).unwrap(), card);
+
+        assert!(VCard::parse("not a card").is_err());
+    }
+}
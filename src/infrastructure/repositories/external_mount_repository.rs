@@ -0,0 +1,568 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use bytes::Bytes;
+use futures::{Stream, StreamExt, TryStreamExt};
+
+use crate::application::ports::external_storage_ports::{ExternalEntry, ExternalStoragePort};
+use crate::application::ports::outbound::{FileStoragePort, FolderStoragePort};
+use crate::application::services::external_storage_service::{current_mount_caller, ExternalStorageService};
+use crate::common::errors::{DomainError, ErrorKind};
+use crate::domain::entities::file::File;
+use crate::domain::entities::folder::Folder;
+use crate::domain::services::path_service::StoragePath;
+
+/// Prefijo de los IDs de archivos y carpetas que viven en un montaje externo
+const EXTERNAL_ID_PREFIX: &str = "ext.";
+
+/// Carpeta virtual bajo la que se muestran las rutas de los montajes
+const EXTERNAL_PATH_ROOT: &str = ".external";
+
+/// ID estable de una entrada de un montaje: el montaje y la ruta van
+/// codificados, así no hace falta guardar ningún mapeo
+pub fn external_id(mount: &str, path: &str) -> String {
+    format!("{}{}.{}", EXTERNAL_ID_PREFIX, URL_SAFE_NO_PAD.encode(mount), URL_SAFE_NO_PAD.encode(path))
+}
+
+/// Montaje y ruta de un ID de `external_id`; `None` para los IDs locales
+pub fn parse_external_id(id: &str) -> Option<(String, String)> {
+    let (mount, path) = id.strip_prefix(EXTERNAL_ID_PREFIX)?.split_once('.')?;
+    let mount = String::from_utf8(URL_SAFE_NO_PAD.decode(mount).ok()?).ok()?;
+    let path = String::from_utf8(URL_SAFE_NO_PAD.decode(path).ok()?).ok()?;
+    let path = ExternalStorageService::normalize_path(&path).ok()?;
+    (!mount.is_empty()).then_some((mount, path))
+}
+
+fn join_path(base: &str, name: &str) -> String {
+    if base.is_empty() { name.to_string() } else { format!("{}/{}", base, name) }
+}
+
+fn parent_path(path: &str) -> &str {
+    path.rsplit_once('/').map(|(parent, _)| parent).unwrap_or("")
+}
+
+fn virtual_path(mount: &str, path: &str) -> StoragePath {
+    StoragePath::from_string(&join_path(&format!("{}/{}", EXTERNAL_PATH_ROOT, mount), path))
+}
+
+/// Carpeta padre de una entrada; la raíz de un montaje cuelga de la raíz
+fn parent_id(mount: &str, path: &str) -> Option<String> {
+    (!path.is_empty()).then(|| external_id(mount, parent_path(path)))
+}
+
+fn invalid_name(name: &str) -> DomainError {
+    DomainError::validation_error(format!("Invalid name: {}", name))
+}
+
+fn check_name(name: &str) -> Result<(), DomainError> {
+    if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\']) {
+        return Err(invalid_name(name));
+    }
+    Ok(())
+}
+
+fn not_supported(operation: &str) -> DomainError {
+    DomainError::operation_not_supported("ExternalMount", format!("{} is not available on external mounts", operation))
+}
+
+/// Acceso a los montajes externos desde el árbol de carpetas, con el
+/// llamante de la petición en curso
+#[derive(Clone)]
+struct Mounts {
+    service: Arc<ExternalStorageService>,
+}
+
+impl Mounts {
+    async fn backend(&self, mount: &str, write: bool) -> Result<Arc<dyn ExternalStoragePort>, DomainError> {
+        match current_mount_caller() {
+            Some(caller) => self.service.mount_for_caller(&caller, mount, write).await,
+            // Fuera de una petición (tareas de fondo) no se sabe quién accede;
+            // no se responde NotFound para que nadie dé la entrada por borrada
+            None => Err(DomainError::new(
+                ErrorKind::Unavailable,
+                "ExternalMount",
+                format!("Mount '{}' is only reachable within a user request", mount),
+            )),
+        }
+    }
+
+    /// Raíces de los montajes que puede usar el llamante
+    async fn roots(&self) -> Result<Vec<Folder>, DomainError> {
+        let Some(caller) = current_mount_caller() else {
+            return Ok(Vec::new());
+        };
+        let mut roots = Vec::new();
+        for name in self.service.mount_names() {
+            if self.service.mount_for_caller(&caller, &name, false).await.is_ok() {
+                roots.push(root_folder(&name)?);
+            }
+        }
+        Ok(roots)
+    }
+
+    async fn entry(&self, mount: &str, path: &str) -> Result<ExternalEntry, DomainError> {
+        self.backend(mount, false).await?.stat(path).await
+    }
+
+    async fn folder(&self, mount: &str, path: &str) -> Result<Folder, DomainError> {
+        if path.is_empty() {
+            self.backend(mount, false).await?;
+            return root_folder(mount);
+        }
+        let entry = self.entry(mount, path).await?;
+        if !entry.is_dir {
+            return Err(DomainError::not_found("Folder", external_id(mount, path)));
+        }
+        to_folder(mount, &entry)
+    }
+
+    async fn file(&self, mount: &str, path: &str) -> Result<File, DomainError> {
+        let entry = self.entry(mount, path).await?;
+        if entry.is_dir {
+            return Err(DomainError::not_found("File", external_id(mount, path)));
+        }
+        to_file(mount, &entry)
+    }
+
+    /// Borra una entrada y, si es un directorio, todo su contenido
+    async fn delete_tree(&self, mount: &str, path: &str) -> Result<(), DomainError> {
+        if path.is_empty() {
+            return Err(DomainError::new(ErrorKind::InvalidInput, "ExternalMount", "The mount root cannot be deleted"));
+        }
+        let backend = self.backend(mount, true).await?;
+        let mut pending = vec![path.to_string()];
+        let mut directories = Vec::new();
+        while let Some(dir) = pending.pop() {
+            for entry in backend.list(&dir).await? {
+                if entry.is_dir {
+                    pending.push(entry.path);
+                } else {
+                    backend.delete(&entry.path).await?;
+                }
+            }
+            directories.push(dir);
+        }
+        // Los hijos se visitan después que sus padres
+        for dir in directories.iter().rev() {
+            backend.delete(dir).await?;
+        }
+        Ok(())
+    }
+
+    async fn rename(&self, mount: &str, from: &str, to: &str) -> Result<(), DomainError> {
+        let backend = self.backend(mount, true).await?;
+        match backend.stat(to).await {
+            Ok(_) => return Err(DomainError::already_exists("ExternalFile", to)),
+            Err(e) if e.kind == ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        backend.rename(from, to).await
+    }
+}
+
+fn root_folder(mount: &str) -> Result<Folder, DomainError> {
+    Folder::with_timestamps(external_id(mount, ""), mount.to_string(), virtual_path(mount, ""), None, 0, 0)
+        .map_err(|e| DomainError::internal_error("ExternalMount", e.to_string()))
+}
+
+fn to_folder(mount: &str, entry: &ExternalEntry) -> Result<Folder, DomainError> {
+    Folder::with_timestamps(
+        external_id(mount, &entry.path),
+        entry.name.clone(),
+        virtual_path(mount, &entry.path),
+        parent_id(mount, &entry.path),
+        entry.modified_at,
+        entry.modified_at,
+    )
+    .map_err(|e| DomainError::internal_error("ExternalMount", e.to_string()))
+}
+
+fn to_file(mount: &str, entry: &ExternalEntry) -> Result<File, DomainError> {
+    File::with_timestamps(
+        external_id(mount, &entry.path),
+        entry.name.clone(),
+        virtual_path(mount, &entry.path),
+        entry.size,
+        mime_guess::from_path(&entry.name).first_or_octet_stream().to_string(),
+        parent_id(mount, &entry.path),
+        entry.modified_at,
+        entry.modified_at,
+    )
+    .map_err(|e| DomainError::internal_error("ExternalMount", e.to_string()))
+}
+
+fn single_chunk(content: Vec<u8>) -> crate::application::ports::external_storage_ports::ExternalByteStream {
+    Box::pin(futures::stream::once(async move { Ok(Bytes::from(content)) }))
+}
+
+/// Repositorio de carpetas que añade los montajes externos al árbol.
+///
+/// Cada montaje aparece como una carpeta raíz más, solo para quien puede
+/// usarlo (ver `with_mount_caller`). Las operaciones sobre IDs locales pasan
+/// tal cual al repositorio envuelto.
+pub struct ExternalMountFolderRepository {
+    inner: Arc<dyn FolderStoragePort>,
+    mounts: Mounts,
+}
+
+impl ExternalMountFolderRepository {
+    pub fn new(inner: Arc<dyn FolderStoragePort>, service: Arc<ExternalStorageService>) -> Self {
+        Self { inner, mounts: Mounts { service } }
+    }
+}
+
+#[async_trait]
+impl FolderStoragePort for ExternalMountFolderRepository {
+    async fn create_folder(&self, name: String, parent_id: Option<String>) -> Result<Folder, DomainError> {
+        let Some((mount, parent)) = parent_id.as_deref().and_then(parse_external_id) else {
+            return self.inner.create_folder(name, parent_id).await;
+        };
+        check_name(&name)?;
+        let path = join_path(&parent, &name);
+        let backend = self.mounts.backend(&mount, true).await?;
+        if backend.stat(&path).await.is_ok() {
+            return Err(DomainError::already_exists("Folder", &name));
+        }
+        backend.create_dir(&path).await?;
+        self.mounts.folder(&mount, &path).await
+    }
+
+    async fn get_folder(&self, id: &str) -> Result<Folder, DomainError> {
+        match parse_external_id(id) {
+            Some((mount, path)) => self.mounts.folder(&mount, &path).await,
+            None => self.inner.get_folder(id).await,
+        }
+    }
+
+    async fn get_folder_by_path(&self, storage_path: &StoragePath) -> Result<Folder, DomainError> {
+        self.inner.get_folder_by_path(storage_path).await
+    }
+
+    async fn list_folders(&self, parent_id: Option<&str>) -> Result<Vec<Folder>, DomainError> {
+        match parent_id.map(|id| (id, parse_external_id(id))) {
+            None => {
+                let mut folders = self.inner.list_folders(None).await?;
+                folders.extend(self.mounts.roots().await?);
+                Ok(folders)
+            }
+            Some((_, Some((mount, path)))) => {
+                let mut entries = self.mounts.backend(&mount, false).await?.list(&path).await?;
+                entries.sort_by(|a, b| a.name.cmp(&b.name));
+                entries.iter().filter(|entry| entry.is_dir).map(|entry| to_folder(&mount, entry)).collect()
+            }
+            Some((id, None)) => self.inner.list_folders(Some(id)).await,
+        }
+    }
+
+    async fn list_folders_paginated(
+        &self,
+        parent_id: Option<&str>,
+        offset: usize,
+        limit: usize,
+        include_total: bool,
+    ) -> Result<(Vec<Folder>, Option<usize>), DomainError> {
+        let external_parent = parent_id.is_some_and(|id| parse_external_id(id).is_some());
+        if !external_parent {
+            let roots = if parent_id.is_none() { self.mounts.roots().await? } else { Vec::new() };
+            if roots.is_empty() {
+                return self.inner.list_folders_paginated(parent_id, offset, limit, include_total).await;
+            }
+        }
+
+        // Los montajes son pocos y sus listados no se paginan en origen
+        let folders = self.list_folders(parent_id).await?;
+        let total = folders.len();
+        let page = folders.into_iter().skip(offset).take(limit).collect();
+        Ok((page, include_total.then_some(total)))
+    }
+
+    async fn rename_folder(&self, id: &str, new_name: String) -> Result<Folder, DomainError> {
+        let Some((mount, path)) = parse_external_id(id) else {
+            return self.inner.rename_folder(id, new_name).await;
+        };
+        if path.is_empty() {
+            return Err(not_supported("Renaming a mount"));
+        }
+        check_name(&new_name)?;
+        self.mounts.folder(&mount, &path).await?;
+        let target = join_path(parent_path(&path), &new_name);
+        self.mounts.rename(&mount, &path, &target).await?;
+        self.mounts.folder(&mount, &target).await
+    }
+
+    async fn move_folder(&self, id: &str, new_parent_id: Option<&str>) -> Result<Folder, DomainError> {
+        let source = parse_external_id(id);
+        let target = new_parent_id.and_then(parse_external_id);
+        match (source, target) {
+            (None, None) => self.inner.move_folder(id, new_parent_id).await,
+            (Some((mount, path)), Some((target_mount, target_parent))) if mount == target_mount && !path.is_empty() => {
+                let folder = self.mounts.folder(&mount, &path).await?;
+                if target_parent == path || target_parent.starts_with(&format!("{}/", path)) {
+                    return Err(DomainError::validation_error("A folder cannot be moved into itself"));
+                }
+                let target = join_path(&target_parent, folder.name());
+                self.mounts.rename(&mount, &path, &target).await?;
+                self.mounts.folder(&mount, &target).await
+            }
+            // Entre montajes, o entre un montaje y el almacenamiento local, se
+            // usan las transferencias de /api/external
+            _ => Err(not_supported("Moving folders between storages")),
+        }
+    }
+
+    async fn delete_folder(&self, id: &str) -> Result<(), DomainError> {
+        match parse_external_id(id) {
+            Some((mount, path)) => {
+                self.mounts.folder(&mount, &path).await?;
+                self.mounts.delete_tree(&mount, &path).await
+            }
+            None => self.inner.delete_folder(id).await,
+        }
+    }
+
+    async fn folder_exists(&self, storage_path: &StoragePath) -> Result<bool, DomainError> {
+        self.inner.folder_exists(storage_path).await
+    }
+
+    async fn get_folder_path(&self, id: &str) -> Result<StoragePath, DomainError> {
+        match parse_external_id(id) {
+            Some((mount, path)) => Ok(self.mounts.folder(&mount, &path).await?.storage_path().clone()),
+            None => self.inner.get_folder_path(id).await,
+        }
+    }
+}
+
+/// Repositorio de archivos que sirve los archivos de los montajes externos
+/// por su ID, con las mismas reglas de acceso que `ExternalMountFolderRepository`
+pub struct ExternalMountFileRepository {
+    inner: Arc<dyn FileStoragePort>,
+    mounts: Mounts,
+}
+
+impl ExternalMountFileRepository {
+    pub fn new(inner: Arc<dyn FileStoragePort>, service: Arc<ExternalStorageService>) -> Self {
+        Self { inner, mounts: Mounts { service } }
+    }
+}
+
+#[async_trait]
+impl FileStoragePort for ExternalMountFileRepository {
+    async fn save_file(
+        &self,
+        name: String,
+        folder_id: Option<String>,
+        content_type: String,
+        content: Vec<u8>,
+    ) -> Result<File, DomainError> {
+        let Some((mount, folder)) = folder_id.as_deref().and_then(parse_external_id) else {
+            return self.inner.save_file(name, folder_id, content_type, content).await;
+        };
+        check_name(&name)?;
+        let path = join_path(&folder, &name);
+        let backend = self.mounts.backend(&mount, true).await?;
+        if backend.stat(&path).await.is_ok() {
+            return Err(DomainError::already_exists("File", &name));
+        }
+        backend.write_stream(&path, single_chunk(content)).await?;
+        self.mounts.file(&mount, &path).await
+    }
+
+    async fn copy_file(
+        &self,
+        file_id: &str,
+        name: String,
+        target_folder_id: Option<String>,
+    ) -> Result<File, DomainError> {
+        let source = parse_external_id(file_id);
+        let Some((mount, folder)) = target_folder_id.as_deref().and_then(parse_external_id) else {
+            if source.is_none() {
+                return self.inner.copy_file(file_id, name, target_folder_id).await;
+            }
+            // Local storage only saves from bytes
+            let file = self.get_file(file_id).await?;
+            let content = self.get_file_content(file_id).await?;
+            return self.inner.save_file(name, target_folder_id, file.mime_type().to_string(), content).await;
+        };
+        check_name(&name)?;
+        let path = join_path(&folder, &name);
+        let backend = self.mounts.backend(&mount, true).await?;
+        if backend.stat(&path).await.is_ok() {
+            return Err(DomainError::already_exists("File", &name));
+        }
+        let content = Box::into_pin(self.get_file_stream(file_id).await?);
+        backend.write_stream(&path, content).await?;
+        self.mounts.file(&mount, &path).await
+    }
+
+    async fn save_file_from_path(
+        &self,
+        name: String,
+        folder_id: Option<String>,
+        content_type: String,
+        source: &std::path::Path,
+    ) -> Result<File, DomainError> {
+        let Some((mount, folder)) = folder_id.as_deref().and_then(parse_external_id) else {
+            return self.inner.save_file_from_path(name, folder_id, content_type, source).await;
+        };
+        check_name(&name)?;
+        let path = join_path(&folder, &name);
+        let backend = self.mounts.backend(&mount, true).await?;
+        if backend.stat(&path).await.is_ok() {
+            return Err(DomainError::already_exists("File", &name));
+        }
+        let content = tokio::fs::File::open(source).await
+            .map_err(|e| DomainError::internal_error("FileStorage", format!("Failed to read {}: {}", source.display(), e)))?;
+        backend.write_stream(&path, Box::pin(tokio_util::io::ReaderStream::new(content))).await?;
+        self.mounts.file(&mount, &path).await
+    }
+
+    async fn get_file(&self, id: &str) -> Result<File, DomainError> {
+        match parse_external_id(id) {
+            Some((mount, path)) => self.mounts.file(&mount, &path).await,
+            None => self.inner.get_file(id).await,
+        }
+    }
+
+    async fn list_files(&self, folder_id: Option<&str>) -> Result<Vec<File>, DomainError> {
+        let Some((mount, path)) = folder_id.and_then(parse_external_id) else {
+            return self.inner.list_files(folder_id).await;
+        };
+        let mut entries = self.mounts.backend(&mount, false).await?.list(&path).await?;
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        entries.iter().filter(|entry| !entry.is_dir).map(|entry| to_file(&mount, entry)).collect()
+    }
+
+    async fn delete_file(&self, id: &str) -> Result<(), DomainError> {
+        match parse_external_id(id) {
+            Some((mount, path)) => {
+                self.mounts.file(&mount, &path).await?;
+                self.mounts.backend(&mount, true).await?.delete(&path).await
+            }
+            None => self.inner.delete_file(id).await,
+        }
+    }
+
+    async fn get_file_content(&self, id: &str) -> Result<Vec<u8>, DomainError> {
+        let Some((mount, path)) = parse_external_id(id) else {
+            return self.inner.get_file_content(id).await;
+        };
+        let stream = self.mounts.backend(&mount, false).await?.read_stream(&path).await?;
+        let chunks: Vec<Bytes> = stream
+            .try_collect()
+            .await
+            .map_err(|e| DomainError::internal_error("ExternalMount", e.to_string()))?;
+        Ok(chunks.concat())
+    }
+
+    async fn get_file_stream(&self, id: &str) -> Result<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>, DomainError> {
+        match parse_external_id(id) {
+            Some((mount, path)) => {
+                let stream = self.mounts.backend(&mount, false).await?.read_stream(&path).await?;
+                Ok(Box::new(stream))
+            }
+            None => self.inner.get_file_stream(id).await,
+        }
+    }
+
+    async fn get_file_range(&self, id: &str, start: u64, length: u64) -> Result<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>, DomainError> {
+        let Some((mount, path)) = parse_external_id(id) else {
+            return self.inner.get_file_range(id, start, length).await;
+        };
+        // Los backends solo leen desde el principio: se descarta lo anterior a `start`
+        let stream = self.mounts.backend(&mount, false).await?.read_stream(&path).await?;
+        let ranged = stream.scan((start, length), |(skip, remaining): &mut (u64, u64), chunk| {
+            let item = if *remaining == 0 {
+                None
+            } else {
+                Some(chunk.map(|chunk| {
+                    let skipped = (*skip).min(chunk.len() as u64);
+                    *skip -= skipped;
+                    let chunk = chunk.slice(skipped as usize..);
+                    let kept = (*remaining).min(chunk.len() as u64);
+                    *remaining -= kept;
+                    chunk.slice(..kept as usize)
+                }))
+            };
+            futures::future::ready(item)
+        });
+        Ok(Box::new(ranged))
+    }
+
+    async fn move_file(&self, file_id: &str, target_folder_id: Option<String>) -> Result<File, DomainError> {
+        let source = parse_external_id(file_id);
+        let target = target_folder_id.as_deref().and_then(parse_external_id);
+        match (source, target) {
+            (None, None) => self.inner.move_file(file_id, target_folder_id).await,
+            (Some((mount, path)), Some((target_mount, folder))) if mount == target_mount => {
+                let file = self.mounts.file(&mount, &path).await?;
+                let target = join_path(&folder, file.name());
+                self.mounts.rename(&mount, &path, &target).await?;
+                self.mounts.file(&mount, &target).await
+            }
+            _ => Err(not_supported("Moving files between storages")),
+        }
+    }
+
+    async fn get_file_path(&self, id: &str) -> Result<StoragePath, DomainError> {
+        match parse_external_id(id) {
+            Some((mount, path)) => Ok(self.mounts.file(&mount, &path).await?.storage_path().clone()),
+            None => self.inner.get_file_path(id).await,
+        }
+    }
+
+    async fn get_parent_folder_id(&self, path: &str) -> Result<String, DomainError> {
+        self.inner.get_parent_folder_id(path).await
+    }
+
+    async fn update_file_content(&self, file_id: &str, content: Vec<u8>) -> Result<(), DomainError> {
+        let Some((mount, path)) = parse_external_id(file_id) else {
+            return self.inner.update_file_content(file_id, content).await;
+        };
+        self.mounts.file(&mount, &path).await?;
+        self.mounts.backend(&mount, true).await?.write_stream(&path, single_chunk(content)).await?;
+        Ok(())
+    }
+
+    async fn write_file_range(&self, file_id: &str, offset: u64, content: &[u8]) -> Result<(), DomainError> {
+        if parse_external_id(file_id).is_none() {
+            return self.inner.write_file_range(file_id, offset, content).await;
+        }
+        let mut current = self.get_file_content(file_id).await?;
+        let offset = usize::try_from(offset)
+            .ok()
+            .filter(|offset| *offset <= current.len())
+            .ok_or_else(|| DomainError::validation_error(format!("Offset {} is past the end of file {}", offset, file_id)))?;
+        let end = offset + content.len();
+        if end > current.len() {
+            current.resize(end, 0);
+        }
+        current[offset..end].copy_from_slice(content);
+        self.update_file_content(file_id, current).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_external_ids_round_trip() {
+        let id = external_id("nas", "docs/Informe 2024.pdf");
+        assert!(id.starts_with(EXTERNAL_ID_PREFIX));
+        assert_eq!(parse_external_id(&id), Some(("nas".to_string(), "docs/Informe 2024.pdf".to_string())));
+        assert_eq!(parse_external_id(&external_id("nas", "")), Some(("nas".to_string(), String::new())));
+
+        // Los IDs locales y los que intentan salir del montaje no se aceptan
+        assert_eq!(parse_external_id("0b9a7c1e-8c6e-4e1a-9f7d-3c2b1a0f9e8d"), None);
+        assert_eq!(parse_external_id(&external_id("nas", "../etc")), None);
+    }
+
+    #[test]
+    fn test_parent_ids() {
+        assert_eq!(parent_id("nas", ""), None);
+        assert_eq!(parent_id("nas", "a"), Some(external_id("nas", "")));
+        assert_eq!(parent_id("nas", "a/b"), Some(external_id("nas", "a")));
+    }
+}
@@ -1,8 +1,13 @@
-use std::path::PathBuf;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use async_trait::async_trait;
 use bytes::Bytes;
-use futures::Stream;
+use futures::{Stream, StreamExt};
+use tokio::fs::{self, File as TokioFile};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio::time;
+use tokio_util::codec::{BytesCodec, FramedRead};
 
 use crate::domain::entities::file::File;
 use crate::application::ports::storage_ports::FileReadPort;
@@ -149,6 +154,29 @@ impl FileFsReadRepository {
         
         Ok(file)
     }
+    
+    /// Resuelve la ruta absoluta y el tamaño de un archivo existente
+    async fn open_target(&self, id: &str) -> Result<(PathBuf, u64), DomainError> {
+        let file = self.get_file(id).await?;
+        Ok((self.path_resolver.resolve_storage_path(file.storage_path()), file.size()))
+    }
+    
+    /// Abre un archivo para lectura respetando el timeout configurado
+    async fn open_file(&self, id: &str, abs_path: &Path) -> Result<TokioFile, DomainError> {
+        time::timeout(self.config.timeouts.file_timeout(), TokioFile::open(abs_path)).await
+            .map_err(|_| DomainError::internal_error("File", format!("Timeout opening file: {}", abs_path.display())))?
+            .map_err(|e| DomainError::internal_error("File", format!("Failed to open file with ID: {}: {}", id, e)))
+    }
+    
+    /// Tamaño de chunk para leer `size` bytes: el configurado para archivos
+    /// grandes y 4KB para el resto
+    fn chunk_size(&self, size: u64) -> usize {
+        if self.config.resources.is_large_file(size) {
+            self.config.resources.chunk_size_bytes
+        } else {
+            4096
+        }
+    }
 }
 
 #[async_trait]
@@ -170,26 +198,36 @@ impl FileReadPort for FileFsReadRepository {
     }
     
     async fn get_file_content(&self, id: &str) -> Result<Vec<u8>, DomainError> {
-        // Primero obtenemos el archivo para verificar existencia
-        let file = self.get_file_by_id(id).await
-            .map_err(|e| match e {
-                crate::domain::repositories::file_repository::FileRepositoryError::NotFound(msg) => DomainError::not_found("File", msg),
-                crate::domain::repositories::file_repository::FileRepositoryError::IoError(io_err) => DomainError::internal_error("File", io_err.to_string()),
-                crate::domain::repositories::file_repository::FileRepositoryError::Timeout(msg) => DomainError::internal_error("File", msg),
-                _ => DomainError::internal_error("File", e.to_string()),
-            })?;
+        let (abs_path, _) = self.open_target(id).await?;
+        
+        time::timeout(self.config.timeouts.file_timeout(), fs::read(&abs_path)).await
+            .map_err(|_| DomainError::internal_error("File", format!("Timeout reading file: {}", abs_path.display())))?
+            .map_err(|e| DomainError::internal_error("File", format!("Failed to read file with ID: {}: {}", id, e)))
+    }
+    
+    async fn get_file_stream(&self, id: &str) -> Result<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>, DomainError> {
+        let (abs_path, size) = self.open_target(id).await?;
+        let handle = self.open_file(id, &abs_path).await?;
         
-        // Ruta absoluta del archivo
-        let _abs_path = self.path_resolver.resolve_storage_path(file.storage_path());
+        tracing::debug!("Streaming file {} (size: {} bytes)", abs_path.display(), size);
         
-        // Implementación real debe leer el contenido del archivo
-        // Por ahora, devolvemos un vector vacío
-        Ok(Vec::new())
+        let stream = FramedRead::with_capacity(handle, BytesCodec::new(), self.chunk_size(size))
+            .map(|result| result.map(|bytes_mut| bytes_mut.freeze()));
+        
+        Ok(Box::new(stream))
     }
     
-    async fn get_file_stream(&self, _id: &str) -> Result<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>, DomainError> {
-        // Implementación real debe devolver un stream de bytes del archivo
-        // Por ahora, lanzamos un error
-        Err(DomainError::internal_error("File stream", "Stream functionality not yet implemented"))
+    async fn get_file_range(&self, id: &str, start: u64, length: u64) -> Result<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>, DomainError> {
+        let (abs_path, _) = self.open_target(id).await?;
+        let mut handle = self.open_file(id, &abs_path).await?;
+        
+        // Saltamos hasta el inicio del rango y leemos solo los bytes pedidos
+        handle.seek(SeekFrom::Start(start)).await
+            .map_err(|e| DomainError::internal_error("File", format!("Failed to seek in file with ID: {}: {}", id, e)))?;
+        
+        let stream = FramedRead::with_capacity(handle.take(length), BytesCodec::new(), self.chunk_size(length))
+            .map(|result| result.map(|bytes_mut| bytes_mut.freeze()));
+        
+        Ok(Box::new(stream))
     }
-}
\ No newline at end of file
+}
@@ -1,8 +1,9 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use async_trait::async_trait;
-use tokio::{fs, io::AsyncWriteExt, time};
+use std::io::SeekFrom;
+use tokio::{fs, io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt}, time};
 use tokio::fs::File as TokioFile;
 use tokio_util::codec::{BytesCodec, FramedRead};
 use tracing::instrument;
@@ -375,6 +376,57 @@ impl FileFsRepository {
         
         Ok(())
     }
+    
+    /// Copia un archivo en el disco sin cargar su contenido en memoria
+    async fn copy_file_on_disk(
+        &self,
+        file_id: &str,
+        name: String,
+        folder_id: Option<String>,
+    ) -> FileRepositoryResult<File> {
+        let source = self.get_file_by_id(file_id).await?;
+        let source_path = self.resolve_storage_path(source.storage_path());
+        self.save_file_from_disk(name, folder_id, source.mime_type().to_string(), &source_path).await
+    }
+    
+    /// Guarda un archivo nuevo con el contenido de un fichero del disco, sin
+    /// cargarlo en memoria.
+    ///
+    /// Se crea primero la entrada vacía del destino, que fija su nombre y su
+    /// ID, y después el sistema de archivos copia los datos encima.
+    async fn save_file_from_disk(
+        &self,
+        name: String,
+        folder_id: Option<String>,
+        content_type: String,
+        source_path: &Path,
+    ) -> FileRepositoryResult<File> {
+        let target = self.save_file_from_bytes(name, folder_id, content_type, Vec::new()).await?;
+        let target_path = self.resolve_storage_path(target.storage_path());
+        
+        if let Err(e) = fs::copy(source_path, &target_path).await {
+            tracing::error!("Error copying {} to {}: {}", source_path.display(), target_path.display(), e);
+            if let Err(e) = FileRepository::delete_file(self, target.id()).await {
+                tracing::warn!("Could not remove incomplete copy {}: {}", target.id(), e);
+            }
+            return Err(FileRepositoryError::IoError(e));
+        }
+        
+        // La entrada vacía dejó en la caché un tamaño de cero bytes
+        self.metadata_cache.invalidate(&target_path).await;
+        let (size, created_at, modified_at) = self.get_file_metadata(&target_path).await?;
+        
+        self.create_file_entity(
+            target.id().to_string(),
+            target.name().to_string(),
+            target.storage_path().clone(),
+            size,
+            target.mime_type().to_string(),
+            target.folder_id().map(String::from),
+            Some(created_at),
+            Some(modified_at),
+        ).await
+    }
 }
 
 // Convert IdMappingError to FileRepositoryError
@@ -428,6 +480,29 @@ impl FileStoragePort for FileFsRepository {
             .map_err(|e| DomainError::internal_error("FileStorage", format!("Failed to save file: {}", e)))
     }
     
+    async fn copy_file(
+        &self,
+        file_id: &str,
+        name: String,
+        target_folder_id: Option<String>,
+    ) -> Result<File, DomainError> {
+        self.copy_file_on_disk(file_id, name, target_folder_id)
+            .await
+            .map_err(|e| DomainError::internal_error("FileStorage", format!("Failed to copy file with ID: {}: {}", file_id, e)))
+    }
+    
+    async fn save_file_from_path(
+        &self,
+        name: String,
+        folder_id: Option<String>,
+        content_type: String,
+        source: &Path,
+    ) -> Result<File, DomainError> {
+        self.save_file_from_disk(name, folder_id, content_type, source)
+            .await
+            .map_err(|e| DomainError::internal_error("FileStorage", format!("Failed to save file from {}: {}", source.display(), e)))
+    }
+    
     async fn get_file(&self, id: &str) -> Result<File, DomainError> {
         self.get_file_by_id(id)
             .await
@@ -458,6 +533,67 @@ impl FileStoragePort for FileFsRepository {
             .map_err(|e| DomainError::internal_error("FileStorage", format!("Failed to get stream for file with ID: {}: {}", id, e)))
     }
     
+    async fn get_file_range(&self, id: &str, start: u64, length: u64) -> Result<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>, DomainError> {
+        let file = self.get_file_by_id(id)
+            .await
+            .map_err(|e| DomainError::internal_error("FileStorage", format!("Failed to get file with ID: {}: {}", id, e)))?;
+        let abs_path = self.resolve_storage_path(file.storage_path());
+        
+        let mut handle = time::timeout(
+            self.config.timeouts.file_timeout(),
+            TokioFile::open(&abs_path)
+        ).await
+        .map_err(|_| DomainError::internal_error("FileStorage", format!("Timeout opening file range for: {}", abs_path.display())))?
+        .map_err(|e| DomainError::internal_error("FileStorage", format!("Failed to open file with ID: {}: {}", id, e)))?;
+        
+        // Saltamos hasta el inicio del rango y leemos solo los bytes pedidos
+        handle.seek(SeekFrom::Start(start)).await
+            .map_err(|e| DomainError::internal_error("FileStorage", format!("Failed to seek in file with ID: {}: {}", id, e)))?;
+        
+        let chunk_size = if self.config.resources.is_large_file(length) {
+            self.config.resources.chunk_size_bytes
+        } else {
+            4096
+        };
+        
+        let stream = FramedRead::with_capacity(handle.take(length), BytesCodec::new(), chunk_size)
+            .map(|result| result.map(|bytes_mut| bytes_mut.freeze()));
+        
+        Ok(Box::new(stream))
+    }
+    
+    async fn write_file_range(&self, file_id: &str, offset: u64, content: &[u8]) -> Result<(), DomainError> {
+        let file = self.get_file_by_id(file_id)
+            .await
+            .map_err(|e| DomainError::internal_error("FileStorage", format!("Failed to get file with ID: {}: {}", file_id, e)))?;
+        let abs_path = self.resolve_storage_path(file.storage_path());
+        
+        let mut handle = time::timeout(
+            self.config.timeouts.file_timeout(),
+            fs::OpenOptions::new().write(true).open(&abs_path)
+        ).await
+        .map_err(|_| DomainError::internal_error("FileStorage", format!("Timeout opening file for range write: {}", abs_path.display())))?
+        .map_err(|e| DomainError::internal_error("FileStorage", format!("Failed to open file with ID: {}: {}", file_id, e)))?;
+        
+        // Solo se escriben los bloques afectados; sin huecos tras el final
+        let size = handle.metadata().await
+            .map_err(|e| DomainError::internal_error("FileStorage", format!("Failed to read size of file with ID: {}: {}", file_id, e)))?
+            .len();
+        if offset > size {
+            return Err(DomainError::validation_error(format!("Offset {} is past the end of file {}", offset, file_id)));
+        }
+        handle.seek(SeekFrom::Start(offset)).await
+            .map_err(|e| DomainError::internal_error("FileStorage", format!("Failed to seek in file with ID: {}: {}", file_id, e)))?;
+        handle.write_all(content).await
+            .map_err(|e| DomainError::internal_error("FileStorage", format!("Failed to write range of file with ID: {}: {}", file_id, e)))?;
+        handle.sync_all().await
+            .map_err(|e| DomainError::internal_error("FileStorage", format!("Failed to sync file with ID: {}: {}", file_id, e)))?;
+        
+        // El tamaño y la fecha cacheados ya no son válidos
+        self.metadata_cache.invalidate(&abs_path).await;
+        Ok(())
+    }
+    
     async fn move_file(&self, file_id: &str, target_folder_id: Option<String>) -> Result<File, DomainError> {
         // Clone target_folder_id before passing to avoid ownership issues
         let cloned_target = target_folder_id.clone();
@@ -1238,9 +1374,10 @@ impl FileRepository for FileFsRepository {
                         continue;
                     }
                     
-                    // Skip special files
+                    // Skip special files; dotfiles in the storage root are internal stores,
+                    // user dotfiles are filtered by the hidden file rules of the services
                     let file_name_lossy = entry.file_name().to_string_lossy().to_string();
-                    if file_name_lossy.starts_with('.') || file_name_lossy == "folder_ids.json" || file_name_lossy == "file_ids.json" {
+                    if (folder_id.is_none() && file_name_lossy.starts_with('.')) || file_name_lossy == "folder_ids.json" || file_name_lossy == "file_ids.json" {
                         continue;
                     }
                     
@@ -1570,16 +1707,18 @@ impl FileRepository for FileFsRepository {
         // Ensure the target directory exists
         self.ensure_parent_directory(&new_abs_path).await?;
         
-        // Move the file physically with fsync (efficient rename operation) with timeout
-        time::timeout(
+        // Move the file physically with fsync with timeout: a rename on the same
+        // filesystem, a verified copy plus delete across devices
+        let renamed = time::timeout(
             self.config.timeouts.file_timeout(),
-            FileSystemUtils::rename_with_sync(&old_abs_path, &new_abs_path)
+            FileSystemUtils::move_with_sync(&old_abs_path, &new_abs_path)
         ).await
         .map_err(|_| FileRepositoryError::Timeout(format!("Timeout moving file from {} to {}", 
                                                         old_abs_path.display(), new_abs_path.display())))?
         .map_err(FileRepositoryError::IoError)?;
             
-        tracing::info!("File moved successfully from {:?} to {:?}", old_abs_path, new_abs_path);
+        tracing::info!("File moved successfully from {:?} to {:?} ({})", old_abs_path, new_abs_path,
+                       if renamed { "rename" } else { "verified copy" });
         
         // Update the ID mapping
         self.id_mapping_service.update_path(id, &new_storage_path).await
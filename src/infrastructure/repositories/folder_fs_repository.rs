@@ -13,6 +13,7 @@ use crate::domain::repositories::folder_repository::{
 use crate::domain::services::path_service::{StoragePath, PathService};
 // use crate::application::ports::outbound::IdMappingPort;
 use crate::infrastructure::services::id_mapping_service::{IdMappingService, IdMappingError};
+use crate::infrastructure::services::file_system_utils::FileSystemUtils;
 use crate::application::services::storage_mediator::StorageMediator;
 use crate::application::ports::outbound::FolderStoragePort;
 use crate::common::errors::DomainError;
@@ -853,11 +854,13 @@ impl FolderRepository for FolderFsRepository {
                 .map_err(FolderRepositoryError::IoError)?;
         }
         
-        // Move the directory physically (efficient rename operation)
-        fs::rename(&old_abs_path, &new_abs_path).await
+        // Rename in place when both paths share a filesystem (metadata only);
+        // otherwise copy with verification and remove the source afterwards
+        let renamed = FileSystemUtils::move_with_sync(&old_abs_path, &new_abs_path).await
             .map_err(FolderRepositoryError::IoError)?;
             
-        tracing::info!("Folder moved successfully from {:?} to {:?}", old_abs_path, new_abs_path);
+        tracing::info!("Folder moved successfully from {:?} to {:?} ({})", old_abs_path, new_abs_path,
+                       if renamed { "rename" } else { "verified copy" });
         
         // Update the ID mapping
         self.id_mapping_service.update_path(id, moved_folder.storage_path()).await
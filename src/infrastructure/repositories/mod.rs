@@ -11,6 +11,8 @@ pub mod trash_fs_repository;
 pub mod file_fs_repository_trash;
 pub mod folder_fs_repository_trash;
 pub mod share_fs_repository;
+pub mod share_access_log_fs_repository;
+pub mod external_mount_repository;
 
 // Repositorios PostgreSQL
 pub mod pg;
@@ -20,4 +22,4 @@ pub use file_metadata_manager::FileMetadataManager;
 pub use file_path_resolver::FilePathResolver;
 pub use file_fs_read_repository::FileFsReadRepository;
 pub use file_fs_write_repository::FileFsWriteRepository;
-pub use pg::{UserPgRepository, SessionPgRepository};
+pub use pg::{UserPgRepository, SessionPgRepository, AppPasswordPgRepository, UserQuarantinePgRepository, OidcIdentityPgRepository};
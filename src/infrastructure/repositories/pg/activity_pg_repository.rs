@@ -0,0 +1,135 @@
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+
+use crate::common::errors::DomainError;
+use crate::domain::entities::activity::{Activity, ActivityAction, ActivityTargetKind};
+use crate::domain::repositories::activity_repository::{ActivityFilter, ActivityRepository, ActivityRepositoryResult};
+
+pub struct ActivityPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl ActivityPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn map_sqlx_error(err: sqlx::Error) -> DomainError {
+        DomainError::database_error(format!("Error de base de datos en la actividad: {}", err))
+    }
+
+    fn row_to_activity(row: &PgRow) -> Result<Activity, DomainError> {
+        let action: String = row.get("action");
+        let target_kind: String = row.get("target_kind");
+
+        Ok(Activity {
+            id: row.get("id"),
+            actor_id: row.get("actor_id"),
+            actor_name: row.get("actor_name"),
+            action: ActivityAction::parse(&action)
+                .ok_or_else(|| DomainError::database_error(format!("Acción de actividad desconocida: {}", action)))?,
+            target_kind: ActivityTargetKind::parse(&target_kind)
+                .ok_or_else(|| DomainError::database_error(format!("Tipo de elemento desconocido: {}", target_kind)))?,
+            target_id: row.get("target_id"),
+            target_name: row.get("target_name"),
+            folder_id: row.get("folder_id"),
+            details: row.get("details"),
+            created_at: row.get("created_at"),
+        })
+    }
+}
+
+#[async_trait]
+impl ActivityRepository for ActivityPgRepository {
+    /// Guarda una acción
+    async fn record(&self, activity: &Activity) -> ActivityRepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO auth.activities (
+                id, actor_id, actor_name, action, target_kind, target_id,
+                target_name, folder_id, details, created_at
+            ) VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10
+            )
+            "#
+        )
+        .bind(&activity.id)
+        .bind(&activity.actor_id)
+        .bind(&activity.actor_name)
+        .bind(activity.action.as_str())
+        .bind(activity.target_kind.as_str())
+        .bind(&activity.target_id)
+        .bind(&activity.target_name)
+        .bind(&activity.folder_id)
+        .bind(&activity.details)
+        .bind(activity.created_at)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    /// Obtiene una página de la actividad filtrada, la más reciente primero
+    async fn list(&self, filter: &ActivityFilter, limit: i64, offset: i64) -> ActivityRepositoryResult<(Vec<Activity>, i64)> {
+        let action = filter.action.map(|action| action.as_str());
+        let target_kind = filter.target_kind.map(|kind| kind.as_str());
+
+        // Los filtros vacíos se cumplen siempre; la carpeta incluye lo que
+        // contiene y lo que se hizo con ella misma
+        let count_row = sqlx::query(
+            r#"
+            SELECT COUNT(*) AS count
+            FROM auth.activities
+            WHERE ($1::text IS NULL OR actor_id = $1)
+              AND ($2::text IS NULL OR folder_id = $2 OR target_id = $2)
+              AND ($3::text IS NULL OR action = $3)
+              AND ($4::text IS NULL OR target_kind = $4)
+              AND ($5::timestamptz IS NULL OR created_at >= $5)
+              AND ($6::timestamptz IS NULL OR created_at < $6)
+            "#
+        )
+        .bind(&filter.actor_id)
+        .bind(&filter.folder_id)
+        .bind(action)
+        .bind(target_kind)
+        .bind(filter.since)
+        .bind(filter.until)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                id, actor_id, actor_name, action, target_kind, target_id,
+                target_name, folder_id, details, created_at
+            FROM auth.activities
+            WHERE ($1::text IS NULL OR actor_id = $1)
+              AND ($2::text IS NULL OR folder_id = $2 OR target_id = $2)
+              AND ($3::text IS NULL OR action = $3)
+              AND ($4::text IS NULL OR target_kind = $4)
+              AND ($5::timestamptz IS NULL OR created_at >= $5)
+              AND ($6::timestamptz IS NULL OR created_at < $6)
+            ORDER BY created_at DESC
+            LIMIT $7 OFFSET $8
+            "#
+        )
+        .bind(&filter.actor_id)
+        .bind(&filter.folder_id)
+        .bind(action)
+        .bind(target_kind)
+        .bind(filter.since)
+        .bind(filter.until)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        let activities = rows.iter().map(Self::row_to_activity).collect::<Result<Vec<_>, _>>()?;
+        Ok((activities, count_row.get("count")))
+    }
+}
@@ -29,7 +29,7 @@ impl AddressBookRepository for AddressBookPgRepository {
             r#"
             INSERT INTO carddav.address_books (id, name, owner_id, description, color, is_public, created_at, updated_at)
             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-            RETURNING id, name, owner_id, description, color, is_public, created_at, updated_at
+            RETURNING id, name, owner_id, description, color, is_public, created_at, updated_at, sync_revision
             "#
         )
         .bind(address_book.id)
@@ -53,6 +53,7 @@ impl AddressBookRepository for AddressBookPgRepository {
             is_public: row.get("is_public"),
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
+            sync_revision: row.get("sync_revision"),
         })
     }
 
@@ -63,7 +64,7 @@ impl AddressBookRepository for AddressBookPgRepository {
             UPDATE carddav.address_books
             SET name = $1, description = $2, color = $3, is_public = $4, updated_at = $5
             WHERE id = $6
-            RETURNING id, name, owner_id, description, color, is_public, created_at, updated_at
+            RETURNING id, name, owner_id, description, color, is_public, created_at, updated_at, sync_revision
             "#
         )
         .bind(&address_book.name)
@@ -85,6 +86,7 @@ impl AddressBookRepository for AddressBookPgRepository {
             is_public: row.get("is_public"),
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
+            sync_revision: row.get("sync_revision"),
         })
     }
 
@@ -106,7 +108,7 @@ impl AddressBookRepository for AddressBookPgRepository {
     async fn get_address_book_by_id(&self, id: &Uuid) -> AddressBookRepositoryResult<Option<AddressBook>> {
         let maybe_row = sqlx::query(
             r#"
-            SELECT id, name, owner_id, description, color, is_public, created_at, updated_at
+            SELECT id, name, owner_id, description, color, is_public, created_at, updated_at, sync_revision
             FROM carddav.address_books
             WHERE id = $1
             "#
@@ -125,6 +127,7 @@ impl AddressBookRepository for AddressBookPgRepository {
             is_public: row.get("is_public"),
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
+            sync_revision: row.get("sync_revision"),
         });
 
         Ok(result)
@@ -133,7 +136,7 @@ impl AddressBookRepository for AddressBookPgRepository {
     async fn get_address_books_by_owner(&self, owner_id: &str) -> AddressBookRepositoryResult<Vec<AddressBook>> {
         let rows = sqlx::query(
             r#"
-            SELECT id, name, owner_id, description, color, is_public, created_at, updated_at
+            SELECT id, name, owner_id, description, color, is_public, created_at, updated_at, sync_revision
             FROM carddav.address_books
             WHERE owner_id = $1
             ORDER BY name
@@ -154,6 +157,7 @@ impl AddressBookRepository for AddressBookPgRepository {
                 is_public: row.get("is_public"),
                 created_at: row.get("created_at"),
                 updated_at: row.get("updated_at"),
+                sync_revision: row.get("sync_revision"),
             })
             .collect();
 
@@ -163,10 +167,16 @@ impl AddressBookRepository for AddressBookPgRepository {
     async fn get_shared_address_books(&self, user_id: &str) -> AddressBookRepositoryResult<Vec<AddressBook>> {
         let rows = sqlx::query(
             r#"
-            SELECT a.id, a.name, a.owner_id, a.description, a.color, a.is_public, a.created_at, a.updated_at
+            SELECT a.id, a.name, a.owner_id, a.description, a.color, a.is_public, a.created_at, a.updated_at, a.sync_revision
             FROM carddav.address_books a
-            INNER JOIN carddav.address_book_shares s ON a.id = s.address_book_id
-            WHERE s.user_id = $1
+            WHERE EXISTS (
+                SELECT 1 FROM carddav.address_book_shares s
+                WHERE s.address_book_id = a.id AND s.user_id = $1
+            ) OR EXISTS (
+                SELECT 1 FROM carddav.address_book_group_shares gs
+                INNER JOIN auth.user_group_members m ON m.group_id = gs.group_id
+                WHERE gs.address_book_id = a.id AND m.user_id = $1
+            )
             ORDER BY a.name
             "#
         )
@@ -185,6 +195,7 @@ impl AddressBookRepository for AddressBookPgRepository {
                 is_public: row.get("is_public"),
                 created_at: row.get("created_at"),
                 updated_at: row.get("updated_at"),
+                sync_revision: row.get("sync_revision"),
             })
             .collect();
 
@@ -194,7 +205,7 @@ impl AddressBookRepository for AddressBookPgRepository {
     async fn get_public_address_books(&self) -> AddressBookRepositoryResult<Vec<AddressBook>> {
         let rows = sqlx::query(
             r#"
-            SELECT id, name, owner_id, description, color, is_public, created_at, updated_at
+            SELECT id, name, owner_id, description, color, is_public, created_at, updated_at, sync_revision
             FROM carddav.address_books
             WHERE is_public = true
             ORDER BY name
@@ -214,6 +225,7 @@ impl AddressBookRepository for AddressBookPgRepository {
                 is_public: row.get("is_public"),
                 created_at: row.get("created_at"),
                 updated_at: row.get("updated_at"),
+                sync_revision: row.get("sync_revision"),
             })
             .collect();
 
@@ -274,4 +286,84 @@ impl AddressBookRepository for AddressBookPgRepository {
 
         Ok(result)
     }
-}
\ No newline at end of file
+
+    async fn share_address_book_with_group(&self, address_book_id: &Uuid, group_id: &str, can_write: bool) -> AddressBookRepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO carddav.address_book_group_shares (address_book_id, group_id, can_write)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (address_book_id, group_id) DO UPDATE SET can_write = $3
+            "#
+        )
+        .bind(address_book_id)
+        .bind(group_id)
+        .bind(can_write)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to share address book with group: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn unshare_address_book_from_group(&self, address_book_id: &Uuid, group_id: &str) -> AddressBookRepositoryResult<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM carddav.address_book_group_shares
+            WHERE address_book_id = $1 AND group_id = $2
+            "#
+        )
+        .bind(address_book_id)
+        .bind(group_id)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to unshare address book from group: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn get_address_book_group_shares(&self, address_book_id: &Uuid) -> AddressBookRepositoryResult<Vec<(String, bool)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT group_id, can_write
+            FROM carddav.address_book_group_shares
+            WHERE address_book_id = $1
+            ORDER BY group_id
+            "#
+        )
+        .bind(address_book_id)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to get address book group shares: {}", e)))?;
+
+        let result = rows.into_iter()
+            .map(|row| (row.get("group_id"), row.get("can_write")))
+            .collect();
+
+        Ok(result)
+    }
+
+    async fn get_user_address_book_share(&self, address_book_id: &Uuid, user_id: &str) -> AddressBookRepositoryResult<Option<bool>> {
+        let row = sqlx::query(
+            r#"
+            SELECT bool_or(shares.can_write) AS can_write
+            FROM (
+                SELECT s.can_write
+                FROM carddav.address_book_shares s
+                WHERE s.address_book_id = $1 AND s.user_id = $2
+                UNION ALL
+                SELECT gs.can_write
+                FROM carddav.address_book_group_shares gs
+                INNER JOIN auth.user_group_members m ON m.group_id = gs.group_id
+                WHERE gs.address_book_id = $1 AND m.user_id = $2
+            ) shares
+            "#
+        )
+        .bind(address_book_id)
+        .bind(user_id)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to check address book share: {}", e)))?;
+
+        Ok(row.get("can_write"))
+    }
+}
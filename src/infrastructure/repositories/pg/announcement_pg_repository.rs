@@ -0,0 +1,216 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+
+use crate::common::errors::DomainError;
+use crate::domain::entities::announcement::{Announcement, AnnouncementAcknowledgment, AnnouncementSeverity};
+use crate::domain::repositories::announcement_repository::{AnnouncementRepository, AnnouncementRepositoryResult};
+
+pub struct AnnouncementPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl AnnouncementPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn map_sqlx_error(err: sqlx::Error) -> DomainError {
+        DomainError::database_error(format!("Error de base de datos en anuncios: {}", err))
+    }
+
+    fn row_to_announcement(row: &PgRow) -> Result<Announcement, DomainError> {
+        let severity: String = row.get("severity");
+
+        Ok(Announcement {
+            id: row.get("id"),
+            title: row.get("title"),
+            message: row.get("message"),
+            severity: AnnouncementSeverity::parse(&severity)
+                .ok_or_else(|| DomainError::database_error(format!("Gravedad de anuncio desconocida: {}", severity)))?,
+            starts_at: row.get("starts_at"),
+            ends_at: row.get("ends_at"),
+            target_groups: row.get("target_groups"),
+            created_by: row.get("created_by"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+    }
+}
+
+#[async_trait]
+impl AnnouncementRepository for AnnouncementPgRepository {
+    /// Obtiene todos los anuncios, los más recientes primero
+    async fn list_announcements(&self) -> AnnouncementRepositoryResult<Vec<Announcement>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                id, title, message, severity, starts_at, ends_at,
+                target_groups, created_by, created_at, updated_at
+            FROM auth.announcements
+            ORDER BY starts_at DESC
+            "#
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        rows.iter().map(Self::row_to_announcement).collect()
+    }
+
+    /// Obtiene los anuncios cuya ventana incluye `now`
+    async fn list_active(&self, now: DateTime<Utc>) -> AnnouncementRepositoryResult<Vec<Announcement>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                id, title, message, severity, starts_at, ends_at,
+                target_groups, created_by, created_at, updated_at
+            FROM auth.announcements
+            WHERE starts_at <= $1 AND (ends_at IS NULL OR ends_at > $1)
+            ORDER BY starts_at DESC
+            "#
+        )
+        .bind(now)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        rows.iter().map(Self::row_to_announcement).collect()
+    }
+
+    /// Obtiene un anuncio
+    async fn get_announcement(&self, id: &str) -> AnnouncementRepositoryResult<Option<Announcement>> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                id, title, message, severity, starts_at, ends_at,
+                target_groups, created_by, created_at, updated_at
+            FROM auth.announcements
+            WHERE id = $1
+            "#
+        )
+        .bind(id)
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        row.as_ref().map(Self::row_to_announcement).transpose()
+    }
+
+    /// Crea o actualiza un anuncio (el autor y la fecha de creación no cambian)
+    async fn save_announcement(&self, announcement: &Announcement) -> AnnouncementRepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO auth.announcements (
+                id, title, message, severity, starts_at, ends_at,
+                target_groups, created_by, created_at, updated_at
+            ) VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10
+            )
+            ON CONFLICT (id) DO UPDATE SET
+                title = EXCLUDED.title,
+                message = EXCLUDED.message,
+                severity = EXCLUDED.severity,
+                starts_at = EXCLUDED.starts_at,
+                ends_at = EXCLUDED.ends_at,
+                target_groups = EXCLUDED.target_groups,
+                updated_at = EXCLUDED.updated_at
+            "#
+        )
+        .bind(&announcement.id)
+        .bind(&announcement.title)
+        .bind(&announcement.message)
+        .bind(announcement.severity.as_str())
+        .bind(announcement.starts_at)
+        .bind(announcement.ends_at)
+        .bind(&announcement.target_groups)
+        .bind(&announcement.created_by)
+        .bind(announcement.created_at)
+        .bind(announcement.updated_at)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    /// Elimina un anuncio; sus lecturas se borran en cascada
+    async fn delete_announcement(&self, id: &str) -> AnnouncementRepositoryResult<bool> {
+        let result = sqlx::query("DELETE FROM auth.announcements WHERE id = $1")
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Registra la lectura, conservando la primera si ya existía
+    async fn mark_read(&self, id: &str, user_id: &str, read_at: DateTime<Utc>) -> AnnouncementRepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO auth.announcement_reads (announcement_id, user_id, read_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (announcement_id, user_id) DO NOTHING
+            "#
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(read_at)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    /// De los anuncios indicados, devuelve los que el usuario ya ha leído
+    async fn read_announcements(&self, user_id: &str, ids: &[String]) -> AnnouncementRepositoryResult<Vec<String>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let rows = sqlx::query(
+            r#"
+            SELECT announcement_id
+            FROM auth.announcement_reads
+            WHERE user_id = $1 AND announcement_id = ANY($2)
+            "#
+        )
+        .bind(user_id)
+        .bind(ids)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(rows.iter().map(|row| row.get("announcement_id")).collect())
+    }
+
+    /// Cuenta solo a los usuarios activos de los grupos destinatarios, para
+    /// que la tasa de lectura no supere el 100% si cambian los grupos
+    async fn acknowledgment(&self, announcement: &Announcement) -> AnnouncementRepositoryResult<AnnouncementAcknowledgment> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                COUNT(*) AS target_count,
+                COUNT(r.user_id) AS read_count
+            FROM auth.users u
+            LEFT JOIN auth.announcement_reads r
+                ON r.user_id = u.id AND r.announcement_id = $1
+            WHERE u.active AND (cardinality($2::text[]) = 0 OR u.role::text = ANY($2))
+            "#
+        )
+        .bind(&announcement.id)
+        .bind(&announcement.target_groups)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(AnnouncementAcknowledgment {
+            read_count: row.get("read_count"),
+            target_count: row.get("target_count"),
+        })
+    }
+}
@@ -0,0 +1,94 @@
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+
+use crate::application::ports::auth_ports::AppPasswordStoragePort;
+use crate::common::errors::DomainError;
+use crate::domain::entities::app_password::AppPassword;
+
+pub struct AppPasswordPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl AppPasswordPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn map_sqlx_error(err: sqlx::Error) -> DomainError {
+        DomainError::database_error(format!("Error de base de datos en contraseñas de aplicación: {}", err))
+    }
+
+    fn row_to_app_password(row: &PgRow) -> AppPassword {
+        AppPassword {
+            id: row.get("id"),
+            user_id: row.get("user_id"),
+            label: row.get("label"),
+            password_hash: row.get("password_hash"),
+            created_at: row.get("created_at"),
+            last_used_at: row.get("last_used_at"),
+        }
+    }
+}
+
+#[async_trait]
+impl AppPasswordStoragePort for AppPasswordPgRepository {
+    async fn create_app_password(&self, app_password: AppPassword) -> Result<AppPassword, DomainError> {
+        sqlx::query(
+            r#"
+            INSERT INTO auth.app_passwords (id, user_id, label, password_hash, created_at, last_used_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#
+        )
+        .bind(&app_password.id)
+        .bind(&app_password.user_id)
+        .bind(&app_password.label)
+        .bind(&app_password.password_hash)
+        .bind(app_password.created_at)
+        .bind(app_password.last_used_at)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(app_password)
+    }
+
+    async fn list_app_passwords(&self, user_id: &str) -> Result<Vec<AppPassword>, DomainError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, user_id, label, password_hash, created_at, last_used_at
+            FROM auth.app_passwords
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            "#
+        )
+        .bind(user_id)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(rows.iter().map(Self::row_to_app_password).collect())
+    }
+
+    async fn delete_app_password(&self, user_id: &str, id: &str) -> Result<bool, DomainError> {
+        let result = sqlx::query("DELETE FROM auth.app_passwords WHERE id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(user_id)
+            .execute(&*self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn touch_app_password(&self, id: &str) -> Result<(), DomainError> {
+        sqlx::query("UPDATE auth.app_passwords SET last_used_at = NOW() WHERE id = $1")
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+}
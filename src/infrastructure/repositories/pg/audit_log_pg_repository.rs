@@ -0,0 +1,122 @@
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+
+use crate::common::errors::DomainError;
+use crate::domain::entities::audit_event::{AuditAction, AuditEvent};
+use crate::domain::repositories::audit_log_repository::{AuditLogFilter, AuditLogRepository, AuditLogRepositoryResult};
+
+pub struct AuditLogPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl AuditLogPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn map_sqlx_error(err: sqlx::Error) -> DomainError {
+        DomainError::database_error(format!("Error de base de datos en el registro de auditoría: {}", err))
+    }
+
+    fn row_to_event(row: &PgRow) -> Result<AuditEvent, DomainError> {
+        let action: String = row.get("action");
+
+        Ok(AuditEvent {
+            id: row.get("id"),
+            occurred_at: row.get("occurred_at"),
+            action: AuditAction::parse(&action)
+                .ok_or_else(|| DomainError::database_error(format!("Acción de auditoría desconocida: {}", action)))?,
+            actor_id: row.get("actor_id"),
+            actor_name: row.get("actor_name"),
+            target: row.get("target"),
+            ip_address: row.get("ip_address"),
+            success: row.get("success"),
+            details: row.get("details"),
+        })
+    }
+}
+
+#[async_trait]
+impl AuditLogRepository for AuditLogPgRepository {
+    /// Añade una entrada; la tabla rechaza cualquier cambio posterior
+    async fn append(&self, event: &AuditEvent) -> AuditLogRepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO auth.audit_log (
+                id, occurred_at, action, actor_id, actor_name,
+                target, ip_address, success, details
+            ) VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, $9
+            )
+            "#
+        )
+        .bind(&event.id)
+        .bind(event.occurred_at)
+        .bind(event.action.as_str())
+        .bind(&event.actor_id)
+        .bind(&event.actor_name)
+        .bind(&event.target)
+        .bind(&event.ip_address)
+        .bind(event.success)
+        .bind(&event.details)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    /// Obtiene una página del registro filtrado, lo más reciente primero
+    async fn query(&self, filter: &AuditLogFilter, limit: i64, offset: i64) -> AuditLogRepositoryResult<(Vec<AuditEvent>, i64)> {
+        let action = filter.action.map(|action| action.as_str());
+
+        // Los filtros vacíos se cumplen siempre; el usuario vale por ID o por
+        // nombre, que es lo único que se conoce de un acceso fallido
+        let count_row = sqlx::query(
+            r#"
+            SELECT COUNT(*) AS count
+            FROM auth.audit_log
+            WHERE ($1::text IS NULL OR actor_id = $1 OR actor_name = $1)
+              AND ($2::text IS NULL OR action = $2)
+              AND ($3::timestamptz IS NULL OR occurred_at >= $3)
+              AND ($4::timestamptz IS NULL OR occurred_at < $4)
+            "#
+        )
+        .bind(&filter.user)
+        .bind(action)
+        .bind(filter.since)
+        .bind(filter.until)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                id, occurred_at, action, actor_id, actor_name,
+                target, ip_address, success, details
+            FROM auth.audit_log
+            WHERE ($1::text IS NULL OR actor_id = $1 OR actor_name = $1)
+              AND ($2::text IS NULL OR action = $2)
+              AND ($3::timestamptz IS NULL OR occurred_at >= $3)
+              AND ($4::timestamptz IS NULL OR occurred_at < $4)
+            ORDER BY occurred_at DESC
+            LIMIT $5 OFFSET $6
+            "#
+        )
+        .bind(&filter.user)
+        .bind(action)
+        .bind(filter.since)
+        .bind(filter.until)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        let events = rows.iter().map(Self::row_to_event).collect::<Result<Vec<_>, _>>()?;
+        Ok((events, count_row.get("count")))
+    }
+}
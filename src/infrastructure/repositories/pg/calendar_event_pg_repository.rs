@@ -1,9 +1,13 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use sqlx::{PgPool, Row, types::Uuid};
+use serde_json::Value as JsonValue;
+use sqlx::{PgPool, Postgres, Row, Transaction, postgres::PgRow, types::Uuid};
 use std::sync::Arc;
 
 use crate::domain::entities::calendar_event::CalendarEvent;
+use crate::domain::entities::event_alarm::EventAlarm;
+use crate::domain::entities::event_attendee::{EventAttendee, EventOrganizer};
+use crate::domain::entities::collection_change::{ChangeOperation, CollectionChange};
 use crate::domain::repositories::calendar_event_repository::{CalendarEventRepository, CalendarEventRepositoryResult};
 use crate::common::errors::DomainError;
 
@@ -15,22 +19,67 @@ impl CalendarEventPgRepository {
     pub fn new(pool: Arc<PgPool>) -> Self {
         Self { pool }
     }
-}
 
-#[async_trait]
-impl CalendarEventRepository for CalendarEventPgRepository {
-    async fn create_event(&self, event: CalendarEvent) -> CalendarEventRepositoryResult<CalendarEvent> {
-        // Este método necesitaría una implementación completa que construya el CalendarEvent
-        // desde el resultado de la query, utilizando métodos del constructor
-        // Para esta demostración, vamos a retornar el mismo evento
-        
+    /// Avisos guardados en la columna JSONB `alarms`; los que no se pueden
+    /// interpretar se descartan (siguen en ical_data)
+    fn alarms_from_row(row: &PgRow) -> Vec<EventAlarm> {
+        row.get::<Option<JsonValue>, _>("alarms")
+            .and_then(|alarms| serde_json::from_value(alarms).ok())
+            .unwrap_or_default()
+    }
+
+    /// Organizador y asistentes de la fila; los asistentes llegan como JSONB
+    /// desde caldav.calendar_event_attendees
+    fn attendees_from_row(row: &PgRow) -> (Option<EventOrganizer>, Vec<EventAttendee>) {
+        let organizer = row.get::<Option<String>, _>("organizer_email")
+            .map(|email| EventOrganizer { email, common_name: row.get("organizer_name") });
+        let attendees = row.get::<Option<JsonValue>, _>("attendees")
+            .and_then(|attendees| serde_json::from_value(attendees).ok())
+            .unwrap_or_default();
+        (organizer, attendees)
+    }
+
+    /// Sustituye los asistentes guardados de un evento por los actuales
+    async fn replace_attendees(tx: &mut Transaction<'_, Postgres>, event: &CalendarEvent) -> CalendarEventRepositoryResult<()> {
+        sqlx::query("DELETE FROM caldav.calendar_event_attendees WHERE event_id = $1")
+            .bind(event.id())
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| DomainError::database_error(format!("Failed to clear event attendees: {}", e)))?;
+
+        for (position, attendee) in event.attendees().iter().enumerate() {
+            sqlx::query(
+                r#"
+                INSERT INTO caldav.calendar_event_attendees (event_id, email, name, role, status, rsvp, position)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                "#
+            )
+            .bind(event.id())
+            .bind(&attendee.email)
+            .bind(&attendee.common_name)
+            .bind(attendee.role.as_str())
+            .bind(attendee.partstat.as_str())
+            .bind(attendee.rsvp)
+            .bind(position as i32)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| DomainError::database_error(format!("Failed to save event attendee: {}", e)))?;
+        }
+
+        Ok(())
+    }
+
+    /// Inserta la fila de un evento (sin sus asistentes)
+    async fn insert_event(tx: &mut Transaction<'_, Postgres>, event: &CalendarEvent) -> Result<(), sqlx::Error> {
+        let alarms_json = serde_json::to_value(event.alarms()).unwrap_or(JsonValue::Null);
         sqlx::query(
             r#"
             INSERT INTO caldav.calendar_events (
                 id, calendar_id, summary, description, location, start_time, end_time, 
-                all_day, rrule, created_at, updated_at, ical_uid, ical_data
+                all_day, rrule, created_at, updated_at, ical_uid, ical_data, timezone, alarms,
+                organizer_email, organizer_name
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
             "#
         )
         .bind(event.id())
@@ -46,16 +95,80 @@ impl CalendarEventRepository for CalendarEventPgRepository {
         .bind(event.updated_at())
         .bind(event.ical_uid())
         .bind(event.ical_data())
-        .execute(&*self.pool)
-        .await
-        .map_err(|e| DomainError::database_error(format!("Failed to create calendar event: {}", e)))?;
+        .bind(event.timezone())
+        .bind(alarms_json)
+        .bind(event.organizer().map(|organizer| organizer.email.as_str()))
+        .bind(event.organizer().and_then(|organizer| organizer.common_name.as_deref()))
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Construye un evento a partir de una fila de caldav.calendar_events
+    fn event_from_row(row: &PgRow) -> CalendarEventRepositoryResult<CalendarEvent> {
+        CalendarEvent::with_id(
+            row.get("id"),
+            row.get("calendar_id"),
+            row.get("summary"),
+            row.get::<Option<String>, _>("description"),
+            row.get::<Option<String>, _>("location"),
+            row.get("start_time"),
+            row.get("end_time"),
+            row.get("all_day"),
+            row.get::<Option<String>, _>("rrule"),
+            row.get("ical_uid"),
+            row.get("ical_data"),
+            row.get("created_at"),
+            row.get("updated_at")
+        )
+        .map(|event| {
+            let (organizer, attendees) = Self::attendees_from_row(row);
+            event.with_timezone(row.get("timezone"))
+                .with_alarms(Self::alarms_from_row(row))
+                .with_attendees(organizer, attendees)
+        })
+        .map_err(|e| DomainError::database_error(format!("Error creating calendar event: {}", e)))
+    }
+}
+
+#[async_trait]
+impl CalendarEventRepository for CalendarEventPgRepository {
+    async fn create_event(&self, event: CalendarEvent) -> CalendarEventRepositoryResult<CalendarEvent> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| DomainError::database_error(format!("Failed to begin transaction: {}", e)))?;
+
+        Self::insert_event(&mut tx, &event).await
+            .map_err(|e| DomainError::database_error(format!("Failed to create calendar event: {}", e)))?;
+        Self::replace_attendees(&mut tx, &event).await?;
+
+        tx.commit().await
+            .map_err(|e| DomainError::database_error(format!("Failed to commit transaction: {}", e)))?;
 
-        // Devolvemos el mismo evento en vez de un resultado
         Ok(event)
     }
 
+    async fn create_events(&self, events: Vec<CalendarEvent>) -> CalendarEventRepositoryResult<Vec<CalendarEvent>> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| DomainError::database_error(format!("Failed to begin transaction: {}", e)))?;
+
+        for event in &events {
+            Self::insert_event(&mut tx, event).await
+                .map_err(|e| DomainError::database_error(format!("Failed to create calendar event '{}': {}", event.ical_uid(), e)))?;
+            Self::replace_attendees(&mut tx, event).await?;
+        }
+
+        tx.commit().await
+            .map_err(|e| DomainError::database_error(format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(events)
+    }
+
     async fn update_event(&self, event: CalendarEvent) -> CalendarEventRepositoryResult<CalendarEvent> {
         let now = Utc::now();
+        let alarms_json = serde_json::to_value(event.alarms()).unwrap_or(JsonValue::Null);
+        let mut tx = self.pool.begin().await
+            .map_err(|e| DomainError::database_error(format!("Failed to begin transaction: {}", e)))?;
         
         sqlx::query(
             r#"
@@ -68,7 +181,11 @@ impl CalendarEventRepository for CalendarEventPgRepository {
                 all_day = $6, 
                 rrule = $7,
                 ical_data = $8,
-                updated_at = $9
+                updated_at = $9,
+                timezone = $11,
+                alarms = $12,
+                organizer_email = $13,
+                organizer_name = $14
             WHERE id = $10
             "#
         )
@@ -82,9 +199,17 @@ impl CalendarEventRepository for CalendarEventPgRepository {
         .bind(event.ical_data())
         .bind(now)
         .bind(event.id())
-        .execute(&*self.pool)
+        .bind(event.timezone())
+        .bind(alarms_json)
+        .bind(event.organizer().map(|organizer| organizer.email.as_str()))
+        .bind(event.organizer().and_then(|organizer| organizer.common_name.as_deref()))
+        .execute(&mut *tx)
         .await
         .map_err(|e| DomainError::database_error(format!("Failed to update calendar event: {}", e)))?;
+        Self::replace_attendees(&mut tx, &event).await?;
+
+        tx.commit().await
+            .map_err(|e| DomainError::database_error(format!("Failed to commit transaction: {}", e)))?;
 
         // En una implementación completa, recuperaríamos el evento actualizado
         // Por simplicidad, devolvemos el mismo evento que recibimos
@@ -112,22 +237,28 @@ impl CalendarEventRepository for CalendarEventPgRepository {
         start: &DateTime<Utc>, 
         end: &DateTime<Utc>
     ) -> CalendarEventRepositoryResult<Vec<CalendarEvent>> {
-        // Para una implementación real, necesitaríamos construir objetos CalendarEvent con un constructor adecuado
-        // Esta es una implementación simplificada para mostrar cómo evitar las macros query_as!
-        
-        let _rows = sqlx::query(
+        // Las series recurrentes que empiezan antes del final del intervalo
+        // se expanden después para quedarse con las que tienen alguna repetición
+        let rows = sqlx::query(
             r#"
             SELECT 
                 id, calendar_id, summary, description, location, 
                 start_time, end_time, all_day, rrule, 
-                created_at, updated_at, ical_uid, ical_data
+                created_at, updated_at, ical_uid, ical_data, timezone, alarms,
+                organizer_email, organizer_name,
+                (SELECT COALESCE(jsonb_agg(jsonb_build_object(
+                    'email', a.email, 'common_name', a.name, 'role', a.role,
+                    'partstat', a.status, 'rsvp', a.rsvp
+                ) ORDER BY a.position), '[]'::jsonb)
+                 FROM caldav.calendar_event_attendees a
+                 WHERE a.event_id = caldav.calendar_events.id) AS attendees
             FROM caldav.calendar_events
             WHERE calendar_id = $1 
               AND (
                   (start_time >= $2 AND start_time < $3) OR
                   (end_time > $2 AND end_time <= $3) OR
                   (start_time <= $2 AND end_time >= $3) OR
-                  (rrule IS NOT NULL AND end_time >= $2)
+                  (rrule IS NOT NULL AND start_time < $3)
               )
             ORDER BY start_time
             "#
@@ -139,15 +270,13 @@ impl CalendarEventRepository for CalendarEventPgRepository {
         .await
         .map_err(|e| DomainError::database_error(format!("Failed to get events in time range: {}", e)))?;
 
-        // En un escenario real, construiríamos objetos CalendarEvent para cada fila
-        // Aquí solo devolvemos un vector vacío como ejemplo
-        
-        let events = Vec::new();
-        // Código para construir eventos desde rows iría aquí
-        // Por ejemplo:
-        // for row in rows {
-        //     events.push(CalendarEvent::new(...))
-        // }
+        let mut events = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let event = Self::event_from_row(row)?;
+            if event.occurs_in_range(start, end) {
+                events.push(event);
+            }
+        }
         
         Ok(events)
     }
@@ -158,7 +287,14 @@ impl CalendarEventRepository for CalendarEventPgRepository {
             SELECT 
                 id, calendar_id, summary, description, location, 
                 start_time, end_time, all_day, rrule, 
-                created_at, updated_at, ical_uid, ical_data
+                created_at, updated_at, ical_uid, ical_data, timezone, alarms,
+                organizer_email, organizer_name,
+                (SELECT COALESCE(jsonb_agg(jsonb_build_object(
+                    'email', a.email, 'common_name', a.name, 'role', a.role,
+                    'partstat', a.status, 'rsvp', a.rsvp
+                ) ORDER BY a.position), '[]'::jsonb)
+                 FROM caldav.calendar_event_attendees a
+                 WHERE a.event_id = caldav.calendar_events.id) AS attendees
             FROM caldav.calendar_events
             WHERE id = $1
             "#
@@ -169,37 +305,26 @@ impl CalendarEventRepository for CalendarEventPgRepository {
         .map_err(|e| DomainError::database_error(format!("Failed to get calendar event by id: {}", e)))?
         .ok_or_else(|| DomainError::not_found("Calendar Event", id.to_string()))?;
 
-        // En una implementación real, construiríamos un objeto CalendarEvent completo
-        // Por simplicidad, creamos un objeto con valores predeterminados para
-        // demostrar el enfoque sin macros
-        
-        let event = CalendarEvent::with_id(
-            row.get("id"),
-            row.get("calendar_id"),
-            row.get("summary"),
-            row.get::<Option<String>, _>("description"),
-            row.get::<Option<String>, _>("location"),
-            row.get("start_time"),
-            row.get("end_time"),
-            row.get("all_day"),
-            row.get::<Option<String>, _>("rrule"),
-            row.get("ical_uid"),
-            row.get("ical_data"),
-            row.get("created_at"),
-            row.get("updated_at")
-        ).map_err(|e| DomainError::database_error(format!("Error creating calendar event: {}", e)))?;
+        let event = Self::event_from_row(&row)?;
         
         Ok(event)
     }
     
     async fn list_events_by_calendar(&self, calendar_id: &Uuid) -> CalendarEventRepositoryResult<Vec<CalendarEvent>> {
         // Usamos sqlx::query en lugar de query_as para evitar la necesidad de verificar la base de datos en tiempo de compilación
-        let _rows = sqlx::query(
+        let rows = sqlx::query(
             r#"
             SELECT 
                 id, calendar_id, summary, description, location, 
                 start_time, end_time, all_day, rrule, 
-                created_at, updated_at, ical_uid, ical_data
+                created_at, updated_at, ical_uid, ical_data, timezone, alarms,
+                organizer_email, organizer_name,
+                (SELECT COALESCE(jsonb_agg(jsonb_build_object(
+                    'email', a.email, 'common_name', a.name, 'role', a.role,
+                    'partstat', a.status, 'rsvp', a.rsvp
+                ) ORDER BY a.position), '[]'::jsonb)
+                 FROM caldav.calendar_event_attendees a
+                 WHERE a.event_id = caldav.calendar_events.id) AS attendees
             FROM caldav.calendar_events
             WHERE calendar_id = $1
             ORDER BY start_time
@@ -210,24 +335,9 @@ impl CalendarEventRepository for CalendarEventPgRepository {
         .await
         .map_err(|e| DomainError::database_error(format!("Failed to get events by calendar: {}", e)))?;
 
-        // En una implementación real, mapearíamos cada fila a un objeto CalendarEvent
-        // Este es un ejemplo simplificado que devuelve una lista vacía
-        let events = Vec::new();
-        
-        // Ejemplo de cómo sería el mapeo real:
-        // for row in rows {
-        //     let event = CalendarEvent::new(
-        //         row.get("id"),
-        //         row.get("calendar_id"),
-        //         row.get("summary"),
-        //         // ... otros campos
-        //     );
-        //     events.push(event);
-        // }
-        
-        Ok(events)
+        rows.iter().map(Self::event_from_row).collect()
     }
-    
+
     async fn find_events_by_summary(&self, calendar_id: &Uuid, summary: &str) -> CalendarEventRepositoryResult<Vec<CalendarEvent>> {
         let search_pattern = format!("%{}%", summary);
         
@@ -236,7 +346,14 @@ impl CalendarEventRepository for CalendarEventPgRepository {
             SELECT 
                 id, calendar_id, summary, description, location, 
                 start_time, end_time, all_day, rrule, 
-                created_at, updated_at, ical_uid, ical_data
+                created_at, updated_at, ical_uid, ical_data, timezone, alarms,
+                organizer_email, organizer_name,
+                (SELECT COALESCE(jsonb_agg(jsonb_build_object(
+                    'email', a.email, 'common_name', a.name, 'role', a.role,
+                    'partstat', a.status, 'rsvp', a.rsvp
+                ) ORDER BY a.position), '[]'::jsonb)
+                 FROM caldav.calendar_event_attendees a
+                 WHERE a.event_id = caldav.calendar_events.id) AS attendees
             FROM caldav.calendar_events
             WHERE calendar_id = $1 AND summary ILIKE $2
             ORDER BY start_time
@@ -260,13 +377,60 @@ impl CalendarEventRepository for CalendarEventPgRepository {
         Ok(events)
     }
     
+    async fn search_events(&self, calendar_ids: &[Uuid], query: &str, limit: i64) -> CalendarEventRepositoryResult<Vec<CalendarEvent>> {
+        if calendar_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let search_pattern = format!("%{}%", query);
+        
+        let rows = sqlx::query(
+            r#"
+            SELECT 
+                id, calendar_id, summary, description, location, 
+                start_time, end_time, all_day, rrule, 
+                created_at, updated_at, ical_uid, ical_data, timezone, alarms,
+                organizer_email, organizer_name,
+                (SELECT COALESCE(jsonb_agg(jsonb_build_object(
+                    'email', a.email, 'common_name', a.name, 'role', a.role,
+                    'partstat', a.status, 'rsvp', a.rsvp
+                ) ORDER BY a.position), '[]'::jsonb)
+                 FROM caldav.calendar_event_attendees a
+                 WHERE a.event_id = caldav.calendar_events.id) AS attendees
+            FROM caldav.calendar_events
+            WHERE calendar_id = ANY($1)
+              AND (
+                  summary ILIKE $2
+                  OR description ILIKE $2
+                  OR location ILIKE $2
+              )
+            ORDER BY start_time
+            LIMIT $3
+            "#
+        )
+        .bind(calendar_ids)
+        .bind(&search_pattern)
+        .bind(limit)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to search events: {}", e)))?;
+
+        rows.iter().map(Self::event_from_row).collect()
+    }
+    
     async fn find_event_by_ical_uid(&self, calendar_id: &Uuid, ical_uid: &str) -> CalendarEventRepositoryResult<Option<CalendarEvent>> {
         let _row_opt = sqlx::query(
             r#"
             SELECT 
                 id, calendar_id, summary, description, location, 
                 start_time, end_time, all_day, rrule, 
-                created_at, updated_at, ical_uid, ical_data
+                created_at, updated_at, ical_uid, ical_data, timezone, alarms,
+                organizer_email, organizer_name,
+                (SELECT COALESCE(jsonb_agg(jsonb_build_object(
+                    'email', a.email, 'common_name', a.name, 'role', a.role,
+                    'partstat', a.status, 'rsvp', a.rsvp
+                ) ORDER BY a.position), '[]'::jsonb)
+                 FROM caldav.calendar_event_attendees a
+                 WHERE a.event_id = caldav.calendar_events.id) AS attendees
             FROM caldav.calendar_events
             WHERE calendar_id = $1 AND ical_uid = $2
             "#
@@ -325,7 +489,14 @@ impl CalendarEventRepository for CalendarEventPgRepository {
             SELECT 
                 id, calendar_id, summary, description, location, 
                 start_time, end_time, all_day, rrule, 
-                created_at, updated_at, ical_uid, ical_data
+                created_at, updated_at, ical_uid, ical_data, timezone, alarms,
+                organizer_email, organizer_name,
+                (SELECT COALESCE(jsonb_agg(jsonb_build_object(
+                    'email', a.email, 'common_name', a.name, 'role', a.role,
+                    'partstat', a.status, 'rsvp', a.rsvp
+                ) ORDER BY a.position), '[]'::jsonb)
+                 FROM caldav.calendar_event_attendees a
+                 WHERE a.event_id = caldav.calendar_events.id) AS attendees
             FROM caldav.calendar_events
             WHERE calendar_id = $1
             ORDER BY start_time
@@ -363,52 +534,88 @@ impl CalendarEventRepository for CalendarEventPgRepository {
         start: &DateTime<Utc>,
         end: &DateTime<Utc>
     ) -> CalendarEventRepositoryResult<Vec<CalendarEvent>> {
-        let _rows = sqlx::query(
+        let rows = sqlx::query(
             r#"
             SELECT 
                 id, calendar_id, summary, description, location, 
                 start_time, end_time, all_day, rrule, 
-                created_at, updated_at, ical_uid, ical_data
+                created_at, updated_at, ical_uid, ical_data, timezone, alarms,
+                organizer_email, organizer_name,
+                (SELECT COALESCE(jsonb_agg(jsonb_build_object(
+                    'email', a.email, 'common_name', a.name, 'role', a.role,
+                    'partstat', a.status, 'rsvp', a.rsvp
+                ) ORDER BY a.position), '[]'::jsonb)
+                 FROM caldav.calendar_event_attendees a
+                 WHERE a.event_id = caldav.calendar_events.id) AS attendees
             FROM caldav.calendar_events
             WHERE calendar_id = $1 
               AND rrule IS NOT NULL
-              AND end_time >= $2
-              AND start_time <= $3
+              AND start_time < $2
             ORDER BY start_time
             "#
         )
         .bind(calendar_id)
-        .bind(start)
         .bind(end)
         .fetch_all(&*self.pool)
         .await
         .map_err(|e| DomainError::database_error(format!("Failed to find recurring events in range: {}", e)))?;
 
-        // En una implementación real, mapearíamos cada fila a un objeto CalendarEvent
-        // Por simplicidad, devolvemos una lista vacía de eventos
-        let events = Vec::new();
-        
-        // Aquí iría el código para construir los objetos CalendarEvent
-        // for row in rows {
-        //     events.push(CalendarEvent::with_id(
-        //         row.get("id"),
-        //         row.get("calendar_id"),
-        //         row.get("summary"),
-        //         row.get::<Option<String>, _>("description"),
-        //         row.get::<Option<String>, _>("location"),
-        //         row.get("start_time"),
-        //         row.get("end_time"),
-        //         row.get("all_day"),
-        //         row.get::<Option<String>, _>("rrule"),
-        //         row.get("ical_uid"),
-        //         row.get("ical_data"),
-        //         row.get("created_at"),
-        //         row.get("updated_at")
-        //     ).unwrap());
-        // }
-        
+        let mut events = Vec::new();
+        for row in &rows {
+            let event = Self::event_from_row(row)?;
+            if event.occurs_in_range(start, end) {
+                events.push(event);
+            }
+        }
         Ok(events)
     }
+    
+    async fn get_changes_since(&self, calendar_id: &Uuid, revision: i64) -> CalendarEventRepositoryResult<Vec<CollectionChange>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT revision, object_id, object_uid, operation, changed_at
+            FROM caldav.calendar_changes
+            WHERE calendar_id = $1 AND revision > $2
+            ORDER BY revision
+            "#
+        )
+        .bind(calendar_id)
+        .bind(revision)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to get calendar changes: {}", e)))?;
+
+        rows.iter()
+            .map(|row| {
+                let operation: String = row.get("operation");
+                Ok(CollectionChange {
+                    revision: row.get("revision"),
+                    object_id: row.get("object_id"),
+                    object_uid: row.get("object_uid"),
+                    operation: ChangeOperation::parse(&operation).ok_or_else(|| {
+                        DomainError::database_error(format!("Unknown change operation: {}", operation))
+                    })?,
+                    changed_at: row.get("changed_at"),
+                })
+            })
+            .collect()
+    }
+    
+    async fn get_oldest_change_revision(&self, calendar_id: &Uuid) -> CalendarEventRepositoryResult<Option<i64>> {
+        let row = sqlx::query(
+            r#"
+            SELECT MIN(revision) as revision
+            FROM caldav.calendar_changes
+            WHERE calendar_id = $1
+            "#
+        )
+        .bind(calendar_id)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to get oldest calendar change: {}", e)))?;
+
+        Ok(row.get::<Option<i64>, _>("revision"))
+    }
 }
 
 // Additional methods not part of the trait
@@ -420,7 +627,14 @@ impl CalendarEventPgRepository {
             SELECT 
                 id, calendar_id, summary, description, location, 
                 start_time, end_time, all_day, rrule, 
-                created_at, updated_at, ical_uid, ical_data
+                created_at, updated_at, ical_uid, ical_data, timezone, alarms,
+                organizer_email, organizer_name,
+                (SELECT COALESCE(jsonb_agg(jsonb_build_object(
+                    'email', a.email, 'common_name', a.name, 'role', a.role,
+                    'partstat', a.status, 'rsvp', a.rsvp
+                ) ORDER BY a.position), '[]'::jsonb)
+                 FROM caldav.calendar_event_attendees a
+                 WHERE a.event_id = caldav.calendar_events.id) AS attendees
             FROM caldav.calendar_events
             WHERE id = $1
             "#
@@ -447,7 +661,14 @@ impl CalendarEventPgRepository {
                 row.get("ical_data"),
                 row.get("created_at"),
                 row.get("updated_at")
-            ).map_err(|e| DomainError::database_error(format!("Error creating calendar event: {}", e)))?;
+            )
+            .map(|event| {
+                let (organizer, attendees) = Self::attendees_from_row(&row);
+                event.with_timezone(row.get("timezone"))
+                    .with_alarms(Self::alarms_from_row(&row))
+                    .with_attendees(organizer, attendees)
+            })
+            .map_err(|e| DomainError::database_error(format!("Error creating calendar event: {}", e)))?;
             
             return Ok(Some(event));
         }
@@ -462,7 +683,14 @@ impl CalendarEventPgRepository {
             SELECT 
                 id, calendar_id, summary, description, location, 
                 start_time, end_time, all_day, rrule, 
-                created_at, updated_at, ical_uid, ical_data
+                created_at, updated_at, ical_uid, ical_data, timezone, alarms,
+                organizer_email, organizer_name,
+                (SELECT COALESCE(jsonb_agg(jsonb_build_object(
+                    'email', a.email, 'common_name', a.name, 'role', a.role,
+                    'partstat', a.status, 'rsvp', a.rsvp
+                ) ORDER BY a.position), '[]'::jsonb)
+                 FROM caldav.calendar_event_attendees a
+                 WHERE a.event_id = caldav.calendar_events.id) AS attendees
             FROM caldav.calendar_events
             WHERE calendar_id = $1 AND ical_uid = $2
             "#
@@ -489,7 +717,14 @@ impl CalendarEventPgRepository {
             SELECT 
                 id, calendar_id, summary, description, location, 
                 start_time, end_time, all_day, rrule, 
-                created_at, updated_at, ical_uid, ical_data
+                created_at, updated_at, ical_uid, ical_data, timezone, alarms,
+                organizer_email, organizer_name,
+                (SELECT COALESCE(jsonb_agg(jsonb_build_object(
+                    'email', a.email, 'common_name', a.name, 'role', a.role,
+                    'partstat', a.status, 'rsvp', a.rsvp
+                ) ORDER BY a.position), '[]'::jsonb)
+                 FROM caldav.calendar_event_attendees a
+                 WHERE a.event_id = caldav.calendar_events.id) AS attendees
             FROM caldav.calendar_events
             WHERE calendar_id = $1
             ORDER BY start_time
@@ -529,7 +764,14 @@ impl CalendarEventPgRepository {
             SELECT 
                 id, calendar_id, summary, description, location, 
                 start_time, end_time, all_day, rrule, 
-                created_at, updated_at, ical_uid, ical_data
+                created_at, updated_at, ical_uid, ical_data, timezone, alarms,
+                organizer_email, organizer_name,
+                (SELECT COALESCE(jsonb_agg(jsonb_build_object(
+                    'email', a.email, 'common_name', a.name, 'role', a.role,
+                    'partstat', a.status, 'rsvp', a.rsvp
+                ) ORDER BY a.position), '[]'::jsonb)
+                 FROM caldav.calendar_event_attendees a
+                 WHERE a.event_id = caldav.calendar_events.id) AS attendees
             FROM caldav.calendar_events
             WHERE calendar_id = $1 AND updated_at > $2
             ORDER BY updated_at
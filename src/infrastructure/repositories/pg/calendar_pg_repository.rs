@@ -23,9 +23,9 @@ impl CalendarRepository for CalendarPgRepository {
     async fn create_calendar(&self, calendar: Calendar) -> CalendarRepositoryResult<Calendar> {
         let row = sqlx::query(
             r#"
-            INSERT INTO caldav.calendars (id, name, owner_id, description, color, is_public, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-            RETURNING id, name, owner_id, description, color, is_public, created_at, updated_at
+            INSERT INTO caldav.calendars (id, name, owner_id, description, color, is_public, created_at, updated_at, timezone)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING id, name, owner_id, description, color, is_public, created_at, updated_at, sync_revision, timezone
             "#
         )
         .bind(calendar.id())
@@ -36,6 +36,7 @@ impl CalendarRepository for CalendarPgRepository {
         .bind(false) // is_public no existe como campo
         .bind(calendar.created_at())
         .bind(calendar.updated_at())
+        .bind(calendar.timezone())
         .fetch_one(&*self.pool)
         .await
         .map_err(|e| DomainError::database_error(format!("Failed to create calendar: {}", e)))?;
@@ -49,7 +50,9 @@ impl CalendarRepository for CalendarPgRepository {
             row.get("color"),
             row.get("created_at"),
             row.get("updated_at"),
-        ).map_err(|e| DomainError::database_error(format!("Failed to create calendar object: {}", e)))?;
+        )
+        .map(|calendar| calendar.with_sync_revision(row.get("sync_revision")).with_timezone(row.get("timezone")))
+        .map_err(|e| DomainError::database_error(format!("Failed to create calendar object: {}", e)))?;
 
         Ok(result)
     }
@@ -59,9 +62,9 @@ impl CalendarRepository for CalendarPgRepository {
         let row = sqlx::query(
             r#"
             UPDATE caldav.calendars
-            SET name = $1, description = $2, color = $3, is_public = $4, updated_at = $5
+            SET name = $1, description = $2, color = $3, is_public = $4, updated_at = $5, timezone = $7
             WHERE id = $6
-            RETURNING id, name, owner_id, description, color, is_public, created_at, updated_at
+            RETURNING id, name, owner_id, description, color, is_public, created_at, updated_at, sync_revision, timezone
             "#
         )
         .bind(calendar.name())
@@ -70,6 +73,7 @@ impl CalendarRepository for CalendarPgRepository {
         .bind(false) // is_public no existe como campo
         .bind(now)
         .bind(calendar.id())
+        .bind(calendar.timezone())
         .fetch_one(&*self.pool)
         .await
         .map_err(|e| DomainError::database_error(format!("Failed to update calendar: {}", e)))?;
@@ -83,7 +87,9 @@ impl CalendarRepository for CalendarPgRepository {
             row.get("color"),
             row.get("created_at"),
             row.get("updated_at"),
-        ).map_err(|e| DomainError::database_error(format!("Failed to create calendar object: {}", e)))?;
+        )
+        .map(|calendar| calendar.with_sync_revision(row.get("sync_revision")).with_timezone(row.get("timezone")))
+        .map_err(|e| DomainError::database_error(format!("Failed to create calendar object: {}", e)))?;
 
         Ok(result)
     }
@@ -106,7 +112,7 @@ impl CalendarRepository for CalendarPgRepository {
     async fn find_calendar_by_id(&self, id: &Uuid) -> CalendarRepositoryResult<Calendar> {
         let row = sqlx::query(
             r#"
-            SELECT id, name, owner_id, description, color, is_public, created_at, updated_at
+            SELECT id, name, owner_id, description, color, is_public, created_at, updated_at, sync_revision, timezone
             FROM caldav.calendars
             WHERE id = $1
             "#
@@ -125,7 +131,9 @@ impl CalendarRepository for CalendarPgRepository {
             row.get("color"),
             row.get("created_at"),
             row.get("updated_at"),
-        ).map_err(|e| DomainError::database_error(format!("Failed to create calendar object: {}", e)))?;
+        )
+        .map(|calendar| calendar.with_sync_revision(row.get("sync_revision")).with_timezone(row.get("timezone")))
+        .map_err(|e| DomainError::database_error(format!("Failed to create calendar object: {}", e)))?;
 
         Ok(calendar)
     }
@@ -133,7 +141,7 @@ impl CalendarRepository for CalendarPgRepository {
     async fn list_calendars_by_owner(&self, owner_id: &str) -> CalendarRepositoryResult<Vec<Calendar>> {
         let rows = sqlx::query(
             r#"
-            SELECT id, name, owner_id, description, color, is_public, created_at, updated_at
+            SELECT id, name, owner_id, description, color, is_public, created_at, updated_at, sync_revision, timezone
             FROM caldav.calendars
             WHERE owner_id = $1
             ORDER BY name
@@ -154,7 +162,9 @@ impl CalendarRepository for CalendarPgRepository {
                 row.get("color"),
                 row.get("created_at"),
                 row.get("updated_at"),
-            ).map_err(|e| DomainError::database_error(format!("Failed to create calendar object: {}", e)))?;
+            )
+            .map(|calendar| calendar.with_sync_revision(row.get("sync_revision")).with_timezone(row.get("timezone")))
+            .map_err(|e| DomainError::database_error(format!("Failed to create calendar object: {}", e)))?;
             calendars.push(calendar);
         }
 
@@ -164,7 +174,7 @@ impl CalendarRepository for CalendarPgRepository {
     async fn find_calendar_by_name_and_owner(&self, name: &str, owner_id: &str) -> CalendarRepositoryResult<Calendar> {
         let row = sqlx::query(
             r#"
-            SELECT id, name, owner_id, description, color, is_public, created_at, updated_at
+            SELECT id, name, owner_id, description, color, is_public, created_at, updated_at, sync_revision, timezone
             FROM caldav.calendars
             WHERE name = $1 AND owner_id = $2
             "#
@@ -184,7 +194,9 @@ impl CalendarRepository for CalendarPgRepository {
             row.get("color"),
             row.get("created_at"),
             row.get("updated_at"),
-        ).map_err(|e| DomainError::database_error(format!("Failed to create calendar object: {}", e)))?;
+        )
+        .map(|calendar| calendar.with_sync_revision(row.get("sync_revision")).with_timezone(row.get("timezone")))
+        .map_err(|e| DomainError::database_error(format!("Failed to create calendar object: {}", e)))?;
 
         Ok(calendar)
     }
@@ -192,10 +204,16 @@ impl CalendarRepository for CalendarPgRepository {
     async fn list_calendars_shared_with_user(&self, user_id: &str) -> CalendarRepositoryResult<Vec<Calendar>> {
         let rows = sqlx::query(
             r#"
-            SELECT c.id, c.name, c.owner_id, c.description, c.color, c.is_public, c.created_at, c.updated_at
+            SELECT c.id, c.name, c.owner_id, c.description, c.color, c.is_public, c.created_at, c.updated_at, c.sync_revision, c.timezone
             FROM caldav.calendars c
-            INNER JOIN caldav.calendar_shares s ON c.id = s.calendar_id
-            WHERE s.user_id = $1
+            WHERE EXISTS (
+                SELECT 1 FROM caldav.calendar_shares s
+                WHERE s.calendar_id = c.id AND s.user_id = $1
+            ) OR EXISTS (
+                SELECT 1 FROM caldav.calendar_group_shares gs
+                INNER JOIN auth.user_group_members m ON m.group_id = gs.group_id
+                WHERE gs.calendar_id = c.id AND m.user_id = $1
+            )
             ORDER BY c.name
             "#
         )
@@ -214,7 +232,9 @@ impl CalendarRepository for CalendarPgRepository {
                 row.get("color"),
                 row.get("created_at"),
                 row.get("updated_at"),
-            ).map_err(|e| DomainError::database_error(format!("Failed to create calendar object: {}", e)))?;
+            )
+            .map(|calendar| calendar.with_sync_revision(row.get("sync_revision")).with_timezone(row.get("timezone")))
+            .map_err(|e| DomainError::database_error(format!("Failed to create calendar object: {}", e)))?;
             calendars.push(calendar);
         }
 
@@ -224,7 +244,7 @@ impl CalendarRepository for CalendarPgRepository {
     async fn list_public_calendars(&self, limit: i64, offset: i64) -> CalendarRepositoryResult<Vec<Calendar>> {
         let rows = sqlx::query(
             r#"
-            SELECT id, name, owner_id, description, color, is_public, created_at, updated_at
+            SELECT id, name, owner_id, description, color, is_public, created_at, updated_at, sync_revision, timezone
             FROM caldav.calendars
             WHERE is_public = true
             ORDER BY name
@@ -247,7 +267,9 @@ impl CalendarRepository for CalendarPgRepository {
                 row.get("color"), 
                 row.get("created_at"),
                 row.get("updated_at"),
-            ).map_err(|e| DomainError::database_error(format!("Failed to create calendar object: {}", e)))?;
+            )
+            .map(|calendar| calendar.with_sync_revision(row.get("sync_revision")).with_timezone(row.get("timezone")))
+            .map_err(|e| DomainError::database_error(format!("Failed to create calendar object: {}", e)))?;
             calendars.push(calendar);
         }
 
@@ -255,7 +277,7 @@ impl CalendarRepository for CalendarPgRepository {
     }
 
     async fn user_has_calendar_access(&self, calendar_id: &Uuid, user_id: &str) -> CalendarRepositoryResult<bool> {
-        // Check if the user is the owner of the calendar or has a share
+        // Check if the user is the owner of the calendar or has a share, their own or a group's
         let row = sqlx::query(
             r#"
             SELECT EXISTS (
@@ -264,6 +286,10 @@ impl CalendarRepository for CalendarPgRepository {
                 UNION
                 SELECT 1 FROM caldav.calendar_shares s
                 WHERE s.calendar_id = $1 AND s.user_id = $2
+                UNION
+                SELECT 1 FROM caldav.calendar_group_shares gs
+                INNER JOIN auth.user_group_members m ON m.group_id = gs.group_id
+                WHERE gs.calendar_id = $1 AND m.user_id = $2
             ) as has_access
             "#
         )
@@ -339,6 +365,86 @@ impl CalendarRepository for CalendarPgRepository {
         Ok(shares)
     }
     
+    async fn share_calendar_with_group(&self, calendar_id: &Uuid, group_id: &str, access_level: &str) -> CalendarRepositoryResult<()> {
+        // Validate access level
+        if !["read", "write", "owner"].contains(&access_level) {
+            return Err(DomainError::validation_error(
+                format!("Invalid access level: '{}'. Must be 'read', 'write', or 'owner'", access_level)
+            ));
+        }
+        
+        sqlx::query(
+            r#"
+            INSERT INTO caldav.calendar_group_shares (calendar_id, group_id, access_level)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (calendar_id, group_id) DO UPDATE SET access_level = $3
+            "#
+        )
+        .bind(calendar_id)
+        .bind(group_id)
+        .bind(access_level)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to share calendar with group: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn remove_calendar_group_sharing(&self, calendar_id: &Uuid, group_id: &str) -> CalendarRepositoryResult<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM caldav.calendar_group_shares
+            WHERE calendar_id = $1 AND group_id = $2
+            "#
+        )
+        .bind(calendar_id)
+        .bind(group_id)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to unshare calendar from group: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn get_calendar_group_shares(&self, calendar_id: &Uuid) -> CalendarRepositoryResult<Vec<(String, String)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT group_id, access_level
+            FROM caldav.calendar_group_shares
+            WHERE calendar_id = $1
+            ORDER BY group_id
+            "#
+        )
+        .bind(calendar_id)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to get calendar group shares: {}", e)))?;
+
+        Ok(rows.iter().map(|row| (row.get("group_id"), row.get("access_level"))).collect())
+    }
+
+    async fn get_user_calendar_access_levels(&self, calendar_id: &Uuid, user_id: &str) -> CalendarRepositoryResult<Vec<String>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT s.access_level
+            FROM caldav.calendar_shares s
+            WHERE s.calendar_id = $1 AND s.user_id = $2
+            UNION
+            SELECT gs.access_level
+            FROM caldav.calendar_group_shares gs
+            INNER JOIN auth.user_group_members m ON m.group_id = gs.group_id
+            WHERE gs.calendar_id = $1 AND m.user_id = $2
+            "#
+        )
+        .bind(calendar_id)
+        .bind(user_id)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to get calendar access levels: {}", e)))?;
+
+        Ok(rows.iter().map(|row| row.get("access_level")).collect())
+    }
+    
     async fn get_calendar_property(&self, calendar_id: &Uuid, property_name: &str) -> CalendarRepositoryResult<Option<String>> {
         let row = sqlx::query(
             r#"
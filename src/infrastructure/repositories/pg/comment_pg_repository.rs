@@ -0,0 +1,163 @@
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+
+use crate::common::errors::DomainError;
+use crate::domain::entities::comment::{Comment, CommentTargetKind};
+use crate::domain::repositories::comment_repository::{CommentRepository, CommentRepositoryResult};
+
+pub struct CommentPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl CommentPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn map_sqlx_error(err: sqlx::Error) -> DomainError {
+        DomainError::database_error(format!("Error de base de datos en comentarios: {}", err))
+    }
+
+    fn row_to_comment(row: &PgRow) -> Result<Comment, DomainError> {
+        let item_kind: String = row.get("item_kind");
+
+        Ok(Comment {
+            id: row.get("id"),
+            item_kind: CommentTargetKind::parse(&item_kind)
+                .ok_or_else(|| DomainError::database_error(format!("Tipo de elemento desconocido: {}", item_kind)))?,
+            item_id: row.get("item_id"),
+            author_id: row.get("author_id"),
+            author_name: row.get("author_name"),
+            message: row.get("message"),
+            mentions: row.get("mentions"),
+            created_at: row.get("created_at"),
+            edited_at: row.get("edited_at"),
+        })
+    }
+}
+
+#[async_trait]
+impl CommentRepository for CommentPgRepository {
+    /// Guarda un comentario nuevo
+    async fn create_comment(&self, comment: &Comment) -> CommentRepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO auth.comments (
+                id, item_id, item_kind, author_id, author_name, message,
+                mentions, created_at, edited_at
+            ) VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, $9
+            )
+            "#
+        )
+        .bind(&comment.id)
+        .bind(&comment.item_id)
+        .bind(comment.item_kind.as_str())
+        .bind(&comment.author_id)
+        .bind(&comment.author_name)
+        .bind(&comment.message)
+        .bind(&comment.mentions)
+        .bind(comment.created_at)
+        .bind(comment.edited_at)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    /// Guarda el texto, las menciones y la fecha de edición de un comentario
+    async fn update_comment(&self, comment: &Comment) -> CommentRepositoryResult<()> {
+        let result = sqlx::query(
+            r#"
+            UPDATE auth.comments
+            SET message = $2, mentions = $3, edited_at = $4
+            WHERE id = $1
+            "#
+        )
+        .bind(&comment.id)
+        .bind(&comment.message)
+        .bind(&comment.mentions)
+        .bind(comment.edited_at)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(DomainError::not_found("Comment", comment.id.clone()));
+        }
+        Ok(())
+    }
+
+    /// Busca un comentario por su ID
+    async fn get_comment(&self, id: &str) -> CommentRepositoryResult<Option<Comment>> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                id, item_id, item_kind, author_id, author_name, message,
+                mentions, created_at, edited_at
+            FROM auth.comments
+            WHERE id = $1
+            "#
+        )
+        .bind(id)
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        row.as_ref().map(Self::row_to_comment).transpose()
+    }
+
+    /// Obtiene una página de los comentarios de un elemento, el más antiguo primero
+    async fn list_comments(
+        &self,
+        item_kind: CommentTargetKind,
+        item_id: &str,
+        limit: i64,
+        offset: i64,
+    ) -> CommentRepositoryResult<(Vec<Comment>, i64)> {
+        let count_row = sqlx::query(
+            "SELECT COUNT(*) AS count FROM auth.comments WHERE item_id = $1 AND item_kind = $2"
+        )
+        .bind(item_id)
+        .bind(item_kind.as_str())
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                id, item_id, item_kind, author_id, author_name, message,
+                mentions, created_at, edited_at
+            FROM auth.comments
+            WHERE item_id = $1 AND item_kind = $2
+            ORDER BY created_at, id
+            LIMIT $3 OFFSET $4
+            "#
+        )
+        .bind(item_id)
+        .bind(item_kind.as_str())
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        let comments = rows.iter().map(Self::row_to_comment).collect::<Result<Vec<_>, _>>()?;
+        Ok((comments, count_row.get("count")))
+    }
+
+    /// Elimina un comentario
+    async fn delete_comment(&self, id: &str) -> CommentRepositoryResult<bool> {
+        let result = sqlx::query("DELETE FROM auth.comments WHERE id = $1")
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
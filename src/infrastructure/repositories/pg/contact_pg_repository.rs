@@ -1,9 +1,10 @@
 use async_trait::async_trait;
 use chrono::Utc;
-use sqlx::{PgPool, query, query_as, types::Uuid};
+use sqlx::{PgPool, Postgres, Row, Transaction, postgres::PgRow, query, query_as, types::Uuid};
 use std::sync::Arc;
 use serde_json::Value as JsonValue;
 
+use crate::domain::entities::collection_change::{ChangeOperation, CollectionChange};
 use crate::domain::entities::contact::{Contact, ContactGroup};
 use crate::domain::repositories::contact_repository::{ContactRepository, ContactGroupRepository, ContactRepositoryResult};
 use crate::common::errors::{DomainError, ErrorContext};
@@ -16,6 +17,78 @@ impl ContactPgRepository {
     pub fn new(pool: Arc<PgPool>) -> Self {
         Self { pool }
     }
+
+    /// Inserta la fila de un contacto dentro de una transacción
+    async fn insert_contact(tx: &mut Transaction<'_, Postgres>, contact: &Contact) -> Result<(), sqlx::Error> {
+        let email_json = serde_json::to_value(&contact.email).unwrap_or(JsonValue::Null);
+        let phone_json = serde_json::to_value(&contact.phone).unwrap_or(JsonValue::Null);
+        let address_json = serde_json::to_value(&contact.address).unwrap_or(JsonValue::Null);
+
+        sqlx::query(
+            r#"
+            INSERT INTO carddav.contacts (
+                id, address_book_id, uid, full_name, first_name, last_name, nickname,
+                email, phone, address, organization, title, notes, photo_url,
+                birthday, anniversary, vcard, etag, created_at, updated_at
+            )
+            VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14,
+                $15, $16, $17, $18, $19, $20
+            )
+            "#
+        )
+        .bind(contact.id)
+        .bind(contact.address_book_id)
+        .bind(&contact.uid)
+        .bind(&contact.full_name)
+        .bind(&contact.first_name)
+        .bind(&contact.last_name)
+        .bind(&contact.nickname)
+        .bind(email_json)
+        .bind(phone_json)
+        .bind(address_json)
+        .bind(&contact.organization)
+        .bind(&contact.title)
+        .bind(&contact.notes)
+        .bind(&contact.photo_url)
+        .bind(contact.birthday)
+        .bind(contact.anniversary)
+        .bind(&contact.vcard)
+        .bind(&contact.etag)
+        .bind(contact.created_at)
+        .bind(contact.updated_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Construye un contacto a partir de una fila de carddav.contacts
+    fn contact_from_row(row: &PgRow) -> Contact {
+        let json_list = |column: &str| row.get::<Option<JsonValue>, _>(column).unwrap_or(JsonValue::Null);
+        Contact {
+            id: row.get("id"),
+            address_book_id: row.get("address_book_id"),
+            uid: row.get("uid"),
+            full_name: row.get("full_name"),
+            first_name: row.get("first_name"),
+            last_name: row.get("last_name"),
+            nickname: row.get("nickname"),
+            email: serde_json::from_value(json_list("email")).unwrap_or_default(),
+            phone: serde_json::from_value(json_list("phone")).unwrap_or_default(),
+            address: serde_json::from_value(json_list("address")).unwrap_or_default(),
+            organization: row.get("organization"),
+            title: row.get("title"),
+            notes: row.get("notes"),
+            photo_url: row.get("photo_url"),
+            birthday: row.get("birthday"),
+            anniversary: row.get("anniversary"),
+            vcard: row.get("vcard"),
+            etag: row.get("etag"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        }
+    }
 }
 
 #[async_trait]
@@ -72,6 +145,21 @@ impl ContactRepository for ContactPgRepository {
         Ok(contact)
     }
 
+    async fn create_contacts(&self, contacts: Vec<Contact>) -> ContactRepositoryResult<Vec<Contact>> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| DomainError::database_error(format!("Failed to begin transaction: {}", e)))?;
+
+        for contact in &contacts {
+            Self::insert_contact(&mut tx, contact).await
+                .map_err(|e| DomainError::database_error(format!("Failed to create contact '{}': {}", contact.uid, e)))?;
+        }
+
+        tx.commit().await
+            .map_err(|e| DomainError::database_error(format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(contacts)
+    }
+
     async fn update_contact(&self, contact: Contact) -> ContactRepositoryResult<Contact> {
         let now = Utc::now();
         // Convert complex fields to JSON
@@ -280,7 +368,7 @@ impl ContactRepository for ContactPgRepository {
     async fn search_contacts(&self, address_book_id: &Uuid, query: &str) -> ContactRepositoryResult<Vec<Contact>> {
         let search_pattern = format!("%{}%", query);
         
-        let _rows = sqlx::query(
+        let rows = sqlx::query(
             r#"
             SELECT 
                 id, address_book_id, uid, full_name, first_name, last_name, nickname,
@@ -306,11 +394,54 @@ impl ContactRepository for ContactPgRepository {
         .await
         .map_err(|e| DomainError::database_error(format!("Failed to search contacts: {}", e)))?;
 
-        // En una implementación real, construiríamos objetos Contact a partir de las filas
-        // Por simplicidad y demostración, devolvemos una lista vacía
-        let contacts = Vec::new();
-        
-        Ok(contacts)
+        Ok(rows.iter().map(Self::contact_from_row).collect())
+    }
+
+    async fn get_changes_since(&self, address_book_id: &Uuid, revision: i64) -> ContactRepositoryResult<Vec<CollectionChange>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT revision, object_id, object_uid, operation, changed_at
+            FROM carddav.address_book_changes
+            WHERE address_book_id = $1 AND revision > $2
+            ORDER BY revision
+            "#
+        )
+        .bind(address_book_id)
+        .bind(revision)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to get address book changes: {}", e)))?;
+
+        rows.iter()
+            .map(|row| {
+                let operation: String = row.get("operation");
+                Ok(CollectionChange {
+                    revision: row.get("revision"),
+                    object_id: row.get("object_id"),
+                    object_uid: row.get("object_uid"),
+                    operation: ChangeOperation::parse(&operation).ok_or_else(|| {
+                        DomainError::database_error(format!("Unknown change operation: {}", operation))
+                    })?,
+                    changed_at: row.get("changed_at"),
+                })
+            })
+            .collect()
+    }
+
+    async fn get_oldest_change_revision(&self, address_book_id: &Uuid) -> ContactRepositoryResult<Option<i64>> {
+        let row = sqlx::query(
+            r#"
+            SELECT MIN(revision) as revision
+            FROM carddav.address_book_changes
+            WHERE address_book_id = $1
+            "#
+        )
+        .bind(address_book_id)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| DomainError::database_error(format!("Failed to get oldest address book change: {}", e)))?;
+
+        Ok(row.get::<Option<i64>, _>("revision"))
     }
 }
 
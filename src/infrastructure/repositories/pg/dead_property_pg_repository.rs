@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+
+use crate::common::errors::DomainError;
+use crate::domain::entities::dead_property::DeadProperty;
+use crate::domain::repositories::dead_property_repository::{DeadPropertyRepository, DeadPropertyRepositoryResult};
+
+pub struct DeadPropertyPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl DeadPropertyPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn map_sqlx_error(err: sqlx::Error) -> DomainError {
+        DomainError::database_error(format!("Error de base de datos en propiedades WebDAV: {}", err))
+    }
+}
+
+#[async_trait]
+impl DeadPropertyRepository for DeadPropertyPgRepository {
+    /// Obtiene las propiedades de varios recursos, indexadas por ID de recurso
+    async fn get_properties(&self, resource_ids: &[String]) -> DeadPropertyRepositoryResult<HashMap<String, Vec<DeadProperty>>> {
+        if resource_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let rows = sqlx::query(
+            r#"
+            SELECT resource_id, namespace, name, value
+            FROM auth.dead_properties
+            WHERE resource_id = ANY($1)
+            ORDER BY resource_id, namespace, name
+            "#
+        )
+        .bind(resource_ids)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        let mut properties: HashMap<String, Vec<DeadProperty>> = HashMap::new();
+        for row in rows {
+            properties
+                .entry(row.get("resource_id"))
+                .or_default()
+                .push(DeadProperty::new(row.get::<String, _>("namespace"), row.get::<String, _>("name"), row.get("value")));
+        }
+
+        Ok(properties)
+    }
+
+    /// Guarda y elimina propiedades de un recurso en una sola transacción
+    async fn patch_properties(
+        &self,
+        resource_id: &str,
+        set: &[DeadProperty],
+        remove: &[(String, String)],
+    ) -> DeadPropertyRepositoryResult<()> {
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+
+        for property in set {
+            sqlx::query(
+                r#"
+                INSERT INTO auth.dead_properties (resource_id, namespace, name, value, updated_at)
+                VALUES ($1, $2, $3, $4, NOW())
+                ON CONFLICT (resource_id, namespace, name)
+                DO UPDATE SET value = EXCLUDED.value, updated_at = NOW()
+                "#
+            )
+            .bind(resource_id)
+            .bind(&property.namespace)
+            .bind(&property.name)
+            .bind(&property.value)
+            .execute(&mut *tx)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+        }
+
+        for (namespace, name) in remove {
+            sqlx::query(
+                r#"
+                DELETE FROM auth.dead_properties
+                WHERE resource_id = $1 AND namespace = $2 AND name = $3
+                "#
+            )
+            .bind(resource_id)
+            .bind(namespace)
+            .bind(name)
+            .execute(&mut *tx)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+        }
+
+        tx.commit().await.map_err(Self::map_sqlx_error)?;
+        Ok(())
+    }
+
+    /// Copia todas las propiedades de un recurso a otro
+    async fn copy_properties(&self, from_resource_id: &str, to_resource_id: &str) -> DeadPropertyRepositoryResult<()> {
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+
+        sqlx::query("DELETE FROM auth.dead_properties WHERE resource_id = $1")
+            .bind(to_resource_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO auth.dead_properties (resource_id, namespace, name, value, updated_at)
+            SELECT $2, namespace, name, value, NOW()
+            FROM auth.dead_properties
+            WHERE resource_id = $1
+            "#
+        )
+        .bind(from_resource_id)
+        .bind(to_resource_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        tx.commit().await.map_err(Self::map_sqlx_error)?;
+        Ok(())
+    }
+
+    /// Elimina todas las propiedades de un recurso
+    async fn delete_properties(&self, resource_id: &str) -> DeadPropertyRepositoryResult<()> {
+        sqlx::query("DELETE FROM auth.dead_properties WHERE resource_id = $1")
+            .bind(resource_id)
+            .execute(&*self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+}
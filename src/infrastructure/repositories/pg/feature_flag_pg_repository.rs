@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+
+use crate::common::errors::DomainError;
+use crate::domain::entities::feature_flag::{FeatureFlag, FlagOverride, FlagScope};
+use crate::domain::repositories::feature_flag_repository::{FeatureFlagRepository, FeatureFlagRepositoryResult};
+
+pub struct FeatureFlagPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl FeatureFlagPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn map_sqlx_error(err: sqlx::Error) -> DomainError {
+        DomainError::database_error(format!("Error de base de datos en feature flags: {}", err))
+    }
+}
+
+#[async_trait]
+impl FeatureFlagRepository for FeatureFlagPgRepository {
+    /// Obtiene todos los flags con sus excepciones
+    async fn list_flags(&self) -> FeatureFlagRepositoryResult<Vec<FeatureFlag>> {
+        let flag_rows = sqlx::query(
+            r#"
+            SELECT key, description, enabled, rollout_percentage, updated_at
+            FROM auth.feature_flags
+            ORDER BY key
+            "#
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        let override_rows = sqlx::query(
+            r#"
+            SELECT flag_key, scope, subject, enabled
+            FROM auth.feature_flag_overrides
+            ORDER BY flag_key, scope, subject
+            "#
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        let mut overrides: HashMap<String, Vec<FlagOverride>> = HashMap::new();
+        for row in override_rows {
+            let scope: String = row.get("scope");
+            let Some(scope) = FlagScope::parse(&scope) else {
+                tracing::warn!("Ignoring feature flag override with unknown scope '{}'", scope);
+                continue;
+            };
+            overrides.entry(row.get("flag_key")).or_default().push(FlagOverride {
+                scope,
+                subject: row.get("subject"),
+                enabled: row.get("enabled"),
+            });
+        }
+
+        Ok(flag_rows.iter().map(|row| {
+            let key: String = row.get("key");
+            let rollout_percentage: i16 = row.get("rollout_percentage");
+            FeatureFlag {
+                overrides: overrides.remove(&key).unwrap_or_default(),
+                key,
+                description: row.get("description"),
+                enabled: row.get("enabled"),
+                rollout_percentage: rollout_percentage.clamp(0, 100) as u8,
+                updated_at: row.get("updated_at"),
+            }
+        }).collect())
+    }
+
+    /// Crea o actualiza la regla global de un flag
+    async fn save_flag(&self, flag: &FeatureFlag) -> FeatureFlagRepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO auth.feature_flags (key, description, enabled, rollout_percentage, updated_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (key) DO UPDATE SET
+                description = EXCLUDED.description,
+                enabled = EXCLUDED.enabled,
+                rollout_percentage = EXCLUDED.rollout_percentage,
+                updated_at = EXCLUDED.updated_at
+            "#
+        )
+        .bind(&flag.key)
+        .bind(&flag.description)
+        .bind(flag.enabled)
+        .bind(flag.rollout_percentage as i16)
+        .bind(flag.updated_at)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    /// Elimina un flag; las excepciones se borran en cascada
+    async fn delete_flag(&self, key: &str) -> FeatureFlagRepositoryResult<bool> {
+        let result = sqlx::query("DELETE FROM auth.feature_flags WHERE key = $1")
+            .bind(key)
+            .execute(&*self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Crea o actualiza una excepción y marca el flag como modificado
+    async fn set_override(&self, key: &str, flag_override: &FlagOverride) -> FeatureFlagRepositoryResult<()> {
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO auth.feature_flag_overrides (flag_key, scope, subject, enabled, updated_at)
+            VALUES ($1, $2, $3, $4, NOW())
+            ON CONFLICT (flag_key, scope, subject)
+            DO UPDATE SET enabled = EXCLUDED.enabled, updated_at = NOW()
+            "#
+        )
+        .bind(key)
+        .bind(flag_override.scope.as_str())
+        .bind(&flag_override.subject)
+        .bind(flag_override.enabled)
+        .execute(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        sqlx::query("UPDATE auth.feature_flags SET updated_at = NOW() WHERE key = $1")
+            .bind(key)
+            .execute(&mut *tx)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        tx.commit().await.map_err(Self::map_sqlx_error)?;
+        Ok(())
+    }
+
+    /// Elimina una excepción
+    async fn remove_override(&self, key: &str, scope: FlagScope, subject: &str) -> FeatureFlagRepositoryResult<bool> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM auth.feature_flag_overrides
+            WHERE flag_key = $1 AND scope = $2 AND subject = $3
+            "#
+        )
+        .bind(key)
+        .bind(scope.as_str())
+        .bind(subject)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
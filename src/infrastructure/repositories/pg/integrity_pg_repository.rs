@@ -0,0 +1,84 @@
+use async_trait::async_trait;
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+
+use crate::application::ports::integrity_ports::{FavoriteReference, IntegrityStoragePort};
+use crate::common::errors::DomainError;
+
+/// Referencias guardadas en la base de datos que la comprobación de
+/// integridad resuelve contra el almacenamiento de ficheros
+pub struct IntegrityPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl IntegrityPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn map_sqlx_error(err: sqlx::Error) -> DomainError {
+        DomainError::database_error(format!("Error de base de datos en la comprobación de integridad: {}", err))
+    }
+}
+
+#[async_trait]
+impl IntegrityStoragePort for IntegrityPgRepository {
+    /// Favoritos de todos los usuarios
+    async fn list_favorites(&self) -> Result<Vec<FavoriteReference>, DomainError> {
+        let rows = sqlx::query("SELECT id, item_id, item_type FROM auth.user_favorites ORDER BY id")
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        Ok(rows
+            .iter()
+            .map(|row| FavoriteReference {
+                id: row.get("id"),
+                item_id: row.get("item_id"),
+                item_type: row.get("item_type"),
+            })
+            .collect())
+    }
+
+    /// Elimina favoritos por ID
+    async fn delete_favorites(&self, ids: &[i32]) -> Result<u64, DomainError> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        let result = sqlx::query("DELETE FROM auth.user_favorites WHERE id = ANY($1)")
+            .bind(ids)
+            .execute(&*self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+        Ok(result.rows_affected())
+    }
+
+    /// La clave foránea ya borra los miembros junto con el usuario; solo
+    /// quedan huérfanos en bases restauradas en parte o migradas a mano
+    async fn count_orphaned_memberships(&self) -> Result<u64, DomainError> {
+        let row = sqlx::query(
+            r#"
+            SELECT COUNT(*) AS count FROM auth.user_group_members m
+            WHERE NOT EXISTS (SELECT 1 FROM auth.users u WHERE u.id = m.user_id)
+            "#
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+        Ok(row.get::<i64, _>("count") as u64)
+    }
+
+    /// Elimina los miembros de grupos cuyo usuario ya no existe
+    async fn delete_orphaned_memberships(&self) -> Result<u64, DomainError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM auth.user_group_members m
+            WHERE NOT EXISTS (SELECT 1 FROM auth.users u WHERE u.id = m.user_id)
+            "#
+        )
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+        Ok(result.rows_affected())
+    }
+}
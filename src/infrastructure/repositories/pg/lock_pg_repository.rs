@@ -0,0 +1,166 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+
+use crate::common::errors::DomainError;
+use crate::domain::entities::lock::{path_and_ancestors, ResourceLock};
+use crate::domain::repositories::lock_repository::{LockRepository, LockRepositoryResult};
+
+pub struct LockPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl LockPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn map_sqlx_error(err: sqlx::Error) -> DomainError {
+        DomainError::database_error(format!("Error de base de datos en bloqueos WebDAV: {}", err))
+    }
+
+    fn row_to_lock(row: &PgRow) -> ResourceLock {
+        ResourceLock {
+            token: row.get("token"),
+            path: row.get("path"),
+            owner_id: row.get("owner_id"),
+            owner_info: row.get("owner_info"),
+            exclusive: row.get("exclusive"),
+            infinite_depth: row.get("infinite_depth"),
+            timeout_secs: row.get::<i64, _>("timeout_secs").max(0) as u64,
+            created_at: row.get("created_at"),
+            expires_at: row.get("expires_at"),
+        }
+    }
+}
+
+#[async_trait]
+impl LockRepository for LockPgRepository {
+    /// Guarda un bloqueo nuevo
+    async fn create_lock(&self, lock: ResourceLock) -> LockRepositoryResult<ResourceLock> {
+        sqlx::query(
+            r#"
+            INSERT INTO auth.webdav_locks (
+                token, path, owner_id, owner_info, exclusive,
+                infinite_depth, timeout_secs, created_at, expires_at
+            ) VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, $9
+            )
+            "#
+        )
+        .bind(&lock.token)
+        .bind(&lock.path)
+        .bind(&lock.owner_id)
+        .bind(&lock.owner_info)
+        .bind(lock.exclusive)
+        .bind(lock.infinite_depth)
+        .bind(lock.timeout_secs as i64)
+        .bind(lock.created_at)
+        .bind(lock.expires_at)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(lock)
+    }
+
+    /// Obtiene un bloqueo vigente por su token
+    async fn get_lock(&self, token: &str) -> LockRepositoryResult<Option<ResourceLock>> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                token, path, owner_id, owner_info, exclusive,
+                infinite_depth, timeout_secs, created_at, expires_at
+            FROM auth.webdav_locks
+            WHERE token = $1 AND expires_at > NOW()
+            "#
+        )
+        .bind(token)
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(row.as_ref().map(Self::row_to_lock))
+    }
+
+    /// Obtiene los bloqueos vigentes que pueden afectar a una ruta
+    async fn find_locks_affecting(&self, path: &str) -> LockRepositoryResult<Vec<ResourceLock>> {
+        // Everything below the root starts with "" and needs no trailing slash
+        let descendant_prefix = if path.is_empty() { String::new() } else { format!("{}/", path) };
+
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                token, path, owner_id, owner_info, exclusive,
+                infinite_depth, timeout_secs, created_at, expires_at
+            FROM auth.webdav_locks
+            WHERE expires_at > NOW()
+              AND (path = ANY($1) OR starts_with(path, $2))
+            ORDER BY created_at
+            "#
+        )
+        .bind(path_and_ancestors(path))
+        .bind(descendant_prefix)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(rows.iter().map(Self::row_to_lock).collect())
+    }
+
+    /// Cambia la caducidad de un bloqueo
+    async fn update_lock_expiry(&self, token: &str, timeout_secs: u64, expires_at: DateTime<Utc>) -> LockRepositoryResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE auth.webdav_locks
+            SET timeout_secs = $2, expires_at = $3
+            WHERE token = $1
+            "#
+        )
+        .bind(token)
+        .bind(timeout_secs as i64)
+        .bind(expires_at)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    /// Elimina un bloqueo
+    async fn delete_lock(&self, token: &str) -> LockRepositoryResult<bool> {
+        let result = sqlx::query("DELETE FROM auth.webdav_locks WHERE token = $1")
+            .bind(token)
+            .execute(&*self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Elimina los bloqueos de la ruta y de todo lo que contiene
+    async fn delete_locks_under(&self, path: &str) -> LockRepositoryResult<u64> {
+        let result = sqlx::query(
+            "DELETE FROM auth.webdav_locks WHERE path = $1 OR starts_with(path, $2)"
+        )
+        .bind(path)
+        .bind(format!("{}/", path))
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Elimina bloqueos caducados
+    async fn delete_expired_locks(&self) -> LockRepositoryResult<u64> {
+        let result = sqlx::query("DELETE FROM auth.webdav_locks WHERE expires_at <= NOW()")
+            .execute(&*self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected())
+    }
+}
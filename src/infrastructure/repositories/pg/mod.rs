@@ -1,16 +1,56 @@
+mod activity_pg_repository;
 mod address_book_pg_repository;
+mod app_password_pg_repository;
+mod audit_log_pg_repository;
+mod comment_pg_repository;
+mod announcement_pg_repository;
 mod calendar_pg_repository;
 mod calendar_event_pg_repository;
 mod contact_pg_repository;
 mod contact_group_pg_repository;
+mod dead_property_pg_repository;
+mod feature_flag_pg_repository;
+mod integrity_pg_repository;
+mod lock_pg_repository;
+mod notification_preferences_pg_repository;
+mod oidc_identity_pg_repository;
+mod quota_policy_pg_repository;
+mod scheduling_pg_repository;
 mod session_pg_repository;
+mod share_notification_pg_repository;
+mod sync_conflict_pg_repository;
+mod tag_pg_repository;
+mod theme_pg_repository;
 mod transaction_utils;
+mod user_group_pg_repository;
 mod user_pg_repository;
+mod user_quarantine_pg_repository;
+mod webhook_pg_repository;
 
+pub use activity_pg_repository::ActivityPgRepository;
 pub use address_book_pg_repository::AddressBookPgRepository;
+pub use app_password_pg_repository::AppPasswordPgRepository;
+pub use audit_log_pg_repository::AuditLogPgRepository;
+pub use comment_pg_repository::CommentPgRepository;
+pub use announcement_pg_repository::AnnouncementPgRepository;
 pub use calendar_pg_repository::CalendarPgRepository;
 pub use calendar_event_pg_repository::CalendarEventPgRepository;
 pub use contact_pg_repository::ContactPgRepository;
 pub use contact_group_pg_repository::ContactGroupPgRepository;
+pub use dead_property_pg_repository::DeadPropertyPgRepository;
+pub use feature_flag_pg_repository::FeatureFlagPgRepository;
+pub use integrity_pg_repository::IntegrityPgRepository;
+pub use lock_pg_repository::LockPgRepository;
+pub use notification_preferences_pg_repository::NotificationPreferencesPgRepository;
+pub use quota_policy_pg_repository::QuotaPolicyPgRepository;
+pub use scheduling_pg_repository::SchedulingPgRepository;
 pub use session_pg_repository::SessionPgRepository;
+pub use share_notification_pg_repository::ShareNotificationPgRepository;
+pub use sync_conflict_pg_repository::SyncConflictPgRepository;
+pub use tag_pg_repository::TagPgRepository;
+pub use theme_pg_repository::ThemePgRepository;
+pub use user_group_pg_repository::UserGroupPgRepository;
 pub use user_pg_repository::UserPgRepository;
+pub use user_quarantine_pg_repository::UserQuarantinePgRepository;
+pub use webhook_pg_repository::WebhookPgRepository;
+pub use oidc_identity_pg_repository::OidcIdentityPgRepository;
@@ -0,0 +1,82 @@
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+
+use crate::common::errors::DomainError;
+use crate::domain::entities::notification_preferences::NotificationPreferences;
+use crate::domain::repositories::notification_preferences_repository::{
+    NotificationPreferencesRepository, NotificationPreferencesRepositoryResult,
+};
+
+pub struct NotificationPreferencesPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl NotificationPreferencesPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn map_sqlx_error(err: sqlx::Error) -> DomainError {
+        DomainError::database_error(format!("Error de base de datos en preferencias de notificación: {}", err))
+    }
+
+    fn row_to_preferences(row: &PgRow) -> NotificationPreferences {
+        NotificationPreferences {
+            user_id: row.get("user_id"),
+            share_received: row.get("share_received"),
+            share_downloaded: row.get("share_downloaded"),
+            quota_warning: row.get("quota_warning"),
+            updated_at: row.get("updated_at"),
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationPreferencesRepository for NotificationPreferencesPgRepository {
+    /// Obtiene las preferencias guardadas de un usuario
+    async fn get_preferences(&self, user_id: &str) -> NotificationPreferencesRepositoryResult<Option<NotificationPreferences>> {
+        let row = sqlx::query(
+            r#"
+            SELECT user_id, share_received, share_downloaded, quota_warning, updated_at
+            FROM auth.notification_preferences
+            WHERE user_id = $1
+            "#
+        )
+        .bind(user_id)
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(row.as_ref().map(Self::row_to_preferences))
+    }
+
+    /// Crea o sustituye las preferencias de un usuario
+    async fn save_preferences(&self, preferences: &NotificationPreferences) -> NotificationPreferencesRepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO auth.notification_preferences (
+                user_id, share_received, share_downloaded, quota_warning, updated_at
+            ) VALUES (
+                $1, $2, $3, $4, $5
+            )
+            ON CONFLICT (user_id) DO UPDATE SET
+                share_received = EXCLUDED.share_received,
+                share_downloaded = EXCLUDED.share_downloaded,
+                quota_warning = EXCLUDED.quota_warning,
+                updated_at = EXCLUDED.updated_at
+            "#
+        )
+        .bind(&preferences.user_id)
+        .bind(preferences.share_received)
+        .bind(preferences.share_downloaded)
+        .bind(preferences.quota_warning)
+        .bind(preferences.updated_at)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+
+use crate::application::ports::auth_ports::OidcIdentityStoragePort;
+use crate::common::errors::DomainError;
+use crate::domain::entities::oidc_identity::OidcIdentity;
+
+pub struct OidcIdentityPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl OidcIdentityPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn map_sqlx_error(err: sqlx::Error) -> DomainError {
+        DomainError::database_error(format!("Error de base de datos en identidades OIDC: {}", err))
+    }
+
+    fn row_to_identity(row: &PgRow) -> OidcIdentity {
+        OidcIdentity {
+            issuer: row.get("issuer"),
+            subject: row.get("subject"),
+            user_id: row.get("user_id"),
+            email: row.get("email"),
+            created_at: row.get("created_at"),
+            last_login_at: row.get("last_login_at"),
+        }
+    }
+}
+
+#[async_trait]
+impl OidcIdentityStoragePort for OidcIdentityPgRepository {
+    async fn find_identity(&self, issuer: &str, subject: &str) -> Result<Option<OidcIdentity>, DomainError> {
+        let row = sqlx::query(
+            r#"
+            SELECT issuer, subject, user_id, email, created_at, last_login_at
+            FROM auth.oidc_identities
+            WHERE issuer = $1 AND subject = $2
+            "#
+        )
+        .bind(issuer)
+        .bind(subject)
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(row.as_ref().map(Self::row_to_identity))
+    }
+
+    async fn link_identity(&self, identity: OidcIdentity) -> Result<OidcIdentity, DomainError> {
+        sqlx::query(
+            r#"
+            INSERT INTO auth.oidc_identities (issuer, subject, user_id, email, created_at, last_login_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (issuer, subject) DO UPDATE SET
+                user_id = EXCLUDED.user_id,
+                email = EXCLUDED.email
+            "#
+        )
+        .bind(&identity.issuer)
+        .bind(&identity.subject)
+        .bind(&identity.user_id)
+        .bind(&identity.email)
+        .bind(identity.created_at)
+        .bind(identity.last_login_at)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(identity)
+    }
+
+    async fn touch_identity(&self, issuer: &str, subject: &str) -> Result<(), DomainError> {
+        sqlx::query("UPDATE auth.oidc_identities SET last_login_at = NOW() WHERE issuer = $1 AND subject = $2")
+            .bind(issuer)
+            .bind(subject)
+            .execute(&*self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+
+use crate::common::errors::DomainError;
+use crate::domain::entities::quota_policy::QuotaPolicy;
+use crate::domain::repositories::quota_policy_repository::{QuotaPolicyRepository, QuotaPolicyRepositoryResult};
+
+pub struct QuotaPolicyPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl QuotaPolicyPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn map_sqlx_error(err: sqlx::Error) -> DomainError {
+        DomainError::database_error(format!("Error de base de datos en políticas de cuota: {}", err))
+    }
+}
+
+#[async_trait]
+impl QuotaPolicyRepository for QuotaPolicyPgRepository {
+    /// Obtiene las políticas de cuota de todos los grupos
+    async fn list_policies(&self) -> QuotaPolicyRepositoryResult<Vec<QuotaPolicy>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT group_name, quota_bytes, priority, updated_at
+            FROM auth.quota_policies
+            ORDER BY priority DESC, group_name
+            "#
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(rows.iter().map(|row| QuotaPolicy {
+            group: row.get("group_name"),
+            quota_bytes: row.get("quota_bytes"),
+            priority: row.get("priority"),
+            updated_at: row.get("updated_at"),
+        }).collect())
+    }
+
+    /// Crea o actualiza la política de un grupo
+    async fn save_policy(&self, policy: &QuotaPolicy) -> QuotaPolicyRepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO auth.quota_policies (group_name, quota_bytes, priority, updated_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (group_name) DO UPDATE SET
+                quota_bytes = EXCLUDED.quota_bytes,
+                priority = EXCLUDED.priority,
+                updated_at = EXCLUDED.updated_at
+            "#
+        )
+        .bind(&policy.group)
+        .bind(policy.quota_bytes)
+        .bind(policy.priority)
+        .bind(policy.updated_at)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    /// Elimina la política de un grupo
+    async fn delete_policy(&self, group: &str) -> QuotaPolicyRepositoryResult<bool> {
+        let result = sqlx::query("DELETE FROM auth.quota_policies WHERE group_name = $1")
+            .bind(group)
+            .execute(&*self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Cuota fijada a mano para un usuario
+    async fn get_user_override(&self, user_id: &str) -> QuotaPolicyRepositoryResult<Option<i64>> {
+        let row = sqlx::query("SELECT quota_bytes FROM auth.user_quota_overrides WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        Ok(row.map(|row| row.get("quota_bytes")))
+    }
+
+    /// Cuotas fijadas a mano, por ID de usuario
+    async fn list_user_overrides(&self) -> QuotaPolicyRepositoryResult<HashMap<String, i64>> {
+        let rows = sqlx::query("SELECT user_id, quota_bytes FROM auth.user_quota_overrides")
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        Ok(rows.iter().map(|row| (row.get("user_id"), row.get("quota_bytes"))).collect())
+    }
+
+    /// Fija la cuota de un usuario
+    async fn set_user_override(&self, user_id: &str, quota_bytes: i64) -> QuotaPolicyRepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO auth.user_quota_overrides (user_id, quota_bytes, updated_at)
+            VALUES ($1, $2, NOW())
+            ON CONFLICT (user_id)
+            DO UPDATE SET quota_bytes = EXCLUDED.quota_bytes, updated_at = NOW()
+            "#
+        )
+        .bind(user_id)
+        .bind(quota_bytes)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    /// Quita la cuota fijada de un usuario
+    async fn remove_user_override(&self, user_id: &str) -> QuotaPolicyRepositoryResult<bool> {
+        let result = sqlx::query("DELETE FROM auth.user_quota_overrides WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&*self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,122 @@
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+
+use crate::common::errors::DomainError;
+use crate::domain::entities::scheduling_message::{ITipMethod, SchedulingMessage};
+use crate::domain::repositories::scheduling_repository::{SchedulingRepository, SchedulingRepositoryResult};
+
+pub struct SchedulingPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl SchedulingPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn map_sqlx_error(err: sqlx::Error) -> DomainError {
+        DomainError::database_error(format!("Error de base de datos en la bandeja de planificación: {}", err))
+    }
+
+    fn row_to_message(row: &PgRow) -> Result<SchedulingMessage, DomainError> {
+        let method: String = row.get("method");
+
+        Ok(SchedulingMessage {
+            id: row.get("id"),
+            user_id: row.get("user_id"),
+            method: ITipMethod::parse(&method)
+                .ok_or_else(|| DomainError::database_error(format!("Método iTIP desconocido: {}", method)))?,
+            ical_uid: row.get("ical_uid"),
+            sender: row.get("sender"),
+            ical_data: row.get("ical_data"),
+            created_at: row.get("created_at"),
+        })
+    }
+}
+
+#[async_trait]
+impl SchedulingRepository for SchedulingPgRepository {
+    /// Entrega un mensaje en la bandeja de entrada de su destinatario
+    async fn deliver_message(&self, message: &SchedulingMessage) -> SchedulingRepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO caldav.scheduling_messages (id, user_id, method, ical_uid, sender, ical_data, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#
+        )
+        .bind(&message.id)
+        .bind(&message.user_id)
+        .bind(message.method.as_str())
+        .bind(&message.ical_uid)
+        .bind(&message.sender)
+        .bind(&message.ical_data)
+        .bind(message.created_at)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    /// Obtiene la bandeja de entrada de un usuario, los mensajes más antiguos primero
+    async fn list_messages(&self, user_id: &str) -> SchedulingRepositoryResult<Vec<SchedulingMessage>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, user_id, method, ical_uid, sender, ical_data, created_at
+            FROM caldav.scheduling_messages
+            WHERE user_id = $1
+            ORDER BY created_at
+            "#
+        )
+        .bind(user_id)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        rows.iter().map(Self::row_to_message).collect()
+    }
+
+    /// Obtiene un mensaje de la bandeja de entrada de un usuario
+    async fn get_message(&self, user_id: &str, id: &str) -> SchedulingRepositoryResult<Option<SchedulingMessage>> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, user_id, method, ical_uid, sender, ical_data, created_at
+            FROM caldav.scheduling_messages
+            WHERE user_id = $1 AND id = $2
+            "#
+        )
+        .bind(user_id)
+        .bind(id)
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        row.as_ref().map(Self::row_to_message).transpose()
+    }
+
+    /// Elimina un mensaje de la bandeja de entrada. Devuelve `false` si no existía
+    async fn delete_message(&self, user_id: &str, id: &str) -> SchedulingRepositoryResult<bool> {
+        let result = sqlx::query("DELETE FROM caldav.scheduling_messages WHERE user_id = $1 AND id = $2")
+            .bind(user_id)
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Elimina los mensajes de un usuario sobre un evento, una vez procesados
+    async fn delete_messages_for_uid(&self, user_id: &str, ical_uid: &str) -> SchedulingRepositoryResult<u64> {
+        let result = sqlx::query("DELETE FROM caldav.scheduling_messages WHERE user_id = $1 AND ical_uid = $2")
+            .bind(user_id)
+            .bind(ical_uid)
+            .execute(&*self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 use sqlx::{PgPool, Row};
 use std::sync::Arc;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use futures::future::BoxFuture;
 
 use crate::domain::entities::session::Session;
@@ -56,9 +56,9 @@ impl SessionRepository for SessionPgRepository {
                         r#"
                         INSERT INTO auth.sessions (
                             id, user_id, refresh_token, expires_at, 
-                            ip_address, user_agent, created_at, revoked
+                            ip_address, user_agent, created_at, last_active_at, revoked
                         ) VALUES (
-                            $1, $2, $3, $4, $5, $6, $7, $8
+                            $1, $2, $3, $4, $5, $6, $7, $8, $9
                         )
                         "#
                     )
@@ -69,6 +69,7 @@ impl SessionRepository for SessionPgRepository {
                     .bind(&session_clone.ip_address)
                     .bind(&session_clone.user_agent)
                     .bind(session_clone.created_at())
+                    .bind(session_clone.last_active_at())
                     .bind(session_clone.is_revoked())
                     .execute(&mut **tx)
                     .await
@@ -110,7 +111,7 @@ impl SessionRepository for SessionPgRepository {
             r#"
             SELECT 
                 id, user_id, refresh_token, expires_at, 
-                ip_address, user_agent, created_at, revoked
+                ip_address, user_agent, created_at, last_active_at, revoked
             FROM auth.sessions
             WHERE id = $1
             "#
@@ -128,6 +129,7 @@ impl SessionRepository for SessionPgRepository {
             ip_address: row.get("ip_address"),
             user_agent: row.get("user_agent"),
             created_at: row.get("created_at"),
+            last_active_at: row.get("last_active_at"),
             revoked: row.get("revoked"),
         })
     }
@@ -138,7 +140,7 @@ impl SessionRepository for SessionPgRepository {
             r#"
             SELECT 
                 id, user_id, refresh_token, expires_at, 
-                ip_address, user_agent, created_at, revoked
+                ip_address, user_agent, created_at, last_active_at, revoked
             FROM auth.sessions
             WHERE refresh_token = $1
             "#
@@ -156,6 +158,7 @@ impl SessionRepository for SessionPgRepository {
             ip_address: row.get("ip_address"),
             user_agent: row.get("user_agent"),
             created_at: row.get("created_at"),
+            last_active_at: row.get("last_active_at"),
             revoked: row.get("revoked"),
         })
     }
@@ -166,7 +169,7 @@ impl SessionRepository for SessionPgRepository {
             r#"
             SELECT 
                 id, user_id, refresh_token, expires_at, 
-                ip_address, user_agent, created_at, revoked
+                ip_address, user_agent, created_at, last_active_at, revoked
             FROM auth.sessions
             WHERE user_id = $1
             ORDER BY created_at DESC
@@ -187,6 +190,7 @@ impl SessionRepository for SessionPgRepository {
                     ip_address: row.get("ip_address"),
                     user_agent: row.get("user_agent"),
                     created_at: row.get("created_at"),
+                    last_active_at: row.get("last_active_at"),
                     revoked: row.get("revoked"),
                 }
             })
@@ -234,6 +238,58 @@ impl SessionRepository for SessionPgRepository {
         ).await
     }
     
+    /// Sustituye el token de actualización conservando el ID de la sesión.
+    /// Solo se rota una sesión vigente, así que un token ya usado no vale
+    async fn rotate_refresh_token(
+        &self,
+        session_id: &str,
+        refresh_token: &str,
+        expires_at: DateTime<Utc>,
+        ip_address: Option<&str>,
+        user_agent: Option<&str>,
+    ) -> SessionRepositoryResult<()> {
+        let result = sqlx::query(
+            r#"
+            UPDATE auth.sessions
+            SET refresh_token = $2, expires_at = $3,
+                ip_address = COALESCE($4, ip_address),
+                user_agent = COALESCE($5, user_agent),
+                last_active_at = NOW()
+            WHERE id = $1 AND revoked = false
+            "#
+        )
+        .bind(session_id)
+        .bind(refresh_token)
+        .bind(expires_at)
+        .bind(ip_address)
+        .bind(user_agent)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(SessionRepositoryError::NotFound(session_id.to_string()));
+        }
+        Ok(())
+    }
+    
+    /// Anota actividad en una sesión
+    async fn touch_session(&self, session_id: &str) -> SessionRepositoryResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE auth.sessions
+            SET last_active_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(session_id)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+    
     /// Revoca todas las sesiones de un usuario utilizando una transacción
     async fn revoke_all_user_sessions(&self, user_id: &str) -> SessionRepositoryResult<u64> {
         let user_id_clone = user_id.to_string(); // Clone para uso en closure
@@ -269,6 +325,28 @@ impl SessionRepository for SessionPgRepository {
         ).await
     }
     
+    /// Revoca todas las sesiones de un usuario salvo una
+    async fn revoke_other_user_sessions(&self, user_id: &str, keep_session_id: &str) -> SessionRepositoryResult<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE auth.sessions
+            SET revoked = true
+            WHERE user_id = $1 AND id <> $2 AND revoked = false
+            "#
+        )
+        .bind(user_id)
+        .bind(keep_session_id)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        let affected = result.rows_affected();
+        if affected > 0 {
+            tracing::info!("Revocadas {} sesiones del usuario {} salvo la actual", affected, user_id);
+        }
+        Ok(affected)
+    }
+    
     /// Elimina sesiones expiradas
     async fn delete_expired_sessions(&self) -> SessionRepositoryResult<u64> {
         let now = Utc::now();
@@ -301,6 +379,31 @@ impl SessionStoragePort for SessionPgRepository {
             .map_err(DomainError::from)
     }
     
+    async fn get_session_by_id(&self, session_id: &str) -> Result<Session, DomainError> {
+        SessionRepository::get_session_by_id(self, session_id).await.map_err(DomainError::from)
+    }
+    
+    async fn get_sessions_by_user_id(&self, user_id: &str) -> Result<Vec<Session>, DomainError> {
+        SessionRepository::get_sessions_by_user_id(self, user_id).await.map_err(DomainError::from)
+    }
+    
+    async fn rotate_refresh_token(
+        &self,
+        session_id: &str,
+        refresh_token: &str,
+        expires_at: DateTime<Utc>,
+        ip_address: Option<&str>,
+        user_agent: Option<&str>,
+    ) -> Result<(), DomainError> {
+        SessionRepository::rotate_refresh_token(self, session_id, refresh_token, expires_at, ip_address, user_agent)
+            .await
+            .map_err(DomainError::from)
+    }
+    
+    async fn touch_session(&self, session_id: &str) -> Result<(), DomainError> {
+        SessionRepository::touch_session(self, session_id).await.map_err(DomainError::from)
+    }
+    
     async fn revoke_session(&self, session_id: &str) -> Result<(), DomainError> {
         SessionRepository::revoke_session(self, session_id).await.map_err(DomainError::from)
     }
@@ -310,4 +413,10 @@ impl SessionStoragePort for SessionPgRepository {
             .await
             .map_err(DomainError::from)
     }
-}
\ No newline at end of file
+    
+    async fn revoke_other_user_sessions(&self, user_id: &str, keep_session_id: &str) -> Result<u64, DomainError> {
+        SessionRepository::revoke_other_user_sessions(self, user_id, keep_session_id)
+            .await
+            .map_err(DomainError::from)
+    }
+}
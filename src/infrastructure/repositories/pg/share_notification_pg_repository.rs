@@ -0,0 +1,146 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+
+use crate::common::errors::DomainError;
+use crate::domain::entities::share_notification::{ShareChange, ShareNotification, SharedItemKind};
+use crate::domain::repositories::share_notification_repository::{ShareNotificationRepository, ShareNotificationRepositoryResult};
+
+pub struct ShareNotificationPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl ShareNotificationPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn map_sqlx_error(err: sqlx::Error) -> DomainError {
+        DomainError::database_error(format!("Error de base de datos en avisos de compartición: {}", err))
+    }
+
+    fn row_to_notification(row: &PgRow) -> Result<ShareNotification, DomainError> {
+        let change: String = row.get("change");
+        let item_kind: String = row.get("item_kind");
+
+        Ok(ShareNotification {
+            id: row.get("id"),
+            user_id: row.get("user_id"),
+            change: ShareChange::parse(&change)
+                .ok_or_else(|| DomainError::database_error(format!("Cambio de compartición desconocido: {}", change)))?,
+            item_kind: SharedItemKind::parse(&item_kind)
+                .ok_or_else(|| DomainError::database_error(format!("Tipo de elemento compartido desconocido: {}", item_kind)))?,
+            item_id: row.get("item_id"),
+            item_name: row.get("item_name"),
+            actor_id: row.get("actor_id"),
+            actor_name: row.get("actor_name"),
+            created_at: row.get("created_at"),
+            read_at: row.get("read_at"),
+        })
+    }
+}
+
+#[async_trait]
+impl ShareNotificationRepository for ShareNotificationPgRepository {
+    /// Guarda los avisos en una sola transacción
+    async fn create_notifications(&self, notifications: &[ShareNotification]) -> ShareNotificationRepositoryResult<()> {
+        if notifications.is_empty() {
+            return Ok(());
+        }
+
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+
+        for notification in notifications {
+            sqlx::query(
+                r#"
+                INSERT INTO auth.share_notifications (
+                    id, user_id, change, item_kind, item_id, item_name,
+                    actor_id, actor_name, created_at, read_at
+                ) VALUES (
+                    $1, $2, $3, $4, $5, $6, $7, $8, $9, $10
+                )
+                "#
+            )
+            .bind(&notification.id)
+            .bind(&notification.user_id)
+            .bind(notification.change.as_str())
+            .bind(notification.item_kind.as_str())
+            .bind(&notification.item_id)
+            .bind(&notification.item_name)
+            .bind(&notification.actor_id)
+            .bind(&notification.actor_name)
+            .bind(notification.created_at)
+            .bind(notification.read_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+        }
+
+        tx.commit().await.map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    /// Obtiene los avisos de un usuario, los más recientes primero
+    async fn list_notifications(&self, user_id: &str, limit: i64) -> ShareNotificationRepositoryResult<Vec<ShareNotification>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                id, user_id, change, item_kind, item_id, item_name,
+                actor_id, actor_name, created_at, read_at
+            FROM auth.share_notifications
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2
+            "#
+        )
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        rows.iter().map(Self::row_to_notification).collect()
+    }
+
+    /// Marca un aviso como leído sin mover la fecha si ya lo estaba
+    async fn mark_read(&self, user_id: &str, id: &str, read_at: DateTime<Utc>) -> ShareNotificationRepositoryResult<Option<ShareNotification>> {
+        let row = sqlx::query(
+            r#"
+            UPDATE auth.share_notifications
+            SET read_at = COALESCE(read_at, $3)
+            WHERE id = $1 AND user_id = $2
+            RETURNING
+                id, user_id, change, item_kind, item_id, item_name,
+                actor_id, actor_name, created_at, read_at
+            "#
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(read_at)
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        row.as_ref().map(Self::row_to_notification).transpose()
+    }
+
+    /// Elimina un aviso del usuario
+    async fn delete_notification(&self, user_id: &str, id: &str) -> ShareNotificationRepositoryResult<bool> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM auth.share_notifications
+            WHERE id = $1 AND user_id = $2
+            "#
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,147 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+
+use crate::common::errors::DomainError;
+use crate::domain::entities::sync_conflict::{ConflictKind, ConflictResolution, SyncConflict};
+use crate::domain::repositories::sync_conflict_repository::{SyncConflictRepository, SyncConflictRepositoryResult};
+
+pub struct SyncConflictPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl SyncConflictPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn map_sqlx_error(err: sqlx::Error) -> DomainError {
+        DomainError::database_error(format!("Error de base de datos en conflictos de sincronización: {}", err))
+    }
+
+    fn row_to_conflict(row: &PgRow) -> Result<SyncConflict, DomainError> {
+        let kind: String = row.get("kind");
+        let resolution: Option<String> = row.get("resolution");
+
+        Ok(SyncConflict {
+            id: row.get("id"),
+            user_id: row.get("user_id"),
+            kind: ConflictKind::parse(&kind)
+                .ok_or_else(|| DomainError::database_error(format!("Tipo de conflicto desconocido: {}", kind)))?,
+            path: row.get("path"),
+            conflict_path: row.get("conflict_path"),
+            detail: row.get("detail"),
+            occurrences: row.get("occurrences"),
+            created_at: row.get("created_at"),
+            last_seen_at: row.get("last_seen_at"),
+            resolved_at: row.get("resolved_at"),
+            resolution: resolution.as_deref().and_then(ConflictResolution::parse),
+        })
+    }
+}
+
+#[async_trait]
+impl SyncConflictRepository for SyncConflictPgRepository {
+    /// Guarda un conflicto nuevo o suma una ocurrencia al que sigue abierto
+    async fn record_conflict(&self, conflict: SyncConflict) -> SyncConflictRepositoryResult<SyncConflict> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO auth.sync_conflicts (
+                id, user_id, kind, path, conflict_path, detail,
+                occurrences, created_at, last_seen_at
+            ) VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, $9
+            )
+            ON CONFLICT (user_id, kind, path, (COALESCE(conflict_path, ''))) WHERE resolved_at IS NULL
+            DO UPDATE SET
+                occurrences = auth.sync_conflicts.occurrences + 1,
+                last_seen_at = EXCLUDED.last_seen_at,
+                detail = EXCLUDED.detail
+            RETURNING
+                id, user_id, kind, path, conflict_path, detail, occurrences,
+                created_at, last_seen_at, resolved_at, resolution
+            "#
+        )
+        .bind(&conflict.id)
+        .bind(&conflict.user_id)
+        .bind(conflict.kind.as_str())
+        .bind(&conflict.path)
+        .bind(&conflict.conflict_path)
+        .bind(&conflict.detail)
+        .bind(conflict.occurrences)
+        .bind(conflict.created_at)
+        .bind(conflict.last_seen_at)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Self::row_to_conflict(&row)
+    }
+
+    /// Obtiene los conflictos de un usuario, los más recientes primero
+    async fn list_conflicts(&self, user_id: &str, include_resolved: bool) -> SyncConflictRepositoryResult<Vec<SyncConflict>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                id, user_id, kind, path, conflict_path, detail, occurrences,
+                created_at, last_seen_at, resolved_at, resolution
+            FROM auth.sync_conflicts
+            WHERE user_id = $1 AND ($2 OR resolved_at IS NULL)
+            ORDER BY last_seen_at DESC
+            "#
+        )
+        .bind(user_id)
+        .bind(include_resolved)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        rows.iter().map(Self::row_to_conflict).collect()
+    }
+
+    /// Obtiene un conflicto de un usuario
+    async fn get_conflict(&self, user_id: &str, id: &str) -> SyncConflictRepositoryResult<Option<SyncConflict>> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                id, user_id, kind, path, conflict_path, detail, occurrences,
+                created_at, last_seen_at, resolved_at, resolution
+            FROM auth.sync_conflicts
+            WHERE id = $1 AND user_id = $2
+            "#
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        row.as_ref().map(Self::row_to_conflict).transpose()
+    }
+
+    /// Marca un conflicto como resuelto
+    async fn mark_resolved(
+        &self,
+        id: &str,
+        resolution: ConflictResolution,
+        resolved_at: DateTime<Utc>,
+    ) -> SyncConflictRepositoryResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE auth.sync_conflicts
+            SET resolution = $2, resolved_at = $3
+            WHERE id = $1
+            "#
+        )
+        .bind(id)
+        .bind(resolution.as_str())
+        .bind(resolved_at)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+}
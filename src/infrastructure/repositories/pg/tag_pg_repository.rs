@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use sqlx::{PgPool, Row, postgres::PgRow};
+
+use crate::common::errors::DomainError;
+use crate::domain::entities::tag::{Tag, TaggedItem, TaggedItemType};
+use crate::domain::repositories::tag_repository::{TagRepository, TagRepositoryResult};
+
+pub struct TagPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl TagPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn map_sqlx_error(err: sqlx::Error) -> DomainError {
+        DomainError::database_error(format!("Error de base de datos en etiquetas: {}", err))
+    }
+
+    /// Un usuario no puede repetir nombre de etiqueta; un duplicado es un conflicto
+    fn map_write_error(err: sqlx::Error, tag: &Tag) -> DomainError {
+        if let sqlx::Error::Database(db_err) = &err {
+            if db_err.code().is_some_and(|code| code == "23505") {
+                return DomainError::already_exists("Tag", tag.name.clone());
+            }
+        }
+        Self::map_sqlx_error(err)
+    }
+
+    fn row_to_tag(row: &PgRow) -> Tag {
+        Tag {
+            id: row.get("id"),
+            owner_id: row.get("owner_id"),
+            name: row.get("name"),
+            color: row.get("color"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        }
+    }
+
+    /// La columna solo admite 'file' y 'folder'; cualquier otro valor se lee como archivo
+    fn row_to_item(row: &PgRow) -> TaggedItem {
+        let item_type: String = row.get("item_type");
+        TaggedItem {
+            item_id: row.get("item_id"),
+            item_type: TaggedItemType::parse(&item_type).unwrap_or(TaggedItemType::File),
+        }
+    }
+}
+
+#[async_trait]
+impl TagRepository for TagPgRepository {
+    /// Crea una etiqueta
+    async fn create_tag(&self, tag: &Tag) -> TagRepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO auth.tags (id, owner_id, name, color, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#
+        )
+        .bind(&tag.id)
+        .bind(&tag.owner_id)
+        .bind(&tag.name)
+        .bind(&tag.color)
+        .bind(tag.created_at)
+        .bind(tag.updated_at)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| Self::map_write_error(e, tag))?;
+
+        Ok(())
+    }
+
+    /// Guarda el nombre y el color de una etiqueta
+    async fn update_tag(&self, tag: &Tag) -> TagRepositoryResult<()> {
+        let result = sqlx::query(
+            r#"
+            UPDATE auth.tags
+            SET name = $2, color = $3, updated_at = $4
+            WHERE id = $1
+            "#
+        )
+        .bind(&tag.id)
+        .bind(&tag.name)
+        .bind(&tag.color)
+        .bind(tag.updated_at)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| Self::map_write_error(e, tag))?;
+
+        if result.rows_affected() == 0 {
+            return Err(DomainError::not_found("Tag", tag.id.clone()));
+        }
+        Ok(())
+    }
+
+    /// Busca una etiqueta por su ID
+    async fn get_tag(&self, id: &str) -> TagRepositoryResult<Option<Tag>> {
+        let row = sqlx::query(
+            "SELECT id, owner_id, name, color, created_at, updated_at FROM auth.tags WHERE id = $1"
+        )
+        .bind(id)
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(row.as_ref().map(Self::row_to_tag))
+    }
+
+    /// Etiquetas de un usuario, por nombre
+    async fn list_tags(&self, owner_id: &str) -> TagRepositoryResult<Vec<Tag>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, owner_id, name, color, created_at, updated_at
+            FROM auth.tags
+            WHERE owner_id = $1
+            ORDER BY LOWER(name)
+            "#
+        )
+        .bind(owner_id)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(rows.iter().map(Self::row_to_tag).collect())
+    }
+
+    /// Elimina una etiqueta; sus asignaciones se borran en cascada
+    async fn delete_tag(&self, id: &str) -> TagRepositoryResult<bool> {
+        let result = sqlx::query("DELETE FROM auth.tags WHERE id = $1")
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Pone una etiqueta a un elemento
+    async fn assign_tag(&self, tag_id: &str, item: &TaggedItem) -> TagRepositoryResult<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO auth.tag_assignments (tag_id, item_id, item_type)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (tag_id, item_id, item_type) DO NOTHING
+            "#
+        )
+        .bind(tag_id)
+        .bind(&item.item_id)
+        .bind(item.item_type.as_str())
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Quita una etiqueta de un elemento
+    async fn unassign_tag(&self, tag_id: &str, item: &TaggedItem) -> TagRepositoryResult<bool> {
+        let result = sqlx::query(
+            "DELETE FROM auth.tag_assignments WHERE tag_id = $1 AND item_id = $2 AND item_type = $3"
+        )
+        .bind(tag_id)
+        .bind(&item.item_id)
+        .bind(item.item_type.as_str())
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Elementos que llevan una etiqueta
+    async fn list_tagged_items(&self, tag_id: &str) -> TagRepositoryResult<Vec<TaggedItem>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT item_id, item_type
+            FROM auth.tag_assignments
+            WHERE tag_id = $1
+            ORDER BY created_at DESC, item_id
+            "#
+        )
+        .bind(tag_id)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(rows.iter().map(Self::row_to_item).collect())
+    }
+
+    /// Etiquetas de un usuario puestas en varios elementos, por nombre
+    async fn get_tags_for_items(&self, owner_id: &str, item_ids: &[String]) -> TagRepositoryResult<HashMap<String, Vec<Tag>>> {
+        if item_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let rows = sqlx::query(
+            r#"
+            SELECT a.item_id, t.id, t.owner_id, t.name, t.color, t.created_at, t.updated_at
+            FROM auth.tag_assignments a
+            INNER JOIN auth.tags t ON t.id = a.tag_id
+            WHERE t.owner_id = $1 AND a.item_id = ANY($2)
+            ORDER BY a.item_id, LOWER(t.name)
+            "#
+        )
+        .bind(owner_id)
+        .bind(item_ids)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        let mut tags: HashMap<String, Vec<Tag>> = HashMap::new();
+        for row in rows {
+            tags.entry(row.get("item_id")).or_default().push(Self::row_to_tag(&row));
+        }
+
+        Ok(tags)
+    }
+
+    /// Elementos que llevan todas las etiquetas indicadas
+    async fn find_items_with_all_tags(&self, tag_ids: &[String]) -> TagRepositoryResult<Vec<TaggedItem>> {
+        if tag_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let rows = sqlx::query(
+            r#"
+            SELECT item_id, item_type
+            FROM auth.tag_assignments
+            WHERE tag_id = ANY($1)
+            GROUP BY item_id, item_type
+            HAVING COUNT(DISTINCT tag_id) = cardinality($1::text[])
+            "#
+        )
+        .bind(tag_ids)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(rows.iter().map(Self::row_to_item).collect())
+    }
+}
@@ -0,0 +1,96 @@
+use async_trait::async_trait;
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+
+use crate::common::errors::DomainError;
+use crate::domain::entities::theme::{InstanceTheme, ThemeLogo};
+use crate::domain::repositories::theme_repository::{ThemeRepository, ThemeRepositoryResult};
+
+pub struct ThemePgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl ThemePgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn map_sqlx_error(err: sqlx::Error) -> DomainError {
+        DomainError::database_error(format!("Error de base de datos en el tema: {}", err))
+    }
+}
+
+#[async_trait]
+impl ThemeRepository for ThemePgRepository {
+    /// Obtiene la única fila del tema, si existe
+    async fn get_theme(&self) -> ThemeRepositoryResult<Option<InstanceTheme>> {
+        let row = sqlx::query(
+            r#"
+            SELECT instance_name, primary_color, accent_color, login_message,
+                   logo, logo_content_type, updated_at
+            FROM auth.instance_theme
+            WHERE id = TRUE
+            "#
+        )
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(row.map(|row| {
+            let logo: Option<Vec<u8>> = row.get("logo");
+            let logo_content_type: Option<String> = row.get("logo_content_type");
+            InstanceTheme {
+                instance_name: row.get("instance_name"),
+                primary_color: row.get("primary_color"),
+                accent_color: row.get("accent_color"),
+                login_message: row.get("login_message"),
+                logo: logo.zip(logo_content_type).map(|(data, content_type)| ThemeLogo { content_type, data }),
+                updated_at: row.get("updated_at"),
+            }
+        }))
+    }
+
+    /// Crea o actualiza el tema conservando el logo
+    async fn save_theme(&self, theme: &InstanceTheme) -> ThemeRepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO auth.instance_theme (id, instance_name, primary_color, accent_color, login_message, updated_at)
+            VALUES (TRUE, $1, $2, $3, $4, $5)
+            ON CONFLICT (id) DO UPDATE SET
+                instance_name = EXCLUDED.instance_name,
+                primary_color = EXCLUDED.primary_color,
+                accent_color = EXCLUDED.accent_color,
+                login_message = EXCLUDED.login_message,
+                updated_at = EXCLUDED.updated_at
+            "#
+        )
+        .bind(&theme.instance_name)
+        .bind(&theme.primary_color)
+        .bind(&theme.accent_color)
+        .bind(&theme.login_message)
+        .bind(theme.updated_at)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    /// Sustituye o quita el logo; el tema debe haberse guardado antes
+    async fn set_logo(&self, logo: Option<&ThemeLogo>) -> ThemeRepositoryResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE auth.instance_theme
+            SET logo = $1, logo_content_type = $2
+            WHERE id = TRUE
+            "#
+        )
+        .bind(logo.map(|logo| logo.data.as_slice()))
+        .bind(logo.map(|logo| logo.content_type.as_str()))
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+}
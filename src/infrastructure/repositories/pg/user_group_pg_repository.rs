@@ -0,0 +1,185 @@
+use async_trait::async_trait;
+use sqlx::{PgPool, Row, postgres::PgRow};
+use std::sync::Arc;
+
+use crate::common::errors::DomainError;
+use crate::domain::entities::user_group::UserGroup;
+use crate::domain::repositories::user_group_repository::{UserGroupRepository, UserGroupRepositoryResult};
+
+pub struct UserGroupPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl UserGroupPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn map_sqlx_error(err: sqlx::Error) -> DomainError {
+        DomainError::database_error(format!("Error de base de datos en grupos de usuarios: {}", err))
+    }
+
+    /// Los nombres de grupo son únicos; un duplicado es un conflicto, no un fallo
+    fn map_write_error(err: sqlx::Error, group: &UserGroup) -> DomainError {
+        if let sqlx::Error::Database(db_err) = &err {
+            if db_err.code().is_some_and(|code| code == "23505") {
+                return DomainError::already_exists("UserGroup", group.name.clone());
+            }
+        }
+        Self::map_sqlx_error(err)
+    }
+
+    fn row_to_group(row: &PgRow) -> UserGroup {
+        UserGroup {
+            id: row.get("id"),
+            name: row.get("name"),
+            description: row.get("description"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        }
+    }
+}
+
+#[async_trait]
+impl UserGroupRepository for UserGroupPgRepository {
+    /// Crea un grupo
+    async fn create_group(&self, group: &UserGroup) -> UserGroupRepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO auth.user_groups (id, name, description, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#
+        )
+        .bind(&group.id)
+        .bind(&group.name)
+        .bind(&group.description)
+        .bind(group.created_at)
+        .bind(group.updated_at)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| Self::map_write_error(e, group))?;
+
+        Ok(())
+    }
+
+    /// Guarda el nombre y la descripción de un grupo
+    async fn update_group(&self, group: &UserGroup) -> UserGroupRepositoryResult<()> {
+        let result = sqlx::query(
+            r#"
+            UPDATE auth.user_groups
+            SET name = $2, description = $3, updated_at = $4
+            WHERE id = $1
+            "#
+        )
+        .bind(&group.id)
+        .bind(&group.name)
+        .bind(&group.description)
+        .bind(group.updated_at)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| Self::map_write_error(e, group))?;
+
+        if result.rows_affected() == 0 {
+            return Err(DomainError::not_found("UserGroup", group.id.clone()));
+        }
+        Ok(())
+    }
+
+    /// Busca un grupo por su ID
+    async fn get_group(&self, id: &str) -> UserGroupRepositoryResult<Option<UserGroup>> {
+        let row = sqlx::query(
+            "SELECT id, name, description, created_at, updated_at FROM auth.user_groups WHERE id = $1"
+        )
+        .bind(id)
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(row.as_ref().map(Self::row_to_group))
+    }
+
+    /// Obtiene todos los grupos
+    async fn list_groups(&self) -> UserGroupRepositoryResult<Vec<UserGroup>> {
+        let rows = sqlx::query(
+            "SELECT id, name, description, created_at, updated_at FROM auth.user_groups ORDER BY name"
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(rows.iter().map(Self::row_to_group).collect())
+    }
+
+    /// Elimina un grupo; sus miembros y lo compartido con él se borran en cascada
+    async fn delete_group(&self, id: &str) -> UserGroupRepositoryResult<bool> {
+        let result = sqlx::query("DELETE FROM auth.user_groups WHERE id = $1")
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Añade un usuario a un grupo
+    async fn add_member(&self, group_id: &str, user_id: &str) -> UserGroupRepositoryResult<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO auth.user_group_members (group_id, user_id)
+            VALUES ($1, $2)
+            ON CONFLICT (group_id, user_id) DO NOTHING
+            "#
+        )
+        .bind(group_id)
+        .bind(user_id)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Quita a un usuario de un grupo
+    async fn remove_member(&self, group_id: &str, user_id: &str) -> UserGroupRepositoryResult<bool> {
+        let result = sqlx::query("DELETE FROM auth.user_group_members WHERE group_id = $1 AND user_id = $2")
+            .bind(group_id)
+            .bind(user_id)
+            .execute(&*self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// IDs de los miembros de un grupo, por orden de alta
+    async fn list_members(&self, group_id: &str) -> UserGroupRepositoryResult<Vec<String>> {
+        let rows = sqlx::query(
+            "SELECT user_id FROM auth.user_group_members WHERE group_id = $1 ORDER BY added_at, user_id"
+        )
+        .bind(group_id)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(rows.iter().map(|row| row.get("user_id")).collect())
+    }
+
+    /// Grupos de los que es miembro un usuario
+    async fn list_groups_for_user(&self, user_id: &str) -> UserGroupRepositoryResult<Vec<UserGroup>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT g.id, g.name, g.description, g.created_at, g.updated_at
+            FROM auth.user_groups g
+            INNER JOIN auth.user_group_members m ON m.group_id = g.id
+            WHERE m.user_id = $1
+            ORDER BY g.name
+            "#
+        )
+        .bind(user_id)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(rows.iter().map(Self::row_to_group).collect())
+    }
+}
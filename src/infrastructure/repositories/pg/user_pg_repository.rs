@@ -1,9 +1,9 @@
 use async_trait::async_trait;
-use sqlx::{PgPool, Row};
+use sqlx::{PgPool, Row, postgres::PgRow};
 use std::sync::Arc;
 use futures::future::BoxFuture;
 
-use crate::domain::entities::user::{User, UserRole};
+use crate::domain::entities::user::{AuthSource, User, UserRole};
 use crate::domain::repositories::user_repository::{UserRepository, UserRepositoryError, UserRepositoryResult};
 use crate::application::ports::auth_ports::UserStoragePort;
 use crate::common::errors::DomainError;
@@ -50,6 +50,13 @@ impl UserPgRepository {
     }
 }
 
+/// Origen de la cuenta de una fila de `auth.users`
+fn auth_source(row: &PgRow) -> AuthSource {
+    row.try_get::<String, _>("auth_source").ok()
+        .and_then(|source| AuthSource::parse(&source))
+        .unwrap_or(AuthSource::Local)
+}
+
 #[async_trait]
 impl UserRepository for UserPgRepository {
     /// Crea un nuevo usuario utilizando una transacción
@@ -74,9 +81,9 @@ impl UserRepository for UserPgRepository {
                         INSERT INTO auth.users (
                             id, username, email, password_hash, role, 
                             storage_quota_bytes, storage_used_bytes, 
-                            created_at, updated_at, last_login_at, active
+                            created_at, updated_at, last_login_at, active, auth_source
                         ) VALUES (
-                            $1, $2, $3, $4, $5::auth.userrole, $6, $7, $8, $9, $10, $11
+                            $1, $2, $3, $4, $5::auth.userrole, $6, $7, $8, $9, $10, $11, $12
                         )
                         RETURNING *
                         "#
@@ -92,6 +99,7 @@ impl UserRepository for UserPgRepository {
                     .bind(user_clone.updated_at())
                     .bind(user_clone.last_login_at())
                     .bind(user_clone.is_active())
+                    .bind(user_clone.auth_source().as_str())
                     .execute(&mut **tx)
                     .await
                     .map_err(Self::map_sqlx_error)?;
@@ -114,7 +122,7 @@ impl UserRepository for UserPgRepository {
             SELECT 
                 id, username, email, password_hash, role::text as role_text, 
                 storage_quota_bytes, storage_used_bytes, 
-                created_at, updated_at, last_login_at, active
+                created_at, updated_at, last_login_at, active, auth_source
             FROM auth.users
             WHERE id = $1
             "#
@@ -126,10 +134,7 @@ impl UserRepository for UserPgRepository {
 
         // Convert role string to UserRole enum
         let role_str: Option<String> = row.try_get("role_text").unwrap_or(None);
-        let role = match role_str.as_deref() {
-            Some("admin") => UserRole::Admin,
-            _ => UserRole::User,
-        };
+        let role = role_str.as_deref().and_then(UserRole::parse).unwrap_or(UserRole::User);
         
         Ok(User::from_data(
             row.get("id"),
@@ -143,6 +148,7 @@ impl UserRepository for UserPgRepository {
             row.get("updated_at"),
             row.get("last_login_at"),
             row.get("active"),
+            auth_source(&row),
         ))
     }
     
@@ -153,7 +159,7 @@ impl UserRepository for UserPgRepository {
             SELECT 
                 id, username, email, password_hash, role::text as role_text, 
                 storage_quota_bytes, storage_used_bytes, 
-                created_at, updated_at, last_login_at, active
+                created_at, updated_at, last_login_at, active, auth_source
             FROM auth.users
             WHERE username = $1
             "#
@@ -165,10 +171,7 @@ impl UserRepository for UserPgRepository {
 
         // Convert role string to UserRole enum
         let role_str: Option<String> = row.try_get("role_text").unwrap_or(None);
-        let role = match role_str.as_deref() {
-            Some("admin") => UserRole::Admin,
-            _ => UserRole::User,
-        };
+        let role = role_str.as_deref().and_then(UserRole::parse).unwrap_or(UserRole::User);
         
         Ok(User::from_data(
             row.get("id"),
@@ -182,6 +185,7 @@ impl UserRepository for UserPgRepository {
             row.get("updated_at"),
             row.get("last_login_at"),
             row.get("active"),
+            auth_source(&row),
         ))
     }
     
@@ -192,7 +196,7 @@ impl UserRepository for UserPgRepository {
             SELECT 
                 id, username, email, password_hash, role::text as role_text, 
                 storage_quota_bytes, storage_used_bytes, 
-                created_at, updated_at, last_login_at, active
+                created_at, updated_at, last_login_at, active, auth_source
             FROM auth.users
             WHERE email = $1
             "#
@@ -204,10 +208,7 @@ impl UserRepository for UserPgRepository {
 
         // Convert role string to UserRole enum
         let role_str: Option<String> = row.try_get("role_text").unwrap_or(None);
-        let role = match role_str.as_deref() {
-            Some("admin") => UserRole::Admin,
-            _ => UserRole::User,
-        };
+        let role = role_str.as_deref().and_then(UserRole::parse).unwrap_or(UserRole::User);
         
         Ok(User::from_data(
             row.get("id"),
@@ -221,6 +222,7 @@ impl UserRepository for UserPgRepository {
             row.get("updated_at"),
             row.get("last_login_at"),
             row.get("active"),
+            auth_source(&row),
         ))
     }
     
@@ -247,7 +249,8 @@ impl UserRepository for UserPgRepository {
                             storage_used_bytes = $7,
                             updated_at = $8,
                             last_login_at = $9,
-                            active = $10
+                            active = $10,
+                            auth_source = $11
                         WHERE id = $1
                         "#
                     )
@@ -261,6 +264,7 @@ impl UserRepository for UserPgRepository {
                     .bind(user_clone.updated_at())
                     .bind(user_clone.last_login_at())
                     .bind(user_clone.is_active())
+                    .bind(user_clone.auth_source().as_str())
                     .execute(&mut **tx)
                     .await
                     .map_err(Self::map_sqlx_error)?;
@@ -322,7 +326,7 @@ impl UserRepository for UserPgRepository {
             SELECT 
                 id, username, email, password_hash, role::text as role_text, 
                 storage_quota_bytes, storage_used_bytes, 
-                created_at, updated_at, last_login_at, active
+                created_at, updated_at, last_login_at, active, auth_source
             FROM auth.users
             ORDER BY created_at DESC
             LIMIT $1 OFFSET $2
@@ -338,10 +342,7 @@ impl UserRepository for UserPgRepository {
             .map(|row| {
                 // Convert role string to UserRole enum for each row
                 let role_str: Option<String> = row.try_get("role_text").unwrap_or(None);
-                let role = match role_str.as_deref() {
-                    Some("admin") => UserRole::Admin,
-                    _ => UserRole::User,
-                };
+                let role = role_str.as_deref().and_then(UserRole::parse).unwrap_or(UserRole::User);
                 
                 User::from_data(
                     row.get("id"),
@@ -355,6 +356,7 @@ impl UserRepository for UserPgRepository {
                     row.get("updated_at"),
                     row.get("last_login_at"),
                     row.get("active"),
+                    auth_source(&row),
                 )
             })
             .collect();
@@ -432,7 +434,7 @@ impl UserRepository for UserPgRepository {
             SELECT 
                 id, username, email, password_hash, role::text as role_text, 
                 storage_quota_bytes, storage_used_bytes, 
-                created_at, updated_at, last_login_at, active
+                created_at, updated_at, last_login_at, active, auth_source
             FROM auth.users
             WHERE role::text = $1
             ORDER BY created_at DESC
@@ -447,10 +449,58 @@ impl UserRepository for UserPgRepository {
             .map(|row| {
                 // Convert role string to UserRole enum for each row
                 let role_str: Option<String> = row.try_get("role_text").unwrap_or(None);
-                let role = match role_str.as_deref() {
-                    Some("admin") => UserRole::Admin,
-                    _ => UserRole::User,
-                };
+                let role = role_str.as_deref().and_then(UserRole::parse).unwrap_or(UserRole::User);
+                
+                User::from_data(
+                    row.get("id"),
+                    row.get("username"),
+                    row.get("email"),
+                    row.get("password_hash"),
+                    role,
+                    row.get("storage_quota_bytes"),
+                    row.get("storage_used_bytes"),
+                    row.get("created_at"),
+                    row.get("updated_at"),
+                    row.get("last_login_at"),
+                    row.get("active"),
+                    auth_source(&row),
+                )
+            })
+            .collect();
+
+        Ok(users)
+    }
+    
+    /// Busca usuarios activos por nombre de usuario o correo (sin distinguir mayúsculas)
+    async fn search_users(&self, query: &str, limit: i64) -> UserRepositoryResult<Vec<User>> {
+        // Escapar los comodines de LIKE para que el texto se busque literalmente
+        let pattern = format!(
+            "%{}%",
+            query.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+        );
+        let rows = sqlx::query(
+            r#"
+            SELECT 
+                id, username, email, password_hash, role::text as role_text, 
+                storage_quota_bytes, storage_used_bytes, 
+                created_at, updated_at, last_login_at, active, auth_source
+            FROM auth.users
+            WHERE active = true
+              AND (username ILIKE $1 OR email ILIKE $1)
+            ORDER BY username
+            LIMIT $2
+            "#
+        )
+        .bind(pattern)
+        .bind(limit)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        let users = rows.into_iter()
+            .map(|row| {
+                let role_str: Option<String> = row.try_get("role_text").unwrap_or(None);
+                let role = role_str.as_deref().and_then(UserRole::parse).unwrap_or(UserRole::User);
                 
                 User::from_data(
                     row.get("id"),
@@ -464,6 +514,7 @@ impl UserRepository for UserPgRepository {
                     row.get("updated_at"),
                     row.get("last_login_at"),
                     row.get("active"),
+                    auth_source(&row),
                 )
             })
             .collect();
@@ -525,6 +576,10 @@ impl UserStoragePort for UserPgRepository {
         UserRepository::list_users_by_role(self, role).await.map_err(DomainError::from)
     }
     
+    async fn search_users(&self, query: &str, limit: i64) -> Result<Vec<User>, DomainError> {
+        UserRepository::search_users(self, query, limit).await.map_err(DomainError::from)
+    }
+    
     async fn delete_user(&self, user_id: &str) -> Result<(), DomainError> {
         UserRepository::delete_user(self, user_id)
             .await
@@ -0,0 +1,97 @@
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+
+use crate::application::ports::auth_ports::UserQuarantineStoragePort;
+use crate::common::errors::DomainError;
+use crate::domain::entities::user_quarantine::UserQuarantine;
+
+pub struct UserQuarantinePgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl UserQuarantinePgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn map_sqlx_error(err: sqlx::Error) -> DomainError {
+        DomainError::database_error(format!("Error de base de datos en la cuarentena de usuarios: {}", err))
+    }
+
+    fn row_to_quarantine(row: &PgRow) -> UserQuarantine {
+        UserQuarantine {
+            user_id: row.get("user_id"),
+            quarantined_at: row.get("quarantined_at"),
+            purge_after: row.get("purge_after"),
+            quarantined_by: row.get("quarantined_by"),
+        }
+    }
+}
+
+#[async_trait]
+impl UserQuarantineStoragePort for UserQuarantinePgRepository {
+    async fn save_quarantine(&self, quarantine: UserQuarantine) -> Result<UserQuarantine, DomainError> {
+        sqlx::query(
+            r#"
+            INSERT INTO auth.user_quarantine (user_id, quarantined_at, purge_after, quarantined_by)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id) DO UPDATE SET
+                quarantined_at = EXCLUDED.quarantined_at,
+                purge_after = EXCLUDED.purge_after,
+                quarantined_by = EXCLUDED.quarantined_by
+            "#
+        )
+        .bind(&quarantine.user_id)
+        .bind(quarantine.quarantined_at)
+        .bind(quarantine.purge_after)
+        .bind(&quarantine.quarantined_by)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(quarantine)
+    }
+
+    async fn get_quarantine(&self, user_id: &str) -> Result<Option<UserQuarantine>, DomainError> {
+        let row = sqlx::query(
+            r#"
+            SELECT user_id, quarantined_at, purge_after, quarantined_by
+            FROM auth.user_quarantine
+            WHERE user_id = $1
+            "#
+        )
+        .bind(user_id)
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(row.as_ref().map(Self::row_to_quarantine))
+    }
+
+    async fn list_quarantines(&self) -> Result<Vec<UserQuarantine>, DomainError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT user_id, quarantined_at, purge_after, quarantined_by
+            FROM auth.user_quarantine
+            ORDER BY purge_after ASC
+            "#
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(rows.iter().map(Self::row_to_quarantine).collect())
+    }
+
+    async fn delete_quarantine(&self, user_id: &str) -> Result<bool, DomainError> {
+        let result = sqlx::query("DELETE FROM auth.user_quarantine WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&*self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
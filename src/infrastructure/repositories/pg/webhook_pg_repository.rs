@@ -0,0 +1,293 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+
+use crate::common::errors::DomainError;
+use crate::domain::entities::webhook::{DeliveryStatus, Webhook, WebhookDelivery, WebhookEvent};
+use crate::domain::repositories::webhook_repository::{WebhookRepository, WebhookRepositoryResult};
+
+pub struct WebhookPgRepository {
+    pool: Arc<PgPool>,
+}
+
+impl WebhookPgRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn map_sqlx_error(err: sqlx::Error) -> DomainError {
+        DomainError::database_error(format!("Error de base de datos en los webhooks: {}", err))
+    }
+
+    fn parse_event(value: &str) -> Result<WebhookEvent, DomainError> {
+        WebhookEvent::parse(value)
+            .ok_or_else(|| DomainError::database_error(format!("Suceso de webhook desconocido: {}", value)))
+    }
+
+    fn row_to_webhook(row: &PgRow) -> Result<Webhook, DomainError> {
+        let events: Vec<String> = row.get("events");
+
+        Ok(Webhook {
+            id: row.get("id"),
+            url: row.get("url"),
+            events: events.iter().map(|event| Self::parse_event(event)).collect::<Result<Vec<_>, _>>()?,
+            secret: row.get("secret"),
+            description: row.get("description"),
+            active: row.get("active"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+    }
+
+    fn row_to_delivery(row: &PgRow) -> Result<WebhookDelivery, DomainError> {
+        let event: String = row.get("event");
+        let status: String = row.get("status");
+        let attempts: i32 = row.get("attempts");
+        let response_status: Option<i32> = row.get("response_status");
+
+        Ok(WebhookDelivery {
+            id: row.get("id"),
+            webhook_id: row.get("webhook_id"),
+            event: Self::parse_event(&event)?,
+            payload: row.get("payload"),
+            status: DeliveryStatus::parse(&status)
+                .ok_or_else(|| DomainError::database_error(format!("Estado de envío desconocido: {}", status)))?,
+            attempts: attempts.max(0) as u32,
+            next_attempt_at: row.get("next_attempt_at"),
+            response_status: response_status.map(|status| status as u16),
+            error: row.get("error"),
+            created_at: row.get("created_at"),
+            delivered_at: row.get("delivered_at"),
+        })
+    }
+
+    fn event_names(webhook: &Webhook) -> Vec<String> {
+        webhook.events.iter().map(|event| event.as_str().to_string()).collect()
+    }
+}
+
+#[async_trait]
+impl WebhookRepository for WebhookPgRepository {
+    async fn create_webhook(&self, webhook: &Webhook) -> WebhookRepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO auth.webhooks (
+                id, url, events, secret, description, active, created_at, updated_at
+            ) VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8
+            )
+            "#
+        )
+        .bind(&webhook.id)
+        .bind(&webhook.url)
+        .bind(Self::event_names(webhook))
+        .bind(&webhook.secret)
+        .bind(&webhook.description)
+        .bind(webhook.active)
+        .bind(webhook.created_at)
+        .bind(webhook.updated_at)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    async fn update_webhook(&self, webhook: &Webhook) -> WebhookRepositoryResult<()> {
+        let result = sqlx::query(
+            r#"
+            UPDATE auth.webhooks
+            SET url = $2, events = $3, secret = $4, description = $5,
+                active = $6, updated_at = $7
+            WHERE id = $1
+            "#
+        )
+        .bind(&webhook.id)
+        .bind(&webhook.url)
+        .bind(Self::event_names(webhook))
+        .bind(&webhook.secret)
+        .bind(&webhook.description)
+        .bind(webhook.active)
+        .bind(webhook.updated_at)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(DomainError::not_found("Webhook", webhook.id.clone()));
+        }
+        Ok(())
+    }
+
+    /// Borra el webhook; sus envíos se borran en cascada
+    async fn delete_webhook(&self, id: &str) -> WebhookRepositoryResult<()> {
+        let result = sqlx::query("DELETE FROM auth.webhooks WHERE id = $1")
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(DomainError::not_found("Webhook", id));
+        }
+        Ok(())
+    }
+
+    async fn get_webhook(&self, id: &str) -> WebhookRepositoryResult<Option<Webhook>> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, url, events, secret, description, active, created_at, updated_at
+            FROM auth.webhooks
+            WHERE id = $1
+            "#
+        )
+        .bind(id)
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        row.as_ref().map(Self::row_to_webhook).transpose()
+    }
+
+    async fn list_webhooks(&self) -> WebhookRepositoryResult<Vec<Webhook>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, url, events, secret, description, active, created_at, updated_at
+            FROM auth.webhooks
+            ORDER BY created_at
+            "#
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        rows.iter().map(Self::row_to_webhook).collect()
+    }
+
+    async fn list_webhooks_for_event(&self, event: WebhookEvent) -> WebhookRepositoryResult<Vec<Webhook>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, url, events, secret, description, active, created_at, updated_at
+            FROM auth.webhooks
+            WHERE active AND $1 = ANY(events)
+            ORDER BY created_at
+            "#
+        )
+        .bind(event.as_str())
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        rows.iter().map(Self::row_to_webhook).collect()
+    }
+
+    async fn create_delivery(&self, delivery: &WebhookDelivery) -> WebhookRepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO auth.webhook_deliveries (
+                id, webhook_id, event, payload, status, attempts,
+                next_attempt_at, response_status, error, created_at, delivered_at
+            ) VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11
+            )
+            "#
+        )
+        .bind(&delivery.id)
+        .bind(&delivery.webhook_id)
+        .bind(delivery.event.as_str())
+        .bind(&delivery.payload)
+        .bind(delivery.status.as_str())
+        .bind(delivery.attempts as i32)
+        .bind(delivery.next_attempt_at)
+        .bind(delivery.response_status.map(i32::from))
+        .bind(&delivery.error)
+        .bind(delivery.created_at)
+        .bind(delivery.delivered_at)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    async fn update_delivery(&self, delivery: &WebhookDelivery) -> WebhookRepositoryResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE auth.webhook_deliveries
+            SET status = $2, attempts = $3, next_attempt_at = $4,
+                response_status = $5, error = $6, delivered_at = $7
+            WHERE id = $1
+            "#
+        )
+        .bind(&delivery.id)
+        .bind(delivery.status.as_str())
+        .bind(delivery.attempts as i32)
+        .bind(delivery.next_attempt_at)
+        .bind(delivery.response_status.map(i32::from))
+        .bind(&delivery.error)
+        .bind(delivery.delivered_at)
+        .execute(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    async fn due_deliveries(&self, now: DateTime<Utc>, limit: i64) -> WebhookRepositoryResult<Vec<WebhookDelivery>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                id, webhook_id, event, payload, status, attempts,
+                next_attempt_at, response_status, error, created_at, delivered_at
+            FROM auth.webhook_deliveries
+            WHERE status = 'pending' AND next_attempt_at <= $1
+            ORDER BY next_attempt_at
+            LIMIT $2
+            "#
+        )
+        .bind(now)
+        .bind(limit)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        rows.iter().map(Self::row_to_delivery).collect()
+    }
+
+    async fn list_deliveries(&self, webhook_id: &str, limit: i64, offset: i64) -> WebhookRepositoryResult<(Vec<WebhookDelivery>, i64)> {
+        let count_row = sqlx::query(
+            r#"
+            SELECT COUNT(*) AS count
+            FROM auth.webhook_deliveries
+            WHERE webhook_id = $1
+            "#
+        )
+        .bind(webhook_id)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                id, webhook_id, event, payload, status, attempts,
+                next_attempt_at, response_status, error, created_at, delivered_at
+            FROM auth.webhook_deliveries
+            WHERE webhook_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#
+        )
+        .bind(webhook_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        let deliveries = rows.iter().map(Self::row_to_delivery).collect::<Result<Vec<_>, _>>()?;
+        Ok((deliveries, count_row.get("count")))
+    }
+}
@@ -0,0 +1,95 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use tokio::fs::{self, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+use crate::{
+    application::ports::share_ports::ShareAccessLogPort,
+    common::errors::DomainError,
+    domain::entities::share_access_log::ShareAccessEntry,
+};
+
+/// Registro de accesos de los enlaces compartidos en un fichero JSON Lines
+/// (`share_access_log.jsonl` junto a `shares.json`).
+///
+/// Cada suceso es una línea que se añade al final; las líneas que no se
+/// pueden leer se ignoran para que un corte a mitad de escritura no
+/// inutilice el registro.
+pub struct ShareAccessLogFsRepository {
+    path: PathBuf,
+    // Serializa las escrituras para que no se mezclen las líneas
+    write_lock: Mutex<()>,
+}
+
+impl ShareAccessLogFsRepository {
+    pub fn new(storage_path: PathBuf) -> Self {
+        Self {
+            path: storage_path.join("share_access_log.jsonl"),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    fn io_error(e: std::io::Error) -> DomainError {
+        DomainError::internal_error("ShareAccessLog", format!("Error en el registro de accesos: {}", e))
+    }
+}
+
+#[async_trait]
+impl ShareAccessLogPort for ShareAccessLogFsRepository {
+    async fn record(&self, entry: ShareAccessEntry) -> Result<(), DomainError> {
+        let mut line = serde_json::to_string(&entry)
+            .map_err(|e| DomainError::internal_error("ShareAccessLog", e.to_string()))?;
+        line.push('\n');
+
+        let _guard = self.write_lock.lock().await;
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).await.map_err(Self::io_error)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .map_err(Self::io_error)?;
+        file.write_all(line.as_bytes()).await.map_err(Self::io_error)?;
+        file.flush().await.map_err(Self::io_error)
+    }
+
+    async fn list_for_share(&self, share_id: &str, limit: usize) -> Result<Vec<ShareAccessEntry>, DomainError> {
+        let content = match fs::read_to_string(&self.path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(Self::io_error(e)),
+        };
+
+        Ok(content.lines()
+            .rev()
+            .filter_map(|line| serde_json::from_str::<ShareAccessEntry>(line).ok())
+            .filter(|entry| entry.share_id == share_id)
+            .take(limit)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::share_access_log::ShareAccessEvent;
+
+    #[tokio::test]
+    async fn test_entries_are_listed_per_share_newest_first() {
+        let temp = tempfile::tempdir().unwrap();
+        let log = ShareAccessLogFsRepository::new(temp.path().to_path_buf());
+        assert!(log.list_for_share("a", 10).await.unwrap().is_empty());
+
+        log.record(ShareAccessEntry::new("a", ShareAccessEvent::CodeSent, Some("ana@example.com"), None)).await.unwrap();
+        log.record(ShareAccessEntry::new("b", ShareAccessEvent::Accessed, None, Some("10.0.0.1"))).await.unwrap();
+        log.record(ShareAccessEntry::new("a", ShareAccessEvent::Verified, Some("ana@example.com"), None)).await.unwrap();
+
+        let events: Vec<_> = log.list_for_share("a", 10).await.unwrap().into_iter().map(|e| e.event).collect();
+        assert_eq!(events, vec![ShareAccessEvent::Verified, ShareAccessEvent::CodeSent]);
+        assert_eq!(log.list_for_share("a", 1).await.unwrap().len(), 1);
+    }
+}
@@ -1,14 +1,14 @@
-use std::{path::Path, sync::Arc};
+use std::{collections::HashMap, path::Path, sync::Arc, time::SystemTime};
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use tokio::{fs, io};
+use tokio::{fs, io, sync::RwLock};
 
 use crate::{
     application::ports::share_ports::ShareStoragePort,
     common::{config::AppConfig, errors::DomainError},
     domain::{
-        entities::share::{Share, ShareItemType},
+        entities::share::{LinkPreview, Share, ShareItemType, SharePermissions},
     },
 };
 
@@ -24,18 +24,80 @@ struct ShareRecord {
     permissions_read: bool,
     permissions_write: bool,
     permissions_reshare: bool,
+    #[serde(default)]
+    permissions_upload: bool,
+    /// Bitmask of `SharePermissions`; the flags above are kept alongside it
+    /// for readers of older versions
+    #[serde(default)]
+    permissions: Option<u8>,
     created_at: u64,
     created_by: String,
     access_count: u64,
+    #[serde(default)]
+    download_count: u64,
+    #[serde(default)]
+    link_preview: Option<String>,
+    #[serde(default)]
+    email_verification: bool,
+    #[serde(default)]
+    allowed_emails: Vec<String>,
+    #[serde(default)]
+    allowed_groups: Vec<String>,
+}
+
+/// Enlaces de shares.json agrupados por elemento. Se vuelve a leer el
+/// archivo cuando cambia su fecha de modificación o lo escribe este repositorio
+#[derive(Default)]
+struct ShareIndex {
+    loaded: bool,
+    modified: Option<SystemTime>,
+    by_item: HashMap<(String, String), Vec<Share>>,
 }
 
 pub struct ShareFsRepository {
     config: Arc<AppConfig>,
+    index: RwLock<ShareIndex>,
 }
 
 impl ShareFsRepository {
     pub fn new(config: Arc<AppConfig>) -> Self {
-        Self { config }
+        Self { config, index: RwLock::new(ShareIndex::default()) }
+    }
+
+    /// Enlaces de los elementos, desde el índice en memoria
+    async fn indexed_shares(&self, items: &[(String, ShareItemType)]) -> Result<Vec<Share>, io::Error> {
+        let modified = match fs::metadata(self.get_shares_path()).await {
+            Ok(metadata) => Some(metadata.modified()?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+        let lookup = |index: &ShareIndex| -> Vec<Share> {
+            items.iter()
+                .filter_map(|(id, kind)| index.by_item.get(&(id.clone(), kind.to_string())))
+                .flatten()
+                .cloned()
+                .collect()
+        };
+
+        {
+            let index = self.index.read().await;
+            if index.loaded && index.modified == modified {
+                return Ok(lookup(&index));
+            }
+        }
+
+        let records = self.read_shares().await?;
+        let mut index = self.index.write().await;
+        index.by_item.clear();
+        for record in &records {
+            index.by_item
+                .entry((record.item_id.clone(), record.item_type.clone()))
+                .or_default()
+                .push(self.to_entity(record));
+        }
+        index.loaded = true;
+        index.modified = modified;
+        Ok(lookup(&index))
     }
 
     /// Obtiene la ruta del archivo JSON donde se almacenan los enlaces compartidos
@@ -69,7 +131,9 @@ impl ShareFsRepository {
             fs::create_dir_all(dir).await?
         }
 
-        fs::write(path, json).await
+        fs::write(path, json).await?;
+        self.index.write().await.loaded = false;
+        Ok(())
     }
 
     /// Convierte un registro del sistema de archivos a una entidad de dominio
@@ -77,11 +141,17 @@ impl ShareFsRepository {
         let item_type = ShareItemType::try_from(record.item_type.as_str())
             .unwrap_or(ShareItemType::File);
 
-        let permissions = crate::domain::entities::share::SharePermissions::new(
-            record.permissions_read,
-            record.permissions_write,
-            record.permissions_reshare,
-        );
+        // Antes de la máscara, poder escribir incluía poder borrar
+        let permissions = match record.permissions {
+            Some(bits) => SharePermissions::from_bits(bits),
+            None => SharePermissions::new(
+                record.permissions_read,
+                record.permissions_write,
+                record.permissions_reshare,
+            )
+            .with_delete(record.permissions_write)
+            .with_upload(record.permissions_upload),
+        };
 
         Share {
             id: record.id.clone(),
@@ -94,6 +164,13 @@ impl ShareFsRepository {
             created_at: record.created_at,
             created_by: record.created_by.clone(),
             access_count: record.access_count,
+            download_count: record.download_count,
+            link_preview: record.link_preview.as_deref()
+                .and_then(|preview| LinkPreview::try_from(preview).ok())
+                .unwrap_or_default(),
+            email_verification: record.email_verification,
+            allowed_emails: record.allowed_emails.clone(),
+            allowed_groups: record.allowed_groups.clone(),
         }
     }
 
@@ -106,12 +183,19 @@ impl ShareFsRepository {
             token: share.token.clone(),
             password_hash: share.password_hash.clone(),
             expires_at: share.expires_at,
-            permissions_read: share.permissions.read,
-            permissions_write: share.permissions.write,
-            permissions_reshare: share.permissions.reshare,
+            permissions_read: share.permissions.read(),
+            permissions_write: share.permissions.write(),
+            permissions_reshare: share.permissions.reshare(),
+            permissions_upload: share.permissions.upload(),
+            permissions: Some(share.permissions.bits()),
             created_at: share.created_at,
             created_by: share.created_by.clone(),
             access_count: share.access_count,
+            download_count: share.download_count,
+            link_preview: Some(share.link_preview.to_string()),
+            email_verification: share.email_verification,
+            allowed_emails: share.allowed_emails.clone(),
+            allowed_groups: share.allowed_groups.clone(),
         }
     }
 }
@@ -247,4 +331,28 @@ impl ShareStoragePort for ShareFsRepository {
 
         Ok((paginated, total))
     }
+
+    async fn find_all_shares(&self) -> Result<Vec<Share>, DomainError> {
+        let shares = self.read_shares().await
+            .map_err(|e| DomainError::internal_error("Share", e.to_string()))?;
+
+        Ok(shares.iter().map(|record| self.to_entity(record)).collect())
+    }
+
+    async fn find_shares_for_recipient(
+        &self,
+        items: &[(String, ShareItemType)],
+        email: &str,
+        group_ids: &[String],
+    ) -> Result<Vec<Share>, DomainError> {
+        let shares = self.indexed_shares(items).await
+            .map_err(|e| DomainError::internal_error("Share", e.to_string()))?;
+
+        Ok(shares.into_iter()
+            .filter(|share| {
+                share.allowed_emails.iter().any(|allowed| allowed == email)
+                    || share.allowed_groups.iter().any(|group| group_ids.contains(group))
+            })
+            .collect())
+    }
 }
@@ -389,4 +389,18 @@ impl TrashRepository for TrashFsRepository {
             
         Ok(expired_items)
     }
+    
+    async fn get_all_items(&self) -> Result<Vec<TrashedItem>> {
+        let entries = self.get_trash_entries().await?;
+        
+        let mut items = Vec::with_capacity(entries.len());
+        for entry in entries {
+            match self.entry_to_trashed_item(entry) {
+                Ok(item) => items.push(item),
+                Err(e) => error!("Error converting trash entry: {}", e),
+            }
+        }
+        
+        Ok(items)
+    }
 }
\ No newline at end of file
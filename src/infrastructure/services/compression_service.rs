@@ -1,4 +1,4 @@
-use std::io::{Read};
+use std::io::{Read, Write};
 use std::sync::Arc;
 use async_trait::async_trait;
 use bytes::Bytes;
@@ -7,6 +7,7 @@ use tracing::error;
 use std::io;
 use flate2::Compression;
 use flate2::read::GzEncoder as GzEncoderRead;
+use flate2::write::GzEncoder as GzEncoderWrite;
 use flate2::bufread::GzDecoder;
 
 use crate::infrastructure::services::buffer_pool::BufferPool;
@@ -65,6 +66,49 @@ pub trait CompressionService: Send + Sync {
     fn should_compress(&self, mime_type: &str, size: u64) -> bool;
 }
 
+/// Comprime un stream con Gzip trozo a trozo, sin acumular el contenido en
+/// memoria. A diferencia de `compress_stream` no toma prestado el servicio,
+/// por lo que el resultado puede usarse directamente como cuerpo de respuesta
+pub fn gzip_stream<S>(stream: S, level: CompressionLevel) -> impl Stream<Item = io::Result<Bytes>> + Send + 'static
+where
+    S: Stream<Item = io::Result<Bytes>> + Send + 'static,
+{
+    async_stream::stream! {
+        let mut encoder = GzEncoderWrite::new(Vec::new(), level.into());
+        let mut stream = Box::pin(stream);
+        
+        while let Some(result) = stream.next().await {
+            let chunk = match result {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    yield Err(e);
+                    return;
+                }
+            };
+            
+            if let Err(e) = encoder.write_all(&chunk) {
+                yield Err(e);
+                return;
+            }
+            
+            // Entregar lo que el encoder ya haya producido
+            let compressed = std::mem::take(encoder.get_mut());
+            if !compressed.is_empty() {
+                yield Ok(Bytes::from(compressed));
+            }
+        }
+        
+        match encoder.finish() {
+            Ok(rest) => {
+                if !rest.is_empty() {
+                    yield Ok(Bytes::from(rest));
+                }
+            },
+            Err(e) => yield Err(e),
+        }
+    }
+}
+
 /// Implementación de servicios de compresión usando Gzip
 pub struct GzipCompressionService {
     /// Pool de buffers para optimización de memoria
@@ -233,39 +277,7 @@ impl CompressionService for GzipCompressionService {
     where
         S: Stream<Item = io::Result<Bytes>> + Send + 'static + Unpin
     {
-        // For now, simplify the implementation to avoid complex pinning issues
-        // This implementation collects all stream data and then compresses it at once
-        // Future optimization would be to implement true streaming compression
-        let compression_level = level;
-        
-        Box::pin(async_stream::stream! {
-            let mut data = Vec::new();
-            
-            // Collect all bytes from the stream
-            let mut stream = Box::pin(stream);
-            while let Some(result) = stream.next().await {
-                match result {
-                    Ok(bytes) => {
-                        data.extend_from_slice(&bytes);
-                    },
-                    Err(e) => {
-                        yield Err(e);
-                        return;
-                    }
-                }
-            }
-            
-            // Compress collected data
-            match self.compress_data(&data, compression_level).await {
-                Ok(compressed) => {
-                    // Return compressed data as a single chunk
-                    yield Ok(Bytes::from(compressed));
-                },
-                Err(e) => {
-                    yield Err(e);
-                }
-            }
-        })
+        gzip_stream(stream, level)
     }
     
     /// Descomprime un stream de bytes
@@ -405,6 +417,25 @@ mod tests {
         assert_eq!(String::from_utf8(decompressed).unwrap(), expected);
     }
     
+    #[tokio::test]
+    async fn test_gzip_stream_emits_output_before_input_ends() {
+        let service = GzipCompressionService::new();
+        
+        // Datos poco compresibles para que el encoder vacíe su buffer interno
+        let data: Vec<u8> = (0..512 * 1024u32).map(|i| (i.wrapping_mul(2654435761) >> 13) as u8).collect();
+        let chunks: Vec<io::Result<Bytes>> = data.chunks(16 * 1024)
+            .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+            .collect();
+        
+        let compressed_chunks: Vec<Bytes> = gzip_stream(futures::stream::iter(chunks), CompressionLevel::Fast)
+            .try_collect().await.unwrap();
+        assert!(compressed_chunks.len() > 1);
+        
+        let compressed: Vec<u8> = compressed_chunks.concat();
+        let decompressed = service.decompress_data(&compressed).await.unwrap();
+        assert_eq!(decompressed, data);
+    }
+    
     #[test]
     fn test_should_compress() {
         let service = GzipCompressionService::new();
@@ -0,0 +1,92 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use tokio::fs;
+use uuid::Uuid;
+
+use crate::application::ports::contact_photo_ports::{ContactPhoto, ContactPhotoStoragePort};
+use crate::common::errors::DomainError;
+use crate::domain::services::vcard_photo_service;
+
+/// Almacén de fotos de contactos: un fichero por contacto dentro de un
+/// directorio del almacenamiento. El tipo de la imagen se deduce de su
+/// cabecera al leerla, así que no hace falta guardarlo aparte.
+pub struct ContactPhotoStore {
+    root: PathBuf,
+}
+
+impl ContactPhotoStore {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    /// Ruta de la foto; el id tiene que ser un UUID para no salir del directorio
+    fn photo_path(&self, contact_id: &str) -> Result<PathBuf, DomainError> {
+        let id = Uuid::parse_str(contact_id)
+            .map_err(|_| DomainError::validation_error(format!("Invalid contact ID: {}", contact_id)))?;
+        Ok(self.root.join(id.to_string()))
+    }
+}
+
+#[async_trait]
+impl ContactPhotoStoragePort for ContactPhotoStore {
+    async fn store_photo(&self, contact_id: &str, photo: &ContactPhoto) -> Result<(), DomainError> {
+        let path = self.photo_path(contact_id)?;
+        fs::create_dir_all(&self.root)
+            .await
+            .map_err(|e| DomainError::internal_error("ContactPhoto", format!("Failed to create photo directory: {}", e)))?;
+
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, &photo.content)
+            .await
+            .map_err(|e| DomainError::internal_error("ContactPhoto", format!("Failed to write photo: {}", e)))?;
+        fs::rename(&temp_path, &path)
+            .await
+            .map_err(|e| DomainError::internal_error("ContactPhoto", format!("Failed to write photo: {}", e)))
+    }
+
+    async fn get_photo(&self, contact_id: &str) -> Result<Option<ContactPhoto>, DomainError> {
+        let path = self.photo_path(contact_id)?;
+        match fs::read(&path).await {
+            Ok(content) => {
+                let media_type = vcard_photo_service::sniff_image_type(&content)
+                    .unwrap_or("application/octet-stream")
+                    .to_string();
+                Ok(Some(ContactPhoto { content, media_type }))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(DomainError::internal_error("ContactPhoto", format!("Failed to read photo: {}", e))),
+        }
+    }
+
+    async fn delete_photo(&self, contact_id: &str) -> Result<(), DomainError> {
+        let path = self.photo_path(contact_id)?;
+        match fs::remove_file(&path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(DomainError::internal_error("ContactPhoto", format!("Failed to delete photo: {}", e))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_photos_are_stored_per_contact() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = ContactPhotoStore::new(temp.path().join("photos"));
+        let id = Uuid::new_v4().to_string();
+        let photo = ContactPhoto { content: vec![0x89, b'P', b'N', b'G', 0], media_type: "image/png".to_string() };
+
+        assert_eq!(store.get_photo(&id).await.unwrap(), None);
+        store.store_photo(&id, &photo).await.unwrap();
+        assert_eq!(store.get_photo(&id).await.unwrap(), Some(photo));
+        store.delete_photo(&id).await.unwrap();
+        store.delete_photo(&id).await.unwrap();
+        assert_eq!(store.get_photo(&id).await.unwrap(), None);
+
+        assert!(store.get_photo("../secret").await.is_err());
+    }
+}
@@ -0,0 +1,61 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+
+use crate::application::ports::content_hash_ports::{ContentHash, ContentHashPort};
+use crate::common::errors::DomainError;
+use crate::infrastructure::services::persisted_map::PersistedMap;
+
+/// Servicio que guarda el SHA-256 del contenido de los ficheros en un
+/// fichero JSON junto al almacenamiento
+pub struct ContentHashService {
+    hashes: PersistedMap<ContentHash>,
+}
+
+impl ContentHashService {
+    /// Crea el servicio cargando los hashes existentes
+    pub async fn new(store_path: PathBuf) -> Result<Self, DomainError> {
+        let hashes: PersistedMap<ContentHash> = PersistedMap::load("ContentHash", store_path).await?;
+        tracing::info!("Loaded content hashes for {} files", hashes.read().await.len());
+        Ok(Self { hashes })
+    }
+}
+
+#[async_trait]
+impl ContentHashPort for ContentHashService {
+    async fn get_hash(&self, file_id: &str) -> Result<Option<ContentHash>, DomainError> {
+        Ok(self.hashes.read().await.get(file_id).cloned())
+    }
+
+    async fn store_hash(&self, file_id: &str, hash: ContentHash) -> Result<(), DomainError> {
+        self.hashes.update(|hashes| hashes.insert(file_id.to_string(), hash.clone()).as_ref() != Some(&hash)).await;
+        Ok(())
+    }
+
+    async fn remove_hash(&self, file_id: &str) -> Result<(), DomainError> {
+        self.hashes.remove(file_id).await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_hashes_are_persisted() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = temp.path().join("hashes.json");
+        let hash = ContentHash { sha256: "ab".repeat(32), size: 5, modified_at: 1_700_000_000 };
+
+        let service = ContentHashService::new(store.clone()).await.unwrap();
+        service.store_hash("f1", hash.clone()).await.unwrap();
+        service.store_hash("f2", hash.clone()).await.unwrap();
+        service.remove_hash("f2").await.unwrap();
+        service.hashes.flush().await.unwrap();
+
+        let reloaded = ContentHashService::new(store).await.unwrap();
+        assert_eq!(reloaded.get_hash("f1").await.unwrap(), Some(hash));
+        assert_eq!(reloaded.get_hash("f2").await.unwrap(), None);
+    }
+}
@@ -0,0 +1,450 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::{Cursor, Write};
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use zip::{ZipWriter, write::SimpleFileOptions};
+
+use crate::application::dtos::dav_capture_dto::DavCaptureSessionDto;
+use crate::application::ports::dav_capture_ports::{CapturedBody, CapturedExchange, DavCaptureUseCase};
+use crate::common::errors::DomainError;
+
+/// Valor que sustituye a las credenciales en la captura
+const REDACTED: &str = "[REDACTED]";
+
+/// Cabeceras que nunca se escriben en claro
+const SENSITIVE_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+    "x-auth-token",
+    "x-api-key",
+];
+
+/// Parámetros de consulta que llevan credenciales
+const SENSITIVE_QUERY_PARAMS: &[&str] = &["token", "access_token", "refresh_token", "password", "api_key", "key"];
+
+/// Límites de una captura
+#[derive(Debug, Clone, Copy)]
+pub struct DavCaptureLimits {
+    /// Duración máxima de la ventana de captura
+    pub max_minutes: u32,
+    /// Bytes guardados de cada cuerpo
+    pub max_body_bytes: usize,
+    /// Bytes máximos del registro de un usuario
+    pub max_capture_bytes: u64,
+}
+
+/// Devuelve el valor de la cabecera tal cual o redactado si lleva credenciales.
+///
+/// Para `Authorization` se conserva el esquema (`Basic`, `Bearer`), útil para
+/// saber cómo se autentica el cliente.
+pub fn redact_header(name: &str, value: &str) -> String {
+    let name = name.to_ascii_lowercase();
+    if !SENSITIVE_HEADERS.contains(&name.as_str()) {
+        return value.to_string();
+    }
+    match value.split_once(' ') {
+        Some((scheme, _)) if name.ends_with("authorization") => format!("{} {}", scheme, REDACTED),
+        _ => REDACTED.to_string(),
+    }
+}
+
+/// Redacta los parámetros de consulta que llevan credenciales
+pub fn redact_uri(uri: &str) -> String {
+    let Some((path, query)) = uri.split_once('?') else { return uri.to_string() };
+    let query = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((key, _)) if SENSITIVE_QUERY_PARAMS.contains(&key.to_ascii_lowercase().as_str()) => {
+                format!("{}={}", key, REDACTED)
+            }
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+    format!("{}?{}", path, query)
+}
+
+/// Los nombres de usuario se usan como nombre de fichero del registro
+fn validate_username(username: &str) -> Result<(), DomainError> {
+    let valid = !username.is_empty()
+        && username.len() <= 64
+        && !username.starts_with('.')
+        && username.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '@'));
+    if valid {
+        Ok(())
+    } else {
+        Err(DomainError::validation_error(format!("Invalid username '{}'", username)))
+    }
+}
+
+fn write_body(out: &mut String, prefix: &str, body: &CapturedBody) {
+    if body.content.is_empty() {
+        return;
+    }
+    match std::str::from_utf8(&body.content) {
+        Ok(text) => {
+            for line in text.lines() {
+                let _ = writeln!(out, "{} {}", prefix, line);
+            }
+        }
+        Err(_) => {
+            let _ = writeln!(out, "{} [{} bytes of binary data]", prefix, body.content.len());
+        }
+    }
+    match body.total_len {
+        Some(total) if total > body.content.len() as u64 => {
+            let _ = writeln!(out, "{} [truncated, {} bytes in total]", prefix, total);
+        }
+        None => {
+            let _ = writeln!(out, "{} [truncated]", prefix);
+        }
+        _ => {}
+    }
+}
+
+/// Da formato de texto a un intercambio, con las credenciales ya redactadas
+fn format_exchange(number: u64, exchange: &CapturedExchange) -> String {
+    let uri = redact_uri(&exchange.uri);
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "=== #{} {} {} {} -> {} ({} ms)",
+        number,
+        exchange.started_at.to_rfc3339(),
+        exchange.method,
+        uri,
+        exchange.status,
+        exchange.duration_ms
+    );
+
+    let _ = writeln!(out, "> {} {}", exchange.method, uri);
+    for (name, value) in &exchange.request_headers {
+        let _ = writeln!(out, "> {}: {}", name, redact_header(name, value));
+    }
+    let _ = writeln!(out, ">");
+    write_body(&mut out, ">", &exchange.request_body);
+
+    let _ = writeln!(out, "< {}", exchange.status);
+    for (name, value) in &exchange.response_headers {
+        let _ = writeln!(out, "< {}: {}", name, redact_header(name, value));
+    }
+    let _ = writeln!(out, "<");
+    match &exchange.response_body {
+        Some(body) => write_body(&mut out, "<", body),
+        None => {
+            let _ = writeln!(out, "< [streamed body not captured]");
+        }
+    }
+    out.push('\n');
+    out
+}
+
+/// Servicio que graba el tráfico DAV de los usuarios indicados por un
+/// administrador durante una ventana limitada, para generar paquetes de
+/// diagnóstico.
+///
+/// El estado de las capturas vive en memoria; al reiniciar el servidor
+/// todas las capturas terminan.
+pub struct DavCaptureService {
+    capture_dir: PathBuf,
+    limits: DavCaptureLimits,
+    sessions: RwLock<HashMap<String, DavCaptureSessionDto>>,
+    write_mutex: Mutex<()>,
+}
+
+impl DavCaptureService {
+    /// Crea el servicio guardando los registros en `capture_dir`
+    pub fn new(capture_dir: PathBuf, limits: DavCaptureLimits) -> Self {
+        Self {
+            capture_dir,
+            limits,
+            sessions: RwLock::new(HashMap::new()),
+            write_mutex: Mutex::new(()),
+        }
+    }
+
+    fn log_path(&self, username: &str) -> PathBuf {
+        self.capture_dir.join(format!("{}.log", username))
+    }
+
+    fn is_active(&self, session: &DavCaptureSessionDto) -> bool {
+        session.stopped_at.is_none()
+            && Utc::now() < session.expires_at
+            && session.captured_bytes < self.limits.max_capture_bytes
+    }
+
+    fn snapshot(&self, session: &DavCaptureSessionDto) -> DavCaptureSessionDto {
+        DavCaptureSessionDto { active: self.is_active(session), ..session.clone() }
+    }
+
+    fn session(&self, username: &str) -> Result<DavCaptureSessionDto, DomainError> {
+        self.sessions
+            .read()
+            .map_err(|_| DomainError::internal_error("DavCapture", "Capture registry poisoned"))?
+            .get(username)
+            .map(|session| self.snapshot(session))
+            .ok_or_else(|| DomainError::not_found("DavCapture", username))
+    }
+}
+
+#[async_trait]
+impl DavCaptureUseCase for DavCaptureService {
+    async fn start_capture(
+        &self,
+        username: &str,
+        minutes: Option<u32>,
+        started_by: &str,
+    ) -> Result<DavCaptureSessionDto, DomainError> {
+        validate_username(username)?;
+        let minutes = minutes.unwrap_or(self.limits.max_minutes);
+        if minutes == 0 || minutes > self.limits.max_minutes {
+            return Err(DomainError::validation_error(format!(
+                "Capture length must be between 1 and {} minutes",
+                self.limits.max_minutes
+            )));
+        }
+
+        let _guard = self.write_mutex.lock().await;
+        fs::create_dir_all(&self.capture_dir)
+            .await
+            .map_err(|e| DomainError::internal_error("DavCapture", format!("Failed to create capture directory: {}", e)))?;
+        fs::write(self.log_path(username), b"")
+            .await
+            .map_err(|e| DomainError::internal_error("DavCapture", format!("Failed to create capture log: {}", e)))?;
+
+        let now = Utc::now();
+        let session = DavCaptureSessionDto {
+            username: username.to_string(),
+            started_by: started_by.to_string(),
+            started_at: now,
+            expires_at: now + Duration::minutes(minutes as i64),
+            stopped_at: None,
+            exchanges: 0,
+            captured_bytes: 0,
+            active: true,
+        };
+        self.sessions
+            .write()
+            .map_err(|_| DomainError::internal_error("DavCapture", "Capture registry poisoned"))?
+            .insert(username.to_string(), session.clone());
+
+        tracing::info!("DAV capture started for '{}' by '{}' ({} minutes)", username, started_by, minutes);
+        Ok(session)
+    }
+
+    async fn stop_capture(&self, username: &str) -> Result<DavCaptureSessionDto, DomainError> {
+        {
+            let mut sessions = self.sessions
+                .write()
+                .map_err(|_| DomainError::internal_error("DavCapture", "Capture registry poisoned"))?;
+            let session = sessions.get_mut(username).ok_or_else(|| DomainError::not_found("DavCapture", username))?;
+            if session.stopped_at.is_none() {
+                session.stopped_at = Some(Utc::now());
+            }
+        }
+        tracing::info!("DAV capture stopped for '{}'", username);
+        self.session(username)
+    }
+
+    async fn list_captures(&self) -> Result<Vec<DavCaptureSessionDto>, DomainError> {
+        let sessions = self.sessions
+            .read()
+            .map_err(|_| DomainError::internal_error("DavCapture", "Capture registry poisoned"))?;
+        let mut captures: Vec<_> = sessions.values().map(|session| self.snapshot(session)).collect();
+        captures.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        Ok(captures)
+    }
+
+    async fn discard_capture(&self, username: &str) -> Result<(), DomainError> {
+        let _guard = self.write_mutex.lock().await;
+        self.sessions
+            .write()
+            .map_err(|_| DomainError::internal_error("DavCapture", "Capture registry poisoned"))?
+            .remove(username)
+            .ok_or_else(|| DomainError::not_found("DavCapture", username))?;
+
+        match fs::remove_file(self.log_path(username)).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(DomainError::internal_error("DavCapture", format!("Failed to delete capture log: {}", e))),
+        }
+    }
+
+    async fn export_bundle(&self, username: &str) -> Result<Vec<u8>, DomainError> {
+        let session = self.session(username)?;
+        let log = {
+            let _guard = self.write_mutex.lock().await;
+            match fs::read(self.log_path(username)).await {
+                Ok(log) => log,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+                Err(e) => {
+                    return Err(DomainError::internal_error("DavCapture", format!("Failed to read capture log: {}", e)))
+                }
+            }
+        };
+
+        let session_json = serde_json::to_vec_pretty(&session)
+            .map_err(|e| DomainError::internal_error("DavCapture", format!("Failed to serialize capture: {}", e)))?;
+        let zip_error = |e: &dyn std::fmt::Display| {
+            DomainError::internal_error("DavCapture", format!("Failed to build diagnostics bundle: {}", e))
+        };
+
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        for (name, content) in [("capture.json", &session_json), ("exchanges.log", &log)] {
+            zip.start_file(name, options).map_err(|e| zip_error(&e))?;
+            zip.write_all(content).map_err(|e| zip_error(&e))?;
+        }
+        Ok(zip.finish().map_err(|e| zip_error(&e))?.into_inner())
+    }
+
+    fn is_capturing(&self, username: &str) -> bool {
+        self.sessions
+            .read()
+            .ok()
+            .and_then(|sessions| sessions.get(username).map(|session| self.is_active(session)))
+            .unwrap_or(false)
+    }
+
+    fn max_body_bytes(&self) -> usize {
+        self.limits.max_body_bytes
+    }
+
+    async fn record_exchange(&self, username: &str, exchange: CapturedExchange) -> Result<(), DomainError> {
+        let _guard = self.write_mutex.lock().await;
+        let number = {
+            let sessions = self.sessions
+                .read()
+                .map_err(|_| DomainError::internal_error("DavCapture", "Capture registry poisoned"))?;
+            match sessions.get(username) {
+                Some(session) if self.is_active(session) => session.exchanges + 1,
+                _ => return Ok(()),
+            }
+        };
+
+        let entry = format_exchange(number, &exchange);
+        let mut log = fs::OpenOptions::new()
+            .append(true)
+            .open(self.log_path(username))
+            .await
+            .map_err(|e| DomainError::internal_error("DavCapture", format!("Failed to open capture log: {}", e)))?;
+        log.write_all(entry.as_bytes())
+            .await
+            .map_err(|e| DomainError::internal_error("DavCapture", format!("Failed to write capture log: {}", e)))?;
+
+        if let Ok(mut sessions) = self.sessions.write() {
+            if let Some(session) = sessions.get_mut(username) {
+                session.exchanges = number;
+                session.captured_bytes += entry.len() as u64;
+                if session.captured_bytes >= self.limits.max_capture_bytes {
+                    tracing::warn!("DAV capture for '{}' reached its size limit", username);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> DavCaptureLimits {
+        DavCaptureLimits { max_minutes: 30, max_body_bytes: 1024, max_capture_bytes: 1024 * 1024 }
+    }
+
+    fn exchange(body: &str) -> CapturedExchange {
+        CapturedExchange {
+            started_at: Utc::now(),
+            duration_ms: 3,
+            method: "PROPFIND".to_string(),
+            uri: "/webdav/docs/?token=abc&depth=1".to_string(),
+            request_headers: vec![
+                ("authorization".to_string(), "Basic dXNlcjpwYXNz".to_string()),
+                ("cookie".to_string(), "session=secret".to_string()),
+                ("depth".to_string(), "1".to_string()),
+            ],
+            request_body: CapturedBody { content: body.as_bytes().to_vec(), total_len: Some(body.len() as u64) },
+            status: 207,
+            response_headers: vec![("content-type".to_string(), "application/xml".to_string())],
+            response_body: Some(CapturedBody { content: b"<multistatus/>".to_vec(), total_len: None }),
+        }
+    }
+
+    #[test]
+    fn test_redaction() {
+        assert_eq!(redact_header("Authorization", "Bearer eyJhbGci"), "Bearer [REDACTED]");
+        assert_eq!(redact_header("Cookie", "a=b; c=d"), "[REDACTED]");
+        assert_eq!(redact_header("Depth", "infinity"), "infinity");
+        assert_eq!(redact_uri("/webdav/a?Token=x&depth=1"), "/webdav/a?Token=[REDACTED]&depth=1");
+        assert_eq!(redact_uri("/webdav/a"), "/webdav/a");
+    }
+
+    #[test]
+    fn test_format_exchange_hides_credentials() {
+        let entry = format_exchange(1, &exchange("<propfind/>"));
+        assert!(entry.contains("> authorization: Basic [REDACTED]"));
+        assert!(!entry.contains("dXNlcjpwYXNz"));
+        assert!(!entry.contains("secret"));
+        assert!(!entry.contains("token=abc"));
+        assert!(entry.contains("> <propfind/>"));
+        assert!(entry.contains("< <multistatus/>"));
+        assert!(entry.contains("< [truncated]"));
+    }
+
+    #[tokio::test]
+    async fn test_capture_lifecycle() {
+        let temp = tempfile::tempdir().unwrap();
+        let service = DavCaptureService::new(temp.path().join("captures"), limits());
+
+        assert!(service.start_capture("alice", Some(31), "admin").await.is_err());
+        assert!(service.start_capture("../etc", Some(5), "admin").await.is_err());
+
+        service.start_capture("alice", Some(5), "admin").await.unwrap();
+        assert!(service.is_capturing("alice"));
+        assert!(!service.is_capturing("bob"));
+
+        service.record_exchange("alice", exchange("<propfind/>")).await.unwrap();
+        service.record_exchange("bob", exchange("<propfind/>")).await.unwrap();
+
+        let stopped = service.stop_capture("alice").await.unwrap();
+        assert!(!stopped.active);
+        assert_eq!(stopped.exchanges, 1);
+        assert!(!service.is_capturing("alice"));
+
+        // Nothing is recorded once the capture is stopped
+        service.record_exchange("alice", exchange("<late/>")).await.unwrap();
+        let bundle = service.export_bundle("alice").await.unwrap();
+        let mut archive = zip::ZipArchive::new(Cursor::new(bundle)).unwrap();
+        let mut log = String::new();
+        std::io::Read::read_to_string(&mut archive.by_name("exchanges.log").unwrap(), &mut log).unwrap();
+        assert!(log.contains("<propfind/>"));
+        assert!(!log.contains("<late/>"));
+        assert!(archive.by_name("capture.json").is_ok());
+
+        service.discard_capture("alice").await.unwrap();
+        assert!(service.export_bundle("alice").await.is_err());
+        assert!(service.list_captures().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_capture_stops_at_size_limit() {
+        let temp = tempfile::tempdir().unwrap();
+        let service = DavCaptureService::new(
+            temp.path().to_path_buf(),
+            DavCaptureLimits { max_capture_bytes: 10, ..limits() },
+        );
+        service.start_capture("alice", None, "admin").await.unwrap();
+        service.record_exchange("alice", exchange("<propfind/>")).await.unwrap();
+        assert!(!service.is_capturing("alice"));
+    }
+}
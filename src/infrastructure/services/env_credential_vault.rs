@@ -0,0 +1,75 @@
+use std::env;
+use std::path::PathBuf;
+use async_trait::async_trait;
+
+use crate::application::ports::credential_ports::{CredentialVaultPort, StoredCredential};
+use crate::common::errors::DomainError;
+
+/// Credential vault backed by environment variables.
+///
+/// A credential stored under the key `legacy` is read from
+/// `OXICLOUD_VAULT_LEGACY_USERNAME`, `OXICLOUD_VAULT_LEGACY_PASSWORD`
+/// and `OXICLOUD_VAULT_LEGACY_KEY_PATH`.
+pub struct EnvCredentialVault {
+    prefix: String,
+}
+
+impl EnvCredentialVault {
+    /// Creates a vault that reads `OXICLOUD_VAULT_*` variables
+    pub fn new() -> Self {
+        Self::with_prefix("OXICLOUD_VAULT")
+    }
+
+    /// Creates a vault with a custom variable prefix
+    pub fn with_prefix(prefix: &str) -> Self {
+        Self { prefix: prefix.to_string() }
+    }
+
+    fn variable_name(&self, key: &str, field: &str) -> String {
+        let normalized: String = key
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+            .collect();
+        format!("{}_{}_{}", self.prefix, normalized, field)
+    }
+}
+
+impl Default for EnvCredentialVault {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl CredentialVaultPort for EnvCredentialVault {
+    async fn get_credential(&self, key: &str) -> Result<StoredCredential, DomainError> {
+        let credential = StoredCredential {
+            username: env::var(self.variable_name(key, "USERNAME")).ok(),
+            password: env::var(self.variable_name(key, "PASSWORD")).ok(),
+            private_key_path: env::var(self.variable_name(key, "KEY_PATH")).ok().map(PathBuf::from),
+        };
+
+        if credential.username.is_none() && credential.password.is_none() && credential.private_key_path.is_none() {
+            return Err(DomainError::not_found("Credential", key));
+        }
+
+        Ok(credential)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variable_name_normalizes_key() {
+        let vault = EnvCredentialVault::new();
+        assert_eq!(vault.variable_name("legacy-server.1", "USERNAME"), "OXICLOUD_VAULT_LEGACY_SERVER_1_USERNAME");
+    }
+
+    #[tokio::test]
+    async fn test_missing_credential_is_not_found() {
+        let vault = EnvCredentialVault::with_prefix("OXICLOUD_TEST_VAULT_MISSING");
+        assert!(vault.get_credential("nothing").await.is_err());
+    }
+}
@@ -0,0 +1,41 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time;
+use tracing::{debug, info};
+
+use crate::application::services::external_storage_service::ExternalStorageService;
+
+/// Servicio que detecta cambios en montajes externos comparando listados periódicos
+pub struct ExternalChangeWatcher {
+    external_storage_service: Arc<ExternalStorageService>,
+}
+
+impl ExternalChangeWatcher {
+    pub fn new(external_storage_service: Arc<ExternalStorageService>) -> Self {
+        Self { external_storage_service }
+    }
+
+    /// Inicia la detección periódica de cambios para un montaje
+    pub fn start_watch_job(&self, mount: &str, interval_secs: u64) {
+        let service = self.external_storage_service.clone();
+        let mount = mount.to_string();
+        let interval_secs = interval_secs.max(10); // Mínimo 10 segundos
+
+        info!("Iniciando detección de cambios del montaje '{}' cada {} segundos", mount, interval_secs);
+
+        tokio::spawn(async move {
+            let mut interval = time::interval(Duration::from_secs(interval_secs));
+            interval.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+
+            loop {
+                interval.tick().await;
+                // Los errores se registran en el estado del montaje
+                match service.scan_for_changes(&mount).await {
+                    Ok(count) if count > 0 => info!("Detectados {} cambios en el montaje '{}'", count, mount),
+                    Ok(_) => debug!("Sin cambios en el montaje '{}'", mount),
+                    Err(e) => debug!("Fallo al revisar el montaje '{}': {}", mount, e),
+                }
+            }
+        });
+    }
+}
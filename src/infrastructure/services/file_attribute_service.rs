@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+
+use crate::application::ports::file_attribute_ports::{FileAttributePort, FileAttributes};
+use crate::common::errors::DomainError;
+use crate::domain::services::extended_attribute_service::ExtendedAttribute;
+use crate::infrastructure::services::persisted_map::PersistedMap;
+
+/// Servicio que guarda los atributos extendidos de los ficheros (modo POSIX,
+/// metadatos del Finder) en un fichero JSON junto al almacenamiento
+pub struct FileAttributeService {
+    attributes: PersistedMap<FileAttributes>,
+}
+
+impl FileAttributeService {
+    /// Crea el servicio cargando los atributos existentes
+    pub async fn new(store_path: PathBuf) -> Result<Self, DomainError> {
+        let attributes: PersistedMap<FileAttributes> = PersistedMap::load("FileAttributes", store_path).await?;
+        tracing::info!("Loaded extended attributes for {} files", attributes.read().await.len());
+        Ok(Self { attributes })
+    }
+}
+
+#[async_trait]
+impl FileAttributePort for FileAttributeService {
+    async fn get_attributes(&self, file_ids: &[String]) -> Result<HashMap<String, FileAttributes>, DomainError> {
+        let attributes = self.attributes.read().await;
+        Ok(file_ids
+            .iter()
+            .filter_map(|id| attributes.get(id).map(|attrs| (id.clone(), attrs.clone())))
+            .collect())
+    }
+
+    async fn set_attribute(
+        &self,
+        file_id: &str,
+        attribute: ExtendedAttribute,
+        value: &str,
+    ) -> Result<String, DomainError> {
+        let value = attribute.normalize(value)?;
+
+        self.attributes.update(|attributes| {
+            let attrs = attributes.entry(file_id.to_string()).or_default();
+            attrs.insert(attribute.property_name().to_string(), value.clone()).as_ref() != Some(&value)
+        }).await;
+        Ok(value)
+    }
+
+    async fn remove_attribute(&self, file_id: &str, attribute: ExtendedAttribute) -> Result<(), DomainError> {
+        self.attributes.update(|attributes| {
+            let Some(attrs) = attributes.get_mut(file_id) else { return false };
+            let removed = attrs.remove(attribute.property_name()).is_some();
+            if attrs.is_empty() {
+                attributes.remove(file_id);
+            }
+            removed
+        }).await;
+        Ok(())
+    }
+
+    async fn copy_attributes(&self, from_file_id: &str, to_file_id: &str) -> Result<(), DomainError> {
+        self.attributes.update(|attributes| {
+            match attributes.get(from_file_id).cloned() {
+                Some(attrs) => attributes.insert(to_file_id.to_string(), attrs),
+                None => attributes.remove(to_file_id),
+            };
+            true
+        }).await;
+        Ok(())
+    }
+
+    async fn remove_attributes(&self, file_id: &str) -> Result<(), DomainError> {
+        self.attributes.remove(file_id).await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_attributes_are_persisted() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = temp.path().join("attributes.json");
+
+        let service = FileAttributeService::new(store.clone()).await.unwrap();
+        assert_eq!(service.set_attribute("f1", ExtendedAttribute::UnixMode, "755").await.unwrap(), "0755");
+        service.set_attribute("f1", ExtendedAttribute::Executable, "T").await.unwrap();
+        assert!(service.set_attribute("f1", ExtendedAttribute::Executable, "maybe").await.is_err());
+        service.attributes.flush().await.unwrap();
+
+        let reloaded = FileAttributeService::new(store).await.unwrap();
+        let found = reloaded.get_attributes(&["f1".to_string(), "f2".to_string()]).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found["f1"].get("unix-mode").map(String::as_str), Some("0755"));
+        assert_eq!(found["f1"].get("executable").map(String::as_str), Some("T"));
+    }
+
+    #[tokio::test]
+    async fn test_copy_and_remove() {
+        let temp = tempfile::tempdir().unwrap();
+        let service = FileAttributeService::new(temp.path().join("a.json")).await.unwrap();
+        service.set_attribute("f1", ExtendedAttribute::FinderTags, "Red").await.unwrap();
+
+        service.copy_attributes("f1", "f2").await.unwrap();
+        service.remove_attribute("f1", ExtendedAttribute::FinderTags).await.unwrap();
+        service.remove_attributes("f3").await.unwrap();
+
+        let found = service.get_attributes(&["f1".to_string(), "f2".to_string()]).await.unwrap();
+        assert!(!found.contains_key("f1"));
+        assert_eq!(found["f2"].get("finder-tags").map(String::as_str), Some("Red"));
+    }
+}
@@ -147,6 +147,104 @@ impl FileSystemUtils {
         Ok(())
     }
     
+    /// Whether two paths live on the same filesystem, so a rename can move
+    /// them without copying. A destination that does not exist yet is
+    /// checked through its closest existing ancestor.
+    pub async fn is_same_filesystem<P: AsRef<Path>, Q: AsRef<Path>>(a: P, b: Q) -> Result<bool, IoError> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+
+            async fn device_of(path: &Path) -> Result<u64, IoError> {
+                let mut current = Some(path);
+                while let Some(candidate) = current {
+                    match fs::metadata(candidate).await {
+                        Ok(metadata) => return Ok(metadata.dev()),
+                        Err(e) if e.kind() == std::io::ErrorKind::NotFound => current = candidate.parent(),
+                        Err(e) => return Err(e),
+                    }
+                }
+                Err(IoError::new(std::io::ErrorKind::NotFound, format!("No existing ancestor for {}", path.display())))
+            }
+
+            Ok(device_of(a.as_ref()).await? == device_of(b.as_ref()).await?)
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = (a, b);
+            Ok(true)
+        }
+    }
+
+    /// Moves a file or directory. On the same filesystem this is a plain
+    /// rename; across devices the tree is copied, every file size verified,
+    /// and only then the source removed. A failed copy removes the partial
+    /// destination. Returns `true` when the move was a rename.
+    pub async fn move_with_sync<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> Result<bool, IoError> {
+        let from = from.as_ref();
+        let to = to.as_ref();
+
+        if Self::is_same_filesystem(from, to).await? {
+            Self::rename_with_sync(from, to).await?;
+            return Ok(true);
+        }
+
+        warn!("Moving {} across filesystems to {}, copying content", from.display(), to.display());
+        if let Err(e) = Self::copy_tree_verified(from, to).await {
+            let rollback = if fs::metadata(to).await.map(|m| m.is_dir()).unwrap_or(false) {
+                fs::remove_dir_all(to).await
+            } else {
+                fs::remove_file(to).await
+            };
+            if let Err(rollback_error) = rollback {
+                if rollback_error.kind() != std::io::ErrorKind::NotFound {
+                    error!("Failed to roll back partial copy at {}: {}", to.display(), rollback_error);
+                }
+            }
+            return Err(e);
+        }
+
+        if fs::metadata(from).await?.is_dir() {
+            Self::remove_dir_with_sync(from, true).await?;
+        } else {
+            Self::remove_file_with_sync(from).await?;
+        }
+        Ok(false)
+    }
+
+    /// Copies a file or directory tree, checking the size of every copied file
+    async fn copy_tree_verified(from: &Path, to: &Path) -> Result<(), IoError> {
+        let mut pending = vec![(from.to_path_buf(), to.to_path_buf())];
+
+        while let Some((source, target)) = pending.pop() {
+            let metadata = fs::metadata(&source).await?;
+            if metadata.is_dir() {
+                Self::create_dir_with_sync(&target).await?;
+                let mut entries = fs::read_dir(&source).await?;
+                while let Some(entry) = entries.next_entry().await? {
+                    pending.push((entry.path(), target.join(entry.file_name())));
+                }
+                continue;
+            }
+
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            let copied = fs::copy(&source, &target).await?;
+            let written = File::open(&target).await?;
+            written.sync_all().await?;
+            let target_size = written.metadata().await?.len();
+            if copied != metadata.len() || target_size != metadata.len() {
+                return Err(IoError::other(format!(
+                    "Verification failed for {}: expected {} bytes, found {}",
+                    target.display(), metadata.len(), target_size
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Removes a file with directory syncing
     pub async fn remove_file_with_sync<P: AsRef<Path>>(path: P) -> Result<(), IoError> {
         let path = path.as_ref();
@@ -278,4 +376,31 @@ mod tests {
         
         assert_eq!(contents, "Test content");
     }
+
+    #[tokio::test]
+    async fn test_move_with_sync_renames_on_same_filesystem() {
+        let temp_dir = tempdir().unwrap();
+        let source_dir = temp_dir.path().join("folder");
+        FileSystemUtils::write_with_sync(source_dir.join("a.txt"), b"A", false).await.unwrap();
+        let dest_dir = temp_dir.path().join("nested/moved");
+
+        assert!(FileSystemUtils::is_same_filesystem(&source_dir, &dest_dir).await.unwrap());
+        assert!(FileSystemUtils::move_with_sync(&source_dir, &dest_dir).await.unwrap());
+        assert!(!source_dir.exists());
+        assert_eq!(fs::read(dest_dir.join("a.txt")).await.unwrap(), b"A");
+    }
+
+    #[tokio::test]
+    async fn test_copy_tree_verified() {
+        let temp_dir = tempdir().unwrap();
+        let source_dir = temp_dir.path().join("source");
+        FileSystemUtils::write_with_sync(source_dir.join("a.txt"), b"A", false).await.unwrap();
+        FileSystemUtils::write_with_sync(source_dir.join("sub/b.txt"), b"BB", false).await.unwrap();
+        let dest_dir = temp_dir.path().join("dest");
+
+        FileSystemUtils::copy_tree_verified(&source_dir, &dest_dir).await.unwrap();
+        assert_eq!(fs::read(dest_dir.join("a.txt")).await.unwrap(), b"A");
+        assert_eq!(fs::read(dest_dir.join("sub/b.txt")).await.unwrap(), b"BB");
+        assert!(source_dir.join("sub/b.txt").exists());
+    }
 }
\ No newline at end of file
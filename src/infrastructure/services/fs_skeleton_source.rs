@@ -0,0 +1,114 @@
+use std::path::{Path, PathBuf};
+use async_trait::async_trait;
+use tokio::fs;
+
+use crate::application::ports::skeleton_ports::{SkeletonEntry, SkeletonSourcePort};
+use crate::common::errors::DomainError;
+
+/// Fuente de esqueletos basada en el sistema de ficheros.
+///
+/// Cada subdirectorio de la raíz es un esqueleto: el de un grupo lleva el ID
+/// del grupo como nombre y `skeleton/default/` es el de los usuarios cuyos
+/// grupos no tienen esqueleto propio.
+pub struct FsSkeletonSource {
+    root: PathBuf,
+}
+
+impl FsSkeletonSource {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    /// Devuelve el directorio de un esqueleto si existe
+    async fn skeleton_dir(&self, name: &str) -> Option<PathBuf> {
+        let valid_name = !name.is_empty()
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid_name {
+            return None;
+        }
+
+        let dir = self.root.join(name);
+        fs::metadata(&dir).await.map(|m| m.is_dir()).unwrap_or(false).then_some(dir)
+    }
+
+    /// Recorre un directorio en profundidad, carpetas antes que su contenido
+    async fn collect(base: &Path) -> Result<Vec<SkeletonEntry>, DomainError> {
+        let io_error = |e: std::io::Error| {
+            DomainError::internal_error("Skeleton", format!("Error leyendo el esqueleto: {}", e))
+        };
+
+        let mut entries = Vec::new();
+        let mut pending = vec![(base.to_path_buf(), String::new())];
+
+        while let Some((dir, prefix)) = pending.pop() {
+            let mut reader = fs::read_dir(&dir).await.map_err(io_error)?;
+            let mut children = Vec::new();
+            while let Some(item) = reader.next_entry().await.map_err(io_error)? {
+                children.push(item);
+            }
+            // Orden estable para que la aplicación sea determinista
+            children.sort_by_key(|item| item.file_name());
+
+            for item in children {
+                let name = item.file_name().to_string_lossy().to_string();
+                let relative_path = if prefix.is_empty() { name.clone() } else { format!("{}/{}", prefix, name) };
+                let file_type = item.file_type().await.map_err(io_error)?;
+
+                if file_type.is_dir() {
+                    entries.push(SkeletonEntry { relative_path: relative_path.clone(), is_dir: true, content: None });
+                    pending.push((item.path(), relative_path));
+                } else if file_type.is_file() {
+                    let content = fs::read(item.path()).await.map_err(io_error)?;
+                    entries.push(SkeletonEntry { relative_path, is_dir: false, content: Some(content) });
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+}
+
+#[async_trait]
+impl SkeletonSourcePort for FsSkeletonSource {
+    async fn load_skeleton(&self, name: &str) -> Result<Option<Vec<SkeletonEntry>>, DomainError> {
+        match self.skeleton_dir(name).await {
+            Some(dir) => Self::collect(&dir).await.map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_loads_skeletons_by_name() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(temp.path().join("default/Documents")).unwrap();
+        std::fs::write(temp.path().join("default/Documents/Welcome.txt"), b"hi").unwrap();
+        let group_id = "7f3c2a1e-5b4d-4c6e-8f9a-0b1c2d3e4f5a";
+        std::fs::create_dir_all(temp.path().join(group_id).join("Reports")).unwrap();
+
+        let source = FsSkeletonSource::new(temp.path().to_path_buf());
+
+        let group = source.load_skeleton(group_id).await.unwrap().unwrap();
+        assert_eq!(group.len(), 1);
+        assert_eq!(group[0].relative_path, "Reports");
+
+        let default = source.load_skeleton("default").await.unwrap().unwrap();
+        assert_eq!(default[0], SkeletonEntry { relative_path: "Documents".to_string(), is_dir: true, content: None });
+        assert_eq!(default[1].relative_path, "Documents/Welcome.txt");
+        assert_eq!(default[1].content.as_deref(), Some(&b"hi"[..]));
+
+        assert!(source.load_skeleton("other-group").await.unwrap().is_none());
+        // Names cannot escape the skeleton root
+        assert!(source.load_skeleton("../default").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_missing_root_yields_no_skeleton() {
+        let source = FsSkeletonSource::new(PathBuf::from("/nonexistent/oxicloud-skeleton"));
+        assert!(source.load_skeleton("default").await.unwrap().is_none());
+    }
+}
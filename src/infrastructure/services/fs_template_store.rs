@@ -0,0 +1,130 @@
+use std::path::PathBuf;
+use async_trait::async_trait;
+use tokio::fs;
+
+use crate::application::ports::template_ports::{StoredTemplate, TemplateStorePort};
+use crate::common::errors::DomainError;
+
+/// Tamaño máximo de una plantilla
+const MAX_TEMPLATE_SIZE: usize = 50 * 1024 * 1024;
+
+/// Almacén de plantillas basado en el sistema de ficheros.
+///
+/// Cada fichero de la raíz (por ejemplo `templates/Informe.odt`) es una
+/// plantilla; los subdirectorios y los ficheros ocultos se ignoran.
+pub struct FsTemplateStore {
+    root: PathBuf,
+}
+
+impl FsTemplateStore {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    /// Ruta de una plantilla; rechaza los nombres que saldrían de la raíz
+    fn template_path(&self, name: &str) -> Result<PathBuf, DomainError> {
+        let valid = !name.trim().is_empty()
+            && !name.starts_with('.')
+            && !name.contains(['/', '\\', '\0'])
+            && name.chars().count() <= 255;
+        if !valid {
+            return Err(DomainError::validation_error(format!("Invalid template name: {}", name)));
+        }
+        Ok(self.root.join(name))
+    }
+
+    fn io_error(e: std::io::Error) -> DomainError {
+        DomainError::internal_error("Template", format!("Error en el almacén de plantillas: {}", e))
+    }
+}
+
+#[async_trait]
+impl TemplateStorePort for FsTemplateStore {
+    async fn list_templates(&self) -> Result<Vec<StoredTemplate>, DomainError> {
+        let mut reader = match fs::read_dir(&self.root).await {
+            Ok(reader) => reader,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(Self::io_error(e)),
+        };
+
+        let mut templates = Vec::new();
+        while let Some(item) = reader.next_entry().await.map_err(Self::io_error)? {
+            let name = item.file_name().to_string_lossy().to_string();
+            let metadata = item.metadata().await.map_err(Self::io_error)?;
+            if metadata.is_file() && !name.starts_with('.') {
+                templates.push(StoredTemplate { name, size: metadata.len() });
+            }
+        }
+        templates.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        Ok(templates)
+    }
+
+    async fn read_template(&self, name: &str) -> Result<Vec<u8>, DomainError> {
+        let path = self.template_path(name)?;
+        match fs::read(&path).await {
+            Ok(content) => Ok(content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(DomainError::not_found("Template", name)),
+            Err(e) => Err(Self::io_error(e)),
+        }
+    }
+
+    async fn save_template(&self, name: &str, content: &[u8]) -> Result<StoredTemplate, DomainError> {
+        let path = self.template_path(name)?;
+        if content.len() > MAX_TEMPLATE_SIZE {
+            return Err(DomainError::validation_error("Templates cannot be larger than 50 MB"));
+        }
+
+        fs::create_dir_all(&self.root).await.map_err(Self::io_error)?;
+        // Se escribe aparte y se renombra para no servir nunca una plantilla a medias
+        let temp_path = self.root.join(format!(".{}.tmp", uuid::Uuid::new_v4()));
+        fs::write(&temp_path, content).await.map_err(Self::io_error)?;
+        if let Err(e) = fs::rename(&temp_path, &path).await {
+            let _ = fs::remove_file(&temp_path).await;
+            return Err(Self::io_error(e));
+        }
+
+        Ok(StoredTemplate { name: name.to_string(), size: content.len() as u64 })
+    }
+
+    async fn delete_template(&self, name: &str) -> Result<bool, DomainError> {
+        let path = self.template_path(name)?;
+        match fs::remove_file(&path).await {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(Self::io_error(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_save_list_and_delete_templates() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = FsTemplateStore::new(temp.path().join("templates"));
+        assert!(store.list_templates().await.unwrap().is_empty());
+
+        store.save_template("Report.md", b"# {{title}}").await.unwrap();
+        store.save_template("agenda.txt", b"{{date}}").await.unwrap();
+        std::fs::create_dir_all(temp.path().join("templates/nested")).unwrap();
+
+        let names: Vec<_> = store.list_templates().await.unwrap().into_iter().map(|t| t.name).collect();
+        assert_eq!(names, vec!["agenda.txt", "Report.md"]);
+        assert_eq!(store.read_template("Report.md").await.unwrap(), b"# {{title}}");
+
+        assert!(store.delete_template("agenda.txt").await.unwrap());
+        assert!(!store.delete_template("agenda.txt").await.unwrap());
+        assert!(store.read_template("agenda.txt").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_names_cannot_escape_the_root() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = FsTemplateStore::new(temp.path().to_path_buf());
+        assert!(store.save_template("../evil.txt", b"x").await.is_err());
+        assert!(store.save_template(".hidden", b"x").await.is_err());
+        assert!(store.read_template("a/b.txt").await.is_err());
+    }
+}
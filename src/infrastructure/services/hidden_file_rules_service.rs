@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+use tokio::fs;
+use tokio::sync::Mutex;
+
+use crate::application::ports::hidden_file_ports::HiddenFileRulesPort;
+use crate::common::errors::DomainError;
+use crate::domain::services::hidden_file_service::HiddenFileRules;
+
+/// Servicio con las reglas de ficheros ocultos de la instancia y las que
+/// elige cada usuario, guardadas en un fichero JSON junto al almacenamiento
+pub struct HiddenFileRulesService {
+    instance_rules: HiddenFileRules,
+    store_path: PathBuf,
+    user_rules: RwLock<HashMap<String, HiddenFileRules>>,
+    save_mutex: Mutex<()>,
+}
+
+impl HiddenFileRulesService {
+    /// Crea el servicio cargando las reglas de usuario existentes
+    pub async fn new(instance_rules: HiddenFileRules, store_path: PathBuf) -> Result<Self, DomainError> {
+        let user_rules: HashMap<String, HiddenFileRules> = match fs::read_to_string(&store_path).await {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                tracing::error!("Ignoring corrupted hidden file rules {}: {}", store_path.display(), e);
+                HashMap::new()
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => {
+                return Err(DomainError::internal_error(
+                    "HiddenFileRules",
+                    format!("Failed to read hidden file rules {}: {}", store_path.display(), e),
+                ))
+            }
+        };
+
+        Ok(Self {
+            instance_rules,
+            store_path,
+            user_rules: RwLock::new(user_rules),
+            save_mutex: Mutex::new(()),
+        })
+    }
+
+    /// Guarda las reglas de usuario escribiendo primero un fichero temporal
+    async fn persist(&self) -> Result<(), DomainError> {
+        let _guard = self.save_mutex.lock().await;
+        let json = {
+            let user_rules = self.user_rules
+                .read()
+                .map_err(|_| DomainError::internal_error("HiddenFileRules", "Rule registry poisoned"))?;
+            serde_json::to_string(&*user_rules)
+                .map_err(|e| DomainError::internal_error("HiddenFileRules", format!("Failed to serialize rules: {}", e)))?
+        };
+
+        let temp_path = self.store_path.with_extension("json.tmp");
+        fs::write(&temp_path, json)
+            .await
+            .map_err(|e| DomainError::internal_error("HiddenFileRules", format!("Failed to write rules: {}", e)))?;
+        fs::rename(&temp_path, &self.store_path)
+            .await
+            .map_err(|e| DomainError::internal_error("HiddenFileRules", format!("Failed to write rules: {}", e)))
+    }
+}
+
+#[async_trait]
+impl HiddenFileRulesPort for HiddenFileRulesService {
+    fn instance_rules(&self) -> HiddenFileRules {
+        self.instance_rules
+    }
+
+    fn user_rules(&self, user_id: &str) -> Option<HiddenFileRules> {
+        self.user_rules.read().ok().and_then(|rules| rules.get(user_id).copied())
+    }
+
+    async fn set_user_rules(&self, user_id: &str, rules: Option<HiddenFileRules>) -> Result<(), DomainError> {
+        {
+            let mut user_rules = self.user_rules
+                .write()
+                .map_err(|_| DomainError::internal_error("HiddenFileRules", "Rule registry poisoned"))?;
+            match rules {
+                Some(rules) => user_rules.insert(user_id.to_string(), rules),
+                None => user_rules.remove(user_id),
+            };
+        }
+        self.persist().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::hidden_file_service::HiddenFilePolicy;
+
+    #[tokio::test]
+    async fn test_user_rules_are_persisted_and_combined() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = temp.path().join("rules.json");
+        let instance = HiddenFileRules { dotfiles: HiddenFilePolicy::Hide, system_files: HiddenFilePolicy::Store };
+
+        let service = HiddenFileRulesService::new(instance, store.clone()).await.unwrap();
+        let user = HiddenFileRules { dotfiles: HiddenFilePolicy::Store, system_files: HiddenFilePolicy::Reject };
+        service.set_user_rules("u1", Some(user)).await.unwrap();
+
+        let reloaded = HiddenFileRulesService::new(instance, store).await.unwrap();
+        assert_eq!(reloaded.user_rules("u1"), Some(user));
+        assert_eq!(
+            reloaded.rules_for_user("u1"),
+            HiddenFileRules { dotfiles: HiddenFilePolicy::Hide, system_files: HiddenFilePolicy::Reject }
+        );
+        assert_eq!(reloaded.rules_for_user("u2"), instance);
+
+        reloaded.set_user_rules("u1", None).await.unwrap();
+        assert_eq!(reloaded.user_rules("u1"), None);
+    }
+}
@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::application::dtos::image_tagging_dto::ImageLabelDto;
+use crate::application::ports::image_tagging_ports::ImageLabelingPort;
+use crate::common::errors::DomainError;
+
+/// Label as returned by the inference service.
+///
+/// `name` and `score` are accepted as aliases since common model servers use them.
+#[derive(Debug, Deserialize)]
+struct RemoteLabel {
+    #[serde(alias = "name")]
+    label: String,
+    #[serde(alias = "score", default)]
+    confidence: f32,
+}
+
+/// Response body: either `{"labels": [...]}` or a bare array of labels
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RemoteResponse {
+    Wrapped { labels: Vec<RemoteLabel> },
+    Bare(Vec<RemoteLabel>),
+}
+
+/// Adapter for an external inference service reached over HTTP.
+///
+/// The image is POSTed as the raw request body with its MIME type as
+/// `Content-Type`; the service answers with the detected labels as JSON.
+pub struct HttpImageLabeler {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl HttpImageLabeler {
+    /// Creates an adapter for the given inference URL
+    pub fn new(url: impl Into<String>, timeout_secs: u64) -> Result<Self, DomainError> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(timeout_secs.max(1)))
+            .build()
+            .map_err(|e| DomainError::internal_error("ImageLabeler", format!("Failed to create HTTP client: {}", e)))?;
+
+        Ok(Self { client, url: url.into() })
+    }
+}
+
+/// Parses the JSON body returned by the inference service
+fn parse_labels(body: &[u8]) -> Result<Vec<ImageLabelDto>, DomainError> {
+    let response: RemoteResponse = serde_json::from_slice(body)
+        .map_err(|e| DomainError::internal_error("ImageLabeler", format!("Invalid inference response: {}", e)))?;
+
+    let labels = match response {
+        RemoteResponse::Wrapped { labels } | RemoteResponse::Bare(labels) => labels,
+    };
+
+    Ok(labels
+        .into_iter()
+        .filter(|l| l.confidence.is_finite())
+        .map(|l| ImageLabelDto { label: l.label, confidence: l.confidence.clamp(0.0, 1.0) })
+        .collect())
+}
+
+#[async_trait]
+impl ImageLabelingPort for HttpImageLabeler {
+    async fn label_image(&self, mime_type: &str, content: Vec<u8>) -> Result<Vec<ImageLabelDto>, DomainError> {
+        let response = self.client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, mime_type)
+            .body(content)
+            .send()
+            .await
+            .map_err(|e| DomainError::unavailable("ImageLabeler", format!("Inference service unreachable: {}", e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(DomainError::unavailable(
+                "ImageLabeler",
+                format!("Inference service returned {}", status),
+            ));
+        }
+
+        let body = response.bytes().await
+            .map_err(|e| DomainError::unavailable("ImageLabeler", format!("Failed to read inference response: {}", e)))?;
+        parse_labels(&body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_wrapped_and_bare_responses() {
+        let wrapped = parse_labels(br#"{"labels": [{"label": "dog", "confidence": 0.9}]}"#).unwrap();
+        assert_eq!(wrapped, vec![ImageLabelDto { label: "dog".to_string(), confidence: 0.9 }]);
+
+        let bare = parse_labels(br#"[{"name": "face", "score": 1.5}]"#).unwrap();
+        assert_eq!(bare, vec![ImageLabelDto { label: "face".to_string(), confidence: 1.0 }]);
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_body() {
+        assert!(parse_labels(b"<html>").is_err());
+    }
+}
@@ -0,0 +1,48 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::application::ports::webhook_ports::WebhookSenderPort;
+use crate::common::errors::DomainError;
+
+/// Adapter that POSTs webhook deliveries over HTTP.
+///
+/// Redirects are not followed, so a delivery only counts when the registered
+/// URL itself answers.
+pub struct HttpWebhookSender {
+    client: reqwest::Client,
+}
+
+impl HttpWebhookSender {
+    /// Creates a sender that gives up on a delivery after `timeout_secs`
+    pub fn new(timeout_secs: u64) -> Result<Self, DomainError> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(timeout_secs.max(1)))
+            .redirect(reqwest::redirect::Policy::none())
+            .user_agent(concat!("OxiCloud-Webhooks/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| DomainError::internal_error("Webhook", format!("Failed to create HTTP client: {}", e)))?;
+
+        Ok(Self { client })
+    }
+}
+
+#[async_trait]
+impl WebhookSenderPort for HttpWebhookSender {
+    async fn send(&self, url: &str, headers: &[(&str, String)], body: &str) -> Result<u16, DomainError> {
+        let mut request = self.client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json");
+        for (name, value) in headers {
+            request = request.header(*name, value);
+        }
+
+        let response = request
+            .body(body.to_string())
+            .send()
+            .await
+            .map_err(|e| DomainError::unavailable("Webhook", format!("Webhook endpoint unreachable: {}", e)))?;
+
+        Ok(response.status().as_u16())
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+
+use crate::application::ports::image_fingerprint_ports::{ImageFingerprint, ImageFingerprintPort};
+use image::imageops::FilterType;
+use image::{DynamicImage, RgbImage};
+
+use crate::common::errors::DomainError;
+use crate::infrastructure::services::image_preview_service::{decode_image, MAX_DECODE_PIXELS};
+use crate::infrastructure::services::persisted_map::PersistedMap;
+
+/// Lado máximo de la miniatura de la que se calculan las huellas
+const PREVIEW_MAX_SIDE: u32 = 32;
+
+/// Calcula el hash de diferencias (dHash) de 64 bits de la imagen.
+///
+/// La imagen se reduce a 9x8 niveles de gris y cada bit indica si un píxel es
+/// más claro que su vecino de la derecha, así que las copias recomprimidas,
+/// redimensionadas o retocadas quedan a poca distancia de Hamming.
+fn perceptual_hash(image: &DynamicImage) -> u64 {
+    let gray = image.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash = (hash << 1) | (gray.get_pixel(x, y)[0] > gray.get_pixel(x + 1, y)[0]) as u64;
+        }
+    }
+    hash
+}
+
+/// Color dominante: la media del grupo más poblado tras reducir cada canal a 3 bits
+fn dominant_color(image: &RgbImage) -> [u8; 3] {
+    let mut buckets: HashMap<u16, (u64, [u64; 3])> = HashMap::new();
+    for p in image.pixels() {
+        let key = ((p[0] >> 5) as u16) << 6 | ((p[1] >> 5) as u16) << 3 | (p[2] >> 5) as u16;
+        let entry = buckets.entry(key).or_insert((0, [0; 3]));
+        entry.0 += 1;
+        for c in 0..3 {
+            entry.1[c] += p[c] as u64;
+        }
+    }
+
+    // Los empates se deshacen por clave para que el resultado sea estable
+    buckets
+        .into_iter()
+        .max_by_key(|(key, (count, _))| (*count, std::cmp::Reverse(*key)))
+        .map(|(_, (count, sum))| [(sum[0] / count) as u8, (sum[1] / count) as u8, (sum[2] / count) as u8])
+        .unwrap_or([0, 0, 0])
+}
+
+/// Servicio que calcula huellas perceptuales (dHash y color dominante) al
+/// subir imágenes y las guarda en un fichero JSON junto al almacenamiento
+pub struct ImageFingerprintService {
+    max_source_bytes: usize,
+    fingerprints: PersistedMap<ImageFingerprint>,
+}
+
+impl ImageFingerprintService {
+    /// Crea el servicio cargando las huellas existentes
+    pub async fn new(store_path: PathBuf, max_source_bytes: usize) -> Result<Self, DomainError> {
+        let fingerprints: PersistedMap<ImageFingerprint> = PersistedMap::load("ImageFingerprint", store_path).await?;
+        tracing::info!("Loaded {} image fingerprints", fingerprints.read().await.len());
+        Ok(Self { max_source_bytes, fingerprints })
+    }
+}
+
+#[async_trait]
+impl ImageFingerprintPort for ImageFingerprintService {
+    async fn index_image(
+        &self,
+        file_id: &str,
+        mime_type: &str,
+        content: &[u8],
+    ) -> Result<Option<ImageFingerprint>, DomainError> {
+        if !mime_type.starts_with("image/") || content.len() > self.max_source_bytes {
+            return Ok(None);
+        }
+
+        // Decoding is CPU bound, keep it off the async workers
+        let mime = mime_type.to_string();
+        let data = content.to_vec();
+        let fingerprint = tokio::task::spawn_blocking(move || {
+            decode_image(&mime, &data, MAX_DECODE_PIXELS).map(|image| {
+                let preview = image.thumbnail(PREVIEW_MAX_SIDE, PREVIEW_MAX_SIDE);
+                ImageFingerprint {
+                    perceptual_hash: perceptual_hash(&preview),
+                    dominant_color: dominant_color(&preview.to_rgb8()),
+                }
+            })
+        })
+        .await
+        .map_err(|e| DomainError::internal_error("ImageFingerprint", format!("Fingerprint task failed: {}", e)))?;
+
+        let Some(fingerprint) = fingerprint else {
+            // Updated content may no longer be a supported image
+            self.fingerprints.remove(file_id).await;
+            return Ok(None);
+        };
+
+        self.fingerprints.insert(file_id, fingerprint).await;
+        Ok(Some(fingerprint))
+    }
+
+    async fn list_fingerprints(&self) -> Result<Vec<(String, ImageFingerprint)>, DomainError> {
+        let fingerprints = self.fingerprints.read().await;
+        Ok(fingerprints.iter().map(|(id, f)| (id.clone(), *f)).collect())
+    }
+
+    async fn remove_fingerprint(&self, file_id: &str) -> Result<(), DomainError> {
+        self.fingerprints.remove(file_id).await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 1x1 grayscale PNG
+    const TINY_PNG: &[u8] = &[
+        0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x48, 0x44, 0x52,
+        0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x00, 0x00, 0x00, 0x00, 0x3A, 0x7E, 0x9B,
+        0x55, 0x00, 0x00, 0x00, 0x0A, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9C, 0x63, 0x60, 0x00, 0x00, 0x00,
+        0x02, 0x00, 0x01, 0x48, 0xAF, 0xA4, 0x71, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4E, 0x44, 0xAE,
+        0x42, 0x60, 0x82,
+    ];
+
+    #[tokio::test]
+    async fn test_fingerprints_are_persisted() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = temp.path().join("fingerprints.json");
+
+        let service = ImageFingerprintService::new(store.clone(), 1024).await.unwrap();
+        let fingerprint = service.index_image("f1", "image/png", TINY_PNG).await.unwrap().unwrap();
+        assert_eq!(fingerprint.dominant_color, [0, 0, 0]);
+        assert!(service.index_image("f2", "application/pdf", b"%PDF").await.unwrap().is_none());
+        service.fingerprints.flush().await.unwrap();
+
+        let reloaded = ImageFingerprintService::new(store, 1024).await.unwrap();
+        assert_eq!(reloaded.list_fingerprints().await.unwrap(), vec![("f1".to_string(), fingerprint)]);
+
+        reloaded.remove_fingerprint("f1").await.unwrap();
+        assert!(reloaded.list_fingerprints().await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_perceptual_hash_is_stable_across_sizes() {
+        // Horizontal gradient, darker to the right
+        let gradient = |width: u32, height: u32| {
+            DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, _| {
+                let v = (255 - x * 255 / width) as u8;
+                image::Rgb([v, v, v])
+            }))
+        };
+
+        let small = perceptual_hash(&gradient(18, 16));
+        let large = perceptual_hash(&gradient(32, 30));
+        assert_eq!(small, u64::MAX);
+        assert!((small ^ large).count_ones() <= 2);
+
+        let flat = DynamicImage::ImageRgb8(RgbImage::from_pixel(4, 4, image::Rgb([90, 90, 90])));
+        assert_eq!(perceptual_hash(&flat), 0);
+    }
+
+    #[test]
+    fn test_dominant_color() {
+        let image = RgbImage::from_fn(4, 4, |_, y| if y < 3 { image::Rgb([10, 20, 200]) } else { image::Rgb([250, 250, 250]) });
+        assert_eq!(dominant_color(&image), [10, 20, 200]);
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+
+use crate::application::ports::placeholder_ports::ImagePlaceholderPort;
+use crate::common::errors::DomainError;
+use crate::infrastructure::services::image_preview_service::{decode_image, MAX_DECODE_PIXELS};
+use crate::infrastructure::services::persisted_map::PersistedMap;
+
+/// Componentes blurhash horizontales y verticales (4x3 es el valor recomendado)
+const COMPONENTS_X: u32 = 4;
+const COMPONENTS_Y: u32 = 3;
+
+/// Lado máximo de la miniatura de la que se calcula el blurhash
+const PREVIEW_MAX_SIDE: u32 = 32;
+
+/// Calcula el blurhash (https://blurha.sh) de una imagen PNG o JPEG, o
+/// `None` si no se puede decodificar
+fn compute_blurhash(mime_type: &str, data: &[u8]) -> Option<String> {
+    let preview = decode_image(mime_type, data, MAX_DECODE_PIXELS)?
+        .thumbnail(PREVIEW_MAX_SIDE, PREVIEW_MAX_SIDE)
+        .to_rgba8();
+    blurhash::encode(COMPONENTS_X, COMPONENTS_Y, preview.width(), preview.height(), preview.as_raw()).ok()
+}
+
+/// Servicio que calcula placeholders blurhash al subir imágenes y los guarda
+/// en un fichero JSON junto al almacenamiento
+pub struct ImagePlaceholderService {
+    max_source_bytes: usize,
+    placeholders: PersistedMap<String>,
+}
+
+impl ImagePlaceholderService {
+    /// Crea el servicio cargando los placeholders existentes
+    pub async fn new(store_path: PathBuf, max_source_bytes: usize) -> Result<Self, DomainError> {
+        let placeholders: PersistedMap<String> = PersistedMap::load("Placeholder", store_path).await?;
+        tracing::info!("Loaded {} image placeholders", placeholders.read().await.len());
+        Ok(Self { max_source_bytes, placeholders })
+    }
+}
+
+#[async_trait]
+impl ImagePlaceholderPort for ImagePlaceholderService {
+    async fn generate_placeholder(
+        &self,
+        file_id: &str,
+        mime_type: &str,
+        content: &[u8],
+    ) -> Result<Option<String>, DomainError> {
+        if !mime_type.starts_with("image/") || content.len() > self.max_source_bytes {
+            return Ok(None);
+        }
+
+        // Decoding is CPU bound, keep it off the async workers
+        let mime = mime_type.to_string();
+        let data = content.to_vec();
+        let hash = tokio::task::spawn_blocking(move || {
+            compute_blurhash(&mime, &data)
+        })
+        .await
+        .map_err(|e| DomainError::internal_error("Placeholder", format!("Placeholder task failed: {}", e)))?;
+
+        let Some(hash) = hash else {
+            // Updated content may no longer be a supported image
+            self.placeholders.remove(file_id).await;
+            return Ok(None);
+        };
+
+        self.placeholders.insert(file_id, hash.clone()).await;
+        Ok(Some(hash))
+    }
+
+    async fn get_placeholders(&self, file_ids: &[String]) -> Result<HashMap<String, String>, DomainError> {
+        let placeholders = self.placeholders.read().await;
+        Ok(file_ids
+            .iter()
+            .filter_map(|id| placeholders.get(id).map(|hash| (id.clone(), hash.clone())))
+            .collect())
+    }
+
+    async fn remove_placeholder(&self, file_id: &str) -> Result<(), DomainError> {
+        self.placeholders.remove(file_id).await;
+        Ok(())
+    }
+
+    async fn list_placeholder_ids(&self) -> Result<Vec<String>, DomainError> {
+        Ok(self.placeholders.read().await.keys().cloned().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 1x1 grayscale PNG
+    const TINY_PNG: &[u8] = &[
+        0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x48, 0x44, 0x52,
+        0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x00, 0x00, 0x00, 0x00, 0x3A, 0x7E, 0x9B,
+        0x55, 0x00, 0x00, 0x00, 0x0A, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9C, 0x63, 0x60, 0x00, 0x00, 0x00,
+        0x02, 0x00, 0x01, 0x48, 0xAF, 0xA4, 0x71, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4E, 0x44, 0xAE,
+        0x42, 0x60, 0x82,
+    ];
+
+    #[tokio::test]
+    async fn test_placeholders_are_persisted() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = temp.path().join("placeholders.json");
+
+        let service = ImagePlaceholderService::new(store.clone(), 1024).await.unwrap();
+        let hash = service.generate_placeholder("f1", "image/png", TINY_PNG).await.unwrap();
+        assert!(hash.is_some());
+        assert!(service.generate_placeholder("f2", "text/plain", b"hello").await.unwrap().is_none());
+        service.placeholders.flush().await.unwrap();
+
+        let reloaded = ImagePlaceholderService::new(store, 1024).await.unwrap();
+        let found = reloaded
+            .get_placeholders(&["f1".to_string(), "f2".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found.get("f1"), hash.as_ref());
+
+        reloaded.remove_placeholder("f1").await.unwrap();
+        assert!(reloaded.get_placeholders(&["f1".to_string()]).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_blurhash_of_solid_color() {
+        let red = image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(8, 8, image::Rgb([255, 0, 0])));
+        let mut png = Vec::new();
+        red.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png).unwrap();
+
+        let hash = compute_blurhash("image/png", &png).unwrap();
+        // 4x3 components, then the pure red DC, 0xFF0000 in base83
+        assert_eq!(hash.len(), 28);
+        assert!(hash.starts_with('L'));
+        assert_eq!(&hash[2..6], "TI:j");
+
+        assert!(compute_blurhash("image/gif", b"GIF89a").is_none());
+        assert!(compute_blurhash("image/png", b"not a png").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_skips_large_sources() {
+        let temp = tempfile::tempdir().unwrap();
+        let service = ImagePlaceholderService::new(temp.path().join("p.json"), 16).await.unwrap();
+        assert!(service.generate_placeholder("f1", "image/png", TINY_PNG).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,355 @@
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use image::codecs::png::{CompressionType, FilterType as PngFilterType, PngEncoder};
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat, ImageReader, Limits};
+use tokio::sync::Semaphore;
+
+use crate::application::ports::image_preview_ports::{
+    ImageFit, ImagePreview, ImagePreviewRequest, ImagePreviewUseCase, PreviewQuality, PREVIEW_MIME_TYPES,
+};
+use crate::application::ports::inbound::FileUseCase;
+use crate::common::errors::DomainError;
+
+/// Tiempo máximo de espera por un hueco de renderizado antes de responder 503
+const RENDER_QUEUE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Memoria máxima que puede reservar el decodificador, contra bombas de descompresión
+const MAX_DECODE_ALLOC: u64 = 256 * 1024 * 1024;
+
+/// Píxeles máximos de las imágenes que se decodifican para placeholders y huellas
+pub const MAX_DECODE_PIXELS: usize = 64 * 1024 * 1024;
+
+/// Formato de las imágenes que sabemos decodificar, según su tipo MIME
+fn image_format(mime_type: &str) -> Option<ImageFormat> {
+    match mime_type {
+        "image/png" => Some(ImageFormat::Png),
+        "image/jpeg" | "image/jpg" | "image/pjpeg" => Some(ImageFormat::Jpeg),
+        _ => None,
+    }
+}
+
+/// Lee las dimensiones de una imagen PNG o JPEG de su cabecera, sin decodificarla
+pub fn image_dimensions(mime_type: &str, data: &[u8]) -> Option<(u32, u32)> {
+    let format = image_format(mime_type)?;
+    ImageReader::with_format(Cursor::new(data), format).into_dimensions().ok()
+}
+
+/// Decodifica una imagen PNG o JPEG.
+///
+/// Las imágenes de más de `max_pixels` píxeles se rechazan antes de
+/// decodificarlas, y el decodificador no reserva más de `MAX_DECODE_ALLOC`.
+/// Devuelve `None` para formatos no soportados o datos corruptos.
+pub fn decode_image(mime_type: &str, data: &[u8], max_pixels: usize) -> Option<DynamicImage> {
+    let format = image_format(mime_type)?;
+    let (width, height) = image_dimensions(mime_type, data)?;
+    if (width as usize).saturating_mul(height as usize) > max_pixels {
+        return None;
+    }
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(width);
+    limits.max_image_height = Some(height);
+    limits.max_alloc = Some(MAX_DECODE_ALLOC);
+    let mut reader = ImageReader::with_format(Cursor::new(data), format);
+    reader.limits(limits);
+    reader.decode().ok()
+}
+
+/// Codifica una imagen como PNG; `fast` prima la velocidad sobre la compresión
+pub fn encode_png(image: &DynamicImage, fast: bool) -> Vec<u8> {
+    let compression = if fast { CompressionType::Fast } else { CompressionType::Default };
+    let mut png = Vec::new();
+    let encoder = PngEncoder::new_with_quality(&mut png, compression, PngFilterType::Adaptive);
+    if let Err(e) = image.write_with_encoder(encoder) {
+        tracing::warn!("Could not encode a PNG preview: {}", e);
+    }
+    png
+}
+
+/// Límites que protegen al servidor de peticiones abusivas
+#[derive(Debug, Clone, Copy)]
+pub struct ImagePreviewLimits {
+    /// Lado máximo de la vista previa solicitada
+    pub max_dimension: u32,
+    /// Tamaño máximo del fichero de origen
+    pub max_source_bytes: u64,
+    /// Número máximo de píxeles del fichero de origen
+    pub max_source_pixels: usize,
+    /// Renderizados simultáneos
+    pub max_concurrent: usize,
+    /// Memoria máxima de la caché de vistas previas
+    pub cache_bytes: usize,
+}
+
+/// Recorte en coordenadas de origen y tamaño final de una vista previa
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PreviewPlan {
+    crop: (usize, usize, usize, usize),
+    output: (usize, usize),
+}
+
+/// Calcula el recorte y el tamaño final; nunca amplía la imagen original
+fn plan_preview(source: (usize, usize), request: &ImagePreviewRequest, max_dimension: u32) -> PreviewPlan {
+    let (sw, sh) = (source.0 as f64, source.1 as f64);
+    let full = (0, 0, source.0, source.1);
+    let fit = |scale: f64| {
+        let scale = scale.min(1.0);
+        (((sw * scale).round() as usize).max(1), ((sh * scale).round() as usize).max(1))
+    };
+
+    match (request.width, request.height, request.fit) {
+        (Some(w), Some(h), ImageFit::Cover) => {
+            let (w, h) = (w as f64, h as f64);
+            let scale = (w / sw).max(h / sh);
+            let crop_w = ((w / scale).round() as usize).clamp(1, source.0);
+            let crop_h = ((h / scale).round() as usize).clamp(1, source.1);
+            let output = if scale > 1.0 { (crop_w, crop_h) } else { (w as usize, h as usize) };
+            PreviewPlan {
+                crop: ((source.0 - crop_w) / 2, (source.1 - crop_h) / 2, crop_w, crop_h),
+                output,
+            }
+        }
+        (Some(w), Some(h), ImageFit::Fill) => PreviewPlan {
+            crop: full,
+            output: ((w as usize).min(source.0), (h as usize).min(source.1)),
+        },
+        (Some(w), Some(h), ImageFit::Contain) => PreviewPlan { crop: full, output: fit((w as f64 / sw).min(h as f64 / sh)) },
+        (Some(w), None, _) => PreviewPlan { crop: full, output: fit(w as f64 / sw) },
+        (None, Some(h), _) => PreviewPlan { crop: full, output: fit(h as f64 / sh) },
+        (None, None, _) => PreviewPlan { crop: full, output: fit(max_dimension as f64 / sw.max(sh)) },
+    }
+}
+
+/// Caché LRU de vistas previas limitada por bytes
+struct PreviewCache {
+    entries: HashMap<String, (Arc<Vec<u8>>, u64)>,
+    total_bytes: usize,
+    max_bytes: usize,
+    tick: u64,
+}
+
+impl PreviewCache {
+    fn new(max_bytes: usize) -> Self {
+        Self { entries: HashMap::new(), total_bytes: 0, max_bytes, tick: 0 }
+    }
+
+    fn get(&mut self, key: &str) -> Option<Arc<Vec<u8>>> {
+        self.tick += 1;
+        let tick = self.tick;
+        self.entries.get_mut(key).map(|(content, last_used)| {
+            *last_used = tick;
+            content.clone()
+        })
+    }
+
+    fn insert(&mut self, key: String, content: Arc<Vec<u8>>) {
+        if content.len() > self.max_bytes {
+            return;
+        }
+        while self.total_bytes + content.len() > self.max_bytes {
+            let Some(oldest) = self.entries.iter().min_by_key(|(_, (_, used))| *used).map(|(k, _)| k.clone()) else {
+                break;
+            };
+            if let Some((evicted, _)) = self.entries.remove(&oldest) {
+                self.total_bytes -= evicted.len();
+            }
+        }
+        self.tick += 1;
+        self.total_bytes += content.len();
+        if let Some((replaced, _)) = self.entries.insert(key, (content, self.tick)) {
+            self.total_bytes -= replaced.len();
+        }
+    }
+}
+
+/// Servicio que redimensiona y recorta imágenes bajo demanda para las vistas
+/// previas de las interfaces adaptables, con caché acotada y límites de uso
+pub struct ImagePreviewService {
+    file_service: Arc<dyn FileUseCase>,
+    quality: PreviewQuality,
+    limits: ImagePreviewLimits,
+    permits: Semaphore,
+    cache: Mutex<PreviewCache>,
+}
+
+impl ImagePreviewService {
+    /// Crea el servicio con la calidad por defecto y los límites indicados
+    pub fn new(file_service: Arc<dyn FileUseCase>, quality: PreviewQuality, limits: ImagePreviewLimits) -> Self {
+        Self {
+            file_service,
+            quality,
+            permits: Semaphore::new(limits.max_concurrent.max(1)),
+            cache: Mutex::new(PreviewCache::new(limits.cache_bytes)),
+            limits,
+        }
+    }
+
+    fn validate(&self, request: &ImagePreviewRequest) -> Result<(), DomainError> {
+        for side in [request.width, request.height].into_iter().flatten() {
+            if side == 0 || side > self.limits.max_dimension {
+                return Err(DomainError::validation_error(format!(
+                    "Preview dimensions must be between 1 and {}",
+                    self.limits.max_dimension
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ImagePreviewUseCase for ImagePreviewService {
+    async fn render_preview(&self, file_id: &str, request: ImagePreviewRequest) -> Result<ImagePreview, DomainError> {
+        self.validate(&request)?;
+        let quality = request.quality.unwrap_or(self.quality);
+
+        let file = self.file_service.get_file(file_id).await?;
+        if !PREVIEW_MIME_TYPES.contains(&file.mime_type.as_str()) {
+            return Err(DomainError::validation_error(format!(
+                "Previews are not available for {} files",
+                file.mime_type
+            )));
+        }
+        if file.size > self.limits.max_source_bytes {
+            return Err(DomainError::validation_error("Image is too large to preview"));
+        }
+
+        let key = format!(
+            "{}-{}-{}x{}-{:?}-{:?}",
+            file.id,
+            file.modified_at,
+            request.width.unwrap_or(0),
+            request.height.unwrap_or(0),
+            request.fit,
+            quality
+        )
+        .to_lowercase();
+        let etag = format!("\"{}\"", key);
+
+        if let Some(content) = self.cache.lock().ok().and_then(|mut cache| cache.get(&key)) {
+            return Ok(ImagePreview { content, mime_type: "image/png", etag });
+        }
+
+        // Rendering is CPU and memory bound: cap how many run at once
+        let _permit = tokio::time::timeout(RENDER_QUEUE_TIMEOUT, self.permits.acquire())
+            .await
+            .map_err(|_| DomainError::unavailable("ImagePreview", "Too many previews are being rendered"))?
+            .map_err(|_| DomainError::internal_error("ImagePreview", "Preview renderer is shutting down"))?;
+
+        let content = self.file_service.get_file_content(&file.id).await?;
+        let mime_type = file.mime_type.clone();
+        let limits = self.limits;
+
+        let rendered = tokio::task::spawn_blocking(move || -> Result<Vec<u8>, DomainError> {
+            let (width, height) = image_dimensions(&mime_type, &content)
+                .ok_or_else(|| DomainError::validation_error("File is not a supported image"))?;
+            let (width, height) = (width as usize, height as usize);
+            if width.saturating_mul(height) > limits.max_source_pixels {
+                return Err(DomainError::validation_error("Image is too large to preview"));
+            }
+
+            let plan = plan_preview((width, height), &request, limits.max_dimension);
+            let decoded = decode_image(&mime_type, &content, limits.max_source_pixels)
+                .ok_or_else(|| DomainError::validation_error("Image could not be decoded"))?;
+
+            let (x, y, crop_w, crop_h) = plan.crop;
+            let cropped = decoded.crop_imm(x as u32, y as u32, crop_w as u32, crop_h as u32);
+
+            let smooth = quality == PreviewQuality::High;
+            let filter = if smooth { FilterType::Triangle } else { FilterType::Nearest };
+            let resized = cropped.resize_exact(plan.output.0 as u32, plan.output.1 as u32, filter);
+            Ok(encode_png(&resized, !smooth))
+        })
+        .await
+        .map_err(|e| DomainError::internal_error("ImagePreview", format!("Preview task failed: {}", e)))??;
+
+        let content = Arc::new(rendered);
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.insert(key, content.clone());
+        }
+
+        Ok(ImagePreview { content, mime_type: "image/png", etag })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(width: Option<u32>, height: Option<u32>, fit: ImageFit) -> ImagePreviewRequest {
+        ImagePreviewRequest { width, height, fit, quality: None }
+    }
+
+    #[test]
+    fn test_plan_contain_keeps_aspect_ratio() {
+        let plan = plan_preview((400, 200), &request(Some(100), Some(100), ImageFit::Contain), 2048);
+        assert_eq!(plan, PreviewPlan { crop: (0, 0, 400, 200), output: (100, 50) });
+
+        let plan = plan_preview((400, 200), &request(None, Some(50), ImageFit::Cover), 2048);
+        assert_eq!(plan.output, (100, 50));
+
+        // Never upscales
+        let plan = plan_preview((40, 20), &request(Some(400), None, ImageFit::Contain), 2048);
+        assert_eq!(plan.output, (40, 20));
+
+        let plan = plan_preview((4000, 1000), &request(None, None, ImageFit::Contain), 1000);
+        assert_eq!(plan.output, (1000, 250));
+    }
+
+    #[test]
+    fn test_plan_cover_crops_center() {
+        let plan = plan_preview((400, 200), &request(Some(100), Some(100), ImageFit::Cover), 2048);
+        assert_eq!(plan, PreviewPlan { crop: (100, 0, 200, 200), output: (100, 100) });
+
+        // A box larger than the image crops to its aspect ratio without upscaling
+        let plan = plan_preview((100, 50), &request(Some(400), Some(400), ImageFit::Cover), 2048);
+        assert_eq!(plan, PreviewPlan { crop: (25, 0, 50, 50), output: (50, 50) });
+    }
+
+    #[test]
+    fn test_plan_fill_stretches() {
+        let plan = plan_preview((400, 200), &request(Some(100), Some(100), ImageFit::Fill), 2048);
+        assert_eq!(plan, PreviewPlan { crop: (0, 0, 400, 200), output: (100, 100) });
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let mut cache = PreviewCache::new(10);
+        cache.insert("a".to_string(), Arc::new(vec![0; 4]));
+        cache.insert("b".to_string(), Arc::new(vec![0; 4]));
+        assert!(cache.get("a").is_some());
+
+        cache.insert("c".to_string(), Arc::new(vec![0; 4]));
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("a").is_some());
+        assert_eq!(cache.total_bytes, 8);
+
+        // Entries larger than the whole cache are not stored
+        cache.insert("d".to_string(), Arc::new(vec![0; 11]));
+        assert!(cache.get("d").is_none());
+    }
+
+    #[test]
+    fn test_png_round_trip_and_limits() {
+        let image = DynamicImage::ImageRgb8(image::RgbImage::from_fn(5, 3, |x, y| image::Rgb([(x * 40) as u8, (y * 80) as u8, 7])));
+        for fast in [true, false] {
+            let png = encode_png(&image, fast);
+            assert_eq!(image_dimensions("image/png", &png), Some((5, 3)));
+            assert_eq!(decode_image("image/png", &png, 15).unwrap().to_rgb8(), image.to_rgb8());
+            // Images over the pixel limit are not decoded
+            assert!(decode_image("image/png", &png, 14).is_none());
+        }
+    }
+
+    #[test]
+    fn test_unsupported_or_corrupt_input() {
+        assert_eq!(image_dimensions("image/gif", b"GIF89a"), None);
+        assert_eq!(image_dimensions("image/jpeg", &[0xFF, 0xD8, 0xFF, 0xD9]), None);
+        assert!(decode_image("image/png", b"not a png", usize::MAX).is_none());
+    }
+}
@@ -0,0 +1,258 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use ldap3::{Ldap, LdapConnAsync, LdapConnSettings, LdapError, Scope, SearchEntry, SearchOptions};
+
+use crate::application::ports::directory_ports::{DirectoryAuthPort, DirectoryUser};
+use crate::common::config::LdapConfig;
+use crate::common::errors::DomainError;
+
+// Códigos de resultado usados (RFC 4511, 4.1.9)
+const RESULT_SUCCESS: u32 = 0;
+const RESULT_SIZE_LIMIT_EXCEEDED: u32 = 4;
+const RESULT_INVALID_CREDENTIALS: u32 = 49;
+
+fn ldap_error(message: impl Into<String>) -> DomainError {
+    DomainError::internal_error("LDAP", message.into())
+}
+
+fn protocol_error(e: LdapError) -> DomainError {
+    ldap_error(format!("Error de comunicación con el servidor LDAP: {}", e))
+}
+
+/// Adaptador de un directorio LDAP o Active Directory.
+///
+/// Busca la entrada del usuario con la cuenta de servicio y comprueba la
+/// contraseña haciendo bind con su DN. Los grupos salen del atributo de
+/// pertenencia (`memberOf`) y, si se configura, de una búsqueda de grupos.
+/// El protocolo lo habla `ldap3` (LDAPS o StartTLS incluidos), con una
+/// conexión por autenticación.
+#[derive(Clone)]
+pub struct LdapDirectoryClient {
+    config: LdapConfig,
+}
+
+impl LdapDirectoryClient {
+    pub fn new(config: LdapConfig) -> Result<Self, DomainError> {
+        let url = url::Url::parse(&config.url)
+            .map_err(|e| ldap_error(format!("URL LDAP inválida {}: {}", config.url, e)))?;
+        if !matches!(url.scheme(), "ldap" | "ldaps") {
+            return Err(ldap_error(format!("Esquema LDAP no soportado: {}", url.scheme())));
+        }
+        if url.host_str().is_none() {
+            return Err(ldap_error(format!("La URL LDAP no tiene servidor: {}", config.url)));
+        }
+        ldap3::parse_filter(&config.user_filter)
+            .map_err(|_| ldap_error(format!("Filtro de usuarios inválido: {}", config.user_filter)))?;
+
+        Ok(Self { config })
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(self.config.timeout_secs)
+    }
+
+    async fn connect(&self) -> Result<Ldap, DomainError> {
+        // StartTLS solo se aplica a `ldap://`; `ldaps://` ya va cifrado
+        let settings = LdapConnSettings::new()
+            .set_conn_timeout(self.timeout())
+            .set_starttls(self.config.starttls && self.config.url.starts_with("ldap://"));
+        let (connection, ldap) = LdapConnAsync::with_settings(settings, &self.config.url)
+            .await
+            .map_err(|e| ldap_error(format!("No se pudo conectar con {}: {}", self.config.url, e)))?;
+        ldap3::drive!(connection);
+        Ok(ldap)
+    }
+
+    /// Bind con `dn`; devuelve el código de resultado y su mensaje
+    async fn bind(&self, ldap: &mut Ldap, dn: &str, password: &str) -> Result<(u32, String), DomainError> {
+        let result = ldap.with_timeout(self.timeout())
+            .simple_bind(dn, password)
+            .await
+            .map_err(protocol_error)?;
+        Ok((result.rc, result.text))
+    }
+
+    async fn service_bind(&self, ldap: &mut Ldap) -> Result<(), DomainError> {
+        if self.config.bind_dn.is_empty() {
+            return Ok(());
+        }
+        match self.bind(ldap, &self.config.bind_dn, &self.config.bind_password).await? {
+            (RESULT_SUCCESS, _) => Ok(()),
+            (code, message) => Err(ldap_error(format!("El directorio rechazó la cuenta de servicio ({}): {}", code, message))),
+        }
+    }
+
+    async fn search(
+        &self,
+        ldap: &mut Ldap,
+        base_dn: &str,
+        filter: &str,
+        attributes: &[&str],
+        size_limit: i32,
+    ) -> Result<Vec<DirectoryEntry>, DomainError> {
+        let ldap3::SearchResult(entries, result) = ldap
+            .with_search_options(SearchOptions::new().sizelimit(size_limit).timelimit(self.config.timeout_secs as i32))
+            .with_timeout(self.timeout())
+            .search(base_dn, Scope::Subtree, filter, attributes.to_vec())
+            .await
+            .map_err(protocol_error)?;
+        // Con el límite alcanzado las entradas recibidas siguen valiendo
+        if result.rc != RESULT_SUCCESS && result.rc != RESULT_SIZE_LIMIT_EXCEEDED {
+            return Err(ldap_error(format!("Error en la búsqueda LDAP ({}): {}", result.rc, result.text)));
+        }
+        Ok(entries.into_iter().map(|entry| DirectoryEntry::from(SearchEntry::construct(entry))).collect())
+    }
+
+    async fn authenticate_user(&self, ldap: &mut Ldap, username: &str, password: &str) -> Result<Option<DirectoryUser>, DomainError> {
+        self.service_bind(ldap).await?;
+
+        let filter = self.config.user_filter.replace("{username}", &escape_filter_value(username));
+        let attributes = [
+            self.config.username_attribute.as_str(),
+            self.config.email_attribute.as_str(),
+            self.config.group_attribute.as_str(),
+        ];
+        let mut entries = self.search(ldap, &self.config.base_dn, &filter, &attributes, 2).await?;
+        let entry = match entries.len() {
+            0 => return Ok(None),
+            1 => entries.remove(0),
+            _ => return Err(ldap_error(format!("Hay varias entradas para el usuario {}", username))),
+        };
+
+        match self.bind(ldap, &entry.dn, password).await? {
+            (RESULT_SUCCESS, _) => {}
+            (RESULT_INVALID_CREDENTIALS, _) => return Err(DomainError::access_denied("Auth", "Credenciales inválidas")),
+            (code, message) => return Err(ldap_error(format!("Error al comprobar la contraseña ({}): {}", code, message))),
+        }
+
+        let mut groups = entry.values(&self.config.group_attribute);
+        if let Some(group_filter) = &self.config.group_filter {
+            // La cuenta del usuario puede no tener permiso para buscar grupos
+            self.service_bind(ldap).await?;
+            let filter = group_filter
+                .replace("{dn}", &escape_filter_value(&entry.dn))
+                .replace("{username}", &escape_filter_value(username));
+            let base_dn = self.config.group_base_dn.as_deref().unwrap_or(&self.config.base_dn);
+            let found = self.search(ldap, base_dn, &filter, &["cn"], 0).await?;
+            groups.extend(found.into_iter().map(|group| group.dn));
+        }
+
+        Ok(Some(DirectoryUser {
+            username: entry.first(&self.config.username_attribute).unwrap_or_else(|| username.to_string()),
+            email: entry.first(&self.config.email_attribute),
+            groups,
+            dn: entry.dn,
+        }))
+    }
+}
+
+#[async_trait]
+impl DirectoryAuthPort for LdapDirectoryClient {
+    async fn authenticate(&self, username: &str, password: &str) -> Result<Option<DirectoryUser>, DomainError> {
+        // Un bind con contraseña vacía es anónimo y el servidor lo aceptaría
+        if username.trim().is_empty() || password.is_empty() {
+            return Err(DomainError::access_denied("Auth", "Credenciales inválidas"));
+        }
+
+        let mut ldap = self.connect().await?;
+        let result = self.authenticate_user(&mut ldap, username, password).await;
+        // La conexión se cierra igual si la autenticación falla
+        let _ = ldap.unbind().await;
+        result
+    }
+}
+
+/// Entrada devuelta por una búsqueda, con los nombres de atributo en minúsculas
+#[derive(Debug)]
+struct DirectoryEntry {
+    dn: String,
+    attributes: HashMap<String, Vec<String>>,
+}
+
+impl From<SearchEntry> for DirectoryEntry {
+    fn from(entry: SearchEntry) -> Self {
+        let mut attributes: HashMap<String, Vec<String>> = HashMap::new();
+        for (name, values) in entry.attrs {
+            attributes.entry(name.to_lowercase()).or_default().extend(values);
+        }
+        Self { dn: entry.dn, attributes }
+    }
+}
+
+impl DirectoryEntry {
+    fn values(&self, attribute: &str) -> Vec<String> {
+        self.attributes.get(&attribute.to_lowercase()).cloned().unwrap_or_default()
+    }
+
+    fn first(&self, attribute: &str) -> Option<String> {
+        self.values(attribute).into_iter().find(|value| !value.trim().is_empty())
+    }
+}
+
+/// Escapa un valor para insertarlo en un filtro (RFC 4515)
+fn escape_filter_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '*' => escaped.push_str("\\2a"),
+            '(' => escaped.push_str("\\28"),
+            ')' => escaped.push_str("\\29"),
+            '\\' => escaped.push_str("\\5c"),
+            '\0' => escaped.push_str("\\00"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(url: &str, user_filter: &str) -> LdapConfig {
+        LdapConfig { url: url.to_string(), user_filter: user_filter.to_string(), ..LdapConfig::default() }
+    }
+
+    #[test]
+    fn test_configuration_is_validated() {
+        assert!(LdapDirectoryClient::new(config("ldaps://ldap.example.org", "(uid={username})")).is_ok());
+        assert!(LdapDirectoryClient::new(config("http://ldap.example.org", "(uid={username})")).is_err());
+        assert!(LdapDirectoryClient::new(config("ldap://", "(uid={username})")).is_err());
+        assert!(LdapDirectoryClient::new(config("ldap://ldap.example.org", "(uid={username}")).is_err());
+    }
+
+    #[test]
+    fn test_escape_filter_value() {
+        assert_eq!(escape_filter_value("jo*)(uid=*"), "jo\\2a\\29\\28uid=\\2a");
+        let filter = format!("(uid={})", escape_filter_value("a)(|(uid=*"));
+        assert!(ldap3::parse_filter(&filter).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_empty_passwords_are_rejected_without_contacting_the_server() {
+        // Nadie escucha en este puerto: una conexión fallaría con otro error
+        let client = LdapDirectoryClient::new(config("ldap://127.0.0.1:1", "(uid={username})")).unwrap();
+        let error = client.authenticate("ana", "").await.unwrap_err();
+        assert_eq!(error.kind, crate::common::errors::ErrorKind::AccessDenied);
+        let error = client.authenticate(" ", "secret").await.unwrap_err();
+        assert_eq!(error.kind, crate::common::errors::ErrorKind::AccessDenied);
+    }
+
+    #[test]
+    fn test_attribute_names_are_case_insensitive() {
+        let entry = DirectoryEntry::from(SearchEntry {
+            dn: "uid=ana,ou=people,dc=example,dc=org".to_string(),
+            attrs: HashMap::from([
+                ("mail".to_string(), vec!["ana@example.org".to_string()]),
+                ("memberOf".to_string(), vec!["cn=staff,dc=example,dc=org".to_string(), "cn=admins,dc=example,dc=org".to_string()]),
+            ]),
+            bin_attrs: HashMap::new(),
+        });
+        assert_eq!(entry.dn, "uid=ana,ou=people,dc=example,dc=org");
+        assert_eq!(entry.first("MAIL").as_deref(), Some("ana@example.org"));
+        assert_eq!(entry.values("memberof").len(), 2);
+        assert!(entry.first("cn").is_none());
+    }
+}
@@ -0,0 +1,44 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time;
+use tracing::{debug, error, info};
+
+use crate::application::ports::lock_ports::LockUseCase;
+
+/// Servicio que elimina periódicamente los bloqueos WebDAV caducados
+pub struct LockCleanupService {
+    lock_service: Arc<dyn LockUseCase>,
+    cleanup_interval_minutes: u64,
+}
+
+impl LockCleanupService {
+    pub fn new(lock_service: Arc<dyn LockUseCase>, cleanup_interval_minutes: u64) -> Self {
+        Self {
+            lock_service,
+            cleanup_interval_minutes: cleanup_interval_minutes.max(1), // Mínimo 1 minuto
+        }
+    }
+
+    /// Arranca la limpieza periódica de bloqueos caducados
+    pub async fn start_cleanup_job(&self) {
+        let lock_service = self.lock_service.clone();
+        let interval_minutes = self.cleanup_interval_minutes;
+
+        info!("Iniciando limpieza de bloqueos WebDAV caducados con intervalo de {} minutos", interval_minutes);
+
+        tokio::spawn(async move {
+            let mut interval = time::interval(Duration::from_secs(interval_minutes * 60));
+
+            loop {
+                interval.tick().await;
+                debug!("Ejecutando tarea programada de limpieza de bloqueos");
+
+                match lock_service.expire_locks().await {
+                    Ok(0) => {}
+                    Ok(count) => info!("Eliminados {} bloqueos WebDAV caducados", count),
+                    Err(e) => error!("Error en la limpieza programada de bloqueos: {:?}", e),
+                }
+            }
+        });
+    }
+}
@@ -7,4 +7,30 @@ pub mod file_metadata_cache;
 pub mod compression_service;
 pub mod buffer_pool;
 pub mod trash_cleanup_service;
-pub mod zip_service;
\ No newline at end of file
+pub mod zip_stream_writer;
+pub mod env_credential_vault;
+pub mod external_change_watcher;
+pub mod fs_skeleton_source;
+pub mod fs_template_store;
+pub mod sftp_storage_service;
+pub mod smb_storage_service;
+pub mod snapshot_storage_service;
+pub mod image_placeholder_service;
+pub mod image_fingerprint_service;
+pub mod http_image_labeler;
+pub mod upload_session_cleanup_service;
+pub mod lock_cleanup_service;
+pub mod file_attribute_service;
+pub mod image_preview_service;
+pub mod dav_capture_service;
+pub mod hidden_file_rules_service;
+pub mod storage_gc_service;
+pub mod contact_photo_store;
+pub mod content_hash_service;
+pub mod persisted_map;
+pub mod oidc_client;
+pub mod smtp_mail_sender;
+pub mod ldap_client;
+pub mod http_webhook_sender;
+pub mod text_extractor;
+pub mod tantivy_full_text_index;
@@ -0,0 +1,255 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use tokio::sync::{Mutex, OnceCell};
+
+use crate::application::ports::oidc_ports::{OidcClaims, OidcProviderPort};
+use crate::common::config::OidcConfig;
+use crate::common::errors::DomainError;
+
+/// Subset of the provider metadata published at
+/// `<issuer>/.well-known/openid-configuration`
+#[derive(Debug, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+    #[serde(default)]
+    id_token_signing_alg_values_supported: Vec<String>,
+}
+
+/// Algorithms accepted for ID tokens when neither the configuration nor the
+/// provider lists any
+const DEFAULT_ALGORITHMS: [Algorithm; 2] = [Algorithm::RS256, Algorithm::ES256];
+
+fn is_hmac(algorithm: Algorithm) -> bool {
+    matches!(algorithm, Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512)
+}
+
+/// Algorithms an ID token may be signed with: the configured ones, or else
+/// the asymmetric ones the provider advertises, or else the defaults.
+///
+/// HMAC algorithms sign with the client secret, which this server shares
+/// with the provider, so they are only accepted when configured.
+fn allowed_algorithms(configured: &[Algorithm], advertised: &[String]) -> Vec<Algorithm> {
+    if !configured.is_empty() {
+        return configured.to_vec();
+    }
+    let advertised: Vec<Algorithm> = advertised
+        .iter()
+        .filter_map(|name| Algorithm::from_str(name).ok())
+        .filter(|algorithm| !is_hmac(*algorithm))
+        .collect();
+    if advertised.is_empty() {
+        DEFAULT_ALGORITHMS.to_vec()
+    } else {
+        advertised
+    }
+}
+
+/// Token endpoint response; only the ID token is used
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+fn oidc_error(message: impl Into<String>) -> DomainError {
+    DomainError::internal_error("OIDC", message.into())
+}
+
+/// Adapter for an OpenID Connect provider reached over HTTP.
+///
+/// The provider metadata is discovered on first use and kept for the life of
+/// the process. Signing keys are cached too and fetched again when a token is
+/// signed with a key that is not known yet, so key rotation needs no restart.
+pub struct OidcHttpClient {
+    client: reqwest::Client,
+    config: OidcConfig,
+    redirect_uri: String,
+    /// Algorithms from the configuration; empty defers to the provider
+    algorithms: Vec<Algorithm>,
+    metadata: OnceCell<ProviderMetadata>,
+    jwks: Mutex<Option<JwkSet>>,
+}
+
+impl OidcHttpClient {
+    /// Creates a client for the configured provider; `redirect_uri` is the
+    /// callback URL registered at the provider
+    pub fn new(config: OidcConfig, redirect_uri: String) -> Result<Self, DomainError> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(15))
+            .build()
+            .map_err(|e| oidc_error(format!("Failed to create HTTP client: {}", e)))?;
+        let algorithms = config.id_token_algorithms
+            .iter()
+            .map(|name| Algorithm::from_str(name).map_err(|_| oidc_error(format!("Unknown ID token algorithm: {}", name))))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            client,
+            config,
+            redirect_uri,
+            algorithms,
+            metadata: OnceCell::new(),
+            jwks: Mutex::new(None),
+        })
+    }
+
+    async fn metadata(&self) -> Result<&ProviderMetadata, DomainError> {
+        self.metadata.get_or_try_init(|| async {
+            let url = format!("{}/.well-known/openid-configuration", self.config.issuer_url);
+            let metadata: ProviderMetadata = self.get_json(&url).await?;
+            tracing::info!("OIDC provider discovered: {}", metadata.issuer);
+            Ok(metadata)
+        }).await
+    }
+
+    async fn get_json<T: serde::de::DeserializeOwned>(&self, url: &str) -> Result<T, DomainError> {
+        self.client.get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| oidc_error(format!("Request to {} failed: {}", url, e)))?
+            .json()
+            .await
+            .map_err(|e| oidc_error(format!("Invalid response from {}: {}", url, e)))
+    }
+
+    /// Returns the key a token was signed with, refreshing the key set once
+    /// if the key is unknown
+    async fn decoding_key(&self, kid: Option<&str>) -> Result<DecodingKey, DomainError> {
+        let metadata = self.metadata().await?;
+        let mut jwks = self.jwks.lock().await;
+
+        for refresh in [false, true] {
+            if refresh || jwks.is_none() {
+                *jwks = Some(self.get_json(&metadata.jwks_uri).await?);
+            }
+            let Some(set) = jwks.as_ref() else { continue };
+            let jwk = match kid {
+                Some(kid) => set.find(kid),
+                None => set.keys.first(),
+            };
+            if let Some(jwk) = jwk {
+                return DecodingKey::from_jwk(jwk).map_err(|e| oidc_error(format!("Unusable signing key: {}", e)));
+            }
+        }
+        Err(oidc_error("ID token signed with an unknown key"))
+    }
+
+    /// Verifies the signature, issuer, audience and expiry of an ID token
+    async fn verify_id_token(&self, id_token: &str) -> Result<OidcClaims, DomainError> {
+        let metadata = self.metadata().await?;
+        let header = jsonwebtoken::decode_header(id_token)
+            .map_err(|e| oidc_error(format!("Malformed ID token: {}", e)))?;
+
+        // The token header chooses the algorithm, so it must be one we allow
+        let allowed = allowed_algorithms(&self.algorithms, &metadata.id_token_signing_alg_values_supported);
+        if !allowed.contains(&header.alg) {
+            return Err(DomainError::access_denied("OIDC", format!("ID token signed with a disallowed algorithm: {:?}", header.alg)));
+        }
+        let key = if is_hmac(header.alg) {
+            DecodingKey::from_secret(self.config.client_secret.as_bytes())
+        } else {
+            self.decoding_key(header.kid.as_deref()).await?
+        };
+        let mut validation = Validation::new(header.alg);
+        validation.set_audience(&[&self.config.client_id]);
+        validation.set_issuer(&[&metadata.issuer]);
+
+        let claims = jsonwebtoken::decode::<Map<String, Value>>(id_token, &key, &validation)
+            .map_err(|e| DomainError::access_denied("OIDC", format!("Invalid ID token: {}", e)))?
+            .claims;
+
+        let string_claim = |name: &str| claims.get(name).and_then(Value::as_str).map(str::to_string);
+        let subject = string_claim("sub").ok_or_else(|| oidc_error("ID token without subject"))?;
+        // Some providers send the flag as a string
+        let email_verified = match claims.get("email_verified") {
+            Some(Value::Bool(verified)) => *verified,
+            Some(Value::String(verified)) => verified == "true",
+            _ => false,
+        };
+
+        Ok(OidcClaims {
+            issuer: metadata.issuer.clone(),
+            subject,
+            email: string_claim("email"),
+            email_verified,
+            username: string_claim(&self.config.username_claim),
+            name: string_claim("name"),
+            nonce: string_claim("nonce"),
+        })
+    }
+}
+
+#[async_trait]
+impl OidcProviderPort for OidcHttpClient {
+    async fn authorization_url(&self, state: &str, nonce: &str, code_challenge: &str) -> Result<String, DomainError> {
+        let metadata = self.metadata().await?;
+        let url = url::Url::parse_with_params(&metadata.authorization_endpoint, &[
+            ("response_type", "code"),
+            ("client_id", self.config.client_id.as_str()),
+            ("redirect_uri", self.redirect_uri.as_str()),
+            ("scope", self.config.scopes.as_str()),
+            ("state", state),
+            ("nonce", nonce),
+            ("code_challenge", code_challenge),
+            ("code_challenge_method", "S256"),
+        ]).map_err(|e| oidc_error(format!("Invalid authorization endpoint: {}", e)))?;
+
+        Ok(url.to_string())
+    }
+
+    async fn exchange_code(&self, code: &str, code_verifier: &str) -> Result<OidcClaims, DomainError> {
+        let metadata = self.metadata().await?;
+        let response = self.client.post(&metadata.token_endpoint)
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", self.redirect_uri.as_str()),
+                ("client_id", self.config.client_id.as_str()),
+                ("client_secret", self.config.client_secret.as_str()),
+                ("code_verifier", code_verifier),
+            ])
+            .send()
+            .await
+            .map_err(|e| oidc_error(format!("Token request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(DomainError::access_denied("OIDC", format!("Code exchange rejected ({}): {}", status, body)));
+        }
+        let tokens: TokenResponse = response.json()
+            .await
+            .map_err(|e| oidc_error(format!("Invalid token response: {}", e)))?;
+
+        self.verify_id_token(&tokens.id_token).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allowed_algorithms() {
+        // Defaults when nothing is configured or advertised
+        assert_eq!(allowed_algorithms(&[], &[]), DEFAULT_ALGORITHMS.to_vec());
+
+        // Advertised HMAC and unknown algorithms are ignored
+        let advertised = vec!["HS256".to_string(), "PS256".to_string(), "none".to_string()];
+        assert_eq!(allowed_algorithms(&[], &advertised), vec![Algorithm::PS256]);
+        let only_hmac = vec!["HS256".to_string()];
+        assert_eq!(allowed_algorithms(&[], &only_hmac), DEFAULT_ALGORITHMS.to_vec());
+
+        // The configuration wins, HMAC included
+        assert_eq!(allowed_algorithms(&[Algorithm::HS256], &advertised), vec![Algorithm::HS256]);
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio::fs;
+use tokio::sync::{Mutex, RwLock, RwLockReadGuard};
+
+use crate::common::errors::DomainError;
+
+/// Tiempo entre el primer cambio pendiente y la escritura en disco
+const FLUSH_DELAY: Duration = Duration::from_millis(500);
+
+/// Mapa en memoria que se guarda en un fichero JSON junto al almacenamiento.
+///
+/// Las escrituras se agrupan: el primer cambio programa un guardado al cabo
+/// de `FLUSH_DELAY` que incluye todos los que lleguen mientras tanto, en vez
+/// de reescribir el fichero entero en cada cambio. El fichero se escribe en
+/// un temporal que luego se renombra, así que nunca queda a medias.
+pub struct PersistedMap<V> {
+    inner: Arc<Inner<V>>,
+}
+
+struct Inner<V> {
+    /// Nombre usado en los errores y en el registro
+    context: &'static str,
+    path: PathBuf,
+    entries: RwLock<HashMap<String, V>>,
+    flush_scheduled: AtomicBool,
+    save_mutex: Mutex<()>,
+}
+
+impl<V> PersistedMap<V>
+where
+    V: Serialize + DeserializeOwned + Send + Sync + 'static,
+{
+    /// Carga el mapa del fichero; si no existe empieza vacío y si está
+    /// corrupto se descarta
+    pub async fn load(context: &'static str, path: PathBuf) -> Result<Self, DomainError> {
+        let entries: HashMap<String, V> = match fs::read_to_string(&path).await {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                tracing::error!("Ignoring corrupted {} store {}: {}", context, path.display(), e);
+                HashMap::new()
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => {
+                return Err(DomainError::internal_error(
+                    context,
+                    format!("Failed to read store {}: {}", path.display(), e),
+                ))
+            }
+        };
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                context,
+                path,
+                entries: RwLock::new(entries),
+                flush_scheduled: AtomicBool::new(false),
+                save_mutex: Mutex::new(()),
+            }),
+        })
+    }
+
+    pub async fn read(&self) -> RwLockReadGuard<'_, HashMap<String, V>> {
+        self.inner.entries.read().await
+    }
+
+    /// Aplica `change` al mapa y programa el guardado si devuelve `true`
+    pub async fn update(&self, change: impl FnOnce(&mut HashMap<String, V>) -> bool) -> bool {
+        let changed = change(&mut *self.inner.entries.write().await);
+        if changed {
+            self.schedule_flush();
+        }
+        changed
+    }
+
+    pub async fn insert(&self, key: &str, value: V) {
+        self.update(|entries| {
+            entries.insert(key.to_string(), value);
+            true
+        }).await;
+    }
+
+    pub async fn remove(&self, key: &str) -> Option<V> {
+        let mut removed = None;
+        self.update(|entries| {
+            removed = entries.remove(key);
+            removed.is_some()
+        }).await;
+        removed
+    }
+
+    /// Guarda ya los cambios pendientes
+    pub async fn flush(&self) -> Result<(), DomainError> {
+        self.inner.flush().await
+    }
+
+    fn schedule_flush(&self) {
+        if self.inner.flush_scheduled.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let inner = self.inner.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(FLUSH_DELAY).await;
+            if let Err(e) = inner.flush().await {
+                tracing::error!("Failed to save the {} store: {}", inner.context, e);
+            }
+        });
+    }
+}
+
+impl<V: Serialize> Inner<V> {
+    async fn flush(&self) -> Result<(), DomainError> {
+        let _guard = self.save_mutex.lock().await;
+        // Los cambios que lleguen a partir de aquí programan otro guardado
+        self.flush_scheduled.store(false, Ordering::Release);
+        let json = {
+            let entries = self.entries.read().await;
+            serde_json::to_string(&*entries)
+                .map_err(|e| DomainError::internal_error(self.context, format!("Failed to serialize store: {}", e)))?
+        };
+
+        let temp_path = self.path.with_extension("json.tmp");
+        fs::write(&temp_path, json)
+            .await
+            .map_err(|e| DomainError::internal_error(self.context, format!("Failed to write store: {}", e)))?;
+        fs::rename(&temp_path, &self.path)
+            .await
+            .map_err(|e| DomainError::internal_error(self.context, format!("Failed to write store: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_changes_are_saved_after_the_delay() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("store.json");
+
+        let map = PersistedMap::<u32>::load("Test", path.clone()).await.unwrap();
+        for i in 0..100 {
+            map.insert(&format!("k{}", i), i).await;
+        }
+        assert_eq!(map.remove("k0").await, Some(0));
+        assert_eq!(map.remove("k0").await, None);
+        assert!(!path.exists());
+
+        tokio::time::sleep(FLUSH_DELAY * 3).await;
+        let reloaded = PersistedMap::<u32>::load("Test", path).await.unwrap();
+        let entries = reloaded.read().await;
+        assert_eq!(entries.len(), 99);
+        assert_eq!(entries.get("k99"), Some(&99));
+    }
+
+    #[tokio::test]
+    async fn test_flush_and_corrupted_stores() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("store.json");
+
+        std::fs::write(&path, "{not json").unwrap();
+        let map = PersistedMap::<String>::load("Test", path.clone()).await.unwrap();
+        assert!(map.read().await.is_empty());
+
+        assert!(!map.update(|_| false).await);
+        map.insert("a", "1".to_string()).await;
+        map.flush().await.unwrap();
+
+        let reloaded = PersistedMap::<String>::load("Test", path).await.unwrap();
+        assert_eq!(reloaded.read().await.get("a").map(String::as_str), Some("1"));
+    }
+}
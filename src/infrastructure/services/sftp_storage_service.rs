@@ -0,0 +1,760 @@
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use futures::StreamExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::{debug, warn};
+
+use crate::application::ports::credential_ports::StoredCredential;
+use crate::application::ports::external_storage_ports::{
+    ExternalByteStream, ExternalEntry, ExternalStoragePort,
+};
+use crate::common::config::{ExternalMountConfig, ExternalStorageConfig};
+use crate::common::errors::{DomainError, ErrorKind};
+
+// SFTP protocol version 3 packet types (draft-ietf-secsh-filexfer-02)
+const SSH_FXP_INIT: u8 = 1;
+const SSH_FXP_VERSION: u8 = 2;
+const SSH_FXP_OPEN: u8 = 3;
+const SSH_FXP_CLOSE: u8 = 4;
+const SSH_FXP_READ: u8 = 5;
+const SSH_FXP_WRITE: u8 = 6;
+const SSH_FXP_OPENDIR: u8 = 11;
+const SSH_FXP_READDIR: u8 = 12;
+const SSH_FXP_REMOVE: u8 = 13;
+const SSH_FXP_MKDIR: u8 = 14;
+const SSH_FXP_RMDIR: u8 = 15;
+const SSH_FXP_STAT: u8 = 17;
+const SSH_FXP_RENAME: u8 = 18;
+const SSH_FXP_STATUS: u8 = 101;
+const SSH_FXP_HANDLE: u8 = 102;
+const SSH_FXP_DATA: u8 = 103;
+const SSH_FXP_NAME: u8 = 104;
+const SSH_FXP_ATTRS: u8 = 105;
+
+const SSH_FX_OK: u32 = 0;
+const SSH_FX_EOF: u32 = 1;
+const SSH_FX_NO_SUCH_FILE: u32 = 2;
+const SSH_FX_PERMISSION_DENIED: u32 = 3;
+
+const SSH_FXF_READ: u32 = 0x01;
+const SSH_FXF_WRITE: u32 = 0x02;
+const SSH_FXF_CREAT: u32 = 0x08;
+const SSH_FXF_TRUNC: u32 = 0x10;
+
+const SSH_FILEXFER_ATTR_SIZE: u32 = 0x01;
+const SSH_FILEXFER_ATTR_UIDGID: u32 = 0x02;
+const SSH_FILEXFER_ATTR_PERMISSIONS: u32 = 0x04;
+const SSH_FILEXFER_ATTR_ACMODTIME: u32 = 0x08;
+const SSH_FILEXFER_ATTR_EXTENDED: u32 = 0x8000_0000;
+
+const S_IFMT: u32 = 0o170000;
+const S_IFDIR: u32 = 0o040000;
+
+/// Size of each READ/WRITE request
+const TRANSFER_CHUNK_SIZE: usize = 32 * 1024;
+/// Upper bound for a single packet, protects against corrupted streams
+const MAX_PACKET_SIZE: usize = 256 * 1024;
+
+/// File attributes as transmitted by the SFTP protocol
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct SftpAttrs {
+    size: Option<u64>,
+    permissions: Option<u32>,
+    mtime: Option<u32>,
+}
+
+impl SftpAttrs {
+    fn is_dir(&self) -> bool {
+        self.permissions.map(|p| p & S_IFMT == S_IFDIR).unwrap_or(false)
+    }
+}
+
+/// Decoded server response
+#[derive(Debug)]
+enum SftpResponse {
+    Status { code: u32, message: String },
+    Handle(Bytes),
+    Data(Bytes),
+    Name(Vec<(String, SftpAttrs)>),
+    Attrs(SftpAttrs),
+}
+
+fn put_string(buf: &mut BytesMut, value: &[u8]) {
+    buf.put_u32(value.len() as u32);
+    buf.put_slice(value);
+}
+
+fn encode_packet(packet_type: u8, payload: &[u8]) -> Bytes {
+    let mut buf = BytesMut::with_capacity(payload.len() + 5);
+    buf.put_u32(payload.len() as u32 + 1);
+    buf.put_u8(packet_type);
+    buf.put_slice(payload);
+    buf.freeze()
+}
+
+fn protocol_error(message: &str) -> DomainError {
+    DomainError::new(ErrorKind::InternalError, "Sftp", format!("Protocol error: {}", message))
+}
+
+fn get_u32(buf: &mut Bytes) -> Result<u32, DomainError> {
+    if buf.remaining() < 4 {
+        return Err(protocol_error("truncated packet"));
+    }
+    Ok(buf.get_u32())
+}
+
+fn get_u64(buf: &mut Bytes) -> Result<u64, DomainError> {
+    if buf.remaining() < 8 {
+        return Err(protocol_error("truncated packet"));
+    }
+    Ok(buf.get_u64())
+}
+
+fn get_string(buf: &mut Bytes) -> Result<Bytes, DomainError> {
+    let len = get_u32(buf)? as usize;
+    if buf.remaining() < len {
+        return Err(protocol_error("truncated string"));
+    }
+    Ok(buf.split_to(len))
+}
+
+fn get_attrs(buf: &mut Bytes) -> Result<SftpAttrs, DomainError> {
+    let flags = get_u32(buf)?;
+    let mut attrs = SftpAttrs::default();
+
+    if flags & SSH_FILEXFER_ATTR_SIZE != 0 {
+        attrs.size = Some(get_u64(buf)?);
+    }
+    if flags & SSH_FILEXFER_ATTR_UIDGID != 0 {
+        get_u32(buf)?;
+        get_u32(buf)?;
+    }
+    if flags & SSH_FILEXFER_ATTR_PERMISSIONS != 0 {
+        attrs.permissions = Some(get_u32(buf)?);
+    }
+    if flags & SSH_FILEXFER_ATTR_ACMODTIME != 0 {
+        get_u32(buf)?;
+        attrs.mtime = Some(get_u32(buf)?);
+    }
+    if flags & SSH_FILEXFER_ATTR_EXTENDED != 0 {
+        let count = get_u32(buf)?;
+        for _ in 0..count {
+            get_string(buf)?;
+            get_string(buf)?;
+        }
+    }
+
+    Ok(attrs)
+}
+
+/// Decodes the body of a response packet (everything after the length prefix)
+fn decode_response(mut body: Bytes) -> Result<(u32, SftpResponse), DomainError> {
+    if body.remaining() < 1 {
+        return Err(protocol_error("empty packet"));
+    }
+    let packet_type = body.get_u8();
+    let id = get_u32(&mut body)?;
+
+    let response = match packet_type {
+        SSH_FXP_STATUS => {
+            let code = get_u32(&mut body)?;
+            // Some servers omit the message and language tag
+            let message = get_string(&mut body)
+                .map(|m| String::from_utf8_lossy(&m).to_string())
+                .unwrap_or_default();
+            SftpResponse::Status { code, message }
+        }
+        SSH_FXP_HANDLE => SftpResponse::Handle(get_string(&mut body)?),
+        SSH_FXP_DATA => SftpResponse::Data(get_string(&mut body)?),
+        SSH_FXP_NAME => {
+            let count = get_u32(&mut body)?;
+            let mut names = Vec::with_capacity(count.min(1024) as usize);
+            for _ in 0..count {
+                let filename = get_string(&mut body)?;
+                let _longname = get_string(&mut body)?;
+                let attrs = get_attrs(&mut body)?;
+                names.push((String::from_utf8_lossy(&filename).to_string(), attrs));
+            }
+            SftpResponse::Name(names)
+        }
+        SSH_FXP_ATTRS => SftpResponse::Attrs(get_attrs(&mut body)?),
+        other => return Err(protocol_error(&format!("unexpected packet type {}", other))),
+    };
+
+    Ok((id, response))
+}
+
+fn status_error(code: u32, message: &str, path: &str) -> DomainError {
+    match code {
+        SSH_FX_NO_SUCH_FILE => DomainError::not_found("ExternalFile", path),
+        SSH_FX_PERMISSION_DENIED => DomainError::access_denied("ExternalFile", format!("Permission denied: {}", path)),
+        _ => DomainError::new(
+            ErrorKind::InternalError,
+            "Sftp",
+            format!("SFTP operation on '{}' failed ({}): {}", path, code, message),
+        ),
+    }
+}
+
+/// A single SFTP channel running over the system `ssh` client.
+///
+/// Sessions are cheap to open because every mount shares an SSH
+/// ControlMaster connection; the semaphore permit bounds how many
+/// channels a mount keeps open at the same time.
+struct SftpSession {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    next_id: u32,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl SftpSession {
+    async fn send(&mut self, packet_type: u8, payload: BytesMut) -> Result<u32, DomainError> {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+
+        let mut body = BytesMut::with_capacity(payload.len() + 4);
+        body.put_u32(id);
+        body.put_slice(&payload);
+
+        self.stdin
+            .write_all(&encode_packet(packet_type, &body))
+            .await
+            .map_err(|e| DomainError::internal_error("Sftp", format!("Failed to write to ssh: {}", e)))?;
+        Ok(id)
+    }
+
+    async fn read_packet(&mut self) -> Result<Bytes, DomainError> {
+        let len = self
+            .stdout
+            .read_u32()
+            .await
+            .map_err(|e| DomainError::internal_error("Sftp", format!("Connection closed: {}", e)))? as usize;
+        if len == 0 || len > MAX_PACKET_SIZE {
+            return Err(protocol_error("invalid packet length"));
+        }
+
+        let mut body = vec![0u8; len];
+        self.stdout
+            .read_exact(&mut body)
+            .await
+            .map_err(|e| DomainError::internal_error("Sftp", format!("Connection closed: {}", e)))?;
+        Ok(Bytes::from(body))
+    }
+
+    async fn request(&mut self, packet_type: u8, payload: BytesMut) -> Result<SftpResponse, DomainError> {
+        let id = self.send(packet_type, payload).await?;
+        let (response_id, response) = decode_response(self.read_packet().await?)?;
+        if response_id != id {
+            return Err(protocol_error("response id mismatch"));
+        }
+        Ok(response)
+    }
+
+    async fn expect_status(&mut self, packet_type: u8, payload: BytesMut, path: &str) -> Result<(), DomainError> {
+        match self.request(packet_type, payload).await? {
+            SftpResponse::Status { code: SSH_FX_OK, .. } => Ok(()),
+            SftpResponse::Status { code, message } => Err(status_error(code, &message, path)),
+            _ => Err(protocol_error("expected status")),
+        }
+    }
+
+    async fn expect_handle(&mut self, packet_type: u8, payload: BytesMut, path: &str) -> Result<Bytes, DomainError> {
+        match self.request(packet_type, payload).await? {
+            SftpResponse::Handle(handle) => Ok(handle),
+            SftpResponse::Status { code, message } => Err(status_error(code, &message, path)),
+            _ => Err(protocol_error("expected handle")),
+        }
+    }
+
+    async fn stat(&mut self, remote: &str) -> Result<SftpAttrs, DomainError> {
+        let mut payload = BytesMut::new();
+        put_string(&mut payload, remote.as_bytes());
+        match self.request(SSH_FXP_STAT, payload).await? {
+            SftpResponse::Attrs(attrs) => Ok(attrs),
+            SftpResponse::Status { code, message } => Err(status_error(code, &message, remote)),
+            _ => Err(protocol_error("expected attrs")),
+        }
+    }
+
+    async fn read_dir(&mut self, remote: &str) -> Result<Vec<(String, SftpAttrs)>, DomainError> {
+        let mut payload = BytesMut::new();
+        put_string(&mut payload, remote.as_bytes());
+        let handle = self.expect_handle(SSH_FXP_OPENDIR, payload, remote).await?;
+
+        let mut entries = Vec::new();
+        loop {
+            let mut payload = BytesMut::new();
+            put_string(&mut payload, &handle);
+            match self.request(SSH_FXP_READDIR, payload).await? {
+                SftpResponse::Name(names) => entries.extend(names),
+                SftpResponse::Status { code: SSH_FX_EOF, .. } => break,
+                SftpResponse::Status { code, message } => return Err(status_error(code, &message, remote)),
+                _ => return Err(protocol_error("expected name")),
+            }
+        }
+
+        self.close_handle(&handle).await?;
+        Ok(entries)
+    }
+
+    async fn open(&mut self, remote: &str, flags: u32) -> Result<Bytes, DomainError> {
+        let mut payload = BytesMut::new();
+        put_string(&mut payload, remote.as_bytes());
+        payload.put_u32(flags);
+        payload.put_u32(0); // no attributes
+        self.expect_handle(SSH_FXP_OPEN, payload, remote).await
+    }
+
+    /// Reads the next chunk of an open file, `None` at end of file
+    async fn read_chunk(&mut self, handle: &Bytes, offset: u64) -> Result<Option<Bytes>, DomainError> {
+        let mut payload = BytesMut::new();
+        put_string(&mut payload, handle);
+        payload.put_u64(offset);
+        payload.put_u32(TRANSFER_CHUNK_SIZE as u32);
+        match self.request(SSH_FXP_READ, payload).await? {
+            SftpResponse::Data(data) => Ok(Some(data)),
+            SftpResponse::Status { code: SSH_FX_EOF, .. } => Ok(None),
+            SftpResponse::Status { code, message } => Err(status_error(code, &message, "read")),
+            _ => Err(protocol_error("expected data")),
+        }
+    }
+
+    async fn write_chunk(&mut self, handle: &Bytes, offset: u64, data: &[u8]) -> Result<(), DomainError> {
+        let mut payload = BytesMut::with_capacity(data.len() + handle.len() + 16);
+        put_string(&mut payload, handle);
+        payload.put_u64(offset);
+        put_string(&mut payload, data);
+        self.expect_status(SSH_FXP_WRITE, payload, "write").await
+    }
+
+    async fn close_handle(&mut self, handle: &Bytes) -> Result<(), DomainError> {
+        let mut payload = BytesMut::new();
+        put_string(&mut payload, handle);
+        self.expect_status(SSH_FXP_CLOSE, payload, "close").await
+    }
+
+    async fn path_op(&mut self, packet_type: u8, remote: &str, with_attrs: bool) -> Result<(), DomainError> {
+        let mut payload = BytesMut::new();
+        put_string(&mut payload, remote.as_bytes());
+        if with_attrs {
+            payload.put_u32(0);
+        }
+        self.expect_status(packet_type, payload, remote).await
+    }
+
+    async fn shutdown(mut self) {
+        let _ = self.stdin.shutdown().await;
+        if let Err(e) = self.child.wait().await {
+            debug!("Error waiting for sftp channel: {}", e);
+        }
+    }
+}
+
+/// External storage backend for SFTP servers.
+///
+/// Authentication is key based (BatchMode); the key path and login name
+/// come from the credential vault entry referenced by the mount. The
+/// adapter relies on the system OpenSSH client for transport, host key
+/// verification and connection reuse (ControlMaster/ControlPersist).
+pub struct SftpStorageAdapter {
+    host: String,
+    port: u16,
+    username: Option<String>,
+    identity_file: Option<PathBuf>,
+    root: String,
+    control_path: PathBuf,
+    idle_secs: u64,
+    sessions: Arc<Semaphore>,
+    read_only: bool,
+    connect_timeout: Duration,
+}
+
+impl SftpStorageAdapter {
+    /// Creates an adapter for the given mount configuration
+    pub fn new(
+        mount: &ExternalMountConfig,
+        credential: Option<StoredCredential>,
+        config: &ExternalStorageConfig,
+    ) -> Result<Self, DomainError> {
+        let host = mount
+            .host
+            .clone()
+            .filter(|h| !h.is_empty())
+            .ok_or_else(|| DomainError::validation_error(format!("Mount '{}' requires a host", mount.name)))?;
+
+        let credential = credential.unwrap_or_default();
+
+        std::fs::create_dir_all(&config.spool_dir).map_err(|e| {
+            DomainError::internal_error("Sftp", format!("Cannot create spool directory: {}", e))
+        })?;
+
+        Ok(Self {
+            host,
+            port: mount.port.unwrap_or(22),
+            username: credential.username,
+            identity_file: credential.private_key_path,
+            root: mount.root.trim_end_matches('/').to_string(),
+            // %C expands to a hash of host, port and user, keeping socket paths short
+            control_path: config.spool_dir.join("sftp-%C"),
+            idle_secs: config.connection_idle_secs,
+            sessions: Arc::new(Semaphore::new(config.max_sessions_per_mount.max(1))),
+            read_only: mount.read_only,
+            connect_timeout: Duration::from_secs(15),
+        })
+    }
+
+    fn remote_path(&self, path: &str) -> String {
+        match (self.root.is_empty(), path.is_empty()) {
+            (true, true) => ".".to_string(),
+            (true, false) => path.to_string(),
+            (false, true) => self.root.clone(),
+            (false, false) => format!("{}/{}", self.root, path),
+        }
+    }
+
+    fn ssh_args(&self) -> Vec<String> {
+        let mut args = vec![
+            "-p".to_string(), self.port.to_string(),
+            "-o".to_string(), "BatchMode=yes".to_string(),
+            "-o".to_string(), "StrictHostKeyChecking=accept-new".to_string(),
+            "-o".to_string(), "ControlMaster=auto".to_string(),
+            "-o".to_string(), format!("ControlPath={}", self.control_path.display()),
+            "-o".to_string(), format!("ControlPersist={}", self.idle_secs),
+            "-o".to_string(), format!("ConnectTimeout={}", self.connect_timeout.as_secs()),
+            "-o".to_string(), "ServerAliveInterval=30".to_string(),
+        ];
+        if let Some(identity) = &self.identity_file {
+            args.push("-i".to_string());
+            args.push(identity.display().to_string());
+            args.push("-o".to_string());
+            args.push("IdentitiesOnly=yes".to_string());
+        }
+        if let Some(username) = &self.username {
+            args.push("-l".to_string());
+            args.push(username.clone());
+        }
+        args.push("-s".to_string());
+        args.push(self.host.clone());
+        args.push("sftp".to_string());
+        args
+    }
+
+    async fn open_session(&self) -> Result<SftpSession, DomainError> {
+        let permit = self
+            .sessions
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|_| DomainError::internal_error("Sftp", "Session pool closed"))?;
+
+        let mut child = Command::new("ssh")
+            .args(self.ssh_args())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| DomainError::internal_error("Sftp", format!("Failed to start ssh: {}", e)))?;
+
+        let stdin = child.stdin.take().ok_or_else(|| protocol_error("missing stdin"))?;
+        let stdout = child.stdout.take().ok_or_else(|| protocol_error("missing stdout"))?;
+
+        let mut session = SftpSession {
+            child,
+            stdin,
+            stdout: BufReader::new(stdout),
+            next_id: 1,
+            _permit: permit,
+        };
+
+        // INIT carries the version instead of a request id
+        let mut init = BytesMut::new();
+        init.put_u32(3);
+        let handshake = async {
+            session
+                .stdin
+                .write_all(&encode_packet(SSH_FXP_INIT, &init))
+                .await
+                .map_err(|e| DomainError::internal_error("Sftp", format!("Failed to write to ssh: {}", e)))?;
+            let mut body = session.read_packet().await?;
+            if body.get_u8() != SSH_FXP_VERSION {
+                return Err(protocol_error("expected version"));
+            }
+            Ok(())
+        };
+
+        match tokio::time::timeout(self.connect_timeout * 2, handshake).await {
+            Ok(Ok(())) => Ok(session),
+            Ok(Err(e)) => {
+                warn!("SFTP handshake with {} failed: {}", self.host, e);
+                Err(e)
+            }
+            Err(_) => Err(DomainError::timeout("Sftp", format!("Timed out connecting to {}", self.host))),
+        }
+    }
+
+    fn ensure_writable(&self) -> Result<(), DomainError> {
+        if self.read_only {
+            return Err(DomainError::access_denied("ExternalMount", "Mount is read-only"));
+        }
+        Ok(())
+    }
+
+    fn to_entry(path: &str, name: &str, attrs: &SftpAttrs) -> ExternalEntry {
+        let is_dir = attrs.is_dir();
+        ExternalEntry {
+            name: name.to_string(),
+            path: path.to_string(),
+            is_dir,
+            size: if is_dir { 0 } else { attrs.size.unwrap_or(0) },
+            modified_at: attrs.mtime.unwrap_or(0) as u64,
+        }
+    }
+}
+
+#[async_trait]
+impl ExternalStoragePort for SftpStorageAdapter {
+    fn backend(&self) -> &'static str {
+        "sftp"
+    }
+
+    fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    async fn list(&self, path: &str) -> Result<Vec<ExternalEntry>, DomainError> {
+        let mut session = self.open_session().await?;
+        let result = session.read_dir(&self.remote_path(path)).await;
+        session.shutdown().await;
+
+        Ok(result?
+            .into_iter()
+            .filter(|(name, _)| name != "." && name != "..")
+            .map(|(name, attrs)| {
+                let child_path = if path.is_empty() { name.clone() } else { format!("{}/{}", path, name) };
+                Self::to_entry(&child_path, &name, &attrs)
+            })
+            .collect())
+    }
+
+    async fn stat(&self, path: &str) -> Result<ExternalEntry, DomainError> {
+        let mut session = self.open_session().await?;
+        let result = session.stat(&self.remote_path(path)).await;
+        session.shutdown().await;
+
+        let name = path.rsplit('/').next().unwrap_or_default();
+        Ok(Self::to_entry(path, name, &result?))
+    }
+
+    async fn read_stream(&self, path: &str) -> Result<ExternalByteStream, DomainError> {
+        let remote = self.remote_path(path);
+        let mut session = self.open_session().await?;
+        let handle = match session.open(&remote, SSH_FXF_READ).await {
+            Ok(handle) => handle,
+            Err(e) => {
+                session.shutdown().await;
+                return Err(e);
+            }
+        };
+
+        let stream = async_stream::stream! {
+            let mut offset = 0u64;
+            loop {
+                match session.read_chunk(&handle, offset).await {
+                    Ok(Some(data)) => {
+                        offset += data.len() as u64;
+                        yield Ok(data);
+                    }
+                    Ok(None) => break,
+                    Err(e) => {
+                        yield Err(std::io::Error::other(e.to_string()));
+                        break;
+                    }
+                }
+            }
+            let _ = session.close_handle(&handle).await;
+            session.shutdown().await;
+        };
+
+        Ok(Box::pin(stream))
+    }
+
+    async fn write_stream(&self, path: &str, mut content: ExternalByteStream) -> Result<u64, DomainError> {
+        self.ensure_writable()?;
+
+        let remote = self.remote_path(path);
+        let mut session = self.open_session().await?;
+        let handle = match session.open(&remote, SSH_FXF_WRITE | SSH_FXF_CREAT | SSH_FXF_TRUNC).await {
+            Ok(handle) => handle,
+            Err(e) => {
+                session.shutdown().await;
+                return Err(e);
+            }
+        };
+
+        let mut offset = 0u64;
+        let mut result = Ok(());
+        'outer: while let Some(chunk) = content.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    result = Err(DomainError::internal_error("Sftp", format!("Upload stream failed: {}", e)));
+                    break;
+                }
+            };
+            for piece in chunk.chunks(TRANSFER_CHUNK_SIZE) {
+                if let Err(e) = session.write_chunk(&handle, offset, piece).await {
+                    result = Err(e);
+                    break 'outer;
+                }
+                offset += piece.len() as u64;
+            }
+        }
+
+        let close_result = session.close_handle(&handle).await;
+        session.shutdown().await;
+        result?;
+        close_result?;
+
+        Ok(offset)
+    }
+
+    async fn create_dir(&self, path: &str) -> Result<(), DomainError> {
+        self.ensure_writable()?;
+        let mut session = self.open_session().await?;
+        let result = session.path_op(SSH_FXP_MKDIR, &self.remote_path(path), true).await;
+        session.shutdown().await;
+        result
+    }
+
+    async fn delete(&self, path: &str) -> Result<(), DomainError> {
+        self.ensure_writable()?;
+        let remote = self.remote_path(path);
+        let mut session = self.open_session().await?;
+        let result = match session.stat(&remote).await {
+            Ok(attrs) if attrs.is_dir() => session.path_op(SSH_FXP_RMDIR, &remote, false).await,
+            Ok(_) => session.path_op(SSH_FXP_REMOVE, &remote, false).await,
+            Err(e) => Err(e),
+        };
+        session.shutdown().await;
+        result
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<(), DomainError> {
+        self.ensure_writable()?;
+        let (remote_from, remote_to) = (self.remote_path(from), self.remote_path(to));
+        let mut payload = BytesMut::new();
+        put_string(&mut payload, remote_from.as_bytes());
+        put_string(&mut payload, remote_to.as_bytes());
+
+        let mut session = self.open_session().await?;
+        let result = session.expect_status(SSH_FXP_RENAME, payload, &remote_from).await;
+        session.shutdown().await;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response_packet(packet_type: u8, id: u32, payload: &[u8]) -> Bytes {
+        let mut body = BytesMut::new();
+        body.put_u8(packet_type);
+        body.put_u32(id);
+        body.put_slice(payload);
+        body.freeze()
+    }
+
+    #[test]
+    fn test_encode_packet_prefixes_length_and_type() {
+        let packet = encode_packet(SSH_FXP_INIT, &[0, 0, 0, 3]);
+        assert_eq!(&packet[..], &[0, 0, 0, 5, SSH_FXP_INIT, 0, 0, 0, 3]);
+    }
+
+    #[test]
+    fn test_decode_status() {
+        let mut payload = BytesMut::new();
+        payload.put_u32(SSH_FX_NO_SUCH_FILE);
+        put_string(&mut payload, b"No such file");
+        put_string(&mut payload, b"");
+
+        let (id, response) = decode_response(response_packet(SSH_FXP_STATUS, 7, &payload)).unwrap();
+        assert_eq!(id, 7);
+        match response {
+            SftpResponse::Status { code, message } => {
+                assert_eq!(code, SSH_FX_NO_SUCH_FILE);
+                assert_eq!(message, "No such file");
+            }
+            other => panic!("unexpected response {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_decode_name_with_attributes() {
+        let mut payload = BytesMut::new();
+        payload.put_u32(1);
+        put_string(&mut payload, b"docs");
+        put_string(&mut payload, b"drwxr-xr-x 2 user user 4096 Jan 1 docs");
+        payload.put_u32(SSH_FILEXFER_ATTR_SIZE | SSH_FILEXFER_ATTR_UIDGID | SSH_FILEXFER_ATTR_PERMISSIONS | SSH_FILEXFER_ATTR_ACMODTIME);
+        payload.put_u64(4096);
+        payload.put_u32(1000);
+        payload.put_u32(1000);
+        payload.put_u32(S_IFDIR | 0o755);
+        payload.put_u32(1_700_000_000);
+        payload.put_u32(1_700_000_100);
+
+        let (_, response) = decode_response(response_packet(SSH_FXP_NAME, 1, &payload)).unwrap();
+        match response {
+            SftpResponse::Name(names) => {
+                assert_eq!(names.len(), 1);
+                assert_eq!(names[0].0, "docs");
+                assert!(names[0].1.is_dir());
+                assert_eq!(names[0].1.mtime, Some(1_700_000_100));
+            }
+            other => panic!("unexpected response {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_decode_rejects_truncated_packet() {
+        let packet = response_packet(SSH_FXP_HANDLE, 1, &[0, 0, 0, 9, 1]);
+        assert!(decode_response(packet).is_err());
+    }
+
+    #[test]
+    fn test_remote_path_joins_root() {
+        let mount = ExternalMountConfig {
+            name: "legacy".to_string(),
+            backend: "sftp".to_string(),
+            host: Some("files.example.com".to_string()),
+            port: None,
+            share: None,
+            root: "/srv/share/".to_string(),
+            credential: None,
+            read_only: false,
+            poll_interval_secs: None,
+            snapshot_subdir: None,
+            allowed_users: Vec::new(),
+            allowed_groups: Vec::new(),
+        };
+        let config = ExternalStorageConfig {
+            spool_dir: std::env::temp_dir().join("oxicloud-sftp-test"),
+            ..ExternalStorageConfig::default()
+        };
+        let adapter = SftpStorageAdapter::new(&mount, None, &config).unwrap();
+
+        assert_eq!(adapter.remote_path(""), "/srv/share");
+        assert_eq!(adapter.remote_path("a/b.txt"), "/srv/share/a/b.txt");
+        assert_eq!(adapter.port, 22);
+    }
+}
@@ -0,0 +1,542 @@
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use futures::StreamExt;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+use tokio_util::io::ReaderStream;
+use tracing::warn;
+
+use crate::application::ports::credential_ports::StoredCredential;
+use crate::application::ports::external_storage_ports::{
+    ExternalByteStream, ExternalEntry, ExternalStoragePort,
+};
+use crate::common::config::{ExternalMountConfig, ExternalStorageConfig};
+use crate::common::errors::{DomainError, ErrorKind};
+
+/// Length of the timestamp smbclient prints at the end of each listing line
+const LISTING_DATE_LEN: usize = 24;
+
+/// NT status codes meaning the server or share cannot be reached
+const UNREACHABLE_STATUSES: &[&str] = &[
+    "NT_STATUS_HOST_UNREACHABLE",
+    "NT_STATUS_NETWORK_UNREACHABLE",
+    "NT_STATUS_CONNECTION_REFUSED",
+    "NT_STATUS_CONNECTION_RESET",
+    "NT_STATUS_CONNECTION_DISCONNECTED",
+    "NT_STATUS_IO_TIMEOUT",
+    "NT_STATUS_BAD_NETWORK_NAME",
+    "NT_STATUS_BAD_NETWORK_PATH",
+    "NT_STATUS_UNSUCCESSFUL",
+];
+
+/// Punctuation allowed in path segments besides letters, digits and spaces.
+///
+/// Paths are sent inside smbclient's `-c` command string, where `"` ends
+/// the quoted argument and `;` starts a new command; only characters known
+/// to be inert there are let through.
+const PATH_PUNCTUATION: &str = "._-+,=@#&!'~$%()[]{}";
+
+/// Checks that a mount relative path is safe to quote in a smbclient command.
+///
+/// Every segment must be a plain file name: no `.` or `..`, no leading or
+/// trailing spaces and no characters outside the allow-list.
+fn validate_path(path: &str) -> Result<(), DomainError> {
+    if path.is_empty() {
+        return Ok(());
+    }
+    for segment in path.split('/') {
+        let valid = !segment.is_empty()
+            && segment != "."
+            && segment != ".."
+            && segment.trim() == segment
+            && segment.chars().all(|c| c.is_alphanumeric() || c == ' ' || PATH_PUNCTUATION.contains(c));
+        if !valid {
+            return Err(DomainError::validation_error(format!(
+                "SMB path segment '{}' contains unsupported characters",
+                segment.escape_default()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Parses one line of smbclient's `ls` output.
+///
+/// Lines look like `  report.pdf    A   48213  Tue Mar  4 10:21:07 2025`;
+/// the name may contain spaces, so the line is parsed from the right.
+fn parse_listing_line(line: &str) -> Option<(String, bool, u64, u64)> {
+    if !line.starts_with("  ") {
+        return None;
+    }
+    let line = line.trim_end();
+    if line.len() < LISTING_DATE_LEN + 4 || !line.is_char_boundary(line.len() - LISTING_DATE_LEN) {
+        return None;
+    }
+
+    let (rest, date) = line.split_at(line.len() - LISTING_DATE_LEN);
+    let modified = NaiveDateTime::parse_from_str(date.trim(), "%a %b %e %H:%M:%S %Y")
+        .ok()?
+        .and_utc()
+        .timestamp()
+        .max(0) as u64;
+
+    let rest = rest.trim_end();
+    let (rest, size) = rest.rsplit_once(char::is_whitespace)?;
+    let size = size.parse::<u64>().ok()?;
+
+    // The attribute column is a short run of flag letters (D, A, H, S, R, N)
+    let rest = rest.trim_end();
+    let (name, attrs) = match rest.rsplit_once(char::is_whitespace) {
+        Some((name, attrs)) if !attrs.is_empty() && attrs.chars().all(|c| "DAHSRNV".contains(c)) => (name, attrs),
+        _ => (rest, ""),
+    };
+
+    let name = name.trim();
+    if name.is_empty() || name == "." || name == ".." {
+        return None;
+    }
+
+    let is_dir = attrs.contains('D');
+    Some((name.to_string(), is_dir, if is_dir { 0 } else { size }, modified))
+}
+
+/// Maps smbclient output to a domain error, if it reports one
+fn classify_output(output: &str, path: &str) -> Option<DomainError> {
+    let status = output
+        .split(|c: char| c.is_whitespace() || c == ':' || c == ',')
+        .find(|token| token.starts_with("NT_STATUS_") && *token != "NT_STATUS_OK")?;
+
+    let error = match status {
+        "NT_STATUS_OBJECT_NAME_NOT_FOUND" | "NT_STATUS_OBJECT_PATH_NOT_FOUND" | "NT_STATUS_NO_SUCH_FILE" => {
+            DomainError::not_found("ExternalFile", path)
+        }
+        "NT_STATUS_OBJECT_NAME_COLLISION" => DomainError::already_exists("ExternalFile", path),
+        "NT_STATUS_ACCESS_DENIED" | "NT_STATUS_MEDIA_WRITE_PROTECTED" => {
+            DomainError::access_denied("ExternalFile", format!("Access denied: {}", path))
+        }
+        "NT_STATUS_LOGON_FAILURE" | "NT_STATUS_ACCOUNT_DISABLED" | "NT_STATUS_PASSWORD_EXPIRED" => {
+            DomainError::access_denied("Smb", format!("Authentication against the share failed ({})", status))
+        }
+        "NT_STATUS_DIRECTORY_NOT_EMPTY" => DomainError::new(
+            ErrorKind::InvalidInput,
+            "ExternalFile",
+            format!("Directory is not empty: {}", path),
+        ),
+        s if UNREACHABLE_STATUSES.contains(&s) => {
+            DomainError::unavailable("Smb", format!("The remote share is unreachable ({})", s))
+        }
+        s => DomainError::internal_error("Smb", format!("SMB operation on '{}' failed: {}", path, s)),
+    };
+
+    Some(error)
+}
+
+/// External storage backend for SMB/CIFS shares.
+///
+/// Commands are executed through the system `smbclient`; credentials are
+/// written to a private authentication file in the spool directory so they
+/// never show up in the process list.
+pub struct SmbStorageAdapter {
+    service: String,
+    port: Option<u16>,
+    root: String,
+    auth_file: Option<PathBuf>,
+    sessions: Arc<Semaphore>,
+    read_only: bool,
+    timeout: Duration,
+}
+
+impl SmbStorageAdapter {
+    /// Creates an adapter for the given mount configuration
+    pub fn new(
+        mount: &ExternalMountConfig,
+        credential: Option<StoredCredential>,
+        config: &ExternalStorageConfig,
+    ) -> Result<Self, DomainError> {
+        let host = mount
+            .host
+            .clone()
+            .filter(|h| !h.is_empty())
+            .ok_or_else(|| DomainError::validation_error(format!("Mount '{}' requires a host", mount.name)))?;
+        let share = mount
+            .share
+            .clone()
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| DomainError::validation_error(format!("Mount '{}' requires a share", mount.name)))?;
+
+        let auth_file = match credential {
+            Some(credential) => Some(Self::write_auth_file(&mount.name, &credential, config)?),
+            None => None,
+        };
+
+        Ok(Self {
+            service: format!("//{}/{}", host, share),
+            port: mount.port,
+            root: mount.root.trim_matches('/').to_string(),
+            auth_file,
+            sessions: Arc::new(Semaphore::new(config.max_sessions_per_mount.max(1))),
+            read_only: mount.read_only,
+            timeout: Duration::from_secs(30),
+        })
+    }
+
+    fn write_auth_file(
+        mount_name: &str,
+        credential: &StoredCredential,
+        config: &ExternalStorageConfig,
+    ) -> Result<PathBuf, DomainError> {
+        let io_error = |e: std::io::Error| {
+            DomainError::internal_error("Smb", format!("Cannot write authentication file: {}", e))
+        };
+
+        std::fs::create_dir_all(&config.spool_dir).map_err(io_error)?;
+
+        // "DOMAIN\user" selects a workgroup/domain explicitly
+        let username = credential.username.clone().unwrap_or_default();
+        let (domain, username) = match username.split_once('\\') {
+            Some((domain, user)) => (Some(domain.to_string()), user.to_string()),
+            None => (None, username),
+        };
+
+        let mut content = format!("username = {}\npassword = {}\n", username, credential.password.clone().unwrap_or_default());
+        if let Some(domain) = domain {
+            content.push_str(&format!("domain = {}\n", domain));
+        }
+
+        let safe_name: String = mount_name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        let path = config.spool_dir.join(format!("smb-{}.auth", safe_name));
+
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&path).map_err(io_error)?;
+        std::io::Write::write_all(&mut file, content.as_bytes()).map_err(io_error)?;
+
+        Ok(path)
+    }
+
+    /// Converts a mount relative path into a share path using backslashes
+    fn remote_path(&self, path: &str) -> Result<String, DomainError> {
+        validate_path(path)?;
+        let joined = match (self.root.is_empty(), path.is_empty()) {
+            (true, _) => path.to_string(),
+            (false, true) => self.root.clone(),
+            (false, false) => format!("{}/{}", self.root, path),
+        };
+        Ok(format!("\\{}", joined.replace('/', "\\")))
+    }
+
+    fn command(&self, commands: &str) -> Command {
+        let mut cmd = Command::new("smbclient");
+        cmd.arg(&self.service);
+        match &self.auth_file {
+            Some(auth_file) => {
+                cmd.arg("-A").arg(auth_file);
+            }
+            None => {
+                cmd.arg("-N");
+            }
+        }
+        if let Some(port) = self.port {
+            cmd.arg("-p").arg(port.to_string());
+        }
+        cmd.arg("-c").arg(commands);
+        cmd.kill_on_drop(true);
+        cmd
+    }
+
+    /// Runs a command batch and returns its combined output
+    async fn run(&self, commands: &str, path: &str) -> Result<String, DomainError> {
+        let _permit = self
+            .sessions
+            .acquire()
+            .await
+            .map_err(|_| DomainError::internal_error("Smb", "Session pool closed"))?;
+
+        let output = tokio::time::timeout(self.timeout, self.command(commands).output())
+            .await
+            .map_err(|_| DomainError::unavailable("Smb", format!("Timed out talking to {}", self.service)))?
+            .map_err(|e| DomainError::internal_error("Smb", format!("Failed to start smbclient: {}", e)))?;
+
+        let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+        text.push_str(&String::from_utf8_lossy(&output.stderr));
+
+        if let Some(error) = classify_output(&text, path) {
+            return Err(error);
+        }
+        if !output.status.success() {
+            warn!("smbclient exited with {} for {}: {}", output.status, self.service, text.trim());
+            return Err(DomainError::unavailable("Smb", format!("Cannot access {}", self.service)));
+        }
+
+        Ok(text)
+    }
+
+    fn ensure_writable(&self) -> Result<(), DomainError> {
+        if self.read_only {
+            return Err(DomainError::access_denied("ExternalMount", "Mount is read-only"));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ExternalStoragePort for SmbStorageAdapter {
+    fn backend(&self) -> &'static str {
+        "smb"
+    }
+
+    fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    async fn list(&self, path: &str) -> Result<Vec<ExternalEntry>, DomainError> {
+        let remote = self.remote_path(path)?;
+        let output = self.run(&format!("cd \"{}\"; ls", remote), path).await?;
+
+        Ok(output
+            .lines()
+            .filter_map(parse_listing_line)
+            .map(|(name, is_dir, size, modified_at)| ExternalEntry {
+                path: if path.is_empty() { name.clone() } else { format!("{}/{}", path, name) },
+                name,
+                is_dir,
+                size,
+                modified_at,
+            })
+            .collect())
+    }
+
+    async fn stat(&self, path: &str) -> Result<ExternalEntry, DomainError> {
+        if path.is_empty() {
+            // The share root always exists once it can be listed
+            self.list(path).await?;
+            return Ok(ExternalEntry {
+                name: String::new(),
+                path: String::new(),
+                is_dir: true,
+                size: 0,
+                modified_at: 0,
+            });
+        }
+
+        let (parent, name) = path.rsplit_once('/').unwrap_or(("", path));
+        self.list(parent)
+            .await?
+            .into_iter()
+            .find(|entry| entry.name == name)
+            .ok_or_else(|| DomainError::not_found("ExternalFile", path))
+    }
+
+    async fn read_stream(&self, path: &str) -> Result<ExternalByteStream, DomainError> {
+        let remote = self.remote_path(path)?;
+        let permit = self
+            .sessions
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|_| DomainError::internal_error("Smb", "Session pool closed"))?;
+
+        // "get <file> -" writes the file content to stdout
+        let mut child = self
+            .command(&format!("get \"{}\" -", remote))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| DomainError::internal_error("Smb", format!("Failed to start smbclient: {}", e)))?;
+
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| DomainError::internal_error("Smb", "Missing smbclient stdout"))?;
+
+        let stream = ReaderStream::new(stdout).map(move |chunk| {
+            // Keep the process and the session permit alive while streaming
+            let _ = (&child, &permit);
+            chunk
+        });
+
+        Ok(Box::pin(stream))
+    }
+
+    async fn write_stream(&self, path: &str, mut content: ExternalByteStream) -> Result<u64, DomainError> {
+        self.ensure_writable()?;
+        let remote = self.remote_path(path)?;
+        let _permit = self
+            .sessions
+            .acquire()
+            .await
+            .map_err(|_| DomainError::internal_error("Smb", "Session pool closed"))?;
+
+        // "put - <file>" reads the file content from stdin
+        let mut child = self
+            .command(&format!("put - \"{}\"", remote))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| DomainError::internal_error("Smb", format!("Failed to start smbclient: {}", e)))?;
+
+        let mut stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| DomainError::internal_error("Smb", "Missing smbclient stdin"))?;
+
+        let mut written = 0u64;
+        while let Some(chunk) = content.next().await {
+            let chunk = chunk.map_err(|e| DomainError::internal_error("Smb", format!("Upload stream failed: {}", e)))?;
+            stdin
+                .write_all(&chunk)
+                .await
+                .map_err(|e| DomainError::unavailable("Smb", format!("Connection to share lost: {}", e)))?;
+            written += chunk.len() as u64;
+        }
+        drop(stdin);
+
+        let output = child
+            .wait_with_output()
+            .await
+            .map_err(|e| DomainError::internal_error("Smb", format!("smbclient failed: {}", e)))?;
+        let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+        text.push_str(&String::from_utf8_lossy(&output.stderr));
+        if let Some(error) = classify_output(&text, path) {
+            return Err(error);
+        }
+
+        Ok(written)
+    }
+
+    async fn create_dir(&self, path: &str) -> Result<(), DomainError> {
+        self.ensure_writable()?;
+        let remote = self.remote_path(path)?;
+        self.run(&format!("mkdir \"{}\"", remote), path).await.map(|_| ())
+    }
+
+    async fn delete(&self, path: &str) -> Result<(), DomainError> {
+        self.ensure_writable()?;
+        let remote = self.remote_path(path)?;
+        let entry = self.stat(path).await?;
+        let command = if entry.is_dir { "rmdir" } else { "del" };
+        self.run(&format!("{} \"{}\"", command, remote), path).await.map(|_| ())
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<(), DomainError> {
+        self.ensure_writable()?;
+        let (remote_from, remote_to) = (self.remote_path(from)?, self.remote_path(to)?);
+        self.run(&format!("rename \"{}\" \"{}\"", remote_from, remote_to), from).await.map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_listing_line() {
+        let line = "  annual report.pdf                  A    48213  Tue Mar  4 10:21:07 2025";
+        let (name, is_dir, size, modified) = parse_listing_line(line).unwrap();
+        assert_eq!(name, "annual report.pdf");
+        assert!(!is_dir);
+        assert_eq!(size, 48213);
+        assert_eq!(modified, 1741083667);
+    }
+
+    #[test]
+    fn test_parse_listing_line_directory_and_dots() {
+        let dir = parse_listing_line("  Projects                            D        0  Mon Jan 13 08:00:00 2025").unwrap();
+        assert_eq!(dir.0, "Projects");
+        assert!(dir.1);
+
+        assert!(parse_listing_line("  .                                   D        0  Mon Jan 13 08:00:00 2025").is_none());
+        assert!(parse_listing_line("\t\t12345 blocks of size 1024. 678 blocks available").is_none());
+    }
+
+    /// `ls` output as smbclient prints it for a Windows share: dot entries,
+    /// hidden and system attributes, sizes wider than their column, a name
+    /// long enough to push the columns and the trailing free space summary
+    const SMBCLIENT_LISTING: &str = "  .                                   D        0  Tue Mar  4 10:21:07 2025
+  ..                                  D        0  Mon Jan 13 08:00:00 2025
+  $RECYCLE.BIN                      DHS        0  Thu Oct 10 14:02:11 2024
+  Budget (final) v2.xlsx              A    23552  Fri Feb 28 17:30:45 2025
+  desktop.ini                       AHS      282  Sat Dec  7 09:14:52 2024
+  DATA                                D        0  Wed Feb 12 16:45:31 2025
+  Plan A                              A      100  Wed Feb 12 16:45:31 2025
+  debian-12.iso                       N 4700000000  Fri Nov  1 12:00:00 2024
+  Informe año fiscal 2024 - revisión definitiva para la junta.docx      A   118272  Mon Mar  3 09:05:00 2025
+
+		98566144 blocks of size 1024. 43451392 blocks available
+";
+
+    #[test]
+    fn test_parse_smbclient_listing() {
+        let entries: Vec<_> = SMBCLIENT_LISTING.lines().filter_map(parse_listing_line).collect();
+        let names: Vec<_> = entries.iter().map(|(name, ..)| name.as_str()).collect();
+        assert_eq!(names, [
+            "$RECYCLE.BIN",
+            "Budget (final) v2.xlsx",
+            "desktop.ini",
+            "DATA",
+            "Plan A",
+            "debian-12.iso",
+            "Informe año fiscal 2024 - revisión definitiva para la junta.docx",
+        ]);
+
+        let by_name = |wanted: &str| entries.iter().find(|(name, ..)| name == wanted).unwrap();
+        assert!(by_name("$RECYCLE.BIN").1);
+        assert!(by_name("DATA").1);
+        assert!(!by_name("Plan A").1);
+        assert_eq!(by_name("Plan A").2, 100);
+        assert_eq!(by_name("debian-12.iso").2, 4_700_000_000);
+        assert_eq!(by_name("desktop.ini").2, 282);
+    }
+
+    #[test]
+    fn test_validate_path() {
+        for path in ["", "Projects", "Budget (final) v2.xlsx", "Informe año/Q1 & Q2 [draft].docx", "$RECYCLE.BIN/Bob's notes"] {
+            assert!(validate_path(path).is_ok(), "{}", path);
+        }
+        for path in [
+            "a\"; !ls",
+            "a; del *",
+            "a\\b",
+            "../secret",
+            "a/./b",
+            "a//b",
+            " leading",
+            "trailing ",
+            "line\nbreak",
+            "tab\there",
+            "wild*",
+            "pipe|name",
+        ] {
+            assert!(validate_path(path).is_err(), "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_classify_output() {
+        let err = classify_output("tree connect failed: NT_STATUS_BAD_NETWORK_NAME", "").unwrap();
+        assert_eq!(err.kind, ErrorKind::Unavailable);
+
+        let err = classify_output("NT_STATUS_OBJECT_NAME_NOT_FOUND opening remote file \\a.txt", "a.txt").unwrap();
+        assert_eq!(err.kind, ErrorKind::NotFound);
+
+        let err = classify_output("session setup failed: NT_STATUS_LOGON_FAILURE", "").unwrap();
+        assert_eq!(err.kind, ErrorKind::AccessDenied);
+
+        assert!(classify_output("putting file - as \\a.txt", "a.txt").is_none());
+    }
+}
@@ -0,0 +1,241 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use openssl::ssl::{SslConnector, SslMethod};
+
+use crate::application::ports::mail_ports::{MailSenderPort, OutgoingMail};
+use crate::common::config::{MailConfig, SmtpSecurity};
+use crate::common::errors::DomainError;
+
+/// Tiempo máximo de espera de cada operación con el servidor
+const SMTP_TIMEOUT: Duration = Duration::from_secs(20);
+
+fn smtp_error(message: impl Into<String>) -> DomainError {
+    DomainError::internal_error("Mail", message.into())
+}
+
+/// Cliente SMTP mínimo para enviar mensajes de texto a un único destinatario.
+///
+/// Admite conexiones en claro, STARTTLS y TLS directo, y AUTH PLAIN. La
+/// conversación es bloqueante y se hace en un hilo aparte para no frenar el
+/// runtime; abre una conexión por mensaje, que basta para correos sueltos.
+#[derive(Clone)]
+pub struct SmtpMailSender {
+    config: MailConfig,
+    host: String,
+}
+
+impl SmtpMailSender {
+    /// Crea el cliente si hay un servidor SMTP configurado
+    pub fn from_config(config: &MailConfig) -> Option<Self> {
+        let host = config.smtp_host.clone()?;
+        Some(Self { config: config.clone(), host })
+    }
+
+    fn deliver(&self, mail: &OutgoingMail) -> Result<(), DomainError> {
+        let address = (self.host.as_str(), self.config.smtp_port)
+            .to_socket_addrs()
+            .map_err(|e| smtp_error(format!("No se pudo resolver {}: {}", self.host, e)))?
+            .next()
+            .ok_or_else(|| smtp_error(format!("No se pudo resolver {}", self.host)))?;
+        let tcp = TcpStream::connect_timeout(&address, SMTP_TIMEOUT)
+            .map_err(|e| smtp_error(format!("No se pudo conectar con {}: {}", self.host, e)))?;
+        tcp.set_read_timeout(Some(SMTP_TIMEOUT)).map_err(io_error)?;
+        tcp.set_write_timeout(Some(SMTP_TIMEOUT)).map_err(io_error)?;
+
+        let message = format_message(&self.config.from, mail);
+        match self.config.security {
+            SmtpSecurity::None => {
+                let mut session = SmtpSession::new(tcp);
+                session.expect(220)?;
+                session.hello()?;
+                self.transaction(&mut session, mail, &message)
+            }
+            SmtpSecurity::StartTls => {
+                let mut session = SmtpSession::new(tcp);
+                session.expect(220)?;
+                session.hello()?;
+                session.command("STARTTLS", 220)?;
+                let mut session = SmtpSession::new(self.tls(session.into_inner())?);
+                session.hello()?;
+                self.transaction(&mut session, mail, &message)
+            }
+            SmtpSecurity::Tls => {
+                let mut session = SmtpSession::new(self.tls(tcp)?);
+                session.expect(220)?;
+                session.hello()?;
+                self.transaction(&mut session, mail, &message)
+            }
+        }
+    }
+
+    fn tls(&self, tcp: TcpStream) -> Result<openssl::ssl::SslStream<TcpStream>, DomainError> {
+        let connector = SslConnector::builder(SslMethod::tls_client())
+            .map_err(|e| smtp_error(format!("No se pudo preparar TLS: {}", e)))?
+            .build();
+        connector.connect(&self.host, tcp)
+            .map_err(|e| smtp_error(format!("Fallo en la negociación TLS con {}: {}", self.host, e)))
+    }
+
+    /// Autenticación y envío de un mensaje en una sesión ya saludada
+    fn transaction<S: Read + Write>(&self, session: &mut SmtpSession<S>, mail: &OutgoingMail, message: &str) -> Result<(), DomainError> {
+        if let Some(username) = &self.config.username {
+            let credentials = STANDARD.encode(format!("\0{}\0{}", username, self.config.password));
+            session.command(&format!("AUTH PLAIN {}", credentials), 235)?;
+        }
+        session.command(&format!("MAIL FROM:<{}>", envelope_address(&self.config.from)), 250)?;
+        session.command(&format!("RCPT TO:<{}>", envelope_address(&mail.to)), 250)?;
+        session.command("DATA", 354)?;
+        session.send(&format!("{}\r\n.", message))?;
+        session.expect(250)?;
+        // El mensaje ya está aceptado; un fallo al despedirse no importa
+        let _ = session.command("QUIT", 221);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MailSenderPort for SmtpMailSender {
+    async fn send(&self, mail: OutgoingMail) -> Result<(), DomainError> {
+        if [&mail.to, &mail.subject].iter().any(|value| value.contains(['\r', '\n'])) {
+            return Err(DomainError::validation_error("Mail headers cannot contain line breaks"));
+        }
+
+        let sender = self.clone();
+        let to = mail.to.clone();
+        tokio::task::spawn_blocking(move || sender.deliver(&mail))
+            .await
+            .map_err(|e| smtp_error(format!("Envío de correo interrumpido: {}", e)))??;
+
+        tracing::info!("Correo enviado a {}", to);
+        Ok(())
+    }
+}
+
+/// Conversación con el servidor sobre una conexión en claro o cifrada
+struct SmtpSession<S: Read + Write> {
+    stream: BufReader<S>,
+}
+
+impl<S: Read + Write> SmtpSession<S> {
+    fn new(stream: S) -> Self {
+        Self { stream: BufReader::new(stream) }
+    }
+
+    /// Conexión subyacente; solo es seguro tras una respuesta completa
+    fn into_inner(self) -> S {
+        self.stream.into_inner()
+    }
+
+    fn hello(&mut self) -> Result<(), DomainError> {
+        self.command("EHLO oxicloud", 250)
+    }
+
+    fn send(&mut self, line: &str) -> Result<(), DomainError> {
+        let stream = self.stream.get_mut();
+        stream.write_all(line.as_bytes()).map_err(io_error)?;
+        stream.write_all(b"\r\n").map_err(io_error)?;
+        stream.flush().map_err(io_error)
+    }
+
+    fn command(&mut self, line: &str, expected: u16) -> Result<(), DomainError> {
+        self.send(line)?;
+        self.expect(expected)
+    }
+
+    /// Lee una respuesta, de una o varias líneas, y comprueba su código
+    fn expect(&mut self, expected: u16) -> Result<(), DomainError> {
+        let mut reply = String::new();
+        loop {
+            let mut line = String::new();
+            if self.stream.read_line(&mut line).map_err(io_error)? == 0 {
+                return Err(smtp_error("El servidor SMTP cerró la conexión"));
+            }
+            reply.push_str(&line);
+            // Las líneas intermedias llevan un guion tras el código: "250-..."
+            if line.len() < 4 || line.as_bytes()[3] != b'-' {
+                break;
+            }
+        }
+
+        match reply.get(..3).and_then(|code| code.parse::<u16>().ok()) {
+            Some(code) if code == expected => Ok(()),
+            _ => Err(smtp_error(format!("Respuesta inesperada del servidor SMTP: {}", reply.trim_end()))),
+        }
+    }
+}
+
+fn io_error(e: std::io::Error) -> DomainError {
+    smtp_error(format!("Error de comunicación con el servidor SMTP: {}", e))
+}
+
+/// Dirección de una cabecera como `Nombre <correo>`, o la cabecera entera
+fn envelope_address(header: &str) -> &str {
+    match (header.rfind('<'), header.rfind('>')) {
+        (Some(start), Some(end)) if start < end => &header[start + 1..end],
+        _ => header.trim(),
+    }
+}
+
+/// Cabecera codificada según RFC 2047 si no es ASCII
+fn encode_header(value: &str) -> String {
+    if value.is_ascii() {
+        value.to_string()
+    } else {
+        format!("=?UTF-8?B?{}?=", STANDARD.encode(value))
+    }
+}
+
+/// Mensaje completo con cabeceras y el cuerpo en base64, listo para DATA
+fn format_message(from: &str, mail: &OutgoingMail) -> String {
+    let domain = envelope_address(from).rsplit_once('@').map(|(_, domain)| domain).unwrap_or("localhost");
+    let body = STANDARD.encode(mail.body.replace("\r\n", "\n").replace('\n', "\r\n"));
+    let body_lines: Vec<&str> = body.as_bytes()
+        .chunks(76)
+        .map(|chunk| std::str::from_utf8(chunk).unwrap_or_default())
+        .collect();
+
+    format!(
+        "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nMessage-ID: <{}@{}>\r\nMIME-Version: 1.0\r\n\
+         Content-Type: text/plain; charset=utf-8\r\nContent-Transfer-Encoding: base64\r\n\r\n{}",
+        from,
+        mail.to,
+        encode_header(&mail.subject),
+        chrono::Utc::now().to_rfc2822(),
+        uuid::Uuid::new_v4(),
+        domain,
+        body_lines.join("\r\n"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_envelope_address() {
+        assert_eq!(envelope_address("OxiCloud <noreply@example.com>"), "noreply@example.com");
+        assert_eq!(envelope_address(" ana@example.com "), "ana@example.com");
+    }
+
+    #[test]
+    fn test_format_message() {
+        let message = format_message("OxiCloud <noreply@example.com>", &OutgoingMail {
+            to: "ana@example.com".to_string(),
+            subject: "Código de acceso".to_string(),
+            body: "Line one\n.\nLine three".to_string(),
+        });
+        assert!(message.contains("Subject: =?UTF-8?B?"));
+        assert!(message.contains("Message-ID: <"));
+        assert!(message.contains("@example.com>\r\n"));
+
+        let (_, body) = message.split_once("\r\n\r\n").unwrap();
+        // En base64 ninguna línea empieza por un punto que cierre DATA antes de tiempo
+        assert!(body.lines().all(|line| !line.starts_with('.') && line.len() <= 76));
+        assert_eq!(STANDARD.decode(body.replace("\r\n", "")).unwrap(), b"Line one\r\n.\r\nLine three");
+    }
+}
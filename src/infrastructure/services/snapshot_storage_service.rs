@@ -0,0 +1,290 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::fs;
+use tokio_util::io::ReaderStream;
+
+use crate::application::ports::external_storage_ports::{
+    ExternalByteStream, ExternalEntry, ExternalStoragePort,
+};
+use crate::common::config::ExternalMountConfig;
+use crate::common::errors::DomainError;
+
+/// Read-only backend exposing filesystem snapshots (ZFS, btrfs, ...).
+///
+/// The mount root is the directory that holds one sub-directory per
+/// snapshot, e.g. `/tank/data/.zfs/snapshot` or `/data/.snapshots`. Each
+/// snapshot shows up as a top-level folder of the mount. For layouts that
+/// nest the tree inside each snapshot (snapper uses `<n>/snapshot`), the
+/// optional `snapshot_subdir` is appended to the snapshot directory.
+///
+/// Snapshots of the storage volume hold every user's files, so the mount
+/// is per user: each user only sees their own home folder inside each
+/// snapshot (see `for_user`), and only the snapshots that contain it.
+pub struct SnapshotStorageAdapter {
+    root: PathBuf,
+    snapshot_subdir: Option<String>,
+    /// Home folder the adapter is limited to; `None` for the whole volume
+    home: Option<String>,
+}
+
+impl SnapshotStorageAdapter {
+    /// Creates an adapter for the given mount configuration
+    pub fn new(mount: &ExternalMountConfig) -> Result<Self, DomainError> {
+        if mount.root.is_empty() {
+            return Err(DomainError::validation_error(format!(
+                "Mount '{}' requires the snapshot directory as root", mount.name
+            )));
+        }
+
+        let root = std::fs::canonicalize(&mount.root).map_err(|e| {
+            DomainError::unavailable("Snapshot", format!("Snapshot directory '{}' is not accessible: {}", mount.root, e))
+        })?;
+
+        Ok(Self {
+            root,
+            snapshot_subdir: mount
+                .snapshot_subdir
+                .as_ref()
+                .map(|s| s.trim_matches('/').to_string())
+                .filter(|s| !s.is_empty()),
+            home: None,
+        })
+    }
+
+    /// Directory of a snapshot that the mount exposes: the snapshot itself,
+    /// its `snapshot_subdir` and, once scoped, the user's home folder in it
+    fn snapshot_base(&self, snapshot: &str) -> PathBuf {
+        let mut base = self.root.join(snapshot);
+        if let Some(subdir) = &self.snapshot_subdir {
+            base = base.join(subdir);
+        }
+        if let Some(home) = &self.home {
+            base = base.join(home);
+        }
+        base
+    }
+
+    /// Resolves a mount relative path (`<snapshot>/<path inside snapshot>`)
+    /// to a local path, refusing anything that escapes the snapshot tree
+    async fn resolve(&self, path: &str) -> Result<PathBuf, DomainError> {
+        if path.is_empty() {
+            return Ok(self.root.clone());
+        }
+
+        let (snapshot, inner) = path.split_once('/').unwrap_or((path, ""));
+        let base = self.snapshot_base(snapshot);
+        let local = if inner.is_empty() { base.clone() } else { base.join(inner) };
+
+        // Canonicalize to follow symlinks, then make sure we are still inside
+        let canonical = fs::canonicalize(&local)
+            .await
+            .map_err(|_| DomainError::not_found("ExternalFile", path))?;
+        let canonical_base = fs::canonicalize(&base)
+            .await
+            .map_err(|_| DomainError::not_found("ExternalFile", path))?;
+        if !canonical.starts_with(&canonical_base) {
+            return Err(DomainError::access_denied("ExternalFile", format!("Path escapes the snapshot: {}", path)));
+        }
+
+        Ok(canonical)
+    }
+
+    async fn entry_for(path: &str, name: &str, local: &Path) -> Result<ExternalEntry, DomainError> {
+        let metadata = fs::metadata(local)
+            .await
+            .map_err(|_| DomainError::not_found("ExternalFile", path))?;
+        let modified_at = metadata
+            .modified()
+            .ok()
+            .and_then(|m| m.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        Ok(ExternalEntry {
+            name: name.to_string(),
+            path: path.to_string(),
+            is_dir: metadata.is_dir(),
+            size: if metadata.is_dir() { 0 } else { metadata.len() },
+            modified_at,
+        })
+    }
+
+    fn read_only_error() -> DomainError {
+        DomainError::access_denied("Snapshot", "Snapshots are read-only")
+    }
+}
+
+#[async_trait]
+impl ExternalStoragePort for SnapshotStorageAdapter {
+    fn backend(&self) -> &'static str {
+        "snapshot"
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
+    fn is_per_user(&self) -> bool {
+        self.home.is_none()
+    }
+
+    fn for_user(&self, username: &str) -> Result<Arc<dyn ExternalStoragePort>, DomainError> {
+        if username.is_empty() || username.contains(['/', '\\']) {
+            return Err(DomainError::access_denied("Snapshot", format!("Invalid username: {}", username)));
+        }
+        Ok(Arc::new(Self {
+            root: self.root.clone(),
+            snapshot_subdir: self.snapshot_subdir.clone(),
+            home: Some(format!("Mi Carpeta - {}", username)),
+        }))
+    }
+
+    async fn list(&self, path: &str) -> Result<Vec<ExternalEntry>, DomainError> {
+        let local = self.resolve(path).await?;
+        let mut reader = fs::read_dir(&local)
+            .await
+            .map_err(|_| DomainError::not_found("ExternalFile", path))?;
+
+        let mut entries = Vec::new();
+        while let Some(item) = reader
+            .next_entry()
+            .await
+            .map_err(|e| DomainError::internal_error("Snapshot", format!("Failed to read directory: {}", e)))?
+        {
+            let name = item.file_name().to_string_lossy().to_string();
+            let child_path = if path.is_empty() { name.clone() } else { format!("{}/{}", path, name) };
+
+            // Top-level entries are snapshots; skip anything that is not a
+            // directory and the snapshots taken before the user's home existed
+            if path.is_empty() && !self.snapshot_base(&name).is_dir() {
+                continue;
+            }
+
+            // Entries that disappear or cannot be read while listing are skipped
+            if let Ok(entry) = Self::entry_for(&child_path, &name, &item.path()).await {
+                entries.push(entry);
+            }
+        }
+
+        Ok(entries)
+    }
+
+    async fn stat(&self, path: &str) -> Result<ExternalEntry, DomainError> {
+        let local = self.resolve(path).await?;
+        let name = path.rsplit('/').next().unwrap_or_default();
+        Self::entry_for(path, name, &local).await
+    }
+
+    async fn read_stream(&self, path: &str) -> Result<ExternalByteStream, DomainError> {
+        let local = self.resolve(path).await?;
+        let file = fs::File::open(&local)
+            .await
+            .map_err(|_| DomainError::not_found("ExternalFile", path))?;
+        Ok(Box::pin(ReaderStream::new(file)))
+    }
+
+    async fn write_stream(&self, _path: &str, _content: ExternalByteStream) -> Result<u64, DomainError> {
+        Err(Self::read_only_error())
+    }
+
+    async fn create_dir(&self, _path: &str) -> Result<(), DomainError> {
+        Err(Self::read_only_error())
+    }
+
+    async fn delete(&self, _path: &str) -> Result<(), DomainError> {
+        Err(Self::read_only_error())
+    }
+
+    async fn rename(&self, _from: &str, _to: &str) -> Result<(), DomainError> {
+        Err(Self::read_only_error())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    fn mount_config(root: &Path, subdir: Option<&str>) -> ExternalMountConfig {
+        ExternalMountConfig {
+            name: "Snapshots".to_string(),
+            backend: "snapshot".to_string(),
+            host: None,
+            port: None,
+            share: None,
+            root: root.to_string_lossy().to_string(),
+            credential: None,
+            read_only: true,
+            poll_interval_secs: None,
+            snapshot_subdir: subdir.map(String::from),
+            allowed_users: Vec::new(),
+            allowed_groups: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lists_snapshots_and_reads_files() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(temp.path().join("daily-1/snapshot/docs")).unwrap();
+        std::fs::write(temp.path().join("daily-1/snapshot/docs/a.txt"), b"hello").unwrap();
+        std::fs::write(temp.path().join("stray-file"), b"x").unwrap();
+
+        let adapter = SnapshotStorageAdapter::new(&mount_config(temp.path(), Some("snapshot"))).unwrap();
+
+        let snapshots = adapter.list("").await.unwrap();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].name, "daily-1");
+        assert!(snapshots[0].is_dir);
+
+        let files = adapter.list("daily-1/docs").await.unwrap();
+        assert_eq!(files[0].path, "daily-1/docs/a.txt");
+        assert_eq!(files[0].size, 5);
+
+        let mut stream = adapter.read_stream("daily-1/docs/a.txt").await.unwrap();
+        let chunk = stream.next().await.unwrap().unwrap();
+        assert_eq!(&chunk[..], b"hello");
+    }
+
+    #[tokio::test]
+    async fn test_scopes_snapshots_to_user_home() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(temp.path().join("daily-1/Mi Carpeta - ana/docs")).unwrap();
+        std::fs::write(temp.path().join("daily-1/Mi Carpeta - ana/docs/a.txt"), b"ana").unwrap();
+        std::fs::create_dir_all(temp.path().join("daily-1/Mi Carpeta - luis")).unwrap();
+        std::fs::write(temp.path().join("daily-1/Mi Carpeta - luis/b.txt"), b"luis").unwrap();
+        std::fs::create_dir_all(temp.path().join("daily-0/Mi Carpeta - luis")).unwrap();
+
+        let adapter = SnapshotStorageAdapter::new(&mount_config(temp.path(), None)).unwrap();
+        assert!(adapter.is_per_user());
+        assert!(adapter.for_user("../luis").is_err());
+
+        let ana = adapter.for_user("ana").unwrap();
+        assert!(!ana.is_per_user());
+        let snapshots = ana.list("").await.unwrap();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].name, "daily-1");
+
+        let files = ana.list("daily-1/docs").await.unwrap();
+        assert_eq!(files[0].path, "daily-1/docs/a.txt");
+        assert!(ana.stat("daily-1/b.txt").await.is_err());
+        assert!(ana.list("daily-0").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rejects_writes_and_escapes() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(temp.path().join("snaps/s1")).unwrap();
+        std::fs::write(temp.path().join("secret.txt"), b"secret").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(temp.path().join("secret.txt"), temp.path().join("snaps/s1/link")).unwrap();
+
+        let adapter = SnapshotStorageAdapter::new(&mount_config(&temp.path().join("snaps"), None)).unwrap();
+
+        assert!(adapter.create_dir("s1/new").await.is_err());
+        assert!(adapter.delete("s1").await.is_err());
+        #[cfg(unix)]
+        assert!(adapter.read_stream("s1/link").await.is_err());
+    }
+}
@@ -0,0 +1,433 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime};
+
+use async_trait::async_trait;
+use tokio::time;
+use tracing::{debug, error, info, warn};
+
+use crate::application::dtos::execution_plan_dto::ExecutionPlanDto;
+use crate::application::dtos::storage_gc_dto::{StorageGcReportDto, StorageGcStatsDto};
+use crate::application::ports::image_fingerprint_ports::ImageFingerprintPort;
+use crate::application::ports::inbound::FileUseCase;
+use crate::application::ports::placeholder_ports::ImagePlaceholderPort;
+use crate::application::ports::storage_gc_ports::StorageGcUseCase;
+use crate::application::ports::upload_session_ports::UploadSessionUseCase;
+use crate::application::services::execution_plan::ExecutionPlan;
+use crate::common::errors::{DomainError, ErrorKind};
+use crate::domain::repositories::trash_repository::TrashRepository;
+
+/// Extensión de los ficheros temporales de las subidas por partes
+const UPLOAD_PART_EXTENSION: &str = "part";
+
+/// Si el nombre es el de un temporal de escritura atómica (`NamedTempFile`
+/// crea `.tmp` seguido de seis caracteres alfanuméricos)
+fn is_atomic_write_leftover(name: &str) -> bool {
+    name.len() == 10
+        && name.starts_with(".tmp")
+        && name[4..].chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// Último momento en que cambió una entrada.
+///
+/// En Unix se tiene en cuenta también el ctime, que sí cambia al mover un
+/// fichero (mover a la papelera conserva la fecha de modificación).
+fn last_change(metadata: &Metadata) -> SystemTime {
+    let modified = metadata.modified().unwrap_or_else(|_| SystemTime::now());
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        let changed = SystemTime::UNIX_EPOCH + Duration::from_secs(metadata.ctime().max(0) as u64);
+        modified.max(changed)
+    }
+    #[cfg(not(unix))]
+    {
+        modified
+    }
+}
+
+/// Tamaño de un fichero o de todo un directorio
+fn disk_usage(path: &Path) -> u64 {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else { return 0 };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|entry| disk_usage(&entry.path())).sum())
+        .unwrap_or(0)
+}
+
+fn remove_entry(path: &Path) -> std::io::Result<()> {
+    if std::fs::symlink_metadata(path)?.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    }
+}
+
+/// Entradas directas de un directorio (vacío si no existe)
+fn list_dir(path: &Path) -> Vec<(PathBuf, String, Metadata)> {
+    let Ok(entries) = std::fs::read_dir(path) else { return Vec::new() };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            Some((entry.path(), entry.file_name().to_string_lossy().into_owned(), metadata))
+        })
+        .collect()
+}
+
+/// Busca temporales de escrituras atómicas en todo el árbol
+fn find_atomic_write_leftovers(root: &Path, found: &mut Vec<(PathBuf, Metadata)>) {
+    for (path, name, metadata) in list_dir(root) {
+        if metadata.is_dir() {
+            find_atomic_write_leftovers(&path, found);
+        } else if metadata.is_file() && is_atomic_write_leftover(&name) {
+            found.push((path, metadata));
+        }
+    }
+}
+
+/// Recolector de basura del almacenamiento.
+///
+/// Elimina lo que ya nadie referencia: ficheros de la papelera sin entrada en
+/// el índice, ficheros temporales de subidas sin sesión, temporales de
+/// escrituras atómicas interrumpidas y miniaturas (placeholders) o huellas de
+/// imágenes de archivos borrados.
+///
+/// Nada se borra antes del periodo de gracia: los ficheros tienen que llevar
+/// ese tiempo sin cambios y las entradas de metadatos tienen que haber
+/// aparecido huérfanas en pasadas separadas por ese tiempo.
+pub struct StorageGcService {
+    storage_root: PathBuf,
+    grace_period: Duration,
+    file_service: Arc<dyn FileUseCase>,
+    trash_repository: Option<Arc<dyn TrashRepository>>,
+    upload_sessions: Option<Arc<dyn UploadSessionUseCase>>,
+    placeholders: Option<Arc<dyn ImagePlaceholderPort>>,
+    fingerprints: Option<Arc<dyn ImageFingerprintPort>>,
+    /// Entradas de metadatos huérfanas y cuándo se vieron así por primera vez
+    suspects: Mutex<HashMap<String, SystemTime>>,
+    run_lock: tokio::sync::Mutex<()>,
+    stats: RwLock<StorageGcStatsDto>,
+}
+
+impl StorageGcService {
+    pub fn new(storage_root: PathBuf, file_service: Arc<dyn FileUseCase>, grace_period_hours: u64) -> Self {
+        let grace_period = Duration::from_secs(grace_period_hours * 3600);
+        Self {
+            storage_root,
+            grace_period,
+            file_service,
+            trash_repository: None,
+            upload_sessions: None,
+            placeholders: None,
+            fingerprints: None,
+            suspects: Mutex::new(HashMap::new()),
+            run_lock: tokio::sync::Mutex::new(()),
+            stats: RwLock::new(StorageGcStatsDto {
+                grace_period_secs: grace_period.as_secs(),
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Limpia la papelera de ficheros sin entrada en el índice
+    pub fn with_trash_repository(mut self, trash_repository: Arc<dyn TrashRepository>) -> Self {
+        self.trash_repository = Some(trash_repository);
+        self
+    }
+
+    /// Limpia los ficheros temporales de subidas sin sesión
+    pub fn with_upload_sessions(mut self, upload_sessions: Arc<dyn UploadSessionUseCase>) -> Self {
+        self.upload_sessions = Some(upload_sessions);
+        self
+    }
+
+    /// Olvida los placeholders de archivos borrados
+    pub fn with_placeholders(mut self, placeholders: Arc<dyn ImagePlaceholderPort>) -> Self {
+        self.placeholders = Some(placeholders);
+        self
+    }
+
+    /// Olvida las huellas de imágenes de archivos borrados
+    pub fn with_fingerprints(mut self, fingerprints: Arc<dyn ImageFingerprintPort>) -> Self {
+        self.fingerprints = Some(fingerprints);
+        self
+    }
+
+    /// Inicia las pasadas periódicas; con intervalo 0 solo se ejecuta a petición
+    pub fn start_gc_job(self: Arc<Self>, interval_hours: u64) {
+        if interval_hours == 0 {
+            info!("Recolección de basura del almacenamiento solo bajo petición");
+            return;
+        }
+
+        info!("Iniciando recolección de basura del almacenamiento con intervalo de {} horas", interval_hours);
+        tokio::spawn(async move {
+            let mut interval = time::interval(Duration::from_secs(interval_hours * 3600));
+            loop {
+                interval.tick().await;
+                debug!("Ejecutando recolección de basura programada");
+                if let Err(e) = self.run().await {
+                    error!("Error en la recolección de basura programada: {}", e);
+                }
+            }
+        });
+    }
+
+    fn is_stale(&self, metadata: &Metadata, now: SystemTime) -> bool {
+        now.duration_since(last_change(metadata))
+            .is_ok_and(|age| age >= self.grace_period)
+    }
+
+    /// Anota el borrado de una entrada en el plan y, salvo en una
+    /// simulación, la borra del disco y la anota en el informe
+    fn reclaim(&self, plan: &mut ExecutionPlan, action: &str, path: &Path, report: &mut StorageGcReportDto) -> bool {
+        let size = disk_usage(path);
+        let target = path.strip_prefix(&self.storage_root).unwrap_or(path).display().to_string();
+        if !plan.step(action, target, Some(size)) {
+            return false;
+        }
+        match remove_entry(path) {
+            Ok(()) => {
+                report.reclaimed_bytes += size;
+                true
+            }
+            Err(e) => {
+                report.errors.push(format!("{}: {}", path.display(), e));
+                false
+            }
+        }
+    }
+
+    /// IDs de los elementos que siguen en la papelera
+    async fn trashed_ids(&self) -> Result<Option<HashSet<String>>, DomainError> {
+        let Some(trash_repository) = &self.trash_repository else { return Ok(None) };
+        let items = trash_repository.get_all_items().await?;
+        Ok(Some(items.iter().map(|item| item.original_id.to_string()).collect()))
+    }
+
+    /// Ficheros y carpetas de `.trash` sin entrada en el índice
+    fn collect_trash(&self, plan: &mut ExecutionPlan, trashed: &HashSet<String>, now: SystemTime, report: &mut StorageGcReportDto) {
+        let trash_dir = self.storage_root.join(".trash");
+        let mut candidates = list_dir(&trash_dir.join("folders"));
+        for (user_dir, _, metadata) in list_dir(&trash_dir.join("files")) {
+            if metadata.is_dir() {
+                candidates.extend(list_dir(&user_dir));
+            }
+        }
+
+        for (path, name, metadata) in candidates {
+            if !trashed.contains(&name) && self.is_stale(&metadata, now)
+                && self.reclaim(plan, "remove_trash_blob", &path, report)
+            {
+                report.orphaned_trash_blobs += 1;
+            }
+        }
+    }
+
+    /// Ficheros temporales de subidas cuya sesión ya no existe
+    async fn collect_upload_fragments(&self, plan: &mut ExecutionPlan, now: SystemTime, report: &mut StorageGcReportDto) -> Result<(), DomainError> {
+        let Some(upload_sessions) = &self.upload_sessions else { return Ok(()) };
+        let live: HashSet<PathBuf> = upload_sessions.list_temp_files().await?.into_iter().collect();
+
+        for (path, _, metadata) in list_dir(upload_sessions.temp_dir()) {
+            let is_part = path.extension().is_some_and(|ext| ext == UPLOAD_PART_EXTENSION);
+            if metadata.is_file() && is_part && !live.contains(&path) && self.is_stale(&metadata, now)
+                && self.reclaim(plan, "remove_upload_fragment", &path, report)
+            {
+                report.stale_upload_fragments += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn collect_temp_files(&self, plan: &mut ExecutionPlan, now: SystemTime, report: &mut StorageGcReportDto) {
+        let mut leftovers = Vec::new();
+        find_atomic_write_leftovers(&self.storage_root, &mut leftovers);
+        for (path, metadata) in leftovers {
+            if self.is_stale(&metadata, now) && self.reclaim(plan, "remove_temp_file", &path, report) {
+                report.stale_temp_files += 1;
+            }
+        }
+    }
+
+    /// Filtra los IDs de archivos que ya no existen y que llevan huérfanos
+    /// todo el periodo de gracia. Una simulación no empieza a vigilar los
+    /// huérfanos nuevos
+    async fn expired_orphans(
+        &self,
+        dry_run: bool,
+        kind: &str,
+        file_ids: Vec<String>,
+        trashed: &HashSet<String>,
+        now: SystemTime,
+        seen: &mut HashSet<String>,
+    ) -> Result<Vec<String>, DomainError> {
+        let mut expired = Vec::new();
+        for file_id in file_ids {
+            // Los archivos en la papelera pueden restaurarse
+            if trashed.contains(&file_id) {
+                continue;
+            }
+            match self.file_service.get_file(&file_id).await {
+                Ok(_) => continue,
+                Err(e) if e.kind == ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+
+            let key = format!("{}:{}", kind, file_id);
+            let first_seen = {
+                let mut suspects = self.suspects.lock().unwrap();
+                if dry_run {
+                    suspects.get(&key).copied().unwrap_or(now)
+                } else {
+                    *suspects.entry(key.clone()).or_insert(now)
+                }
+            };
+            seen.insert(key);
+            if now.duration_since(first_seen).is_ok_and(|age| age >= self.grace_period) {
+                expired.push(file_id);
+            }
+        }
+        Ok(expired)
+    }
+
+    async fn collect_image_metadata(&self, plan: &mut ExecutionPlan, trashed: &HashSet<String>, now: SystemTime, report: &mut StorageGcReportDto) -> Result<(), DomainError> {
+        let mut seen = HashSet::new();
+        let dry_run = plan.is_dry_run();
+
+        if let Some(placeholders) = &self.placeholders {
+            let ids = placeholders.list_placeholder_ids().await?;
+            for file_id in self.expired_orphans(dry_run, "placeholder", ids, trashed, now, &mut seen).await? {
+                if !plan.step("remove_placeholder", file_id.as_str(), None) {
+                    continue;
+                }
+                match placeholders.remove_placeholder(&file_id).await {
+                    Ok(()) => report.orphaned_placeholders += 1,
+                    Err(e) => report.errors.push(format!("placeholder {}: {}", file_id, e)),
+                }
+            }
+        }
+
+        if let Some(fingerprints) = &self.fingerprints {
+            let ids = fingerprints.list_fingerprints().await?.into_iter().map(|(id, _)| id).collect();
+            for file_id in self.expired_orphans(dry_run, "fingerprint", ids, trashed, now, &mut seen).await? {
+                if !plan.step("remove_fingerprint", file_id.as_str(), None) {
+                    continue;
+                }
+                match fingerprints.remove_fingerprint(&file_id).await {
+                    Ok(()) => report.orphaned_fingerprints += 1,
+                    Err(e) => report.errors.push(format!("fingerprint {}: {}", file_id, e)),
+                }
+            }
+        }
+
+        // Lo que ya no está huérfano (o ya se borró) deja de vigilarse
+        if !dry_run {
+            self.suspects.lock().unwrap().retain(|key, _| seen.contains(key));
+        }
+        Ok(())
+    }
+
+    async fn collect(&self, plan: &mut ExecutionPlan, report: &mut StorageGcReportDto) -> Result<(), DomainError> {
+        let now = SystemTime::now();
+        let trashed = self.trashed_ids().await?;
+
+        if let Some(trashed) = &trashed {
+            self.collect_trash(plan, trashed, now, report);
+        }
+        self.collect_upload_fragments(plan, now, report).await?;
+        self.collect_temp_files(plan, now, report);
+        self.collect_image_metadata(plan, &trashed.unwrap_or_default(), now, report).await
+    }
+}
+
+fn now_secs() -> u64 {
+    chrono::Utc::now().timestamp().max(0) as u64
+}
+
+#[async_trait]
+impl StorageGcUseCase for StorageGcService {
+    async fn run(&self) -> Result<StorageGcReportDto, DomainError> {
+        let Ok(_guard) = self.run_lock.try_lock() else {
+            return Err(DomainError::new(ErrorKind::Locked, "StorageGc", "A garbage collection pass is already running"));
+        };
+        self.stats.write().unwrap().running = true;
+
+        let mut report = StorageGcReportDto {
+            started_at: now_secs(),
+            ..Default::default()
+        };
+        let result = self.collect(&mut ExecutionPlan::new("storage_gc", false), &mut report).await;
+        report.finished_at = Some(now_secs());
+        if let Err(e) = &result {
+            report.errors.push(e.to_string());
+        }
+
+        {
+            let mut stats = self.stats.write().unwrap();
+            stats.running = false;
+            stats.runs += 1;
+            stats.total_reclaimed_bytes += report.reclaimed_bytes;
+            stats.last_run = Some(report.clone());
+        }
+
+        if report.errors.is_empty() {
+            info!(
+                "Recolección de basura: {} bytes liberados ({} de papelera, {} subidas, {} temporales, {} placeholders, {} huellas)",
+                report.reclaimed_bytes, report.orphaned_trash_blobs, report.stale_upload_fragments,
+                report.stale_temp_files, report.orphaned_placeholders, report.orphaned_fingerprints
+            );
+        } else {
+            warn!("Recolección de basura con {} errores: {:?}", report.errors.len(), report.errors);
+        }
+
+        result.map(|_| report)
+    }
+
+    async fn dry_run(&self) -> Result<ExecutionPlanDto, DomainError> {
+        let mut plan = ExecutionPlan::new("storage_gc", true);
+        self.collect(&mut plan, &mut StorageGcReportDto::default()).await?;
+        Ok(plan.finish())
+    }
+
+    async fn stats(&self) -> StorageGcStatsDto {
+        self.stats.read().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_atomic_write_leftover_names() {
+        assert!(is_atomic_write_leftover(".tmpA1b2C3"));
+        assert!(!is_atomic_write_leftover(".tmpA1b2C"));
+        assert!(!is_atomic_write_leftover(".tmp-1b2C3"));
+        assert!(!is_atomic_write_leftover("tmpA1b2C3x"));
+        assert!(!is_atomic_write_leftover("report.tmp"));
+    }
+
+    #[test]
+    fn test_finds_leftovers_and_measures_usage() {
+        let root = tempfile::tempdir().unwrap();
+        let nested = root.path().join("Mi Carpeta - ana/Docs");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(nested.join(".tmpAbC123"), b"partial").unwrap();
+        std::fs::write(nested.join("notes.txt"), b"keep me").unwrap();
+
+        let mut found = Vec::new();
+        find_atomic_write_leftovers(root.path(), &mut found);
+        assert_eq!(found.len(), 1);
+        assert!(found[0].0.ends_with(".tmpAbC123"));
+
+        assert_eq!(disk_usage(root.path()), 14);
+        remove_entry(&root.path().join("Mi Carpeta - ana")).unwrap();
+        assert_eq!(disk_usage(root.path()), 0);
+    }
+}
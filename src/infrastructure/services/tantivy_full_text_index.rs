@@ -0,0 +1,195 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use tantivy::collector::TopDocs;
+use tantivy::directory::MmapDirectory;
+use tantivy::query::QueryParser;
+use tantivy::schema::{Field, Schema, Value, STORED, STRING, TEXT};
+use tantivy::snippet::SnippetGenerator;
+use tantivy::{doc, Index, IndexReader, IndexWriter, ReloadPolicy, TantivyDocument, Term};
+
+use crate::application::ports::full_text_ports::{FullTextHit, FullTextIndexPort};
+use crate::common::errors::DomainError;
+
+const FILE_ID_FIELD: &str = "file_id";
+const NAME_FIELD: &str = "name";
+const CONTENT_FIELD: &str = "content";
+
+/// Memory the writer may use before flushing a segment to disk
+const WRITER_MEMORY_BYTES: usize = 50 * 1024 * 1024;
+
+/// Longest snippet returned with a hit, in characters
+const SNIPPET_MAX_CHARS: usize = 200;
+
+fn index_error(e: impl std::fmt::Display) -> DomainError {
+    DomainError::internal_error("FullTextIndex", format!("Full-text index error: {}", e))
+}
+
+struct IndexInner {
+    index: Index,
+    reader: IndexReader,
+    writer: Mutex<IndexWriter>,
+    file_id: Field,
+    name: Field,
+    content: Field,
+}
+
+impl IndexInner {
+    fn new(index: Index) -> Result<Self, DomainError> {
+        let schema = index.schema();
+        let file_id = schema.get_field(FILE_ID_FIELD).map_err(index_error)?;
+        let name = schema.get_field(NAME_FIELD).map_err(index_error)?;
+        let content = schema.get_field(CONTENT_FIELD).map_err(index_error)?;
+
+        let writer: IndexWriter = index.writer_with_num_threads(1, WRITER_MEMORY_BYTES).map_err(index_error)?;
+        let reader: IndexReader = index.reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()
+            .map_err(index_error)?;
+
+        Ok(Self { index, reader, writer: Mutex::new(writer), file_id, name, content })
+    }
+
+    /// Applies a change and makes it visible to the next searches
+    fn write(&self, change: impl FnOnce(&mut IndexWriter, &Self) -> tantivy::Result<()>) -> Result<(), DomainError> {
+        let mut writer = self.writer.lock()
+            .map_err(|_| DomainError::internal_error("FullTextIndex", "Index writer lock poisoned"))?;
+        change(&mut writer, self).map_err(index_error)?;
+        writer.commit().map_err(index_error)?;
+        drop(writer);
+        self.reader.reload().map_err(index_error)
+    }
+
+    fn index_document(&self, file_id: &str, name: &str, text: String) -> Result<(), DomainError> {
+        self.write(|writer, inner| {
+            writer.delete_term(Term::from_field_text(inner.file_id, file_id));
+            writer.add_document(doc!(
+                inner.file_id => file_id,
+                inner.name => name,
+                inner.content => text,
+            ))?;
+            Ok(())
+        })
+    }
+
+    fn remove_document(&self, file_id: &str) -> Result<(), DomainError> {
+        self.write(|writer, inner| {
+            writer.delete_term(Term::from_field_text(inner.file_id, file_id));
+            Ok(())
+        })
+    }
+
+    fn search(&self, query: &str, limit: usize) -> Result<Vec<FullTextHit>, DomainError> {
+        let searcher = self.reader.searcher();
+        // Lenient parsing: user input with stray quotes or operators still searches
+        let (query, _) = QueryParser::for_index(&self.index, vec![self.name, self.content]).parse_query_lenient(query);
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit.max(1))).map_err(index_error)?;
+
+        let mut snippets = SnippetGenerator::create(&searcher, &*query, self.content).map_err(index_error)?;
+        snippets.set_max_num_chars(SNIPPET_MAX_CHARS);
+
+        let mut hits = Vec::with_capacity(top_docs.len());
+        for (score, address) in top_docs {
+            let document: TantivyDocument = searcher.doc(address).map_err(index_error)?;
+            let Some(file_id) = document.get_first(self.file_id).and_then(|value| value.as_str()) else {
+                continue;
+            };
+            hits.push(FullTextHit {
+                file_id: file_id.to_string(),
+                score,
+                snippet: snippets.snippet_from_doc(&document).to_html(),
+            });
+        }
+        Ok(hits)
+    }
+}
+
+/// Full-text index of file names and contents kept with Tantivy.
+///
+/// Every change is committed right away, so searches see it as soon as the
+/// call returns. Tantivy works synchronously, so calls run on the blocking
+/// thread pool.
+pub struct TantivyFullTextIndex {
+    inner: Arc<IndexInner>,
+}
+
+impl TantivyFullTextIndex {
+    fn schema() -> Schema {
+        let mut builder = Schema::builder();
+        builder.add_text_field(FILE_ID_FIELD, STRING | STORED);
+        builder.add_text_field(NAME_FIELD, TEXT | STORED);
+        // Stored so snippets can be cut from it
+        builder.add_text_field(CONTENT_FIELD, TEXT | STORED);
+        builder.build()
+    }
+
+    /// Opens the index kept in `dir`, creating it if needed
+    pub fn open(dir: &Path) -> Result<Self, DomainError> {
+        std::fs::create_dir_all(dir).map_err(index_error)?;
+        let directory = MmapDirectory::open(dir).map_err(index_error)?;
+        let index = Index::open_or_create(directory, Self::schema()).map_err(index_error)?;
+        Ok(Self { inner: Arc::new(IndexInner::new(index)?) })
+    }
+
+    /// Index kept only in memory
+    pub fn in_memory() -> Result<Self, DomainError> {
+        Ok(Self { inner: Arc::new(IndexInner::new(Index::create_in_ram(Self::schema()))?) })
+    }
+
+    async fn run<T, F>(&self, operation: F) -> Result<T, DomainError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&IndexInner) -> Result<T, DomainError> + Send + 'static,
+    {
+        let inner = self.inner.clone();
+        tokio::task::spawn_blocking(move || operation(&inner))
+            .await
+            .map_err(index_error)?
+    }
+}
+
+#[async_trait]
+impl FullTextIndexPort for TantivyFullTextIndex {
+    async fn index_document(&self, file_id: &str, name: &str, text: String) -> Result<(), DomainError> {
+        let (file_id, name) = (file_id.to_string(), name.to_string());
+        self.run(move |inner| inner.index_document(&file_id, &name, text)).await
+    }
+
+    async fn remove_document(&self, file_id: &str) -> Result<(), DomainError> {
+        let file_id = file_id.to_string();
+        self.run(move |inner| inner.remove_document(&file_id)).await
+    }
+
+    async fn search(&self, query: &str, limit: usize) -> Result<Vec<FullTextHit>, DomainError> {
+        let query = query.to_string();
+        self.run(move |inner| inner.search(&query, limit)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_indexed_content_is_found_with_a_snippet() {
+        let index = TantivyFullTextIndex::in_memory().unwrap();
+        index.index_document("f1", "acta.txt", "La reunión aprobó el presupuesto de mantenimiento".to_string()).await.unwrap();
+        index.index_document("f2", "lista.md", "Comprar pan y leche".to_string()).await.unwrap();
+
+        let hits = index.search("presupuesto", 10).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].file_id, "f1");
+        assert!(hits[0].snippet.contains("<b>presupuesto</b>"));
+
+        // Reindexing replaces the previous content
+        index.index_document("f1", "acta.txt", "Orden del día".to_string()).await.unwrap();
+        assert!(index.search("presupuesto", 10).await.unwrap().is_empty());
+
+        index.remove_document("f2").await.unwrap();
+        assert!(index.search("leche", 10).await.unwrap().is_empty());
+
+        // Malformed queries still search
+        assert!(index.search("\"orden AND", 10).await.is_ok());
+    }
+}
@@ -0,0 +1,144 @@
+use std::io::{Cursor, Read};
+
+use async_trait::async_trait;
+use quick_xml::Reader;
+use quick_xml::events::Event;
+
+use crate::application::ports::full_text_ports::TextExtractionPort;
+use crate::common::errors::DomainError;
+
+/// Largest `word/document.xml` read from a DOCX file, so a compressed bomb
+/// cannot exhaust memory
+const MAX_DOCX_XML_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Document formats text is extracted from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DocumentFormat {
+    PlainText,
+    Pdf,
+    Docx,
+}
+
+impl DocumentFormat {
+    fn from_name(file_name: &str) -> Option<Self> {
+        let (_, extension) = file_name.rsplit_once('.')?;
+        match extension.to_ascii_lowercase().as_str() {
+            "txt" | "text" | "md" | "markdown" => Some(Self::PlainText),
+            "pdf" => Some(Self::Pdf),
+            "docx" => Some(Self::Docx),
+            _ => None,
+        }
+    }
+}
+
+/// Extracts the text of plain text, Markdown, PDF and DOCX files.
+///
+/// Markdown is indexed as written: its markup is punctuation the index
+/// ignores anyway. Extraction runs on the blocking thread pool, and a PDF
+/// that makes the parser panic is reported as an error.
+pub struct DocumentTextExtractor;
+
+/// Text of the paragraphs of a DOCX document, one per line
+fn docx_text(content: &[u8]) -> Result<String, DomainError> {
+    let invalid = |e: String| DomainError::validation_error(format!("Invalid DOCX file: {}", e));
+
+    let mut archive = zip::ZipArchive::new(Cursor::new(content)).map_err(|e| invalid(e.to_string()))?;
+    let mut xml = String::new();
+    archive.by_name("word/document.xml").map_err(|e| invalid(e.to_string()))?
+        .take(MAX_DOCX_XML_BYTES)
+        .read_to_string(&mut xml)
+        .map_err(|e| invalid(e.to_string()))?;
+
+    let mut reader = Reader::from_str(&xml);
+    let mut text = String::new();
+    let mut in_text = false;
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) if e.local_name().as_ref() == b"t" => in_text = true,
+            Ok(Event::End(e)) => match e.local_name().as_ref() {
+                b"t" => in_text = false,
+                b"p" => text.push('\n'),
+                _ => {}
+            },
+            Ok(Event::Empty(e)) => match e.local_name().as_ref() {
+                b"tab" => text.push('\t'),
+                b"br" | b"cr" => text.push('\n'),
+                _ => {}
+            },
+            Ok(Event::Text(e)) if in_text => {
+                text.push_str(&e.unescape().map_err(|e| invalid(e.to_string()))?);
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(invalid(e.to_string())),
+            _ => {}
+        }
+    }
+    Ok(text)
+}
+
+fn extract(format: DocumentFormat, content: &[u8]) -> Result<String, DomainError> {
+    match format {
+        DocumentFormat::PlainText => Ok(String::from_utf8_lossy(content).into_owned()),
+        DocumentFormat::Pdf => pdf_extract::extract_text_from_mem(content)
+            .map_err(|e| DomainError::validation_error(format!("Invalid PDF file: {}", e))),
+        DocumentFormat::Docx => docx_text(content),
+    }
+}
+
+#[async_trait]
+impl TextExtractionPort for DocumentTextExtractor {
+    fn supports(&self, file_name: &str) -> bool {
+        DocumentFormat::from_name(file_name).is_some()
+    }
+
+    async fn extract_text(&self, file_name: &str, content: Vec<u8>) -> Result<Option<String>, DomainError> {
+        let Some(format) = DocumentFormat::from_name(file_name) else {
+            return Ok(None);
+        };
+        let text = tokio::task::spawn_blocking(move || extract(format, &content))
+            .await
+            .map_err(|e| DomainError::internal_error("TextExtractor", format!("Text extraction failed: {}", e)))??;
+        Ok(Some(text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::{ZipWriter, write::SimpleFileOptions};
+
+    fn docx(document_xml: &str) -> Vec<u8> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file("word/document.xml", SimpleFileOptions::default()).unwrap();
+        zip.write_all(document_xml.as_bytes()).unwrap();
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[tokio::test]
+    async fn test_docx_paragraphs_become_lines() {
+        let content = docx(concat!(
+            r#"<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>"#,
+            r#"<w:p><w:r><w:t>Informe</w:t></w:r><w:r><w:tab/><w:t xml:space="preserve">anual &amp; cuentas</w:t></w:r></w:p>"#,
+            r#"<w:p><w:r><w:t>Segunda línea</w:t></w:r></w:p>"#,
+            r#"</w:body></w:document>"#,
+        ));
+
+        let text = DocumentTextExtractor.extract_text("Informe.DOCX", content).await.unwrap();
+        assert_eq!(text.as_deref(), Some("Informe\tanual & cuentas\nSegunda línea\n"));
+    }
+
+    #[tokio::test]
+    async fn test_formats_are_chosen_by_extension() {
+        let extractor = DocumentTextExtractor;
+        assert!(extractor.supports("notas.md"));
+        assert!(extractor.supports("scan.PDF"));
+        assert!(!extractor.supports("photo.jpg"));
+        assert!(!extractor.supports("README"));
+
+        let text = extractor.extract_text("notas.md", b"# Title\n\nSome *text*".to_vec()).await.unwrap();
+        assert_eq!(text.as_deref(), Some("# Title\n\nSome *text*"));
+        assert_eq!(extractor.extract_text("photo.jpg", vec![0xff, 0xd8]).await.unwrap(), None);
+        assert!(extractor.extract_text("broken.docx", b"not a zip".to_vec()).await.is_err());
+    }
+}
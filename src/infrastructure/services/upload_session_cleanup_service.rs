@@ -0,0 +1,53 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time;
+use tracing::{debug, error, info};
+
+use crate::application::ports::upload_session_ports::UploadSessionUseCase;
+
+/// Servicio que revalida las subidas pendientes al arrancar y elimina
+/// periódicamente las sesiones abandonadas
+pub struct UploadSessionCleanupService {
+    upload_service: Arc<dyn UploadSessionUseCase>,
+    cleanup_interval_minutes: u64,
+}
+
+impl UploadSessionCleanupService {
+    pub fn new(upload_service: Arc<dyn UploadSessionUseCase>, cleanup_interval_minutes: u64) -> Self {
+        Self {
+            upload_service,
+            cleanup_interval_minutes: cleanup_interval_minutes.max(1), // Mínimo 1 minuto
+        }
+    }
+
+    /// Revalida las sesiones persistidas y arranca la limpieza periódica
+    pub async fn start_cleanup_job(&self) {
+        match self.upload_service.recover_sessions().await {
+            Ok(report) => info!(
+                "Subidas pendientes revalidadas: {} reanudables, {} corregidas, {} descartadas",
+                report.resumed, report.adjusted, report.dropped
+            ),
+            Err(e) => error!("Error revalidando las subidas pendientes: {:?}", e),
+        }
+
+        let upload_service = self.upload_service.clone();
+        let interval_minutes = self.cleanup_interval_minutes;
+
+        info!("Iniciando limpieza de subidas caducadas con intervalo de {} minutos", interval_minutes);
+
+        tokio::spawn(async move {
+            let mut interval = time::interval(Duration::from_secs(interval_minutes * 60));
+
+            loop {
+                interval.tick().await;
+                debug!("Ejecutando tarea programada de limpieza de subidas");
+
+                match upload_service.expire_sessions().await {
+                    Ok(0) => {}
+                    Ok(count) => info!("Eliminadas {} subidas caducadas", count),
+                    Err(e) => error!("Error en la limpieza programada de subidas: {:?}", e),
+                }
+            }
+        });
+    }
+}
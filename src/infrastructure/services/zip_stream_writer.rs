@@ -0,0 +1,487 @@
+use std::io::{self, Write};
+
+use bytes::Bytes;
+use chrono::{Datelike, Timelike};
+use flate2::write::DeflateEncoder;
+use flate2::{Compression, Crc};
+use serde::{Deserialize, Serialize};
+
+const LOCAL_HEADER_SIGNATURE: u32 = 0x04034b50;
+const DATA_DESCRIPTOR_SIGNATURE: u32 = 0x08074b50;
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x02014b50;
+const ZIP64_END_SIGNATURE: u32 = 0x06064b50;
+const ZIP64_LOCATOR_SIGNATURE: u32 = 0x07064b50;
+const END_SIGNATURE: u32 = 0x06054b50;
+
+/// Bit 3: tamaños y CRC van en un descriptor tras los datos
+const FLAG_DATA_DESCRIPTOR: u16 = 0x0008;
+/// Bit 11: nombres en UTF-8
+const FLAG_UTF8: u16 = 0x0800;
+
+const METHOD_STORED: u16 = 0;
+const METHOD_DEFLATED: u16 = 8;
+
+const VERSION_DEFLATE: u16 = 20;
+const VERSION_ZIP64: u16 = 45;
+/// Creado en Unix (3), especificación 4.5
+const VERSION_MADE_BY: u16 = (3 << 8) | VERSION_ZIP64;
+
+const ZIP64_EXTRA_ID: u16 = 0x0001;
+const U32_SENTINEL: u32 = 0xFFFF_FFFF;
+const U16_SENTINEL: u16 = 0xFFFF;
+
+/// Tamaño a partir del cual una entrada se declara ZIP64 desde la cabecera
+/// local. Deja margen para la expansión de datos no comprimibles
+const ZIP64_SIZE_THRESHOLD: u64 = 0xFFF0_0000;
+
+const FILE_MODE: u32 = 0o100644;
+const DIRECTORY_MODE: u32 = 0o040755;
+const MSDOS_DIRECTORY: u32 = 0x10;
+
+/// Datos de una entrada que se repiten en el directorio central
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CentralEntry {
+    name: Vec<u8>,
+    flags: u16,
+    method: u16,
+    time: u16,
+    date: u16,
+    crc: u32,
+    compressed_size: u64,
+    uncompressed_size: u64,
+    offset: u64,
+    external_attributes: u32,
+}
+
+/// Estado del escritor entre dos entradas, suficiente para continuar el
+/// mismo ZIP más tarde, por ejemplo tras reiniciar el servidor
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ZipCheckpoint {
+    /// Bytes del ZIP escritos hasta el punto de control
+    pub offset: u64,
+    /// Entradas completas, para el directorio central
+    pub entries: Vec<CentralEntry>,
+}
+
+/// Archivo cuyo contenido se está escribiendo
+struct OpenFile {
+    entry: CentralEntry,
+    encoder: DeflateEncoder<Vec<u8>>,
+    crc: Crc,
+    zip64: bool,
+}
+
+/// Escritor de ZIP en streaming.
+///
+/// A diferencia de `zip::ZipWriter` no necesita `Seek`: cada archivo lleva un
+/// descriptor de datos tras su contenido, de modo que los bytes generados se
+/// pueden enviar al cliente a medida que se producen con `take_output`.
+/// Los archivos se comprimen con deflate y los nombres se marcan como UTF-8.
+/// Cuando los tamaños o desplazamientos superan 4 GiB se emiten los campos y
+/// registros ZIP64.
+pub struct ZipStreamWriter {
+    output: Vec<u8>,
+    offset: u64,
+    entries: Vec<CentralEntry>,
+    current: Option<OpenFile>,
+}
+
+impl Default for ZipStreamWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ZipStreamWriter {
+    pub fn new() -> Self {
+        Self {
+            output: Vec::new(),
+            offset: 0,
+            entries: Vec::new(),
+            current: None,
+        }
+    }
+
+    /// Continúa un ZIP desde un punto de control. Los bytes que siga
+    /// generando van a continuación de los `checkpoint.offset` ya escritos
+    pub fn resume(checkpoint: ZipCheckpoint) -> Self {
+        Self {
+            output: Vec::new(),
+            offset: checkpoint.offset,
+            entries: checkpoint.entries,
+            current: None,
+        }
+    }
+
+    /// Punto de control con las entradas terminadas. Solo puede tomarse entre
+    /// entradas, y cuenta también los bytes pendientes de `take_output`
+    pub fn checkpoint(&self) -> io::Result<ZipCheckpoint> {
+        self.ensure_no_open_file()?;
+        Ok(ZipCheckpoint {
+            offset: self.offset,
+            entries: self.entries.clone(),
+        })
+    }
+
+    /// Añade una entrada de directorio; `name` debe terminar en `/`
+    pub fn add_directory(&mut self, name: &str, modified_at: u64) -> io::Result<()> {
+        self.ensure_no_open_file()?;
+        let (time, date) = dos_date_time(modified_at);
+        let entry = CentralEntry {
+            name: name.as_bytes().to_vec(),
+            flags: FLAG_UTF8,
+            method: METHOD_STORED,
+            time,
+            date,
+            crc: 0,
+            compressed_size: 0,
+            uncompressed_size: 0,
+            offset: self.offset,
+            external_attributes: (DIRECTORY_MODE << 16) | MSDOS_DIRECTORY,
+        };
+        self.write_local_header(&entry, false);
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// Empieza un archivo. `size_hint` es el tamaño esperado del contenido y
+    /// decide si la entrada se declara ZIP64
+    pub fn start_file(&mut self, name: &str, modified_at: u64, size_hint: u64) -> io::Result<()> {
+        self.ensure_no_open_file()?;
+        let (time, date) = dos_date_time(modified_at);
+        let zip64 = size_hint >= ZIP64_SIZE_THRESHOLD;
+        let entry = CentralEntry {
+            name: name.as_bytes().to_vec(),
+            flags: FLAG_UTF8 | FLAG_DATA_DESCRIPTOR,
+            method: METHOD_DEFLATED,
+            time,
+            date,
+            crc: 0,
+            compressed_size: 0,
+            uncompressed_size: 0,
+            offset: self.offset,
+            external_attributes: FILE_MODE << 16,
+        };
+        self.write_local_header(&entry, zip64);
+        self.current = Some(OpenFile {
+            entry,
+            encoder: DeflateEncoder::new(Vec::new(), Compression::default()),
+            crc: Crc::new(),
+            zip64,
+        });
+        Ok(())
+    }
+
+    /// Escribe contenido del archivo abierto
+    pub fn write(&mut self, data: &[u8]) -> io::Result<()> {
+        let file = self.current.as_mut()
+            .ok_or_else(|| io::Error::other("no file is open in the ZIP stream"))?;
+        file.crc.update(data);
+        file.entry.uncompressed_size += data.len() as u64;
+        file.encoder.write_all(data)?;
+
+        let compressed = std::mem::take(file.encoder.get_mut());
+        file.entry.compressed_size += compressed.len() as u64;
+        self.emit(&compressed);
+        Ok(())
+    }
+
+    /// Cierra el archivo abierto escribiendo el resto de datos comprimidos y
+    /// su descriptor
+    pub fn finish_file(&mut self) -> io::Result<()> {
+        let OpenFile { mut entry, encoder, crc, zip64 } = self.current.take()
+            .ok_or_else(|| io::Error::other("no file is open in the ZIP stream"))?;
+        let remaining = encoder.finish()?;
+        entry.compressed_size += remaining.len() as u64;
+        entry.crc = crc.sum();
+        self.emit(&remaining);
+
+        if !zip64 && (entry.compressed_size >= U32_SENTINEL as u64 || entry.uncompressed_size >= U32_SENTINEL as u64) {
+            return Err(io::Error::other(format!(
+                "{} is larger than declared when it was added to the ZIP stream",
+                String::from_utf8_lossy(&entry.name)
+            )));
+        }
+
+        let mut descriptor = Vec::with_capacity(24);
+        put_u32(&mut descriptor, DATA_DESCRIPTOR_SIGNATURE);
+        put_u32(&mut descriptor, entry.crc);
+        if zip64 {
+            put_u64(&mut descriptor, entry.compressed_size);
+            put_u64(&mut descriptor, entry.uncompressed_size);
+        } else {
+            put_u32(&mut descriptor, entry.compressed_size as u32);
+            put_u32(&mut descriptor, entry.uncompressed_size as u32);
+        }
+        self.emit(&descriptor);
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// Escribe el directorio central y el final del archivo
+    pub fn finish(&mut self) -> io::Result<()> {
+        self.ensure_no_open_file()?;
+        let central_start = self.offset;
+
+        let mut central = Vec::new();
+        for entry in &self.entries {
+            let mut extra = Vec::new();
+            if entry.uncompressed_size >= U32_SENTINEL as u64 {
+                put_u64(&mut extra, entry.uncompressed_size);
+            }
+            if entry.compressed_size >= U32_SENTINEL as u64 {
+                put_u64(&mut extra, entry.compressed_size);
+            }
+            if entry.offset >= U32_SENTINEL as u64 {
+                put_u64(&mut extra, entry.offset);
+            }
+            let needs_zip64 = !extra.is_empty();
+
+            put_u32(&mut central, CENTRAL_HEADER_SIGNATURE);
+            put_u16(&mut central, VERSION_MADE_BY);
+            put_u16(&mut central, if needs_zip64 { VERSION_ZIP64 } else { VERSION_DEFLATE });
+            put_u16(&mut central, entry.flags);
+            put_u16(&mut central, entry.method);
+            put_u16(&mut central, entry.time);
+            put_u16(&mut central, entry.date);
+            put_u32(&mut central, entry.crc);
+            put_u32(&mut central, clamp_u32(entry.compressed_size));
+            put_u32(&mut central, clamp_u32(entry.uncompressed_size));
+            put_u16(&mut central, entry.name.len() as u16);
+            put_u16(&mut central, if needs_zip64 { extra.len() as u16 + 4 } else { 0 });
+            put_u16(&mut central, 0); // comentario
+            put_u16(&mut central, 0); // disco
+            put_u16(&mut central, 0); // atributos internos
+            put_u32(&mut central, entry.external_attributes);
+            put_u32(&mut central, clamp_u32(entry.offset));
+            central.extend_from_slice(&entry.name);
+            if needs_zip64 {
+                put_u16(&mut central, ZIP64_EXTRA_ID);
+                put_u16(&mut central, extra.len() as u16);
+                central.extend_from_slice(&extra);
+            }
+        }
+        let central_size = central.len() as u64;
+        self.emit(&central);
+
+        let entry_count = self.entries.len() as u64;
+        let mut end = Vec::new();
+        if entry_count >= U16_SENTINEL as u64 || central_size >= U32_SENTINEL as u64 || central_start >= U32_SENTINEL as u64 {
+            let zip64_end_offset = self.offset;
+            put_u32(&mut end, ZIP64_END_SIGNATURE);
+            put_u64(&mut end, 44); // tamaño del registro sin los 12 primeros bytes
+            put_u16(&mut end, VERSION_MADE_BY);
+            put_u16(&mut end, VERSION_ZIP64);
+            put_u32(&mut end, 0); // disco
+            put_u32(&mut end, 0); // disco del directorio central
+            put_u64(&mut end, entry_count);
+            put_u64(&mut end, entry_count);
+            put_u64(&mut end, central_size);
+            put_u64(&mut end, central_start);
+
+            put_u32(&mut end, ZIP64_LOCATOR_SIGNATURE);
+            put_u32(&mut end, 0);
+            put_u64(&mut end, zip64_end_offset);
+            put_u32(&mut end, 1); // número total de discos
+        }
+        put_u32(&mut end, END_SIGNATURE);
+        put_u16(&mut end, 0);
+        put_u16(&mut end, 0);
+        put_u16(&mut end, entry_count.min(U16_SENTINEL as u64) as u16);
+        put_u16(&mut end, entry_count.min(U16_SENTINEL as u64) as u16);
+        put_u32(&mut end, clamp_u32(central_size));
+        put_u32(&mut end, clamp_u32(central_start));
+        put_u16(&mut end, 0); // comentario
+        self.emit(&end);
+        Ok(())
+    }
+
+    /// Devuelve los bytes generados desde la última llamada
+    pub fn take_output(&mut self) -> Bytes {
+        Bytes::from(std::mem::take(&mut self.output))
+    }
+
+    /// Bytes generados en total, incluidos los pendientes de `take_output`
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Bytes generados pendientes de recoger con `take_output`
+    pub fn buffered_len(&self) -> usize {
+        self.output.len()
+    }
+
+    fn ensure_no_open_file(&self) -> io::Result<()> {
+        if self.current.is_some() {
+            return Err(io::Error::other("the previous file in the ZIP stream is still open"));
+        }
+        Ok(())
+    }
+
+    fn write_local_header(&mut self, entry: &CentralEntry, zip64: bool) {
+        let mut header = Vec::with_capacity(30 + entry.name.len() + 20);
+        put_u32(&mut header, LOCAL_HEADER_SIGNATURE);
+        put_u16(&mut header, if zip64 { VERSION_ZIP64 } else { VERSION_DEFLATE });
+        put_u16(&mut header, entry.flags);
+        put_u16(&mut header, entry.method);
+        put_u16(&mut header, entry.time);
+        put_u16(&mut header, entry.date);
+        // CRC y tamaños se conocen al terminar: van en el descriptor de datos
+        put_u32(&mut header, 0);
+        put_u32(&mut header, if zip64 { U32_SENTINEL } else { 0 });
+        put_u32(&mut header, if zip64 { U32_SENTINEL } else { 0 });
+        put_u16(&mut header, entry.name.len() as u16);
+        put_u16(&mut header, if zip64 { 20 } else { 0 });
+        header.extend_from_slice(&entry.name);
+        if zip64 {
+            put_u16(&mut header, ZIP64_EXTRA_ID);
+            put_u16(&mut header, 16);
+            put_u64(&mut header, 0);
+            put_u64(&mut header, 0);
+        }
+        self.emit(&header);
+    }
+
+    fn emit(&mut self, data: &[u8]) {
+        self.output.extend_from_slice(data);
+        self.offset += data.len() as u64;
+    }
+}
+
+/// Convierte un timestamp Unix a fecha y hora MS-DOS (sin zona horaria y con
+/// resolución de dos segundos). Las fechas anteriores a 1980 se fijan al mínimo
+fn dos_date_time(timestamp: u64) -> (u16, u16) {
+    let datetime = match chrono::DateTime::from_timestamp(timestamp as i64, 0) {
+        Some(datetime) if datetime.year() >= 1980 && datetime.year() <= 2107 => datetime,
+        _ => return (0, (1 << 5) | 1),
+    };
+    let time = (datetime.hour() << 11) | (datetime.minute() << 5) | (datetime.second() / 2);
+    let date = (((datetime.year() - 1980) as u32) << 9) | (datetime.month() << 5) | datetime.day();
+    (time as u16, date as u16)
+}
+
+fn clamp_u32(value: u64) -> u32 {
+    value.min(U32_SENTINEL as u64) as u32
+}
+
+fn put_u16(buffer: &mut Vec<u8>, value: u16) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u32(buffer: &mut Vec<u8>, value: u32) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u64(buffer: &mut Vec<u8>, value: u64) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Read};
+
+    fn read_entry(archive: &mut zip::ZipArchive<Cursor<Vec<u8>>>, name: &str) -> Vec<u8> {
+        let mut content = Vec::new();
+        archive.by_name(name).unwrap().read_to_end(&mut content).unwrap();
+        content
+    }
+
+    #[test]
+    fn produces_an_archive_readable_by_zip_readers() {
+        let mut writer = ZipStreamWriter::new();
+        let mut archive = Vec::new();
+
+        writer.add_directory("Fotos/", 1_700_000_000).unwrap();
+        writer.start_file("Fotos/notas.txt", 1_700_000_000, 11).unwrap();
+        writer.write(b"hola ").unwrap();
+        writer.write(b"mundo").unwrap();
+        archive.extend_from_slice(&writer.take_output());
+        writer.write(b"!").unwrap();
+        writer.finish_file().unwrap();
+        writer.add_directory("Fotos/Año 2024/", 1_700_000_000).unwrap();
+        writer.start_file("Fotos/Año 2024/vacío.bin", 1_700_000_000, 0).unwrap();
+        writer.finish_file().unwrap();
+        writer.finish().unwrap();
+        archive.extend_from_slice(&writer.take_output());
+        assert_eq!(writer.buffered_len(), 0);
+
+        let mut archive = zip::ZipArchive::new(Cursor::new(archive)).unwrap();
+        assert_eq!(archive.len(), 4);
+        assert!(archive.by_name("Fotos/").unwrap().is_dir());
+        assert!(archive.by_name("Fotos/Año 2024/").unwrap().is_dir());
+        assert_eq!(read_entry(&mut archive, "Fotos/notas.txt"), b"hola mundo!");
+        assert!(read_entry(&mut archive, "Fotos/Año 2024/vacío.bin").is_empty());
+        assert_eq!(archive.by_name("Fotos/notas.txt").unwrap().unix_mode(), Some(FILE_MODE));
+    }
+
+    #[test]
+    fn compresses_large_contents_in_chunks() {
+        let content: Vec<u8> = (0..512 * 1024).map(|i| (i % 7) as u8).collect();
+        let mut writer = ZipStreamWriter::new();
+        let mut archive = Vec::new();
+
+        writer.start_file("grande.bin", 0, content.len() as u64).unwrap();
+        for chunk in content.chunks(8192) {
+            writer.write(chunk).unwrap();
+            archive.extend_from_slice(&writer.take_output());
+        }
+        writer.finish_file().unwrap();
+        writer.finish().unwrap();
+        archive.extend_from_slice(&writer.take_output());
+
+        assert!(archive.len() < content.len() / 10);
+        let mut archive = zip::ZipArchive::new(Cursor::new(archive)).unwrap();
+        assert_eq!(read_entry(&mut archive, "grande.bin"), content);
+    }
+
+    #[test]
+    fn resumes_from_a_checkpoint() {
+        let mut writer = ZipStreamWriter::new();
+        writer.start_file("a.txt", 0, 3).unwrap();
+        writer.write(b"uno").unwrap();
+        assert!(writer.checkpoint().is_err());
+        writer.finish_file().unwrap();
+        let mut archive = writer.take_output().to_vec();
+        let checkpoint = writer.checkpoint().unwrap();
+        assert_eq!(checkpoint.offset, archive.len() as u64);
+
+        // Lo escrito tras el punto de control se pierde y se vuelve a generar
+        writer.start_file("b.txt", 0, 3).unwrap();
+        writer.write(b"dos").unwrap();
+        drop(writer);
+
+        let checkpoint: ZipCheckpoint = serde_json::from_str(&serde_json::to_string(&checkpoint).unwrap()).unwrap();
+        let mut writer = ZipStreamWriter::resume(checkpoint);
+        writer.start_file("b.txt", 0, 3).unwrap();
+        writer.write(b"dos").unwrap();
+        writer.finish_file().unwrap();
+        writer.finish().unwrap();
+        archive.extend_from_slice(&writer.take_output());
+
+        let mut archive = zip::ZipArchive::new(Cursor::new(archive)).unwrap();
+        assert_eq!(archive.len(), 2);
+        assert_eq!(read_entry(&mut archive, "a.txt"), b"uno");
+        assert_eq!(read_entry(&mut archive, "b.txt"), b"dos");
+    }
+
+    #[test]
+    fn rejects_entries_while_a_file_is_open() {
+        let mut writer = ZipStreamWriter::new();
+        writer.start_file("a.txt", 0, 0).unwrap();
+        assert!(writer.add_directory("b/", 0).is_err());
+        assert!(writer.finish().is_err());
+        writer.finish_file().unwrap();
+        assert!(writer.write(b"x").is_err());
+    }
+
+    #[test]
+    fn converts_timestamps_to_dos_format() {
+        // 2024-03-15 13:45:30 UTC
+        let (time, date) = dos_date_time(1_710_510_330);
+        assert_eq!(time, (13 << 11) | (45 << 5) | 15);
+        assert_eq!(date, (44 << 9) | (3 << 5) | 15);
+        // Antes de 1980 se usa el 1 de enero de 1980
+        assert_eq!(dos_date_time(0), (0, (1 << 5) | 1));
+    }
+}
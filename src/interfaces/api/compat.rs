@@ -0,0 +1,162 @@
+//! Versions of the public REST API and the shims that keep older contracts
+//! working after a breaking DTO change.
+//!
+//! Handlers always build the current DTO. When a response has to look
+//! different for older clients, the DTO implements [`CompatShim::shim`] and
+//! the handler returns it wrapped in [`Versioned`], so both contracts are
+//! served from the same code.
+
+use std::convert::Infallible;
+
+use axum::{
+    extract::FromRequestParts,
+    http::{request::Parts, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+use crate::common::errors::AppError;
+
+/// Header a client can send to pick a version on unversioned routes; the
+/// server echoes the version it used
+pub const API_VERSION_HEADER: &str = "x-oxicloud-api-version";
+
+/// Vendor media type prefix accepted in `Accept` (`application/vnd.oxicloud.v1+json`)
+const VENDOR_MEDIA_TYPE: &str = "application/vnd.oxicloud.";
+
+/// Versions of the REST API, oldest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ApiVersion {
+    V1,
+}
+
+impl ApiVersion {
+    /// Version served when the client does not ask for one
+    pub const CURRENT: ApiVersion = ApiVersion::V1;
+
+    /// Every version the server still answers
+    pub const SUPPORTED: &'static [ApiVersion] = &[ApiVersion::V1];
+
+    pub fn number(self) -> u32 {
+        match self {
+            ApiVersion::V1 => 1,
+        }
+    }
+
+    /// Path segment of the version (`v1`)
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ApiVersion::V1 => "v1",
+        }
+    }
+
+    /// Parses `v1`, `V1` or `1`; unsupported versions return `None`
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let number = value.strip_prefix(['v', 'V']).unwrap_or(value);
+        Self::SUPPORTED.iter().copied().find(|version| version.number().to_string() == number)
+    }
+
+    /// Whether a path segment names a version, supported or not (`v1`, `v27`)
+    pub fn is_version_segment(segment: &str) -> bool {
+        segment.strip_prefix('v')
+            .is_some_and(|number| !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit()))
+    }
+}
+
+/// Returns the version requested through `X-OxiCloud-API-Version` or a vendor
+/// media type in `Accept`, failing with 406 when it is not supported
+pub fn requested_version(headers: &HeaderMap) -> Result<Option<ApiVersion>, AppError> {
+    let unsupported = |requested: &str| AppError::new(
+        StatusCode::NOT_ACCEPTABLE,
+        format!(
+            "API version {} is not supported; supported versions: {}",
+            requested,
+            ApiVersion::SUPPORTED.iter().map(|version| version.as_str()).collect::<Vec<_>>().join(", ")
+        ),
+        "UnsupportedApiVersion",
+    );
+
+    if let Some(value) = headers.get(API_VERSION_HEADER) {
+        let requested = value.to_str().unwrap_or_default();
+        return ApiVersion::parse(requested).map(Some).ok_or_else(|| unsupported(requested));
+    }
+
+    let accept = headers.get(axum::http::header::ACCEPT).and_then(|v| v.to_str().ok()).unwrap_or_default();
+    for media_type in accept.split(',') {
+        let media_type = media_type.split(';').next().unwrap_or_default().trim();
+        if let Some(vendor) = media_type.strip_prefix(VENDOR_MEDIA_TYPE) {
+            let requested = vendor.split('+').next().unwrap_or_default();
+            return ApiVersion::parse(requested).map(Some).ok_or_else(|| unsupported(requested));
+        }
+    }
+    Ok(None)
+}
+
+/// The version negotiated for the request; requests that did not go through
+/// the versioning middleware get the current one
+impl<S: Send + Sync> FromRequestParts<S> for ApiVersion {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts.extensions.get::<ApiVersion>().copied().unwrap_or(ApiVersion::CURRENT))
+    }
+}
+
+/// A DTO whose JSON representation may differ between API versions
+pub trait CompatShim: Serialize {
+    /// Rewrites the current JSON representation into the one clients of
+    /// `version` expect. Only DTOs with a breaking change override it.
+    fn shim(value: serde_json::Value, _version: ApiVersion) -> serde_json::Value {
+        value
+    }
+}
+
+/// JSON response rendered for the negotiated API version
+pub struct Versioned<T>(pub ApiVersion, pub T);
+
+impl<T: CompatShim> IntoResponse for Versioned<T> {
+    fn into_response(self) -> Response {
+        let Versioned(version, dto) = self;
+        if version == ApiVersion::CURRENT {
+            return Json(dto).into_response();
+        }
+        match serde_json::to_value(&dto) {
+            Ok(value) => Json(T::shim(value, version)).into_response(),
+            Err(e) => AppError::internal_error(format!("Failed to serialize response: {}", e)).into_response(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn parses_supported_versions_only() {
+        assert_eq!(ApiVersion::parse("v1"), Some(ApiVersion::V1));
+        assert_eq!(ApiVersion::parse("1"), Some(ApiVersion::V1));
+        assert_eq!(ApiVersion::parse("v2"), None);
+        assert!(ApiVersion::is_version_segment("v27"));
+        assert!(!ApiVersion::is_version_segment("videos"));
+        assert!(!ApiVersion::is_version_segment("v"));
+    }
+
+    #[test]
+    fn negotiates_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(requested_version(&headers).unwrap(), None);
+
+        headers.insert("accept", HeaderValue::from_static("text/html, application/vnd.oxicloud.v1+json;q=0.9"));
+        assert_eq!(requested_version(&headers).unwrap(), Some(ApiVersion::V1));
+
+        headers.insert("accept", HeaderValue::from_static("application/vnd.oxicloud.v3+json"));
+        assert_eq!(requested_version(&headers).unwrap_err().status_code, StatusCode::NOT_ACCEPTABLE);
+
+        // The explicit header wins over Accept
+        headers.insert(API_VERSION_HEADER, HeaderValue::from_static("1"));
+        assert_eq!(requested_version(&headers).unwrap(), Some(ApiVersion::V1));
+    }
+}
@@ -0,0 +1,95 @@
+//! Entity tags and conditional requests (RFC 9110, sections 8.8.3 and 13).
+//!
+//! Responses that are rebuilt on every request (PROPFIND listings, discovery
+//! documents) get a weak ETag computed from the data they are rendered from,
+//! so a polling client can be answered with 304 before anything is rendered.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use axum::{
+    body::Body,
+    http::{header, HeaderMap, HeaderValue, Response, StatusCode},
+};
+
+/// Builds a weak ETag from the values a response depends on
+#[derive(Default)]
+pub struct WeakEtag {
+    hasher: DefaultHasher,
+}
+
+impl WeakEtag {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a value the response depends on
+    pub fn add<T: Hash + ?Sized>(&mut self, value: &T) -> &mut Self {
+        value.hash(&mut self.hasher);
+        self
+    }
+
+    /// The tag, in its quoted `W/"..."` form
+    pub fn finish(&self) -> String {
+        format!("W/\"{:016x}\"", self.hasher.finish())
+    }
+}
+
+/// Removes the weak prefix and the quotes from an entity tag
+fn opaque_tag(tag: &str) -> &str {
+    let tag = tag.trim();
+    tag.strip_prefix("W/").unwrap_or(tag).trim_matches('"')
+}
+
+/// Whether `If-None-Match` lists `etag` (or `*`), using the weak comparison
+/// RFC 9110 requires for this header
+pub fn if_none_match(headers: &HeaderMap, etag: &str) -> bool {
+    headers.get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|tag| tag.trim() == "*" || opaque_tag(tag) == opaque_tag(etag))
+}
+
+/// Empty 304 response carrying the current ETag
+pub fn not_modified(etag: &str) -> Response<Body> {
+    let mut response = Response::builder()
+        .status(StatusCode::NOT_MODIFIED)
+        .body(Body::empty())
+        .unwrap();
+    set_etag(&mut response, etag);
+    response
+}
+
+/// Adds an `ETag` header to a response
+pub fn set_etag(response: &mut Response<Body>, etag: &str) {
+    if let Ok(value) = HeaderValue::from_str(etag) {
+        response.headers_mut().insert(header::ETAG, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weak_tags_change_with_their_inputs() {
+        let tag = WeakEtag::new().add("folder").add(&42u64).finish();
+        assert!(tag.starts_with("W/\"") && tag.ends_with('"'));
+        assert_eq!(tag, WeakEtag::new().add("folder").add(&42u64).finish());
+        assert_ne!(tag, WeakEtag::new().add("folder").add(&43u64).finish());
+    }
+
+    #[test]
+    fn if_none_match_uses_weak_comparison() {
+        let mut headers = HeaderMap::new();
+        assert!(!if_none_match(&headers, "W/\"abc\""));
+
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_static("\"xyz\", \"abc\""));
+        assert!(if_none_match(&headers, "W/\"abc\""));
+        assert!(!if_none_match(&headers, "W/\"abd\""));
+
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_static("*"));
+        assert!(if_none_match(&headers, "\"anything\""));
+    }
+}
@@ -0,0 +1,105 @@
+use std::pin::Pin;
+
+use axum::{
+    body::Body,
+    http::{HeaderMap, HeaderName, HeaderValue},
+};
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use http_body::Frame;
+use http_body_util::StreamBody;
+use sha2::{Digest, Sha256};
+
+use crate::domain::services::content_digest_service::digest_header_value;
+
+type ByteStream = Pin<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>>;
+
+/// Header with the hexadecimal SHA-256 of the stored file
+pub const X_HASH_SHA256: HeaderName = HeaderName::from_static("x-hash-sha256");
+
+/// RFC 3230 instance digest
+pub const DIGEST: HeaderName = HeaderName::from_static("digest");
+
+/// Adds `X-Hash-SHA256` and, with `with_digest`, the RFC 3230 `Digest` header.
+///
+/// `Digest` describes the bytes on the wire, so callers leave it out of
+/// content-coded responses.
+pub fn insert_digest_headers(headers: &mut HeaderMap, sha256: &str, with_digest: bool) {
+    if let Ok(value) = HeaderValue::from_str(sha256) {
+        headers.insert(X_HASH_SHA256, value);
+    }
+    if with_digest {
+        if let Some(value) = digest_header_value(sha256).and_then(|value| HeaderValue::from_str(&value).ok()) {
+            headers.insert(DIGEST, value);
+        }
+    }
+}
+
+/// What a whole-file download does with the SHA-256 of the bytes it sent
+pub enum DownloadDigest {
+    /// Compare with the stored hash; on a mismatch the last chunk is never
+    /// sent, so the client sees a short, failed transfer instead of corrupt data
+    Verify(String),
+    /// Hand the hash of a complete transfer to `on_complete`, also sending it
+    /// as trailers when the client accepts them
+    Record {
+        trailers: bool,
+        with_digest: bool,
+        on_complete: Box<dyn FnOnce(String) + Send>,
+    },
+}
+
+/// Response body that hashes the file as it is streamed
+pub fn digest_body(stream: ByteStream, digest: DownloadDigest) -> Body {
+    let frames = async_stream::stream! {
+        let mut stream = stream;
+        let mut hasher = Sha256::new();
+        // One chunk is held back so a failed verification can still cut the transfer short
+        let mut pending: Option<Bytes> = None;
+        while let Some(chunk) = stream.next().await {
+            match chunk {
+                Ok(chunk) => {
+                    hasher.update(&chunk);
+                    if let Some(previous) = pending.replace(chunk) {
+                        yield Ok(Frame::data(previous));
+                    }
+                }
+                Err(e) => {
+                    yield Err(e);
+                    return;
+                }
+            }
+        }
+
+        let sha256 = format!("{:x}", hasher.finalize());
+        match digest {
+            DownloadDigest::Verify(expected) if expected != sha256 => {
+                tracing::error!("Download checksum mismatch: stored {}, read {}", expected, sha256);
+                yield Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "File content does not match its stored checksum"));
+                return;
+            }
+            DownloadDigest::Verify(_) => {
+                if let Some(last) = pending {
+                    yield Ok(Frame::data(last));
+                }
+            }
+            DownloadDigest::Record { trailers, with_digest, on_complete } => {
+                if let Some(last) = pending {
+                    yield Ok(Frame::data(last));
+                }
+                if trailers {
+                    let mut headers = HeaderMap::new();
+                    insert_digest_headers(&mut headers, &sha256, with_digest);
+                    yield Ok(Frame::trailers(headers));
+                }
+                on_complete(sha256);
+            }
+        }
+    };
+    Body::new(StreamBody::new(frames))
+}
+
+/// Value of the `Trailer` header announcing the digest trailers
+pub fn trailer_names(with_digest: bool) -> &'static str {
+    if with_digest { "X-Hash-SHA256, Digest" } else { "X-Hash-SHA256" }
+}
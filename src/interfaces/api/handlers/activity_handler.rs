@@ -0,0 +1,39 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::get,
+    extract::{Path, Query, State, Json, Extension},
+    response::IntoResponse,
+};
+
+use crate::application::dtos::activity_dto::ActivityQueryDto;
+use crate::application::ports::activity_ports::ActivityUseCase;
+use crate::common::errors::AppError;
+use crate::interfaces::middleware::auth::CurrentUser;
+
+pub type ActivityState = Arc<dyn ActivityUseCase>;
+
+/// Routes for the activity feed of the current user
+pub fn activity_routes() -> Router<ActivityState> {
+    Router::new()
+        .route("/", get(list_activity))
+}
+
+/// Lists what the current user uploaded, renamed, deleted, shared or restored
+async fn list_activity(
+    State(service): State<ActivityState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(query): Query<ActivityQueryDto>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(service.list_user_activity(&current_user.id, query).await?))
+}
+
+/// Lists what anyone did in a folder or to the folder itself
+pub async fn folder_activity(
+    State(service): State<ActivityState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+    Query(query): Query<ActivityQueryDto>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(service.list_folder_activity(&current_user.username, current_user.is_admin(), &id, query).await?))
+}
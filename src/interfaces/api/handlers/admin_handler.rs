@@ -0,0 +1,817 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::{get, post, put},
+    extract::{State, Path, Json, Extension, Query},
+    body::Bytes,
+    http::{StatusCode, HeaderMap, header},
+    response::{IntoResponse, Response},
+};
+
+use crate::application::dtos::announcement_dto::SaveAnnouncementDto;
+use crate::application::dtos::audit_log_dto::AuditLogQueryDto;
+use crate::application::dtos::dav_capture_dto::StartDavCaptureDto;
+use crate::application::dtos::feature_flag_dto::{SetFlagOverrideDto, UpdateFeatureFlagDto};
+use crate::application::dtos::quota_dto::{SetQuotaPolicyDto, SetStorageQuotaDto};
+use crate::application::dtos::theme_dto::UpdateThemeDto;
+use crate::application::dtos::user_dto::{ChangeRoleDto, DirectoryLinkDto};
+use crate::application::dtos::user_group_dto::{CreateUserGroupDto, UpdateUserGroupDto};
+use crate::application::dtos::pagination::PaginationRequestDto;
+use crate::application::dtos::webhook_dto::{CreateWebhookDto, UpdateWebhookDto};
+use crate::application::ports::announcement_ports::AnnouncementUseCase;
+use crate::application::ports::audit_ports::AuditLogUseCase;
+use crate::application::ports::dav_capture_ports::DavCaptureUseCase;
+use crate::application::ports::demo_ports::DemoUseCase;
+use crate::application::ports::feature_flag_ports::FeatureFlagUseCase;
+use crate::application::ports::integrity_ports::IntegrityUseCase;
+use crate::application::ports::quota_ports::StorageQuotaUseCase;
+use crate::application::ports::storage_gc_ports::StorageGcUseCase;
+use crate::application::ports::template_ports::TemplateUseCase;
+use crate::application::ports::theme_ports::ThemeUseCase;
+use crate::application::ports::user_group_ports::UserGroupUseCase;
+use crate::application::ports::user_quarantine_ports::UserQuarantineUseCase;
+use crate::application::ports::webhook_ports::WebhookUseCase;
+use crate::domain::entities::feature_flag::FlagScope;
+use crate::domain::services::path_codec_service::content_disposition;
+use crate::common::di::AppState;
+use crate::interfaces::middleware::auth::CurrentUser;
+use crate::common::errors::AppError;
+
+pub fn admin_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/users", get(list_users))
+        .route("/users/{id}/role", put(change_user_role))
+        .route("/users/{id}/directory", put(set_directory_link))
+        .route("/users/{id}/skeleton", post(apply_user_skeleton))
+}
+
+/// `?dry_run=true` de las operaciones destructivas: devuelve el plan con lo
+/// que se haría en lugar de hacerlo
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct DryRunQuery {
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Paginación del listado de usuarios
+#[derive(Debug, serde::Deserialize)]
+pub struct ListUsersQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Rutas para publicar anuncios y consultar cuántos destinatarios los han leído
+pub fn announcement_routes() -> Router<Arc<dyn AnnouncementUseCase>> {
+    Router::new()
+        .route("/", get(list_announcements).post(create_announcement))
+        .route("/{id}", put(update_announcement).delete(delete_announcement))
+        .route("/{id}/acknowledgment", get(get_announcement_acknowledgment))
+}
+
+/// Rutas para capturar el tráfico DAV de un usuario y descargar el paquete de diagnóstico
+pub fn dav_capture_routes() -> Router<Arc<dyn DavCaptureUseCase>> {
+    Router::new()
+        .route("/", get(list_dav_captures).post(start_dav_capture))
+        .route("/{username}", axum::routing::delete(discard_dav_capture))
+        .route("/{username}/stop", post(stop_dav_capture))
+        .route("/{username}/bundle", get(download_dav_capture_bundle))
+}
+
+/// Rutas para restaurar a petición la cuenta de demostración
+pub fn demo_routes() -> Router<Arc<dyn DemoUseCase>> {
+    Router::new()
+        .route("/reset", post(reset_demo))
+}
+
+/// Rutas para consultar y cambiar los feature flags en caliente
+pub fn feature_flag_routes() -> Router<Arc<dyn FeatureFlagUseCase>> {
+    Router::new()
+        .route("/", get(list_feature_flags))
+        .route("/{key}", put(upsert_feature_flag).delete(delete_feature_flag))
+        .route("/{key}/overrides/{scope}/{subject}", put(set_feature_flag_override).delete(remove_feature_flag_override))
+}
+
+/// Rutas para consultar y ajustar la cuota de almacenamiento de cada usuario,
+/// las cuotas por defecto de cada grupo y el informe de asignación y uso
+pub fn quota_routes() -> Router<Arc<dyn StorageQuotaUseCase>> {
+    Router::new()
+        .route("/report", get(get_quota_report))
+        .route("/policies", get(list_quota_policies))
+        .route("/policies/{group}", put(set_quota_policy).delete(delete_quota_policy))
+        .route("/{user_id}", get(get_storage_quota).put(set_storage_quota))
+        .route("/{user_id}/override", axum::routing::delete(clear_storage_quota_override))
+}
+
+/// Rutas para consultar y lanzar la recolección de basura del almacenamiento
+pub fn storage_gc_routes() -> Router<Arc<dyn StorageGcUseCase>> {
+    Router::new()
+        .route("/", get(get_storage_gc_stats))
+        .route("/run", post(run_storage_gc))
+}
+
+/// Rutas para consultar el registro de auditoría por usuario, tipo de suceso
+/// y periodo
+pub fn audit_log_routes() -> Router<Arc<dyn AuditLogUseCase>> {
+    Router::new()
+        .route("/", get(query_audit_log))
+}
+
+/// Rutas para buscar enlaces compartidos, favoritos y miembros de grupos que
+/// apuntan a lo que ya no existe, y para eliminarlos o repararlos
+pub fn integrity_routes() -> Router<Arc<dyn IntegrityUseCase>> {
+    Router::new()
+        .route("/", get(check_integrity))
+        .route("/repair", post(repair_integrity))
+}
+
+/// Rutas para cambiar el nombre, los colores, el logo y el mensaje de acceso de la instancia
+pub fn theme_routes() -> Router<Arc<dyn ThemeUseCase>> {
+    Router::new()
+        .route("/", put(update_theme).delete(reset_theme))
+        .route("/logo", put(upload_theme_logo).delete(remove_theme_logo))
+}
+
+/// Rutas para crear grupos de usuarios y gestionar sus miembros
+pub fn user_group_routes() -> Router<Arc<dyn UserGroupUseCase>> {
+    Router::new()
+        .route("/", get(list_user_groups).post(create_user_group))
+        .route("/{id}", get(get_user_group).put(update_user_group).delete(delete_user_group))
+        .route("/{id}/members/{user_id}", put(add_user_group_member).delete(remove_user_group_member))
+}
+
+/// Rutas para borrar usuarios conservando sus datos, reactivarlos o
+/// terminar de borrarlos
+pub fn user_quarantine_routes() -> Router<Arc<dyn UserQuarantineUseCase>> {
+    Router::new()
+        .route("/quarantine", get(list_quarantined_users))
+        .route("/{id}", axum::routing::delete(quarantine_user))
+        .route("/{id}/reactivate", post(reactivate_user))
+        .route("/{id}/purge", post(purge_user))
+}
+
+/// Rutas para gestionar las plantillas de documentos que se ofrecen a todos los usuarios
+pub fn template_routes() -> Router<Arc<dyn TemplateUseCase>> {
+    Router::new()
+        .route("/", get(list_global_templates))
+        .route("/{name}", put(save_global_template).delete(delete_global_template))
+}
+
+/// Rutas para registrar webhooks y consultar sus envíos
+pub fn webhook_routes() -> Router<Arc<dyn WebhookUseCase>> {
+    Router::new()
+        .route("/", get(list_webhooks).post(create_webhook))
+        .route("/{id}", get(get_webhook).put(update_webhook).delete(delete_webhook))
+        .route("/{id}/deliveries", get(list_webhook_deliveries))
+}
+
+/// Verifica que el usuario actual tenga rol de administrador
+fn ensure_admin(current_user: &CurrentUser) -> Result<(), AppError> {
+    if !current_user.is_admin() {
+        return Err(AppError::forbidden("Se requiere rol de administrador"));
+    }
+    Ok(())
+}
+
+/// Lista los usuarios de la instancia
+async fn list_users(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(query): Query<ListUsersQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let auth_service = state.auth_service.as_ref()
+        .ok_or_else(|| AppError::internal_error("Servicio de autenticación no configurado"))?;
+    
+    let limit = query.limit.unwrap_or(100).clamp(1, 1000);
+    let offset = query.offset.unwrap_or(0).max(0);
+    let users = auth_service.auth_application_service
+        .list_users(&current_user.caller(), limit, offset).await?;
+    
+    Ok(Json(users))
+}
+
+/// Cambia el rol de un usuario
+async fn change_user_role(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(user_id): Path<String>,
+    Json(dto): Json<ChangeRoleDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let auth_service = state.auth_service.as_ref()
+        .ok_or_else(|| AppError::internal_error("Servicio de autenticación no configurado"))?;
+    
+    let user = auth_service.auth_application_service
+        .change_role(&current_user.caller(), &user_id, &dto.role).await?;
+    
+    Ok(Json(user))
+}
+
+/// Vincula una cuenta local con el directorio externo, o deshace el vínculo
+async fn set_directory_link(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(user_id): Path<String>,
+    Json(dto): Json<DirectoryLinkDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let auth_service = state.auth_service.as_ref()
+        .ok_or_else(|| AppError::internal_error("Servicio de autenticación no configurado"))?;
+    
+    let user = auth_service.auth_application_service
+        .set_directory_link(&current_user.caller(), &user_id, dto.linked).await?;
+    
+    Ok(Json(user))
+}
+
+/// Vuelve a aplicar el esqueleto de carpetas en la carpeta personal de un usuario
+async fn apply_user_skeleton(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(user_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    
+    let auth_service = state.auth_service.as_ref()
+        .ok_or_else(|| AppError::internal_error("Servicio de autenticación no configurado"))?;
+    
+    let result = auth_service.auth_application_service.apply_user_skeleton(&user_id).await?;
+    
+    Ok((StatusCode::OK, Json(result)))
+}
+
+/// Lista las capturas DAV activas y terminadas
+async fn list_dav_captures(
+    State(capture): State<Arc<dyn DavCaptureUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(capture.list_captures().await?))
+}
+
+/// Empieza a capturar las peticiones DAV de un usuario durante un tiempo limitado
+async fn start_dav_capture(
+    State(capture): State<Arc<dyn DavCaptureUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(request): Json<StartDavCaptureDto>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    let session = capture
+        .start_capture(request.username.trim(), request.minutes, &current_user.username)
+        .await?;
+    Ok((StatusCode::CREATED, Json(session)))
+}
+
+/// Detiene una captura conservando lo grabado
+async fn stop_dav_capture(
+    State(capture): State<Arc<dyn DavCaptureUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(username): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(capture.stop_capture(&username).await?))
+}
+
+/// Elimina una captura y sus datos
+async fn discard_dav_capture(
+    State(capture): State<Arc<dyn DavCaptureUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(username): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    capture.discard_capture(&username).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Descarga el paquete de diagnóstico (ZIP) de una captura
+async fn download_dav_capture_bundle(
+    State(capture): State<Arc<dyn DavCaptureUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(username): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    let bundle = capture.export_bundle(&username).await?;
+    let filename = format!(
+        "dav-capture-{}-{}.zip",
+        username,
+        chrono::Utc::now().format("%Y%m%d-%H%M%S")
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (header::CONTENT_DISPOSITION, content_disposition("attachment", &filename)),
+            (header::CACHE_CONTROL, "no-store".to_string()),
+        ],
+        bundle,
+    ))
+}
+
+/// Interpreta el ámbito de una excepción recibido en la ruta
+fn parse_flag_scope(scope: &str) -> Result<FlagScope, AppError> {
+    FlagScope::parse(scope)
+        .ok_or_else(|| AppError::bad_request(format!("Ámbito desconocido '{}': use 'group' o 'user'", scope)))
+}
+
+/// Lista los feature flags con sus excepciones
+async fn list_feature_flags(
+    State(flags): State<Arc<dyn FeatureFlagUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(flags.list_flags().await?))
+}
+
+/// Crea un flag o cambia su regla global (activación y porcentaje de despliegue)
+async fn upsert_feature_flag(
+    State(flags): State<Arc<dyn FeatureFlagUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(key): Path<String>,
+    Json(update): Json<UpdateFeatureFlagDto>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(flags.upsert_flag(&key, update).await?))
+}
+
+/// Elimina un flag y sus excepciones
+async fn delete_feature_flag(
+    State(flags): State<Arc<dyn FeatureFlagUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(key): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    flags.delete_flag(&key).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Activa o desactiva un flag para un grupo o un usuario
+async fn set_feature_flag_override(
+    State(flags): State<Arc<dyn FeatureFlagUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((key, scope, subject)): Path<(String, String, String)>,
+    Json(request): Json<SetFlagOverrideDto>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    let scope = parse_flag_scope(&scope)?;
+    Ok(Json(flags.set_override(&key, scope, &subject, request.enabled).await?))
+}
+
+/// Quita la excepción de un grupo o usuario
+async fn remove_feature_flag_override(
+    State(flags): State<Arc<dyn FeatureFlagUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((key, scope, subject)): Path<(String, String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    let scope = parse_flag_scope(&scope)?;
+    Ok(Json(flags.remove_override(&key, scope, &subject).await?))
+}
+
+/// Devuelve la cuota, el uso y el espacio disponible de un usuario
+async fn get_storage_quota(
+    State(quotas): State<Arc<dyn StorageQuotaUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(user_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(quotas.get_quota(&current_user.caller(), &user_id).await?))
+}
+
+/// Cambia la cuota de un usuario; un valor negativo la deja sin límite
+async fn set_storage_quota(
+    State(quotas): State<Arc<dyn StorageQuotaUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(user_id): Path<String>,
+    Json(dto): Json<SetStorageQuotaDto>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(quotas.set_quota(&current_user.caller(), &user_id, dto.quota_bytes).await?))
+}
+
+/// Vuelve a aplicar a un usuario la cuota de su grupo
+async fn clear_storage_quota_override(
+    State(quotas): State<Arc<dyn StorageQuotaUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(user_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(quotas.clear_quota_override(&current_user.caller(), &user_id).await?))
+}
+
+/// Lista la cuota por defecto de cada grupo
+async fn list_quota_policies(
+    State(quotas): State<Arc<dyn StorageQuotaUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(quotas.list_policies(&current_user.caller()).await?))
+}
+
+/// Crea o cambia la cuota por defecto de un grupo
+async fn set_quota_policy(
+    State(quotas): State<Arc<dyn StorageQuotaUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(group): Path<String>,
+    Json(dto): Json<SetQuotaPolicyDto>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(quotas.set_policy(&current_user.caller(), &group, dto).await?))
+}
+
+/// Elimina la cuota por defecto de un grupo
+async fn delete_quota_policy(
+    State(quotas): State<Arc<dyn StorageQuotaUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(group): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    quotas.delete_policy(&current_user.caller(), &group).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Cuota asignada frente a espacio usado, por grupo y por usuario
+async fn get_quota_report(
+    State(quotas): State<Arc<dyn StorageQuotaUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(quotas.allocation_report(&current_user.caller()).await?))
+}
+
+/// Lista los grupos de usuarios con sus miembros
+async fn list_user_groups(
+    State(groups): State<Arc<dyn UserGroupUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(groups.list_groups(&current_user.caller()).await?))
+}
+
+/// Devuelve un grupo con sus miembros
+async fn get_user_group(
+    State(groups): State<Arc<dyn UserGroupUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(groups.get_group(&current_user.caller(), &id).await?))
+}
+
+/// Crea un grupo, opcionalmente con sus primeros miembros
+async fn create_user_group(
+    State(groups): State<Arc<dyn UserGroupUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(dto): Json<CreateUserGroupDto>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok((StatusCode::CREATED, Json(groups.create_group(&current_user.caller(), dto).await?)))
+}
+
+/// Cambia el nombre o la descripción de un grupo
+async fn update_user_group(
+    State(groups): State<Arc<dyn UserGroupUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+    Json(dto): Json<UpdateUserGroupDto>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(groups.update_group(&current_user.caller(), &id, dto).await?))
+}
+
+/// Elimina un grupo; deja de compartirse con él lo que se le había compartido
+async fn delete_user_group(
+    State(groups): State<Arc<dyn UserGroupUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    groups.delete_group(&current_user.caller(), &id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Añade un usuario a un grupo
+async fn add_user_group_member(
+    State(groups): State<Arc<dyn UserGroupUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((id, user_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(groups.add_member(&current_user.caller(), &id, &user_id).await?))
+}
+
+/// Quita a un usuario de un grupo
+async fn remove_user_group_member(
+    State(groups): State<Arc<dyn UserGroupUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((id, user_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(groups.remove_member(&current_user.caller(), &id, &user_id).await?))
+}
+
+/// Entradas del registro de auditoría, las más recientes primero
+async fn query_audit_log(
+    State(audit_log): State<Arc<dyn AuditLogUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(query): Query<AuditLogQueryDto>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(audit_log.query(query).await?))
+}
+
+/// Lista los webhooks registrados
+async fn list_webhooks(
+    State(webhooks): State<Arc<dyn WebhookUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(webhooks.list_webhooks().await?))
+}
+
+/// Registra un webhook; la respuesta incluye el secreto de la firma, que no
+/// se vuelve a mostrar
+async fn create_webhook(
+    State(webhooks): State<Arc<dyn WebhookUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(dto): Json<CreateWebhookDto>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok((StatusCode::CREATED, Json(webhooks.create_webhook(dto).await?)))
+}
+
+/// Obtiene un webhook
+async fn get_webhook(
+    State(webhooks): State<Arc<dyn WebhookUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(webhooks.get_webhook(&id).await?))
+}
+
+/// Cambia la URL, los sucesos, la descripción o el estado de un webhook
+async fn update_webhook(
+    State(webhooks): State<Arc<dyn WebhookUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+    Json(dto): Json<UpdateWebhookDto>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(webhooks.update_webhook(&id, dto).await?))
+}
+
+/// Borra un webhook y sus envíos
+async fn delete_webhook(
+    State(webhooks): State<Arc<dyn WebhookUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    webhooks.delete_webhook(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Envíos de un webhook, los más recientes primero
+async fn list_webhook_deliveries(
+    State(webhooks): State<Arc<dyn WebhookUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+    Query(pagination): Query<PaginationRequestDto>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(webhooks.list_deliveries(&id, pagination).await?))
+}
+
+/// Espacio recuperado por la recolección de basura y resultado de la última pasada
+async fn get_storage_gc_stats(
+    State(gc): State<Arc<dyn StorageGcUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(gc.stats().await))
+}
+
+/// Cuenta las referencias rotas sin tocar nada, para revisarlas antes de reparar
+async fn check_integrity(
+    State(integrity): State<Arc<dyn IntegrityUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(integrity.check(false).await?))
+}
+
+/// Elimina o repara las referencias rotas y devuelve el resumen; con
+/// `?dry_run=true` solo devuelve el plan de lo que cambiaría
+async fn repair_integrity(
+    State(integrity): State<Arc<dyn IntegrityUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(query): Query<DryRunQuery>,
+) -> Result<Response, AppError> {
+    ensure_admin(&current_user)?;
+    if query.dry_run {
+        return Ok(Json(integrity.dry_run_repair().await?).into_response());
+    }
+    let report = integrity.check(true).await?;
+    tracing::info!("{} reparó las referencias rotas ({} errores)", current_user.username, report.errors.len());
+    Ok(Json(report).into_response())
+}
+
+/// Lanza una pasada de la recolección de basura en segundo plano; con
+/// `?dry_run=true` devuelve al momento lo que borraría
+async fn run_storage_gc(
+    State(gc): State<Arc<dyn StorageGcUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(query): Query<DryRunQuery>,
+) -> Result<Response, AppError> {
+    ensure_admin(&current_user)?;
+    if query.dry_run {
+        return Ok(Json(gc.dry_run().await?).into_response());
+    }
+    if gc.stats().await.running {
+        return Err(AppError::conflict("Ya hay una recolección de basura en curso"));
+    }
+
+    let service = gc.clone();
+    tokio::spawn(async move {
+        if let Err(e) = service.run().await {
+            tracing::error!("Error en la recolección de basura solicitada: {}", e);
+        }
+    });
+    Ok((StatusCode::ACCEPTED, Json(gc.stats().await)).into_response())
+}
+
+/// Cambia el nombre, los colores o el mensaje de acceso de la instancia
+async fn update_theme(
+    State(theme): State<Arc<dyn ThemeUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(update): Json<UpdateThemeDto>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(theme.update_theme(update).await?))
+}
+
+/// Vuelve a la imagen de marca original de OxiCloud
+async fn reset_theme(
+    State(theme): State<Arc<dyn ThemeUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(theme.reset_theme().await?))
+}
+
+/// Sustituye el logo por la imagen enviada en el cuerpo de la petición
+async fn upload_theme_logo(
+    State(theme): State<Arc<dyn ThemeUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    let content_type = headers.get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| AppError::unsupported_media_type("Falta la cabecera Content-Type del logo"))?;
+    Ok(Json(theme.set_logo(content_type, body.to_vec()).await?))
+}
+
+/// Quita el logo personalizado y vuelve al original
+async fn remove_theme_logo(
+    State(theme): State<Arc<dyn ThemeUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(theme.remove_logo().await?))
+}
+
+/// Lista los anuncios con su tasa de lectura
+async fn list_announcements(
+    State(announcements): State<Arc<dyn AnnouncementUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(announcements.list_announcements().await?))
+}
+
+/// Publica un anuncio para todos los usuarios o para algunos grupos
+async fn create_announcement(
+    State(announcements): State<Arc<dyn AnnouncementUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(request): Json<SaveAnnouncementDto>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    let announcement = announcements.create_announcement(&current_user.id, request).await?;
+    Ok((StatusCode::CREATED, Json(announcement)))
+}
+
+/// Cambia el texto, la gravedad, la ventana o los destinatarios de un anuncio
+async fn update_announcement(
+    State(announcements): State<Arc<dyn AnnouncementUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+    Json(request): Json<SaveAnnouncementDto>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(announcements.update_announcement(&id, request).await?))
+}
+
+/// Elimina un anuncio y sus lecturas
+async fn delete_announcement(
+    State(announcements): State<Arc<dyn AnnouncementUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    announcements.delete_announcement(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Devuelve cuántos destinatarios han leído un anuncio
+async fn get_announcement_acknowledgment(
+    State(announcements): State<Arc<dyn AnnouncementUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(announcements.acknowledgment(&id).await?))
+}
+
+/// Restaura ahora la cuenta de demostración con los datos de ejemplo
+async fn reset_demo(
+    State(demo): State<Arc<dyn DemoUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(demo.reset().await?))
+}
+
+/// Borra un usuario: lo desactiva y conserva sus datos durante el periodo configurado
+async fn quarantine_user(
+    State(service): State<Arc<dyn UserQuarantineUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(user_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    let quarantined = service.quarantine_user(&current_user.id, &current_user.username, &user_id).await?;
+    Ok(Json(quarantined))
+}
+
+/// Lista los usuarios borrados cuyos datos aún se conservan
+async fn list_quarantined_users(
+    State(service): State<Arc<dyn UserQuarantineUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(service.list_quarantined().await?))
+}
+
+/// Deshace el borrado de un usuario en cuarentena
+async fn reactivate_user(
+    State(service): State<Arc<dyn UserQuarantineUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(user_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(service.reactivate_user(&user_id).await?))
+}
+
+/// Elimina definitivamente un usuario en cuarentena y sus datos; con
+/// `?dry_run=true` solo devuelve el plan
+async fn purge_user(
+    State(service): State<Arc<dyn UserQuarantineUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(user_id): Path<String>,
+    Query(query): Query<DryRunQuery>,
+) -> Result<Response, AppError> {
+    ensure_admin(&current_user)?;
+    let plan = service.purge_user(&user_id, query.dry_run).await?;
+    if plan.dry_run {
+        return Ok(Json(plan).into_response());
+    }
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// Lista las plantillas que se ofrecen a todos los usuarios
+async fn list_global_templates(
+    State(templates): State<Arc<dyn TemplateUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(templates.list_global_templates().await?))
+}
+
+/// Añade o sustituye una plantilla; el cuerpo es el contenido del fichero
+async fn save_global_template(
+    State(templates): State<Arc<dyn TemplateUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(name): Path<String>,
+    body: Bytes,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    Ok(Json(templates.save_global_template(&name, &body).await?))
+}
+
+/// Elimina una plantilla que se ofrecía a todos los usuarios
+async fn delete_global_template(
+    State(templates): State<Arc<dyn TemplateUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(name): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_admin(&current_user)?;
+    templates.delete_global_template(&name).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,56 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::{get, post},
+    extract::{Path, State, Json, Extension},
+    http::StatusCode,
+    response::IntoResponse,
+};
+
+use crate::application::ports::announcement_ports::AnnouncementUseCase;
+use crate::common::errors::AppError;
+use crate::interfaces::middleware::auth::CurrentUser;
+
+type AnnouncementState = Arc<dyn AnnouncementUseCase>;
+
+/// Routes for users to read the announcements published by admins
+pub fn announcement_routes() -> Router<AnnouncementState> {
+    Router::new()
+        .route("/", get(list_announcements))
+        .route("/banner", get(get_banner))
+        .route("/{id}/read", post(mark_read))
+}
+
+/// Groups an announcement can target; the role is the only group a user has for now
+fn user_groups(user: &CurrentUser) -> Vec<String> {
+    vec![user.role.clone()]
+}
+
+/// Lists the active announcements for the notification center
+async fn list_announcements(
+    State(service): State<AnnouncementState>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(service.list_for_user(&current_user.id, &user_groups(&current_user)).await?))
+}
+
+/// Returns the announcement to show in the banner, or 204 when there is none
+async fn get_banner(
+    State(service): State<AnnouncementState>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<axum::response::Response, AppError> {
+    Ok(match service.banner(&current_user.id, &user_groups(&current_user)).await? {
+        Some(announcement) => Json(announcement).into_response(),
+        None => StatusCode::NO_CONTENT.into_response(),
+    })
+}
+
+/// Acknowledges an announcement so it leaves the banner
+async fn mark_read(
+    State(service): State<AnnouncementState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    service.mark_read(&current_user.id, &user_groups(&current_user), &id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
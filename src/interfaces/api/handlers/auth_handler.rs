@@ -1,19 +1,41 @@
+use std::net::SocketAddr;
 use std::sync::Arc;
 use axum::{
     Router,
-    routing::{post, get, put},
-    extract::{State, Json, Extension},
+    routing::{post, get, put, delete},
+    extract::{ConnectInfo, State, Json, Extension, Path, Query},
     http::{StatusCode, HeaderMap, header},
-    response::IntoResponse,
+    response::{IntoResponse, Redirect},
 };
+use serde::Deserialize;
 
 use crate::common::di::AppState;
 use crate::application::dtos::user_dto::{
-    LoginDto, RegisterDto, UserDto, ChangePasswordDto, RefreshTokenDto, AuthResponseDto
+    LoginDto, RegisterDto, UserDto, ChangePasswordDto, RefreshTokenDto, AuthResponseDto, CreateAppPasswordDto,
+    RevokedSessionsDto, SessionClient,
 };
+use crate::application::services::oidc_service::{OidcLoginService, PENDING_LOGIN_TTL_MINUTES};
 use crate::interfaces::middleware::auth::CurrentUser;
 use crate::common::errors::AppError;
 
+/// Page the OpenID Connect callback sends the browser back to
+const OIDC_LOGIN_PAGE: &str = "/login";
+
+/// Cookie binding an OpenID Connect login to the browser that started it
+const OIDC_STATE_COOKIE: &str = "oxicloud_oidc_state";
+
+/// Path the state cookie is sent to: only the OpenID Connect routes
+const OIDC_COOKIE_PATH: &str = "/api/auth/oidc";
+
+/// Query parameters the provider sends back to the callback
+#[derive(Debug, Deserialize)]
+struct OidcCallbackQuery {
+    code: Option<String>,
+    state: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
 pub fn auth_routes() -> Router<Arc<AppState>> {
     Router::new()
         .route("/register", post(register))
@@ -22,6 +44,15 @@ pub fn auth_routes() -> Router<Arc<AppState>> {
         .route("/me", get(get_current_user))
         .route("/change-password", put(change_password))
         .route("/logout", post(logout))
+        .route("/app-passwords", get(list_app_passwords).post(create_app_password))
+        .route("/app-passwords/{id}", delete(revoke_app_password))
+        .route("/sessions", get(list_sessions))
+        .route("/sessions/revoke-others", post(revoke_other_sessions))
+        .route("/sessions/{id}", delete(revoke_session))
+        .route("/oidc/config", get(oidc_config))
+        .route("/oidc/login", get(oidc_login))
+        .route("/oidc/link", post(oidc_link))
+        .route("/oidc/callback", get(oidc_callback))
 }
 
 async fn register(
@@ -61,6 +92,7 @@ async fn register(
             created_at: now,
             updated_at: now,
             last_login_at: None,
+            auth_source: "local".to_string(),
         };
         
         return Ok((StatusCode::CREATED, Json(mock_user)));
@@ -138,8 +170,30 @@ async fn register(
     }
 }
 
+/// Device data recorded on the session: connection address and User-Agent
+fn session_client(headers: &HeaderMap, connect_info: Option<Extension<ConnectInfo<SocketAddr>>>) -> SessionClient {
+    SessionClient {
+        ip_address: connect_info.map(|Extension(ConnectInfo(addr))| addr.ip().to_string()),
+        user_agent: headers
+            .get(header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string),
+    }
+}
+
+/// Session of the access token the request was made with
+fn current_session_id(state: &AppState, headers: &HeaderMap) -> Option<String> {
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))?;
+    state.auth_service.as_ref()?.auth_application_service.token_session_id(token.trim())
+}
+
 async fn login(
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
     Json(dto): Json<LoginDto>,
 ) -> Result<impl IntoResponse, AppError> {
     // Add detailed logging for debugging
@@ -178,6 +232,7 @@ async fn login(
                 created_at: now,
                 updated_at: now,
                 last_login_at: None,
+                auth_source: "local".to_string(),
             },
             access_token: "mock_access_token".to_string(),
             refresh_token: "mock_refresh_token".to_string(),
@@ -189,7 +244,7 @@ async fn login(
     }
     
     // Try the normal login process
-    match auth_service.auth_application_service.login(dto.clone()).await {
+    match auth_service.auth_application_service.login(dto.clone(), session_client(&headers, connect_info)).await {
         Ok(auth_response) => {
             tracing::info!("Login successful for user: {}", dto.username);
             // Log the response structure for debugging
@@ -212,6 +267,8 @@ async fn login(
 
 async fn refresh_token(
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
     Json(dto): Json<RefreshTokenDto>,
 ) -> Result<impl IntoResponse, AppError> {
     // Add rate limiting for token refresh to prevent refresh loops
@@ -238,6 +295,7 @@ async fn refresh_token(
             created_at: now,
             updated_at: now,
             last_login_at: None,
+            auth_source: "local".to_string(),
         };
         
         let auth_response = AuthResponseDto {
@@ -255,7 +313,9 @@ async fn refresh_token(
     let auth_service = state.auth_service.as_ref()
         .ok_or_else(|| AppError::internal_error("Servicio de autenticación no configurado"))?;
     
-    let auth_response = auth_service.auth_application_service.refresh_token(dto).await?;
+    let auth_response = auth_service.auth_application_service
+        .refresh_token(dto, session_client(&headers, connect_info))
+        .await?;
     
     // Log successful token refresh
     tracing::info!("Token refresh successful, new token issued");
@@ -331,3 +391,200 @@ async fn logout(
     Ok(StatusCode::OK)
 }
 
+/// Lists the app passwords of the current user, without the passwords
+async fn list_app_passwords(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    let auth_service = state.auth_service.as_ref()
+        .ok_or_else(|| AppError::internal_error("Servicio de autenticación no configurado"))?;
+    
+    let app_passwords = auth_service.auth_application_service.list_app_passwords(&current_user.id).await?;
+    Ok((StatusCode::OK, Json(app_passwords)))
+}
+
+/// Creates an app password for a DAV client; the response is the only time
+/// the password is shown
+async fn create_app_password(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(dto): Json<CreateAppPasswordDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let auth_service = state.auth_service.as_ref()
+        .ok_or_else(|| AppError::internal_error("Servicio de autenticación no configurado"))?;
+    
+    let created = auth_service.auth_application_service.create_app_password(&current_user.id, dto).await?;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// Revokes an app password of the current user
+async fn revoke_app_password(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let auth_service = state.auth_service.as_ref()
+        .ok_or_else(|| AppError::internal_error("Servicio de autenticación no configurado"))?;
+    
+    auth_service.auth_application_service.revoke_app_password(&current_user.id, &id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Lists the active sessions of the current user, marking the one the
+/// request comes from
+async fn list_sessions(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<CurrentUser>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AppError> {
+    let auth_service = state.auth_service.as_ref()
+        .ok_or_else(|| AppError::internal_error("Servicio de autenticación no configurado"))?;
+    
+    let current_session = current_session_id(&state, &headers);
+    let sessions = auth_service.auth_application_service
+        .list_sessions(&current_user.id, current_session.as_deref())
+        .await?;
+    Ok((StatusCode::OK, Json(sessions)))
+}
+
+/// Signs a session of the current user out; its tokens stop working at once
+async fn revoke_session(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let auth_service = state.auth_service.as_ref()
+        .ok_or_else(|| AppError::internal_error("Servicio de autenticación no configurado"))?;
+    
+    auth_service.auth_application_service.revoke_user_session(&current_user.id, &id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Signs every other session of the current user out
+async fn revoke_other_sessions(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<CurrentUser>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AppError> {
+    let auth_service = state.auth_service.as_ref()
+        .ok_or_else(|| AppError::internal_error("Servicio de autenticación no configurado"))?;
+    
+    let current_session = current_session_id(&state, &headers);
+    let revoked = auth_service.auth_application_service
+        .revoke_other_sessions(&current_user.id, current_session.as_deref())
+        .await?;
+    Ok((StatusCode::OK, Json(RevokedSessionsDto { revoked })))
+}
+
+fn oidc_service(state: &AppState) -> Result<&Arc<OidcLoginService>, AppError> {
+    state.auth_service.as_ref()
+        .and_then(|auth_service| auth_service.oidc_service.as_ref())
+        .ok_or_else(|| AppError::not_found("OpenID Connect login is not configured"))
+}
+
+/// Tells the login page whether to offer single sign-on
+async fn oidc_config(
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let oidc_service = state.auth_service.as_ref()
+        .and_then(|auth_service| auth_service.oidc_service.as_ref());
+    Json(serde_json::json!({
+        "enabled": oidc_service.is_some(),
+        "provider_name": oidc_service.map(|service| service.provider_name()),
+    }))
+}
+
+/// `Set-Cookie` value that stores the `state` of a login in the browser, or
+/// clears it when `state` is empty. `SameSite=Lax` lets the cookie come back
+/// on the provider's top-level redirect to the callback.
+fn oidc_state_cookie(oidc_service: &OidcLoginService, state: &str) -> String {
+    let max_age = if state.is_empty() { 0 } else { PENDING_LOGIN_TTL_MINUTES * 60 };
+    let secure = if oidc_service.secure_cookie() { "; Secure" } else { "" };
+    format!(
+        "{}={}; Path={}; Max-Age={}; HttpOnly; SameSite=Lax{}",
+        OIDC_STATE_COOKIE, state, OIDC_COOKIE_PATH, max_age, secure
+    )
+}
+
+/// Value of a cookie sent by the browser
+fn cookie_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+/// Starts a login at the identity provider
+async fn oidc_login(
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let oidc_service = oidc_service(&state)?;
+    let (authorization_url, oidc_state) = oidc_service.begin_login(None).await?;
+    Ok((
+        [(header::SET_COOKIE, oidc_state_cookie(oidc_service, &oidc_state))],
+        Redirect::to(&authorization_url),
+    ))
+}
+
+/// Starts linking an identity provider account to the current user; the
+/// client navigates to the returned URL
+async fn oidc_link(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    let oidc_service = oidc_service(&state)?;
+    let (authorization_url, oidc_state) = oidc_service.begin_login(Some(current_user.id)).await?;
+    Ok((
+        [(header::SET_COOKIE, oidc_state_cookie(oidc_service, &oidc_state))],
+        Json(serde_json::json!({ "authorization_url": authorization_url })),
+    ))
+}
+
+/// Completes a login at the identity provider.
+///
+/// Only the browser that started the login, which holds its `state` in a
+/// cookie, may complete it. The tokens are handed to the login page in the
+/// URL fragment, which is never sent to the server, and errors as
+/// `#oidc_error=<message>`.
+async fn oidc_callback(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
+    Query(query): Query<OidcCallbackQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let oidc_service = oidc_service(&state)?;
+    // The state cookie is single use, whatever the outcome
+    let clear_cookie = [(header::SET_COOKIE, oidc_state_cookie(oidc_service, ""))];
+    let fragment = |pairs: &[(&str, &str)]| {
+        let fragment = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(pairs)
+            .finish();
+        (clear_cookie.clone(), Redirect::to(&format!("{}#{}", OIDC_LOGIN_PAGE, fragment)))
+    };
+
+    if let Some(error) = query.error {
+        let message = query.error_description.unwrap_or(error);
+        tracing::warn!("OIDC provider returned an error: {}", message);
+        return Ok(fragment(&[("oidc_error", &message)]));
+    }
+    let (Some(code), Some(oidc_state)) = (query.code, query.state) else {
+        return Ok(fragment(&[("oidc_error", "Missing authorization code")]));
+    };
+
+    let client = session_client(&headers, connect_info);
+    match oidc_service.complete_login(&code, &oidc_state, cookie_value(&headers, OIDC_STATE_COOKIE), client).await {
+        Ok(auth) => Ok(fragment(&[
+            ("access_token", &auth.access_token),
+            ("refresh_token", &auth.refresh_token),
+            ("expires_in", &auth.expires_in.to_string()),
+        ])),
+        Err(e) => {
+            tracing::warn!("OIDC login failed: {}", e);
+            Ok(fragment(&[("oidc_error", &e.to_string())]))
+        }
+    }
+}
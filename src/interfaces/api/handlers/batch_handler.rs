@@ -1,8 +1,8 @@
 use std::sync::Arc;
 use axum::{
-    extract::{State, Json},
+    extract::{Extension, State, Json},
     response::IntoResponse,
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
 };
 use serde::{Deserialize, Serialize};
 
@@ -11,7 +11,10 @@ use crate::application::services::batch_operations::{
 };
 use crate::application::dtos::file_dto::FileDto;
 use crate::application::dtos::folder_dto::FolderDto;
-use crate::interfaces::api::handlers::ApiResult;
+use crate::application::ports::share_ports::ShareTarget;
+use crate::domain::entities::share::SharePermissions;
+use crate::interfaces::api::handlers::{request_locale, ApiResult};
+use crate::interfaces::middleware::share_access::{ensure_body_access, ShareBodyCheck};
 
 /// Estado compartido para el handler de batch
 #[derive(Clone)]
@@ -125,9 +128,34 @@ where
     }
 }
 
+/// Comprueba los permisos de compartición de todos los elementos del lote;
+/// basta uno sin permiso para rechazarlo entero
+async fn ensure_batch_access(
+    check: &Option<Extension<ShareBodyCheck>>,
+    checks: Vec<(ShareTarget, SharePermissions)>,
+) -> ApiResult<()> {
+    ensure_body_access(check, &checks)
+        .await
+        .map_err(|e| (e.status_code, e.message))
+}
+
+fn item_checks(
+    ids: &[String],
+    target: fn(String) -> ShareTarget,
+    required: SharePermissions,
+) -> Vec<(ShareTarget, SharePermissions)> {
+    ids.iter().map(|id| (target(id.clone()), required)).collect()
+}
+
+/// La carpeta de destino recibe archivos nuevos
+fn target_check(folder_id: &Option<String>) -> Option<(ShareTarget, SharePermissions)> {
+    folder_id.clone().map(|id| (ShareTarget::Folder(id), SharePermissions::WRITE))
+}
+
 /// Handler para mover múltiples archivos en lote
 pub async fn move_files_batch(
     State(state): State<BatchHandlerState>,
+    share_check: Option<Extension<ShareBodyCheck>>,
     Json(request): Json<BatchFileOperationRequest>,
 ) -> ApiResult<impl IntoResponse> {
     // Verificar que hay archivos para procesar
@@ -140,6 +168,11 @@ pub async fn move_files_batch(
         ).into_response());
     }
     
+    // Mover quita los archivos de su carpeta y los añade al destino
+    let mut checks = item_checks(&request.file_ids, ShareTarget::File, SharePermissions::DELETE);
+    checks.extend(target_check(&request.target_folder_id));
+    ensure_batch_access(&share_check, checks).await?;
+    
     // Ejecutar operación de lote
     let result = state.batch_service
         .move_files(request.file_ids, request.target_folder_id)
@@ -166,6 +199,8 @@ pub async fn move_files_batch(
 /// Handler para copiar múltiples archivos en lote
 pub async fn copy_files_batch(
     State(state): State<BatchHandlerState>,
+    share_check: Option<Extension<ShareBodyCheck>>,
+    headers: HeaderMap,
     Json(request): Json<BatchFileOperationRequest>,
 ) -> ApiResult<impl IntoResponse> {
     // Verificar que hay archivos para procesar
@@ -178,9 +213,14 @@ pub async fn copy_files_batch(
         ).into_response());
     }
     
+    // Copiar solo lee los originales
+    let mut checks = item_checks(&request.file_ids, ShareTarget::File, SharePermissions::READ);
+    checks.extend(target_check(&request.target_folder_id));
+    ensure_batch_access(&share_check, checks).await?;
+    
     // Ejecutar operación de lote
     let result = state.batch_service
-        .copy_files(request.file_ids, request.target_folder_id)
+        .copy_files(request.file_ids, request.target_folder_id, request_locale(&headers))
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
     
@@ -204,6 +244,7 @@ pub async fn copy_files_batch(
 /// Handler para eliminar múltiples archivos en lote
 pub async fn delete_files_batch(
     State(state): State<BatchHandlerState>,
+    share_check: Option<Extension<ShareBodyCheck>>,
     Json(request): Json<BatchFileOperationRequest>,
 ) -> ApiResult<impl IntoResponse> {
     // Verificar que hay archivos para procesar
@@ -216,6 +257,8 @@ pub async fn delete_files_batch(
         ).into_response());
     }
     
+    ensure_batch_access(&share_check, item_checks(&request.file_ids, ShareTarget::File, SharePermissions::DELETE)).await?;
+    
     // Ejecutar operación de lote
     let result = state.batch_service
         .delete_files(request.file_ids)
@@ -248,6 +291,7 @@ pub async fn delete_files_batch(
 /// Handler para eliminar múltiples carpetas en lote
 pub async fn delete_folders_batch(
     State(state): State<BatchHandlerState>,
+    share_check: Option<Extension<ShareBodyCheck>>,
     Json(request): Json<BatchFolderOperationRequest>,
 ) -> ApiResult<impl IntoResponse> {
     // Verificar que hay carpetas para procesar
@@ -260,6 +304,8 @@ pub async fn delete_folders_batch(
         ).into_response());
     }
     
+    ensure_batch_access(&share_check, item_checks(&request.folder_ids, ShareTarget::Folder, SharePermissions::DELETE)).await?;
+    
     // Ejecutar operación de lote
     let result = state.batch_service
         .delete_folders(request.folder_ids, request.recursive)
@@ -292,6 +338,7 @@ pub async fn delete_folders_batch(
 /// Handler para crear múltiples carpetas en lote
 pub async fn create_folders_batch(
     State(state): State<BatchHandlerState>,
+    share_check: Option<Extension<ShareBodyCheck>>,
     Json(request): Json<BatchCreateFoldersRequest>,
 ) -> ApiResult<impl IntoResponse> {
     // Verificar que hay carpetas para procesar
@@ -310,6 +357,9 @@ pub async fn create_folders_batch(
         .map(|detail| (detail.name, detail.parent_id))
         .collect();
     
+    let checks = request.folders.iter().filter_map(|detail| target_check(&detail.parent_id)).collect();
+    ensure_batch_access(&share_check, checks).await?;
+    
     // Ejecutar operación de lote
     let result = state.batch_service
         .create_folders(folders)
@@ -336,6 +386,7 @@ pub async fn create_folders_batch(
 /// Handler para obtener múltiples archivos en lote
 pub async fn get_files_batch(
     State(state): State<BatchHandlerState>,
+    share_check: Option<Extension<ShareBodyCheck>>,
     Json(request): Json<BatchFileOperationRequest>,
 ) -> ApiResult<impl IntoResponse> {
     // Verificar que hay archivos para procesar
@@ -348,6 +399,8 @@ pub async fn get_files_batch(
         ).into_response());
     }
     
+    ensure_batch_access(&share_check, item_checks(&request.file_ids, ShareTarget::File, SharePermissions::READ)).await?;
+    
     // Ejecutar operación de lote
     let result = state.batch_service
         .get_multiple_files(request.file_ids)
@@ -374,6 +427,7 @@ pub async fn get_files_batch(
 /// Handler para obtener múltiples carpetas en lote
 pub async fn get_folders_batch(
     State(state): State<BatchHandlerState>,
+    share_check: Option<Extension<ShareBodyCheck>>,
     Json(request): Json<BatchFolderOperationRequest>,
 ) -> ApiResult<impl IntoResponse> {
     // Verificar que hay carpetas para procesar
@@ -386,6 +440,8 @@ pub async fn get_folders_batch(
         ).into_response());
     }
     
+    ensure_batch_access(&share_check, item_checks(&request.folder_ids, ShareTarget::Folder, SharePermissions::READ)).await?;
+    
     // Ejecutar operación de lote
     let result = state.batch_service
         .get_multiple_folders(request.folder_ids)
@@ -1,23 +1,384 @@
 use axum::{
     Router,
-    routing::get,
-    http::StatusCode,
-    response::IntoResponse,
+    routing::{any, get},
+    body::Body,
+    extract::{Path, State},
+    http::{header, Request, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
-use std::sync::Arc;
 use serde_json::json;
 
+use crate::application::adapters::caldav_adapter::{CalDavAdapter, CalDavReportType, DiscoveryLinks, PrincipalReportType};
+use crate::application::adapters::carddav_adapter::CardDavAdapter;
+use crate::application::adapters::webdav_adapter::{PropFindRequest, PropFindType};
+use crate::application::dtos::dav_principal_dto::{PrincipalDto, PrincipalKind};
+use crate::application::dtos::dav_sync_dto::SyncOutcome;
+use crate::application::ports::scheduling_ports::SchedulingUseCase;
+use crate::common::bounded_buffer::read_body;
 use crate::common::di::AppState;
+use crate::common::errors::AppError;
+use crate::interfaces::api::handlers::carddav_protocol_handler::HOME_HREF as ADDRESSBOOK_HOME_HREF;
+use crate::interfaces::middleware::auth::CurrentUser;
 
-// Temporary placeholder implementation
+/// Largest REPORT body accepted
+const MAX_REPORT_BODY: usize = 1024 * 1024;
+
+/// Principal collection, parent of `users/{username}/` and `groups/{name}/`
+const PRINCIPALS_HREF: &str = "/api/caldav/principals/";
+
+/// Scheduling inbox of the current user (RFC 6638, 2.2)
+const INBOX_HREF: &str = "/api/caldav/inbox/";
+
+/// Scheduling outbox of the current user (RFC 6638, 2.1)
+const OUTBOX_HREF: &str = "/api/caldav/outbox/";
+
+/// Context path `/.well-known/caldav` redirects to (RFC 6764), which is
+/// also the calendar home of the current user
+pub const CONTEXT_HREF: &str = "/api/caldav";
+
+/**
+ * Creates the CalDAV router.
+ *
+ * Calendar collections live at `/{calendar_id}/`; so far they answer the
+ * sync-collection REPORT (RFC 6578) used by clients for incremental sync.
+ * User and group principals live below `/principals/`, which answers the
+ * principal-property-search and principal-search-property-set REPORTs
+ * (RFC 3744) clients use to find people to share with.
+ *
+ * `/inbox/` and `/outbox/` are the scheduling collections of the current
+ * user (RFC 6638): iTIP messages POSTed to the outbox are delivered to the
+ * inboxes of the other users of this server.
+ *
+ * The root and `users/{username}/` below `/principals/` answer PROPFIND with
+ * the current-user-principal, calendar-home-set and addressbook-home-set
+ * properties clients use to configure an account from the server URL.
+ */
 pub fn caldav_routes() -> Router<AppState> {
     Router::new()
+        .route("/", any(handle_context_methods))
         .route("/placeholder", get(placeholder_handler))
+        .route("/principals", any(handle_principal_methods))
+        .route("/principals/", any(handle_principal_methods))
+        .route("/principals/{*path}", any(handle_principal_methods))
+        .route("/inbox", any(handle_inbox_methods))
+        .route("/inbox/", any(handle_inbox_methods))
+        .route("/inbox/{message}", any(handle_inbox_message_methods))
+        .route("/outbox", any(handle_outbox_methods))
+        .route("/outbox/", any(handle_outbox_methods))
+        .route("/{calendar_id}", any(handle_calendar_methods))
+        .route("/{calendar_id}/", any(handle_calendar_methods))
 }
 
 async fn placeholder_handler() -> impl IntoResponse {
     (StatusCode::OK, Json(json!({
         "message": "CalDAV functionality is not yet implemented"
     })))
-}
\ No newline at end of file
+}
+
+async fn handle_calendar_methods(
+    State(state): State<AppState>,
+    Path(calendar_id): Path<String>,
+    req: Request<Body>,
+) -> Result<Response<Body>, AppError> {
+    match req.method().as_str() {
+        "REPORT" => handle_report(state, calendar_id, req).await,
+        method => Err(AppError::method_not_allowed(format!("Method not allowed: {}", method))),
+    }
+}
+
+/**
+ * Handles REPORT requests on a calendar collection.
+ *
+ * Answers sync-collection with the events changed since the client's token,
+ * a 507 entry for the collection when more changes are pending, and 403 with
+ * `DAV:valid-sync-token` when the token is unknown or too old.
+ */
+async fn handle_report(
+    state: AppState,
+    calendar_id: String,
+    req: Request<Body>,
+) -> Result<Response<Body>, AppError> {
+    let user = req.extensions().get::<CurrentUser>().cloned().ok_or_else(|| {
+        AppError::unauthorized("Authentication required")
+    })?;
+    let service = state.dav_sync_service.clone().ok_or_else(|| {
+        AppError::new(StatusCode::SERVICE_UNAVAILABLE, "CalDAV sync requires a database", "ServiceUnavailable")
+    })?;
+
+    let body = read_body(req.into_body(), MAX_REPORT_BODY).await?;
+    let report = CalDavAdapter::parse_report(body.as_ref())
+        .map_err(|e| AppError::bad_request(format!("Invalid REPORT body: {}", e)))?;
+
+    let CalDavReportType::SyncCollection { sync_token, limit, props } = report else {
+        return Err(AppError::new(StatusCode::NOT_IMPLEMENTED, "Only sync-collection reports are supported", "NotImplemented"));
+    };
+
+    let is_admin = user.role == "admin";
+    let outcome = service.sync_calendar(&user.id, is_admin, &calendar_id, &sync_token, limit).await?;
+
+    let mut xml = Vec::new();
+    let status = match outcome {
+        SyncOutcome::Changes(sync) => {
+            let collection_href = format!("/api/caldav/{}/", calendar_id);
+            CalDavAdapter::generate_sync_collection_response(
+                &mut xml,
+                &sync.events,
+                &sync.deleted_uids,
+                sync.truncated,
+                &sync.sync_token,
+                &props,
+                &collection_href,
+            ).map_err(|e| AppError::internal_error(format!("Failed to generate XML: {}", e)))?;
+            StatusCode::MULTI_STATUS
+        }
+        SyncOutcome::InvalidToken => {
+            CalDavAdapter::generate_invalid_sync_token_error(&mut xml)
+                .map_err(|e| AppError::internal_error(format!("Failed to generate XML: {}", e)))?;
+            StatusCode::FORBIDDEN
+        }
+    };
+
+    Ok(Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+        .body(Body::from(xml))
+        .unwrap())
+}
+
+/// Where the current user's principal and collections are
+pub fn discovery_links(user: &CurrentUser) -> DiscoveryLinks {
+    DiscoveryLinks {
+        principal_href: format!("{}users/{}/", PRINCIPALS_HREF, user.username),
+        principal_collection_href: PRINCIPALS_HREF.to_string(),
+        calendar_home_href: CONTEXT_HREF.to_string(),
+        addressbook_home_href: ADDRESSBOOK_HOME_HREF.to_string(),
+        inbox_href: INBOX_HREF.to_string(),
+        outbox_href: OUTBOX_HREF.to_string(),
+    }
+}
+
+async fn read_propfind(req: Request<Body>) -> Result<PropFindRequest, AppError> {
+    let body = read_body(req.into_body(), MAX_REPORT_BODY).await?;
+    if body.is_empty() {
+        return Ok(PropFindRequest { prop_find_type: PropFindType::AllProp });
+    }
+    CardDavAdapter::parse_propfind(body.as_ref())
+        .map_err(|e| AppError::bad_request(format!("Invalid PROPFIND body: {}", e)))
+}
+
+fn multistatus(xml: Vec<u8>) -> Response<Body> {
+    Response::builder()
+        .status(StatusCode::MULTI_STATUS)
+        .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+        .body(Body::from(xml))
+        .unwrap()
+}
+
+/**
+ * Handles PROPFIND on the CalDAV context path, pointing the client at the
+ * principal and homes of the current user.
+ */
+async fn handle_context_methods(req: Request<Body>) -> Result<Response<Body>, AppError> {
+    if req.method().as_str() != "PROPFIND" {
+        return Err(AppError::method_not_allowed(format!("Method not allowed: {}", req.method())));
+    }
+    let user = current_user(&req)?;
+    let request = read_propfind(req).await?;
+
+    let mut xml = Vec::new();
+    CalDavAdapter::generate_discovery_response(&mut xml, CONTEXT_HREF, &request, &discovery_links(&user))
+        .map_err(|e| AppError::internal_error(format!("Failed to generate XML: {}", e)))?;
+    Ok(multistatus(xml))
+}
+
+async fn handle_principal_methods(
+    State(state): State<AppState>,
+    req: Request<Body>,
+) -> Result<Response<Body>, AppError> {
+    match req.method().as_str() {
+        "PROPFIND" => handle_principal_propfind(req).await,
+        "REPORT" => handle_principal_report(state, req).await,
+        method => Err(AppError::method_not_allowed(format!("Method not allowed: {}", method))),
+    }
+}
+
+/**
+ * Handles PROPFIND on the principal collection and on the principal of the
+ * current user. Other principals are only reachable through the principal
+ * REPORTs, so PROPFIND on them is answered with 404.
+ */
+async fn handle_principal_propfind(req: Request<Body>) -> Result<Response<Body>, AppError> {
+    let user = current_user(&req)?;
+    let links = discovery_links(&user);
+    let path = req.uri().path().trim_end_matches('/').to_string();
+    let request = read_propfind(req).await?;
+
+    let mut xml = Vec::new();
+    if path.ends_with("/principals") {
+        CalDavAdapter::generate_discovery_response(&mut xml, PRINCIPALS_HREF, &request, &links)
+    } else if path.ends_with(&format!("/principals/users/{}", user.username)) {
+        // The principal of the current user may always show its own address
+        let principal = PrincipalDto {
+            kind: PrincipalKind::User,
+            name: user.username.clone(),
+            display_name: user.username.clone(),
+            email: Some(user.email.clone()),
+            group_membership: Vec::new(),
+            group_member_set: Some(Vec::new()),
+        };
+        CalDavAdapter::generate_principal_propfind_response(&mut xml, &principal, &request, &links)
+    } else {
+        return Err(AppError::not_found("Principal not found"));
+    }
+    .map_err(|e| AppError::internal_error(format!("Failed to generate XML: {}", e)))?;
+    Ok(multistatus(xml))
+}
+
+/**
+ * Handles REPORT requests on the principal collection or any principal.
+ *
+ * principal-search-property-set lists the searchable properties and
+ * principal-property-search returns the matching users and groups, with the
+ * privacy rules of the principal service applied to the current user.
+ */
+async fn handle_principal_report(
+    state: AppState,
+    req: Request<Body>,
+) -> Result<Response<Body>, AppError> {
+    let user = req.extensions().get::<CurrentUser>().cloned().ok_or_else(|| {
+        AppError::unauthorized("Authentication required")
+    })?;
+
+    let body = read_body(req.into_body(), MAX_REPORT_BODY).await?;
+    let report = CalDavAdapter::parse_principal_report(body.as_ref())
+        .map_err(|e| AppError::bad_request(format!("Invalid REPORT body: {}", e)))?;
+
+    let mut xml = Vec::new();
+    let status = match report {
+        PrincipalReportType::SearchPropertySet => {
+            CalDavAdapter::generate_principal_search_property_set(&mut xml)
+                .map_err(|e| AppError::internal_error(format!("Failed to generate XML: {}", e)))?;
+            StatusCode::OK
+        }
+        PrincipalReportType::PropertySearch { search, props } => {
+            let service = state.dav_principal_service.clone().ok_or_else(|| {
+                AppError::new(StatusCode::SERVICE_UNAVAILABLE, "Principal search requires a database", "ServiceUnavailable")
+            })?;
+            let result = service.search_principals(&user.id, &user.role, &search).await?;
+            CalDavAdapter::generate_principal_search_response(&mut xml, &result, &props, PRINCIPALS_HREF)
+                .map_err(|e| AppError::internal_error(format!("Failed to generate XML: {}", e)))?;
+            StatusCode::MULTI_STATUS
+        }
+    };
+
+    Ok(Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+        .body(Body::from(xml))
+        .unwrap())
+}
+
+fn current_user(req: &Request<Body>) -> Result<CurrentUser, AppError> {
+    req.extensions().get::<CurrentUser>().cloned().ok_or_else(|| {
+        AppError::unauthorized("Authentication required")
+    })
+}
+
+fn scheduling_service(state: &AppState) -> Result<std::sync::Arc<dyn SchedulingUseCase>, AppError> {
+    state.scheduling_service.clone().ok_or_else(|| {
+        AppError::new(StatusCode::SERVICE_UNAVAILABLE, "Scheduling requires a database", "ServiceUnavailable")
+    })
+}
+
+/**
+ * Handles PROPFIND on the scheduling inbox, listing the pending iTIP
+ * messages of the current user as `{id}.ics` resources.
+ */
+async fn handle_inbox_methods(
+    State(state): State<AppState>,
+    req: Request<Body>,
+) -> Result<Response<Body>, AppError> {
+    if req.method().as_str() != "PROPFIND" {
+        return Err(AppError::method_not_allowed(format!("Method not allowed: {}", req.method())));
+    }
+    let user = current_user(&req)?;
+    let depth = req.headers().get("Depth")
+        .and_then(|depth| depth.to_str().ok())
+        .unwrap_or("1")
+        .to_string();
+
+    let messages = scheduling_service(&state)?.list_inbox(&user.id).await?;
+    let mut xml = Vec::new();
+    CalDavAdapter::generate_schedule_inbox_response(&mut xml, &messages, &depth, INBOX_HREF)
+        .map_err(|e| AppError::internal_error(format!("Failed to generate XML: {}", e)))?;
+
+    Ok(Response::builder()
+        .status(StatusCode::MULTI_STATUS)
+        .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+        .body(Body::from(xml))
+        .unwrap())
+}
+
+/**
+ * Handles GET and DELETE on a message of the scheduling inbox. Clients
+ * delete a message once they have processed it.
+ */
+async fn handle_inbox_message_methods(
+    State(state): State<AppState>,
+    Path(message): Path<String>,
+    req: Request<Body>,
+) -> Result<Response<Body>, AppError> {
+    let user = current_user(&req)?;
+    let service = scheduling_service(&state)?;
+    let message_id = message.strip_suffix(".ics").unwrap_or(&message);
+
+    match req.method().as_str() {
+        "GET" => {
+            let message = service.get_inbox_message(&user.id, message_id).await?;
+            Ok(Response::builder()
+                .status(StatusCode::OK)
+                .header(header::CONTENT_TYPE, "text/calendar; charset=utf-8")
+                .header(header::ETAG, format!("\"{}\"", message.id))
+                .body(Body::from(message.ical_data))
+                .unwrap())
+        }
+        "DELETE" => {
+            service.delete_inbox_message(&user.id, message_id).await?;
+            Ok(StatusCode::NO_CONTENT.into_response())
+        }
+        method => Err(AppError::method_not_allowed(format!("Method not allowed: {}", method))),
+    }
+}
+
+/**
+ * Handles POST on the scheduling outbox.
+ *
+ * The body is an iTIP REQUEST, REPLY or CANCEL; the answer is a
+ * schedule-response with the delivery status of every recipient.
+ */
+async fn handle_outbox_methods(
+    State(state): State<AppState>,
+    req: Request<Body>,
+) -> Result<Response<Body>, AppError> {
+    if req.method() != axum::http::Method::POST {
+        return Err(AppError::method_not_allowed(format!("Method not allowed: {}", req.method())));
+    }
+    let user = current_user(&req)?;
+    let service = scheduling_service(&state)?;
+
+    let body = read_body(req.into_body(), MAX_REPORT_BODY).await?;
+    let ical_data = std::str::from_utf8(&body)
+        .map_err(|_| AppError::bad_request("The iTIP message must be UTF-8 text"))?;
+    let results = service.send_message(&user.id, ical_data).await?;
+
+    let mut xml = Vec::new();
+    CalDavAdapter::generate_schedule_response(&mut xml, &results)
+        .map_err(|e| AppError::internal_error(format!("Failed to generate XML: {}", e)))?;
+
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+        .body(Body::from(xml))
+        .unwrap())
+}
@@ -0,0 +1,117 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::{get, post},
+    extract::{Path, Query, State, Json, Extension},
+    body::Bytes,
+    http::header,
+    response::IntoResponse,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::application::dtos::calendar_dto::DuplicateUidPolicy;
+use crate::application::dtos::notification_dto::JobKind;
+use crate::application::ports::calendar_ical_ports::{CalendarICalUseCase, EventExpansion};
+use crate::application::ports::notification_ports::NotificationUseCase;
+use crate::common::errors::AppError;
+use crate::domain::services::path_codec_service::content_disposition;
+use crate::interfaces::middleware::auth::CurrentUser;
+use crate::interfaces::middleware::job_tracking::track_job;
+
+type CalendarICalState = Arc<dyn CalendarICalUseCase>;
+
+/// Query parameters of an import
+#[derive(Debug, Default, Deserialize)]
+struct ImportQuery {
+    /// `skip` (default) or `replace` for events whose UID is already in the calendar
+    #[serde(default)]
+    on_duplicate: DuplicateUidPolicy,
+}
+
+/// Query parameters of a JSON export
+#[derive(Debug, Default, Deserialize)]
+struct JsonExportQuery {
+    /// List the occurrences between `start` and `end` instead of the stored events
+    #[serde(default)]
+    expand: bool,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    /// Keep the iCalendar source of each event (not in expanded exports)
+    #[serde(default)]
+    include_ical: bool,
+}
+
+/// Routes to import and export whole calendars as iCalendar (.ics) files,
+/// and to export them as JSON; failed imports and exports are reported to
+/// `notifications`
+pub fn calendar_ical_routes(notifications: Option<Arc<dyn NotificationUseCase>>) -> Router<CalendarICalState> {
+    Router::new()
+        .route("/{id}/import", track_job(post(import_calendar), &notifications, JobKind::CalendarImport))
+        .route("/{id}/export", track_job(get(export_calendar), &notifications, JobKind::CalendarExport))
+        .route("/{id}/export.json", track_job(get(export_calendar_json), &notifications, JobKind::CalendarExport))
+}
+
+/// Imports every event of the .ics file in the request body into a calendar
+async fn import_calendar(
+    State(service): State<CalendarICalState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+    Query(query): Query<ImportQuery>,
+    body: Bytes,
+) -> Result<impl IntoResponse, AppError> {
+    let ical_data = std::str::from_utf8(&body)
+        .map_err(|_| AppError::bad_request("iCalendar data must be UTF-8"))?;
+    let result = service.import_calendar(
+        &current_user.id,
+        current_user.role == "admin",
+        &id,
+        ical_data,
+        query.on_duplicate,
+    ).await?;
+    Ok(Json(result))
+}
+
+/// Downloads a calendar as a single .ics file
+async fn export_calendar(
+    State(service): State<CalendarICalState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let export = service.export_calendar(&current_user.id, current_user.role == "admin", &id).await?;
+    // The name may contain path separators; the header encoding handles the rest
+    let filename = format!("{}.ics", export.name.trim().replace(['/', '\\'], "_"));
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/calendar; charset=utf-8".to_string()),
+            (header::CONTENT_DISPOSITION, content_disposition("attachment", &filename)),
+        ],
+        export.ical_data,
+    ))
+}
+
+/// Downloads a calendar and its events as JSON (schema in doc/JSON-EXPORT.md)
+async fn export_calendar_json(
+    State(service): State<CalendarICalState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+    Query(query): Query<JsonExportQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let expansion = match (query.expand, query.start, query.end) {
+        (false, _, _) => EventExpansion::Raw,
+        (true, Some(start), Some(end)) => EventExpansion::Expanded { start, end },
+        (true, _, _) => return Err(AppError::bad_request("Expanded exports need both start and end")),
+    };
+    let export = service.export_calendar_json(
+        &current_user.id,
+        current_user.role == "admin",
+        &id,
+        expansion,
+        query.include_ical,
+    ).await?;
+    let filename = format!("{}.json", export.calendar.name.trim().replace(['/', '\\'], "_"));
+    Ok((
+        [(header::CONTENT_DISPOSITION, content_disposition("attachment", &filename))],
+        Json(export),
+    ))
+}
@@ -0,0 +1,77 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::get,
+    extract::State,
+    http::header,
+    response::IntoResponse,
+};
+
+use crate::common::di::AppState;
+use crate::application::dtos::capabilities_dto::{
+    CapabilitiesDto, FeatureCapabilitiesDto, UploadCapabilitiesDto, DavCapabilitiesDto, ApiCapabilitiesDto,
+};
+use crate::interfaces::api::compat::{ApiVersion, CompatShim, Versioned};
+use crate::interfaces::api::handlers::webdav_handler::DAV_COMPLIANCE_CLASSES;
+
+pub fn capabilities_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(get_capabilities))
+}
+
+/// Devuelve las capacidades y límites de la instancia (no requiere autenticación)
+async fn get_capabilities(
+    State(state): State<Arc<AppState>>,
+    version: ApiVersion,
+) -> impl IntoResponse {
+    let config = &state.core.config;
+    
+    let capabilities = CapabilitiesDto {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        features: FeatureCapabilitiesDto {
+            auth: config.features.enable_auth && state.auth_service.is_some(),
+            file_sharing: config.features.enable_file_sharing && state.share_service.is_some(),
+            trash: config.features.enable_trash && state.trash_service.is_some(),
+            search: config.features.enable_search,
+            favorites: state.favorites_service.is_some(),
+            recent_items: state.recent_service.is_some(),
+            user_storage_quotas: config.features.enable_user_storage_quotas,
+            external_storage: !config.external_storage.mounts.is_empty(),
+            image_placeholders: config.features.enable_image_placeholders,
+            similar_photos: config.features.enable_image_fingerprints,
+            image_tagging: config.image_tagging.inference_url.is_some() && state.db_pool.is_some(),
+            image_previews: true,
+            resumable_uploads: state.db_pool.is_some(),
+            versioning: false,
+            e2ee: false,
+            federation: false,
+        },
+        upload: UploadCapabilitiesDto {
+            max_upload_size_bytes: config.resources.max_upload_size_bytes(),
+            chunk_size_bytes: config.resources.chunk_size_bytes as u64,
+            parallel_threshold_bytes: config.concurrency.min_size_for_parallel_chunks_mb * 1024 * 1024,
+        },
+        // Lo mismo que monta el router de la API
+        dav: DavCapabilitiesDto {
+            webdav: config.features.enable_webdav,
+            webdav_classes: if config.features.enable_webdav {
+                DAV_COMPLIANCE_CLASSES.iter().map(|c| c.to_string()).collect()
+            } else {
+                Vec::new()
+            },
+            webdav_path: "/api/webdav".to_string(),
+            caldav: config.features.enable_caldav,
+            carddav: config.features.enable_carddav,
+        },
+        api: ApiCapabilitiesDto {
+            current_version: ApiVersion::CURRENT.as_str().to_string(),
+            supported_versions: ApiVersion::SUPPORTED.iter().map(|v| v.as_str().to_string()).collect(),
+            legacy_sunset: config.api.legacy_sunset,
+        },
+    };
+    
+    // Las capacidades cambian poco; permitimos cachearlas brevemente
+    ([(header::CACHE_CONTROL, "public, max-age=300")], Versioned(version, capabilities))
+}
+
+impl CompatShim for CapabilitiesDto {}
@@ -1,10 +1,12 @@
 use axum::{
     Router,
     routing::{get, put, delete, post},
+    body::Bytes,
     extract::{Path, State, Json},
-    http::StatusCode,
-    response::IntoResponse,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
 };
+use base64::Engine;
 use std::sync::Arc;
 use serde_json::json;
 
@@ -15,8 +17,10 @@ use crate::application::dtos::address_book_dto::{
 };
 use crate::application::dtos::contact_dto::{
     ContactDto, CreateContactDto, UpdateContactDto, CreateContactVCardDto,
-    ContactGroupDto, CreateContactGroupDto, UpdateContactGroupDto, GroupMembershipDto
+    ContactGroupDto, CreateContactGroupDto, UpdateContactGroupDto, GroupMembershipDto,
+    ContactPhotosRequestDto
 };
+use crate::domain::services::vcard_photo_service;
 
 // CardDAV handler implementation
 pub fn carddav_routes() -> Router<AppState> {
@@ -57,6 +61,10 @@ pub fn carddav_routes() -> Router<AppState> {
         .route("/address-books/:address_book_id/contacts/:contact_id/vcard", 
             get(get_contact_vcard)
         )
+        .route("/address-books/:address_book_id/contacts/:contact_id/photo", 
+            get(get_contact_photo)
+            .put(set_contact_photo)
+        )
         
         // Group operations
         .route("/address-books/:id/groups", 
@@ -78,6 +86,11 @@ pub fn carddav_routes() -> Router<AppState> {
         .route("/contacts/:contact_id/groups", 
             get(list_groups_for_contact)
         )
+        
+        // Photo operations
+        .route("/contacts/photos", 
+            post(get_contact_photos)
+        )
 }
 
 // Address Book handlers
@@ -594,14 +607,21 @@ async fn delete_contact(
 async fn get_contact_vcard(
     State(state): State<AppState>,
     Path((_, contact_id)): Path<(String, String)>,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
     let user_id = "default_user"; // In production, get this from auth middleware
     
+    // Clients that choke on large images send `Prefer: photo-max-size=N`
+    let max_photo_bytes = headers.get("Prefer")
+        .and_then(|value| value.to_str().ok())
+        .and_then(vcard_photo_service::photo_size_preference);
+    
     match &state.contact_service {
         Some(contact_service) => {
             let params = json!({
                 "contact_id": contact_id,
-                "user_id": user_id
+                "user_id": user_id,
+                "max_photo_bytes": max_photo_bytes
             });
             
             match contact_service.handle_request("get_contact_vcard", params).await {
@@ -951,4 +971,116 @@ async fn list_groups_for_contact(
             })))
         }
     }
-}
\ No newline at end of file
+}
+
+// Photo handlers
+async fn get_contact_photos(
+    State(state): State<AppState>,
+    Json(mut dto): Json<ContactPhotosRequestDto>,
+) -> impl IntoResponse {
+    dto.user_id = "default_user".to_string(); // In production, get this from auth middleware
+    
+    match &state.contact_service {
+        Some(contact_service) => {
+            match contact_service.handle_request("get_contact_photos", serde_json::to_value(dto).unwrap()).await {
+                Ok(result) => (StatusCode::OK, Json(result)),
+                Err(e) => {
+                    (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({
+                        "error": format!("Failed to get contact photos: {}", e)
+                    })))
+                }
+            }
+        },
+        None => {
+            (StatusCode::NOT_IMPLEMENTED, Json(json!({
+                "error": "Contact service not available"
+            })))
+        }
+    }
+}
+
+async fn get_contact_photo(
+    State(state): State<AppState>,
+    Path((_, contact_id)): Path<(String, String)>,
+) -> Response {
+    let user_id = "default_user"; // In production, get this from auth middleware
+    
+    match &state.contact_service {
+        Some(contact_service) => {
+            let params = json!({
+                "contact_id": contact_id,
+                "user_id": user_id
+            });
+            
+            match contact_service.handle_request("get_contact_photo", params).await {
+                Ok(result) => {
+                    let media_type = result["media_type"].as_str()
+                        .unwrap_or("application/octet-stream")
+                        .to_string();
+                    let content = result["data"].as_str()
+                        .and_then(|data| base64::engine::general_purpose::STANDARD.decode(data).ok())
+                        .unwrap_or_default();
+                    
+                    (
+                        StatusCode::OK,
+                        [
+                            (header::CONTENT_TYPE, media_type),
+                            (header::CACHE_CONTROL, "private, no-cache".to_string()),
+                        ],
+                        content
+                    ).into_response()
+                },
+                Err(e) => {
+                    (StatusCode::NOT_FOUND, Json(json!({
+                        "error": format!("Failed to get contact photo: {}", e)
+                    }))).into_response()
+                }
+            }
+        },
+        None => {
+            (StatusCode::NOT_IMPLEMENTED, Json(json!({
+                "error": "Contact service not available"
+            }))).into_response()
+        }
+    }
+}
+
+async fn set_contact_photo(
+    State(state): State<AppState>,
+    Path((_, contact_id)): Path<(String, String)>,
+    body: Bytes,
+) -> Response {
+    let user_id = "default_user"; // In production, get this from auth middleware
+    
+    match &state.contact_service {
+        Some(contact_service) => {
+            let params = json!({
+                "contact_id": contact_id,
+                "user_id": user_id,
+                "data": base64::engine::general_purpose::STANDARD.encode(&body)
+            });
+            
+            match contact_service.handle_request("set_contact_photo", params).await {
+                Ok(result) => {
+                    let contact: ContactDto = match serde_json::from_value(result) {
+                        Ok(contact) => contact,
+                        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({
+                            "error": "Failed to parse contact"
+                        }))).into_response(),
+                    };
+                    (StatusCode::OK, Json(json!(contact))).into_response()
+                },
+                Err(e) => {
+                    (StatusCode::BAD_REQUEST, Json(json!({
+                        "error": format!("Failed to set contact photo: {}", e)
+                    }))).into_response()
+                }
+            }
+        },
+        None => {
+            (StatusCode::NOT_IMPLEMENTED, Json(json!({
+                "error": "Contact service not available"
+            }))).into_response()
+        }
+    }
+}
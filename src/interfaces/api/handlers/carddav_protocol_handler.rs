@@ -0,0 +1,322 @@
+use std::sync::Arc;
+
+use axum::{
+    Router,
+    routing::any,
+    body::Body,
+    extract::{Path, State},
+    http::{header, HeaderMap, Request, StatusCode},
+    response::{IntoResponse, Response},
+};
+
+use crate::application::adapters::caldav_adapter::CalDavAdapter;
+use crate::application::adapters::carddav_adapter::{CardDavAdapter, CardDavReportType, VCARD_CONTENT_TYPE};
+use crate::application::adapters::webdav_adapter::{PropFindRequest, PropFindType};
+use crate::application::dtos::dav_sync_dto::SyncOutcome;
+use crate::application::ports::carddav_ports::CardDavUseCase;
+use crate::application::services::carddav_service::MAX_VCARD_BYTES;
+use crate::application::services::dav_multiget_service::DavHref;
+use crate::common::bounded_buffer::read_body;
+use crate::common::di::AppState;
+use crate::common::errors::AppError;
+use crate::interfaces::api::handlers::caldav_handler::discovery_links;
+use crate::interfaces::middleware::auth::CurrentUser;
+
+/// Context path `/.well-known/carddav` redirects to (RFC 6764)
+pub const CONTEXT_HREF: &str = "/api/carddav";
+
+/// Largest PROPFIND or REPORT body accepted
+const MAX_REQUEST_BODY: usize = 1024 * 1024;
+
+/// Address book home of the current user (RFC 6352, section 7.1.1)
+pub const HOME_HREF: &str = "/api/carddav/addressbooks/";
+
+/**
+ * Creates the CardDAV protocol router.
+ *
+ * `/addressbooks/` is the address book home of the current user and lists
+ * every address book they own or have been shared. Each address book lives
+ * at `/addressbooks/{address_book_id}/` and answers PROPFIND, the
+ * addressbook-query and addressbook-multiget REPORTs (RFC 6352, section 8)
+ * and sync-collection (RFC 6578) for incremental sync.
+ * Contacts are `{uid}.vcf` resources below it, read and written with GET,
+ * PUT and DELETE and guarded by their ETags.
+ *
+ * The root answers PROPFIND with the current-user-principal and
+ * addressbook-home-set clients look for when discovering the account.
+ */
+pub fn carddav_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", any(handle_context_methods))
+        .route("/addressbooks", any(handle_home_methods))
+        .route("/addressbooks/", any(handle_home_methods))
+        .route("/addressbooks/{address_book_id}", any(handle_address_book_methods))
+        .route("/addressbooks/{address_book_id}/", any(handle_address_book_methods))
+        .route("/addressbooks/{address_book_id}/{resource}", any(handle_vcard_methods))
+}
+
+fn current_user(req: &Request<Body>) -> Result<CurrentUser, AppError> {
+    req.extensions().get::<CurrentUser>().cloned().ok_or_else(|| {
+        AppError::unauthorized("Authentication required")
+    })
+}
+
+fn carddav_service(state: &AppState) -> Result<Arc<dyn CardDavUseCase>, AppError> {
+    state.carddav_service.clone().ok_or_else(|| {
+        AppError::new(StatusCode::SERVICE_UNAVAILABLE, "CardDAV requires a database", "ServiceUnavailable")
+    })
+}
+
+fn depth(headers: &HeaderMap) -> String {
+    headers.get("Depth")
+        .and_then(|depth| depth.to_str().ok())
+        .unwrap_or("1")
+        .to_string()
+}
+
+fn header_value<'a>(headers: &'a HeaderMap, name: header::HeaderName) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+async fn read_propfind(req: Request<Body>) -> Result<PropFindRequest, AppError> {
+    let body = read_body(req.into_body(), MAX_REQUEST_BODY).await?;
+    if body.is_empty() {
+        return Ok(PropFindRequest { prop_find_type: PropFindType::AllProp });
+    }
+    CardDavAdapter::parse_propfind(body.as_ref())
+        .map_err(|e| AppError::bad_request(format!("Invalid PROPFIND body: {}", e)))
+}
+
+fn multistatus(xml: Vec<u8>) -> Response<Body> {
+    xml_response(StatusCode::MULTI_STATUS, xml)
+}
+
+fn xml_response(status: StatusCode, xml: Vec<u8>) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+        .body(Body::from(xml))
+        .unwrap()
+}
+
+/**
+ * Handles PROPFIND on the CardDAV context path, pointing the client at the
+ * principal and address book home of the current user.
+ */
+async fn handle_context_methods(req: Request<Body>) -> Result<Response<Body>, AppError> {
+    if req.method().as_str() != "PROPFIND" {
+        return Err(AppError::method_not_allowed(format!("Method not allowed: {}", req.method())));
+    }
+    let user = current_user(&req)?;
+    let request = read_propfind(req).await?;
+
+    let mut xml = Vec::new();
+    CalDavAdapter::generate_discovery_response(&mut xml, CONTEXT_HREF, &request, &discovery_links(&user))
+        .map_err(|e| AppError::internal_error(format!("Failed to generate XML: {}", e)))?;
+    Ok(multistatus(xml))
+}
+
+/**
+ * Handles PROPFIND on the address book home, listing the address books of
+ * the current user at depth 1.
+ */
+async fn handle_home_methods(
+    State(state): State<AppState>,
+    req: Request<Body>,
+) -> Result<Response<Body>, AppError> {
+    if req.method().as_str() != "PROPFIND" {
+        return Err(AppError::method_not_allowed(format!("Method not allowed: {}", req.method())));
+    }
+    let user = current_user(&req)?;
+    let depth = depth(req.headers());
+    let request = read_propfind(req).await?;
+
+    let address_books = carddav_service(&state)?.list_address_books(&user.id).await?;
+    let mut xml = Vec::new();
+    let principal_href = discovery_links(&user).principal_href;
+    CardDavAdapter::generate_home_response(&mut xml, &address_books, &request, &depth, HOME_HREF, &principal_href)
+        .map_err(|e| AppError::internal_error(format!("Failed to generate XML: {}", e)))?;
+    Ok(multistatus(xml))
+}
+
+async fn handle_address_book_methods(
+    State(state): State<AppState>,
+    Path(address_book_id): Path<String>,
+    req: Request<Body>,
+) -> Result<Response<Body>, AppError> {
+    match req.method().as_str() {
+        "PROPFIND" => handle_address_book_propfind(state, address_book_id, req).await,
+        "REPORT" => handle_report(state, address_book_id, req).await,
+        method => Err(AppError::method_not_allowed(format!("Method not allowed: {}", method))),
+    }
+}
+
+/**
+ * Handles PROPFIND on an address book: its collection properties (ctag,
+ * supported address data and reports) and, at depth 1, its vCards.
+ */
+async fn handle_address_book_propfind(
+    state: AppState,
+    address_book_id: String,
+    req: Request<Body>,
+) -> Result<Response<Body>, AppError> {
+    let user = current_user(&req)?;
+    let is_admin = user.role == "admin";
+    let depth = depth(req.headers());
+    let request = read_propfind(req).await?;
+    let service = carddav_service(&state)?;
+
+    let address_book = service.get_address_book(&user.id, is_admin, &address_book_id).await?;
+    let vcards = if depth == "0" {
+        Vec::new()
+    } else {
+        service.list_vcards(&user.id, is_admin, &address_book_id).await?
+    };
+
+    let collection_href = format!("{}{}/", HOME_HREF, address_book_id);
+    let mut xml = Vec::new();
+    CardDavAdapter::generate_address_book_response(&mut xml, &address_book, &vcards, &request, &depth, &collection_href)
+        .map_err(|e| AppError::internal_error(format!("Failed to generate XML: {}", e)))?;
+    Ok(multistatus(xml))
+}
+
+/**
+ * Handles REPORT requests on an address book.
+ *
+ * addressbook-query returns the vCards matching the filter, cut at the
+ * client's limit; addressbook-multiget returns the requested vCards and a
+ * 404 entry for every href that is not a vCard of this address book.
+ * sync-collection returns the vCards changed since the client's token, or
+ * 403 with `DAV:valid-sync-token` when the token is unknown or too old.
+ */
+async fn handle_report(
+    state: AppState,
+    address_book_id: String,
+    req: Request<Body>,
+) -> Result<Response<Body>, AppError> {
+    let user = current_user(&req)?;
+    let is_admin = user.role == "admin";
+    let service = carddav_service(&state)?;
+
+    let body = read_body(req.into_body(), MAX_REQUEST_BODY).await?;
+    let report = CardDavAdapter::parse_report(body.as_ref())
+        .map_err(|e| AppError::bad_request(format!("Invalid REPORT body: {}", e)))?;
+
+    let collection_href = format!("{}{}/", HOME_HREF, address_book_id);
+    let mut xml = Vec::new();
+    let generated = match report {
+        CardDavReportType::AddressbookQuery { filter, limit, props } => {
+            let mut vcards = service.query_vcards(&user.id, is_admin, &address_book_id, &filter).await?;
+            let truncated = limit.is_some_and(|limit| vcards.len() > limit);
+            if let Some(limit) = limit {
+                vcards.truncate(limit);
+            }
+            CardDavAdapter::generate_report_response(&mut xml, &vcards, &[], &props, truncated, &collection_href)
+        }
+        CardDavReportType::AddressbookMultiget { hrefs, props } => {
+            let mut uids = Vec::new();
+            let mut missing = Vec::new();
+            for href in hrefs {
+                match DavHref::parse(&href) {
+                    Some(DavHref::Contact { address_book_id: id, uid }) if id.to_string() == address_book_id => uids.push(uid),
+                    _ => missing.push(href),
+                }
+            }
+            let mut vcards = Vec::with_capacity(uids.len());
+            for (uid, vcard) in service.get_vcards(&user.id, is_admin, &address_book_id, &uids).await? {
+                match vcard {
+                    Some(vcard) => vcards.push(vcard),
+                    None => missing.push(format!("{}{}.vcf", collection_href, uid)),
+                }
+            }
+            CardDavAdapter::generate_report_response(&mut xml, &vcards, &missing, &props, false, &collection_href)
+        }
+        CardDavReportType::SyncCollection { sync_token, limit, props } => {
+            let sync_service = state.dav_sync_service.clone().ok_or_else(|| {
+                AppError::new(StatusCode::SERVICE_UNAVAILABLE, "CardDAV sync requires a database", "ServiceUnavailable")
+            })?;
+            match sync_service.sync_address_book(&user.id, is_admin, &address_book_id, &sync_token, limit).await? {
+                SyncOutcome::Changes(sync) => CardDavAdapter::generate_sync_collection_response(
+                    &mut xml,
+                    &sync.vcards,
+                    &sync.deleted_uids,
+                    sync.truncated,
+                    &sync.sync_token,
+                    &props,
+                    &collection_href,
+                ),
+                SyncOutcome::InvalidToken => {
+                    CalDavAdapter::generate_invalid_sync_token_error(&mut xml)
+                        .map_err(|e| AppError::internal_error(format!("Failed to generate XML: {}", e)))?;
+                    return Ok(xml_response(StatusCode::FORBIDDEN, xml));
+                }
+            }
+        }
+    };
+    generated.map_err(|e| AppError::internal_error(format!("Failed to generate XML: {}", e)))?;
+
+    Ok(multistatus(xml))
+}
+
+/**
+ * Handles GET, PUT and DELETE on a vCard resource.
+ *
+ * PUT and DELETE honour If-Match, and PUT honours `If-None-Match: *` so a
+ * client never overwrites a contact changed by another device; a failed
+ * condition answers 412.
+ */
+async fn handle_vcard_methods(
+    State(state): State<AppState>,
+    Path((address_book_id, resource)): Path<(String, String)>,
+    req: Request<Body>,
+) -> Result<Response<Body>, AppError> {
+    let user = current_user(&req)?;
+    let is_admin = user.role == "admin";
+    let service = carddav_service(&state)?;
+    let uid = resource.strip_suffix(".vcf").unwrap_or(&resource).to_string();
+
+    match req.method().as_str() {
+        method @ ("GET" | "HEAD") => {
+            let vcard = service.get_vcard(&user.id, is_admin, &address_book_id, &uid).await?;
+            let etag = CardDavAdapter::vcard_etag(&vcard);
+            let body = if method == "HEAD" { Body::empty() } else { Body::from(vcard.vcard.clone()) };
+            Ok(Response::builder()
+                .status(StatusCode::OK)
+                .header(header::CONTENT_TYPE, VCARD_CONTENT_TYPE)
+                .header(header::CONTENT_LENGTH, vcard.vcard.len())
+                .header(header::ETAG, etag)
+                .header(header::LAST_MODIFIED, vcard.updated_at.format("%a, %d %b %Y %H:%M:%S GMT").to_string())
+                .body(body)
+                .unwrap())
+        }
+        "PUT" => {
+            let headers = req.headers().clone();
+            let body = read_body(req.into_body(), MAX_VCARD_BYTES).await?;
+            let vcard = std::str::from_utf8(&body)
+                .map_err(|_| AppError::bad_request("The vCard must be UTF-8 text"))?;
+
+            let (stored, created) = service.put_vcard(
+                &user.id,
+                is_admin,
+                &address_book_id,
+                &uid,
+                vcard,
+                header_value(&headers, header::IF_MATCH),
+                header_value(&headers, header::IF_NONE_MATCH),
+            ).await?;
+
+            let status = if created { StatusCode::CREATED } else { StatusCode::NO_CONTENT };
+            Ok(Response::builder()
+                .status(status)
+                .header(header::ETAG, CardDavAdapter::vcard_etag(&stored))
+                .body(Body::empty())
+                .unwrap())
+        }
+        "DELETE" => {
+            let if_match = header_value(req.headers(), header::IF_MATCH).map(str::to_string);
+            service.delete_vcard(&user.id, is_admin, &address_book_id, &uid, if_match.as_deref()).await?;
+            Ok(StatusCode::NO_CONTENT.into_response())
+        }
+        method => Err(AppError::method_not_allowed(format!("Method not allowed: {}", method))),
+    }
+}
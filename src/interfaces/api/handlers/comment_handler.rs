@@ -0,0 +1,73 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::{get, put},
+    extract::{Path, Query, State, Json, Extension},
+    http::StatusCode,
+    response::IntoResponse,
+};
+
+use crate::application::dtos::comment_dto::{CommentQueryDto, CreateCommentDto, UpdateCommentDto};
+use crate::application::ports::comment_ports::CommentUseCase;
+use crate::common::errors::AppError;
+use crate::domain::entities::comment::CommentTargetKind;
+use crate::interfaces::middleware::auth::CurrentUser;
+
+type CommentState = Arc<dyn CommentUseCase>;
+
+/// Routes to read and write comments on files and folders
+pub fn comment_routes() -> Router<CommentState> {
+    Router::new()
+        .route("/{item_type}/{item_id}", get(list_comments).post(create_comment))
+        .route("/{id}", put(update_comment).delete(delete_comment))
+}
+
+fn parse_item_type(item_type: &str) -> Result<CommentTargetKind, AppError> {
+    CommentTargetKind::parse(item_type)
+        .ok_or_else(|| AppError::bad_request(format!("Unknown item type: {}. Expected file or folder", item_type)))
+}
+
+/// Lists the comments on a file or folder, oldest first
+async fn list_comments(
+    State(service): State<CommentState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((item_type, item_id)): Path<(String, String)>,
+    Query(query): Query<CommentQueryDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let item_type = parse_item_type(&item_type)?;
+    Ok(Json(service.list_comments(current_user.share_recipient(), current_user.is_admin(), item_type, &item_id, query).await?))
+}
+
+/// Writes a comment on a file or folder
+async fn create_comment(
+    State(service): State<CommentState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((item_type, item_id)): Path<(String, String)>,
+    Json(request): Json<CreateCommentDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let item_type = parse_item_type(&item_type)?;
+    let comment = service
+        .create_comment(current_user.share_recipient(), current_user.is_admin(), item_type, &item_id, request)
+        .await?;
+    Ok((StatusCode::CREATED, Json(comment)))
+}
+
+/// Changes the text of one of the user's comments
+async fn update_comment(
+    State(service): State<CommentState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+    Json(request): Json<UpdateCommentDto>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(service.update_comment(&current_user.id, &id, request).await?))
+}
+
+/// Deletes a comment; administrators can delete anyone's
+async fn delete_comment(
+    State(service): State<CommentState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    service.delete_comment(&current_user.id, current_user.is_admin(), &id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
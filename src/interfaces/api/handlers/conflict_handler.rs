@@ -0,0 +1,48 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::{get, post},
+    extract::{Path, Query, State, Json, Extension},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+
+use crate::application::dtos::sync_conflict_dto::ResolveConflictDto;
+use crate::application::ports::sync_conflict_ports::SyncConflictUseCase;
+use crate::common::errors::AppError;
+use crate::interfaces::middleware::auth::CurrentUser;
+
+type ConflictState = Arc<dyn SyncConflictUseCase>;
+
+#[derive(Debug, Deserialize)]
+struct ListConflictsParams {
+    /// Also return conflicts the user already resolved
+    #[serde(default)]
+    include_resolved: bool,
+}
+
+/// Routes for the cross-device sync conflict dashboard
+pub fn conflict_routes() -> Router<ConflictState> {
+    Router::new()
+        .route("/", get(list_conflicts))
+        .route("/{id}/resolve", post(resolve_conflict))
+}
+
+/// Lists the sync conflicts of the current user
+async fn list_conflicts(
+    State(service): State<ConflictState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(params): Query<ListConflictsParams>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(service.list_conflicts(&current_user.id, params.include_resolved).await?))
+}
+
+/// Applies keep mine, keep theirs or keep both to a conflict
+async fn resolve_conflict(
+    State(service): State<ConflictState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+    Json(dto): Json<ResolveConflictDto>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(service.resolve_conflict(&current_user.id, &id, dto.resolution).await?))
+}
@@ -0,0 +1,103 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::{get, post},
+    extract::{Path, Query, State, Json, Extension},
+    body::{Body, Bytes},
+    http::{header, HeaderMap},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+
+use crate::application::dtos::contact_dto::ContactImportFormat;
+use crate::application::dtos::notification_dto::JobKind;
+use crate::application::ports::contact_import_ports::ContactImportUseCase;
+use crate::application::ports::notification_ports::NotificationUseCase;
+use crate::common::errors::AppError;
+use crate::domain::services::path_codec_service::content_disposition;
+use crate::interfaces::middleware::auth::CurrentUser;
+use crate::interfaces::middleware::job_tracking::track_job;
+
+type ContactImportState = Arc<dyn ContactImportUseCase>;
+
+/// Query parameters of an import
+#[derive(Debug, Default, Deserialize)]
+struct ImportQuery {
+    /// `vcf` or `csv`; taken from the Content-Type when missing
+    format: Option<ContactImportFormat>,
+    /// Contact field of each CSV column, comma separated (`first_name,last_name,,email`)
+    columns: Option<String>,
+}
+
+/// Routes to import contacts into an address book and export it whole, as
+/// vCards or JSON; failed imports and exports are reported to `notifications`
+pub fn contact_import_routes(notifications: Option<Arc<dyn NotificationUseCase>>) -> Router<ContactImportState> {
+    Router::new()
+        .route("/{id}/import", track_job(post(import_contacts), &notifications, JobKind::ContactImport))
+        .route("/{id}/export.vcf", track_job(get(export_address_book), &notifications, JobKind::ContactExport))
+        .route("/{id}/export.json", track_job(get(export_address_book_json), &notifications, JobKind::ContactExport))
+}
+
+/// Imports every contact of the .vcf or .csv file in the request body into an address book
+async fn import_contacts(
+    State(service): State<ContactImportState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+    Query(query): Query<ImportQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<impl IntoResponse, AppError> {
+    let data = std::str::from_utf8(&body)
+        .map_err(|_| AppError::bad_request("Contact data must be UTF-8"))?;
+    let format = query.format.unwrap_or_else(|| {
+        let is_csv = headers.get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|content_type| content_type.trim().to_ascii_lowercase().starts_with("text/csv"));
+        if is_csv { ContactImportFormat::Csv } else { ContactImportFormat::Vcf }
+    });
+    let columns: Option<Vec<String>> = query.columns
+        .map(|columns| columns.split(',').map(|column| column.trim().to_string()).collect());
+
+    let result = service.import_contacts(
+        &current_user.id,
+        current_user.role == "admin",
+        &id,
+        data,
+        format,
+        columns.as_deref(),
+    ).await?;
+    Ok(Json(result))
+}
+
+/// Downloads every contact of an address book as a single .vcf file
+async fn export_address_book(
+    State(service): State<ContactImportState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let export = service.export_address_book(&current_user.id, current_user.role == "admin", &id).await?;
+    // The name may contain path separators; the header encoding handles the rest
+    let filename = format!("{}.vcf", export.name.trim().replace(['/', '\\'], "_"));
+    let vcards = futures::stream::iter(export.vcards.into_iter().map(Ok::<_, std::io::Error>));
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/vcard; charset=utf-8".to_string()),
+            (header::CONTENT_DISPOSITION, content_disposition("attachment", &filename)),
+        ],
+        Body::from_stream(vcards),
+    ))
+}
+
+/// Downloads an address book and its contacts as JSON (schema in doc/JSON-EXPORT.md)
+async fn export_address_book_json(
+    State(service): State<ContactImportState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let export = service.export_address_book_json(&current_user.id, current_user.role == "admin", &id).await?;
+    let filename = format!("{}.json", export.address_book.name.trim().replace(['/', '\\'], "_"));
+    Ok((
+        [(header::CONTENT_DISPOSITION, content_disposition("attachment", &filename))],
+        Json(export),
+    ))
+}
@@ -0,0 +1,29 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::post,
+    extract::{State, Json, Extension},
+    response::IntoResponse,
+};
+
+use crate::application::dtos::dav_multiget_dto::MultigetRequestDto;
+use crate::application::ports::dav_multiget_ports::DavMultigetUseCase;
+use crate::common::errors::AppError;
+use crate::interfaces::middleware::auth::CurrentUser;
+
+type DavMultigetState = Arc<dyn DavMultigetUseCase>;
+
+/// Routes for fetching several WebDAV, CalDAV and CardDAV resources at once.
+pub fn dav_multiget_routes() -> Router<DavMultigetState> {
+    Router::new()
+        .route("/multiget", post(multiget))
+}
+
+/// Returns the body and ETag of every requested href, in request order
+async fn multiget(
+    State(service): State<DavMultigetState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(request): Json<MultigetRequestDto>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(service.multiget(current_user.share_recipient(), current_user.is_admin(), &request.hrefs).await?))
+}
@@ -0,0 +1,77 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::get,
+    extract::{Path, State, Json, Extension},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+    body::Body,
+};
+
+use crate::application::adapters::webdav_adapter::WebDavAdapter;
+use crate::application::dtos::dav_transfer_dto::DavTransferState;
+use crate::application::ports::dav_transfer_ports::DavTransferUseCase;
+use crate::common::errors::AppError;
+use crate::domain::services::path_codec_service::encode_path;
+use crate::interfaces::middleware::auth::CurrentUser;
+
+type TransferServiceState = Arc<dyn DavTransferUseCase>;
+
+/// Where the transfer routes are mounted
+pub const DAV_TRANSFER_ROUTE: &str = "/api/dav-transfers";
+
+/// Status URL of a background WebDAV transfer, sent in the `Location`
+/// header of its `202 Accepted` response
+pub fn dav_transfer_url(transfer_id: &str) -> String {
+    format!("{}/{}", DAV_TRANSFER_ROUTE, transfer_id)
+}
+
+/// Routes with the progress of WebDAV COPY and MOVE requests that run in
+/// the background
+pub fn dav_transfer_routes() -> Router<TransferServiceState> {
+    Router::new()
+        .route("/{id}", get(get_transfer))
+        .route("/{id}/multistatus", get(get_multistatus))
+}
+
+/// Progress of a transfer of the current user
+async fn get_transfer(
+    State(service): State<TransferServiceState>,
+    current_user: Option<Extension<CurrentUser>>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let Extension(user) = current_user.ok_or_else(|| AppError::unauthorized("Authentication required"))?;
+    Ok(Json(service.get_transfer(&user.id, &id).await?))
+}
+
+/// DAV:multistatus with the outcome of a finished transfer, as the request
+/// would have returned it. While the transfer runs this answers
+/// `202 Accepted` with its progress
+async fn get_multistatus(
+    State(service): State<TransferServiceState>,
+    current_user: Option<Extension<CurrentUser>>,
+    Path(id): Path<String>,
+) -> Result<Response, AppError> {
+    let Extension(user) = current_user.ok_or_else(|| AppError::unauthorized("Authentication required"))?;
+    let transfer = service.get_transfer(&user.id, &id).await?;
+    if transfer.state == DavTransferState::Running {
+        return Ok((StatusCode::ACCEPTED, Json(transfer)).into_response());
+    }
+
+    let status = transfer.status
+        .and_then(|status| StatusCode::from_u16(status).ok())
+        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let mut body = Vec::new();
+    WebDavAdapter::generate_status_response(
+        &mut body,
+        &format!("/webdav/{}", encode_path(&transfer.destination)),
+        &format!("HTTP/1.1 {}", status),
+        transfer.error.as_deref(),
+    ).map_err(|e| AppError::internal_error(format!("Failed to generate XML: {}", e)))?;
+
+    Ok(Response::builder()
+        .status(StatusCode::MULTI_STATUS)
+        .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+        .body(Body::from(body))
+        .unwrap())
+}
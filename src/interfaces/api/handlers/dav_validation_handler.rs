@@ -0,0 +1,81 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::{get, post},
+    extract::{Path, State, Json, Extension},
+    http::StatusCode,
+    response::IntoResponse,
+};
+
+use crate::application::ports::dav_validation_ports::{DavValidationUseCase, ValidationRequester};
+use crate::common::errors::AppError;
+use crate::interfaces::middleware::auth::CurrentUser;
+
+type DavValidationState = Arc<dyn DavValidationUseCase>;
+
+/// Routes for calendar and address book validation reports and repair jobs.
+///
+/// Users can scan the collections they have access to; administrators can
+/// scan and repair any collection.
+pub fn dav_validation_routes() -> Router<DavValidationState> {
+    Router::new()
+        .route("/calendars/{id}", get(validate_calendar))
+        .route("/calendars/{id}/repair", post(repair_calendar))
+        .route("/address-books/{id}", get(validate_address_book))
+        .route("/address-books/{id}/repair", post(repair_address_book))
+        .route("/repairs/{job_id}", get(get_repair_job))
+}
+
+fn requester(current_user: &CurrentUser) -> ValidationRequester {
+    ValidationRequester {
+        user_id: current_user.id.clone(),
+        is_admin: current_user.role == "admin",
+    }
+}
+
+/// Lists the invalid events of a calendar with their suggested fixes
+async fn validate_calendar(
+    State(service): State<DavValidationState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(service.validate_calendar(&requester(&current_user), &id).await?))
+}
+
+/// Starts applying every automatic fix of a calendar
+async fn repair_calendar(
+    State(service): State<DavValidationState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let job = service.start_calendar_repair(&requester(&current_user), &id).await?;
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+/// Lists the invalid contacts of an address book with their suggested fixes
+async fn validate_address_book(
+    State(service): State<DavValidationState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(service.validate_address_book(&requester(&current_user), &id).await?))
+}
+
+/// Starts applying every automatic fix of an address book
+async fn repair_address_book(
+    State(service): State<DavValidationState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let job = service.start_address_book_repair(&requester(&current_user), &id).await?;
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+/// Returns the progress of a repair job
+async fn get_repair_job(
+    State(service): State<DavValidationState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(job_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(service.get_repair_job(&requester(&current_user), &job_id).await?))
+}
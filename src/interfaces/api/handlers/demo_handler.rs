@@ -0,0 +1,24 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::get,
+    extract::{State, Json},
+    response::IntoResponse,
+};
+
+use crate::application::ports::demo_ports::DemoUseCase;
+
+type DemoState = Arc<dyn DemoUseCase>;
+
+/// Public routes of the demo mode, used by the login page
+pub fn demo_routes() -> Router<DemoState> {
+    Router::new()
+        .route("/", get(get_demo_info))
+}
+
+/// Returns the demo credentials and when the data is reset next
+async fn get_demo_info(
+    State(service): State<DemoState>,
+) -> impl IntoResponse {
+    Json(service.info().await)
+}
@@ -0,0 +1,146 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::get,
+    body::Body,
+    extract::{Extension, Path, Query, State, Json},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use futures::TryStreamExt;
+
+use crate::application::dtos::external_storage_dto::{ExternalChangesQuery, ExternalMoveRequestDto, ExternalPathQuery};
+use crate::application::ports::external_storage_ports::ExternalStorageUseCase;
+use crate::common::errors::AppError;
+use crate::domain::services::path_codec_service::content_disposition;
+use crate::interfaces::middleware::auth::CurrentUser;
+
+type ExternalStorageState = Arc<dyn ExternalStorageUseCase>;
+
+/// Routes for browsing external storage mounts (SFTP, SMB, ...).
+///
+/// Every handler needs a signed-in user; mount it behind `require_user`.
+pub fn external_storage_routes() -> Router<ExternalStorageState> {
+    Router::new()
+        .route("/", get(list_mounts))
+        .route("/{mount}/entries", get(list_entries).delete(delete_entry))
+        .route("/{mount}/content", get(download_file).put(upload_file))
+        .route("/{mount}/directories", axum::routing::post(create_directory))
+        .route("/{mount}/changes", get(list_changes))
+        .route("/move", axum::routing::post(move_entry))
+        .route("/transfers", get(list_transfers))
+        .route("/transfers/{id}", get(get_transfer))
+}
+
+/// Lists the external mounts the user may use
+async fn list_mounts(
+    State(service): State<ExternalStorageState>,
+    Extension(user): Extension<CurrentUser>,
+) -> impl IntoResponse {
+    Json(service.list_mounts(&user.mount_user()).await)
+}
+
+/// Lists a directory of a mount
+async fn list_entries(
+    State(service): State<ExternalStorageState>,
+    Extension(user): Extension<CurrentUser>,
+    Path(mount): Path<String>,
+    Query(query): Query<ExternalPathQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let entries = service.list_directory(&user.mount_user(), &mount, &query.path).await?;
+    Ok(Json(entries))
+}
+
+/// Streams a file from a mount
+async fn download_file(
+    State(service): State<ExternalStorageState>,
+    Extension(user): Extension<CurrentUser>,
+    Path(mount): Path<String>,
+    Query(query): Query<ExternalPathQuery>,
+) -> Result<Response, AppError> {
+    let (entry, stream) = service.download_file(&user.mount_user(), &mount, &query.path).await?;
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, entry.mime_type)
+        .header(header::CONTENT_LENGTH, entry.size)
+        .header(header::CONTENT_DISPOSITION, content_disposition("attachment", &entry.name))
+        .body(Body::from_stream(stream))
+        .map_err(|e| AppError::internal_error(format!("Failed to build response: {}", e)))
+}
+
+/// Streams the request body into a file on a mount
+async fn upload_file(
+    State(service): State<ExternalStorageState>,
+    Extension(user): Extension<CurrentUser>,
+    Path(mount): Path<String>,
+    Query(query): Query<ExternalPathQuery>,
+    body: Body,
+) -> Result<impl IntoResponse, AppError> {
+    let stream = body.into_data_stream().map_err(std::io::Error::other);
+    let entry = service.upload_file(&user.mount_user(), &mount, &query.path, Box::pin(stream)).await?;
+    Ok((StatusCode::CREATED, Json(entry)))
+}
+
+/// Creates a directory on a mount
+async fn create_directory(
+    State(service): State<ExternalStorageState>,
+    Extension(user): Extension<CurrentUser>,
+    Path(mount): Path<String>,
+    Query(query): Query<ExternalPathQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let entry = service.create_directory(&user.mount_user(), &mount, &query.path).await?;
+    Ok((StatusCode::CREATED, Json(entry)))
+}
+
+/// Deletes a file or empty directory on a mount
+async fn delete_entry(
+    State(service): State<ExternalStorageState>,
+    Extension(user): Extension<CurrentUser>,
+    Path(mount): Path<String>,
+    Query(query): Query<ExternalPathQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    service.delete_entry(&user.mount_user(), &mount, &query.path).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Lists changes detected on a mount by the periodic scans
+async fn list_changes(
+    State(service): State<ExternalStorageState>,
+    Extension(user): Extension<CurrentUser>,
+    Path(mount): Path<String>,
+    Query(query): Query<ExternalChangesQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let changes = service.list_changes(&user.mount_user(), &mount, query.since).await?;
+    Ok(Json(changes))
+}
+
+/// Moves an entry: renamed in place within a mount, transferred in the
+/// background between mounts (202 with the job)
+async fn move_entry(
+    State(service): State<ExternalStorageState>,
+    Extension(user): Extension<CurrentUser>,
+    Json(request): Json<ExternalMoveRequestDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let result = service.move_entry(&user.mount_user(), request).await?;
+    let status = if result.job.is_some() { StatusCode::ACCEPTED } else { StatusCode::OK };
+    Ok((status, Json(result)))
+}
+
+/// Lists transfer jobs between mounts
+async fn list_transfers(
+    State(service): State<ExternalStorageState>,
+    Extension(user): Extension<CurrentUser>,
+) -> impl IntoResponse {
+    Json(service.list_transfers(&user.mount_user()).await)
+}
+
+/// Returns the progress of a transfer job
+async fn get_transfer(
+    State(service): State<ExternalStorageState>,
+    Extension(user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let job = service.get_transfer(&user.mount_user(), &id).await?;
+    Ok(Json(job))
+}
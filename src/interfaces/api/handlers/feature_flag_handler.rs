@@ -0,0 +1,32 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::get,
+    extract::{State, Json, Extension},
+    response::IntoResponse,
+};
+
+use crate::application::ports::feature_flag_ports::{FeatureFlagUseCase, FlagSubject};
+use crate::common::errors::AppError;
+use crate::interfaces::middleware::auth::CurrentUser;
+
+type FeatureFlagState = Arc<dyn FeatureFlagUseCase>;
+
+/// Routes for clients to find out which flagged features they can use
+pub fn feature_flag_routes() -> Router<FeatureFlagState> {
+    Router::new()
+        .route("/", get(evaluate_flags))
+}
+
+/// Subject a flag is evaluated for; the role is the only group a user has for now
+pub fn flag_subject(user: &CurrentUser) -> FlagSubject {
+    FlagSubject::new(user.id.clone(), vec![user.role.clone()])
+}
+
+/// Returns every flag and whether it is on for the current user
+async fn evaluate_flags(
+    State(flags): State<FeatureFlagState>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(flags.evaluate_all(&flag_subject(&current_user)).await))
+}
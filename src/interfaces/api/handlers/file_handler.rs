@@ -1,7 +1,8 @@
 use std::sync::Arc;
 use axum::{
-    extract::{Path, State, Multipart, Query},
-    http::{StatusCode, header, HeaderName, HeaderValue, Response},
+    body::Body,
+    extract::{Path, State, Multipart, Query, Extension},
+    http::{StatusCode, header, HeaderMap, HeaderName, HeaderValue, Response},
     response::IntoResponse,
     Json,
 };
@@ -13,10 +14,26 @@ use std::task::{Context, Poll};
 use std::pin::Pin;
 
 use crate::application::services::file_service::{FileService, FileServiceError};
+use crate::application::services::free_name_service::FreeNameService;
+use crate::application::ports::image_preview_ports::{ImageFit, ImagePreviewRequest, ImagePreviewUseCase, PreviewQuality};
+use crate::domain::services::naming_service::NamingPattern;
+use crate::interfaces::api::handlers::request_locale;
+use crate::interfaces::api::range_response::{file_range_response, requested_write_range, write_file_range, ACCEPT_RANGES_BYTES};
+use crate::interfaces::api::content_digest::{digest_body, insert_digest_headers, trailer_names, DownloadDigest};
+use crate::domain::services::content_digest_service::{accepts_trailers, sha256_hex, wants_sha256};
+use crate::domain::services::path_codec_service::content_disposition;
+use crate::common::bounded_buffer::read_body;
+use crate::common::errors::AppError;
 use crate::infrastructure::services::compression_service::{
-    CompressionService, GzipCompressionService, CompressionLevel
+    gzip_stream, CompressionService, GzipCompressionService, CompressionLevel
 };
 use crate::common::di::AppState;
+use crate::application::ports::undo_ports::UndoAction;
+use crate::interfaces::api::handlers::undo_handler::with_undo_token;
+use crate::interfaces::middleware::auth::CurrentUser;
+use crate::interfaces::middleware::share_access::{ensure_body_access, ShareBodyCheck};
+use crate::application::ports::share_ports::ShareTarget;
+use crate::domain::entities::share::SharePermissions;
 
 /**
  * Type aliases for dependency injection state.
@@ -27,6 +44,9 @@ type FileServiceState = Arc<FileService>;
 /// Global application state for dependency injection
 type GlobalState = AppState;
 
+/// Files larger than this are streamed instead of loaded into memory
+const STREAMING_THRESHOLD: u64 = 10 * 1024 * 1024;
+
 /**
  * API handler for file-related operations.
  * 
@@ -79,6 +99,7 @@ impl FileHandler {
     /// Uploads a file
     pub async fn upload_file(
         State(service): State<FileServiceState>,
+        share_check: Option<Extension<ShareBodyCheck>>,
         mut multipart: Multipart,
     ) -> impl IntoResponse {
         // Extract file from multipart request
@@ -96,7 +117,16 @@ impl FileHandler {
                 let content_type = field.content_type().unwrap_or("application/octet-stream").to_string();
                 tracing::info!("File received: {} ({})", filename, content_type);
                 
-                let bytes = field.bytes().await.unwrap_or_default();
+                // Bounded by the upload limit; an oversized file is a 413, not an empty file
+                let bytes = match field.bytes().await {
+                    Ok(bytes) => bytes,
+                    Err(err) => {
+                        tracing::error!("Error reading uploaded file '{}': {}", filename, err);
+                        return (err.status(), Json(serde_json::json!({
+                            "error": format!("Error reading uploaded file: {}", err.body_text())
+                        }))).into_response();
+                    }
+                };
                 tracing::info!("File size: {} bytes", bytes.len());
                 
                 file_part = Some((filename, content_type, bytes));
@@ -110,6 +140,15 @@ impl FileHandler {
             }
         }
         
+        // The target folder is only known now; users uploading into someone
+        // else's folder need write access through a share
+        if let Some(folder_id) = &folder_id {
+            let check = [(ShareTarget::Folder(folder_id.clone()), SharePermissions::WRITE)];
+            if let Err(err) = ensure_body_access(&share_check, &check).await {
+                return err.into_response();
+            }
+        }
+        
         // Check if file was provided
         if let Some((filename, content_type, data)) = file_part {
             tracing::info!("Uploading file '{}' to folder_id: {:?}", filename, folder_id);
@@ -174,11 +213,18 @@ impl FileHandler {
         }
     }
     
-    /// Downloads a file with optional compression
+    /// Downloads a file with optional compression.
+    ///
+    /// The SHA-256 of the content is sent as `X-Hash-SHA256` and, when the
+    /// client's `Want-Digest` allows it, as an RFC 3230 `Digest`. Without a
+    /// stored hash, streamed files send it as trailers to clients that accept
+    /// them (`TE: trailers`). `verify=true` checks the bytes against the
+    /// stored hash and fails the transfer on a mismatch.
     pub async fn download_file(
         State(service): State<FileServiceState>,
         Path(id): Path<String>,
         Query(params): Query<HashMap<String, String>>,
+        request_headers: HeaderMap,
     ) -> impl IntoResponse {
         // Initialize compression service
         let compression_service = GzipCompressionService::new();
@@ -187,6 +233,8 @@ impl FileHandler {
         let compression_param = params.get("compress").map(|v| v.as_str());
         let force_compress = compression_param == Some("true") || compression_param == Some("1");
         let force_no_compress = compression_param == Some("false") || compression_param == Some("0");
+        let verify = params.get("verify").map_or(false, |v| v == "true" || v == "1");
+        let want_digest = wants_sha256(request_headers.get("want-digest").and_then(|v| v.to_str().ok()));
         
         // Determine compression level from query params
         let compression_level = match params.get("compression_level").map(|v| v.as_str()) {
@@ -199,8 +247,21 @@ impl FileHandler {
         // Get file info first to check it exists and get metadata
         match service.get_file(&id).await {
             Ok(file) => {
-                // Determine if we should compress based on file type and size
-                let should_compress = if force_no_compress {
+                // Byte ranges are served uncompressed so offsets match the stored file
+                let etag = format!("\"{}\"", file.id);
+                let last_modified = chrono::DateTime::<chrono::Utc>::from_timestamp(file.modified_at as i64, 0)
+                    .unwrap_or_else(chrono::Utc::now);
+                match file_range_response(service.as_ref(), &request_headers, &file, &etag, last_modified).await {
+                    Ok(Some(response)) => return response,
+                    Ok(None) => {},
+                    Err(err) => return err.into_response(),
+                }
+                
+                let stored_hash = service.get_content_hash(&file).await;
+                
+                // Determine if we should compress based on file type and size;
+                // verified downloads are sent as stored
+                let should_compress = if force_no_compress || verify {
                     false
                 } else if force_compress {
                     true
@@ -214,105 +275,75 @@ impl FileHandler {
                     file.name, file.size / 1024, file.mime_type, should_compress
                 );
                 
-                // For large files, use streaming response with potential compression
-                if file.size > 10 * 1024 * 1024 { // 10MB threshold for streaming
-                    match service.get_file_content(&id).await {
-                        Ok(content) => {
-                            // Create base headers
-                            let mut headers = HashMap::new();
-                            
-                            // Determine if the file should be displayed inline or downloaded
-                            // Images and PDFs should be displayed inline by default, or if inline param is present
+                // Large files are streamed from storage so they are never held in memory
+                if file.size > STREAMING_THRESHOLD {
+                    match service.get_file_stream(&id).await {
+                        Ok(stream) => {
                             let force_inline = params.get("inline").map_or(false, |v| v == "true" || v == "1");
                             
                             let disposition = if force_inline || 
                                              file.mime_type.starts_with("image/") || 
                                              file.mime_type == "application/pdf" {
-                                format!("inline; filename=\"{}\"", file.name)
+                                content_disposition("inline", &file.name)
                             } else {
-                                format!("attachment; filename=\"{}\"", file.name)
+                                content_disposition("attachment", &file.name)
                             };
                             
-                            headers.insert(header::CONTENT_DISPOSITION.to_string(), disposition);
+                            let stream = Box::into_pin(stream);
+                            let builder = Response::builder()
+                                .status(StatusCode::OK)
+                                .header(header::CONTENT_TYPE, file.mime_type.clone())
+                                .header(header::CONTENT_DISPOSITION, disposition);
                             
-                            if should_compress {
-                                // Add content-encoding header for compressed response
-                                headers.insert(header::CONTENT_ENCODING.to_string(), "gzip".to_string());
-                                headers.insert(header::CONTENT_TYPE.to_string(), file.mime_type.clone());
-                                headers.insert(header::VARY.to_string(), "Accept-Encoding".to_string());
-                                
-                                // Compress the content
-                                match compression_service.compress_data(&content, compression_level).await {
-                                    Ok(compressed_content) => {
-                                        tracing::debug!(
-                                            "Compressed file: {} from {}KB to {}KB (ratio: {:.2})", 
-                                            file.name, 
-                                            content.len() / 1024, 
-                                            compressed_content.len() / 1024,
-                                            content.len() as f64 / compressed_content.len() as f64
-                                        );
-                                        
-                                        // Build a custom response with headers and body
-                                        let mut response = Response::builder()
-                                            .status(StatusCode::OK)
-                                            .body(axum::body::Body::from(compressed_content))
-                                            .unwrap();
-                                            
-                                        // Add headers to response
-                                        for (name, value) in headers {
-                                            response.headers_mut().insert(
-                                                HeaderName::from_bytes(name.as_bytes()).unwrap(),
-                                                HeaderValue::from_str(&value).unwrap()
-                                            );
-                                        }
-                                        
-                                        response
-                                    },
-                                    Err(e) => {
-                                        tracing::warn!("Compression failed, sending uncompressed: {}", e);
-                                        // Fall back to uncompressed
-                                        headers.insert(header::CONTENT_TYPE.to_string(), file.mime_type.clone());
-                                        
-                                        // Build a custom response with headers and body
-                                        let mut response = Response::builder()
-                                            .status(StatusCode::OK)
-                                            .body(axum::body::Body::from(content))
-                                            .unwrap();
-                                            
-                                        // Add headers to response
-                                        for (name, value) in headers {
-                                            response.headers_mut().insert(
-                                                HeaderName::from_bytes(name.as_bytes()).unwrap(),
-                                                HeaderValue::from_str(&value).unwrap()
-                                            );
-                                        }
-                                        
-                                        response
-                                    }
+                            let mut builder = builder;
+                            let response = if should_compress {
+                                if let (Some(sha256), Some(headers)) = (&stored_hash, builder.headers_mut()) {
+                                    insert_digest_headers(headers, sha256, false);
                                 }
-                            } else {
-                                // No compression, return as-is
-                                headers.insert(header::CONTENT_TYPE.to_string(), file.mime_type.clone());
-                                
-                                // Build a custom response with headers and body
-                                let mut response = Response::builder()
-                                    .status(StatusCode::OK)
-                                    .body(axum::body::Body::from(content))
-                                    .unwrap();
-                                    
-                                // Add headers to response
-                                for (name, value) in headers {
-                                    response.headers_mut().insert(
-                                        HeaderName::from_bytes(name.as_bytes()).unwrap(),
-                                        HeaderValue::from_str(&value).unwrap()
-                                    );
+                                // The compressed length is unknown up front, so the body is chunked
+                                builder
+                                    .header(header::CONTENT_ENCODING, "gzip")
+                                    .header(header::VARY, "Accept-Encoding")
+                                    .body(axum::body::Body::from_stream(gzip_stream(stream, compression_level)))
+                            } else if let Some(sha256) = &stored_hash {
+                                if let Some(headers) = builder.headers_mut() {
+                                    insert_digest_headers(headers, sha256, want_digest);
                                 }
-                                
-                                response
-                            }
+                                let body = if verify {
+                                    digest_body(stream, DownloadDigest::Verify(sha256.clone()))
+                                } else {
+                                    axum::body::Body::from_stream(stream)
+                                };
+                                builder
+                                    .header(header::CONTENT_LENGTH, file.size)
+                                    .header(header::ACCEPT_RANGES, ACCEPT_RANGES_BYTES)
+                                    .body(body)
+                            } else {
+                                // Hash the file on its way out and keep it for the next download;
+                                // trailers need a chunked body, so no Content-Length then
+                                let trailers = accepts_trailers(request_headers.get(header::TE).and_then(|v| v.to_str().ok()));
+                                builder = if trailers {
+                                    builder.header(header::TRAILER, trailer_names(want_digest))
+                                } else {
+                                    builder.header(header::CONTENT_LENGTH, file.size)
+                                };
+                                let hash_service = service.clone();
+                                let hashed_file = file.clone();
+                                let on_complete = Box::new(move |sha256: String| {
+                                    tokio::spawn(async move { hash_service.store_content_hash(&hashed_file, sha256).await });
+                                });
+                                builder
+                                    .header(header::ACCEPT_RANGES, ACCEPT_RANGES_BYTES)
+                                    .body(digest_body(stream, DownloadDigest::Record { trailers, with_digest: want_digest, on_complete }))
+                            };
+                            
+                            response.unwrap_or_else(|e| {
+                                tracing::error!("Error building download response: {}", e);
+                                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+                            })
                         },
                         Err(err) => {
-                            tracing::error!("Error getting file content: {}", err);
+                            tracing::error!("Error opening file stream: {}", err);
                             (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
                                 "error": format!("Error reading file: {}", err)
                             }))).into_response()
@@ -322,6 +353,22 @@ impl FileHandler {
                     // For smaller files, load entirely but still potentially compress
                     match service.get_file_content(&id).await {
                         Ok(content) => {
+                            let computed_hash = sha256_hex(&content);
+                            match &stored_hash {
+                                Some(stored) if *stored != computed_hash => {
+                                    tracing::error!("Checksum mismatch for file {}: stored {}, read {}", file.id, stored, computed_hash);
+                                    if verify {
+                                        return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
+                                            "error": "File content does not match its stored checksum"
+                                        }))).into_response();
+                                    }
+                                },
+                                Some(_) => {},
+                                None => service.store_content_hash(&file, computed_hash.clone()).await,
+                            }
+                            // Clients compare against the stored hash, so a corrupted file is noticed
+                            let content_hash = stored_hash.unwrap_or(computed_hash);
+                            
                             // Create base headers
                             let mut headers = HashMap::new();
                             
@@ -332,9 +379,9 @@ impl FileHandler {
                             let disposition = if force_inline || 
                                              file.mime_type.starts_with("image/") || 
                                              file.mime_type == "application/pdf" {
-                                format!("inline; filename=\"{}\"", file.name)
+                                content_disposition("inline", &file.name)
                             } else {
-                                format!("attachment; filename=\"{}\"", file.name)
+                                content_disposition("attachment", &file.name)
                             };
                             
                             headers.insert(header::CONTENT_DISPOSITION.to_string(), disposition);
@@ -369,6 +416,7 @@ impl FileHandler {
                                                 HeaderValue::from_str(&value).unwrap()
                                             );
                                         }
+                                        insert_digest_headers(response.headers_mut(), &content_hash, false);
                                         
                                         response
                                     },
@@ -390,6 +438,7 @@ impl FileHandler {
                                                 HeaderValue::from_str(&value).unwrap()
                                             );
                                         }
+                                        insert_digest_headers(response.headers_mut(), &content_hash, want_digest);
                                         
                                         response
                                     }
@@ -397,6 +446,7 @@ impl FileHandler {
                             } else {
                                 // No compression, return as-is
                                 headers.insert(header::CONTENT_TYPE.to_string(), file.mime_type.clone());
+                                headers.insert(header::ACCEPT_RANGES.to_string(), ACCEPT_RANGES_BYTES.to_string());
                                 
                                 // Build a custom response with headers and body
                                 let mut response = Response::builder()
@@ -411,6 +461,7 @@ impl FileHandler {
                                         HeaderValue::from_str(&value).unwrap()
                                     );
                                 }
+                                insert_digest_headers(response.headers_mut(), &content_hash, want_digest);
                                 
                                 response
                             }
@@ -485,6 +536,7 @@ impl FileHandler {
     /// Deletes a file (with trash support)
     pub async fn delete_file(
         State(state): State<GlobalState>,
+        current_user: Option<Extension<CurrentUser>>,
         Path(id): Path<String>,
     ) -> impl IntoResponse {
         // Check if trash service is available
@@ -502,7 +554,13 @@ impl FileHandler {
                 Ok(_) => {
                     tracing::info!("File successfully moved to trash: {}", id);
                     // Note: Use 204 No Content for consistency with DELETE operations
-                    return StatusCode::NO_CONTENT.into_response();
+                    let undo = UndoAction::RestoreFromTrash {
+                        item_id: id,
+                        item_type: "file".to_string(),
+                        trash_user_id: default_user_id,
+                    };
+                    let user = current_user.as_ref().map(|Extension(user)| user);
+                    return with_undo_token(&state, user, StatusCode::NO_CONTENT.into_response(), undo).await;
                 },
                 Err(err) => {
                     tracing::error!("Could not move file to trash: {:?}", err);
@@ -540,6 +598,26 @@ impl FileHandler {
         }
     }
     
+    /// Overwrites a byte range of a file in place (`PATCH` with `Content-Range`
+    /// or `X-Update-Range`), so large files can be edited without re-uploading
+    pub async fn patch_file(
+        State(state): State<GlobalState>,
+        Path(id): Path<String>,
+        headers: HeaderMap,
+        body: Body,
+    ) -> Result<impl IntoResponse, AppError> {
+        let range = requested_write_range(&headers)?
+            .ok_or_else(|| AppError::bad_request("PATCH needs a Content-Range or X-Update-Range header"))?;
+        
+        let file_service = &state.applications.file_service;
+        let file = file_service.get_file(&id).await?;
+        let content = read_body(body, state.core.config.resources.memory_limits().upload_body).await?;
+        
+        tracing::info!("API request: Writing {} bytes into file {} ({:?})", content.len(), id, range);
+        let file = write_file_range(file_service.as_ref(), &file, range, &content).await?;
+        Ok((StatusCode::OK, Json(file)))
+    }
+    
     /// Moves a file to a different folder
     pub async fn move_file(
         State(service): State<FileServiceState>,
@@ -584,6 +662,93 @@ impl FileHandler {
             }
         }
     }
+    
+    /// Suggests a free name in a folder following the requested pattern
+    /// (`numbered`, `copy` or `conflict`), with suffixes in the client's language
+    pub async fn suggest_free_name(
+        State(service): State<Arc<FreeNameService>>,
+        headers: HeaderMap,
+        Query(query): Query<FreeNameQuery>,
+    ) -> Result<impl IntoResponse, AppError> {
+        let today = chrono::Utc::now().date_naive();
+        let pattern = match query.pattern.as_deref() {
+            None => NamingPattern::Numbered,
+            Some(value) => NamingPattern::parse(value, today)
+                .ok_or_else(|| AppError::bad_request(format!("Unknown naming pattern: {}", value)))?,
+        };
+        
+        let reservation = service
+            .reserve_name(query.folder_id.as_deref(), &query.name, pattern, request_locale(&headers))
+            .await?;
+        Ok(Json(serde_json::json!({ "name": reservation.name() })))
+    }
+    
+    /// Returns an image resized on demand (`w`, `h`, `fit`, `quality`),
+    /// so responsive clients can ask for exactly the size they display
+    pub async fn get_image_preview(
+        State(service): State<Arc<dyn ImagePreviewUseCase>>,
+        Path(id): Path<String>,
+        headers: HeaderMap,
+        Query(query): Query<ImagePreviewQuery>,
+    ) -> Result<impl IntoResponse, AppError> {
+        let fit = match query.fit.as_deref() {
+            None => ImageFit::Contain,
+            Some(value) => ImageFit::parse(value)
+                .ok_or_else(|| AppError::bad_request(format!("Unknown fit mode: {}", value)))?,
+        };
+        let quality = match query.quality.as_deref() {
+            None => None,
+            Some(value) => Some(PreviewQuality::parse(value)
+                .ok_or_else(|| AppError::bad_request(format!("Unknown preview quality: {}", value)))?),
+        };
+        
+        let preview = service
+            .render_preview(&id, ImagePreviewRequest { width: query.w, height: query.h, fit, quality })
+            .await?;
+        
+        let cache_headers = [
+            (header::ETAG, preview.etag.clone()),
+            (header::CACHE_CONTROL, "private, max-age=86400".to_string()),
+        ];
+        let not_modified = headers
+            .get(header::IF_NONE_MATCH)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|tags| tags.split(',').any(|tag| tag.trim() == preview.etag));
+        if not_modified {
+            return Ok((StatusCode::NOT_MODIFIED, cache_headers).into_response());
+        }
+        
+        Ok((
+            StatusCode::OK,
+            cache_headers,
+            [(header::CONTENT_TYPE, preview.mime_type)],
+            preview.content.as_ref().clone(),
+        ).into_response())
+    }
+}
+
+/// Query for an on-demand image preview
+#[derive(Debug, Deserialize)]
+pub struct ImagePreviewQuery {
+    /// Target width in pixels
+    pub w: Option<u32>,
+    /// Target height in pixels
+    pub h: Option<u32>,
+    /// `contain` (default), `cover` or `fill`
+    pub fit: Option<String>,
+    /// `fast` or `high` (defaults to the server setting)
+    pub quality: Option<String>,
+}
+
+/// Query for suggesting a free file name
+#[derive(Debug, Deserialize)]
+pub struct FreeNameQuery {
+    /// Desired name
+    pub name: String,
+    /// Folder to check (None means root)
+    pub folder_id: Option<String>,
+    /// Naming pattern (defaults to numbered)
+    pub pattern: Option<String>,
 }
 
 /// Payload for moving a file
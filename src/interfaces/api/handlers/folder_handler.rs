@@ -1,20 +1,26 @@
 use std::sync::Arc;
 use std::collections::HashMap;
 use axum::{
-    extract::{Path, State, Query},
-    http::{StatusCode, header, HeaderName, HeaderValue, Response},
+    body::Body,
+    extract::{Extension, Path, State, Query},
+    http::{StatusCode, header, HeaderValue, Response},
     response::IntoResponse,
     Json,
 };
 
 use crate::application::services::folder_service::FolderService;
-use crate::application::dtos::folder_dto::{CreateFolderDto, RenameFolderDto, MoveFolderDto};
+use crate::application::dtos::folder_dto::{CreateFolderDto, RenameFolderDto, MoveFolderDto, CopyFolderDto, FolderExportFormat};
 use crate::application::dtos::pagination::PaginationRequestDto;
 use crate::common::errors::ErrorKind;
 use crate::application::ports::inbound::FolderUseCase;
 use crate::common::di::AppState as GlobalAppState;
 use crate::interfaces::middleware::auth::AuthUser;
-use crate::infrastructure::services::zip_service::ZipService;
+use crate::interfaces::middleware::share_access::{ensure_body_access, ShareBodyCheck};
+use crate::application::ports::share_ports::ShareTarget;
+use crate::domain::entities::share::SharePermissions;
+use crate::application::services::archive_service::ArchiveService;
+use crate::application::services::folder_export_service::FolderExportService;
+use crate::domain::services::path_codec_service::content_disposition;
 
 type AppState = Arc<FolderService>;
 
@@ -25,8 +31,17 @@ impl FolderHandler {
     /// Creates a new folder
     pub async fn create_folder(
         State(service): State<AppState>,
+        share_check: Option<Extension<ShareBodyCheck>>,
         Json(dto): Json<CreateFolderDto>,
     ) -> impl IntoResponse {
+        // The parent comes in the body, so the share layer could not check it
+        if let Some(parent_id) = &dto.parent_id {
+            let check = [(ShareTarget::Folder(parent_id.clone()), SharePermissions::WRITE)];
+            if let Err(err) = ensure_body_access(&share_check, &check).await {
+                return err.into_response();
+            }
+        }
+        
         match service.create_folder(dto).await {
             Ok(folder) => (StatusCode::CREATED, Json(folder)).into_response(),
             Err(err) => {
@@ -152,6 +167,27 @@ impl FolderHandler {
         }
     }
     
+    /// Copies a folder with all its subfolders and files
+    pub async fn copy_folder(
+        State(service): State<AppState>,
+        Path(id): Path<String>,
+        Json(dto): Json<CopyFolderDto>,
+    ) -> impl IntoResponse {
+        match service.copy_folder(&id, dto).await {
+            Ok(folder) => (StatusCode::CREATED, Json(folder)).into_response(),
+            Err(err) => {
+                let status = match err.kind {
+                    ErrorKind::NotFound => StatusCode::NOT_FOUND,
+                    ErrorKind::AlreadyExists => StatusCode::CONFLICT,
+                    ErrorKind::InvalidInput => StatusCode::BAD_REQUEST,
+                    _ => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                
+                (status, err.to_string()).into_response()
+            }
+        }
+    }
+    
     /// Deletes a folder (with trash support)
     pub async fn delete_folder(
         State(service): State<AppState>,
@@ -224,61 +260,29 @@ impl FolderHandler {
     ) -> impl IntoResponse {
         tracing::info!("Downloading folder as ZIP: {}", id);
         
-        // Get folder information first to check it exists and get name
-        let folder_service = &state.applications.folder_service;
-        let file_service = &state.applications.file_service;
+        let archive_service = ArchiveService::new(
+            state.applications.folder_service.clone(),
+            state.applications.file_service.clone(),
+        );
         
-        match folder_service.get_folder(&id).await {
-            Ok(folder) => {
-                tracing::info!("Preparing ZIP for folder: {} ({})", folder.name, id);
-                
-                // Create ZIP service with the required services
-                let zip_service = ZipService::new(
-                    file_service.clone(),
-                    folder_service.clone()
-                );
+        match archive_service.folder_archive(&id).await {
+            Ok(archive) => {
+                // The archive is generated while the client reads it, so its length is unknown
+                let content_disposition = content_disposition("attachment", &archive.file_name);
+                let mut response = Response::builder()
+                    .status(StatusCode::OK)
+                    .body(Body::from_stream(archive.stream))
+                    .unwrap();
                 
-                // Create the ZIP file
-                match zip_service.create_folder_zip(&id, &folder.name).await {
-                    Ok(zip_data) => {
-                        tracing::info!("ZIP file created successfully, size: {} bytes", zip_data.len());
-                        
-                        // Setup headers for download
-                        let filename = format!("{}.zip", folder.name);
-                        let content_disposition = format!("attachment; filename=\"{}\"", filename);
-                        
-                        // Build response with the ZIP data
-                        let mut headers = HashMap::new();
-                        headers.insert(header::CONTENT_TYPE.to_string(), "application/zip".to_string());
-                        headers.insert(header::CONTENT_DISPOSITION.to_string(), content_disposition);
-                        headers.insert(header::CONTENT_LENGTH.to_string(), zip_data.len().to_string());
-                        
-                        // Build the response
-                        let mut response = Response::builder()
-                            .status(StatusCode::OK)
-                            .body(axum::body::Body::from(zip_data))
-                            .unwrap();
-                        
-                        // Add headers to response
-                        for (name, value) in headers {
-                            response.headers_mut().insert(
-                                HeaderName::from_bytes(name.as_bytes()).unwrap(),
-                                HeaderValue::from_str(&value).unwrap()
-                            );
-                        }
-                        
-                        response
-                    },
-                    Err(err) => {
-                        tracing::error!("Error creating ZIP file: {}", err);
-                        (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
-                            "error": format!("Error creating ZIP file: {}", err)
-                        }))).into_response()
-                    }
+                response.headers_mut().insert(header::CONTENT_TYPE, HeaderValue::from_static("application/zip"));
+                if let Ok(value) = HeaderValue::from_str(&content_disposition) {
+                    response.headers_mut().insert(header::CONTENT_DISPOSITION, value);
                 }
+                
+                response
             },
             Err(err) => {
-                tracing::error!("Folder not found: {}", err);
+                tracing::error!("Error preparing ZIP for folder {}: {}", id, err);
                 let status = match err.kind {
                     ErrorKind::NotFound => StatusCode::NOT_FOUND,
                     _ => StatusCode::INTERNAL_SERVER_ERROR,
@@ -290,4 +294,58 @@ impl FolderHandler {
             }
         }
     }
+    
+    /// Exports a recursive manifest of the folder contents as CSV or JSON
+    /// (`?format=csv|json`, CSV by default)
+    pub async fn export_folder(
+        State(state): State<GlobalAppState>,
+        Path(id): Path<String>,
+        Query(params): Query<HashMap<String, String>>,
+    ) -> impl IntoResponse {
+        let format = match params.get("format").map(String::as_str) {
+            None => FolderExportFormat::Csv,
+            Some(value) => match FolderExportFormat::parse(value) {
+                Some(format) => format,
+                None => {
+                    return (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+                        "error": format!("Unsupported export format: {}", value)
+                    }))).into_response();
+                }
+            },
+        };
+        tracing::info!("Exporting contents of folder {} as {}", id, format.extension());
+        
+        let export_service = FolderExportService::new(
+            state.applications.folder_service.clone(),
+            state.applications.file_service.clone(),
+        ).with_share_service(state.share_service.clone());
+        
+        match export_service.export(&id, format).await {
+            Ok((file_name, content)) => {
+                let content_disposition = content_disposition("attachment", &file_name);
+                let mut response = Response::builder()
+                    .status(StatusCode::OK)
+                    .body(Body::from(content))
+                    .unwrap();
+                
+                response.headers_mut().insert(header::CONTENT_TYPE, HeaderValue::from_static(format.content_type()));
+                if let Ok(value) = HeaderValue::from_str(&content_disposition) {
+                    response.headers_mut().insert(header::CONTENT_DISPOSITION, value);
+                }
+                
+                response
+            },
+            Err(err) => {
+                tracing::error!("Error exporting contents of folder {}: {}", id, err);
+                let status = match err.kind {
+                    ErrorKind::NotFound => StatusCode::NOT_FOUND,
+                    _ => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                
+                (status, Json(serde_json::json!({
+                    "error": format!("Error exporting folder: {}", err)
+                }))).into_response()
+            }
+        }
+    }
 }
\ No newline at end of file
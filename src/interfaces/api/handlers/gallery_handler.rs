@@ -0,0 +1,61 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::get,
+    extract::{Query, State, Json},
+    response::IntoResponse,
+};
+
+use crate::application::dtos::gallery_dto::{
+    GalleryPageQuery, DEFAULT_GALLERY_PAGE_SIZE, DEFAULT_GALLERY_PREFETCH, MAX_GALLERY_WINDOW,
+};
+use crate::application::ports::gallery_ports::{GalleryUseCase, GalleryWindow};
+use crate::application::ports::image_preview_ports::{ImageFit, ImagePreviewRequest, PreviewQuality};
+use crate::common::errors::AppError;
+
+type GalleryState = Arc<dyn GalleryUseCase>;
+
+/// Thumbnail side used when the client gives neither width nor height
+const DEFAULT_THUMBNAIL_SIZE: u32 = 256;
+
+/// Routes for scrolling through photo folders
+pub fn gallery_routes() -> Router<GalleryState> {
+    Router::new()
+        .route("/prefetch", get(gallery_page))
+}
+
+/// Returns the images in the viewport with their thumbnail URLs and warms
+/// the preview cache for the images that follow
+async fn gallery_page(
+    State(service): State<GalleryState>,
+    Query(query): Query<GalleryPageQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let limit = query.limit.unwrap_or(DEFAULT_GALLERY_PAGE_SIZE);
+    let prefetch = query.prefetch.unwrap_or(DEFAULT_GALLERY_PREFETCH);
+    if limit == 0 || limit > MAX_GALLERY_WINDOW || prefetch > MAX_GALLERY_WINDOW {
+        return Err(AppError::bad_request(format!(
+            "limit must be between 1 and {0} and prefetch at most {0}", MAX_GALLERY_WINDOW
+        )));
+    }
+
+    let fit = match query.fit.as_deref() {
+        None => ImageFit::Cover,
+        Some(value) => ImageFit::parse(value)
+            .ok_or_else(|| AppError::bad_request(format!("Unknown fit mode: {}", value)))?,
+    };
+    let quality = match query.quality.as_deref() {
+        None => None,
+        Some(value) => Some(PreviewQuality::parse(value)
+            .ok_or_else(|| AppError::bad_request(format!("Unknown preview quality: {}", value)))?),
+    };
+    let (width, height) = match (query.w, query.h) {
+        (None, None) => (Some(DEFAULT_THUMBNAIL_SIZE), Some(DEFAULT_THUMBNAIL_SIZE)),
+        sides => sides,
+    };
+
+    let window = GalleryWindow { folder_id: query.folder_id, offset: query.offset, limit, prefetch };
+    let page = service
+        .gallery_page(window, ImagePreviewRequest { width, height, fit, quality })
+        .await?;
+    Ok(Json(page))
+}
@@ -0,0 +1,57 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::get,
+    extract::{State, Json, Extension},
+    http::StatusCode,
+    response::IntoResponse,
+};
+
+use crate::application::dtos::hidden_file_dto::HiddenFileSettingsDto;
+use crate::application::ports::hidden_file_ports::HiddenFileRulesPort;
+use crate::common::errors::AppError;
+use crate::domain::services::hidden_file_service::HiddenFileRules;
+use crate::interfaces::middleware::auth::CurrentUser;
+
+type HiddenFilesState = Arc<dyn HiddenFileRulesPort>;
+
+/// Routes for the per-user dotfile and system file rules
+pub fn hidden_files_routes() -> Router<HiddenFilesState> {
+    Router::new()
+        .route("/", get(get_settings).put(update_settings).delete(reset_settings))
+}
+
+fn settings(service: &HiddenFilesState, user_id: &str) -> HiddenFileSettingsDto {
+    HiddenFileSettingsDto {
+        instance: service.instance_rules(),
+        user: service.user_rules(user_id),
+        effective: service.rules_for_user(user_id),
+    }
+}
+
+/// Returns the instance, user and effective rules of the current user
+async fn get_settings(
+    State(service): State<HiddenFilesState>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(settings(&service, &current_user.id)))
+}
+
+/// Stores the rules of the current user; they can only tighten the instance rules
+async fn update_settings(
+    State(service): State<HiddenFilesState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(rules): Json<HiddenFileRules>,
+) -> Result<impl IntoResponse, AppError> {
+    service.set_user_rules(&current_user.id, Some(rules)).await?;
+    Ok(Json(settings(&service, &current_user.id)))
+}
+
+/// Goes back to the instance rules
+async fn reset_settings(
+    State(service): State<HiddenFilesState>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    service.set_user_rules(&current_user.id, None).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,78 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::{get, post},
+    extract::{Path, Query, State, Json, Extension},
+    http::StatusCode,
+    response::IntoResponse,
+};
+
+use crate::application::dtos::image_tagging_dto::{LabelSearchQuery, UpdateImageTaggingDto};
+use crate::application::ports::image_tagging_ports::ImageTaggingUseCase;
+use crate::common::errors::AppError;
+use crate::interfaces::middleware::auth::CurrentUser;
+
+type ImageTaggingState = Arc<dyn ImageTaggingUseCase>;
+
+/// Routes for opt-in image tagging through the inference service
+pub fn image_tagging_routes() -> Router<ImageTaggingState> {
+    Router::new()
+        .route("/settings", get(get_settings).put(update_settings))
+        .route("/files/{id}/labels", get(get_file_labels))
+        .route("/search", get(search_by_label))
+        .route("/backfill", post(start_backfill))
+        .route("/backfill/{job_id}", get(get_backfill_job))
+}
+
+/// Returns whether tagging is enabled for the current user
+async fn get_settings(
+    State(service): State<ImageTaggingState>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(service.get_settings(&current_user.id).await?))
+}
+
+/// Enables or disables tagging; disabling deletes existing labels
+async fn update_settings(
+    State(service): State<ImageTaggingState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(dto): Json<UpdateImageTaggingDto>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(service.set_enabled(&current_user.id, dto.enabled).await?))
+}
+
+/// Lists the labels of a file
+async fn get_file_labels(
+    State(service): State<ImageTaggingState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(service.get_file_labels(&current_user.id, &id).await?))
+}
+
+/// Finds files by label
+async fn search_by_label(
+    State(service): State<ImageTaggingState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(query): Query<LabelSearchQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(service.search_by_label(&current_user.id, &query.label).await?))
+}
+
+/// Starts labeling the images already stored by the current user
+async fn start_backfill(
+    State(service): State<ImageTaggingState>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    let job = service.start_backfill(&current_user.id, &current_user.username).await?;
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+/// Returns the progress of a backfill job
+async fn get_backfill_job(
+    State(service): State<ImageTaggingState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(job_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(service.get_backfill_job(&current_user.id, &job_id).await?))
+}
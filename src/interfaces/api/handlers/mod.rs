@@ -1,15 +1,57 @@
 pub mod file_handler;
 pub mod folder_handler;
+pub mod gallery_handler;
 pub mod i18n_handler;
 pub mod batch_handler;
 pub mod auth_handler;
+pub mod admin_handler;
+pub mod announcement_handler;
+pub mod demo_handler;
 pub mod trash_handler;
+pub mod scheduling_handler;
 pub mod search_handler;
 pub mod share_handler;
 pub mod favorites_handler;
 pub mod recent_handler;
 pub mod webdav_handler;
 pub mod caldav_handler;
+pub mod carddav_protocol_handler;
+pub mod calendar_ical_handler;
+pub mod contact_import_handler;
+pub mod capabilities_handler;
+pub mod external_storage_handler;
+pub mod photo_handler;
+pub mod image_tagging_handler;
+pub mod upload_session_handler;
+pub mod hidden_files_handler;
+pub mod conflict_handler;
+pub mod feature_flag_handler;
+pub mod plugin_handler;
+pub mod dav_multiget_handler;
+pub mod dav_validation_handler;
+pub mod theme_handler;
+pub mod undo_handler;
+pub mod presence_handler;
+pub mod notification_handler;
+pub mod notification_preferences_handler;
+pub mod dav_transfer_handler;
+pub mod template_handler;
+pub mod user_group_handler;
+pub mod zip_export_handler;
+pub mod well_known_handler;
+pub mod ws_handler;
+pub mod activity_handler;
+pub mod tag_handler;
+pub mod comment_handler;
 
 /// Tipo de resultado para controladores de API
-pub type ApiResult<T> = Result<T, (axum::http::StatusCode, String)>;
\ No newline at end of file
+pub type ApiResult<T> = Result<T, (axum::http::StatusCode, String)>;
+
+/// Idioma preferido del cliente según la cabecera Accept-Language
+pub fn request_locale(headers: &axum::http::HeaderMap) -> crate::domain::services::i18n_service::Locale {
+    headers
+        .get(axum::http::header::ACCEPT_LANGUAGE)
+        .and_then(|v| v.to_str().ok())
+        .map(crate::domain::services::i18n_service::Locale::from_accept_language)
+        .unwrap_or_else(crate::domain::services::i18n_service::Locale::default)
+}
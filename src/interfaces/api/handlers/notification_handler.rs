@@ -0,0 +1,53 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::{get, post, delete},
+    extract::{Path, State, Json, Extension},
+    http::StatusCode,
+    response::IntoResponse,
+};
+
+use crate::application::ports::notification_ports::NotificationUseCase;
+use crate::common::errors::AppError;
+use crate::interfaces::middleware::auth::CurrentUser;
+
+type NotificationState = Arc<dyn NotificationUseCase>;
+
+/// Routes of the notification center with the failures of the user's jobs
+/// and the sharing changes that affect them
+pub fn notification_routes() -> Router<NotificationState> {
+    Router::new()
+        .route("/", get(list_notifications))
+        .route("/{id}/read", post(mark_read))
+        .route("/{id}", delete(dismiss))
+}
+
+/// Lists the notifications of the current user, newest first
+async fn list_notifications(
+    State(service): State<NotificationState>,
+    current_user: Option<Extension<CurrentUser>>,
+) -> impl IntoResponse {
+    let user_id = current_user.as_ref().map(|Extension(user)| user.id.as_str());
+    Json(service.list_notifications(user_id).await)
+}
+
+/// Marks a notification as seen
+async fn mark_read(
+    State(service): State<NotificationState>,
+    current_user: Option<Extension<CurrentUser>>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = current_user.as_ref().map(|Extension(user)| user.id.as_str());
+    Ok(Json(service.mark_read(user_id, &id).await?))
+}
+
+/// Removes a notification
+async fn dismiss(
+    State(service): State<NotificationState>,
+    current_user: Option<Extension<CurrentUser>>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = current_user.as_ref().map(|Extension(user)| user.id.as_str());
+    service.dismiss(user_id, &id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::get,
+    extract::{State, Json, Extension},
+    response::IntoResponse,
+};
+
+use crate::application::dtos::notification_preferences_dto::UpdateEmailPreferencesDto;
+use crate::application::ports::email_notification_ports::NotificationPreferencesUseCase;
+use crate::common::errors::AppError;
+use crate::interfaces::middleware::auth::CurrentUser;
+
+type PreferencesState = Arc<dyn NotificationPreferencesUseCase>;
+
+/// Routes with the emails the current user wants to receive
+pub fn notification_preferences_routes() -> Router<PreferencesState> {
+    Router::new()
+        .route("/", get(get_preferences).put(update_preferences))
+}
+
+/// Email preferences of the current user
+async fn get_preferences(
+    State(service): State<PreferencesState>,
+    current_user: Option<Extension<CurrentUser>>,
+) -> Result<impl IntoResponse, AppError> {
+    let Extension(user) = current_user.ok_or_else(|| AppError::unauthorized("Authentication required"))?;
+    Ok(Json(service.get_preferences(&user.id).await?))
+}
+
+/// Turns emails of the current user on or off
+async fn update_preferences(
+    State(service): State<PreferencesState>,
+    current_user: Option<Extension<CurrentUser>>,
+    Json(update): Json<UpdateEmailPreferencesDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let Extension(user) = current_user.ok_or_else(|| AppError::unauthorized("Authentication required"))?;
+    Ok(Json(service.update_preferences(&user.id, update).await?))
+}
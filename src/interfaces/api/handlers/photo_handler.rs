@@ -0,0 +1,37 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::get,
+    extract::{Query, State, Json, Extension},
+    response::IntoResponse,
+};
+
+use crate::application::dtos::photo_dto::{SimilarPhotosQuery, DEFAULT_SIMILARITY_DISTANCE, MAX_SIMILARITY_DISTANCE};
+use crate::application::ports::image_fingerprint_ports::DuplicatePhotoUseCase;
+use crate::common::errors::AppError;
+use crate::interfaces::middleware::auth::CurrentUser;
+
+type PhotoState = Arc<dyn DuplicatePhotoUseCase>;
+
+/// Routes for photo library tools
+pub fn photo_routes() -> Router<PhotoState> {
+    Router::new()
+        .route("/similar", get(find_similar_photos))
+}
+
+/// Groups duplicate and visually similar photos of the current user
+async fn find_similar_photos(
+    State(service): State<PhotoState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(query): Query<SimilarPhotosQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let threshold = query.threshold.unwrap_or(DEFAULT_SIMILARITY_DISTANCE);
+    if threshold > MAX_SIMILARITY_DISTANCE {
+        return Err(AppError::bad_request(format!(
+            "threshold must be between 0 and {}", MAX_SIMILARITY_DISTANCE
+        )));
+    }
+
+    let groups = service.find_similar_photos(&current_user.username, threshold).await?;
+    Ok(Json(groups))
+}
@@ -0,0 +1,24 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::get,
+    extract::{State, Json},
+    response::IntoResponse,
+};
+
+use crate::interfaces::plugins::PluginRegistry;
+
+type PluginState = Arc<PluginRegistry>;
+
+/// Manifest of the plugins compiled into this server
+pub fn plugin_routes() -> Router<PluginState> {
+    Router::new()
+        .route("/", get(list_plugins))
+}
+
+/// Lists the enabled plugins and where their routes are mounted
+async fn list_plugins(
+    State(registry): State<PluginState>,
+) -> impl IntoResponse {
+    Json(registry.manifest())
+}
@@ -0,0 +1,103 @@
+use std::convert::Infallible;
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::get,
+    extract::{Path, Query, State, Json, Extension},
+    http::StatusCode,
+    response::{IntoResponse, sse::{Event, KeepAlive, Sse}},
+};
+use serde::Deserialize;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::application::dtos::presence_dto::PresenceHeartbeatDto;
+use crate::application::ports::presence_ports::PresenceUseCase;
+use crate::common::errors::AppError;
+use crate::interfaces::middleware::auth::CurrentUser;
+
+type PresenceState = Arc<dyn PresenceUseCase>;
+
+/// Query parameters of the event stream
+#[derive(Debug, Deserialize)]
+struct EventsQuery {
+    /// Comma separated IDs of the files to watch
+    files: String,
+}
+
+/// Routes to announce and watch who has each file open.
+///
+/// Changes are pushed as server-sent events: the client opens
+/// `GET /events?files=<id>,<id>` and receives a `presence` event with a
+/// `PresenceEventDto` every time someone opens, starts editing or closes one
+/// of those files.
+pub fn presence_routes() -> Router<PresenceState> {
+    Router::new()
+        .route("/events", get(presence_events))
+        .route("/{file_id}", get(list_presence).put(heartbeat).delete(leave))
+}
+
+/// Lists the users who have a file open
+async fn list_presence(
+    State(service): State<PresenceState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(file_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(service.list(current_user.share_recipient(), current_user.is_admin(), &file_id).await?))
+}
+
+/// Announces that the current user has a file open; the UI repeats it while
+/// the file stays open and gets back everyone who has it open
+async fn heartbeat(
+    State(service): State<PresenceState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(file_id): Path<String>,
+    heartbeat: Option<Json<PresenceHeartbeatDto>>,
+) -> Result<impl IntoResponse, AppError> {
+    let heartbeat = heartbeat.map(|Json(heartbeat)| heartbeat).unwrap_or_default();
+    let presences = service
+        .heartbeat(current_user.share_recipient(), current_user.is_admin(), &file_id, heartbeat)
+        .await?;
+    Ok(Json(presences))
+}
+
+/// Announces that the current user closed a file
+async fn leave(
+    State(service): State<PresenceState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(file_id): Path<String>,
+) -> impl IntoResponse {
+    service.leave(&file_id, &current_user.id).await;
+    StatusCode::NO_CONTENT
+}
+
+/// Streams the presence changes of the requested files
+async fn presence_events(
+    State(service): State<PresenceState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(query): Query<EventsQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let mut files: Vec<String> = query.files.split(',')
+        .map(|file_id| file_id.trim().to_string())
+        .filter(|file_id| !file_id.is_empty())
+        .collect();
+    files.sort();
+    files.dedup();
+    let mut events = service.watch(current_user.share_recipient(), current_user.is_admin(), &files).await?;
+
+    let stream = async_stream::stream! {
+        loop {
+            match events.recv().await {
+                Ok(event) if files.binary_search(&event.presence.file_id).is_ok() => {
+                    if let Ok(event) = Event::default().event("presence").json_data(&event) {
+                        yield Ok::<_, Infallible>(event);
+                    }
+                }
+                Ok(_) => {}
+                // A slow client misses some events; the next heartbeat catches it up
+                Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break,
+            }
+        }
+    };
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
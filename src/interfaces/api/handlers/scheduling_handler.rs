@@ -0,0 +1,63 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::{get, delete, post, put},
+    extract::{Path, State, Json, Extension},
+    http::StatusCode,
+    response::IntoResponse,
+};
+
+use crate::application::dtos::scheduling_dto::UpdateParticipationDto;
+use crate::application::ports::scheduling_ports::SchedulingUseCase;
+use crate::common::errors::AppError;
+use crate::interfaces::middleware::auth::CurrentUser;
+
+type SchedulingState = Arc<dyn SchedulingUseCase>;
+
+/// Routes to send invitations and answer them without a CalDAV client
+pub fn scheduling_routes() -> Router<SchedulingState> {
+    Router::new()
+        .route("/inbox", get(list_inbox))
+        .route("/inbox/{id}", delete(delete_inbox_message))
+        .route("/calendars/{calendar_id}/events/{uid}/invitations", post(send_invitations))
+        .route("/calendars/{calendar_id}/events/{uid}/participation", put(update_participation))
+}
+
+/// Lists the invitations, replies and cancellations received by the user
+async fn list_inbox(
+    State(service): State<SchedulingState>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(service.list_inbox(&current_user.id).await?))
+}
+
+/// Dismisses a message of the inbox
+async fn delete_inbox_message(
+    State(service): State<SchedulingState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    service.delete_inbox_message(&current_user.id, &id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Sends the invitations of an event to its attendees on this server
+async fn send_invitations(
+    State(service): State<SchedulingState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((calendar_id, uid)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    let is_admin = current_user.role == "admin";
+    Ok(Json(service.send_invitations(&current_user.id, is_admin, &calendar_id, &uid).await?))
+}
+
+/// Accepts, declines or tentatively accepts an event and replies to its organizer
+async fn update_participation(
+    State(service): State<SchedulingState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((calendar_id, uid)): Path<(String, String)>,
+    Json(request): Json<UpdateParticipationDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let is_admin = current_user.role == "admin";
+    Ok(Json(service.respond(&current_user.id, is_admin, &calendar_id, &uid, &request.partstat).await?))
+}
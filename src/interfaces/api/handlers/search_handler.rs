@@ -1,13 +1,18 @@
 use axum::{
-    extract::{State, Query, Json},
+    extract::{State, Query, Json, Extension},
     response::IntoResponse,
     http::StatusCode,
 };
 use serde_json::json;
 use tracing::{info, error};
 
-use crate::application::dtos::search_dto::SearchCriteriaDto;
+use crate::application::dtos::search_dto::{SearchCriteriaDto, SearchResultType, UnifiedSearchQueryDto};
 use crate::common::di::AppState;
+use crate::common::errors::{AppError, DomainError};
+use crate::interfaces::middleware::auth::CurrentUser;
+
+/// Resultados por grupo de la búsqueda unificada si no se pide otro límite
+const DEFAULT_GROUP_LIMIT: usize = 20;
 
 /**
  * Manejador para las operaciones de búsqueda a través de la API.
@@ -24,11 +29,13 @@ impl SearchHandler {
      * Este endpoint permite búsquedas simples directamente con parámetros URL.
      * 
      * @param state Estado de la aplicación con servicios
+     * @param current_user Usuario autenticado
      * @param query_params Parámetros de búsqueda como query string
      * @return Respuesta HTTP con los resultados de la búsqueda
      */
     pub async fn search_files_get(
         State(state): State<AppState>,
+        Extension(current_user): Extension<CurrentUser>,
         Query(params): Query<SearchParams>,
     ) -> impl IntoResponse {
         info!("API: Búsqueda de archivos con parámetros: {:?}", params);
@@ -50,6 +57,7 @@ impl SearchHandler {
         // Convertir parámetros de búsqueda a DTO
         let search_criteria = SearchCriteriaDto {
             name_contains: params.query,
+            content: params.content,
             file_types: params.type_filter.map(|t| t.split(',').map(|s| s.trim().to_string()).collect()),
             created_after: params.created_after,
             created_before: params.created_before,
@@ -58,13 +66,14 @@ impl SearchHandler {
             min_size: params.min_size,
             max_size: params.max_size,
             folder_id: params.folder_id,
+            tags: params.tags.map(|t| t.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()),
             recursive: params.recursive.unwrap_or(true),
             limit: params.limit.unwrap_or(100),
             offset: params.offset.unwrap_or(0),
         };
         
         // Realizar la búsqueda
-        match search_service.search(search_criteria).await {
+        match search_service.search(search_criteria, current_user.share_recipient(), current_user.is_admin()).await {
             Ok(results) => {
                 info!("Búsqueda completada, {} archivos y {} carpetas encontrados", 
                      results.files.len(), results.folders.len());
@@ -89,11 +98,13 @@ impl SearchHandler {
      * proporcionados en el cuerpo de la solicitud.
      * 
      * @param state Estado de la aplicación con servicios
+     * @param current_user Usuario autenticado
      * @param criteria Criterios de búsqueda completos
      * @return Respuesta HTTP con los resultados de la búsqueda
      */
     pub async fn search_files_post(
         State(state): State<AppState>,
+        Extension(current_user): Extension<CurrentUser>,
         Json(criteria): Json<SearchCriteriaDto>,
     ) -> impl IntoResponse {
         info!("API: Búsqueda avanzada de archivos");
@@ -113,7 +124,7 @@ impl SearchHandler {
         };
         
         // Realizar la búsqueda
-        match search_service.search(criteria).await {
+        match search_service.search(criteria, current_user.share_recipient(), current_user.is_admin()).await {
             Ok(results) => {
                 info!("Búsqueda completada, {} archivos y {} carpetas encontrados", 
                      results.files.len(), results.folders.len());
@@ -131,6 +142,43 @@ impl SearchHandler {
         }
     }
     
+    /**
+     * Busca a la vez en archivos, contactos y eventos de calendario.
+     * 
+     * Devuelve un grupo de resultados por cada tipo buscado. Con `types` se
+     * eligen los tipos (`files`, `contacts`, `calendar_events`, separados por comas).
+     * 
+     * @param state Estado de la aplicación con servicios
+     * @param current_user Usuario autenticado
+     * @param params Texto a buscar, tipos y límite por grupo
+     * @return Respuesta HTTP con los resultados agrupados por tipo
+     */
+    pub async fn search_all(
+        State(state): State<AppState>,
+        Extension(current_user): Extension<CurrentUser>,
+        Query(params): Query<UnifiedSearchParams>,
+    ) -> Result<impl IntoResponse, AppError> {
+        info!("API: Búsqueda unificada de {}", current_user.username);
+        
+        let search_service = state.applications.search_service.as_ref()
+            .ok_or_else(|| DomainError::unavailable("Search", "Search service is not available"))?;
+        
+        let types = params.types.as_deref().unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| SearchResultType::parse(name)
+                .ok_or_else(|| AppError::bad_request(format!("Unknown result type: {}", name))))
+            .collect::<Result<Vec<_>, _>>()?;
+        
+        let query = UnifiedSearchQueryDto {
+            query: params.q,
+            types,
+            limit: params.limit.unwrap_or(DEFAULT_GROUP_LIMIT),
+        };
+        Ok(Json(search_service.search_all(query, current_user.share_recipient(), current_user.is_admin()).await?))
+    }
+    
     /**
      * Limpia la caché de resultados de búsqueda.
      * 
@@ -189,6 +237,9 @@ pub struct SearchParams {
     /// Texto a buscar en nombres de archivos y carpetas
     pub query: Option<String>,
     
+    /// Texto a buscar en el contenido de los archivos
+    pub content: Option<String>,
+    
     /// Filtro por tipos de archivo (extensiones separadas por comas)
     #[serde(rename = "type")]
     pub type_filter: Option<String>,
@@ -214,6 +265,9 @@ pub struct SearchParams {
     /// ID de carpeta para limitar la búsqueda
     pub folder_id: Option<String>,
     
+    /// IDs de etiquetas separados por comas; los elementos deben llevarlas todas
+    pub tags: Option<String>,
+    
     /// Búsqueda recursiva en subcarpetas
     pub recursive: Option<bool>,
     
@@ -222,4 +276,17 @@ pub struct SearchParams {
     
     /// Desplazamiento para paginación
     pub offset: Option<usize>,
-}
\ No newline at end of file
+}
+
+/// Parámetros de la búsqueda unificada
+#[derive(Debug, serde::Deserialize)]
+pub struct UnifiedSearchParams {
+    /// Texto a buscar
+    pub q: String,
+    
+    /// Tipos de resultado separados por comas; todos si se omite
+    pub types: Option<String>,
+    
+    /// Número máximo de resultados en cada grupo
+    pub limit: Option<usize>,
+}
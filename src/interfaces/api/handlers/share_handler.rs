@@ -1,20 +1,28 @@
-use std::sync::Arc;
+use std::{net::SocketAddr, sync::Arc};
 
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
-    response::IntoResponse,
-    Json,
+    body::Body,
+    extract::{ConnectInfo, Multipart, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{Html, IntoResponse},
+    routing::{get, post},
+    Extension, Json, Router,
 };
 use serde::Deserialize;
 use serde_json::json;
 
 use crate::{
     application::{
-        dtos::share_dto::{CreateShareDto, UpdateShareDto}, 
-        ports::share_ports::ShareUseCase
+        dtos::share_dto::{CreateShareDto, PublicShareMetadataDto, QrCodeFormat, RequestShareCodeDto, UpdateShareDto, VerifyShareCodeDto},
+        ports::share_ports::{ShareCredentials, ShareTarget, ShareUseCase}
+    },
+    common::errors::{DomainError, ErrorKind},
+    domain::entities::share::SharePermissions,
+    domain::services::{email_template_service::format_size, path_codec_service::content_disposition},
+    interfaces::middleware::{
+        auth::CurrentUser,
+        share_access::{ensure_body_access, ShareBodyCheck},
     },
-    common::errors::ErrorKind,
 };
 
 #[derive(Debug, Deserialize)]
@@ -23,19 +31,57 @@ pub struct GetSharesQuery {
     pub per_page: Option<usize>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct QrCodeQuery {
+    /// `png` (default) or `svg`
+    pub format: Option<String>,
+    /// Pixels per module
+    pub scale: Option<usize>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct VerifyPasswordRequest {
     pub password: String,
 }
 
+/// Header carrying the session of a visitor who verified their email
+pub const SHARE_SESSION_HEADER: &str = "x-share-session";
+
+/// Header carrying the password of a password-protected link. Kept out of
+/// the query string so it does not end up in access logs.
+pub const SHARE_PASSWORD_HEADER: &str = "x-share-password";
+
+#[derive(Debug, Deserialize)]
+pub struct SharedContentQuery {
+    /// File to download from a shared folder
+    pub file: Option<String>,
+    /// Subfolder of a shared folder to list
+    pub folder: Option<String>,
+}
+
+/// Address of the client, when the server exposes connection info
+fn client_ip(connect_info: Option<Extension<ConnectInfo<SocketAddr>>>) -> Option<String> {
+    connect_info.map(|Extension(ConnectInfo(addr))| addr.ip().to_string())
+}
+
 /// Create a new shared link
 pub async fn create_shared_link(
     State(share_use_case): State<Arc<dyn ShareUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    share_check: Option<Extension<ShareBodyCheck>>,
     Json(dto): Json<CreateShareDto>,
 ) -> impl IntoResponse {
-    // For now, we'll use a default user ID until auth is implemented
-    let user_id = "default-user";
-    match share_use_case.create_shared_link(&user_id, dto).await {
+    // Sharing someone else's item needs the reshare permission on it
+    let target = if dto.item_type.eq_ignore_ascii_case("folder") {
+        ShareTarget::Folder(dto.item_id.clone())
+    } else {
+        ShareTarget::File(dto.item_id.clone())
+    };
+    if let Err(error) = ensure_body_access(&share_check, &[(target, SharePermissions::RESHARE)]).await {
+        return error.into_response();
+    }
+
+    match share_use_case.create_shared_link(&current_user.id, dto).await {
         Ok(share) => (StatusCode::CREATED, Json(share)).into_response(),
         Err(err) => {
             let status = match err.kind {
@@ -65,17 +111,65 @@ pub async fn get_shared_link(
     }
 }
 
+/// Get a QR code of a shared link's public URL as PNG or SVG.
+///
+/// Only the owner of the link may get it, and expired links answer 410. The
+/// response tells whether the link needs a password and, through `Expires`,
+/// when it stops working.
+pub async fn get_shared_link_qr_code(
+    State(share_use_case): State<Arc<dyn ShareUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+    Query(query): Query<QrCodeQuery>,
+) -> impl IntoResponse {
+    let format = match query.format.as_deref().map(QrCodeFormat::parse) {
+        None => QrCodeFormat::Png,
+        Some(Some(format)) => format,
+        Some(None) => return (StatusCode::BAD_REQUEST, Json(json!({ "error": "Unsupported QR code format" }))).into_response(),
+    };
+
+    match share_use_case.get_shared_link_qr_code(&id, &current_user.id, format, query.scale.unwrap_or(8)).await {
+        Ok(qr) => {
+            let mut response = (
+                StatusCode::OK,
+                [
+                    (header::CONTENT_TYPE, qr.mime_type.to_string()),
+                    (header::CACHE_CONTROL, "private, no-cache".to_string()),
+                    (header::HeaderName::from_static("x-share-requires-password"), qr.share.has_password.to_string()),
+                ],
+                qr.content,
+            ).into_response();
+            let expires = qr.share.expires_at
+                .and_then(|expires_at| chrono::DateTime::from_timestamp(expires_at as i64, 0))
+                .and_then(|expires_at| header::HeaderValue::from_str(&expires_at.format("%a, %d %b %Y %H:%M:%S GMT").to_string()).ok());
+            if let Some(expires) = expires {
+                response.headers_mut().insert(header::EXPIRES, expires);
+            }
+            response
+        }
+        Err(err) => {
+            let status = match err.kind {
+                ErrorKind::NotFound => StatusCode::NOT_FOUND,
+                ErrorKind::AccessDenied if err.message.contains("expired") => StatusCode::GONE,
+                ErrorKind::AccessDenied => StatusCode::FORBIDDEN,
+                ErrorKind::InvalidInput => StatusCode::BAD_REQUEST,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (status, Json(json!({ "error": err.to_string() }))).into_response()
+        }
+    }
+}
+
 /// Get all shared links created by the current user
 pub async fn get_user_shares(
     State(share_use_case): State<Arc<dyn ShareUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
     Query(query): Query<GetSharesQuery>,
 ) -> impl IntoResponse {
-    // For now, we'll use a default user ID until auth is implemented
-    let user_id = "default-user";
     let page = query.page.unwrap_or(1);
     let per_page = query.per_page.unwrap_or(20);
     
-    match share_use_case.get_user_shared_links(&user_id, page, per_page).await {
+    match share_use_case.get_user_shared_links(&current_user.id, page, per_page).await {
         Ok(shares) => (StatusCode::OK, Json(shares)).into_response(),
         Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": err.to_string() }))).into_response()
     }
@@ -119,37 +213,152 @@ pub async fn delete_shared_link(
     }
 }
 
-/// Access a shared item via its token
+/// Access a shared item via its token. Links that require email verification
+/// need the session from `/api/public/shares/{token}/email/verify` in the
+/// `X-Share-Session` header.
 pub async fn access_shared_item(
     State(share_use_case): State<Arc<dyn ShareUseCase>>,
     Path(token): Path<String>,
+    headers: HeaderMap,
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
 ) -> impl IntoResponse {
-    // Register the access
-    let _ = share_use_case.register_shared_link_access(&token).await;
-    
-    // Get the shared link
-    match share_use_case.get_shared_link_by_token(&token).await {
+    let session = headers.get(SHARE_SESSION_HEADER).and_then(|value| value.to_str().ok());
+    let client_ip = client_ip(connect_info);
+
+    // Register the access and get the shared link
+    match share_use_case.access_shared_link(&token, session, client_ip.as_deref()).await {
         Ok(item) => (StatusCode::OK, Json(item)).into_response(),
-        Err(err) => {
-            let status = match err.kind {
-                ErrorKind::NotFound => StatusCode::NOT_FOUND,
-                ErrorKind::AccessDenied => {
-                    if err.message.contains("expired") {
-                        StatusCode::GONE // HTTP 410 Gone for expired links
-                    } else if err.message.contains("password") {
-                        return (StatusCode::UNAUTHORIZED, Json(json!({ 
-                            "error": "Password required", 
-                            "requiresPassword": true 
-                        }))).into_response();
-                    } else {
-                        StatusCode::FORBIDDEN
-                    }
-                },
-                _ => StatusCode::INTERNAL_SERVER_ERROR,
-            };
-            
-            (status, Json(json!({ "error": err.to_string() }))).into_response()
+        Err(err) => shared_item_error(err),
+    }
+}
+
+/// Response for a visitor who can't open a shared item, telling the client
+/// whether a password or an email verification would let them in
+fn shared_item_error(err: DomainError) -> axum::response::Response {
+    let status = match err.kind {
+        ErrorKind::NotFound => StatusCode::NOT_FOUND,
+        ErrorKind::InvalidInput => StatusCode::BAD_REQUEST,
+        ErrorKind::AccessDenied => {
+            if err.message.contains("expired") {
+                StatusCode::GONE // HTTP 410 Gone for expired links
+            } else if err.message.contains("Email verification") {
+                return (StatusCode::UNAUTHORIZED, Json(json!({
+                    "error": "Email verification required",
+                    "requiresEmail": true
+                }))).into_response();
+            } else if err.message.contains("password") {
+                return (StatusCode::UNAUTHORIZED, Json(json!({ 
+                    "error": "Password required", 
+                    "requiresPassword": true 
+                }))).into_response();
+            } else {
+                StatusCode::FORBIDDEN
+            }
+        },
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    
+    (status, Json(json!({ "error": err.to_string() }))).into_response()
+}
+
+/// Download the content of a shared item: the file of a file share, or the
+/// `file` of a shared folder. Password-protected links need the password in
+/// the `X-Share-Password` header; email-verified ones a session in `X-Share-Session`.
+pub async fn download_shared_item(
+    State(share_use_case): State<Arc<dyn ShareUseCase>>,
+    Path(token): Path<String>,
+    Query(query): Query<SharedContentQuery>,
+    headers: HeaderMap,
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
+) -> impl IntoResponse {
+    let client_ip = client_ip(connect_info);
+    let credentials = ShareCredentials {
+        password: headers.get(SHARE_PASSWORD_HEADER).and_then(|value| value.to_str().ok()),
+        email_session: headers.get(SHARE_SESSION_HEADER).and_then(|value| value.to_str().ok()),
+        client_ip: client_ip.as_deref(),
+    };
+
+    match share_use_case.download_shared_file(&token, query.file.as_deref(), credentials).await {
+        Ok(download) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, download.mime_type),
+                (header::CONTENT_LENGTH, download.size.to_string()),
+                (header::CONTENT_DISPOSITION, content_disposition("attachment", &download.name)),
+                (header::CACHE_CONTROL, "no-store".to_string()),
+                (header::HeaderName::from_static("x-robots-tag"), "noindex".to_string()),
+            ],
+            Body::from_stream(Box::into_pin(download.content)),
+        ).into_response(),
+        Err(err) => shared_item_error(err),
+    }
+}
+
+/// List a shared folder, or its `folder` subfolder, with the same
+/// credentials as downloads
+pub async fn list_shared_folder_items(
+    State(share_use_case): State<Arc<dyn ShareUseCase>>,
+    Path(token): Path<String>,
+    Query(query): Query<SharedContentQuery>,
+    headers: HeaderMap,
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
+) -> impl IntoResponse {
+    let client_ip = client_ip(connect_info);
+    let credentials = ShareCredentials {
+        password: headers.get(SHARE_PASSWORD_HEADER).and_then(|value| value.to_str().ok()),
+        email_session: headers.get(SHARE_SESSION_HEADER).and_then(|value| value.to_str().ok()),
+        client_ip: client_ip.as_deref(),
+    };
+
+    match share_use_case.list_shared_folder(&token, query.folder.as_deref(), credentials).await {
+        Ok(listing) => (StatusCode::OK, [(header::CACHE_CONTROL, "no-store")], Json(listing)).into_response(),
+        Err(err) => shared_item_error(err),
+    }
+}
+
+/// Upload a file into a shared folder whose link accepts uploads, as the
+/// `file` field of a multipart form. File drop links take uploads without
+/// letting visitors list or download anything. Same credentials as downloads.
+pub async fn upload_to_shared_item(
+    State(share_use_case): State<Arc<dyn ShareUseCase>>,
+    Path(token): Path<String>,
+    headers: HeaderMap,
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    let mut file_part = None;
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(err) => return (err.status(), Json(json!({ "error": err.body_text() }))).into_response(),
+        };
+        if field.name() != Some("file") {
+            continue;
         }
+        let file_name = field.file_name().unwrap_or("unnamed").to_string();
+        let content_type = field.content_type().unwrap_or("application/octet-stream").to_string();
+        // Bounded by the upload limit; an oversized file is a 413, not an empty file
+        match field.bytes().await {
+            Ok(bytes) => file_part = Some((file_name, content_type, bytes)),
+            Err(err) => return (err.status(), Json(json!({ "error": err.body_text() }))).into_response(),
+        }
+        break;
+    }
+    let Some((file_name, content_type, bytes)) = file_part else {
+        return (StatusCode::BAD_REQUEST, Json(json!({ "error": "No file provided" }))).into_response();
+    };
+
+    let client_ip = client_ip(connect_info);
+    let credentials = ShareCredentials {
+        password: headers.get(SHARE_PASSWORD_HEADER).and_then(|value| value.to_str().ok()),
+        email_session: headers.get(SHARE_SESSION_HEADER).and_then(|value| value.to_str().ok()),
+        client_ip: client_ip.as_deref(),
+    };
+
+    match share_use_case.upload_to_shared_folder(&token, &file_name, &content_type, bytes.to_vec(), credentials).await {
+        Ok(item) => (StatusCode::CREATED, [(header::CACHE_CONTROL, "no-store")], Json(item)).into_response(),
+        Err(err) => shared_item_error(err),
     }
 }
 
@@ -178,4 +387,261 @@ pub async fn verify_shared_item_password(
             (status, Json(json!({ "error": err.to_string() }))).into_response()
         }
     }
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OEmbedQuery {
+    pub url: String,
+    pub format: Option<String>,
+    pub maxwidth: Option<u32>,
+    pub maxheight: Option<u32>,
+}
+
+/// Unauthenticated share metadata and email verification routes; mount them
+/// behind a rate limiter
+pub fn public_share_metadata_routes() -> Router<Arc<dyn ShareUseCase>> {
+    Router::new()
+        .route("/{token}", get(get_public_share_metadata))
+        .route("/{token}/thumbnail", get(get_public_share_thumbnail))
+        .route("/{token}/email/code", post(request_share_email_code))
+        .route("/{token}/email/verify", post(verify_share_email_code))
+}
+
+/// oEmbed provider endpoint for public share URLs; mount it behind a rate limiter
+pub fn share_oembed_routes() -> Router<Arc<dyn ShareUseCase>> {
+    Router::new().route("/", get(get_share_oembed))
+}
+
+/// Link preview pages served at the public share URLs (`/s/{token}`)
+pub fn share_link_preview_routes() -> Router<Arc<dyn ShareUseCase>> {
+    Router::new().route("/{token}", get(get_share_link_preview))
+}
+
+/// Get the public metadata of a shared link (name, size, type and preview
+/// availability). Every failure answers the same 404 so tokens can't be probed.
+pub async fn get_public_share_metadata(
+    State(share_use_case): State<Arc<dyn ShareUseCase>>,
+    Path(token): Path<String>,
+) -> impl IntoResponse {
+    match share_use_case.get_public_share_metadata(&token).await {
+        Ok(metadata) => (
+            StatusCode::OK,
+            [(header::CACHE_CONTROL, "no-store"), (header::HeaderName::from_static("x-robots-tag"), "noindex")],
+            Json(metadata),
+        ).into_response(),
+        Err(_) => (StatusCode::NOT_FOUND, Json(json!({ "error": "Shared link not available" }))).into_response(),
+    }
+}
+
+/// Send a one-time code to a visitor's email for a share that requires email
+/// verification. Answers 202 whether or not the address is allowed.
+pub async fn request_share_email_code(
+    State(share_use_case): State<Arc<dyn ShareUseCase>>,
+    Path(token): Path<String>,
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
+    Json(req): Json<RequestShareCodeDto>,
+) -> impl IntoResponse {
+    let client_ip = client_ip(connect_info);
+    match share_use_case.request_share_email_code(&token, &req.email, client_ip.as_deref()).await {
+        Ok(()) => (StatusCode::ACCEPTED, Json(json!({ "message": "If the address is allowed, a code has been sent" }))).into_response(),
+        Err(err) => {
+            let status = match err.kind {
+                ErrorKind::NotFound => StatusCode::NOT_FOUND,
+                ErrorKind::InvalidInput => StatusCode::BAD_REQUEST,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (status, Json(json!({ "error": err.to_string() }))).into_response()
+        }
+    }
+}
+
+/// Exchange a one-time code for a session that opens the share
+pub async fn verify_share_email_code(
+    State(share_use_case): State<Arc<dyn ShareUseCase>>,
+    Path(token): Path<String>,
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
+    Json(req): Json<VerifyShareCodeDto>,
+) -> impl IntoResponse {
+    let client_ip = client_ip(connect_info);
+    match share_use_case.verify_share_email_code(&token, &req.email, &req.code, client_ip.as_deref()).await {
+        Ok(session) => (StatusCode::OK, [(header::CACHE_CONTROL, "no-store")], Json(session)).into_response(),
+        Err(err) => {
+            let status = match err.kind {
+                ErrorKind::NotFound => StatusCode::NOT_FOUND,
+                ErrorKind::AccessDenied => StatusCode::UNAUTHORIZED,
+                ErrorKind::InvalidInput => StatusCode::BAD_REQUEST,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (status, Json(json!({ "error": err.to_string() }))).into_response()
+        }
+    }
+}
+
+/// Get the access log of a shared link: accesses, codes sent and
+/// verifications, newest first. Only the owner of the link may read it.
+pub async fn get_share_access_log(
+    State(share_use_case): State<Arc<dyn ShareUseCase>>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match share_use_case.get_share_access_log(&id, &current_user.id).await {
+        Ok(entries) => (StatusCode::OK, Json(entries)).into_response(),
+        Err(err) => {
+            let status = match err.kind {
+                ErrorKind::NotFound => StatusCode::NOT_FOUND,
+                ErrorKind::AccessDenied => StatusCode::FORBIDDEN,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (status, Json(json!({ "error": err.to_string() }))).into_response()
+        }
+    }
+}
+
+/// Get the link preview thumbnail of a shared image, when the share allows it
+pub async fn get_public_share_thumbnail(
+    State(share_use_case): State<Arc<dyn ShareUseCase>>,
+    Path(token): Path<String>,
+) -> impl IntoResponse {
+    match share_use_case.get_public_share_thumbnail(&token).await {
+        Ok(preview) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, preview.mime_type.to_string()),
+                (header::CACHE_CONTROL, "public, max-age=300".to_string()),
+                (header::ETAG, preview.etag),
+            ],
+            preview.content.as_ref().clone(),
+        ).into_response(),
+        Err(_) => (StatusCode::NOT_FOUND, Json(json!({ "error": "Shared link not available" }))).into_response(),
+    }
+}
+
+/// oEmbed (https://oembed.com) response for a public share URL (`/s/{token}`).
+/// Only JSON is supported; the thumbnail is left out when it exceeds the
+/// consumer's maximum size.
+pub async fn get_share_oembed(
+    State(share_use_case): State<Arc<dyn ShareUseCase>>,
+    Query(query): Query<OEmbedQuery>,
+) -> impl IntoResponse {
+    if query.format.as_deref().is_some_and(|format| format != "json") {
+        return (StatusCode::NOT_IMPLEMENTED, Json(json!({ "error": "Only the json format is supported" }))).into_response();
+    }
+    let metadata = match share_token_from_url(&query.url) {
+        Some(token) => share_use_case.get_public_share_metadata(&token).await,
+        None => return (StatusCode::NOT_FOUND, Json(json!({ "error": "Not a shared link URL" }))).into_response(),
+    };
+    let metadata = match metadata {
+        Ok(metadata) => metadata,
+        Err(_) => return (StatusCode::NOT_FOUND, Json(json!({ "error": "Shared link not available" }))).into_response(),
+    };
+
+    let (title, _) = link_preview_text(&metadata);
+    let mut response = json!({
+        "version": "1.0",
+        "type": "link",
+        "title": title,
+        "provider_name": "OxiCloud",
+        "cache_age": 60,
+    });
+    if let Some(thumbnail) = metadata.thumbnail.as_ref().filter(|thumbnail| {
+        query.maxwidth.is_none_or(|max| thumbnail.width <= max) && query.maxheight.is_none_or(|max| thumbnail.height <= max)
+    }) {
+        response["thumbnail_url"] = json!(thumbnail.url);
+        response["thumbnail_width"] = json!(thumbnail.width);
+        response["thumbnail_height"] = json!(thumbnail.height);
+    }
+    (
+        StatusCode::OK,
+        [(header::CACHE_CONTROL, "public, max-age=60"), (header::HeaderName::from_static("x-robots-tag"), "noindex")],
+        Json(response),
+    ).into_response()
+}
+
+/// Token of a public share URL (`.../s/{token}`)
+fn share_token_from_url(url: &str) -> Option<String> {
+    let url = url::Url::parse(url).ok()?;
+    let mut segments = url.path_segments()?.filter(|segment| !segment.is_empty());
+    match (segments.next(), segments.next(), segments.next()) {
+        (Some("s"), Some(token), None) => Some(token.to_string()),
+        _ => None,
+    }
+}
+
+/// Serve a minimal HTML page with Open Graph tags so chat apps can unfurl
+/// a shared link
+pub async fn get_share_link_preview(
+    State(share_use_case): State<Arc<dyn ShareUseCase>>,
+    Path(token): Path<String>,
+) -> impl IntoResponse {
+    match share_use_case.get_public_share_metadata(&token).await {
+        Ok(metadata) => (
+            StatusCode::OK,
+            [(header::CACHE_CONTROL, "public, max-age=60"), (header::HeaderName::from_static("x-robots-tag"), "noindex")],
+            Html(link_preview_html(&metadata)),
+        ).into_response(),
+        Err(_) => (StatusCode::NOT_FOUND, Html(link_preview_page("Shared link not available", "This link does not exist or has expired."))).into_response(),
+    }
+}
+
+fn link_preview_text(metadata: &PublicShareMetadataDto) -> (String, String) {
+    let kind = if metadata.item_type == "folder" { "folder" } else { "file" };
+    if metadata.requires_password {
+        return (format!("Password-protected {}", kind), format!("A {} shared on OxiCloud", kind));
+    }
+    if metadata.requires_email {
+        return (format!("Shared {}", kind), format!("A {} shared on OxiCloud; verify your email to open it", kind));
+    }
+    let title = metadata.name.clone().unwrap_or_else(|| format!("Shared {}", kind));
+    let description = match (metadata.size, &metadata.mime_type) {
+        (Some(size), Some(mime_type)) => format!("{} · {}", format_size(size), mime_type),
+        _ => format!("A {} shared on OxiCloud", kind),
+    };
+    (title, description)
+}
+
+fn link_preview_html(metadata: &PublicShareMetadataDto) -> String {
+    let (title, description) = link_preview_text(metadata);
+    let url = escape_html(&metadata.url);
+    let base_url = metadata.url.rsplit_once("/s/").map(|(base, _)| base).unwrap_or_default();
+    let oembed_url: String = url::form_urlencoded::byte_serialize(metadata.url.as_bytes()).collect();
+
+    let mut head = format!(
+        "<meta property=\"og:url\" content=\"{url}\">\n\
+         <link rel=\"alternate\" type=\"application/json+oembed\" href=\"{}\">\n",
+        escape_html(&format!("{}/api/public/oembed?url={}&format=json", base_url, oembed_url))
+    );
+    match &metadata.thumbnail {
+        Some(thumbnail) => head.push_str(&format!(
+            "<meta property=\"og:image\" content=\"{}\">\n<meta property=\"og:image:width\" content=\"{}\">\n\
+             <meta property=\"og:image:height\" content=\"{}\">\n<meta name=\"twitter:card\" content=\"summary_large_image\">\n",
+            escape_html(&thumbnail.url), thumbnail.width, thumbnail.height
+        )),
+        None => head.push_str("<meta name=\"twitter:card\" content=\"summary\">\n"),
+    }
+    link_preview_document(&title, &description, &head)
+}
+
+fn link_preview_page(title: &str, description: &str) -> String {
+    link_preview_document(title, description, "<meta name=\"twitter:card\" content=\"summary\">\n")
+}
+
+fn link_preview_document(title: &str, description: &str, extra_head: &str) -> String {
+    let title = escape_html(title);
+    let description = escape_html(description);
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"robots\" content=\"noindex\">\n\
+         <title>{title}</title>\n<meta property=\"og:site_name\" content=\"OxiCloud\">\n\
+         <meta property=\"og:type\" content=\"website\">\n<meta property=\"og:title\" content=\"{title}\">\n\
+         <meta property=\"og:description\" content=\"{description}\">\n{extra_head}\
+         </head>\n<body>\n<h1>{title}</h1>\n<p>{description}</p>\n</body>\n</html>\n"
+    )
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
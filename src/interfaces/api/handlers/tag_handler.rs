@@ -0,0 +1,109 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::{delete, get, put},
+    extract::{Path, State, Json, Extension},
+    http::StatusCode,
+    response::IntoResponse,
+};
+
+use crate::application::dtos::tag_dto::{CreateTagDto, TagAssignmentDto, UpdateTagDto};
+use crate::application::ports::tag_ports::TagUseCase;
+use crate::common::errors::AppError;
+use crate::domain::entities::tag::TaggedItemType;
+use crate::interfaces::middleware::auth::CurrentUser;
+
+type TagState = Arc<dyn TagUseCase>;
+
+/// Routes for users to manage their tags and put them on files and folders
+pub fn tag_routes() -> Router<TagState> {
+    Router::new()
+        .route("/", get(list_tags).post(create_tag))
+        .route("/{id}", put(update_tag).delete(delete_tag))
+        .route("/{id}/items", get(list_tagged_items).post(assign_tag))
+        .route("/{id}/items/{item_type}/{item_id}", delete(unassign_tag))
+        .route("/items/{item_type}/{item_id}", get(get_item_tags))
+}
+
+fn parse_item_type(item_type: &str) -> Result<TaggedItemType, AppError> {
+    TaggedItemType::parse(item_type)
+        .ok_or_else(|| AppError::bad_request(format!("Unknown item type: {}. Expected file or folder", item_type)))
+}
+
+/// Lists the user's tags
+async fn list_tags(
+    State(service): State<TagState>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(service.list_tags(&current_user.id).await?))
+}
+
+/// Creates a tag
+async fn create_tag(
+    State(service): State<TagState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(request): Json<CreateTagDto>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok((StatusCode::CREATED, Json(service.create_tag(&current_user.id, request).await?)))
+}
+
+/// Renames a tag or changes its color
+async fn update_tag(
+    State(service): State<TagState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+    Json(request): Json<UpdateTagDto>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(service.update_tag(&current_user.id, &id, request).await?))
+}
+
+/// Deletes a tag and removes it from every item
+async fn delete_tag(
+    State(service): State<TagState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    service.delete_tag(&current_user.id, &id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Lists the files and folders carrying a tag
+async fn list_tagged_items(
+    State(service): State<TagState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(service.list_tagged_items(&current_user.id, &id).await?))
+}
+
+/// Puts a tag on a file or folder
+async fn assign_tag(
+    State(service): State<TagState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+    Json(item): Json<TagAssignmentDto>,
+) -> Result<impl IntoResponse, AppError> {
+    service.assign_tag(&current_user.id, &id, item).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Removes a tag from a file or folder
+async fn unassign_tag(
+    State(service): State<TagState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((id, item_type, item_id)): Path<(String, String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    let item = TagAssignmentDto { item_id, item_type: parse_item_type(&item_type)? };
+    service.unassign_tag(&current_user.id, &id, item).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Lists the user's tags on a file or folder
+async fn get_item_tags(
+    State(service): State<TagState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((item_type, item_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    let item_type = parse_item_type(&item_type)?;
+    Ok(Json(service.get_item_tags(&current_user.id, item_type, &item_id).await?))
+}
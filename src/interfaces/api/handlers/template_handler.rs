@@ -0,0 +1,45 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::get,
+    extract::{State, Json, Extension},
+    http::{StatusCode, HeaderMap},
+    response::IntoResponse,
+};
+
+use crate::application::dtos::template_dto::NewFromTemplateDto;
+use crate::application::ports::template_ports::{TemplateUseCase, TemplateUser};
+use crate::common::errors::AppError;
+use crate::interfaces::api::handlers::request_locale;
+use crate::interfaces::middleware::auth::CurrentUser;
+
+type TemplateState = Arc<dyn TemplateUseCase>;
+
+/// Routes listing the templates of the "New document" menu
+pub fn template_routes() -> Router<TemplateState> {
+    Router::new()
+        .route("/", get(list_templates))
+}
+
+/// Lists the templates available to the current user
+async fn list_templates(
+    State(service): State<TemplateState>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(service.list_templates(&current_user.username).await?))
+}
+
+/// Creates a document from a template (`POST /api/files/new-from-template`)
+pub async fn new_from_template(
+    State(service): State<TemplateState>,
+    Extension(current_user): Extension<CurrentUser>,
+    headers: HeaderMap,
+    Json(request): Json<NewFromTemplateDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let user = TemplateUser {
+        username: current_user.username,
+        email: current_user.email,
+    };
+    let file = service.create_from_template(&user, request, request_locale(&headers)).await?;
+    Ok((StatusCode::CREATED, Json(file)))
+}
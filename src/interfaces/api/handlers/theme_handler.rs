@@ -0,0 +1,66 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::get,
+    extract::{State, Json},
+    http::header,
+    response::IntoResponse,
+};
+
+use crate::application::ports::theme_ports::ThemeUseCase;
+use crate::common::errors::AppError;
+
+type ThemeState = Arc<dyn ThemeUseCase>;
+
+/// Short cache for the unversioned URLs, so a new theme shows up within a minute
+const THEME_CACHE_CONTROL: &str = "public, max-age=60";
+
+/// Public routes with the instance branding; the login page needs them before
+/// anyone is authenticated
+pub fn theme_routes() -> Router<ThemeState> {
+    Router::new()
+        .route("/", get(get_theme))
+        .route("/theme.css", get(get_stylesheet))
+        .route("/logo", get(get_logo))
+}
+
+/// Returns the instance name, colors, login message and asset URLs
+async fn get_theme(
+    State(theme): State<ThemeState>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(([(header::CACHE_CONTROL, THEME_CACHE_CONTROL)], Json(theme.get_theme().await)))
+}
+
+/// Returns the stylesheet that applies the theme colors to the web UI
+async fn get_stylesheet(
+    State(theme): State<ThemeState>,
+) -> Result<impl IntoResponse, AppError> {
+    let (css, version) = theme.stylesheet().await;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/css; charset=utf-8".to_string()),
+            (header::CACHE_CONTROL, THEME_CACHE_CONTROL.to_string()),
+            (header::ETAG, format!("\"theme-{}\"", version)),
+        ],
+        css,
+    ))
+}
+
+/// Returns the custom logo, or 404 when the built-in one is in use
+async fn get_logo(
+    State(theme): State<ThemeState>,
+) -> Result<impl IntoResponse, AppError> {
+    let logo = theme.get_logo().await
+        .ok_or_else(|| AppError::not_found("No custom logo configured"))?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, logo.content_type),
+            (header::CACHE_CONTROL, THEME_CACHE_CONTROL.to_string()),
+            (header::ETAG, format!("\"logo-{}\"", logo.version)),
+            // SVG logos may carry scripts; never run them if the logo is opened directly
+            (header::CONTENT_SECURITY_POLICY, "default-src 'none'; style-src 'unsafe-inline'; sandbox".to_string()),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+        ],
+        logo.data,
+    ))
+}
@@ -0,0 +1,63 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::post,
+    extract::{Path, State, Json, Extension},
+    http::{HeaderName, HeaderValue},
+    response::{IntoResponse, Response},
+};
+
+use crate::application::ports::undo_ports::{UndoAction, UndoUseCase};
+use crate::common::di::AppState;
+use crate::common::errors::AppError;
+use crate::interfaces::middleware::auth::CurrentUser;
+
+type UndoState = Arc<dyn UndoUseCase>;
+
+/// Header carrying the token that undoes the operation of the response
+pub const X_UNDO_TOKEN: HeaderName = HeaderName::from_static("x-undo-token");
+
+/// Header with the RFC 3339 instant after which the undo token stops working
+pub const X_UNDO_EXPIRES: HeaderName = HeaderName::from_static("x-undo-expires");
+
+/// Routes for undoing recent deletes, moves and renames
+pub fn undo_routes() -> Router<UndoState> {
+    Router::new()
+        .route("/{token}", post(undo))
+}
+
+/// Reverses the operation an undo token was issued for
+async fn undo(
+    State(service): State<UndoState>,
+    current_user: Option<Extension<CurrentUser>>,
+    Path(token): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = current_user.as_ref().map(|Extension(user)| user.id.as_str());
+    Ok(Json(service.undo(user_id, &token).await?))
+}
+
+/// Registers `action` for a successful response and adds the undo token
+/// headers to it; other responses, or a server without undo, pass through.
+pub async fn with_undo_token(
+    state: &AppState,
+    current_user: Option<&CurrentUser>,
+    mut response: Response,
+    action: UndoAction,
+) -> Response {
+    let Some(service) = &state.undo_service else {
+        return response;
+    };
+    if !response.status().is_success() {
+        return response;
+    }
+
+    let undo = service.register(current_user.map(|user| user.id.as_str()), action).await;
+    let headers = response.headers_mut();
+    if let Ok(value) = HeaderValue::from_str(&undo.token) {
+        headers.insert(X_UNDO_TOKEN, value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&undo.expires_at.to_rfc3339()) {
+        headers.insert(X_UNDO_EXPIRES, value);
+    }
+    response
+}
@@ -0,0 +1,330 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::{head, post},
+    body::{Body, Bytes},
+    extract::{Path, State, Json, Extension, OriginalUri},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use base64::Engine;
+use futures::TryStreamExt;
+
+use crate::application::dtos::upload_session_dto::{CreateUploadSessionDto, UploadSessionDto};
+use crate::application::ports::share_ports::ShareTarget;
+use crate::application::ports::upload_session_ports::UploadSessionUseCase;
+use crate::common::errors::AppError;
+use crate::domain::entities::share::SharePermissions;
+use crate::interfaces::middleware::auth::CurrentUser;
+use crate::interfaces::middleware::share_access::{ensure_body_access, ShareBodyCheck};
+
+type UploadSessionState = Arc<dyn UploadSessionUseCase>;
+
+/// Header carrying the byte offset a chunk starts at
+const UPLOAD_OFFSET_HEADER: &str = "Upload-Offset";
+
+/// TUS protocol version spoken by the `/tus` endpoints
+const TUS_VERSION: &str = "1.0.0";
+
+/// TUS extensions supported besides the core protocol
+const TUS_EXTENSIONS: &str = "creation,termination,expiration";
+
+/// Content type TUS requires on PATCH requests
+const TUS_CHUNK_CONTENT_TYPE: &str = "application/offset+octet-stream";
+
+/// Response header naming the file created when the last chunk arrives
+const UPLOAD_FILE_ID_HEADER: &str = "OxiCloud-File-Id";
+
+/// Routes for resumable chunked uploads
+pub fn upload_session_routes() -> Router<UploadSessionState> {
+    Router::new()
+        .route("/", post(create_session))
+        .route("/{id}", axum::routing::get(get_session).patch(append_chunk).delete(cancel_session))
+        .route("/{id}/complete", post(complete_session))
+}
+
+/// Routes implementing the TUS resumable upload protocol over the same
+/// sessions, for clients such as tus-js-client or Uppy
+pub fn tus_routes() -> Router<UploadSessionState> {
+    Router::new()
+        .route("/", post(tus_create).options(tus_options))
+        .route("/{id}", head(tus_head).patch(tus_patch).delete(tus_terminate).options(tus_options))
+}
+
+/// Users uploading into someone else's folder need write access through a
+/// share; later chunks only reach sessions of the same user
+async fn ensure_target_access(
+    share_check: &Option<Extension<ShareBodyCheck>>,
+    folder_id: Option<&str>,
+) -> Result<(), AppError> {
+    match folder_id {
+        Some(folder_id) => {
+            ensure_body_access(share_check, &[(ShareTarget::Folder(folder_id.to_string()), SharePermissions::WRITE)]).await
+        }
+        None => Ok(()),
+    }
+}
+
+/// Starts a chunked upload
+async fn create_session(
+    State(service): State<UploadSessionState>,
+    Extension(current_user): Extension<CurrentUser>,
+    share_check: Option<Extension<ShareBodyCheck>>,
+    Json(dto): Json<CreateUploadSessionDto>,
+) -> Result<impl IntoResponse, AppError> {
+    ensure_target_access(&share_check, dto.folder_id.as_deref()).await?;
+    let session = service.create_session(&current_user.id, dto).await?;
+    Ok((StatusCode::CREATED, Json(session)))
+}
+
+/// Returns the offset to resume from
+async fn get_session(
+    State(service): State<UploadSessionState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(service.get_session(&current_user.id, &id).await?))
+}
+
+/// Appends the request body at the offset given in `Upload-Offset`
+async fn append_chunk(
+    State(service): State<UploadSessionState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<impl IntoResponse, AppError> {
+    let offset = headers
+        .get(UPLOAD_OFFSET_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .ok_or_else(|| AppError::bad_request(format!("Missing or invalid {} header", UPLOAD_OFFSET_HEADER)))?;
+
+    let session = service.append_chunk(&current_user.id, &id, offset, body).await?;
+    Ok(Json(session))
+}
+
+/// Creates the file once every byte has been received
+async fn complete_session(
+    State(service): State<UploadSessionState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let file = service.complete_session(&current_user.id, &id).await?;
+    Ok((StatusCode::CREATED, Json(file)))
+}
+
+/// Abandons an upload
+async fn cancel_session(
+    State(service): State<UploadSessionState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    service.cancel_session(&current_user.id, &id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Starts a response carrying the TUS version header every reply needs
+fn tus_response(status: StatusCode) -> axum::http::response::Builder {
+    Response::builder()
+        .status(status)
+        .header("Tus-Resumable", TUS_VERSION)
+}
+
+/// Rejects requests for a TUS version other than the one implemented
+fn unsupported_tus_version(headers: &HeaderMap) -> Option<Response<Body>> {
+    let requested = headers.get("Tus-Resumable").and_then(|v| v.to_str().ok());
+    if requested == Some(TUS_VERSION) {
+        return None;
+    }
+    Some(tus_response(StatusCode::PRECONDITION_FAILED)
+        .header("Tus-Version", TUS_VERSION)
+        .body(Body::empty())
+        .unwrap())
+}
+
+fn parse_u64_header(headers: &HeaderMap, name: &str) -> Result<u64, AppError> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .ok_or_else(|| AppError::bad_request(format!("Missing or invalid {} header", name)))
+}
+
+/// Decodes `Upload-Metadata`: comma separated pairs of a key and an optional
+/// base64 value
+fn parse_upload_metadata(value: &str) -> Result<HashMap<String, String>, AppError> {
+    let mut metadata = HashMap::new();
+    for pair in value.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+        let (key, encoded) = pair.split_once(' ').unwrap_or((pair, ""));
+        let decoded = base64::engine::general_purpose::STANDARD
+            .decode(encoded.trim())
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .ok_or_else(|| AppError::bad_request(format!("Invalid Upload-Metadata value for '{}'", key)))?;
+        metadata.insert(key.to_string(), decoded);
+    }
+    Ok(metadata)
+}
+
+fn upload_expires(session: &UploadSessionDto) -> String {
+    session.expires_at.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// Advertises the TUS version, extensions and size limit
+async fn tus_options(State(service): State<UploadSessionState>) -> impl IntoResponse {
+    tus_response(StatusCode::NO_CONTENT)
+        .header("Tus-Version", TUS_VERSION)
+        .header("Tus-Extension", TUS_EXTENSIONS)
+        .header("Tus-Max-Size", service.max_upload_size())
+        .body(Body::empty())
+        .unwrap()
+}
+
+/// Creates an upload from `Upload-Length` and `Upload-Metadata`
+/// (`filename`, `filetype` and optionally `folder_id`)
+async fn tus_create(
+    State(service): State<UploadSessionState>,
+    Extension(current_user): Extension<CurrentUser>,
+    share_check: Option<Extension<ShareBodyCheck>>,
+    OriginalUri(uri): OriginalUri,
+    headers: HeaderMap,
+) -> Result<Response<Body>, AppError> {
+    if let Some(response) = unsupported_tus_version(&headers) {
+        return Ok(response);
+    }
+    if headers.contains_key("Upload-Defer-Length") {
+        return Err(AppError::bad_request("Deferred upload length is not supported"));
+    }
+
+    let total_size = parse_u64_header(&headers, "Upload-Length")?;
+    if total_size > service.max_upload_size() {
+        return Ok(tus_response(StatusCode::PAYLOAD_TOO_LARGE).body(Body::empty()).unwrap());
+    }
+
+    let mut metadata = match headers.get("Upload-Metadata").and_then(|v| v.to_str().ok()) {
+        Some(value) => parse_upload_metadata(value)?,
+        None => HashMap::new(),
+    };
+    let name = metadata
+        .remove("filename")
+        .or_else(|| metadata.remove("name"))
+        .ok_or_else(|| AppError::bad_request("Upload-Metadata must include a filename"))?;
+    let content_type = metadata
+        .remove("filetype")
+        .or_else(|| metadata.remove("type"))
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+
+    let folder_id = metadata.remove("folder_id").filter(|value| !value.is_empty());
+    ensure_target_access(&share_check, folder_id.as_deref()).await?;
+
+    let session = service.create_session(&current_user.id, CreateUploadSessionDto {
+        name,
+        folder_id,
+        content_type,
+        total_size,
+    }).await?;
+
+    let location = format!("{}/{}", uri.path().trim_end_matches('/'), session.id);
+    Ok(tus_response(StatusCode::CREATED)
+        .header(header::LOCATION, location)
+        .header("Upload-Expires", upload_expires(&session))
+        .body(Body::empty())
+        .unwrap())
+}
+
+/// Reports the offset a client should resume from
+async fn tus_head(
+    State(service): State<UploadSessionState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response<Body>, AppError> {
+    if let Some(response) = unsupported_tus_version(&headers) {
+        return Ok(response);
+    }
+
+    let session = service.get_session(&current_user.id, &id).await?;
+    Ok(tus_response(StatusCode::OK)
+        .header(UPLOAD_OFFSET_HEADER, session.offset)
+        .header("Upload-Length", session.total_size)
+        .header("Upload-Expires", upload_expires(&session))
+        .header(header::CACHE_CONTROL, "no-store")
+        .body(Body::empty())
+        .unwrap())
+}
+
+/// Streams the body into the upload at `Upload-Offset`. The file is created
+/// as soon as the last byte arrives, since TUS has no separate completion step
+async fn tus_patch(
+    State(service): State<UploadSessionState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    body: Body,
+) -> Result<Response<Body>, AppError> {
+    if let Some(response) = unsupported_tus_version(&headers) {
+        return Ok(response);
+    }
+
+    let content_type = headers.get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok());
+    if content_type != Some(TUS_CHUNK_CONTENT_TYPE) {
+        return Ok(tus_response(StatusCode::UNSUPPORTED_MEDIA_TYPE).body(Body::empty()).unwrap());
+    }
+    let offset = parse_u64_header(&headers, UPLOAD_OFFSET_HEADER)?;
+
+    let data = Box::pin(body.into_data_stream().map_err(std::io::Error::other));
+    let session = service.append_stream(&current_user.id, &id, offset, data).await?;
+
+    let mut response = tus_response(StatusCode::NO_CONTENT)
+        .header(UPLOAD_OFFSET_HEADER, session.offset)
+        .header("Upload-Expires", upload_expires(&session));
+    if session.offset == session.total_size {
+        let file = service.complete_session(&current_user.id, &id).await?;
+        response = response.header(UPLOAD_FILE_ID_HEADER, file.id);
+    }
+
+    Ok(response.body(Body::empty()).unwrap())
+}
+
+/// Abandons an upload (TUS termination extension)
+async fn tus_terminate(
+    State(service): State<UploadSessionState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response<Body>, AppError> {
+    if let Some(response) = unsupported_tus_version(&headers) {
+        return Ok(response);
+    }
+
+    service.cancel_session(&current_user.id, &id).await?;
+    Ok(tus_response(StatusCode::NO_CONTENT).body(Body::empty()).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_upload_metadata() {
+        let metadata = parse_upload_metadata("filename cmVwb3J0LnBkZg==, filetype YXBwbGljYXRpb24vcGRm,is_confidential").unwrap();
+        assert_eq!(metadata["filename"], "report.pdf");
+        assert_eq!(metadata["filetype"], "application/pdf");
+        // Keys without a value are allowed and decode to an empty string
+        assert_eq!(metadata["is_confidential"], "");
+
+        assert!(parse_upload_metadata("filename not*base64").is_err());
+    }
+
+    #[test]
+    fn test_unsupported_tus_version() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(unsupported_tus_version(&headers).unwrap().status(), StatusCode::PRECONDITION_FAILED);
+
+        headers.insert("Tus-Resumable", TUS_VERSION.parse().unwrap());
+        assert!(unsupported_tus_version(&headers).is_none());
+    }
+}
@@ -0,0 +1,46 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::get,
+    extract::{Path, State, Json, Extension},
+    response::IntoResponse,
+};
+
+use crate::application::ports::user_group_ports::UserGroupUseCase;
+use crate::common::errors::AppError;
+use crate::interfaces::middleware::auth::CurrentUser;
+
+type UserGroupState = Arc<dyn UserGroupUseCase>;
+
+/// Routes for users to find the groups they can share with and the ones they belong to
+pub fn user_group_routes() -> Router<UserGroupState> {
+    Router::new()
+        .route("/", get(list_groups))
+        .route("/mine", get(list_my_groups))
+        .route("/{id}", get(get_group))
+}
+
+/// Lists every group, to pick one as the grantee of a share
+async fn list_groups(
+    State(service): State<UserGroupState>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(service.list_groups(&current_user.caller()).await?))
+}
+
+/// Lists the groups the user is a member of
+async fn list_my_groups(
+    State(service): State<UserGroupState>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(service.my_groups(&current_user.caller()).await?))
+}
+
+/// Returns a group the user belongs to, with its members
+async fn get_group(
+    State(service): State<UserGroupState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(service.get_group(&current_user.caller(), &id).await?))
+}
@@ -9,24 +9,53 @@
 
 use axum::{
     Router,
+    extract::State,
     response::Response,
-    http::{StatusCode, header, HeaderName, Request},
-    body::{Body, self},
+    http::{StatusCode, header, HeaderMap, HeaderName, Request},
+    body::Body,
 };
+use std::collections::HashMap;
 use std::sync::Arc;
 use uuid::Uuid;
 use chrono::Utc;
 use bytes::Buf;
 
 use crate::common::di::AppState;
-use crate::application::adapters::webdav_adapter::{WebDavAdapter, PropFindRequest, LockInfo, LockScope, LockType};
+use crate::application::adapters::webdav_adapter::{WebDavAdapter, PropFindRequest, PropFindType, LockInfo, LockScope, LockType, QualifiedName, CALENDARSERVER_NS, OWNCLOUD_NS};
+use crate::application::adapters::webdav_ordering::{collection_ctag, order_listing, ListingOrder, ListingWindow, OrderKey};
 use crate::interfaces::middleware::auth::CurrentUser;
 use crate::application::dtos::folder_dto::FolderDto;
-use crate::common::errors::AppError;
+use crate::common::bounded_buffer::{read_body, BoundedBuffer};
+use crate::common::errors::{AppError, ErrorKind};
+use crate::application::dtos::file_dto::FileDto;
+use crate::application::ports::file_attribute_ports::FileAttributes;
+use crate::domain::services::extended_attribute_service::ExtendedAttribute;
+use crate::domain::services::hidden_file_service::HiddenFileRules;
+use crate::domain::entities::lock::ResourceLock;
+use crate::domain::entities::dead_property::DeadProperty;
+use crate::application::ports::lock_ports::LockRequest;
+use crate::application::ports::ownership_ports::{TransferItem, TransferPlan};
+use crate::interfaces::api::handlers::dav_transfer_handler::dav_transfer_url;
+use crate::domain::services::ownership_service::TransferOperation;
+use crate::domain::entities::sync_conflict::ConflictKind;
+use crate::domain::services::naming_service::conflict_copy_original;
+use crate::domain::services::path_codec_service::{decode_path, encode_path};
+use crate::domain::services::byte_range_service::WriteRange;
+use crate::interfaces::api::conditional::{if_none_match, not_modified, set_etag, WeakEtag};
+use crate::interfaces::api::range_response::{file_range_response, requested_write_range, write_file_range, ACCEPT_RANGES_BYTES};
 
 // Create a custom DAV header since it's not in the standard headers
 const HEADER_DAV: HeaderName = HeaderName::from_static("dav");
+
+/// Compliance classes sent in the DAV header: classes 1 and 2 of RFC 4918
+/// plus SabreDAV's ranged PATCH. The capabilities endpoint advertises the same.
+pub const DAV_COMPLIANCE_CLASSES: &[&str] = &["1", "2", "sabredav-partialupdate"];
 const HEADER_LOCK_TOKEN: HeaderName = HeaderName::from_static("lock-token");
+// Ordering hints of PROPFIND listings, see `webdav_ordering`
+const HEADER_ORDER_BY: HeaderName = HeaderName::from_static("x-order-by");
+const HEADER_OFFSET: HeaderName = HeaderName::from_static("x-offset");
+const HEADER_LIMIT: HeaderName = HeaderName::from_static("x-limit");
+const HEADER_TOTAL_COUNT: HeaderName = HeaderName::from_static("x-total-count");
 // const HEADER_IF: HeaderName = HeaderName::from_static("if");
 
 /**
@@ -38,21 +67,37 @@ const HEADER_LOCK_TOKEN: HeaderName = HeaderName::from_static("lock-token");
  * @return Router configured with WebDAV endpoints
  */
 pub fn webdav_routes() -> Router<AppState> {
-    // Create the router with a single catchall route
+    // Create the router with the root and a catchall route
     // This will internally dispatch to the appropriate method handler
     Router::new()
+        .route("/webdav/", axum::routing::any(handle_webdav_methods))
         .route("/webdav/{*path}", axum::routing::any(handle_webdav_methods))
 }
 
+/// Path of the requested resource below the WebDAV root, decoded
+fn request_path(uri: &axum::http::Uri) -> Result<String, AppError> {
+    let parts = uri.path().split('/').collect::<Vec<&str>>();
+    if parts.len() > 2 {
+        Ok(decode_path(&parts[2..].join("/"))?)
+    } else {
+        Ok(String::new())
+    }
+}
+
 async fn handle_webdav_methods(
-    req: Request<Body>,
+    State(state): State<AppState>,
+    mut req: Request<Body>,
 ) -> Result<Response<Body>, AppError> {
+    // The method handlers take the state from the request, next to the
+    // CurrentUser the DAV authentication layer inserted
+    req.extensions_mut().insert(Arc::new(state));
     let method = req.method().clone();
     
     match method.as_str() {
         "OPTIONS" => handle_options(req).await,
         "GET" => handle_get(req).await,
         "PUT" => handle_put(req).await,
+        "PATCH" => handle_patch(req).await,
         "MKCOL" => handle_mkcol(req).await,
         "DELETE" => handle_delete(req).await,
         "MOVE" => handle_move(req).await,
@@ -88,8 +133,8 @@ async fn handle_options(
 
     Ok(Response::builder()
         .status(StatusCode::OK)
-        .header(HEADER_DAV, "1, 2") // Class 1 and 2 WebDAV support
-        .header(header::ALLOW, "OPTIONS, GET, HEAD, PUT, DELETE, PROPFIND, PROPPATCH, MKCOL, COPY, MOVE, LOCK, UNLOCK")
+        .header(HEADER_DAV, DAV_COMPLIANCE_CLASSES.join(", "))
+        .header(header::ALLOW, "OPTIONS, GET, HEAD, PUT, PATCH, DELETE, PROPFIND, PROPPATCH, MKCOL, COPY, MOVE, LOCK, UNLOCK")
         .body(Body::empty())
         .unwrap())
 }
@@ -112,14 +157,7 @@ async fn handle_propfind(
 ) -> Result<Response<Body>, AppError> {
     // Clone all necessary data first to avoid borrow issues
     let uri = req.uri().clone();
-    let path = {
-        let parts = uri.path().split('/').collect::<Vec<&str>>();
-        if parts.len() > 2 {
-            parts[2..].join("/")
-        } else {
-            "".to_string()
-        }
-    };
+    let path = request_path(&uri)?;
     
     // Extract depth header (cloning to avoid borrowing issues)
     let depth = req.headers()
@@ -136,35 +174,29 @@ async fn handle_propfind(
         state_ref.clone()
     };
     
-    let _user = {
+    let user = {
         let user_ref = req.extensions().get::<CurrentUser>().ok_or_else(|| {
             AppError::unauthorized("Authentication required")
         })?;
         user_ref.clone()
     };
     
-    // Extract the body separately to avoid borrow issues
-    let body_bytes = {
-        // Convert the request into a body
-        let body = req.into_body();
-        
-        // Read request body
-        body::to_bytes(body, usize::MAX)
-            .await
-            .map_err(|e| {
-                AppError::bad_request(format!("Failed to read request body: {}", e))
-            })?
-    };
+    // Kept for If-None-Match once the listing is known
+    let request_headers = req.headers().clone();
+    let listing_hint = listing_hint(&request_headers)?;
+    
+    // Read request body
+    let body_bytes = read_body(req.into_body(), state.core.config.resources.memory_limits().xml_body).await?;
     
     // Parse PROPFIND request
     let propfind_request = if body_bytes.is_empty() {
         // Empty body means get all properties
         PropFindRequest {
-            prop_find_type: crate::application::adapters::webdav_adapter::PropFindType::AllProp,
+            prop_find_type: PropFindType::AllProp,
         }
     } else {
         // Parse XML body
-        WebDavAdapter::parse_propfind(body_bytes.reader()).map_err(|e| {
+        WebDavAdapter::parse_propfind(body_bytes.clone().reader()).map_err(|e| {
             AppError::bad_request(format!("Failed to parse PROPFIND request: {}", e))
         })?
     };
@@ -174,18 +206,22 @@ async fn handle_propfind(
     let file_service = &state.applications.file_service;
     
     // Determine base HREF
-    let base_href = format!("/webdav/{}/", path);
+    let base_href = match encode_path(path.trim_matches('/')) {
+        encoded if encoded.is_empty() => "/webdav/".to_string(),
+        encoded => format!("/webdav/{}/", encoded),
+    };
     
     // Check if path exists as a file or folder
     if path.is_empty() || path == "/" {
         // Root folder
-        let subfolders = folder_service.list_folders(None).await.map_err(|e| {
+        let mut subfolders = folder_service.list_folders(None).await.map_err(|e| {
             AppError::internal_error(format!("Failed to get subfolders: {}", e))
         })?;
         
-        let files = file_service.list_files(None).await.map_err(|e| {
+        let mut files = file_service.list_files(None).await.map_err(|e| {
             AppError::internal_error(format!("Failed to get files: {}", e))
         })?;
+        retain_visible_files(&state, &user, &mut files);
         
         // Create root folder DTO for response
         let root_folder = FolderDto {
@@ -198,8 +234,19 @@ async fn handle_propfind(
             is_root: true,
         };
         
+        let (ctag, total) = prepare_listing(&mut files, &mut subfolders, &depth, &propfind_request, listing_hint);
+        let attributes = load_attributes(&state, &files).await;
+        let dead_properties = load_dead_properties(&state, Some(&root_folder), &files, &subfolders).await;
+        let tags = load_tags(&state, &user, &propfind_request, Some(&root_folder), &files, &subfolders).await;
+        
+        // Polling clients get 304 while nothing in the listing changed
+        let etag = propfind_etag(&body_bytes, &depth, None, &files, &subfolders, &attributes, &dead_properties, &tags, ctag.as_deref());
+        if if_none_match(&request_headers, &etag) {
+            return Ok(not_modified(&etag));
+        }
+        
         // Generate response
-        let mut response_body = Vec::new();
+        let mut response_body = BoundedBuffer::new(state.core.config.resources.memory_limits().response_memory);
         WebDavAdapter::generate_propfind_response(
             &mut response_body,
             Some(&root_folder),
@@ -208,30 +255,35 @@ async fn handle_propfind(
             &propfind_request,
             &depth,
             &base_href,
+            &attributes,
+            &dead_properties,
+            &tags,
+            ctag.as_deref(),
         ).map_err(|e| {
             AppError::internal_error(format!("Failed to generate PROPFIND response: {}", e))
         })?;
         
-        Ok(Response::builder()
-            .status(StatusCode::MULTI_STATUS)
-            .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
-            .body(Body::from(response_body))
-            .unwrap())
+        let mut response = propfind_response(response_body, &etag)?;
+        set_listing_headers(&mut response, listing_hint, total);
+        Ok(response)
     } else {
         // Check if path is a folder
         let folder_result = folder_service.get_folder_by_path(&path).await;
         
         if let Ok(folder) = folder_result {
-            // Path is a folder
-            let files = if depth != "0" {
+            // Path is a folder; a Depth 0 request still needs the members
+            // to compute the collection tag
+            let list_members = depth != "0" || requests_ctag(&propfind_request);
+            let mut files = if list_members {
                 file_service.list_files(Some(&folder.id)).await.map_err(|e| {
                     AppError::internal_error(format!("Failed to get files: {}", e))
                 })?
             } else {
                 vec![]
             };
+            retain_visible_files(&state, &user, &mut files);
             
-            let subfolders = if depth != "0" {
+            let mut subfolders = if list_members {
                 folder_service.list_folders(Some(&folder.id)).await.map_err(|e| {
                     AppError::internal_error(format!("Failed to get subfolders: {}", e))
                 })?
@@ -239,8 +291,18 @@ async fn handle_propfind(
                 vec![]
             };
             
+            let (ctag, total) = prepare_listing(&mut files, &mut subfolders, &depth, &propfind_request, listing_hint);
+            let attributes = load_attributes(&state, &files).await;
+            let dead_properties = load_dead_properties(&state, Some(&folder), &files, &subfolders).await;
+            let tags = load_tags(&state, &user, &propfind_request, Some(&folder), &files, &subfolders).await;
+            
+            let etag = propfind_etag(&body_bytes, &depth, Some(&folder), &files, &subfolders, &attributes, &dead_properties, &tags, ctag.as_deref());
+            if if_none_match(&request_headers, &etag) {
+                return Ok(not_modified(&etag));
+            }
+            
             // Generate response
-            let mut response_body = Vec::new();
+            let mut response_body = BoundedBuffer::new(state.core.config.resources.memory_limits().response_memory);
             WebDavAdapter::generate_propfind_response(
                 &mut response_body,
                 Some(&folder),
@@ -249,37 +311,47 @@ async fn handle_propfind(
                 &propfind_request,
                 &depth,
                 &base_href,
+                &attributes,
+                &dead_properties,
+                &tags,
+                ctag.as_deref(),
             ).map_err(|e| {
                 AppError::internal_error(format!("Failed to generate PROPFIND response: {}", e))
             })?;
             
-            Ok(Response::builder()
-                .status(StatusCode::MULTI_STATUS)
-                .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
-                .body(Body::from(response_body))
-                .unwrap())
+            let mut response = propfind_response(response_body, &etag)?;
+            set_listing_headers(&mut response, listing_hint, total);
+            Ok(response)
         } else {
             // Check if path is a file
             let file_result = file_service.get_file_by_path(&path).await;
             
             if let Ok(file) = file_result {
                 // Path is a file
-                let mut response_body = Vec::new();
+                let mut attributes = load_attributes(&state, std::slice::from_ref(&file)).await;
+                let mut dead_properties = load_dead_properties(&state, None, std::slice::from_ref(&file), &[]).await;
+                let mut tags = load_tags(&state, &user, &propfind_request, None, std::slice::from_ref(&file), &[]).await;
+                
+                let etag = propfind_etag(&body_bytes, &depth, None, std::slice::from_ref(&file), &[], &attributes, &dead_properties, &tags, None);
+                if if_none_match(&request_headers, &etag) {
+                    return Ok(not_modified(&etag));
+                }
+                
+                let mut response_body = BoundedBuffer::new(state.core.config.resources.memory_limits().response_memory);
                 WebDavAdapter::generate_propfind_response_for_file(
                     &mut response_body,
                     &file,
                     &propfind_request,
                     &depth,
                     &base_href,
+                    attributes.remove(&file.id).as_ref(),
+                    &dead_properties.remove(&file.id).unwrap_or_default(),
+                    &tags.remove(&file.id).unwrap_or_default(),
                 ).map_err(|e| {
                     AppError::internal_error(format!("Failed to generate PROPFIND response: {}", e))
                 })?;
                 
-                Ok(Response::builder()
-                    .status(StatusCode::MULTI_STATUS)
-                    .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
-                    .body(Body::from(response_body))
-                    .unwrap())
+                propfind_response(response_body, &etag)
             } else {
                 // Path does not exist
                 Err(AppError::not_found(format!("Resource not found: {}", path)))
@@ -288,6 +360,425 @@ async fn handle_propfind(
     }
 }
 
+/**
+ * Parses the ordering hint of a PROPFIND listing.
+ * 
+ * `X-Order-By` takes `<key> [asc|desc]`; `X-Offset` and `X-Limit` page
+ * through the ordered members and imply name order when given alone.
+ * Fails with 400 on a malformed value instead of returning an unordered
+ * page the client would take for a stable one.
+ */
+fn listing_hint(headers: &HeaderMap) -> Result<Option<(ListingOrder, ListingWindow)>, AppError> {
+    fn header<'a>(headers: &'a HeaderMap, name: &HeaderName) -> Result<Option<&'a str>, AppError> {
+        headers.get(name)
+            .map(|v| v.to_str().map(str::trim).map_err(|_| AppError::bad_request(format!("Invalid {} header", name))))
+            .transpose()
+    }
+    fn count(headers: &HeaderMap, name: &HeaderName) -> Result<Option<usize>, AppError> {
+        header(headers, name)?
+            .map(|v| v.parse().map_err(|_| AppError::bad_request(format!("Invalid {} header: {}", name, v))))
+            .transpose()
+    }
+    
+    let order = header(headers, &HEADER_ORDER_BY)?
+        .map(|v| ListingOrder::parse(v).ok_or_else(|| AppError::bad_request(format!("Invalid {} header: {}", HEADER_ORDER_BY, v))))
+        .transpose()?;
+    let offset = count(headers, &HEADER_OFFSET)?;
+    let limit = count(headers, &HEADER_LIMIT)?;
+    
+    if order.is_none() && offset.is_none() && limit.is_none() {
+        return Ok(None);
+    }
+    let order = order.unwrap_or(ListingOrder { key: OrderKey::Name, descending: false });
+    Ok(Some((order, ListingWindow { offset: offset.unwrap_or(0), limit })))
+}
+
+/**
+ * Whether a PROPFIND asks for the CalendarServer `getctag` of a collection.
+ */
+fn requests_ctag(request: &PropFindRequest) -> bool {
+    match &request.prop_find_type {
+        PropFindType::Prop(props) => props.iter().any(|p| p.namespace == CALENDARSERVER_NS && p.name == "getctag"),
+        _ => false,
+    }
+}
+
+/**
+ * Prepares the members of a listed collection for the response.
+ * 
+ * The ctag is computed from every visible member before the listing hint
+ * cuts it down to a page, so all pages of a listing share it. Members a
+ * `Depth: 0` response does not show are dropped afterwards.
+ * 
+ * @return The ctag, when requested, and the total number of members, when
+ * the listing was ordered
+ */
+fn prepare_listing(
+    files: &mut Vec<FileDto>,
+    subfolders: &mut Vec<FolderDto>,
+    depth: &str,
+    request: &PropFindRequest,
+    hint: Option<(ListingOrder, ListingWindow)>,
+) -> (Option<String>, Option<usize>) {
+    let ctag = requests_ctag(request).then(|| collection_ctag(files, subfolders));
+    if depth == "0" {
+        files.clear();
+        subfolders.clear();
+        return (ctag, None);
+    }
+    let total = hint.map(|(order, window)| order_listing(files, subfolders, order, window));
+    (ctag, total)
+}
+
+/**
+ * Echoes the applied ordering and the size of the whole listing, so clients
+ * paging through it know when to stop.
+ */
+fn set_listing_headers(response: &mut Response<Body>, hint: Option<(ListingOrder, ListingWindow)>, total: Option<usize>) {
+    if let (Some((order, _)), Some(total)) = (hint, total) {
+        let headers = response.headers_mut();
+        if let Ok(value) = order.header_value().parse() {
+            headers.insert(HEADER_ORDER_BY, value);
+        }
+        headers.insert(HEADER_TOTAL_COUNT, total.into());
+    }
+}
+
+/**
+ * Returns the hidden file rules that apply to the user, if configured.
+ */
+fn hidden_file_rules(state: &AppState, user: &CurrentUser) -> Option<HiddenFileRules> {
+    state.hidden_file_rules.as_ref().map(|rules| rules.rules_for_user(&user.id))
+}
+
+/**
+ * Removes from a listing the files the user's rules hide.
+ * 
+ * Hidden files remain reachable by path, so clients that wrote them
+ * (e.g. `.DS_Store`) can still read them back.
+ */
+fn retain_visible_files(state: &AppState, user: &CurrentUser, files: &mut Vec<FileDto>) {
+    if let Some(rules) = hidden_file_rules(state, user) {
+        files.retain(|file| !rules.is_hidden(&file.name));
+    }
+}
+
+/**
+ * Fails with 403 when the user's rules reject files with this name.
+ */
+fn check_upload_name(state: &AppState, user: &CurrentUser, path: &str) -> Result<(), AppError> {
+    let name = path.trim_end_matches('/').rsplit('/').next().unwrap_or(path);
+    if let Some(rules) = hidden_file_rules(state, user) {
+        rules.check_upload(name)?;
+    }
+    Ok(())
+}
+
+/**
+ * Fails with 507 Insufficient Storage when writing `additional_bytes` more
+ * would take the user past their storage quota.
+ */
+async fn check_quota(state: &AppState, user: &CurrentUser, additional_bytes: u64) -> Result<(), AppError> {
+    if let Some(quotas) = &state.quota_service {
+        if additional_bytes > 0 {
+            quotas.check_quota(&user.id, additional_bytes).await?;
+        }
+    }
+    Ok(())
+}
+
+/**
+ * Adds the size change of a completed write to the user's storage usage.
+ * 
+ * A failure here only leaves the usage behind until the next recalculation,
+ * so it is logged instead of failing a write that already happened.
+ */
+async fn record_usage(state: &AppState, user: &CurrentUser, delta_bytes: i64) {
+    if let Some(quotas) = &state.quota_service {
+        if let Err(e) = quotas.record_usage(&user.id, delta_bytes).await {
+            tracing::warn!("Could not record storage usage for {}: {}", user.username, e);
+        }
+    }
+}
+
+/**
+ * Bytes a range write adds to a file: only what lands past its current end.
+ */
+fn range_growth(file: &FileDto, range: WriteRange, length: usize) -> u64 {
+    range.offset(file.size, length as u64)
+        .map(|offset| (offset + length as u64).saturating_sub(file.size))
+        .unwrap_or(0)
+}
+
+/**
+ * Returns the lock tokens the client submitted in the If header.
+ */
+fn submitted_lock_tokens(headers: &HeaderMap) -> Vec<String> {
+    headers.get("If")
+        .and_then(|v| v.to_str().ok())
+        .map(WebDavAdapter::parse_if_tokens)
+        .unwrap_or_default()
+}
+
+/**
+ * Fails with 423 Locked when a lock protects the resource and the request
+ * does not carry its token.
+ * 
+ * `changes_membership` is set when the resource is created, deleted or moved,
+ * which also involves the locks on its parent and on anything inside it.
+ */
+async fn check_locks(
+    state: &AppState,
+    user: &CurrentUser,
+    headers: &HeaderMap,
+    path: &str,
+    changes_membership: bool,
+) -> Result<(), AppError> {
+    if let Some(locks) = &state.lock_service {
+        if let Err(e) = locks.check_write(path, &user.id, &submitted_lock_tokens(headers), changes_membership).await {
+            if e.kind == ErrorKind::Locked {
+                record_conflict(state, user, ConflictKind::RejectedWrite, path, None, Some(e.message.clone())).await;
+            }
+            return Err(e.into());
+        }
+    }
+    Ok(())
+}
+
+/**
+ * Records a sync conflict for the conflict dashboard.
+ * 
+ * Failures are only logged: the dashboard is informative and must never
+ * change the outcome of the request that ran into the conflict.
+ */
+async fn record_conflict(
+    state: &AppState,
+    user: &CurrentUser,
+    kind: ConflictKind,
+    path: &str,
+    conflict_path: Option<&str>,
+    detail: Option<String>,
+) {
+    if let Some(conflicts) = &state.sync_conflict_service {
+        if let Err(e) = conflicts.record_conflict(&user.id, kind, path, conflict_path, detail).await {
+            tracing::warn!("Failed to record sync conflict on {}: {}", path, e);
+        }
+    }
+}
+
+/**
+ * Drops the locks of a resource that no longer exists at `path`.
+ */
+async fn release_locks(state: &AppState, path: &str) {
+    if let Some(locks) = &state.lock_service {
+        if let Err(e) = locks.release_locks(path).await {
+            tracing::warn!("Failed to release locks of {}: {}", path, e);
+        }
+    }
+}
+
+/**
+ * Loads the extended attributes stored for the listed files.
+ * 
+ * Returns an empty map when the feature is disabled or the store fails,
+ * since the attributes are not essential to answer a PROPFIND.
+ */
+async fn load_attributes(state: &AppState, files: &[FileDto]) -> HashMap<String, FileAttributes> {
+    let Some(service) = &state.file_attribute_service else {
+        return HashMap::new();
+    };
+    
+    let ids: Vec<String> = files.iter().map(|f| f.id.clone()).collect();
+    service.get_attributes(&ids).await.unwrap_or_else(|e| {
+        tracing::warn!("Could not load extended attributes: {}", e);
+        HashMap::new()
+    })
+}
+
+/**
+ * Loads the custom properties stored for a folder, its files and subfolders.
+ * 
+ * Like extended attributes, they are left out of the response when the
+ * store is unavailable rather than failing the PROPFIND.
+ */
+async fn load_dead_properties(
+    state: &AppState,
+    folder: Option<&FolderDto>,
+    files: &[FileDto],
+    subfolders: &[FolderDto],
+) -> HashMap<String, Vec<DeadProperty>> {
+    let Some(service) = &state.dead_property_service else {
+        return HashMap::new();
+    };
+    
+    let ids: Vec<String> = folder.into_iter().chain(subfolders).map(|f| f.id.clone())
+        .chain(files.iter().map(|f| f.id.clone()))
+        .collect();
+    service.get_properties(&ids).await.unwrap_or_else(|e| {
+        tracing::warn!("Could not load custom properties: {}", e);
+        HashMap::new()
+    })
+}
+
+/**
+ * Loads the names of the user's tags on the listed resources, keyed by
+ * resource ID. Nothing is loaded when tags are disabled or when the request
+ * names its properties without asking for `oc:tags`.
+ */
+async fn load_tags(
+    state: &AppState,
+    user: &CurrentUser,
+    request: &PropFindRequest,
+    folder: Option<&FolderDto>,
+    files: &[FileDto],
+    subfolders: &[FolderDto],
+) -> HashMap<String, Vec<String>> {
+    let Some(service) = &state.tag_service else {
+        return HashMap::new();
+    };
+    if let PropFindType::Prop(props) = &request.prop_find_type {
+        if !props.iter().any(|p| p.namespace == OWNCLOUD_NS && p.name == "tags") {
+            return HashMap::new();
+        }
+    }
+    
+    let ids: Vec<String> = folder.into_iter().chain(subfolders).map(|f| f.id.clone())
+        .chain(files.iter().map(|f| f.id.clone()))
+        .collect();
+    match service.get_tags_for_items(&user.id, &ids).await {
+        Ok(tags) => tags.into_iter()
+            .map(|(id, tags)| (id, tags.into_iter().map(|tag| tag.name).collect()))
+            .collect(),
+        Err(e) => {
+            tracing::warn!("Could not load tags: {}", e);
+            HashMap::new()
+        }
+    }
+}
+
+/**
+ * Returns the ID under which the custom properties of a resource are stored.
+ */
+async fn resource_id(state: &AppState, path: &str) -> Option<String> {
+    if path.is_empty() {
+        return Some("root".to_string());
+    }
+    if let Ok(folder) = state.applications.folder_service.get_folder_by_path(path).await {
+        return Some(folder.id);
+    }
+    state.applications.file_service.get_file_by_path(path).await.ok().map(|file| file.id)
+}
+
+/**
+ * Copies the custom properties of a resource onto its copy.
+ */
+async fn copy_dead_properties(state: &AppState, from_id: &str, to_id: &str) {
+    if let Some(service) = &state.dead_property_service {
+        if let Err(e) = service.copy_properties(from_id, to_id).await {
+            tracing::warn!("Could not copy custom properties of {}: {}", from_id, e);
+        }
+    }
+}
+
+/**
+ * Forgets the custom properties of a deleted resource.
+ */
+async fn remove_dead_properties(state: &AppState, id: &str) {
+    if let Some(service) = &state.dead_property_service {
+        if let Err(e) = service.remove_properties(id).await {
+            tracing::warn!("Could not remove custom properties of {}: {}", id, e);
+        }
+    }
+}
+
+/**
+ * Copies the extended attributes of a file onto its copy.
+ */
+async fn copy_attributes(state: &AppState, from_file_id: &str, to_file_id: &str) {
+    if let Some(service) = &state.file_attribute_service {
+        if let Err(e) = service.copy_attributes(from_file_id, to_file_id).await {
+            tracing::warn!("Could not copy extended attributes of file {}: {}", from_file_id, e);
+        }
+    }
+}
+
+/**
+ * Builds a 207 Multi-Status response; large listings were spilled to disk
+ * while being generated and are streamed back from there.
+ */
+fn multistatus_response(body: BoundedBuffer) -> Result<Response<Body>, AppError> {
+    let body = body.into_body().map_err(|e| {
+        AppError::internal_error(format!("Failed to read the generated response: {}", e))
+    })?;
+    
+    Ok(Response::builder()
+        .status(StatusCode::MULTI_STATUS)
+        .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+        .body(body)
+        .unwrap())
+}
+
+/**
+ * Wraps a PROPFIND multistatus body with its ETag.
+ */
+fn propfind_response(body: BoundedBuffer, etag: &str) -> Result<Response<Body>, AppError> {
+    let mut response = multistatus_response(body)?;
+    set_etag(&mut response, etag);
+    Ok(response)
+}
+
+/**
+ * Weak ETag of a PROPFIND response, computed from everything the listing is
+ * rendered from: the request body, the depth, the resources with their
+ * metadata, their stored properties and tags, and the collection tag.
+ * 
+ * It is known before rendering, so an unchanged listing costs a few lookups
+ * and an empty 304 instead of a full multistatus body.
+ */
+#[allow(clippy::too_many_arguments)]
+fn propfind_etag(
+    request_body: &[u8],
+    depth: &str,
+    folder: Option<&FolderDto>,
+    files: &[FileDto],
+    subfolders: &[FolderDto],
+    attributes: &HashMap<String, FileAttributes>,
+    dead_properties: &HashMap<String, Vec<DeadProperty>>,
+    tags: &HashMap<String, Vec<String>>,
+    ctag: Option<&str>,
+) -> String {
+    let mut etag = WeakEtag::new();
+    etag.add(request_body).add(depth).add(&ctag);
+    for folder in folder.into_iter().chain(subfolders) {
+        etag.add(&folder.id).add(&folder.name).add(&folder.modified_at);
+    }
+    for file in files {
+        etag.add(&file.id).add(&file.name).add(&file.size).add(&file.mime_type).add(&file.modified_at);
+    }
+    // Map order is random; sort so equal content gives equal tags
+    let mut attribute_ids: Vec<_> = attributes.keys().collect();
+    attribute_ids.sort();
+    for id in attribute_ids {
+        let mut values: Vec<_> = attributes[id].iter().collect();
+        values.sort();
+        etag.add(id).add(&values);
+    }
+    let mut property_ids: Vec<_> = dead_properties.keys().collect();
+    property_ids.sort();
+    for id in property_ids {
+        etag.add(id);
+        for property in &dead_properties[id] {
+            etag.add(&property.namespace).add(&property.name).add(&property.value);
+        }
+    }
+    let mut tag_ids: Vec<_> = tags.keys().collect();
+    tag_ids.sort();
+    for id in tag_ids {
+        etag.add(id).add(&tags[id]);
+    }
+    etag.finish()
+}
+
 /**
  * Handles PROPPATCH requests to set or remove resource properties.
  * 
@@ -305,50 +796,100 @@ async fn handle_proppatch(
 ) -> Result<Response<Body>, AppError> {
     // Extract State, Extension, and Path from request
     let uri = req.uri().clone();
-    let parts = uri.path().split('/').collect::<Vec<&str>>();
-    let path = if parts.len() > 2 {
-        parts[2..].join("/")
-    } else {
-        "".to_string()
-    };
+    let path = request_path(&uri)?;
     
-    let _state = req.extensions().get::<Arc<AppState>>().ok_or_else(|| {
+    let state = req.extensions().get::<Arc<AppState>>().ok_or_else(|| {
         AppError::internal_error("Missing AppState extension")
-    })?;
-    let _user = req.extensions().get::<CurrentUser>().ok_or_else(|| {
+    })?.clone();
+    let user = req.extensions().get::<CurrentUser>().ok_or_else(|| {
         AppError::unauthorized("Authentication required")
     })?;
     
+    check_locks(&state, user, req.headers(), &path, false).await?;
+    
     // Read request body
-    let body_bytes = body::to_bytes(req.into_body(), usize::MAX)
-        .await
-        .map_err(|e| {
-            AppError::bad_request(format!("Failed to read request body: {}", e))
-        })?;
+    let body_bytes = read_body(req.into_body(), state.core.config.resources.memory_limits().xml_body).await?;
     
     // Parse PROPPATCH request
     let (props_to_set, props_to_remove) = WebDavAdapter::parse_proppatch(body_bytes.reader()).map_err(|e| {
         AppError::bad_request(format!("Failed to parse PROPPATCH request: {}", e))
     })?;
     
-    // For now, we don't actually persist custom properties, but we respond as if we did
-    // In a full implementation, we would store these properties in a database
+    // Extended attributes of files are persisted when the feature is enabled
+    // and any other custom property goes to the dead property store. DAV:
+    // properties are accepted but not stored, as clients such as the Windows
+    // redirector fail when their timestamps are rejected
+    let attribute_target = match &state.file_attribute_service {
+        Some(service) if !path.is_empty() => state.applications.file_service
+            .get_file_by_path(&path)
+            .await
+            .ok()
+            .map(|file| (service.clone(), file.id)),
+        _ => None,
+    };
+    let dead_property_target = match &state.dead_property_service {
+        Some(service) => resource_id(&state, &path).await.map(|id| (service.clone(), id)),
+        None => None,
+    };
+    let is_dead_property = |name: &QualifiedName| {
+        name.namespace != "DAV:"
+            && !(attribute_target.is_some() && ExtendedAttribute::from_property(&name.name).is_some())
+    };
+    
+    // PROPPATCH is atomic: validate every value before storing any of them
+    let mut success = attribute_target.is_none() || !props_to_set.iter().any(|prop| {
+        ExtendedAttribute::from_property(&prop.name.name)
+            .is_some_and(|attr| attr.normalize(prop.value.as_deref().unwrap_or_default()).is_err())
+    });
+    
+    // The dead property store validates and writes its share in one transaction,
+    // so it runs before the attributes are touched
+    if let Some((service, resource_id)) = dead_property_target.as_ref().filter(|_| success) {
+        let set = props_to_set.iter()
+            .filter(|prop| is_dead_property(&prop.name))
+            .map(|prop| DeadProperty::new(prop.name.namespace.clone(), prop.name.name.clone(), prop.value.clone()))
+            .collect();
+        let remove = props_to_remove.iter()
+            .filter(|name| is_dead_property(name))
+            .map(|name| (name.namespace.clone(), name.name.clone()))
+            .collect();
+        
+        match service.patch_properties(resource_id, set, remove).await {
+            Ok(()) => {}
+            Err(e) if matches!(e.kind, ErrorKind::InvalidInput | ErrorKind::AccessDenied) => success = false,
+            Err(e) => return Err(AppError::internal_error(format!("Failed to store properties: {}", e))),
+        }
+    }
+    
+    if let Some((service, file_id)) = attribute_target.as_ref().filter(|_| success) {
+        for prop in &props_to_set {
+            if let Some(attr) = ExtendedAttribute::from_property(&prop.name.name) {
+                service.set_attribute(file_id, attr, prop.value.as_deref().unwrap_or_default())
+                    .await
+                    .map_err(|e| AppError::internal_error(format!("Failed to store property: {}", e)))?;
+            }
+        }
+        for prop in &props_to_remove {
+            if let Some(attr) = ExtendedAttribute::from_property(&prop.name) {
+                service.remove_attribute(file_id, attr)
+                    .await
+                    .map_err(|e| AppError::internal_error(format!("Failed to remove property: {}", e)))?;
+            }
+        }
+    }
     
-    // Generate response - we'll pretend all operations succeeded
     let mut results = Vec::new();
     
-    // For each property to set, indicate success
     for prop in &props_to_set {
-        results.push((&prop.name, true));
+        results.push((&prop.name, success));
     }
     
-    // For each property to remove, indicate success
     for prop in &props_to_remove {
-        results.push((prop, true));
+        results.push((prop, success));
     }
     
     // Generate response
-    let href = format!("/webdav/{}", path);
+    let href = format!("/webdav/{}", encode_path(&path));
     let mut response_body = Vec::new();
     WebDavAdapter::generate_proppatch_response(
         &mut response_body,
@@ -380,12 +921,7 @@ async fn handle_get(
 ) -> Result<Response<Body>, AppError> {
     // Extract State, Extension, and Path from request
     let uri = req.uri().clone();
-    let parts = uri.path().split('/').collect::<Vec<&str>>();
-    let path = if parts.len() > 2 {
-        parts[2..].join("/")
-    } else {
-        "".to_string()
-    };
+    let path = request_path(&uri)?;
     
     let state = req.extensions().get::<Arc<AppState>>().ok_or_else(|| {
         AppError::internal_error("Missing AppState extension")
@@ -396,7 +932,6 @@ async fn handle_get(
     
     // Get file service from state
     let file_service = &state.applications.file_service;
-    let file_retrieval_service = &state.applications.file_retrieval_service;
     
     // Check if path is empty (root folder)
     if path.is_empty() || path == "/" {
@@ -408,8 +943,17 @@ async fn handle_get(
         AppError::not_found(format!("File not found: {}", path))
     })?;
     
-    // Get file content
-    let content = file_retrieval_service.get_file_content(&file.id).await.map_err(|e| {
+    let etag = format!("\"{}\"", file.id);
+    let last_modified = chrono::DateTime::<Utc>::from_timestamp(file.modified_at as i64, 0)
+        .unwrap_or_else(Utc::now);
+    
+    // Byte ranges let clients seek in media files and resume downloads
+    if let Some(response) = file_range_response(file_service.as_ref(), req.headers(), &file, &etag, last_modified).await? {
+        return Ok(response);
+    }
+    
+    // Stream the whole file instead of loading it into memory
+    let content = file_service.get_file_stream(&file.id).await.map_err(|e| {
         AppError::internal_error(format!("Failed to get file content: {}", e))
     })?;
     
@@ -417,12 +961,11 @@ async fn handle_get(
     Ok(Response::builder()
         .status(StatusCode::OK)
         .header(header::CONTENT_TYPE, file.mime_type)
-        .header(header::CONTENT_LENGTH, content.len())
-        .header(header::ETAG, format!("\"{}\"", file.id))
-        .header(header::LAST_MODIFIED, chrono::DateTime::<Utc>::from_timestamp(file.created_at as i64, 0)
-            .unwrap_or_else(|| Utc::now())
-            .to_rfc2822())
-        .body(Body::from(content))
+        .header(header::CONTENT_LENGTH, file.size)
+        .header(header::ACCEPT_RANGES, ACCEPT_RANGES_BYTES)
+        .header(header::ETAG, etag)
+        .header(header::LAST_MODIFIED, last_modified.to_rfc2822())
+        .body(Body::from_stream(Box::into_pin(content)))
         .unwrap())
 }
 
@@ -442,14 +985,7 @@ async fn handle_put(
 ) -> Result<Response<Body>, AppError> {
     // Clone all necessary data first to avoid borrow issues
     let uri = req.uri().clone();
-    let path = {
-        let parts = uri.path().split('/').collect::<Vec<&str>>();
-        if parts.len() > 2 {
-            parts[2..].join("/")
-        } else {
-            "".to_string()
-        }
-    };
+    let path = request_path(&uri)?;
     
     // Get the state and user in a way that doesn't keep req borrowed
     let state = {
@@ -459,7 +995,7 @@ async fn handle_put(
         state_ref.clone()
     };
     
-    let _user = {
+    let user = {
         let user_ref = req.extensions().get::<CurrentUser>().ok_or_else(|| {
             AppError::unauthorized("Authentication required")
         })?;
@@ -474,6 +1010,19 @@ async fn handle_put(
         return Err(AppError::bad_request("Cannot PUT to root folder"));
     }
     
+    // Dotfiles and system files may be rejected by the instance or the user
+    check_upload_name(&state, &user, &path)?;
+    
+    // Check if file exists
+    let existing_file = file_service.get_file_by_path(&path).await.ok();
+    let file_exists = existing_file.is_some();
+    
+    // Locked resources need the lock token; creating a file changes its parent
+    check_locks(&state, &user, req.headers(), &path, !file_exists).await?;
+    
+    // Apache-style PUT with Content-Range updates only part of the file
+    let write_range = requested_write_range(req.headers())?;
+    
     // Extract content type before consuming the request
     let content_type = req.headers()
         .get(header::CONTENT_TYPE)
@@ -482,26 +1031,40 @@ async fn handle_put(
         .to_string();
     
     // Read request body
-    let body_bytes = {
-        // Convert the request into a body
-        let body = req.into_body();
+    let body_bytes = read_body(req.into_body(), state.core.config.resources.memory_limits().upload_body).await?;
+    
+    if let (Some(file), Some(range)) = (&existing_file, write_range) {
+        let growth = range_growth(file, range, body_bytes.len());
+        check_quota(&state, &user, growth).await?;
+        write_file_range(file_service.as_ref(), file, range, &body_bytes).await?;
+        record_usage(&state, &user, growth as i64).await;
         
-        // Read request body
-        body::to_bytes(body, usize::MAX)
-            .await
-            .map_err(|e| {
-                AppError::bad_request(format!("Failed to read request body: {}", e))
-            })?
-    };
+        return Ok(Response::builder()
+            .status(StatusCode::NO_CONTENT)
+            .body(Body::empty())
+            .unwrap());
+    }
     
-    // Check if file exists
-    let file_exists = file_service.get_file_by_path(&path).await.is_ok();
+    // A range on a new file only makes sense when it starts at the beginning
+    if write_range.is_some_and(|range| range.offset(0, body_bytes.len() as u64) != Some(0)) {
+        return Err(AppError::new(
+            StatusCode::RANGE_NOT_SATISFIABLE,
+            format!("Cannot write a range into {}, which does not exist yet", path),
+            "RangeNotSatisfiable",
+        ));
+    }
+    
+    // Only the bytes a write adds count against the quota
+    let previous_size = existing_file.as_ref().map_or(0, |file| file.size);
+    let delta = body_bytes.len() as i64 - previous_size as i64;
+    check_quota(&state, &user, delta.max(0) as u64).await?;
     
     if file_exists {
         // Update existing file
         file_service.update_file(&path, &body_bytes).await.map_err(|e| {
             AppError::internal_error(format!("Failed to update file: {}", e))
         })?;
+        record_usage(&state, &user, delta).await;
         
         Ok(Response::builder()
             .status(StatusCode::NO_CONTENT)
@@ -522,6 +1085,19 @@ async fn handle_put(
         file_service.create_file(parent_path, filename, &body_bytes, &content_type).await.map_err(|e| {
             AppError::internal_error(format!("Failed to create file: {}", e))
         })?;
+        record_usage(&state, &user, delta).await;
+        
+        // Desktop clients upload a "conflicted copy" next to a file edited on two devices
+        if let Some(original) = conflict_copy_original(filename) {
+            let original_path = if parent_path.is_empty() {
+                original
+            } else {
+                format!("{}/{}", parent_path, original)
+            };
+            if file_service.get_file_by_path(&original_path).await.is_ok() {
+                record_conflict(&state, &user, ConflictKind::ConflictedCopy, &original_path, Some(&path), None).await;
+            }
+        }
         
         Ok(Response::builder()
             .status(StatusCode::CREATED)
@@ -531,30 +1107,67 @@ async fn handle_put(
 }
 
 /**
- * Handles MKCOL requests to create folders.
+ * Handles PATCH requests that update a byte range of an existing file.
  * 
- * This handler creates a new folder at the specified path.
+ * The range comes from `X-Update-Range` (SabreDAV partial updates: a byte
+ * range, `bytes=-N` for the last N bytes or `append`) or from `Content-Range`.
+ * Only the affected bytes are rewritten, so clients can edit large files
+ * without uploading them again.
  * 
- * @param state The application state containing service dependencies
- * @param user The authenticated user information
- * @param path The requested resource path
- * @return HTTP response indicating success
+ * @param req The HTTP request containing the new bytes
+ * @return 204 on success, 416 when the range does not fit the file
  */
-async fn handle_mkcol(
+async fn handle_patch(
     req: Request<Body>,
 ) -> Result<Response<Body>, AppError> {
-    // Clone all necessary data first to avoid borrow issues
-    let uri = req.uri().clone();
-    let path = {
-        let parts = uri.path().split('/').collect::<Vec<&str>>();
-        if parts.len() > 2 {
-            parts[2..].join("/")
-        } else {
-            "".to_string()
-        }
-    };
+    let path = request_path(req.uri())?;
     
-    // Get the state and user in a way that doesn't keep req borrowed
+    let state = req.extensions().get::<Arc<AppState>>().cloned().ok_or_else(|| {
+        AppError::internal_error("Missing AppState extension")
+    })?;
+    let user = req.extensions().get::<CurrentUser>().cloned().ok_or_else(|| {
+        AppError::unauthorized("Authentication required")
+    })?;
+    
+    let file_service = &state.applications.file_service;
+    let file = file_service.get_file_by_path(&path).await
+        .map_err(|_| AppError::not_found(format!("File not found: {}", path)))?;
+    
+    check_locks(&state, &user, req.headers(), &path, false).await?;
+    
+    let range = requested_write_range(req.headers())?
+        .ok_or_else(|| AppError::bad_request("PATCH needs an X-Update-Range or Content-Range header"))?;
+    let body_bytes = read_body(req.into_body(), state.core.config.resources.memory_limits().upload_body).await?;
+    
+    let growth = range_growth(&file, range, body_bytes.len());
+    check_quota(&state, &user, growth).await?;
+    write_file_range(file_service.as_ref(), &file, range, &body_bytes).await?;
+    record_usage(&state, &user, growth as i64).await;
+    
+    Ok(Response::builder()
+        .status(StatusCode::NO_CONTENT)
+        .body(Body::empty())
+        .unwrap())
+}
+
+/**
+ * Handles MKCOL requests to create folders.
+ * 
+ * This handler creates a new folder at the specified path.
+ * 
+ * @param state The application state containing service dependencies
+ * @param user The authenticated user information
+ * @param path The requested resource path
+ * @return HTTP response indicating success
+ */
+async fn handle_mkcol(
+    req: Request<Body>,
+) -> Result<Response<Body>, AppError> {
+    // Clone all necessary data first to avoid borrow issues
+    let uri = req.uri().clone();
+    let path = request_path(&uri)?;
+    
+    // Get the state and user in a way that doesn't keep req borrowed
     let state = {
         let state_ref = req.extensions().get::<Arc<AppState>>().ok_or_else(|| {
             AppError::internal_error("Missing AppState extension")
@@ -562,7 +1175,7 @@ async fn handle_mkcol(
         state_ref.clone()
     };
     
-    let _user = {
+    let user = {
         let user_ref = req.extensions().get::<CurrentUser>().ok_or_else(|| {
             AppError::unauthorized("Authentication required")
         })?;
@@ -577,18 +1190,10 @@ async fn handle_mkcol(
         return Err(AppError::conflict("Root folder already exists"));
     }
     
+    check_locks(&state, &user, req.headers(), &path, true).await?;
+    
     // Read request body - must be empty for MKCOL
-    let body_bytes = {
-        // Convert the request into a body
-        let body = req.into_body();
-        
-        // Read request body
-        body::to_bytes(body, usize::MAX)
-            .await
-            .map_err(|e| {
-                AppError::bad_request(format!("Failed to read request body: {}", e))
-            })?
-    };
+    let body_bytes = read_body(req.into_body(), state.core.config.resources.memory_limits().xml_body).await?;
     
     if !body_bytes.is_empty() {
         return Err(AppError::unsupported_media_type("MKCOL request body must be empty"));
@@ -643,17 +1248,12 @@ async fn handle_delete(
 ) -> Result<Response<Body>, AppError> {
     // Extract State, Extension, and Path from request
     let uri = req.uri().clone();
-    let parts = uri.path().split('/').collect::<Vec<&str>>();
-    let path = if parts.len() > 2 {
-        parts[2..].join("/")
-    } else {
-        "".to_string()
-    };
+    let path = request_path(&uri)?;
     
     let state = req.extensions().get::<Arc<AppState>>().ok_or_else(|| {
         AppError::internal_error("Missing AppState extension")
     })?;
-    let _user = req.extensions().get::<CurrentUser>().ok_or_else(|| {
+    let user = req.extensions().get::<CurrentUser>().ok_or_else(|| {
         AppError::unauthorized("Authentication required")
     })?;
     
@@ -666,6 +1266,8 @@ async fn handle_delete(
         return Err(AppError::forbidden("Cannot delete root folder"));
     }
     
+    check_locks(state, user, req.headers(), &path, true).await?;
+    
     // Check if path is a folder
     let folder_result = folder_service.get_folder_by_path(&path).await;
     
@@ -674,6 +1276,7 @@ async fn handle_delete(
         folder_service.delete_folder(&folder.id).await.map_err(|e| {
             AppError::internal_error(format!("Failed to delete folder: {}", e))
         })?;
+        remove_dead_properties(state, &folder.id).await;
     } else {
         // Try to delete file
         let file = file_service.get_file_by_path(&path).await.map_err(|_e| {
@@ -683,8 +1286,11 @@ async fn handle_delete(
         file_service.delete_file(&file.id).await.map_err(|e| {
             AppError::internal_error(format!("Failed to delete file: {}", e))
         })?;
+        remove_dead_properties(state, &file.id).await;
     }
     
+    release_locks(state, &path).await;
+    
     Ok(Response::builder()
         .status(StatusCode::NO_CONTENT)
         .body(Body::empty())
@@ -694,7 +1300,9 @@ async fn handle_delete(
 /**
  * Handles MOVE requests to rename or relocate files or folders.
  * 
- * This handler moves a file or folder from one path to another.
+ * This handler moves a file or folder from one path to another. Folders
+ * larger than the background threshold are answered with 202 Accepted
+ * and keep moving in the background (see `run_transfer`).
  * 
  * @param state The application state containing service dependencies
  * @param user The authenticated user information
@@ -707,17 +1315,12 @@ async fn handle_move(
 ) -> Result<Response<Body>, AppError> {
     // Extract State, Extension, and Path from request
     let uri = req.uri().clone();
-    let parts = uri.path().split('/').collect::<Vec<&str>>();
-    let source_path = if parts.len() > 2 {
-        parts[2..].join("/")
-    } else {
-        "".to_string()
-    };
+    let source_path = request_path(&uri)?;
     
     let state = req.extensions().get::<Arc<AppState>>().ok_or_else(|| {
         AppError::internal_error("Missing AppState extension")
     })?;
-    let _user = req.extensions().get::<CurrentUser>().ok_or_else(|| {
+    let user = req.extensions().get::<CurrentUser>().ok_or_else(|| {
         AppError::unauthorized("Authentication required")
     })?;
     
@@ -730,79 +1333,112 @@ async fn handle_move(
     // Extract destination path from URL
     let destination_path = if let Some(webdav_prefix) = destination.find("/webdav/") {
         let after_prefix = &destination[webdav_prefix + 8..];
-        after_prefix.trim_end_matches('/')
+        decode_path(after_prefix.trim_end_matches('/'))?
     } else {
         return Err(AppError::bad_request("Invalid destination URL"));
     };
     
-    // Get services from state
+    // Both the source and the destination may be locked
+    check_locks(state, user, req.headers(), &source_path, true).await?;
+    check_locks(state, user, req.headers(), &destination_path, true).await?;
+    
+    let source = resolve_resource(state, &source_path).await?;
+    let plan = plan_transfer(state, user, TransferOperation::Move, &source, &source_path, &destination_path, true).await?;
+    let size = background_size(state, &source, plan.as_ref(), true).await;
+    
+    let work = {
+        let (state, user) = (state.clone(), user.clone());
+        let (source_path, destination_path) = (source_path.clone(), destination_path.clone());
+        async move { move_resource(&state, &user, &source, &source_path, &destination_path, plan).await }
+    };
+    run_transfer(state, user, TransferOperation::Move, &source_path, &destination_path, size, work).await
+}
+
+/**
+ * Moves a file or folder to `destination_path` and records the transfer.
+ */
+async fn move_resource(
+    state: &AppState,
+    user: &CurrentUser,
+    source: &DavResource,
+    source_path: &str,
+    destination_path: &str,
+    plan: Option<TransferPlan>,
+) -> Result<(), AppError> {
     let file_service = &state.applications.file_service;
     let folder_service = &state.applications.folder_service;
     
-    // Check if source is a folder
-    let folder_result = folder_service.get_folder_by_path(&source_path).await;
+    if plan.as_ref().is_some_and(|plan| plan.operation == TransferOperation::Copy) {
+        // The cross-user policy turns a move into another user's folder into a copy
+        copy_resource(state, user, source, destination_path, true).await?;
+        finish_transfer(state, plan).await;
+        return Ok(());
+    }
     
-    if let Ok(folder) = folder_result {
-        // Move folder
-        let dest_folder_name = destination_path.split('/').last().unwrap_or(&destination_path);
-        let dest_parent_path = if let Some(idx) = destination_path.rfind('/') {
-            &destination_path[..idx]
-        } else {
-            ""
-        };
-        
-        // Create DTOs for moving and renaming
-        let move_dto = crate::application::dtos::folder_dto::MoveFolderDto {
-            parent_id: if dest_parent_path.is_empty() {
-                None
+    match source {
+        DavResource::Folder(folder) => {
+            // Move folder
+            let dest_folder_name = destination_path.split('/').last().unwrap_or(destination_path);
+            let dest_parent_path = if let Some(idx) = destination_path.rfind('/') {
+                &destination_path[..idx]
             } else {
-                match folder_service.get_folder_by_path(dest_parent_path).await {
-                    Ok(parent) => Some(parent.id),
-                    Err(_) => None // If not found, use root
+                ""
+            };
+            
+            // Create DTOs for moving and renaming
+            let move_dto = crate::application::dtos::folder_dto::MoveFolderDto {
+                parent_id: if dest_parent_path.is_empty() {
+                    None
+                } else {
+                    match folder_service.get_folder_by_path(dest_parent_path).await {
+                        Ok(parent) => Some(parent.id),
+                        Err(_) => None // If not found, use root
+                    }
                 }
+            };
+            
+            folder_service.move_folder(&folder.id, move_dto).await.map_err(|e| {
+                AppError::internal_error(format!("Failed to move folder: {}", e))
+            })?;
+            
+            if folder.name != dest_folder_name {
+                let rename_dto = crate::application::dtos::folder_dto::RenameFolderDto {
+                    name: dest_folder_name.to_string()
+                };
+                
+                folder_service.rename_folder(&folder.id, rename_dto).await.map_err(|e| {
+                    AppError::internal_error(format!("Failed to rename folder: {}", e))
+                })?;
             }
-        };
-        
-        folder_service.move_folder(&folder.id, move_dto).await.map_err(|e| {
-            AppError::internal_error(format!("Failed to move folder: {}", e))
-        })?;
-        
-        if folder.name != dest_folder_name {
-            let rename_dto = crate::application::dtos::folder_dto::RenameFolderDto {
-                name: dest_folder_name.to_string()
+        }
+        DavResource::File(file) => {
+            // Move file
+            let dest_parent_path = if let Some(idx) = destination_path.rfind('/') {
+                &destination_path[..idx]
+            } else {
+                ""
             };
             
-            folder_service.rename_folder(&folder.id, rename_dto).await.map_err(|e| {
-                AppError::internal_error(format!("Failed to rename folder: {}", e))
+            file_service.move_file(&file.id, Some(dest_parent_path.to_string())).await.map_err(|e| {
+                AppError::internal_error(format!("Failed to move file: {}", e))
             })?;
         }
-    } else {
-        // Try to move file
-        let file = file_service.get_file_by_path(&source_path).await.map_err(|_e| {
-            AppError::not_found(format!("Resource not found: {}", source_path))
-        })?;
-        
-        let dest_parent_path = if let Some(idx) = destination_path.rfind('/') {
-            &destination_path[..idx]
-        } else {
-            ""
-        };
-        
-        file_service.move_file(&file.id, Some(dest_parent_path.to_string())).await.map_err(|e| {
-            AppError::internal_error(format!("Failed to move file: {}", e))
-        })?;
     }
     
-    Ok(Response::builder()
-        .status(StatusCode::NO_CONTENT)
-        .body(Body::empty())
-        .unwrap())
+    finish_transfer(state, plan).await;
+    
+    // Locks stay with the URL, so the moved resource is no longer locked
+    release_locks(state, source_path).await;
+    
+    Ok(())
 }
 
 /**
  * Handles COPY requests to duplicate files or folders.
  * 
- * This handler copies a file or folder from one path to another.
+ * This handler copies a file or folder from one path to another. Folders
+ * larger than the background threshold are answered with 202 Accepted
+ * and keep copying in the background (see `run_transfer`).
  * 
  * @param state The application state containing service dependencies
  * @param user The authenticated user information
@@ -815,17 +1451,12 @@ async fn handle_copy(
 ) -> Result<Response<Body>, AppError> {
     // Extract State, Extension, and Path from request
     let uri = req.uri().clone();
-    let parts = uri.path().split('/').collect::<Vec<&str>>();
-    let source_path = if parts.len() > 2 {
-        parts[2..].join("/")
-    } else {
-        "".to_string()
-    };
+    let source_path = request_path(&uri)?;
     
     let state = req.extensions().get::<Arc<AppState>>().ok_or_else(|| {
         AppError::internal_error("Missing AppState extension")
     })?;
-    let _user = req.extensions().get::<CurrentUser>().ok_or_else(|| {
+    let user = req.extensions().get::<CurrentUser>().ok_or_else(|| {
         AppError::unauthorized("Authentication required")
     })?;
     
@@ -838,109 +1469,278 @@ async fn handle_copy(
     // Extract destination path from URL
     let destination_path = if let Some(webdav_prefix) = destination.find("/webdav/") {
         let after_prefix = &destination[webdav_prefix + 8..];
-        after_prefix.trim_end_matches('/')
+        decode_path(after_prefix.trim_end_matches('/'))?
     } else {
         return Err(AppError::bad_request("Invalid destination URL"));
     };
     
+    check_locks(state, user, req.headers(), &destination_path, true).await?;
+    
     // Get depth from Depth header
     let depth = req.headers()
         .get("Depth")
         .and_then(|v| v.to_str().ok())
         .unwrap_or("infinity");
     
-    // Get services from state
+    let source = resolve_resource(state, &source_path).await?;
+    let recursive = depth != "0";
+    let plan = plan_transfer(state, user, TransferOperation::Copy, &source, &source_path, &destination_path, recursive).await?;
+    let size = background_size(state, &source, plan.as_ref(), recursive).await;
+    
+    let work = {
+        let (state, user) = (state.clone(), user.clone());
+        let destination_path = destination_path.clone();
+        async move {
+            copy_resource(&state, &user, &source, &destination_path, recursive).await?;
+            finish_transfer(&state, plan).await;
+            Ok(())
+        }
+    };
+    run_transfer(state, user, TransferOperation::Copy, &source_path, &destination_path, size, work).await
+}
+
+/**
+ * Size of a folder MOVE or COPY that is large enough to run in the
+ * background, or `None` if it should run in the foreground.
+ * 
+ * Files and copies without members always run in the foreground. The walk
+ * stops as soon as the tree is known to be over the threshold.
+ */
+async fn background_size(
+    state: &AppState,
+    source: &DavResource,
+    plan: Option<&TransferPlan>,
+    recursive: bool,
+) -> Option<u64> {
+    let threshold = state.dav_transfer_service.as_ref()?.async_threshold();
+    let DavResource::Folder(folder) = source else {
+        return None;
+    };
+    if !recursive {
+        return None;
+    }
+    
+    // The ownership rules have already measured the tree
+    if let Some(plan) = plan {
+        return (plan.size > threshold).then_some(plan.size);
+    }
+    
+    let mut size = 0;
+    let mut pending = vec![folder.id.clone()];
+    while let Some(current) = pending.pop() {
+        if let Ok(files) = state.applications.file_service.list_files(Some(&current)).await {
+            size += files.iter().map(|file| file.size).sum::<u64>();
+        }
+        if size > threshold {
+            return Some(size);
+        }
+        if let Ok(folders) = state.applications.folder_service.list_folders(Some(&current)).await {
+            pending.extend(folders.into_iter().map(|folder| folder.id));
+        }
+    }
+    None
+}
+
+/**
+ * Runs a MOVE or COPY. Large trees are answered with `202 Accepted` and a
+ * `Location` header pointing to the status of the transfer, which keeps
+ * running in the background; its DAV:multistatus is available there once
+ * it finishes.
+ */
+async fn run_transfer<F>(
+    state: &AppState,
+    user: &CurrentUser,
+    operation: TransferOperation,
+    source_path: &str,
+    destination_path: &str,
+    size: Option<u64>,
+    work: F,
+) -> Result<Response<Body>, AppError>
+where
+    F: std::future::Future<Output = Result<(), AppError>> + Send + 'static,
+{
+    let (Some(transfers), Some(size)) = (&state.dav_transfer_service, size) else {
+        work.await?;
+        return Ok(Response::builder()
+            .status(StatusCode::NO_CONTENT)
+            .body(Body::empty())
+            .unwrap());
+    };
+    
+    let transfer = transfers.start(&user.id, operation, source_path, destination_path, size).await;
+    let (transfers, transfer_id) = (transfers.clone(), transfer.id.clone());
+    tokio::spawn(async move {
+        match work.await {
+            Ok(()) => transfers.finish(&transfer_id, StatusCode::NO_CONTENT.as_u16(), None).await,
+            Err(e) => transfers.finish(&transfer_id, e.status_code.as_u16(), Some(e.message)).await,
+        }
+    });
+    
+    let body = serde_json::to_vec(&transfer)
+        .map_err(|e| AppError::internal_error(format!("Failed to serialize transfer: {}", e)))?;
+    Ok(Response::builder()
+        .status(StatusCode::ACCEPTED)
+        .header(header::LOCATION, dav_transfer_url(&transfer.id))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body))
+        .unwrap())
+}
+
+/**
+ * Source resource of a MOVE or COPY.
+ */
+enum DavResource {
+    Folder(FolderDto),
+    File(FileDto),
+}
+
+/**
+ * Resolves the folder or file at a WebDAV path.
+ */
+async fn resolve_resource(state: &AppState, path: &str) -> Result<DavResource, AppError> {
+    if let Ok(folder) = state.applications.folder_service.get_folder_by_path(path).await {
+        return Ok(DavResource::Folder(folder));
+    }
+    state.applications.file_service.get_file_by_path(path).await
+        .map(DavResource::File)
+        .map_err(|_e| AppError::not_found(format!("Resource not found: {}", path)))
+}
+
+/**
+ * Checks a MOVE or COPY against the ownership rules between users' home
+ * folders: a copy is charged to the destination owner, and a move into
+ * another user's folder transfers the item, becomes a copy or is refused
+ * depending on the instance policy.
+ * 
+ * Without an ownership service the transfer goes ahead unaccounted.
+ */
+async fn plan_transfer(
+    state: &AppState,
+    user: &CurrentUser,
+    operation: TransferOperation,
+    source: &DavResource,
+    source_path: &str,
+    destination_path: &str,
+    recursive: bool,
+) -> Result<Option<TransferPlan>, AppError> {
+    let Some(ownership) = &state.ownership_service else {
+        return Ok(None);
+    };
+    let item = match source {
+        DavResource::Folder(folder) => TransferItem::Folder { id: folder.id.clone(), recursive },
+        DavResource::File(file) => TransferItem::File { id: file.id.clone(), size: file.size },
+    };
+    let plan = ownership.plan_transfer(&user.id, operation, item, source_path, destination_path).await?;
+    Ok(Some(plan))
+}
+
+/**
+ * Records the usage and share changes of a completed MOVE or COPY.
+ */
+async fn finish_transfer(state: &AppState, plan: Option<TransferPlan>) {
+    if let (Some(ownership), Some(plan)) = (&state.ownership_service, plan) {
+        ownership.complete_transfer(&plan).await;
+    }
+}
+
+/**
+ * Copies a folder (with its members unless `recursive` is false) or a file
+ * to `destination_path`.
+ */
+async fn copy_resource(
+    state: &AppState,
+    user: &CurrentUser,
+    source: &DavResource,
+    destination_path: &str,
+    recursive: bool,
+) -> Result<(), AppError> {
     let file_service = &state.applications.file_service;
     let folder_service = &state.applications.folder_service;
     let file_retrieval_service = &state.applications.file_retrieval_service;
     
-    // Check if source is a folder
-    let folder_result = folder_service.get_folder_by_path(&source_path).await;
-    
-    if let Ok(folder) = folder_result {
-        // Copy folder
-        let recursive = depth != "0";
-        
-        let dest_folder_name = destination_path.split('/').last().unwrap_or(&destination_path);
-        let dest_parent_path = if let Some(idx) = destination_path.rfind('/') {
-            &destination_path[..idx]
-        } else {
-            ""
-        };
-        
-        // For now, just create a new folder and copy files individually
-        // In a real implementation, we would have a dedicated copy_folder service method
-        let create_dto = crate::application::dtos::folder_dto::CreateFolderDto {
-            name: dest_folder_name.to_string(),
-            parent_id: if dest_parent_path.is_empty() { 
-                None 
+    match source {
+        DavResource::Folder(folder) => {
+            let dest_folder_name = destination_path.split('/').last().unwrap_or(destination_path);
+            let dest_parent_path = if let Some(idx) = destination_path.rfind('/') {
+                &destination_path[..idx]
+            } else {
+                ""
+            };
+            let dest_parent_id = if dest_parent_path.is_empty() {
+                None
             } else {
                 // Try to get the parent folder ID from its path
                 match folder_service.get_folder_by_path(dest_parent_path).await {
                     Ok(parent) => Some(parent.id),
                     Err(_) => None // If not found, use root
                 }
+            };
+            
+            if recursive {
+                // Deep copy of the whole tree; a failed copy leaves nothing behind
+                let copy_dto = crate::application::dtos::folder_dto::CopyFolderDto {
+                    parent_id: dest_parent_id,
+                    name: Some(dest_folder_name.to_string()),
+                };
+                folder_service.copy_folder(&folder.id, copy_dto).await.map_err(|e| match e.kind {
+                    ErrorKind::AlreadyExists => AppError::new(
+                        StatusCode::PRECONDITION_FAILED,
+                        format!("Destination already exists: {}", destination_path),
+                        "PreconditionFailed",
+                    ),
+                    ErrorKind::InvalidInput => AppError::forbidden(e.to_string()),
+                    _ => AppError::internal_error(format!("Failed to copy folder: {}", e)),
+                })?;
+            } else {
+                // Depth 0 copies the collection without its members
+                let create_dto = crate::application::dtos::folder_dto::CreateFolderDto {
+                    name: dest_folder_name.to_string(),
+                    parent_id: dest_parent_id,
+                };
+                
+                let new_folder = folder_service.create_folder(create_dto).await.map_err(|e| {
+                    AppError::internal_error(format!("Failed to create destination folder: {}", e))
+                })?;
+                copy_dead_properties(state, &folder.id, &new_folder.id).await;
             }
-        };
-        
-        let _new_folder = folder_service.create_folder(create_dto).await.map_err(|e| {
-            AppError::internal_error(format!("Failed to create destination folder: {}", e))
-        })?;
-        
-        if recursive {
-            // Copy subfolders and files (simplified implementation)
-            let files = file_service.list_files(Some(&folder.id)).await.map_err(|e| {
-                AppError::internal_error(format!("Failed to list files: {}", e))
+        }
+        DavResource::File(file) => {
+            // Get file content
+            let content = file_retrieval_service.get_file_content(&file.id).await.map_err(|e| {
+                AppError::internal_error(format!("Failed to get file content: {}", e))
             })?;
             
-            for file in files {
-                // Get file content
-                if let Ok(file_source) = file_service.get_file_by_path(&format!("{}/{}", source_path, file.name)).await {
-                    if let Ok(content) = file_retrieval_service.get_file_content(&file_source.id).await {
-                        // Create new file in destination
-                        file_service.create_file(&destination_path, &file.name, &content, &file.mime_type).await.map_err(|e| {
-                            AppError::internal_error(format!("Failed to copy file {}: {}", file.name, e))
-                        })?;
-                    }
-                }
-            }
+            check_upload_name(state, user, destination_path)?;
+            
+            // Get destination parent path and filename
+            let dest_filename = destination_path.split('/').last().unwrap_or(destination_path);
+            let dest_parent_path = if let Some(idx) = destination_path.rfind('/') {
+                &destination_path[..idx]
+            } else {
+                ""
+            };
+            
+            // Create new file in destination
+            let copied = file_service.create_file(dest_parent_path, dest_filename, &content, &file.mime_type).await.map_err(|e| {
+                AppError::internal_error(format!("Failed to copy file: {}", e))
+            })?;
+            copy_attributes(state, &file.id, &copied.id).await;
+            copy_dead_properties(state, &file.id, &copied.id).await;
         }
-    } else {
-        // Try to copy file
-        let file = file_service.get_file_by_path(&source_path).await.map_err(|_e| {
-            AppError::not_found(format!("Resource not found: {}", source_path))
-        })?;
-        
-        // Get file content
-        let content = file_retrieval_service.get_file_content(&file.id).await.map_err(|e| {
-            AppError::internal_error(format!("Failed to get file content: {}", e))
-        })?;
-        
-        // Get destination parent path and filename
-        let dest_filename = destination_path.split('/').last().unwrap_or(&destination_path);
-        let dest_parent_path = if let Some(idx) = destination_path.rfind('/') {
-            &destination_path[..idx]
-        } else {
-            ""
-        };
-        
-        // Create new file in destination
-        file_service.create_file(dest_parent_path, dest_filename, &content, &file.mime_type).await.map_err(|e| {
-            AppError::internal_error(format!("Failed to copy file: {}", e))
-        })?;
     }
-    
-    Ok(Response::builder()
-        .status(StatusCode::NO_CONTENT)
-        .body(Body::empty())
-        .unwrap())
+    Ok(())
 }
 
 /**
  * Handles LOCK requests to lock resources.
  * 
  * This handler processes WebDAV LOCK requests according to RFC 4918,
- * creating a lock on a file or folder.
+ * creating a lock on a file or folder, or refreshing an existing one when
+ * the body is empty and the If header carries its token. Locking an unmapped
+ * URL creates an empty file, as clients lock before their first PUT.
+ * 
+ * Locks are stored by the lock service when a database is available;
+ * otherwise tokens are issued without being enforced.
  * 
  * @param state The application state containing service dependencies
  * @param user The authenticated user information
@@ -953,17 +1753,10 @@ async fn handle_lock(
 ) -> Result<Response<Body>, AppError> {
     // Clone all necessary data first to avoid borrow issues
     let uri = req.uri().clone();
-    let path = {
-        let parts = uri.path().split('/').collect::<Vec<&str>>();
-        if parts.len() > 2 {
-            parts[2..].join("/")
-        } else {
-            "".to_string()
-        }
-    };
+    let path = request_path(&uri)?;
     
     // Get the state and user in a way that doesn't keep req borrowed
-    let _state = {
+    let state = {
         let state_ref = req.extensions().get::<Arc<AppState>>().ok_or_else(|| {
             AppError::internal_error("Missing AppState extension")
         })?;
@@ -984,106 +1777,159 @@ async fn handle_lock(
         .unwrap_or("infinity")
         .to_string();
     
-    let timeout = req.headers()
+    // Depth 1 is not allowed for LOCK
+    let infinite_depth = match depth.to_lowercase().as_str() {
+        "0" => false,
+        "infinity" => true,
+        _ => return Err(AppError::bad_request("Depth must be 0 or infinity for LOCK")),
+    };
+    
+    let timeout_secs = req.headers()
         .get("Timeout")
         .and_then(|v| v.to_str().ok())
-        .map(|s| s.to_string());
+        .and_then(WebDavAdapter::parse_timeout);
     
-    let if_header_value = req.headers()
-        .get("If")
-        .and_then(|v| v.to_str().ok())
-        .map(|s| s.to_string());
+    let tokens = submitted_lock_tokens(req.headers());
     
-    // Extract the body separately to avoid borrow issues
-    let body_bytes = {
-        // Convert the request into a body
-        let body = req.into_body();
-        
-        // Read request body
-        body::to_bytes(body, usize::MAX)
-            .await
-            .map_err(|e| {
-                AppError::bad_request(format!("Failed to read request body: {}", e))
-            })?
-    };
+    // Read request body
+    let body_bytes = read_body(req.into_body(), state.core.config.resources.memory_limits().xml_body).await?;
     
-    // Check if this is a lock refresh (If header with a lock token)
-    if let Some(if_header) = if_header_value {
-        // This is a lock refresh request
-        // Extract lock token from If header
-        let token = if_header
-            .trim()
-            .trim_start_matches("(<")
-            .trim_end_matches(">)")
-            .to_string();
-        
-        // In a full implementation, we would look up the lock in a database
-        // and refresh its timeout. For now, just respond as if we did.
+    let (lock_info, status) = if body_bytes.is_empty() {
+        // An empty body refreshes the lock identified in the If header
+        if tokens.is_empty() {
+            return Err(AppError::bad_request("Invalid LOCK request"));
+        }
         
-        // Generate lock token and owner (for a real implementation, we'd store these)
-        let lock_info = LockInfo {
-            token,
-            owner: Some(user.id.clone()),
-            depth: depth.to_string(),
-            timeout,
-            scope: LockScope::Exclusive, // Default to exclusive
-            type_: LockType::Write,      // Default to write
+        let lock_info = match &state.lock_service {
+            Some(locks) => {
+                let lock = locks.refresh_lock(&path, &user.id, &tokens, timeout_secs).await.map_err(|e| {
+                    match e.kind {
+                        ErrorKind::NotFound => AppError::new(
+                            StatusCode::PRECONDITION_FAILED,
+                            "No lock matches the submitted token",
+                            "PreconditionFailed",
+                        ),
+                        _ => AppError::from(e),
+                    }
+                })?;
+                lock_info_from(&lock)
+            },
+            None => LockInfo {
+                token: tokens[0].clone(),
+                owner: Some(user.id.clone()),
+                depth,
+                timeout: timeout_secs.map(|secs| format!("Second-{}", secs)),
+                scope: LockScope::Exclusive,
+                type_: LockType::Write,
+            },
         };
-        
-        // Generate response
-        let href = format!("/webdav/{}", path);
-        let mut response_body = Vec::new();
-        WebDavAdapter::generate_lock_response(
-            &mut response_body,
-            &lock_info,
-            &href,
-        ).map_err(|e| {
-            AppError::internal_error(format!("Failed to generate LOCK response: {}", e))
-        })?;
-        
-        Ok(Response::builder()
-            .status(StatusCode::OK)
-            .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
-            .header(HEADER_LOCK_TOKEN, format!("<{}>", lock_info.token))
-            .body(Body::from(response_body))
-            .unwrap())
-    } else if !body_bytes.is_empty() {
+        (lock_info, StatusCode::OK)
+    } else {
         // Parse lock request
         let (scope, type_, owner) = WebDavAdapter::parse_lockinfo(body_bytes.reader()).map_err(|e| {
             AppError::bad_request(format!("Failed to parse LOCK request: {}", e))
         })?;
         
-        // Generate lock token and owner (for a real implementation, we'd store these)
-        let token = format!("opaquelocktoken:{}", Uuid::new_v4());
-        let lock_info = LockInfo {
-            token,
-            owner: owner.or(Some(user.id.clone())),
-            depth: depth.to_string(),
-            timeout,
-            scope,
-            type_,
+        let lock_info = match &state.lock_service {
+            Some(locks) => {
+                let lock = match locks.lock(LockRequest {
+                    path: path.clone(),
+                    owner_id: user.id.clone(),
+                    owner_info: owner,
+                    exclusive: scope == LockScope::Exclusive,
+                    infinite_depth,
+                    timeout_secs,
+                }).await {
+                    Ok(lock) => lock,
+                    Err(e) => {
+                        if e.kind == ErrorKind::Locked {
+                            record_conflict(&state, &user, ConflictKind::LockContention, &path, None, Some(e.message.clone())).await;
+                        }
+                        return Err(e.into());
+                    },
+                };
+                lock_info_from(&lock)
+            },
+            None => LockInfo {
+                token: format!("opaquelocktoken:{}", Uuid::new_v4()),
+                owner: owner.or(Some(user.id.clone())),
+                depth,
+                timeout: timeout_secs.map(|secs| format!("Second-{}", secs)),
+                scope,
+                type_,
+            },
         };
         
-        // Generate response
-        let href = format!("/webdav/{}", path);
-        let mut response_body = Vec::new();
-        WebDavAdapter::generate_lock_response(
-            &mut response_body,
-            &lock_info,
-            &href,
-        ).map_err(|e| {
-            AppError::internal_error(format!("Failed to generate LOCK response: {}", e))
-        })?;
-        
-        Ok(Response::builder()
-            .status(StatusCode::OK)
-            .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
-            .header(HEADER_LOCK_TOKEN, format!("<{}>", lock_info.token))
-            .body(Body::from(response_body))
-            .unwrap())
-    } else {
-        Err(AppError::bad_request("Invalid LOCK request"))
+        // Locking an unmapped URL creates an empty resource
+        match create_locked_empty_file(&state, &user, &path).await {
+            Ok(true) => (lock_info, StatusCode::CREATED),
+            Ok(false) => (lock_info, StatusCode::OK),
+            Err(e) => {
+                if let Some(locks) = &state.lock_service {
+                    let _ = locks.unlock(&path, &user.id, &lock_info.token).await;
+                }
+                return Err(e);
+            }
+        }
+    };
+    
+    // Generate response
+    let href = format!("/webdav/{}", encode_path(&path));
+    let mut response_body = Vec::new();
+    WebDavAdapter::generate_lock_response(
+        &mut response_body,
+        &lock_info,
+        &href,
+    ).map_err(|e| {
+        AppError::internal_error(format!("Failed to generate LOCK response: {}", e))
+    })?;
+    
+    Ok(Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+        .header(HEADER_LOCK_TOKEN, format!("<{}>", lock_info.token))
+        .body(Body::from(response_body))
+        .unwrap())
+}
+
+/**
+ * Builds the lockdiscovery information of a stored lock.
+ */
+fn lock_info_from(lock: &ResourceLock) -> LockInfo {
+    LockInfo {
+        token: lock.token.clone(),
+        owner: lock.owner_info.clone().or(Some(lock.owner_id.clone())),
+        depth: if lock.infinite_depth { "infinity" } else { "0" }.to_string(),
+        timeout: Some(lock.timeout_header()),
+        scope: if lock.exclusive { LockScope::Exclusive } else { LockScope::Shared },
+        type_: LockType::Write,
+    }
+}
+
+/**
+ * Creates an empty file at `path` if nothing exists there yet.
+ * 
+ * @return Whether the file was created
+ */
+async fn create_locked_empty_file(state: &AppState, user: &CurrentUser, path: &str) -> Result<bool, AppError> {
+    if path.is_empty()
+        || state.applications.folder_service.get_folder_by_path(path).await.is_ok()
+        || state.applications.file_service.get_file_by_path(path).await.is_ok()
+    {
+        return Ok(false);
     }
+    
+    check_upload_name(state, user, path)?;
+    
+    let filename = path.split('/').last().unwrap_or("unnamed");
+    let parent_path = path.rfind('/').map(|idx| &path[..idx]).unwrap_or("");
+    let content_type = mime_guess::from_path(filename).first_or_octet_stream().to_string();
+    
+    state.applications.file_service.create_file(parent_path, filename, &[], &content_type).await.map_err(|e| {
+        AppError::internal_error(format!("Failed to create file: {}", e))
+    })?;
+    
+    Ok(true)
 }
 
 /**
@@ -1103,24 +1949,17 @@ async fn handle_unlock(
 ) -> Result<Response<Body>, AppError> {
     // Clone all necessary data first to avoid borrow issues
     let uri = req.uri().clone();
-    let _path = {
-        let parts = uri.path().split('/').collect::<Vec<&str>>();
-        if parts.len() > 2 {
-            parts[2..].join("/")
-        } else {
-            "".to_string()
-        }
-    };
+    let path = request_path(&uri)?;
     
     // Get the state and user in a way that doesn't keep req borrowed
-    let _state = {
+    let state = {
         let state_ref = req.extensions().get::<Arc<AppState>>().ok_or_else(|| {
             AppError::internal_error("Missing AppState extension")
         })?;
         state_ref.clone()
     };
     
-    let _user = {
+    let user = {
         let user_ref = req.extensions().get::<CurrentUser>().ok_or_else(|| {
             AppError::unauthorized("Authentication required")
         })?;
@@ -1134,17 +1973,26 @@ async fn handle_unlock(
         .ok_or_else(|| AppError::bad_request("Lock-Token header required"))?;
     
     // Extract token from header value (format: <token>)
-    let _token = lock_token
+    let token = lock_token
         .trim()
         .trim_start_matches('<')
         .trim_end_matches('>')
         .to_string();
     
-    // In a full implementation, we would look up the lock in a database
-    // and remove it. For now, just respond as if we did.
+    if let Some(locks) = &state.lock_service {
+        locks.unlock(&path, &user.id, &token).await.map_err(|e| {
+            match e.kind {
+                // RFC 4918: the token must identify a lock on the request URI
+                ErrorKind::NotFound | ErrorKind::InvalidInput => {
+                    AppError::conflict(format!("Lock token does not match {}", uri.path()))
+                },
+                _ => AppError::from(e),
+            }
+        })?;
+    }
     
     Ok(Response::builder()
         .status(StatusCode::NO_CONTENT)
         .body(Body::empty())
         .unwrap())
-}
\ No newline at end of file
+}
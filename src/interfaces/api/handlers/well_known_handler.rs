@@ -0,0 +1,38 @@
+use axum::{
+    Router,
+    routing::any,
+    body::Body,
+    http::{header, StatusCode},
+    response::Response,
+};
+
+use crate::common::di::AppState;
+use crate::interfaces::api::handlers::{caldav_handler, carddav_protocol_handler};
+
+/// Root of the WebDAV file tree
+const WEBDAV_HREF: &str = "/api/webdav/";
+
+/**
+ * Creates the `/.well-known` service discovery routes (RFC 6764).
+ *
+ * CalDAV and CardDAV clients that are only given the server name look for
+ * `/.well-known/caldav` and `/.well-known/carddav` and follow the redirect
+ * to the context path, where PROPFIND tells them the principal and homes of
+ * the user. `/.well-known/webdav` points file clients at the WebDAV root.
+ * Any method is redirected, since clients start with PROPFIND as often as
+ * with GET.
+ */
+pub fn well_known_routes() -> Router<AppState> {
+    Router::new()
+        .route("/.well-known/caldav", any(|| async { redirect(caldav_handler::CONTEXT_HREF) }))
+        .route("/.well-known/carddav", any(|| async { redirect(carddav_protocol_handler::CONTEXT_HREF) }))
+        .route("/.well-known/webdav", any(|| async { redirect(WEBDAV_HREF) }))
+}
+
+fn redirect(location: &str) -> Response<Body> {
+    Response::builder()
+        .status(StatusCode::MOVED_PERMANENTLY)
+        .header(header::LOCATION, location)
+        .body(Body::empty())
+        .unwrap()
+}
@@ -0,0 +1,95 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::get,
+    extract::{Query, State, Extension, ws::{Message, WebSocket, WebSocketUpgrade}},
+    response::Response,
+};
+use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::application::ports::realtime_ports::{RealtimeAudience, RealtimeEvent, RealtimeEventPort, RealtimeMessage};
+use crate::application::services::auth_application_service::AuthApplicationService;
+use crate::common::errors::AppError;
+use crate::interfaces::middleware::auth::CurrentUser;
+
+/// Services behind the WebSocket endpoint
+#[derive(Clone)]
+pub struct WsState {
+    pub events: Arc<dyn RealtimeEventPort>,
+
+    /// Validates the `token` query parameter; browsers cannot send an
+    /// `Authorization` header when opening a WebSocket
+    pub auth: Option<Arc<AuthApplicationService>>,
+}
+
+/// Query parameters of the WebSocket handshake
+#[derive(Debug, Deserialize)]
+struct ConnectQuery {
+    token: Option<String>,
+}
+
+/// Route of the real-time channel.
+///
+/// After the handshake the server sends one JSON text message per change of
+/// the current user's files, shares and calendars, shaped as
+/// `{"type": "file_created", ..., "at": "<RFC 3339>"}`. A `resync` message
+/// means some changes were dropped because the client fell behind. Anything
+/// the client sends is ignored.
+pub fn ws_routes() -> Router<WsState> {
+    Router::new()
+        .route("/", get(connect))
+}
+
+/// Upgrades the request of an authenticated user to a WebSocket
+async fn connect(
+    State(state): State<WsState>,
+    current_user: Option<Extension<CurrentUser>>,
+    Query(query): Query<ConnectQuery>,
+    upgrade: WebSocketUpgrade,
+) -> Result<Response, AppError> {
+    let user = match (current_user, &state.auth, query.token) {
+        (Some(Extension(user)), _, _) => user,
+        (None, Some(auth), Some(token)) => auth.authenticate_token(&token).await
+            .map(CurrentUser::from)
+            .map_err(|_| AppError::unauthorized("Invalid or expired token"))?,
+        _ => return Err(AppError::unauthorized("Authentication required")),
+    };
+    let events = state.events.clone();
+    Ok(upgrade.on_upgrade(move |socket| forward_events(socket, events, user)))
+}
+
+/// Sends the events of the user until either side closes the connection
+async fn forward_events(socket: WebSocket, events: Arc<dyn RealtimeEventPort>, user: CurrentUser) {
+    let (mut sender, mut incoming) = socket.split();
+    let mut receiver = events.subscribe();
+    loop {
+        tokio::select! {
+            received = receiver.recv() => {
+                let message = match received {
+                    Ok(message) if message.audience.includes(&user.id, &user.username) => message,
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(missed)) => RealtimeMessage {
+                        audience: RealtimeAudience::User(user.id.clone()),
+                        event: RealtimeEvent::Resync { missed },
+                        at: chrono::Utc::now(),
+                    },
+                    Err(RecvError::Closed) => break,
+                };
+                let Ok(text) = serde_json::to_string(&message) else {
+                    continue;
+                };
+                if sender.send(Message::Text(text.into())).await.is_err() {
+                    break;
+                }
+            }
+            received = incoming.next() => match received {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                // Pings are answered by the WebSocket layer
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    tracing::debug!("WebSocket of {} closed", user.username);
+}
@@ -0,0 +1,90 @@
+use std::sync::Arc;
+use axum::{
+    Router,
+    routing::{get, post},
+    body::Body,
+    extract::{Path, Query, State, Json, Extension},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+
+use crate::application::dtos::zip_export_dto::{CreateZipExportDto, ZipExportDownloadQuery};
+use crate::application::ports::zip_export_ports::ZipExportUseCase;
+use crate::common::errors::AppError;
+use crate::domain::services::path_codec_service::content_disposition;
+use crate::interfaces::middleware::auth::CurrentUser;
+
+type ZipExportState = Arc<dyn ZipExportUseCase>;
+
+/// Routes for background ZIP exports of large selections
+pub fn zip_export_routes() -> Router<ZipExportState> {
+    Router::new()
+        .route("/", get(list_jobs).post(create_job))
+        .route("/{id}", get(get_job).delete(cancel_job))
+        .route("/{id}/resume", post(resume_job))
+        .route("/{id}/download", get(download_archive))
+}
+
+/// Queues an export; the archive is built in the background
+async fn create_job(
+    State(service): State<ZipExportState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(dto): Json<CreateZipExportDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let job = service.create_job(&current_user.id, dto).await?;
+    Ok((StatusCode::CREATED, Json(job)))
+}
+
+/// Lists the exports of the current user
+async fn list_jobs(
+    State(service): State<ZipExportState>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(service.list_jobs(&current_user.id).await?))
+}
+
+/// Returns the progress of an export, and its download link once completed
+async fn get_job(
+    State(service): State<ZipExportState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(service.get_job(&current_user.id, &id).await?))
+}
+
+/// Continues a failed export from its last checkpoint
+async fn resume_job(
+    State(service): State<ZipExportState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(service.resume_job(&current_user.id, &id).await?))
+}
+
+/// Stops an export and deletes its archive
+async fn cancel_job(
+    State(service): State<ZipExportState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    service.cancel_job(&current_user.id, &id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Streams a completed archive; the signed token is the only credential,
+/// so the link works in download managers and can be handed over
+async fn download_archive(
+    State(service): State<ZipExportState>,
+    Path(id): Path<String>,
+    Query(query): Query<ZipExportDownloadQuery>,
+) -> Result<Response, AppError> {
+    let download = service.open_download(&id, &query.token).await?;
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/zip")
+        .header(header::CONTENT_LENGTH, download.size)
+        .header(header::CONTENT_DISPOSITION, content_disposition("attachment", &download.file_name))
+        .body(Body::from_stream(download.content))
+        .map_err(|e| AppError::internal_error(format!("Failed to build response: {}", e)))
+}
@@ -1,4 +1,8 @@
+pub mod compat;
+pub mod conditional;
+pub mod content_digest;
 pub mod handlers;
+pub mod range_response;
 pub mod routes;
 
 pub use routes::create_api_routes;
\ No newline at end of file
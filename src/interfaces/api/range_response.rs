@@ -0,0 +1,151 @@
+use std::pin::Pin;
+
+use axum::{
+    body::Body,
+    http::{header, HeaderMap, Response, StatusCode},
+};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::{stream, Stream, StreamExt};
+use uuid::Uuid;
+
+use crate::application::dtos::file_dto::FileDto;
+use crate::application::ports::inbound::FileUseCase;
+use crate::common::errors::AppError;
+use crate::domain::services::byte_range_service::{
+    parse_content_range, parse_update_range, select_ranges, ByteRange, RangeSelection, WriteRange,
+};
+
+type PartStream = Pin<Box<dyn Stream<Item = Result<Bytes, std::io::Error>> + Send>>;
+
+/// Value of `Accept-Ranges` on every file download
+pub const ACCEPT_RANGES_BYTES: &str = "bytes";
+
+/// Header SabreDAV-style clients use to place a partial update
+pub const X_UPDATE_RANGE: &str = "x-update-range";
+
+/// Builds a 206 or 416 response when the request asks for byte ranges.
+///
+/// Returns `None` when the whole file has to be sent: no `Range` header,
+/// one the server ignores, or an `If-Range` validator that no longer matches.
+pub async fn file_range_response(
+    files: &dyn FileUseCase,
+    request_headers: &HeaderMap,
+    file: &FileDto,
+    etag: &str,
+    last_modified: DateTime<Utc>,
+) -> Result<Option<Response<Body>>, AppError> {
+    let header_value = |name: header::HeaderName| request_headers.get(name).and_then(|v| v.to_str().ok());
+    let selection = select_ranges(
+        header_value(header::RANGE),
+        header_value(header::IF_RANGE),
+        file.size,
+        etag,
+        last_modified,
+    );
+
+    let ranges = match selection {
+        RangeSelection::Full => return Ok(None),
+        RangeSelection::Unsatisfiable => {
+            return Ok(Some(Response::builder()
+                .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                .header(header::CONTENT_RANGE, format!("bytes */{}", file.size))
+                .header(header::ACCEPT_RANGES, ACCEPT_RANGES_BYTES)
+                .body(Body::empty())
+                .unwrap()));
+        },
+        RangeSelection::Partial(ranges) => ranges,
+    };
+
+    let builder = Response::builder()
+        .status(StatusCode::PARTIAL_CONTENT)
+        .header(header::ACCEPT_RANGES, ACCEPT_RANGES_BYTES)
+        .header(header::ETAG, etag)
+        .header(header::LAST_MODIFIED, last_modified.to_rfc2822());
+
+    if let [range] = ranges.as_slice() {
+        let content = files.get_file_range(&file.id, range.start, range.length()).await?;
+        return Ok(Some(builder
+            .header(header::CONTENT_TYPE, &file.mime_type)
+            .header(header::CONTENT_RANGE, range.content_range(file.size))
+            .header(header::CONTENT_LENGTH, range.length())
+            .body(Body::from_stream(Box::into_pin(content)))
+            .unwrap()));
+    }
+
+    let boundary = Uuid::new_v4().simple().to_string();
+    let (parts, length) = multipart_byteranges(files, file, &ranges, &boundary).await?;
+    Ok(Some(builder
+        .header(header::CONTENT_TYPE, format!("multipart/byteranges; boundary={}", boundary))
+        .header(header::CONTENT_LENGTH, length)
+        .body(Body::from_stream(stream::iter(parts).flatten()))
+        .unwrap()))
+}
+
+/// Reads where a partial write goes from `Content-Range` or `X-Update-Range`.
+///
+/// Returns `None` when the request carries neither, so the body replaces
+/// the whole file; a header that cannot be parsed is a 400.
+pub fn requested_write_range(request_headers: &HeaderMap) -> Result<Option<WriteRange>, AppError> {
+    let header_value = |name: &str| request_headers.get(name).and_then(|v| v.to_str().ok());
+    if let Some(value) = header_value(header::CONTENT_RANGE.as_str()) {
+        return parse_content_range(value)
+            .map(Some)
+            .ok_or_else(|| AppError::bad_request(format!("Invalid Content-Range: {}", value)));
+    }
+    if let Some(value) = header_value(X_UPDATE_RANGE) {
+        return parse_update_range(value)
+            .map(Some)
+            .ok_or_else(|| AppError::bad_request(format!("Invalid X-Update-Range: {}", value)));
+    }
+    Ok(None)
+}
+
+/// Writes `content` into `file` at the requested range and returns the
+/// updated file. Ranges that do not match the body or would leave a gap
+/// after the end of the file are a 416.
+pub async fn write_file_range(
+    files: &dyn FileUseCase,
+    file: &FileDto,
+    range: WriteRange,
+    content: &[u8],
+) -> Result<FileDto, AppError> {
+    let offset = range.offset(file.size, content.len() as u64).ok_or_else(|| AppError::new(
+        StatusCode::RANGE_NOT_SATISFIABLE,
+        format!("The range does not fit a {} byte body into a {} byte file", content.len(), file.size),
+        "RangeNotSatisfiable",
+    ))?;
+    Ok(files.write_file_range(&file.id, offset, content).await?)
+}
+
+/// Streams a `multipart/byteranges` body (RFC 9110, section 14.6) and returns its length
+async fn multipart_byteranges(
+    files: &dyn FileUseCase,
+    file: &FileDto,
+    ranges: &[ByteRange],
+    boundary: &str,
+) -> Result<(Vec<PartStream>, u64), AppError> {
+    fn literal(text: String) -> PartStream {
+        Box::pin(stream::once(futures::future::ready(Ok(Bytes::from(text)))))
+    }
+
+    let mut parts = Vec::with_capacity(ranges.len() * 2 + 1);
+    let mut length = 0u64;
+    for range in ranges {
+        let part_header = format!(
+            "\r\n--{}\r\nContent-Type: {}\r\nContent-Range: {}\r\n\r\n",
+            boundary,
+            file.mime_type,
+            range.content_range(file.size)
+        );
+        length += part_header.len() as u64 + range.length();
+        parts.push(literal(part_header));
+        parts.push(Box::into_pin(files.get_file_range(&file.id, range.start, range.length()).await?));
+    }
+
+    let closing = format!("\r\n--{}--\r\n", boundary);
+    length += closing.len() as u64;
+    parts.push(literal(closing));
+
+    Ok((parts, length))
+}
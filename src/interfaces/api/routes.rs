@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use axum::{
     routing::{get, post, put, delete},
     Router,
-    extract::{State, Query, Path},
+    extract::{State, Query, Path, Extension},
     http::StatusCode,
     Json,
     response::IntoResponse,
@@ -15,18 +15,23 @@ use tower_http::{
 use serde_json::json;
 use crate::common::config::AppConfig;
 use crate::common::di::AppState;
+use crate::domain::entities::user::Permission;
 
 use crate::interfaces::middleware::cache::{HttpCache, start_cache_cleanup_task};
 
 use crate::application::services::folder_service::FolderService;
 use crate::application::services::file_service::FileService;
 use crate::application::services::i18n_application_service::I18nApplicationService;
+use crate::application::services::auth_application_service::AuthApplicationService;
 use crate::application::services::batch_operations::BatchOperationService;
+use crate::application::services::free_name_service::FreeNameService;
 use crate::application::ports::trash_ports::TrashUseCase;
-use crate::application::ports::inbound::SearchUseCase;
-use crate::application::ports::share_ports::ShareUseCase;
+use crate::application::ports::inbound::{FolderUseCase, SearchUseCase};
+use crate::application::ports::share_ports::{ShareAccessUseCase, ShareUseCase};
 use crate::application::ports::favorites_ports::FavoritesUseCase;
 use crate::application::ports::recent_ports::RecentItemsUseCase;
+use crate::application::ports::image_preview_ports::ImagePreviewUseCase;
+use crate::application::ports::dav_capture_ports::DavCaptureUseCase;
 
 use crate::interfaces::api::handlers::folder_handler::FolderHandler;
 use crate::interfaces::api::handlers::file_handler::FileHandler;
@@ -36,6 +41,15 @@ use crate::interfaces::api::handlers::batch_handler::{
     self, BatchHandlerState
 };
 use crate::application::dtos::pagination::PaginationRequestDto;
+use crate::application::dtos::folder_dto::{MoveFolderDto, RenameFolderDto};
+use crate::application::ports::undo_ports::UndoAction;
+use crate::interfaces::api::handlers::undo_handler::with_undo_token;
+use crate::interfaces::middleware::auth::CurrentUser;
+use crate::interfaces::middleware::share_access::{ShareAccess, ShareAccessRoutes};
+use crate::application::dtos::notification_dto::JobKind;
+use crate::application::ports::notification_ports::NotificationUseCase;
+use crate::interfaces::middleware::job_tracking::track_job;
+use crate::application::ports::activity_ports::ActivityUseCase;
 
 /// Creates API routes for the application
 pub fn create_api_routes(
@@ -45,8 +59,14 @@ pub fn create_api_routes(
     trash_service: Option<Arc<dyn TrashUseCase>>,
     search_service: Option<Arc<dyn SearchUseCase>>,
     share_service: Option<Arc<dyn ShareUseCase>>,
+    share_access_service: Option<Arc<dyn ShareAccessUseCase>>,
     favorites_service: Option<Arc<dyn FavoritesUseCase>>,
     recent_service: Option<Arc<dyn RecentItemsUseCase>>,
+    image_preview_service: Option<Arc<dyn ImagePreviewUseCase>>,
+    dav_capture_service: Option<Arc<dyn DavCaptureUseCase>>,
+    dav_auth_service: Option<Arc<AuthApplicationService>>,
+    notification_service: Option<Arc<dyn NotificationUseCase>>,
+    activity_service: Option<Arc<dyn ActivityUseCase>>,
 ) -> Router<crate::common::di::AppState> {
     // Create a simplified AppState for the trash view
     // Setup required components for repository construction
@@ -121,13 +141,29 @@ pub fn create_api_routes(
         favorites_service: favorites_service.clone(), // Include the favorites service for routes
         recent_service: recent_service.clone(), // Include the recent service for routes
         calendar_service: None, // Adding missing field
-        contact_service: None   // Adding missing field
+        contact_service: None,  // Adding missing field
+        file_attribute_service: None,
+        hidden_file_rules: None,
+        lock_service: None,
+        dead_property_service: None,
+        sync_conflict_service: None,
+        feature_flags: None,
+        quota_service: None,
+        ownership_service: None,
+        undo_service: None,
+        dav_sync_service: None,
+        dav_principal_service: None,
+        scheduling_service: None,
+        carddav_service: None,
     };
+    // Servicio compartido para encontrar nombres libres (copias, conflictos, importaciones)
+    let free_name_service = Arc::new(FreeNameService::new(file_service.clone(), folder_service.clone()));
+    
     // Inicializar el servicio de operaciones por lotes
     let batch_service = Arc::new(BatchOperationService::default(
         file_service.clone(),
         folder_service.clone()
-    ));
+    ).with_free_name_service(free_name_service.clone()));
     
     // Crear estado para el manejador de operaciones por lotes
     let batch_handler_state = BatchHandlerState {
@@ -175,19 +211,21 @@ pub fn create_api_routes(
             // Listar contenido paginado de una carpeta por su ID
             FolderHandler::list_folders_paginated(State(service), pagination, Some(&id)).await
         }))
-        .route("/{id}/rename", put(FolderHandler::rename_folder))
-        .route("/{id}/move", put(FolderHandler::move_folder))
+        .route("/{id}/copy", post(FolderHandler::copy_folder))
         .with_state(folder_service.clone());
         
-    // Special route for ZIP download that requires AppState instead of just FolderService
+    // Special route for ZIP download that requires AppState instead of just FolderService.
+    // Both are user jobs whose failures end up in the notification center.
     let folder_zip_router = Router::new()
-        .route("/{id}/download", get(FolderHandler::download_folder_zip))
+        .route("/{id}/download", track_job(get(FolderHandler::download_folder_zip), &notification_service, JobKind::ZipDownload))
+        .route("/{id}/export", track_job(get(FolderHandler::export_folder), &notification_service, JobKind::FolderExport))
         .with_state(app_state.clone());
         
     // Create folder operations that use trash separately
     let folders_ops_router = Router::new()
         .route("/{id}", delete(|
             State(state): State<AppState>,
+            current_user: Option<Extension<CurrentUser>>,
             Path(id): Path<String>
         | async move {
             // Try to use trash service if available
@@ -198,7 +236,13 @@ pub fn create_api_routes(
                 match trash_service.move_to_trash(&id, "folder", &default_user).await {
                     Ok(_) => {
                         tracing::info!("Folder successfully moved to trash: {}", id);
-                        return StatusCode::NO_CONTENT.into_response();
+                        let undo = UndoAction::RestoreFromTrash {
+                            item_id: id,
+                            item_type: "folder".to_string(),
+                            trash_user_id: default_user,
+                        };
+                        let user = current_user.as_ref().map(|Extension(user)| user);
+                        return with_undo_token(&state, user, StatusCode::NO_CONTENT.into_response(), undo).await;
                     },
                     Err(err) => {
                         tracing::warn!("Could not move folder to trash, falling back to permanent delete: {}", err);
@@ -213,10 +257,59 @@ pub fn create_api_routes(
                 Ok(_) => StatusCode::NO_CONTENT.into_response(),
                 Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response()
             }
+        }))
+        // Renames and moves remember the previous name and parent so they can be undone
+        .route("/{id}/rename", put({
+            let folder_service = folder_service.clone();
+            move |
+                State(state): State<AppState>,
+                current_user: Option<Extension<CurrentUser>>,
+                Path(id): Path<String>,
+                Json(dto): Json<RenameFolderDto>,
+            | async move {
+                let previous = folder_service.get_folder(&id).await.ok();
+                let response = FolderHandler::rename_folder(State(folder_service), Path(id.clone()), Json(dto)).await.into_response();
+                match previous {
+                    Some(folder) => {
+                        let undo = UndoAction::RenameFolder { folder_id: id, name: folder.name };
+                        with_undo_token(&state, current_user.as_ref().map(|Extension(user)| user), response, undo).await
+                    }
+                    None => response,
+                }
+            }
+        }))
+        .route("/{id}/move", put({
+            let folder_service = folder_service.clone();
+            move |
+                State(state): State<AppState>,
+                current_user: Option<Extension<CurrentUser>>,
+                Path(id): Path<String>,
+                Json(dto): Json<MoveFolderDto>,
+            | async move {
+                let previous = folder_service.get_folder(&id).await.ok();
+                let response = FolderHandler::move_folder(State(folder_service), Path(id.clone()), Json(dto)).await.into_response();
+                match previous {
+                    Some(folder) => {
+                        let undo = UndoAction::MoveFolder { folder_id: id, parent_id: folder.parent_id };
+                        with_undo_token(&state, current_user.as_ref().map(|Extension(user)| user), response, undo).await
+                    }
+                    None => response,
+                }
+            }
         }));
         
     // Merge the routers
-    let folders_router = folders_basic_router.merge(folders_ops_router).merge(folder_zip_router);
+    let mut folders_router = folders_basic_router.merge(folders_ops_router).merge(folder_zip_router);
+    
+    // What anyone did inside a folder, for its owner
+    if let Some(service) = activity_service.clone() {
+        use crate::interfaces::api::handlers::activity_handler;
+        folders_router = folders_router.merge(
+            Router::new()
+                .route("/{id}/activity", get(activity_handler::folder_activity))
+                .with_state(service)
+        );
+    }
         
     // Create file routes for basic operations and trash-enabled delete
     let basic_file_router = Router::new()
@@ -251,13 +344,15 @@ pub fn create_api_routes(
         // Uses the correct URL pattern
         .route("/{id}", delete(|
             State(state): State<AppState>, 
+            current_user: Option<Extension<CurrentUser>>,
             Path(id): Path<String>
         | async move {
             tracing::info!("File delete route called explicitly for ID: {}", id);
-            FileHandler::delete_file(State(state), Path(id)).await
-        }))
+            FileHandler::delete_file(State(state), current_user, Path(id)).await
+        }).patch(FileHandler::patch_file))
         .route("/{id}/move", put(|
             State(state): State<AppState>,
+            current_user: Option<Extension<CurrentUser>>,
             Path(id): Path<String>,
             Json(payload): Json<serde_json::Value>,
         | async move {
@@ -267,14 +362,37 @@ pub fn create_api_routes(
                 .map(|s| s.to_string());
                 
             let file_service = &state.applications.file_service;
-            match file_service.move_file(&id, folder_id).await {
+            // The current folder is what an undo moves the file back to
+            let previous_folder_id = file_service.get_file(&id).await.ok().map(|file| file.folder_id);
+            let response = match file_service.move_file(&id, folder_id).await {
                 Ok(file_dto) => (StatusCode::OK, Json(file_dto)).into_response(),
-                Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response()
+                Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            };
+            match previous_folder_id {
+                Some(folder_id) => {
+                    let undo = UndoAction::MoveFile { file_id: id, folder_id };
+                    with_undo_token(&state, current_user.as_ref().map(|Extension(user)| user), response, undo).await
+                }
+                None => response,
             }
         }));
     
+    // Free name suggestions for clients creating copies or conflict copies
+    let free_name_router = Router::new()
+        .route("/free-name", get(FileHandler::suggest_free_name))
+        .with_state(free_name_service);
+    
     // Merge the routers
-    let files_router = basic_file_router.merge(file_operations_router);
+    let mut files_router = basic_file_router.merge(file_operations_router).merge(free_name_router);
+    
+    // On-demand resized images for responsive views
+    if let Some(service) = image_preview_service {
+        files_router = files_router.merge(
+            Router::new()
+                .route("/{id}/image", get(FileHandler::get_image_preview))
+                .with_state(service)
+        );
+    }
     
     // Crear rutas para operaciones por lotes
     let batch_router = Router::new()
@@ -298,6 +416,8 @@ pub fn create_api_routes(
             .route("/", get(SearchHandler::search_files_get))
             // Advanced search with full criteria object
             .route("/advanced", post(SearchHandler::search_files_post))
+            // Files, contacts and calendar events at once
+            .route("/all", get(SearchHandler::search_all))
             // Clear search cache
             .route("/cache", delete(SearchHandler::clear_search_cache))
             .with_state(app_state.clone())
@@ -317,6 +437,9 @@ pub fn create_api_routes(
             .route("/{id}", get(share_handler::get_shared_link))
             .route("/{id}", put(share_handler::update_shared_link))
             .route("/{id}", delete(share_handler::delete_shared_link))
+            .route("/{id}/qr", get(share_handler::get_shared_link_qr_code))
+            .route("/{id}/access-log", get(share_handler::get_share_access_log))
+            .route_layer(axum::middleware::from_fn(crate::interfaces::middleware::auth::require_user))
             .with_state(share_service.clone())
     } else {
         Router::new()
@@ -329,6 +452,9 @@ pub fn create_api_routes(
         Router::new()
             .route("/{token}", get(share_handler::access_shared_item))
             .route("/{token}/verify", post(share_handler::verify_shared_item_password))
+            .route("/{token}/download", get(share_handler::download_shared_item))
+            .route("/{token}/items", get(share_handler::list_shared_folder_items))
+            .route("/{token}/upload", post(share_handler::upload_to_shared_item))
             .with_state(share_service.clone())
     } else {
         Router::new()
@@ -363,16 +489,36 @@ pub fn create_api_routes(
     };
 
     let mut router = Router::new()
-        .nest("/folders", folders_router)
-        .nest("/files", files_router)
-        .nest("/batch", batch_router)
+        .nest("/folders", with_mount_user(with_write_permission(
+            with_share_permissions(folders_router, &share_access_service, ShareAccessRoutes::Folders),
+            Permission::WriteFiles,
+        )))
+        .nest("/files", with_mount_user(with_write_permission(
+            with_share_permissions(files_router, &share_access_service, ShareAccessRoutes::Files),
+            Permission::WriteFiles,
+        )))
+        .nest("/batch", with_mount_user(with_write_permission(
+            with_share_permissions(batch_router, &share_access_service, ShareAccessRoutes::Batch),
+            Permission::WriteFiles,
+        )))
         .nest("/search", search_router)
-        .nest("/shares", share_router)
-        .nest("/s", public_share_router)
+        .nest("/shares", with_mount_user(with_write_permission(
+            with_share_permissions(share_router, &share_access_service, ShareAccessRoutes::Shares),
+            Permission::CreateShares,
+        )))
+        .nest("/s", public_share_router.layer(axum::middleware::from_fn(
+            crate::interfaces::middleware::external_mounts::external_mount_shared_link_middleware,
+        )))
         .nest("/favorites", favorites_router)
         .nest("/recent", recent_router)
         ;
     
+    // Activity feed of the current user
+    if let Some(service) = activity_service.clone() {
+        use crate::interfaces::api::handlers::activity_handler::activity_routes;
+        router = router.nest("/activity", activity_routes().with_state(service));
+    }
+    
     // Store the share service in app_state for future use
     if let Some(share_service) = share_service.clone() {
         app_state.share_service = Some(share_service);
@@ -621,7 +767,7 @@ pub fn create_api_routes(
     }
     
     // Get the app configuration
-    let _config = AppConfig::from_env();
+    let config = AppConfig::from_env();
     
     // For now, just use the router as is - we'll properly implement the auth middleware later
     // when all implementation details are fixed
@@ -634,29 +780,31 @@ pub fn create_api_routes(
         tracing::info!("Trash service is available - trash view is functional");
     }
     
-    // Add WebDAV routes if needed
-    let webdav_enabled = true; // In production, you'd read this from a config
-    let router = if webdav_enabled {
+    // Add the DAV routes that are enabled; the capabilities endpoint reports the same flags
+    // WebDAV
+    let router = if config.features.enable_webdav {
         use crate::interfaces::api::handlers::webdav_handler;
-        router.merge(webdav_handler::webdav_routes())
+        let webdav_routes = with_share_permissions(webdav_handler::webdav_routes(), &share_access_service, ShareAccessRoutes::WebDav);
+        let webdav_routes = with_write_permission(webdav_routes, Permission::WriteFiles);
+        router.merge(with_dav_auth(with_dav_capture(webdav_routes, &dav_capture_service), &dav_auth_service))
     } else {
         router
     };
     
-    // Add CalDAV routes if needed
-    let caldav_enabled = true; // In production, you'd read this from a config
-    let router = if caldav_enabled {
+    // CalDAV
+    let router = if config.features.enable_caldav {
         use crate::interfaces::api::handlers::caldav_handler;
-        router.nest("/caldav", caldav_handler::caldav_routes())
+        let caldav_routes = with_write_permission(caldav_handler::caldav_routes(), Permission::WriteFiles);
+        router.nest("/caldav", with_dav_auth(with_dav_capture(caldav_routes, &dav_capture_service), &dav_auth_service))
     } else {
         router
     };
     
-    // Add CardDAV routes if needed
-    let carddav_enabled = true; // In production, you'd read this from a config
-    let router = if carddav_enabled {
-        // Note: We'll implement carddav_handler in the next phase
-        router
+    // CardDAV
+    let router = if config.features.enable_carddav {
+        use crate::interfaces::api::handlers::carddav_protocol_handler;
+        let carddav_routes = with_write_permission(carddav_protocol_handler::carddav_routes(), Permission::WriteFiles);
+        router.nest("/carddav", with_dav_auth(with_dav_capture(carddav_routes, &dav_capture_service), &dav_auth_service))
     } else {
         router
     };
@@ -666,4 +814,73 @@ pub fn create_api_routes(
         .layer(TraceLayer::new_for_http())
         // HTTP caching is disabled temporarily due to compatibility issues
         // .layer(HttpCacheLayer::new(http_cache.clone()).with_max_age(folders_ttl))
+}
+
+/// Authenticates the DAV routes with HTTP Basic or a bearer token.
+///
+/// DAV clients cannot log in to get a token, so these routes also take
+/// Basic credentials and answer 401 with a challenge instead of a JSON error.
+fn with_dav_auth(
+    routes: Router<AppState>,
+    dav_auth_service: &Option<Arc<AuthApplicationService>>,
+) -> Router<AppState> {
+    match dav_auth_service {
+        Some(service) => routes.layer(axum::middleware::from_fn_with_state(
+            service.clone(),
+            crate::interfaces::middleware::auth::dav_auth_middleware,
+        )),
+        None => routes,
+    }
+}
+
+/// Rejects the writes of users whose role lacks `permission`.
+///
+/// Reads always pass, so guests can browse what they have access to. The
+/// layer must run after authentication to know the user's role.
+fn with_write_permission<S: Clone + Send + Sync + 'static>(routes: Router<S>, permission: Permission) -> Router<S> {
+    routes.layer(axum::middleware::from_fn_with_state(
+        permission,
+        crate::interfaces::middleware::auth::require_permission_for_writes,
+    ))
+}
+
+/// Lets the user of the request reach the external mounts shown in the
+/// folder tree. It wraps the other layers, which may already look items up
+fn with_mount_user<S: Clone + Send + Sync + 'static>(routes: Router<S>) -> Router<S> {
+    routes.layer(axum::middleware::from_fn(
+        crate::interfaces::middleware::external_mounts::external_mount_user_middleware,
+    ))
+}
+
+/// Enforces share permissions on users who open files in someone else's home
+/// folder. Like the role check, the layer must run after authentication
+fn with_share_permissions<S: Clone + Send + Sync + 'static>(
+    routes: Router<S>,
+    share_access_service: &Option<Arc<dyn ShareAccessUseCase>>,
+    kind: ShareAccessRoutes,
+) -> Router<S> {
+    match share_access_service {
+        Some(service) => routes.layer(axum::middleware::from_fn_with_state(
+            ShareAccess { access: service.clone(), routes: kind },
+            crate::interfaces::middleware::share_access::share_access_middleware,
+        )),
+        None => routes,
+    }
+}
+
+/// Records the DAV traffic of users with an active diagnostics capture.
+///
+/// The layer wraps only the DAV routes, so it runs after authentication and
+/// can tell whose request it is.
+fn with_dav_capture(
+    routes: Router<AppState>,
+    dav_capture_service: &Option<Arc<dyn DavCaptureUseCase>>,
+) -> Router<AppState> {
+    match dav_capture_service {
+        Some(service) => routes.layer(axum::middleware::from_fn_with_state(
+            service.clone(),
+            crate::interfaces::middleware::dav_capture::dav_capture_middleware,
+        )),
+        None => routes,
+    }
 }
\ No newline at end of file
@@ -0,0 +1,220 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::{uri::PathAndQuery, HeaderValue, StatusCode, Uri},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, TimeZone, Utc};
+
+use crate::common::errors::AppError;
+use crate::interfaces::api::compat::{requested_version, ApiVersion, API_VERSION_HEADER};
+
+/// Prefijos bajo `/api` que hablan un protocolo propio y no se versionan
+const UNVERSIONED_PREFIXES: &[&str] = &["webdav", "caldav", "carddav"];
+
+/// Fecha en que las rutas sin versión pasaron a estar obsoletas (llegada de `/api/v1`)
+fn legacy_deprecated_at() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 10, 15, 0, 0, 0).unwrap()
+}
+
+/// Ruta obsoleta de la API y su reemplazo
+#[derive(Debug, Clone)]
+pub struct DeprecatedRoute {
+    /// Prefijo de la ruta sin versión, p. ej. `/api/files/upload`
+    pub prefix: String,
+    /// Momento desde el que está obsoleta
+    pub deprecated_at: DateTime<Utc>,
+    /// Momento en que dejará de responder, si ya se conoce
+    pub sunset: Option<DateTime<Utc>>,
+    /// Ruta que la reemplaza
+    pub successor: Option<String>,
+}
+
+/// Política de versiones de la API: qué rutas están obsoletas y cuándo se retiran
+#[derive(Debug, Clone, Default)]
+pub struct ApiVersionPolicy {
+    legacy_sunset: Option<DateTime<Utc>>,
+    deprecated_routes: Vec<DeprecatedRoute>,
+}
+
+impl ApiVersionPolicy {
+    /// Crea la política; `legacy_sunset` es la retirada de las rutas sin versión
+    pub fn new(legacy_sunset: Option<DateTime<Utc>>) -> Self {
+        Self {
+            legacy_sunset,
+            deprecated_routes: Vec::new(),
+        }
+    }
+
+    /// Marca como obsoleta una ruta concreta, también en sus versiones nuevas
+    pub fn with_deprecated_route(mut self, route: DeprecatedRoute) -> Self {
+        self.deprecated_routes.push(route);
+        self
+    }
+
+    /// Obsolescencia aplicable a una petición ya normalizada a `/api/...`
+    fn deprecation_for(&self, path: &str, legacy: bool, version: ApiVersion) -> Option<DeprecatedRoute> {
+        if let Some(route) = self.deprecated_routes.iter().find(|route| path.starts_with(&route.prefix)) {
+            return Some(route.clone());
+        }
+        legacy.then(|| DeprecatedRoute {
+            prefix: "/api/".to_string(),
+            deprecated_at: legacy_deprecated_at(),
+            sunset: self.legacy_sunset,
+            successor: path.strip_prefix("/api/").map(|rest| format!("/api/{}/{}", version.as_str(), rest)),
+        })
+    }
+}
+
+/// Negocia la versión de la API de cada petición bajo `/api`.
+///
+/// Las rutas `/api/v{N}/...` se reescriben a las rutas internas `/api/...`
+/// y las versiones desconocidas devuelven 404. Las rutas sin versión siguen
+/// funcionando con la versión que pida el cliente (cabecera
+/// `X-OxiCloud-API-Version` o `Accept: application/vnd.oxicloud.v1+json`) o
+/// con la actual, pero se anuncian como obsoletas con `Deprecation`, `Sunset`
+/// y un `Link` a su sucesora.
+///
+/// Reescribe la URI, así que debe envolver al router y no añadirse con
+/// `Router::layer`, que se ejecuta después del enrutado.
+pub async fn api_version_middleware(
+    State(policy): State<Arc<ApiVersionPolicy>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path().to_string();
+    let Some(rest) = path.strip_prefix("/api/") else {
+        return next.run(request).await;
+    };
+    let first_segment = rest.split('/').next().unwrap_or_default();
+    if UNVERSIONED_PREFIXES.contains(&first_segment) {
+        return next.run(request).await;
+    }
+
+    let (version, legacy) = if ApiVersion::is_version_segment(first_segment) {
+        let Some(version) = ApiVersion::parse(first_segment) else {
+            return AppError::new(
+                StatusCode::NOT_FOUND,
+                format!("API version {} does not exist", first_segment),
+                "UnsupportedApiVersion",
+            ).into_response();
+        };
+        let internal_path = format!("/api{}", &rest[first_segment.len()..]);
+        if let Err(e) = rewrite_path(&mut request, &internal_path) {
+            return e.into_response();
+        }
+        (version, false)
+    } else {
+        match requested_version(request.headers()) {
+            Ok(version) => (version.unwrap_or(ApiVersion::CURRENT), true),
+            Err(e) => return e.into_response(),
+        }
+    };
+
+    let deprecation = policy.deprecation_for(request.uri().path(), legacy, version);
+    request.extensions_mut().insert(version);
+
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    headers.insert(API_VERSION_HEADER, HeaderValue::from(version.number()));
+    if let Some(route) = deprecation {
+        if let Ok(value) = HeaderValue::from_str(&format!("@{}", route.deprecated_at.timestamp())) {
+            headers.insert("deprecation", value);
+        }
+        if let Some(sunset) = route.sunset {
+            if let Ok(value) = HeaderValue::from_str(&http_date(sunset)) {
+                headers.insert("sunset", value);
+            }
+        }
+        if let Some(successor) = route.successor {
+            if let Ok(value) = HeaderValue::from_str(&format!("<{}>; rel=\"successor-version\"", successor)) {
+                headers.append(axum::http::header::LINK, value);
+            }
+        }
+    }
+    response
+}
+
+/// Cambia la ruta de la petición conservando la query
+fn rewrite_path(request: &mut Request, path: &str) -> Result<(), AppError> {
+    let path_and_query = match request.uri().query() {
+        Some(query) => format!("{}?{}", path, query),
+        None => path.to_string(),
+    };
+    let mut parts = request.uri().clone().into_parts();
+    parts.path_and_query = Some(PathAndQuery::try_from(path_and_query)
+        .map_err(|e| AppError::bad_request(format!("Invalid request path: {}", e)))?);
+    *request.uri_mut() = Uri::from_parts(parts)
+        .map_err(|e| AppError::bad_request(format!("Invalid request path: {}", e)))?;
+    Ok(())
+}
+
+/// Fecha en el formato de las cabeceras HTTP (RFC 9110, IMF-fixdate)
+fn http_date(date: DateTime<Utc>) -> String {
+    date.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get, Router};
+    use tower::{Layer, ServiceExt};
+
+    async fn call(policy: ApiVersionPolicy, uri: &str) -> Response {
+        let router = Router::new()
+            .route("/api/files", get(|version: ApiVersion, uri: Uri| async move {
+                format!("{} {}", version.as_str(), uri)
+            }))
+            .route("/api/webdav/{*path}", get(|| async { "dav" }));
+        let app = axum::middleware::from_fn_with_state(Arc::new(policy), api_version_middleware).layer(router);
+        app.oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap()).await.unwrap()
+    }
+
+    async fn text(response: Response) -> String {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn versioned_paths_reach_the_unversioned_routes() {
+        let response = call(ApiVersionPolicy::default(), "/api/v1/files?folder=a").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[API_VERSION_HEADER], "1");
+        assert!(response.headers().get("deprecation").is_none());
+        assert_eq!(text(response).await, "v1 /api/files?folder=a");
+
+        let response = call(ApiVersionPolicy::default(), "/api/v9/files").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn unversioned_paths_are_announced_as_deprecated() {
+        let sunset = Utc.with_ymd_and_hms(2027, 6, 30, 0, 0, 0).unwrap();
+        let response = call(ApiVersionPolicy::new(Some(sunset)), "/api/files").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let headers = response.headers();
+        assert_eq!(headers["deprecation"], format!("@{}", legacy_deprecated_at().timestamp()));
+        assert_eq!(headers["sunset"], "Wed, 30 Jun 2027 00:00:00 GMT");
+        assert_eq!(headers["link"], "</api/v1/files>; rel=\"successor-version\"");
+
+        // Los protocolos DAV no se versionan
+        let response = call(ApiVersionPolicy::default(), "/api/webdav/doc.txt").await;
+        assert!(response.headers().get("deprecation").is_none());
+        assert!(response.headers().get(API_VERSION_HEADER).is_none());
+    }
+
+    #[tokio::test]
+    async fn deprecated_routes_are_announced_in_every_version() {
+        let policy = ApiVersionPolicy::default().with_deprecated_route(DeprecatedRoute {
+            prefix: "/api/files".to_string(),
+            deprecated_at: Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap(),
+            sunset: None,
+            successor: Some("/api/v1/items".to_string()),
+        });
+        let response = call(policy, "/api/v1/files").await;
+        assert_eq!(response.headers()["link"], "</api/v1/items>; rel=\"successor-version\"");
+        assert!(response.headers().get("sunset").is_none());
+    }
+}
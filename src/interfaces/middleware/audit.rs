@@ -0,0 +1,52 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+
+use crate::application::ports::audit_ports::AuditLogPort;
+use crate::domain::entities::audit_event::{AuditAction, AuditEvent};
+use crate::interfaces::middleware::auth::CurrentUser;
+
+/// Prefijo de las rutas de administración
+const ADMIN_PATH_PREFIX: &str = "/api/admin";
+
+/// Registra en la auditoría las peticiones a las rutas de administración que
+/// modifican algo, con su resultado.
+///
+/// Se aplica a toda la aplicación, después de identificar al usuario, para
+/// no depender de que cada ruta de administración lo incluya; también quedan
+/// los intentos rechazados de quien no es administrador. Las lecturas no se
+/// registran.
+pub async fn admin_audit_middleware(
+    State(audit_log): State<Arc<dyn AuditLogPort>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path().to_string();
+    if !path.starts_with(ADMIN_PATH_PREFIX) || matches!(*request.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+        return next.run(request).await;
+    }
+
+    let method = request.method().clone();
+    let user = request.extensions().get::<CurrentUser>().cloned();
+    let ip_address = request.extensions().get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string());
+
+    let response = next.run(request).await;
+    let status = response.status();
+    audit_log.record(AuditEvent::new(
+        AuditAction::AdminAction,
+        user.as_ref().map(|user| user.id.clone()),
+        user.map(|user| user.username),
+        Some(path),
+        ip_address,
+        status.is_success(),
+        Some(format!("{} {}", method, status.as_u16())),
+    )).await;
+    response
+}
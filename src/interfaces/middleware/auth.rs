@@ -1,13 +1,22 @@
+use std::net::SocketAddr;
 use std::sync::Arc;
 use axum::{
-    extract::{State, Request},
+    extract::{ConnectInfo, State, Request},
     http::{StatusCode, HeaderMap, header},
     middleware::Next,
     response::{Response, IntoResponse},
     body::Body,
 };
 
+use base64::Engine;
+
+use crate::application::dtos::user_dto::UserDto;
+use crate::application::ports::auth_ports::Caller;
+use crate::application::ports::external_storage_ports::ExternalMountUser;
+use crate::application::ports::share_ports::ShareRecipient;
+use crate::application::services::auth_application_service::AuthApplicationService;
 use crate::common::di::AppState;
+use crate::domain::entities::user::{Permission, UserRole};
 
 // Extensión para almacenar datos del usuario autenticado
 #[derive(Clone, Debug)]
@@ -18,6 +27,51 @@ pub struct CurrentUser {
     pub role: String,
 }
 
+impl CurrentUser {
+    /// Rol del usuario; un rol desconocido se trata como invitado
+    pub fn user_role(&self) -> UserRole {
+        UserRole::parse(&self.role).unwrap_or(UserRole::Guest)
+    }
+
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.user_role().has_permission(permission)
+    }
+
+    pub fn is_admin(&self) -> bool {
+        self.user_role() == UserRole::Admin
+    }
+
+    /// Identidad con la que se llama a los servicios
+    pub fn caller(&self) -> Caller {
+        Caller::new(self.id.clone(), self.user_role())
+    }
+
+    /// Identidad con la que se comprueban los enlaces compartidos
+    pub fn share_recipient(&self) -> ShareRecipient<'_> {
+        ShareRecipient { user_id: &self.id, username: &self.username, email: &self.email }
+    }
+
+    /// Identidad con la que se usan los montajes externos
+    pub fn mount_user(&self) -> ExternalMountUser {
+        ExternalMountUser {
+            id: self.id.clone(),
+            username: self.username.clone(),
+            is_admin: self.is_admin(),
+        }
+    }
+}
+
+impl From<UserDto> for CurrentUser {
+    fn from(user: UserDto) -> Self {
+        Self {
+            id: user.id,
+            username: user.username,
+            email: user.email,
+            role: user.role,
+        }
+    }
+}
+
 // Estructura para usar en extractores de Axum
 #[derive(Clone, Debug)]
 pub struct AuthUser {
@@ -149,30 +203,221 @@ pub async fn auth_middleware(
     Err(AuthError::TokenNotProvided)
 }
 
-// Middleware simplificado para verificar roles de administrador
+/// Restringe las rutas a los administradores.
+///
+/// Se aplica como `route_layer` a las rutas de administración y necesita que
+/// el usuario ya esté identificado (ver `api_credentials_middleware`): sin
+/// usuario responde 401 y con un usuario que no es administrador, 403.
 pub async fn require_admin(
-    headers: HeaderMap,
+    request: Request,
+    next: Next,
+) -> Result<Response, AuthError> {
+    match request.extensions().get::<CurrentUser>() {
+        None => Err(AuthError::TokenNotProvided),
+        Some(user) if !user.is_admin() => {
+            tracing::info!("Acceso de administración denegado a {} ({})", user.username, user.role);
+            Err(AuthError::AccessDenied("Se requiere rol de administrador".to_string()))
+        },
+        Some(_) => Ok(next.run(request).await),
+    }
+}
+
+/// Restringe las rutas a usuarios identificados.
+///
+/// Como `require_admin`, se aplica como `route_layer` detrás de
+/// `api_credentials_middleware`; sin usuario responde 401.
+pub async fn require_user(
+    request: Request,
+    next: Next,
+) -> Result<Response, AuthError> {
+    match request.extensions().get::<CurrentUser>() {
+        None => Err(AuthError::TokenNotProvided),
+        Some(_) => Ok(next.run(request).await),
+    }
+}
+
+/// Exige un permiso a las peticiones que modifican datos.
+///
+/// Las lecturas (GET, HEAD, OPTIONS, PROPFIND, REPORT) pasan siempre, de modo
+/// que un invitado puede consultar pero no escribir. Las peticiones sin
+/// usuario se dejan pasar: de rechazarlas se encargan los handlers, como
+/// hasta ahora.
+pub async fn require_permission_for_writes(
+    State(permission): State<Permission>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AuthError> {
+    if is_read_only(request.method().as_str()) {
+        return Ok(next.run(request).await);
+    }
+    match request.extensions().get::<CurrentUser>() {
+        Some(user) if !user.has_permission(permission) => {
+            tracing::info!("{} {} denegado a {} ({})", request.method(), request.uri().path(), user.username, user.role);
+            Err(AuthError::AccessDenied(format!("El rol '{}' no permite esta operación", user.role)))
+        },
+        _ => Ok(next.run(request).await),
+    }
+}
+
+fn is_read_only(method: &str) -> bool {
+    matches!(method, "GET" | "HEAD" | "OPTIONS" | "PROPFIND" | "REPORT")
+}
+
+/// Desafío que se envía a los clientes DAV, y a los de la API que usan
+/// Basic, sin credenciales válidas
+const DAV_CHALLENGE: &str = "Basic realm=\"OxiCloud\", charset=\"UTF-8\"";
+
+/// Autenticación de las rutas WebDAV, CalDAV y CardDAV.
+///
+/// Los clientes DAV no saben obtener un token, así que además del token
+/// `Bearer` de la API se acepta HTTP Basic con una contraseña de aplicación o,
+/// si la configuración lo permite, con la de la cuenta. Sin credenciales
+/// válidas se responde 401 con el desafío Basic, que es lo que hace que el
+/// cliente pida usuario y contraseña. OPTIONS no necesita credenciales, ya
+/// que los clientes lo usan para descubrir el servidor antes de autenticarse.
+pub async fn dav_auth_middleware(
+    State(auth): State<Arc<AuthApplicationService>>,
     mut request: Request,
     next: Next,
 ) -> Response {
-    // Implementación simplificada que verifica si hay un token de admin
-    if let Some(auth_value) = headers.get(header::AUTHORIZATION) {
-        if let Ok(auth_str) = auth_value.to_str() {
-            if auth_str.contains("admin") {
-                // Autorizado como admin
-                let current_user = CurrentUser {
-                    id: "admin-user-id".to_string(),
-                    username: "admin".to_string(),
-                    email: "admin@example.com".to_string(),
-                    role: "admin".to_string(),
-                };
-                request.extensions_mut().insert(current_user);
-                return next.run(request).await;
+    if request.extensions().get::<CurrentUser>().is_some() || request.method() == axum::http::Method::OPTIONS {
+        return next.run(request).await;
+    }
+    
+    let authorization = authorization_header(&request);
+    let user = match &authorization {
+        Some(authorization) => authenticate_credentials(&auth, authorization, client_ip(&request).as_deref()).await,
+        None => None,
+    };
+    
+    match user {
+        Some(user) => {
+            request.extensions_mut().insert(CurrentUser::from(user));
+            next.run(request).await
+        },
+        None => {
+            if authorization.is_some() {
+                tracing::info!("Credenciales DAV rechazadas para {}", request.uri().path());
             }
-        }
+            basic_challenge()
+        },
     }
+}
+
+/// Autenticación con contraseñas de aplicación en toda la API.
+///
+/// Las peticiones con `Authorization: Basic` se identifican con las mismas
+/// reglas que las de DAV, de modo que un script o un dispositivo puede llamar
+/// a la API con su contraseña de aplicación; si no son válidas se responde
+/// 401 con el desafío Basic. Un token `Bearer` válido también identifica al
+/// usuario; uno que no lo es se deja pasar sin usuario, como hasta ahora, y
+/// las peticiones sin credenciales siguen igual.
+pub async fn api_credentials_middleware(
+    State(auth): State<Arc<AuthApplicationService>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    if request.extensions().get::<CurrentUser>().is_some() {
+        return next.run(request).await;
+    }
+    let Some(authorization) = authorization_header(&request) else {
+        return next.run(request).await;
+    };
     
-    // Acceso denegado
-    let error = AuthError::AccessDenied("Se requiere rol de administrador".to_string());
-    error.into_response()
-}
\ No newline at end of file
+    match authenticate_credentials(&auth, &authorization, client_ip(&request).as_deref()).await {
+        Some(user) => {
+            request.extensions_mut().insert(CurrentUser::from(user));
+            next.run(request).await
+        },
+        None if is_basic(&authorization) => {
+            tracing::info!("Credenciales Basic rechazadas para {}", request.uri().path());
+            basic_challenge()
+        },
+        None => next.run(request).await,
+    }
+}
+
+fn authorization_header(request: &Request) -> Option<String> {
+    request.headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+fn is_basic(authorization: &str) -> bool {
+    authorization.split_once(' ').is_some_and(|(scheme, _)| scheme.eq_ignore_ascii_case("Basic"))
+}
+
+/// Dirección del cliente, si el servidor expone los datos de la conexión
+fn client_ip(request: &Request) -> Option<String> {
+    request.extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string())
+}
+
+/// Usuario de una cabecera `Authorization` con un token `Bearer` o
+/// credenciales `Basic`; `None` si no son válidas
+async fn authenticate_credentials(auth: &AuthApplicationService, authorization: &str, client_ip: Option<&str>) -> Option<UserDto> {
+    match authorization.split_once(' ') {
+        Some((scheme, token)) if scheme.eq_ignore_ascii_case("Bearer") => {
+            auth.authenticate_token(token.trim()).await.ok()
+        },
+        Some((scheme, credentials)) if scheme.eq_ignore_ascii_case("Basic") => {
+            let (username, password) = decode_basic_credentials(credentials.trim())?;
+            auth.authenticate_basic(&username, &password, client_ip).await.ok()
+        },
+        _ => None,
+    }
+}
+
+/// Respuesta 401 que hace que el cliente pida usuario y contraseña
+fn basic_challenge() -> Response {
+    Response::builder()
+        .status(StatusCode::UNAUTHORIZED)
+        .header(header::WWW_AUTHENTICATE, DAV_CHALLENGE)
+        .body(Body::empty())
+        .unwrap()
+}
+
+/// Usuario y contraseña de una cabecera `Authorization: Basic` (RFC 7617)
+fn decode_basic_credentials(credentials: &str) -> Option<(String, String)> {
+    let decoded = base64::engine::general_purpose::STANDARD.decode(credentials).ok()?;
+    let decoded = String::from_utf8(decoded).ok()?;
+    let (username, password) = decoded.split_once(':')?;
+    Some((username.to_string(), password.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_basic_credentials() {
+        // "ana:pa:ss wörd" — the password may contain colons and non-ASCII text
+        assert_eq!(
+            decode_basic_credentials("YW5hOnBhOnNzIHfDtnJk"),
+            Some(("ana".to_string(), "pa:ss wörd".to_string()))
+        );
+        assert_eq!(decode_basic_credentials("bm9jb2xvbg=="), None);
+        assert_eq!(decode_basic_credentials("not base64!"), None);
+    }
+
+    #[test]
+    fn test_current_user_permissions() {
+        let user = |role: &str| CurrentUser {
+            id: "1".to_string(),
+            username: "ana".to_string(),
+            email: "ana@example.com".to_string(),
+            role: role.to_string(),
+        };
+        assert!(user("admin").is_admin());
+        assert!(user("user").has_permission(Permission::WriteFiles));
+        assert!(!user("guest").has_permission(Permission::WriteFiles));
+        // Un rol desconocido no concede nada
+        assert_eq!(user("superuser").user_role(), UserRole::Guest);
+        
+        assert!(is_read_only("PROPFIND"));
+        assert!(!is_read_only("PUT"));
+        assert!(!is_read_only("MKCOL"));
+    }
+}
@@ -0,0 +1,118 @@
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use axum::{
+    body::{Body, to_bytes},
+    extract::{Request, State},
+    http::HeaderMap,
+    middleware::Next,
+    response::Response,
+};
+use futures::StreamExt;
+use http_body::Body as _;
+
+use crate::application::ports::dav_capture_ports::{CapturedBody, CapturedExchange, DavCaptureUseCase};
+use crate::interfaces::middleware::auth::CurrentUser;
+
+fn header_pairs(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| (name.as_str().to_string(), String::from_utf8_lossy(value.as_bytes()).into_owned()))
+        .collect()
+}
+
+/// Copia los primeros bytes de un cuerpo según lo va leyendo el handler
+#[derive(Default)]
+struct BodyTap {
+    content: Vec<u8>,
+    seen: u64,
+    finished: bool,
+}
+
+/// Graba las peticiones DAV de los usuarios con una captura activa.
+///
+/// El cuerpo de la petición se copia mientras el handler lo consume, sin
+/// retenerlo entero en memoria. El de la respuesta solo se captura si su
+/// tamaño es conocido y cabe en el límite; las descargas en streaming se
+/// registran sin cuerpo.
+pub async fn dav_capture_middleware(
+    State(capture): State<Arc<dyn DavCaptureUseCase>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let username = match request.extensions().get::<CurrentUser>() {
+        Some(user) if capture.is_capturing(&user.username) => user.username.clone(),
+        _ => return next.run(request).await,
+    };
+
+    let limit = capture.max_body_bytes();
+    let started_at = chrono::Utc::now();
+    let started = Instant::now();
+    let method = request.method().to_string();
+    let uri = request.uri().to_string();
+    let request_headers = header_pairs(request.headers());
+
+    let tap = Arc::new(Mutex::new(BodyTap::default()));
+    let (parts, body) = request.into_parts();
+    let stream_tap = tap.clone();
+    let body = Body::from_stream(body.into_data_stream().map(move |chunk| {
+        if let (Ok(bytes), Ok(mut tap)) = (&chunk, stream_tap.lock()) {
+            let room = limit.saturating_sub(tap.content.len());
+            tap.content.extend_from_slice(&bytes[..room.min(bytes.len())]);
+            tap.seen += bytes.len() as u64;
+        }
+        chunk
+    }).chain(futures::stream::once({
+        let stream_tap = tap.clone();
+        async move {
+            if let Ok(mut tap) = stream_tap.lock() {
+                tap.finished = true;
+            }
+            Ok::<_, axum::Error>(axum::body::Bytes::new())
+        }
+    })));
+
+    let response = next.run(Request::from_parts(parts, body)).await;
+
+    let status = response.status().as_u16();
+    let response_headers = header_pairs(response.headers());
+    let (parts, body) = response.into_parts();
+    let (body, response_body) = match body.size_hint().exact() {
+        Some(len) if len <= limit as u64 => match to_bytes(body, limit).await {
+            Ok(bytes) => {
+                let captured = CapturedBody { content: bytes.to_vec(), total_len: Some(len) };
+                (Body::from(bytes), Some(captured))
+            }
+            Err(e) => {
+                tracing::warn!("Could not capture DAV response body: {}", e);
+                (Body::empty(), None)
+            }
+        },
+        _ => (body, None),
+    };
+
+    let request_body = tap
+        .lock()
+        .map(|tap| CapturedBody {
+            content: tap.content.clone(),
+            total_len: tap.finished.then_some(tap.seen),
+        })
+        .unwrap_or_default();
+
+    let exchange = CapturedExchange {
+        started_at,
+        duration_ms: started.elapsed().as_millis() as u64,
+        method,
+        uri,
+        request_headers,
+        request_body,
+        status,
+        response_headers,
+        response_body,
+    };
+    if let Err(e) = capture.record_exchange(&username, exchange).await {
+        tracing::warn!("Failed to record DAV exchange for '{}': {}", username, e);
+    }
+
+    Response::from_parts(parts, body)
+}
@@ -0,0 +1,35 @@
+use axum::{
+    extract::Request,
+    middleware::Next,
+    response::Response,
+};
+
+use crate::application::services::external_storage_service::{with_mount_caller, ExternalMountCaller};
+use crate::interfaces::middleware::auth::CurrentUser;
+
+/// Da acceso a los montajes externos del árbol de carpetas al usuario de la
+/// petición.
+///
+/// Los repositorios de archivos y carpetas consultan quién llama para listar
+/// solo los montajes que el usuario puede usar y para rechazar los demás. Sin
+/// usuario los montajes no aparecen.
+pub async fn external_mount_user_middleware(
+    request: Request,
+    next: Next,
+) -> Response {
+    let user = request.extensions().get::<CurrentUser>().map(CurrentUser::mount_user);
+    match user {
+        Some(user) => with_mount_caller(ExternalMountCaller::User(user), next.run(request)).await,
+        None => next.run(request).await,
+    }
+}
+
+/// Da acceso a los montajes externos a las rutas públicas de los enlaces
+/// compartidos. El servicio de compartición ya valida el enlace y solo
+/// llega al elemento compartido y a lo que contiene.
+pub async fn external_mount_shared_link_middleware(
+    request: Request,
+    next: Next,
+) -> Response {
+    with_mount_caller(ExternalMountCaller::SharedLink, next.run(request)).await
+}
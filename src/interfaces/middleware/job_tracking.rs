@@ -0,0 +1,131 @@
+use std::sync::Arc;
+
+use axum::{
+    body::{Body, to_bytes},
+    extract::{Request, State},
+    http::{HeaderName, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::Response,
+    routing::MethodRouter,
+};
+use futures::StreamExt;
+
+use crate::application::dtos::notification_dto::{JobFailure, JobKind, RetryActionDto};
+use crate::application::ports::notification_ports::NotificationUseCase;
+use crate::interfaces::middleware::auth::CurrentUser;
+
+/// Cabecera con el ID del trabajo de la respuesta
+pub const X_JOB_ID: HeaderName = HeaderName::from_static("x-job-id");
+
+/// Tamaño máximo del cuerpo de error que se lee para extraer la causa
+const MAX_ERROR_BODY: usize = 64 * 1024;
+
+/// Estado del middleware: dónde notificar y qué tipo de trabajo es
+#[derive(Clone)]
+pub struct JobTracking {
+    notifications: Arc<dyn NotificationUseCase>,
+    kind: JobKind,
+}
+
+/// Convierte una ruta en un trabajo seguido: la respuesta lleva un ID de
+/// trabajo y sus fallos llegan al centro de notificaciones del usuario. Sin
+/// servicio de notificaciones la ruta queda igual.
+pub fn track_job<S>(
+    route: MethodRouter<S>,
+    notifications: &Option<Arc<dyn NotificationUseCase>>,
+    kind: JobKind,
+) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    match notifications {
+        Some(notifications) => route.layer(axum::middleware::from_fn_with_state(
+            JobTracking { notifications: notifications.clone(), kind },
+            job_tracking_middleware,
+        )),
+        None => route,
+    }
+}
+
+/// Causa de un error a partir del cuerpo de la respuesta: el `message` de
+/// `AppError`, el `error` de los handlers que devuelven JSON propio o, si no
+/// hay ninguno, el texto del estado HTTP
+fn error_cause(status: StatusCode, body: &[u8]) -> String {
+    serde_json::from_slice::<serde_json::Value>(body).ok()
+        .and_then(|json| {
+            ["message", "error"].iter()
+                .find_map(|field| json.get(field).and_then(|value| value.as_str()).map(str::to_string))
+        })
+        .or_else(|| {
+            let text = String::from_utf8_lossy(body).trim().to_string();
+            (!text.is_empty() && !text.starts_with('{')).then_some(text)
+        })
+        .unwrap_or_else(|| status.canonical_reason().unwrap_or("Request failed").to_string())
+}
+
+/// Sigue un trabajo iniciado por el usuario.
+///
+/// Los errores que el handler devuelve se notifican con su causa; en las
+/// respuestas correctas se vigila el cuerpo, porque una descarga en streaming
+/// puede cortarse a mitad y el cliente solo vería un archivo truncado.
+pub async fn job_tracking_middleware(
+    State(tracking): State<JobTracking>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let job_id = uuid::Uuid::new_v4().to_string();
+    let user_id = request.extensions().get::<CurrentUser>().map(|user| user.id.clone());
+    let retry = RetryActionDto {
+        method: request.method().to_string(),
+        href: request.uri().path_and_query().map(|pq| pq.to_string()).unwrap_or_else(|| request.uri().path().to_string()),
+        requires_upload: !matches!(*request.method(), Method::GET | Method::HEAD),
+    };
+
+    let mut response = next.run(request).await;
+    if let Ok(value) = HeaderValue::from_str(&job_id) {
+        response.headers_mut().insert(X_JOB_ID, value);
+    }
+
+    let status = response.status();
+    // Sin sesión no hay a quién notificar: el cliente tiene que autenticarse
+    if status == StatusCode::UNAUTHORIZED {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    if !status.is_success() {
+        let body = to_bytes(body, MAX_ERROR_BODY).await.unwrap_or_default();
+        let failure = JobFailure { job_id, kind: tracking.kind, error: error_cause(status, &body), retry };
+        tracking.notifications.report_job_failure(user_id.as_deref(), failure).await;
+        return Response::from_parts(parts, Body::from(body));
+    }
+
+    let mut failure = Some(JobFailure { job_id, kind: tracking.kind, error: String::new(), retry });
+    let body = Body::from_stream(body.into_data_stream().map(move |chunk| {
+        if let (Err(e), Some(mut failure)) = (&chunk, failure.take()) {
+            failure.error = format!("The download was interrupted: {}", e);
+            let notifications = tracking.notifications.clone();
+            let user_id = user_id.clone();
+            tokio::spawn(async move {
+                notifications.report_job_failure(user_id.as_deref(), failure).await;
+            });
+        }
+        chunk
+    }));
+    Response::from_parts(parts, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_cause() {
+        let app_error = br#"{"status":"404 Not Found","message":"Calendar not found","error_type":"NotFound"}"#;
+        assert_eq!(error_cause(StatusCode::NOT_FOUND, app_error), "Calendar not found");
+        let handler_error = br#"{"error":"Error exporting folder: disk full"}"#;
+        assert_eq!(error_cause(StatusCode::INTERNAL_SERVER_ERROR, handler_error), "Error exporting folder: disk full");
+        assert_eq!(error_cause(StatusCode::BAD_REQUEST, b"iCalendar data must be UTF-8"), "iCalendar data must be UTF-8");
+        assert_eq!(error_cause(StatusCode::BAD_GATEWAY, b""), "Bad Gateway");
+    }
+}
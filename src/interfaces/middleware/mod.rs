@@ -1,3 +1,10 @@
 pub mod cache;
 pub mod auth;
-pub mod redirect; // Add redirect middleware for API to Axum transition
\ No newline at end of file
+pub mod dav_capture;
+pub mod api_version;
+pub mod redirect; // Add redirect middleware for API to Axum transition
+pub mod rate_limit;
+pub mod job_tracking;
+pub mod share_access;
+pub mod external_mounts;
+pub mod audit;
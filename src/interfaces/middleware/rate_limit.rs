@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::common::errors::AppError;
+
+/// Número de claves a partir del cual se purgan las ventanas caducadas
+const PURGE_THRESHOLD: usize = 10_000;
+
+/// Limitador de peticiones por clave con ventanas fijas.
+///
+/// Cada clave (normalmente la IP del cliente) puede hacer `max_requests`
+/// peticiones por ventana; las siguientes se rechazan hasta que empiece la
+/// próxima. Vive en memoria, así que cada instancia del servidor lleva su
+/// propia cuenta.
+pub struct RateLimiter {
+    max_requests: u32,
+    window: Duration,
+    windows: Mutex<HashMap<IpAddr, (Instant, u32)>>,
+}
+
+impl RateLimiter {
+    /// Crea un limitador de `max_requests` peticiones por `window`
+    pub fn new(max_requests: u32, window: Duration) -> Self {
+        Self {
+            max_requests: max_requests.max(1),
+            window,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Cuenta una petición de `key`; si supera el límite devuelve el tiempo
+    /// que queda hasta la siguiente ventana
+    pub fn check(&self, key: IpAddr) -> Result<(), Duration> {
+        self.check_at(key, Instant::now())
+    }
+
+    fn check_at(&self, key: IpAddr, now: Instant) -> Result<(), Duration> {
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        if windows.len() >= PURGE_THRESHOLD {
+            windows.retain(|_, (started, _)| now.duration_since(*started) < self.window);
+        }
+
+        let (started, count) = windows.entry(key).or_insert((now, 0));
+        if now.duration_since(*started) >= self.window {
+            *started = now;
+            *count = 0;
+        }
+        if *count >= self.max_requests {
+            return Err(self.window.saturating_sub(now.duration_since(*started)));
+        }
+        *count += 1;
+        Ok(())
+    }
+}
+
+/// Limita las peticiones por dirección IP del cliente y responde 429 con
+/// `Retry-After` al superar el límite.
+///
+/// Usa la dirección de la conexión, no `X-Forwarded-For`, que el propio
+/// cliente puede falsear; sin ella (p. ej. en tests) todas las peticiones
+/// comparten la misma cuenta.
+pub async fn rate_limit_middleware(
+    State(limiter): State<Arc<RateLimiter>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let client = request.extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip())
+        .unwrap_or(IpAddr::from([0, 0, 0, 0]));
+
+    match limiter.check(client) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => {
+            let mut response = AppError::new(
+                StatusCode::TOO_MANY_REQUESTS,
+                "Too many requests, try again later",
+                "TooManyRequests",
+            ).into_response();
+            let seconds = retry_after.as_secs().max(1);
+            response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_over_the_limit_wait_for_the_next_window() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(60));
+        let client = IpAddr::from([10, 0, 0, 1]);
+        let start = Instant::now();
+
+        assert!(limiter.check_at(client, start).is_ok());
+        assert!(limiter.check_at(client, start).is_ok());
+        let retry_after = limiter.check_at(client, start + Duration::from_secs(20)).unwrap_err();
+        assert_eq!(retry_after, Duration::from_secs(40));
+
+        // Otros clientes tienen su propia cuenta
+        assert!(limiter.check_at(IpAddr::from([10, 0, 0, 2]), start).is_ok());
+        assert!(limiter.check_at(client, start + Duration::from_secs(60)).is_ok());
+    }
+}
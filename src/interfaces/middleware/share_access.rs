@@ -0,0 +1,234 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, Method},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::application::ports::share_ports::{ShareAccessUseCase, ShareRecipient, ShareTarget};
+use crate::common::errors::AppError;
+use crate::domain::entities::share::SharePermissions;
+use crate::domain::services::path_codec_service::decode_path;
+use crate::interfaces::middleware::auth::CurrentUser;
+
+/// Rutas que protege la capa; cada una nombra sus elementos a su manera
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareAccessRoutes {
+    /// `/api/files/{id}/...`
+    Files,
+    /// `/api/folders/{id}/...`
+    Folders,
+    /// `/webdav/{ruta}`
+    WebDav,
+    /// `/api/batch/...`: todos los elementos van en el cuerpo
+    Batch,
+    /// `/api/gallery/...`: la carpeta va en la consulta
+    Gallery,
+    /// `/api/uploads/...`: la carpeta de destino va en el cuerpo o en los
+    /// metadatos TUS al crear la sesión
+    Uploads,
+    /// `/api/shares/...`: el elemento que se comparte va en el cuerpo al
+    /// crear el enlace
+    Shares,
+}
+
+/// Estado del middleware
+#[derive(Clone)]
+pub struct ShareAccess {
+    pub access: Arc<dyn ShareAccessUseCase>,
+    pub routes: ShareAccessRoutes,
+}
+
+/// Comprobación de los elementos que la petición nombra en el cuerpo (la
+/// carpeta de destino de una subida o de una carpeta nueva, los IDs de un
+/// lote). El middleware la deja en las peticiones a las que se aplican los
+/// permisos y el handler la usa después de leer el cuerpo.
+#[derive(Clone)]
+pub struct ShareBodyCheck {
+    access: Arc<dyn ShareAccessUseCase>,
+    user: CurrentUser,
+}
+
+impl ShareBodyCheck {
+    /// Falla con el primer elemento al que no llegan los permisos del usuario
+    pub async fn ensure(&self, checks: &[(ShareTarget, SharePermissions)]) -> Result<(), AppError> {
+        let recipient = ShareRecipient { user_id: &self.user.id, username: &self.user.username, email: &self.user.email };
+        for (target, required) in checks {
+            if let Err(error) = self.access.ensure_access(recipient, target, *required).await {
+                tracing::info!("{:?} denegado a {}: {}", target, self.user.username, error);
+                return Err(error.into());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Para los handlers: sin comprobación en la petición (administradores,
+/// peticiones sin usuario, enlaces desactivados) todo pasa
+pub async fn ensure_body_access(
+    check: &Option<axum::Extension<ShareBodyCheck>>,
+    checks: &[(ShareTarget, SharePermissions)],
+) -> Result<(), AppError> {
+    match check {
+        Some(axum::Extension(check)) => check.ensure(checks).await,
+        None => Ok(()),
+    }
+}
+
+/// Permisos que pide un método sobre el elemento de la ruta
+fn required_for(method: &Method, action: Option<&str>) -> SharePermissions {
+    match (method.as_str(), action) {
+        ("GET" | "HEAD" | "OPTIONS" | "PROPFIND" | "REPORT", _) => SharePermissions::READ,
+        ("DELETE", _) => SharePermissions::DELETE,
+        // Copiar solo lee el origen; mover lo quita de donde estaba
+        (_, Some("copy")) => SharePermissions::READ,
+        (_, Some("move")) => SharePermissions::DELETE,
+        _ => SharePermissions::WRITE,
+    }
+}
+
+/// Carpeta que una petición nombra en el parámetro `folder_id` de la consulta
+fn query_folder(method: &Method, query: Option<&str>) -> Vec<(ShareTarget, SharePermissions)> {
+    query
+        .and_then(|query| url::form_urlencoded::parse(query.as_bytes()).find(|(key, _)| key == "folder_id"))
+        .map(|(_, folder_id)| vec![(ShareTarget::Folder(folder_id.into_owned()), required_for(method, None))])
+        .unwrap_or_default()
+}
+
+/// Comprobaciones de una petición a la API REST, con la ruta ya sin el
+/// prefijo `/files`, `/folders` o `/gallery`
+fn rest_checks(
+    routes: ShareAccessRoutes,
+    method: &Method,
+    path: &str,
+    query: Option<&str>,
+) -> Vec<(ShareTarget, SharePermissions)> {
+    let mut segments = path.trim_matches('/').split('/');
+    let first = segments.next().unwrap_or_default();
+    let action = segments.next();
+    match (routes, first) {
+        // Los lotes, las subidas y los enlaces nuevos se comprueban en el
+        // handler con `ShareBodyCheck`
+        (ShareAccessRoutes::Batch | ShareAccessRoutes::Uploads | ShareAccessRoutes::Shares, _) => Vec::new(),
+        // El listado de una carpeta y la galería la nombran en la consulta
+        (ShareAccessRoutes::Files, "") | (ShareAccessRoutes::Gallery, _) => query_folder(method, query),
+        // Rutas que no nombran ningún elemento
+        (_, "") | (ShareAccessRoutes::Files, "upload" | "free-name") | (ShareAccessRoutes::Folders, "paginated") => Vec::new(),
+        (ShareAccessRoutes::Files, id) => vec![(ShareTarget::File(id.to_string()), required_for(method, action))],
+        (_, id) => vec![(ShareTarget::Folder(id.to_string()), required_for(method, action))],
+    }
+}
+
+/// Ruta de destino de un COPY o MOVE. Si falta o no es válida se deja al
+/// handler, que es quien responde el error
+fn destination_path(headers: &HeaderMap) -> Option<String> {
+    let destination = headers.get("Destination")?.to_str().ok()?;
+    let start = destination.find("/webdav/")? + "/webdav/".len();
+    decode_path(destination[start..].trim_end_matches('/')).ok()
+}
+
+/// Comprobaciones de una petición WebDAV
+fn webdav_checks(method: &Method, path: &str, headers: &HeaderMap) -> Vec<(ShareTarget, SharePermissions)> {
+    let Some(relative) = path.strip_prefix("/webdav") else {
+        return Vec::new();
+    };
+    let Ok(source) = decode_path(relative.trim_matches('/')) else {
+        return Vec::new();
+    };
+    let destination = destination_path(headers);
+    match method.as_str() {
+        "OPTIONS" => Vec::new(),
+        "COPY" | "MOVE" => {
+            let source_permission = if method.as_str() == "COPY" { SharePermissions::READ } else { SharePermissions::DELETE };
+            let mut checks = vec![(ShareTarget::Path(source), source_permission)];
+            if let Some(destination) = destination {
+                checks.push((ShareTarget::Path(destination), SharePermissions::WRITE));
+            }
+            checks
+        }
+        _ => vec![(ShareTarget::Path(source), required_for(method, None))],
+    }
+}
+
+/// Aplica los permisos de los enlaces a quien no es dueño de los ficheros.
+///
+/// Solo afecta a usuarios que entran en la carpeta personal de otro: los
+/// administradores, los dueños y las peticiones sin usuario pasan como
+/// hasta ahora. Las subidas, las carpetas nuevas y los lotes de la API REST
+/// nombran sus elementos en el cuerpo: los comprueba el handler con la
+/// `ShareBodyCheck` que se deja en la petición.
+pub async fn share_access_middleware(
+    State(state): State<ShareAccess>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let user = match request.extensions().get::<CurrentUser>() {
+        Some(user) if !user.is_admin() => user.clone(),
+        _ => return Ok(next.run(request).await),
+    };
+
+    let checks = match state.routes {
+        ShareAccessRoutes::WebDav => webdav_checks(request.method(), request.uri().path(), request.headers()),
+        routes => rest_checks(routes, request.method(), request.uri().path(), request.uri().query()),
+    };
+
+    let recipient = ShareRecipient { user_id: &user.id, username: &user.username, email: &user.email };
+    for (target, required) in &checks {
+        if let Err(error) = state.access.ensure_access(recipient, target, *required).await {
+            tracing::info!("{} {} denegado a {}: {}", request.method(), request.uri().path(), user.username, error);
+            return Err(error.into());
+        }
+    }
+
+    request.extensions_mut().insert(ShareBodyCheck { access: state.access.clone(), user });
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rest_checks() {
+        let checks = rest_checks(ShareAccessRoutes::Files, &Method::GET, "/f1/download", None);
+        assert_eq!(checks, vec![(ShareTarget::File("f1".to_string()), SharePermissions::READ)]);
+
+        let checks = rest_checks(ShareAccessRoutes::Files, &Method::GET, "/", Some("folder_id=d%201"));
+        assert_eq!(checks, vec![(ShareTarget::Folder("d 1".to_string()), SharePermissions::READ)]);
+
+        let checks = rest_checks(ShareAccessRoutes::Folders, &Method::PUT, "/d1/move", None);
+        assert_eq!(checks, vec![(ShareTarget::Folder("d1".to_string()), SharePermissions::DELETE)]);
+
+        let checks = rest_checks(ShareAccessRoutes::Folders, &Method::PUT, "/d1/rename", None);
+        assert_eq!(checks, vec![(ShareTarget::Folder("d1".to_string()), SharePermissions::WRITE)]);
+
+        assert!(rest_checks(ShareAccessRoutes::Files, &Method::POST, "/upload", None).is_empty());
+        assert!(rest_checks(ShareAccessRoutes::Folders, &Method::GET, "/paginated", None).is_empty());
+        assert!(rest_checks(ShareAccessRoutes::Batch, &Method::POST, "/files/delete", None).is_empty());
+        assert!(rest_checks(ShareAccessRoutes::Uploads, &Method::POST, "/", None).is_empty());
+        assert!(rest_checks(ShareAccessRoutes::Shares, &Method::POST, "/", None).is_empty());
+
+        let checks = rest_checks(ShareAccessRoutes::Gallery, &Method::GET, "/prefetch", Some("folder_id=d1&offset=40"));
+        assert_eq!(checks, vec![(ShareTarget::Folder("d1".to_string()), SharePermissions::READ)]);
+        assert!(rest_checks(ShareAccessRoutes::Gallery, &Method::GET, "/prefetch", None).is_empty());
+    }
+
+    #[test]
+    fn test_webdav_checks() {
+        let mut headers = HeaderMap::new();
+        headers.insert("Destination", "https://cloud.example/webdav/Mi%20Carpeta%20-%20ana/b.txt".parse().unwrap());
+
+        let checks = webdav_checks(&Method::from_bytes(b"MOVE").unwrap(), "/webdav/Mi%20Carpeta%20-%20bob/a.txt", &headers);
+        assert_eq!(checks, vec![
+            (ShareTarget::Path("Mi Carpeta - bob/a.txt".to_string()), SharePermissions::DELETE),
+            (ShareTarget::Path("Mi Carpeta - ana/b.txt".to_string()), SharePermissions::WRITE),
+        ]);
+
+        let checks = webdav_checks(&Method::PUT, "/webdav/Mi%20Carpeta%20-%20bob/new.txt", &HeaderMap::new());
+        assert_eq!(checks, vec![(ShareTarget::Path("Mi Carpeta - bob/new.txt".to_string()), SharePermissions::WRITE)]);
+
+        assert!(webdav_checks(&Method::OPTIONS, "/webdav/", &HeaderMap::new()).is_empty());
+    }
+}
@@ -1,5 +1,6 @@
 pub mod api;
 pub mod web;
 pub mod middleware;
+pub mod plugins;
 
 pub use api::create_api_routes;
\ No newline at end of file
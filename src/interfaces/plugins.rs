@@ -0,0 +1,193 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::Router;
+use serde::Serialize;
+
+use crate::common::di::AppState;
+use crate::common::errors::DomainError;
+
+/// Prefix every plugin's routes are mounted under, followed by its id
+pub const PLUGIN_ROUTE_PREFIX: &str = "/api/plugins";
+
+/// Identity of a plugin as shown by the manifest endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginInfo {
+    /// Stable identifier, also used as the route segment (`notes`, `bookmarks`)
+    pub id: &'static str,
+    pub name: &'static str,
+    pub version: &'static str,
+    pub description: &'static str,
+}
+
+/// Future returned by one run of a plugin job
+pub type PluginJobFuture = Pin<Box<dyn Future<Output = Result<(), DomainError>> + Send>>;
+
+/// Background task the host runs on a fixed interval
+#[derive(Clone)]
+pub struct PluginJob {
+    pub name: &'static str,
+    pub interval: Duration,
+    run: Arc<dyn Fn() -> PluginJobFuture + Send + Sync>,
+}
+
+impl PluginJob {
+    pub fn new<F, Fut>(name: &'static str, interval: Duration, run: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), DomainError>> + Send + 'static,
+    {
+        Self {
+            name,
+            interval,
+            run: Arc::new(move || Box::pin(run()) as PluginJobFuture),
+        }
+    }
+}
+
+/// Extension compiled into the server behind its own cargo feature.
+///
+/// Plugins share the host's `AppState`, so they can reuse its services
+/// (files, folders, auth) instead of bringing their own storage.
+pub trait Plugin: Send + Sync + 'static {
+    fn info(&self) -> PluginInfo;
+
+    /// Routes mounted at `/api/plugins/{id}`
+    fn routes(&self) -> Option<Router<Arc<AppState>>> {
+        None
+    }
+
+    /// Jobs started once the server is up
+    fn jobs(&self, _state: &Arc<AppState>) -> Vec<PluginJob> {
+        Vec::new()
+    }
+}
+
+/// Manifest entry for a registered plugin
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginManifest {
+    #[serde(flatten)]
+    pub info: PluginInfo,
+
+    /// Where the plugin's routes live, if it has any
+    pub route_prefix: Option<String>,
+}
+
+/// Plugins enabled in this build
+#[derive(Default)]
+pub struct PluginRegistry {
+    plugins: Vec<Arc<dyn Plugin>>,
+}
+
+impl PluginRegistry {
+    /// Adds a plugin; ids must be unique and usable as a path segment
+    pub fn register(&mut self, plugin: Arc<dyn Plugin>) -> Result<(), DomainError> {
+        let id = plugin.info().id;
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-') {
+            return Err(DomainError::validation_error(format!("Invalid plugin id '{}'", id)));
+        }
+        if self.plugins.iter().any(|p| p.info().id == id) {
+            return Err(DomainError::validation_error(format!("Plugin '{}' is already registered", id)));
+        }
+        self.plugins.push(plugin);
+        Ok(())
+    }
+
+    pub fn manifest(&self) -> Vec<PluginManifest> {
+        self.plugins
+            .iter()
+            .map(|plugin| {
+                let info = plugin.info();
+                PluginManifest {
+                    route_prefix: plugin.routes().map(|_| format!("{}/{}", PLUGIN_ROUTE_PREFIX, info.id)),
+                    info,
+                }
+            })
+            .collect()
+    }
+
+    /// Nests every plugin's routes into the application router
+    pub fn mount<S>(&self, mut app: Router<S>, state: &Arc<AppState>) -> Router<S>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        for plugin in &self.plugins {
+            if let Some(routes) = plugin.routes() {
+                let id = plugin.info().id;
+                tracing::info!("Mounting routes of plugin '{}'", id);
+                app = app.nest(&format!("{}/{}", PLUGIN_ROUTE_PREFIX, id), routes.with_state(state.clone()));
+            }
+        }
+        app
+    }
+
+    /// Spawns the background jobs of every plugin
+    pub fn start_jobs(&self, state: &Arc<AppState>) {
+        for plugin in &self.plugins {
+            let id = plugin.info().id;
+            for job in plugin.jobs(state) {
+                tracing::info!("Starting job '{}' of plugin '{}' every {:?}", job.name, id, job.interval);
+                tokio::spawn(async move {
+                    let mut interval = tokio::time::interval(job.interval);
+                    loop {
+                        interval.tick().await;
+                        if let Err(e) = (job.run)().await {
+                            tracing::error!("Job '{}' of plugin '{}' failed: {}", job.name, id, e);
+                        }
+                    }
+                });
+            }
+        }
+    }
+}
+
+/// Builds the registry of plugins compiled into this binary. Each plugin
+/// crate is an optional dependency enabled by a cargo feature of the same
+/// name and registered here under `#[cfg(feature = "...")]`
+#[allow(unused_mut, clippy::let_and_return)]
+pub fn compiled_plugins() -> PluginRegistry {
+    let mut registry = PluginRegistry::default();
+    registry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+
+    struct NotesPlugin;
+
+    impl Plugin for NotesPlugin {
+        fn info(&self) -> PluginInfo {
+            PluginInfo { id: "notes", name: "Notes", version: "0.1.0", description: "Markdown notes" }
+        }
+
+        fn routes(&self) -> Option<Router<Arc<AppState>>> {
+            Some(Router::new().route("/", get(|| async { "notes" })))
+        }
+    }
+
+    struct BadIdPlugin;
+
+    impl Plugin for BadIdPlugin {
+        fn info(&self) -> PluginInfo {
+            PluginInfo { id: "Bad/Id", name: "Bad", version: "0.1.0", description: "" }
+        }
+    }
+
+    #[test]
+    fn test_register_and_manifest() {
+        let mut registry = PluginRegistry::default();
+        registry.register(Arc::new(NotesPlugin)).unwrap();
+
+        assert!(registry.register(Arc::new(NotesPlugin)).is_err());
+        assert!(registry.register(Arc::new(BadIdPlugin)).is_err());
+
+        let manifest = registry.manifest();
+        assert_eq!(manifest.len(), 1);
+        assert_eq!(manifest[0].info.id, "notes");
+        assert_eq!(manifest[0].route_prefix.as_deref(), Some("/api/plugins/notes"));
+    }
+}
@@ -43,6 +43,13 @@ mod interfaces;
 
 use application::services::folder_service::FolderService;
 use application::services::file_service::FileService;
+use application::services::duplicate_photo_service::DuplicatePhotoService;
+use application::services::image_tagging_service::ImageTaggingService;
+use application::services::upload_session_service::UploadSessionService;
+use application::services::lock_service::LockService;
+use application::services::dead_property_service::DeadPropertyService;
+use application::services::sync_conflict_service::SyncConflictService;
+use application::services::feature_flag_service::FeatureFlagService;
 use application::services::i18n_application_service::I18nApplicationService;
 use application::services::storage_mediator::FileSystemStorageMediator;
 use application::services::share_service::ShareService;
@@ -62,6 +69,18 @@ use interfaces::{create_api_routes, web::create_web_routes};
 use application::services::trash_service::TrashService;
 use infrastructure::repositories::trash_fs_repository::TrashFsRepository;
 use infrastructure::services::trash_cleanup_service::TrashCleanupService;
+use infrastructure::services::upload_session_cleanup_service::UploadSessionCleanupService;
+use infrastructure::services::lock_cleanup_service::LockCleanupService;
+use infrastructure::services::storage_gc_service::StorageGcService;
+use infrastructure::services::image_placeholder_service::ImagePlaceholderService;
+use infrastructure::services::image_fingerprint_service::ImageFingerprintService;
+use infrastructure::services::file_attribute_service::FileAttributeService;
+use infrastructure::services::content_hash_service::ContentHashService;
+use infrastructure::services::image_preview_service::{ImagePreviewLimits, ImagePreviewService};
+use infrastructure::services::dav_capture_service::{DavCaptureLimits, DavCaptureService};
+use infrastructure::services::hidden_file_rules_service::HiddenFileRulesService;
+use application::ports::image_preview_ports::PreviewQuality;
+use infrastructure::services::http_image_labeler::HttpImageLabeler;
 use common::db::create_database_pool;
 use common::auth_factory::create_auth_services;
 use common::di::AppState;
@@ -79,12 +98,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Load configuration from environment variables
     let config = common::config::AppConfig::from_env();
     
+    // ASCII fallback used in every download's Content-Disposition
+    domain::services::path_codec_service::set_filename_fallback(config.downloads.filename_fallback());
+    
     // Set up storage directory
     let storage_path = config.storage_path.clone();
     if !storage_path.exists() {
         std::fs::create_dir_all(&storage_path).expect("Failed to create storage directory");
     }
 
+    // Directory holding the skeletons copied into new users' home folders
+    let skeleton_path = config.skeleton_path.clone();
+
     // Set up locales directory
     let locales_path = PathBuf::from("./static/locales");
     if !locales_path.exists() {
@@ -110,6 +135,43 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Create a reference to db_pool for use throughout the code
     let db_pool_ref = db_pool.as_ref();
 
+    // User groups, so one share covers a whole team
+    let user_group_service = db_pool_ref.map(|pool| {
+        Arc::new(application::services::user_group_service::UserGroupService::new(
+            Arc::new(infrastructure::repositories::pg::UserGroupPgRepository::new(pool.clone())),
+            Arc::new(infrastructure::repositories::pg::UserPgRepository::new(pool.clone())),
+        ))
+    });
+
+    // Initialize external storage mounts (SFTP, ...) from the environment configuration
+    let external_storage_service = if config.external_storage.mounts.is_empty() {
+        None
+    } else {
+        let vault = Arc::new(infrastructure::services::env_credential_vault::EnvCredentialVault::new());
+        let mut service = common::external_storage_factory::create_external_storage_service(
+            &config.external_storage,
+            vault,
+        ).await;
+        // Mounts limited to groups check membership on every request
+        if let Some(groups) = user_group_service.clone() {
+            service = service.with_groups(groups);
+        }
+        tracing::info!("External storage initialized with {} mounts", service.mount_count());
+        let service = Arc::new(service);
+        
+        // Start periodic change detection for mounts that request it
+        let watcher = infrastructure::services::external_change_watcher::ExternalChangeWatcher::new(service.clone());
+        for mount in &config.external_storage.mounts {
+            if let Some(interval_secs) = mount.poll_interval_secs {
+                if service.has_mount(&mount.name) {
+                    watcher.start_watch_job(&mount.name, interval_secs);
+                }
+            }
+        }
+        
+        Some(service)
+    };
+
     // Initialize path service
     let path_service = Arc::new(PathService::new(storage_path.clone()));
     
@@ -165,7 +227,6 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing::info!("ID mapping optimizer initialized with batch processing and caching");
     
     // Initialize the metadata cache 
-    let config = common::config::AppConfig::default();
     let metadata_cache = Arc::new(FileMetadataCache::default_with_config(config.clone()));
     
     // Start the periodic cleanup task for cache maintenance
@@ -204,9 +265,296 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         parallel_processor
     ));
 
+    // External mounts show up as folders in the tree of the users allowed on them;
+    // services that browse or share the tree see them, indexing and usage do not
+    let (tree_folder_storage, tree_file_storage): (
+        Arc<dyn application::ports::outbound::FolderStoragePort>,
+        Arc<dyn application::ports::outbound::FileStoragePort>,
+    ) = match &external_storage_service {
+        Some(service) => (
+            Arc::new(infrastructure::repositories::external_mount_repository::ExternalMountFolderRepository::new(
+                folder_repository.clone(),
+                service.clone(),
+            )),
+            Arc::new(infrastructure::repositories::external_mount_repository::ExternalMountFileRepository::new(
+                file_repository.clone(),
+                service.clone(),
+            )),
+        ),
+        None => (folder_repository.clone(), file_repository.clone()),
+    };
+
+    // Users who reach someone else's files through shares get only what those shares allow;
+    // services that take item IDs check it themselves, the routes through the share middleware
+    let share_repository = Arc::new(ShareFsRepository::new(
+        Arc::new(config.clone())
+    ));
+    let share_access = {
+        let mut service = application::services::share_access_service::ShareAccessService::new(
+            share_repository.clone(),
+            tree_file_storage.clone(),
+            tree_folder_storage.clone(),
+        );
+        if let Some(groups) = user_group_service.clone() {
+            service = service.with_groups(groups);
+        }
+        Arc::new(service) as Arc<dyn application::ports::share_ports::ShareAccessUseCase>
+    };
+
     // Initialize application services
-    let folder_service = Arc::new(FolderService::new(folder_repository.clone()));
-    let file_service = Arc::new(FileService::new(file_repository.clone()));
+    // Image processing stores live in hidden files so they never show up in listings
+    let max_image_source_bytes = config.resources.max_in_memory_file_size_mb as usize * 1024 * 1024;
+    // Dotfile and system file rules of the instance, plus the ones chosen by each user
+    let hidden_file_rules: Arc<dyn application::ports::hidden_file_ports::HiddenFileRulesPort> = Arc::new(
+        HiddenFileRulesService::new(config.hidden_files.rules(), storage_path.join(".hidden_file_rules.json"))
+            .await
+            .expect("Failed to initialize hidden file rules"),
+    );
+    // Changes to files, shares and calendars pushed to connected clients over /ws
+    let realtime_event_service = Arc::new(application::services::realtime_event_service::RealtimeEventService::new())
+        as Arc<dyn application::ports::realtime_ports::RealtimeEventPort>;
+    // Domain events published by services; subscribers are attached once they all exist
+    let event_bus = Arc::new(application::services::event_bus_service::InProcessEventBus::new());
+    let mut file_service = FileService::new(tree_file_storage.clone())
+        .with_hidden_file_rules(hidden_file_rules.clone())
+        .with_event_bus(event_bus.clone());
+    let image_placeholder_service = if config.features.enable_image_placeholders {
+        let service = Arc::new(ImagePlaceholderService::new(
+            storage_path.join(".placeholders.json"),
+            max_image_source_bytes,
+        ).await.expect("Failed to initialize image placeholder service"));
+        file_service = file_service.with_placeholder_service(service.clone());
+        Some(service)
+    } else {
+        None
+    };
+    let image_fingerprint_service = if config.features.enable_image_fingerprints {
+        let service = Arc::new(ImageFingerprintService::new(
+            storage_path.join(".image_fingerprints.json"),
+            max_image_source_bytes,
+        ).await.expect("Failed to initialize image fingerprint service"));
+        file_service = file_service.with_fingerprint_service(service.clone());
+        Some(service)
+    } else {
+        None
+    };
+    // Optional image tagging through an external inference service (needs the database)
+    let image_tagging_service: Option<Arc<dyn application::ports::image_tagging_ports::ImageTaggingUseCase>> =
+    match (&config.image_tagging.inference_url, db_pool_ref) {
+        (Some(url), Some(pool)) => match HttpImageLabeler::new(url.clone(), config.image_tagging.timeout_secs) {
+            Ok(labeler) => {
+                let service = ImageTaggingService::new(
+                    pool.clone(),
+                    Arc::new(labeler),
+                    file_repository.clone(),
+                    folder_repository.clone(),
+                ).with_limits(config.image_tagging.min_confidence, config.image_tagging.max_labels);
+                tracing::info!("Image tagging enabled using inference service at {}", url);
+                Some(Arc::new(service))
+            },
+            Err(e) => {
+                tracing::error!("Image tagging disabled: {}", e);
+                None
+            }
+        },
+        (Some(_), None) => {
+            tracing::warn!("Image tagging is disabled (requires database connection)");
+            None
+        },
+        _ => None,
+    };
+    if let Some(service) = &image_tagging_service {
+        file_service = file_service.with_tagging_service(service.clone());
+    }
+    // Extended attributes (mode bits, Finder metadata) sent by WebDAV clients
+    let file_attribute_service: Option<Arc<dyn application::ports::file_attribute_ports::FileAttributePort>> =
+    if config.features.enable_extended_attributes {
+        let service = Arc::new(FileAttributeService::new(
+            storage_path.join(".file_attributes.json"),
+        ).await.expect("Failed to initialize file attribute service"));
+        file_service = file_service.with_attribute_service(service.clone());
+        Some(service)
+    } else {
+        None
+    };
+    // SHA-256 of uploaded content, exposed on downloads so clients can verify transfers
+    if config.features.enable_content_hashes {
+        let service = Arc::new(ContentHashService::new(
+            storage_path.join(".content_hashes.json"),
+        ).await.expect("Failed to initialize content hash service"));
+        file_service = file_service.with_content_hash_service(service);
+    }
+    let file_service = Arc::new(file_service);
+    let duplicate_photo_service = image_fingerprint_service.clone().map(|fingerprint_service| {
+        Arc::new(DuplicatePhotoService::new(fingerprint_service, file_service.clone()))
+            as Arc<dyn application::ports::image_fingerprint_ports::DuplicatePhotoUseCase>
+    });
+    
+    // WebDAV locks are stored in the database so they survive restarts
+    let lock_service = db_pool_ref.map(|pool| {
+        Arc::new(LockService::new(
+            Arc::new(infrastructure::repositories::pg::LockPgRepository::new(pool.clone())),
+            config.webdav_locks.default_timeout_secs,
+            config.webdav_locks.max_timeout_secs,
+        )) as Arc<dyn application::ports::lock_ports::LockUseCase>
+    });
+    if let Some(service) = &lock_service {
+        LockCleanupService::new(service.clone(), 15).start_cleanup_job().await;
+    }
+    
+    // Custom properties set through PROPPATCH
+    let dead_property_service = db_pool_ref.map(|pool| {
+        Arc::new(DeadPropertyService::new(
+            Arc::new(infrastructure::repositories::pg::DeadPropertyPgRepository::new(pool.clone())),
+        )) as Arc<dyn application::ports::dead_property_ports::DeadPropertyUseCase>
+    });
+    
+    // Folder copies duplicate the files and metadata of the whole tree
+    let folder_service = Arc::new(
+        FolderService::new(tree_folder_storage.clone())
+            .with_file_storage(tree_file_storage.clone())
+            .with_metadata(file_attribute_service.clone(), dead_property_service.clone())
+            .with_event_bus(event_bus.clone()),
+    );
+    
+    // Conflicts detected while syncing, listed in the conflict dashboard
+    let sync_conflict_service = db_pool_ref.map(|pool| {
+        let mut service = SyncConflictService::new(
+            Arc::new(infrastructure::repositories::pg::SyncConflictPgRepository::new(pool.clone())),
+            file_service.clone(),
+        );
+        if let Some(locks) = &lock_service {
+            service = service.with_lock_service(locks.clone());
+        }
+        Arc::new(service) as Arc<dyn application::ports::sync_conflict_ports::SyncConflictUseCase>
+    });
+    
+    // Feature flags for gradual rollouts, cached so handlers can check them on every request
+    let feature_flags = db_pool_ref.map(|pool| {
+        Arc::new(FeatureFlagService::new(
+            Arc::new(infrastructure::repositories::pg::FeatureFlagPgRepository::new(pool.clone())),
+            std::time::Duration::from_secs(config.feature_flags.cache_ttl_secs),
+        )) as Arc<dyn application::ports::feature_flag_ports::FeatureFlagUseCase>
+    });
+    
+    // Instance branding, read on every page load and changed rarely
+    let theme_service = db_pool_ref.map(|pool| {
+        Arc::new(application::services::theme_service::ThemeService::new(
+            Arc::new(infrastructure::repositories::pg::ThemePgRepository::new(pool.clone())),
+            std::time::Duration::from_secs(60),
+        )) as Arc<dyn application::ports::theme_ports::ThemeUseCase>
+    });
+    
+    // Announcements published by admins, shown in the notification center and the banner
+    let announcement_service = db_pool_ref.map(|pool| {
+        Arc::new(application::services::announcement_service::AnnouncementService::new(
+            Arc::new(infrastructure::repositories::pg::AnnouncementPgRepository::new(pool.clone())),
+        )) as Arc<dyn application::ports::announcement_ports::AnnouncementUseCase>
+    });
+    
+    // Validation reports and repair jobs for calendar and contact data
+    let dav_validation_service = db_pool_ref.map(|pool| {
+        Arc::new(application::services::dav_validation_service::DavValidationService::new(
+            Arc::new(infrastructure::repositories::pg::CalendarPgRepository::new(pool.clone())),
+            Arc::new(infrastructure::repositories::pg::CalendarEventPgRepository::new(pool.clone())),
+            Arc::new(infrastructure::repositories::pg::AddressBookPgRepository::new(pool.clone())),
+            Arc::new(infrastructure::repositories::pg::ContactPgRepository::new(pool.clone())),
+        )) as Arc<dyn application::ports::dav_validation_ports::DavValidationUseCase>
+    });
+    
+    // Whole-calendar import and export as .ics files
+    let calendar_ical_service = db_pool_ref.map(|pool| {
+        Arc::new(application::services::calendar_ical_service::CalendarICalService::new(
+            Arc::new(infrastructure::repositories::pg::CalendarPgRepository::new(pool.clone())),
+            Arc::new(infrastructure::repositories::pg::CalendarEventPgRepository::new(pool.clone())),
+            Arc::new(infrastructure::repositories::pg::AddressBookPgRepository::new(pool.clone())),
+        )) as Arc<dyn application::ports::calendar_ical_ports::CalendarICalUseCase>
+    });
+    
+    // Bulk contact import from .vcf and .csv files
+    let contact_import_service = db_pool_ref.map(|pool| {
+        Arc::new(application::services::contact_import_service::ContactImportService::new(
+            Arc::new(infrastructure::repositories::pg::CalendarPgRepository::new(pool.clone())),
+            Arc::new(infrastructure::repositories::pg::AddressBookPgRepository::new(pool.clone())),
+            Arc::new(infrastructure::repositories::pg::ContactPgRepository::new(pool.clone())),
+        ).with_photo_storage(Arc::new(infrastructure::services::contact_photo_store::ContactPhotoStore::new(
+            config.storage_path.join(".contact_photos"),
+        )))) as Arc<dyn application::ports::contact_import_ports::ContactImportUseCase>
+    });
+    
+    // JSON multiget over WebDAV, CalDAV and CardDAV hrefs for the web UI
+    let mut dav_multiget_service = application::services::dav_multiget_service::DavMultigetService::new(file_service.clone(), share_access.clone());
+    if let Some(pool) = db_pool_ref {
+        dav_multiget_service = dav_multiget_service.with_dav_repositories(
+            Arc::new(infrastructure::repositories::pg::CalendarPgRepository::new(pool.clone())),
+            Arc::new(infrastructure::repositories::pg::CalendarEventPgRepository::new(pool.clone())),
+            Arc::new(infrastructure::repositories::pg::AddressBookPgRepository::new(pool.clone())),
+            Arc::new(infrastructure::repositories::pg::ContactPgRepository::new(pool.clone())),
+        );
+    }
+    let dav_multiget_service = Arc::new(dav_multiget_service) as Arc<dyn application::ports::dav_multiget_ports::DavMultigetUseCase>;
+    
+    // Emails about shares received, downloads of shared links and nearly full
+    // quotas, sent by a background worker. Needs a mail server and the
+    // database, where each user's preferences are kept
+    let email_notification_service = match (infrastructure::services::smtp_mail_sender::SmtpMailSender::from_config(&config.mail), db_pool_ref) {
+        (Some(mail_sender), Some(pool)) if config.mail.notifications => {
+            let base_url = config.public_share.base_url.clone()
+                .unwrap_or_else(|| format!("http://{}:{}", config.server_host, config.server_port));
+            let mut service = application::services::email_notification_service::EmailNotificationService::new(
+                Arc::new(mail_sender),
+                Arc::new(infrastructure::repositories::pg::UserPgRepository::new(pool.clone())),
+                Arc::new(infrastructure::repositories::pg::NotificationPreferencesPgRepository::new(pool.clone())),
+                base_url,
+            );
+            if let Some(templates_path) = &config.mail.templates_path {
+                service = service.with_templates_dir(templates_path);
+            }
+            let service = Arc::new(service);
+            service.clone().start_worker();
+            tracing::info!("Email notifications enabled");
+            Some(service)
+        }
+        _ => None,
+    };
+    
+    // Per-user storage quotas, checked before every write when enabled. Group
+    // policies are cached for a minute since every write resolves them
+    let quota_service = if config.features.enable_user_storage_quotas {
+        db_pool_ref.map(|pool| {
+            let policies = Arc::new(application::services::quota_policy_service::QuotaPolicyService::new(
+                Arc::new(infrastructure::repositories::pg::QuotaPolicyPgRepository::new(pool.clone())),
+                std::time::Duration::from_secs(60),
+            ));
+            let mut service = application::services::quota_service::QuotaService::new(
+                Arc::new(infrastructure::repositories::pg::UserPgRepository::new(pool.clone())),
+            ).with_policies(policies);
+            if let Some(email) = &email_notification_service {
+                service = service.with_email_notifications(email.clone(), config.mail.quota_warning_percent);
+            }
+            Arc::new(service) as Arc<dyn application::ports::quota_ports::StorageQuotaUseCase>
+        })
+    } else {
+        None
+    };
+    
+    // Resumable chunked uploads keep their state in the database so they survive restarts
+    let upload_session_service = db_pool_ref.map(|pool| {
+        let mut service = UploadSessionService::new(
+            pool.clone(),
+            file_service.clone(),
+            storage_path.join(".uploads"),
+            config.storage.upload_session_ttl_hours,
+            config.resources.max_upload_size_bytes(),
+        );
+        if let Some(quotas) = &quota_service {
+            service = service.with_quota_service(quotas.clone());
+        }
+        Arc::new(service) as Arc<dyn application::ports::upload_session_ports::UploadSessionUseCase>
+    });
+    if let Some(service) = &upload_session_service {
+        UploadSessionCleanupService::new(service.clone(), 15).start_cleanup_job().await;
+    }
     
     // Initialize trash service if enabled
     let trash_repository = if config.features.enable_trash {
@@ -499,7 +847,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             file_repo_adapter,
             folder_repo_adapter,
             config.storage.trash_retention_days,
-        ));
+        ).with_event_bus(event_bus.clone()));
         
         // Initialize trash cleanup service
         let cleanup_service = TrashCleanupService::new(
@@ -519,6 +867,28 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         None
     };
     
+    // Garbage collection of orphaned trash blobs, upload fragments, temp files and image metadata
+    let mut storage_gc = StorageGcService::new(
+        storage_path.clone(),
+        file_service.clone(),
+        config.storage.gc_grace_period_hours,
+    );
+    if let Some(trash_repo) = &trash_repository {
+        storage_gc = storage_gc.with_trash_repository(trash_repo.clone());
+    }
+    if let Some(service) = &upload_session_service {
+        storage_gc = storage_gc.with_upload_sessions(service.clone());
+    }
+    if let Some(service) = &image_placeholder_service {
+        storage_gc = storage_gc.with_placeholders(service.clone());
+    }
+    if let Some(service) = &image_fingerprint_service {
+        storage_gc = storage_gc.with_fingerprints(service.clone());
+    }
+    let storage_gc = Arc::new(storage_gc);
+    storage_gc.clone().start_gc_job(config.storage.gc_interval_hours);
+    let storage_gc_service = storage_gc as Arc<dyn application::ports::storage_gc_ports::StorageGcUseCase>;
+    
     // Initialize i18n service
     let i18n_repository = Arc::new(FileSystemI18nService::new(locales_path.clone()));
     let i18n_service = Arc::new(I18nApplicationService::new(i18n_repository.clone()));
@@ -533,12 +903,34 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     tracing::info!("Compression service initialized with buffer pool support");
     
+    // Template gallery of the "New document" menu: admin templates on disk plus each user's Templates folder
+    let template_service: Arc<dyn application::ports::template_ports::TemplateUseCase> = Arc::new(application::services::template_service::TemplateService::new(
+        Arc::new(infrastructure::services::fs_template_store::FsTemplateStore::new(config.templates_path.clone())),
+        file_service.clone(),
+        folder_service.clone(),
+        Arc::new(application::services::free_name_service::FreeNameService::new(file_service.clone(), folder_service.clone())),
+    ));
+    
+    // Initialize the home folder skeleton service used when provisioning users
+    let mut skeleton_service = application::services::user_skeleton_service::UserSkeletonService::new(
+        Arc::new(infrastructure::services::fs_skeleton_source::FsSkeletonSource::new(skeleton_path)),
+        folder_service.clone(),
+        file_service.clone(),
+    );
+    // Each group can have its own skeleton
+    if let Some(groups) = user_group_service.clone() {
+        skeleton_service = skeleton_service.with_groups(groups);
+    }
+    let skeleton_service = Arc::new(skeleton_service);
+    
     // Initialize auth services if enabled and database connection is available
     let auth_services = if config.features.enable_auth && db_pool_ref.is_some() {
         match create_auth_services(
             &config, 
             db_pool_ref.unwrap().clone(),
-            Some(folder_service.clone())  // Pasar el servicio de carpetas para creación automática de carpetas de usuario
+            Some(folder_service.clone()),  // Pasar el servicio de carpetas para creación automática de carpetas de usuario
+            Some(skeleton_service.clone()),
+            Some(event_bus.clone()),
         ).await {
             Ok(services) => {
                 tracing::info!("Authentication services initialized successfully with folder service");
@@ -553,6 +945,48 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         None
     };
     
+    // Demo mode: a sample account whose data is restored on a schedule
+    let demo_service = match (&auth_services, db_pool_ref) {
+        (Some(auth), Some(pool)) if config.demo.enabled => {
+            let service = Arc::new(application::services::demo_service::DemoService::new(
+                config.demo.clone(),
+                auth.auth_application_service.clone(),
+                Arc::new(infrastructure::repositories::pg::UserPgRepository::new(pool.clone())),
+                folder_service.clone(),
+                skeleton_service.clone(),
+                Arc::new(infrastructure::repositories::pg::CalendarPgRepository::new(pool.clone())),
+                Arc::new(infrastructure::repositories::pg::CalendarEventPgRepository::new(pool.clone())),
+                Arc::new(infrastructure::repositories::pg::AddressBookPgRepository::new(pool.clone())),
+                Arc::new(infrastructure::repositories::pg::ContactPgRepository::new(pool.clone())),
+            ));
+            service.clone().start_reset_job();
+            Some(service as Arc<dyn application::ports::demo_ports::DemoUseCase>)
+        }
+        _ => {
+            if config.demo.enabled {
+                tracing::warn!("Demo mode requires authentication and a database; it is disabled");
+            }
+            None
+        }
+    };
+    
+    // Deleted users keep their data in quarantine until it is purged
+    let user_quarantine_service = match (&auth_services, db_pool_ref) {
+        (Some(auth), Some(pool)) => {
+            let service = Arc::new(application::services::user_quarantine_service::UserQuarantineService::new(
+                auth.auth_application_service.clone(),
+                Arc::new(infrastructure::repositories::pg::UserPgRepository::new(pool.clone())),
+                Arc::new(infrastructure::repositories::pg::SessionPgRepository::new(pool.clone())),
+                Arc::new(infrastructure::repositories::pg::UserQuarantinePgRepository::new(pool.clone())),
+                folder_service.clone(),
+                config.auth.user_quarantine_days,
+            ));
+            service.clone().start_purge_job();
+            Some(service as Arc<dyn application::ports::user_quarantine_ports::UserQuarantineUseCase>)
+        }
+        _ => None,
+    };
+    
     // Create AppState for DI container
     let core_services = common::di::CoreServices {
         path_service: path_service.clone(),
@@ -595,32 +1029,189 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }),
     };
     
+    // Index of document contents for full-text search, kept next to the files
+    let full_text_index: Option<Arc<dyn application::ports::full_text_ports::FullTextIndexPort>> =
+        if config.features.enable_full_text_search {
+            match infrastructure::services::tantivy_full_text_index::TantivyFullTextIndex::open(&storage_path.join(".fulltext_index")) {
+                Ok(index) => {
+                    tracing::info!("Full-text index opened");
+                    Some(Arc::new(index))
+                }
+                Err(e) => {
+                    tracing::error!("Failed to open full-text index, content search disabled: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+    
     // Create the search service
     let search_service: Option<Arc<dyn application::ports::inbound::SearchUseCase>> = {
         // Create the search service with caching
-        let search_service = Arc::new(application::services::search_service::SearchService::new(
+        let mut search_service = application::services::search_service::SearchService::new(
             file_repository.clone(),
             folder_repository.clone(),
+            share_access.clone(),
             300, // Cache TTL in seconds (5 minutes)
             1000, // Maximum cache entries
-        ));
+        ).with_hidden_file_rules(hidden_file_rules.clone());
+        if let Some(index) = &full_text_index {
+            search_service = search_service.with_full_text(index.clone());
+        }
+        // Contacts and calendar events are searched too when they are stored
+        if let Some(pool) = db_pool_ref {
+            search_service = search_service.with_dav_repositories(
+                Arc::new(infrastructure::repositories::pg::CalendarPgRepository::new(pool.clone())),
+                Arc::new(infrastructure::repositories::pg::CalendarEventPgRepository::new(pool.clone())),
+                Arc::new(infrastructure::repositories::pg::AddressBookPgRepository::new(pool.clone())),
+                Arc::new(infrastructure::repositories::pg::ContactPgRepository::new(pool.clone())),
+            ).with_tags(Arc::new(infrastructure::repositories::pg::TagPgRepository::new(pool.clone())));
+        }
+        let search_service = Arc::new(search_service);
         
         tracing::info!("Search service initialized with caching (TTL: 300s, max entries: 1000)");
         Some(search_service)
     };
     
-    // Initialize share repository and service if enabled
-    let share_service: Option<Arc<dyn application::ports::share_ports::ShareUseCase>> = if config.features.enable_file_sharing {
-        let share_repository = Arc::new(ShareFsRepository::new(
-            Arc::new(config.clone())
+    // Webhooks registered by admins; deliveries are stored and retried by a worker
+    let webhook_service = match (&auth_services, db_pool_ref) {
+        (Some(_), Some(pool)) => match infrastructure::services::http_webhook_sender::HttpWebhookSender::new(10) {
+            Ok(sender) => {
+                let service = Arc::new(application::services::webhook_service::WebhookService::new(
+                    Arc::new(infrastructure::repositories::pg::WebhookPgRepository::new(pool.clone())),
+                    Arc::new(sender),
+                ));
+                service.clone().start_worker();
+                tracing::info!("Webhook service initialized");
+                Some(service)
+            }
+            Err(e) => {
+                tracing::error!("Failed to initialize webhook service: {}", e);
+                None
+            }
+        },
+        _ => None,
+    };
+    
+    // Activity feeds of uploads, changes, renames, deletes, shares and restores, kept in the database
+    let activity_service = db_pool_ref.map(|pool| {
+        Arc::new(application::services::activity_service::ActivityService::new(
+            Arc::new(infrastructure::repositories::pg::ActivityPgRepository::new(pool.clone())),
+            file_service.clone(),
+            folder_service.clone(),
+        )) as Arc<dyn application::ports::activity_ports::ActivityUseCase>
+    });
+    
+    // Search, real-time notifications, the audit log, activity feeds, webhooks and the full-text index react to domain events
+    {
+        use application::services::domain_event_subscribers::{ActivityFeedSubscriber, AuditLogSubscriber, RealtimeNotificationSubscriber, SearchIndexSubscriber};
+        let mut subscribers: Vec<Arc<dyn application::ports::event_bus_ports::DomainEventSubscriber>> = vec![
+            Arc::new(RealtimeNotificationSubscriber::new(realtime_event_service.clone())),
+            Arc::new(AuditLogSubscriber),
+        ];
+        if let Some(search) = &search_service {
+            subscribers.push(Arc::new(SearchIndexSubscriber::new(search.clone())));
+        }
+        if let (Some(activity), Some(pool)) = (&activity_service, db_pool_ref) {
+            subscribers.push(Arc::new(ActivityFeedSubscriber::new(
+                activity.clone(),
+                Arc::new(infrastructure::repositories::pg::UserPgRepository::new(pool.clone())),
+            )));
+        }
+        if let Some(webhooks) = &webhook_service {
+            subscribers.push(webhooks.clone());
+        }
+        if let Some(index) = &full_text_index {
+            subscribers.push(Arc::new(application::services::full_text_index_service::FullTextIndexService::new(
+                index.clone(),
+                Arc::new(infrastructure::services::text_extractor::DocumentTextExtractor),
+                file_repository.clone(),
+                config.resources.max_in_memory_file_size_mb * 1024 * 1024,
+            )));
+        }
+        event_bus.start_dispatcher(subscribers);
+    }
+    
+    // On-demand image previews, bounded in size, concurrency and cache memory
+    let image_preview_service: Arc<dyn application::ports::image_preview_ports::ImagePreviewUseCase> =
+        Arc::new(ImagePreviewService::new(
+            file_service.clone(),
+            PreviewQuality::parse(&config.image_preview.quality).unwrap_or(PreviewQuality::High),
+            ImagePreviewLimits {
+                max_dimension: config.image_preview.max_dimension,
+                max_source_bytes: config.resources.max_in_memory_file_size_mb * 1024 * 1024,
+                max_source_pixels: config.image_preview.max_source_megapixels as usize * 1_000_000,
+                max_concurrent: config.image_preview.max_concurrent,
+                cache_bytes: config.image_preview.cache_size_mb as usize * 1024 * 1024,
+            },
         ));
-        
-        let share_service = Arc::new(ShareService::new(
+    
+    // Gallery pages warm the preview cache for the images the user scrolls to next
+    let gallery_service: Arc<dyn application::ports::gallery_ports::GalleryUseCase> = Arc::new(
+        application::services::gallery_service::GalleryService::new(file_service.clone(), image_preview_service.clone())
+    );
+    
+    // Notification center for the failures of jobs started by users and the
+    // sharing changes they are told about; share notices live in the database
+    let mut notification_service = application::services::notification_service::NotificationService::new();
+    if let Some(pool) = db_pool_ref {
+        notification_service = notification_service.with_repository(Arc::new(
+            infrastructure::repositories::pg::ShareNotificationPgRepository::new(pool.clone())
+        ));
+    }
+    if let Some(email) = &email_notification_service {
+        notification_service = notification_service.with_email(email.clone());
+    }
+    notification_service = notification_service.with_realtime_events(realtime_event_service.clone());
+    let notification_service = Arc::new(notification_service)
+        as Arc<dyn application::ports::notification_ports::NotificationUseCase>;
+
+    // Initialize share service if enabled
+    let share_service: Option<Arc<dyn application::ports::share_ports::ShareUseCase>> = if config.features.enable_file_sharing {
+        let mut share_service = ShareService::new(
             Arc::new(config.clone()),
-            share_repository,
-            file_repository.clone(),
-            folder_repository.clone()
+            share_repository.clone(),
+            tree_file_storage.clone(),
+            tree_folder_storage.clone()
+        ).with_image_previews(image_preview_service.clone())
+        .with_event_bus(event_bus.clone());
+        // Links of disabled users, such as deleted ones in quarantine, stop working
+        if let Some(pool) = db_pool_ref {
+            share_service = share_service.with_user_storage(Arc::new(infrastructure::repositories::pg::UserPgRepository::new(pool.clone())));
+        }
+        // Links limited to user groups check membership on every verification
+        if let Some(groups) = user_group_service.clone() {
+            share_service = share_service.with_groups(groups);
+        }
+        // Recipients of links addressed to them are told about new and removed shares
+        share_service = share_service.with_notifications(notification_service.clone());
+        // Owners are emailed when files are downloaded through their links
+        if let Some(email) = &email_notification_service {
+            share_service = share_service.with_email_notifications(email.clone());
+        }
+        // Accesses and email verifications are logged next to shares.json
+        share_service = share_service.with_access_log(Arc::new(
+            infrastructure::repositories::share_access_log_fs_repository::ShareAccessLogFsRepository::new(config.storage_path.clone())
         ));
+        // Email-verified links need a mail server to send the one-time codes
+        if let Some(mail_sender) = infrastructure::services::smtp_mail_sender::SmtpMailSender::from_config(&config.mail) {
+            let email_access = Arc::new(application::services::share_email_access_service::ShareEmailAccessService::new(
+                Arc::new(mail_sender),
+                config.public_share.email_code_ttl_secs,
+                config.public_share.email_session_secs,
+            ));
+            email_access.clone().start_expiry_job();
+            share_service = share_service.with_email_access(email_access);
+            tracing::info!("Email verification for shared links enabled");
+        }
+        // Links created before share passwords were hashed still keep them as typed
+        match share_service.hash_legacy_passwords().await {
+            Ok(0) => {}
+            Ok(migrated) => tracing::info!("Hashed the plaintext passwords of {} shared links", migrated),
+            Err(e) => tracing::warn!("Could not hash the plaintext passwords of shared links: {}", e),
+        }
+        let share_service = Arc::new(share_service);
         
         tracing::info!("File sharing service initialized successfully");
         Some(share_service)
@@ -628,6 +1219,30 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         tracing::info!("File sharing service is disabled in configuration");
         None
     };
+    
+    // The share middleware only runs on the file routes when sharing is enabled
+    let share_access_service = config.features.enable_file_sharing.then(|| share_access.clone());
+    
+    // Integrity check of shares, favorites and group memberships left pointing at deleted things
+    let mut integrity_service = application::services::integrity_service::IntegrityService::new(
+        tree_file_storage.clone(),
+        tree_folder_storage.clone(),
+    );
+    if config.features.enable_file_sharing {
+        integrity_service = integrity_service.with_shares(share_repository.clone());
+    }
+    if let Some(trash_repo) = &trash_repository {
+        integrity_service = integrity_service.with_trash_repository(trash_repo.clone());
+    }
+    if let Some(pool) = db_pool_ref {
+        integrity_service = integrity_service
+            .with_user_storage(Arc::new(infrastructure::repositories::pg::UserPgRepository::new(pool.clone())))
+            .with_storage(Arc::new(infrastructure::repositories::pg::IntegrityPgRepository::new(pool.clone())));
+    }
+    if let Some(groups) = user_group_service.clone() {
+        integrity_service = integrity_service.with_groups(groups);
+    }
+    let integrity_service: Arc<dyn application::ports::integrity_ports::IntegrityUseCase> = Arc::new(integrity_service);
 
     // Initialize favorites service if database is available
     let favorites_service: Option<Arc<dyn application::ports::favorites_ports::FavoritesUseCase>> = 
@@ -668,7 +1283,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let application_services = common::di::ApplicationServices {
         folder_service: folder_service.clone(),
         file_service: file_service.clone(),
-        file_upload_service: Arc::new(application::services::file_upload_service::FileUploadService::default_stub()),
+        file_upload_service: Arc::new(match &quota_service {
+            Some(quotas) => application::services::file_upload_service::FileUploadService::default_stub()
+                .with_quota_service(quotas.clone()),
+            None => application::services::file_upload_service::FileUploadService::default_stub(),
+        }),
         file_retrieval_service: Arc::new(application::services::file_retrieval_service::FileRetrievalService::default_stub()),
         file_management_service: Arc::new(application::services::file_management_service::FileManagementService::default_stub()),
         file_use_case_factory: Arc::new(application::services::file_use_case_factory::AppFileUseCaseFactory::default_stub()),
@@ -696,6 +1315,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         storage_usage_service: None,
         calendar_service: calendar_service_option,
         contact_service: contact_service.clone(),
+        file_attribute_service,
+        hidden_file_rules: Some(hidden_file_rules.clone()),
+        lock_service,
+        dead_property_service,
+        sync_conflict_service: sync_conflict_service.clone(),
+        feature_flags: feature_flags.clone(),
+        quota_service: quota_service.clone(),
+        ownership_service: None,
+        undo_service: None,
+        dav_sync_service: None,
+        dav_principal_service: None,
+        scheduling_service: None,
+        carddav_service: None,
+        dav_transfer_service: None,
+        tag_service: None,
     };
     
     // Initialize storage usage service
@@ -710,7 +1344,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         let service = Arc::new(application::services::storage_usage_service::StorageUsageService::new(
             file_repository.clone(),
             user_repository,
-        ));
+        ).with_hidden_file_rules(hidden_file_rules.clone()));
         
         tracing::info!("Storage usage service initialized successfully");
         
@@ -723,11 +1357,162 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         None
     };
     
+    // Ownership rules for WebDAV moves and copies between users' home folders
+    if let Some(pool) = db_pool_ref {
+        let mut service = application::services::ownership_service::OwnershipService::new(
+            Arc::new(infrastructure::repositories::pg::UserPgRepository::new(pool.clone())),
+            file_service.clone(),
+            folder_service.clone(),
+            config.storage.cross_user_move_policy,
+        );
+        if let Some(quotas) = &quota_service {
+            service = service.with_quota_service(quotas.clone());
+        }
+        if let Some(shares) = &share_service {
+            service = service.with_share_service(shares.clone());
+        }
+        if let Some(favorites) = &favorites_service {
+            service = service.with_favorites_service(favorites.clone());
+        }
+        app_state = app_state.with_ownership_service(Arc::new(service));
+    }
+
+    // User tags on files and folders, also shown to WebDAV clients
+    let tag_service = db_pool_ref.map(|pool| {
+        let mut service = application::services::tag_service::TagService::new(
+            Arc::new(infrastructure::repositories::pg::TagPgRepository::new(pool.clone())),
+            file_service.clone(),
+            folder_service.clone(),
+        );
+        if let Some(search) = &search_service {
+            service = service.with_search(search.clone());
+        }
+        Arc::new(service) as Arc<dyn application::ports::tag_ports::TagUseCase>
+    });
+    if let Some(service) = &tag_service {
+        app_state = app_state.with_tag_service(service.clone());
+    }
+
+    // Comments on files and folders; mentions go to the notification center
+    let comment_service = db_pool_ref.map(|pool| {
+        let service = application::services::comment_service::CommentService::new(
+            Arc::new(infrastructure::repositories::pg::CommentPgRepository::new(pool.clone())),
+            file_service.clone(),
+            folder_service.clone(),
+            Arc::new(infrastructure::repositories::pg::UserPgRepository::new(pool.clone())),
+            share_access.clone(),
+        ).with_notifications(notification_service.clone());
+        Arc::new(service) as Arc<dyn application::ports::comment_ports::CommentUseCase>
+    });
+
+    // Background ZIP exports keep checkpoints in the database so they resume after restarts
+    let zip_export_service = db_pool_ref.map(|pool| {
+        let service = Arc::new(
+            application::services::zip_export_service::ZipExportService::new(
+                pool.clone(),
+                folder_service.clone(),
+                file_service.clone(),
+                Arc::new(infrastructure::repositories::pg::UserPgRepository::new(pool.clone())),
+                share_access.clone(),
+                storage_path.join(".zip_exports"),
+                config.storage.zip_export_ttl_hours,
+                config.auth.jwt_secret.clone(),
+            )
+            .with_notifications(notification_service.clone()),
+        );
+        service.clone().start_jobs();
+        service as Arc<dyn application::ports::zip_export_ports::ZipExportUseCase>
+    });
+
+    // Undo tokens for deletes, moves and renames made through the API
+    let undo_service = if config.storage.undo_window_minutes > 0 {
+        let mut service = application::services::undo_service::UndoService::new(
+            file_service.clone(),
+            folder_service.clone(),
+            config.storage.undo_window_minutes,
+        );
+        if let Some(trash) = &trash_service {
+            service = service.with_trash_service(trash.clone());
+        }
+        let service = Arc::new(service) as Arc<dyn application::ports::undo_ports::UndoUseCase>;
+        app_state = app_state.with_undo_service(service.clone());
+        Some(service)
+    } else {
+        None
+    };
+
+    // WebDAV COPY and MOVE of large folders answer 202 and finish in the background
+    let dav_transfer_service = if config.webdav_transfers.async_threshold_mb > 0 {
+        let service = Arc::new(
+            application::services::dav_transfer_service::DavTransferService::new(
+                config.webdav_transfers.async_threshold_mb,
+                config.webdav_transfers.result_ttl_secs,
+            )
+            .with_notifications(notification_service.clone()),
+        ) as Arc<dyn application::ports::dav_transfer_ports::DavTransferUseCase>;
+        app_state = app_state.with_dav_transfer_service(service.clone());
+        Some(service)
+    } else {
+        None
+    };
+
+    // Incremental CalDAV and CardDAV sync (sync-collection) backed by the change journal
+    if let Some(pool) = db_pool_ref {
+        app_state = app_state.with_dav_sync_service(Arc::new(application::services::dav_sync_service::DavSyncService::new(
+            Arc::new(infrastructure::repositories::pg::CalendarPgRepository::new(pool.clone())),
+            Arc::new(infrastructure::repositories::pg::CalendarEventPgRepository::new(pool.clone())),
+            Arc::new(infrastructure::repositories::pg::AddressBookPgRepository::new(pool.clone())),
+            Arc::new(infrastructure::repositories::pg::ContactPgRepository::new(pool.clone())),
+        )));
+        // Principal lookup for sharing dialogs (principal-property-search)
+        app_state = app_state.with_dav_principal_service(Arc::new(application::services::dav_principal_service::DavPrincipalService::new(
+            Arc::new(infrastructure::repositories::pg::UserPgRepository::new(pool.clone())),
+        )));
+        // iTIP scheduling between users of this server (CalDAV inbox/outbox)
+        app_state = app_state.with_scheduling_service(Arc::new(application::services::scheduling_service::SchedulingService::new(
+            Arc::new(infrastructure::repositories::pg::CalendarPgRepository::new(pool.clone())),
+            Arc::new(infrastructure::repositories::pg::CalendarEventPgRepository::new(pool.clone())),
+            Arc::new(infrastructure::repositories::pg::AddressBookPgRepository::new(pool.clone())),
+            Arc::new(infrastructure::repositories::pg::SchedulingPgRepository::new(pool.clone())),
+            Arc::new(infrastructure::repositories::pg::UserPgRepository::new(pool.clone())),
+        ).with_event_bus(event_bus.clone())));
+        // CardDAV protocol (address book collections and vCard resources); embedded
+        // contact photos are also kept in the storage backend
+        app_state = app_state.with_carddav_service(Arc::new(application::services::carddav_service::CardDavService::new(
+            Arc::new(infrastructure::repositories::pg::CalendarPgRepository::new(pool.clone())),
+            Arc::new(infrastructure::repositories::pg::AddressBookPgRepository::new(pool.clone())),
+            Arc::new(infrastructure::repositories::pg::ContactPgRepository::new(pool.clone())),
+        ).with_photo_storage(Arc::new(infrastructure::services::contact_photo_store::ContactPhotoStore::new(
+            config.storage_path.join(".contact_photos"),
+        ))).with_event_bus(event_bus.clone())));
+    }
+    
     // Wrap in Arc after all modifications
     let app_state = Arc::new(app_state);
 
     // Build application router
-    let api_routes = create_api_routes(folder_service, file_service, Some(i18n_service), trash_service, search_service, share_service, favorites_service, recent_service);
+    
+    // DAV traffic capture for diagnostics; captures are per user, so it needs auth
+    let dav_capture_service: Option<Arc<dyn application::ports::dav_capture_ports::DavCaptureUseCase>> =
+        if config.features.enable_auth && auth_services.is_some() {
+            Some(Arc::new(DavCaptureService::new(
+                config.storage_path.join(".dav_captures"),
+                DavCaptureLimits {
+                    max_minutes: config.dav_capture.max_minutes,
+                    max_body_bytes: config.dav_capture.max_body_kb * 1024,
+                    max_capture_bytes: config.dav_capture.max_capture_mb * 1024 * 1024,
+                },
+            )))
+        } else {
+            None
+        };
+    
+    // DAV clients authenticate with HTTP Basic (app passwords or, if allowed, the account password)
+    let dav_auth_service = auth_services.as_ref()
+        .filter(|_| config.features.enable_auth)
+        .map(|services| services.auth_application_service.clone());
+    
+    let api_routes = create_api_routes(folder_service, file_service.clone(), Some(i18n_service), trash_service, search_service, share_service.clone(), share_access_service, favorites_service, recent_service, Some(image_preview_service), dav_capture_service.clone(), dav_auth_service.clone(), Some(notification_service.clone()), activity_service);
     let web_routes = create_web_routes();
     
     // Build the app router
@@ -747,8 +1532,316 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         
         // Add auth routes at /api/auth
         app = app.nest("/api/auth", auth_router);
+        
+        // Admin routes only answer to users with the admin role
+        use axum::middleware::from_fn;
+        use interfaces::middleware::auth::require_admin;
+        
+        // Add admin routes at /api/admin
+        use interfaces::api::handlers::admin_handler::admin_routes;
+        app = app.nest("/api/admin", admin_routes().route_layer(from_fn(require_admin)).with_state(app_state.clone()));
+        
+        // Add user deletion with data quarantine at /api/admin/users
+        if let Some(service) = user_quarantine_service {
+            use interfaces::api::handlers::admin_handler::user_quarantine_routes;
+            app = app.nest("/api/admin/users", user_quarantine_routes().route_layer(from_fn(require_admin)).with_state(service));
+        }
+        
+        // Add user groups at /api/groups and their administration at /api/admin/groups
+        if let Some(service) = user_group_service.clone() {
+            use interfaces::api::handlers::{admin_handler, user_group_handler};
+            let service: Arc<dyn application::ports::user_group_ports::UserGroupUseCase> = service;
+            app = app.nest("/api/groups", user_group_handler::user_group_routes().with_state(service.clone()));
+            app = app.nest("/api/admin/groups", admin_handler::user_group_routes().route_layer(from_fn(require_admin)).with_state(service));
+        }
+        
+        // Add DAV capture administration at /api/admin/dav-captures
+        if let Some(service) = dav_capture_service {
+            use interfaces::api::handlers::admin_handler::dav_capture_routes;
+            app = app.nest("/api/admin/dav-captures", dav_capture_routes().route_layer(from_fn(require_admin)).with_state(service));
+        }
+        
+        // Add feature flag administration at /api/admin/feature-flags
+        if let Some(service) = feature_flags.clone() {
+            use interfaces::api::handlers::admin_handler::feature_flag_routes;
+            app = app.nest("/api/admin/feature-flags", feature_flag_routes().route_layer(from_fn(require_admin)).with_state(service));
+        }
+        
+        // Add branding administration at /api/admin/theme
+        if let Some(service) = theme_service.clone() {
+            use interfaces::api::handlers::admin_handler::theme_routes;
+            app = app.nest("/api/admin/theme", theme_routes().route_layer(from_fn(require_admin)).with_state(service));
+        }
+        
+        // Add the on-demand reset of the demo account at /api/admin/demo
+        if let Some(service) = demo_service.clone() {
+            use interfaces::api::handlers::admin_handler::demo_routes;
+            app = app.nest("/api/admin/demo", demo_routes().route_layer(from_fn(require_admin)).with_state(service));
+        }
+        
+        // Add announcement administration at /api/admin/announcements
+        if let Some(service) = announcement_service.clone() {
+            use interfaces::api::handlers::admin_handler::announcement_routes;
+            app = app.nest("/api/admin/announcements", announcement_routes().route_layer(from_fn(require_admin)).with_state(service));
+        }
+        
+        // Add storage quota administration at /api/admin/quotas
+        if let Some(service) = quota_service.clone() {
+            use interfaces::api::handlers::admin_handler::quota_routes;
+            app = app.nest("/api/admin/quotas", quota_routes().route_layer(from_fn(require_admin)).with_state(service));
+        }
+        
+        // Add document template management at /api/admin/templates
+        {
+            use interfaces::api::handlers::admin_handler::template_routes;
+            app = app.nest("/api/admin/templates", template_routes().route_layer(from_fn(require_admin)).with_state(template_service.clone()));
+        }
+        
+        // Add storage garbage collection stats at /api/admin/storage-gc
+        use interfaces::api::handlers::admin_handler::storage_gc_routes;
+        app = app.nest("/api/admin/storage-gc", storage_gc_routes().route_layer(from_fn(require_admin)).with_state(storage_gc_service.clone()));
+        
+        // Add the broken references check and repair at /api/admin/integrity
+        use interfaces::api::handlers::admin_handler::integrity_routes;
+        app = app.nest("/api/admin/integrity", integrity_routes().route_layer(from_fn(require_admin)).with_state(integrity_service.clone()));
+        
+        // Add the audit log query at /api/admin/audit-log
+        if let Some(auth) = &auth_services {
+            use interfaces::api::handlers::admin_handler::audit_log_routes;
+            let service = auth.audit_log_service.clone() as Arc<dyn application::ports::audit_ports::AuditLogUseCase>;
+            app = app.nest("/api/admin/audit-log", audit_log_routes().route_layer(from_fn(require_admin)).with_state(service));
+        }
+        
+        // Add webhook management and delivery logs at /api/admin/webhooks
+        if let Some(webhooks) = &webhook_service {
+            use interfaces::api::handlers::admin_handler::webhook_routes;
+            let service = webhooks.clone() as Arc<dyn application::ports::webhook_ports::WebhookUseCase>;
+            app = app.nest("/api/admin/webhooks", webhook_routes().route_layer(from_fn(require_admin)).with_state(service));
+        }
+        
+        // Add per-user hidden file rules at /api/settings/hidden-files
+        use interfaces::api::handlers::hidden_files_handler::hidden_files_routes;
+        app = app.nest("/api/settings/hidden-files", hidden_files_routes().with_state(hidden_file_rules.clone()));
+    }
+
+    // Add unauthenticated share metadata and email verification at /api/public/shares,
+    // oEmbed at /api/public/oembed and link previews at /s, all sharing one per-client rate limit
+    if let Some(service) = share_service {
+        use interfaces::api::handlers::share_handler::{public_share_metadata_routes, share_link_preview_routes, share_oembed_routes};
+        use interfaces::middleware::rate_limit::{rate_limit_middleware, RateLimiter};
+        let limiter = Arc::new(RateLimiter::new(
+            config.public_share.metadata_requests,
+            std::time::Duration::from_secs(config.public_share.metadata_window_secs),
+        ));
+        app = app.nest("/api/public/shares", public_share_metadata_routes()
+            .layer(axum::middleware::from_fn_with_state(limiter.clone(), rate_limit_middleware))
+            .with_state(service.clone()));
+        app = app.nest("/api/public/oembed", share_oembed_routes()
+            .layer(axum::middleware::from_fn_with_state(limiter.clone(), rate_limit_middleware))
+            .with_state(service.clone()));
+        app = app.nest("/s", share_link_preview_routes()
+            .layer(axum::middleware::from_fn_with_state(limiter, rate_limit_middleware))
+            .with_state(service));
+    }
+
+    // Add external storage routes if any mount is configured. Only signed-in users
+    // get in, each to the mounts allowed to them, and only roles that write files change them
+    if let Some(service) = external_storage_service {
+        use axum::middleware::{from_fn, from_fn_with_state};
+        use interfaces::api::handlers::external_storage_handler::external_storage_routes;
+        use interfaces::middleware::auth::{require_permission_for_writes, require_user};
+        app = app.nest("/api/external", external_storage_routes()
+            .route_layer(from_fn_with_state(domain::entities::user::Permission::WriteFiles, require_permission_for_writes))
+            .route_layer(from_fn(require_user))
+            .with_state(service as Arc<dyn application::ports::external_storage_ports::ExternalStorageUseCase>));
+    }
+    
+    // Add similar photo search if fingerprints are computed
+    if let Some(service) = duplicate_photo_service {
+        use interfaces::api::handlers::photo_handler::photo_routes;
+        app = app.nest("/api/photos", photo_routes().with_state(service));
+    }
+    
+    // Add gallery pages with preview prefetching. Only signed-in users get in,
+    // to the folders they own or that were shared with them
+    {
+        use axum::middleware::{from_fn, from_fn_with_state};
+        use interfaces::api::handlers::gallery_handler::gallery_routes;
+        use interfaces::middleware::auth::require_user;
+        use interfaces::middleware::share_access::{share_access_middleware, ShareAccess, ShareAccessRoutes};
+        let folder_access = ShareAccess { access: share_access.clone(), routes: ShareAccessRoutes::Gallery };
+        app = app.nest("/api/gallery", gallery_routes()
+            .route_layer(from_fn_with_state(folder_access, share_access_middleware))
+            .route_layer(from_fn(require_user))
+            .with_state(gallery_service));
+    }
+    
+    // Add image tagging routes if an inference service is configured
+    if let Some(service) = image_tagging_service {
+        use interfaces::api::handlers::image_tagging_handler::image_tagging_routes;
+        app = app.nest("/api/tagging", image_tagging_routes().with_state(service));
+    }
+    
+    // Add calendar and contact validation routes if the database is available
+    if let Some(service) = dav_validation_service {
+        use interfaces::api::handlers::dav_validation_handler::dav_validation_routes;
+        app = app.nest("/api/dav-validation", dav_validation_routes().with_state(service));
+    }
+    if let Some(service) = calendar_ical_service {
+        use interfaces::api::handlers::calendar_ical_handler::calendar_ical_routes;
+        app = app.nest("/api/calendars", calendar_ical_routes(Some(notification_service.clone())).with_state(service));
+    }
+    if let Some(service) = contact_import_service {
+        use interfaces::api::handlers::contact_import_handler::contact_import_routes;
+        app = app.nest("/api/address-books", contact_import_routes(Some(notification_service.clone())).with_state(service));
+    }
+    {
+        use interfaces::api::handlers::dav_multiget_handler::dav_multiget_routes;
+        app = app.nest("/api/dav", dav_multiget_routes().with_state(dav_multiget_service));
+    }
+    
+    // Add resumable upload routes if the database is available. Uploads into
+    // someone else's folder need write access through a share
+    if let Some(service) = upload_session_service {
+        use axum::middleware::from_fn_with_state;
+        use interfaces::api::handlers::upload_session_handler::{tus_routes, upload_session_routes};
+        use interfaces::middleware::share_access::{share_access_middleware, ShareAccess, ShareAccessRoutes};
+        let target_access = ShareAccess { access: share_access.clone(), routes: ShareAccessRoutes::Uploads };
+        app = app
+            .nest("/api/uploads/tus", tus_routes()
+                .layer(from_fn_with_state(target_access.clone(), share_access_middleware))
+                .with_state(service.clone()))
+            .nest("/api/uploads", upload_session_routes()
+                .layer(from_fn_with_state(target_access, share_access_middleware))
+                .with_state(service));
+    }
+    
+    // Add background ZIP export routes if the database is available
+    if let Some(service) = zip_export_service {
+        use interfaces::api::handlers::zip_export_handler::zip_export_routes;
+        app = app.nest("/api/exports", zip_export_routes().with_state(service));
+    }
+    
+    // Add the flags evaluated for the current user if the database is available
+    if let Some(service) = feature_flags {
+        use interfaces::api::handlers::feature_flag_handler::feature_flag_routes;
+        app = app.nest("/api/features", feature_flag_routes().with_state(service));
+    }
+    
+    // Add tags and their assignments if the database is available
+    if let Some(service) = tag_service {
+        use interfaces::api::handlers::tag_handler::tag_routes;
+        app = app.nest("/api/tags", tag_routes().with_state(service));
+    }
+    
+    // Add comments on files and folders if the database is available. Only
+    // signed-in users get in; the service checks their access to each item
+    if let Some(service) = comment_service {
+        use axum::middleware::from_fn;
+        use interfaces::api::handlers::comment_handler::comment_routes;
+        use interfaces::middleware::auth::require_user;
+        app = app.nest("/api/comments", comment_routes().route_layer(from_fn(require_user)).with_state(service));
+    }
+    
+    // Add the sync conflict dashboard if the database is available
+    if let Some(service) = sync_conflict_service {
+        use interfaces::api::handlers::conflict_handler::conflict_routes;
+        app = app.nest("/api/conflicts", conflict_routes().with_state(service));
+    }
+    
+    // Add the announcements for the current user if the database is available
+    if let Some(service) = announcement_service {
+        use interfaces::api::handlers::announcement_handler::announcement_routes;
+        app = app.nest("/api/announcements", announcement_routes().with_state(service));
+    }
+    
+    // Add invitations and replies between users if the database is available
+    if let Some(service) = app_state.scheduling_service.clone() {
+        use interfaces::api::handlers::scheduling_handler::scheduling_routes;
+        app = app.nest("/api/scheduling", scheduling_routes().with_state(service));
+    }
+    
+    // Public demo credentials for the login page when demo mode is enabled
+    if let Some(service) = demo_service {
+        use interfaces::api::handlers::demo_handler::demo_routes;
+        app = app.nest("/api/demo", demo_routes().with_state(service));
+    }
+    
+    // Public instance branding for the web UI and the login page
+    if let Some(service) = theme_service {
+        use interfaces::api::handlers::theme_handler::theme_routes;
+        app = app.nest("/api/theme", theme_routes().with_state(service));
     }
 
+    // Who has each file open, so the UI can warn before conflicting edits
+    {
+        use interfaces::api::handlers::presence_handler::presence_routes;
+        let service = Arc::new(application::services::presence_service::PresenceService::new(
+            file_service.clone(),
+            share_access.clone(),
+        ));
+        service.clone().start_expiry_job();
+        app = app.nest("/api/presence", presence_routes().with_state(service as Arc<dyn application::ports::presence_ports::PresenceUseCase>));
+    }
+
+    // Live updates of files, shares and calendars over a WebSocket
+    {
+        use interfaces::api::handlers::ws_handler::{ws_routes, WsState};
+        app = app.nest("/ws", ws_routes().with_state(WsState {
+            events: realtime_event_service.clone(),
+            auth: dav_auth_service.clone(),
+        }));
+    }
+
+    // Templates of the "New document" menu and document creation from them
+    {
+        use interfaces::api::handlers::template_handler::{template_routes, new_from_template};
+        app = app
+            .nest("/api/templates", template_routes().with_state(template_service.clone()))
+            .route("/api/files/new-from-template", axum::routing::post(new_from_template).with_state(template_service));
+    }
+
+    // Notification center with the failures of ZIP downloads, imports and exports
+    // and the files, folders, calendars and address books shared with the user
+    {
+        use interfaces::api::handlers::notification_handler::notification_routes;
+        app = app.nest("/api/notifications", notification_routes().with_state(notification_service));
+    }
+
+    // Emails each user wants to receive
+    if let Some(service) = email_notification_service {
+        use interfaces::api::handlers::notification_preferences_handler::notification_preferences_routes;
+        let service = service as Arc<dyn application::ports::email_notification_ports::NotificationPreferencesUseCase>;
+        app = app.nest("/api/notification-preferences", notification_preferences_routes().with_state(service));
+    }
+
+    // Undo of recent deletes, moves and renames
+    if let Some(service) = undo_service {
+        use interfaces::api::handlers::undo_handler::undo_routes;
+        app = app.nest("/api/undo", undo_routes().with_state(service));
+    }
+
+    // Status of WebDAV COPY and MOVE requests running in the background
+    if let Some(service) = dav_transfer_service {
+        use interfaces::api::handlers::dav_transfer_handler::{dav_transfer_routes, DAV_TRANSFER_ROUTE};
+        app = app.nest(DAV_TRANSFER_ROUTE, dav_transfer_routes().with_state(service));
+    }
+
+    // DAV service discovery for clients configured with only the server name
+    use interfaces::api::handlers::well_known_handler::well_known_routes;
+    app = app.merge(well_known_routes());
+
+    // Public instance capabilities for clients (no authentication required)
+    use interfaces::api::handlers::capabilities_handler::capabilities_routes;
+    app = app.nest("/api/capabilities", capabilities_routes().with_state(app_state.clone()));
+    
+    // Mount the plugins compiled into this build and publish their manifest
+    let plugins = Arc::new(interfaces::plugins::compiled_plugins());
+    app = plugins.mount(app, &app_state);
+    plugins.start_jobs(&app_state);
+    use interfaces::api::handlers::plugin_handler::plugin_routes;
+    app = app.nest(interfaces::plugins::PLUGIN_ROUTE_PREFIX, plugin_routes().with_state(plugins));
+
     // Preload common directories to warm the cache
     tracing::info!("Preloading common directories to warm up cache...");
     if let Ok(count) = metadata_cache.preload_directory(&storage_path, true, 1).await {
@@ -756,7 +1849,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
     
     // Start server with clear message
-    let addr = SocketAddr::from(([0, 0, 0, 0], 8086));
+    let addr = SocketAddr::from(([0, 0, 0, 0], config.server_port));
     tracing::info!("Starting OxiCloud server on http://{}", addr);
     
     // Start the server
@@ -768,6 +1861,25 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Apply the redirect middleware to handle legacy routes
     app = app.layer(axum::middleware::from_fn(redirect_middleware));
     
+    // Changes made through the admin routes, and attempts to, go to the audit log;
+    // the layer sits inside the credentials one so it knows who made them
+    if let Some(auth) = &auth_services {
+        use interfaces::middleware::audit::admin_audit_middleware;
+        let audit_log = auth.audit_log_service.clone() as Arc<dyn application::ports::audit_ports::AuditLogPort>;
+        app = app.layer(axum::middleware::from_fn_with_state(audit_log, admin_audit_middleware));
+    }
+    
+    // Every API route, not only the DAV ones, accepts app passwords over HTTP Basic
+    if let Some(service) = dav_auth_service {
+        use interfaces::middleware::auth::api_credentials_middleware;
+        app = app.layer(axum::middleware::from_fn_with_state(service, api_credentials_middleware));
+    }
+    
+    // Limit request bodies to the configured maximum upload size
+    app = app.layer(axum::extract::DefaultBodyLimit::max(
+        config.resources.max_upload_size_bytes() as usize
+    ));
+    
     // Create a standard TCP listener
     let listener = tokio::net::TcpListener::bind(addr).await?;
     tracing::info!("Server binding to http://{}", addr);
@@ -781,8 +1893,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Add global state to the router
     let app = app.with_state(app_state_inner);
     
+    // Negotiate the API version around the router, since /api/v1 paths are rewritten before routing
+    use interfaces::middleware::api_version::{api_version_middleware, ApiVersionPolicy};
+    use tower::Layer;
+    let api_version_policy = Arc::new(ApiVersionPolicy::new(config.api.legacy_sunset));
+    let app = axum::middleware::from_fn_with_state(api_version_policy, api_version_middleware).layer(app);
+    
     // Use axum's serve function with the router with state
-    axum::serve(listener, app).await?;
+    axum::serve(listener, axum::ServiceExt::<axum::extract::Request>::into_make_service_with_connect_info::<std::net::SocketAddr>(app)).await?;
     
     tracing::info!("Server shutdown completed");
     
@@ -180,6 +180,14 @@ function setupEventListeners() {
         }
     });
     
+    // New document button; delegated because the actions bar is re-rendered
+    document.addEventListener('click', (e) => {
+        const button = e.target.closest('#new-document-btn');
+        if (button) {
+            fileOps.showNewDocumentMenu(button);
+        }
+    });
+    
     // New folder button
     elements.newFolderBtn.addEventListener('click', () => {
         const folderName = prompt(window.i18n ? window.i18n.t('dialogs.new_name') : 'Nombre de la carpeta:');
@@ -304,6 +312,9 @@ function setupEventListeners() {
                         <button class="btn btn-secondary" id="new-folder-btn">
                             <i class="fas fa-folder-plus" style="margin-right: 5px;"></i> <span data-i18n="actions.new_folder">Nueva carpeta</span>
                         </button>
+                        <button class="btn btn-secondary" id="new-document-btn">
+                            <i class="fas fa-file-medical" style="margin-right: 5px;"></i> <span data-i18n="actions.new_document">Nuevo documento</span>
+                        </button>
                     </div>
                     <div class="view-toggle">
                         <button class="toggle-btn active" id="grid-view-btn" title="Vista de cuadrícula">
@@ -879,6 +890,9 @@ function switchToFilesView() {
             <button class="btn btn-secondary" id="new-folder-btn">
                 <i class="fas fa-folder-plus" style="margin-right: 5px;"></i> <span data-i18n="actions.new_folder">Nueva carpeta</span>
             </button>
+            <button class="btn btn-secondary" id="new-document-btn">
+                <i class="fas fa-file-medical" style="margin-right: 5px;"></i> <span data-i18n="actions.new_document">Nuevo documento</span>
+            </button>
         </div>
         <div class="view-toggle">
             <button class="toggle-btn active" id="grid-view-btn" title="Vista de cuadrícula">
@@ -9,6 +9,7 @@ const LOGIN_ENDPOINT = `${API_URL}/login`;
 const REGISTER_ENDPOINT = `${API_URL}/register`;
 const ME_ENDPOINT = `${API_URL}/me`;
 const REFRESH_ENDPOINT = `${API_URL}/refresh`;
+const OIDC_CONFIG_ENDPOINT = `${API_URL}/oidc/config`;
 
 // Storage keys
 const TOKEN_KEY = 'oxicloud_token';
@@ -146,6 +147,12 @@ document.addEventListener('DOMContentLoaded', () => {
     
     (async () => {
     try {
+        // Coming back from the identity provider
+        if (await handleOidcCallback()) {
+            return;
+        }
+        initOidcButton();
+        
         // First check if the token is valid
         const token = localStorage.getItem(TOKEN_KEY);
         const tokenExpiry = localStorage.getItem(TOKEN_EXPIRY_KEY);
@@ -500,6 +507,70 @@ async function register(username, email, password, role = 'user') {
 /**
  * Fetch current user data
  */
+/**
+ * Show the single sign-on button if an identity provider is configured
+ */
+async function initOidcButton() {
+    const button = document.getElementById('oidc-login');
+    if (!button) return;
+    
+    try {
+        const response = await fetch(OIDC_CONFIG_ENDPOINT);
+        if (!response.ok) return;
+        
+        const config = await response.json();
+        if (config.enabled) {
+            button.textContent = `Iniciar sesión con ${config.provider_name}`;
+            button.style.display = 'block';
+        }
+    } catch (error) {
+        console.error('Error checking single sign-on:', error);
+    }
+}
+
+/**
+ * Store the tokens the identity provider callback leaves in the URL fragment.
+ * Returns true if the page was handling a callback.
+ */
+async function handleOidcCallback() {
+    const params = new URLSearchParams(window.location.hash.substring(1));
+    if (!params.has('access_token') && !params.has('oidc_error')) {
+        return false;
+    }
+    // Do not leave the tokens in the address bar or the history
+    history.replaceState(null, '', window.location.pathname);
+    
+    const error = params.get('oidc_error');
+    if (error) {
+        loginError.textContent = error;
+        loginError.style.display = 'block';
+        initOidcButton();
+        return true;
+    }
+    
+    const token = params.get('access_token');
+    localStorage.setItem(TOKEN_KEY, token);
+    localStorage.setItem(REFRESH_TOKEN_KEY, params.get('refresh_token') || '');
+    
+    const expiryTime = new Date();
+    expiryTime.setSeconds(expiryTime.getSeconds() + parseInt(params.get('expires_in') || '0'));
+    localStorage.setItem(TOKEN_EXPIRY_KEY, expiryTime.toISOString());
+    
+    try {
+        const userData = await fetchUserData(token);
+        localStorage.setItem(USER_DATA_KEY, JSON.stringify(userData));
+        sessionStorage.removeItem('redirect_count');
+        redirectToMainApp();
+    } catch (error) {
+        localStorage.removeItem(TOKEN_KEY);
+        localStorage.removeItem(REFRESH_TOKEN_KEY);
+        localStorage.removeItem(TOKEN_EXPIRY_KEY);
+        loginError.textContent = error.message;
+        loginError.style.display = 'block';
+    }
+    return true;
+}
+
 async function fetchUserData(token) {
     try {
         const response = await fetch(ME_ENDPOINT, {
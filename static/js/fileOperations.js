@@ -179,6 +179,93 @@ const fileOps = {
         }
     },
 
+    /**
+     * Show the templates of the "New document" menu below a button
+     * @param {HTMLElement} anchor - Button the menu belongs to
+     */
+    async showNewDocumentMenu(anchor) {
+        document.getElementById('new-document-menu')?.remove();
+
+        let templates = [];
+        try {
+            const response = await fetch('/api/templates');
+            if (response.ok) {
+                templates = await response.json();
+            }
+        } catch (error) {
+            console.error('Error loading templates:', error);
+        }
+
+        const menu = document.createElement('div');
+        menu.id = 'new-document-menu';
+        menu.className = 'context-menu';
+
+        if (templates.length === 0) {
+            const empty = document.createElement('div');
+            empty.className = 'context-menu-item';
+            empty.textContent = 'No hay plantillas disponibles';
+            menu.appendChild(empty);
+        }
+        templates.forEach(template => {
+            const item = document.createElement('div');
+            item.className = 'context-menu-item';
+            const icon = document.createElement('i');
+            icon.className = template.scope === 'user' ? 'fas fa-user' : 'fas fa-file-alt';
+            item.appendChild(icon);
+            item.appendChild(document.createTextNode(template.name));
+            item.addEventListener('click', () => {
+                menu.remove();
+                this.createFromTemplate(template);
+            });
+            menu.appendChild(item);
+        });
+
+        const rect = anchor.getBoundingClientRect();
+        menu.style.left = `${rect.left + window.scrollX}px`;
+        menu.style.top = `${rect.bottom + window.scrollY + 4}px`;
+        menu.style.display = 'block';
+        document.body.appendChild(menu);
+
+        // Close the menu on the next click anywhere else
+        setTimeout(() => document.addEventListener('click', () => menu.remove(), { once: true }));
+    },
+
+    /**
+     * Create a document in the current folder from a template
+     * @param {Object} template - Template as listed by /api/templates
+     */
+    async createFromTemplate(template) {
+        const baseName = template.name.replace(/\.[^.]+$/, '');
+        const name = prompt(window.i18n ? window.i18n.t('dialogs.new_name') : 'Nombre del documento:', baseName);
+        if (!name) return;
+
+        try {
+            const response = await fetch('/api/files/new-from-template', {
+                method: 'POST',
+                headers: {
+                    'Content-Type': 'application/json'
+                },
+                body: JSON.stringify({
+                    template_id: template.id,
+                    folder_id: window.app.currentPath || null,
+                    name: name
+                })
+            });
+
+            if (response.ok) {
+                const file = await response.json();
+                await window.loadFiles({forceRefresh: true});
+                window.ui.showNotification('Documento creado', `"${file.name}" creado correctamente`);
+            } else {
+                console.error('Create from template error:', await response.text());
+                window.ui.showNotification('Error', 'Error al crear el documento');
+            }
+        } catch (error) {
+            console.error('Error creating document from template:', error);
+            window.ui.showNotification('Error', 'Error al crear el documento');
+        }
+    },
+
     /**
      * Move a file to another folder
      * @param {string} fileId - File ID
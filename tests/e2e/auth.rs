@@ -0,0 +1,35 @@
+use serde_json::json;
+
+use crate::harness::{TestServer, TEST_PASSWORD};
+
+#[tokio::test]
+#[ignore = "needs Docker or OXICLOUD_TEST_DATABASE_URL"]
+async fn users_can_only_log_in_with_their_own_credentials() {
+    let server = TestServer::start().await;
+    let alice = server.create_user("alice").await;
+    let bob = server.create_user("bob").await;
+    assert_ne!(alice.id, bob.id);
+
+    let client = server.anonymous();
+    assert!(client.login("alice", TEST_PASSWORD).await.status().is_success());
+    assert!(client.login("alice", "not-alice's-password").await.status().is_client_error());
+    assert!(client.login("alice", &format!("{} ", TEST_PASSWORD)).await.status().is_client_error());
+    assert!(client.login("carol", TEST_PASSWORD).await.status().is_client_error());
+}
+
+#[tokio::test]
+#[ignore = "needs Docker or OXICLOUD_TEST_DATABASE_URL"]
+async fn usernames_cannot_be_registered_twice() {
+    let server = TestServer::start().await;
+    server.create_user("alice").await;
+
+    let response = server
+        .anonymous()
+        .post_json("/api/auth/register", &json!({
+            "username": "alice",
+            "email": "another-alice@example.com",
+            "password": TEST_PASSWORD,
+        }))
+        .await;
+    assert!(response.status().is_client_error(), "duplicate registration returned {}", response.status());
+}
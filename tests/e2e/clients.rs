@@ -0,0 +1,133 @@
+use reqwest::{Method, RequestBuilder, Response};
+use serde::Serialize;
+
+/// Client for the REST API
+pub struct ApiClient {
+    http: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+impl ApiClient {
+    pub fn new(base_url: &str, token: Option<&str>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.to_string(),
+            token: token.map(str::to_string),
+        }
+    }
+
+    pub fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let request = self.http.request(method, format!("{}{}", self.base_url, path));
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    pub async fn get(&self, path: &str) -> Response {
+        self.request(Method::GET, path).send().await.expect("request failed")
+    }
+
+    pub async fn delete(&self, path: &str) -> Response {
+        self.request(Method::DELETE, path).send().await.expect("request failed")
+    }
+
+    pub async fn post_json<T: Serialize + ?Sized>(&self, path: &str, body: &T) -> Response {
+        self.request(Method::POST, path).json(body).send().await.expect("request failed")
+    }
+
+    pub async fn put_json<T: Serialize + ?Sized>(&self, path: &str, body: &T) -> Response {
+        self.request(Method::PUT, path).json(body).send().await.expect("request failed")
+    }
+
+    pub async fn login(&self, username: &str, password: &str) -> Response {
+        self.post_json("/api/auth/login", &serde_json::json!({
+            "username": username,
+            "password": password,
+        }))
+        .await
+    }
+
+    /// Uploads a file through the multipart endpoint
+    pub async fn upload_file(&self, folder_id: Option<&str>, name: &str, content: &[u8], mime_type: &str) -> Response {
+        let part = reqwest::multipart::Part::bytes(content.to_vec())
+            .file_name(name.to_string())
+            .mime_str(mime_type)
+            .expect("invalid MIME type");
+        let mut form = reqwest::multipart::Form::new().part("file", part);
+        if let Some(folder_id) = folder_id {
+            form = form.text("folder_id", folder_id.to_string());
+        }
+        self.request(Method::POST, "/api/files/upload").multipart(form).send().await.expect("request failed")
+    }
+}
+
+/// Client for a DAV tree (WebDAV, CalDAV or CardDAV), addressed by path
+/// relative to its root
+pub struct DavClient {
+    http: reqwest::Client,
+    root_url: String,
+    token: String,
+}
+
+impl DavClient {
+    pub fn new(root_url: String, token: &str) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            root_url,
+            token: token.to_string(),
+        }
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.root_url, path.trim_start_matches('/'))
+    }
+
+    pub fn request(&self, method: &str, path: &str) -> RequestBuilder {
+        let method = Method::from_bytes(method.as_bytes()).expect("invalid HTTP method");
+        self.http.request(method, self.url(path)).bearer_auth(&self.token)
+    }
+
+    pub async fn get(&self, path: &str) -> Response {
+        self.request("GET", path).send().await.expect("request failed")
+    }
+
+    pub async fn put(&self, path: &str, content: &[u8], content_type: &str) -> Response {
+        self.request("PUT", path)
+            .header("Content-Type", content_type)
+            .body(content.to_vec())
+            .send()
+            .await
+            .expect("request failed")
+    }
+
+    pub async fn delete(&self, path: &str) -> Response {
+        self.request("DELETE", path).send().await.expect("request failed")
+    }
+
+    pub async fn mkcol(&self, path: &str) -> Response {
+        self.request("MKCOL", path).send().await.expect("request failed")
+    }
+
+    pub async fn propfind(&self, path: &str, depth: &str) -> Response {
+        self.request("PROPFIND", path)
+            .header("Depth", depth)
+            .header("Content-Type", "application/xml; charset=utf-8")
+            .body(r#"<?xml version="1.0" encoding="utf-8"?><D:propfind xmlns:D="DAV:"><D:allprop/></D:propfind>"#)
+            .send()
+            .await
+            .expect("request failed")
+    }
+
+    /// Sends a REPORT such as calendar-query, addressbook-query or sync-collection
+    pub async fn report(&self, path: &str, depth: &str, body: &str) -> Response {
+        self.request("REPORT", path)
+            .header("Depth", depth)
+            .header("Content-Type", "application/xml; charset=utf-8")
+            .body(body.to_string())
+            .send()
+            .await
+            .expect("request failed")
+    }
+}
@@ -0,0 +1,172 @@
+use reqwest::StatusCode;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::harness::{TestServer, TestUser};
+
+/// sync-collection REPORT asking for every event of a calendar
+const INITIAL_SYNC: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<D:sync-collection xmlns:D="DAV:">
+  <D:sync-token/>
+  <D:sync-level>1</D:sync-level>
+  <D:prop><D:getetag/></D:prop>
+</D:sync-collection>"#;
+
+const EVENT: &str = "BEGIN:VCALENDAR\r\n\
+VERSION:2.0\r\n\
+PRODID:-//OxiCloud//e2e//EN\r\n\
+BEGIN:VEVENT\r\n\
+UID:e2e-standup\r\n\
+DTSTAMP:20250101T090000Z\r\n\
+DTSTART:20250102T090000Z\r\n\
+DTEND:20250102T091500Z\r\n\
+SUMMARY:Standup\r\n\
+END:VEVENT\r\n\
+END:VCALENDAR\r\n";
+
+const VCARD: &str = "BEGIN:VCARD\r\n\
+VERSION:3.0\r\n\
+UID:e2e-carol\r\n\
+FN:Carol Example\r\n\
+EMAIL:carol@example.com\r\n\
+END:VCARD\r\n";
+
+fn is_denied(status: StatusCode) -> bool {
+    matches!(status, StatusCode::NOT_FOUND | StatusCode::FORBIDDEN)
+}
+
+/// Calendars and address books have no creation endpoint yet, so the tests
+/// insert them straight into the database
+async fn create_collection(server: &TestServer, table: &str, owner: &TestUser, name: &str) -> String {
+    let id = Uuid::new_v4().to_string();
+    let mut db = server.database().await;
+    sqlx::query(&format!("INSERT INTO {} (id, name, owner_id) VALUES ($1::uuid, $2, $3)", table))
+        .bind(&id)
+        .bind(name)
+        .bind(&owner.id)
+        .execute(&mut db)
+        .await
+        .unwrap_or_else(|e| panic!("cannot create {} in {}: {}", name, table, e));
+    id
+}
+
+#[tokio::test]
+#[ignore = "needs Docker or OXICLOUD_TEST_DATABASE_URL"]
+async fn webdav_put_then_get_returns_the_same_content() {
+    let server = TestServer::start().await;
+    let alice = server.create_user("alice").await;
+    let dav = server.webdav(&alice);
+    let path = format!("{}/notes.txt", alice.home());
+
+    let response = dav.put(&path, b"written over WebDAV", "text/plain").await;
+    assert!(response.status().is_success(), "PUT returned {}", response.status());
+
+    let response = dav.get(&path).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.bytes().await.unwrap().as_ref(), b"written over WebDAV");
+
+    let response = dav.propfind(&alice.home(), "1").await;
+    assert_eq!(response.status(), StatusCode::MULTI_STATUS);
+    assert!(response.text().await.unwrap().contains("notes.txt"));
+
+    // The file is the same one the REST API lists
+    let home_id = server.home_folder_id(&alice).await;
+    let response = server.api(&alice).get(&format!("/api/files?folder_id={}", home_id)).await;
+    let files: Vec<Value> = response.json().await.unwrap();
+    assert!(files.iter().any(|file| file["name"] == "notes.txt"));
+}
+
+#[tokio::test]
+#[ignore = "needs Docker or OXICLOUD_TEST_DATABASE_URL"]
+async fn webdav_users_do_not_see_each_others_files() {
+    let server = TestServer::start().await;
+    let alice = server.create_user("alice").await;
+    let bob = server.create_user("bob").await;
+    let path = format!("{}/alice-only.txt", alice.home());
+
+    let response = server.webdav(&alice).put(&path, b"private", "text/plain").await;
+    assert!(response.status().is_success(), "PUT returned {}", response.status());
+
+    let bob_dav = server.webdav(&bob);
+    let status = bob_dav.get(&path).await.status();
+    assert!(is_denied(status), "bob read alice's file: {}", status);
+    let status = bob_dav.propfind(&alice.home(), "1").await.status();
+    assert!(is_denied(status), "bob listed alice's home folder: {}", status);
+    let status = bob_dav.put(&path, b"overwritten", "text/plain").await.status();
+    assert!(is_denied(status), "bob overwrote alice's file: {}", status);
+    let status = bob_dav.put(&format!("{}/planted.txt", alice.home()), b"planted", "text/plain").await.status();
+    assert!(is_denied(status), "bob wrote into alice's home folder: {}", status);
+    let status = bob_dav.delete(&path).await.status();
+    assert!(is_denied(status), "bob deleted alice's file: {}", status);
+
+    let response = server.webdav(&alice).get(&path).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.bytes().await.unwrap().as_ref(), b"private");
+}
+
+#[tokio::test]
+#[ignore = "needs Docker or OXICLOUD_TEST_DATABASE_URL"]
+async fn caldav_syncs_imported_events_to_their_owner_only() {
+    let server = TestServer::start().await;
+    let alice = server.create_user("alice").await;
+    let bob = server.create_user("bob").await;
+    let calendar_id = create_collection(&server, "caldav.calendars", &alice, "Work").await;
+
+    let response = server
+        .api(&alice)
+        .request(reqwest::Method::POST, &format!("/api/calendars/{}/import", calendar_id))
+        .header("Content-Type", "text/calendar")
+        .body(EVENT)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let result: Value = response.json().await.unwrap();
+    assert_eq!(result["imported"], 1);
+
+    let response = server.caldav(&alice).report(&format!("{}/", calendar_id), "1", INITIAL_SYNC).await;
+    assert_eq!(response.status(), StatusCode::MULTI_STATUS);
+    let xml = response.text().await.unwrap();
+    assert!(xml.contains(&format!("/api/caldav/{}/e2e-standup.ics", calendar_id)), "event missing from {}", xml);
+    assert!(xml.contains("<D:sync-token>"));
+
+    let status = server.caldav(&bob).report(&format!("{}/", calendar_id), "1", INITIAL_SYNC).await.status();
+    assert!(is_denied(status), "bob synced alice's calendar: {}", status);
+}
+
+#[tokio::test]
+#[ignore = "needs Docker or OXICLOUD_TEST_DATABASE_URL"]
+async fn carddav_put_then_get_returns_the_contact_to_its_owner_only() {
+    let server = TestServer::start().await;
+    let alice = server.create_user("alice").await;
+    let bob = server.create_user("bob").await;
+    let address_book_id = create_collection(&server, "carddav.address_books", &alice, "Friends").await;
+    let resource = format!("addressbooks/{}/e2e-carol.vcf", address_book_id);
+    let dav = server.carddav(&alice);
+
+    let response = dav.put(&resource, VCARD.as_bytes(), "text/vcard; charset=utf-8").await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let etag = response.headers()["etag"].to_str().unwrap().to_string();
+
+    let response = dav.get(&resource).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["etag"].to_str().unwrap(), etag);
+    let vcard = response.text().await.unwrap();
+    assert!(vcard.contains("FN:Carol Example"), "unexpected vCard {}", vcard);
+
+    let response = dav.propfind(&format!("addressbooks/{}/", address_book_id), "1").await;
+    assert_eq!(response.status(), StatusCode::MULTI_STATUS);
+    assert!(response.text().await.unwrap().contains("e2e-carol.vcf"));
+
+    let bob_dav = server.carddav(&bob);
+    let status = bob_dav.get(&resource).await.status();
+    assert!(is_denied(status), "bob read alice's contact: {}", status);
+    let status = bob_dav.put(&resource, VCARD.as_bytes(), "text/vcard; charset=utf-8").await.status();
+    assert!(is_denied(status), "bob overwrote alice's contact: {}", status);
+    let status = bob_dav.delete(&resource).await.status();
+    assert!(is_denied(status), "bob deleted alice's contact: {}", status);
+
+    let response = dav.delete(&resource).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(dav.get(&resource).await.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,159 @@
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+use crate::harness::TestServer;
+
+#[tokio::test]
+#[ignore = "needs Docker or OXICLOUD_TEST_DATABASE_URL"]
+async fn uploaded_file_downloads_unchanged() {
+    let server = TestServer::start().await;
+    let alice = server.create_user("alice").await;
+    let api = server.api(&alice);
+
+    let content: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+    let response = api.upload_file(None, "data.bin", &content, "application/octet-stream").await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let file: Value = response.json().await.unwrap();
+    let id = file["id"].as_str().unwrap();
+
+    let response = api.get(&format!("/api/files/{}?compress=false", id)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.bytes().await.unwrap().as_ref(), content.as_slice());
+
+    // Byte ranges are served straight from storage
+    let response = api
+        .request(reqwest::Method::GET, &format!("/api/files/{}", id))
+        .header("Range", "bytes=1000-1999")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(response.bytes().await.unwrap().as_ref(), &content[1000..2000]);
+}
+
+#[tokio::test]
+#[ignore = "needs Docker or OXICLOUD_TEST_DATABASE_URL"]
+async fn created_folder_holds_uploaded_files() {
+    let server = TestServer::start().await;
+    let alice = server.create_user("alice").await;
+    let api = server.api(&alice);
+
+    let response = api.post_json("/api/folders", &json!({ "name": "Reports", "parent_id": null })).await;
+    assert!(response.status().is_success(), "creating a folder returned {}", response.status());
+    let folder: Value = response.json().await.unwrap();
+    let folder_id = folder["id"].as_str().unwrap();
+
+    let response = api.upload_file(Some(folder_id), "q1.txt", b"first quarter", "text/plain").await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response = api.get(&format!("/api/files?folder_id={}", folder_id)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let files: Vec<Value> = response.json().await.unwrap();
+    assert!(files.iter().any(|file| file["name"] == "q1.txt"));
+}
+
+#[tokio::test]
+#[ignore = "needs Docker or OXICLOUD_TEST_DATABASE_URL"]
+async fn folder_downloads_as_a_streamed_zip() {
+    let server = TestServer::start().await;
+    let alice = server.create_user("alice").await;
+    let api = server.api(&alice);
+
+    let response = api.post_json("/api/folders", &json!({ "name": "Project", "parent_id": null })).await;
+    assert!(response.status().is_success(), "creating a folder returned {}", response.status());
+    let folder: Value = response.json().await.unwrap();
+    let folder_id = folder["id"].as_str().unwrap();
+    let response = api.post_json("/api/folders", &json!({ "name": "docs", "parent_id": folder_id })).await;
+    assert!(response.status().is_success(), "creating a subfolder returned {}", response.status());
+    let subfolder: Value = response.json().await.unwrap();
+
+    let large: Vec<u8> = (0..300_000u32).map(|i| (i % 251) as u8).collect();
+    let response = api.upload_file(Some(folder_id), "data.bin", &large, "application/octet-stream").await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let response = api.upload_file(subfolder["id"].as_str(), "readme.txt", b"read me", "text/plain").await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response = api.get(&format!("/api/folders/{}/download", folder_id)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "application/zip");
+    let archive = response.bytes().await.unwrap().to_vec();
+
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(archive)).unwrap();
+    let mut read = |name: &str| {
+        let mut content = Vec::new();
+        std::io::Read::read_to_end(&mut archive.by_name(name).unwrap(), &mut content).unwrap();
+        content
+    };
+    assert_eq!(read("Project/data.bin"), large);
+    assert_eq!(read("Project/docs/readme.txt"), b"read me");
+}
+
+#[tokio::test]
+#[ignore = "needs Docker or OXICLOUD_TEST_DATABASE_URL"]
+async fn patch_overwrites_a_byte_range_in_place() {
+    let server = TestServer::start().await;
+    let alice = server.create_user("alice").await;
+    let api = server.api(&alice);
+
+    let mut content: Vec<u8> = (0..100_000u32).map(|i| (i % 251) as u8).collect();
+    let response = api.upload_file(None, "disk.img", &content, "application/octet-stream").await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let file: Value = response.json().await.unwrap();
+    let id = file["id"].as_str().unwrap();
+
+    // Overwrite a block in the middle and extend past the end
+    let patch = |range: &str, body: Vec<u8>| {
+        api.request(reqwest::Method::PATCH, &format!("/api/files/{}", id))
+            .header("Content-Range", range.to_string())
+            .body(body)
+            .send()
+    };
+    let response = patch("bytes 5000-5009/100000", vec![0xAA; 10]).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    content[5000..5010].fill(0xAA);
+
+    let response = patch("bytes 99995-100004/*", vec![0xBB; 10]).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let file: Value = response.json().await.unwrap();
+    assert_eq!(file["size"], 100_005);
+    content.truncate(99_995);
+    content.extend_from_slice(&[0xBB; 10]);
+
+    // Ranges that leave a gap or do not match the body are rejected
+    let response = patch("bytes 200000-200009/*", vec![0; 10]).await.unwrap();
+    assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+    let response = patch("bytes 0-9/*", vec![0; 4]).await.unwrap();
+    assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+
+    let response = api.get(&format!("/api/files/{}?compress=false", id)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.bytes().await.unwrap().as_ref(), content.as_slice());
+}
+
+#[tokio::test]
+#[ignore = "needs Docker or OXICLOUD_TEST_DATABASE_URL"]
+async fn users_cannot_reach_each_others_files() {
+    let server = TestServer::start().await;
+    let alice = server.create_user("alice").await;
+    let bob = server.create_user("bob").await;
+    let home_id = server.home_folder_id(&alice).await;
+
+    let response = server.api(&alice).upload_file(Some(&home_id), "diary.txt", b"dear diary", "text/plain").await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let file: Value = response.json().await.unwrap();
+    let id = file["id"].as_str().unwrap();
+
+    let api = server.api(&bob);
+    assert_eq!(api.get(&format!("/api/files/{}", id)).await.status(), StatusCode::FORBIDDEN);
+    assert_eq!(api.get(&format!("/api/files?folder_id={}", home_id)).await.status(), StatusCode::FORBIDDEN);
+    assert_eq!(api.get(&format!("/api/folders/{}/download", home_id)).await.status(), StatusCode::FORBIDDEN);
+    assert_eq!(api.delete(&format!("/api/files/{}", id)).await.status(), StatusCode::FORBIDDEN);
+    let response = api.upload_file(Some(&home_id), "planted.txt", b"planted", "text/plain").await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = api.post_json("/api/folders", &json!({ "name": "planted", "parent_id": home_id })).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = server.api(&alice).get(&format!("/api/files/{}?compress=false", id)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.bytes().await.unwrap().as_ref(), b"dear diary");
+}
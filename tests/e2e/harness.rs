@@ -0,0 +1,266 @@
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+use sqlx::{Connection, Executor, PgConnection};
+use tempfile::TempDir;
+use testcontainers_modules::postgres::Postgres;
+use testcontainers_modules::testcontainers::runners::AsyncRunner;
+use testcontainers_modules::testcontainers::{ContainerAsync, ImageExt};
+use tokio::process::{Child, Command};
+use uuid::Uuid;
+
+use crate::clients::{ApiClient, DavClient};
+
+/// Postgres server to create the ephemeral databases on instead of a container
+const DATABASE_URL_VAR: &str = "OXICLOUD_TEST_DATABASE_URL";
+
+/// Image tag of the Postgres container started for each test
+const POSTGRES_TAG: &str = "15-alpine";
+
+/// Tracing filter for the server output; the server is silent when unset
+const LOG_VAR: &str = "OXICLOUD_TEST_LOG";
+
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Password used for every user created by the tests
+pub const TEST_PASSWORD: &str = "E2e-Passw0rd!";
+
+/// Home folders are named after their user with this prefix
+const HOME_FOLDER_PREFIX: &str = "Mi Carpeta - ";
+
+/// Postgres server the test database lives on
+pub struct TestPostgres {
+    admin_url: String,
+    _container: Option<ContainerAsync<Postgres>>,
+}
+
+impl TestPostgres {
+    /// Uses the server given in `OXICLOUD_TEST_DATABASE_URL`, or starts a
+    /// throwaway container. Panics when neither is available
+    pub async fn start() -> Self {
+        if let Ok(admin_url) = std::env::var(DATABASE_URL_VAR) {
+            return Self { admin_url, _container: None };
+        }
+
+        let container = Postgres::default().with_tag(POSTGRES_TAG).start().await.unwrap_or_else(|e| {
+            panic!("end-to-end tests need Docker or {}, and no Postgres container could be started: {}", DATABASE_URL_VAR, e)
+        });
+        let host = container.get_host().await.expect("cannot get the Postgres container host");
+        let port = container.get_host_port_ipv4(5432).await.expect("cannot get the Postgres container port");
+        Self {
+            admin_url: format!("postgres://postgres:postgres@{}:{}/postgres", host, port),
+            _container: Some(container),
+        }
+    }
+}
+
+/// Database created for one test and dropped when it ends
+pub struct TestDatabase {
+    admin_url: String,
+    name: String,
+    pub url: String,
+}
+
+impl TestDatabase {
+    /// Creates an empty database and applies every migration in `migrations/`
+    pub async fn create(admin_url: &str) -> Self {
+        let name = format!("oxicloud_e2e_{}", Uuid::new_v4().simple());
+        let mut admin = PgConnection::connect(admin_url)
+            .await
+            .unwrap_or_else(|e| panic!("cannot connect to {}: {}", DATABASE_URL_VAR, e));
+        admin
+            .execute(format!("CREATE DATABASE {}", name).as_str())
+            .await
+            .expect("failed to create the test database");
+
+        let mut url = url::Url::parse(admin_url).expect("invalid test database URL");
+        url.set_path(&name);
+        let database = Self { admin_url: admin_url.to_string(), name, url: url.to_string() };
+
+        let mut conn = PgConnection::connect(&database.url).await.expect("cannot connect to the test database");
+        let migrations_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("migrations");
+        let mut migrations: Vec<_> = std::fs::read_dir(&migrations_dir)
+            .expect("cannot read the migrations directory")
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "sql"))
+            .collect();
+        migrations.sort();
+        for migration in migrations {
+            let sql = std::fs::read_to_string(&migration).expect("cannot read migration");
+            sqlx::raw_sql(&sql)
+                .execute(&mut conn)
+                .await
+                .unwrap_or_else(|e| panic!("migration {} failed: {}", migration.display(), e));
+        }
+
+        database
+    }
+}
+
+impl Drop for TestDatabase {
+    fn drop(&mut self) {
+        // Drop runs outside any runtime guarantee, so the cleanup gets its own
+        let admin_url = self.admin_url.clone();
+        let statement = format!("DROP DATABASE IF EXISTS {} WITH (FORCE)", self.name);
+        let cleanup = std::thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+            runtime.block_on(async {
+                if let Ok(mut admin) = PgConnection::connect(&admin_url).await {
+                    let _ = admin.execute(statement.as_str()).await;
+                }
+            });
+        });
+        let _ = cleanup.join();
+    }
+}
+
+/// A user registered through the API, with a valid access token
+pub struct TestUser {
+    pub id: String,
+    pub username: String,
+    pub token: String,
+}
+
+impl TestUser {
+    /// Path of the user's home folder below the storage root
+    pub fn home(&self) -> String {
+        format!("{}{}", HOME_FOLDER_PREFIX, self.username)
+    }
+}
+
+/// The server binary running against its own storage and database
+pub struct TestServer {
+    pub base_url: String,
+    process: Child,
+    _storage: TempDir,
+    // Dropped in order: the database before the server it lives on
+    database: TestDatabase,
+    _postgres: TestPostgres,
+}
+
+impl TestServer {
+    /// Starts a server against a fresh database
+    pub async fn start() -> Self {
+        let postgres = TestPostgres::start().await;
+        let database = TestDatabase::create(&postgres.admin_url).await;
+        let storage = tempfile::tempdir().expect("cannot create the storage directory");
+        let port = free_port();
+        let log_filter = std::env::var(LOG_VAR).ok();
+
+        let mut command = Command::new(env!("CARGO_BIN_EXE_oxicloud"));
+        command
+            .current_dir(env!("CARGO_MANIFEST_DIR"))
+            .env("OXICLOUD_STORAGE_PATH", storage.path())
+            .env("OXICLOUD_SERVER_PORT", port.to_string())
+            .env("OXICLOUD_DB_CONNECTION_STRING", &database.url)
+            .env("OXICLOUD_ENABLE_AUTH", "true")
+            .env("OXICLOUD_JWT_SECRET", format!("e2e-{}", Uuid::new_v4()))
+            .env("RUST_LOG", log_filter.as_deref().unwrap_or("off"))
+            .stdout(if log_filter.is_some() { Stdio::inherit() } else { Stdio::null() })
+            .stderr(if log_filter.is_some() { Stdio::inherit() } else { Stdio::null() })
+            .kill_on_drop(true);
+        let process = command.spawn().expect("failed to start the oxicloud binary");
+
+        let mut server = Self {
+            base_url: format!("http://127.0.0.1:{}", port),
+            process,
+            _storage: storage,
+            database,
+            _postgres: postgres,
+        };
+        server.wait_until_ready().await;
+        server
+    }
+
+    async fn wait_until_ready(&mut self) {
+        let client = reqwest::Client::new();
+        let started = Instant::now();
+        loop {
+            if let Some(status) = self.process.try_wait().expect("cannot poll the server process") {
+                panic!("server exited during startup with {}", status);
+            }
+            let probe = client.get(format!("{}/api/capabilities", self.base_url)).send().await;
+            if probe.is_ok_and(|response| response.status().is_success()) {
+                return;
+            }
+            assert!(started.elapsed() < STARTUP_TIMEOUT, "server did not become ready in {:?}", STARTUP_TIMEOUT);
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+    }
+
+    /// Client without credentials
+    pub fn anonymous(&self) -> ApiClient {
+        ApiClient::new(&self.base_url, None)
+    }
+
+    /// REST client authenticated as `user`
+    pub fn api(&self, user: &TestUser) -> ApiClient {
+        ApiClient::new(&self.base_url, Some(&user.token))
+    }
+
+    /// WebDAV client authenticated as `user`
+    pub fn webdav(&self, user: &TestUser) -> DavClient {
+        DavClient::new(format!("{}/api/webdav", self.base_url), &user.token)
+    }
+
+    /// CalDAV client authenticated as `user`
+    pub fn caldav(&self, user: &TestUser) -> DavClient {
+        DavClient::new(format!("{}/api/caldav", self.base_url), &user.token)
+    }
+
+    /// CardDAV client authenticated as `user`
+    pub fn carddav(&self, user: &TestUser) -> DavClient {
+        DavClient::new(format!("{}/api/carddav", self.base_url), &user.token)
+    }
+
+    /// Connection to the server's database, for the data no endpoint creates
+    pub async fn database(&self) -> PgConnection {
+        PgConnection::connect(&self.database.url).await.expect("cannot connect to the test database")
+    }
+
+    /// ID of the home folder created for `user` when they registered
+    pub async fn home_folder_id(&self, user: &TestUser) -> String {
+        let response = self.api(user).get("/api/folders").await;
+        assert!(response.status().is_success(), "listing root folders returned {}", response.status());
+        let folders: Vec<serde_json::Value> = response.json().await.expect("folder list is not JSON");
+        folders.iter()
+            .find(|folder| folder["name"] == user.home().as_str())
+            .and_then(|folder| folder["id"].as_str())
+            .unwrap_or_else(|| panic!("{} has no home folder", user.username))
+            .to_string()
+    }
+
+    /// Registers a user and logs in as them
+    pub async fn create_user(&self, username: &str) -> TestUser {
+        let client = self.anonymous();
+        let response = client
+            .post_json("/api/auth/register", &json!({
+                "username": username,
+                "email": format!("{}@example.com", username),
+                "password": TEST_PASSWORD,
+            }))
+            .await;
+        assert!(response.status().is_success(), "registering {} failed: {}", username, response.status());
+
+        let response = client.login(username, TEST_PASSWORD).await;
+        assert!(response.status().is_success(), "logging in as {} failed: {}", username, response.status());
+        let body: serde_json::Value = response.json().await.expect("login did not return JSON");
+
+        TestUser {
+            id: body["user"]["id"].as_str().expect("login response without user id").to_string(),
+            username: username.to_string(),
+            token: body["access_token"].as_str().expect("login response without token").to_string(),
+        }
+    }
+}
+
+/// Asks the OS for a port that is free right now
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .map(|addr| addr.port())
+        .expect("cannot find a free port")
+}
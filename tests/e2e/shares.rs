@@ -0,0 +1,68 @@
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+use crate::harness::{TestServer, TestUser};
+
+/// Uploads a file into the home folder of `user` and returns its ID
+async fn upload_to_home(server: &TestServer, user: &TestUser, name: &str, content: &[u8]) -> String {
+    let home_id = server.home_folder_id(user).await;
+    let response = server.api(user).upload_file(Some(&home_id), name, content, "text/plain").await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let file: Value = response.json().await.unwrap();
+    file["id"].as_str().unwrap().to_string()
+}
+
+fn share_request(file_id: &str, allowed_emails: &[&str]) -> Value {
+    json!({
+        "item_id": file_id,
+        "item_type": "file",
+        "permissions": { "read": true, "write": false, "reshare": false },
+        "allowed_emails": allowed_emails,
+    })
+}
+
+#[tokio::test]
+#[ignore = "needs Docker or OXICLOUD_TEST_DATABASE_URL"]
+async fn users_cannot_share_each_others_files() {
+    let server = TestServer::start().await;
+    let alice = server.create_user("alice").await;
+    let bob = server.create_user("bob").await;
+    let file_id = upload_to_home(&server, &alice, "plans.txt", b"secret plans").await;
+
+    let response = server.api(&bob).post_json("/api/shares", &share_request(&file_id, &[])).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = server.api(&alice).post_json("/api/shares", &share_request(&file_id, &[])).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+}
+
+#[tokio::test]
+#[ignore = "needs Docker or OXICLOUD_TEST_DATABASE_URL"]
+async fn shared_files_open_with_the_permissions_of_the_share() {
+    let server = TestServer::start().await;
+    let alice = server.create_user("alice").await;
+    let bob = server.create_user("bob").await;
+    let carol = server.create_user("carol").await;
+    let file_id = upload_to_home(&server, &alice, "report.txt", b"quarterly report").await;
+
+    let response = server.api(&alice).post_json("/api/shares", &share_request(&file_id, &["bob@example.com"])).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    // Bob may read the file, but not change, delete or reshare it
+    let api = server.api(&bob);
+    let response = api.get(&format!("/api/files/{}?compress=false", file_id)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.bytes().await.unwrap().as_ref(), b"quarterly report");
+    assert_eq!(api.delete(&format!("/api/files/{}", file_id)).await.status(), StatusCode::FORBIDDEN);
+    let response = api.post_json("/api/shares", &share_request(&file_id, &["carol@example.com"])).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // Users the share does not name get nothing
+    let response = server.api(&carol).get(&format!("/api/files/{}", file_id)).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = server.webdav(&bob).get(&format!("{}/report.txt", alice.home())).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = server.webdav(&carol).get(&format!("{}/report.txt", alice.home())).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,24 @@
+use serde_json::Value;
+
+use crate::harness::TestServer;
+
+#[tokio::test]
+#[ignore = "needs Docker or OXICLOUD_TEST_DATABASE_URL"]
+async fn versioned_and_legacy_routes_serve_the_same_api() {
+    let server = TestServer::start().await;
+    let client = server.anonymous();
+
+    let versioned = client.get("/api/v1/capabilities").await;
+    assert!(versioned.status().is_success(), "GET /api/v1/capabilities returned {}", versioned.status());
+    assert_eq!(versioned.headers()["x-oxicloud-api-version"], "1");
+    assert!(versioned.headers().get("deprecation").is_none());
+    let capabilities: Value = versioned.json().await.unwrap();
+    assert_eq!(capabilities["api"]["current_version"], "v1");
+
+    let legacy = client.get("/api/capabilities").await;
+    assert!(legacy.status().is_success());
+    assert!(legacy.headers()["deprecation"].to_str().unwrap().starts_with('@'));
+    assert_eq!(legacy.headers()["link"], "</api/v1/capabilities>; rel=\"successor-version\"");
+
+    assert_eq!(client.get("/api/v2/capabilities").await.status(), 404);
+}